@@ -122,6 +122,8 @@ pub struct ProviderConfig {
     pub force_responses_api: bool,
     /// OAuth/本地 Provider 需要的凭证文件路径
     pub credential_path: Option<String>,
+    /// 采样温度（None 时使用 Provider 默认值）
+    pub temperature: Option<f32>,
 }
 
 impl ProviderContinuationCapable for ProviderConfig {
@@ -290,6 +292,7 @@ impl AsterAgentState {
                 .unwrap_or_else(|| format!("manual:{session_id}")),
             force_responses_api: config.force_responses_api,
             credential_path: config.credential_path.clone(),
+            temperature: config.temperature,
         })
         .await
         .map_err(|e| format!("创建 Provider 失败: {e}"))?;
@@ -335,16 +338,36 @@ impl AsterAgentState {
         provider_type: &str,
         model: &str,
         session_id: &str,
+    ) -> Result<AsterProviderConfig, String> {
+        self.configure_provider_from_pool_with_temperature(
+            db,
+            provider_type,
+            model,
+            session_id,
+            None,
+        )
+        .await
+    }
+
+    /// 从凭证池配置 Provider，并指定采样温度（None 时使用 Provider 默认值）
+    pub async fn configure_provider_from_pool_with_temperature(
+        &self,
+        db: &DbConnection,
+        provider_type: &str,
+        model: &str,
+        session_id: &str,
+        temperature: Option<f32>,
     ) -> Result<AsterProviderConfig, String> {
         // 确保 Agent 已初始化（使用带数据库的版本）
         self.init_agent_with_db(db).await?;
 
         // 从凭证池选择凭证并获取配置
-        let aster_config = self
+        let mut aster_config = self
             .credential_bridge
             .select_and_configure(db, provider_type, model)
             .await
             .map_err(|e| format!("从凭证池选择凭证失败: {e}"))?;
+        aster_config.temperature = temperature;
 
         // 创建 Provider
         let provider = create_aster_provider(&aster_config)
@@ -370,6 +393,7 @@ impl AsterAgentState {
             credential_uuid: Some(aster_config.credential_uuid.clone()),
             force_responses_api: aster_config.force_responses_api,
             credential_path: aster_config.credential_path.clone(),
+            temperature: aster_config.temperature,
         };
         let mut config_guard = self.current_provider_config.write().await;
         *config_guard = Some(config);
@@ -756,6 +780,7 @@ mod tests {
             credential_uuid: None,
             force_responses_api: false,
             credential_path: None,
+            temperature: None,
         };
 
         assert_eq!(
@@ -779,6 +804,7 @@ mod tests {
             credential_uuid: None,
             force_responses_api: true,
             credential_path: None,
+            temperature: None,
         };
 
         assert_eq!(
@@ -802,6 +828,7 @@ mod tests {
             credential_uuid: None,
             force_responses_api: false,
             credential_path: None,
+            temperature: None,
         };
 
         assert_eq!(
@@ -0,0 +1,537 @@
+//! 工作区代码索引
+//!
+//! 为 Agent 代码导航提供轻量索引，避免在大仓库中每次都整文件读取：
+//! - 文件树缓存：按修改时间与大小增量刷新，跳过 `.git`、`node_modules`、`target` 等目录
+//! - 符号提取：通过 tree-sitter 解析 Rust / TypeScript / JavaScript / Python / Go 的定义
+//! - 最近变更：增量刷新时记录新增、修改、删除的文件
+//!
+//! 索引按工作区根目录缓存在进程内，首次使用时构建，之后在查询前按需刷新。
+
+mod symbols;
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::Serialize;
+use tree_sitter::Parser;
+
+pub use symbols::{CodeLanguage, CodeSymbol};
+
+/// 最多索引的文件数
+const MAX_INDEXED_FILES: usize = 20_000;
+/// 超过该大小的文件不做符号提取
+const MAX_PARSE_FILE_BYTES: u64 = 512 * 1024;
+/// 保留的最近变更条数
+const MAX_RECENT_CHANGES: usize = 200;
+/// 两次刷新的最小间隔
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+/// 引用结果中单行内容的最大字符数
+const MAX_REFERENCE_LINE_CHARS: usize = 200;
+
+/// 不进入索引的目录（隐藏目录同样跳过）
+const IGNORED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "dist",
+    "build",
+    "out",
+    "coverage",
+    "vendor",
+    "__pycache__",
+    "venv",
+];
+
+/// 文件变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeKind {
+    Added,
+    Modified,
+    Removed,
+}
+
+/// 最近变更的文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub kind: FileChangeKind,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// 符号搜索结果
+#[derive(Debug, Clone, Serialize)]
+pub struct SymbolMatch {
+    #[serde(flatten)]
+    pub symbol: CodeSymbol,
+    pub score: i32,
+    /// 所在文件是否在最近变更中
+    pub recently_changed: bool,
+}
+
+/// 符号引用
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SymbolReference {
+    pub path: String,
+    pub line: usize,
+    pub column: usize,
+    pub text: String,
+    /// 该位置是否为符号定义
+    pub is_definition: bool,
+}
+
+/// 符号搜索条件
+#[derive(Debug, Clone, Default)]
+pub struct SymbolQuery<'a> {
+    pub query: &'a str,
+    pub kind: Option<&'a str>,
+    pub path_prefix: Option<&'a str>,
+    pub recently_changed_only: bool,
+    pub limit: usize,
+}
+
+/// 索引统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CodeIndexStats {
+    pub file_count: usize,
+    pub symbol_count: usize,
+    /// 文件数超出上限，索引不完整
+    pub truncated: bool,
+}
+
+#[derive(Debug)]
+struct IndexedFile {
+    modified: Option<SystemTime>,
+    size: u64,
+    language: Option<CodeLanguage>,
+    symbols: Vec<CodeSymbol>,
+    identifiers: HashSet<String>,
+}
+
+/// 单个工作区的代码索引
+#[derive(Debug)]
+pub struct CodeIndex {
+    root: PathBuf,
+    files: BTreeMap<String, IndexedFile>,
+    recent_changes: VecDeque<FileChange>,
+    last_refresh: Option<Instant>,
+    truncated: bool,
+}
+
+impl CodeIndex {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            files: BTreeMap::new(),
+            recent_changes: VecDeque::new(),
+            last_refresh: None,
+            truncated: false,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn stats(&self) -> CodeIndexStats {
+        CodeIndexStats {
+            file_count: self.files.len(),
+            symbol_count: self.files.values().map(|file| file.symbols.len()).sum(),
+            truncated: self.truncated,
+        }
+    }
+
+    /// 距上次刷新超过最小间隔时刷新
+    pub fn refresh_if_stale(&mut self) {
+        let fresh = self
+            .last_refresh
+            .is_some_and(|last| last.elapsed() < REFRESH_INTERVAL);
+        if !fresh {
+            self.refresh();
+        }
+    }
+
+    /// 增量刷新：只重新解析修改时间或大小变化的文件
+    ///
+    /// 首次构建不记录变更，之后的新增、修改、删除都写入最近变更。
+    pub fn refresh(&mut self) {
+        let initial = self.last_refresh.is_none();
+        let (entries, truncated) = scan_tree(&self.root);
+        self.truncated = truncated;
+
+        let mut parser = Parser::new();
+        let mut seen = HashSet::with_capacity(entries.len());
+        let mut changes = Vec::new();
+
+        for (relative, metadata) in entries {
+            let modified = metadata.modified().ok();
+            let size = metadata.len();
+            seen.insert(relative.clone());
+
+            let change = match self.files.get(&relative) {
+                Some(file) if file.modified == modified && file.size == size => continue,
+                Some(_) => FileChangeKind::Modified,
+                None => FileChangeKind::Added,
+            };
+            let file = self.index_file(&mut parser, &relative, modified, size);
+            self.files.insert(relative.clone(), file);
+            changes.push((relative, change));
+        }
+
+        let removed = self
+            .files
+            .keys()
+            .filter(|path| !seen.contains(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in removed {
+            self.files.remove(&path);
+            changes.push((path, FileChangeKind::Removed));
+        }
+
+        if !initial {
+            let detected_at = Utc::now();
+            for (path, kind) in changes {
+                self.recent_changes.retain(|change| change.path != path);
+                self.recent_changes.push_front(FileChange {
+                    path,
+                    kind,
+                    detected_at,
+                });
+            }
+            self.recent_changes.truncate(MAX_RECENT_CHANGES);
+        }
+        self.last_refresh = Some(Instant::now());
+    }
+
+    fn index_file(
+        &self,
+        parser: &mut Parser,
+        relative: &str,
+        modified: Option<SystemTime>,
+        size: u64,
+    ) -> IndexedFile {
+        let language = CodeLanguage::from_path(Path::new(relative));
+        let parsed = language
+            .filter(|_| size <= MAX_PARSE_FILE_BYTES)
+            .and_then(|language| {
+                let source = fs::read_to_string(self.root.join(relative)).ok()?;
+                Some(symbols::extract_symbols(
+                    parser, language, relative, &source,
+                ))
+            })
+            .unwrap_or_default();
+        IndexedFile {
+            modified,
+            size,
+            language,
+            symbols: parsed.symbols,
+            identifiers: parsed.identifiers,
+        }
+    }
+
+    /// 最近变更的文件（新的在前）
+    pub fn recent_changes(&self, limit: usize) -> Vec<FileChange> {
+        self.recent_changes.iter().take(limit).cloned().collect()
+    }
+
+    fn is_recently_changed(&self, path: &str) -> bool {
+        self.recent_changes.iter().any(|change| change.path == path)
+    }
+
+    /// 按名称搜索符号
+    ///
+    /// 精确匹配优先，其次是忽略大小写、前缀、包含；最近变更的文件略微加权。
+    pub fn search_symbols(&self, query: &SymbolQuery<'_>) -> Vec<SymbolMatch> {
+        let needle = query.query.trim();
+        let needle_lower = needle.to_lowercase();
+        let mut matches = self
+            .files
+            .iter()
+            .filter(|(path, _)| {
+                query.path_prefix.map_or(true, |prefix| {
+                    path.starts_with(prefix.trim_start_matches("./"))
+                })
+            })
+            .flat_map(|(path, file)| {
+                let recently_changed = self.is_recently_changed(path);
+                file.symbols
+                    .iter()
+                    .map(move |symbol| (symbol, recently_changed))
+            })
+            .filter(|(symbol, recently_changed)| {
+                query.kind.map_or(true, |kind| symbol.kind == kind)
+                    && (!query.recently_changed_only || *recently_changed)
+            })
+            .filter_map(|(symbol, recently_changed)| {
+                let score = score_symbol_name(&symbol.name, needle, &needle_lower)?;
+                Some(SymbolMatch {
+                    symbol: symbol.clone(),
+                    score: score + if recently_changed { 5 } else { 0 },
+                    recently_changed,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.symbol.path.cmp(&b.symbol.path))
+                .then_with(|| a.symbol.line.cmp(&b.symbol.line))
+        });
+        matches.truncate(query.limit);
+        matches
+    }
+
+    /// 查找符号的全词匹配引用
+    ///
+    /// 只读取标识符集合中包含该名称的文件，排除仅在注释或字符串里出现的文件。
+    pub fn find_references(
+        &self,
+        name: &str,
+        path_prefix: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SymbolReference>, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("符号名称不能为空".to_string());
+        }
+        let pattern = Regex::new(&format!(r"\b{}\b", regex::escape(name)))
+            .map_err(|error| format!("无效的符号名称: {error}"))?;
+
+        let mut references = Vec::new();
+        for (path, file) in &self.files {
+            if let Some(prefix) = path_prefix {
+                if !path.starts_with(prefix.trim_start_matches("./")) {
+                    continue;
+                }
+            }
+            if file.language.is_none() || !file.identifiers.contains(name) {
+                continue;
+            }
+            let Ok(source) = fs::read_to_string(self.root.join(path)) else {
+                continue;
+            };
+            let definitions = file
+                .symbols
+                .iter()
+                .filter(|symbol| symbol.name == name)
+                .map(|symbol| (symbol.line, symbol.column))
+                .collect::<HashSet<_>>();
+
+            for (row, line) in source.lines().enumerate() {
+                for found in pattern.find_iter(line) {
+                    let column = line[..found.start()].chars().count() + 1;
+                    references.push(SymbolReference {
+                        path: path.clone(),
+                        line: row + 1,
+                        column,
+                        text: symbols::truncate_chars(line.trim(), MAX_REFERENCE_LINE_CHARS),
+                        is_definition: definitions.contains(&(row + 1, column)),
+                    });
+                    if references.len() >= limit {
+                        return Ok(references);
+                    }
+                }
+            }
+        }
+        Ok(references)
+    }
+}
+
+fn score_symbol_name(name: &str, needle: &str, needle_lower: &str) -> Option<i32> {
+    if needle.is_empty() {
+        return Some(1);
+    }
+    if name == needle {
+        return Some(100);
+    }
+    let name_lower = name.to_lowercase();
+    if name_lower == needle_lower {
+        Some(90)
+    } else if name_lower.starts_with(needle_lower) {
+        Some(70)
+    } else if name_lower.contains(needle_lower) {
+        Some(50)
+    } else {
+        None
+    }
+}
+
+/// 遍历工作区文件，返回（相对路径，元数据）与是否因超出上限被截断
+fn scan_tree(root: &Path) -> (Vec<(String, fs::Metadata)>, bool) {
+    let mut entries = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(read_dir) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in read_dir.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            if file_type.is_dir() {
+                if !file_name.starts_with('.') && !IGNORED_DIRS.contains(&&*file_name) {
+                    pending.push(entry.path());
+                }
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            let path = entry.path();
+            let Ok(relative) = path.strip_prefix(root) else {
+                continue;
+            };
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if entries.len() >= MAX_INDEXED_FILES {
+                return (entries, true);
+            }
+            let relative = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            entries.push((relative, metadata));
+        }
+    }
+    (entries, false)
+}
+
+/// 获取工作区的共享索引（不存在时创建，尚未构建）
+pub fn workspace_code_index(root: &Path) -> Arc<Mutex<CodeIndex>> {
+    static INDEXES: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<CodeIndex>>>>> = OnceLock::new();
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mut indexes = INDEXES
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    indexes
+        .entry(root.clone())
+        .or_insert_with(|| Arc::new(Mutex::new(CodeIndex::new(root))))
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, relative: &str, content: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn query(text: &str) -> SymbolQuery<'_> {
+        SymbolQuery {
+            query: text,
+            limit: 20,
+            ..SymbolQuery::default()
+        }
+    }
+
+    #[test]
+    fn test_refresh_skips_ignored_dirs_and_searches_symbols() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "src/config.rs", "pub fn load_config() {}\n");
+        write(dir.path(), "src/loader.rs", "pub fn config_loader() {}\n");
+        write(
+            dir.path(),
+            "node_modules/pkg/index.js",
+            "function load_config() {}\n",
+        );
+        write(dir.path(), ".git/HEAD", "ref: refs/heads/main\n");
+
+        let mut index = CodeIndex::new(dir.path());
+        index.refresh();
+        assert_eq!(index.stats().file_count, 2);
+        assert!(index.recent_changes(10).is_empty());
+
+        let matches = index.search_symbols(&query("load_config"));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].symbol.path, "src/config.rs");
+        assert_eq!(matches[0].score, 100);
+
+        let matches = index.search_symbols(&query("config"));
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].symbol.name, "config_loader");
+    }
+
+    #[test]
+    fn test_refresh_tracks_recent_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "a.py", "def first():\n    pass\n");
+        write(dir.path(), "b.py", "def second():\n    pass\n");
+
+        let mut index = CodeIndex::new(dir.path());
+        index.refresh();
+
+        write(
+            dir.path(),
+            "a.py",
+            "def first():\n    return 1\n\ndef third():\n    pass\n",
+        );
+        write(dir.path(), "c.go", "package main\n\nfunc Fourth() {}\n");
+        fs::remove_file(dir.path().join("b.py")).unwrap();
+        index.refresh();
+
+        let mut changes = index
+            .recent_changes(10)
+            .into_iter()
+            .map(|change| (change.path, change.kind))
+            .collect::<Vec<_>>();
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            changes,
+            vec![
+                ("a.py".to_string(), FileChangeKind::Modified),
+                ("b.py".to_string(), FileChangeKind::Removed),
+                ("c.go".to_string(), FileChangeKind::Added),
+            ]
+        );
+
+        let recent = index.search_symbols(&SymbolQuery {
+            recently_changed_only: true,
+            ..query("")
+        });
+        let names = recent
+            .iter()
+            .map(|item| item.symbol.name.as_str())
+            .collect::<HashSet<_>>();
+        assert_eq!(names, HashSet::from(["first", "third", "Fourth"]));
+    }
+
+    #[test]
+    fn test_find_references_marks_definition() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "src/lib.rs",
+            "pub fn parse() {}\n\nfn run() {\n    parse();\n}\n",
+        );
+        write(
+            dir.path(),
+            "src/other.rs",
+            "// parse is mentioned only here\n",
+        );
+
+        let mut index = CodeIndex::new(dir.path());
+        index.refresh();
+        let references = index.find_references("parse", None, 50).unwrap();
+
+        assert_eq!(references.len(), 2);
+        assert!(references[0].is_definition);
+        assert_eq!((references[1].line, references[1].column), (4, 5));
+        assert!(!references[1].is_definition);
+        assert!(index.find_references("  ", None, 50).is_err());
+    }
+}
@@ -0,0 +1,374 @@
+//! 基于 tree-sitter 的符号提取
+//!
+//! 每种语言一条查询：`@name` 捕获符号名，另一个捕获名即符号类型。
+//! 同时收集文件内出现的全部标识符，供引用查找时快速排除无关文件。
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use tree_sitter::{Language, Node, Parser, Query, QueryCursor};
+
+/// 签名展示的最大字符数
+const MAX_SIGNATURE_CHARS: usize = 160;
+
+/// 支持符号提取的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CodeLanguage {
+    Rust,
+    TypeScript,
+    Tsx,
+    JavaScript,
+    Python,
+    Go,
+}
+
+impl CodeLanguage {
+    /// 按扩展名识别语言
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "rs" => Some(Self::Rust),
+            "ts" | "mts" | "cts" => Some(Self::TypeScript),
+            "tsx" => Some(Self::Tsx),
+            "js" | "jsx" | "mjs" | "cjs" => Some(Self::JavaScript),
+            "py" | "pyi" => Some(Self::Python),
+            "go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    fn grammar(self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::language(),
+            Self::TypeScript => tree_sitter_typescript::language_typescript(),
+            Self::Tsx => tree_sitter_typescript::language_tsx(),
+            Self::JavaScript => tree_sitter_javascript::language(),
+            Self::Python => tree_sitter_python::language(),
+            Self::Go => tree_sitter_go::language(),
+        }
+    }
+
+    fn symbol_query(self) -> &'static str {
+        match self {
+            Self::Rust => RUST_QUERY,
+            Self::TypeScript | Self::Tsx => TYPESCRIPT_QUERY,
+            Self::JavaScript => JAVASCRIPT_QUERY,
+            Self::Python => PYTHON_QUERY,
+            Self::Go => GO_QUERY,
+        }
+    }
+
+    const ALL: [Self; 6] = [
+        Self::Rust,
+        Self::TypeScript,
+        Self::Tsx,
+        Self::JavaScript,
+        Self::Python,
+        Self::Go,
+    ];
+}
+
+const RUST_QUERY: &str = r#"
+(function_item name: (identifier) @name) @function
+(function_signature_item name: (identifier) @name) @function
+(struct_item name: (type_identifier) @name) @struct
+(union_item name: (type_identifier) @name) @struct
+(enum_item name: (type_identifier) @name) @enum
+(trait_item name: (type_identifier) @name) @trait
+(type_item name: (type_identifier) @name) @type
+(const_item name: (identifier) @name) @constant
+(static_item name: (identifier) @name) @constant
+(mod_item name: (identifier) @name) @module
+(macro_definition name: (identifier) @name) @macro
+"#;
+
+const TYPESCRIPT_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @function
+(generator_function_declaration name: (identifier) @name) @function
+(class_declaration name: (type_identifier) @name) @class
+(abstract_class_declaration name: (type_identifier) @name) @class
+(interface_declaration name: (type_identifier) @name) @interface
+(type_alias_declaration name: (type_identifier) @name) @type
+(enum_declaration name: (identifier) @name) @enum
+(method_definition name: (property_identifier) @name) @method
+(program (lexical_declaration (variable_declarator name: (identifier) @name) @variable))
+(program (export_statement (lexical_declaration (variable_declarator name: (identifier) @name) @variable)))
+"#;
+
+const JAVASCRIPT_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @function
+(generator_function_declaration name: (identifier) @name) @function
+(class_declaration name: (identifier) @name) @class
+(method_definition name: (property_identifier) @name) @method
+(program (lexical_declaration (variable_declarator name: (identifier) @name) @variable))
+(program (export_statement (lexical_declaration (variable_declarator name: (identifier) @name) @variable)))
+"#;
+
+const PYTHON_QUERY: &str = r#"
+(function_definition name: (identifier) @name) @function
+(class_definition name: (identifier) @name) @class
+(module (expression_statement (assignment left: (identifier) @name) @variable))
+"#;
+
+const GO_QUERY: &str = r#"
+(function_declaration name: (identifier) @name) @function
+(method_declaration name: (field_identifier) @name) @method
+(type_spec name: (type_identifier) @name) @type
+(source_file (const_declaration (const_spec name: (identifier) @name) @constant))
+"#;
+
+/// 符号定义
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CodeSymbol {
+    pub name: String,
+    /// function / method / struct / class / interface / enum / trait / type / constant / variable / module / macro
+    pub kind: &'static str,
+    /// 相对工作区根目录的路径（`/` 分隔）
+    pub path: String,
+    /// 行号（从 1 开始）
+    pub line: usize,
+    /// 列号（从 1 开始）
+    pub column: usize,
+    /// 所属类型/模块（impl、class、trait 等）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<String>,
+    /// 定义所在行（截断）
+    pub signature: String,
+}
+
+/// 单个文件的解析结果
+#[derive(Debug, Default)]
+pub(crate) struct FileSymbols {
+    pub symbols: Vec<CodeSymbol>,
+    pub identifiers: HashSet<String>,
+}
+
+fn compiled_queries() -> &'static HashMap<CodeLanguage, Query> {
+    static QUERIES: OnceLock<HashMap<CodeLanguage, Query>> = OnceLock::new();
+    QUERIES.get_or_init(|| {
+        CodeLanguage::ALL
+            .into_iter()
+            .filter_map(
+                |language| match Query::new(&language.grammar(), language.symbol_query()) {
+                    Ok(query) => Some((language, query)),
+                    Err(error) => {
+                        tracing::warn!("[CodeIndex] {:?} 符号查询编译失败: {}", language, error);
+                        None
+                    }
+                },
+            )
+            .collect()
+    })
+}
+
+/// 解析源码，提取符号定义与标识符集合
+pub(crate) fn extract_symbols(
+    parser: &mut Parser,
+    language: CodeLanguage,
+    path: &str,
+    source: &str,
+) -> FileSymbols {
+    let mut result = FileSymbols::default();
+    if parser.set_language(&language.grammar()).is_err() {
+        return result;
+    }
+    let Some(tree) = parser.parse(source, None) else {
+        return result;
+    };
+    let root = tree.root_node();
+    collect_identifiers(root, source, &mut result.identifiers);
+
+    let Some(query) = compiled_queries().get(&language) else {
+        return result;
+    };
+    let capture_names = query.capture_names();
+    let lines = source.lines().collect::<Vec<_>>();
+    let mut seen = HashSet::new();
+    let mut cursor = QueryCursor::new();
+
+    for query_match in cursor.matches(query, root, source.as_bytes()) {
+        let mut name_node = None;
+        let mut definition = None;
+        for capture in query_match.captures {
+            match capture_names[capture.index as usize] {
+                "name" => name_node = Some(capture.node),
+                kind => definition = Some((kind, capture.node)),
+            }
+        }
+        let (Some(name_node), Some((kind, definition_node))) = (name_node, definition) else {
+            continue;
+        };
+        let Ok(name) = name_node.utf8_text(source.as_bytes()) else {
+            continue;
+        };
+        if !seen.insert(name_node.start_byte()) {
+            continue;
+        }
+
+        let container = resolve_container(definition_node, source);
+        let kind = refine_kind(kind, definition_node, container.as_ref());
+        let position = name_node.start_position();
+        let signature = lines
+            .get(definition_node.start_position().row)
+            .map(|line| truncate_chars(line.trim(), MAX_SIGNATURE_CHARS))
+            .unwrap_or_default();
+
+        result.symbols.push(CodeSymbol {
+            name: name.to_string(),
+            kind,
+            path: path.to_string(),
+            line: position.row + 1,
+            column: position.column + 1,
+            container: container.map(|(name, _)| name),
+            signature,
+        });
+    }
+    result
+}
+
+/// 统一符号类型：类型内部的函数记为 method，函数值变量记为 function
+fn refine_kind(kind: &str, node: Node, container: Option<&(String, bool)>) -> &'static str {
+    match kind {
+        "function" if container.is_some_and(|(_, is_type)| *is_type) => "method",
+        "variable" => match node.child_by_field_name("value").map(|value| value.kind()) {
+            Some("arrow_function" | "function_expression" | "function" | "lambda") => "function",
+            _ => "variable",
+        },
+        "function" => "function",
+        "method" => "method",
+        "struct" => "struct",
+        "class" => "class",
+        "interface" => "interface",
+        "enum" => "enum",
+        "trait" => "trait",
+        "type" => "type",
+        "constant" => "constant",
+        "module" => "module",
+        "macro" => "macro",
+        _ => "symbol",
+    }
+}
+
+/// 查找最近的所属作用域，返回（名称，是否为类型作用域）
+fn resolve_container(node: Node, source: &str) -> Option<(String, bool)> {
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        let (field, is_type) = match parent.kind() {
+            "impl_item" => ("type", true),
+            "trait_item"
+            | "class_declaration"
+            | "abstract_class_declaration"
+            | "class_definition"
+            | "interface_declaration" => ("name", true),
+            "mod_item" => ("name", false),
+            _ => {
+                current = parent.parent();
+                continue;
+            }
+        };
+        return parent
+            .child_by_field_name(field)
+            .and_then(|name| name.utf8_text(source.as_bytes()).ok())
+            .map(|name| (name.to_string(), is_type));
+    }
+    None
+}
+
+fn collect_identifiers(root: Node, source: &str, identifiers: &mut HashSet<String>) {
+    let mut cursor = root.walk();
+    loop {
+        let node = cursor.node();
+        if node.child_count() == 0 && node.kind().ends_with("identifier") {
+            if let Ok(text) = node.utf8_text(source.as_bytes()) {
+                if !identifiers.contains(text) {
+                    identifiers.insert(text.to_string());
+                }
+            }
+        }
+        if cursor.goto_first_child() {
+            continue;
+        }
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+            if !cursor.goto_parent() {
+                return;
+            }
+        }
+    }
+}
+
+pub(crate) fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((index, _)) => format!("{}…", &text[..index]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract(language: CodeLanguage, source: &str) -> FileSymbols {
+        let mut parser = Parser::new();
+        extract_symbols(&mut parser, language, "sample", source)
+    }
+
+    fn find<'a>(symbols: &'a FileSymbols, name: &str) -> &'a CodeSymbol {
+        symbols
+            .symbols
+            .iter()
+            .find(|symbol| symbol.name == name)
+            .unwrap_or_else(|| panic!("symbol {name} should be extracted"))
+    }
+
+    #[test]
+    fn test_symbol_queries_compile_for_all_languages() {
+        assert_eq!(compiled_queries().len(), CodeLanguage::ALL.len());
+    }
+
+    #[test]
+    fn test_extract_rust_symbols_with_impl_container() {
+        let source = "pub struct Config {\n    port: u16,\n}\n\nimpl Config {\n    pub fn load() -> Self {\n        Config { port: 0 }\n    }\n}\n\nfn helper() {}\n";
+        let symbols = extract(CodeLanguage::Rust, source);
+
+        let config = find(&symbols, "Config");
+        assert_eq!(config.kind, "struct");
+        assert_eq!((config.line, config.column), (1, 12));
+
+        let load = find(&symbols, "load");
+        assert_eq!(load.kind, "method");
+        assert_eq!(load.container.as_deref(), Some("Config"));
+        assert_eq!(load.signature, "pub fn load() -> Self {");
+
+        assert_eq!(find(&symbols, "helper").kind, "function");
+        assert!(symbols.identifiers.contains("port"));
+    }
+
+    #[test]
+    fn test_extract_typescript_symbols() {
+        let source = "export interface Props { id: string }\nexport const useThing = () => 1;\nconst LIMIT = 3;\nclass Store {\n  load() {}\n}\n";
+        let symbols = extract(CodeLanguage::TypeScript, source);
+
+        assert_eq!(find(&symbols, "Props").kind, "interface");
+        assert_eq!(find(&symbols, "useThing").kind, "function");
+        assert_eq!(find(&symbols, "LIMIT").kind, "variable");
+        let load = find(&symbols, "load");
+        assert_eq!(load.kind, "method");
+        assert_eq!(load.container.as_deref(), Some("Store"));
+    }
+
+    #[test]
+    fn test_extract_python_method() {
+        let source = "class Service:\n    def run(self):\n        pass\n\ndef main():\n    Service().run()\n";
+        let symbols = extract(CodeLanguage::Python, source);
+
+        assert_eq!(find(&symbols, "Service").kind, "class");
+        assert_eq!(find(&symbols, "run").kind, "method");
+        assert_eq!(find(&symbols, "main").kind, "function");
+    }
+}
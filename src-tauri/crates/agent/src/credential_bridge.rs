@@ -71,6 +71,8 @@ pub struct AsterProviderConfig {
     pub force_responses_api: bool,
     /// OAuth/本地 Provider 需要的凭证文件路径
     pub credential_path: Option<String>,
+    /// 采样温度（None 时使用 Provider 默认值）
+    pub temperature: Option<f32>,
 }
 
 /// 凭证池桥接器
@@ -122,6 +124,7 @@ impl CredentialBridge {
                 Some(model),
                 Some(provider_type), // 传递 provider_id_hint 支持智能降级
                 None,
+                None,
             )
             .await
             .map_err(CredentialBridgeError::DatabaseError)?
@@ -276,6 +279,13 @@ impl CredentialBridge {
                 let token = self.get_oauth_token(creds_file_path).await?;
                 ("google".to_string(), Some(token), None, false)
             }
+
+            // Mock 仅在本地代理服务器内生效，Agent 无法直接调用
+            CredentialData::Mock { .. } => {
+                return Err(CredentialBridgeError::UnsupportedCredentialType(
+                    "Mock 凭证仅支持通过本地 API 服务器调用".to_string(),
+                ));
+            }
         };
 
         Ok(AsterProviderConfig {
@@ -289,6 +299,7 @@ impl CredentialBridge {
                 CredentialData::KiroOAuth { creds_file_path } => Some(creds_file_path.clone()),
                 _ => None,
             },
+            temperature: None,
         })
     }
 
@@ -404,9 +415,11 @@ pub async fn create_aster_provider(
     config: &AsterProviderConfig,
 ) -> Result<Arc<dyn Provider>, CredentialBridgeError> {
     if config.provider_name == "kiro" {
-        let model_config = ModelConfig::new(&config.model_name).map_err(|e| {
-            CredentialBridgeError::ProviderCreationFailed(format!("创建 ModelConfig 失败: {e}"))
-        })?;
+        let model_config = ModelConfig::new(&config.model_name)
+            .map_err(|e| {
+                CredentialBridgeError::ProviderCreationFailed(format!("创建 ModelConfig 失败: {e}"))
+            })?
+            .with_temperature(config.temperature);
 
         let credential_path = config.credential_path.clone().ok_or_else(|| {
             CredentialBridgeError::ProviderCreationFailed(
@@ -428,9 +441,11 @@ pub async fn create_aster_provider(
     set_provider_env_vars(config);
 
     // 创建 ModelConfig
-    let model_config = ModelConfig::new(&config.model_name).map_err(|e| {
-        CredentialBridgeError::ProviderCreationFailed(format!("创建 ModelConfig 失败: {e}"))
-    })?;
+    let model_config = ModelConfig::new(&config.model_name)
+        .map_err(|e| {
+            CredentialBridgeError::ProviderCreationFailed(format!("创建 ModelConfig 失败: {e}"))
+        })?
+        .with_temperature(config.temperature);
 
     // 创建 Provider
     aster::providers::create(&config.provider_name, model_config)
@@ -588,6 +603,7 @@ pub fn map_pool_type_to_aster(pool_type: &PoolProviderType) -> &'static str {
         PoolProviderType::AzureOpenai => "azure",
         PoolProviderType::AwsBedrock => "bedrock",
         PoolProviderType::Ollama => "ollama",
+        PoolProviderType::Mock => "openai",
     }
 }
 
@@ -691,6 +707,7 @@ mod tests {
             credential_uuid: "test-uuid".to_string(),
             force_responses_api: true,
             credential_path: None,
+            temperature: None,
         };
 
         set_provider_env_vars(&config);
@@ -750,6 +767,7 @@ mod tests {
             credential_uuid: "test-uuid".to_string(),
             force_responses_api: false,
             credential_path: None,
+            temperature: None,
         };
 
         set_provider_env_vars(&config);
@@ -600,6 +600,18 @@ pub enum TauriAgentEvent {
     #[serde(rename = "model_change")]
     ModelChange { model: String, mode: String },
 
+    /// 会话已锁定 Provider / 模型，切换请求需要用户确认
+    ///
+    /// 本回合继续使用锁定配置；确认后前端在请求元数据中携带 `model_lock_override=true` 重新提交。
+    #[serde(rename = "model_lock_override_requested")]
+    ModelLockOverrideRequested {
+        session_id: String,
+        locked_provider: String,
+        locked_model: String,
+        requested_provider: String,
+        requested_model: String,
+    },
+
     /// 上下文准备轨迹
     #[serde(rename = "context_trace")]
     ContextTrace { steps: Vec<TauriContextTraceStep> },
@@ -735,6 +747,9 @@ pub struct TauriMessage {
     pub role: String,
     pub content: Vec<TauriMessageContent>,
     pub timestamp: i64,
+    /// token 用量与费用（历史消息持久化时计算）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<lime_core::models::model_registry::MessageCost>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1206,6 +1221,7 @@ pub fn convert_to_tauri_message(message: &Message) -> TauriMessage {
         role: format!("{:?}", message.role).to_lowercase(),
         content,
         timestamp: message.created,
+        cost: None,
     }
 }
 
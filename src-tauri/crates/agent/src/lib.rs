@@ -15,6 +15,7 @@ pub mod ask_bridge;
 pub mod aster_runtime_support;
 pub mod aster_state;
 pub mod aster_state_support;
+pub mod code_index;
 pub mod credential_bridge;
 pub mod durable_memory_fs;
 pub mod event_converter;
@@ -95,15 +96,16 @@ pub use runtime_queue::{
 pub use session_state_snapshot::SessionStateSnapshot;
 pub use session_store::{
     create_session_sync, delete_session, get_persisted_session_metadata_sync,
-    get_runtime_session_detail, get_session_sync, list_sessions_sync,
+    get_runtime_session_detail, get_session_sync, list_sessions_page_sync, list_sessions_sync,
     list_title_preview_messages_sync, rename_session_sync, update_session_execution_strategy_sync,
     update_session_working_dir_sync, ChildSubagentRuntimeStatus, ChildSubagentSession,
     PersistedSessionMetadata, SessionDetail, SessionInfo, SessionTitlePreviewMessage,
     SessionTodoItem, SubagentParentContext,
 };
 pub use skill_execution::{
-    execute_skill_prompt, execute_skill_workflow, SkillEventEmitter, SkillExecutionError,
-    SkillExecutionResult, SkillWorkflowExecution, StepResult,
+    execute_skill_prompt, execute_skill_workflow, SkillCostEstimator, SkillEventEmitter,
+    SkillExecutionError, SkillExecutionResult, SkillModelAttempt, SkillModelChain,
+    SkillWorkflowExecution, StepResult,
 };
 pub use subagent_control::{
     collect_subagent_cascade_session_ids, derive_subagent_runtime_status_kind,
@@ -17,6 +17,7 @@ use lime_core::database::agent_session_repository::{
 use lime_core::database::dao::agent_timeline::{
     AgentThreadItem, AgentThreadTurn, AgentTimelineDao,
 };
+use lime_core::database::pagination::{Page, PageRequest};
 use lime_core::database::DbConnection;
 use lime_core::workspace::WorkspaceManager;
 use lime_services::aster_session_store::LimeSessionStore;
@@ -782,6 +783,17 @@ pub fn list_sessions_sync(db: &DbConnection) -> Result<Vec<SessionInfo>, String>
         .collect())
 }
 
+/// 分页列出会话（按更新时间降序）
+pub fn list_sessions_page_sync(
+    db: &DbConnection,
+    page: &PageRequest,
+) -> Result<Page<SessionInfo>, String> {
+    let conn = db.lock().map_err(|e| format!("数据库锁定失败: {e}"))?;
+    let sessions = agent_session_repository::list_session_overviews_page(&conn, page)?;
+
+    Ok(sessions.map(build_runtime_session_info))
+}
+
 pub fn get_persisted_session_metadata_sync(
     db: &DbConnection,
     session_id: &str,
@@ -1119,6 +1131,7 @@ fn convert_agent_message(
         role: message.role.clone(),
         content,
         timestamp,
+        cost: message.cost.clone(),
     };
 
     // 调试日志
@@ -1222,6 +1235,7 @@ mod tests {
                 tool_calls: None,
                 tool_call_id: None,
                 reasoning_content: None,
+                cost: None,
             },
         )
         .expect("add message");
@@ -1573,6 +1587,7 @@ mod tests {
             }]),
             tool_call_id: None,
             reasoning_content: None,
+            cost: None,
         };
 
         let assistant_converted = convert_agent_message(
@@ -1594,6 +1609,7 @@ mod tests {
             tool_calls: None,
             tool_call_id: Some("call-1".to_string()),
             reasoning_content: None,
+            cost: None,
         };
 
         let tool_converted = convert_agent_message(
@@ -1632,6 +1648,7 @@ mod tests {
             tool_calls: None,
             tool_call_id: None,
             reasoning_content: None,
+            cost: None,
         };
 
         let converted = convert_agent_message(
@@ -1660,6 +1677,7 @@ mod tests {
             tool_calls: None,
             tool_call_id: Some("call-2".to_string()),
             reasoning_content: None,
+            cost: None,
         };
 
         let converted = convert_agent_message(
@@ -1720,6 +1738,7 @@ mod tests {
                 }]),
                 tool_call_id: None,
                 reasoning_content: None,
+                cost: None,
             },
             AgentMessage {
                 role: "user".to_string(),
@@ -1728,6 +1747,7 @@ mod tests {
                 tool_calls: None,
                 tool_call_id: None,
                 reasoning_content: None,
+                cost: None,
             },
             AgentMessage {
                 role: "assistant".to_string(),
@@ -1736,6 +1756,7 @@ mod tests {
                 tool_calls: None,
                 tool_call_id: None,
                 reasoning_content: None,
+                cost: None,
             },
         ];
 
@@ -1846,6 +1867,7 @@ mod tests {
                 tool_calls: None,
                 tool_call_id: None,
                 reasoning_content: None,
+                cost: None,
             },
         )
         .expect("add system message");
@@ -1859,6 +1881,7 @@ mod tests {
                 tool_calls: None,
                 tool_call_id: None,
                 reasoning_content: None,
+                cost: None,
             },
         )
         .expect("add user message");
@@ -1872,6 +1895,7 @@ mod tests {
                 tool_calls: None,
                 tool_call_id: None,
                 reasoning_content: None,
+                cost: None,
             },
         )
         .expect("add assistant message");
@@ -1885,6 +1909,7 @@ mod tests {
                 tool_calls: None,
                 tool_call_id: Some("tool-1".to_string()),
                 reasoning_content: None,
+                cost: None,
             },
         )
         .expect("add tool message");
@@ -4,10 +4,16 @@ use crate::{
 };
 use aster::agents::SessionConfig;
 use aster::conversation::message::Message;
+use aster::session::SessionManager;
 use futures::StreamExt;
-use lime_skills::{ExecutionCallback, LoadedSkillDefinition};
+use lime_core::database::DbConnection;
+use lime_skills::{
+    apply_output_processors, ExecutionCallback, ExecutionUsage, LoadedSkillDefinition, StepUsage,
+    OUTPUT_PROCESSOR_ERROR_CODE, OUTPUT_PROCESSOR_STEP_ID,
+};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 pub type SkillEventEmitter = Arc<dyn Fn(String, TauriAgentEvent) + Send + Sync + 'static>;
 
@@ -18,6 +24,14 @@ pub struct StepResult {
     pub success: bool,
     pub output: Option<String>,
     pub error: Option<String>,
+    /// 执行该步骤的 Provider（未启用模型候选链时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 步骤的 token / 耗时 / 费用（未调用模型的步骤为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<StepUsage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,6 +40,32 @@ pub struct SkillExecutionResult {
     pub output: Option<String>,
     pub error: Option<String>,
     pub steps_completed: Vec<StepResult>,
+    /// 所有步骤的用量合计
+    #[serde(default)]
+    pub usage: ExecutionUsage,
+}
+
+impl SkillExecutionResult {
+    fn new(
+        success: bool,
+        output: Option<String>,
+        error: Option<String>,
+        steps_completed: Vec<StepResult>,
+    ) -> Self {
+        let usage = execution_usage(&steps_completed);
+        Self {
+            success,
+            output,
+            error,
+            steps_completed,
+            usage,
+        }
+    }
+}
+
+/// 汇总各步骤用量
+fn execution_usage(steps: &[StepResult]) -> ExecutionUsage {
+    ExecutionUsage::from_steps(steps.iter().filter_map(|step| step.usage.as_ref()))
 }
 
 pub struct SkillWorkflowExecution<'a> {
@@ -37,6 +77,201 @@ pub struct SkillWorkflowExecution<'a> {
     pub callback: &'a dyn ExecutionCallback,
     pub memory_prompt: Option<&'a str>,
     pub emitter: SkillEventEmitter,
+    /// Provider 出错时依次切换的模型候选链
+    pub model_chain: Option<&'a SkillModelChain>,
+}
+
+/// 模型候选链中的一次尝试
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillModelAttempt {
+    pub provider: String,
+    pub model: String,
+    /// 出错的步骤（Provider 配置阶段失败时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub step_id: Option<String>,
+    /// 失败原因，为空表示该候选仍在服务
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Skill 步骤的计费接口
+///
+/// 由宿主接入费用上限的计费路径，确保币种折算与网关请求一致。
+pub trait SkillCostEstimator: Send + Sync {
+    /// 按模型定价估算费用（USD），模型没有定价时返回 `None`
+    fn estimate_cost_usd(&self, model: &str, input_tokens: u32, output_tokens: u32) -> Option<f64>;
+}
+
+impl std::fmt::Debug for dyn SkillCostEstimator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SkillCostEstimator")
+    }
+}
+
+#[derive(Debug, Default)]
+struct SkillModelChainState {
+    next: usize,
+    current: Option<(String, String)>,
+    attempts: Vec<SkillModelAttempt>,
+}
+
+/// Skill 的 Provider / 模型候选链
+///
+/// 按顺序从凭证池配置 `(provider, model)` 候选；配置失败或执行中 Provider
+/// 返回错误时切换到下一项，并记录每次尝试供执行历史展示。
+/// 兜底候选只参与配置阶段，运行期切换仅在 Skill 声明的候选之间进行。
+#[derive(Debug)]
+pub struct SkillModelChain {
+    db: DbConnection,
+    session_id: String,
+    candidates: Vec<(String, String)>,
+    configure_fallbacks: Vec<(String, String)>,
+    cost_estimator: Option<Arc<dyn SkillCostEstimator>>,
+    state: Mutex<SkillModelChainState>,
+}
+
+impl SkillModelChain {
+    pub fn new(db: DbConnection, session_id: &str, candidates: Vec<(String, String)>) -> Self {
+        Self {
+            db,
+            session_id: session_id.to_string(),
+            candidates,
+            configure_fallbacks: Vec::new(),
+            cost_estimator: None,
+            state: Mutex::new(SkillModelChainState::default()),
+        }
+    }
+
+    /// 设置步骤计费方式，未设置时不统计费用
+    pub fn with_cost_estimator(mut self, estimator: Arc<dyn SkillCostEstimator>) -> Self {
+        self.cost_estimator = Some(estimator);
+        self
+    }
+
+    /// 追加仅在配置阶段使用的兜底候选（跳过已声明的 Provider）
+    pub fn with_configure_fallbacks(mut self, fallbacks: &[(&str, &str)]) -> Self {
+        for (provider, model) in fallbacks {
+            if self
+                .candidates
+                .iter()
+                .all(|(declared, _)| declared != provider)
+            {
+                self.configure_fallbacks
+                    .push((provider.to_string(), model.to_string()));
+            }
+        }
+        self
+    }
+
+    /// 配置下一个可用候选，返回实际生效的 `(provider, model)`
+    pub async fn activate_next(
+        &self,
+        aster_state: &AsterAgentState,
+    ) -> Result<(String, String), String> {
+        self.activate(aster_state, true).await
+    }
+
+    async fn activate(
+        &self,
+        aster_state: &AsterAgentState,
+        include_fallbacks: bool,
+    ) -> Result<(String, String), String> {
+        let fallbacks: &[(String, String)] = if include_fallbacks {
+            &self.configure_fallbacks
+        } else {
+            &[]
+        };
+        let mut last_error = None;
+        loop {
+            let candidate = {
+                let mut state = self.lock();
+                let candidate = self
+                    .candidates
+                    .iter()
+                    .chain(fallbacks)
+                    .nth(state.next)
+                    .cloned();
+                state.next += 1;
+                candidate
+            };
+            let Some((provider, model)) = candidate else {
+                return Err(last_error.unwrap_or_else(|| "没有可用的模型候选".to_string()));
+            };
+
+            let result = aster_state
+                .configure_provider_from_pool(&self.db, &provider, &model, &self.session_id)
+                .await;
+            let mut state = self.lock();
+            match result {
+                Ok(config) => {
+                    let resolved = (config.provider_name, config.model_name);
+                    state.current = Some(resolved.clone());
+                    state.attempts.push(SkillModelAttempt {
+                        provider,
+                        model,
+                        step_id: None,
+                        error: None,
+                    });
+                    return Ok(resolved);
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        "[SkillModelChain] 候选 {} / {} 配置失败: {}",
+                        provider,
+                        model,
+                        error
+                    );
+                    state.attempts.push(SkillModelAttempt {
+                        provider,
+                        model,
+                        step_id: None,
+                        error: Some(error.clone()),
+                    });
+                    last_error = Some(error);
+                }
+            }
+        }
+    }
+
+    /// 记录当前候选在 `step_id` 上的失败并切换到下一项，声明的候选耗尽时返回 `None`
+    pub async fn fall_back(
+        &self,
+        aster_state: &AsterAgentState,
+        step_id: &str,
+        error: &str,
+    ) -> Option<(String, String)> {
+        {
+            let mut state = self.lock();
+            if let Some(attempt) = state
+                .attempts
+                .iter_mut()
+                .rev()
+                .find(|attempt| attempt.error.is_none())
+            {
+                attempt.step_id = Some(step_id.to_string());
+                attempt.error = Some(error.to_string());
+            }
+            if state.next >= self.candidates.len() {
+                return None;
+            }
+            state.current = None;
+        }
+        self.activate(aster_state, false).await.ok()
+    }
+
+    /// 当前生效的 `(provider, model)`
+    pub fn current(&self) -> Option<(String, String)> {
+        self.lock().current.clone()
+    }
+
+    /// 全部尝试记录（按发生顺序）
+    pub fn attempts(&self) -> Vec<SkillModelAttempt> {
+        self.lock().attempts.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SkillModelChainState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -57,6 +292,10 @@ impl std::error::Error for SkillExecutionError {}
 struct StreamedSkillReply {
     output: String,
     error: Option<String>,
+    /// 产生该回复的 `(provider, model)`
+    served_by: Option<(String, String)>,
+    /// 本轮（含切换候选后的重试）的用量
+    usage: StepUsage,
 }
 
 fn emit_skill_event(emitter: &SkillEventEmitter, event_name: &str, event: TauriAgentEvent) {
@@ -97,6 +336,51 @@ fn build_prompt_system_prompt(skill_markdown: &str, memory_prompt: Option<&str>)
     }
 }
 
+/// 对最终输出执行 Skill 声明的后处理器，失败时返回带错误码的消息
+fn apply_skill_output_processors(
+    skill: &LoadedSkillDefinition,
+    output: &str,
+) -> Result<String, String> {
+    apply_output_processors(&skill.output_processors, output)
+        .map_err(|error| format!("{OUTPUT_PROCESSOR_ERROR_CODE}|{error}"))
+}
+
+fn postprocess_failed_step(error: &str) -> StepResult {
+    StepResult {
+        step_id: OUTPUT_PROCESSOR_STEP_ID.to_string(),
+        step_name: "输出后处理".to_string(),
+        success: false,
+        output: None,
+        error: Some(error.to_string()),
+        provider: None,
+        model: None,
+        usage: None,
+    }
+}
+
+/// 读取会话累计的 `(input_tokens, output_tokens)`，会话不存在时视为 0
+async fn session_token_usage(session_id: &str) -> (u64, u64) {
+    match SessionManager::get_session(session_id, false).await {
+        Ok(session) => {
+            let tokens = |accumulated: Option<i32>, last: Option<i32>| {
+                accumulated.or(last).unwrap_or(0).max(0) as u64
+            };
+            (
+                tokens(session.accumulated_input_tokens, session.input_tokens),
+                tokens(session.accumulated_output_tokens, session.output_tokens),
+            )
+        }
+        Err(error) => {
+            tracing::debug!(
+                "[execute_skill] 读取会话 {} 用量失败: {}",
+                session_id,
+                error
+            );
+            (0, 0)
+        }
+    }
+}
+
 async fn stream_skill_session(
     aster_state: &AsterAgentState,
     session_id: &str,
@@ -152,7 +436,96 @@ async fn stream_skill_session(
 
     aster_state.remove_cancel_token(session_id).await;
 
-    Ok(StreamedSkillReply { output, error })
+    Ok(StreamedSkillReply {
+        output,
+        error,
+        served_by: None,
+        usage: StepUsage::default(),
+    })
+}
+
+/// 执行一轮会话；Provider 出错时沿模型候选链切换并在新会话中重试
+#[allow(clippy::too_many_arguments)]
+async fn stream_skill_session_with_fallback(
+    aster_state: &AsterAgentState,
+    session_id: &str,
+    step_id: &str,
+    event_name: &str,
+    system_prompt: &str,
+    user_text: &str,
+    emitter: &SkillEventEmitter,
+    model_chain: Option<&SkillModelChain>,
+    callback: Option<&dyn ExecutionCallback>,
+) -> Result<StreamedSkillReply, SkillExecutionError> {
+    let started = Instant::now();
+    let mut usage = StepUsage::default();
+    let mut attempt = 0;
+    loop {
+        // 失败的会话已写入部分消息，重试使用新的会话
+        let attempt_session_id = if attempt == 0 {
+            session_id.to_string()
+        } else {
+            format!("{session_id}-fallback-{attempt}")
+        };
+        let session_config = SessionConfigBuilder::new(&attempt_session_id)
+            .system_prompt(system_prompt.to_string())
+            .include_context_trace(true)
+            .build();
+        let served_by = model_chain.and_then(SkillModelChain::current);
+        // 调用方传入的会话可能已有历史用量，按差值计算本轮消耗
+        let (input_before, output_before) = session_token_usage(&attempt_session_id).await;
+        let mut reply = stream_skill_session(
+            aster_state,
+            &attempt_session_id,
+            event_name,
+            session_config,
+            Message::user().with_text(user_text),
+            emitter,
+        )
+        .await?;
+        reply.served_by = served_by;
+
+        // 每次尝试按各自的模型计费，失败的尝试同样消耗 token
+        let (input_after, output_after) = session_token_usage(&attempt_session_id).await;
+        let input_tokens = input_after.saturating_sub(input_before);
+        let output_tokens = output_after.saturating_sub(output_before);
+        usage.input_tokens += input_tokens;
+        usage.output_tokens += output_tokens;
+        let estimator = model_chain.and_then(|chain| chain.cost_estimator.as_deref());
+        if let (Some((_, model)), Some(estimator)) = (&reply.served_by, estimator) {
+            let cost = estimator.estimate_cost_usd(
+                model,
+                u32::try_from(input_tokens).unwrap_or(u32::MAX),
+                u32::try_from(output_tokens).unwrap_or(u32::MAX),
+            );
+            if let Some(cost) = cost {
+                usage.cost_usd = Some(usage.cost_usd.unwrap_or(0.0) + cost);
+            }
+        }
+
+        let fallback = match (&reply.error, model_chain) {
+            (Some(error), Some(chain)) => chain.fall_back(aster_state, step_id, error).await,
+            _ => None,
+        };
+        let Some((provider, model)) = fallback else {
+            usage.model = reply.served_by.as_ref().map(|(_, model)| model.clone());
+            usage.latency_ms = started.elapsed().as_millis() as u64;
+            reply.usage = usage;
+            return Ok(reply);
+        };
+        let error = reply.error.as_deref().unwrap_or_default();
+        tracing::warn!(
+            "[execute_skill] 步骤 {} 执行失败，切换到 {} / {} 重试: {}",
+            step_id,
+            provider,
+            model,
+            error
+        );
+        if let Some(callback) = callback {
+            callback.on_step_error(step_id, error, true);
+        }
+        attempt += 1;
+    }
 }
 
 pub async fn execute_skill_workflow(
@@ -167,6 +540,7 @@ pub async fn execute_skill_workflow(
         callback,
         memory_prompt,
         emitter,
+        model_chain,
     } = request;
     let steps = &skill.workflow_steps;
     let total_steps = steps.len();
@@ -202,22 +576,21 @@ pub async fn execute_skill_workflow(
             memory_prompt,
         );
         let step_session_id = format!("{session_id}-step-{}", step.id);
-        let session_config = SessionConfigBuilder::new(&step_session_id)
-            .system_prompt(step_system_prompt)
-            .include_context_trace(true)
-            .build();
         let step_input = build_step_input(user_input, &accumulated_context, idx == 0);
-        let user_message = Message::user().with_text(&step_input);
 
-        let reply = stream_skill_session(
+        let reply = stream_skill_session_with_fallback(
             aster_state,
             &step_session_id,
+            &step.id,
             &event_name,
-            session_config,
-            user_message,
+            &step_system_prompt,
+            &step_input,
             &emitter,
+            model_chain,
+            Some(callback),
         )
         .await?;
+        let (provider, model) = reply.served_by.clone().unzip();
 
         if let Some(error) = &reply.error {
             callback.on_step_error(&step.id, error, false);
@@ -227,37 +600,57 @@ pub async fn execute_skill_workflow(
                 success: false,
                 output: None,
                 error: Some(error.clone()),
+                provider,
+                model,
+                usage: Some(reply.usage),
             });
 
             let final_error = format!("步骤 '{}' 执行失败: {}", step.name, error);
-            callback.on_complete(false, None, Some(&final_error));
+            let result = SkillExecutionResult::new(false, None, Some(final_error), steps_completed);
+            callback.on_complete(false, None, result.error.as_deref(), &result.usage);
             emit_skill_event(
                 &emitter,
                 &event_name,
                 TauriAgentEvent::FinalDone { usage: None },
             );
 
-            return Ok(SkillExecutionResult {
-                success: false,
-                output: None,
-                error: Some(final_error),
-                steps_completed,
-            });
+            return Ok(result);
         }
 
-        callback.on_step_complete(&step.id, &reply.output);
+        callback.on_step_complete(&step.id, &reply.output, &reply.usage);
         steps_completed.push(StepResult {
             step_id: step.id.clone(),
             step_name: step.name.clone(),
             success: true,
             output: Some(reply.output.clone()),
             error: None,
+            provider,
+            model,
+            usage: Some(reply.usage),
         });
         accumulated_context = reply.output.clone();
         final_output = reply.output;
     }
 
-    callback.on_complete(true, Some(&final_output), None);
+    let final_output = match apply_skill_output_processors(skill, &final_output) {
+        Ok(output) => output,
+        Err(error) => {
+            callback.on_step_error(OUTPUT_PROCESSOR_STEP_ID, &error, false);
+            steps_completed.push(postprocess_failed_step(&error));
+            let result = SkillExecutionResult::new(false, None, Some(error), steps_completed);
+            callback.on_complete(false, None, result.error.as_deref(), &result.usage);
+            emit_skill_event(
+                &emitter,
+                &event_name,
+                TauriAgentEvent::FinalDone { usage: None },
+            );
+
+            return Ok(result);
+        }
+    };
+
+    let result = SkillExecutionResult::new(true, Some(final_output), None, steps_completed);
+    callback.on_complete(true, result.output.as_deref(), None, &result.usage);
     emit_skill_event(
         &emitter,
         &event_name,
@@ -265,19 +658,17 @@ pub async fn execute_skill_workflow(
     );
 
     tracing::info!(
-        "[execute_skill_workflow] Workflow 执行完成: skill={}, steps_completed={}",
+        "[execute_skill_workflow] Workflow 执行完成: skill={}, steps_completed={}, input_tokens={}, output_tokens={}",
         skill.skill_name,
-        steps_completed.len()
+        result.steps_completed.len(),
+        result.usage.input_tokens,
+        result.usage.output_tokens
     );
 
-    Ok(SkillExecutionResult {
-        success: true,
-        output: Some(final_output),
-        error: None,
-        steps_completed,
-    })
+    Ok(result)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn execute_skill_prompt(
     aster_state: &AsterAgentState,
     skill: &LoadedSkillDefinition,
@@ -286,51 +677,65 @@ pub async fn execute_skill_prompt(
     session_id: &str,
     memory_prompt: Option<&str>,
     emitter: SkillEventEmitter,
+    model_chain: Option<&SkillModelChain>,
 ) -> Result<SkillExecutionResult, SkillExecutionError> {
     let event_name = format!("skill-exec-{execution_id}");
-    let session_config = SessionConfigBuilder::new(session_id)
-        .system_prompt(build_prompt_system_prompt(
-            &skill.markdown_content,
-            memory_prompt,
-        ))
-        .include_context_trace(true)
-        .build();
-    let user_message = Message::user().with_text(user_input);
-    let reply = stream_skill_session(
+    let system_prompt = build_prompt_system_prompt(&skill.markdown_content, memory_prompt);
+    let reply = stream_skill_session_with_fallback(
         aster_state,
         session_id,
+        "main",
         &event_name,
-        session_config,
-        user_message,
+        &system_prompt,
+        user_input,
         &emitter,
+        model_chain,
+        None,
     )
     .await?;
+    let (provider, model) = reply.served_by.unzip();
 
     if let Some(error) = reply.error {
-        return Ok(SkillExecutionResult {
-            success: false,
-            output: None,
-            error: Some(error.clone()),
-            steps_completed: vec![StepResult {
+        return Ok(SkillExecutionResult::new(
+            false,
+            None,
+            Some(error.clone()),
+            vec![StepResult {
                 step_id: "main".to_string(),
                 step_name: skill.display_name.clone(),
                 success: false,
                 output: None,
                 error: Some(error),
+                provider,
+                model,
+                usage: Some(reply.usage),
             }],
-        });
+        ));
     }
 
-    Ok(SkillExecutionResult {
+    let main_step = StepResult {
+        step_id: "main".to_string(),
+        step_name: skill.display_name.clone(),
         success: true,
         output: Some(reply.output.clone()),
         error: None,
-        steps_completed: vec![StepResult {
-            step_id: "main".to_string(),
-            step_name: skill.display_name.clone(),
-            success: true,
-            output: Some(reply.output),
-            error: None,
-        }],
-    })
+        provider,
+        model,
+        usage: Some(reply.usage),
+    };
+
+    match apply_skill_output_processors(skill, &reply.output) {
+        Ok(output) => Ok(SkillExecutionResult::new(
+            true,
+            Some(output),
+            None,
+            vec![main_step],
+        )),
+        Err(error) => Ok(SkillExecutionResult::new(
+            false,
+            None,
+            Some(error.clone()),
+            vec![main_step, postprocess_failed_step(&error)],
+        )),
+    }
 }
@@ -3,6 +3,7 @@
 //! 将 Playwright MCP Server 的工具映射到 Aster 工具系统
 //! 提供浏览器自动化能力,包括导航、快照、点击、输入等操作
 
+use lime_mcp::McpContent;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::sync::Arc;
@@ -50,6 +51,18 @@ pub struct BrowserToolResult {
     pub output: String,
     /// 错误信息
     pub error: Option<String>,
+    /// 结果中的图片（如截图），`output` 中以占位文本标注
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub images: Vec<McpContent>,
+}
+
+impl BrowserToolResult {
+    /// 转换为 Anthropic `tool_result` 的 content 数组（文本在前，图片随后）
+    pub fn to_anthropic_content(&self) -> Vec<Value> {
+        let mut blocks = vec![serde_json::json!({ "type": "text", "text": self.output })];
+        blocks.extend(self.images.iter().map(McpContent::to_anthropic_block));
+        blocks
+    }
 }
 
 /// Browser Tool 包装器
@@ -155,13 +168,20 @@ impl BrowserTool {
 
         // 转换结果
         let is_error = result.is_error.unwrap_or(false);
+        let mut images = Vec::new();
         let output = result
             .content
             .into_iter()
             .map(|c| match c.raw {
                 rmcp::model::RawContent::Text(text) => text.text,
                 rmcp::model::RawContent::Image(img) => {
-                    format!("[Image: {}]", img.mime_type)
+                    let image = McpContent::Image {
+                        data: img.data,
+                        mime_type: img.mime_type,
+                    };
+                    let placeholder = image.to_text();
+                    images.push(image);
+                    placeholder
                 }
                 _ => "[Unknown content]".to_string(),
             })
@@ -172,6 +192,7 @@ impl BrowserTool {
             success: !is_error,
             output: output.clone(),
             error: if is_error { Some(output) } else { None },
+            images,
         })
     }
 
@@ -250,6 +271,7 @@ mod tests {
             success: true,
             output: "Page loaded".to_string(),
             error: None,
+            images: Vec::new(),
         };
         assert!(result.success);
         assert_eq!(result.output, "Page loaded");
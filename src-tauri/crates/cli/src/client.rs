@@ -0,0 +1,189 @@
+//! 管理 API 客户端
+//!
+//! 服务地址与 API Key 的解析顺序：命令行参数 > 环境变量 > 应用配置文件。
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use lime_core::config::{Config, ConfigManager};
+use reqwest::{Method, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// 请求超时（Skill 运行会等待模型完整输出）
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 连接参数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub base_url: String,
+    pub api_key: String,
+}
+
+impl Endpoint {
+    /// 解析连接参数，未显式指定的部分从配置文件读取
+    pub fn resolve(
+        url: Option<String>,
+        api_key: Option<String>,
+        config_path: Option<&Path>,
+    ) -> Result<Self> {
+        if let (Some(url), Some(api_key)) = (&url, &api_key) {
+            return Ok(Self {
+                base_url: url.trim_end_matches('/').to_string(),
+                api_key: api_key.clone(),
+            });
+        }
+
+        let path = config_path
+            .map(Path::to_path_buf)
+            .unwrap_or_else(ConfigManager::default_config_path);
+        let manager = ConfigManager::load(&path)
+            .with_context(|| format!("读取配置文件失败: {}", path.display()))?;
+        let from_config = Self::from_config(manager.config());
+
+        Ok(Self {
+            base_url: url
+                .map(|u| u.trim_end_matches('/').to_string())
+                .unwrap_or(from_config.base_url),
+            api_key: api_key.unwrap_or(from_config.api_key),
+        })
+    }
+
+    /// 从应用配置推导本机访问地址
+    pub fn from_config(config: &Config) -> Self {
+        let server = &config.server;
+        let scheme = if server.tls.enable { "https" } else { "http" };
+        // 监听所有网卡时通过回环地址访问
+        let host = match server.host.as_str() {
+            "0.0.0.0" | "" => "127.0.0.1".to_string(),
+            "::" => "[::1]".to_string(),
+            host if host.contains(':') && !host.starts_with('[') => format!("[{host}]"),
+            host => host.to_string(),
+        };
+        Self {
+            base_url: format!("{scheme}://{host}:{}", server.port),
+            api_key: server.api_key.clone(),
+        }
+    }
+}
+
+/// 管理 API 客户端
+pub struct AdminClient {
+    http: reqwest::Client,
+    endpoint: Endpoint,
+}
+
+impl AdminClient {
+    pub fn new(endpoint: Endpoint) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("创建 HTTP 客户端失败")?;
+        Ok(Self { http, endpoint })
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        self.http
+            .request(method, format!("{}{}", self.endpoint.base_url, path))
+            .bearer_auth(&self.endpoint.api_key)
+    }
+
+    async fn send(&self, builder: RequestBuilder) -> Result<Response> {
+        let response = builder.send().await.with_context(|| {
+            format!(
+                "无法连接 {}，请确认应用已启动且 API 服务已开启",
+                self.endpoint.base_url
+            )
+        })?;
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await.unwrap_or_default();
+        bail!("请求失败 ({status}): {}", error_message(&body))
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
+        let response = self.send(self.request(Method::GET, path)).await?;
+        response.json().await.context("解析响应失败")
+    }
+
+    pub async fn get_json_with_query<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        let response = self
+            .send(self.request(Method::GET, path).query(query))
+            .await?;
+        response.json().await.context("解析响应失败")
+    }
+
+    pub async fn get_text(&self, path: &str) -> Result<String> {
+        let response = self.send(self.request(Method::GET, path)).await?;
+        response.text().await.context("读取响应失败")
+    }
+
+    pub async fn post_json<T: DeserializeOwned>(&self, path: &str, body: &Value) -> Result<T> {
+        let response = self
+            .send(self.request(Method::POST, path).json(body))
+            .await?;
+        response.json().await.context("解析响应失败")
+    }
+}
+
+/// 从错误响应体中提取可读信息
+///
+/// 兼容管理 API（`{"message": ...}`）与网关错误（`{"error": {"message": ...}}`）两种格式。
+pub fn error_message(body: &str) -> String {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return body.trim().to_string();
+    };
+    value
+        .get("message")
+        .or_else(|| value.pointer("/error/message"))
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| body.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_from_config() {
+        let mut config = Config::default();
+        config.server.host = "0.0.0.0".to_string();
+        config.server.port = 9000;
+        config.server.api_key = "sk-local".to_string();
+        assert_eq!(
+            Endpoint::from_config(&config),
+            Endpoint {
+                base_url: "http://127.0.0.1:9000".to_string(),
+                api_key: "sk-local".to_string(),
+            }
+        );
+
+        config.server.host = "::1".to_string();
+        config.server.tls.enable = true;
+        assert_eq!(
+            Endpoint::from_config(&config).base_url,
+            "https://[::1]:9000"
+        );
+    }
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(
+            error_message(r#"{"error":"skill_not_found","message":"Skill 不存在: x"}"#),
+            "Skill 不存在: x"
+        );
+        assert_eq!(
+            error_message(r#"{"error":{"message":"Invalid API key"}}"#),
+            "Invalid API key"
+        );
+        assert_eq!(error_message("bad gateway\n"), "bad gateway");
+    }
+}
@@ -0,0 +1,288 @@
+//! Lime 命令行工具
+//!
+//! 通过管理 API（`/v1/admin/*`）与运行中的 Lime 应用交互，便于脚本化常见操作：
+//!
+//! ```text
+//! lime-cli credentials
+//! lime-cli skills run <name> --input "..."
+//! lime-cli sessions export <id> -o session.json
+//! lime-cli logs --follow --level error
+//! ```
+
+mod client;
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use lime_core::logger::LogEntry;
+use lime_core::models::provider_pool_model::ProviderPoolOverview;
+use serde::Deserialize;
+use serde_json::Value;
+
+use client::{AdminClient, Endpoint};
+
+/// 跟踪日志时每次轮询的最大条数（与服务端上限一致）
+const FOLLOW_PAGE_LIMIT: usize = 1000;
+
+#[derive(Debug, Parser)]
+#[command(name = "lime-cli", version, about = "Lime 命令行工具")]
+struct Cli {
+    /// 服务地址，如 http://127.0.0.1:8999（默认读取应用配置）
+    #[arg(long, env = "LIME_URL", global = true)]
+    url: Option<String>,
+    /// 服务器 API Key（默认读取应用配置）
+    #[arg(long, env = "LIME_API_KEY", global = true, hide_env_values = true)]
+    api_key: Option<String>,
+    /// 应用配置文件路径
+    #[arg(long, env = "LIME_CONFIG", global = true)]
+    config: Option<PathBuf>,
+    /// 以 JSON 输出原始结果
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// 列出凭证池
+    Credentials,
+    /// Skill 操作
+    Skills {
+        #[command(subcommand)]
+        command: SkillsCommand,
+    },
+    /// 会话操作
+    Sessions {
+        #[command(subcommand)]
+        command: SessionsCommand,
+    },
+    /// 查看应用日志
+    Logs {
+        /// 持续输出新日志
+        #[arg(short, long)]
+        follow: bool,
+        /// 按级别过滤（info / warn / error / debug）
+        #[arg(long)]
+        level: Option<String>,
+        /// 初次输出的最大条数
+        #[arg(short = 'n', long, default_value_t = 100)]
+        limit: usize,
+        /// 跟踪模式下的轮询间隔（毫秒）
+        #[arg(long, default_value_t = 1000)]
+        interval: u64,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum SkillsCommand {
+    /// 列出已安装的 Skill
+    List,
+    /// 运行 Skill（仅 prompt 模式），输出模型回复
+    Run {
+        /// Skill 名称
+        name: String,
+        /// 用户输入（未指定时从标准输入读取）
+        #[arg(short, long)]
+        input: Option<String>,
+        /// 模型（默认使用 Skill 声明的模型）
+        #[arg(short, long)]
+        model: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum SessionsCommand {
+    /// 列出会话
+    List,
+    /// 导出会话（含消息）为 JSON
+    Export {
+        /// 会话 ID
+        id: String,
+        /// 输出文件（默认输出到标准输出）
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct LogsResponse {
+    entries: Vec<LogEntry>,
+    cursor: Option<String>,
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run(Cli::parse()).await {
+        eprintln!("错误: {e:#}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let endpoint = Endpoint::resolve(cli.url, cli.api_key, cli.config.as_deref())?;
+    let client = AdminClient::new(endpoint)?;
+
+    match cli.command {
+        Command::Credentials => {
+            let overview: Vec<ProviderPoolOverview> =
+                client.get_json("/v1/admin/credentials").await?;
+            if cli.json {
+                return print_json(&overview);
+            }
+            for pool in overview.iter().filter(|p| !p.credentials.is_empty()) {
+                println!(
+                    "{} ({}/{} 健康)",
+                    pool.provider_type, pool.stats.healthy_count, pool.stats.total_count
+                );
+                for cred in &pool.credentials {
+                    let status = if cred.is_disabled {
+                        "disabled"
+                    } else if cred.is_healthy {
+                        "healthy"
+                    } else {
+                        "unhealthy"
+                    };
+                    println!(
+                        "  {}  {:<9}  {}  {}",
+                        cred.uuid,
+                        status,
+                        cred.name.as_deref().unwrap_or("-"),
+                        cred.display_credential
+                    );
+                }
+            }
+        }
+        Command::Skills {
+            command: SkillsCommand::List,
+        } => {
+            let skills: Vec<Value> = client.get_json("/v1/admin/skills").await?;
+            if cli.json {
+                return print_json(&skills);
+            }
+            for skill in &skills {
+                println!(
+                    "{}  [{}]  {}",
+                    str_field(skill, "name"),
+                    str_field(skill, "execution_mode"),
+                    str_field(skill, "description")
+                );
+            }
+        }
+        Command::Skills {
+            command: SkillsCommand::Run { name, input, model },
+        } => {
+            let input = match input {
+                Some(input) => input,
+                None => {
+                    let mut buffer = String::new();
+                    std::io::stdin()
+                        .read_to_string(&mut buffer)
+                        .context("读取标准输入失败")?;
+                    buffer
+                }
+            };
+            let body = serde_json::json!({ "input": input, "model": model });
+            let response: Value = client
+                .post_json(&format!("/v1/admin/skills/{name}/run"), &body)
+                .await?;
+            if cli.json {
+                return print_json(&response);
+            }
+            let Some(content) = response
+                .pointer("/choices/0/message/content")
+                .and_then(Value::as_str)
+            else {
+                bail!("响应中没有模型回复: {response}");
+            };
+            println!("{content}");
+        }
+        Command::Sessions {
+            command: SessionsCommand::List,
+        } => {
+            let sessions: Vec<Value> = client.get_json("/v1/admin/sessions").await?;
+            if cli.json {
+                return print_json(&sessions);
+            }
+            for session in &sessions {
+                println!(
+                    "{}  {}  {}",
+                    str_field(session, "id"),
+                    str_field(session, "updated_at"),
+                    str_field(session, "name")
+                );
+            }
+        }
+        Command::Sessions {
+            command: SessionsCommand::Export { id, output },
+        } => {
+            let content = client
+                .get_text(&format!("/v1/admin/sessions/{id}/export"))
+                .await?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, content)
+                        .with_context(|| format!("写入 {} 失败", path.display()))?;
+                    eprintln!("已导出会话 {id} 到 {}", path.display());
+                }
+                None => println!("{content}"),
+            }
+        }
+        Command::Logs {
+            follow,
+            level,
+            limit,
+            interval,
+        } => {
+            let mut cursor: Option<String> = None;
+            loop {
+                // 跟踪阶段取服务端允许的最大条数，避免轮询间隔内的日志被截断
+                let page_limit = if cursor.is_some() {
+                    FOLLOW_PAGE_LIMIT
+                } else {
+                    limit
+                };
+                let mut query = vec![("limit", page_limit.to_string())];
+                if let Some(level) = &level {
+                    query.push(("level", level.clone()));
+                }
+                if let Some(since) = &cursor {
+                    query.push(("since", since.clone()));
+                }
+                let response: LogsResponse =
+                    client.get_json_with_query("/v1/admin/logs", &query).await?;
+                for entry in &response.entries {
+                    if cli.json {
+                        println!("{}", serde_json::to_string(entry)?);
+                    } else {
+                        println!(
+                            "{} [{}] {}",
+                            entry.timestamp,
+                            entry.level.to_uppercase(),
+                            entry.message
+                        );
+                    }
+                }
+                if !follow {
+                    break;
+                }
+                cursor = response.cursor.or(cursor);
+                tokio::time::sleep(Duration::from_millis(interval.max(100))).await;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or("-")
+}
@@ -3,6 +3,7 @@
 //! 定义 Agent 模块使用的核心类型
 //! 参考 aster 项目的 Conversation 设计，支持连续对话和工具调用
 
+use crate::models::model_registry::MessageCost;
 use crate::models::provider_type::is_custom_provider_id;
 use serde::{Deserialize, Serialize};
 
@@ -204,6 +205,9 @@ pub struct AgentMessage {
     /// DeepSeek Reasoner 在 Tool Calls 场景下要求此字段
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_content: Option<String>,
+    /// token 用量与费用（assistant 消息持久化时计算）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<MessageCost>,
 }
 
 /// 消息内容类型
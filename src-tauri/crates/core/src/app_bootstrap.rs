@@ -14,6 +14,7 @@ pub enum ConfigError {
     DefaultApiKeyWithNonLocalBind,
     TlsNotSupported,
     RemoteManagementNotSupported,
+    InvalidRouteAuth(String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -35,6 +36,7 @@ impl std::fmt::Display for ConfigError {
             ConfigError::RemoteManagementNotSupported => {
                 write!(f, "远程管理需要 TLS 支持，当前版本未启用")
             }
+            ConfigError::InvalidRouteAuth(e) => write!(f, "路由认证配置无效: {e}"),
         }
     }
 }
@@ -64,5 +66,11 @@ pub fn load_and_validate_config() -> Result<Config, ConfigError> {
         return Err(ConfigError::RemoteManagementNotSupported);
     }
 
+    config
+        .server
+        .route_auth
+        .validate(&config.server.host)
+        .map_err(ConfigError::InvalidRouteAuth)?;
+
     Ok(config)
 }
@@ -74,6 +74,10 @@ pub fn resolve_sessions_dir() -> Result<PathBuf, String> {
     resolve_runtime_subdir("sessions")
 }
 
+pub fn resolve_reports_dir() -> Result<PathBuf, String> {
+    resolve_runtime_subdir("reports")
+}
+
 pub fn resolve_skills_dir() -> Result<PathBuf, String> {
     resolve_runtime_subdir("skills")
 }
@@ -82,6 +86,10 @@ pub fn resolve_aster_dir() -> Result<PathBuf, String> {
     resolve_runtime_subdir("aster")
 }
 
+pub fn resolve_plugin_data_dir() -> Result<PathBuf, String> {
+    resolve_runtime_subdir("plugin_data")
+}
+
 pub fn resolve_project_skills_dir() -> Option<PathBuf> {
     std::env::current_dir()
         .ok()
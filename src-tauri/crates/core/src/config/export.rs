@@ -301,9 +301,31 @@ impl ExportService {
         // 脱敏凭证池中的 API Key
         redacted.credential_pool = Self::redact_credential_pool(&config.credential_pool);
 
+        // 脱敏租户与护栏策略绑定的 API Key（保留条目数）
+        for tenant in &mut redacted.tenants.tenants {
+            Self::redact_key_list(&mut tenant.api_keys);
+        }
+        for policy in &mut redacted.system_prompt_guard.policies {
+            Self::redact_key_list(&mut policy.api_keys);
+        }
+
+        // 脱敏设备同步存储凭证
+        if redacted.device_sync.webdav.password.is_some() {
+            redacted.device_sync.webdav.password = Some(REDACTED_PLACEHOLDER.to_string());
+        }
+        if !redacted.device_sync.s3.secret_access_key.is_empty() {
+            redacted.device_sync.s3.secret_access_key = REDACTED_PLACEHOLDER.to_string();
+        }
+
         redacted
     }
 
+    fn redact_key_list(keys: &mut [String]) {
+        for key in keys.iter_mut() {
+            *key = REDACTED_PLACEHOLDER.to_string();
+        }
+    }
+
     /// 脱敏凭证池
     fn redact_credential_pool(pool: &CredentialPoolConfig) -> CredentialPoolConfig {
         CredentialPoolConfig {
@@ -336,6 +358,7 @@ impl ExportService {
             vertex_api_keys: pool.vertex_api_keys.clone(),
             codex: pool.codex.clone(),
             asr: pool.asr.clone(),
+            migrated_ids: pool.migrated_ids.clone(),
         }
     }
 
@@ -372,6 +395,36 @@ impl ExportService {
             }
         }
 
+        // 检查租户与护栏策略绑定的 API Key
+        let bound_keys = config
+            .tenants
+            .tenants
+            .iter()
+            .flat_map(|tenant| tenant.api_keys.iter())
+            .chain(
+                config
+                    .system_prompt_guard
+                    .policies
+                    .iter()
+                    .flat_map(|policy| policy.api_keys.iter()),
+            );
+        for key in bound_keys {
+            if !key.is_empty() && key != REDACTED_PLACEHOLDER {
+                return true;
+            }
+        }
+
+        // 检查设备同步存储凭证
+        if let Some(ref password) = config.device_sync.webdav.password {
+            if !password.is_empty() && password != REDACTED_PLACEHOLDER {
+                return true;
+            }
+        }
+        let s3_secret = &config.device_sync.s3.secret_access_key;
+        if !s3_secret.is_empty() && s3_secret != REDACTED_PLACEHOLDER {
+            return true;
+        }
+
         false
     }
 
@@ -379,27 +432,32 @@ impl ExportService {
     pub fn yaml_contains_secrets(yaml: &str) -> bool {
         // 检查是否包含看起来像 API 密钥的模式
         let secret_patterns = [
-            "sk-",      // OpenAI API key prefix
-            "sk-ant-",  // Anthropic API key prefix
-            "api_key:", // API key field (if not redacted)
+            "sk-",                // OpenAI API key prefix
+            "sk-ant-",            // Anthropic API key prefix
+            "api_key:",           // API key field (if not redacted)
+            "password:",          // WebDAV password
+            "secret_access_key:", // S3 secret key
         ];
 
-        for pattern in &secret_patterns {
-            if yaml.contains(pattern) && !yaml.contains(REDACTED_PLACEHOLDER) {
-                // 进一步检查是否是实际的密钥值
-                for line in yaml.lines() {
-                    if line.contains(pattern) && !line.contains(REDACTED_PLACEHOLDER) {
-                        // 排除注释行
-                        let trimmed = line.trim();
-                        if !trimmed.starts_with('#') {
-                            return true;
-                        }
-                    }
-                }
+        // 逐行检查，已脱敏的行不影响其余行的判断
+        yaml.lines().any(|line| {
+            let trimmed = line.trim();
+            // 排除注释行与已脱敏的行
+            if trimmed.starts_with('#') || trimmed.contains(REDACTED_PLACEHOLDER) {
+                return false;
             }
-        }
-
-        false
+            secret_patterns.iter().any(|pattern| {
+                let Some(pos) = trimmed.find(pattern) else {
+                    return false;
+                };
+                if !pattern.ends_with(':') {
+                    return true;
+                }
+                // 字段值为空时不算泄露
+                let value = trimmed[pos + pattern.len()..].trim();
+                !matches!(value, "" | "''" | "\"\"" | "null" | "~")
+            })
+        })
     }
 }
 
@@ -677,6 +735,65 @@ mod unit_tests {
         assert!(ExportService::contains_secrets(&config));
     }
 
+    #[test]
+    fn test_redact_device_sync_secrets() {
+        let mut config = Config::default();
+        config.device_sync.webdav.password = Some("dav-secret".to_string());
+        config.device_sync.s3.secret_access_key = "s3-secret".to_string();
+        config.server.api_key = REDACTED_PLACEHOLDER.to_string();
+        assert!(ExportService::contains_secrets(&config));
+
+        let redacted = ExportService::redact_config(&config);
+        assert!(!ExportService::contains_secrets(&redacted));
+
+        let yaml = ExportService::export_yaml(&config, true).expect("导出应成功");
+        assert!(!yaml.contains("dav-secret"));
+        assert!(!yaml.contains("s3-secret"));
+        assert!(ExportService::yaml_contains_secrets(
+            "device_sync:\n  webdav:\n    password: dav-secret\n"
+        ));
+        assert!(!ExportService::yaml_contains_secrets(
+            "device_sync:\n  s3:\n    secret_access_key: ''\n"
+        ));
+    }
+
+    #[test]
+    fn test_redact_bound_api_keys() {
+        let mut config = Config::default();
+        config.server.api_key = REDACTED_PLACEHOLDER.to_string();
+        config.tenants.tenants.push(
+            serde_json::from_value(serde_json::json!({
+                "id": "team-a",
+                "api_keys": ["tenant-key-1", "tenant-key-2"],
+            }))
+            .unwrap(),
+        );
+        config.system_prompt_guard.policies.push(
+            serde_json::from_value(serde_json::json!({
+                "id": "guard-1",
+                "api_keys": ["guard-key"],
+                "template": "guard",
+            }))
+            .unwrap(),
+        );
+        assert!(ExportService::contains_secrets(&config));
+
+        let redacted = ExportService::redact_config(&config);
+        assert!(!ExportService::contains_secrets(&redacted));
+        assert_eq!(
+            redacted.tenants.tenants[0].api_keys,
+            vec![REDACTED_PLACEHOLDER, REDACTED_PLACEHOLDER]
+        );
+        assert_eq!(
+            redacted.system_prompt_guard.policies[0].api_keys,
+            vec![REDACTED_PLACEHOLDER]
+        );
+
+        let yaml = ExportService::export_yaml(&config, true).expect("导出应成功");
+        assert!(!yaml.contains("tenant-key-1"));
+        assert!(!yaml.contains("guard-key"));
+    }
+
     #[test]
     fn test_export_config_only() {
         let config = Config::default();
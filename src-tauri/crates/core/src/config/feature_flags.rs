@@ -0,0 +1,152 @@
+//! 功能开关
+//!
+//! 实验性子系统通过功能开关控制，默认值在代码中定义，
+//! 配置文件 `feature_flags` 只保存与默认值不同的覆盖项：
+//!
+//! ```yaml
+//! feature_flags:
+//!   wasm_plugins: true
+//! ```
+//!
+//! 未知的开关名会被保留（兼容新版本写入的配置），但不影响任何功能。
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// 功能开关
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeatureFlag {
+    /// 请求对冲：慢请求超时后向备用凭证并发发送
+    RequestHedging,
+    /// WASM 插件运行时
+    WasmPlugins,
+    /// 多模型集成（ensemble）模式
+    EnsembleMode,
+}
+
+impl FeatureFlag {
+    /// 所有已知开关
+    pub const ALL: &'static [FeatureFlag] = &[
+        FeatureFlag::RequestHedging,
+        FeatureFlag::WasmPlugins,
+        FeatureFlag::EnsembleMode,
+    ];
+
+    /// 配置中的键名
+    pub fn key(self) -> &'static str {
+        match self {
+            FeatureFlag::RequestHedging => "request_hedging",
+            FeatureFlag::WasmPlugins => "wasm_plugins",
+            FeatureFlag::EnsembleMode => "ensemble_mode",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            FeatureFlag::RequestHedging => "慢请求超时后向备用凭证发送对冲请求，取先返回的结果",
+            FeatureFlag::WasmPlugins => "允许加载 .wasm 插件（沙箱运行，无需原生二进制）",
+            FeatureFlag::EnsembleMode => "同一请求分发到多个模型并合并结果",
+        }
+    }
+
+    /// 代码中的默认值
+    pub fn default_enabled(self) -> bool {
+        match self {
+            FeatureFlag::RequestHedging | FeatureFlag::WasmPlugins | FeatureFlag::EnsembleMode => {
+                false
+            }
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|flag| flag.key() == key)
+    }
+}
+
+/// 单个开关的当前状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureFlagState {
+    pub key: String,
+    pub description: String,
+    pub enabled: bool,
+    pub default_enabled: bool,
+    /// 是否被配置覆盖
+    pub overridden: bool,
+}
+
+/// 功能开关配置（仅保存覆盖项）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FeatureFlagSettings {
+    overrides: BTreeMap<String, bool>,
+}
+
+impl FeatureFlagSettings {
+    pub fn is_default(value: &Self) -> bool {
+        value.overrides.is_empty()
+    }
+
+    /// 开关是否启用（未覆盖时取代码默认值）
+    pub fn is_enabled(&self, flag: FeatureFlag) -> bool {
+        self.overrides
+            .get(flag.key())
+            .copied()
+            .unwrap_or_else(|| flag.default_enabled())
+    }
+
+    /// 设置开关；与默认值相同时移除覆盖项
+    pub fn set(&mut self, flag: FeatureFlag, enabled: bool) {
+        if enabled == flag.default_enabled() {
+            self.overrides.remove(flag.key());
+        } else {
+            self.overrides.insert(flag.key().to_string(), enabled);
+        }
+    }
+
+    /// 所有已知开关的当前状态
+    pub fn states(&self) -> Vec<FeatureFlagState> {
+        FeatureFlag::ALL
+            .iter()
+            .map(|&flag| FeatureFlagState {
+                key: flag.key().to_string(),
+                description: flag.description().to_string(),
+                enabled: self.is_enabled(flag),
+                default_enabled: flag.default_enabled(),
+                overridden: self.overrides.contains_key(flag.key()),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_and_overrides() {
+        let mut flags: FeatureFlagSettings =
+            serde_yaml::from_str("wasm_plugins: true\nfuture_flag: true\n").unwrap();
+        assert!(flags.is_enabled(FeatureFlag::WasmPlugins));
+        assert!(!flags.is_enabled(FeatureFlag::RequestHedging));
+
+        let states = flags.states();
+        assert_eq!(states.len(), FeatureFlag::ALL.len());
+        let wasm = states.iter().find(|s| s.key == "wasm_plugins").unwrap();
+        assert!(wasm.enabled && wasm.overridden && !wasm.default_enabled);
+
+        // 设回默认值时移除覆盖项，未知开关保留
+        flags.set(FeatureFlag::WasmPlugins, false);
+        flags.set(FeatureFlag::EnsembleMode, true);
+        assert_eq!(
+            serde_yaml::to_string(&flags).unwrap(),
+            "ensemble_mode: true\nfuture_flag: true\n"
+        );
+        assert_eq!(
+            FeatureFlag::from_key("request_hedging"),
+            Some(FeatureFlag::RequestHedging)
+        );
+        assert_eq!(FeatureFlag::from_key("unknown"), None);
+    }
+}
@@ -431,6 +431,12 @@ impl HotReloadManager {
             ));
         }
 
+        config
+            .server
+            .route_auth
+            .validate(&config.server.host)
+            .map_err(HotReloadError::ValidationError)?;
+
         Ok(())
     }
 
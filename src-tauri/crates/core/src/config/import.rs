@@ -377,6 +377,8 @@ impl ImportService {
             vertex_api_keys: imported.vertex_api_keys.clone(),
             codex: Self::merge_credential_entries(&current.codex, &imported.codex),
             asr: imported.asr.clone(),
+            // 迁移记录只对本机数据库有效
+            migrated_ids: current.migrated_ids.clone(),
         }
     }
 
@@ -6,44 +6,64 @@
 #![allow(unused_imports)]
 
 mod export;
+mod feature_flags;
 mod hot_reload;
 mod import;
 mod path_utils;
+mod snapshot;
 mod types;
 mod yaml;
 
 pub use export::{ExportBundle, ExportOptions, ExportService, REDACTED_PLACEHOLDER};
+pub use feature_flags::{FeatureFlag, FeatureFlagSettings, FeatureFlagState};
 pub use hot_reload::{
     ConfigChangeEvent as FileChangeEvent, ConfigChangeKind, FileWatcher, HotReloadManager,
     ReloadResult,
 };
 pub use import::{ImportOptions, ImportService, ValidationResult};
 pub use path_utils::{collapse_tilde, contains_tilde, expand_tilde};
+pub use snapshot::{
+    redact_secret_fields, strip_url_userinfo, validate_snapshot_name, CredentialSnapshot,
+    PipelineSnapshot, PipelineSnapshotDiff, PipelineSnapshotStore, PipelineSnapshotSummary,
+    SnapshotChange, SnapshotChangeKind,
+};
 pub use types::{
-    generate_secure_api_key, AmpConfig, AmpModelMapping, ApiKeyEntry, AsrCredentialEntry,
-    AsrProviderType, AutomationExecutionMode, AutomationSettings, BaiduConfig, ChannelsConfig,
+    generate_secure_api_key, AdaptiveSelectionSettings, AmpConfig, AmpModelMapping, ApiKeyEntry,
+    ApiKeyRateLimitSettings, AsrCredentialEntry, AsrProviderType, AuditLogSettings,
+    AutomationExecutionMode, AutomationSettings, BaiduConfig, BudgetPeriod, ChannelsConfig,
     ChatAppearanceConfig, CloudflareTunnelConfig, Config, ContentCreatorConfig,
-    ConversationSettings, CrashReportingConfig, CredentialEntry, CredentialPoolConfig,
-    CustomProviderConfig, DeliveryConfig, DiscordAccountConfig, DiscordActionsConfig,
-    DiscordAgentComponentsConfig, DiscordAutoPresenceConfig, DiscordBotConfig,
-    DiscordChannelConfig, DiscordExecApprovalsConfig, DiscordGuildConfig, DiscordIntentsConfig,
-    DiscordThreadBindingsConfig, DiscordUiComponentsConfig, DiscordUiConfig,
-    DiscordVoiceAutoJoinConfig, DiscordVoiceConfig, EndpointProvidersConfig, EnvironmentConfig,
-    EnvironmentVariableOverride, ExperimentalFeatures, FeishuAccountConfig, FeishuBotConfig,
-    FeishuGroupConfig, GatewayConfig, GatewayTunnelConfig, GeminiApiKeyEntry,
-    HintRouteSettingsEntry, HintRouterSettings, ImageGenConfig, InjectionRuleConfig,
-    InjectionSettings, LoggingConfig, MemoryAutoConfig, MemoryConfig, MemoryProfileConfig,
-    MemoryResolveConfig, MemorySourcesConfig, ModelInfo, ModelsConfig, MultiSearchConfig,
-    MultiSearchEngineEntryConfig, NativeAgentConfig, NavigationConfig, OpenAIAsrConfig,
-    PairingSettings, ProviderConfig, ProviderModelsConfig, ProvidersConfig, QuotaExceededConfig,
-    RateLimitSettings, RemoteManagementConfig, ResponseCacheSettings, RetrySettings, RoutingConfig,
-    ScreenshotChatConfig, SearchEngine, ServerConfig, ShellEnvironmentImportConfig, TaskSchedule,
-    TelegramAccountConfig, TelegramBotConfig, TelegramGroupConfig, TelegramTopicConfig, TlsConfig,
-    ToolCallingConfig, ToolExecutionOverrideConfig, ToolExecutionPolicyConfig,
-    ToolExecutionRestrictionProfileConfig, ToolExecutionSandboxProfileConfig,
-    ToolExecutionWarningPolicyConfig, UpdateCheckConfig, UserProfile, VertexApiKeyEntry,
-    VertexModelAlias, VoiceConfig, VoiceInputConfig, VoiceInstruction, VoiceOutputConfig,
-    VoiceOutputMode, VoiceProcessorConfig, WebSearchConfig, WebSearchProvider, WechatAccountConfig,
+    ContextTrimSettings, ContextTrimStrategy, ContextUpgradeSettings, ConversationSettings,
+    ConversionLossMode, ConversionLossSettings, CostBudget, CostBudgetSettings, CostCapSettings,
+    CrashReportingConfig, CredentialEntry, CredentialPoolConfig, CredentialQuotaLimit,
+    CustomProviderConfig, DegenerateRetrySettings, DeliveryConfig, DeviceSyncCategories,
+    DeviceSyncS3Config, DeviceSyncSettings, DeviceSyncStorageKind, DeviceSyncWebdavConfig,
+    DiscordAccountConfig, DiscordActionsConfig, DiscordAgentComponentsConfig,
+    DiscordAutoPresenceConfig, DiscordBotConfig, DiscordChannelConfig, DiscordExecApprovalsConfig,
+    DiscordGuildConfig, DiscordIntentsConfig, DiscordThreadBindingsConfig,
+    DiscordUiComponentsConfig, DiscordUiConfig, DiscordVoiceAutoJoinConfig, DiscordVoiceConfig,
+    EndpointFallbackSettings, EndpointGroup, EndpointProvidersConfig, EnvironmentConfig,
+    EnvironmentVariableOverride, ExperimentalFeatures, ExtensionRegistryConfig,
+    ExtensionRegistrySettings, FailoverChain, FailoverChainSettings, FailoverHop,
+    FeishuAccountConfig, FeishuBotConfig, FeishuGroupConfig, ForwardProxySettings, GatewayConfig,
+    GatewayTunnelConfig, GeminiApiKeyEntry, HintRouteSettingsEntry, HintRouterSettings,
+    ImageGenConfig, InjectionRuleConfig, InjectionSettings, LoggingConfig, MemoryAutoConfig,
+    MemoryConfig, MemoryProfileConfig, MemoryResolveConfig, MemorySourcesConfig, ModelInfo,
+    ModelsConfig, MultiSearchConfig, MultiSearchEngineEntryConfig, NativeAgentConfig,
+    NavigationConfig, OpenAIAsrConfig, PairingSettings, PluginHealthCheckSettings, ProviderConfig,
+    ProviderModelsConfig, ProvidersConfig, QuotaExceededConfig, RateLimitCooldownSettings,
+    RateLimitSettings, RegistryTrustPolicy, RemoteManagementConfig, RequestDeadlineSettings,
+    ResponseCacheMode, ResponseCacheSettings, RetrySettings, RouteAuthMode, RouteAuthRule,
+    RouteAuthSettings, RoutingConfig, ScreenshotChatConfig, SearchEngine, ServerConfig,
+    ShellEnvironmentImportConfig, SseFlowControlSettings, SystemPromptGuardPolicy,
+    SystemPromptGuardPosition, SystemPromptGuardSettings, TaskSchedule, TelegramAccountConfig,
+    TelegramBotConfig, TelegramGroupConfig, TelegramTopicConfig, TenantEntry, TenantSettings,
+    TlsConfig, TokenRefreshSettings, ToolCallingConfig, ToolExecutionOverrideConfig,
+    ToolExecutionPolicyConfig, ToolExecutionRestrictionProfileConfig,
+    ToolExecutionSandboxProfileConfig, ToolExecutionWarningPolicyConfig, TraceSamplingSettings,
+    UpdateCheckConfig, UsageAnalyticsSettings, UsageReportFormat, UsageReportPeriod,
+    UsageReportSettings, UserProfile, VertexApiKeyEntry, VertexModelAlias, VoiceConfig,
+    VoiceInputConfig, VoiceInstruction, VoiceOutputConfig, VoiceOutputMode, VoiceProcessorConfig,
+    VoiceVadConfig, VoiceVadMode, WebSearchConfig, WebSearchProvider, WechatAccountConfig,
     WechatBotConfig, WechatGroupConfig, WhisperLocalConfig, WhisperModelSize,
     WorkspaceSandboxConfig, XunfeiConfig, DEFAULT_API_KEY,
 };
@@ -40,6 +40,11 @@ fn arb_server_config() -> impl Strategy<Value = ServerConfig> {
         api_key,
         tls: crate::config::TlsConfig::default(),
         response_cache: crate::config::ResponseCacheSettings::default(),
+        route_auth: crate::config::RouteAuthSettings::default(),
+        sse_flow_control: crate::config::SseFlowControlSettings::default(),
+        request_deadline: crate::config::RequestDeadlineSettings::default(),
+        forward_proxy: crate::config::ForwardProxySettings::default(),
+        conversion_loss: crate::config::ConversionLossSettings::default(),
     })
 }
 
@@ -118,6 +123,9 @@ fn arb_routing_config() -> impl Strategy<Value = RoutingConfig> {
         .prop_map(|(default_provider, model_aliases)| RoutingConfig {
             default_provider,
             model_aliases,
+            context_upgrade: Default::default(),
+            failover_chains: Default::default(),
+            endpoint_fallback: Default::default(),
         })
 }
 
@@ -360,6 +368,11 @@ fn arb_valid_server_config() -> impl Strategy<Value = ServerConfig> {
         api_key,
         tls: crate::config::TlsConfig::default(),
         response_cache: crate::config::ResponseCacheSettings::default(),
+        route_auth: crate::config::RouteAuthSettings::default(),
+        sse_flow_control: crate::config::SseFlowControlSettings::default(),
+        request_deadline: crate::config::RequestDeadlineSettings::default(),
+        forward_proxy: crate::config::ForwardProxySettings::default(),
+        conversion_loss: crate::config::ConversionLossSettings::default(),
     })
 }
 
@@ -1241,6 +1254,7 @@ fn arb_credential_pool_config() -> impl Strategy<Value = CredentialPoolConfig> {
                 vertex_api_keys: vec![],
                 codex: vec![],
                 asr: vec![],
+                migrated_ids: vec![],
             },
         )
 }
@@ -2259,6 +2273,7 @@ fn arb_extended_credential_pool_config() -> impl Strategy<Value = CredentialPool
                     vertex_api_keys,
                     codex,
                     asr: vec![],
+                    migrated_ids: vec![],
                 }
             },
         )
@@ -3,6 +3,8 @@
 //! 定义 Lime 的配置结构，支持 YAML 和 JSON 序列化/反序列化
 //! 保持与旧版 JSON 配置的向后兼容性
 
+use super::feature_flags::FeatureFlagSettings;
+use crate::credential::CooldownMode;
 use crate::models::injection_types::{InjectionMode, InjectionRule};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -41,6 +43,9 @@ pub struct CredentialPoolConfig {
     /// ASR 语音服务凭证列表
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub asr: Vec<AsrCredentialEntry>,
+    /// 已迁移到凭证池数据库的条目 ID（启动迁移时跳过）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub migrated_ids: Vec<String>,
 }
 
 // ============ ASR 语音服务配置类型 ============
@@ -377,6 +382,9 @@ pub struct Config {
     /// 关闭时最小化到托盘（而不是退出应用）
     #[serde(default = "default_minimize_to_tray")]
     pub minimize_to_tray: bool,
+    /// 后台模式：启动时不显示主窗口，托盘图标作为唯一入口，关闭窗口时始终隐藏到托盘
+    #[serde(default)]
+    pub background_mode: bool,
     /// 用户界面语言 ("zh" 或 "en")
     #[serde(default = "default_language")]
     pub language: String,
@@ -422,6 +430,15 @@ pub struct Config {
     /// 速率限制配置
     #[serde(default)]
     pub rate_limit: RateLimitSettings,
+    /// 按 API Key 的令牌桶限流配置
+    #[serde(default)]
+    pub api_key_rate_limit: ApiKeyRateLimitSettings,
+    /// 系统提示词护栏注入配置
+    #[serde(default)]
+    pub system_prompt_guard: SystemPromptGuardSettings,
+    /// 空响应 / 退化响应自动重试配置
+    #[serde(default)]
+    pub degenerate_retry: DegenerateRetrySettings,
     /// 崩溃上报配置（Sentry 协议兼容）
     #[serde(default)]
     pub crash_reporting: CrashReportingConfig,
@@ -434,6 +451,39 @@ pub struct Config {
     /// 配对认证配置
     #[serde(default)]
     pub pairing: PairingSettings,
+    /// 多租户命名空间配置
+    #[serde(default)]
+    pub tenants: TenantSettings,
+    /// 月度费用硬上限配置
+    #[serde(default)]
+    pub cost_caps: CostCapSettings,
+    /// 费用预算提醒配置
+    #[serde(default)]
+    pub cost_budgets: CostBudgetSettings,
+    /// 请求追踪尾部采样配置
+    #[serde(default)]
+    pub trace_sampling: TraceSamplingSettings,
+    /// 匿名用量统计配置（需用户主动开启）
+    #[serde(default)]
+    pub usage_analytics: UsageAnalyticsSettings,
+    /// 请求审计日志配置
+    #[serde(default)]
+    pub audit_log: AuditLogSettings,
+    /// 定时用量报告配置
+    #[serde(default)]
+    pub usage_reports: UsageReportSettings,
+    /// 插件凭证定时健康检查配置
+    #[serde(default)]
+    pub plugin_health_check: PluginHealthCheckSettings,
+    /// OAuth Token 后台刷新配置
+    #[serde(default)]
+    pub token_refresh: TokenRefreshSettings,
+    /// 自适应凭证选择配置
+    #[serde(default)]
+    pub adaptive_selection: AdaptiveSelectionSettings,
+    /// 凭证限流冷却配置
+    #[serde(default)]
+    pub rate_limit_cooldown: RateLimitCooldownSettings,
     /// 自动化调度配置
     #[serde(default)]
     pub automation: AutomationSettings,
@@ -443,6 +493,15 @@ pub struct Config {
     /// 渠道配置（Telegram / Discord / 飞书 Bot）
     #[serde(default)]
     pub channels: ChannelsConfig,
+    /// 插件 / Skill 扩展注册表配置
+    #[serde(default)]
+    pub extension_registries: ExtensionRegistrySettings,
+    /// 设备间加密同步配置（本节不参与同步）
+    #[serde(default)]
+    pub device_sync: DeviceSyncSettings,
+    /// 功能开关覆盖项（默认值在代码中定义）
+    #[serde(default, skip_serializing_if = "FeatureFlagSettings::is_default")]
+    pub feature_flags: FeatureFlagSettings,
 }
 
 // ============ Native Agent 配置类型 ============
@@ -596,6 +655,9 @@ pub struct NativeAgentConfig {
     /// 工具执行权限覆盖配置（默认策略之上的持久化覆盖）
     #[serde(default, skip_serializing_if = "ToolExecutionPolicyConfig::is_default")]
     pub tool_execution: ToolExecutionPolicyConfig,
+    /// 会话标题最大字符数
+    #[serde(default = "default_title_max_chars")]
+    pub title_max_chars: usize,
 }
 
 fn default_use_default_prompt() -> bool {
@@ -614,6 +676,10 @@ fn default_max_tokens() -> u32 {
     4096
 }
 
+fn default_title_max_chars() -> usize {
+    15
+}
+
 impl Default for NativeAgentConfig {
     fn default() -> Self {
         Self {
@@ -625,6 +691,7 @@ impl Default for NativeAgentConfig {
             max_tokens: default_max_tokens(),
             workspace_sandbox: WorkspaceSandboxConfig::default(),
             tool_execution: ToolExecutionPolicyConfig::default(),
+            title_max_chars: default_title_max_chars(),
         }
     }
 }
@@ -1006,6 +1073,9 @@ pub struct VoiceInputConfig {
     /// 翻译模式使用的指令 ID
     #[serde(default = "default_translate_instruction_id")]
     pub translate_instruction_id: String,
+    /// 语音活动检测（静音自动停止）配置
+    #[serde(default)]
+    pub vad: VoiceVadConfig,
 }
 
 fn default_voice_shortcut() -> String {
@@ -1032,6 +1102,7 @@ impl Default for VoiceInputConfig {
             sound_enabled: default_sound_enabled(),
             translate_shortcut: None,
             translate_instruction_id: default_translate_instruction_id(),
+            vad: VoiceVadConfig::default(),
         }
     }
 }
@@ -1096,6 +1167,63 @@ impl Default for VoiceOutputConfig {
     }
 }
 
+/// 语音活动检测配置
+///
+/// 启用后说话结束（持续静音超过 `silence_duration_ms`）时自动停止录音或切分音频段
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VoiceVadConfig {
+    /// 是否启用
+    #[serde(default)]
+    pub enabled: bool,
+    /// 静音阈值（RMS，0.0 - 1.0）
+    #[serde(default = "default_vad_silence_threshold")]
+    pub silence_threshold: f32,
+    /// 说话后持续静音多久触发（毫秒）
+    #[serde(default = "default_vad_silence_duration_ms")]
+    pub silence_duration_ms: u32,
+    /// 至少检测到多长的语音才算开始说话（毫秒）
+    #[serde(default = "default_vad_min_speech_ms")]
+    pub min_speech_ms: u32,
+    /// 检测到静音后的动作
+    #[serde(default)]
+    pub mode: VoiceVadMode,
+}
+
+fn default_vad_silence_threshold() -> f32 {
+    0.015
+}
+
+fn default_vad_silence_duration_ms() -> u32 {
+    1500
+}
+
+fn default_vad_min_speech_ms() -> u32 {
+    300
+}
+
+impl Default for VoiceVadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            silence_threshold: default_vad_silence_threshold(),
+            silence_duration_ms: default_vad_silence_duration_ms(),
+            min_speech_ms: default_vad_min_speech_ms(),
+            mode: VoiceVadMode::default(),
+        }
+    }
+}
+
+/// 语音活动检测到静音后的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceVadMode {
+    /// 自动停止录音
+    #[default]
+    AutoStop,
+    /// 切分出当前这段音频，继续录音
+    Segment,
+}
+
 /// 语音输出模式
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -1244,6 +1372,21 @@ pub struct ServerConfig {
     /// 响应缓存配置（仅影响非流式请求）
     #[serde(default)]
     pub response_cache: ResponseCacheSettings,
+    /// 按路由的认证方式
+    #[serde(default)]
+    pub route_auth: RouteAuthSettings,
+    /// SSE 转发流控
+    #[serde(default)]
+    pub sse_flow_control: SseFlowControlSettings,
+    /// 请求截止时间
+    #[serde(default)]
+    pub request_deadline: RequestDeadlineSettings,
+    /// HTTP 正向代理模式
+    #[serde(default)]
+    pub forward_proxy: ForwardProxySettings,
+    /// 协议转换损失检测
+    #[serde(default)]
+    pub conversion_loss: ConversionLossSettings,
 }
 
 /// 响应缓存配置
@@ -1264,6 +1407,23 @@ pub struct ResponseCacheSettings {
     /// 可缓存的 HTTP 状态码列表（默认仅 200）
     #[serde(default = "default_response_cache_cacheable_status_codes")]
     pub cacheable_status_codes: Vec<u16>,
+    /// 缓存键的匹配方式
+    #[serde(default)]
+    pub mode: ResponseCacheMode,
+    /// 按路由启用（`chat_completions` / `anthropic_messages` -> 是否启用），未列出的路由默认启用
+    #[serde(default)]
+    pub routes: HashMap<String, bool>,
+}
+
+/// 响应缓存键的匹配方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseCacheMode {
+    /// 规范化字段顺序后完全相同的请求才命中
+    #[default]
+    Exact,
+    /// 额外折叠文本空白、展开单段文本内容并忽略不影响输出的字段
+    Semantic,
 }
 
 fn default_response_cache_enabled() -> bool {
@@ -1294,7 +1454,258 @@ impl Default for ResponseCacheSettings {
             max_entries: default_response_cache_max_entries(),
             max_body_bytes: default_response_cache_max_body_bytes(),
             cacheable_status_codes: default_response_cache_cacheable_status_codes(),
+            mode: ResponseCacheMode::default(),
+            routes: HashMap::new(),
+        }
+    }
+}
+
+/// SSE 转发流控配置
+///
+/// 上游推送快于客户端消费时，待发送数据在内存中排队：
+/// 积压时将排队的 chunk 合并后一次写出，积压超过 `max_buffer_bytes` 时停止读取上游，
+/// 向客户端发送 `buffer_overflow` 错误事件并结束流。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SseFlowControlSettings {
+    /// 是否启用
+    #[serde(default = "default_sse_flow_control_enabled")]
+    pub enabled: bool,
+    /// 单个流允许积压的最大字节数
+    #[serde(default = "default_sse_max_buffer_bytes")]
+    pub max_buffer_bytes: usize,
+    /// 合并后单次写出的最大字节数
+    #[serde(default = "default_sse_max_coalesce_bytes")]
+    pub max_coalesce_bytes: usize,
+}
+
+fn default_sse_flow_control_enabled() -> bool {
+    true
+}
+
+fn default_sse_max_buffer_bytes() -> usize {
+    8 * 1024 * 1024
+}
+
+fn default_sse_max_coalesce_bytes() -> usize {
+    64 * 1024
+}
+
+impl Default for SseFlowControlSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_sse_flow_control_enabled(),
+            max_buffer_bytes: default_sse_max_buffer_bytes(),
+            max_coalesce_bytes: default_sse_max_coalesce_bytes(),
+        }
+    }
+}
+
+/// 请求截止时间配置
+///
+/// 客户端可通过 `x-lime-deadline-ms` 请求头指定本次请求的总预算（不超过 `max_ms`），
+/// 未指定时使用 `default_ms`，两者都没有时不设截止时间。重试、故障转移与工具调用循环
+/// 按剩余时间裁剪；流式响应在距截止 `finalize_reserve_ms` 时以 `deadline_exceeded` 收尾。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RequestDeadlineSettings {
+    /// 默认请求预算（毫秒），0 表示不设置
+    #[serde(default)]
+    pub default_ms: u64,
+    /// 客户端请求头允许的最大预算（毫秒）
+    #[serde(default = "default_request_deadline_max_ms")]
+    pub max_ms: u64,
+    /// 流式响应提前收尾的预留时间（毫秒）
+    #[serde(default = "default_request_deadline_finalize_reserve_ms")]
+    pub finalize_reserve_ms: u64,
+    /// 是否接受客户端 `x-lime-deadline-ms` 请求头
+    #[serde(default = "default_true")]
+    pub allow_client_header: bool,
+}
+
+fn default_request_deadline_max_ms() -> u64 {
+    300_000
+}
+
+fn default_request_deadline_finalize_reserve_ms() -> u64 {
+    1_000
+}
+
+impl Default for RequestDeadlineSettings {
+    fn default() -> Self {
+        Self {
+            default_ms: 0,
+            max_ms: default_request_deadline_max_ms(),
+            finalize_reserve_ms: default_request_deadline_finalize_reserve_ms(),
+            allow_client_header: true,
+        }
+    }
+}
+
+/// 协议转换损失检测配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ConversionLossSettings {
+    #[serde(default)]
+    pub mode: ConversionLossMode,
+}
+
+/// 协议转换损失的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConversionLossMode {
+    /// 不检测，转换时静默丢弃不支持的字段
+    #[default]
+    Permissive,
+    /// 检测被丢弃或改写的字段，通过 `x-lime-conversion-warnings` 响应头与审计日志报告
+    Strict,
+}
+
+/// HTTP / SOCKS5 正向代理配置
+///
+/// 供无法修改 API Base URL、但支持 HTTP 或 SOCKS5 代理的工具使用：发往已知 Provider 主机的
+/// 明文 HTTP 请求改写到本地 API 服务器并使用服务器 API Key 认证，其余流量原样转发。
+/// 发往已知 Provider 主机的 HTTPS 连接无法改写，直接拒绝。
+/// 仅监听回环地址，且拒绝转发到回环与内网地址（`allowed_private_hosts` 除外）。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ForwardProxySettings {
+    /// 是否启用
+    #[serde(default)]
+    pub enabled: bool,
+    /// 监听端口（仅监听 127.0.0.1）
+    #[serde(default = "default_forward_proxy_port")]
+    pub port: u16,
+    /// 额外拦截的主机名 -> Provider ID（补充内置的已知 Provider 主机）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub provider_hosts: HashMap<String, String>,
+    /// 允许转发的回环/内网主机（主机名或 IP），默认全部拒绝
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_private_hosts: Vec<String>,
+}
+
+fn default_forward_proxy_port() -> u16 {
+    8998
+}
+
+impl Default for ForwardProxySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: default_forward_proxy_port(),
+            provider_hosts: HashMap::new(),
+            allowed_private_hosts: Vec::new(),
+        }
+    }
+}
+
+/// 路由认证方式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteAuthMode {
+    /// 无需认证
+    None,
+    /// 需要服务器或租户 API Key
+    #[default]
+    ApiKey,
+    /// 仅允许本机当前操作系统用户（支持的平台上校验对端进程 UID，否则仅限回环连接）
+    OsUser,
+}
+
+/// 单条路由认证规则
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RouteAuthRule {
+    /// 路由路径，精确匹配；以 `*` 结尾时按前缀匹配
+    pub path: String,
+    /// 认证方式
+    pub mode: RouteAuthMode,
+}
+
+impl RouteAuthRule {
+    fn new(path: &str, mode: RouteAuthMode) -> Self {
+        Self {
+            path: path.to_string(),
+            mode,
+        }
+    }
+
+    /// 判断路径是否匹配该规则
+    pub fn matches(&self, path: &str) -> bool {
+        match self.path.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path == self.path,
+        }
+    }
+}
+
+/// 按路由的认证配置
+///
+/// 规则按声明顺序匹配，首条命中生效；未命中时使用 `default_mode`。
+/// 配置了 `rules` 时会整体替换内置默认规则。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RouteAuthSettings {
+    /// 未命中规则时的认证方式
+    #[serde(default)]
+    pub default_mode: RouteAuthMode,
+    /// 路由规则
+    #[serde(default = "default_route_auth_rules")]
+    pub rules: Vec<RouteAuthRule>,
+}
+
+/// 内置默认规则：健康检查开放，诊断接口与浏览器桥接仅限本机用户，其余需要 API Key
+fn default_route_auth_rules() -> Vec<RouteAuthRule> {
+    vec![
+        RouteAuthRule::new("/health", RouteAuthMode::None),
+        RouteAuthRule::new("/cache", RouteAuthMode::OsUser),
+        RouteAuthRule::new("/stats", RouteAuthMode::OsUser),
+        RouteAuthRule::new("/v1/routes", RouteAuthMode::OsUser),
+        RouteAuthRule::new("/lime-chrome-*", RouteAuthMode::OsUser),
+    ]
+}
+
+impl Default for RouteAuthSettings {
+    fn default() -> Self {
+        Self {
+            default_mode: RouteAuthMode::default(),
+            rules: default_route_auth_rules(),
+        }
+    }
+}
+
+impl RouteAuthSettings {
+    /// 解析路径对应的认证方式
+    pub fn resolve(&self, path: &str) -> RouteAuthMode {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(path))
+            .map(|rule| rule.mode)
+            .unwrap_or(self.default_mode)
+    }
+
+    /// 校验配置
+    ///
+    /// 监听非本地地址时，除 `/health` 外不允许开放（`none`）路由。
+    pub fn validate(&self, host: &str) -> Result<(), String> {
+        let non_local = crate::app_utils::is_non_local_bind(host);
+        if non_local && self.default_mode == RouteAuthMode::None {
+            return Err("监听非本地地址时，默认认证方式不能为 none".to_string());
         }
+
+        let mut seen = std::collections::HashSet::new();
+        for rule in &self.rules {
+            if !rule.path.starts_with('/') {
+                return Err(format!("路由认证规则路径必须以 / 开头: {}", rule.path));
+            }
+            if rule.path.trim_end_matches('*').contains('*') {
+                return Err(format!("路由认证规则仅支持末尾通配符 *: {}", rule.path));
+            }
+            if !seen.insert(rule.path.as_str()) {
+                return Err(format!("路由认证规则路径重复: {}", rule.path));
+            }
+            if non_local && rule.mode == RouteAuthMode::None && rule.path != "/health" {
+                return Err(format!(
+                    "监听非本地地址时，不允许开放路由 {}（仅 /health 可设为 none）",
+                    rule.path
+                ));
+            }
+        }
+        Ok(())
     }
 }
 
@@ -1332,7 +1743,7 @@ pub struct RemoteManagementConfig {
 
 /// 配额超限配置
 ///
-/// 用于配置配额超限时的自动切换策略
+/// 用于配置配额超限时的自动切换策略，以及按已知配额提前切换凭证的软上限
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct QuotaExceededConfig {
     /// 是否自动切换到下一个凭证
@@ -1344,6 +1755,34 @@ pub struct QuotaExceededConfig {
     /// 冷却时间（秒）
     #[serde(default = "default_cooldown_seconds")]
     pub cooldown_seconds: u64,
+    /// 用量达到已知配额的该比例时主动切换凭证（0~1）
+    #[serde(default = "default_soft_limit_ratio")]
+    pub soft_limit_ratio: f64,
+    /// 各凭证的已知配额（凭证 UUID -> 配额）
+    #[serde(default)]
+    pub credential_limits: HashMap<String, CredentialQuotaLimit>,
+}
+
+/// 凭证的已知配额（滚动窗口）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CredentialQuotaLimit {
+    /// 窗口内最大请求数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_requests: Option<u64>,
+    /// 窗口内最大 Token 数（输入 + 输出）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    /// 滚动窗口长度（秒）
+    #[serde(default = "default_quota_window_secs")]
+    pub window_secs: u64,
+}
+
+fn default_soft_limit_ratio() -> f64 {
+    0.9
+}
+
+fn default_quota_window_secs() -> u64 {
+    3600
 }
 
 fn default_switch_project() -> bool {
@@ -1364,6 +1803,8 @@ impl Default for QuotaExceededConfig {
             switch_project: default_switch_project(),
             switch_preview_model: default_switch_preview_model(),
             cooldown_seconds: default_cooldown_seconds(),
+            soft_limit_ratio: default_soft_limit_ratio(),
+            credential_limits: HashMap::new(),
         }
     }
 }
@@ -1433,6 +1874,11 @@ impl Default for ServerConfig {
             api_key: default_api_key(),
             tls: TlsConfig::default(),
             response_cache: ResponseCacheSettings::default(),
+            route_auth: RouteAuthSettings::default(),
+            sse_flow_control: SseFlowControlSettings::default(),
+            request_deadline: RequestDeadlineSettings::default(),
+            forward_proxy: ForwardProxySettings::default(),
+            conversion_loss: ConversionLossSettings::default(),
         }
     }
 }
@@ -1532,6 +1978,15 @@ pub struct RoutingConfig {
     /// 模型别名映射
     #[serde(default)]
     pub model_aliases: HashMap<String, String>,
+    /// 上下文窗口不足时自动升级模型
+    #[serde(default)]
+    pub context_upgrade: ContextUpgradeSettings,
+    /// 按模型系列配置的 Provider 故障转移链
+    #[serde(default)]
+    pub failover_chains: FailoverChainSettings,
+    /// Provider 主机名不可用时的等价端点切换
+    #[serde(default)]
+    pub endpoint_fallback: EndpointFallbackSettings,
 }
 
 fn default_provider() -> String {
@@ -1543,10 +1998,137 @@ impl Default for RoutingConfig {
         Self {
             default_provider: default_provider(),
             model_aliases: HashMap::new(),
+            context_upgrade: ContextUpgradeSettings::default(),
+            failover_chains: FailoverChainSettings::default(),
+            endpoint_fallback: EndpointFallbackSettings::default(),
+        }
+    }
+}
+
+/// 上下文窗口感知的模型自动升级配置
+///
+/// 组装后的请求超出所选模型上下文窗口时，按顺序切换到第一个窗口足够的同系列模型，
+/// 并在响应头 `x-lime-model-upgrade` 与请求元数据中记录替换。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ContextUpgradeSettings {
+    /// 是否启用（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 可升级的同系列模型（如 `gpt-4o-mini: [gpt-4o]`），按优先顺序排列
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub siblings: HashMap<String, Vec<String>>,
+}
+
+impl ContextUpgradeSettings {
+    /// 指定模型的候选升级目标（模型名不区分大小写）
+    pub fn candidates(&self, model: &str) -> &[String] {
+        self.siblings
+            .iter()
+            .find(|(source, _)| source.eq_ignore_ascii_case(model))
+            .map(|(_, targets)| targets.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Provider 故障转移链配置
+///
+/// 按模型系列配置有序的 Provider 链（如 `claude_oauth → anthropic → openrouter`）：
+/// 前一跳没有可用凭证或请求失败（429 / 5xx / 认证失败）时切换到下一跳。
+/// 请求显式指定 `X-Provider-Id` 时不使用故障转移链。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct FailoverChainSettings {
+    /// 是否启用（默认关闭，沿用隐式降级）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 故障转移链，按顺序匹配，第一个匹配模型的链生效
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chains: Vec<FailoverChain>,
+}
+
+impl FailoverChainSettings {
+    /// 指定模型生效的故障转移链（未启用或没有匹配时返回 None）
+    pub fn chain_for(&self, model: &str) -> Option<&FailoverChain> {
+        if !self.enabled {
+            return None;
+        }
+        let model = model.to_lowercase();
+        self.chains.iter().find(|chain| {
+            !chain.hops.is_empty()
+                && crate::models::injection_types::pattern_matches(
+                    &chain.model_pattern.to_lowercase(),
+                    &model,
+                )
+        })
+    }
+}
+
+/// 单个模型系列的故障转移链
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FailoverChain {
+    /// 模型系列匹配模式（支持 `claude-*`、`*-preview`、`*flash*`，不区分大小写）
+    pub model_pattern: String,
+    /// 按优先顺序排列的跳
+    pub hops: Vec<FailoverHop>,
+}
+
+/// 故障转移链中的一跳
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FailoverHop {
+    /// Provider 类型或 Provider ID（如 `claude_oauth`、`anthropic`、`openrouter`）
+    pub provider: String,
+    /// 本跳内的重试次数（流式请求不重试）
+    #[serde(default)]
+    pub max_retries: u32,
+    /// 本跳失败后的冷却时间（秒），冷却期内该跳排到链尾；0 表示不冷却
+    #[serde(default = "default_failover_hop_cooldown_secs")]
+    pub cooldown_secs: u64,
+}
+
+fn default_failover_hop_cooldown_secs() -> u64 {
+    60
+}
+
+/// Provider 端点切换配置
+///
+/// 为 Provider 配置一组等价端点（官方镜像、区域端点）：凭证的 base_url 属于某组时，
+/// DNS 解析、连接或 TLS 握手失败会自动切换到组内下一个端点，失败的端点进入冷却。
+/// 自定义代理地址不在任何组内时不受影响。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EndpointFallbackSettings {
+    /// 是否启用（默认关闭）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 端点失败后的冷却时间（秒），冷却期内排到最后，仍作为最后手段
+    #[serde(default = "default_endpoint_fallback_cooldown_secs")]
+    pub cooldown_secs: u64,
+    /// 等价端点组
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<EndpointGroup>,
+}
+
+impl Default for EndpointFallbackSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cooldown_secs: default_endpoint_fallback_cooldown_secs(),
+            groups: Vec::new(),
         }
     }
 }
 
+/// 单个 Provider 的等价端点组
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EndpointGroup {
+    /// 凭证池 Provider 类型（如 `openai`、`claude`、`gemini_api_key`）
+    pub provider: String,
+    /// 等价端点（`scheme://host[:port]`，路径部分被忽略），按优先顺序排列
+    pub endpoints: Vec<String>,
+}
+
+fn default_endpoint_fallback_cooldown_secs() -> u64 {
+    300
+}
+
 /// 重试配置
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct RetrySettings {
@@ -2141,6 +2723,7 @@ impl Default for Config {
             ampcode: AmpConfig::default(),
             endpoint_providers: EndpointProvidersConfig::default(),
             minimize_to_tray: default_minimize_to_tray(),
+            background_mode: false,
             language: default_language(),
             models: ModelsConfig::default(),
             agent: NativeAgentConfig::default(),
@@ -2156,13 +2739,30 @@ impl Default for Config {
             image_gen: ImageGenConfig::default(),
             user_profile: UserProfile::default(),
             rate_limit: RateLimitSettings::default(),
+            api_key_rate_limit: ApiKeyRateLimitSettings::default(),
+            system_prompt_guard: SystemPromptGuardSettings::default(),
+            degenerate_retry: DegenerateRetrySettings::default(),
             crash_reporting: CrashReportingConfig::default(),
             conversation: ConversationSettings::default(),
             hint_router: HintRouterSettings::default(),
             pairing: PairingSettings::default(),
+            tenants: TenantSettings::default(),
+            cost_caps: CostCapSettings::default(),
+            cost_budgets: CostBudgetSettings::default(),
+            trace_sampling: TraceSamplingSettings::default(),
+            usage_analytics: UsageAnalyticsSettings::default(),
+            audit_log: AuditLogSettings::default(),
+            usage_reports: UsageReportSettings::default(),
+            plugin_health_check: PluginHealthCheckSettings::default(),
+            token_refresh: TokenRefreshSettings::default(),
+            adaptive_selection: AdaptiveSelectionSettings::default(),
+            rate_limit_cooldown: RateLimitCooldownSettings::default(),
             automation: AutomationSettings::default(),
             gateway: GatewayConfig::default(),
             channels: ChannelsConfig::default(),
+            extension_registries: ExtensionRegistrySettings::default(),
+            device_sync: DeviceSyncSettings::default(),
+            feature_flags: FeatureFlagSettings::default(),
         }
     }
 }
@@ -2632,6 +3232,9 @@ pub struct VoiceConfig {
     /// 启用语音输出
     #[serde(default)]
     pub voice_output_enabled: Option<bool>,
+    /// edge-tts 兼容服务地址（TTS 服务商为 edge 时使用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tts_endpoint: Option<String>,
 }
 
 /// 图像生成服务配置
@@ -2967,6 +3570,7 @@ mod unit_tests {
             vertex_api_keys: vec![],
             codex: vec![],
             asr: vec![],
+            migrated_ids: vec![],
         };
 
         let yaml = serde_yaml::to_string(&pool).unwrap();
@@ -3002,6 +3606,17 @@ mod unit_tests {
         assert_eq!(config.max_entries, 200);
         assert_eq!(config.max_body_bytes, 1_048_576);
         assert_eq!(config.cacheable_status_codes, vec![200]);
+        assert_eq!(config.mode, ResponseCacheMode::Exact);
+        assert!(config.routes.is_empty());
+    }
+
+    #[test]
+    fn test_response_cache_settings_deserialize_mode_and_routes() {
+        let config: ResponseCacheSettings =
+            serde_yaml::from_str("mode: semantic\nroutes:\n  anthropic_messages: false\n").unwrap();
+        assert_eq!(config.mode, ResponseCacheMode::Semantic);
+        assert_eq!(config.routes.get("anthropic_messages"), Some(&false));
+        assert_eq!(config.ttl_secs, 600);
     }
 
     #[test]
@@ -3308,6 +3923,7 @@ mod unit_tests {
                 baidu_config: None,
                 openai_config: None,
             }],
+            migrated_ids: vec![],
         };
 
         let yaml = serde_yaml::to_string(&pool).unwrap();
@@ -3462,27 +4078,316 @@ impl Default for RateLimitSettings {
     }
 }
 
-/// 对话管理配置
+/// 按 API Key 的令牌桶限流配置
+///
+/// 每个 API Key 一个令牌桶：容量为 `burst`，按 `requests_per_minute` 匀速补充。
+/// 单个 Key 的覆盖值保存在数据库 `api_key_rate_limits` 表中。
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
-pub struct ConversationSettings {
-    #[serde(default)]
-    pub trim_enabled: bool,
-    #[serde(default = "default_max_messages")]
-    pub max_messages: usize,
+pub struct ApiKeyRateLimitSettings {
     #[serde(default)]
-    pub summary_enabled: bool,
+    pub enabled: bool,
+    /// 每分钟补充的令牌数（持续速率）
+    #[serde(default = "default_api_key_requests_per_minute")]
+    pub requests_per_minute: u32,
+    /// 桶容量（允许的突发请求数）
+    #[serde(default = "default_api_key_burst")]
+    pub burst: u32,
 }
 
-fn default_max_messages() -> usize {
-    50
+fn default_api_key_requests_per_minute() -> u32 {
+    120
+}
+fn default_api_key_burst() -> u32 {
+    20
 }
 
-impl Default for ConversationSettings {
+impl Default for ApiKeyRateLimitSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            requests_per_minute: default_api_key_requests_per_minute(),
+            burst: default_api_key_burst(),
+        }
+    }
+}
+
+/// 系统提示词护栏注入配置
+///
+/// 对经代理转发的 `/v1/messages`、`/v1/chat/completions`、`/v1/responses` 请求，
+/// 按 API Key / 租户 / 路由匹配策略，将组织要求的护栏文本注入 system 提示词。
+/// 命中的所有策略按声明顺序注入，注入内容记入内存审计。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SystemPromptGuardSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 护栏策略
+    #[serde(default)]
+    pub policies: Vec<SystemPromptGuardPolicy>,
+    /// 内存中保留的注入审计条数
+    #[serde(default = "default_system_prompt_guard_audit_capacity")]
+    pub audit_capacity: usize,
+}
+
+fn default_system_prompt_guard_audit_capacity() -> usize {
+    500
+}
+
+impl Default for SystemPromptGuardSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            policies: Vec::new(),
+            audit_capacity: default_system_prompt_guard_audit_capacity(),
+        }
+    }
+}
+
+impl SystemPromptGuardSettings {
+    /// 校验配置
+    pub fn validate(&self) -> Result<(), String> {
+        let mut seen = std::collections::HashSet::new();
+        for policy in &self.policies {
+            let id = policy.id.trim();
+            if id.is_empty() {
+                return Err("护栏策略 ID 不能为空".to_string());
+            }
+            if !seen.insert(id) {
+                return Err(format!("护栏策略 ID 重复: {id}"));
+            }
+            if policy.template.trim().is_empty() {
+                return Err(format!("护栏策略 {id} 的模板不能为空"));
+            }
+            for route in policy.routes.iter().chain(&policy.exempt_routes) {
+                if !route.starts_with('/') {
+                    return Err(format!("护栏策略 {id} 的路由必须以 / 开头: {route}"));
+                }
+                if route.trim_end_matches('*').contains('*') {
+                    return Err(format!("护栏策略 {id} 的路由仅支持末尾通配符 *: {route}"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 护栏文本的注入位置
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemPromptGuardPosition {
+    /// 追加到客户端 system 提示词之后
+    #[default]
+    Append,
+    /// 插入到客户端 system 提示词之前
+    Prepend,
+}
+
+/// 单条护栏策略
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SystemPromptGuardPolicy {
+    /// 策略 ID（唯一，写入审计）
+    pub id: String,
+    /// 显示名称
+    #[serde(default)]
+    pub name: String,
+    /// 是否启用
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 匹配的 API Key（与 `tenants` 均为空时匹配所有 Key）
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    /// 匹配的租户 ID
+    #[serde(default)]
+    pub tenants: Vec<String>,
+    /// 生效路由（精确匹配；以 `*` 结尾时按前缀匹配），为空时对所有支持的路由生效
+    #[serde(default)]
+    pub routes: Vec<String>,
+    /// 豁免路由，优先于 `routes`
+    #[serde(default)]
+    pub exempt_routes: Vec<String>,
+    /// 护栏文本模板，支持 `{{tenant}}`、`{{model}}`、`{{route}}`、`{{date}}`、`{{policy}}` 变量
+    pub template: String,
+    /// 注入位置
+    #[serde(default)]
+    pub position: SystemPromptGuardPosition,
+}
+
+impl SystemPromptGuardPolicy {
+    /// 判断请求是否命中该策略
+    pub fn matches(&self, api_key: Option<&str>, tenant: Option<&str>, path: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if self
+            .exempt_routes
+            .iter()
+            .any(|pattern| route_pattern_matches(pattern, path))
+        {
+            return false;
+        }
+        if !self.routes.is_empty()
+            && !self
+                .routes
+                .iter()
+                .any(|pattern| route_pattern_matches(pattern, path))
+        {
+            return false;
+        }
+        if self.api_keys.is_empty() && self.tenants.is_empty() {
+            return true;
+        }
+        let key_matched = api_key.is_some_and(|key| {
+            self.api_keys
+                .iter()
+                .any(|candidate| candidate.trim() == key.trim())
+        });
+        let tenant_matched =
+            tenant.is_some_and(|tenant| self.tenants.iter().any(|id| id.trim() == tenant));
+        key_matched || tenant_matched
+    }
+}
+
+/// 路由匹配：精确匹配；以 `*` 结尾时按前缀匹配
+fn route_pattern_matches(pattern: &str, path: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => path.starts_with(prefix),
+        None => path == pattern,
+    }
+}
+
+/// 空响应 / 退化响应自动重试配置
+///
+/// Provider 偶尔返回空内容或单个字符的非流式响应。命中判定规则时，
+/// 换用同类型的另一个凭证重试一次，原响应以 `degenerate` 状态写入审计日志。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DegenerateRetrySettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 文本内容（去除首尾空白后）少于该字符数视为退化；包含工具调用的响应不判定
+    #[serde(default = "default_degenerate_min_content_chars")]
+    pub min_content_chars: usize,
+    /// 视为退化的结束原因（如 `error`），不区分大小写，与内容长度无关
+    #[serde(default)]
+    pub finish_reasons: Vec<String>,
+    /// 请求的 `max_tokens` 不超过该值时不检测（短输出本身就是预期结果）
+    #[serde(default = "default_degenerate_exempt_max_tokens")]
+    pub exempt_max_tokens: u32,
+}
+
+fn default_degenerate_min_content_chars() -> usize {
+    2
+}
+
+fn default_degenerate_exempt_max_tokens() -> u32 {
+    16
+}
+
+impl Default for DegenerateRetrySettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_content_chars: default_degenerate_min_content_chars(),
+            finish_reasons: Vec::new(),
+            exempt_max_tokens: default_degenerate_exempt_max_tokens(),
+        }
+    }
+}
+
+/// 对话管理配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConversationSettings {
+    #[serde(default)]
+    pub trim_enabled: bool,
+    #[serde(default = "default_max_messages")]
+    pub max_messages: usize,
+    #[serde(default)]
+    pub summary_enabled: bool,
+    /// 上下文窗口修剪配置
+    #[serde(default)]
+    pub context_trim: ContextTrimSettings,
+}
+
+fn default_max_messages() -> usize {
+    50
+}
+
+impl Default for ConversationSettings {
     fn default() -> Self {
         Self {
             trim_enabled: false,
             max_messages: 50,
             summary_enabled: false,
+            context_trim: ContextTrimSettings::default(),
+        }
+    }
+}
+
+/// 上下文修剪策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ContextTrimStrategy {
+    /// 从最旧的消息开始丢弃
+    #[default]
+    DropOldest,
+    /// 保留首条消息与最近消息，丢弃中间部分
+    DropMiddle,
+    /// 丢弃最旧的消息，并在 system 提示词中附上标注为网关生成的摘录说明
+    SummarizeOldest,
+}
+
+impl ContextTrimStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::DropOldest => "drop_oldest",
+            Self::DropMiddle => "drop_middle",
+            Self::SummarizeOldest => "summarize_oldest",
+        }
+    }
+}
+
+impl std::str::FromStr for ContextTrimStrategy {
+    type Err = String;
+
+    /// 同时接受 `drop_oldest` 与 `drop-oldest` 两种写法
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "drop_oldest" => Ok(Self::DropOldest),
+            "drop_middle" => Ok(Self::DropMiddle),
+            "summarize_oldest" => Ok(Self::SummarizeOldest),
+            other => Err(format!("未知的上下文修剪策略: {other}")),
+        }
+    }
+}
+
+/// 上下文窗口修剪配置
+///
+/// 组装后的请求超过目标模型上下文窗口时，在分发给 Provider 前按策略修剪消息。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContextTrimSettings {
+    /// 是否启用
+    #[serde(default)]
+    pub enabled: bool,
+    /// 默认策略
+    #[serde(default)]
+    pub strategy: ContextTrimStrategy,
+    /// 为模型输出预留的 token 数（请求未指定 max_tokens 时使用）
+    #[serde(default = "default_context_trim_reserve_output_tokens")]
+    pub reserve_output_tokens: u32,
+    /// 按路由覆盖策略（键为请求路径，如 `/v1/messages`）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub routes: HashMap<String, ContextTrimStrategy>,
+}
+
+fn default_context_trim_reserve_output_tokens() -> u32 {
+    4096
+}
+
+impl Default for ContextTrimSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strategy: ContextTrimStrategy::default(),
+            reserve_output_tokens: default_context_trim_reserve_output_tokens(),
+            routes: HashMap::new(),
         }
     }
 }
@@ -3511,6 +4416,663 @@ pub struct PairingSettings {
     pub enabled: bool,
 }
 
+/// 多租户命名空间配置
+///
+/// 多人共用同一台本地服务时，按 API Key 将请求归属到不同租户，
+/// 每个租户拥有独立的凭证子集、速率限制与用量统计。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct TenantSettings {
+    /// 是否启用租户隔离（关闭时租户 API Key 不会被接受）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 租户列表
+    #[serde(default)]
+    pub tenants: Vec<TenantEntry>,
+}
+
+/// 租户条目
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TenantEntry {
+    /// 租户 ID（唯一）
+    pub id: String,
+    /// 显示名称
+    #[serde(default)]
+    pub name: String,
+    /// 归属到该租户的 API Key 列表
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    /// 允许使用的凭证 UUID（为空表示不按 UUID 限制）
+    #[serde(default)]
+    pub allowed_credentials: Vec<String>,
+    /// 允许使用的 Provider 类型（为空表示不按 Provider 限制）
+    #[serde(default)]
+    pub allowed_providers: Vec<String>,
+    /// 租户级速率限制（为空时不做租户级限流）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitSettings>,
+    /// 是否启用
+    #[serde(default = "default_tenant_enabled")]
+    pub enabled: bool,
+}
+
+fn default_tenant_enabled() -> bool {
+    true
+}
+
+/// 月度费用硬上限配置
+///
+/// 费用按模型注册表中的定价（每百万 Token）估算并折算为 USD，按自然月累计。
+/// 超出上限后拒绝付费 Provider 的请求，免费/本地 Provider 不受影响。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CostCapSettings {
+    /// 是否启用费用上限
+    #[serde(default)]
+    pub enabled: bool,
+    /// 全局月度上限（USD，为空表示不限制）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_limit_usd: Option<f64>,
+    /// 按 Provider 的月度上限（USD）
+    #[serde(default)]
+    pub provider_limits_usd: HashMap<String, f64>,
+    /// 视为免费的 Provider（本地模型等），不计费也不受上限约束
+    #[serde(default = "default_cost_cap_free_providers")]
+    pub free_providers: Vec<String>,
+    /// 达到上限的该比例时发送预警事件（0~1）
+    #[serde(default = "default_cost_cap_warning_ratio")]
+    pub warning_ratio: f64,
+    /// CNY 定价折算为 USD 的汇率（1 CNY = ? USD）
+    #[serde(default = "default_cost_cap_cny_to_usd_rate")]
+    pub cny_to_usd_rate: f64,
+}
+
+fn default_cost_cap_free_providers() -> Vec<String> {
+    vec!["ollama".to_string(), "mock".to_string()]
+}
+
+fn default_cost_cap_warning_ratio() -> f64 {
+    0.8
+}
+
+fn default_cost_cap_cny_to_usd_rate() -> f64 {
+    0.14
+}
+
+impl Default for CostCapSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            monthly_limit_usd: None,
+            provider_limits_usd: HashMap::new(),
+            free_providers: default_cost_cap_free_providers(),
+            warning_ratio: default_cost_cap_warning_ratio(),
+            cny_to_usd_rate: default_cost_cap_cny_to_usd_rate(),
+        }
+    }
+}
+
+/// 预算周期
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Daily,
+    Monthly,
+}
+
+/// 费用预算（只提醒，不拦截请求）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CostBudget {
+    pub period: BudgetPeriod,
+    /// 限定 Provider（为空表示全部 Provider）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// 预算金额（USD）
+    pub amount_usd: f64,
+}
+
+/// 费用预算提醒配置
+///
+/// 每个请求的 Token 与费用写入 `request_costs` 明细表，按日/月累计花费，
+/// 达到预算的提醒比例或超出预算时发送事件。与费用上限不同，预算不会拒绝请求。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CostBudgetSettings {
+    /// 是否启用预算提醒
+    #[serde(default)]
+    pub enabled: bool,
+    /// 预算列表
+    #[serde(default)]
+    pub budgets: Vec<CostBudget>,
+    /// 达到预算的该比例时发送提醒事件（0~1）
+    #[serde(default = "default_cost_budget_alert_ratio")]
+    pub alert_ratio: f64,
+}
+
+fn default_cost_budget_alert_ratio() -> f64 {
+    0.8
+}
+
+impl Default for CostBudgetSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            budgets: Vec::new(),
+            alert_ratio: default_cost_budget_alert_ratio(),
+        }
+    }
+}
+
+/// 请求追踪尾部采样配置
+///
+/// 每个请求的详细追踪事件先缓冲在内存中，请求结束后按结果决定是否持久化：
+/// 出错的请求按 `error_sample_rate`、超过 `latency_threshold_ms` 的慢请求按
+/// `slow_sample_rate`、其余请求按 `success_sample_rate` 采样。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TraceSamplingSettings {
+    /// 是否启用尾部采样
+    #[serde(default = "default_trace_sampling_enabled")]
+    pub enabled: bool,
+    /// 慢请求阈值（毫秒）
+    #[serde(default = "default_trace_latency_threshold_ms")]
+    pub latency_threshold_ms: u64,
+    /// 出错请求的采样率（0~1）
+    #[serde(default = "default_trace_full_sample_rate")]
+    pub error_sample_rate: f64,
+    /// 慢请求的采样率（0~1）
+    #[serde(default = "default_trace_full_sample_rate")]
+    pub slow_sample_rate: f64,
+    /// 正常请求的采样率（0~1，默认不保留）
+    #[serde(default)]
+    pub success_sample_rate: f64,
+    /// 最多保留的追踪条数
+    #[serde(default = "default_trace_max_traces")]
+    pub max_traces: usize,
+}
+
+fn default_trace_sampling_enabled() -> bool {
+    true
+}
+
+fn default_trace_latency_threshold_ms() -> u64 {
+    30_000
+}
+
+fn default_trace_full_sample_rate() -> f64 {
+    1.0
+}
+
+fn default_trace_max_traces() -> usize {
+    200
+}
+
+impl Default for TraceSamplingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_trace_sampling_enabled(),
+            latency_threshold_ms: default_trace_latency_threshold_ms(),
+            error_sample_rate: default_trace_full_sample_rate(),
+            slow_sample_rate: default_trace_full_sample_rate(),
+            success_sample_rate: 0.0,
+            max_traces: default_trace_max_traces(),
+        }
+    }
+}
+
+/// 匿名用量统计配置
+///
+/// 默认关闭。开启后仅在本地累计粗粒度计数（各 Provider 请求数、功能使用次数），
+/// 不记录请求内容、模型名、凭证或任何标识；导出摘要时按 `epsilon` 为每个计数加入拉普拉斯噪声。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsageAnalyticsSettings {
+    /// 是否启用
+    #[serde(default)]
+    pub enabled: bool,
+    /// 导出摘要的隐私预算（越小噪声越大）
+    #[serde(default = "default_usage_analytics_epsilon")]
+    pub epsilon: f64,
+}
+
+fn default_usage_analytics_epsilon() -> f64 {
+    1.0
+}
+
+impl Default for UsageAnalyticsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            epsilon: default_usage_analytics_epsilon(),
+        }
+    }
+}
+
+/// 请求审计日志配置
+///
+/// 默认关闭。开启后每个请求的脱敏元数据（Provider、模型、凭证 ID、耗时、状态、Token 数、
+/// 错误信息与截断的请求体）写入 SQLite，并建立全文索引用于检索。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuditLogSettings {
+    /// 是否启用
+    #[serde(default)]
+    pub enabled: bool,
+    /// 是否记录请求体（脱敏后截断）
+    #[serde(default = "default_audit_log_include_body")]
+    pub include_request_body: bool,
+    /// 请求体最多保留的字符数
+    #[serde(default = "default_audit_log_max_body_chars")]
+    pub max_body_chars: usize,
+    /// 保留天数（0 表示不自动清理）
+    #[serde(default = "default_audit_log_retention_days")]
+    pub retention_days: u32,
+}
+
+fn default_audit_log_include_body() -> bool {
+    true
+}
+
+fn default_audit_log_max_body_chars() -> usize {
+    4096
+}
+
+fn default_audit_log_retention_days() -> u32 {
+    30
+}
+
+impl Default for AuditLogSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            include_request_body: default_audit_log_include_body(),
+            max_body_chars: default_audit_log_max_body_chars(),
+            retention_days: default_audit_log_retention_days(),
+        }
+    }
+}
+
+// ============ 用量报告配置类型 ============
+
+/// 用量报告周期
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageReportPeriod {
+    /// 前一自然日（UTC）
+    #[default]
+    Daily,
+    /// 前一个完整的自然周（UTC，周一至周日）
+    Weekly,
+}
+
+/// 用量报告输出格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageReportFormat {
+    Markdown,
+    Csv,
+}
+
+/// 定时用量报告配置
+///
+/// 默认关闭。开启后每个周期结束时汇总请求数、Token、费用与错误率（按 Provider、
+/// 模型、凭证与租户分组），写入输出目录，配置了 Webhook 时同时推送。
+/// 错误率来自请求审计日志，未开启审计日志时不统计。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsageReportSettings {
+    /// 是否启用定时报告
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub period: UsageReportPeriod,
+    /// 输出格式（为空时使用 Markdown）
+    #[serde(default = "default_usage_report_formats")]
+    pub formats: Vec<UsageReportFormat>,
+    /// 输出目录（为空时写入应用数据目录下的 `reports`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_dir: Option<String>,
+    /// 报告生成后 POST 的 Webhook 地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook_url: Option<String>,
+}
+
+fn default_usage_report_formats() -> Vec<UsageReportFormat> {
+    vec![UsageReportFormat::Markdown]
+}
+
+impl Default for UsageReportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            period: UsageReportPeriod::default(),
+            formats: default_usage_report_formats(),
+            output_dir: None,
+            webhook_url: None,
+        }
+    }
+}
+
+/// 插件凭证定时健康检查配置
+///
+/// 对 Provider 类型由插件声明了健康探测（plugin.json `health_probes`）的凭证池凭证，
+/// 按间隔主动执行探测，结果写入凭证健康状态并发布 `HealthChanged` 事件。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PluginHealthCheckSettings {
+    /// 是否启用定时检查
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 检查间隔（分钟，最小 5）
+    #[serde(default = "default_plugin_health_check_interval_minutes")]
+    pub interval_minutes: u64,
+}
+
+fn default_plugin_health_check_interval_minutes() -> u64 {
+    30
+}
+
+impl Default for PluginHealthCheckSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: default_plugin_health_check_interval_minutes(),
+        }
+    }
+}
+
+/// OAuth Token 后台刷新配置
+///
+/// 定时扫描凭证池中缓存 Token 的过期时间，在即将过期前主动刷新，
+/// 避免请求时才懒刷新带来的首包延迟与 401。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenRefreshSettings {
+    /// 是否启用后台刷新
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 扫描间隔（分钟，最小 1）
+    #[serde(default = "default_token_refresh_scan_interval_minutes")]
+    pub scan_interval_minutes: u64,
+    /// 刷新窗口：Token 在该分钟数内过期即刷新
+    #[serde(default = "default_token_refresh_window_minutes")]
+    pub refresh_window_minutes: u64,
+    /// 单个凭证每轮最多尝试次数（网络类错误才重试）
+    #[serde(default = "default_token_refresh_max_attempts")]
+    pub max_attempts: u32,
+    /// 重试基础间隔（秒），按指数退避并叠加随机抖动
+    #[serde(default = "default_token_refresh_retry_base_secs")]
+    pub retry_base_secs: u64,
+}
+
+fn default_token_refresh_scan_interval_minutes() -> u64 {
+    5
+}
+
+fn default_token_refresh_window_minutes() -> u64 {
+    15
+}
+
+fn default_token_refresh_max_attempts() -> u32 {
+    3
+}
+
+fn default_token_refresh_retry_base_secs() -> u64 {
+    5
+}
+
+impl Default for TokenRefreshSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            scan_interval_minutes: default_token_refresh_scan_interval_minutes(),
+            refresh_window_minutes: default_token_refresh_window_minutes(),
+            max_attempts: default_token_refresh_max_attempts(),
+            retry_base_secs: default_token_refresh_retry_base_secs(),
+        }
+    }
+}
+
+/// 自适应凭证选择配置
+///
+/// 开启后按模型统计各凭证的成功率与延迟，用 ε-greedy 策略把流量逐步转向表现更好的凭证：
+/// 以 `exploration_rate` 的概率随机选择以持续探索，其余时候选择得分最高的凭证。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdaptiveSelectionSettings {
+    /// 是否启用（关闭时使用默认的权重评分选择）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 探索概率（0-1）
+    #[serde(default = "default_adaptive_exploration_rate")]
+    pub exploration_rate: f64,
+    /// 延迟在得分中的占比（0-1），其余为成功率
+    #[serde(default = "default_adaptive_latency_weight")]
+    pub latency_weight: f64,
+    /// 参考延迟（毫秒）：延迟等于该值时延迟得分为 0.5
+    #[serde(default = "default_adaptive_reference_latency_ms")]
+    pub reference_latency_ms: u64,
+    /// 每次记录结果时历史统计的衰减系数（0-1，越小越快遗忘旧数据）
+    #[serde(default = "default_adaptive_decay")]
+    pub decay: f64,
+}
+
+fn default_adaptive_exploration_rate() -> f64 {
+    0.1
+}
+
+fn default_adaptive_latency_weight() -> f64 {
+    0.3
+}
+
+fn default_adaptive_reference_latency_ms() -> u64 {
+    5_000
+}
+
+fn default_adaptive_decay() -> f64 {
+    0.98
+}
+
+impl Default for AdaptiveSelectionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            exploration_rate: default_adaptive_exploration_rate(),
+            latency_weight: default_adaptive_latency_weight(),
+            reference_latency_ms: default_adaptive_reference_latency_ms(),
+            decay: default_adaptive_decay(),
+        }
+    }
+}
+
+/// 凭证限流冷却配置
+///
+/// 凭证收到 429 后进入冷却期，选择凭证时冷却中的凭证让位于其他可用凭证。
+/// `adaptive` 模式按凭证学习到的限流节奏调整冷却时长，并在发请求前主动节流。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RateLimitCooldownSettings {
+    /// 冷却模式（`fixed` / `adaptive`）
+    #[serde(default)]
+    pub mode: CooldownMode,
+    /// 基础冷却时间（秒）
+    #[serde(default = "default_rate_limit_base_cooldown_secs")]
+    pub base_cooldown_secs: u64,
+    /// 最大冷却时间（秒）
+    #[serde(default = "default_rate_limit_max_cooldown_secs")]
+    pub max_cooldown_secs: u64,
+}
+
+fn default_rate_limit_base_cooldown_secs() -> u64 {
+    60
+}
+
+fn default_rate_limit_max_cooldown_secs() -> u64 {
+    3600
+}
+
+impl Default for RateLimitCooldownSettings {
+    fn default() -> Self {
+        Self {
+            mode: CooldownMode::default(),
+            base_cooldown_secs: default_rate_limit_base_cooldown_secs(),
+            max_cooldown_secs: default_rate_limit_max_cooldown_secs(),
+        }
+    }
+}
+
+// ============ 扩展注册表配置类型 ============
+
+/// 扩展注册表配置
+///
+/// 插件与 Skill 可从自托管的索引 JSON 发现和安装（HTTPS + minisign 签名），
+/// 企业内网可通过 `disable_github_sources` 完全停用 GitHub 仓库查询。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ExtensionRegistrySettings {
+    /// 注册表列表（按顺序查询，同名条目以先出现者为准）
+    #[serde(default)]
+    pub registries: Vec<ExtensionRegistryConfig>,
+    /// 停用内置的 GitHub Skill 仓库查询
+    #[serde(default)]
+    pub disable_github_sources: bool,
+}
+
+/// 单个扩展注册表
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExtensionRegistryConfig {
+    /// 注册表 ID（唯一）
+    pub id: String,
+    /// 显示名称
+    #[serde(default)]
+    pub name: String,
+    /// 索引 JSON 地址（必须为 HTTPS），签名位于 `<index_url>.minisig`
+    pub index_url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// minisign 公钥（base64，即 `minisign -G` 生成的 `RW...` 字符串）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// 信任策略
+    #[serde(default)]
+    pub trust: RegistryTrustPolicy,
+}
+
+/// 注册表信任策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistryTrustPolicy {
+    /// 索引必须通过公钥签名校验
+    #[default]
+    Signed,
+    /// 接受未签名的索引（仅建议用于受控内网），包仍按 sha256 校验
+    Unsigned,
+}
+
+// ============ 设备同步配置类型 ============
+
+/// 设备间同步配置
+///
+/// 选定类别的本地状态加密后存放到用户自备的存储（同步文件夹、WebDAV 或 S3 兼容端点），
+/// 同步口令仅在每次推送 / 拉取时输入，不写入配置。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct DeviceSyncSettings {
+    /// 是否启用设备同步
+    #[serde(default)]
+    pub enabled: bool,
+    /// 本设备显示名称（为空时使用主机名）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_name: Option<String>,
+    /// 存储类型
+    #[serde(default)]
+    pub storage: DeviceSyncStorageKind,
+    /// 同步文件夹路径（storage = folder，支持 `~`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder_path: Option<String>,
+    #[serde(default)]
+    pub webdav: DeviceSyncWebdavConfig,
+    #[serde(default)]
+    pub s3: DeviceSyncS3Config,
+    /// 参与同步的类别
+    #[serde(default)]
+    pub categories: DeviceSyncCategories,
+}
+
+/// 设备同步存储类型
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceSyncStorageKind {
+    /// 本地同步文件夹（如 iCloud Drive / Dropbox / Syncthing 目录）
+    #[default]
+    Folder,
+    Webdav,
+    S3,
+}
+
+/// WebDAV 存储配置
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct DeviceSyncWebdavConfig {
+    /// 存放同步包的目录地址（例如 https://dav.example.com/lime/）
+    #[serde(default)]
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// S3 兼容存储配置（使用 path-style 地址与 SigV4 签名）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceSyncS3Config {
+    /// 端点地址（例如 https://s3.us-east-1.amazonaws.com 或 MinIO / R2 地址）
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default = "default_device_sync_s3_region")]
+    pub region: String,
+    #[serde(default)]
+    pub bucket: String,
+    /// 对象键前缀（例如 `lime/`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub access_key_id: String,
+    #[serde(default)]
+    pub secret_access_key: String,
+}
+
+fn default_device_sync_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+impl Default for DeviceSyncS3Config {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            region: default_device_sync_s3_region(),
+            bucket: String::new(),
+            prefix: None,
+            access_key_id: String::new(),
+            secret_access_key: String::new(),
+        }
+    }
+}
+
+/// 按类别选择参与同步的内容
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceSyncCategories {
+    /// 应用配置（不含本节）
+    #[serde(default = "default_true")]
+    pub settings: bool,
+    /// API Key Provider 与 OAuth 凭证文件（需显式开启）
+    #[serde(default)]
+    pub credentials: bool,
+    /// 本地 Skill 目录
+    #[serde(default = "default_true")]
+    pub skills: bool,
+    /// Prompt 库
+    #[serde(default = "default_true")]
+    pub prompts: bool,
+}
+
+impl Default for DeviceSyncCategories {
+    fn default() -> Self {
+        Self {
+            settings: true,
+            credentials: false,
+            skills: true,
+            prompts: true,
+        }
+    }
+}
+
 // ============ Gateway 配置类型 ============
 
 /// Gateway 全局配置
@@ -855,6 +855,54 @@ server:
         );
     }
 
+    #[test]
+    fn test_parse_yaml_with_route_auth_settings() {
+        use crate::config::RouteAuthMode;
+
+        let yaml = r#"
+server:
+  route_auth:
+    default_mode: api_key
+    rules:
+      - path: /health
+        mode: none
+      - path: /v1/models
+        mode: os_user
+      - path: /internal/*
+        mode: os_user
+"#;
+        let config = ConfigManager::parse_yaml(yaml).unwrap();
+        let route_auth = &config.server.route_auth;
+        assert_eq!(route_auth.resolve("/health"), RouteAuthMode::None);
+        assert_eq!(route_auth.resolve("/v1/models"), RouteAuthMode::OsUser);
+        assert_eq!(route_auth.resolve("/internal/debug"), RouteAuthMode::OsUser);
+        assert_eq!(
+            route_auth.resolve("/v1/chat/completions"),
+            RouteAuthMode::ApiKey
+        );
+        assert!(route_auth.validate("127.0.0.1").is_ok());
+
+        // 默认配置：健康检查开放，诊断接口仅限本机用户
+        let defaults = Config::default().server.route_auth;
+        assert_eq!(defaults.resolve("/health"), RouteAuthMode::None);
+        assert_eq!(defaults.resolve("/stats"), RouteAuthMode::OsUser);
+        assert_eq!(defaults.resolve("/v1/messages"), RouteAuthMode::ApiKey);
+        assert!(defaults.validate("0.0.0.0").is_ok());
+
+        let open_on_lan = ConfigManager::parse_yaml(
+            r#"
+server:
+  route_auth:
+    rules:
+      - path: /v1/models
+        mode: none
+"#,
+        )
+        .unwrap();
+        assert!(open_on_lan.server.route_auth.validate("127.0.0.1").is_ok());
+        assert!(open_on_lan.server.route_auth.validate("0.0.0.0").is_err());
+    }
+
     #[test]
     fn test_export_redacted() {
         let mut config = Config::default();
@@ -0,0 +1,413 @@
+//! CPU 密集型任务线程池
+//!
+//! Whisper 转写、大请求的协议转换与 Token 计数直接在异步运行时上执行会占住工作线程，
+//! 导致同一运行时上的流式响应卡顿。这类任务统一提交到独立的 CPU 工作线程池：
+//! - 任务按优先级（高 / 普通 / 低）出队，同优先级先进先出
+//! - [`run_cpu_task`] 提交闭包并在异步上下文中等待结果，任务 panic 时返回错误而不影响线程池
+//! - [`cpu_pool_stats`] 返回线程利用率、排队数与按任务类型的耗时统计
+
+use parking_lot::{Condvar, Mutex};
+use serde::{Deserialize, Serialize};
+use std::collections::{BinaryHeap, HashMap};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// 默认线程池的最大线程数
+const MAX_DEFAULT_WORKERS: usize = 8;
+
+/// 任务优先级
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CpuTaskPriority {
+    /// 后台任务（如索引、统计）
+    Low,
+    /// 请求路径上的计算（如 Token 计数、协议转换）
+    Normal,
+    /// 用户正在等待的任务（如语音转写）
+    High,
+}
+
+/// 任务类型（用于统计）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CpuTaskKind {
+    Transcription,
+    JsonTransform,
+    TokenCount,
+    Other,
+}
+
+impl CpuTaskKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Transcription => "transcription",
+            Self::JsonTransform => "json_transform",
+            Self::TokenCount => "token_count",
+            Self::Other => "other",
+        }
+    }
+}
+
+/// CPU 任务错误
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CpuTaskError {
+    #[error("CPU 任务 {0} 执行时 panic")]
+    Panicked(&'static str),
+}
+
+/// 按任务类型的统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuTaskKindStats {
+    pub kind: String,
+    pub completed: u64,
+    pub panicked: u64,
+    /// 平均排队时间（毫秒）
+    pub avg_queue_ms: f64,
+    /// 平均执行时间（毫秒）
+    pub avg_run_ms: f64,
+    /// 最长执行时间（毫秒）
+    pub max_run_ms: u64,
+}
+
+/// 线程池统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CpuPoolStats {
+    pub workers: usize,
+    /// 正在执行任务的线程数
+    pub busy_workers: usize,
+    /// 排队中的任务数（按优先级）
+    pub queued_high: usize,
+    pub queued_normal: usize,
+    pub queued_low: usize,
+    pub completed: u64,
+    pub panicked: u64,
+    /// 启动以来的平均利用率（0.0-1.0）
+    pub utilization: f64,
+    pub kinds: Vec<CpuTaskKindStats>,
+}
+
+type Job = Box<dyn FnOnce() -> bool + Send>;
+
+struct QueuedTask {
+    priority: CpuTaskPriority,
+    seq: u64,
+    kind: CpuTaskKind,
+    enqueued_at: Instant,
+    job: Job,
+}
+
+impl PartialEq for QueuedTask {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.seq == other.seq
+    }
+}
+
+impl Eq for QueuedTask {}
+
+impl PartialOrd for QueuedTask {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// 大顶堆：优先级高者在前，同优先级序号小者在前
+impl Ord for QueuedTask {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+#[derive(Default)]
+struct KindCounters {
+    completed: u64,
+    panicked: u64,
+    total_queue: Duration,
+    total_run: Duration,
+    max_run: Duration,
+}
+
+struct Shared {
+    queue: Mutex<BinaryHeap<QueuedTask>>,
+    available: Condvar,
+    seq: AtomicU64,
+    busy: AtomicUsize,
+    busy_nanos: AtomicU64,
+    started_at: Instant,
+    counters: Mutex<HashMap<CpuTaskKind, KindCounters>>,
+}
+
+/// CPU 工作线程池
+pub struct CpuPool {
+    shared: Arc<Shared>,
+    workers: usize,
+}
+
+impl CpuPool {
+    /// 创建线程池（至少 1 个线程）
+    pub fn new(workers: usize) -> Self {
+        let workers = workers.max(1);
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(BinaryHeap::new()),
+            available: Condvar::new(),
+            seq: AtomicU64::new(0),
+            busy: AtomicUsize::new(0),
+            busy_nanos: AtomicU64::new(0),
+            started_at: Instant::now(),
+            counters: Mutex::new(HashMap::new()),
+        });
+        for index in 0..workers {
+            let shared = shared.clone();
+            let spawned = std::thread::Builder::new()
+                .name(format!("lime-cpu-{index}"))
+                .spawn(move || worker_loop(&shared));
+            if let Err(e) = spawned {
+                tracing::error!("[CPU_POOL] 创建工作线程失败: {}", e);
+            }
+        }
+        Self { shared, workers }
+    }
+
+    /// 提交任务，返回结果接收端
+    pub fn submit<T, F>(
+        &self,
+        priority: CpuTaskPriority,
+        kind: CpuTaskKind,
+        task: F,
+    ) -> oneshot::Receiver<Result<T, CpuTaskError>>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            let result = catch_unwind(AssertUnwindSafe(task));
+            let panicked = result.is_err();
+            let _ = tx.send(result.map_err(|_| CpuTaskError::Panicked(kind.as_str())));
+            panicked
+        });
+        let task = QueuedTask {
+            priority,
+            seq: self.shared.seq.fetch_add(1, Ordering::Relaxed),
+            kind,
+            enqueued_at: Instant::now(),
+            job,
+        };
+        self.shared.queue.lock().push(task);
+        self.shared.available.notify_one();
+        rx
+    }
+
+    /// 提交任务并等待结果
+    pub async fn run<T, F>(
+        &self,
+        priority: CpuTaskPriority,
+        kind: CpuTaskKind,
+        task: F,
+    ) -> Result<T, CpuTaskError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        self.submit(priority, kind, task)
+            .await
+            .unwrap_or(Err(CpuTaskError::Panicked(kind.as_str())))
+    }
+
+    pub fn stats(&self) -> CpuPoolStats {
+        let (queued_high, queued_normal, queued_low) = {
+            let queue = self.shared.queue.lock();
+            queue
+                .iter()
+                .fold((0, 0, 0), |(high, normal, low), task| match task.priority {
+                    CpuTaskPriority::High => (high + 1, normal, low),
+                    CpuTaskPriority::Normal => (high, normal + 1, low),
+                    CpuTaskPriority::Low => (high, normal, low + 1),
+                })
+        };
+        let mut kinds: Vec<CpuTaskKindStats> = self
+            .shared
+            .counters
+            .lock()
+            .iter()
+            .map(|(kind, counters)| {
+                let finished = (counters.completed + counters.panicked).max(1) as f64;
+                CpuTaskKindStats {
+                    kind: kind.as_str().to_string(),
+                    completed: counters.completed,
+                    panicked: counters.panicked,
+                    avg_queue_ms: counters.total_queue.as_secs_f64() * 1000.0 / finished,
+                    avg_run_ms: counters.total_run.as_secs_f64() * 1000.0 / finished,
+                    max_run_ms: counters.max_run.as_millis() as u64,
+                }
+            })
+            .collect();
+        kinds.sort_by(|a, b| a.kind.cmp(&b.kind));
+
+        let capacity = self.shared.started_at.elapsed().as_nanos() as f64 * self.workers as f64;
+        let busy = self.shared.busy_nanos.load(Ordering::Relaxed) as f64;
+        CpuPoolStats {
+            workers: self.workers,
+            busy_workers: self.shared.busy.load(Ordering::Relaxed),
+            queued_high,
+            queued_normal,
+            queued_low,
+            completed: kinds.iter().map(|k| k.completed).sum(),
+            panicked: kinds.iter().map(|k| k.panicked).sum(),
+            utilization: if capacity > 0.0 {
+                (busy / capacity).min(1.0)
+            } else {
+                0.0
+            },
+            kinds,
+        }
+    }
+}
+
+fn worker_loop(shared: &Shared) {
+    loop {
+        let task = {
+            let mut queue = shared.queue.lock();
+            loop {
+                if let Some(task) = queue.pop() {
+                    break task;
+                }
+                shared.available.wait(&mut queue);
+            }
+        };
+
+        shared.busy.fetch_add(1, Ordering::Relaxed);
+        let started = Instant::now();
+        let queue_wait = started.duration_since(task.enqueued_at);
+        let panicked = (task.job)();
+        let run = started.elapsed();
+        shared.busy.fetch_sub(1, Ordering::Relaxed);
+        shared
+            .busy_nanos
+            .fetch_add(run.as_nanos() as u64, Ordering::Relaxed);
+
+        let mut counters = shared.counters.lock();
+        let entry = counters.entry(task.kind).or_default();
+        if panicked {
+            entry.panicked += 1;
+            tracing::warn!("[CPU_POOL] {} 任务 panic", task.kind.as_str());
+        } else {
+            entry.completed += 1;
+        }
+        entry.total_queue += queue_wait;
+        entry.total_run += run;
+        entry.max_run = entry.max_run.max(run);
+    }
+}
+
+static CPU_POOL: OnceLock<CpuPool> = OnceLock::new();
+
+/// 全局 CPU 线程池（CPU 核数 - 1，1 到 8 个线程，首次使用时创建）
+pub fn cpu_pool() -> &'static CpuPool {
+    CPU_POOL.get_or_init(|| {
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get().saturating_sub(1))
+            .unwrap_or(1)
+            .clamp(1, MAX_DEFAULT_WORKERS);
+        tracing::info!("[CPU_POOL] 初始化 CPU 线程池: {} 个线程", workers);
+        CpuPool::new(workers)
+    })
+}
+
+/// 在全局 CPU 线程池中执行任务并等待结果
+pub async fn run_cpu_task<T, F>(
+    priority: CpuTaskPriority,
+    kind: CpuTaskKind,
+    task: F,
+) -> Result<T, CpuTaskError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    cpu_pool().run(priority, kind, task).await
+}
+
+/// 全局 CPU 线程池统计
+pub fn cpu_pool_stats() -> CpuPoolStats {
+    cpu_pool().stats()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[tokio::test]
+    async fn test_tasks_run_by_priority() {
+        let pool = CpuPool::new(1);
+        // 先用一个任务占住唯一的线程，保证后续任务都在排队
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let blocker = pool.submit(CpuTaskPriority::Normal, CpuTaskKind::Other, move || {
+            release_rx.recv().unwrap();
+        });
+
+        let (order_tx, order_rx) = mpsc::channel();
+        let receivers: Vec<_> = [
+            (CpuTaskPriority::Low, "low"),
+            (CpuTaskPriority::Normal, "normal-1"),
+            (CpuTaskPriority::High, "high"),
+            (CpuTaskPriority::Normal, "normal-2"),
+        ]
+        .into_iter()
+        .map(|(priority, label)| {
+            let order_tx = order_tx.clone();
+            pool.submit(priority, CpuTaskKind::TokenCount, move || {
+                order_tx.send(label).unwrap();
+            })
+        })
+        .collect();
+        let stats = pool.stats();
+        assert_eq!(
+            (stats.queued_high, stats.queued_normal, stats.queued_low),
+            (1, 2, 1)
+        );
+
+        release_tx.send(()).unwrap();
+        blocker.await.unwrap().unwrap();
+        for rx in receivers {
+            rx.await.unwrap().unwrap();
+        }
+        let order: Vec<_> = order_rx.try_iter().collect();
+        assert_eq!(order, vec!["high", "normal-1", "normal-2", "low"]);
+
+        let stats = pool.stats();
+        assert_eq!(stats.completed, 5);
+        assert_eq!(stats.busy_workers, 0);
+        let token_count = stats
+            .kinds
+            .iter()
+            .find(|k| k.kind == "token_count")
+            .unwrap();
+        assert_eq!(token_count.completed, 4);
+    }
+
+    #[tokio::test]
+    async fn test_panicking_task_returns_error() {
+        let pool = CpuPool::new(1);
+        let result = pool
+            .run(CpuTaskPriority::High, CpuTaskKind::JsonTransform, || {
+                panic!("boom");
+            })
+            .await;
+        assert_eq!(
+            result,
+            Err::<(), _>(CpuTaskError::Panicked("json_transform"))
+        );
+
+        // 线程池在 panic 后仍可用
+        let value = pool
+            .run(CpuTaskPriority::Low, CpuTaskKind::Other, || 40 + 2)
+            .await;
+        assert_eq!(value, Ok(42));
+        assert_eq!(pool.stats().panicked, 1);
+    }
+}
@@ -0,0 +1,119 @@
+//! 会话亲和
+//!
+//! 将会话/对话 ID 绑定到同一凭证（如 Claude OAuth 的对话缓存与账号绑定）。
+//! 绑定按作用域（通常为 Provider 类型）隔离，每次命中后顺延有效期，
+//! 在 TTL 内无请求时过期，由 [`SessionAffinity::purge_expired`] 定期清理。
+
+use chrono::{DateTime, Duration, Utc};
+use dashmap::DashMap;
+
+/// 默认会话亲和有效期（秒）
+pub const DEFAULT_AFFINITY_TTL_SECS: i64 = 30 * 60;
+
+/// 会话亲和绑定
+#[derive(Debug, Clone)]
+pub struct AffinityBinding {
+    /// 绑定的凭证 ID
+    pub credential_id: String,
+    /// 过期时间（每次命中后顺延）
+    pub expires_at: DateTime<Utc>,
+}
+
+/// 会话亲和表（(作用域, 会话 ID) -> 凭证）
+#[derive(Debug)]
+pub struct SessionAffinity {
+    bindings: DashMap<(String, String), AffinityBinding>,
+    ttl: Duration,
+}
+
+impl Default for SessionAffinity {
+    fn default() -> Self {
+        Self::new(Duration::seconds(DEFAULT_AFFINITY_TTL_SECS))
+    }
+}
+
+impl SessionAffinity {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            bindings: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// 绑定有效期
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// 查询会话当前的绑定（已过期时返回 None）
+    pub fn get(&self, scope: &str, session_id: &str) -> Option<AffinityBinding> {
+        let now = Utc::now();
+        self.bindings
+            .get(&(scope.to_string(), session_id.to_string()))
+            .filter(|binding| binding.expires_at > now)
+            .map(|binding| binding.clone())
+    }
+
+    /// 绑定（或改绑）会话到凭证，有效期从现在起重新计算
+    pub fn bind(&self, scope: &str, session_id: &str, credential_id: &str) {
+        self.bindings.insert(
+            (scope.to_string(), session_id.to_string()),
+            AffinityBinding {
+                credential_id: credential_id.to_string(),
+                expires_at: Utc::now() + self.ttl,
+            },
+        );
+    }
+
+    /// 解除会话绑定
+    pub fn clear(&self, scope: &str, session_id: &str) -> bool {
+        self.bindings
+            .remove(&(scope.to_string(), session_id.to_string()))
+            .is_some()
+    }
+
+    /// 解除作用域下的全部绑定
+    pub fn clear_scope(&self, scope: &str) {
+        self.bindings.retain(|(s, _), _| s != scope);
+    }
+
+    /// 清理过期的绑定，返回清理数量
+    pub fn purge_expired(&self) -> usize {
+        let now = Utc::now();
+        let before = self.bindings.len();
+        self.bindings.retain(|_, binding| binding.expires_at > now);
+        before - self.bindings.len()
+    }
+
+    /// 当前绑定数（含尚未清理的过期绑定）
+    pub fn len(&self) -> usize {
+        self.bindings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_and_expire() {
+        let affinity = SessionAffinity::default();
+        affinity.bind("claude", "conv-a", "cred-1");
+        assert_eq!(
+            affinity.get("claude", "conv-a").unwrap().credential_id,
+            "cred-1"
+        );
+        // 不同作用域互不影响
+        assert!(affinity.get("openai", "conv-a").is_none());
+
+        let expired = SessionAffinity::new(Duration::zero());
+        expired.bind("claude", "conv-a", "cred-1");
+        assert!(expired.get("claude", "conv-a").is_none());
+        assert_eq!(expired.purge_expired(), 1);
+        assert!(expired.is_empty());
+    }
+}
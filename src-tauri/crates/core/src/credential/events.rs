@@ -0,0 +1,194 @@
+//! 凭证池变更事件
+//!
+//! 凭证池每次变更（添加、更新、删除、健康状态变化、冷却开始/结束）都会生成一条带
+//! 递增序号的事件，供前端以「快照 + 增量」的方式维护凭证状态而无需轮询：
+//! 1. 订阅事件后获取快照，快照附带生成时的序号
+//! 2. 丢弃序号不大于快照序号的事件，其余事件按序应用
+//! 3. 断线重连时通过 `since` 补齐缺失的事件；缺口超出保留窗口时重新获取快照
+
+use crate::models::provider_pool_model::CredentialDisplay;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::broadcast;
+
+/// 默认保留的最近事件数（用于断线补齐）
+pub const DEFAULT_EVENT_RETENTION: usize = 512;
+
+/// 凭证池变更内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CredentialPoolChange {
+    /// 新增凭证
+    Added { credential: Box<CredentialDisplay> },
+    /// 凭证配置变化（名称、启用状态、权重等）
+    Updated { credential: Box<CredentialDisplay> },
+    /// 删除凭证
+    Removed { uuid: String, provider_type: String },
+    /// 健康状态变化
+    HealthChanged {
+        uuid: String,
+        provider_type: String,
+        is_healthy: bool,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// 进入冷却
+    CooldownStarted {
+        uuid: String,
+        provider_type: String,
+        until: DateTime<Utc>,
+    },
+    /// 冷却结束
+    CooldownEnded { uuid: String, provider_type: String },
+}
+
+/// 凭证池变更事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialPoolEvent {
+    /// 递增序号（从 1 开始）
+    pub seq: u64,
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub change: CredentialPoolChange,
+}
+
+/// 凭证池事件总线
+#[derive(Debug)]
+pub struct CredentialPoolEvents {
+    sender: broadcast::Sender<CredentialPoolEvent>,
+    /// 当前序号与最近事件（同一把锁保证序号与发送顺序一致）
+    state: Mutex<EventLog>,
+    retention: usize,
+}
+
+#[derive(Debug, Default)]
+struct EventLog {
+    seq: u64,
+    recent: VecDeque<CredentialPoolEvent>,
+}
+
+impl Default for CredentialPoolEvents {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_RETENTION)
+    }
+}
+
+impl CredentialPoolEvents {
+    pub fn new(retention: usize) -> Self {
+        let (sender, _) = broadcast::channel(retention.max(16));
+        Self {
+            sender,
+            state: Mutex::new(EventLog::default()),
+            retention: retention.max(1),
+        }
+    }
+
+    /// 发布变更，返回分配的序号
+    pub fn emit(&self, change: CredentialPoolChange) -> u64 {
+        let mut log = self.lock();
+        log.seq += 1;
+        let event = CredentialPoolEvent {
+            seq: log.seq,
+            timestamp: Utc::now(),
+            change,
+        };
+        if log.recent.len() >= self.retention {
+            log.recent.pop_front();
+        }
+        log.recent.push_back(event.clone());
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.sender.send(event);
+        log.seq
+    }
+
+    /// 订阅后续事件
+    pub fn subscribe(&self) -> broadcast::Receiver<CredentialPoolEvent> {
+        self.sender.subscribe()
+    }
+
+    /// 当前序号（快照应在读取数据前取得该序号）
+    pub fn current_seq(&self) -> u64 {
+        self.lock().seq
+    }
+
+    /// 获取序号大于 `seq` 的事件
+    ///
+    /// 所需事件已超出保留窗口时返回 None，调用方需重新获取快照。
+    pub fn since(&self, seq: u64) -> Option<Vec<CredentialPoolEvent>> {
+        let log = self.lock();
+        if seq >= log.seq {
+            return Some(Vec::new());
+        }
+        let oldest = log.recent.front().map(|e| e.seq)?;
+        if oldest > seq + 1 {
+            return None;
+        }
+        Some(log.recent.iter().filter(|e| e.seq > seq).cloned().collect())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, EventLog> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn removed(uuid: &str) -> CredentialPoolChange {
+        CredentialPoolChange::Removed {
+            uuid: uuid.to_string(),
+            provider_type: "claude".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_emit_assigns_sequence_and_broadcasts() {
+        let events = CredentialPoolEvents::default();
+        let mut rx = events.subscribe();
+
+        assert_eq!(events.emit(removed("a")), 1);
+        assert_eq!(events.emit(removed("b")), 2);
+        assert_eq!(events.current_seq(), 2);
+
+        let first = rx.try_recv().unwrap();
+        assert_eq!(first.seq, 1);
+        assert!(matches!(
+            first.change,
+            CredentialPoolChange::Removed { ref uuid, .. } if uuid == "a"
+        ));
+        assert_eq!(rx.try_recv().unwrap().seq, 2);
+    }
+
+    #[test]
+    fn test_since_returns_delta_or_requires_snapshot() {
+        let events = CredentialPoolEvents::new(2);
+        for uuid in ["a", "b", "c"] {
+            events.emit(removed(uuid));
+        }
+
+        let delta = events.since(1).unwrap();
+        assert_eq!(delta.iter().map(|e| e.seq).collect::<Vec<_>>(), vec![2, 3]);
+        assert!(events.since(3).unwrap().is_empty());
+        // 事件 1 已被淘汰，无法从序号 0 补齐
+        assert!(events.since(0).is_none());
+    }
+
+    #[test]
+    fn test_event_serializes_flat() {
+        let events = CredentialPoolEvents::default();
+        events.emit(CredentialPoolChange::HealthChanged {
+            uuid: "a".to_string(),
+            provider_type: "claude".to_string(),
+            is_healthy: false,
+            error: None,
+        });
+        let json = serde_json::to_value(&events.since(0).unwrap()[0]).unwrap();
+        assert_eq!(json["seq"], 1);
+        assert_eq!(json["kind"], "health_changed");
+        assert_eq!(json["is_healthy"], false);
+        assert!(json.get("error").is_none());
+    }
+}
@@ -3,6 +3,7 @@
 //! 提供凭证健康状态检查和自动更新功能
 
 use super::pool::{CredentialPool, PoolError};
+use super::probe::{HealthProbe, ProbeVariables};
 use super::types::{Credential, CredentialStatus};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -218,6 +219,46 @@ impl HealthChecker {
         consecutive_failures >= self.config.failure_threshold
     }
 
+    /// 执行插件声明的健康探测
+    ///
+    /// 状态码在 `expected_status` 中视为健康，返回耗时（毫秒）；
+    /// 失败信息沿用 `HTTP <状态码> - <响应摘要>` 格式，便于调用方识别 401 等错误。
+    pub async fn probe(
+        &self,
+        client: &reqwest::Client,
+        probe: &HealthProbe,
+        vars: &ProbeVariables,
+    ) -> Result<u64, String> {
+        let rendered = probe.render(vars)?;
+        let method = reqwest::Method::from_bytes(rendered.method.as_bytes())
+            .map_err(|_| format!("无效的 HTTP 方法: {}", rendered.method))?;
+
+        let mut request = client
+            .request(method, &rendered.url)
+            .timeout(probe.timeout());
+        for (name, value) in &rendered.headers {
+            request = request.header(name, value);
+        }
+        if let Some(body) = &rendered.body {
+            request = request.json(body);
+        }
+
+        let start = std::time::Instant::now();
+        let response = request.send().await.map_err(|e| format!("请求失败: {e}"))?;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        let status = response.status();
+        if probe.accepts(status.as_u16()) {
+            return Ok(latency_ms);
+        }
+        let body = response.text().await.unwrap_or_default();
+        Err(format!(
+            "HTTP {} - {}",
+            status,
+            body.chars().take(200).collect::<String>()
+        ))
+    }
+
     /// 尝试恢复不健康的凭证
     ///
     /// 将所有不健康的凭证恢复为活跃状态（用于手动恢复）
@@ -423,4 +464,63 @@ mod health_tests {
             assert!(matches!(cred.status, CredentialStatus::Active));
         }
     }
+
+    /// 启动只响应一次的测试服务，返回地址与收到的请求头
+    async fn spawn_status_server(
+        status_line: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buffer = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+                let read = stream.read(&mut chunk).await.unwrap();
+                if read == 0 {
+                    break;
+                }
+                buffer.extend_from_slice(&chunk[..read]);
+            }
+            let response = format!("HTTP/1.1 {status_line}\r\ncontent-length: 2\r\n\r\nno");
+            stream.write_all(response.as_bytes()).await.unwrap();
+            String::from_utf8_lossy(&buffer).to_string()
+        });
+        (format!("http://{addr}"), server)
+    }
+
+    #[tokio::test]
+    async fn test_probe_checks_expected_status() {
+        let checker = HealthChecker::with_defaults();
+        let client = reqwest::Client::new();
+        let probe: HealthProbe = serde_json::from_value(serde_json::json!({
+            "provider_type": "acme",
+            "endpoint": "{{base_url}}/v1/models",
+            "headers": { "x-api-key": "{{api_key}}" },
+            "expected_status": [200, 204]
+        }))
+        .unwrap();
+
+        let (base_url, server) = spawn_status_server("200 OK").await;
+        let vars = ProbeVariables {
+            api_key: Some("sk-test".to_string()),
+            base_url: Some(base_url),
+            model: "acme-mini".to_string(),
+        };
+        assert!(checker.probe(&client, &probe, &vars).await.is_ok());
+        let request = server.await.unwrap().to_lowercase();
+        assert!(request.starts_with("get /v1/models"));
+        assert!(request.contains("x-api-key: sk-test"));
+
+        let (base_url, server) = spawn_status_server("401 Unauthorized").await;
+        let vars = ProbeVariables {
+            base_url: Some(base_url),
+            ..vars
+        };
+        let error = checker.probe(&client, &probe, &vars).await.unwrap_err();
+        assert!(error.starts_with("HTTP 401"));
+        server.await.unwrap();
+    }
 }
@@ -1,15 +1,23 @@
 //! 凭证池核心类型和独立逻辑
 //!
-//! 包含凭证类型定义、凭证池管理、健康检查和风控模块。
+//! 包含凭证类型定义、凭证池管理、健康检查（含插件声明式探测）、风控、会话亲和和变更事件模块。
 //! 负载均衡器（balancer）、配额管理（quota）和同步服务（sync）
 //! 因依赖 infra crate 保留在主 crate 中。
 
+pub mod affinity;
+pub mod events;
 pub mod health;
 pub mod pool;
+pub mod probe;
 pub mod risk;
 pub mod types;
 
+pub use affinity::{AffinityBinding, SessionAffinity, DEFAULT_AFFINITY_TTL_SECS};
+pub use events::{CredentialPoolChange, CredentialPoolEvent, CredentialPoolEvents};
 pub use health::{HealthCheckConfig, HealthCheckResult, HealthChecker, HealthStatus};
 pub use pool::{CredentialPool, PoolError, PoolStatus};
-pub use risk::{CooldownConfig, RateLimitEvent, RateLimitStats, RiskController, RiskLevel};
+pub use probe::{HealthProbe, HealthProbeRegistry, ProbeVariables, RenderedProbe};
+pub use risk::{
+    CooldownConfig, CooldownMode, RateLimitEvent, RateLimitStats, RiskController, RiskLevel,
+};
 pub use types::{Credential, CredentialData, CredentialStats, CredentialStatus};
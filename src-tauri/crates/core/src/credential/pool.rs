@@ -188,6 +188,17 @@ impl CredentialPool {
         Ok(())
     }
 
+    /// 设置凭证负载均衡权重（最小为 1）
+    pub fn set_weight(&self, id: &str, weight: u32) -> Result<(), PoolError> {
+        let mut entry = self
+            .credentials
+            .get_mut(id)
+            .ok_or_else(|| PoolError::CredentialNotFound(id.to_string()))?;
+
+        entry.weight = weight.max(1);
+        Ok(())
+    }
+
     /// 恢复凭证为活跃状态
     pub fn mark_active(&self, id: &str) -> Result<(), PoolError> {
         let mut entry = self
@@ -200,16 +211,19 @@ impl CredentialPool {
     }
 
     /// 更新过期的冷却状态
-    /// 将冷却期已过的凭证恢复为活跃状态
-    pub fn refresh_cooldowns(&self) {
+    /// 将冷却期已过的凭证恢复为活跃状态，返回恢复的凭证 ID
+    pub fn refresh_cooldowns(&self) -> Vec<String> {
         let now = Utc::now();
+        let mut recovered = Vec::new();
         for mut entry in self.credentials.iter_mut() {
             if let CredentialStatus::Cooldown { until } = &entry.status {
                 if *until <= now {
                     entry.status = CredentialStatus::Active;
+                    recovered.push(entry.key().clone());
                 }
             }
         }
+        recovered
     }
 
     /// 获取下一个可用凭证（轮询策略）
@@ -0,0 +1,334 @@
+//! 声明式健康探测
+//!
+//! Provider 插件在 plugin.json 的 `health_probes` 中声明探测请求
+//! （端点、方法、期望状态码、低成本模型调用模板），由 `HealthChecker::probe`
+//! 统一执行，宿主无需为每个 Provider 硬编码检查逻辑。
+//!
+//! 模板中的 `{{api_key}}`、`{{base_url}}`、`{{model}}` 在执行前替换为凭证的实际值。
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// 默认探测超时（毫秒）
+pub const DEFAULT_PROBE_TIMEOUT_MS: u64 = 15_000;
+
+/// 探测超时上限（毫秒）
+pub const MAX_PROBE_TIMEOUT_MS: u64 = 60_000;
+
+/// 单个 Provider 的健康探测声明
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthProbe {
+    /// 适用的 Provider 类型（如 `openai`、`custom-xxx`）
+    pub provider_type: String,
+    /// 探测端点，支持模板变量（如 `{{base_url}}/v1/models`）
+    pub endpoint: String,
+    /// HTTP 方法
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// 视为健康的状态码
+    #[serde(default = "default_expected_status")]
+    pub expected_status: Vec<u16>,
+    /// 请求头，值支持模板变量
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// JSON 请求体模板（如低成本的模型调用），字符串值支持模板变量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+    /// 凭证未配置 base_url 时使用的默认地址
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_base_url: Option<String>,
+    /// 超时（毫秒）
+    #[serde(default = "default_timeout_ms")]
+    pub timeout_ms: u64,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+fn default_expected_status() -> Vec<u16> {
+    vec![200]
+}
+
+fn default_timeout_ms() -> u64 {
+    DEFAULT_PROBE_TIMEOUT_MS
+}
+
+impl HealthProbe {
+    /// 校验声明
+    pub fn validate(&self) -> Result<(), String> {
+        if self.provider_type.trim().is_empty() {
+            return Err("health_probes 缺少 provider_type".to_string());
+        }
+        if self.endpoint.trim().is_empty() {
+            return Err(format!("{} 的健康探测缺少 endpoint", self.provider_type));
+        }
+        if reqwest::Method::from_bytes(self.method.to_uppercase().as_bytes()).is_err() {
+            return Err(format!("无效的 HTTP 方法: {}", self.method));
+        }
+        if self.expected_status.is_empty() {
+            return Err(format!(
+                "{} 的健康探测缺少 expected_status",
+                self.provider_type
+            ));
+        }
+        if let Some(status) = self
+            .expected_status
+            .iter()
+            .find(|s| !(100..=599).contains(*s))
+        {
+            return Err(format!("无效的状态码: {status}"));
+        }
+        Ok(())
+    }
+
+    /// 探测超时（限制在上限以内）
+    pub fn timeout(&self) -> Duration {
+        Duration::from_millis(self.timeout_ms.clamp(1, MAX_PROBE_TIMEOUT_MS))
+    }
+
+    /// 状态码是否符合预期
+    pub fn accepts(&self, status: u16) -> bool {
+        self.expected_status.contains(&status)
+    }
+
+    /// 按凭证渲染出实际请求
+    pub fn render(&self, vars: &ProbeVariables) -> Result<RenderedProbe, String> {
+        let base_url = vars
+            .base_url
+            .as_deref()
+            .or(self.default_base_url.as_deref())
+            .map(|url| url.trim_end_matches('/').to_string());
+        let lookup = |name: &str| -> Result<String, String> {
+            match name {
+                "api_key" => vars
+                    .api_key
+                    .clone()
+                    .ok_or_else(|| "凭证没有 API Key，无法执行声明式探测".to_string()),
+                "base_url" => base_url.clone().ok_or_else(|| {
+                    "凭证未配置 base_url，且探测未声明 default_base_url".to_string()
+                }),
+                "model" => Ok(vars.model.clone()),
+                other => Err(format!("未知的模板变量: {other}")),
+            }
+        };
+
+        let url = render_template(&self.endpoint, &lookup)?;
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(format!("探测端点不是 HTTP 地址: {url}"));
+        }
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| Ok((name.clone(), render_template(value, &lookup)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let body = self
+            .body
+            .as_ref()
+            .map(|body| render_json(body, &lookup))
+            .transpose()?;
+
+        Ok(RenderedProbe {
+            method: self.method.to_uppercase(),
+            url,
+            headers,
+            body,
+        })
+    }
+}
+
+/// 模板变量取值
+#[derive(Debug, Clone, Default)]
+pub struct ProbeVariables {
+    pub api_key: Option<String>,
+    pub base_url: Option<String>,
+    pub model: String,
+}
+
+/// 渲染后的探测请求
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedProbe {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<serde_json::Value>,
+}
+
+fn render_template(
+    template: &str,
+    lookup: &dyn Fn(&str) -> Result<String, String>,
+) -> Result<String, String> {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| format!("模板缺少闭合的 }}}}: {template}"))?;
+        output.push_str(&lookup(after[..end].trim())?);
+        rest = &after[end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+fn render_json(
+    value: &serde_json::Value,
+    lookup: &dyn Fn(&str) -> Result<String, String>,
+) -> Result<serde_json::Value, String> {
+    Ok(match value {
+        serde_json::Value::String(s) => serde_json::Value::String(render_template(s, lookup)?),
+        serde_json::Value::Array(items) => serde_json::Value::Array(
+            items
+                .iter()
+                .map(|item| render_json(item, lookup))
+                .collect::<Result<_, _>>()?,
+        ),
+        serde_json::Value::Object(map) => serde_json::Value::Object(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), render_json(v, lookup)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+/// 已注册的插件健康探测（按 Provider 类型索引）
+#[derive(Debug, Default)]
+pub struct HealthProbeRegistry {
+    probes: RwLock<HashMap<String, (String, HealthProbe)>>,
+}
+
+impl HealthProbeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 用插件清单中的声明整体替换注册表
+    ///
+    /// 无效声明被跳过并返回错误信息；多个插件声明同一 Provider 类型时按插件 ID 排序后先到先得。
+    pub fn replace_all(&self, mut declared: Vec<(String, Vec<HealthProbe>)>) -> Vec<String> {
+        declared.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut errors = Vec::new();
+        let mut probes = HashMap::new();
+        for (plugin_id, plugin_probes) in declared {
+            for probe in plugin_probes {
+                if let Err(e) = probe.validate() {
+                    errors.push(format!("{plugin_id}: {e}"));
+                    continue;
+                }
+                let key = probe.provider_type.to_lowercase();
+                if let Some((owner, _)) = probes.get(&key) {
+                    errors.push(format!(
+                        "{plugin_id}: {} 的健康探测已由 {owner} 声明",
+                        probe.provider_type
+                    ));
+                    continue;
+                }
+                probes.insert(key, (plugin_id.clone(), probe));
+            }
+        }
+        if let Ok(mut guard) = self.probes.write() {
+            *guard = probes;
+        }
+        errors
+    }
+
+    /// 获取 Provider 类型对应的探测声明
+    pub fn get(&self, provider_type: &str) -> Option<HealthProbe> {
+        self.probes
+            .read()
+            .ok()?
+            .get(&provider_type.to_lowercase())
+            .map(|(_, probe)| probe.clone())
+    }
+
+    /// 已注册的 (Provider 类型, 插件 ID) 列表
+    pub fn list(&self) -> Vec<(String, String)> {
+        let mut entries: Vec<_> = self
+            .probes
+            .read()
+            .map(|guard| {
+                guard
+                    .iter()
+                    .map(|(provider, (plugin_id, _))| (provider.clone(), plugin_id.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        entries.sort();
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe() -> HealthProbe {
+        serde_json::from_value(serde_json::json!({
+            "provider_type": "acme",
+            "endpoint": "{{base_url}}/v1/chat/completions",
+            "method": "post",
+            "headers": { "Authorization": "Bearer {{api_key}}" },
+            "body": {
+                "model": "{{model}}",
+                "messages": [{ "role": "user", "content": "ping" }],
+                "max_tokens": 1
+            },
+            "default_base_url": "https://api.acme.dev/"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_substitutes_credential_values() {
+        let probe = probe();
+        assert_eq!(probe.expected_status, vec![200]);
+        assert!(probe.validate().is_ok());
+
+        let rendered = probe
+            .render(&ProbeVariables {
+                api_key: Some("sk-1".to_string()),
+                base_url: None,
+                model: "acme-mini".to_string(),
+            })
+            .unwrap();
+        assert_eq!(rendered.method, "POST");
+        assert_eq!(rendered.url, "https://api.acme.dev/v1/chat/completions");
+        assert_eq!(
+            rendered.headers,
+            vec![("Authorization".to_string(), "Bearer sk-1".to_string())]
+        );
+        assert_eq!(rendered.body.unwrap()["model"], "acme-mini");
+
+        let missing_key = probe.render(&ProbeVariables {
+            model: "acme-mini".to_string(),
+            ..Default::default()
+        });
+        assert!(missing_key.is_err());
+    }
+
+    #[test]
+    fn test_registry_replaces_and_rejects_conflicts() {
+        let registry = HealthProbeRegistry::new();
+        let mut invalid = probe();
+        invalid.endpoint.clear();
+
+        let errors = registry.replace_all(vec![
+            ("plugin-b".to_string(), vec![probe()]),
+            ("plugin-a".to_string(), vec![probe(), invalid]),
+        ]);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            registry.list(),
+            vec![("acme".to_string(), "plugin-a".to_string())]
+        );
+        assert!(registry.get("ACME").is_some());
+
+        registry.replace_all(Vec::new());
+        assert!(registry.get("acme").is_none());
+    }
+}
@@ -7,9 +7,15 @@
 //! - **限流检测**: 检测 API 返回的限流错误（429、rate limit）
 //! - **冷却期管理**: 自动计算和管理凭证冷却时间
 //! - **风险评估**: 根据历史数据评估凭证风险等级
+//! - **自适应冷却**: 按凭证学习限流节奏（典型 Retry-After、429 间隔与间隔内请求数），
+//!   据此调整冷却时长并在发请求前主动节流，学习结果可持久化到 `rate_limit_profiles`
 
+use crate::config::RateLimitCooldownSettings;
+use crate::database::dao::rate_limit_profile::{RateLimitProfile, RateLimitProfileDao};
 use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
+use parking_lot::RwLock;
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -96,6 +102,20 @@ impl RateLimitEvent {
     }
 }
 
+/// 学习限流节奏的指数移动平均系数
+const PROFILE_EWMA_ALPHA: f64 = 0.3;
+
+/// 冷却模式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CooldownMode {
+    /// 固定配置：基础冷却时间 + 指数退避
+    #[default]
+    Fixed,
+    /// 自适应：以学习到的典型 Retry-After 作为基础冷却时间，并按学习到的请求速率节流
+    Adaptive,
+}
+
 /// 冷却配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CooldownConfig {
@@ -115,6 +135,23 @@ pub struct CooldownConfig {
     pub high_risk_threshold: u32,
     /// 触发危险的限流次数阈值
     pub critical_risk_threshold: u32,
+    /// 冷却模式
+    #[serde(default)]
+    pub mode: CooldownMode,
+    /// 自适应模式下采用学习结果所需的最少样本数
+    #[serde(default = "default_adaptive_min_samples")]
+    pub adaptive_min_samples: u64,
+    /// 自适应节流的速率比例（按学习到的触发限流速率的该比例发送请求）
+    #[serde(default = "default_adaptive_pacing_headroom")]
+    pub adaptive_pacing_headroom: f64,
+}
+
+fn default_adaptive_min_samples() -> u64 {
+    3
+}
+
+fn default_adaptive_pacing_headroom() -> f64 {
+    0.8
 }
 
 impl Default for CooldownConfig {
@@ -128,6 +165,20 @@ impl Default for CooldownConfig {
             medium_risk_threshold: 3,     // 3 次限流 -> 中风险
             high_risk_threshold: 5,       // 5 次限流 -> 高风险
             critical_risk_threshold: 10,  // 10 次限流 -> 危险
+            mode: CooldownMode::Fixed,
+            adaptive_min_samples: default_adaptive_min_samples(),
+            adaptive_pacing_headroom: default_adaptive_pacing_headroom(),
+        }
+    }
+}
+
+impl From<&RateLimitCooldownSettings> for CooldownConfig {
+    fn from(settings: &RateLimitCooldownSettings) -> Self {
+        Self {
+            mode: settings.mode,
+            base_cooldown_secs: settings.base_cooldown_secs,
+            max_cooldown_secs: settings.max_cooldown_secs.max(settings.base_cooldown_secs),
+            ..Self::default()
         }
     }
 }
@@ -143,15 +194,27 @@ struct CredentialRiskState {
     cooldown_until: Option<DateTime<Utc>>,
     /// 上次限流时间
     last_rate_limit: Option<DateTime<Utc>>,
+    /// 学习到的限流节奏
+    profile: RateLimitProfile,
+    /// 画像有尚未写库的变化
+    profile_dirty: bool,
+    /// 上次限流以来发出的请求数
+    requests_since_limit: u64,
+    /// 上次发出请求的时间
+    last_request: Option<DateTime<Utc>>,
 }
 
 impl CredentialRiskState {
-    fn new() -> Self {
+    fn new(credential_id: &str) -> Self {
         Self {
             events: VecDeque::new(),
             consecutive_rate_limits: AtomicU64::new(0),
             cooldown_until: None,
             last_rate_limit: None,
+            profile: RateLimitProfile::new(credential_id),
+            profile_dirty: false,
+            requests_since_limit: 0,
+            last_request: None,
         }
     }
 }
@@ -160,8 +223,8 @@ impl CredentialRiskState {
 ///
 /// 管理凭证的限流检测和冷却期
 pub struct RiskController {
-    /// 配置
-    config: CooldownConfig,
+    /// 配置（可热更新）
+    config: RwLock<CooldownConfig>,
     /// 各凭证的风控状态
     states: DashMap<String, CredentialRiskState>,
 }
@@ -170,7 +233,7 @@ impl RiskController {
     /// 创建新的风控控制器
     pub fn new(config: CooldownConfig) -> Self {
         Self {
-            config,
+            config: RwLock::new(config),
             states: DashMap::new(),
         }
     }
@@ -181,8 +244,13 @@ impl RiskController {
     }
 
     /// 获取配置
-    pub fn config(&self) -> &CooldownConfig {
-        &self.config
+    pub fn config(&self) -> CooldownConfig {
+        self.config.read().clone()
+    }
+
+    /// 热更新配置（已记录的限流事件与学习到的节奏保留）
+    pub fn reload(&self, config: CooldownConfig) {
+        *self.config.write() = config;
     }
 
     /// 记录限流事件
@@ -196,11 +264,15 @@ impl RiskController {
         let mut state = self
             .states
             .entry(credential_id.clone())
-            .or_insert_with(CredentialRiskState::new);
+            .or_insert_with(|| CredentialRiskState::new(&credential_id));
 
         // 更新连续限流次数
         state.consecutive_rate_limits.fetch_add(1, Ordering::SeqCst);
-        state.last_rate_limit = Some(Utc::now());
+        let now = Utc::now();
+        let previous = state.last_rate_limit.replace(now);
+
+        // 学习限流节奏（两种模式都学习，切换到自适应模式时可直接使用）
+        self.learn_profile(&mut state, previous, now, retry_after);
 
         // 添加事件到历史
         state.events.push_back(event);
@@ -224,6 +296,87 @@ impl RiskController {
         }
     }
 
+    /// 记录发出的请求（自适应模式按此统计触发限流的请求速率）
+    pub fn record_request(&self, credential_id: &str) {
+        let mut state = self
+            .states
+            .entry(credential_id.to_string())
+            .or_insert_with(|| CredentialRiskState::new(credential_id));
+        state.requests_since_limit += 1;
+        state.last_request = Some(Utc::now());
+    }
+
+    /// 自适应模式下发出下一个请求前建议等待的时间（毫秒）
+    ///
+    /// 按学习到的「两次限流之间的请求数 / 间隔」估算触发限流的速率，
+    /// 以 `adaptive_pacing_headroom` 比例的速率发送请求；无需等待或样本不足时返回 None。
+    pub fn pacing_delay_ms(&self, credential_id: &str) -> Option<u64> {
+        if self.config.read().mode != CooldownMode::Adaptive {
+            return None;
+        }
+        let state = self.states.get(credential_id)?;
+        let min_gap_secs = self.min_request_gap_secs(&state.profile)?;
+        let last_request = state.last_request?;
+        let elapsed_secs = (Utc::now() - last_request).num_milliseconds() as f64 / 1000.0;
+        let wait_secs = min_gap_secs - elapsed_secs;
+        (wait_secs > 0.0).then(|| (wait_secs * 1000.0).ceil() as u64)
+    }
+
+    /// 获取凭证学习到的限流节奏
+    pub fn get_profile(&self, credential_id: &str) -> Option<RateLimitProfile> {
+        self.states
+            .get(credential_id)
+            .map(|state| state.profile.clone())
+            .filter(|profile| profile.rate_limits > 0)
+    }
+
+    /// 从数据库恢复学习到的限流节奏（内存中已有学习结果的凭证不覆盖），返回恢复条数
+    pub fn load_profiles(&self, conn: &Connection) -> Result<usize, rusqlite::Error> {
+        let mut loaded = 0;
+        for profile in RateLimitProfileDao::list(conn)? {
+            let mut state = self
+                .states
+                .entry(profile.credential_id.clone())
+                .or_insert_with(|| CredentialRiskState::new(&profile.credential_id));
+            if state.profile.rate_limits == 0 {
+                state.profile = profile;
+                loaded += 1;
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// 写入有变化的限流节奏，返回写入条数
+    pub fn persist_profiles(&self, conn: &Connection) -> Result<usize, rusqlite::Error> {
+        let mut written = 0;
+        for mut entry in self.states.iter_mut() {
+            if !entry.profile_dirty {
+                continue;
+            }
+            RateLimitProfileDao::upsert(conn, &entry.profile)?;
+            entry.profile_dirty = false;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// 清除凭证学习到的限流节奏
+    pub fn reset_profile(
+        &self,
+        conn: Option<&Connection>,
+        credential_id: &str,
+    ) -> Result<(), rusqlite::Error> {
+        if let Some(mut state) = self.states.get_mut(credential_id) {
+            state.profile = RateLimitProfile::new(credential_id);
+            state.profile_dirty = false;
+            state.requests_since_limit = 0;
+        }
+        if let Some(conn) = conn {
+            RateLimitProfileDao::delete(conn, credential_id)?;
+        }
+        Ok(())
+    }
+
     /// 获取凭证的风险等级
     pub fn get_risk_level(&self, credential_id: &str) -> RiskLevel {
         let state = match self.states.get(credential_id) {
@@ -233,11 +386,11 @@ impl RiskController {
 
         let recent_count = self.count_recent_events(&state);
 
-        if recent_count >= self.config.critical_risk_threshold {
+        if recent_count >= self.config.read().critical_risk_threshold {
             RiskLevel::Critical
-        } else if recent_count >= self.config.high_risk_threshold {
+        } else if recent_count >= self.config.read().high_risk_threshold {
             RiskLevel::High
-        } else if recent_count >= self.config.medium_risk_threshold {
+        } else if recent_count >= self.config.read().medium_risk_threshold {
             RiskLevel::Medium
         } else {
             RiskLevel::Low
@@ -355,7 +508,8 @@ impl RiskController {
 
     /// 清理过期事件
     fn cleanup_old_events(&self, state: &mut CredentialRiskState) {
-        let cutoff = Utc::now() - Duration::seconds(self.config.event_time_window_secs as i64);
+        let cutoff =
+            Utc::now() - Duration::seconds(self.config.read().event_time_window_secs as i64);
 
         // 移除过期事件
         while let Some(front) = state.events.front() {
@@ -367,14 +521,15 @@ impl RiskController {
         }
 
         // 限制事件数量
-        while state.events.len() > self.config.event_window_size {
+        while state.events.len() > self.config.read().event_window_size {
             state.events.pop_front();
         }
     }
 
     /// 统计最近的限流事件数
     fn count_recent_events(&self, state: &CredentialRiskState) -> u32 {
-        let cutoff = Utc::now() - Duration::seconds(self.config.event_time_window_secs as i64);
+        let cutoff =
+            Utc::now() - Duration::seconds(self.config.read().event_time_window_secs as i64);
         state
             .events
             .iter()
@@ -382,17 +537,70 @@ impl RiskController {
             .count() as u32
     }
 
+    /// 更新凭证的限流节奏
+    fn learn_profile(
+        &self,
+        state: &mut CredentialRiskState,
+        previous: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+        retry_after: Option<u64>,
+    ) {
+        let requests = state.requests_since_limit as f64;
+        state.requests_since_limit = 0;
+        state.profile_dirty = true;
+
+        let profile = &mut state.profile;
+        profile.rate_limits += 1;
+        profile.updated_at = now.timestamp();
+        if let Some(retry) = retry_after {
+            profile.retry_after_secs = Some(ewma(profile.retry_after_secs, retry as f64));
+            profile.retry_after_samples += 1;
+        }
+        let Some(previous) = previous else {
+            return;
+        };
+        // 超出统计窗口的间隔不代表限流节奏
+        let interval_secs = (now - previous).num_milliseconds() as f64 / 1000.0;
+        if (0.0..=self.config.read().event_time_window_secs as f64).contains(&interval_secs) {
+            profile.interval_secs = Some(ewma(profile.interval_secs, interval_secs));
+            profile.requests_per_interval = Some(ewma(profile.requests_per_interval, requests));
+            profile.interval_samples += 1;
+        }
+    }
+
+    /// 自适应节流的最小请求间隔（秒），样本不足时返回 None
+    fn min_request_gap_secs(&self, profile: &RateLimitProfile) -> Option<f64> {
+        if profile.interval_samples < self.config.read().adaptive_min_samples.max(1) {
+            return None;
+        }
+        let interval = profile.interval_secs?;
+        let requests = profile.requests_per_interval?.max(1.0);
+        let headroom = self.config.read().adaptive_pacing_headroom.clamp(0.1, 1.0);
+        Some(interval / (requests * headroom))
+    }
+
     /// 计算冷却时间
     fn calculate_cooldown(&self, state: &CredentialRiskState, retry_after: Option<u64>) -> u64 {
+        let config = self.config();
         // 如果有 Retry-After，优先使用
         if let Some(retry) = retry_after {
-            return retry.min(self.config.max_cooldown_secs);
+            return retry.min(config.max_cooldown_secs);
         }
 
-        // 使用指数退避计算冷却时间
+        // 使用指数退避计算冷却时间；自适应模式下以学习到的典型 Retry-After 作为基础
         let consecutive = state.consecutive_rate_limits.load(Ordering::SeqCst);
-        let base = self.config.base_cooldown_secs as f64;
-        let factor = self.config.backoff_factor;
+        let base = match config.mode {
+            CooldownMode::Adaptive
+                if state.profile.retry_after_samples >= config.adaptive_min_samples.max(1) =>
+            {
+                state
+                    .profile
+                    .retry_after_secs
+                    .unwrap_or(config.base_cooldown_secs as f64)
+            }
+            _ => config.base_cooldown_secs as f64,
+        };
+        let factor = config.backoff_factor;
 
         // cooldown = base * factor^(consecutive - 1)
         let cooldown = if consecutive > 0 {
@@ -406,18 +614,18 @@ impl RiskController {
         let adjusted = cooldown * risk_level.cooldown_multiplier();
 
         // 限制在最大值内
-        (adjusted as u64).min(self.config.max_cooldown_secs)
+        (adjusted as u64).min(config.max_cooldown_secs)
     }
 
     /// 从状态计算风险等级
     fn get_risk_level_from_state(&self, state: &CredentialRiskState) -> RiskLevel {
         let recent_count = self.count_recent_events(state);
 
-        if recent_count >= self.config.critical_risk_threshold {
+        if recent_count >= self.config.read().critical_risk_threshold {
             RiskLevel::Critical
-        } else if recent_count >= self.config.high_risk_threshold {
+        } else if recent_count >= self.config.read().high_risk_threshold {
             RiskLevel::High
-        } else if recent_count >= self.config.medium_risk_threshold {
+        } else if recent_count >= self.config.read().medium_risk_threshold {
             RiskLevel::Medium
         } else {
             RiskLevel::Low
@@ -425,6 +633,13 @@ impl RiskController {
     }
 }
 
+fn ewma(current: Option<f64>, sample: f64) -> f64 {
+    match current {
+        Some(current) => current * (1.0 - PROFILE_EWMA_ALPHA) + sample * PROFILE_EWMA_ALPHA,
+        None => sample,
+    }
+}
+
 impl Default for RiskController {
     fn default() -> Self {
         Self::with_defaults()
@@ -576,6 +791,72 @@ mod tests {
         assert!(cooling.contains(&"cred-2".to_string()));
     }
 
+    fn adaptive_controller() -> RiskController {
+        RiskController::new(CooldownConfig {
+            mode: CooldownMode::Adaptive,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_adaptive_cooldown_uses_learned_retry_after() {
+        let controller = adaptive_controller();
+        for _ in 0..3 {
+            controller
+                .record_rate_limit(RateLimitEvent::new("cred-1".to_string()).with_retry_after(20));
+            controller.record_success("cred-1");
+        }
+
+        // 无 Retry-After 时以学习到的 20 秒代替固定的 60 秒（中风险 x1.5）
+        let cooldown = controller.record_rate_limit(RateLimitEvent::new("cred-1".to_string()));
+        assert_eq!(cooldown, 30);
+
+        let fixed = RiskController::with_defaults();
+        for _ in 0..3 {
+            fixed.record_rate_limit(RateLimitEvent::new("cred-1".to_string()).with_retry_after(20));
+            fixed.record_success("cred-1");
+        }
+        assert_eq!(
+            fixed.record_rate_limit(RateLimitEvent::new("cred-1".to_string())),
+            90
+        );
+    }
+
+    #[test]
+    fn test_adaptive_pacing_and_profile_persistence() {
+        let controller = adaptive_controller();
+        assert!(controller.pacing_delay_ms("cred-1").is_none());
+
+        // 每次限流前发出 4 个请求
+        for _ in 0..4 {
+            for _ in 0..4 {
+                controller.record_request("cred-1");
+            }
+            controller.record_rate_limit(RateLimitEvent::new("cred-1".to_string()));
+        }
+        let profile = controller.get_profile("cred-1").unwrap();
+        assert_eq!(profile.rate_limits, 4);
+        assert_eq!(profile.interval_samples, 3);
+        assert_eq!(profile.requests_per_interval, Some(4.0));
+
+        controller.record_request("cred-1");
+        // 测试中限流间隔接近 0，节流间隔极短
+        assert!(controller.pacing_delay_ms("cred-1").unwrap_or(0) <= 1);
+
+        let conn = Connection::open_in_memory().unwrap();
+        crate::database::schema::create_tables(&conn).unwrap();
+        assert_eq!(controller.persist_profiles(&conn).unwrap(), 1);
+        assert_eq!(controller.persist_profiles(&conn).unwrap(), 0);
+
+        let restored = adaptive_controller();
+        assert_eq!(restored.load_profiles(&conn).unwrap(), 1);
+        assert_eq!(restored.get_profile("cred-1"), Some(profile));
+
+        restored.reset_profile(Some(&conn), "cred-1").unwrap();
+        assert!(restored.get_profile("cred-1").is_none());
+        assert!(RateLimitProfileDao::get(&conn, "cred-1").unwrap().is_none());
+    }
+
     #[test]
     fn test_risk_level_cooldown_multiplier() {
         assert_eq!(RiskLevel::Low.cooldown_multiplier(), 1.0);
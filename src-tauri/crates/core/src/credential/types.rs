@@ -26,6 +26,13 @@ pub struct Credential {
     /// Per-Key 代理 URL（覆盖全局代理）
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub proxy_url: Option<String>,
+    /// 负载均衡权重（加权策略下选择比例与权重成正比）
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
 }
 
 impl Credential {
@@ -40,9 +47,16 @@ impl Credential {
             status: CredentialStatus::Active,
             stats: CredentialStats::default(),
             proxy_url: None,
+            weight: default_weight(),
         }
     }
 
+    /// 设置负载均衡权重（最小为 1）
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight.max(1);
+        self
+    }
+
     /// 创建带代理的凭证
     pub fn with_proxy(mut self, proxy_url: Option<String>) -> Self {
         self.proxy_url = proxy_url;
@@ -5,6 +5,7 @@
 
 use crate::agent::types::AgentSession;
 use crate::database::dao::agent::{AgentDao, AgentSessionOverviewRow};
+use crate::database::pagination::{Page, PageRequest};
 use rusqlite::{Connection, OptionalExtension};
 
 #[derive(Debug, Clone)]
@@ -104,6 +105,15 @@ pub fn list_session_overviews(conn: &Connection) -> Result<Vec<SessionRecordOver
         .map_err(|error| format!("获取会话列表失败: {error}"))
 }
 
+pub fn list_session_overviews_page(
+    conn: &Connection,
+    page: &PageRequest,
+) -> Result<Page<SessionRecordOverview>, String> {
+    AgentDao::list_session_overviews_page(conn, page)
+        .map(|rows| rows.map(|row| map_session_overview(conn, row)))
+        .map_err(|error| format!("获取会话列表失败: {error}"))
+}
+
 pub fn get_session_overview(
     conn: &Connection,
     session_id: &str,
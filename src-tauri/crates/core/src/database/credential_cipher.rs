@@ -0,0 +1,411 @@
+//! 凭证数据加密钩子
+//!
+//! `provider_pool_credentials.credential_data` 的加解密实现位于 `lime-credential`
+//! （依赖钥匙串中的主密钥），core 只定义接口，由应用启动时注册。
+//! 未注册时 DAO 按明文读写，保持与旧版本数据库兼容。
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::database::dao::provider_pool::ProviderPoolDao;
+
+/// 凭证加解密错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CipherError {
+    /// 暂时无法加解密（钥匙串被锁定、拒绝访问、密钥集读取失败等），不能据此判定密钥已丢失
+    Unavailable(String),
+    /// 密钥已确认不匹配（认证解密失败或密文使用的密钥版本不存在），数据无法恢复
+    Undecryptable(String),
+}
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unavailable(e) => write!(f, "暂时无法解密: {e}"),
+            Self::Undecryptable(e) => write!(f, "无法解密: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for CipherError {}
+
+/// 凭证数据加解密
+pub trait CredentialCipher: Send + Sync {
+    /// 加密凭证 JSON
+    fn encrypt(&self, plaintext: &str) -> Result<String, CipherError>;
+    /// 解密凭证 JSON
+    fn decrypt(&self, ciphertext: &str) -> Result<String, CipherError>;
+    /// 判断文本是否为本实现产生的密文
+    fn is_encrypted(&self, text: &str) -> bool;
+}
+
+static CREDENTIAL_CIPHER: RwLock<Option<Arc<dyn CredentialCipher>>> = RwLock::new(None);
+
+#[cfg(test)]
+thread_local! {
+    static TEST_CIPHER: std::cell::RefCell<Option<Arc<dyn CredentialCipher>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// 仅在当前测试线程内生效的加密实现，guard 释放时恢复，避免污染其他测试
+#[cfg(test)]
+pub(crate) struct ScopedCredentialCipher;
+
+#[cfg(test)]
+impl ScopedCredentialCipher {
+    pub(crate) fn set(cipher: Arc<dyn CredentialCipher>) -> Self {
+        TEST_CIPHER.with(|slot| *slot.borrow_mut() = Some(cipher));
+        Self
+    }
+}
+
+#[cfg(test)]
+impl Drop for ScopedCredentialCipher {
+    fn drop(&mut self) {
+        TEST_CIPHER.with(|slot| *slot.borrow_mut() = None);
+    }
+}
+
+/// 注册凭证加密实现（重复注册时覆盖）
+pub fn set_credential_cipher(cipher: Arc<dyn CredentialCipher>) {
+    match CREDENTIAL_CIPHER.write() {
+        Ok(mut guard) => *guard = Some(cipher),
+        Err(poisoned) => *poisoned.into_inner() = Some(cipher),
+    }
+}
+
+/// 当前注册的凭证加密实现
+pub fn credential_cipher() -> Option<Arc<dyn CredentialCipher>> {
+    #[cfg(test)]
+    if let Some(cipher) = TEST_CIPHER.with(|slot| slot.borrow().clone()) {
+        return Some(cipher);
+    }
+    match CREDENTIAL_CIPHER.read() {
+        Ok(guard) => guard.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// 凭证加密迁移状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialEncryptionReport {
+    /// 是否已注册加密实现
+    pub cipher_available: bool,
+    pub total: usize,
+    /// 已加密且可正常解密的行数
+    pub encrypted: usize,
+    /// 本次迁移加密的行数
+    pub migrated: usize,
+    /// 暂时无法校验（钥匙串锁定等）的凭证 UUID，不计入无法解密
+    #[serde(default)]
+    pub key_unavailable: Vec<String>,
+    /// 仍为明文的凭证 UUID
+    pub plaintext: Vec<String>,
+    /// 标记为已加密但无法解密或解析的凭证 UUID
+    pub unreadable: Vec<String>,
+}
+
+impl CredentialEncryptionReport {
+    /// 全部凭证均已加密且可读
+    pub fn is_complete(&self) -> bool {
+        self.cipher_available
+            && self.plaintext.is_empty()
+            && self.unreadable.is_empty()
+            && self.key_unavailable.is_empty()
+    }
+}
+
+/// 逐行检查 credential_data 的加密状态
+pub fn verify_credential_encryption(
+    conn: &Connection,
+) -> Result<CredentialEncryptionReport, String> {
+    let rows = ProviderPoolDao::list_raw_credential_data(conn).map_err(|e| e.to_string())?;
+    let cipher = credential_cipher();
+    let mut report = CredentialEncryptionReport {
+        cipher_available: cipher.is_some(),
+        total: rows.len(),
+        ..Default::default()
+    };
+
+    for (uuid, data, flagged) in rows {
+        let Some(cipher) = cipher.as_ref() else {
+            if flagged {
+                report.unreadable.push(uuid);
+            } else {
+                report.plaintext.push(uuid);
+            }
+            continue;
+        };
+        if !flagged || !cipher.is_encrypted(&data) {
+            report.plaintext.push(uuid);
+            continue;
+        }
+        match cipher.decrypt(&data) {
+            Ok(json) if serde_json::from_str::<serde_json::Value>(&json).is_ok() => {
+                report.encrypted += 1;
+            }
+            Err(CipherError::Unavailable(_)) => report.key_unavailable.push(uuid),
+            _ => report.unreadable.push(uuid),
+        }
+    }
+    Ok(report)
+}
+
+/// 立即加密所有明文行并校验结果
+///
+/// 已是密文但未标记状态的行只补写标记；加密失败时中止并返回错误，已处理的行保持加密。
+pub fn migrate_credential_encryption(
+    conn: &Connection,
+) -> Result<CredentialEncryptionReport, String> {
+    let cipher = credential_cipher().ok_or_else(|| "未注册凭证加密实现".to_string())?;
+    let rows = ProviderPoolDao::list_raw_credential_data(conn).map_err(|e| e.to_string())?;
+
+    let mut migrated = 0;
+    for (uuid, data, flagged) in rows {
+        if flagged && cipher.is_encrypted(&data) {
+            continue;
+        }
+        let ciphertext = if cipher.is_encrypted(&data) {
+            data
+        } else {
+            cipher
+                .encrypt(&data)
+                .map_err(|e| format!("凭证 {uuid} 加密失败: {e}"))?
+        };
+        ProviderPoolDao::set_raw_credential_data(conn, &uuid, &ciphertext, true)
+            .map_err(|e| e.to_string())?;
+        migrated += 1;
+    }
+
+    tracing::info!("[凭证加密] 迁移完成，本次加密 {} 条", migrated);
+    let mut report = verify_credential_encryption(conn)?;
+    report.migrated = migrated;
+    Ok(report)
+}
+
+/// 无法解密的凭证（只含元数据，用于密钥丢失后提示重新导入）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnreadableCredential {
+    pub uuid: String,
+    pub provider_type: String,
+    pub name: Option<String>,
+}
+
+/// 列出标记为已加密但无法用当前密钥解密的凭证
+///
+/// 主密钥丢失（钥匙串被清空、换机未迁移）后这些行会被读取接口跳过，
+/// 调用方可据此移除并从备份重新导入。未注册加密实现时返回错误，避免误判；
+/// 钥匙串暂不可用的行不计入。
+pub fn list_unreadable_credentials(conn: &Connection) -> Result<Vec<UnreadableCredential>, String> {
+    let report = verify_credential_encryption(conn)?;
+    if !report.cipher_available {
+        return Err("未注册凭证加密实现".to_string());
+    }
+    unreadable_metadata(conn, report.unreadable)
+}
+
+/// 列出可以安全移除的无法解密凭证
+///
+/// 有任何行因钥匙串暂不可用而无法校验时拒绝，避免把钥匙串恢复后仍可读的凭证当作密钥丢失删除。
+pub fn list_purgeable_credentials(conn: &Connection) -> Result<Vec<UnreadableCredential>, String> {
+    let report = verify_credential_encryption(conn)?;
+    if !report.cipher_available {
+        return Err("未注册凭证加密实现".to_string());
+    }
+    if !report.key_unavailable.is_empty() {
+        return Err(format!(
+            "有 {} 条凭证因钥匙串暂不可用无法校验，请解锁钥匙串后重试",
+            report.key_unavailable.len()
+        ));
+    }
+    unreadable_metadata(conn, report.unreadable)
+}
+
+fn unreadable_metadata(
+    conn: &Connection,
+    uuids: Vec<String>,
+) -> Result<Vec<UnreadableCredential>, String> {
+    let mut credentials = Vec::with_capacity(uuids.len());
+    for uuid in uuids {
+        let meta = ProviderPoolDao::get_meta(conn, &uuid)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("凭证不存在: {uuid}"))?;
+        credentials.push(UnreadableCredential {
+            uuid,
+            provider_type: meta.provider_type,
+            name: meta.name,
+        });
+    }
+    Ok(credentials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+    use crate::models::provider_pool_model::{
+        CredentialData, PoolProviderType, ProviderCredential,
+    };
+
+    /// 反转字符串并加前缀，仅用于验证迁移流程
+    struct TestCipher;
+
+    impl CredentialCipher for TestCipher {
+        fn encrypt(&self, plaintext: &str) -> Result<String, CipherError> {
+            Ok(format!(
+                "test:{}",
+                plaintext.chars().rev().collect::<String>()
+            ))
+        }
+
+        fn decrypt(&self, ciphertext: &str) -> Result<String, CipherError> {
+            ciphertext
+                .strip_prefix("test:")
+                .map(|reversed| reversed.chars().rev().collect())
+                .ok_or_else(|| CipherError::Undecryptable("invalid".to_string()))
+        }
+
+        fn is_encrypted(&self, text: &str) -> bool {
+            text.starts_with("test:")
+        }
+    }
+
+    #[test]
+    fn test_plaintext_rows_are_encrypted_on_access_and_migration() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        // 模拟升级前写入的明文行
+        let legacy = ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: "sk-legacy".to_string(),
+                base_url: None,
+            },
+        );
+        let lazy = ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: "sk-lazy".to_string(),
+                base_url: None,
+            },
+        );
+        for cred in [&legacy, &lazy] {
+            conn.execute(
+                "INSERT INTO provider_pool_credentials
+                 (uuid, provider_type, credential_data, created_at, updated_at)
+                 VALUES (?1, 'openai', ?2, 0, 0)",
+                rusqlite::params![cred.uuid, serde_json::to_string(&cred.credential).unwrap()],
+            )
+            .unwrap();
+        }
+
+        let _cipher = ScopedCredentialCipher::set(Arc::new(TestCipher));
+
+        // 首次读取时加密回写
+        let loaded = ProviderPoolDao::get_by_uuid(&conn, &lazy.uuid)
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            loaded.credential,
+            CredentialData::OpenAIKey { ref api_key, .. } if api_key == "sk-lazy"
+        ));
+        let report = verify_credential_encryption(&conn).unwrap();
+        assert_eq!(report.encrypted, 1);
+        assert_eq!(report.plaintext, vec![legacy.uuid.clone()]);
+
+        let report = migrate_credential_encryption(&conn).unwrap();
+        assert_eq!(report.migrated, 1);
+        assert!(report.is_complete());
+        for (_, data, flagged) in ProviderPoolDao::list_raw_credential_data(&conn).unwrap() {
+            assert!(flagged);
+            assert!(!data.contains("sk-"));
+        }
+        assert_eq!(ProviderPoolDao::get_all(&conn).unwrap().len(), 2);
+
+        // 密钥丢失后无法解密的行被读取接口跳过，但可列出
+        conn.execute(
+            "UPDATE provider_pool_credentials SET credential_data = 'test:corrupted'
+             WHERE uuid = ?1",
+            [&legacy.uuid],
+        )
+        .unwrap();
+        let (readable, skipped) = ProviderPoolDao::get_all_with_skipped(&conn).unwrap();
+        assert_eq!(readable.len(), 1);
+        assert_eq!(skipped, 1);
+        let unreadable = list_unreadable_credentials(&conn).unwrap();
+        assert_eq!(unreadable.len(), 1);
+        assert_eq!(unreadable[0].uuid, legacy.uuid);
+        assert_eq!(unreadable[0].provider_type, "openai");
+
+        // 无法解密的行可按元数据直接粉碎（不经过解密）
+        let purgeable = list_purgeable_credentials(&conn).unwrap();
+        assert_eq!(purgeable, unreadable);
+        let meta = ProviderPoolDao::get_meta(&conn, &legacy.uuid)
+            .unwrap()
+            .unwrap();
+        assert_eq!(meta.provider_type, "openai");
+        assert!(ProviderPoolDao::shred(&conn, &legacy.uuid).unwrap());
+        assert!(ProviderPoolDao::get_meta(&conn, &legacy.uuid)
+            .unwrap()
+            .is_none());
+        assert!(list_unreadable_credentials(&conn).unwrap().is_empty());
+        assert_eq!(ProviderPoolDao::get_all(&conn).unwrap().len(), 1);
+    }
+
+    /// 钥匙串被锁定：所有解密都暂时失败
+    struct LockedCipher;
+
+    impl CredentialCipher for LockedCipher {
+        fn encrypt(&self, _: &str) -> Result<String, CipherError> {
+            Err(CipherError::Unavailable("locked".to_string()))
+        }
+
+        fn decrypt(&self, _: &str) -> Result<String, CipherError> {
+            Err(CipherError::Unavailable("locked".to_string()))
+        }
+
+        fn is_encrypted(&self, text: &str) -> bool {
+            text.starts_with("test:")
+        }
+    }
+
+    #[test]
+    fn test_locked_keychain_is_not_reported_as_unreadable() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO provider_pool_credentials
+             (uuid, provider_type, credential_data, credential_encrypted, created_at, updated_at)
+             VALUES ('locked-1', 'openai', 'test:}{', 1, 0, 0)",
+            [],
+        )
+        .unwrap();
+
+        let _cipher = ScopedCredentialCipher::set(Arc::new(LockedCipher));
+        let report = verify_credential_encryption(&conn).unwrap();
+        assert!(report.unreadable.is_empty());
+        assert_eq!(report.key_unavailable, vec!["locked-1".to_string()]);
+        assert!(list_unreadable_credentials(&conn).unwrap().is_empty());
+        assert!(list_purgeable_credentials(&conn).is_err());
+        assert!(ProviderPoolDao::get_meta(&conn, "locked-1")
+            .unwrap()
+            .is_some());
+
+        // 加密失败时拒绝写入，而不是以明文落盘
+        let cred = ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: "sk-locked".to_string(),
+                base_url: None,
+            },
+        );
+        assert!(ProviderPoolDao::insert(&conn, &cred).is_err());
+        assert!(ProviderPoolDao::get_meta(&conn, &cred.uuid)
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,96 @@
+//! 自适应凭证选择统计（adaptive_selection_stats）数据访问对象
+//!
+//! 保存按模型统计的各凭证成功 / 失败计数与延迟均值（均为衰减后的加权值），
+//! 重启后恢复已学习的选择偏好。
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// 单个（模型, 凭证）的统计
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdaptiveArmStats {
+    pub model: String,
+    pub credential_id: String,
+    /// 衰减后的成功次数
+    pub successes: f64,
+    /// 衰减后的失败次数
+    pub failures: f64,
+    /// 成功请求延迟的指数移动平均（毫秒）
+    pub latency_ms: f64,
+    /// 累计记录次数（不衰减）
+    pub samples: u64,
+    /// 最后更新时间（Unix 秒）
+    pub updated_at: i64,
+}
+
+pub struct AdaptiveSelectionDao;
+
+impl AdaptiveSelectionDao {
+    /// 写入或覆盖统计
+    pub fn upsert(conn: &Connection, stats: &AdaptiveArmStats) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO adaptive_selection_stats
+             (model, credential_id, successes, failures, latency_ms, samples, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+             ON CONFLICT(model, credential_id) DO UPDATE SET
+                successes = excluded.successes,
+                failures = excluded.failures,
+                latency_ms = excluded.latency_ms,
+                samples = excluded.samples,
+                updated_at = excluded.updated_at",
+            params![
+                stats.model,
+                stats.credential_id,
+                stats.successes,
+                stats.failures,
+                stats.latency_ms,
+                stats.samples as i64,
+                stats.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 读取全部统计
+    pub fn list(conn: &Connection) -> Result<Vec<AdaptiveArmStats>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT model, credential_id, successes, failures, latency_ms, samples, updated_at
+             FROM adaptive_selection_stats
+             ORDER BY model, credential_id",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(AdaptiveArmStats {
+                model: row.get(0)?,
+                credential_id: row.get(1)?,
+                successes: row.get(2)?,
+                failures: row.get(3)?,
+                latency_ms: row.get(4)?,
+                samples: row.get::<_, i64>(5)? as u64,
+                updated_at: row.get(6)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// 清除统计：指定模型时只清除该模型，否则全部清除，返回删除行数
+    pub fn clear(conn: &Connection, model: Option<&str>) -> Result<usize, rusqlite::Error> {
+        match model {
+            Some(model) => conn.execute(
+                "DELETE FROM adaptive_selection_stats WHERE model = ?1",
+                params![model],
+            ),
+            None => conn.execute("DELETE FROM adaptive_selection_stats", []),
+        }
+    }
+
+    /// 删除凭证的全部统计
+    pub fn delete_credential(
+        conn: &Connection,
+        credential_id: &str,
+    ) -> Result<usize, rusqlite::Error> {
+        conn.execute(
+            "DELETE FROM adaptive_selection_stats WHERE credential_id = ?1",
+            params![credential_id],
+        )
+    }
+}
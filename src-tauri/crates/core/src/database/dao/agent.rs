@@ -5,9 +5,10 @@
 use crate::agent::types::{
     AgentMessage, AgentSession, ContentPart, FunctionCall, MessageContent, ToolCall,
 };
+use crate::database::pagination::{Page, PageRequest};
 use crate::database::ConversationWindowSummary;
 use chrono::{Local, TimeZone};
-use rusqlite::{params, Connection};
+use rusqlite::{params, params_from_iter, types::Value as SqlValue, Connection};
 
 const JSON_RECURSION_LIMIT: usize = 50;
 
@@ -608,6 +609,44 @@ impl AgentDao {
         rows.collect()
     }
 
+    /// 分页获取会话概览（按更新时间降序，同一时间按 ID 降序）
+    pub fn list_session_overviews_page(
+        conn: &Connection,
+        page: &PageRequest,
+    ) -> Result<Page<AgentSessionOverviewRow>, rusqlite::Error> {
+        let mut sql = "SELECT s.id, s.model, s.system_prompt, s.title, s.created_at, s.updated_at,
+                    s.working_dir, s.execution_strategy,
+                    (SELECT COUNT(*) FROM agent_messages m WHERE m.session_id = s.id)
+             FROM agent_sessions s"
+            .to_string();
+        let mut values = Vec::new();
+        if let Some((filter, bound)) = page.keyset_filter(&["s.updated_at", "s.id"], true)? {
+            sql.push_str(" WHERE ");
+            sql.push_str(&filter);
+            values = bound;
+        }
+        sql.push_str(" ORDER BY s.updated_at DESC, s.id DESC LIMIT ?");
+        values.push(SqlValue::Integer(page.limit() as i64 + 1));
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| {
+                let messages_count: i64 = row.get(8)?;
+                Ok(AgentSessionOverviewRow {
+                    session: map_agent_session_row(row)?,
+                    messages_count: messages_count.max(0) as usize,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Page::from_rows(rows, page.limit(), |row| {
+            vec![
+                SqlValue::Text(row.session.updated_at.clone()),
+                SqlValue::Text(row.session.id.clone()),
+            ]
+        }))
+    }
+
     pub fn get_session_overview(
         conn: &Connection,
         session_id: &str,
@@ -916,6 +955,13 @@ impl AgentDao {
             .transpose()
             .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
 
+        let cost_json = message
+            .cost
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
         conn.execute(
             "INSERT INTO agent_messages (
                 session_id,
@@ -924,9 +970,10 @@ impl AgentDao {
                 timestamp,
                 tool_calls_json,
                 tool_call_id,
-                reasoning_content
+                reasoning_content,
+                cost_json
             )
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
                 session_id,
                 message.role,
@@ -935,6 +982,7 @@ impl AgentDao {
                 tool_calls_json,
                 message.tool_call_id,
                 message.reasoning_content.as_deref(),
+                cost_json,
             ],
         )?;
 
@@ -953,7 +1001,8 @@ impl AgentDao {
         session_id: &str,
     ) -> Result<Vec<AgentMessage>, rusqlite::Error> {
         let mut stmt = conn.prepare(
-            "SELECT role, content_json, timestamp, tool_calls_json, tool_call_id, reasoning_content
+            "SELECT role, content_json, timestamp, tool_calls_json, tool_call_id, reasoning_content,
+                    cost_json
              FROM agent_messages WHERE session_id = ? ORDER BY id ASC",
         )?;
 
@@ -964,6 +1013,7 @@ impl AgentDao {
             let tool_calls_json: Option<String> = row.get(3)?;
             let tool_call_id: Option<String> = row.get(4)?;
             let reasoning_content: Option<String> = row.get(5)?;
+            let cost_json: Option<String> = row.get(6)?;
 
             // 解析 JSON - 支持多种格式
             // 1. Aster 格式: [{"Text":"..."}, {"Text":"..."}]
@@ -980,6 +1030,7 @@ impl AgentDao {
                 tool_calls,
                 tool_call_id,
                 reasoning_content,
+                cost: cost_json.and_then(|json| serde_json::from_str(&json).ok()),
             })
         })?;
 
@@ -1076,6 +1127,7 @@ impl AgentDao {
 #[cfg(test)]
 mod tests {
     use crate::agent::types::MessageContent;
+    use crate::database::pagination::PageRequest;
     use rusqlite::{params, Connection};
 
     use super::{
@@ -1105,7 +1157,8 @@ mod tests {
                 timestamp TEXT NOT NULL,
                 tool_calls_json TEXT,
                 tool_call_id TEXT,
-                reasoning_content TEXT
+                reasoning_content TEXT,
+                cost_json TEXT
             );
             ",
         )
@@ -1402,6 +1455,28 @@ mod tests {
         assert_eq!(overviews[1].session.id, "session-a");
         assert_eq!(overviews[1].messages_count, 2);
 
+        let first = AgentDao::list_session_overviews_page(
+            &conn,
+            &PageRequest {
+                cursor: None,
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        assert_eq!(first.items.len(), 1);
+        assert_eq!(first.items[0].session.id, "session-b");
+        let second = AgentDao::list_session_overviews_page(
+            &conn,
+            &PageRequest {
+                cursor: first.next_cursor,
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        assert_eq!(second.items[0].session.id, "session-a");
+        assert_eq!(second.items[0].messages_count, 2);
+        assert!(second.next_cursor.is_none());
+
         let overview = AgentDao::get_session_overview(&conn, "session-a")
             .unwrap()
             .expect("session-a overview");
@@ -1420,7 +1495,7 @@ mod tests {
     }
 
     #[test]
-    fn add_message_and_get_messages_should_roundtrip_reasoning_content() {
+    fn add_message_and_get_messages_should_roundtrip_reasoning_content_and_cost() {
         let conn = setup_pattern_test_db();
 
         conn.execute(
@@ -1447,6 +1522,12 @@ mod tests {
                 tool_calls: None,
                 tool_call_id: None,
                 reasoning_content: Some("先分析参数，再继续请求".to_string()),
+                cost: Some(crate::models::model_registry::MessageCost::new(
+                    Some("deepseek-reasoner".to_string()),
+                    None,
+                    120,
+                    30,
+                )),
             },
         )
         .unwrap();
@@ -1457,5 +1538,8 @@ mod tests {
             messages[0].reasoning_content.as_deref(),
             Some("先分析参数，再继续请求")
         );
+        let cost = messages[0].cost.as_ref().expect("cost metadata");
+        assert_eq!(cost.total_tokens, 150);
+        assert_eq!(cost.model.as_deref(), Some("deepseek-reasoner"));
     }
 }
@@ -0,0 +1,115 @@
+//! 按 API Key 的限流覆盖（api_key_rate_limits）数据访问对象
+//!
+//! 以 API Key 的 SHA-256 指纹作为主键，数据库中不保存明文 Key。
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// 单个 API Key 的令牌桶覆盖配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ApiKeyRateLimitOverride {
+    /// API Key 指纹（见 [`api_key_fingerprint`]）
+    pub key_fingerprint: String,
+    /// 备注（如租户名、客户端名）
+    pub label: Option<String>,
+    pub requests_per_minute: u32,
+    pub burst: u32,
+    /// 更新时间（Unix 秒）
+    pub updated_at: i64,
+}
+
+/// API Key 指纹：SHA-256 的前 16 个十六进制字符
+pub fn api_key_fingerprint(api_key: &str) -> String {
+    let digest = Sha256::digest(api_key.as_bytes());
+    format!("{digest:x}")[..16].to_string()
+}
+
+pub struct ApiKeyRateLimitDao;
+
+impl ApiKeyRateLimitDao {
+    /// 列出全部覆盖配置
+    pub fn list(conn: &Connection) -> Result<Vec<ApiKeyRateLimitOverride>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT key_fingerprint, label, requests_per_minute, burst, updated_at
+             FROM api_key_rate_limits ORDER BY updated_at DESC",
+        )?;
+        let rows = stmt.query_map([], |row| {
+            Ok(ApiKeyRateLimitOverride {
+                key_fingerprint: row.get(0)?,
+                label: row.get(1)?,
+                requests_per_minute: row.get(2)?,
+                burst: row.get(3)?,
+                updated_at: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// 新增或更新覆盖配置
+    pub fn upsert(
+        conn: &Connection,
+        record: &ApiKeyRateLimitOverride,
+    ) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO api_key_rate_limits
+                (key_fingerprint, label, requests_per_minute, burst, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT(key_fingerprint) DO UPDATE SET
+                label = excluded.label,
+                requests_per_minute = excluded.requests_per_minute,
+                burst = excluded.burst,
+                updated_at = excluded.updated_at",
+            params![
+                record.key_fingerprint,
+                record.label,
+                record.requests_per_minute,
+                record.burst,
+                record.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 删除覆盖配置，返回是否存在
+    pub fn delete(conn: &Connection, key_fingerprint: &str) -> Result<bool, rusqlite::Error> {
+        let affected = conn.execute(
+            "DELETE FROM api_key_rate_limits WHERE key_fingerprint = ?1",
+            params![key_fingerprint],
+        )?;
+        Ok(affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+
+    #[test]
+    fn test_upsert_list_and_delete() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        let fingerprint = api_key_fingerprint("sk-tenant-a");
+        assert_eq!(fingerprint.len(), 16);
+        assert_ne!(fingerprint, api_key_fingerprint("sk-tenant-b"));
+
+        let mut record = ApiKeyRateLimitOverride {
+            key_fingerprint: fingerprint.clone(),
+            label: Some("tenant-a".to_string()),
+            requests_per_minute: 30,
+            burst: 5,
+            updated_at: 100,
+        };
+        ApiKeyRateLimitDao::upsert(&conn, &record).unwrap();
+        record.burst = 10;
+        record.updated_at = 200;
+        ApiKeyRateLimitDao::upsert(&conn, &record).unwrap();
+
+        assert_eq!(ApiKeyRateLimitDao::list(&conn).unwrap(), vec![record]);
+        assert!(ApiKeyRateLimitDao::delete(&conn, &fingerprint).unwrap());
+        assert!(!ApiKeyRateLimitDao::delete(&conn, &fingerprint).unwrap());
+        assert!(ApiKeyRateLimitDao::list(&conn).unwrap().is_empty());
+    }
+}
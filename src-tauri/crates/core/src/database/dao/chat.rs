@@ -10,9 +10,13 @@
 //! - 模式化设计：通过 ChatMode 区分不同场景
 //! - 向后兼容：复用现有的 agent_sessions/agent_messages 表
 
+use crate::models::model_registry::MessageCost;
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
 
+/// 消息元数据中保存费用信息的键
+pub const MESSAGE_COST_METADATA_KEY: &str = "cost";
+
 // ============================================================================
 // 数据模型
 // ============================================================================
@@ -94,11 +98,36 @@ pub struct ChatMessage {
     /// 工具调用 ID
     pub tool_call_id: Option<String>,
     /// 扩展元数据（JSON）
+    ///
+    /// assistant 消息的 token 用量与费用保存在 `cost` 字段（见 [`MessageCost`]）
     pub metadata: Option<serde_json::Value>,
     /// 创建时间
     pub created_at: String,
 }
 
+impl ChatMessage {
+    /// 消息的 token 用量与费用
+    pub fn cost(&self) -> Option<MessageCost> {
+        self.metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(MESSAGE_COST_METADATA_KEY))
+            .and_then(|cost| serde_json::from_value(cost.clone()).ok())
+    }
+
+    /// 附加 token 用量与费用
+    pub fn set_cost(&mut self, cost: &MessageCost) {
+        let value = serde_json::to_value(cost).unwrap_or(serde_json::Value::Null);
+        match self.metadata.as_mut().and_then(|m| m.as_object_mut()) {
+            Some(map) => {
+                map.insert(MESSAGE_COST_METADATA_KEY.to_string(), value);
+            }
+            None => {
+                self.metadata = Some(serde_json::json!({ MESSAGE_COST_METADATA_KEY: value }));
+            }
+        }
+    }
+}
+
 /// 会话详情（包含消息）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatSessionDetail {
@@ -278,9 +307,15 @@ impl ChatDao {
             .transpose()
             .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
 
+        let cost_json = message
+            .cost()
+            .map(|cost| serde_json::to_string(&cost))
+            .transpose()
+            .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+
         conn.execute(
-            "INSERT INTO agent_messages (session_id, role, content_json, timestamp, tool_calls_json, tool_call_id)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            "INSERT INTO agent_messages (session_id, role, content_json, timestamp, tool_calls_json, tool_call_id, cost_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
             params![
                 message.session_id,
                 message.role,
@@ -288,6 +323,7 @@ impl ChatDao {
                 message.created_at,
                 tool_calls_json,
                 message.tool_call_id,
+                cost_json,
             ],
         )?;
 
@@ -309,10 +345,10 @@ impl ChatDao {
         limit: Option<i32>,
     ) -> Result<Vec<ChatMessage>, rusqlite::Error> {
         let query = if limit.is_some() {
-            "SELECT id, session_id, role, content_json, timestamp, tool_calls_json, tool_call_id
+            "SELECT id, session_id, role, content_json, timestamp, tool_calls_json, tool_call_id, cost_json
              FROM agent_messages WHERE session_id = ? ORDER BY id ASC LIMIT ?"
         } else {
-            "SELECT id, session_id, role, content_json, timestamp, tool_calls_json, tool_call_id
+            "SELECT id, session_id, role, content_json, timestamp, tool_calls_json, tool_call_id, cost_json
              FROM agent_messages WHERE session_id = ? ORDER BY id ASC"
         };
 
@@ -385,6 +421,11 @@ impl ChatDao {
         let tool_calls: Option<serde_json::Value> =
             tool_calls_json.and_then(|json| serde_json::from_str(&json).ok());
 
+        let cost_json: Option<String> = row.get(7)?;
+        let metadata = cost_json
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+            .map(|cost| serde_json::json!({ MESSAGE_COST_METADATA_KEY: cost }));
+
         Ok(ChatMessage {
             id: row.get(0)?,
             session_id: row.get(1)?,
@@ -392,7 +433,7 @@ impl ChatDao {
             content,
             tool_calls,
             tool_call_id: row.get(6)?,
-            metadata: None,
+            metadata,
             created_at: row.get(4)?,
         })
     }
@@ -0,0 +1,82 @@
+//! 月度费用累计（monthly_cost_usage）数据访问对象
+//!
+//! 为费用上限提供按月、按 Provider 的费用持久化，以及模型定价查询。
+
+use crate::models::model_registry::ModelPricing;
+use rusqlite::{params, Connection, OptionalExtension};
+
+pub struct CostUsageDao;
+
+impl CostUsageDao {
+    /// 累加某月某 Provider 的费用
+    pub fn add_cost(
+        conn: &Connection,
+        month: &str,
+        provider: &str,
+        cost_usd: f64,
+    ) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO monthly_cost_usage (month, provider, cost_usd, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(month, provider) DO UPDATE SET
+                cost_usd = cost_usd + excluded.cost_usd,
+                updated_at = excluded.updated_at",
+            params![month, provider, cost_usd, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// 获取某月各 Provider 的累计费用
+    pub fn list_month(
+        conn: &Connection,
+        month: &str,
+    ) -> Result<Vec<(String, f64)>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT provider, cost_usd FROM monthly_cost_usage WHERE month = ?1 ORDER BY provider",
+        )?;
+        let rows = stmt.query_map(params![month], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?))
+        })?;
+        rows.collect()
+    }
+
+    /// 从模型注册表读取模型定价
+    pub fn get_model_pricing(
+        conn: &Connection,
+        model_id: &str,
+    ) -> Result<Option<ModelPricing>, rusqlite::Error> {
+        let pricing: Option<Option<String>> = conn
+            .query_row(
+                "SELECT pricing FROM model_registry WHERE id = ?1",
+                params![model_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(pricing
+            .flatten()
+            .and_then(|json| serde_json::from_str::<ModelPricing>(&json).ok()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+
+    #[test]
+    fn test_add_cost_accumulates_per_month_and_provider() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        CostUsageDao::add_cost(&conn, "2026-10", "openai", 1.5).unwrap();
+        CostUsageDao::add_cost(&conn, "2026-10", "openai", 0.25).unwrap();
+        CostUsageDao::add_cost(&conn, "2026-10", "claude", 2.0).unwrap();
+        CostUsageDao::add_cost(&conn, "2026-09", "openai", 9.0).unwrap();
+
+        let usage = CostUsageDao::list_month(&conn, "2026-10").unwrap();
+        assert_eq!(
+            usage,
+            vec![("claude".to_string(), 2.0), ("openai".to_string(), 1.75)]
+        );
+    }
+}
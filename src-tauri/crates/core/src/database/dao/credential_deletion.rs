@@ -0,0 +1,219 @@
+//! 凭证两阶段删除（credential_deletions）数据访问对象
+//!
+//! 删除凭证时先禁用并登记为待粉碎，宽限期内可撤销；到期后由粉碎任务覆盖密钥列、
+//! 清理缓存与审计字段并删除凭证，同一行改为保存粉碎回执。
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+/// 待粉碎的凭证
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingCredentialDeletion {
+    pub uuid: String,
+    pub provider_type: String,
+    pub name: Option<String>,
+    /// 登记前是否已被手动禁用（撤销时恢复）
+    pub was_disabled: bool,
+    /// 登记时间（Unix 秒）
+    pub requested_at: i64,
+    /// 宽限期结束时间（Unix 秒），之后执行粉碎
+    pub purge_after: i64,
+}
+
+/// 粉碎回执
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CredentialDeletionReceipt {
+    pub uuid: String,
+    pub provider_type: String,
+    pub name: Option<String>,
+    /// 凭证数据的 SHA-256 前缀，用于核对被粉碎的是哪份密钥
+    pub secret_fingerprint: String,
+    pub requested_at: i64,
+    pub shredded_at: i64,
+    /// 已清理的项目（凭证数据、Token 缓存、Token 文件、审计错误信息等）
+    pub purged: Vec<String>,
+}
+
+pub struct CredentialDeletionDao;
+
+impl CredentialDeletionDao {
+    /// 登记待粉碎凭证（已登记时覆盖）
+    pub fn insert_pending(
+        conn: &Connection,
+        pending: &PendingCredentialDeletion,
+    ) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT OR REPLACE INTO credential_deletions
+             (uuid, provider_type, name, was_disabled, requested_at, purge_after, shredded_at, receipt)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, NULL, NULL)",
+            params![
+                pending.uuid,
+                pending.provider_type,
+                pending.name,
+                pending.was_disabled,
+                pending.requested_at,
+                pending.purge_after,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 获取待粉碎登记
+    pub fn get_pending(
+        conn: &Connection,
+        uuid: &str,
+    ) -> Result<Option<PendingCredentialDeletion>, rusqlite::Error> {
+        conn.query_row(
+            "SELECT uuid, provider_type, name, was_disabled, requested_at, purge_after
+             FROM credential_deletions
+             WHERE uuid = ?1 AND shredded_at IS NULL",
+            params![uuid],
+            Self::pending_from_row,
+        )
+        .optional()
+    }
+
+    /// 列出待粉碎登记（最早到期在前）
+    pub fn list_pending(
+        conn: &Connection,
+    ) -> Result<Vec<PendingCredentialDeletion>, rusqlite::Error> {
+        Self::query_pending(conn, i64::MAX)
+    }
+
+    /// 列出宽限期已结束的待粉碎登记
+    pub fn list_due(
+        conn: &Connection,
+        now: i64,
+    ) -> Result<Vec<PendingCredentialDeletion>, rusqlite::Error> {
+        Self::query_pending(conn, now)
+    }
+
+    /// 撤销待粉碎登记，返回是否存在
+    pub fn cancel(conn: &Connection, uuid: &str) -> Result<bool, rusqlite::Error> {
+        let affected = conn.execute(
+            "DELETE FROM credential_deletions WHERE uuid = ?1 AND shredded_at IS NULL",
+            params![uuid],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// 写入粉碎回执
+    pub fn record_receipt(
+        conn: &Connection,
+        receipt: &CredentialDeletionReceipt,
+    ) -> Result<(), rusqlite::Error> {
+        let json = serde_json::to_string(receipt).unwrap_or_else(|_| "{}".to_string());
+        conn.execute(
+            "INSERT OR REPLACE INTO credential_deletions
+             (uuid, provider_type, name, was_disabled, requested_at, purge_after, shredded_at, receipt)
+             VALUES (?1, ?2, ?3, 0, ?4, ?5, ?5, ?6)",
+            params![
+                receipt.uuid,
+                receipt.provider_type,
+                receipt.name,
+                receipt.requested_at,
+                receipt.shredded_at,
+                json,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 列出粉碎回执（最新在前）
+    pub fn list_receipts(
+        conn: &Connection,
+        limit: usize,
+    ) -> Result<Vec<CredentialDeletionReceipt>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT receipt FROM credential_deletions
+             WHERE shredded_at IS NOT NULL
+             ORDER BY shredded_at DESC
+             LIMIT ?1",
+        )?;
+        let rows = stmt.query_map(params![limit as i64], |row| row.get::<_, String>(0))?;
+        Ok(rows
+            .filter_map(|json| json.ok())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect())
+    }
+
+    fn query_pending(
+        conn: &Connection,
+        due_before: i64,
+    ) -> Result<Vec<PendingCredentialDeletion>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT uuid, provider_type, name, was_disabled, requested_at, purge_after
+             FROM credential_deletions
+             WHERE shredded_at IS NULL AND purge_after <= ?1
+             ORDER BY purge_after ASC",
+        )?;
+        let rows = stmt.query_map(params![due_before], Self::pending_from_row)?;
+        rows.collect()
+    }
+
+    fn pending_from_row(row: &Row) -> Result<PendingCredentialDeletion, rusqlite::Error> {
+        Ok(PendingCredentialDeletion {
+            uuid: row.get(0)?,
+            provider_type: row.get(1)?,
+            name: row.get(2)?,
+            was_disabled: row.get(3)?,
+            requested_at: row.get(4)?,
+            purge_after: row.get(5)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+
+    fn pending(uuid: &str, purge_after: i64) -> PendingCredentialDeletion {
+        PendingCredentialDeletion {
+            uuid: uuid.to_string(),
+            provider_type: "openai".to_string(),
+            name: Some("work".to_string()),
+            was_disabled: false,
+            requested_at: 100,
+            purge_after,
+        }
+    }
+
+    #[test]
+    fn test_pending_deletion_lifecycle() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        CredentialDeletionDao::insert_pending(&conn, &pending("a", 200)).unwrap();
+        CredentialDeletionDao::insert_pending(&conn, &pending("b", 500)).unwrap();
+        assert_eq!(CredentialDeletionDao::list_pending(&conn).unwrap().len(), 2);
+        assert_eq!(
+            CredentialDeletionDao::list_due(&conn, 300).unwrap(),
+            vec![pending("a", 200)]
+        );
+
+        assert!(CredentialDeletionDao::cancel(&conn, "b").unwrap());
+        assert!(CredentialDeletionDao::get_pending(&conn, "b")
+            .unwrap()
+            .is_none());
+
+        let receipt = CredentialDeletionReceipt {
+            uuid: "a".to_string(),
+            provider_type: "openai".to_string(),
+            name: Some("work".to_string()),
+            secret_fingerprint: "0123abcd".to_string(),
+            requested_at: 100,
+            shredded_at: 300,
+            purged: vec!["credential_data".to_string()],
+        };
+        CredentialDeletionDao::record_receipt(&conn, &receipt).unwrap();
+        assert!(CredentialDeletionDao::list_due(&conn, 1000)
+            .unwrap()
+            .is_empty());
+        assert!(!CredentialDeletionDao::cancel(&conn, "a").unwrap());
+        assert_eq!(
+            CredentialDeletionDao::list_receipts(&conn, 10).unwrap(),
+            vec![receipt]
+        );
+    }
+}
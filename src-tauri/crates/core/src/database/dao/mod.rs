@@ -1,23 +1,34 @@
 pub mod a2ui_form_dao;
+pub mod adaptive_selection;
 pub mod agent;
 pub mod agent_run;
 pub mod agent_timeline;
 pub mod api_key_provider;
+pub mod api_key_rate_limit;
 pub mod automation_job;
 pub mod brand_persona_dao;
 pub mod browser_environment_preset;
 pub mod browser_profile;
 pub mod chat;
+pub mod cost_usage;
+pub mod credential_deletion;
 pub mod installed_plugins;
 pub mod material_dao;
 pub mod mcp;
 pub mod orchestrator;
 pub mod persona_dao;
+pub mod plugin_storage;
 pub mod poster_material_dao;
+pub mod prompt_library;
 pub mod prompts;
 pub mod provider_pool;
 pub mod providers;
 pub mod publish_config_dao;
+pub mod rate_limit_profile;
+pub mod request_audit;
+pub mod request_cost;
 pub mod skills;
 pub mod template_dao;
+pub mod turn_attempt;
 pub mod video_generation_task_dao;
+pub mod virtual_model;
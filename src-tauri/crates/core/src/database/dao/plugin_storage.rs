@@ -0,0 +1,204 @@
+//! 插件键值存储（plugin_storage）数据访问对象
+//!
+//! 只负责按 (plugin_id, key) 读写，命名空间隔离与配额校验见 `plugin::storage`。
+
+use crate::database::pagination::{Page, PageRequest};
+use rusqlite::{params, params_from_iter, types::Value as SqlValue, Connection, OptionalExtension};
+
+pub struct PluginStorageDao;
+
+impl PluginStorageDao {
+    /// 读取值（JSON 文本）
+    pub fn get(
+        conn: &Connection,
+        plugin_id: &str,
+        key: &str,
+    ) -> Result<Option<String>, rusqlite::Error> {
+        conn.query_row(
+            "SELECT value FROM plugin_storage WHERE plugin_id = ?1 AND key = ?2",
+            params![plugin_id, key],
+            |row| row.get(0),
+        )
+        .optional()
+    }
+
+    /// 写入值（已存在时覆盖）
+    pub fn set(
+        conn: &Connection,
+        plugin_id: &str,
+        key: &str,
+        value: &str,
+    ) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO plugin_storage (plugin_id, key, value, updated_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(plugin_id, key) DO UPDATE SET
+                value = excluded.value,
+                updated_at = excluded.updated_at",
+            params![plugin_id, key, value, chrono::Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// 删除值，返回是否存在
+    pub fn delete(conn: &Connection, plugin_id: &str, key: &str) -> Result<bool, rusqlite::Error> {
+        let rows = conn.execute(
+            "DELETE FROM plugin_storage WHERE plugin_id = ?1 AND key = ?2",
+            params![plugin_id, key],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// 列出插件的所有键
+    pub fn list_keys(conn: &Connection, plugin_id: &str) -> Result<Vec<String>, rusqlite::Error> {
+        let mut stmt =
+            conn.prepare("SELECT key FROM plugin_storage WHERE plugin_id = ?1 ORDER BY key")?;
+        let rows = stmt.query_map(params![plugin_id], |row| row.get(0))?;
+        rows.collect()
+    }
+
+    /// 按键名升序分页列出键
+    pub fn list_keys_page(
+        conn: &Connection,
+        plugin_id: &str,
+        page: &PageRequest,
+    ) -> Result<Page<String>, rusqlite::Error> {
+        let mut sql = "SELECT key FROM plugin_storage WHERE plugin_id = ?".to_string();
+        let mut values = vec![SqlValue::Text(plugin_id.to_string())];
+        if let Some((filter, bound)) = page.keyset_filter(&["key"], false)? {
+            sql.push_str(" AND ");
+            sql.push_str(&filter);
+            values.extend(bound);
+        }
+        sql.push_str(" ORDER BY key LIMIT ?");
+        values.push(SqlValue::Integer(page.limit() as i64 + 1));
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        Ok(Page::from_rows(rows, page.limit(), |key| {
+            vec![SqlValue::Text(key.clone())]
+        }))
+    }
+
+    /// 插件已用字节数（键与值的长度之和），可排除某个键
+    pub fn usage_bytes(
+        conn: &Connection,
+        plugin_id: &str,
+        exclude_key: Option<&str>,
+    ) -> Result<u64, rusqlite::Error> {
+        let bytes: i64 = conn.query_row(
+            "SELECT COALESCE(SUM(LENGTH(CAST(key AS BLOB)) + LENGTH(CAST(value AS BLOB))), 0)
+             FROM plugin_storage
+             WHERE plugin_id = ?1 AND (?2 IS NULL OR key != ?2)",
+            params![plugin_id, exclude_key],
+            |row| row.get(0),
+        )?;
+        Ok(bytes.max(0) as u64)
+    }
+
+    /// 列出所有插件中以指定前缀开头的字符串值，返回 (plugin_id, key, 原始 JSON 文本)
+    ///
+    /// 用于主密钥轮换时查找需要重新加密的密文。
+    pub fn list_string_values_with_prefix(
+        conn: &Connection,
+        prefix: &str,
+    ) -> Result<Vec<(String, String, String)>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT plugin_id, key, value FROM plugin_storage
+             WHERE substr(value, 1, ?2) = ?1
+             ORDER BY plugin_id, key",
+        )?;
+        let pattern = format!("\"{prefix}");
+        let rows = stmt.query_map(params![pattern, pattern.chars().count() as i64], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+        rows.collect()
+    }
+
+    /// 清空插件的全部数据，返回删除条数
+    pub fn clear(conn: &Connection, plugin_id: &str) -> Result<usize, rusqlite::Error> {
+        conn.execute(
+            "DELETE FROM plugin_storage WHERE plugin_id = ?1",
+            params![plugin_id],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+
+    #[test]
+    fn test_storage_is_namespaced_per_plugin() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        PluginStorageDao::set(&conn, "plugin-a", "token", "\"a\"").unwrap();
+        PluginStorageDao::set(&conn, "plugin-b", "token", "\"b\"").unwrap();
+        PluginStorageDao::set(&conn, "plugin-a", "token", "\"a2\"").unwrap();
+
+        assert_eq!(
+            PluginStorageDao::get(&conn, "plugin-a", "token")
+                .unwrap()
+                .as_deref(),
+            Some("\"a2\"")
+        );
+        assert_eq!(
+            PluginStorageDao::get(&conn, "plugin-b", "token")
+                .unwrap()
+                .as_deref(),
+            Some("\"b\"")
+        );
+        assert_eq!(
+            PluginStorageDao::usage_bytes(&conn, "plugin-a", None).unwrap(),
+            9
+        );
+        assert_eq!(
+            PluginStorageDao::usage_bytes(&conn, "plugin-a", Some("token")).unwrap(),
+            0
+        );
+
+        assert!(PluginStorageDao::delete(&conn, "plugin-a", "token").unwrap());
+        assert!(!PluginStorageDao::delete(&conn, "plugin-a", "token").unwrap());
+        assert_eq!(
+            PluginStorageDao::list_keys(&conn, "plugin-b").unwrap(),
+            vec!["token".to_string()]
+        );
+        PluginStorageDao::set(&conn, "plugin-b", "secret", "\"enc3:v1:AAAA\"").unwrap();
+        assert_eq!(
+            PluginStorageDao::list_string_values_with_prefix(&conn, "enc3:").unwrap(),
+            vec![(
+                "plugin-b".to_string(),
+                "secret".to_string(),
+                "\"enc3:v1:AAAA\"".to_string()
+            )]
+        );
+        assert_eq!(PluginStorageDao::clear(&conn, "plugin-b").unwrap(), 2);
+    }
+
+    #[test]
+    fn test_list_keys_page() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        for key in ["c", "a", "b"] {
+            PluginStorageDao::set(&conn, "plugin-a", key, "1").unwrap();
+        }
+        PluginStorageDao::set(&conn, "plugin-b", "aa", "1").unwrap();
+
+        let mut page = PageRequest {
+            cursor: None,
+            limit: Some(2),
+        };
+        let first = PluginStorageDao::list_keys_page(&conn, "plugin-a", &page).unwrap();
+        assert_eq!(first.items, vec!["a".to_string(), "b".to_string()]);
+
+        page.cursor = first.next_cursor;
+        let second = PluginStorageDao::list_keys_page(&conn, "plugin-a", &page).unwrap();
+        assert_eq!(second.items, vec!["c".to_string()]);
+        assert!(second.next_cursor.is_none());
+    }
+}
@@ -0,0 +1,380 @@
+//! Prompt 库（prompt_library / prompt_library_versions）数据访问对象
+//!
+//! 与按 app_type 同步到 CLI 配置文件的 `prompts` 表相互独立：库中的 Prompt 按 ID
+//! 被对话、Agent 与 Skill 引用。每次内容或变量变化都会生成新版本，历史版本只追加不修改。
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+/// Prompt 中的变量（内容中以 `{{name}}` 引用）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptVariable {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// 未传值时使用的默认值，为空时该变量必填
+    #[serde(default)]
+    pub default_value: Option<String>,
+}
+
+/// Prompt 库条目（当前版本）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptLibraryEntry {
+    pub id: String,
+    pub name: String,
+    /// 文件夹路径（如 `writing/blog`），为空时位于根目录
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub content: String,
+    #[serde(default)]
+    pub variables: Vec<PromptVariable>,
+    /// 当前版本号（从 1 开始）
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+/// Prompt 的历史版本
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PromptLibraryVersion {
+    pub prompt_id: String,
+    pub version: u32,
+    pub content: String,
+    pub variables: Vec<PromptVariable>,
+    /// 版本说明
+    pub note: Option<String>,
+    pub created_at: i64,
+}
+
+/// Prompt 库查询条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromptLibraryQuery {
+    /// 文件夹（含子文件夹）
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// 按名称、描述与内容模糊匹配
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, name, folder, tags_json, description, content, variables_json,
+        version, created_at, updated_at
+     FROM prompt_library";
+
+pub struct PromptLibraryDao;
+
+impl PromptLibraryDao {
+    /// 按条件列出（按文件夹、名称排序）
+    pub fn list(
+        conn: &Connection,
+        query: &PromptLibraryQuery,
+    ) -> Result<Vec<PromptLibraryEntry>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!(
+            "{SELECT_COLUMNS} ORDER BY COALESCE(folder, ''), name COLLATE NOCASE"
+        ))?;
+        let entries = stmt
+            .query_map([], Self::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(entries
+            .into_iter()
+            .filter(|entry| matches_query(entry, query))
+            .collect())
+    }
+
+    pub fn get(conn: &Connection, id: &str) -> Result<Option<PromptLibraryEntry>, rusqlite::Error> {
+        conn.query_row(
+            &format!("{SELECT_COLUMNS} WHERE id = ?1"),
+            params![id],
+            Self::from_row,
+        )
+        .optional()
+    }
+
+    /// 保存条目：不存在时创建版本 1；内容或变量变化时追加新版本，其余字段原地更新
+    ///
+    /// 返回保存后的条目。
+    pub fn save(
+        conn: &Connection,
+        entry: &PromptLibraryEntry,
+        note: Option<&str>,
+        now: i64,
+    ) -> Result<PromptLibraryEntry, rusqlite::Error> {
+        let existing = Self::get(conn, &entry.id)?;
+        let (version, created_at, changed) = match &existing {
+            Some(current) => {
+                let changed =
+                    current.content != entry.content || current.variables != entry.variables;
+                let version = if changed {
+                    current.version + 1
+                } else {
+                    current.version
+                };
+                (version, current.created_at, changed)
+            }
+            None => (1, now, true),
+        };
+        let tags_json = serde_json::to_string(&entry.tags).unwrap_or_else(|_| "[]".to_string());
+        let variables_json =
+            serde_json::to_string(&entry.variables).unwrap_or_else(|_| "[]".to_string());
+
+        conn.execute(
+            "INSERT INTO prompt_library
+                (id, name, folder, tags_json, description, content, variables_json, version,
+                 created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+             ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                folder = excluded.folder,
+                tags_json = excluded.tags_json,
+                description = excluded.description,
+                content = excluded.content,
+                variables_json = excluded.variables_json,
+                version = excluded.version,
+                updated_at = excluded.updated_at",
+            params![
+                entry.id,
+                entry.name,
+                entry.folder,
+                tags_json,
+                entry.description,
+                entry.content,
+                variables_json,
+                version,
+                created_at,
+                now,
+            ],
+        )?;
+        if changed {
+            conn.execute(
+                "INSERT INTO prompt_library_versions
+                    (prompt_id, version, content, variables_json, note, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![entry.id, version, entry.content, variables_json, note, now],
+            )?;
+        }
+
+        Ok(PromptLibraryEntry {
+            version,
+            created_at,
+            updated_at: now,
+            ..entry.clone()
+        })
+    }
+
+    /// 删除条目及其全部历史版本，返回是否存在
+    pub fn delete(conn: &Connection, id: &str) -> Result<bool, rusqlite::Error> {
+        conn.execute(
+            "DELETE FROM prompt_library_versions WHERE prompt_id = ?1",
+            params![id],
+        )?;
+        let affected = conn.execute("DELETE FROM prompt_library WHERE id = ?1", params![id])?;
+        Ok(affected > 0)
+    }
+
+    /// 列出历史版本（最新在前）
+    pub fn list_versions(
+        conn: &Connection,
+        prompt_id: &str,
+    ) -> Result<Vec<PromptLibraryVersion>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT prompt_id, version, content, variables_json, note, created_at
+             FROM prompt_library_versions WHERE prompt_id = ?1 ORDER BY version DESC",
+        )?;
+        let rows = stmt.query_map(params![prompt_id], Self::version_from_row)?;
+        rows.collect()
+    }
+
+    pub fn get_version(
+        conn: &Connection,
+        prompt_id: &str,
+        version: u32,
+    ) -> Result<Option<PromptLibraryVersion>, rusqlite::Error> {
+        conn.query_row(
+            "SELECT prompt_id, version, content, variables_json, note, created_at
+             FROM prompt_library_versions WHERE prompt_id = ?1 AND version = ?2",
+            params![prompt_id, version],
+            Self::version_from_row,
+        )
+        .optional()
+    }
+
+    fn from_row(row: &Row) -> Result<PromptLibraryEntry, rusqlite::Error> {
+        Ok(PromptLibraryEntry {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            folder: row.get(2)?,
+            tags: parse_json_list(&row.get::<_, String>(3)?),
+            description: row.get(4)?,
+            content: row.get(5)?,
+            variables: parse_json_list(&row.get::<_, String>(6)?),
+            version: row.get(7)?,
+            created_at: row.get(8)?,
+            updated_at: row.get(9)?,
+        })
+    }
+
+    fn version_from_row(row: &Row) -> Result<PromptLibraryVersion, rusqlite::Error> {
+        Ok(PromptLibraryVersion {
+            prompt_id: row.get(0)?,
+            version: row.get(1)?,
+            content: row.get(2)?,
+            variables: parse_json_list(&row.get::<_, String>(3)?),
+            note: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    }
+}
+
+fn parse_json_list<T: serde::de::DeserializeOwned>(json: &str) -> Vec<T> {
+    serde_json::from_str(json).unwrap_or_default()
+}
+
+fn matches_query(entry: &PromptLibraryEntry, query: &PromptLibraryQuery) -> bool {
+    if let Some(folder) = query.folder.as_deref().filter(|f| !f.is_empty()) {
+        let entry_folder = entry.folder.as_deref().unwrap_or("");
+        let in_folder = entry_folder == folder
+            || entry_folder
+                .strip_prefix(folder)
+                .is_some_and(|rest| rest.starts_with('/'));
+        if !in_folder {
+            return false;
+        }
+    }
+    if let Some(tag) = query.tag.as_deref().filter(|t| !t.is_empty()) {
+        if !entry.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            return false;
+        }
+    }
+    if let Some(text) = query
+        .text
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+    {
+        let text = text.to_lowercase();
+        let hit = entry.name.to_lowercase().contains(&text)
+            || entry
+                .description
+                .as_deref()
+                .is_some_and(|d| d.to_lowercase().contains(&text))
+            || entry.content.to_lowercase().contains(&text);
+        if !hit {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+
+    fn entry(id: &str, folder: Option<&str>, content: &str) -> PromptLibraryEntry {
+        PromptLibraryEntry {
+            id: id.to_string(),
+            name: format!("Prompt {id}"),
+            folder: folder.map(str::to_string),
+            tags: vec!["writing".to_string()],
+            description: None,
+            content: content.to_string(),
+            variables: Vec::new(),
+            version: 0,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_save_appends_versions_on_content_change() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        let mut prompt = entry("p1", Some("writing/blog"), "v1 content");
+        let saved = PromptLibraryDao::save(&conn, &prompt, None, 100).unwrap();
+        assert_eq!((saved.version, saved.created_at), (1, 100));
+
+        // 仅改名不生成新版本
+        prompt.name = "Renamed".to_string();
+        assert_eq!(
+            PromptLibraryDao::save(&conn, &prompt, None, 150)
+                .unwrap()
+                .version,
+            1
+        );
+
+        prompt.content = "v2 content".to_string();
+        let saved = PromptLibraryDao::save(&conn, &prompt, Some("tweak"), 200).unwrap();
+        assert_eq!((saved.version, saved.created_at), (2, 100));
+
+        let versions = PromptLibraryDao::list_versions(&conn, "p1").unwrap();
+        assert_eq!(
+            versions.iter().map(|v| v.version).collect::<Vec<_>>(),
+            vec![2, 1]
+        );
+        assert_eq!(versions[0].note.as_deref(), Some("tweak"));
+        assert_eq!(
+            PromptLibraryDao::get_version(&conn, "p1", 1)
+                .unwrap()
+                .unwrap()
+                .content,
+            "v1 content"
+        );
+        let loaded = PromptLibraryDao::get(&conn, "p1").unwrap().unwrap();
+        assert_eq!(loaded.name, "Renamed");
+        assert_eq!(loaded.tags, vec!["writing".to_string()]);
+
+        assert!(PromptLibraryDao::delete(&conn, "p1").unwrap());
+        assert!(PromptLibraryDao::list_versions(&conn, "p1")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_list_filters_by_folder_tag_and_text() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        for prompt in [
+            entry("a", Some("writing"), "Write a blog post"),
+            entry("b", Some("writing/blog"), "Summarize"),
+            entry("c", Some("writing-old"), "Legacy"),
+            entry("d", None, "Review code"),
+        ] {
+            PromptLibraryDao::save(&conn, &prompt, None, 1).unwrap();
+        }
+
+        let ids = |query: PromptLibraryQuery| {
+            PromptLibraryDao::list(&conn, &query)
+                .unwrap()
+                .into_iter()
+                .map(|e| e.id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(PromptLibraryQuery::default()), vec!["d", "a", "b", "c"]);
+        assert_eq!(
+            ids(PromptLibraryQuery {
+                folder: Some("writing".to_string()),
+                ..PromptLibraryQuery::default()
+            }),
+            vec!["a", "b"]
+        );
+        assert_eq!(
+            ids(PromptLibraryQuery {
+                text: Some("BLOG".to_string()),
+                tag: Some("Writing".to_string()),
+                ..PromptLibraryQuery::default()
+            }),
+            vec!["a"]
+        );
+    }
+}
@@ -1,35 +1,56 @@
 //! Provider Pool 数据访问对象
 //!
 //! 提供凭证池的 CRUD 操作。
+//!
+//! 注册了 `CredentialCipher` 时 credential_data 加密存储；升级前的明文行在首次读取时
+//! 加密回写，`credential_encrypted` 列记录每行的加密状态。
 
+use crate::database::credential_cipher::{credential_cipher, CipherError};
 use crate::models::provider_pool_model::{
     CachedTokenInfo, CredentialData, CredentialSource, PoolProviderType, ProviderCredential,
-    ProviderPools,
+    ProviderPools, DEFAULT_CREDENTIAL_WEIGHT,
 };
 use chrono::{DateTime, TimeZone, Utc};
 use rusqlite::{params, Connection};
 
 pub struct ProviderPoolDao;
 
+/// 读取的行：(凭证, 行仍为明文时的原始 JSON)
+type StoredRow = (ProviderCredential, Option<String>);
+
+/// 不解密 credential_data 的凭证元数据（用于删除无法解密的凭证）
+#[derive(Debug, Clone, PartialEq)]
+pub struct CredentialMeta {
+    pub uuid: String,
+    pub provider_type: String,
+    pub name: Option<String>,
+    pub is_disabled: bool,
+}
+
 impl ProviderPoolDao {
     /// 获取所有凭证
     pub fn get_all(conn: &Connection) -> Result<Vec<ProviderCredential>, rusqlite::Error> {
+        Self::get_all_with_skipped(conn).map(|(credentials, _)| credentials)
+    }
+
+    /// 获取所有凭证，并返回本次读取因无法解密或解析而跳过的凭证数
+    pub fn get_all_with_skipped(
+        conn: &Connection,
+    ) -> Result<(Vec<ProviderCredential>, usize), rusqlite::Error> {
         let mut stmt = conn.prepare(
             "SELECT uuid, provider_type, credential_data, name, is_healthy, is_disabled,
                     check_health, check_model_name, not_supported_models, supported_models, usage_count, error_count,
                     last_used, last_error_time, last_error_message, last_health_check_time,
-                    last_health_check_model, created_at, updated_at, source, proxy_url
+                    last_health_check_model, created_at, updated_at, source, proxy_url,
+                    native_web_search, fallback_base_urls, credential_encrypted, weight
              FROM provider_pool_credentials
              ORDER BY provider_type, created_at ASC",
         )?;
 
-        let rows = stmt.query_map([], Self::row_to_credential)?;
+        let rows = stmt.query_map([], Self::row_to_stored_with_uuid)?;
+        let (stored, skipped) = Self::collect_readable(rows);
 
-        let mut credentials = Vec::new();
-        for cred in rows.flatten() {
-            credentials.push(cred);
-        }
-        Ok(credentials)
+        Ok((Self::encrypt_on_access(conn, stored), skipped))
     }
 
     /// 获取指定类型的凭证
@@ -41,21 +62,17 @@ impl ProviderPoolDao {
             "SELECT uuid, provider_type, credential_data, name, is_healthy, is_disabled,
                     check_health, check_model_name, not_supported_models, supported_models, usage_count, error_count,
                     last_used, last_error_time, last_error_message, last_health_check_time,
-                    last_health_check_model, created_at, updated_at, source, proxy_url
+                    last_health_check_model, created_at, updated_at, source, proxy_url,
+                    native_web_search, fallback_base_urls, credential_encrypted, weight
              FROM provider_pool_credentials
              WHERE provider_type = ?1
              ORDER BY created_at ASC",
         )?;
 
-        let rows = stmt.query_map([provider_type.to_string()], |row| {
-            Self::row_to_credential(row)
-        })?;
+        let rows = stmt.query_map([provider_type.to_string()], Self::row_to_stored_with_uuid)?;
+        let (stored, _) = Self::collect_readable(rows);
 
-        let mut credentials = Vec::new();
-        for cred in rows.flatten() {
-            credentials.push(cred);
-        }
-        Ok(credentials)
+        Ok(Self::encrypt_on_access(conn, stored))
     }
 
     /// 获取指定 UUID 的凭证
@@ -67,19 +84,43 @@ impl ProviderPoolDao {
             "SELECT uuid, provider_type, credential_data, name, is_healthy, is_disabled,
                     check_health, check_model_name, not_supported_models, supported_models, usage_count, error_count,
                     last_used, last_error_time, last_error_message, last_health_check_time,
-                    last_health_check_model, created_at, updated_at, source, proxy_url
+                    last_health_check_model, created_at, updated_at, source, proxy_url,
+                    native_web_search, fallback_base_urls, credential_encrypted, weight
              FROM provider_pool_credentials
              WHERE uuid = ?1",
         )?;
 
         let mut rows = stmt.query([uuid])?;
         if let Some(row) = rows.next()? {
-            Ok(Some(Self::row_to_credential(row)?))
+            let stored = Self::row_to_stored(row)?;
+            Ok(Self::encrypt_on_access(conn, vec![stored]).pop())
         } else {
             Ok(None)
         }
     }
 
+    /// 获取凭证元数据（不读取 credential_data，凭证无法解密时也可用）
+    pub fn get_meta(
+        conn: &Connection,
+        uuid: &str,
+    ) -> Result<Option<CredentialMeta>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT uuid, provider_type, name, is_disabled
+             FROM provider_pool_credentials
+             WHERE uuid = ?1",
+        )?;
+        let mut rows = stmt.query([uuid])?;
+        match rows.next()? {
+            Some(row) => Ok(Some(CredentialMeta {
+                uuid: row.get(0)?,
+                provider_type: row.get(1)?,
+                name: row.get(2)?,
+                is_disabled: row.get(3)?,
+            })),
+            None => Ok(None),
+        }
+    }
+
     /// 根据名称获取凭证
     pub fn get_by_name(
         conn: &Connection,
@@ -89,14 +130,16 @@ impl ProviderPoolDao {
             "SELECT uuid, provider_type, credential_data, name, is_healthy, is_disabled,
                     check_health, check_model_name, not_supported_models, supported_models, usage_count, error_count,
                     last_used, last_error_time, last_error_message, last_health_check_time,
-                    last_health_check_model, created_at, updated_at, source, proxy_url
+                    last_health_check_model, created_at, updated_at, source, proxy_url,
+                    native_web_search, fallback_base_urls, credential_encrypted, weight
              FROM provider_pool_credentials
              WHERE name = ?1",
         )?;
 
         let mut rows = stmt.query([name])?;
         if let Some(row) = rows.next()? {
-            Ok(Some(Self::row_to_credential(row)?))
+            let stored = Self::row_to_stored(row)?;
+            Ok(Self::encrypt_on_access(conn, vec![stored]).pop())
         } else {
             Ok(None)
         }
@@ -116,12 +159,15 @@ impl ProviderPoolDao {
 
     /// 插入新凭证
     pub fn insert(conn: &Connection, cred: &ProviderCredential) -> Result<(), rusqlite::Error> {
-        let credential_json =
-            serde_json::to_string(&cred.credential).unwrap_or_else(|_| "{}".to_string());
+        let (credential_data, credential_encrypted) = Self::encode_credential_data(
+            serde_json::to_string(&cred.credential).unwrap_or_else(|_| "{}".to_string()),
+        )?;
         let not_supported_models_json =
             serde_json::to_string(&cred.not_supported_models).unwrap_or_else(|_| "[]".to_string());
         let supported_models_json =
             serde_json::to_string(&cred.supported_models).unwrap_or_else(|_| "[]".to_string());
+        let fallback_base_urls_json =
+            serde_json::to_string(&cred.fallback_base_urls).unwrap_or_else(|_| "[]".to_string());
         let source_str = match cred.source {
             CredentialSource::Manual => "manual",
             CredentialSource::Imported => "imported",
@@ -133,12 +179,13 @@ impl ProviderPoolDao {
              (uuid, provider_type, credential_data, name, is_healthy, is_disabled,
               check_health, check_model_name, not_supported_models, supported_models, usage_count, error_count,
               last_used, last_error_time, last_error_message, last_health_check_time,
-              last_health_check_model, created_at, updated_at, source, proxy_url)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21)",
+              last_health_check_model, created_at, updated_at, source, proxy_url, native_web_search,
+              fallback_base_urls, credential_encrypted, weight)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25)",
             params![
                 cred.uuid,
                 cred.provider_type.to_string(),
-                credential_json,
+                credential_data,
                 cred.name,
                 cred.is_healthy,
                 cred.is_disabled,
@@ -157,6 +204,10 @@ impl ProviderPoolDao {
                 cred.updated_at.timestamp(),
                 source_str,
                 cred.proxy_url,
+                cred.native_web_search,
+                fallback_base_urls_json,
+                credential_encrypted,
+                cred.weight,
             ],
         )?;
         Ok(())
@@ -164,12 +215,15 @@ impl ProviderPoolDao {
 
     /// 更新凭证
     pub fn update(conn: &Connection, cred: &ProviderCredential) -> Result<(), rusqlite::Error> {
-        let credential_json =
-            serde_json::to_string(&cred.credential).unwrap_or_else(|_| "{}".to_string());
+        let (credential_data, credential_encrypted) = Self::encode_credential_data(
+            serde_json::to_string(&cred.credential).unwrap_or_else(|_| "{}".to_string()),
+        )?;
         let not_supported_models_json =
             serde_json::to_string(&cred.not_supported_models).unwrap_or_else(|_| "[]".to_string());
         let supported_models_json =
             serde_json::to_string(&cred.supported_models).unwrap_or_else(|_| "[]".to_string());
+        let fallback_base_urls_json =
+            serde_json::to_string(&cred.fallback_base_urls).unwrap_or_else(|_| "[]".to_string());
 
         conn.execute(
             "UPDATE provider_pool_credentials SET
@@ -177,12 +231,14 @@ impl ProviderPoolDao {
              is_disabled = ?6, check_health = ?7, check_model_name = ?8,
              not_supported_models = ?9, supported_models = ?10, usage_count = ?11, error_count = ?12,
              last_used = ?13, last_error_time = ?14, last_error_message = ?15,
-             last_health_check_time = ?16, last_health_check_model = ?17, updated_at = ?18, proxy_url = ?19
+             last_health_check_time = ?16, last_health_check_model = ?17, updated_at = ?18, proxy_url = ?19,
+             native_web_search = ?20, fallback_base_urls = ?21, credential_encrypted = ?22,
+             weight = ?23
              WHERE uuid = ?1",
             params![
                 cred.uuid,
                 cred.provider_type.to_string(),
-                credential_json,
+                credential_data,
                 cred.name,
                 cred.is_healthy,
                 cred.is_disabled,
@@ -199,11 +255,24 @@ impl ProviderPoolDao {
                 cred.last_health_check_model,
                 cred.updated_at.timestamp(),
                 cred.proxy_url,
+                cred.native_web_search,
+                fallback_base_urls_json,
+                credential_encrypted,
+                cred.weight,
             ],
         )?;
         Ok(())
     }
 
+    /// 插入或更新凭证（按 UUID 判断是否已存在）
+    pub fn upsert(conn: &Connection, cred: &ProviderCredential) -> Result<(), rusqlite::Error> {
+        if Self::get_by_uuid(conn, &cred.uuid)?.is_some() {
+            Self::update(conn, cred)
+        } else {
+            Self::insert(conn, cred)
+        }
+    }
+
     /// 删除凭证
     pub fn delete(conn: &Connection, uuid: &str) -> Result<bool, rusqlite::Error> {
         let affected = conn.execute(
@@ -213,6 +282,49 @@ impl ProviderPoolDao {
         Ok(affected > 0)
     }
 
+    /// 粉碎凭证：覆盖密钥、Token 缓存与错误信息列后删除行
+    ///
+    /// 在 `secure_delete` 下执行，释放的页以零填充。WAL 中可能仍有旧页，
+    /// 需要彻底清除时再调用 [`Self::checkpoint_wal`]。
+    pub fn shred(conn: &Connection, uuid: &str) -> Result<bool, rusqlite::Error> {
+        let secure_delete: i64 = conn.query_row("PRAGMA secure_delete", [], |row| row.get(0))?;
+        conn.pragma_update(None, "secure_delete", true)?;
+        let result = conn
+            .execute(
+                "UPDATE provider_pool_credentials SET
+                 credential_data = '', credential_encrypted = 0, proxy_url = NULL,
+                 cached_access_token = NULL, cached_refresh_token = NULL,
+                 token_expiry_time = NULL, last_refresh_error = NULL, last_error_message = NULL
+                 WHERE uuid = ?1",
+                [uuid],
+            )
+            .and_then(|_| Self::delete(conn, uuid));
+        conn.pragma_update(None, "secure_delete", secure_delete)?;
+        result
+    }
+
+    /// 截断 WAL，使已粉碎的旧页不再残留在数据库文件中（已有的数据库备份不受影响）
+    ///
+    /// 非 WAL 模式下该语句无副作用。
+    pub fn checkpoint_wal(conn: &Connection) {
+        if let Err(e) = conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(())) {
+            tracing::warn!("[凭证删除] 截断 WAL 失败: {}", e);
+        }
+    }
+
+    /// 直接设置禁用状态（不读取 credential_data，凭证无法解密时也可用）
+    pub fn set_disabled(
+        conn: &Connection,
+        uuid: &str,
+        is_disabled: bool,
+    ) -> Result<bool, rusqlite::Error> {
+        let affected = conn.execute(
+            "UPDATE provider_pool_credentials SET is_disabled = ?2, updated_at = ?3 WHERE uuid = ?1",
+            params![uuid, is_disabled, Utc::now().timestamp()],
+        )?;
+        Ok(affected > 0)
+    }
+
     /// 更新健康状态
     #[allow(clippy::too_many_arguments)]
     pub fn update_health_status(
@@ -266,6 +378,15 @@ impl ProviderPoolDao {
         Ok(())
     }
 
+    /// 更新负载均衡权重，返回凭证是否存在
+    pub fn set_weight(conn: &Connection, uuid: &str, weight: u32) -> Result<bool, rusqlite::Error> {
+        let affected = conn.execute(
+            "UPDATE provider_pool_credentials SET weight = ?2, updated_at = ?3 WHERE uuid = ?1",
+            params![uuid, weight, Utc::now().timestamp()],
+        )?;
+        Ok(affected > 0)
+    }
+
     /// 重置凭证计数器
     pub fn reset_counters(conn: &Connection, uuid: &str) -> Result<(), rusqlite::Error> {
         conn.execute(
@@ -294,10 +415,12 @@ impl ProviderPoolDao {
     }
 
     /// 从数据库行转换为 ProviderCredential
-    fn row_to_credential(row: &rusqlite::Row) -> Result<ProviderCredential, rusqlite::Error> {
+    ///
+    /// 行仍为明文时一并返回原始 JSON，供 `encrypt_on_access` 加密回写。
+    fn row_to_stored(row: &rusqlite::Row) -> Result<StoredRow, rusqlite::Error> {
         let uuid: String = row.get(0)?;
         let provider_type_str: String = row.get(1)?;
-        let credential_json: String = row.get(2)?;
+        let credential_data: String = row.get(2)?;
         let name: Option<String> = row.get(3)?;
         let is_healthy: bool = row.get(4)?;
         let is_disabled: bool = row.get(5)?;
@@ -316,10 +439,37 @@ impl ProviderPoolDao {
         let updated_at_ts: i64 = row.get(18)?;
         let source_str: Option<String> = row.get(19).ok();
         let proxy_url: Option<String> = row.get(20).ok();
+        let native_web_search: bool = row
+            .get::<_, Option<bool>>(21)
+            .ok()
+            .flatten()
+            .unwrap_or(false);
+        let fallback_base_urls_json: Option<String> =
+            row.get::<_, Option<String>>(22).ok().flatten();
+        let credential_encrypted: bool = row
+            .get::<_, Option<bool>>(23)
+            .ok()
+            .flatten()
+            .unwrap_or(false);
+        let weight: u32 = row
+            .get::<_, Option<i64>>(24)
+            .ok()
+            .flatten()
+            .map_or(DEFAULT_CREDENTIAL_WEIGHT, |w| {
+                w.clamp(1, u32::MAX as i64) as u32
+            });
 
         let provider_type: PoolProviderType =
             provider_type_str.parse().unwrap_or(PoolProviderType::Kiro);
 
+        let (credential_json, plaintext) =
+            Self::decode_credential_data(credential_data, credential_encrypted).map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    2,
+                    rusqlite::types::Type::Text,
+                    Box::new(e),
+                )
+            })?;
         let credential: CredentialData = serde_json::from_str(&credential_json).map_err(|e| {
             rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
         })?;
@@ -332,13 +482,17 @@ impl ProviderPoolDao {
             .and_then(|s| serde_json::from_str(&s).ok())
             .unwrap_or_default();
 
+        let fallback_base_urls: Vec<String> = fallback_base_urls_json
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+
         let source = match source_str.as_deref() {
             Some("imported") => CredentialSource::Imported,
             Some("private") => CredentialSource::Private,
             _ => CredentialSource::Manual,
         };
 
-        Ok(ProviderCredential {
+        let credential = ProviderCredential {
             uuid,
             provider_type,
             credential,
@@ -368,7 +522,136 @@ impl ProviderPoolDao {
             cached_token: None, // 从 get_token_cache 单独获取
             source,
             proxy_url,
-        })
+            native_web_search,
+            fallback_base_urls,
+            weight,
+        };
+        Ok((credential, plaintext.then_some(credential_json)))
+    }
+
+    /// 读取行并附带 uuid，便于记录被跳过的行
+    fn row_to_stored_with_uuid(
+        row: &rusqlite::Row,
+    ) -> Result<(String, Result<StoredRow, rusqlite::Error>), rusqlite::Error> {
+        Ok((row.get(0)?, Self::row_to_stored(row)))
+    }
+
+    /// 收集可读的行，逐条记录无法解密或解析的凭证，返回 (可读行, 跳过数)
+    fn collect_readable(
+        rows: impl Iterator<
+            Item = Result<(String, Result<StoredRow, rusqlite::Error>), rusqlite::Error>,
+        >,
+    ) -> (Vec<StoredRow>, usize) {
+        let mut stored = Vec::new();
+        let mut skipped = 0;
+        for row in rows {
+            match row {
+                Ok((_, Ok(row))) => stored.push(row),
+                Ok((uuid, Err(e))) => {
+                    skipped += 1;
+                    tracing::warn!("[凭证加密] 跳过无法读取的凭证 {}: {}", uuid, e);
+                }
+                Err(e) => {
+                    skipped += 1;
+                    tracing::warn!("[凭证加密] 跳过无法读取的凭证行: {}", e);
+                }
+            }
+        }
+        if skipped > 0 {
+            tracing::warn!("[凭证加密] 共 {} 条凭证无法读取，未出现在凭证池中", skipped);
+        }
+        (stored, skipped)
+    }
+
+    /// 加密待写入的凭证 JSON，返回 (存储文本, 是否已加密)
+    ///
+    /// 仅在未注册加密实现时按明文保存；已注册但加密失败（如钥匙串不可用）时写入失败，
+    /// 不再把凭证以明文落盘。
+    fn encode_credential_data(credential_json: String) -> Result<(String, bool), rusqlite::Error> {
+        let Some(cipher) = credential_cipher() else {
+            return Ok((credential_json, false));
+        };
+        cipher
+            .encrypt(&credential_json)
+            .map(|ciphertext| (ciphertext, true))
+            .map_err(|e| {
+                tracing::warn!("[凭证加密] 加密失败，拒绝以明文保存凭证: {}", e);
+                rusqlite::Error::ToSqlConversionFailure(Box::new(e))
+            })
+    }
+
+    /// 解密存储的凭证数据，返回 (凭证 JSON, 是否为明文)
+    fn decode_credential_data(
+        credential_data: String,
+        encrypted: bool,
+    ) -> Result<(String, bool), CipherError> {
+        match credential_cipher() {
+            Some(cipher) if encrypted || cipher.is_encrypted(&credential_data) => {
+                cipher.decrypt(&credential_data).map(|json| (json, false))
+            }
+            None if encrypted => Err(CipherError::Unavailable(
+                "凭证数据已加密，但未注册解密实现".to_string(),
+            )),
+            _ => Ok((credential_data, true)),
+        }
+    }
+
+    /// 将读取到的明文行加密回写（升级后的惰性迁移），失败时仅记录日志
+    ///
+    /// 密钥不可用时本批次不再尝试其余行，只记录一条日志。
+    fn encrypt_on_access(conn: &Connection, rows: Vec<StoredRow>) -> Vec<ProviderCredential> {
+        let mut cipher = credential_cipher();
+        rows.into_iter()
+            .map(|(cred, plaintext)| {
+                let (Some(active), Some(json)) = (cipher.as_ref(), plaintext) else {
+                    return cred;
+                };
+                let result = active.encrypt(&json).map(|ciphertext| {
+                    Self::set_raw_credential_data(conn, &cred.uuid, &ciphertext, true)
+                });
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        tracing::warn!("[凭证加密] 凭证 {} 加密回写失败: {}", cred.uuid, e)
+                    }
+                    Err(CipherError::Unavailable(e)) => {
+                        tracing::warn!("[凭证加密] 密钥不可用，暂缓加密明文凭证: {}", e);
+                        cipher = None;
+                    }
+                    Err(e) => tracing::warn!("[凭证加密] 凭证 {} 加密失败: {}", cred.uuid, e),
+                }
+                cred
+            })
+            .collect()
+    }
+
+    /// 列出所有行的原始 credential_data，返回 (uuid, 存储文本, 是否标记为已加密)
+    pub fn list_raw_credential_data(
+        conn: &Connection,
+    ) -> Result<Vec<(String, String, bool)>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT uuid, credential_data, COALESCE(credential_encrypted, 0)
+             FROM provider_pool_credentials
+             ORDER BY created_at ASC",
+        )?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+        rows.collect()
+    }
+
+    /// 直接写入 credential_data 及其加密状态（不修改 updated_at）
+    pub fn set_raw_credential_data(
+        conn: &Connection,
+        uuid: &str,
+        credential_data: &str,
+        encrypted: bool,
+    ) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "UPDATE provider_pool_credentials SET
+             credential_data = ?2, credential_encrypted = ?3
+             WHERE uuid = ?1",
+            params![uuid, credential_data, encrypted],
+        )?;
+        Ok(())
     }
 
     // ==================== Token 缓存操作 ====================
@@ -0,0 +1,121 @@
+//! 限流画像（rate_limit_profiles）数据访问对象
+//!
+//! 保存风控自适应冷却按凭证学习到的限流节奏，重启后恢复。
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+/// 单个凭证学习到的限流节奏（均为指数移动平均）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitProfile {
+    pub credential_id: String,
+    /// 累计限流次数
+    pub rate_limits: u64,
+    /// 典型 Retry-After（秒）
+    pub retry_after_secs: Option<f64>,
+    pub retry_after_samples: u64,
+    /// 相邻两次限流的间隔（秒）
+    pub interval_secs: Option<f64>,
+    /// 两次限流之间发出的请求数
+    pub requests_per_interval: Option<f64>,
+    pub interval_samples: u64,
+    /// 最后更新时间（Unix 秒）
+    pub updated_at: i64,
+}
+
+impl RateLimitProfile {
+    pub fn new(credential_id: impl Into<String>) -> Self {
+        Self {
+            credential_id: credential_id.into(),
+            rate_limits: 0,
+            retry_after_secs: None,
+            retry_after_samples: 0,
+            interval_secs: None,
+            requests_per_interval: None,
+            interval_samples: 0,
+            updated_at: 0,
+        }
+    }
+}
+
+pub struct RateLimitProfileDao;
+
+impl RateLimitProfileDao {
+    /// 写入或覆盖画像
+    pub fn upsert(conn: &Connection, profile: &RateLimitProfile) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO rate_limit_profiles
+             (credential_id, rate_limits, retry_after_secs, retry_after_samples,
+              interval_secs, requests_per_interval, interval_samples, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT(credential_id) DO UPDATE SET
+                rate_limits = excluded.rate_limits,
+                retry_after_secs = excluded.retry_after_secs,
+                retry_after_samples = excluded.retry_after_samples,
+                interval_secs = excluded.interval_secs,
+                requests_per_interval = excluded.requests_per_interval,
+                interval_samples = excluded.interval_samples,
+                updated_at = excluded.updated_at",
+            params![
+                profile.credential_id,
+                profile.rate_limits as i64,
+                profile.retry_after_secs,
+                profile.retry_after_samples as i64,
+                profile.interval_secs,
+                profile.requests_per_interval,
+                profile.interval_samples as i64,
+                profile.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 读取全部画像
+    pub fn list(conn: &Connection) -> Result<Vec<RateLimitProfile>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT credential_id, rate_limits, retry_after_secs, retry_after_samples,
+                    interval_secs, requests_per_interval, interval_samples, updated_at
+             FROM rate_limit_profiles
+             ORDER BY credential_id",
+        )?;
+        let rows = stmt.query_map([], Self::row_to_profile)?;
+        rows.collect()
+    }
+
+    /// 读取单个凭证的画像
+    pub fn get(
+        conn: &Connection,
+        credential_id: &str,
+    ) -> Result<Option<RateLimitProfile>, rusqlite::Error> {
+        conn.query_row(
+            "SELECT credential_id, rate_limits, retry_after_secs, retry_after_samples,
+                    interval_secs, requests_per_interval, interval_samples, updated_at
+             FROM rate_limit_profiles
+             WHERE credential_id = ?1",
+            params![credential_id],
+            Self::row_to_profile,
+        )
+        .optional()
+    }
+
+    /// 删除凭证的画像
+    pub fn delete(conn: &Connection, credential_id: &str) -> Result<usize, rusqlite::Error> {
+        conn.execute(
+            "DELETE FROM rate_limit_profiles WHERE credential_id = ?1",
+            params![credential_id],
+        )
+    }
+
+    fn row_to_profile(row: &rusqlite::Row<'_>) -> Result<RateLimitProfile, rusqlite::Error> {
+        Ok(RateLimitProfile {
+            credential_id: row.get(0)?,
+            rate_limits: row.get::<_, i64>(1)? as u64,
+            retry_after_secs: row.get(2)?,
+            retry_after_samples: row.get::<_, i64>(3)? as u64,
+            interval_secs: row.get(4)?,
+            requests_per_interval: row.get(5)?,
+            interval_samples: row.get::<_, i64>(6)? as u64,
+            updated_at: row.get(7)?,
+        })
+    }
+}
@@ -0,0 +1,503 @@
+//! 请求审计日志（request_audit_log）数据访问对象
+//!
+//! 每个请求一行，记录脱敏后的请求元数据（Provider、模型、凭证、状态、耗时、Token 数、
+//! 错误信息与截断的请求体）。`request_audit_fts` 为 FTS5 外部内容索引，由触发器同步，
+//! 用于按关键字检索。
+
+use crate::database::pagination::{Page, PageCursor, PageRequest};
+use rusqlite::{params, params_from_iter, types::Value as SqlValue, Connection, Row};
+use serde::{Deserialize, Serialize};
+
+/// 单次请求的审计记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestAuditRecord {
+    #[serde(default)]
+    pub id: i64,
+    pub request_id: String,
+    /// 请求时间（Unix 秒）
+    pub created_at: i64,
+    pub provider: String,
+    pub model: String,
+    pub credential_id: Option<String>,
+    pub is_stream: bool,
+    /// 请求状态（success / failed / timeout / cancelled / degenerate）
+    pub status: String,
+    pub latency_ms: u64,
+    pub retry_count: u32,
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub error_message: Option<String>,
+    /// 脱敏并截断后的请求体
+    pub request_body: Option<String>,
+    /// 严格模式下检测到的协议转换损失（如 `seed=dropped, tool_choice=transformed`）
+    #[serde(default)]
+    pub conversion_warnings: Option<String>,
+}
+
+/// 审计日志查询条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditQuery {
+    /// 全文检索关键字（空格分隔，全部匹配）
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    /// 起始时间（Unix 秒，含）
+    #[serde(default)]
+    pub from: Option<i64>,
+    /// 结束时间（Unix 秒，含）
+    #[serde(default)]
+    pub to: Option<i64>,
+    /// 最多返回条数（默认 100；分页检索默认 50、最大 500）
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: Option<usize>,
+    /// 分页游标（仅 [`RequestAuditDao::search_page`] 使用，替代 offset）
+    #[serde(default)]
+    pub cursor: Option<PageCursor>,
+}
+
+/// 按 Provider 汇总的请求状态
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditStatusBucket {
+    pub provider: String,
+    pub total: u64,
+    /// 状态不为 success 的请求数
+    pub failed: u64,
+}
+
+const DEFAULT_QUERY_LIMIT: usize = 100;
+
+/// 被判定为退化并已换凭证重试的原响应的状态，补写 Token 数等字段时跳过
+pub const DEGENERATE_STATUS: &str = "degenerate";
+
+const SELECT_COLUMNS: &str =
+    "SELECT a.id, a.request_id, a.created_at, a.provider, a.model, a.credential_id,
+        a.is_stream, a.status, a.latency_ms, a.retry_count, a.input_tokens, a.output_tokens,
+        a.error_message, a.request_body, a.conversion_warnings
+     FROM request_audit_log a";
+
+pub struct RequestAuditDao;
+
+impl RequestAuditDao {
+    /// 写入一条审计记录，返回行 ID
+    pub fn insert(conn: &Connection, record: &RequestAuditRecord) -> Result<i64, rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO request_audit_log
+                (request_id, created_at, provider, model, credential_id, is_stream, status,
+                 latency_ms, retry_count, input_tokens, output_tokens, error_message, request_body,
+                 conversion_warnings)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                record.request_id,
+                record.created_at,
+                record.provider,
+                record.model,
+                record.credential_id,
+                record.is_stream as i32,
+                record.status,
+                record.latency_ms as i64,
+                record.retry_count,
+                record.input_tokens.map(|v| v as i64),
+                record.output_tokens.map(|v| v as i64),
+                record.error_message,
+                record.request_body,
+                record.conversion_warnings,
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 补充请求的 Token 数，返回更新行数
+    pub fn update_tokens(
+        conn: &Connection,
+        request_id: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+    ) -> Result<usize, rusqlite::Error> {
+        conn.execute(
+            "UPDATE request_audit_log SET input_tokens = ?2, output_tokens = ?3
+             WHERE request_id = ?1 AND status != ?4",
+            params![
+                request_id,
+                input_tokens as i64,
+                output_tokens as i64,
+                DEGENERATE_STATUS
+            ],
+        )
+    }
+
+    /// 补充请求的协议转换损失，返回更新行数
+    pub fn update_conversion_warnings(
+        conn: &Connection,
+        request_id: &str,
+        warnings: &str,
+    ) -> Result<usize, rusqlite::Error> {
+        conn.execute(
+            "UPDATE request_audit_log SET conversion_warnings = ?2
+             WHERE request_id = ?1 AND status != ?3",
+            params![request_id, warnings, DEGENERATE_STATUS],
+        )
+    }
+
+    /// 按条件检索（最新在前）
+    pub fn search(
+        conn: &Connection,
+        query: &AuditQuery,
+    ) -> Result<Vec<RequestAuditRecord>, rusqlite::Error> {
+        let (mut sql, mut values) = Self::build_filtered_query(query, Vec::new());
+        sql.push_str(" ORDER BY a.created_at DESC, a.id DESC LIMIT ? OFFSET ?");
+        values.push(SqlValue::Integer(
+            query.limit.unwrap_or(DEFAULT_QUERY_LIMIT) as i64,
+        ));
+        values.push(SqlValue::Integer(query.offset.unwrap_or(0) as i64));
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(values), Self::from_row)?;
+        rows.collect()
+    }
+
+    /// 按条件分页检索（最新在前），使用 `query.cursor` 翻页，忽略 offset
+    pub fn search_page(
+        conn: &Connection,
+        query: &AuditQuery,
+    ) -> Result<Page<RequestAuditRecord>, rusqlite::Error> {
+        let page = PageRequest {
+            cursor: query.cursor.clone(),
+            limit: query.limit,
+        };
+        let keyset = page
+            .keyset_filter(&["a.created_at", "a.id"], true)?
+            .into_iter()
+            .collect();
+        let (mut sql, mut values) = Self::build_filtered_query(query, keyset);
+        sql.push_str(" ORDER BY a.created_at DESC, a.id DESC LIMIT ?");
+        values.push(SqlValue::Integer(page.limit() as i64 + 1));
+
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(params_from_iter(values), Self::from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Page::from_rows(rows, page.limit(), |record| {
+            vec![
+                SqlValue::Integer(record.created_at),
+                SqlValue::Integer(record.id),
+            ]
+        }))
+    }
+
+    /// 拼接检索条件，`extra` 为附加的 (条件, 绑定值)
+    fn build_filtered_query(
+        query: &AuditQuery,
+        extra: Vec<(String, Vec<SqlValue>)>,
+    ) -> (String, Vec<SqlValue>) {
+        let mut sql = SELECT_COLUMNS.to_string();
+        let mut conditions = Vec::new();
+        let mut values: Vec<SqlValue> = Vec::new();
+
+        if let Some(text) = query.text.as_deref().and_then(fts_match_expression) {
+            sql.push_str(" JOIN request_audit_fts f ON f.rowid = a.id");
+            conditions.push("request_audit_fts MATCH ?".to_string());
+            values.push(SqlValue::Text(text));
+        }
+        for (column, value) in [
+            ("a.provider", &query.provider),
+            ("a.model", &query.model),
+            ("a.status", &query.status),
+        ] {
+            if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+                conditions.push(format!("{column} = ?"));
+                values.push(SqlValue::Text(value.to_string()));
+            }
+        }
+        if let Some(from) = query.from {
+            conditions.push("a.created_at >= ?".to_string());
+            values.push(SqlValue::Integer(from));
+        }
+        if let Some(to) = query.to {
+            conditions.push("a.created_at <= ?".to_string());
+            values.push(SqlValue::Integer(to));
+        }
+        for (condition, bound) in extra {
+            conditions.push(condition);
+            values.extend(bound);
+        }
+
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        (sql, values)
+    }
+
+    /// 按 Provider 汇总 `[from, to]`（Unix 秒，含）范围内的请求状态
+    pub fn summarize_status(
+        conn: &Connection,
+        from: i64,
+        to: i64,
+    ) -> Result<Vec<AuditStatusBucket>, rusqlite::Error> {
+        let mut stmt = conn.prepare(
+            "SELECT provider, COUNT(*), SUM(CASE WHEN status = 'success' THEN 0 ELSE 1 END)
+             FROM request_audit_log
+             WHERE created_at >= ?1 AND created_at <= ?2
+             GROUP BY provider
+             ORDER BY provider",
+        )?;
+        let rows = stmt.query_map(params![from, to], |row| {
+            Ok(AuditStatusBucket {
+                provider: row.get(0)?,
+                total: row.get::<_, i64>(1)? as u64,
+                failed: row.get::<_, i64>(2)? as u64,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// 删除早于指定时间（Unix 秒）的记录，返回删除行数
+    pub fn prune_before(conn: &Connection, created_at: i64) -> Result<usize, rusqlite::Error> {
+        conn.execute(
+            "DELETE FROM request_audit_log WHERE created_at < ?1",
+            params![created_at],
+        )
+    }
+
+    /// 清空审计日志
+    pub fn clear(conn: &Connection) -> Result<usize, rusqlite::Error> {
+        conn.execute("DELETE FROM request_audit_log", [])
+    }
+
+    /// 清除指定凭证相关记录的错误信息（凭证粉碎时调用），同步更新全文索引，返回清除行数
+    pub fn scrub_credential_errors(
+        conn: &Connection,
+        credential_id: &str,
+    ) -> Result<usize, rusqlite::Error> {
+        let ids = {
+            let mut stmt = conn.prepare(
+                "SELECT id FROM request_audit_log
+                 WHERE credential_id = ?1 AND error_message IS NOT NULL",
+            )?;
+            let rows = stmt.query_map(params![credential_id], |row| row.get::<_, i64>(0))?;
+            rows.collect::<Result<Vec<_>, _>>()?
+        };
+        // 外部内容索引没有 UPDATE 触发器，需先按旧值删除索引再按新值写回
+        for id in &ids {
+            conn.execute(
+                "INSERT INTO request_audit_fts(request_audit_fts, rowid, provider, model, credential_id, error_message, request_body)
+                 SELECT 'delete', id, provider, model, credential_id, error_message, request_body
+                 FROM request_audit_log WHERE id = ?1",
+                params![id],
+            )?;
+            conn.execute(
+                "UPDATE request_audit_log SET error_message = NULL WHERE id = ?1",
+                params![id],
+            )?;
+            conn.execute(
+                "INSERT INTO request_audit_fts(rowid, provider, model, credential_id, error_message, request_body)
+                 SELECT id, provider, model, credential_id, error_message, request_body
+                 FROM request_audit_log WHERE id = ?1",
+                params![id],
+            )?;
+        }
+        Ok(ids.len())
+    }
+
+    fn from_row(row: &Row) -> Result<RequestAuditRecord, rusqlite::Error> {
+        Ok(RequestAuditRecord {
+            id: row.get(0)?,
+            request_id: row.get(1)?,
+            created_at: row.get(2)?,
+            provider: row.get(3)?,
+            model: row.get(4)?,
+            credential_id: row.get(5)?,
+            is_stream: row.get::<_, i32>(6)? == 1,
+            status: row.get(7)?,
+            latency_ms: row.get::<_, i64>(8)? as u64,
+            retry_count: row.get(9)?,
+            input_tokens: row.get::<_, Option<i64>>(10)?.map(|v| v as u64),
+            output_tokens: row.get::<_, Option<i64>>(11)?.map(|v| v as u64),
+            error_message: row.get(12)?,
+            request_body: row.get(13)?,
+            conversion_warnings: row.get(14)?,
+        })
+    }
+}
+
+/// 将用户输入转换为 FTS5 查询：每个关键字作为短语（转义双引号），全部匹配
+fn fts_match_expression(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+
+    fn record(request_id: &str, created_at: i64, error: Option<&str>) -> RequestAuditRecord {
+        RequestAuditRecord {
+            id: 0,
+            request_id: request_id.to_string(),
+            created_at,
+            provider: "claude".to_string(),
+            model: "claude-sonnet-4-5".to_string(),
+            credential_id: Some("cred-1".to_string()),
+            is_stream: false,
+            status: if error.is_some() { "failed" } else { "success" }.to_string(),
+            latency_ms: 120,
+            retry_count: 0,
+            input_tokens: None,
+            output_tokens: None,
+            error_message: error.map(str::to_string),
+            request_body: Some(r#"{"messages":[{"content":"hello world"}]}"#.to_string()),
+            conversion_warnings: None,
+        }
+    }
+
+    #[test]
+    fn test_audit_insert_search_and_prune() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        RequestAuditDao::insert(&conn, &record("r1", 100, None)).unwrap();
+        RequestAuditDao::insert(&conn, &record("r2", 200, Some("rate limit exceeded"))).unwrap();
+        assert_eq!(
+            RequestAuditDao::update_tokens(&conn, "r1", 10, 20).unwrap(),
+            1
+        );
+
+        let all = RequestAuditDao::search(&conn, &AuditQuery::default()).unwrap();
+        assert_eq!(
+            all.iter()
+                .map(|r| r.request_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["r2", "r1"]
+        );
+        assert_eq!(all[1].input_tokens, Some(10));
+        assert_eq!(
+            RequestAuditDao::update_conversion_warnings(&conn, "r2", "seed=dropped").unwrap(),
+            1
+        );
+        assert_eq!(
+            RequestAuditDao::search(&conn, &AuditQuery::default()).unwrap()[0]
+                .conversion_warnings
+                .as_deref(),
+            Some("seed=dropped")
+        );
+        assert_eq!(
+            RequestAuditDao::summarize_status(&conn, 0, 150).unwrap(),
+            vec![AuditStatusBucket {
+                provider: "claude".to_string(),
+                total: 1,
+                failed: 0,
+            }]
+        );
+        assert_eq!(
+            RequestAuditDao::summarize_status(&conn, 0, 300).unwrap()[0].failed,
+            1
+        );
+
+        let hits = RequestAuditDao::search(
+            &conn,
+            &AuditQuery {
+                text: Some("rate \"limit".to_string()),
+                ..AuditQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].request_id, "r2");
+
+        let hits = RequestAuditDao::search(
+            &conn,
+            &AuditQuery {
+                text: Some("hello".to_string()),
+                status: Some("success".to_string()),
+                ..AuditQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].request_id, "r1");
+
+        assert_eq!(RequestAuditDao::prune_before(&conn, 150).unwrap(), 1);
+        let hits = RequestAuditDao::search(
+            &conn,
+            &AuditQuery {
+                text: Some("hello".to_string()),
+                ..AuditQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].request_id, "r2");
+    }
+
+    #[test]
+    fn test_scrub_credential_errors_updates_index() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        RequestAuditDao::insert(&conn, &record("r1", 100, Some("invalid key sk-leaked"))).unwrap();
+        RequestAuditDao::insert(&conn, &record("r2", 200, None)).unwrap();
+        assert_eq!(
+            RequestAuditDao::scrub_credential_errors(&conn, "cred-1").unwrap(),
+            1
+        );
+
+        let leaked = AuditQuery {
+            text: Some("sk-leaked".to_string()),
+            ..AuditQuery::default()
+        };
+        assert!(RequestAuditDao::search(&conn, &leaked).unwrap().is_empty());
+        let hits = RequestAuditDao::search(
+            &conn,
+            &AuditQuery {
+                text: Some("hello".to_string()),
+                ..AuditQuery::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|r| r.error_message.is_none()));
+    }
+
+    #[test]
+    fn test_audit_search_page() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        for (request_id, created_at) in [("r1", 100), ("r2", 200), ("r3", 200), ("r4", 300)] {
+            RequestAuditDao::insert(&conn, &record(request_id, created_at, None)).unwrap();
+        }
+
+        let mut query = AuditQuery {
+            limit: Some(3),
+            ..AuditQuery::default()
+        };
+        let first = RequestAuditDao::search_page(&conn, &query).unwrap();
+        assert_eq!(
+            first
+                .items
+                .iter()
+                .map(|r| r.request_id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["r4", "r3", "r2"]
+        );
+
+        // 翻页期间插入的新记录不影响后续页
+        RequestAuditDao::insert(&conn, &record("r5", 400, None)).unwrap();
+        query.cursor = first.next_cursor;
+        let second = RequestAuditDao::search_page(&conn, &query).unwrap();
+        assert_eq!(second.items.len(), 1);
+        assert_eq!(second.items[0].request_id, "r1");
+        assert!(second.next_cursor.is_none());
+    }
+}
@@ -0,0 +1,205 @@
+//! 请求费用明细（request_costs）数据访问对象
+//!
+//! 每个请求一行，记录 Provider、凭证、模型、会话、租户与 Token 数及估算费用，
+//! 用于按日/月、Provider、模型、租户等维度汇总花费。
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+/// 单次请求的费用记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestCostRecord {
+    pub request_id: String,
+    /// 请求时间（Unix 秒）
+    pub created_at: i64,
+    /// 日期（UTC，`YYYY-MM-DD`）
+    pub day: String,
+    /// 月份（UTC，`YYYY-MM`）
+    pub month: String,
+    pub provider: String,
+    pub credential_id: Option<String>,
+    pub model: String,
+    pub session_id: Option<String>,
+    /// 所属租户（使用主 API Key 时为空）
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+}
+
+/// 汇总维度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendGroup {
+    Day,
+    Month,
+    Provider,
+    Credential,
+    Model,
+    Session,
+    Tenant,
+}
+
+impl SpendGroup {
+    fn column(self) -> &'static str {
+        match self {
+            SpendGroup::Day => "day",
+            SpendGroup::Month => "month",
+            SpendGroup::Provider => "provider",
+            SpendGroup::Credential => "credential_id",
+            SpendGroup::Model => "model",
+            SpendGroup::Session => "session_id",
+            SpendGroup::Tenant => "tenant_id",
+        }
+    }
+}
+
+/// 按维度汇总的花费
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpendBucket {
+    /// 维度取值（凭证/会话/租户为空的请求归入空字符串）
+    pub key: String,
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+}
+
+pub struct RequestCostDao;
+
+impl RequestCostDao {
+    /// 写入一条请求费用记录
+    pub fn insert(conn: &Connection, record: &RequestCostRecord) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO request_costs
+                (request_id, created_at, day, month, provider, credential_id, model, session_id,
+                 tenant_id, input_tokens, output_tokens, cost_usd)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                record.request_id,
+                record.created_at,
+                record.day,
+                record.month,
+                record.provider,
+                record.credential_id,
+                record.model,
+                record.session_id,
+                record.tenant_id,
+                record.input_tokens as i64,
+                record.output_tokens as i64,
+                record.cost_usd,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 按维度汇总 `[from_day, to_day]`（含）范围内的花费，按维度取值排序
+    pub fn summarize(
+        conn: &Connection,
+        group: SpendGroup,
+        from_day: &str,
+        to_day: &str,
+    ) -> Result<Vec<SpendBucket>, rusqlite::Error> {
+        let column = group.column();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT COALESCE({column}, ''), COUNT(*), SUM(input_tokens), SUM(output_tokens), SUM(cost_usd)
+             FROM request_costs
+             WHERE day >= ?1 AND day <= ?2
+             GROUP BY 1
+             ORDER BY 1"
+        ))?;
+        let rows = stmt.query_map(params![from_day, to_day], |row| {
+            Ok(SpendBucket {
+                key: row.get(0)?,
+                requests: row.get::<_, i64>(1)? as u64,
+                input_tokens: row.get::<_, i64>(2)? as u64,
+                output_tokens: row.get::<_, i64>(3)? as u64,
+                cost_usd: row.get(4)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    /// 删除早于指定日期的记录，返回删除行数
+    pub fn prune_before(conn: &Connection, day: &str) -> Result<usize, rusqlite::Error> {
+        conn.execute("DELETE FROM request_costs WHERE day < ?1", params![day])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+
+    fn record(day: &str, provider: &str, model: &str, cost_usd: f64) -> RequestCostRecord {
+        RequestCostRecord {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            created_at: 0,
+            day: day.to_string(),
+            month: day[..7].to_string(),
+            provider: provider.to_string(),
+            credential_id: None,
+            model: model.to_string(),
+            session_id: Some("session-1".to_string()),
+            tenant_id: (provider == "openai").then(|| "team-a".to_string()),
+            input_tokens: 100,
+            output_tokens: 50,
+            cost_usd,
+        }
+    }
+
+    #[test]
+    fn test_summarize_by_group_and_range() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        for r in [
+            record("2026-09-30", "openai", "gpt-4o", 5.0),
+            record("2026-10-01", "openai", "gpt-4o", 1.0),
+            record("2026-10-01", "claude", "claude-sonnet-4-5", 2.0),
+            record("2026-10-02", "openai", "gpt-4o-mini", 0.5),
+        ] {
+            RequestCostDao::insert(&conn, &r).unwrap();
+        }
+
+        let by_day =
+            RequestCostDao::summarize(&conn, SpendGroup::Day, "2026-10-01", "2026-10-31").unwrap();
+        assert_eq!(
+            by_day
+                .iter()
+                .map(|b| (b.key.as_str(), b.requests, b.cost_usd))
+                .collect::<Vec<_>>(),
+            vec![("2026-10-01", 2, 3.0), ("2026-10-02", 1, 0.5)]
+        );
+
+        let by_provider =
+            RequestCostDao::summarize(&conn, SpendGroup::Provider, "2026-10-01", "2026-10-31")
+                .unwrap();
+        assert_eq!(by_provider[1].key, "openai");
+        assert_eq!(by_provider[1].cost_usd, 1.5);
+        assert_eq!(by_provider[1].input_tokens, 200);
+
+        let by_credential =
+            RequestCostDao::summarize(&conn, SpendGroup::Credential, "2026-09-01", "2026-10-31")
+                .unwrap();
+        assert_eq!(by_credential.len(), 1);
+        assert_eq!(by_credential[0].key, "");
+
+        let by_tenant =
+            RequestCostDao::summarize(&conn, SpendGroup::Tenant, "2026-10-01", "2026-10-31")
+                .unwrap();
+        assert_eq!(
+            by_tenant
+                .iter()
+                .map(|b| (b.key.as_str(), b.requests))
+                .collect::<Vec<_>>(),
+            vec![("", 1), ("team-a", 2)]
+        );
+
+        assert_eq!(
+            RequestCostDao::prune_before(&conn, "2026-10-01").unwrap(),
+            1
+        );
+    }
+}
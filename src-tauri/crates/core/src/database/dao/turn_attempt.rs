@@ -0,0 +1,567 @@
+//! 对话回复的多次生成（agent_turn_attempts）数据访问对象
+//!
+//! 重新生成最后一轮回复时，不复制消息，而是把当前回复（用户消息及其后的全部消息行）
+//! 快照为该轮的一次「尝试」，再截断 agent_messages 重新执行。切换尝试时交换快照与当前消息行。
+//!
+//! 同一轮的尝试以 `anchor_message_id`（该轮用户消息之前最后一条消息的 ID）归组：
+//! 重新生成不会改动更早的消息，因此锚点在多次生成之间保持不变。
+//! 当前生效的尝试 `active = 1`，其消息保存在 agent_messages 中，`messages_json` 为空。
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+/// 预览文本的最大字符数
+const PREVIEW_MAX_CHARS: usize = 120;
+
+/// 重新生成时的参数覆盖
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TurnAttemptOverrides {
+    /// 使用其他模型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// 追加到用户消息后的补充要求
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<String>,
+}
+
+/// agent_messages 消息行快照（原样保存各列，切换时无损还原）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnMessageSnapshot {
+    pub role: String,
+    pub content_json: String,
+    pub timestamp: String,
+    #[serde(default)]
+    pub tool_calls_json: Option<String>,
+    #[serde(default)]
+    pub tool_call_id: Option<String>,
+    #[serde(default)]
+    pub reasoning_content: Option<String>,
+    #[serde(default)]
+    pub cost_json: Option<String>,
+}
+
+/// 会话的最后一轮（最后一条用户输入及其后的消息）
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastTurn {
+    /// 该轮用户消息之前最后一条消息的 ID（没有时为 0）
+    pub anchor_message_id: i64,
+    /// 该轮用户消息的 ID
+    pub user_message_id: i64,
+    /// 用户消息及其后的全部消息
+    pub messages: Vec<TurnMessageSnapshot>,
+}
+
+/// 一轮回复的一次尝试
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TurnAttempt {
+    pub id: String,
+    pub session_id: String,
+    /// 尝试序号（从 1 开始，切换后保持不变）
+    pub attempt: u32,
+    /// 是否为当前显示的回复
+    pub active: bool,
+    pub overrides: TurnAttemptOverrides,
+    /// 生成该回复的模型（来自消息费用记录）
+    pub model: Option<String>,
+    /// 回复文本预览
+    pub preview: String,
+    pub created_at: i64,
+}
+
+/// 开始重新生成的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct TurnRegeneration {
+    /// 新尝试的序号
+    pub attempt: u32,
+    /// 该轮原始用户消息内容（不含补充要求），用于重新提交
+    pub base_content: serde_json::Value,
+}
+
+pub struct TurnAttemptDao;
+
+impl TurnAttemptDao {
+    /// 读取会话的最后一轮，没有用户消息时返回 None
+    ///
+    /// 工具结果同样以 user 角色保存，但带有 `tool_call_id`，不视为用户输入。
+    pub fn last_turn(
+        conn: &Connection,
+        session_id: &str,
+    ) -> Result<Option<LastTurn>, rusqlite::Error> {
+        let user_message_id: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM agent_messages
+                 WHERE session_id = ?1 AND role = 'user' AND tool_call_id IS NULL
+                 ORDER BY id DESC LIMIT 1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(user_message_id) = user_message_id else {
+            return Ok(None);
+        };
+        let anchor_message_id: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(id), 0) FROM agent_messages WHERE session_id = ?1 AND id < ?2",
+            params![session_id, user_message_id],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare(
+            "SELECT role, content_json, timestamp, tool_calls_json, tool_call_id,
+                    reasoning_content, cost_json
+             FROM agent_messages WHERE session_id = ?1 AND id >= ?2 ORDER BY id ASC",
+        )?;
+        let messages = stmt
+            .query_map(
+                params![session_id, user_message_id],
+                Self::snapshot_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Some(LastTurn {
+            anchor_message_id,
+            user_message_id,
+            messages,
+        }))
+    }
+
+    /// 列出最后一轮的全部尝试（按序号排序）
+    pub fn list_last_turn(
+        conn: &Connection,
+        session_id: &str,
+    ) -> Result<Vec<TurnAttempt>, rusqlite::Error> {
+        let Some(last) = Self::last_turn(conn, session_id)? else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, attempt, active, messages_json, overrides_json, created_at
+             FROM agent_turn_attempts
+             WHERE session_id = ?1 AND anchor_message_id = ?2 ORDER BY attempt ASC",
+        )?;
+        let rows = stmt
+            .query_map(params![session_id, last.anchor_message_id], |row| {
+                Ok((
+                    Self::attempt_from_row(row)?,
+                    row.get::<_, Option<String>>(4)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(mut attempt, messages_json)| {
+                let messages = match messages_json {
+                    Some(json) => serde_json::from_str(&json).unwrap_or_default(),
+                    None => last.messages.clone(),
+                };
+                attempt.model = reply_model(&messages);
+                attempt.preview = reply_preview(&messages);
+                attempt
+            })
+            .collect())
+    }
+
+    /// 开始重新生成最后一轮：快照当前回复、记录新尝试并截断该轮消息
+    ///
+    /// 调用方随后以返回的原始用户消息重新提交该轮。会话没有用户消息时返回 None。
+    pub fn begin_regeneration(
+        conn: &Connection,
+        session_id: &str,
+        overrides: &TurnAttemptOverrides,
+        now: i64,
+    ) -> Result<Option<TurnRegeneration>, rusqlite::Error> {
+        let Some(last) = Self::last_turn(conn, session_id)? else {
+            return Ok(None);
+        };
+        let tx = conn.unchecked_transaction()?;
+
+        // 锚点之后的尝试属于已被删除或改写的轮次
+        tx.execute(
+            "DELETE FROM agent_turn_attempts WHERE session_id = ?1 AND anchor_message_id > ?2",
+            params![session_id, last.anchor_message_id],
+        )?;
+
+        let active = Self::active_row(&tx, session_id, last.anchor_message_id)?;
+        let base_content_json = match &active {
+            Some((id, base_content_json)) => {
+                Self::archive_active(&tx, id, &last.messages)?;
+                base_content_json.clone()
+            }
+            None => {
+                let base_content_json = last.messages[0].content_json.clone();
+                if last.messages.len() > 1 {
+                    Self::insert_attempt(
+                        &tx,
+                        session_id,
+                        last.anchor_message_id,
+                        1,
+                        &base_content_json,
+                        Some(&last.messages),
+                        &TurnAttemptOverrides::default(),
+                        now,
+                    )?;
+                }
+                base_content_json
+            }
+        };
+
+        let attempt = Self::next_attempt(&tx, session_id, last.anchor_message_id)?;
+        Self::insert_attempt(
+            &tx,
+            session_id,
+            last.anchor_message_id,
+            attempt,
+            &base_content_json,
+            None,
+            overrides,
+            now,
+        )?;
+        tx.execute(
+            "DELETE FROM agent_messages WHERE session_id = ?1 AND id >= ?2",
+            params![session_id, last.user_message_id],
+        )?;
+        tx.commit()?;
+
+        Ok(Some(TurnRegeneration {
+            attempt,
+            base_content: serde_json::from_str(&base_content_json)
+                .unwrap_or(serde_json::Value::Null),
+        }))
+    }
+
+    /// 切换到最后一轮的另一次尝试，返回是否切换成功
+    ///
+    /// 目标不存在、已是当前回复或不属于最后一轮时返回 false。
+    pub fn switch_attempt(
+        conn: &Connection,
+        session_id: &str,
+        attempt_id: &str,
+        now: i64,
+    ) -> Result<bool, rusqlite::Error> {
+        let Some(last) = Self::last_turn(conn, session_id)? else {
+            return Ok(false);
+        };
+        let target: Option<(i64, String, Option<String>)> = conn
+            .query_row(
+                "SELECT anchor_message_id, base_content_json, messages_json
+                 FROM agent_turn_attempts WHERE id = ?1 AND session_id = ?2 AND active = 0",
+                params![attempt_id, session_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?;
+        let Some((anchor, base_content_json, Some(messages_json))) = target else {
+            return Ok(false);
+        };
+        if anchor != last.anchor_message_id {
+            return Ok(false);
+        }
+        let restored: Vec<TurnMessageSnapshot> =
+            serde_json::from_str(&messages_json).unwrap_or_default();
+        if restored.is_empty() {
+            return Ok(false);
+        }
+
+        let tx = conn.unchecked_transaction()?;
+        match Self::active_row(&tx, session_id, anchor)? {
+            Some((id, _)) => Self::archive_active(&tx, &id, &last.messages)?,
+            None => {
+                let attempt = Self::next_attempt(&tx, session_id, anchor)?;
+                Self::insert_attempt(
+                    &tx,
+                    session_id,
+                    anchor,
+                    attempt,
+                    &base_content_json,
+                    Some(&last.messages),
+                    &TurnAttemptOverrides::default(),
+                    now,
+                )?;
+            }
+        }
+
+        tx.execute(
+            "DELETE FROM agent_messages WHERE session_id = ?1 AND id >= ?2",
+            params![session_id, last.user_message_id],
+        )?;
+        for message in &restored {
+            tx.execute(
+                "INSERT INTO agent_messages
+                    (session_id, role, content_json, timestamp, tool_calls_json, tool_call_id,
+                     reasoning_content, cost_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    session_id,
+                    message.role,
+                    message.content_json,
+                    message.timestamp,
+                    message.tool_calls_json,
+                    message.tool_call_id,
+                    message.reasoning_content,
+                    message.cost_json,
+                ],
+            )?;
+        }
+        tx.execute(
+            "UPDATE agent_turn_attempts SET active = 1, messages_json = NULL WHERE id = ?1",
+            params![attempt_id],
+        )?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    fn active_row(
+        conn: &Connection,
+        session_id: &str,
+        anchor_message_id: i64,
+    ) -> Result<Option<(String, String)>, rusqlite::Error> {
+        conn.query_row(
+            "SELECT id, base_content_json FROM agent_turn_attempts
+             WHERE session_id = ?1 AND anchor_message_id = ?2 AND active = 1",
+            params![session_id, anchor_message_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+    }
+
+    fn archive_active(
+        conn: &Connection,
+        id: &str,
+        messages: &[TurnMessageSnapshot],
+    ) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "UPDATE agent_turn_attempts SET active = 0, messages_json = ?2 WHERE id = ?1",
+            params![id, to_json(messages)?],
+        )?;
+        Ok(())
+    }
+
+    fn next_attempt(
+        conn: &Connection,
+        session_id: &str,
+        anchor_message_id: i64,
+    ) -> Result<u32, rusqlite::Error> {
+        conn.query_row(
+            "SELECT COALESCE(MAX(attempt), 0) + 1 FROM agent_turn_attempts
+             WHERE session_id = ?1 AND anchor_message_id = ?2",
+            params![session_id, anchor_message_id],
+            |row| row.get(0),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn insert_attempt(
+        conn: &Connection,
+        session_id: &str,
+        anchor_message_id: i64,
+        attempt: u32,
+        base_content_json: &str,
+        messages: Option<&[TurnMessageSnapshot]>,
+        overrides: &TurnAttemptOverrides,
+        now: i64,
+    ) -> Result<(), rusqlite::Error> {
+        conn.execute(
+            "INSERT INTO agent_turn_attempts
+                (id, session_id, anchor_message_id, attempt, active, base_content_json,
+                 messages_json, overrides_json, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                uuid::Uuid::new_v4().to_string(),
+                session_id,
+                anchor_message_id,
+                attempt,
+                messages.is_none(),
+                base_content_json,
+                messages.map(to_json).transpose()?,
+                to_json(overrides)?,
+                now,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn snapshot_from_row(row: &Row) -> Result<TurnMessageSnapshot, rusqlite::Error> {
+        Ok(TurnMessageSnapshot {
+            role: row.get(0)?,
+            content_json: row.get(1)?,
+            timestamp: row.get(2)?,
+            tool_calls_json: row.get(3)?,
+            tool_call_id: row.get(4)?,
+            reasoning_content: row.get(5)?,
+            cost_json: row.get(6)?,
+        })
+    }
+
+    fn attempt_from_row(row: &Row) -> Result<TurnAttempt, rusqlite::Error> {
+        let overrides_json: String = row.get(5)?;
+        Ok(TurnAttempt {
+            id: row.get(0)?,
+            session_id: row.get(1)?,
+            attempt: row.get(2)?,
+            active: row.get(3)?,
+            overrides: serde_json::from_str(&overrides_json).unwrap_or_default(),
+            model: None,
+            preview: String::new(),
+            created_at: row.get(6)?,
+        })
+    }
+}
+
+fn to_json<T: Serialize + ?Sized>(value: &T) -> Result<String, rusqlite::Error> {
+    serde_json::to_string(value).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))
+}
+
+/// 回复中最后一条带费用记录的 assistant 消息的模型
+fn reply_model(messages: &[TurnMessageSnapshot]) -> Option<String> {
+    messages
+        .iter()
+        .rev()
+        .filter(|message| message.role == "assistant")
+        .filter_map(|message| message.cost_json.as_deref())
+        .filter_map(|json| serde_json::from_str::<serde_json::Value>(json).ok())
+        .find_map(|cost| cost.get("model")?.as_str().map(str::to_string))
+}
+
+/// 回复中 assistant 文本内容的预览
+fn reply_preview(messages: &[TurnMessageSnapshot]) -> String {
+    let text = messages
+        .iter()
+        .filter(|message| message.role == "assistant")
+        .filter_map(|message| serde_json::from_str::<serde_json::Value>(&message.content_json).ok())
+        .flat_map(|content| match content {
+            serde_json::Value::Array(items) => items,
+            other => vec![other],
+        })
+        .filter(|item| item.get("type").and_then(|t| t.as_str()) == Some("text"))
+        .filter_map(|item| item.get("text")?.as_str().map(str::to_string))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let normalized = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if normalized.chars().count() <= PREVIEW_MAX_CHARS {
+        normalized
+    } else {
+        let truncated: String = normalized.chars().take(PREVIEW_MAX_CHARS - 1).collect();
+        format!("{truncated}…")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+
+    fn setup() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        conn.execute(
+            "INSERT INTO agent_sessions (id, model, created_at, updated_at)
+             VALUES ('s1', 'general:default', '2026-01-01T00:00:00Z', '2026-01-01T00:00:00Z')",
+            [],
+        )
+        .unwrap();
+        conn
+    }
+
+    fn add(conn: &Connection, role: &str, text: &str, cost_json: Option<&str>) {
+        conn.execute(
+            "INSERT INTO agent_messages (session_id, role, content_json, timestamp, cost_json)
+             VALUES ('s1', ?1, ?2, '2026-01-01T00:00:00Z', ?3)",
+            params![
+                role,
+                serde_json::json!([{ "type": "text", "text": text }]).to_string(),
+                cost_json
+            ],
+        )
+        .unwrap();
+    }
+
+    fn texts(conn: &Connection) -> Vec<String> {
+        let last = TurnAttemptDao::last_turn(conn, "s1").unwrap().unwrap();
+        last.messages
+            .iter()
+            .map(|m| {
+                let content: serde_json::Value = serde_json::from_str(&m.content_json).unwrap();
+                content[0]["text"].as_str().unwrap().to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_regenerate_and_switch_attempts() {
+        let conn = setup();
+        add(&conn, "user", "hi", None);
+        add(&conn, "assistant", "hello", None);
+        add(&conn, "user", "write a poem", None);
+        add(&conn, "assistant", "roses", Some(r#"{"model":"gpt-4o"}"#));
+
+        let overrides = TurnAttemptOverrides {
+            model: Some("claude-sonnet-4".to_string()),
+            instruction: Some("shorter".to_string()),
+            ..TurnAttemptOverrides::default()
+        };
+        let regeneration = TurnAttemptDao::begin_regeneration(&conn, "s1", &overrides, 100)
+            .unwrap()
+            .unwrap();
+        assert_eq!(regeneration.attempt, 2);
+        assert_eq!(regeneration.base_content[0]["text"], "write a poem");
+        // 截断后只剩第一轮，新一轮由运行时重新写入
+        let last = TurnAttemptDao::last_turn(&conn, "s1").unwrap().unwrap();
+        assert_eq!(last.anchor_message_id, 0);
+        add(&conn, "user", "write a poem\n\nshorter", None);
+        add(&conn, "assistant", "violets", None);
+
+        let attempts = TurnAttemptDao::list_last_turn(&conn, "s1").unwrap();
+        assert_eq!(
+            attempts
+                .iter()
+                .map(|a| (a.attempt, a.active, a.preview.as_str()))
+                .collect::<Vec<_>>(),
+            vec![(1, false, "roses"), (2, true, "violets")]
+        );
+        assert_eq!(attempts[0].model.as_deref(), Some("gpt-4o"));
+        assert_eq!(attempts[1].overrides, overrides);
+
+        assert!(TurnAttemptDao::switch_attempt(&conn, "s1", &attempts[0].id, 200).unwrap());
+        assert_eq!(texts(&conn), vec!["write a poem", "roses"]);
+        // 已是当前回复时不切换
+        assert!(!TurnAttemptDao::switch_attempt(&conn, "s1", &attempts[0].id, 300).unwrap());
+
+        let attempts = TurnAttemptDao::list_last_turn(&conn, "s1").unwrap();
+        assert_eq!(
+            attempts.iter().map(|a| a.active).collect::<Vec<_>>(),
+            vec![true, false]
+        );
+        assert!(TurnAttemptDao::switch_attempt(&conn, "s1", &attempts[1].id, 400).unwrap());
+        assert_eq!(texts(&conn), vec!["write a poem\n\nshorter", "violets"]);
+
+        // 再次重新生成仍以原始用户消息为准
+        let regeneration =
+            TurnAttemptDao::begin_regeneration(&conn, "s1", &TurnAttemptOverrides::default(), 500)
+                .unwrap()
+                .unwrap();
+        assert_eq!(regeneration.attempt, 3);
+        assert_eq!(regeneration.base_content[0]["text"], "write a poem");
+    }
+
+    #[test]
+    fn test_attempts_of_earlier_turns_are_not_switchable() {
+        let conn = setup();
+        add(&conn, "user", "q1", None);
+        add(&conn, "assistant", "a1", None);
+        TurnAttemptDao::begin_regeneration(&conn, "s1", &TurnAttemptOverrides::default(), 1)
+            .unwrap()
+            .unwrap();
+        add(&conn, "user", "q1", None);
+        add(&conn, "assistant", "a1 again", None);
+        let first_turn = TurnAttemptDao::list_last_turn(&conn, "s1").unwrap();
+        assert_eq!(first_turn.len(), 2);
+
+        add(&conn, "user", "q2", None);
+        add(&conn, "assistant", "a2", None);
+        assert!(TurnAttemptDao::list_last_turn(&conn, "s1")
+            .unwrap()
+            .is_empty());
+        assert!(!TurnAttemptDao::switch_attempt(&conn, "s1", &first_turn[0].id, 2).unwrap());
+        assert_eq!(texts(&conn), vec!["q2", "a2"]);
+    }
+}
@@ -0,0 +1,121 @@
+//! 虚拟模型（virtual_models）数据访问对象
+
+use crate::models::VirtualModel;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+const SELECT_COLUMNS: &str = "SELECT name, provider, model, temperature, system_prefix, description, enabled, created_at, updated_at
+     FROM virtual_models";
+
+pub struct VirtualModelDao;
+
+impl VirtualModelDao {
+    /// 获取全部虚拟模型
+    pub fn list(conn: &Connection) -> Result<Vec<VirtualModel>, rusqlite::Error> {
+        let mut stmt = conn.prepare(&format!("{SELECT_COLUMNS} ORDER BY name"))?;
+        let rows = stmt.query_map([], Self::from_row)?;
+        rows.collect()
+    }
+
+    /// 按名称获取已启用的虚拟模型
+    pub fn get_enabled(
+        conn: &Connection,
+        name: &str,
+    ) -> Result<Option<VirtualModel>, rusqlite::Error> {
+        conn.query_row(
+            &format!("{SELECT_COLUMNS} WHERE name = ?1 AND enabled = 1"),
+            params![name],
+            Self::from_row,
+        )
+        .optional()
+    }
+
+    /// 新增或更新虚拟模型（按名称）
+    pub fn upsert(conn: &Connection, model: &VirtualModel) -> Result<(), rusqlite::Error> {
+        let now = chrono::Utc::now().timestamp();
+        conn.execute(
+            "INSERT INTO virtual_models
+                (name, provider, model, temperature, system_prefix, description, enabled, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)
+             ON CONFLICT(name) DO UPDATE SET
+                provider = excluded.provider,
+                model = excluded.model,
+                temperature = excluded.temperature,
+                system_prefix = excluded.system_prefix,
+                description = excluded.description,
+                enabled = excluded.enabled,
+                updated_at = excluded.updated_at",
+            params![
+                model.name,
+                model.provider,
+                model.model,
+                model.temperature,
+                model.system_prefix,
+                model.description,
+                model.enabled as i32,
+                now,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 删除虚拟模型
+    pub fn delete(conn: &Connection, name: &str) -> Result<bool, rusqlite::Error> {
+        let affected = conn.execute("DELETE FROM virtual_models WHERE name = ?1", params![name])?;
+        Ok(affected > 0)
+    }
+
+    fn from_row(row: &Row) -> Result<VirtualModel, rusqlite::Error> {
+        Ok(VirtualModel {
+            name: row.get(0)?,
+            provider: row.get(1)?,
+            model: row.get(2)?,
+            temperature: row.get(3)?,
+            system_prefix: row.get(4)?,
+            description: row.get(5)?,
+            enabled: row.get::<_, i32>(6)? == 1,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+
+    #[test]
+    fn test_virtual_model_crud() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        let mut model = VirtualModel {
+            name: "my-smart".to_string(),
+            provider: Some("claude".to_string()),
+            model: "claude-sonnet-4-5".to_string(),
+            temperature: None,
+            system_prefix: Some("Think step by step.".to_string()),
+            description: None,
+            enabled: true,
+            created_at: 0,
+            updated_at: 0,
+        };
+        VirtualModelDao::upsert(&conn, &model).unwrap();
+
+        let loaded = VirtualModelDao::get_enabled(&conn, "my-smart")
+            .unwrap()
+            .unwrap();
+        assert_eq!(loaded.model, "claude-sonnet-4-5");
+        assert_eq!(loaded.system_prefix.as_deref(), Some("Think step by step."));
+
+        model.enabled = false;
+        VirtualModelDao::upsert(&conn, &model).unwrap();
+        assert!(VirtualModelDao::get_enabled(&conn, "my-smart")
+            .unwrap()
+            .is_none());
+        assert_eq!(VirtualModelDao::list(&conn).unwrap().len(), 1);
+
+        assert!(VirtualModelDao::delete(&conn, "my-smart").unwrap());
+        assert!(VirtualModelDao::list(&conn).unwrap().is_empty());
+    }
+}
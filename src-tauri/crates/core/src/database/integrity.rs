@@ -0,0 +1,411 @@
+//! 数据库完整性检查与自动恢复
+//!
+//! 启动时在打开主连接前执行 `PRAGMA quick_check`（不核对索引与表内容是否一致，
+//! 大库上也能快速完成）。发现损坏时先把损坏文件（连同 `-wal` / `-shm`）隔离为 `*.corrupt-<时间戳>`，然后依次尝试：
+//! 1. 从最近一份通过完整性检查的备份恢复；
+//! 2. 逐表抢救损坏文件中仍可读取的数据，重建新数据库；
+//! 3. 都失败时以空数据库启动（表结构由 `schema::create_tables` 重建）。
+//!
+//! 整个过程生成 [`DatabaseRecoveryReport`]，供前端向用户展示。
+
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+/// `quick_check` 最多返回的问题条数
+const MAX_REPORTED_ISSUES: usize = 20;
+
+static RECOVERY_REPORT: OnceLock<DatabaseRecoveryReport> = OnceLock::new();
+
+/// 恢复动作
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryAction {
+    /// 数据库完好，无需恢复
+    #[default]
+    None,
+    /// 已从备份恢复
+    RestoredBackup,
+    /// 已从损坏文件中抢救数据并重建
+    Salvaged,
+    /// 无法抢救，已重建空数据库
+    Rebuilt,
+    /// 恢复失败
+    Failed,
+}
+
+/// 数据库恢复报告
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseRecoveryReport {
+    /// 是否检测到损坏
+    pub corrupted: bool,
+    /// `quick_check` 报告的问题（或打开失败的错误）
+    pub issues: Vec<String>,
+    /// 执行的恢复动作
+    pub action: RecoveryAction,
+    /// 隔离保存的损坏文件路径
+    pub quarantined_path: Option<String>,
+    /// 用于恢复的备份文件路径
+    pub restored_from: Option<String>,
+    /// 抢救成功的表
+    pub salvaged_tables: Vec<String>,
+    /// 抢救失败的表
+    pub lost_tables: Vec<String>,
+    /// 面向用户的说明
+    pub message: Option<String>,
+}
+
+/// 执行 `PRAGMA quick_check`，返回发现的问题（为空表示完好）
+pub fn check_integrity(conn: &Connection) -> Result<Vec<String>, String> {
+    integrity_issues(conn).map_err(|e| e.to_string())
+}
+
+fn integrity_issues(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA quick_check")?;
+    let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+
+    let mut issues = Vec::new();
+    for row in rows {
+        let line = row?;
+        if line != "ok" {
+            issues.push(line);
+        }
+        if issues.len() >= MAX_REPORTED_ISSUES {
+            break;
+        }
+    }
+    Ok(issues)
+}
+
+/// 检查数据库文件完整性，必要时自动恢复
+///
+/// `backup_dirs` 为查找备份的目录列表，识别 `BackupService` 生成的 `lime_*.db`
+/// 与数据迁移时生成的 `*.bootstrap-backup-*.bak`。
+pub fn ensure_database_integrity(
+    db_path: &Path,
+    backup_dirs: &[PathBuf],
+) -> DatabaseRecoveryReport {
+    if !db_path.exists() {
+        return DatabaseRecoveryReport::default();
+    }
+
+    let issues = match inspect_file(db_path) {
+        Ok(issues) if issues.is_empty() => return DatabaseRecoveryReport::default(),
+        Ok(issues) => issues,
+        Err(error) if is_corruption_error(&error) => vec![error.to_string()],
+        Err(error) => {
+            // 文件被占用、权限不足等不属于损坏，交给正常启动流程报错
+            tracing::warn!("[数据库] 完整性检查无法执行，跳过: {}", error);
+            return DatabaseRecoveryReport::default();
+        }
+    };
+
+    tracing::error!(
+        "[数据库] 完整性检查失败，开始自动恢复: {}",
+        issues.join("; ")
+    );
+
+    let mut report = DatabaseRecoveryReport {
+        corrupted: true,
+        issues,
+        ..Default::default()
+    };
+
+    let quarantined = match quarantine(db_path) {
+        Ok(path) => path,
+        Err(error) => {
+            report.action = RecoveryAction::Failed;
+            report.message = Some(format!("无法隔离损坏的数据库文件: {error}"));
+            return report;
+        }
+    };
+    report.quarantined_path = Some(quarantined.to_string_lossy().to_string());
+
+    if let Some(backup) = latest_healthy_backup(backup_dirs) {
+        match fs::copy(&backup, db_path) {
+            Ok(_) => {
+                report.action = RecoveryAction::RestoredBackup;
+                report.message = Some(format!(
+                    "数据库文件已损坏，已从备份 {} 恢复，备份之后的数据可能丢失",
+                    backup.display()
+                ));
+                report.restored_from = Some(backup.to_string_lossy().to_string());
+                return report;
+            }
+            Err(e) => {
+                tracing::warn!("[数据库] 从备份恢复失败 {}: {}", backup.display(), e);
+            }
+        }
+    }
+
+    match salvage(&quarantined, db_path) {
+        Ok((salvaged, lost)) if !salvaged.is_empty() => {
+            report.action = RecoveryAction::Salvaged;
+            report.message = Some(if lost.is_empty() {
+                "数据库文件已损坏，已抢救全部可读数据并重建数据库".to_string()
+            } else {
+                format!(
+                    "数据库文件已损坏，已抢救 {} 张表，{} 张表无法恢复",
+                    salvaged.len(),
+                    lost.len()
+                )
+            });
+            report.salvaged_tables = salvaged;
+            report.lost_tables = lost;
+        }
+        Ok((_, lost)) => {
+            report.action = RecoveryAction::Rebuilt;
+            report.message = Some("数据库文件已损坏且没有可用备份，已重建空数据库".to_string());
+            report.lost_tables = lost;
+        }
+        Err(error) => {
+            // 抢救中途失败时清理半成品，让启动流程创建空库
+            let _ = remove_with_sidecars(db_path);
+            report.action = RecoveryAction::Rebuilt;
+            report.message = Some(format!(
+                "数据库文件已损坏且无法抢救（{error}），已重建空数据库"
+            ));
+        }
+    }
+
+    report
+}
+
+/// 默认的备份查找目录：`~/.lime/backups` 与数据库所在目录
+pub fn default_backup_dirs(db_path: &Path) -> Vec<PathBuf> {
+    let mut dirs_list = Vec::new();
+    if let Some(home) = dirs::home_dir() {
+        dirs_list.push(home.join(".lime").join("backups"));
+    }
+    if let Some(parent) = db_path.parent() {
+        dirs_list.push(parent.to_path_buf());
+    }
+    dirs_list
+}
+
+/// 设置本次启动的恢复报告（仅首次调用生效）
+pub fn set_recovery_report(report: DatabaseRecoveryReport) {
+    let _ = RECOVERY_REPORT.set(report);
+}
+
+/// 获取本次启动的恢复报告
+pub fn recovery_report() -> DatabaseRecoveryReport {
+    RECOVERY_REPORT.get().cloned().unwrap_or_default()
+}
+
+fn inspect_file(path: &Path) -> rusqlite::Result<Vec<String>> {
+    let conn = Connection::open(path)?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))?;
+    integrity_issues(&conn)
+}
+
+fn is_corruption_error(error: &rusqlite::Error) -> bool {
+    matches!(
+        error.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase)
+    )
+}
+
+/// 将损坏的数据库及其 WAL/SHM 文件重命名为 `*.corrupt-<时间戳>`
+fn quarantine(db_path: &Path) -> Result<PathBuf, String> {
+    let suffix = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let file_name = db_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "database".to_string());
+    let quarantined = db_path.with_file_name(format!("{file_name}.corrupt-{suffix}"));
+
+    fs::rename(db_path, &quarantined).map_err(|e| e.to_string())?;
+    for sidecar in ["-wal", "-shm"] {
+        let source = db_path.with_file_name(format!("{file_name}{sidecar}"));
+        if source.exists() {
+            let target = quarantined.with_file_name(format!(
+                "{}{sidecar}",
+                quarantined
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+            ));
+            fs::rename(&source, &target).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(quarantined)
+}
+
+/// 查找最近一份通过完整性检查的备份
+fn latest_healthy_backup(backup_dirs: &[PathBuf]) -> Option<PathBuf> {
+    let mut candidates: Vec<(SystemTime, PathBuf)> = backup_dirs
+        .iter()
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten())
+        .map(|entry| entry.path())
+        .filter(|path| is_backup_file(path))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.0.cmp(&a.0));
+
+    candidates
+        .into_iter()
+        .map(|(_, path)| path)
+        .find(|path| matches!(inspect_file(path), Ok(issues) if issues.is_empty()))
+}
+
+fn is_backup_file(path: &Path) -> bool {
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+        return false;
+    };
+    if name.contains(".corrupt-") {
+        return false;
+    }
+    // BackupService 生成的 lime_<时间戳>.db 与启动迁移生成的 *.bootstrap-backup-*.bak
+    (name.starts_with("lime_") && name.ends_with(".db")) || name.contains(".bootstrap-backup-")
+}
+
+/// 从损坏文件中逐表抢救数据到新数据库
+///
+/// 返回 (成功的表, 失败的表)。
+fn salvage(source: &Path, target: &Path) -> Result<(Vec<String>, Vec<String>), String> {
+    let source_conn = Connection::open_with_flags(source, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("无法打开损坏的数据库: {e}"))?;
+    let tables: Vec<(String, String)> = {
+        let mut stmt = source_conn
+            .prepare(
+                "SELECT name, sql FROM sqlite_master
+                 WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND sql IS NOT NULL",
+            )
+            .map_err(|e| format!("无法读取表结构: {e}"))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| format!("无法读取表结构: {e}"))?;
+        rows.filter_map(Result::ok).collect()
+    };
+    drop(source_conn);
+
+    let conn = Connection::open(target).map_err(|e| e.to_string())?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS corrupt",
+        [source.to_string_lossy().to_string()],
+    )
+    .map_err(|e| format!("无法挂载损坏的数据库: {e}"))?;
+
+    let mut salvaged = Vec::new();
+    let mut lost = Vec::new();
+    for (name, sql) in tables {
+        let copy = conn.execute_batch(&sql).and_then(|_| {
+            conn.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO main.\"{0}\" SELECT * FROM corrupt.\"{0}\"",
+                    name.replace('"', "\"\"")
+                ),
+                [],
+            )
+        });
+        match copy {
+            Ok(rows) => {
+                tracing::info!("[数据库] 已抢救表 {}（{} 行）", name, rows);
+                salvaged.push(name);
+            }
+            Err(e) => {
+                tracing::warn!("[数据库] 抢救表 {} 失败: {}", name, e);
+                lost.push(name);
+            }
+        }
+    }
+
+    let _ = conn.execute("DETACH DATABASE corrupt", []);
+    Ok((salvaged, lost))
+}
+
+fn remove_with_sidecars(db_path: &Path) -> std::io::Result<()> {
+    let file_name = db_path.file_name().unwrap_or_default().to_string_lossy();
+    for sidecar in ["-wal", "-shm"] {
+        let path = db_path.with_file_name(format!("{file_name}{sidecar}"));
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    if db_path.exists() {
+        fs::remove_file(db_path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_db(path: &Path, rows: &[&str]) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch("CREATE TABLE notes (id INTEGER PRIMARY KEY, body TEXT)")
+            .unwrap();
+        for row in rows {
+            conn.execute("INSERT INTO notes (body) VALUES (?1)", [row])
+                .unwrap();
+        }
+    }
+
+    fn corrupt_file(path: &Path) {
+        let mut bytes = fs::read(path).unwrap();
+        // 破坏文件头，使其不再是合法的 SQLite 数据库
+        bytes[..16].copy_from_slice(b"not a database!!");
+        fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn test_healthy_database_is_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("lime.db");
+        create_db(&db_path, &["a"]);
+
+        let report = ensure_database_integrity(&db_path, &[]);
+        assert!(!report.corrupted);
+        assert_eq!(report.action, RecoveryAction::None);
+        assert!(db_path.exists());
+    }
+
+    #[test]
+    fn test_corrupted_database_restored_from_latest_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let backups = dir.path().join("backups");
+        fs::create_dir_all(&backups).unwrap();
+        create_db(&backups.join("lime_20260101_000000.db"), &["from backup"]);
+
+        let db_path = dir.path().join("lime.db");
+        create_db(&db_path, &["live"]);
+        corrupt_file(&db_path);
+
+        let report = ensure_database_integrity(&db_path, &[backups]);
+        assert!(report.corrupted);
+        assert_eq!(report.action, RecoveryAction::RestoredBackup);
+        assert!(Path::new(report.quarantined_path.as_ref().unwrap()).exists());
+
+        let conn = Connection::open(&db_path).unwrap();
+        let body: String = conn
+            .query_row("SELECT body FROM notes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(body, "from backup");
+    }
+
+    #[test]
+    fn test_unreadable_database_without_backup_is_rebuilt() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("lime.db");
+        create_db(&db_path, &["live"]);
+        corrupt_file(&db_path);
+
+        let report = ensure_database_integrity(&db_path, &[]);
+        assert!(report.corrupted);
+        assert_eq!(report.action, RecoveryAction::Rebuilt);
+        assert!(report.message.is_some());
+        assert!(!db_path.exists());
+    }
+}
@@ -1,11 +1,14 @@
 pub mod agent_runtime_queue_repository;
 pub mod agent_session_repository;
+pub mod credential_cipher;
 pub mod dao;
+pub mod integrity;
 pub mod migration;
 mod migration_support;
 pub mod migration_v2;
 pub mod migration_v3;
 pub mod migration_v4;
+pub mod pagination;
 pub mod schema;
 mod startup_migrations;
 pub mod system_providers;
@@ -51,6 +54,21 @@ pub fn get_db_path() -> Result<PathBuf, String> {
 /// 初始化数据库连接
 pub fn init_database() -> Result<DbConnection, String> {
     let db_path = get_db_path()?;
+
+    // 打开前检查完整性，损坏时自动从备份恢复或抢救数据
+    let recovery = integrity::ensure_database_integrity(
+        &db_path,
+        &integrity::default_backup_dirs(&db_path),
+    );
+    if recovery.corrupted {
+        tracing::warn!(
+            "[数据库] 已处理损坏的数据库: {:?} - {}",
+            recovery.action,
+            recovery.message.as_deref().unwrap_or_default()
+        );
+    }
+    integrity::set_recovery_report(recovery);
+
     let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
 
     // 设置 busy_timeout 为 5 秒，避免 "database is locked" 错误
@@ -0,0 +1,249 @@
+//! 列表查询的游标分页
+//!
+//! 采用 keyset 分页：列表按固定的排序列（最后一列为唯一 ID）排序，游标保存上一页
+//! 最后一条的排序键，下一页从该位置之后继续读取。翻页期间新增或删除数据不会导致
+//! 重复或遗漏，深翻页也无需扫描被跳过的行。
+//!
+//! 游标对调用方不透明（十六进制编码的 JSON 数组），单页条数限制在
+//! [`MAX_PAGE_LIMIT`] 以内。
+
+use rusqlite::types::Value as SqlValue;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// 未指定 limit 时的单页条数
+pub const DEFAULT_PAGE_LIMIT: usize = 50;
+/// 单页条数上限
+pub const MAX_PAGE_LIMIT: usize = 500;
+
+/// 分页游标：上一页最后一条的排序键
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageCursor(Vec<SqlValue>);
+
+impl PageCursor {
+    pub fn new(values: Vec<SqlValue>) -> Self {
+        Self(values)
+    }
+
+    pub fn values(&self) -> &[SqlValue] {
+        &self.0
+    }
+
+    fn encode(&self) -> String {
+        let values: Vec<Value> = self
+            .0
+            .iter()
+            .map(|v| match v {
+                SqlValue::Integer(i) => Value::from(*i),
+                SqlValue::Text(s) => Value::from(s.as_str()),
+                SqlValue::Real(f) => Value::from(*f),
+                SqlValue::Null | SqlValue::Blob(_) => Value::Null,
+            })
+            .collect();
+        let json = Value::Array(values).to_string();
+        json.bytes().map(|b| format!("{b:02x}")).collect()
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        if cursor.len() % 2 != 0 {
+            return None;
+        }
+        let bytes = (0..cursor.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(cursor.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let values: Vec<Value> = serde_json::from_slice(&bytes).ok()?;
+        values
+            .into_iter()
+            .map(|v| match v {
+                Value::String(s) => Some(SqlValue::Text(s)),
+                Value::Number(n) => n
+                    .as_i64()
+                    .map(SqlValue::Integer)
+                    .or_else(|| n.as_f64().map(SqlValue::Real)),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(Self)
+    }
+}
+
+impl Serialize for PageCursor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.encode())
+    }
+}
+
+impl<'de> Deserialize<'de> for PageCursor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Self::decode(&raw).ok_or_else(|| de::Error::custom("无效的分页游标"))
+    }
+}
+
+/// 分页请求
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PageRequest {
+    /// 上一页返回的 `next_cursor`，为空时从第一页开始
+    #[serde(default)]
+    pub cursor: Option<PageCursor>,
+    /// 单页条数（默认 [`DEFAULT_PAGE_LIMIT`]，最大 [`MAX_PAGE_LIMIT`]）
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl PageRequest {
+    /// 生效的单页条数
+    pub fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT)
+    }
+
+    /// 生成 keyset 过滤条件与绑定值
+    ///
+    /// `columns` 为排序列（需与 ORDER BY 一致，最后一列唯一），`descending` 表示降序。
+    /// 没有游标时返回 None；游标列数不匹配时返回错误。
+    pub fn keyset_filter(
+        &self,
+        columns: &[&str],
+        descending: bool,
+    ) -> Result<Option<(String, Vec<SqlValue>)>, rusqlite::Error> {
+        let Some(cursor) = &self.cursor else {
+            return Ok(None);
+        };
+        if cursor.values().len() != columns.len() {
+            return Err(rusqlite::Error::InvalidParameterCount(
+                cursor.values().len(),
+                columns.len(),
+            ));
+        }
+
+        let op = if descending { "<" } else { ">" };
+        let mut clauses = Vec::with_capacity(columns.len());
+        let mut values = Vec::new();
+        for (i, column) in columns.iter().enumerate() {
+            let mut parts: Vec<String> = columns[..i].iter().map(|c| format!("{c} = ?")).collect();
+            values.extend(cursor.values()[..i].iter().cloned());
+            parts.push(format!("{column} {op} ?"));
+            values.push(cursor.values()[i].clone());
+            clauses.push(format!("({})", parts.join(" AND ")));
+        }
+        Ok(Some((format!("({})", clauses.join(" OR ")), values)))
+    }
+}
+
+/// 一页结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// 下一页游标，没有更多数据时为空
+    pub next_cursor: Option<PageCursor>,
+}
+
+impl<T> Page<T> {
+    /// 由多读取一条（`limit + 1`）的查询结果构建分页，`cursor_of` 返回条目的排序键
+    pub fn from_rows(
+        mut rows: Vec<T>,
+        limit: usize,
+        cursor_of: impl Fn(&T) -> Vec<SqlValue>,
+    ) -> Self {
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+        let next_cursor = has_more
+            .then(|| rows.last().map(|row| PageCursor::new(cursor_of(row))))
+            .flatten();
+        Self {
+            items: rows,
+            next_cursor,
+        }
+    }
+
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::{params_from_iter, Connection};
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = PageCursor::new(vec![
+            SqlValue::Text("2026-10-17T00:00:00Z".to_string()),
+            SqlValue::Integer(42),
+        ]);
+        let json = serde_json::to_string(&cursor).unwrap();
+        assert_eq!(serde_json::from_str::<PageCursor>(&json).unwrap(), cursor);
+        assert!(serde_json::from_str::<PageCursor>("\"zz\"").is_err());
+    }
+
+    #[test]
+    fn test_limit_is_capped() {
+        assert_eq!(PageRequest::default().limit(), DEFAULT_PAGE_LIMIT);
+        let request = PageRequest {
+            cursor: None,
+            limit: Some(100_000),
+        };
+        assert_eq!(request.limit(), MAX_PAGE_LIMIT);
+    }
+
+    #[test]
+    fn test_keyset_pagination_is_stable() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE t (id INTEGER PRIMARY KEY, ts INTEGER NOT NULL);
+             INSERT INTO t (id, ts) VALUES (1, 10), (2, 20), (3, 20), (4, 30), (5, 20);",
+        )
+        .unwrap();
+
+        let fetch = |request: &PageRequest| -> Page<(i64, i64)> {
+            let mut sql = "SELECT id, ts FROM t".to_string();
+            let mut values = Vec::new();
+            if let Some((filter, bound)) = request.keyset_filter(&["ts", "id"], true).unwrap() {
+                sql.push_str(" WHERE ");
+                sql.push_str(&filter);
+                values = bound;
+            }
+            sql.push_str(" ORDER BY ts DESC, id DESC LIMIT ?");
+            values.push(SqlValue::Integer(request.limit() as i64 + 1));
+            let mut stmt = conn.prepare(&sql).unwrap();
+            let rows = stmt
+                .query_map(params_from_iter(values), |row| {
+                    Ok((row.get(0)?, row.get(1)?))
+                })
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            Page::from_rows(rows, request.limit(), |(id, ts)| {
+                vec![SqlValue::Integer(*ts), SqlValue::Integer(*id)]
+            })
+        };
+
+        let mut request = PageRequest {
+            cursor: None,
+            limit: Some(2),
+        };
+        let mut ids = Vec::new();
+        loop {
+            let page = fetch(&request);
+            ids.extend(page.items.iter().map(|(id, _)| *id));
+            match page.next_cursor {
+                Some(cursor) => request.cursor = Some(cursor),
+                None => break,
+            }
+        }
+        assert_eq!(ids, vec![4, 5, 3, 2, 1]);
+
+        let mismatched = PageRequest {
+            cursor: Some(PageCursor::new(vec![SqlValue::Integer(1)])),
+            limit: None,
+        };
+        assert!(mismatched.keyset_filter(&["ts", "id"], true).is_err());
+    }
+}
@@ -256,6 +256,30 @@ pub fn create_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
         [],
     );
 
+    // Migration: 添加原生联网搜索开关（默认关闭，避免额外计费）
+    let _ = conn.execute(
+        "ALTER TABLE provider_pool_credentials ADD COLUMN native_web_search INTEGER DEFAULT 0",
+        [],
+    );
+
+    // Migration: 添加备用 base_url 列表（JSON 数组）
+    let _ = conn.execute(
+        "ALTER TABLE provider_pool_credentials ADD COLUMN fallback_base_urls TEXT",
+        [],
+    );
+
+    // Migration: 凭证数据加密状态（0 = 明文，1 = 已加密），旧数据在首次读取时加密
+    let _ = conn.execute(
+        "ALTER TABLE provider_pool_credentials ADD COLUMN credential_encrypted INTEGER DEFAULT 0",
+        [],
+    );
+
+    // Migration: 凭证负载均衡权重
+    let _ = conn.execute(
+        "ALTER TABLE provider_pool_credentials ADD COLUMN weight INTEGER DEFAULT 1",
+        [],
+    );
+
     // 已安装插件表
     // _需求: 1.2, 1.3_
     conn.execute(
@@ -280,6 +304,18 @@ pub fn create_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
         [],
     )?;
 
+    // 插件键值存储表（按插件 ID 隔离）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS plugin_storage (
+            plugin_id TEXT NOT NULL,
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (plugin_id, key)
+        )",
+        [],
+    )?;
+
     // ============================================================================
     // Orchestrator 相关表
     // ============================================================================
@@ -566,6 +602,7 @@ pub fn create_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
             tool_calls_json TEXT,
             tool_call_id TEXT,
             reasoning_content TEXT,
+            cost_json TEXT,
             FOREIGN KEY (session_id) REFERENCES agent_sessions(id) ON DELETE CASCADE
         )",
         [],
@@ -575,6 +612,8 @@ pub fn create_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
         "ALTER TABLE agent_messages ADD COLUMN reasoning_content TEXT",
         [],
     );
+    // 消息级 token 用量与费用（assistant 消息持久化时写入）
+    let _ = conn.execute("ALTER TABLE agent_messages ADD COLUMN cost_json TEXT", []);
 
     // 创建 agent_messages 索引
     conn.execute(
@@ -1447,6 +1486,218 @@ pub fn create_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
         [],
     )?;
 
+    // 月度费用累计表（费用上限使用）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS monthly_cost_usage (
+            month TEXT NOT NULL,
+            provider TEXT NOT NULL,
+            cost_usd REAL NOT NULL DEFAULT 0,
+            updated_at TEXT NOT NULL,
+            PRIMARY KEY (month, provider)
+        )",
+        [],
+    )?;
+
+    // 请求费用明细表（按请求记录 Token 与费用）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS request_costs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            request_id TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            day TEXT NOT NULL,
+            month TEXT NOT NULL,
+            provider TEXT NOT NULL,
+            credential_id TEXT,
+            model TEXT NOT NULL,
+            session_id TEXT,
+            tenant_id TEXT,
+            input_tokens INTEGER NOT NULL DEFAULT 0,
+            output_tokens INTEGER NOT NULL DEFAULT 0,
+            cost_usd REAL NOT NULL DEFAULT 0
+        )",
+        [],
+    )?;
+    let _ = conn.execute("ALTER TABLE request_costs ADD COLUMN tenant_id TEXT", []);
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_request_costs_day ON request_costs(day)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_request_costs_month ON request_costs(month)",
+        [],
+    )?;
+
+    // 虚拟模型表（用户自定义模型别名）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS virtual_models (
+            name TEXT PRIMARY KEY,
+            provider TEXT,
+            model TEXT NOT NULL,
+            temperature REAL,
+            system_prefix TEXT,
+            description TEXT,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // 请求审计日志表（脱敏后的请求元数据，FTS5 全文索引）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS request_audit_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            request_id TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            credential_id TEXT,
+            is_stream INTEGER NOT NULL DEFAULT 0,
+            status TEXT NOT NULL,
+            latency_ms INTEGER NOT NULL DEFAULT 0,
+            retry_count INTEGER NOT NULL DEFAULT 0,
+            input_tokens INTEGER,
+            output_tokens INTEGER,
+            error_message TEXT,
+            request_body TEXT,
+            conversion_warnings TEXT
+        )",
+        [],
+    )?;
+    let _ = conn.execute(
+        "ALTER TABLE request_audit_log ADD COLUMN conversion_warnings TEXT",
+        [],
+    );
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_request_audit_log_created_at ON request_audit_log(created_at)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_request_audit_log_request_id ON request_audit_log(request_id)",
+        [],
+    )?;
+    conn.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS request_audit_fts USING fts5(
+            provider, model, credential_id, error_message, request_body,
+            content='request_audit_log', content_rowid='id'
+        )",
+        [],
+    )?;
+    conn.execute_batch(
+        "CREATE TRIGGER IF NOT EXISTS request_audit_log_ai AFTER INSERT ON request_audit_log BEGIN
+            INSERT INTO request_audit_fts(rowid, provider, model, credential_id, error_message, request_body)
+            VALUES (new.id, new.provider, new.model, new.credential_id, new.error_message, new.request_body);
+         END;
+         CREATE TRIGGER IF NOT EXISTS request_audit_log_ad AFTER DELETE ON request_audit_log BEGIN
+            INSERT INTO request_audit_fts(request_audit_fts, rowid, provider, model, credential_id, error_message, request_body)
+            VALUES ('delete', old.id, old.provider, old.model, old.credential_id, old.error_message, old.request_body);
+         END;",
+    )?;
+
+    // 按 API Key 的限流覆盖（以 Key 指纹为主键，不保存明文 Key）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS api_key_rate_limits (
+            key_fingerprint TEXT PRIMARY KEY,
+            label TEXT,
+            requests_per_minute INTEGER NOT NULL,
+            burst INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
+    // Prompt 库（跨对话、Agent 与 Skill 共享，按 ID 引用）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS prompt_library (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            folder TEXT,
+            tags_json TEXT NOT NULL DEFAULT '[]',
+            description TEXT,
+            content TEXT NOT NULL,
+            variables_json TEXT NOT NULL DEFAULT '[]',
+            version INTEGER NOT NULL DEFAULT 1,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS prompt_library_versions (
+            prompt_id TEXT NOT NULL,
+            version INTEGER NOT NULL,
+            content TEXT NOT NULL,
+            variables_json TEXT NOT NULL DEFAULT '[]',
+            note TEXT,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (prompt_id, version)
+        )",
+        [],
+    )?;
+
+    // 对话最后一轮回复的多次生成（重新生成时保留的历史版本）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS agent_turn_attempts (
+            id TEXT PRIMARY KEY,
+            session_id TEXT NOT NULL,
+            anchor_message_id INTEGER NOT NULL,
+            attempt INTEGER NOT NULL,
+            active INTEGER NOT NULL DEFAULT 0,
+            base_content_json TEXT NOT NULL,
+            messages_json TEXT,
+            overrides_json TEXT NOT NULL DEFAULT '{}',
+            created_at INTEGER NOT NULL,
+            UNIQUE (session_id, anchor_message_id, attempt),
+            FOREIGN KEY (session_id) REFERENCES agent_sessions(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // 凭证两阶段删除：宽限期内的待粉碎凭证与粉碎回执
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS credential_deletions (
+            uuid TEXT PRIMARY KEY,
+            provider_type TEXT NOT NULL,
+            name TEXT,
+            was_disabled INTEGER NOT NULL DEFAULT 0,
+            requested_at INTEGER NOT NULL,
+            purge_after INTEGER NOT NULL,
+            shredded_at INTEGER,
+            receipt TEXT
+        )",
+        [],
+    )?;
+
+    // 自适应凭证选择：按模型统计的凭证成功率与延迟（已衰减的加权计数）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS adaptive_selection_stats (
+            model TEXT NOT NULL,
+            credential_id TEXT NOT NULL,
+            successes REAL NOT NULL DEFAULT 0,
+            failures REAL NOT NULL DEFAULT 0,
+            latency_ms REAL NOT NULL DEFAULT 0,
+            samples INTEGER NOT NULL DEFAULT 0,
+            updated_at INTEGER NOT NULL,
+            PRIMARY KEY (model, credential_id)
+        )",
+        [],
+    )?;
+
+    // 风控自适应冷却：按凭证学习的限流节奏（Retry-After、429 间隔与间隔内请求数）
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS rate_limit_profiles (
+            credential_id TEXT PRIMARY KEY,
+            rate_limits INTEGER NOT NULL DEFAULT 0,
+            retry_after_secs REAL,
+            retry_after_samples INTEGER NOT NULL DEFAULT 0,
+            interval_secs REAL,
+            requests_per_interval REAL,
+            interval_samples INTEGER NOT NULL DEFAULT 0,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    )?;
+
     Ok(())
 }
 
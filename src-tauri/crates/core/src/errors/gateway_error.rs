@@ -13,6 +13,8 @@ pub enum GatewayErrorCode {
     RequestConflict,
     RateLimited,
     NoCredentials,
+    CostCapExceeded,
+    DeadlineExceeded,
     UpstreamTimeout,
     UpstreamUnavailable,
     UpstreamError,
@@ -62,6 +64,8 @@ impl GatewayErrorCode {
             Self::RequestConflict => "请求冲突",
             Self::RateLimited => "请求过于频繁，请稍后重试",
             Self::NoCredentials => "当前没有可用凭证",
+            Self::CostCapExceeded => "已超出月度费用上限",
+            Self::DeadlineExceeded => "请求已超过截止时间",
             Self::UpstreamTimeout => "上游请求超时",
             Self::UpstreamUnavailable => "上游服务暂不可用",
             Self::UpstreamError => "上游服务返回错误",
@@ -170,6 +174,7 @@ mod tests {
         assert!(GatewayErrorCode::RateLimited.retryable());
         assert!(GatewayErrorCode::UpstreamTimeout.retryable());
         assert!(!GatewayErrorCode::AuthenticationFailed.retryable());
+        assert!(!GatewayErrorCode::CostCapExceeded.retryable());
     }
 
     #[test]
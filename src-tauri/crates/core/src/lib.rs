@@ -4,6 +4,7 @@
 //!
 //! ## 模块结构
 //! - `app_utils`: 应用通用工具函数
+//! - `safe_mode`: 连续启动失败检测与安全模式
 //! - `models`: 核心数据模型定义
 //! - `data`: 静态数据
 //! - `logger`: 日志配置
@@ -13,9 +14,11 @@
 //! - `connect`: Deep Link 协议和中转商注册表
 //! - `middleware`: HTTP 中间件（认证、限速）
 //! - `orchestrator`: 模型选择编排器
+//! - `response_stream`: 统一响应流抽象（Provider 产出、服务端与命令层消费）
 //! - `plugin`: 插件系统（加载、管理、UI、安装）
 //! - `session`: 会话管理（限速、粘性路由）
 //! - `session_files`: 会话文件存储
+//! - `cpu_pool`: CPU 密集型任务线程池（优先级队列与利用率统计）
 
 pub mod app_bootstrap;
 pub mod app_paths;
@@ -24,6 +27,7 @@ pub mod data;
 pub mod env_compat;
 pub mod logger;
 pub mod models;
+pub mod safe_mode;
 pub mod tray_format;
 pub mod tray_menu_meta;
 pub mod tray_state;
@@ -32,10 +36,12 @@ pub mod tray_state;
 pub mod backends;
 pub mod config;
 pub mod connect;
+pub mod cpu_pool;
 pub mod errors;
 pub mod middleware;
 pub mod orchestrator;
 pub mod plugin;
+pub mod response_stream;
 pub mod session;
 pub mod session_files;
 pub mod tool_calling;
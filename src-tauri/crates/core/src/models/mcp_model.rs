@@ -2,10 +2,65 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+/// MCP 服务器传输方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpTransportKind {
+    /// 本地子进程，通过 stdin/stdout 通信
+    #[default]
+    Stdio,
+    /// 远程 Streamable HTTP（MCP 2025-03-26）
+    StreamableHttp,
+    /// 远程 HTTP + SSE（MCP 2024-11-05，旧版）
+    Sse,
+}
+
+impl McpTransportKind {
+    /// 是否为远程传输
+    pub fn is_remote(self) -> bool {
+        !matches!(self, McpTransportKind::Stdio)
+    }
+}
+
+/// 远程 MCP 服务器断线重连策略
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct McpReconnectConfig {
+    /// 连接失败或断开后的最大重试次数（0 表示不重连）
+    pub max_attempts: u32,
+    /// 首次重试延迟（毫秒）
+    pub initial_delay_ms: u64,
+    /// 最大重试延迟（毫秒）
+    pub max_delay_ms: u64,
+}
+
+impl Default for McpReconnectConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_delay_ms: 500,
+            max_delay_ms: 10_000,
+        }
+    }
+}
+
+impl McpReconnectConfig {
+    /// 第 `attempt` 次重试（从 1 开始）前的等待时间，指数退避
+    pub fn delay_for_attempt(&self, attempt: u32) -> std::time::Duration {
+        let factor = 1u64 << attempt.saturating_sub(1).min(16);
+        let delay = self.initial_delay_ms.saturating_mul(factor);
+        std::time::Duration::from_millis(delay.min(self.max_delay_ms))
+    }
+}
+
 /// MCP 服务器配置（类型化）
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServerConfigTyped {
-    /// 启动命令
+    /// 传输方式
+    #[serde(default)]
+    pub transport: McpTransportKind,
+    /// 启动命令（远程传输时为空）
+    #[serde(default)]
     pub command: String,
     /// 命令参数
     #[serde(default)]
@@ -19,6 +74,18 @@ pub struct McpServerConfigTyped {
     /// 超时时间（秒）
     #[serde(default = "default_timeout")]
     pub timeout: u64,
+    /// 解释器路径（node / python 可执行文件）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interpreter: Option<String>,
+    /// 远程服务器地址（Streamable HTTP / SSE）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// 远程请求附加的 HTTP 头（如 Authorization），值支持 `${VAR}` 引用环境变量
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// 远程服务器断线重连策略
+    #[serde(default)]
+    pub reconnect: McpReconnectConfig,
 }
 
 fn default_timeout() -> u64 {
@@ -28,11 +95,16 @@ fn default_timeout() -> u64 {
 impl Default for McpServerConfigTyped {
     fn default() -> Self {
         Self {
+            transport: McpTransportKind::Stdio,
             command: String::new(),
             args: Vec::new(),
             env: HashMap::new(),
             cwd: None,
             timeout: 30,
+            interpreter: None,
+            url: None,
+            headers: HashMap::new(),
+            reconnect: McpReconnectConfig::default(),
         }
     }
 }
@@ -87,6 +159,11 @@ impl McpServer {
         serde_json::from_value(self.server_config.clone()).unwrap_or_else(|_| {
             // 尝试手动提取字段
             McpServerConfigTyped {
+                transport: self
+                    .server_config
+                    .get("transport")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
                 command: self
                     .server_config
                     .get("command")
@@ -123,6 +200,31 @@ impl McpServer {
                     .get("timeout")
                     .and_then(|v| v.as_u64())
                     .unwrap_or(30),
+                interpreter: self
+                    .server_config
+                    .get("interpreter")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                url: self
+                    .server_config
+                    .get("url")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                headers: self
+                    .server_config
+                    .get("headers")
+                    .and_then(|v| v.as_object())
+                    .map(|obj| {
+                        obj.iter()
+                            .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                reconnect: self
+                    .server_config
+                    .get("reconnect")
+                    .and_then(|v| serde_json::from_value(v.clone()).ok())
+                    .unwrap_or_default(),
             }
         })
     }
@@ -135,8 +237,29 @@ impl McpServer {
         let mut errors = Vec::new();
         let config = self.parse_config();
 
-        // 验证 command 不为空
-        if config.command.trim().is_empty() {
+        if config.transport.is_remote() {
+            // 远程传输需要 http(s) 地址
+            let url = config.url.as_deref().map(str::trim).unwrap_or_default();
+            if url.is_empty() {
+                errors.push(ConfigValidationError {
+                    field: "url".to_string(),
+                    message: "远程服务器地址不能为空".to_string(),
+                });
+            } else if !(url.starts_with("http://") || url.starts_with("https://")) {
+                errors.push(ConfigValidationError {
+                    field: "url".to_string(),
+                    message: "远程服务器地址必须以 http:// 或 https:// 开头".to_string(),
+                });
+            }
+
+            if config.headers.keys().any(|name| name.trim().is_empty()) {
+                errors.push(ConfigValidationError {
+                    field: "headers".to_string(),
+                    message: "请求头名称不能为空".to_string(),
+                });
+            }
+        } else if config.command.trim().is_empty() {
+            // 验证 command 不为空
             errors.push(ConfigValidationError {
                 field: "command".to_string(),
                 message: "启动命令不能为空".to_string(),
@@ -171,6 +294,18 @@ impl McpServer {
             });
         }
 
+        // 验证 interpreter 不为空白
+        if config
+            .interpreter
+            .as_deref()
+            .is_some_and(|interpreter| interpreter.trim().is_empty())
+        {
+            errors.push(ConfigValidationError {
+                field: "interpreter".to_string(),
+                message: "解释器路径不能为空白".to_string(),
+            });
+        }
+
         errors
     }
 
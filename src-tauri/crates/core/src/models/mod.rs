@@ -12,6 +12,7 @@ pub mod machine_id;
 pub mod mcp_model;
 pub mod model_registry;
 pub mod openai;
+pub mod openai_responses;
 pub mod project_model;
 pub mod prompt_model;
 pub mod provider_model;
@@ -20,6 +21,7 @@ pub mod provider_type;
 pub mod route_model;
 pub mod skill_model;
 pub mod vertex_model;
+pub mod virtual_model;
 
 #[allow(unused_imports)]
 pub use anthropic::*;
@@ -28,7 +30,7 @@ pub use client_type::{select_provider, ClientType};
 #[allow(unused_imports)]
 pub use codewhisperer::*;
 pub use injection_types::{InjectionMode, InjectionRule};
-pub use mcp_model::McpServer;
+pub use mcp_model::{McpReconnectConfig, McpServer, McpTransportKind};
 #[allow(unused_imports)]
 pub use openai::*;
 pub use project_model::Persona;
@@ -48,3 +50,4 @@ pub use skill_model::{
     VIDEO_GENERATE_SKILL_DIRECTORY,
 };
 pub use vertex_model::{VertexApiKeyEntry, VertexModelAlias};
+pub use virtual_model::VirtualModel;
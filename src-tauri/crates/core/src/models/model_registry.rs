@@ -48,6 +48,56 @@ impl Default for ModelPricing {
     }
 }
 
+impl ModelPricing {
+    /// 按输入/输出 token 数计算费用
+    pub fn estimate_cost(&self, input_tokens: u32, output_tokens: u32) -> f64 {
+        let input = self.input_per_million.unwrap_or(0.0) * input_tokens as f64;
+        let output = self.output_per_million.unwrap_or(0.0) * output_tokens as f64;
+        (input + output) / 1_000_000.0
+    }
+}
+
+/// 单条消息的 token 用量与费用
+///
+/// 在消息持久化时按当时的模型定价计算并保存，读取时无需重新计算。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageCost {
+    /// 产生该消息的模型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 输入 token 数
+    pub input_tokens: u32,
+    /// 输出 token 数
+    pub output_tokens: u32,
+    /// 总 token 数
+    pub total_tokens: u32,
+    /// 费用（模型无定价时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+    /// 货币单位
+    pub currency: String,
+}
+
+impl MessageCost {
+    pub fn new(
+        model: Option<String>,
+        pricing: Option<&ModelPricing>,
+        input_tokens: u32,
+        output_tokens: u32,
+    ) -> Self {
+        Self {
+            model,
+            input_tokens,
+            output_tokens,
+            total_tokens: input_tokens.saturating_add(output_tokens),
+            cost: pricing.map(|p| p.estimate_cost(input_tokens, output_tokens)),
+            currency: pricing
+                .map(|p| p.currency.clone())
+                .unwrap_or_else(|| "USD".to_string()),
+        }
+    }
+}
+
 /// 模型限制
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ModelLimits {
@@ -635,6 +685,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_message_cost_from_pricing() {
+        let pricing = ModelPricing {
+            input_per_million: Some(3.0),
+            output_per_million: Some(15.0),
+            ..ModelPricing::default()
+        };
+        let cost = MessageCost::new(
+            Some("claude-sonnet-4-5".to_string()),
+            Some(&pricing),
+            1000,
+            200,
+        );
+        assert_eq!(cost.total_tokens, 1200);
+        assert!((cost.cost.unwrap() - 0.006).abs() < 1e-12);
+        assert_eq!(cost.currency, "USD");
+
+        let unpriced = MessageCost::new(None, None, 10, 5);
+        assert_eq!(unpriced.cost, None);
+        assert!(!serde_json::to_string(&unpriced)
+            .unwrap()
+            .contains("\"cost\""));
+    }
+
     #[test]
     fn test_model_status_parsing() {
         assert_eq!(
@@ -0,0 +1,147 @@
+//! OpenAI Responses API 数据模型
+//!
+//! Codex 等较新的 OpenAI 客户端使用 `/v1/responses` 而非 chat completions。
+//! 请求中的 `input` 条目与工具定义形态较多，保留为 JSON 值由转换器按 `type` 解析；
+//! 响应对象与流式事件中的快照按规范建模。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Responses API 请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponsesRequest {
+    pub model: String,
+    #[serde(default)]
+    pub input: ResponsesInput,
+    /// 系统指令（对应 chat completions 的 system 消息）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u32>,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<ResponsesReasoning>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
+    /// 服务端会话续接（不支持，客户端需在 `input` 中携带完整对话）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_response_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// 请求输入：纯文本或条目列表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResponsesInput {
+    Text(String),
+    Items(Vec<Value>),
+}
+
+impl Default for ResponsesInput {
+    fn default() -> Self {
+        Self::Items(Vec::new())
+    }
+}
+
+/// 推理配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResponsesReasoning {
+    /// 推理强度（minimal / low / medium / high）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effort: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+/// Responses API 响应对象
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponsesResponse {
+    pub id: String,
+    /// 固定为 `response`
+    pub object: String,
+    /// 创建时间（Unix 秒）
+    pub created_at: i64,
+    /// in_progress / completed / incomplete / failed
+    pub status: String,
+    pub model: String,
+    pub output: Vec<ResponseOutputItem>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<ResponsesUsage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incomplete_details: Option<ResponsesIncompleteDetails>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ResponsesError>,
+}
+
+/// 输出条目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseOutputItem {
+    Message {
+        id: String,
+        role: String,
+        status: String,
+        content: Vec<ResponseOutputContent>,
+    },
+    FunctionCall {
+        id: String,
+        call_id: String,
+        name: String,
+        arguments: String,
+        status: String,
+    },
+    Reasoning {
+        id: String,
+        summary: Vec<ReasoningSummaryPart>,
+    },
+}
+
+/// 消息内容片段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseOutputContent {
+    OutputText {
+        text: String,
+        #[serde(default)]
+        annotations: Vec<Value>,
+    },
+}
+
+/// 推理摘要片段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReasoningSummaryPart {
+    SummaryText { text: String },
+}
+
+/// Token 用量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponsesUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// 未完成原因
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponsesIncompleteDetails {
+    /// max_output_tokens / content_filter
+    pub reason: String,
+}
+
+/// 失败信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponsesError {
+    pub code: String,
+    pub message: String,
+}
@@ -87,6 +87,52 @@ pub enum CredentialData {
         api_key: String,
         base_url: Option<String>,
     },
+
+    /// 测试模式 Mock 凭证（返回确定性响应，用于端到端测试）
+    Mock {
+        #[serde(flatten)]
+        config: MockProviderConfig,
+    },
+}
+
+/// Mock Provider 注入的故障类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MockFault {
+    /// 返回 429 Too Many Requests
+    RateLimited,
+    /// 返回 500 Internal Server Error
+    ServerError,
+    /// 返回 200 但响应体不是合法 JSON
+    MalformedJson,
+}
+
+/// Mock Provider 配置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct MockProviderConfig {
+    /// 固定响应文本；为空时回显最后一条用户消息
+    #[serde(default)]
+    pub response_text: Option<String>,
+    /// 模拟延迟（毫秒）
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// 注入的故障类型
+    #[serde(default)]
+    pub fault: Option<MockFault>,
+    /// 每 N 次请求注入一次故障（1 表示每次都失败，0 表示不注入）
+    #[serde(default)]
+    pub fault_every: u32,
+}
+
+impl MockProviderConfig {
+    /// 第 `call_index` 次请求（从 1 开始）是否注入故障
+    pub fn fault_for_call(&self, call_index: u64) -> Option<MockFault> {
+        let fault = self.fault?;
+        if self.fault_every == 0 || call_index % self.fault_every as u64 != 0 {
+            return None;
+        }
+        Some(fault)
+    }
 }
 
 impl CredentialData {
@@ -131,6 +177,12 @@ impl CredentialData {
             CredentialData::AnthropicKey { api_key, .. } => {
                 format!("Anthropic: {}", mask_key(api_key))
             }
+            CredentialData::Mock { config } => match config.fault {
+                Some(fault) if config.fault_every > 0 => {
+                    format!("Mock: {:?} every {}", fault, config.fault_every)
+                }
+                _ => "Mock".to_string(),
+            },
         }
     }
 
@@ -149,8 +201,97 @@ impl CredentialData {
             CredentialData::ClaudeOAuth { .. } => PoolProviderType::ClaudeOAuth,
 
             CredentialData::AnthropicKey { .. } => PoolProviderType::Anthropic,
+            CredentialData::Mock { .. } => PoolProviderType::Mock,
         }
     }
+
+    /// 是否支持 Provider 原生联网搜索工具
+    ///
+    /// Anthropic（`web_search_20250305`）与 Gemini（`googleSearch`，经 Antigravity）。
+    pub fn supports_native_web_search(&self) -> bool {
+        matches!(
+            self,
+            CredentialData::ClaudeKey { .. }
+                | CredentialData::AnthropicKey { .. }
+                | CredentialData::AntigravityOAuth { .. }
+        )
+    }
+
+    /// 是否支持工具结果中的图片
+    ///
+    /// Claude 以 `tool_result` 图片块、Gemini（经 Antigravity）以 `inlineData` 透传；
+    /// 其他凭证的工具结果图片会被替换为文本占位。
+    pub fn supports_tool_result_images(&self) -> bool {
+        matches!(
+            self,
+            CredentialData::ClaudeKey { .. }
+                | CredentialData::AnthropicKey { .. }
+                | CredentialData::AntigravityOAuth { .. }
+        )
+    }
+
+    /// 自定义 base_url（仅 API Key 类凭证）
+    pub fn base_url(&self) -> Option<&str> {
+        match self {
+            CredentialData::OpenAIKey { base_url, .. }
+            | CredentialData::ClaudeKey { base_url, .. }
+            | CredentialData::VertexKey { base_url, .. }
+            | CredentialData::GeminiApiKey { base_url, .. }
+            | CredentialData::AnthropicKey { base_url, .. } => base_url.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// API Key（仅 API Key 类凭证）
+    pub fn api_key(&self) -> Option<&str> {
+        match self {
+            CredentialData::OpenAIKey { api_key, .. }
+            | CredentialData::ClaudeKey { api_key, .. }
+            | CredentialData::VertexKey { api_key, .. }
+            | CredentialData::GeminiApiKey { api_key, .. }
+            | CredentialData::AnthropicKey { api_key, .. } => Some(api_key),
+            _ => None,
+        }
+    }
+
+    /// 替换 base_url，凭证类型不支持自定义 base_url 时返回 false
+    pub fn set_base_url(&mut self, url: String) -> bool {
+        match self {
+            CredentialData::OpenAIKey { base_url, .. }
+            | CredentialData::ClaudeKey { base_url, .. }
+            | CredentialData::VertexKey { base_url, .. }
+            | CredentialData::GeminiApiKey { base_url, .. }
+            | CredentialData::AnthropicKey { base_url, .. } => {
+                *base_url = Some(url);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// 实际请求的 base_url：自定义 base_url，未配置时为 Provider 官方地址
+    ///
+    /// AnthropicKey 是否配置 base_url 决定其调用方式，因此没有默认值。
+    pub fn effective_base_url(&self) -> Option<&str> {
+        self.base_url().or(match self {
+            CredentialData::OpenAIKey { .. } => Some("https://api.openai.com"),
+            CredentialData::ClaudeKey { .. } => Some("https://api.anthropic.com"),
+            CredentialData::VertexKey { .. } => {
+                Some("https://generativelanguage.googleapis.com/v1beta")
+            }
+            CredentialData::GeminiApiKey { .. } => {
+                Some("https://generativelanguage.googleapis.com")
+            }
+            _ => None,
+        })
+    }
+
+    /// 是否支持 `logprobs` / `top_logprobs` / `echo` 透传
+    ///
+    /// 仅 OpenAI 兼容的 Chat Completions 上游；其他凭证会剔除这些字段。
+    pub fn supports_logprobs(&self) -> bool {
+        matches!(self, CredentialData::OpenAIKey { .. })
+    }
 }
 
 /// 通配符模式匹配
@@ -232,12 +373,28 @@ pub struct ProviderCredential {
     pub source: CredentialSource,
     /// 代理 URL（可覆盖全局代理设置）
     pub proxy_url: Option<String>,
+    /// 是否允许使用 Provider 原生联网搜索工具（按搜索次数额外计费，默认关闭）
+    #[serde(default)]
+    pub native_web_search: bool,
+    /// 备用 base_url 列表（主 base_url 不可达时按顺序切换，仅适用于自定义 base_url 的 API Key 凭证）
+    #[serde(default)]
+    pub fallback_base_urls: Vec<String>,
+    /// 负载均衡权重（如付费账号、高限额档位可调高），选择概率与权重成正比
+    #[serde(default = "default_credential_weight")]
+    pub weight: u32,
 }
 
+/// 凭证默认权重
+pub const DEFAULT_CREDENTIAL_WEIGHT: u32 = 1;
+
 fn default_true() -> bool {
     true
 }
 
+fn default_credential_weight() -> u32 {
+    DEFAULT_CREDENTIAL_WEIGHT
+}
+
 impl ProviderCredential {
     /// 创建新凭证
     pub fn new(provider_type: PoolProviderType, credential: CredentialData) -> Self {
@@ -265,6 +422,9 @@ impl ProviderCredential {
             cached_token: None,
             source: CredentialSource::Manual,
             proxy_url: None,
+            native_web_search: false,
+            fallback_base_urls: Vec::new(),
+            weight: DEFAULT_CREDENTIAL_WEIGHT,
         }
     }
 
@@ -510,6 +670,7 @@ pub fn get_default_check_model(provider_type: PoolProviderType) -> &'static str
         PoolProviderType::AzureOpenai => "gpt-4o-mini",
         PoolProviderType::AwsBedrock => "claude-sonnet-4-5-20250929",
         PoolProviderType::Ollama => "llama3.2",
+        PoolProviderType::Mock => "mock-model",
     }
 }
 
@@ -546,6 +707,45 @@ pub struct CredentialDisplay {
     pub api_key: Option<String>,
     /// 凭证级代理 URL（可覆盖全局代理设置）
     pub proxy_url: Option<String>,
+    /// 是否支持 Provider 原生联网搜索
+    pub supports_native_web_search: bool,
+    /// 是否已开启原生联网搜索
+    pub native_web_search: bool,
+    /// 备用 base_url 列表
+    #[serde(default)]
+    pub fallback_base_urls: Vec<String>,
+    /// 负载均衡权重
+    #[serde(default = "default_credential_weight")]
+    pub weight: u32,
+    /// 端点健康状态（由服务层填充，未检测过时为 None）
+    #[serde(default)]
+    pub endpoint_health: Option<CredentialEndpointHealth>,
+}
+
+/// 单个 base_url 的可达性状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EndpointStatus {
+    pub url: String,
+    /// 最近一次检测是否可达（与凭证本身是否有效无关）
+    pub reachable: bool,
+    /// 最近一次检测耗时（毫秒）
+    pub latency_ms: Option<u64>,
+    /// 最近一次检测时间（RFC3339 格式）
+    pub last_checked: Option<String>,
+    pub last_error: Option<String>,
+    /// 连续不可达次数
+    pub consecutive_failures: u32,
+}
+
+/// 凭证的端点健康状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialEndpointHealth {
+    /// 当前固定使用的 base_url
+    pub active_base_url: String,
+    /// 是否已切换到备用 base_url
+    pub failed_over: bool,
+    /// 主 base_url 与备用 base_url 的状态（按优先级排列）
+    pub endpoints: Vec<EndpointStatus>,
 }
 
 /// 获取凭证类型字符串
@@ -561,6 +761,7 @@ fn get_credential_type(cred: &CredentialData) -> String {
         CredentialData::CodexOAuth { .. } => "codex_oauth".to_string(),
         CredentialData::ClaudeOAuth { .. } => "claude_oauth".to_string(),
         CredentialData::AnthropicKey { .. } => "anthropic_key".to_string(),
+        CredentialData::Mock { .. } => "mock".to_string(),
     }
 }
 
@@ -582,6 +783,27 @@ pub fn get_oauth_creds_path(cred: &CredentialData) -> Option<String> {
     }
 }
 
+/// 替换 OAuth 凭证的文件路径，非 OAuth 凭证返回 false
+pub fn set_oauth_creds_path(cred: &mut CredentialData, path: String) -> bool {
+    match cred {
+        CredentialData::KiroOAuth { creds_file_path }
+        | CredentialData::GeminiOAuth {
+            creds_file_path, ..
+        }
+        | CredentialData::AntigravityOAuth {
+            creds_file_path, ..
+        }
+        | CredentialData::CodexOAuth {
+            creds_file_path, ..
+        }
+        | CredentialData::ClaudeOAuth { creds_file_path } => {
+            *creds_file_path = path;
+            true
+        }
+        _ => false,
+    }
+}
+
 /// 从 CredentialData 中提取 base_url（仅适用于 API Key 类型）
 fn get_base_url(cred: &CredentialData) -> Option<String> {
     match cred {
@@ -642,6 +864,11 @@ impl From<&ProviderCredential> for CredentialDisplay {
             base_url: get_base_url(&cred.credential),
             api_key: get_api_key(&cred.credential),
             proxy_url: cred.proxy_url.clone(),
+            supports_native_web_search: cred.credential.supports_native_web_search(),
+            native_web_search: cred.native_web_search,
+            fallback_base_urls: cred.fallback_base_urls.clone(),
+            weight: cred.weight,
+            endpoint_health: None,
         }
     }
 }
@@ -701,6 +928,12 @@ pub struct UpdateCredentialRequest {
     pub new_api_key: Option<String>,
     /// 新的代理 URL（可覆盖全局代理设置）
     pub new_proxy_url: Option<String>,
+    /// 是否开启原生联网搜索（会产生额外计费）
+    #[serde(default)]
+    pub native_web_search: Option<bool>,
+    /// 新的备用 base_url 列表（空列表表示清除）
+    #[serde(default)]
+    pub new_fallback_base_urls: Option<Vec<String>>,
 }
 
 pub type ProviderPools = HashMap<PoolProviderType, Vec<ProviderCredential>>;
@@ -709,6 +942,24 @@ pub type ProviderPools = HashMap<PoolProviderType, Vec<ProviderCredential>>;
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_mock_credential_serde_and_fault_schedule() {
+        let json = r#"{"type":"mock","latency_ms":50,"fault":"rate_limited","fault_every":3}"#;
+        let cred: CredentialData = serde_json::from_str(json).unwrap();
+        assert_eq!(cred.provider_type(), PoolProviderType::Mock);
+
+        let CredentialData::Mock { config } = &cred else {
+            panic!("expected mock credential");
+        };
+        assert_eq!(config.latency_ms, 50);
+        assert_eq!(config.fault_for_call(1), None);
+        assert_eq!(config.fault_for_call(3), Some(MockFault::RateLimited));
+        assert_eq!(config.fault_for_call(6), Some(MockFault::RateLimited));
+
+        let default_cred: CredentialData = serde_json::from_str(r#"{"type":"mock"}"#).unwrap();
+        assert_eq!(default_cred.display_name(), "Mock");
+    }
+
     #[test]
     fn test_pattern_matches_exact() {
         assert!(pattern_matches("gemini-2.5-pro", "gemini-2.5-pro"));
@@ -770,6 +1021,9 @@ mod tests {
             cached_token: None,
             source: CredentialSource::Manual,
             proxy_url: None,
+            native_web_search: false,
+            fallback_base_urls: vec![],
+            weight: DEFAULT_CREDENTIAL_WEIGHT,
         };
 
         assert!(!cred.supports_model("claude-opus"));
@@ -805,6 +1059,9 @@ mod tests {
             cached_token: None,
             source: CredentialSource::Manual,
             proxy_url: None,
+            native_web_search: false,
+            fallback_base_urls: vec![],
+            weight: DEFAULT_CREDENTIAL_WEIGHT,
         };
 
         // Exact match exclusion
@@ -842,6 +1099,9 @@ mod tests {
             cached_token: None,
             source: CredentialSource::Manual,
             proxy_url: None,
+            native_web_search: false,
+            fallback_base_urls: vec![],
+            weight: DEFAULT_CREDENTIAL_WEIGHT,
         };
 
         // Prefix wildcard exclusion
@@ -883,6 +1143,9 @@ mod tests {
             cached_token: None,
             source: CredentialSource::Manual,
             proxy_url: None,
+            native_web_search: false,
+            fallback_base_urls: vec![],
+            weight: DEFAULT_CREDENTIAL_WEIGHT,
         };
 
         // Contains wildcard exclusion
@@ -921,6 +1184,9 @@ mod tests {
             cached_token: None,
             source: CredentialSource::Manual,
             proxy_url: None,
+            native_web_search: false,
+            fallback_base_urls: vec![],
+            weight: DEFAULT_CREDENTIAL_WEIGHT,
         };
 
         // Excluded by not_supported_models (exact match)
@@ -960,6 +1226,9 @@ mod tests {
             cached_token: None,
             source: CredentialSource::Manual,
             proxy_url: None,
+            native_web_search: false,
+            fallback_base_urls: vec![],
+            weight: DEFAULT_CREDENTIAL_WEIGHT,
         };
 
         // All models should be supported since not_supported_models is empty
@@ -35,6 +35,8 @@ pub enum ProviderType {
     #[serde(rename = "aws_bedrock")]
     AwsBedrock,
     Ollama,
+    /// 测试用 Mock Provider（返回确定性响应，不消耗真实额度）
+    Mock,
 }
 
 impl std::fmt::Display for ProviderType {
@@ -54,6 +56,7 @@ impl std::fmt::Display for ProviderType {
             ProviderType::AzureOpenai => write!(f, "azure_openai"),
             ProviderType::AwsBedrock => write!(f, "aws_bedrock"),
             ProviderType::Ollama => write!(f, "ollama"),
+            ProviderType::Mock => write!(f, "mock"),
         }
     }
 }
@@ -79,6 +82,7 @@ impl std::str::FromStr for ProviderType {
             "azure_openai" | "azure-openai" => Ok(ProviderType::AzureOpenai),
             "aws_bedrock" | "aws-bedrock" => Ok(ProviderType::AwsBedrock),
             "ollama" => Ok(ProviderType::Ollama),
+            "mock" => Ok(ProviderType::Mock),
             // OpenAI 兼容的第三方 Provider 映射到 OpenAI
             "deepseek" | "deep_seek" | "deep-seek" => Ok(ProviderType::OpenAI),
             "qwen" | "tongyi" | "dashscope" => Ok(ProviderType::OpenAI),
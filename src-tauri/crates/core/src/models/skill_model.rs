@@ -79,6 +79,13 @@ pub struct Skill {
     pub repo_name: Option<String>,
     #[serde(rename = "repoBranch", skip_serializing_if = "Option::is_none")]
     pub repo_branch: Option<String>,
+    /// 来源扩展注册表 ID（来自自托管索引时）
+    #[serde(
+        rename = "registryId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub registry_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub license: Option<String>,
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
@@ -184,6 +191,12 @@ impl ParsedSkillManifest {
         let mapping = self.raw_frontmatter.as_mapping()?;
         yaml_mapping_get(mapping, key).and_then(yaml_scalar_to_bool)
     }
+
+    /// 读取原始 YAML 值（列表、对象等非标量字段）
+    pub fn raw_value(&self, key: &str) -> Option<&serde_yaml::Value> {
+        let mapping = self.raw_frontmatter.as_mapping()?;
+        yaml_mapping_get(mapping, key)
+    }
 }
 
 pub fn split_skill_frontmatter(content: &str) -> Option<(&str, &str)> {
@@ -0,0 +1,192 @@
+//! 虚拟模型
+//!
+//! 用户自定义的模型别名（如 `my-fast`、`my-smart`），映射到 Provider + 模型 + 参数预设。
+//! 虚拟模型存储在 SQLite `virtual_models` 表，出现在 `/v1/models` 列表中，
+//! 请求使用虚拟模型名时由路由解析为真实模型，并补齐预设的默认参数。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// 虚拟模型定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualModel {
+    /// 虚拟模型名（客户端请求中使用的 model）
+    pub name: String,
+    /// 目标 Provider（等同于 `X-Provider-Id`，为空时按默认路由选择）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// 目标模型
+    pub model: String,
+    /// 默认 temperature（请求未指定时生效）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// 系统提示词前缀（拼接在请求的系统提示词之前）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prefix: Option<String>,
+    /// 描述
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 是否启用
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl VirtualModel {
+    /// 为 OpenAI Chat Completions 请求补齐默认参数
+    pub fn apply_openai_defaults(&self, payload: &mut Value) {
+        self.apply_temperature(payload);
+        let Some(prefix) = self.system_prefix() else {
+            return;
+        };
+        let Some(messages) = payload.get_mut("messages").and_then(Value::as_array_mut) else {
+            return;
+        };
+        let first_system_text = messages
+            .first_mut()
+            .filter(|m| m.get("role").and_then(Value::as_str) == Some("system"))
+            .and_then(|m| m.get_mut("content"))
+            .filter(|c| c.is_string());
+        match first_system_text {
+            Some(content) => {
+                let original = content.as_str().unwrap_or_default();
+                *content = Value::String(join_prefix(prefix, original));
+            }
+            None => messages.insert(
+                0,
+                serde_json::json!({ "role": "system", "content": prefix }),
+            ),
+        }
+    }
+
+    /// 为 Anthropic Messages 请求补齐默认参数
+    pub fn apply_anthropic_defaults(&self, payload: &mut Value) {
+        self.apply_temperature(payload);
+        let Some(prefix) = self.system_prefix() else {
+            return;
+        };
+        let Some(obj) = payload.as_object_mut() else {
+            return;
+        };
+        let system = match obj.remove("system") {
+            Some(Value::String(s)) => Value::String(join_prefix(prefix, &s)),
+            Some(Value::Array(mut blocks)) => {
+                blocks.insert(0, serde_json::json!({ "type": "text", "text": prefix }));
+                Value::Array(blocks)
+            }
+            _ => Value::String(prefix.to_string()),
+        };
+        obj.insert("system".to_string(), system);
+    }
+
+    fn apply_temperature(&self, payload: &mut Value) {
+        let Some(temperature) = self.temperature else {
+            return;
+        };
+        let Some(obj) = payload.as_object_mut() else {
+            return;
+        };
+        if matches!(obj.get("temperature"), None | Some(Value::Null)) {
+            obj.insert("temperature".to_string(), serde_json::json!(temperature));
+        }
+    }
+
+    fn system_prefix(&self) -> Option<&str> {
+        self.system_prefix
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    }
+}
+
+fn join_prefix(prefix: &str, original: &str) -> String {
+    if original.is_empty() {
+        prefix.to_string()
+    } else {
+        format!("{prefix}\n\n{original}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn virtual_model() -> VirtualModel {
+        VirtualModel {
+            name: "my-fast".to_string(),
+            provider: Some("openai".to_string()),
+            model: "gpt-4o-mini".to_string(),
+            temperature: Some(0.2),
+            system_prefix: Some("Be concise.".to_string()),
+            description: None,
+            enabled: true,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_apply_openai_defaults() {
+        let vm = virtual_model();
+
+        let mut payload = json!({
+            "model": "my-fast",
+            "messages": [{ "role": "user", "content": "hi" }]
+        });
+        vm.apply_openai_defaults(&mut payload);
+        assert_eq!(payload["temperature"], json!(0.2));
+        assert_eq!(
+            payload["messages"][0],
+            json!({ "role": "system", "content": "Be concise." })
+        );
+
+        let mut payload = json!({
+            "model": "my-fast",
+            "temperature": 1.0,
+            "messages": [
+                { "role": "system", "content": "You are helpful." },
+                { "role": "user", "content": "hi" }
+            ]
+        });
+        vm.apply_openai_defaults(&mut payload);
+        assert_eq!(payload["temperature"], json!(1.0));
+        assert_eq!(
+            payload["messages"][0]["content"],
+            json!("Be concise.\n\nYou are helpful.")
+        );
+        assert_eq!(payload["messages"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_apply_anthropic_defaults() {
+        let vm = virtual_model();
+
+        let mut payload = json!({ "model": "my-fast", "system": "You are helpful." });
+        vm.apply_anthropic_defaults(&mut payload);
+        assert_eq!(payload["system"], json!("Be concise.\n\nYou are helpful."));
+        assert_eq!(payload["temperature"], json!(0.2));
+
+        let mut payload = json!({
+            "model": "my-fast",
+            "system": [{ "type": "text", "text": "You are helpful." }]
+        });
+        vm.apply_anthropic_defaults(&mut payload);
+        assert_eq!(
+            payload["system"][0],
+            json!({ "type": "text", "text": "Be concise." })
+        );
+
+        let mut payload = json!({ "model": "my-fast" });
+        vm.apply_anthropic_defaults(&mut payload);
+        assert_eq!(payload["system"], json!("Be concise."));
+    }
+}
@@ -0,0 +1,713 @@
+//! 插件数据表
+//!
+//! 插件在 plugin.json 的 `database.tables` 中声明数据表，宿主在连接插件时按声明创建
+//! `plugin_<编码后的插件 ID>__<表名>` 表（表已存在时只补充新增的列）。
+//!
+//! 插件只能通过结构化操作访问自己声明的表，宿主据此生成带参数的预编译语句，
+//! 不执行插件提供的 SQL：
+//! - insert / update / delete / query，条件为列的等值匹配（`null` 匹配 `IS NULL`）
+//! - batch：多个写操作在同一事务中执行，任一失败全部回滚
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::database::{lock_db, DbConnection};
+
+/// 单个插件最多声明的表数
+const MAX_TABLES: usize = 32;
+/// 单表最多声明的列数
+const MAX_COLUMNS: usize = 64;
+/// 标识符（表名、列名）的最大长度
+const MAX_IDENTIFIER_LEN: usize = 63;
+/// 单次 batch 的最大操作数
+pub const MAX_BATCH_OPERATIONS: usize = 500;
+/// query 默认返回行数
+const DEFAULT_QUERY_LIMIT: usize = 100;
+/// query 最大返回行数
+const MAX_QUERY_LIMIT: usize = 1000;
+
+/// plugin.json 中的 `database` 声明
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PluginDatabaseSchema {
+    #[serde(default)]
+    pub tables: Vec<PluginTableSchema>,
+}
+
+/// 数据表声明
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginTableSchema {
+    /// 表名（不含插件前缀）
+    pub name: String,
+    pub columns: Vec<PluginColumnSchema>,
+}
+
+/// 列声明
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginColumnSchema {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub column_type: PluginColumnType,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub primary_key: bool,
+    /// 仅在建表时生效，后续版本新增的列总是可空
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub not_null: bool,
+}
+
+/// 列类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginColumnType {
+    Integer,
+    Real,
+    Text,
+    /// 任意 JSON 值，以文本保存，读取时还原
+    Json,
+}
+
+impl PluginColumnType {
+    fn sql_type(self) -> &'static str {
+        match self {
+            PluginColumnType::Integer => "INTEGER",
+            PluginColumnType::Real => "REAL",
+            PluginColumnType::Text | PluginColumnType::Json => "TEXT",
+        }
+    }
+}
+
+/// 插件数据表错误
+#[derive(Debug, thiserror::Error)]
+pub enum PluginDbError {
+    #[error("数据表声明无效: {0}")]
+    InvalidSchema(String),
+
+    #[error("插件未声明数据表: {0}")]
+    UnknownTable(String),
+
+    #[error("数据表 {table} 中没有列 {column}")]
+    UnknownColumn { table: String, column: String },
+
+    #[error("列 {column} 的值无效: {reason}")]
+    InvalidValue { column: String, reason: String },
+
+    #[error("无效的操作: {0}")]
+    InvalidOperation(String),
+
+    #[error("批量操作过多: {size}，上限 {limit}")]
+    BatchTooLarge { size: usize, limit: usize },
+
+    #[error("数据库错误: {0}")]
+    Database(String),
+}
+
+impl From<rusqlite::Error> for PluginDbError {
+    fn from(e: rusqlite::Error) -> Self {
+        PluginDbError::Database(e.to_string())
+    }
+}
+
+/// 写操作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum PluginDbWrite {
+    Insert {
+        table: String,
+        #[serde(default)]
+        values: Map<String, Value>,
+    },
+    Update {
+        table: String,
+        values: Map<String, Value>,
+        #[serde(default, rename = "where")]
+        filter: Map<String, Value>,
+    },
+    Delete {
+        table: String,
+        #[serde(default, rename = "where")]
+        filter: Map<String, Value>,
+    },
+}
+
+/// 查询
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PluginDbQuery {
+    pub table: String,
+    /// 返回的列，为空时返回全部声明的列
+    #[serde(default)]
+    pub columns: Vec<String>,
+    #[serde(default, rename = "where")]
+    pub filter: Map<String, Value>,
+    #[serde(default)]
+    pub order_by: Option<String>,
+    #[serde(default)]
+    pub descending: bool,
+    /// 默认 100，上限 1000
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub offset: usize,
+}
+
+/// 写操作结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginDbWriteResult {
+    pub rows_affected: usize,
+    /// insert 生成的 rowid
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_insert_id: Option<i64>,
+}
+
+/// 绑定到单个插件的数据表句柄
+#[derive(Clone)]
+pub struct PluginDatabase {
+    db: DbConnection,
+    plugin_id: String,
+    schema: PluginDatabaseSchema,
+}
+
+impl PluginDatabase {
+    pub fn new(
+        db: DbConnection,
+        plugin_id: impl Into<String>,
+        schema: PluginDatabaseSchema,
+    ) -> Self {
+        Self {
+            db,
+            plugin_id: plugin_id.into(),
+            schema,
+        }
+    }
+
+    pub fn plugin_id(&self) -> &str {
+        &self.plugin_id
+    }
+
+    /// 插件表名前缀：插件 ID 中 `[a-z0-9]` 以外的字节编码为 `_xx`，保证不同插件不会冲突
+    pub fn table_prefix(plugin_id: &str) -> String {
+        let mut prefix = String::from("plugin_");
+        for byte in plugin_id.bytes() {
+            if byte.is_ascii_lowercase() || byte.is_ascii_digit() {
+                prefix.push(byte as char);
+            } else {
+                prefix.push_str(&format!("_{byte:02x}"));
+            }
+        }
+        prefix.push_str("__");
+        prefix
+    }
+
+    /// 声明的表对应的实际表名
+    pub fn table_name(&self, table: &str) -> String {
+        format!("{}{}", Self::table_prefix(&self.plugin_id), table)
+    }
+
+    /// 按声明创建表，已存在的表补充新增列
+    pub fn migrate(&self) -> Result<(), PluginDbError> {
+        validate_schema(&self.schema)?;
+        let conn = lock_db(&self.db).map_err(PluginDbError::Database)?;
+        for table in &self.schema.tables {
+            let name = self.table_name(&table.name);
+            let existing = existing_columns(&conn, &name)?;
+            if existing.is_empty() {
+                conn.execute_batch(&create_table_sql(&name, table))?;
+                continue;
+            }
+            for column in table
+                .columns
+                .iter()
+                .filter(|column| !existing.contains(&column.name))
+            {
+                conn.execute_batch(&format!(
+                    "ALTER TABLE \"{name}\" ADD COLUMN \"{}\" {}",
+                    column.name,
+                    column.column_type.sql_type()
+                ))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 执行单个写操作
+    pub fn execute(&self, op: &PluginDbWrite) -> Result<PluginDbWriteResult, PluginDbError> {
+        let conn = lock_db(&self.db).map_err(PluginDbError::Database)?;
+        self.execute_on(&conn, op)
+    }
+
+    /// 在同一事务中执行多个写操作，任一失败全部回滚
+    pub fn batch(&self, ops: &[PluginDbWrite]) -> Result<Vec<PluginDbWriteResult>, PluginDbError> {
+        if ops.len() > MAX_BATCH_OPERATIONS {
+            return Err(PluginDbError::BatchTooLarge {
+                size: ops.len(),
+                limit: MAX_BATCH_OPERATIONS,
+            });
+        }
+        let mut conn = lock_db(&self.db).map_err(PluginDbError::Database)?;
+        let tx = conn.transaction()?;
+        let results = ops
+            .iter()
+            .map(|op| self.execute_on(&tx, op))
+            .collect::<Result<Vec<_>, _>>()?;
+        tx.commit()?;
+        Ok(results)
+    }
+
+    /// 查询，返回以列名为键的行
+    pub fn query(&self, query: &PluginDbQuery) -> Result<Vec<Map<String, Value>>, PluginDbError> {
+        let table = self.table(&query.table)?;
+        let columns: Vec<&PluginColumnSchema> = if query.columns.is_empty() {
+            table.columns.iter().collect()
+        } else {
+            query
+                .columns
+                .iter()
+                .map(|name| column(table, name))
+                .collect::<Result<_, _>>()?
+        };
+
+        let column_list = columns
+            .iter()
+            .map(|column| format!("\"{}\"", column.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut sql = format!(
+            "SELECT {column_list} FROM \"{}\"",
+            self.table_name(&table.name)
+        );
+        let mut params = Vec::new();
+        push_where(&mut sql, &mut params, table, &query.filter)?;
+        if let Some(order_by) = &query.order_by {
+            let order_column = column(table, order_by)?;
+            sql.push_str(&format!(" ORDER BY \"{}\"", order_column.name));
+            if query.descending {
+                sql.push_str(" DESC");
+            }
+        }
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_QUERY_LIMIT)
+            .min(MAX_QUERY_LIMIT);
+        sql.push_str(" LIMIT ? OFFSET ?");
+        params.push(SqlValue::Integer(limit as i64));
+        params.push(SqlValue::Integer(query.offset as i64));
+
+        let conn = lock_db(&self.db).map_err(PluginDbError::Database)?;
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params.iter()), |row| {
+            columns
+                .iter()
+                .enumerate()
+                .map(|(idx, column)| Ok((column, row.get::<_, SqlValue>(idx)?)))
+                .collect::<Result<Vec<_>, rusqlite::Error>>()
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            let mut object = Map::new();
+            for (column, value) in row? {
+                object.insert(column.name.clone(), from_sql(column, value));
+            }
+            result.push(object);
+        }
+        Ok(result)
+    }
+
+    /// 删除插件的全部数据表（卸载时调用），返回删除的表数
+    pub fn drop_tables(db: &DbConnection, plugin_id: &str) -> Result<usize, PluginDbError> {
+        let prefix = Self::table_prefix(plugin_id);
+        let conn = lock_db(db).map_err(PluginDbError::Database)?;
+        let tables: Vec<String> = {
+            let mut stmt = conn.prepare(
+                "SELECT name FROM sqlite_master
+                 WHERE type = 'table' AND substr(name, 1, length(?1)) = ?1",
+            )?;
+            let names = stmt.query_map([&prefix], |row| row.get(0))?;
+            names.collect::<Result<_, _>>()?
+        };
+        for table in &tables {
+            conn.execute_batch(&format!("DROP TABLE IF EXISTS \"{table}\""))?;
+        }
+        Ok(tables.len())
+    }
+
+    fn table(&self, name: &str) -> Result<&PluginTableSchema, PluginDbError> {
+        self.schema
+            .tables
+            .iter()
+            .find(|table| table.name == name)
+            .ok_or_else(|| PluginDbError::UnknownTable(name.to_string()))
+    }
+
+    fn execute_on(
+        &self,
+        conn: &Connection,
+        op: &PluginDbWrite,
+    ) -> Result<PluginDbWriteResult, PluginDbError> {
+        let mut params = Vec::new();
+        let sql = match op {
+            PluginDbWrite::Insert { table, values } => {
+                let table = self.table(table)?;
+                let name = self.table_name(&table.name);
+                if values.is_empty() {
+                    format!("INSERT INTO \"{name}\" DEFAULT VALUES")
+                } else {
+                    let mut columns = Vec::new();
+                    for (key, value) in values {
+                        let column = column(table, key)?;
+                        columns.push(format!("\"{}\"", column.name));
+                        params.push(to_sql(column, value)?);
+                    }
+                    format!(
+                        "INSERT INTO \"{name}\" ({}) VALUES ({})",
+                        columns.join(", "),
+                        vec!["?"; columns.len()].join(", ")
+                    )
+                }
+            }
+            PluginDbWrite::Update {
+                table,
+                values,
+                filter,
+            } => {
+                let table = self.table(table)?;
+                if values.is_empty() {
+                    return Err(PluginDbError::InvalidOperation(
+                        "update 需要至少一个列值".to_string(),
+                    ));
+                }
+                let mut assignments = Vec::new();
+                for (key, value) in values {
+                    let column = column(table, key)?;
+                    assignments.push(format!("\"{}\" = ?", column.name));
+                    params.push(to_sql(column, value)?);
+                }
+                let mut sql = format!(
+                    "UPDATE \"{}\" SET {}",
+                    self.table_name(&table.name),
+                    assignments.join(", ")
+                );
+                push_where(&mut sql, &mut params, table, filter)?;
+                sql
+            }
+            PluginDbWrite::Delete { table, filter } => {
+                let table = self.table(table)?;
+                let mut sql = format!("DELETE FROM \"{}\"", self.table_name(&table.name));
+                push_where(&mut sql, &mut params, table, filter)?;
+                sql
+            }
+        };
+
+        let rows_affected = conn.execute(&sql, params_from_iter(params.iter()))?;
+        let last_insert_id =
+            matches!(op, PluginDbWrite::Insert { .. }).then(|| conn.last_insert_rowid());
+        Ok(PluginDbWriteResult {
+            rows_affected,
+            last_insert_id,
+        })
+    }
+}
+
+fn validate_identifier(kind: &str, name: &str) -> Result<(), PluginDbError> {
+    let mut chars = name.chars();
+    let valid_start = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_');
+    if !valid_start
+        || name.len() > MAX_IDENTIFIER_LEN
+        || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        return Err(PluginDbError::InvalidSchema(format!(
+            "{kind} {name:?} 只能包含字母、数字和下划线，且不能以数字开头"
+        )));
+    }
+    Ok(())
+}
+
+fn validate_schema(schema: &PluginDatabaseSchema) -> Result<(), PluginDbError> {
+    if schema.tables.len() > MAX_TABLES {
+        return Err(PluginDbError::InvalidSchema(format!(
+            "最多声明 {MAX_TABLES} 张表"
+        )));
+    }
+    for (idx, table) in schema.tables.iter().enumerate() {
+        validate_identifier("表名", &table.name)?;
+        if schema.tables[..idx].iter().any(|t| t.name == table.name) {
+            return Err(PluginDbError::InvalidSchema(format!(
+                "表 {} 重复声明",
+                table.name
+            )));
+        }
+        if table.columns.is_empty() || table.columns.len() > MAX_COLUMNS {
+            return Err(PluginDbError::InvalidSchema(format!(
+                "表 {} 的列数需在 1 到 {MAX_COLUMNS} 之间",
+                table.name
+            )));
+        }
+        for (col_idx, column) in table.columns.iter().enumerate() {
+            validate_identifier("列名", &column.name)?;
+            if table.columns[..col_idx]
+                .iter()
+                .any(|c| c.name.eq_ignore_ascii_case(&column.name))
+            {
+                return Err(PluginDbError::InvalidSchema(format!(
+                    "表 {} 的列 {} 重复声明",
+                    table.name, column.name
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn create_table_sql(name: &str, table: &PluginTableSchema) -> String {
+    let mut definitions: Vec<String> = table
+        .columns
+        .iter()
+        .map(|column| {
+            let mut definition = format!("\"{}\" {}", column.name, column.column_type.sql_type());
+            if column.not_null {
+                definition.push_str(" NOT NULL");
+            }
+            definition
+        })
+        .collect();
+    let primary_keys: Vec<String> = table
+        .columns
+        .iter()
+        .filter(|column| column.primary_key)
+        .map(|column| format!("\"{}\"", column.name))
+        .collect();
+    if !primary_keys.is_empty() {
+        definitions.push(format!("PRIMARY KEY ({})", primary_keys.join(", ")));
+    }
+    format!(
+        "CREATE TABLE IF NOT EXISTS \"{name}\" ({})",
+        definitions.join(", ")
+    )
+}
+
+fn existing_columns(conn: &Connection, table: &str) -> Result<Vec<String>, PluginDbError> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info(\"{table}\")"))?;
+    let columns = stmt.query_map([], |row| row.get::<_, String>(1))?;
+    Ok(columns.collect::<Result<_, _>>()?)
+}
+
+fn column<'a>(
+    table: &'a PluginTableSchema,
+    name: &str,
+) -> Result<&'a PluginColumnSchema, PluginDbError> {
+    table
+        .columns
+        .iter()
+        .find(|column| column.name == name)
+        .ok_or_else(|| PluginDbError::UnknownColumn {
+            table: table.name.clone(),
+            column: name.to_string(),
+        })
+}
+
+fn push_where(
+    sql: &mut String,
+    params: &mut Vec<SqlValue>,
+    table: &PluginTableSchema,
+    filter: &Map<String, Value>,
+) -> Result<(), PluginDbError> {
+    let mut conditions = Vec::new();
+    for (key, value) in filter {
+        let column = column(table, key)?;
+        if value.is_null() {
+            conditions.push(format!("\"{}\" IS NULL", column.name));
+        } else {
+            conditions.push(format!("\"{}\" = ?", column.name));
+            params.push(to_sql(column, value)?);
+        }
+    }
+    if !conditions.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&conditions.join(" AND "));
+    }
+    Ok(())
+}
+
+fn to_sql(column: &PluginColumnSchema, value: &Value) -> Result<SqlValue, PluginDbError> {
+    let invalid = |reason: &str| PluginDbError::InvalidValue {
+        column: column.name.clone(),
+        reason: reason.to_string(),
+    };
+    if value.is_null() {
+        return Ok(SqlValue::Null);
+    }
+    match column.column_type {
+        PluginColumnType::Integer => match value {
+            Value::Bool(b) => Ok(SqlValue::Integer(i64::from(*b))),
+            Value::Number(n) => n
+                .as_i64()
+                .map(SqlValue::Integer)
+                .ok_or_else(|| invalid("需要整数")),
+            _ => Err(invalid("需要整数")),
+        },
+        PluginColumnType::Real => value
+            .as_f64()
+            .map(SqlValue::Real)
+            .ok_or_else(|| invalid("需要数字")),
+        PluginColumnType::Text => value
+            .as_str()
+            .map(|s| SqlValue::Text(s.to_string()))
+            .ok_or_else(|| invalid("需要字符串")),
+        PluginColumnType::Json => Ok(SqlValue::Text(value.to_string())),
+    }
+}
+
+fn from_sql(column: &PluginColumnSchema, value: SqlValue) -> Value {
+    match value {
+        SqlValue::Null => Value::Null,
+        SqlValue::Integer(i) => Value::from(i),
+        SqlValue::Real(f) => Value::from(f),
+        SqlValue::Text(text) if column.column_type == PluginColumnType::Json => {
+            serde_json::from_str(&text).unwrap_or(Value::String(text))
+        }
+        SqlValue::Text(text) => Value::String(text),
+        SqlValue::Blob(bytes) => Value::from(bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn test_db() -> DbConnection {
+        Arc::new(Mutex::new(Connection::open_in_memory().unwrap()))
+    }
+
+    fn notes_schema() -> PluginDatabaseSchema {
+        serde_json::from_value(serde_json::json!({
+            "tables": [{
+                "name": "notes",
+                "columns": [
+                    { "name": "id", "type": "integer", "primary_key": true },
+                    { "name": "title", "type": "text", "not_null": true },
+                    { "name": "meta", "type": "json" }
+                ]
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn write(value: Value) -> PluginDbWrite {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_insert_update_query_roundtrip() {
+        let db = test_db();
+        let plugin = PluginDatabase::new(db.clone(), "my-plugin", notes_schema());
+        plugin.migrate().unwrap();
+        assert_eq!(plugin.table_name("notes"), "plugin_my_2dplugin__notes");
+
+        let inserted = plugin
+            .execute(&write(serde_json::json!({
+                "op": "insert",
+                "table": "notes",
+                "values": { "title": "a", "meta": { "tags": ["x"] } }
+            })))
+            .unwrap();
+        assert_eq!(inserted.last_insert_id, Some(1));
+
+        let updated = plugin
+            .execute(&write(serde_json::json!({
+                "op": "update",
+                "table": "notes",
+                "values": { "title": "b" },
+                "where": { "id": 1 }
+            })))
+            .unwrap();
+        assert_eq!(updated.rows_affected, 1);
+
+        let rows = plugin
+            .query(&PluginDbQuery {
+                table: "notes".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            Value::Object(rows[0].clone()),
+            serde_json::json!({ "id": 1, "title": "b", "meta": { "tags": ["x"] } })
+        );
+
+        // 其他插件看不到这张表，列名不能注入
+        let other = PluginDatabase::new(db.clone(), "other", PluginDatabaseSchema::default());
+        assert!(matches!(
+            other.query(&PluginDbQuery {
+                table: "notes".to_string(),
+                ..Default::default()
+            }),
+            Err(PluginDbError::UnknownTable(_))
+        ));
+        assert!(matches!(
+            plugin.execute(&write(serde_json::json!({
+                "op": "delete",
+                "table": "notes",
+                "where": { "id\" OR 1=1 --": 1 }
+            }))),
+            Err(PluginDbError::UnknownColumn { .. })
+        ));
+
+        assert_eq!(PluginDatabase::drop_tables(&db, "my-plugin").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_batch_rolls_back_on_error() {
+        let plugin = PluginDatabase::new(test_db(), "p", notes_schema());
+        plugin.migrate().unwrap();
+
+        let result = plugin.batch(&[
+            write(
+                serde_json::json!({ "op": "insert", "table": "notes", "values": { "title": "a" } }),
+            ),
+            // title NOT NULL
+            write(serde_json::json!({ "op": "insert", "table": "notes", "values": { "meta": 1 } })),
+        ]);
+        assert!(matches!(result, Err(PluginDbError::Database(_))));
+        let rows = plugin
+            .query(&PluginDbQuery {
+                table: "notes".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(rows.is_empty());
+    }
+
+    #[test]
+    fn test_migrate_adds_new_columns_and_rejects_invalid_schema() {
+        let db = test_db();
+        PluginDatabase::new(db.clone(), "p", notes_schema())
+            .migrate()
+            .unwrap();
+
+        let mut schema = notes_schema();
+        schema.tables[0].columns.push(PluginColumnSchema {
+            name: "score".to_string(),
+            column_type: PluginColumnType::Real,
+            primary_key: false,
+            not_null: true,
+        });
+        let plugin = PluginDatabase::new(db.clone(), "p", schema);
+        plugin.migrate().unwrap();
+        plugin
+            .execute(&write(serde_json::json!({
+                "op": "insert",
+                "table": "notes",
+                "values": { "title": "a", "score": 1.5 }
+            })))
+            .unwrap();
+
+        let mut invalid = notes_schema();
+        invalid.tables[0].name = "bad name".to_string();
+        assert!(matches!(
+            PluginDatabase::new(db, "p", invalid).migrate(),
+            Err(PluginDbError::InvalidSchema(_))
+        ));
+    }
+}
@@ -0,0 +1,368 @@
+//! 插件事件总线
+//!
+//! 进程内按主题发布 / 订阅的事件总线：
+//! - 插件通过 [`PluginEventClient::emit`] 发布事件，事件带上来源插件 ID
+//! - 订阅需要插件声明 `EventSubscribe` 权限，主题支持 `*` 后缀通配
+//! - 每个事件同时通过 [`DynEmitter`] 以 [`PLUGIN_BUS_EVENT`] 交给宿主，由宿主按主题投递到插件 UI
+//!
+//! 投递在发布线程中同步调用订阅者的 [`PluginEventSink`]，订阅者应只做入队等轻量操作。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::event_scope::topic_matches;
+use super::types::PluginPermission;
+use crate::event_emit::DynEmitter;
+
+/// 转发到前端的事件名
+pub const PLUGIN_BUS_EVENT: &str = "plugin-bus-event";
+
+/// 主题的最大字节数
+const MAX_TOPIC_BYTES: usize = 128;
+
+/// 总线上的事件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginBusEvent {
+    /// 发布者插件 ID
+    pub source: String,
+    pub topic: String,
+    pub payload: Value,
+    /// 发布时间（RFC 3339）
+    pub timestamp: String,
+}
+
+/// 订阅者回调：`(subscription_id, event)`
+pub type PluginEventSink = Arc<dyn Fn(u64, &PluginBusEvent) + Send + Sync>;
+
+/// 事件总线错误
+#[derive(Debug, thiserror::Error)]
+pub enum PluginEventError {
+    #[error("插件未声明权限: {0:?}")]
+    PermissionDenied(PluginPermission),
+
+    #[error("无效的事件主题: {0}")]
+    InvalidTopic(String),
+
+    #[error("当前插件运行时不支持事件订阅")]
+    Unsupported,
+}
+
+/// 订阅信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginEventSubscription {
+    pub id: u64,
+    pub plugin_id: String,
+    /// 主题（支持 `*` 后缀通配）
+    pub topic: String,
+}
+
+struct Subscriber {
+    info: PluginEventSubscription,
+    sink: PluginEventSink,
+}
+
+/// 插件事件总线
+#[derive(Default)]
+pub struct PluginEventBus {
+    next_id: AtomicU64,
+    subscribers: RwLock<Vec<Subscriber>>,
+    emitter: RwLock<Option<DynEmitter>>,
+}
+
+impl PluginEventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置前端转发发射器
+    pub fn set_emitter(&self, emitter: DynEmitter) {
+        if let Ok(mut current) = self.emitter.write() {
+            *current = Some(emitter);
+        }
+    }
+
+    /// 发布事件，返回投递到的订阅数
+    pub fn publish(&self, source: &str, topic: &str, payload: Value) -> usize {
+        let event = PluginBusEvent {
+            source: source.to_string(),
+            topic: topic.to_string(),
+            payload,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        };
+
+        let targets: Vec<(u64, PluginEventSink)> = self
+            .subscribers
+            .read()
+            .map(|subscribers| {
+                subscribers
+                    .iter()
+                    .filter(|s| topic_matches(&s.info.topic, topic))
+                    .map(|s| (s.info.id, s.sink.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        // 在锁外调用回调，允许回调中再次订阅或发布
+        for (id, sink) in &targets {
+            sink(*id, &event);
+        }
+
+        let emitter = self.emitter.read().ok().and_then(|e| e.clone());
+        if let Some(emitter) = emitter {
+            match serde_json::to_value(&event) {
+                Ok(payload) => {
+                    if let Err(e) = emitter.emit_event(PLUGIN_BUS_EVENT, &payload) {
+                        tracing::debug!("[PluginEventBus] 转发前端失败: {}", e);
+                    }
+                }
+                Err(e) => tracing::debug!("[PluginEventBus] 事件序列化失败: {}", e),
+            }
+        }
+
+        targets.len()
+    }
+
+    /// 订阅主题，返回订阅 ID
+    pub fn subscribe(&self, plugin_id: &str, topic: &str, sink: PluginEventSink) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Ok(mut subscribers) = self.subscribers.write() {
+            subscribers.push(Subscriber {
+                info: PluginEventSubscription {
+                    id,
+                    plugin_id: plugin_id.to_string(),
+                    topic: topic.to_string(),
+                },
+                sink,
+            });
+        }
+        id
+    }
+
+    /// 取消插件的指定订阅，返回订阅是否存在
+    pub fn unsubscribe(&self, plugin_id: &str, id: u64) -> bool {
+        self.subscribers
+            .write()
+            .map(|mut subscribers| {
+                let before = subscribers.len();
+                subscribers.retain(|s| !(s.info.id == id && s.info.plugin_id == plugin_id));
+                before != subscribers.len()
+            })
+            .unwrap_or(false)
+    }
+
+    /// 取消插件的所有订阅（插件断开或卸载时调用），返回取消数量
+    pub fn unsubscribe_plugin(&self, plugin_id: &str) -> usize {
+        self.subscribers
+            .write()
+            .map(|mut subscribers| {
+                let before = subscribers.len();
+                subscribers.retain(|s| s.info.plugin_id != plugin_id);
+                before - subscribers.len()
+            })
+            .unwrap_or(0)
+    }
+
+    /// 当前订阅（`plugin_id` 为空时返回全部）
+    pub fn subscriptions(&self, plugin_id: Option<&str>) -> Vec<PluginEventSubscription> {
+        self.subscribers
+            .read()
+            .map(|subscribers| {
+                subscribers
+                    .iter()
+                    .filter(|s| match plugin_id {
+                        Some(id) => s.info.plugin_id == id,
+                        None => true,
+                    })
+                    .map(|s| s.info.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// 绑定到单个插件的事件总线句柄
+#[derive(Clone)]
+pub struct PluginEventClient {
+    bus: Arc<PluginEventBus>,
+    plugin_id: String,
+    permissions: Vec<PluginPermission>,
+    /// 订阅投递回调；为空表示该插件运行时无法接收事件
+    sink: Option<PluginEventSink>,
+}
+
+impl PluginEventClient {
+    pub fn new(
+        bus: Arc<PluginEventBus>,
+        plugin_id: impl Into<String>,
+        permissions: Vec<PluginPermission>,
+        sink: Option<PluginEventSink>,
+    ) -> Self {
+        Self {
+            bus,
+            plugin_id: plugin_id.into(),
+            permissions,
+            sink,
+        }
+    }
+
+    pub fn plugin_id(&self) -> &str {
+        &self.plugin_id
+    }
+
+    /// 发布事件，返回投递到的订阅数
+    pub fn emit(&self, topic: &str, payload: Value) -> Result<usize, PluginEventError> {
+        validate_topic(topic, false)?;
+        Ok(self.bus.publish(&self.plugin_id, topic, payload))
+    }
+
+    /// 订阅主题（需要 `EventSubscribe` 权限），返回订阅 ID
+    pub fn subscribe(&self, topic: &str) -> Result<u64, PluginEventError> {
+        if !self.permissions.contains(&PluginPermission::EventSubscribe) {
+            return Err(PluginEventError::PermissionDenied(
+                PluginPermission::EventSubscribe,
+            ));
+        }
+        validate_topic(topic, true)?;
+        let sink = self.sink.clone().ok_or(PluginEventError::Unsupported)?;
+        Ok(self.bus.subscribe(&self.plugin_id, topic, sink))
+    }
+
+    pub fn unsubscribe(&self, id: u64) -> bool {
+        self.bus.unsubscribe(&self.plugin_id, id)
+    }
+}
+
+/// 主题只允许可见字符；订阅主题可以以 `*` 结尾
+fn validate_topic(topic: &str, allow_wildcard: bool) -> Result<(), PluginEventError> {
+    if topic.is_empty() || topic.len() > MAX_TOPIC_BYTES {
+        return Err(PluginEventError::InvalidTopic(format!(
+            "主题长度需在 1 到 {MAX_TOPIC_BYTES} 字节之间"
+        )));
+    }
+    if topic.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        return Err(PluginEventError::InvalidTopic(topic.to_string()));
+    }
+    let body = if allow_wildcard {
+        topic.strip_suffix('*').unwrap_or(topic)
+    } else {
+        topic
+    };
+    if body.contains('*') {
+        return Err(PluginEventError::InvalidTopic(topic.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_emit::EventEmit;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingEmitter(Mutex<Vec<(String, Value)>>);
+
+    impl EventEmit for Arc<RecordingEmitter> {
+        fn emit_event(&self, event: &str, payload: &Value) -> Result<(), String> {
+            self.0
+                .lock()
+                .unwrap()
+                .push((event.to_string(), payload.clone()));
+            Ok(())
+        }
+    }
+
+    fn recording_sink() -> (PluginEventSink, Arc<Mutex<Vec<(u64, PluginBusEvent)>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink_received = received.clone();
+        let sink: PluginEventSink = Arc::new(move |id: u64, event: &PluginBusEvent| {
+            sink_received.lock().unwrap().push((id, event.clone()));
+        });
+        (sink, received)
+    }
+
+    #[test]
+    fn test_publish_delivers_to_matching_subscribers_and_frontend() {
+        let bus = Arc::new(PluginEventBus::new());
+        let emitter = Arc::new(RecordingEmitter::default());
+        bus.set_emitter(DynEmitter::new(emitter.clone()));
+
+        let (sink, received) = recording_sink();
+        let subscriber = PluginEventClient::new(
+            bus.clone(),
+            "plugin-b",
+            vec![PluginPermission::EventSubscribe],
+            Some(sink),
+        );
+        let publisher = PluginEventClient::new(bus.clone(), "plugin-a", Vec::new(), None);
+
+        let id = subscriber.subscribe("quota.*").unwrap();
+        assert_eq!(
+            publisher
+                .emit("quota.low", serde_json::json!({ "left": 3 }))
+                .unwrap(),
+            1
+        );
+        assert_eq!(publisher.emit("other", Value::Null).unwrap(), 0);
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].0, id);
+        assert_eq!(received[0].1.source, "plugin-a");
+        assert_eq!(received[0].1.topic, "quota.low");
+
+        let forwarded = emitter.0.lock().unwrap();
+        assert_eq!(forwarded.len(), 2);
+        assert_eq!(forwarded[0].0, PLUGIN_BUS_EVENT);
+        assert_eq!(forwarded[0].1["payload"]["left"], 3);
+
+        // 只能取消自己的订阅
+        assert!(!publisher.unsubscribe(id));
+        assert!(subscriber.unsubscribe(id));
+        assert!(bus.subscriptions(None).is_empty());
+    }
+
+    #[test]
+    fn test_subscribe_requires_permission_and_sink() {
+        let bus = Arc::new(PluginEventBus::new());
+        let (sink, _) = recording_sink();
+
+        let no_permission = PluginEventClient::new(bus.clone(), "p", Vec::new(), Some(sink));
+        assert!(matches!(
+            no_permission.subscribe("topic"),
+            Err(PluginEventError::PermissionDenied(
+                PluginPermission::EventSubscribe
+            ))
+        ));
+
+        let no_sink = PluginEventClient::new(
+            bus.clone(),
+            "p",
+            vec![PluginPermission::EventSubscribe],
+            None,
+        );
+        assert!(matches!(
+            no_sink.subscribe("topic"),
+            Err(PluginEventError::Unsupported)
+        ));
+        assert!(matches!(
+            no_sink.emit("a*b", Value::Null),
+            Err(PluginEventError::InvalidTopic(_))
+        ));
+    }
+
+    #[test]
+    fn test_unsubscribe_plugin_removes_all() {
+        let bus = PluginEventBus::new();
+        let (sink, _) = recording_sink();
+        bus.subscribe("p", "a", sink.clone());
+        bus.subscribe("p", "b*", sink.clone());
+        bus.subscribe("q", "a", sink);
+
+        assert_eq!(bus.unsubscribe_plugin("p"), 2);
+        assert_eq!(bus.subscriptions(Some("q")).len(), 1);
+        assert_eq!(bus.publish("q", "a", Value::Null), 1);
+    }
+}
@@ -0,0 +1,223 @@
+//! 插件 UI 事件权限范围
+//!
+//! 插件 UI 只能收到其清单 `ui.events` 中声明的事件主题。事件在发射层经过
+//! [`PluginEventScope::authorize`] 检查后，才会投递到该插件专属的事件通道
+//! （[`plugin_event_channel`]）；被拒绝的投递会记录到审计日志中。
+//!
+//! 主题支持精确匹配与 `*` 后缀通配（如 `flow-*`、`mcp:*`）。
+//! 每个插件都隐式拥有自身的 UI 消息与任务事件（[`IMPLICIT_PLUGIN_TOPICS`]）。
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::RwLock;
+
+/// 插件隐式拥有的事件主题
+pub const IMPLICIT_PLUGIN_TOPICS: &[&str] = &["plugin-ui-message", "plugin-task-event"];
+
+/// 插件专属事件通道前缀
+pub const PLUGIN_EVENT_CHANNEL_PREFIX: &str = "plugin-event:";
+
+/// 审计日志保留的拒绝记录数
+const DENIED_AUDIT_CAPACITY: usize = 200;
+
+/// 插件专属事件通道名
+///
+/// Tauri 事件名只允许字母数字与 `-` `/` `:` `_`，其余字符替换为 `_`。
+pub fn plugin_event_channel(plugin_id: &str) -> String {
+    let sanitized: String = plugin_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | ':' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{PLUGIN_EVENT_CHANNEL_PREFIX}{sanitized}")
+}
+
+/// 被拒绝的事件投递记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeniedEventDelivery {
+    /// 目标插件 ID
+    pub plugin_id: String,
+    /// 事件名称
+    pub event: String,
+    /// 拒绝原因
+    pub reason: String,
+    /// 发生时间（RFC 3339）
+    pub timestamp: String,
+}
+
+/// 插件事件审计快照
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PluginEventAudit {
+    /// 累计拒绝次数
+    pub total_denied: u64,
+    /// 最近的拒绝记录（由旧到新）
+    pub recent: Vec<DeniedEventDelivery>,
+}
+
+#[derive(Debug, Default)]
+struct AuditLog {
+    total_denied: u64,
+    recent: VecDeque<DeniedEventDelivery>,
+}
+
+/// 插件事件权限范围注册表
+#[derive(Debug, Default)]
+pub struct PluginEventScope {
+    topics: RwLock<HashMap<String, Vec<String>>>,
+    audit: RwLock<AuditLog>,
+}
+
+impl PluginEventScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册插件声明的事件主题（覆盖已有声明）
+    pub fn register(&self, plugin_id: &str, topics: Vec<String>) {
+        let topics = topics
+            .into_iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if let Ok(mut map) = self.topics.write() {
+            map.insert(plugin_id.to_string(), topics);
+        }
+    }
+
+    /// 注销插件（插件卸载或禁用时调用）
+    pub fn unregister(&self, plugin_id: &str) {
+        if let Ok(mut map) = self.topics.write() {
+            map.remove(plugin_id);
+        }
+    }
+
+    /// 已注册的插件 ID
+    pub fn registered_plugins(&self) -> Vec<String> {
+        self.topics
+            .read()
+            .map(|map| map.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 插件是否允许接收该事件（不记录审计）
+    pub fn is_allowed(&self, plugin_id: &str, event: &str) -> bool {
+        if IMPLICIT_PLUGIN_TOPICS.contains(&event) {
+            return true;
+        }
+        self.topics
+            .read()
+            .ok()
+            .and_then(|map| {
+                map.get(plugin_id)
+                    .map(|topics| topics.iter().any(|t| topic_matches(t, event)))
+            })
+            .unwrap_or(false)
+    }
+
+    /// 检查投递权限，拒绝时写入审计日志
+    pub fn authorize(&self, plugin_id: &str, event: &str) -> bool {
+        if self.is_allowed(plugin_id, event) {
+            return true;
+        }
+
+        let registered = self
+            .topics
+            .read()
+            .map(|map| map.contains_key(plugin_id))
+            .unwrap_or(false);
+        let reason = if registered {
+            "事件不在插件声明的主题范围内"
+        } else {
+            "插件未注册事件权限"
+        };
+        tracing::debug!(
+            "[PluginEventScope] 拒绝投递: plugin={} event={} reason={}",
+            plugin_id,
+            event,
+            reason
+        );
+
+        if let Ok(mut audit) = self.audit.write() {
+            audit.total_denied += 1;
+            if audit.recent.len() >= DENIED_AUDIT_CAPACITY {
+                audit.recent.pop_front();
+            }
+            audit.recent.push_back(DeniedEventDelivery {
+                plugin_id: plugin_id.to_string(),
+                event: event.to_string(),
+                reason: reason.to_string(),
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            });
+        }
+        false
+    }
+
+    /// 获取审计快照，可按插件过滤
+    pub fn audit(&self, plugin_id: Option<&str>, limit: usize) -> PluginEventAudit {
+        let Ok(audit) = self.audit.read() else {
+            return PluginEventAudit::default();
+        };
+        let matching: Vec<&DeniedEventDelivery> = audit
+            .recent
+            .iter()
+            .filter(|d| plugin_id.is_none_or(|id| d.plugin_id == id))
+            .collect();
+        let skip = matching.len().saturating_sub(limit);
+        PluginEventAudit {
+            total_denied: audit.total_denied,
+            recent: matching.into_iter().skip(skip).cloned().collect(),
+        }
+    }
+}
+
+/// 主题匹配：精确匹配或 `*` 后缀通配
+pub(crate) fn topic_matches(topic: &str, event: &str) -> bool {
+    match topic.strip_suffix('*') {
+        Some(prefix) => event.starts_with(prefix),
+        None => topic == event,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declared_topics_are_enforced() {
+        let scope = PluginEventScope::new();
+        scope.register(
+            "stats-panel",
+            vec!["flow-*".to_string(), "server-status".to_string()],
+        );
+
+        assert!(scope.authorize("stats-panel", "flow-record-added"));
+        assert!(scope.authorize("stats-panel", "server-status"));
+        assert!(scope.authorize("stats-panel", "plugin-ui-message"));
+        assert!(!scope.authorize("stats-panel", "server-status-changed"));
+        assert!(!scope.authorize("stats-panel", "credential-updated"));
+        assert!(!scope.authorize("unknown", "flow-record-added"));
+
+        let audit = scope.audit(None, 10);
+        assert_eq!(audit.total_denied, 3);
+        assert_eq!(audit.recent[1].event, "credential-updated");
+        assert_eq!(audit.recent[2].reason, "插件未注册事件权限");
+        assert_eq!(scope.audit(Some("unknown"), 10).recent.len(), 1);
+
+        scope.unregister("stats-panel");
+        assert!(!scope.is_allowed("stats-panel", "server-status"));
+    }
+
+    #[test]
+    fn test_plugin_event_channel_is_sanitized() {
+        assert_eq!(plugin_event_channel("my-plugin"), "plugin-event:my-plugin");
+        assert_eq!(
+            plugin_event_channel("com.example plugin"),
+            "plugin-event:com_example_plugin"
+        );
+    }
+}
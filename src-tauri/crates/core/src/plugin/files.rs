@@ -0,0 +1,468 @@
+//! 插件文件沙箱
+//!
+//! 每个插件拥有独立的数据目录（`<plugin_data>/<plugin_id>`），
+//! 插件只能通过 `PluginFileSandbox` 读写该目录内的文件：
+//! - 读取 / 列目录需要声明 `FileSystemRead` 权限，写入需要 `FileSystemWrite` 权限
+//! - 只接受相对路径，拒绝绝对路径、`..` 以及指向目录外的符号链接
+//! - 写入时校验单文件大小与插件总配额
+
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::types::PluginPermission;
+
+/// 文件配额
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginFileQuota {
+    /// 单个文件的最大字节数
+    pub max_file_bytes: u64,
+    /// 单个插件数据目录的总字节数
+    pub max_total_bytes: u64,
+}
+
+impl Default for PluginFileQuota {
+    fn default() -> Self {
+        Self {
+            max_file_bytes: 10 * 1024 * 1024,
+            max_total_bytes: 100 * 1024 * 1024,
+        }
+    }
+}
+
+/// 插件文件访问错误
+#[derive(Debug, thiserror::Error)]
+pub enum PluginFsError {
+    #[error("插件未声明权限: {0:?}")]
+    PermissionDenied(PluginPermission),
+
+    #[error("无效的文件路径: {0}")]
+    InvalidPath(String),
+
+    #[error("文件不存在: {0}")]
+    NotFound(String),
+
+    #[error("文件过大: {size} 字节，上限 {limit} 字节")]
+    FileTooLarge { size: u64, limit: u64 },
+
+    #[error("插件文件配额不足: 写入后 {required} 字节，上限 {limit} 字节")]
+    QuotaExceeded { required: u64, limit: u64 },
+
+    #[error("文件读写失败: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// 目录项
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginFileEntry {
+    pub name: String,
+    pub is_dir: bool,
+    /// 文件大小（目录为 0）
+    pub size: u64,
+}
+
+/// 插件文件用量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginFileUsage {
+    pub files: usize,
+    pub used_bytes: u64,
+    pub quota_bytes: u64,
+}
+
+/// 绑定到单个插件数据目录的文件句柄
+#[derive(Debug, Clone)]
+pub struct PluginFileSandbox {
+    root: PathBuf,
+    plugin_id: String,
+    permissions: Vec<PluginPermission>,
+    quota: PluginFileQuota,
+}
+
+impl PluginFileSandbox {
+    /// 创建沙箱，数据目录为 `data_root/<plugin_id>`（首次写入时创建）
+    pub fn new(
+        data_root: &Path,
+        plugin_id: impl Into<String>,
+        permissions: Vec<PluginPermission>,
+    ) -> Self {
+        Self::with_quota(
+            data_root,
+            plugin_id,
+            permissions,
+            PluginFileQuota::default(),
+        )
+    }
+
+    pub fn with_quota(
+        data_root: &Path,
+        plugin_id: impl Into<String>,
+        permissions: Vec<PluginPermission>,
+        quota: PluginFileQuota,
+    ) -> Self {
+        let plugin_id = plugin_id.into();
+        Self {
+            root: data_root.join(&plugin_id),
+            plugin_id,
+            permissions,
+            quota,
+        }
+    }
+
+    pub fn plugin_id(&self) -> &str {
+        &self.plugin_id
+    }
+
+    /// 插件数据目录
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn require(&self, permission: PluginPermission) -> Result<(), PluginFsError> {
+        if self.permissions.contains(&permission) {
+            Ok(())
+        } else {
+            Err(PluginFsError::PermissionDenied(permission))
+        }
+    }
+
+    /// 将插件传入的相对路径解析为数据目录内的绝对路径
+    fn resolve(&self, path: &str) -> Result<PathBuf, PluginFsError> {
+        let relative = normalize_relative_path(path)?;
+        let full = self.root.join(relative);
+        self.ensure_within_root(&full)?;
+        Ok(full)
+    }
+
+    /// 检查路径中已存在的部分解析符号链接后仍位于数据目录内
+    fn ensure_within_root(&self, path: &Path) -> Result<(), PluginFsError> {
+        let Ok(root) = self.root.canonicalize() else {
+            // 数据目录尚未创建，其下不可能存在符号链接
+            return Ok(());
+        };
+
+        let mut existing = path;
+        while existing.symlink_metadata().is_err() {
+            match existing.parent() {
+                Some(parent) => existing = parent,
+                None => return Ok(()),
+            }
+        }
+        let canonical = existing
+            .canonicalize()
+            .map_err(|_| PluginFsError::InvalidPath("无法解析的符号链接".to_string()))?;
+        if !canonical.starts_with(&root) {
+            return Err(PluginFsError::InvalidPath(
+                "路径超出插件数据目录".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 读取文件内容
+    pub fn read(&self, path: &str) -> Result<Vec<u8>, PluginFsError> {
+        self.require(PluginPermission::FileSystemRead)?;
+        let full = self.resolve(path)?;
+        let metadata = match fs::metadata(&full) {
+            Ok(metadata) if metadata.is_file() => metadata,
+            _ => return Err(PluginFsError::NotFound(path.to_string())),
+        };
+        if metadata.len() > self.quota.max_file_bytes {
+            return Err(PluginFsError::FileTooLarge {
+                size: metadata.len(),
+                limit: self.quota.max_file_bytes,
+            });
+        }
+        Ok(fs::read(&full)?)
+    }
+
+    /// 写入文件（`append` 为 true 时追加），父目录不存在时自动创建
+    pub fn write(&self, path: &str, content: &[u8], append: bool) -> Result<(), PluginFsError> {
+        self.require(PluginPermission::FileSystemWrite)?;
+        let full = self.resolve(path)?;
+        if full.is_dir() {
+            return Err(PluginFsError::InvalidPath(format!("{path} 是目录")));
+        }
+
+        let existing = fs::metadata(&full).map(|m| m.len()).unwrap_or(0);
+        let new_size = if append {
+            existing + content.len() as u64
+        } else {
+            content.len() as u64
+        };
+        if new_size > self.quota.max_file_bytes {
+            return Err(PluginFsError::FileTooLarge {
+                size: new_size,
+                limit: self.quota.max_file_bytes,
+            });
+        }
+        // 覆盖已有文件时不重复计算旧内容
+        let required = self.used_bytes()?.saturating_sub(existing) + new_size;
+        if required > self.quota.max_total_bytes {
+            return Err(PluginFsError::QuotaExceeded {
+                required,
+                limit: self.quota.max_total_bytes,
+            });
+        }
+
+        if let Some(parent) = full.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // 创建目录后再检查一次，防止中间目录被替换为符号链接
+        self.ensure_within_root(&full)?;
+
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(append)
+            .truncate(!append)
+            .open(&full)?;
+        file.write_all(content)?;
+        Ok(())
+    }
+
+    /// 列出目录内容（`path` 为空时列出数据目录根），按名称排序
+    pub fn list(&self, path: &str) -> Result<Vec<PluginFileEntry>, PluginFsError> {
+        self.require(PluginPermission::FileSystemRead)?;
+        let full = if path.trim().is_empty() {
+            if !self.root.exists() {
+                return Ok(Vec::new());
+            }
+            self.root.clone()
+        } else {
+            self.resolve(path)?
+        };
+        if !full.is_dir() {
+            return Err(PluginFsError::NotFound(path.to_string()));
+        }
+
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&full)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            entries.push(PluginFileEntry {
+                name: entry.file_name().to_string_lossy().to_string(),
+                is_dir: metadata.is_dir(),
+                size: if metadata.is_file() {
+                    metadata.len()
+                } else {
+                    0
+                },
+            });
+        }
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(entries)
+    }
+
+    /// 数据目录用量
+    pub fn usage(&self) -> Result<PluginFileUsage, PluginFsError> {
+        let (files, used_bytes) = dir_usage(&self.root)?;
+        Ok(PluginFileUsage {
+            files,
+            used_bytes,
+            quota_bytes: self.quota.max_total_bytes,
+        })
+    }
+
+    fn used_bytes(&self) -> Result<u64, PluginFsError> {
+        Ok(dir_usage(&self.root)?.1)
+    }
+
+    /// 删除插件数据目录（卸载插件时调用）
+    pub fn remove_all(&self) -> Result<(), PluginFsError> {
+        match fs::remove_dir_all(&self.root) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// 规范化插件传入的相对路径，只允许普通路径段
+fn normalize_relative_path(path: &str) -> Result<PathBuf, PluginFsError> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(PluginFsError::InvalidPath("路径不能为空".to_string()));
+    }
+    if path.chars().any(char::is_control) {
+        return Err(PluginFsError::InvalidPath(
+            "路径不能包含控制字符".to_string(),
+        ));
+    }
+
+    let mut normalized = PathBuf::new();
+    for component in Path::new(&path.replace('\\', "/")).components() {
+        match component {
+            Component::Normal(part) => normalized.push(part),
+            Component::CurDir => {}
+            _ => {
+                return Err(PluginFsError::InvalidPath(format!(
+                    "只允许数据目录内的相对路径: {path}"
+                )))
+            }
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        return Err(PluginFsError::InvalidPath("路径不能为空".to_string()));
+    }
+    Ok(normalized)
+}
+
+/// 统计目录下的文件数与总字节数（不跟随符号链接）
+fn dir_usage(dir: &Path) -> std::io::Result<(usize, u64)> {
+    let mut files = 0;
+    let mut bytes = 0;
+    let read_dir = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok((0, 0)),
+        Err(e) => return Err(e),
+    };
+    for entry in read_dir {
+        let entry = entry?;
+        let metadata = entry.path().symlink_metadata()?;
+        if metadata.is_dir() {
+            let (sub_files, sub_bytes) = dir_usage(&entry.path())?;
+            files += sub_files;
+            bytes += sub_bytes;
+        } else if metadata.is_file() {
+            files += 1;
+            bytes += metadata.len();
+        }
+    }
+    Ok((files, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const READ_WRITE: [PluginPermission; 2] = [
+        PluginPermission::FileSystemRead,
+        PluginPermission::FileSystemWrite,
+    ];
+
+    #[test]
+    fn test_read_write_list_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let sandbox = PluginFileSandbox::new(temp.path(), "plugin-a", READ_WRITE.to_vec());
+        let other = PluginFileSandbox::new(temp.path(), "plugin-b", READ_WRITE.to_vec());
+
+        assert!(sandbox.list("").unwrap().is_empty());
+        sandbox.write("cache/data.json", b"{}", false).unwrap();
+        sandbox.write("log.txt", b"a", false).unwrap();
+        sandbox.write("log.txt", b"b", true).unwrap();
+
+        assert_eq!(sandbox.read("log.txt").unwrap(), b"ab");
+        assert_eq!(sandbox.read("./cache/data.json").unwrap(), b"{}");
+        assert!(matches!(
+            other.read("log.txt"),
+            Err(PluginFsError::NotFound(_))
+        ));
+
+        let names: Vec<_> = sandbox
+            .list("")
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.name, e.is_dir))
+            .collect();
+        assert_eq!(
+            names,
+            vec![("cache".to_string(), true), ("log.txt".to_string(), false)]
+        );
+        assert_eq!(sandbox.usage().unwrap().used_bytes, 4);
+    }
+
+    #[test]
+    fn test_permissions_enforced() {
+        let temp = TempDir::new().unwrap();
+        let read_only = PluginFileSandbox::new(
+            temp.path(),
+            "plugin-a",
+            vec![PluginPermission::FileSystemRead],
+        );
+        let none = PluginFileSandbox::new(temp.path(), "plugin-a", Vec::new());
+
+        assert!(matches!(
+            read_only.write("a.txt", b"x", false),
+            Err(PluginFsError::PermissionDenied(
+                PluginPermission::FileSystemWrite
+            ))
+        ));
+        assert!(matches!(
+            none.list(""),
+            Err(PluginFsError::PermissionDenied(
+                PluginPermission::FileSystemRead
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_path_traversal_rejected() {
+        let temp = TempDir::new().unwrap();
+        let sandbox = PluginFileSandbox::new(temp.path(), "plugin-a", READ_WRITE.to_vec());
+        fs::write(temp.path().join("secret.txt"), "secret").unwrap();
+
+        for path in [
+            "../secret.txt",
+            "a/../../secret.txt",
+            "/etc/passwd",
+            "..\\secret.txt",
+            "",
+        ] {
+            assert!(
+                matches!(sandbox.read(path), Err(PluginFsError::InvalidPath(_))),
+                "{path}"
+            );
+        }
+
+        #[cfg(unix)]
+        {
+            sandbox.write("keep.txt", b"", false).unwrap();
+            std::os::unix::fs::symlink(temp.path(), sandbox.root().join("escape")).unwrap();
+            assert!(matches!(
+                sandbox.read("escape/secret.txt"),
+                Err(PluginFsError::InvalidPath(_))
+            ));
+            assert!(matches!(
+                sandbox.write("escape/new.txt", b"x", false),
+                Err(PluginFsError::InvalidPath(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_quota_enforced() {
+        let temp = TempDir::new().unwrap();
+        let sandbox = PluginFileSandbox::with_quota(
+            temp.path(),
+            "plugin-a",
+            READ_WRITE.to_vec(),
+            PluginFileQuota {
+                max_file_bytes: 8,
+                max_total_bytes: 12,
+            },
+        );
+
+        assert!(matches!(
+            sandbox.write("big.bin", &[0; 9], false),
+            Err(PluginFsError::FileTooLarge { size: 9, .. })
+        ));
+        sandbox.write("a.bin", &[0; 8], false).unwrap();
+        assert!(matches!(
+            sandbox.write("b.bin", &[0; 8], false),
+            Err(PluginFsError::QuotaExceeded { required: 16, .. })
+        ));
+        // 覆盖已有文件时不重复计算旧内容
+        sandbox.write("a.bin", &[1; 8], false).unwrap();
+        assert!(matches!(
+            sandbox.write("a.bin", &[1; 1], true),
+            Err(PluginFsError::FileTooLarge { size: 9, .. })
+        ));
+        assert_eq!(sandbox.usage().unwrap().used_bytes, 8);
+
+        sandbox.remove_all().unwrap();
+        assert!(!sandbox.root().exists());
+    }
+}
@@ -4,7 +4,9 @@
 
 use std::path::Path;
 
+use super::release::LatestRelease;
 use super::types::{GitHubRelease, InstallError, InstallProgress, ProgressCallback};
+use crate::plugin::binary_downloader::ReleaseAsset;
 
 /// 插件下载器
 ///
@@ -102,6 +104,90 @@ impl PluginDownloader {
         Ok(())
     }
 
+    /// 获取仓库的最新 release
+    pub async fn fetch_latest_release(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<LatestRelease, InstallError> {
+        let release = GitHubRelease {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            tag: "latest".to_string(),
+            asset_name: None,
+        };
+        let response = self
+            .client
+            .get(release.latest_api_url())
+            .header("User-Agent", "Lime-Plugin-Installer")
+            .header("Accept", "application/vnd.github.v3+json")
+            .send()
+            .await
+            .map_err(|e| InstallError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(InstallError::DownloadFailed(format!(
+                "获取 {owner}/{repo} 最新 release 失败: HTTP {}",
+                response.status()
+            )));
+        }
+
+        let data: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| InstallError::NetworkError(e.to_string()))?;
+
+        let tag = data["tag_name"]
+            .as_str()
+            .filter(|tag| !tag.is_empty())
+            .ok_or_else(|| InstallError::DownloadFailed("release 缺少 tag_name".to_string()))?
+            .to_string();
+        let assets = data["assets"]
+            .as_array()
+            .map(|assets| {
+                assets
+                    .iter()
+                    .filter_map(|a| {
+                        Some(ReleaseAsset {
+                            name: a["name"].as_str()?.to_string(),
+                            download_url: a["browser_download_url"].as_str()?.to_string(),
+                            size: a["size"].as_u64().unwrap_or(0),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(LatestRelease {
+            version: tag.trim_start_matches('v').to_string(),
+            tag,
+            assets,
+        })
+    }
+
+    /// 下载文本内容（如校验文件）
+    pub async fn fetch_text(&self, url: &str) -> Result<String, InstallError> {
+        let response = self
+            .client
+            .get(url)
+            .header("User-Agent", "Lime-Plugin-Installer")
+            .send()
+            .await
+            .map_err(|e| InstallError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(InstallError::DownloadFailed(format!(
+                "HTTP 错误: {}",
+                response.status()
+            )));
+        }
+
+        response
+            .text()
+            .await
+            .map_err(|e| InstallError::NetworkError(e.to_string()))
+    }
+
     /// 解析 GitHub release URL
     ///
     /// 支持以下格式:
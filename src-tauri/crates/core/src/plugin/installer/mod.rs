@@ -3,6 +3,7 @@
 //! 提供插件安装、卸载和管理功能：
 //! - 从本地文件安装插件
 //! - 从 URL（如 GitHub releases）下载安装插件
+//! - 从 GitHub 最新 release 安装、检查更新
 //! - 插件包验证
 //! - 插件注册表管理
 //! - 安装进度回调
@@ -10,12 +11,16 @@
 mod downloader;
 mod plugin_installer;
 mod registry;
+mod release;
 mod types;
 mod validator;
 
 pub use downloader::PluginDownloader;
 pub use plugin_installer::PluginInstaller;
 pub use registry::PluginRegistry;
+pub use release::{
+    check_min_lime_version, compare_versions, parse_checksums, LatestRelease, PluginUpdateInfo,
+};
 pub use types::{
     GitHubRelease, InstallError, InstallProgress, InstallSource, InstallStage, InstalledPlugin,
     NoopProgressCallback, PackageFormat, ProgressCallback,
@@ -3,6 +3,8 @@
 //! 提供插件安装、卸载的核心逻辑：
 //! - install_from_file: 从本地文件安装
 //! - install_from_url: 从 URL 下载安装
+//! - install_from_github: 从 GitHub 最新 release 安装（校验 release 中的校验文件）
+//! - check_update / update_from_github: 检查并安装新版本
 //! - uninstall: 卸载插件
 //!
 //! _需求: 1.1, 1.2, 1.3, 2.1, 2.2, 4.2_
@@ -15,10 +17,15 @@ use rusqlite::Connection;
 
 use super::downloader::PluginDownloader;
 use super::registry::PluginRegistry;
+use super::release::{
+    check_min_lime_version, compare_versions, parse_checksums, select_checksum_asset,
+    select_package_asset, PluginUpdateInfo,
+};
 use super::types::{
     InstallError, InstallProgress, InstallSource, InstalledPlugin, PackageFormat, ProgressCallback,
 };
 use super::validator::PackageValidator;
+use crate::plugin::{BinaryDownloader, PluginManifest};
 
 /// 插件安装器
 ///
@@ -34,6 +41,8 @@ pub struct PluginInstaller {
     downloader: PluginDownloader,
     /// 验证器
     validator: PackageValidator,
+    /// 当前 Lime 版本，用于检查 `min_lime_version`（未设置时不检查）
+    app_version: Option<String>,
 }
 
 impl PluginInstaller {
@@ -45,6 +54,7 @@ impl PluginInstaller {
             registry: PluginRegistry::new(db_conn),
             downloader: PluginDownloader::new(),
             validator: PackageValidator::new(),
+            app_version: None,
         }
     }
 
@@ -63,9 +73,16 @@ impl PluginInstaller {
             registry,
             downloader: PluginDownloader::new(),
             validator: PackageValidator::new(),
+            app_version: None,
         })
     }
 
+    /// 设置当前 Lime 版本，安装时拒绝要求更高版本的插件
+    pub fn with_app_version(mut self, app_version: impl Into<String>) -> Self {
+        self.app_version = Some(app_version.into());
+        self
+    }
+
     /// 从本地文件安装插件
     ///
     /// 流程: 验证 → 解压 → 注册 → 复制文件
@@ -83,6 +100,7 @@ impl PluginInstaller {
         // 阶段 2: 提取并验证清单
         progress.on_progress(InstallProgress::validating("验证清单文件..."));
         let manifest = self.validator.extract_and_validate_manifest(path, format)?;
+        self.check_compatibility(&manifest)?;
 
         // 检查插件是否已存在，如果存在则先清理旧版本
         if self.registry.exists(&manifest.name)? {
@@ -134,6 +152,18 @@ impl PluginInstaller {
         &self,
         url: &str,
         progress: &dyn ProgressCallback,
+    ) -> Result<InstalledPlugin, InstallError> {
+        self.install_from_url_verified(url, None, progress).await
+    }
+
+    /// 从 URL 安装插件，并在解压前校验包的 SHA256
+    ///
+    /// 用于扩展注册表安装：校验和来自已签名的索引。
+    pub async fn install_from_url_verified(
+        &self,
+        url: &str,
+        checksum: Option<&str>,
+        progress: &dyn ProgressCallback,
     ) -> Result<InstalledPlugin, InstallError> {
         // 确保临时目录存在
         fs::create_dir_all(&self.temp_dir)?;
@@ -143,6 +173,10 @@ impl PluginInstaller {
         self.downloader
             .download(url, &download_path, progress)
             .await?;
+        if let Err(e) = self.validator.validate_integrity(&download_path, checksum) {
+            let _ = fs::remove_file(&download_path);
+            return Err(e);
+        }
 
         // 阶段 2: 验证包格式
         progress.on_progress(InstallProgress::validating("验证包格式..."));
@@ -150,9 +184,17 @@ impl PluginInstaller {
 
         // 阶段 3: 提取并验证清单
         progress.on_progress(InstallProgress::validating("验证清单文件..."));
-        let manifest = self
+        let manifest = match self
             .validator
-            .extract_and_validate_manifest(&download_path, format)?;
+            .extract_and_validate_manifest(&download_path, format)
+            .and_then(|manifest| self.check_compatibility(&manifest).map(|()| manifest))
+        {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                let _ = fs::remove_file(&download_path);
+                return Err(e);
+            }
+        };
 
         // 检查插件是否已存在，如果存在则先清理旧版本
         if self.registry.exists(&manifest.name)? {
@@ -208,6 +250,143 @@ impl PluginInstaller {
         Ok(installed_plugin)
     }
 
+    /// 从 GitHub 仓库的最新 release 安装插件
+    ///
+    /// 选择当前平台的插件包，并用 release 中的校验文件校验 SHA256；
+    /// release 没有校验文件或校验文件中没有该插件包时拒绝安装。
+    pub async fn install_from_github(
+        &self,
+        owner: &str,
+        repo: &str,
+        checksum_file: Option<&str>,
+        progress: &dyn ProgressCallback,
+    ) -> Result<InstalledPlugin, InstallError> {
+        progress.on_progress(InstallProgress::downloading(
+            0,
+            format!("获取 {owner}/{repo} 最新版本..."),
+        ));
+        let release = self.downloader.fetch_latest_release(owner, repo).await?;
+
+        let platform_key = BinaryDownloader::get_platform_key();
+        let package = select_package_asset(&release.assets, platform_key).ok_or_else(|| {
+            InstallError::UnsupportedPlatform(format!(
+                "{owner}/{repo} {} 中没有适用于 {platform_key} 的插件包",
+                release.tag
+            ))
+        })?;
+        let checksum_asset = select_checksum_asset(&release.assets, checksum_file, &package.name)
+            .ok_or_else(|| {
+            InstallError::ValidationFailed(format!("{owner}/{repo} {} 缺少校验文件", release.tag))
+        })?;
+
+        progress.on_progress(InstallProgress::validating("下载校验文件..."));
+        let content = self
+            .downloader
+            .fetch_text(&checksum_asset.download_url)
+            .await?;
+        let checksum = parse_checksums(&content)
+            .remove(&package.name)
+            .or_else(|| {
+                // `<包名>.sha256` 可能只包含哈希本身
+                content
+                    .split_whitespace()
+                    .next()
+                    .filter(|_| checksum_asset.name == format!("{}.sha256", package.name))
+                    .map(str::to_lowercase)
+            })
+            .ok_or_else(|| {
+                InstallError::ValidationFailed(format!(
+                    "校验文件 {} 中没有 {} 的校验和",
+                    checksum_asset.name, package.name
+                ))
+            })?;
+
+        self.install_from_url_verified(&package.download_url, Some(&checksum), progress)
+            .await
+    }
+
+    /// 检查从 GitHub 安装的插件是否有新版本
+    pub async fn check_update(&self, plugin_id: &str) -> Result<PluginUpdateInfo, InstallError> {
+        let plugin = self
+            .registry
+            .get(plugin_id)?
+            .ok_or_else(|| InstallError::NotFound(plugin_id.to_string()))?;
+        let (owner, repo, _) = self.release_repository(&plugin)?;
+        let release = self.downloader.fetch_latest_release(&owner, &repo).await?;
+
+        Ok(PluginUpdateInfo {
+            plugin_id: plugin.id,
+            has_update: compare_versions(&release.version, &plugin.version).is_gt(),
+            installed_version: plugin.version,
+            latest_version: release.version,
+            tag: release.tag,
+            repository: format!("{owner}/{repo}"),
+        })
+    }
+
+    /// 更新从 GitHub 安装的插件到最新 release
+    ///
+    /// 已是最新版本时返回 `None`。
+    pub async fn update_from_github(
+        &self,
+        plugin_id: &str,
+        progress: &dyn ProgressCallback,
+    ) -> Result<Option<InstalledPlugin>, InstallError> {
+        let update = self.check_update(plugin_id).await?;
+        if !update.has_update {
+            progress.on_progress(InstallProgress::complete(format!(
+                "插件 {} 已是最新版本 v{}",
+                plugin_id, update.installed_version
+            )));
+            return Ok(None);
+        }
+
+        let plugin = self
+            .registry
+            .get(plugin_id)?
+            .ok_or_else(|| InstallError::NotFound(plugin_id.to_string()))?;
+        let (owner, repo, checksum_file) = self.release_repository(&plugin)?;
+        self.install_from_github(&owner, &repo, checksum_file.as_deref(), progress)
+            .await
+            .map(Some)
+    }
+
+    /// 插件的 release 来源仓库
+    ///
+    /// 优先使用清单中 Binary 组件声明的仓库，其次是 GitHub 安装来源。
+    fn release_repository(
+        &self,
+        plugin: &InstalledPlugin,
+    ) -> Result<(String, String, Option<String>), InstallError> {
+        let binary = fs::read_to_string(plugin.install_path.join("plugin.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str::<PluginManifest>(&content).ok())
+            .and_then(|manifest| manifest.binary);
+        if let Some(binary) = binary {
+            return Ok((
+                binary.github_owner,
+                binary.github_repo,
+                binary.checksum_file,
+            ));
+        }
+
+        match &plugin.source {
+            InstallSource::GitHub { owner, repo, .. } => Ok((owner.clone(), repo.clone(), None)),
+            _ => Err(InstallError::ValidationFailed(format!(
+                "插件 {} 不是从 GitHub release 安装的，无法检查更新",
+                plugin.id
+            ))),
+        }
+    }
+
+    /// 检查插件与当前 Lime 版本的兼容性
+    fn check_compatibility(&self, manifest: &PluginManifest) -> Result<(), InstallError> {
+        match &self.app_version {
+            Some(app_version) => check_min_lime_version(manifest, app_version),
+            None => Ok(()),
+        }
+    }
+
     /// 卸载插件
     ///
     /// 流程: 删除文件 → 清理数据目录 → 注销注册表
@@ -680,6 +859,45 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_install_rejects_incompatible_min_lime_version() {
+        let (installer, plugins_dir, temp_dir, _db_dir) = create_test_installer();
+        let installer = installer.with_app_version("0.90.0");
+
+        let package_path = temp_dir.path().join("future-plugin.zip");
+        let mut zip = zip::ZipWriter::new(File::create(&package_path).unwrap());
+        let options =
+            zip::write::FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        zip.start_file("plugin.json", options).unwrap();
+        zip.write_all(
+            br#"{
+                "name": "future-plugin",
+                "version": "1.0.0",
+                "description": "Test plugin",
+                "entry": "config.json",
+                "plugin_type": "script",
+                "min_lime_version": "0.95.0"
+            }"#,
+        )
+        .unwrap();
+        zip.finish().unwrap();
+
+        let result = installer
+            .install_from_file(&package_path, &NoopProgressCallback)
+            .await;
+        match result {
+            Err(InstallError::IncompatibleVersion {
+                required, current, ..
+            }) => {
+                assert_eq!(required, "0.95.0");
+                assert_eq!(current, "0.90.0");
+            }
+            other => panic!("期望 IncompatibleVersion 错误，实际: {other:?}"),
+        }
+        assert!(!plugins_dir.path().join("future-plugin").exists());
+        assert!(!installer.is_installed("future-plugin").unwrap());
+    }
+
     #[tokio::test]
     async fn test_uninstall_success() {
         let (installer, plugins_dir, temp_dir, _db_dir) = create_test_installer();
@@ -0,0 +1,235 @@
+//! GitHub Release 插件安装辅助
+//!
+//! 从 GitHub 最新 release 安装、更新插件：
+//! - 选择与当前平台匹配的插件包（zip / tar.gz）
+//! - 解析 release 中的校验文件，得到插件包的 SHA256
+//! - 版本比较与 `min_lime_version` 兼容性检查
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::types::{InstallError, PackageFormat};
+use crate::plugin::binary_downloader::ReleaseAsset;
+use crate::plugin::PluginManifest;
+
+/// 未指定校验文件名时依次尝试的文件名
+const DEFAULT_CHECKSUM_FILES: &[&str] = &["checksums.txt", "SHA256SUMS", "sha256sums.txt"];
+
+/// 所有平台标识，用于排除其他平台的插件包
+const PLATFORM_KEYS: &[&str] = &[
+    "macos-arm64",
+    "macos-x64",
+    "linux-x64",
+    "linux-arm64",
+    "windows-x64",
+];
+
+/// GitHub 最新 release
+#[derive(Debug, Clone)]
+pub struct LatestRelease {
+    /// release tag（如 `v1.2.0`）
+    pub tag: String,
+    /// 去掉 `v` 前缀的版本号
+    pub version: String,
+    /// release 附件
+    pub assets: Vec<ReleaseAsset>,
+}
+
+/// 插件更新检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginUpdateInfo {
+    pub plugin_id: String,
+    /// 已安装版本
+    pub installed_version: String,
+    /// 最新 release 版本
+    pub latest_version: String,
+    /// 最新 release tag
+    pub tag: String,
+    /// 插件来源仓库（owner/repo）
+    pub repository: String,
+    /// 是否有新版本
+    pub has_update: bool,
+}
+
+/// 比较两个版本号
+///
+/// 按数字段逐段比较，缺失的段视为 0；版本号相同时带预发布后缀（`-beta`）的更旧。
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_core, a_pre) = split_version(a);
+    let (b_core, b_pre) = split_version(b);
+
+    let len = a_core.len().max(b_core.len());
+    for i in 0..len {
+        let x = a_core.get(i).copied().unwrap_or(0);
+        let y = b_core.get(i).copied().unwrap_or(0);
+        match x.cmp(&y) {
+            Ordering::Equal => continue,
+            other => return other,
+        }
+    }
+
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(x), Some(y)) => x.cmp(y),
+    }
+}
+
+fn split_version(version: &str) -> (Vec<u64>, Option<&str>) {
+    let version = version.trim().trim_start_matches('v');
+    let (core, pre) = match version.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (version, None),
+    };
+    let core = core
+        .split('+')
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse::<u64>().unwrap_or(0))
+        .collect();
+    (core, pre)
+}
+
+/// 检查插件要求的最低 Lime 版本
+pub fn check_min_lime_version(
+    manifest: &PluginManifest,
+    app_version: &str,
+) -> Result<(), InstallError> {
+    let Some(required) = manifest.min_lime_version.as_deref() else {
+        return Ok(());
+    };
+    if compare_versions(app_version, required) == Ordering::Less {
+        return Err(InstallError::IncompatibleVersion {
+            plugin: manifest.name.clone(),
+            required: required.to_string(),
+            current: app_version.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// 解析校验文件内容（`<sha256>  <filename>` 或 `<sha256> *<filename>`）
+pub fn parse_checksums(content: &str) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let filename = parts.next()?.trim_start_matches('*');
+            Some((filename.to_string(), hash.to_lowercase()))
+        })
+        .collect()
+}
+
+/// 选择当前平台的插件包
+///
+/// 优先选择文件名包含平台标识的包，其次是不带任何平台标识的通用包。
+pub fn select_package_asset<'a>(
+    assets: &'a [ReleaseAsset],
+    platform_key: &str,
+) -> Option<&'a ReleaseAsset> {
+    let packages: Vec<&ReleaseAsset> = assets
+        .iter()
+        .filter(|asset| PackageFormat::from_extension(Path::new(&asset.name)).is_some())
+        .collect();
+
+    packages
+        .iter()
+        .find(|asset| asset.name.contains(platform_key))
+        .or_else(|| {
+            packages
+                .iter()
+                .find(|asset| !PLATFORM_KEYS.iter().any(|key| asset.name.contains(key)))
+        })
+        .copied()
+}
+
+/// 选择校验文件
+///
+/// 依次尝试清单指定的文件名、常见的汇总校验文件和 `<包名>.sha256`。
+pub fn select_checksum_asset<'a>(
+    assets: &'a [ReleaseAsset],
+    preferred: Option<&str>,
+    package_name: &str,
+) -> Option<&'a ReleaseAsset> {
+    let package_checksum = format!("{package_name}.sha256");
+    preferred
+        .into_iter()
+        .chain(DEFAULT_CHECKSUM_FILES.iter().copied())
+        .chain(std::iter::once(package_checksum.as_str()))
+        .find_map(|name| assets.iter().find(|asset| asset.name == name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> ReleaseAsset {
+        ReleaseAsset {
+            name: name.to_string(),
+            download_url: format!("https://github.com/o/r/releases/download/v1.0.0/{name}"),
+            size: 0,
+        }
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.2.0", "1.10.0"), Ordering::Less);
+        assert_eq!(compare_versions("v1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.2.1", "1.2.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.2.0-beta", "1.2.0"), Ordering::Less);
+        assert_eq!(compare_versions("2.0.0-beta", "1.9.9"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_parse_checksums() {
+        let checksums =
+            parse_checksums("ABCDEF  plugin-linux-x64.zip\n0123 *plugin.tar.gz\n\ninvalid-line\n");
+        assert_eq!(
+            checksums.get("plugin-linux-x64.zip").map(String::as_str),
+            Some("abcdef")
+        );
+        assert_eq!(
+            checksums.get("plugin.tar.gz").map(String::as_str),
+            Some("0123")
+        );
+        assert_eq!(checksums.len(), 2);
+    }
+
+    #[test]
+    fn test_select_assets() {
+        let assets = vec![
+            asset("plugin-macos-arm64.zip"),
+            asset("plugin-linux-x64.zip"),
+            asset("plugin.zip"),
+            asset("plugin-server-linux-x64"),
+            asset("checksums.txt"),
+            asset("plugin.zip.sha256"),
+        ];
+
+        assert_eq!(
+            select_package_asset(&assets, "linux-x64").map(|a| a.name.as_str()),
+            Some("plugin-linux-x64.zip")
+        );
+        assert_eq!(
+            select_package_asset(&assets, "windows-x64").map(|a| a.name.as_str()),
+            Some("plugin.zip")
+        );
+
+        assert_eq!(
+            select_checksum_asset(&assets, None, "plugin.zip").map(|a| a.name.as_str()),
+            Some("checksums.txt")
+        );
+        assert_eq!(
+            select_checksum_asset(&assets, Some("missing.txt"), "plugin.zip")
+                .map(|a| a.name.as_str()),
+            Some("checksums.txt")
+        );
+        assert!(select_checksum_asset(&assets[..3], None, "plugin.zip").is_none());
+    }
+}
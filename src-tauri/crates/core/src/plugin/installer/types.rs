@@ -74,6 +74,14 @@ pub enum InstallError {
     /// 不支持的平台
     #[error("不支持的平台: {0}")]
     UnsupportedPlatform(String),
+
+    /// 插件要求更高的 Lime 版本
+    #[error("插件 {plugin} 需要 Lime {required} 或更高版本，当前版本 {current}")]
+    IncompatibleVersion {
+        plugin: String,
+        required: String,
+        current: String,
+    },
 }
 
 /// 安装阶段
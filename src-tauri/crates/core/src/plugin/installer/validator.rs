@@ -231,6 +231,11 @@ impl PackageValidator {
             }
         }
 
+        // 验证健康探测声明
+        for probe in &manifest.health_probes {
+            probe.validate().map_err(InstallError::InvalidManifest)?;
+        }
+
         Ok(())
     }
 
@@ -415,6 +420,9 @@ mod tests {
             min_lime_version: None,
             binary: None,
             ui: None,
+            permissions: Vec::new(),
+            database: None,
+            health_probes: Vec::new(),
         }
     }
 
@@ -961,6 +969,9 @@ mod property_tests {
                 min_lime_version: None,
                 binary: None,
                 ui: None,
+                permissions: Vec::new(),
+                database: None,
+                health_probes: Vec::new(),
             };
 
             let validator = PackageValidator::new();
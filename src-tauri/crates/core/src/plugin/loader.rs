@@ -74,6 +74,9 @@ impl PluginLoader {
             PluginType::Binary => Err(PluginError::LoadError(
                 "二进制组件不通过插件加载器加载".to_string(),
             )),
+            PluginType::Wasm => Err(PluginError::LoadError(
+                "WASM 插件通过插件 RPC 运行时加载".to_string(),
+            )),
         }
     }
 
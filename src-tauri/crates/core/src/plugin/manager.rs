@@ -54,6 +54,19 @@ impl Default for PluginManagerConfig {
     }
 }
 
+/// 插件目录同步结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PluginReloadAction {
+    /// 新插件已加载
+    Loaded(String),
+    /// 已加载的插件被重新加载
+    Reloaded(String),
+    /// 插件目录已删除，插件已卸载
+    Unloaded(String),
+    /// 目录不是脚本插件或插件系统已禁用，无需处理
+    Skipped,
+}
+
 /// 插件管理器
 pub struct PluginManager {
     /// 插件加载器
@@ -143,7 +156,10 @@ impl PluginManager {
             )));
         }
 
-        let config = PluginConfig::default();
+        // 沿用已保存的插件配置（重新加载时保留启用状态与设置）
+        let manifest = self.loader.load_manifest(plugin_dir).await?;
+        let saved_config = self.get_config(&manifest.name);
+        let config = saved_config.clone().unwrap_or_default();
         let plugin = self.loader.load(plugin_dir, &config).await?;
         let name = plugin.name().to_string();
 
@@ -164,7 +180,10 @@ impl PluginManager {
             instance.state.status = PluginStatus::Error;
             instance.state.last_error = Some(e.to_string());
         } else {
-            instance.state.status = PluginStatus::Enabled;
+            instance.state.status = match saved_config {
+                Some(saved) if !saved.enabled => PluginStatus::Disabled,
+                _ => PluginStatus::Enabled,
+            };
         }
 
         self.plugins
@@ -172,6 +191,58 @@ impl PluginManager {
         Ok(name)
     }
 
+    /// 重新加载插件
+    ///
+    /// 先校验新的清单，避免写入中途的清单导致运行中的插件被卸载；
+    /// 随后关闭旧实例并从原目录重新加载，插件配置保持不变。
+    pub async fn reload(&self, name: &str) -> Result<String, PluginError> {
+        let path = {
+            let instance = self
+                .plugins
+                .get(name)
+                .ok_or_else(|| PluginError::NotFound(name.to_string()))?;
+            let inst = instance.read().await;
+            inst.path.clone()
+        };
+
+        self.loader.load_manifest(&path).await?;
+        self.unload(name).await?;
+        self.load(&path).await
+    }
+
+    /// 按插件目录的当前内容同步插件
+    ///
+    /// 供插件目录监控调用：目录中有清单则加载或重新加载，清单消失则卸载。
+    pub async fn sync_dir(&self, plugin_dir: &Path) -> Result<PluginReloadAction, PluginError> {
+        if !self.config.enabled {
+            return Ok(PluginReloadAction::Skipped);
+        }
+
+        let loaded_name = self.name_for_dir(plugin_dir).await;
+        let has_manifest = plugin_dir.join("manifest.json").exists();
+
+        match (loaded_name, has_manifest) {
+            (Some(name), true) => self.reload(&name).await.map(PluginReloadAction::Reloaded),
+            (Some(name), false) => {
+                self.unload(&name).await?;
+                Ok(PluginReloadAction::Unloaded(name))
+            }
+            (None, true) => self.load(plugin_dir).await.map(PluginReloadAction::Loaded),
+            (None, false) => Ok(PluginReloadAction::Skipped),
+        }
+    }
+
+    /// 查找从指定目录加载的插件名称
+    async fn name_for_dir(&self, plugin_dir: &Path) -> Option<String> {
+        for entry in self.plugins.iter() {
+            let inst = entry.value().read().await;
+            if inst.path == plugin_dir {
+                return Some(entry.key().clone());
+            }
+        }
+        None
+    }
+
     /// 卸载插件
     pub async fn unload(&self, name: &str) -> Result<(), PluginError> {
         let instance = self
@@ -8,34 +8,67 @@
 //! - 二进制组件下载和管理
 //! - 声明式插件 UI 系统
 //! - 插件安装和卸载
+//! - 插件键值存储
+//! - 插件数据表
+//! - 插件事件总线
+//! - 插件文件沙箱
+//! - 插件目录监控与热重载
+//! - WASM 插件运行时
 
 pub mod binary_downloader;
+pub mod database;
+pub mod event_bus;
+pub mod event_scope;
 pub mod examples;
+pub mod files;
 pub mod installer;
 mod loader;
 mod manager;
+pub mod storage;
 mod task;
 mod types;
 pub mod ui_builder;
 pub mod ui_trait;
 pub mod ui_types;
+#[cfg(feature = "wasm-plugins")]
+pub mod wasm;
+pub mod watcher;
 
 pub use binary_downloader::BinaryDownloader;
+pub use database::{
+    PluginColumnSchema, PluginColumnType, PluginDatabase, PluginDatabaseSchema, PluginDbError,
+    PluginDbQuery, PluginDbWrite, PluginDbWriteResult, PluginTableSchema,
+};
+pub use event_bus::{
+    PluginBusEvent, PluginEventBus, PluginEventClient, PluginEventError, PluginEventSink,
+    PluginEventSubscription, PLUGIN_BUS_EVENT,
+};
+pub use event_scope::{
+    plugin_event_channel, DeniedEventDelivery, PluginEventAudit, PluginEventScope,
+};
+pub use files::{
+    PluginFileEntry, PluginFileQuota, PluginFileSandbox, PluginFileUsage, PluginFsError,
+};
 pub use loader::PluginLoader;
-pub use manager::PluginManager;
+pub use manager::{PluginManager, PluginManagerConfig, PluginReloadAction};
+pub use storage::{PluginStorage, PluginStorageError, PluginStorageQuota, PluginStorageUsage};
 pub use task::{
     PluginQueueStats, PluginTaskError, PluginTaskEventPayload, PluginTaskFailure, PluginTaskPolicy,
     PluginTaskRecord, PluginTaskState, PluginTaskTracker,
 };
 pub use types::{
     BinaryComponentStatus, BinaryManifest, HookResult, PlatformBinaries, Plugin, PluginConfig,
-    PluginContext, PluginError, PluginInfo, PluginManifest, PluginState, PluginStatus, PluginType,
+    PluginContext, PluginError, PluginInfo, PluginManifest, PluginPermission, PluginState,
+    PluginStatus, PluginType,
 };
 pub use ui_trait::{NoUI, PluginUI};
 pub use ui_types::{
     Action, BoundValue, ChildrenDef, ComponentDef, ComponentType, DataEntry, DataModelUpdate,
     SurfaceDefinition, SurfaceUpdate, UIMessage, UserAction,
 };
+#[cfg(feature = "wasm-plugins")]
+pub use wasm::{WasmHostHandler, WasmLimits, WasmPlugin, WasmPluginError};
+pub use watcher::{PluginDirChange, PluginDirChangeKind, PluginDirWatcher};
 
 #[cfg(test)]
 mod tests;
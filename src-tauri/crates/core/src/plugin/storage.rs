@@ -0,0 +1,258 @@
+//! 插件持久化存储
+//!
+//! 每个插件拥有独立的键值命名空间（`plugin_storage` 表按 plugin_id 隔离），
+//! 插件只能通过绑定了自身 ID 的 `PluginStorage` 访问数据，无法读写其他插件的键。
+//! 值以 JSON 文本保存，写入时校验键长、单值大小与插件总配额。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::database::dao::plugin_storage::PluginStorageDao;
+use crate::database::pagination::{Page, PageRequest};
+use crate::database::{lock_db, DbConnection};
+
+/// 存储配额
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginStorageQuota {
+    /// 键的最大字节数
+    pub max_key_bytes: usize,
+    /// 单个值的最大字节数（序列化后）
+    pub max_value_bytes: usize,
+    /// 单个插件的总字节数（键 + 值）
+    pub max_total_bytes: u64,
+}
+
+impl Default for PluginStorageQuota {
+    fn default() -> Self {
+        Self {
+            max_key_bytes: 256,
+            max_value_bytes: 1024 * 1024,
+            max_total_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// 插件存储错误
+#[derive(Debug, thiserror::Error)]
+pub enum PluginStorageError {
+    #[error("无效的存储键: {0}")]
+    InvalidKey(String),
+
+    #[error("存储值过大: {size} 字节，上限 {limit} 字节")]
+    ValueTooLarge { size: usize, limit: usize },
+
+    #[error("插件存储配额不足: 写入后 {required} 字节，上限 {limit} 字节")]
+    QuotaExceeded { required: u64, limit: u64 },
+
+    #[error("存储值序列化失败: {0}")]
+    Serialization(String),
+
+    #[error("数据库错误: {0}")]
+    Database(String),
+}
+
+impl From<rusqlite::Error> for PluginStorageError {
+    fn from(e: rusqlite::Error) -> Self {
+        PluginStorageError::Database(e.to_string())
+    }
+}
+
+/// 插件存储用量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginStorageUsage {
+    pub keys: usize,
+    pub used_bytes: u64,
+    pub quota_bytes: u64,
+}
+
+/// 绑定到单个插件的存储句柄
+#[derive(Clone)]
+pub struct PluginStorage {
+    db: DbConnection,
+    plugin_id: String,
+    quota: PluginStorageQuota,
+}
+
+impl PluginStorage {
+    pub fn new(db: DbConnection, plugin_id: impl Into<String>) -> Self {
+        Self::with_quota(db, plugin_id, PluginStorageQuota::default())
+    }
+
+    pub fn with_quota(
+        db: DbConnection,
+        plugin_id: impl Into<String>,
+        quota: PluginStorageQuota,
+    ) -> Self {
+        Self {
+            db,
+            plugin_id: plugin_id.into(),
+            quota,
+        }
+    }
+
+    pub fn plugin_id(&self) -> &str {
+        &self.plugin_id
+    }
+
+    fn validate_key(&self, key: &str) -> Result<(), PluginStorageError> {
+        if key.trim().is_empty() {
+            return Err(PluginStorageError::InvalidKey("键不能为空".to_string()));
+        }
+        if key.len() > self.quota.max_key_bytes {
+            return Err(PluginStorageError::InvalidKey(format!(
+                "键长度 {} 超过上限 {}",
+                key.len(),
+                self.quota.max_key_bytes
+            )));
+        }
+        if key.chars().any(char::is_control) {
+            return Err(PluginStorageError::InvalidKey(
+                "键不能包含控制字符".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// 读取值，不存在时返回 None
+    pub fn get(&self, key: &str) -> Result<Option<Value>, PluginStorageError> {
+        self.validate_key(key)?;
+        let conn = lock_db(&self.db).map_err(PluginStorageError::Database)?;
+        PluginStorageDao::get(&conn, &self.plugin_id, key)?
+            .map(|raw| {
+                serde_json::from_str(&raw)
+                    .map_err(|e| PluginStorageError::Serialization(e.to_string()))
+            })
+            .transpose()
+    }
+
+    /// 写入值，超出单值大小或插件总配额时拒绝
+    pub fn set(&self, key: &str, value: &Value) -> Result<(), PluginStorageError> {
+        self.validate_key(key)?;
+        let raw = serde_json::to_string(value)
+            .map_err(|e| PluginStorageError::Serialization(e.to_string()))?;
+        if raw.len() > self.quota.max_value_bytes {
+            return Err(PluginStorageError::ValueTooLarge {
+                size: raw.len(),
+                limit: self.quota.max_value_bytes,
+            });
+        }
+
+        let conn = lock_db(&self.db).map_err(PluginStorageError::Database)?;
+        let required = PluginStorageDao::usage_bytes(&conn, &self.plugin_id, Some(key))?
+            + (key.len() + raw.len()) as u64;
+        if required > self.quota.max_total_bytes {
+            return Err(PluginStorageError::QuotaExceeded {
+                required,
+                limit: self.quota.max_total_bytes,
+            });
+        }
+        PluginStorageDao::set(&conn, &self.plugin_id, key, &raw)?;
+        Ok(())
+    }
+
+    /// 删除值，返回是否存在
+    pub fn delete(&self, key: &str) -> Result<bool, PluginStorageError> {
+        self.validate_key(key)?;
+        let conn = lock_db(&self.db).map_err(PluginStorageError::Database)?;
+        Ok(PluginStorageDao::delete(&conn, &self.plugin_id, key)?)
+    }
+
+    /// 列出所有键
+    pub fn keys(&self) -> Result<Vec<String>, PluginStorageError> {
+        let conn = lock_db(&self.db).map_err(PluginStorageError::Database)?;
+        Ok(PluginStorageDao::list_keys(&conn, &self.plugin_id)?)
+    }
+
+    /// 按键名升序分页列出键
+    pub fn keys_page(&self, page: &PageRequest) -> Result<Page<String>, PluginStorageError> {
+        let conn = lock_db(&self.db).map_err(PluginStorageError::Database)?;
+        Ok(PluginStorageDao::list_keys_page(
+            &conn,
+            &self.plugin_id,
+            page,
+        )?)
+    }
+
+    /// 当前用量
+    pub fn usage(&self) -> Result<PluginStorageUsage, PluginStorageError> {
+        let conn = lock_db(&self.db).map_err(PluginStorageError::Database)?;
+        Ok(PluginStorageUsage {
+            keys: PluginStorageDao::list_keys(&conn, &self.plugin_id)?.len(),
+            used_bytes: PluginStorageDao::usage_bytes(&conn, &self.plugin_id, None)?,
+            quota_bytes: self.quota.max_total_bytes,
+        })
+    }
+
+    /// 清空插件的全部数据（卸载时调用）
+    pub fn clear(&self) -> Result<usize, PluginStorageError> {
+        let conn = lock_db(&self.db).map_err(PluginStorageError::Database)?;
+        Ok(PluginStorageDao::clear(&conn, &self.plugin_id)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+    use rusqlite::Connection;
+    use std::sync::{Arc, Mutex};
+
+    fn test_db() -> DbConnection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        Arc::new(Mutex::new(conn))
+    }
+
+    #[test]
+    fn test_get_set_delete_roundtrip() {
+        let db = test_db();
+        let storage = PluginStorage::new(db.clone(), "plugin-a");
+        let other = PluginStorage::new(db, "plugin-b");
+
+        storage
+            .set("settings", &serde_json::json!({ "theme": "dark" }))
+            .unwrap();
+        assert_eq!(
+            storage.get("settings").unwrap(),
+            Some(serde_json::json!({ "theme": "dark" }))
+        );
+        assert_eq!(other.get("settings").unwrap(), None);
+        assert_eq!(storage.keys().unwrap(), vec!["settings".to_string()]);
+
+        assert!(storage.delete("settings").unwrap());
+        assert_eq!(storage.get("settings").unwrap(), None);
+        assert!(matches!(
+            storage.get(""),
+            Err(PluginStorageError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn test_quota_enforced() {
+        let storage = PluginStorage::with_quota(
+            test_db(),
+            "plugin-a",
+            PluginStorageQuota {
+                max_key_bytes: 16,
+                max_value_bytes: 32,
+                max_total_bytes: 40,
+            },
+        );
+
+        assert!(matches!(
+            storage.set("big", &Value::String("x".repeat(64))),
+            Err(PluginStorageError::ValueTooLarge { .. })
+        ));
+
+        // "a" + "\"...20 个 x...\"" = 23 字节
+        let value = Value::String("x".repeat(20));
+        storage.set("a", &value).unwrap();
+        assert!(matches!(
+            storage.set("b", &value),
+            Err(PluginStorageError::QuotaExceeded { required: 46, .. })
+        ));
+        // 覆盖已有键时不重复计算旧值
+        storage.set("a", &value).unwrap();
+        assert_eq!(storage.usage().unwrap().used_bytes, 23);
+    }
+}
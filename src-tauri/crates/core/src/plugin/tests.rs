@@ -20,6 +20,9 @@ fn test_plugin_manifest_validation() {
         min_lime_version: None,
         binary: None,
         ui: None,
+        permissions: Vec::new(),
+        database: None,
+        health_probes: Vec::new(),
     };
     assert!(valid.validate().is_ok());
 
@@ -158,6 +161,9 @@ fn test_plugin_manifest_serde() {
         min_lime_version: Some("0.13.0".to_string()),
         binary: None,
         ui: None,
+        permissions: Vec::new(),
+        database: None,
+        health_probes: Vec::new(),
     };
 
     // 序列化
@@ -205,6 +211,90 @@ fn test_machine_id_tool_manifest_parsing() {
     assert_eq!(ui.icon, Some("Cpu".to_string()));
 }
 
+#[test]
+fn test_plugin_dir_of() {
+    use std::path::Path;
+
+    let root = Path::new("/data/plugins");
+    assert_eq!(
+        watcher::plugin_dir_of(root, Path::new("/data/plugins/foo/manifest.json")),
+        Some(root.join("foo"))
+    );
+    assert_eq!(
+        watcher::plugin_dir_of(root, Path::new("/data/plugins/foo/dist/index.js")),
+        Some(root.join("foo"))
+    );
+    assert_eq!(
+        watcher::plugin_dir_of(root, Path::new("/data/plugins/bar")),
+        Some(root.join("bar"))
+    );
+    assert_eq!(watcher::plugin_dir_of(root, root), None);
+    assert_eq!(
+        watcher::plugin_dir_of(root, Path::new("/data/plugins/.DS_Store")),
+        None
+    );
+    assert_eq!(
+        watcher::plugin_dir_of(root, Path::new("/data/other/foo/manifest.json")),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_plugin_manager_sync_dir_lifecycle() {
+    use crate::plugin::manager::{PluginManager, PluginManagerConfig, PluginReloadAction};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let manager = PluginManager::new(
+        temp_dir.path().to_path_buf(),
+        PluginManagerConfig::default(),
+    );
+    let plugin_dir = temp_dir.path().join("hot-plugin");
+    std::fs::create_dir_all(&plugin_dir).unwrap();
+
+    // 没有清单的目录不处理
+    assert_eq!(
+        manager.sync_dir(&plugin_dir).await.unwrap(),
+        PluginReloadAction::Skipped
+    );
+
+    let write_manifest = |version: &str| {
+        std::fs::write(
+            plugin_dir.join("manifest.json"),
+            format!(r#"{{"name": "hot-plugin", "version": "{version}"}}"#),
+        )
+        .unwrap();
+    };
+
+    write_manifest("1.0.0");
+    assert_eq!(
+        manager.sync_dir(&plugin_dir).await.unwrap(),
+        PluginReloadAction::Loaded("hot-plugin".to_string())
+    );
+
+    // 禁用状态在重新加载后保留
+    manager.disable("hot-plugin").await.unwrap();
+    write_manifest("1.1.0");
+    assert_eq!(
+        manager.sync_dir(&plugin_dir).await.unwrap(),
+        PluginReloadAction::Reloaded("hot-plugin".to_string())
+    );
+    let info = manager.get_info("hot-plugin").await.unwrap();
+    assert_eq!(info.version, "1.1.0");
+    assert_eq!(info.status, PluginStatus::Disabled);
+
+    // 无效清单不会卸载运行中的插件
+    std::fs::write(plugin_dir.join("manifest.json"), "{").unwrap();
+    assert!(manager.sync_dir(&plugin_dir).await.is_err());
+    assert!(manager.is_loaded("hot-plugin"));
+
+    std::fs::remove_dir_all(&plugin_dir).unwrap();
+    assert_eq!(
+        manager.sync_dir(&plugin_dir).await.unwrap(),
+        PluginReloadAction::Unloaded("hot-plugin".to_string())
+    );
+    assert!(!manager.is_loaded("hot-plugin"));
+}
+
 // Property-based tests
 use proptest::prelude::*;
 
@@ -124,6 +124,15 @@ pub struct PluginManifest {
     /// _需求: 5.3_
     #[serde(default)]
     pub ui: Option<UiManifest>,
+    /// 插件申请的权限
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<PluginPermission>,
+    /// 插件数据表声明（宿主按声明创建带插件前缀的表）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub database: Option<super::database::PluginDatabaseSchema>,
+    /// Provider 插件声明的健康探测（由 `HealthChecker::probe` 执行）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub health_probes: Vec<crate::credential::HealthProbe>,
 }
 
 fn default_entry() -> String {
@@ -139,8 +148,36 @@ impl PluginManifest {
         if self.version.is_empty() {
             return Err(PluginError::InvalidManifest("插件版本不能为空".to_string()));
         }
+        for probe in &self.health_probes {
+            probe.validate().map_err(PluginError::InvalidManifest)?;
+        }
         Ok(())
     }
+
+    /// 是否声明了指定权限
+    pub fn has_permission(&self, permission: PluginPermission) -> bool {
+        self.permissions.contains(&permission)
+    }
+}
+
+/// 插件权限
+///
+/// 在 plugin.json 的 `permissions` 中声明，宿主只放行已声明权限对应的 SDK 能力。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginPermission {
+    /// 读取插件数据目录中的文件（`fs.read` / `fs.list`）
+    #[serde(alias = "fs:read")]
+    FileSystemRead,
+    /// 写入插件数据目录中的文件（`fs.write`）
+    #[serde(alias = "fs:write")]
+    FileSystemWrite,
+    /// 订阅插件事件总线上的事件（`event.subscribe`）
+    #[serde(alias = "event:subscribe")]
+    EventSubscribe,
+    /// 当前版本不认识的权限（忽略，不授予任何能力）
+    #[serde(other)]
+    Unknown,
 }
 
 /// 插件类型
@@ -155,6 +192,8 @@ pub enum PluginType {
     Native,
     /// 二进制可执行文件
     Binary,
+    /// WASM 模块（`entry` 指向 `.wasm` 文件，需开启 `wasm_plugins` 功能开关）
+    Wasm,
 }
 
 /// 平台二进制文件名映射
@@ -234,6 +273,9 @@ pub struct UiManifest {
     /// 窗口默认高度
     #[serde(default)]
     pub default_height: Option<u32>,
+    /// 插件 UI 可接收的事件主题（支持 `*` 后缀通配，如 `flow-*`）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<String>,
 }
 
 /// 二进制组件状态
@@ -611,6 +653,7 @@ mod tests {
                         description,
                         default_width,
                         default_height,
+                        events: Vec::new(),
                     }
                 },
             )
@@ -622,6 +665,7 @@ mod tests {
             Just(PluginType::Script),
             Just(PluginType::Native),
             Just(PluginType::Binary),
+            Just(PluginType::Wasm),
         ]
     }
 
@@ -672,6 +716,9 @@ mod tests {
                         min_lime_version,
                         binary,
                         ui,
+                        permissions: Vec::new(),
+                        database: None,
+                        health_probes: Vec::new(),
                     }
                 },
             )
@@ -707,6 +754,7 @@ mod tests {
             description: None,
             default_width: Some(800),
             default_height: Some(600),
+            events: vec!["flow-*".to_string()],
         };
 
         let json = serde_json::to_string(&ui).unwrap();
@@ -716,6 +764,7 @@ mod tests {
         let parsed: UiManifest = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed.surfaces, ui.surfaces);
         assert_eq!(parsed.icon, ui.icon);
+        assert_eq!(parsed.events, ui.events);
     }
 
     #[test]
@@ -753,7 +802,11 @@ mod tests {
                 description: None,
                 default_width: None,
                 default_height: None,
+                events: vec![],
             }),
+            permissions: vec![PluginPermission::FileSystemRead],
+            database: None,
+            health_probes: Vec::new(),
         };
 
         // 序列化
@@ -0,0 +1,498 @@
+//! WASM 插件运行时
+//!
+//! 简单的插件可以只发布一个可移植的 `.wasm` 文件（`plugin_type: "wasm"`，`entry` 指向模块），
+//! 由宿主在进程内加载，无需为每个平台编译原生二进制。
+//!
+//! 沙箱基于能力：模块不链接 WASI，无法直接访问文件、网络、环境变量与时钟，
+//! 只能通过宿主导入的函数调用 SDK（`storage.*` / `crypto.*` / `fs.*`，与二进制插件的
+//! JSON-RPC 宿主方法一致，并同样受插件权限约束）。每次调用有指令预算（fuel），
+//! 线性内存有上限。
+//!
+//! ## ABI
+//!
+//! 字符串与 JSON 以 UTF-8 字节在线性内存中传递，返回值将指针与长度打包为
+//! `i64`（高 32 位指针，低 32 位长度）。
+//!
+//! 模块导出：
+//! - `memory`：线性内存
+//! - `lime_alloc(len: i32) -> i32`：分配 `len` 字节，宿主写入参数与宿主调用结果时使用
+//! - `lime_call(method_ptr, method_len, params_ptr, params_len: i32) -> i64`：处理调用，
+//!   返回 `{"result": ...}` 或 `{"error": {"code", "message"}}`；`params_len` 为 0 表示无参数
+//! - `lime_init()` / `lime_shutdown()`（可选）：加载后 / 卸载前调用
+//!
+//! 宿主导入（模块 `lime`）：
+//! - `host_call(method_ptr, method_len, params_ptr, params_len: i32) -> i64`：调用 SDK 方法，
+//!   返回格式同 `lime_call`，内存由 `lime_alloc` 分配
+//! - `log(level, ptr, len: i32)`：输出日志（0 debug、1 info、2 warn、3 error）
+
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde_json::Value;
+use wasmtime::{
+    AsContext, AsContextMut, Caller, Config, Engine, Extern, Instance, Linker, Memory, Module,
+    Store, StoreLimits, StoreLimitsBuilder, Trap, TypedFunc,
+};
+
+/// SDK 调用处理器：`(method, params) -> result`，错误为 JSON-RPC 错误码与消息
+pub type WasmHostHandler =
+    Arc<dyn Fn(&str, Option<&Value>) -> Result<Value, (i32, String)> + Send + Sync>;
+
+/// 运行时资源限制
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WasmLimits {
+    /// 线性内存上限（字节）
+    pub max_memory_bytes: usize,
+    /// 单次调用的指令预算
+    pub fuel_per_call: u64,
+    /// 单条消息（方法名、参数、返回值）的最大字节数
+    pub max_message_bytes: usize,
+}
+
+impl Default for WasmLimits {
+    fn default() -> Self {
+        Self {
+            max_memory_bytes: 64 * 1024 * 1024,
+            fuel_per_call: 1_000_000_000,
+            max_message_bytes: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// WASM 插件错误
+#[derive(Debug, thiserror::Error)]
+pub enum WasmPluginError {
+    #[error("加载 WASM 模块失败: {0}")]
+    Load(String),
+
+    #[error("WASM 模块缺少导出: {0}")]
+    MissingExport(&'static str),
+
+    #[error("WASM 插件执行超出指令预算")]
+    FuelExhausted,
+
+    #[error("WASM 插件执行失败: {0}")]
+    Trap(String),
+
+    #[error("WASM 插件返回无效响应: {0}")]
+    InvalidResponse(String),
+
+    #[error("插件错误 [{code}]: {message}")]
+    Rpc { code: i32, message: String },
+}
+
+impl From<wasmtime::Error> for WasmPluginError {
+    fn from(e: wasmtime::Error) -> Self {
+        if e.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+            WasmPluginError::FuelExhausted
+        } else {
+            WasmPluginError::Trap(format!("{e:#}"))
+        }
+    }
+}
+
+/// `lime_call` / `host_call` 的返回格式
+#[derive(Debug, Deserialize)]
+struct CallResponse {
+    #[serde(default)]
+    result: Value,
+    #[serde(default)]
+    error: Option<CallError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CallError {
+    code: i32,
+    message: String,
+}
+
+struct HostState {
+    plugin_id: String,
+    handler: WasmHostHandler,
+    limits: StoreLimits,
+    max_message_bytes: usize,
+}
+
+/// 已加载的 WASM 插件实例
+///
+/// 调用是同步且 CPU 密集的，异步上下文中应在阻塞线程池中调用。
+pub struct WasmPlugin {
+    store: Store<HostState>,
+    memory: Memory,
+    alloc: TypedFunc<i32, i32>,
+    call: TypedFunc<(i32, i32, i32, i32), i64>,
+    shutdown: Option<TypedFunc<(), ()>>,
+    fuel_per_call: u64,
+}
+
+impl WasmPlugin {
+    /// 从文件加载模块
+    pub fn load(
+        plugin_id: impl Into<String>,
+        module_path: &Path,
+        handler: WasmHostHandler,
+        limits: WasmLimits,
+    ) -> Result<Self, WasmPluginError> {
+        let bytes = std::fs::read(module_path)
+            .map_err(|e| WasmPluginError::Load(format!("{}: {e}", module_path.display())))?;
+        Self::from_bytes(plugin_id, &bytes, handler, limits)
+    }
+
+    /// 从字节加载模块（二进制 `.wasm` 或 WAT 文本）
+    pub fn from_bytes(
+        plugin_id: impl Into<String>,
+        bytes: &[u8],
+        handler: WasmHostHandler,
+        limits: WasmLimits,
+    ) -> Result<Self, WasmPluginError> {
+        let mut config = Config::new();
+        config.consume_fuel(true);
+        let engine = Engine::new(&config).map_err(|e| WasmPluginError::Load(e.to_string()))?;
+        let module =
+            Module::new(&engine, bytes).map_err(|e| WasmPluginError::Load(e.to_string()))?;
+
+        let mut linker = Linker::new(&engine);
+        linker
+            .func_wrap("lime", "host_call", host_call)
+            .and_then(|linker| linker.func_wrap("lime", "log", host_log))
+            .map_err(|e| WasmPluginError::Load(e.to_string()))?;
+
+        let mut store = Store::new(
+            &engine,
+            HostState {
+                plugin_id: plugin_id.into(),
+                handler,
+                limits: StoreLimitsBuilder::new()
+                    .memory_size(limits.max_memory_bytes)
+                    .build(),
+                max_message_bytes: limits.max_message_bytes,
+            },
+        );
+        store.limiter(|state| &mut state.limits);
+        store.set_fuel(limits.fuel_per_call)?;
+
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .map_err(|e| WasmPluginError::Load(e.to_string()))?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or(WasmPluginError::MissingExport("memory"))?;
+        let alloc = typed_export(&instance, &mut store, "lime_alloc")?
+            .ok_or(WasmPluginError::MissingExport("lime_alloc"))?;
+        let call = typed_export(&instance, &mut store, "lime_call")?
+            .ok_or(WasmPluginError::MissingExport("lime_call"))?;
+        let shutdown = typed_export(&instance, &mut store, "lime_shutdown")?;
+
+        if let Some(init) = typed_export::<(), ()>(&instance, &mut store, "lime_init")? {
+            init.call(&mut store, ())?;
+        }
+
+        Ok(Self {
+            store,
+            memory,
+            alloc,
+            call,
+            shutdown,
+            fuel_per_call: limits.fuel_per_call,
+        })
+    }
+
+    pub fn plugin_id(&self) -> &str {
+        &self.store.data().plugin_id
+    }
+
+    /// 调用插件方法
+    pub fn call(&mut self, method: &str, params: Option<&Value>) -> Result<Value, WasmPluginError> {
+        self.store.set_fuel(self.fuel_per_call)?;
+
+        let params = params
+            .map(serde_json::to_vec)
+            .transpose()
+            .map_err(|e| WasmPluginError::InvalidResponse(e.to_string()))?
+            .unwrap_or_default();
+        let (method_ptr, method_len) = write_guest(
+            &mut self.store,
+            &self.alloc,
+            &self.memory,
+            method.as_bytes(),
+        )?;
+        let (params_ptr, params_len) = if params.is_empty() {
+            (0, 0)
+        } else {
+            write_guest(&mut self.store, &self.alloc, &self.memory, &params)?
+        };
+
+        let packed = self.call.call(
+            &mut self.store,
+            (method_ptr, method_len, params_ptr, params_len),
+        )?;
+        let max_message_bytes = self.store.data().max_message_bytes;
+        let bytes = read_guest(&self.store, &self.memory, packed, max_message_bytes)
+            .map_err(|e| WasmPluginError::InvalidResponse(e.to_string()))?;
+        let response: CallResponse = serde_json::from_slice(&bytes)
+            .map_err(|e| WasmPluginError::InvalidResponse(e.to_string()))?;
+        match response.error {
+            Some(error) => Err(WasmPluginError::Rpc {
+                code: error.code,
+                message: error.message,
+            }),
+            None => Ok(response.result),
+        }
+    }
+
+    /// 通知插件即将卸载
+    pub fn shutdown(&mut self) -> Result<(), WasmPluginError> {
+        if let Some(shutdown) = self.shutdown.take() {
+            self.store.set_fuel(self.fuel_per_call)?;
+            shutdown.call(&mut self.store, ())?;
+        }
+        Ok(())
+    }
+}
+
+fn typed_export<Params, Results>(
+    instance: &Instance,
+    store: &mut Store<HostState>,
+    name: &str,
+) -> Result<Option<TypedFunc<Params, Results>>, WasmPluginError>
+where
+    Params: wasmtime::WasmParams,
+    Results: wasmtime::WasmResults,
+{
+    match instance.get_func(&mut *store, name) {
+        Some(func) => func
+            .typed(&*store)
+            .map(Some)
+            .map_err(|e| WasmPluginError::Load(format!("导出 {name} 签名不匹配: {e}"))),
+        None => Ok(None),
+    }
+}
+
+fn pack(ptr: i32, len: i32) -> i64 {
+    (((ptr as u32 as u64) << 32) | len as u32 as u64) as i64
+}
+
+fn unpack(value: i64) -> (usize, usize) {
+    let value = value as u64;
+    ((value >> 32) as usize, (value as u32) as usize)
+}
+
+/// 通过 `lime_alloc` 在模块内存中分配并写入字节
+fn write_guest(
+    mut store: impl AsContextMut,
+    alloc: &TypedFunc<i32, i32>,
+    memory: &Memory,
+    bytes: &[u8],
+) -> wasmtime::Result<(i32, i32)> {
+    let len = i32::try_from(bytes.len())?;
+    let ptr = alloc.call(&mut store, len)?;
+    memory.write(&mut store, ptr as u32 as usize, bytes)?;
+    Ok((ptr, len))
+}
+
+fn read_guest(
+    store: impl AsContext,
+    memory: &Memory,
+    packed: i64,
+    max_bytes: usize,
+) -> wasmtime::Result<Vec<u8>> {
+    let (ptr, len) = unpack(packed);
+    read_bytes(store, memory, ptr, len, max_bytes)
+}
+
+fn read_bytes(
+    store: impl AsContext,
+    memory: &Memory,
+    ptr: usize,
+    len: usize,
+    max_bytes: usize,
+) -> wasmtime::Result<Vec<u8>> {
+    if len > max_bytes {
+        return Err(wasmtime::Error::msg(format!(
+            "消息过大: {len} 字节，上限 {max_bytes} 字节"
+        )));
+    }
+    let mut buffer = vec![0; len];
+    memory.read(store, ptr, &mut buffer)?;
+    Ok(buffer)
+}
+
+fn caller_memory(caller: &mut Caller<'_, HostState>) -> wasmtime::Result<Memory> {
+    match caller.get_export("memory") {
+        Some(Extern::Memory(memory)) => Ok(memory),
+        _ => Err(wasmtime::Error::msg("插件未导出 memory")),
+    }
+}
+
+fn read_caller_str(
+    caller: &mut Caller<'_, HostState>,
+    memory: &Memory,
+    ptr: i32,
+    len: i32,
+) -> wasmtime::Result<String> {
+    let max_bytes = caller.data().max_message_bytes;
+    let bytes = read_bytes(
+        &*caller,
+        memory,
+        ptr as u32 as usize,
+        len as u32 as usize,
+        max_bytes,
+    )?;
+    Ok(String::from_utf8(bytes)?)
+}
+
+/// 宿主导入 `lime.host_call`
+fn host_call(
+    mut caller: Caller<'_, HostState>,
+    method_ptr: i32,
+    method_len: i32,
+    params_ptr: i32,
+    params_len: i32,
+) -> wasmtime::Result<i64> {
+    let memory = caller_memory(&mut caller)?;
+    let method = read_caller_str(&mut caller, &memory, method_ptr, method_len)?;
+    let params = if params_len == 0 {
+        None
+    } else {
+        let params = read_caller_str(&mut caller, &memory, params_ptr, params_len)?;
+        Some(serde_json::from_str::<Value>(&params)?)
+    };
+
+    let handler = caller.data().handler.clone();
+    let response = match handler(&method, params.as_ref()) {
+        Ok(result) => serde_json::json!({ "result": result }),
+        Err((code, message)) => {
+            serde_json::json!({ "error": { "code": code, "message": message } })
+        }
+    };
+
+    let alloc = match caller.get_export("lime_alloc") {
+        Some(Extern::Func(func)) => func.typed::<i32, i32>(&caller)?,
+        _ => return Err(wasmtime::Error::msg("插件未导出 lime_alloc")),
+    };
+    let bytes = serde_json::to_vec(&response)?;
+    let (ptr, len) = write_guest(&mut caller, &alloc, &memory, &bytes)?;
+    Ok(pack(ptr, len))
+}
+
+/// 宿主导入 `lime.log`
+fn host_log(
+    mut caller: Caller<'_, HostState>,
+    level: i32,
+    ptr: i32,
+    len: i32,
+) -> wasmtime::Result<()> {
+    let memory = caller_memory(&mut caller)?;
+    let message = read_caller_str(&mut caller, &memory, ptr, len)?;
+    let plugin_id = &caller.data().plugin_id;
+    match level {
+        0 => tracing::debug!("[WASM:{}] {}", plugin_id, message),
+        1 => tracing::info!("[WASM:{}] {}", plugin_id, message),
+        2 => tracing::warn!("[WASM:{}] {}", plugin_id, message),
+        _ => tracing::error!("[WASM:{}] {}", plugin_id, message),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 将调用原样转发给宿主的模块（bump 分配器）
+    const FORWARD_WAT: &str = r#"
+        (module
+          (import "lime" "host_call" (func $host_call (param i32 i32 i32 i32) (result i64)))
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (func (export "lime_alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "lime_call") (param i32 i32 i32 i32) (result i64)
+            (call $host_call (local.get 0) (local.get 1) (local.get 2) (local.get 3))))
+    "#;
+
+    fn echo_handler() -> WasmHostHandler {
+        Arc::new(|method: &str, params: Option<&Value>| match method {
+            "echo" => Ok(params.cloned().unwrap_or(Value::Null)),
+            other => Err((-32601, format!("unknown method {other}"))),
+        })
+    }
+
+    #[test]
+    fn test_call_roundtrip_through_host() {
+        let mut plugin = WasmPlugin::from_bytes(
+            "wasm-echo",
+            FORWARD_WAT.as_bytes(),
+            echo_handler(),
+            WasmLimits::default(),
+        )
+        .unwrap();
+
+        let params = serde_json::json!({ "text": "你好" });
+        assert_eq!(plugin.call("echo", Some(&params)).unwrap(), params);
+        assert_eq!(plugin.call("echo", None).unwrap(), Value::Null);
+        assert!(matches!(
+            plugin.call("missing", None),
+            Err(WasmPluginError::Rpc { code: -32601, .. })
+        ));
+        plugin.shutdown().unwrap();
+    }
+
+    #[test]
+    fn test_fuel_limit_stops_runaway_plugin() {
+        let wat = r#"
+            (module
+              (memory (export "memory") 1)
+              (func (export "lime_alloc") (param i32) (result i32) (i32.const 1024))
+              (func (export "lime_call") (param i32 i32 i32 i32) (result i64)
+                (loop $spin (br $spin))
+                (i64.const 0)))
+        "#;
+        let mut plugin = WasmPlugin::from_bytes(
+            "wasm-spin",
+            wat.as_bytes(),
+            echo_handler(),
+            WasmLimits {
+                fuel_per_call: 100_000,
+                ..WasmLimits::default()
+            },
+        )
+        .unwrap();
+
+        assert!(matches!(
+            plugin.call("anything", None),
+            Err(WasmPluginError::FuelExhausted)
+        ));
+        // 每次调用重新分配预算，实例仍可继续使用
+        assert!(matches!(
+            plugin.call("anything", None),
+            Err(WasmPluginError::FuelExhausted)
+        ));
+    }
+
+    #[test]
+    fn test_missing_exports_and_wasi_imports_rejected() {
+        let no_call = r#"(module (memory (export "memory") 1)
+            (func (export "lime_alloc") (param i32) (result i32) (i32.const 0)))"#;
+        assert!(matches!(
+            WasmPlugin::from_bytes(
+                "p",
+                no_call.as_bytes(),
+                echo_handler(),
+                WasmLimits::default()
+            ),
+            Err(WasmPluginError::MissingExport("lime_call"))
+        ));
+
+        // 未提供 WASI，模块无法导入文件系统等能力
+        let wasi = r#"(module
+            (import "wasi_snapshot_preview1" "fd_write" (func (param i32 i32 i32 i32) (result i32))))"#;
+        assert!(matches!(
+            WasmPlugin::from_bytes("p", wasi.as_bytes(), echo_handler(), WasmLimits::default()),
+            Err(WasmPluginError::Load(_))
+        ));
+    }
+}
@@ -0,0 +1,177 @@
+//! 插件目录监控
+//!
+//! 监控插件目录的文件变化，将变化归并到所属的插件目录（插件目录下的一级子目录），
+//! 按插件防抖后通知宿主，用于插件热重载：
+//! - 目录仍存在：插件被新增或更新，需要重新加载
+//! - 目录已删除：插件被移除，需要卸载
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use super::types::PluginError;
+
+/// 默认防抖时间（安装过程中会连续写入多个文件）
+pub const DEFAULT_PLUGIN_WATCH_DEBOUNCE: Duration = Duration::from_millis(800);
+
+/// 插件目录变更类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginDirChangeKind {
+    /// 插件目录被创建或其中文件被修改
+    Changed,
+    /// 插件目录已被删除
+    Removed,
+}
+
+/// 插件目录变更事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginDirChange {
+    /// 插件 ID（插件目录名）
+    pub plugin_id: String,
+    /// 插件目录
+    pub plugin_dir: PathBuf,
+    /// 变更类型
+    pub kind: PluginDirChangeKind,
+}
+
+/// 插件目录监控器
+///
+/// 持有期间保持监控，drop 后停止。
+pub struct PluginDirWatcher {
+    _watcher: RecommendedWatcher,
+    plugins_dir: PathBuf,
+}
+
+impl PluginDirWatcher {
+    /// 开始监控插件目录
+    ///
+    /// 必须在 tokio 运行时中调用，防抖任务会在后台运行直到监控器被 drop。
+    pub fn start(
+        plugins_dir: &Path,
+        debounce: Duration,
+    ) -> Result<(Self, mpsc::UnboundedReceiver<PluginDirChange>), PluginError> {
+        std::fs::create_dir_all(plugins_dir)?;
+        let plugins_dir = plugins_dir.to_path_buf();
+
+        let (raw_tx, raw_rx) = mpsc::unbounded_channel::<PathBuf>();
+        let (change_tx, change_rx) = mpsc::unbounded_channel();
+
+        let root = plugins_dir.clone();
+        let mut watcher =
+            notify::recommended_watcher(move |res: Result<Event, notify::Error>| match res {
+                Ok(event) => {
+                    if !matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    ) {
+                        return;
+                    }
+                    for path in event.paths {
+                        if let Some(plugin_dir) = plugin_dir_of(&root, &path) {
+                            let _ = raw_tx.send(plugin_dir);
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("插件目录监控错误: {:?}", e);
+                }
+            })
+            .map_err(|e| PluginError::LoadError(format!("创建插件目录监控失败: {e}")))?;
+
+        watcher
+            .watch(&plugins_dir, RecursiveMode::Recursive)
+            .map_err(|e| PluginError::LoadError(format!("监控插件目录失败: {e}")))?;
+
+        tokio::spawn(debounce_changes(raw_rx, change_tx, debounce));
+        tracing::info!("开始监控插件目录: {:?}", plugins_dir);
+
+        Ok((
+            Self {
+                _watcher: watcher,
+                plugins_dir,
+            },
+            change_rx,
+        ))
+    }
+
+    /// 获取监控的插件目录
+    pub fn plugins_dir(&self) -> &Path {
+        &self.plugins_dir
+    }
+}
+
+/// 将变化的路径映射到所属插件目录（插件目录下的一级子目录）
+///
+/// 插件目录本身以及隐藏文件（如 `.DS_Store`、安装临时目录）返回 `None`。
+pub fn plugin_dir_of(plugins_dir: &Path, path: &Path) -> Option<PathBuf> {
+    let relative = path.strip_prefix(plugins_dir).ok()?;
+    match relative.components().next()? {
+        Component::Normal(name) => {
+            let name = name.to_str()?;
+            if name.starts_with('.') {
+                return None;
+            }
+            Some(plugins_dir.join(name))
+        }
+        _ => None,
+    }
+}
+
+/// 按插件目录防抖：同一插件在 `debounce` 内没有新的变化后才发出事件
+async fn debounce_changes(
+    mut raw_rx: mpsc::UnboundedReceiver<PathBuf>,
+    change_tx: mpsc::UnboundedSender<PluginDirChange>,
+    debounce: Duration,
+) {
+    let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+    let tick = (debounce / 4).max(Duration::from_millis(50));
+
+    loop {
+        tokio::select! {
+            received = raw_rx.recv() => {
+                match received {
+                    Some(plugin_dir) => {
+                        pending.insert(plugin_dir, Instant::now());
+                    }
+                    // 监控器已 drop
+                    None => break,
+                }
+            }
+            _ = tokio::time::sleep(tick), if !pending.is_empty() => {
+                let now = Instant::now();
+                let ready: Vec<PathBuf> = pending
+                    .iter()
+                    .filter(|(_, last)| now.duration_since(**last) >= debounce)
+                    .map(|(dir, _)| dir.clone())
+                    .collect();
+                for plugin_dir in ready {
+                    pending.remove(&plugin_dir);
+                    let Some(change) = classify_change(plugin_dir) else {
+                        continue;
+                    };
+                    if change_tx.send(change).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 根据目录当前是否存在判断变更类型
+fn classify_change(plugin_dir: PathBuf) -> Option<PluginDirChange> {
+    let plugin_id = plugin_dir.file_name()?.to_str()?.to_string();
+    let kind = if plugin_dir.is_dir() {
+        PluginDirChangeKind::Changed
+    } else {
+        PluginDirChangeKind::Removed
+    };
+    Some(PluginDirChange {
+        plugin_id,
+        plugin_dir,
+        kind,
+    })
+}
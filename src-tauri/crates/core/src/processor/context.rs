@@ -5,8 +5,28 @@
 use crate::models::provider_type::ProviderType;
 use crate::plugin::PluginContext;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
 
+/// 单个请求最多缓冲的追踪事件数，超出后只计数不再记录
+pub const MAX_TRACE_EVENTS: usize = 256;
+
+/// 请求追踪事件
+///
+/// 在请求处理过程中缓冲于上下文内，请求结束时由尾部采样决定是否持久化。
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TraceEvent {
+    /// 相对请求开始的偏移（毫秒）
+    pub offset_ms: u64,
+    /// 处理阶段（如 `received`、`route`、`provider_response`）
+    pub stage: String,
+    /// 事件描述
+    pub message: String,
+    /// 附加数据
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
 /// 请求上下文
 ///
 /// 在请求处理管道中传递的上下文信息
@@ -34,12 +54,19 @@ pub struct RequestContext {
     pub plugin_ctx: Option<PluginContext>,
     /// 元数据
     pub metadata: std::collections::HashMap<String, serde_json::Value>,
+    /// 详细追踪事件（尾部采样缓冲）
+    pub trace: Vec<TraceEvent>,
+    /// 超出上限被丢弃的追踪事件数
+    pub trace_dropped: u32,
 }
 
 impl RequestContext {
     /// 创建新的请求上下文
+    ///
+    /// 在请求 ID 作用域内（见 [`super::request_id`]）时沿用该 ID，否则生成新的 UUID。
     pub fn new(model: String) -> Self {
-        let request_id = uuid::Uuid::new_v4().to_string();
+        let request_id = super::request_id::current_request_id()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
         Self {
             request_id: request_id.clone(),
             start_time: Instant::now(),
@@ -52,6 +79,8 @@ impl RequestContext {
             is_stream: false,
             plugin_ctx: None,
             metadata: std::collections::HashMap::new(),
+            trace: Vec::new(),
+            trace_dropped: 0,
         }
     }
 
@@ -109,6 +138,34 @@ impl RequestContext {
     pub fn get_metadata(&self, key: &str) -> Option<&serde_json::Value> {
         self.metadata.get(key)
     }
+
+    /// 记录追踪事件
+    pub fn trace(&mut self, stage: &str, message: impl Into<String>) {
+        self.push_trace(stage, message.into(), None);
+    }
+
+    /// 记录带附加数据的追踪事件
+    pub fn trace_with_data(
+        &mut self,
+        stage: &str,
+        message: impl Into<String>,
+        data: serde_json::Value,
+    ) {
+        self.push_trace(stage, message.into(), Some(data));
+    }
+
+    fn push_trace(&mut self, stage: &str, message: String, data: Option<serde_json::Value>) {
+        if self.trace.len() >= MAX_TRACE_EVENTS {
+            self.trace_dropped += 1;
+            return;
+        }
+        self.trace.push(TraceEvent {
+            offset_ms: self.elapsed_ms(),
+            stage: stage.to_string(),
+            message,
+            data,
+        });
+    }
 }
 
 impl Default for RequestContext {
@@ -166,4 +223,18 @@ mod tests {
         assert!(value.is_some());
         assert_eq!(value.unwrap(), &serde_json::json!("value"));
     }
+
+    #[test]
+    fn test_request_context_trace_is_bounded() {
+        let mut ctx = RequestContext::new("model".to_string());
+        ctx.trace_with_data("received", "request received", serde_json::json!({"n": 1}));
+        assert_eq!(ctx.trace[0].stage, "received");
+        assert_eq!(ctx.trace[0].data, Some(serde_json::json!({"n": 1})));
+
+        for i in 0..MAX_TRACE_EVENTS {
+            ctx.trace("step", format!("event {i}"));
+        }
+        assert_eq!(ctx.trace.len(), MAX_TRACE_EVENTS);
+        assert_eq!(ctx.trace_dropped, 1);
+    }
 }
@@ -0,0 +1,114 @@
+//! 请求截止时间
+//!
+//! 服务器入口按客户端 `x-lime-deadline-ms` 请求头或配置的默认值为请求设置整体截止时间，
+//! 在请求作用域内保存，管道各环节据此控制剩余预算：
+//! - 重试与故障转移在剩余时间不足时停止，单次上游调用的超时不超过剩余时间
+//! - 工具调用循环在每轮开始前检查是否已超时
+//! - 流式响应在临近截止时以 `deadline_exceeded` 结束原因收尾，而不是一直挂起
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// 客户端指定请求预算（毫秒）的请求头
+pub const REQUEST_DEADLINE_HEADER: &str = "x-lime-deadline-ms";
+
+/// 因截止时间结束流式响应时使用的结束原因
+pub const DEADLINE_EXCEEDED_FINISH_REASON: &str = "deadline_exceeded";
+
+/// 请求截止时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestDeadline {
+    at: Instant,
+}
+
+impl RequestDeadline {
+    /// 从现在起经过 `budget` 后截止
+    pub fn after(budget: Duration) -> Self {
+        Self {
+            at: Instant::now() + budget,
+        }
+    }
+
+    /// 截止时刻
+    pub fn instant(&self) -> Instant {
+        self.at
+    }
+
+    /// 剩余时间（已截止时为 0）
+    pub fn remaining(&self) -> Duration {
+        self.at.saturating_duration_since(Instant::now())
+    }
+
+    /// 是否已截止
+    pub fn is_exceeded(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// 剩余时间是否足以先等待 `wait` 再预留 `reserve`
+    pub fn allows(&self, wait: Duration, reserve: Duration) -> bool {
+        self.remaining() > wait + reserve
+    }
+
+    /// 将超时限制在剩余时间以内
+    pub fn clamp(&self, timeout: Duration) -> Duration {
+        timeout.min(self.remaining())
+    }
+}
+
+tokio::task_local! {
+    static CURRENT_DEADLINE: RequestDeadline;
+}
+
+/// 在截止时间作用域内执行
+pub async fn scope_deadline<F: Future>(deadline: RequestDeadline, future: F) -> F::Output {
+    CURRENT_DEADLINE.scope(deadline, future).await
+}
+
+/// 当前请求作用域内的截止时间
+pub fn current_deadline() -> Option<RequestDeadline> {
+    CURRENT_DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+/// 解析客户端传入的请求预算（正整数毫秒），并限制在 `max` 以内
+pub fn parse_deadline_header(value: &str, max: Duration) -> Option<Duration> {
+    let ms = value.trim().parse::<u64>().ok().filter(|ms| *ms > 0)?;
+    Some(Duration::from_millis(ms).min(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_deadline_header() {
+        let max = Duration::from_secs(60);
+        assert_eq!(
+            parse_deadline_header(" 1500 ", max),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(parse_deadline_header("600000", max), Some(max));
+        assert_eq!(parse_deadline_header("0", max), None);
+        assert_eq!(parse_deadline_header("1.5s", max), None);
+    }
+
+    #[test]
+    fn test_deadline_budget() {
+        let deadline = RequestDeadline::after(Duration::from_secs(10));
+        assert!(!deadline.is_exceeded());
+        assert!(deadline.allows(Duration::from_secs(1), Duration::from_secs(1)));
+        assert!(!deadline.allows(Duration::from_secs(9), Duration::from_secs(2)));
+        assert!(deadline.clamp(Duration::from_secs(120)) <= Duration::from_secs(10));
+
+        let expired = RequestDeadline::after(Duration::ZERO);
+        assert!(expired.is_exceeded());
+        assert_eq!(expired.clamp(Duration::from_secs(1)), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_scope() {
+        assert_eq!(current_deadline(), None);
+        let deadline = RequestDeadline::after(Duration::from_secs(5));
+        let scoped = scope_deadline(deadline, async { current_deadline() }).await;
+        assert_eq!(scoped, Some(deadline));
+    }
+}
@@ -4,7 +4,17 @@
 //! 完整的请求处理管道（步骤、路由、插件集成等）保留在主 crate 中。
 
 pub mod context;
+pub mod deadline;
 pub mod error;
+pub mod request_id;
 
-pub use context::RequestContext;
+pub use context::{RequestContext, TraceEvent, MAX_TRACE_EVENTS};
+pub use deadline::{
+    current_deadline, parse_deadline_header, scope_deadline, RequestDeadline,
+    DEADLINE_EXCEEDED_FINISH_REASON, REQUEST_DEADLINE_HEADER,
+};
 pub use error::ProcessError;
+pub use request_id::{
+    current_request_id, normalize_client_request_id, scope_request_id, RequestIdExt,
+    REQUEST_ID_HEADER, UPSTREAM_REQUEST_ID_HEADER,
+};
@@ -0,0 +1,84 @@
+//! 请求 ID 作用域
+//!
+//! 服务器入口为每个请求分配 request_id（或沿用客户端 `x-request-id`），在请求作用域内保存：
+//! - [`RequestContext::new`](super::RequestContext::new) 沿用作用域内的 ID
+//! - Provider 调用通过 [`RequestIdExt::with_request_id`] 以 `x-request-id` 转发给上游
+//! - 响应头 `x-lime-request-id`、审计日志与追踪记录使用同一 ID
+
+use std::future::Future;
+
+/// 返回给客户端的请求 ID 响应头
+pub const REQUEST_ID_HEADER: &str = "x-lime-request-id";
+
+/// 客户端传入 / 转发给上游的请求 ID 请求头
+pub const UPSTREAM_REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// 客户端请求 ID 的最大长度
+const MAX_CLIENT_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// 在请求 ID 作用域内执行
+pub async fn scope_request_id<F: Future>(request_id: String, future: F) -> F::Output {
+    CURRENT_REQUEST_ID.scope(request_id, future).await
+}
+
+/// 当前请求作用域内的请求 ID
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// 校验客户端传入的请求 ID：1-128 个字母、数字或 `-_.:`，否则忽略
+pub fn normalize_client_request_id(value: &str) -> Option<String> {
+    let value = value.trim();
+    let valid = !value.is_empty()
+        && value.len() <= MAX_CLIENT_REQUEST_ID_LEN
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'));
+    valid.then(|| value.to_string())
+}
+
+/// 为上游请求附加当前请求 ID
+pub trait RequestIdExt {
+    fn with_request_id(self) -> Self;
+}
+
+impl RequestIdExt for reqwest::RequestBuilder {
+    fn with_request_id(self) -> Self {
+        match current_request_id() {
+            Some(request_id) => self.header(UPSTREAM_REQUEST_ID_HEADER, request_id),
+            None => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_client_request_id() {
+        assert_eq!(
+            normalize_client_request_id(" req-1:a_b.c "),
+            Some("req-1:a_b.c".to_string())
+        );
+        assert_eq!(normalize_client_request_id(""), None);
+        assert_eq!(normalize_client_request_id("bad id"), None);
+        assert_eq!(normalize_client_request_id(&"a".repeat(129)), None);
+    }
+
+    #[tokio::test]
+    async fn test_request_id_scope() {
+        assert_eq!(current_request_id(), None);
+        let scoped = scope_request_id("req-1".to_string(), async {
+            let ctx = super::super::RequestContext::new("gpt-4o".to_string());
+            (current_request_id(), ctx.request_id)
+        })
+        .await;
+        assert_eq!(scoped, (Some("req-1".to_string()), "req-1".to_string()));
+        assert_eq!(current_request_id(), None);
+    }
+}
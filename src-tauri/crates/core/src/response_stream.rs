@@ -0,0 +1,236 @@
+//! 统一响应流抽象
+//!
+//! Provider 将各自的流式协议（OpenAI / Anthropic / Gemini / Codex SSE 等）解码为
+//! `ResponseChunk`，服务端与命令层只面向这一种类型编写转换与事件发送逻辑。
+//!
+//! ```text
+//! Provider 原始流 ──> [解码器] ──> ResponseStream ──> [编码器 / 累积器] ──> SSE / 前端事件 / 完整文本
+//! ```
+
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::pin::Pin;
+
+/// 响应流 chunk
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseChunk {
+    /// 文本增量
+    TextDelta { text: String },
+    /// 工具调用增量
+    ///
+    /// 同一工具调用的多个增量共享 `index`；`id` 与 `name` 通常只出现在首个增量中，
+    /// `arguments` 为参数 JSON 的片段。
+    ToolDelta {
+        index: u32,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default)]
+        arguments: String,
+    },
+    /// Token 用量
+    Usage(ResponseUsage),
+    /// 响应结束
+    Done {
+        /// 结束原因（OpenAI 语义：stop / length / tool_calls / content_filter）
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        finish_reason: Option<String>,
+    },
+    /// 流中错误
+    Error { message: String },
+}
+
+impl ResponseChunk {
+    /// 文本增量
+    pub fn text(text: impl Into<String>) -> Self {
+        ResponseChunk::TextDelta { text: text.into() }
+    }
+
+    /// 结束 chunk
+    pub fn done(finish_reason: Option<&str>) -> Self {
+        ResponseChunk::Done {
+            finish_reason: finish_reason.map(str::to_string),
+        }
+    }
+
+    /// 错误 chunk
+    pub fn error(message: impl Into<String>) -> Self {
+        ResponseChunk::Error {
+            message: message.into(),
+        }
+    }
+
+    /// 是否为终止 chunk（结束或错误）
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            ResponseChunk::Done { .. } | ResponseChunk::Error { .. }
+        )
+    }
+}
+
+/// Token 用量
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponseUsage {
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read_input_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_creation_input_tokens: Option<u32>,
+}
+
+/// 统一响应流
+pub type ResponseStream = Pin<Box<dyn Stream<Item = ResponseChunk> + Send>>;
+
+/// 将 chunk 序列包装为响应流
+pub fn response_stream_from_chunks(chunks: Vec<ResponseChunk>) -> ResponseStream {
+    Box::pin(futures::stream::iter(chunks))
+}
+
+/// 累积完成的工具调用
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccumulatedToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: String,
+}
+
+/// 响应累积器
+///
+/// 将增量 chunk 合并为完整响应，用于非流式消费方（连接测试、日志、缓存等）。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResponseAccumulator {
+    pub text: String,
+    pub tool_calls: Vec<AccumulatedToolCall>,
+    pub usage: Option<ResponseUsage>,
+    pub finish_reason: Option<String>,
+    pub error: Option<String>,
+    pub done: bool,
+    /// chunk index -> tool_calls 下标
+    tool_indices: Vec<(u32, usize)>,
+}
+
+impl ResponseAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 合并一个 chunk
+    pub fn push(&mut self, chunk: &ResponseChunk) {
+        match chunk {
+            ResponseChunk::TextDelta { text } => self.text.push_str(text),
+            ResponseChunk::ToolDelta {
+                index,
+                id,
+                name,
+                arguments,
+            } => {
+                let slot = match self.tool_indices.iter().find(|(i, _)| i == index) {
+                    Some((_, slot)) => *slot,
+                    None => {
+                        self.tool_calls.push(AccumulatedToolCall::default());
+                        let slot = self.tool_calls.len() - 1;
+                        self.tool_indices.push((*index, slot));
+                        slot
+                    }
+                };
+                let call = &mut self.tool_calls[slot];
+                if let Some(id) = id {
+                    call.id.clone_from(id);
+                }
+                if let Some(name) = name {
+                    call.name.push_str(name);
+                }
+                call.arguments.push_str(arguments);
+            }
+            ResponseChunk::Usage(usage) => self.usage = Some(usage.clone()),
+            ResponseChunk::Done { finish_reason } => {
+                if finish_reason.is_some() {
+                    self.finish_reason.clone_from(finish_reason);
+                }
+                self.done = true;
+            }
+            ResponseChunk::Error { message } => self.error = Some(message.clone()),
+        }
+    }
+
+    /// 消费整个响应流
+    pub async fn collect(mut stream: ResponseStream) -> Self {
+        let mut accumulator = Self::new();
+        while let Some(chunk) = stream.next().await {
+            accumulator.push(&chunk);
+        }
+        accumulator
+    }
+}
+
+impl<'a> FromIterator<&'a ResponseChunk> for ResponseAccumulator {
+    fn from_iter<I: IntoIterator<Item = &'a ResponseChunk>>(iter: I) -> Self {
+        let mut accumulator = Self::new();
+        for chunk in iter {
+            accumulator.push(chunk);
+        }
+        accumulator
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_serialization() {
+        let chunk = ResponseChunk::ToolDelta {
+            index: 0,
+            id: Some("call_1".to_string()),
+            name: Some("search".to_string()),
+            arguments: "{\"q\"".to_string(),
+        };
+        let value = serde_json::to_value(&chunk).unwrap();
+        assert_eq!(value["type"], "tool_delta");
+        assert_eq!(value["name"], "search");
+        assert_eq!(
+            serde_json::from_value::<ResponseChunk>(value).unwrap(),
+            chunk
+        );
+    }
+
+    #[tokio::test]
+    async fn test_accumulator_merges_deltas() {
+        let stream = response_stream_from_chunks(vec![
+            ResponseChunk::text("Hel"),
+            ResponseChunk::text("lo"),
+            ResponseChunk::ToolDelta {
+                index: 1,
+                id: Some("call_1".to_string()),
+                name: Some("search".to_string()),
+                arguments: "{\"q\":".to_string(),
+            },
+            ResponseChunk::ToolDelta {
+                index: 1,
+                id: None,
+                name: None,
+                arguments: "\"rust\"}".to_string(),
+            },
+            ResponseChunk::Usage(ResponseUsage {
+                input_tokens: 3,
+                output_tokens: 5,
+                ..Default::default()
+            }),
+            ResponseChunk::done(Some("tool_calls")),
+        ]);
+
+        let result = ResponseAccumulator::collect(stream).await;
+        assert_eq!(result.text, "Hello");
+        assert_eq!(result.tool_calls.len(), 1);
+        assert_eq!(result.tool_calls[0].id, "call_1");
+        assert_eq!(result.tool_calls[0].arguments, "{\"q\":\"rust\"}");
+        assert_eq!(result.usage.unwrap().output_tokens, 5);
+        assert_eq!(result.finish_reason.as_deref(), Some("tool_calls"));
+        assert!(result.done);
+        assert!(result.error.is_none());
+    }
+}
@@ -0,0 +1,225 @@
+//! 安全模式启动
+//!
+//! 启动时在应用数据目录写入哨兵文件，并随启动进度更新所处阶段；
+//! 应用稳定运行后删除哨兵。若启动时发现上次留下的哨兵，说明上次启动
+//! 未能完成（崩溃或初始化失败），连续失败达到阈值后进入安全模式：
+//! 使用默认配置启动，禁用插件与 MCP 服务器自动启动，并根据失败阶段
+//! 给出可疑原因。
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 连续启动失败多少次后进入安全模式
+pub const SAFE_MODE_FAILURE_THRESHOLD: u32 = 2;
+
+const SENTINEL_FILE_NAME: &str = ".startup_sentinel.json";
+
+/// 启动阶段：加载配置
+pub const STAGE_CONFIG: &str = "config";
+/// 启动阶段：初始化应用状态（数据库、服务）
+pub const STAGE_STATES: &str = "states";
+/// 启动阶段：Tauri setup（插件、MCP、窗口）
+pub const STAGE_SETUP: &str = "setup";
+/// 启动阶段：已完成 setup，等待稳定运行
+pub const STAGE_RUNNING: &str = "running";
+
+static SAFE_MODE_REPORT: OnceLock<SafeModeReport> = OnceLock::new();
+
+/// 哨兵文件内容
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SentinelRecord {
+    /// 截至本次启动的连续失败次数
+    consecutive_failures: u32,
+    /// 最后到达的启动阶段
+    stage: String,
+    /// 本次启动时间
+    started_at: i64,
+}
+
+/// 安全模式报告
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SafeModeReport {
+    /// 本次是否以安全模式启动
+    pub active: bool,
+    /// 连续启动失败次数
+    pub consecutive_failures: u32,
+    /// 上次启动失败时所处的阶段
+    pub failed_stage: Option<String>,
+    /// 可疑原因说明
+    pub suspected_culprit: Option<String>,
+}
+
+/// 启动哨兵
+#[derive(Debug, Clone)]
+pub struct StartupSentinel {
+    path: PathBuf,
+}
+
+impl StartupSentinel {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// 使用应用数据目录下的默认哨兵文件
+    pub fn for_app() -> Result<Self, String> {
+        Ok(Self::new(
+            crate::app_paths::preferred_data_dir()?.join(SENTINEL_FILE_NAME),
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 开始一次启动：检查上次是否失败，写入新的哨兵并返回安全模式报告
+    pub fn begin(&self) -> SafeModeReport {
+        let previous = self.read();
+        let consecutive_failures = previous
+            .as_ref()
+            .map(|record| record.consecutive_failures + 1)
+            .unwrap_or(0);
+        let failed_stage = previous.map(|record| record.stage);
+
+        let record = SentinelRecord {
+            consecutive_failures,
+            stage: STAGE_CONFIG.to_string(),
+            started_at: chrono::Utc::now().timestamp(),
+        };
+        if let Err(e) = self.write(&record) {
+            tracing::warn!("[SafeMode] 写入启动哨兵失败: {}", e);
+        }
+
+        SafeModeReport {
+            active: consecutive_failures >= SAFE_MODE_FAILURE_THRESHOLD,
+            consecutive_failures,
+            suspected_culprit: failed_stage.as_deref().map(describe_stage),
+            failed_stage,
+        }
+    }
+
+    /// 记录当前到达的启动阶段
+    pub fn mark_stage(&self, stage: &str) {
+        let mut record = self.read().unwrap_or_default();
+        record.stage = stage.to_string();
+        if let Err(e) = self.write(&record) {
+            tracing::warn!("[SafeMode] 更新启动阶段失败: {}", e);
+        }
+    }
+
+    /// 应用已稳定运行或正常退出，删除哨兵
+    pub fn mark_healthy(&self) {
+        if self.path.exists() {
+            if let Err(e) = fs::remove_file(&self.path) {
+                tracing::warn!("[SafeMode] 删除启动哨兵失败: {}", e);
+            }
+        }
+    }
+
+    fn read(&self) -> Option<SentinelRecord> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        // 哨兵内容损坏时仍视为一次失败启动
+        Some(serde_json::from_str(&content).unwrap_or_default())
+    }
+
+    fn write(&self, record: &SentinelRecord) -> Result<(), String> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string(record).map_err(|e| e.to_string())?;
+        fs::write(&self.path, content).map_err(|e| e.to_string())
+    }
+}
+
+/// 根据失败阶段给出可疑原因
+fn describe_stage(stage: &str) -> String {
+    match stage {
+        STAGE_CONFIG => "配置文件加载或校验失败，可能是配置文件损坏".to_string(),
+        STAGE_STATES => "应用状态初始化失败，可能是数据库或服务初始化异常".to_string(),
+        STAGE_SETUP => "启动初始化失败，可能是插件、MCP 服务器或窗口初始化异常".to_string(),
+        STAGE_RUNNING => "启动后短时间内崩溃，可能是插件或 MCP 服务器导致".to_string(),
+        other => format!("启动在未知阶段失败: {other}"),
+    }
+}
+
+/// 设置本次启动的安全模式报告（仅首次调用生效）
+pub fn set_safe_mode_report(report: SafeModeReport) {
+    let _ = SAFE_MODE_REPORT.set(report);
+}
+
+/// 获取本次启动的安全模式报告
+pub fn safe_mode_report() -> SafeModeReport {
+    SAFE_MODE_REPORT.get().cloned().unwrap_or_default()
+}
+
+/// 本次是否以安全模式启动
+pub fn is_safe_mode() -> bool {
+    SAFE_MODE_REPORT.get().is_some_and(|report| report.active)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_failures_enter_safe_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let sentinel = StartupSentinel::new(dir.path().join(SENTINEL_FILE_NAME));
+
+        // 首次启动：无历史哨兵
+        let report = sentinel.begin();
+        assert!(!report.active);
+        assert_eq!(report.consecutive_failures, 0);
+
+        // 第一次失败（停在 states 阶段）
+        sentinel.mark_stage(STAGE_STATES);
+        let report = sentinel.begin();
+        assert!(!report.active);
+        assert_eq!(report.consecutive_failures, 1);
+        assert_eq!(report.failed_stage.as_deref(), Some(STAGE_STATES));
+
+        // 第二次失败（停在 setup 阶段）→ 安全模式
+        sentinel.mark_stage(STAGE_SETUP);
+        let report = sentinel.begin();
+        assert!(report.active);
+        assert_eq!(report.consecutive_failures, 2);
+        assert_eq!(report.failed_stage.as_deref(), Some(STAGE_SETUP));
+        assert!(report.suspected_culprit.unwrap().contains("插件"));
+    }
+
+    #[test]
+    fn test_healthy_run_resets_failures() {
+        let dir = tempfile::tempdir().unwrap();
+        let sentinel = StartupSentinel::new(dir.path().join(SENTINEL_FILE_NAME));
+
+        sentinel.begin();
+        sentinel.begin();
+        sentinel.mark_healthy();
+        assert!(!sentinel.path().exists());
+
+        let report = sentinel.begin();
+        assert_eq!(report.consecutive_failures, 0);
+        assert!(report.failed_stage.is_none());
+    }
+
+    #[test]
+    fn test_clean_shutdown_is_not_counted_as_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let sentinel = StartupSentinel::new(dir.path().join(SENTINEL_FILE_NAME));
+
+        // 多次启动后在稳定运行前正常退出（RunEvent::Exit 时清除哨兵）
+        for _ in 0..3 {
+            let report = sentinel.begin();
+            assert!(!report.active);
+            assert_eq!(report.consecutive_failures, 0);
+            sentinel.mark_stage(STAGE_SETUP);
+            sentinel.mark_stage(STAGE_RUNNING);
+            sentinel.mark_healthy();
+        }
+
+        let report = sentinel.begin();
+        assert_eq!(report.consecutive_failures, 0);
+        assert!(report.failed_stage.is_none());
+    }
+}
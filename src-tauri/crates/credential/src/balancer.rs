@@ -1,16 +1,25 @@
 //! 负载均衡器实现
 //!
-//! 提供轮询负载均衡策略，支持凭证冷却和自动恢复
+//! 提供轮询、最少使用、随机与加权负载均衡策略，支持凭证冷却和自动恢复。
+//!
+//! 会话亲和：`select_with_affinity` 将会话/对话 ID 绑定到同一凭证（如 Claude OAuth
+//! 的对话缓存与账号绑定），绑定凭证冷却或不可用时按当前策略重新选择并改绑，
+//! 绑定在 TTL 内无请求时过期。
+//!
+//! 通过 `with_events` 接入凭证池事件总线后，凭证进入/结束冷却时会发布对应事件。
 
 use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
 use lime_core::credential::health::{HealthCheckConfig, HealthChecker};
 use lime_core::credential::pool::{CredentialPool, PoolError};
-use lime_core::credential::types::Credential;
+use lime_core::credential::types::{Credential, CredentialStatus};
+pub use lime_core::credential::{AffinityBinding, DEFAULT_AFFINITY_TTL_SECS};
+use lime_core::credential::{CredentialPoolChange, CredentialPoolEvents, SessionAffinity};
 use lime_core::ProviderType;
 use lime_infra::ProxyClientFactory;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
@@ -25,6 +34,8 @@ pub enum BalanceStrategy {
     LeastUsed,
     /// 随机策略
     Random,
+    /// 加权策略（平滑加权轮询，选择比例与凭证权重成正比）
+    Weighted,
 }
 
 /// 冷却信息
@@ -53,10 +64,16 @@ pub struct LoadBalancer {
     pools: DashMap<ProviderType, Arc<CredentialPool>>,
     /// 轮询索引（每个 Provider 独立）
     round_robin_indices: DashMap<ProviderType, AtomicUsize>,
+    /// 加权轮询的当前权重（每个 Provider 独立，凭证 ID -> 当前权重）
+    weighted_state: DashMap<ProviderType, HashMap<String, i64>>,
+    /// 会话亲和绑定（按 Provider 隔离）
+    affinity: SessionAffinity,
     /// 健康检查器
     health_checker: HealthChecker,
     /// 代理客户端工厂
     proxy_factory: ProxyClientFactory,
+    /// 凭证池事件总线（可选）
+    events: Option<Arc<CredentialPoolEvents>>,
 }
 
 impl LoadBalancer {
@@ -66,8 +83,11 @@ impl LoadBalancer {
             strategy,
             pools: DashMap::new(),
             round_robin_indices: DashMap::new(),
+            weighted_state: DashMap::new(),
+            affinity: SessionAffinity::default(),
             health_checker: HealthChecker::with_defaults(),
             proxy_factory: ProxyClientFactory::new(),
+            events: None,
         }
     }
 
@@ -82,8 +102,11 @@ impl LoadBalancer {
             strategy,
             pools: DashMap::new(),
             round_robin_indices: DashMap::new(),
+            weighted_state: DashMap::new(),
+            affinity: SessionAffinity::default(),
             health_checker: HealthChecker::new(health_config),
             proxy_factory: ProxyClientFactory::new(),
+            events: None,
         }
     }
 
@@ -98,6 +121,18 @@ impl LoadBalancer {
         self.proxy_factory = ProxyClientFactory::new().with_global_proxy(proxy_url);
     }
 
+    /// 设置会话亲和有效期
+    pub fn with_affinity_ttl(mut self, ttl: Duration) -> Self {
+        self.affinity = SessionAffinity::new(ttl);
+        self
+    }
+
+    /// 接入凭证池事件总线
+    pub fn with_events(mut self, events: Arc<CredentialPoolEvents>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
     /// 获取代理客户端工厂
     pub fn proxy_factory(&self) -> &ProxyClientFactory {
         &self.proxy_factory
@@ -134,6 +169,8 @@ impl LoadBalancer {
     /// 移除凭证池
     pub fn remove_pool(&self, provider: ProviderType) -> Option<Arc<CredentialPool>> {
         self.round_robin_indices.remove(&provider);
+        self.weighted_state.remove(&provider);
+        self.affinity.clear_scope(&provider.to_string());
         self.pools.remove(&provider).map(|(_, pool)| pool)
     }
 
@@ -145,14 +182,76 @@ impl LoadBalancer {
     /// 选择下一个可用凭证（使用当前策略）
     pub fn select(&self, provider: ProviderType) -> Result<Credential, PoolError> {
         let pool = self.pools.get(&provider).ok_or(PoolError::EmptyPool)?;
-        pool.refresh_cooldowns();
+        self.refresh_pool_cooldowns(&pool);
         match self.strategy {
             BalanceStrategy::RoundRobin => self.select_round_robin(&pool, provider),
             BalanceStrategy::LeastUsed => self.select_least_used(&pool),
             BalanceStrategy::Random => self.select_random(&pool),
+            BalanceStrategy::Weighted => self.select_weighted(&pool, provider),
         }
     }
 
+    /// 按会话亲和选择凭证
+    ///
+    /// 会话已绑定且绑定未过期、凭证可用时返回同一凭证并顺延有效期；
+    /// 否则按当前策略选择并改绑。`session_id` 为空时等同于 `select`。
+    pub fn select_with_affinity(
+        &self,
+        provider: ProviderType,
+        session_id: Option<&str>,
+    ) -> Result<Credential, PoolError> {
+        let Some(session_id) = session_id.filter(|id| !id.is_empty()) else {
+            return self.select(provider);
+        };
+        let scope = provider.to_string();
+
+        let bound = self
+            .affinity
+            .get(&scope, session_id)
+            .map(|binding| binding.credential_id);
+        if let Some(credential_id) = bound {
+            if let Some(credential) = self.available_credential(provider, &credential_id) {
+                self.affinity.bind(&scope, session_id, &credential.id);
+                return Ok(credential);
+            }
+            tracing::debug!(
+                credential_id = %credential_id,
+                provider = %provider,
+                "亲和凭证不可用，重新选择"
+            );
+        }
+
+        let credential = self.select(provider)?;
+        self.affinity.bind(&scope, session_id, &credential.id);
+        Ok(credential)
+    }
+
+    /// 查询会话当前的亲和绑定（已过期时返回 None）
+    pub fn affinity(&self, provider: ProviderType, session_id: &str) -> Option<AffinityBinding> {
+        self.affinity.get(&provider.to_string(), session_id)
+    }
+
+    /// 解除会话亲和绑定
+    pub fn clear_affinity(&self, provider: ProviderType, session_id: &str) -> bool {
+        self.affinity.clear(&provider.to_string(), session_id)
+    }
+
+    /// 清理过期的亲和绑定，返回清理数量
+    pub fn purge_expired_affinities(&self) -> usize {
+        self.affinity.purge_expired()
+    }
+
+    /// 获取池中指定的可用凭证（会先刷新冷却状态）
+    fn available_credential(
+        &self,
+        provider: ProviderType,
+        credential_id: &str,
+    ) -> Option<Credential> {
+        let pool = self.pools.get(&provider)?;
+        self.refresh_pool_cooldowns(&pool);
+        pool.get(credential_id).filter(|c| c.is_available())
+    }
+
     /// 选择下一个可用凭证并创建配置了代理的 HTTP 客户端
     pub fn select_with_client(
         &self,
@@ -183,7 +282,7 @@ impl LoadBalancer {
         max_attempts: Option<usize>,
     ) -> Result<CredentialSelection, PoolError> {
         let pool = self.pools.get(&provider).ok_or(PoolError::EmptyPool)?;
-        pool.refresh_cooldowns();
+        self.refresh_pool_cooldowns(&pool);
 
         let active_count = pool.active_count();
         if active_count == 0 {
@@ -296,6 +395,58 @@ impl LoadBalancer {
         Ok(active_creds[index].clone())
     }
 
+    /// 加权选择凭证
+    ///
+    /// 平滑加权轮询：每次为所有可用凭证累加各自权重，选出当前权重最大者并减去权重总和，
+    /// 使选择比例与权重成正比且同一凭证不会被连续集中选中。
+    fn select_weighted(
+        &self,
+        pool: &CredentialPool,
+        provider: ProviderType,
+    ) -> Result<Credential, PoolError> {
+        let mut active_creds: Vec<Credential> = pool
+            .all()
+            .into_iter()
+            .filter(|c| c.is_available())
+            .collect();
+
+        if active_creds.is_empty() {
+            return Err(PoolError::NoAvailableCredential);
+        }
+        active_creds.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut state = self.weighted_state.entry(provider).or_default();
+        state.retain(|id, _| active_creds.iter().any(|c| &c.id == id));
+
+        let total: i64 = active_creds.iter().map(|c| c.weight.max(1) as i64).sum();
+        let mut best: Option<(usize, i64)> = None;
+        for (index, cred) in active_creds.iter().enumerate() {
+            let current = state.entry(cred.id.clone()).or_insert(0);
+            *current += cred.weight.max(1) as i64;
+            if best.is_none_or(|(_, value)| *current > value) {
+                best = Some((index, *current));
+            }
+        }
+
+        let (index, _) = best.ok_or(PoolError::NoAvailableCredential)?;
+        let selected = active_creds.swap_remove(index);
+        if let Some(current) = state.get_mut(&selected.id) {
+            *current -= total;
+        }
+        Ok(selected)
+    }
+
+    /// 更新凭证权重（运行时生效）
+    pub fn set_weight(
+        &self,
+        provider: ProviderType,
+        credential_id: &str,
+        weight: u32,
+    ) -> Result<(), PoolError> {
+        let pool = self.pools.get(&provider).ok_or(PoolError::EmptyPool)?;
+        pool.set_weight(credential_id, weight)
+    }
+
     /// 标记凭证为冷却状态
     pub fn mark_cooldown(
         &self,
@@ -304,7 +455,17 @@ impl LoadBalancer {
         duration: Duration,
     ) -> Result<(), PoolError> {
         let pool = self.pools.get(&provider).ok_or(PoolError::EmptyPool)?;
-        pool.mark_cooldown(credential_id, duration)
+        pool.mark_cooldown(credential_id, duration)?;
+        if let Some(CredentialStatus::Cooldown { until }) =
+            pool.get(credential_id).map(|c| c.status)
+        {
+            self.emit(CredentialPoolChange::CooldownStarted {
+                uuid: credential_id.to_string(),
+                provider_type: provider.to_string(),
+                until,
+            });
+        }
+        Ok(())
     }
 
     /// 恢复凭证为活跃状态
@@ -314,13 +475,40 @@ impl LoadBalancer {
         credential_id: &str,
     ) -> Result<(), PoolError> {
         let pool = self.pools.get(&provider).ok_or(PoolError::EmptyPool)?;
-        pool.mark_active(credential_id)
+        let was_cooling = pool
+            .get(credential_id)
+            .is_some_and(|c| matches!(c.status, CredentialStatus::Cooldown { .. }));
+        pool.mark_active(credential_id)?;
+        if was_cooling {
+            self.emit_cooldown_ended(provider, credential_id.to_string());
+        }
+        Ok(())
     }
 
     /// 刷新所有池的冷却状态
     pub fn refresh_all_cooldowns(&self) {
         for pool in self.pools.iter() {
-            pool.refresh_cooldowns();
+            self.refresh_pool_cooldowns(&pool);
+        }
+    }
+
+    /// 刷新单个池的冷却状态，并为恢复的凭证发布冷却结束事件
+    fn refresh_pool_cooldowns(&self, pool: &CredentialPool) {
+        for credential_id in pool.refresh_cooldowns() {
+            self.emit_cooldown_ended(pool.provider(), credential_id);
+        }
+    }
+
+    fn emit_cooldown_ended(&self, provider: ProviderType, credential_id: String) {
+        self.emit(CredentialPoolChange::CooldownEnded {
+            uuid: credential_id,
+            provider_type: provider.to_string(),
+        });
+    }
+
+    fn emit(&self, change: CredentialPoolChange) {
+        if let Some(events) = &self.events {
+            events.emit(change);
         }
     }
 
@@ -424,6 +612,89 @@ mod balancer_tests {
         assert_eq!(ids.len(), 3);
     }
 
+    #[test]
+    fn test_load_balancer_select_weighted() {
+        let lb = LoadBalancer::new(BalanceStrategy::Weighted);
+        let pool = Arc::new(CredentialPool::new(ProviderType::Kiro));
+        pool.add(create_test_credential("paid", ProviderType::Kiro).with_weight(3))
+            .unwrap();
+        pool.add(create_test_credential("free", ProviderType::Kiro))
+            .unwrap();
+        lb.register_pool(pool);
+
+        let picks: Vec<String> = (0..8)
+            .map(|_| lb.select(ProviderType::Kiro).unwrap().id)
+            .collect();
+        assert_eq!(picks.iter().filter(|id| *id == "paid").count(), 6);
+        assert_eq!(picks.iter().filter(|id| *id == "free").count(), 2);
+
+        lb.set_weight(ProviderType::Kiro, "free", 3).unwrap();
+        let picks: Vec<String> = (0..8)
+            .map(|_| lb.select(ProviderType::Kiro).unwrap().id)
+            .collect();
+        assert_eq!(picks.iter().filter(|id| *id == "free").count(), 4);
+        assert!(lb.set_weight(ProviderType::Kiro, "missing", 2).is_err());
+    }
+
+    #[test]
+    fn test_load_balancer_select_with_affinity() {
+        let lb = LoadBalancer::round_robin();
+        let pool = Arc::new(CredentialPool::new(ProviderType::Claude));
+        pool.add(create_test_credential("cred-1", ProviderType::Claude))
+            .unwrap();
+        pool.add(create_test_credential("cred-2", ProviderType::Claude))
+            .unwrap();
+        lb.register_pool(pool);
+
+        let first = lb
+            .select_with_affinity(ProviderType::Claude, Some("conv-a"))
+            .unwrap();
+        for _ in 0..3 {
+            let again = lb
+                .select_with_affinity(ProviderType::Claude, Some("conv-a"))
+                .unwrap();
+            assert_eq!(again.id, first.id);
+        }
+
+        // 绑定凭证冷却时回退到其他凭证并改绑
+        lb.mark_cooldown(ProviderType::Claude, &first.id, Duration::hours(1))
+            .unwrap();
+        let fallback = lb
+            .select_with_affinity(ProviderType::Claude, Some("conv-a"))
+            .unwrap();
+        assert_ne!(fallback.id, first.id);
+        assert_eq!(
+            lb.affinity(ProviderType::Claude, "conv-a")
+                .unwrap()
+                .credential_id,
+            fallback.id
+        );
+
+        assert!(lb.clear_affinity(ProviderType::Claude, "conv-a"));
+        assert!(lb.affinity(ProviderType::Claude, "conv-a").is_none());
+    }
+
+    #[test]
+    fn test_load_balancer_affinity_expires() {
+        let lb = LoadBalancer::round_robin().with_affinity_ttl(Duration::zero());
+        let pool = Arc::new(CredentialPool::new(ProviderType::Claude));
+        pool.add(create_test_credential("cred-1", ProviderType::Claude))
+            .unwrap();
+        pool.add(create_test_credential("cred-2", ProviderType::Claude))
+            .unwrap();
+        lb.register_pool(pool);
+
+        let first = lb
+            .select_with_affinity(ProviderType::Claude, Some("conv-b"))
+            .unwrap();
+        assert!(lb.affinity(ProviderType::Claude, "conv-b").is_none());
+        let second = lb
+            .select_with_affinity(ProviderType::Claude, Some("conv-b"))
+            .unwrap();
+        assert_ne!(first.id, second.id);
+        assert_eq!(lb.purge_expired_affinities(), 1);
+    }
+
     #[test]
     fn test_load_balancer_select_empty_pool() {
         let lb = LoadBalancer::round_robin();
@@ -537,6 +808,38 @@ mod balancer_tests {
         ));
     }
 
+    #[test]
+    fn test_load_balancer_cooldown_events() {
+        let events = Arc::new(CredentialPoolEvents::default());
+        let lb = LoadBalancer::round_robin().with_events(events.clone());
+        let pool = Arc::new(CredentialPool::new(ProviderType::Kiro));
+        pool.add(create_test_credential("cred-1", ProviderType::Kiro))
+            .unwrap();
+        lb.register_pool(pool.clone());
+
+        lb.mark_cooldown(ProviderType::Kiro, "cred-1", Duration::seconds(-1))
+            .unwrap();
+        lb.select(ProviderType::Kiro).unwrap();
+        // 已恢复的凭证不会重复发布冷却结束
+        lb.mark_active(ProviderType::Kiro, "cred-1").unwrap();
+
+        let changes: Vec<_> = events
+            .since(0)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.change)
+            .collect();
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(
+            &changes[0],
+            CredentialPoolChange::CooldownStarted { uuid, .. } if uuid == "cred-1"
+        ));
+        assert!(matches!(
+            &changes[1],
+            CredentialPoolChange::CooldownEnded { uuid, .. } if uuid == "cred-1"
+        ));
+    }
+
     #[test]
     fn test_load_balancer_earliest_recovery() {
         let lb = LoadBalancer::round_robin();
@@ -54,6 +54,23 @@ impl Encryptor {
     ///
     /// 返回格式：enc2:base64(nonce || ciphertext)
     pub fn encrypt(&self, plaintext: &str) -> Result<String, EncryptionError> {
+        Ok(format!("{}{}", ENCRYPTED_PREFIX, self.seal(plaintext)?))
+    }
+
+    /// 解密密文
+    ///
+    /// 输入格式：enc2:base64(nonce || ciphertext)
+    pub fn decrypt(&self, encrypted: &str) -> Result<String, EncryptionError> {
+        let encoded = encrypted
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .ok_or(EncryptionError::InvalidFormat)?;
+        self.open(encoded)
+    }
+
+    /// 加密明文，返回不带前缀的 base64(nonce || ciphertext)
+    ///
+    /// 供需要自定义密文头的调用方使用（如 `master_key` 的版本化格式）。
+    pub fn seal(&self, plaintext: &str) -> Result<String, EncryptionError> {
         use chacha20poly1305::aead::AeadCore;
 
         // 生成随机 nonce
@@ -70,19 +87,11 @@ impl Encryptor {
         combined.extend_from_slice(&nonce);
         combined.extend_from_slice(&ciphertext);
 
-        // Base64 编码并添加前缀
-        Ok(format!("{}{}", ENCRYPTED_PREFIX, BASE64.encode(&combined)))
+        Ok(BASE64.encode(&combined))
     }
 
-    /// 解密密文
-    ///
-    /// 输入格式：enc2:base64(nonce || ciphertext)
-    pub fn decrypt(&self, encrypted: &str) -> Result<String, EncryptionError> {
-        // 检查前缀
-        let encoded = encrypted
-            .strip_prefix(ENCRYPTED_PREFIX)
-            .ok_or(EncryptionError::InvalidFormat)?;
-
+    /// 解密不带前缀的 base64(nonce || ciphertext)
+    pub fn open(&self, encoded: &str) -> Result<String, EncryptionError> {
         // Base64 解码
         let combined = BASE64
             .decode(encoded)
@@ -0,0 +1,322 @@
+//! 凭证批量导入
+//!
+//! 从 YAML 配置导入凭证时并发校验（限制并发数），逐个回调持久化并上报进度。
+//! 导入进度记录在配置目录下的状态文件中：应用中途退出后，下次导入同一份配置时
+//! 跳过已完成的凭证，只处理剩余部分。
+
+use crate::sync::{CredentialSyncService, SyncError};
+use chrono::{DateTime, Utc};
+use lime_core::models::provider_pool_model::{CredentialData, ProviderCredential};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// 默认并发校验数
+pub const DEFAULT_IMPORT_CONCURRENCY: usize = 8;
+
+/// 导入进度事件名
+pub const CREDENTIAL_IMPORT_PROGRESS_EVENT: &str = "credential-import:progress";
+
+/// 导入状态文件名（位于配置文件同级目录）
+const IMPORT_STATE_FILE: &str = "credential_import_state.json";
+
+/// 每完成多少个凭证保存一次导入状态
+const STATE_SAVE_INTERVAL: usize = 16;
+
+/// 导入进度
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialImportProgress {
+    /// 配置中的凭证总数
+    pub total: usize,
+    /// 已处理数（含上次导入已完成的部分）
+    pub processed: usize,
+    /// 本次导入成功数
+    pub succeeded: usize,
+    /// 本次导入失败数
+    pub failed: usize,
+    /// 从上次中断处恢复时跳过的已完成数
+    pub resumed: usize,
+    /// 最近处理的凭证 UUID
+    pub current: Option<String>,
+    /// 是否已全部完成
+    pub done: bool,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialImportReport {
+    pub progress: CredentialImportProgress,
+    /// 校验或持久化失败的凭证（UUID -> 原因）
+    pub errors: HashMap<String, String>,
+}
+
+/// 可恢复的导入状态
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialImportState {
+    /// 配置中凭证集合的指纹，配置变化后重新开始
+    pub fingerprint: String,
+    /// 已成功导入的凭证 UUID
+    pub completed: HashSet<String>,
+    /// 失败的凭证（恢复时会重试）
+    pub failed: HashMap<String, String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl CredentialImportState {
+    /// 读取状态文件，不存在或损坏时返回 None
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&mut self, path: &Path) -> Result<(), SyncError> {
+        self.updated_at = Some(Utc::now());
+        let content = serde_json::to_string(self)
+            .map_err(|e| SyncError::IoError(format!("序列化导入状态失败: {e}")))?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+}
+
+/// 计算凭证集合指纹（与顺序无关）
+pub fn credentials_fingerprint(credentials: &[ProviderCredential]) -> String {
+    let mut entries: Vec<String> = credentials
+        .iter()
+        .map(|c| {
+            let data = serde_json::to_string(&c.credential).unwrap_or_default();
+            format!("{}|{}|{}", c.uuid, c.is_disabled, data)
+        })
+        .collect();
+    entries.sort();
+
+    let mut hasher = Sha256::new();
+    for entry in &entries {
+        hasher.update(entry.as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// 校验单个凭证（只做本地检查，不发起网络请求）
+///
+/// - OAuth 凭证：token 文件存在且为合法 JSON
+/// - API Key 凭证：api_key 非空，自定义 base_url 为合法的 http(s) 地址
+pub async fn validate_credential(cred: &ProviderCredential) -> Result<(), String> {
+    match &cred.credential {
+        CredentialData::KiroOAuth { creds_file_path }
+        | CredentialData::GeminiOAuth {
+            creds_file_path, ..
+        }
+        | CredentialData::AntigravityOAuth {
+            creds_file_path, ..
+        }
+        | CredentialData::CodexOAuth {
+            creds_file_path, ..
+        }
+        | CredentialData::ClaudeOAuth { creds_file_path } => {
+            let content = tokio::fs::read_to_string(creds_file_path)
+                .await
+                .map_err(|e| format!("无法读取 token 文件 {creds_file_path}: {e}"))?;
+            serde_json::from_str::<serde_json::Value>(&content)
+                .map_err(|e| format!("token 文件不是合法 JSON {creds_file_path}: {e}"))?;
+            Ok(())
+        }
+        CredentialData::OpenAIKey { api_key, .. }
+        | CredentialData::ClaudeKey { api_key, .. }
+        | CredentialData::VertexKey { api_key, .. }
+        | CredentialData::GeminiApiKey { api_key, .. }
+        | CredentialData::AnthropicKey { api_key, .. } => {
+            if api_key.trim().is_empty() {
+                return Err("api_key 为空".to_string());
+            }
+            if let Some(base_url) = cred.credential.base_url().filter(|u| !u.is_empty()) {
+                let url = reqwest::Url::parse(base_url)
+                    .map_err(|e| format!("无效的 base_url {base_url}: {e}"))?;
+                if !matches!(url.scheme(), "http" | "https") {
+                    return Err(format!("base_url 必须为 http(s) 地址: {base_url}"));
+                }
+            }
+            Ok(())
+        }
+        CredentialData::Mock { .. } => Ok(()),
+    }
+}
+
+impl CredentialSyncService {
+    /// 导入状态文件路径
+    pub fn import_state_path(&self) -> Result<PathBuf, SyncError> {
+        let config_path = self.config_path()?;
+        let dir = config_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+        Ok(dir.join(IMPORT_STATE_FILE))
+    }
+
+    /// 从配置并发导入凭证
+    ///
+    /// 凭证并发校验（最多 `concurrency` 个同时进行），校验通过后在调用方线程中依次调用
+    /// `persist` 写入存储，每处理一个凭证调用一次 `on_progress`。全部处理完且无失败时
+    /// 删除导入状态文件；否则保留，下次导入同一份配置时只重试失败和未处理的凭证。
+    pub async fn import_from_config<P, F>(
+        &self,
+        concurrency: usize,
+        mut persist: P,
+        mut on_progress: F,
+    ) -> Result<CredentialImportReport, SyncError>
+    where
+        P: FnMut(&ProviderCredential) -> Result<(), String>,
+        F: FnMut(&CredentialImportProgress),
+    {
+        let credentials = self.load_from_config()?;
+        let state_path = self.import_state_path()?;
+        let fingerprint = credentials_fingerprint(&credentials);
+
+        let mut state = CredentialImportState::load(&state_path)
+            .filter(|s| s.fingerprint == fingerprint)
+            .unwrap_or_else(|| CredentialImportState {
+                fingerprint,
+                ..Default::default()
+            });
+        state.failed.clear();
+
+        let mut progress = CredentialImportProgress {
+            total: credentials.len(),
+            ..Default::default()
+        };
+        let pending: Vec<ProviderCredential> = credentials
+            .into_iter()
+            .filter(|c| !state.completed.contains(&c.uuid))
+            .collect();
+        progress.resumed = progress.total - pending.len();
+        progress.processed = progress.resumed;
+        if progress.resumed > 0 {
+            tracing::info!(
+                "[CREDENTIAL_IMPORT] 从上次中断处恢复，跳过 {} 个已导入凭证",
+                progress.resumed
+            );
+        }
+
+        let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+        let mut tasks = JoinSet::new();
+        for cred in pending {
+            let semaphore = semaphore.clone();
+            tasks.spawn(async move {
+                let _permit = semaphore.acquire_owned().await;
+                let result = validate_credential(&cred).await;
+                (cred, result)
+            });
+        }
+
+        let mut since_save = 0;
+        while let Some(joined) = tasks.join_next().await {
+            let Ok((cred, result)) = joined else {
+                continue;
+            };
+            match result.and_then(|_| persist(&cred)) {
+                Ok(()) => {
+                    state.completed.insert(cred.uuid.clone());
+                    progress.succeeded += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("[CREDENTIAL_IMPORT] 凭证 {} 导入失败: {}", cred.uuid, e);
+                    state.failed.insert(cred.uuid.clone(), e);
+                    progress.failed += 1;
+                }
+            }
+            progress.processed += 1;
+            progress.current = Some(cred.uuid);
+            on_progress(&progress);
+
+            since_save += 1;
+            if since_save >= STATE_SAVE_INTERVAL {
+                since_save = 0;
+                if let Err(e) = state.save(&state_path) {
+                    tracing::warn!("[CREDENTIAL_IMPORT] 保存导入状态失败: {}", e);
+                }
+            }
+        }
+
+        if state.failed.is_empty() {
+            let _ = std::fs::remove_file(&state_path);
+        } else {
+            state.save(&state_path)?;
+        }
+
+        progress.done = true;
+        progress.current = None;
+        on_progress(&progress);
+
+        Ok(CredentialImportReport {
+            progress,
+            errors: state.failed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::models::provider_pool_model::PoolProviderType;
+
+    fn api_key_credential(uuid: &str, api_key: &str) -> ProviderCredential {
+        let mut cred = ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: api_key.to_string(),
+                base_url: None,
+            },
+        );
+        cred.uuid = uuid.to_string();
+        cred
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_order() {
+        let a = api_key_credential("a", "sk-a");
+        let b = api_key_credential("b", "sk-b");
+        assert_eq!(
+            credentials_fingerprint(&[a.clone(), b.clone()]),
+            credentials_fingerprint(&[b.clone(), a.clone()])
+        );
+
+        let mut changed = b;
+        changed.is_disabled = true;
+        assert_ne!(
+            credentials_fingerprint(&[a.clone(), changed]),
+            credentials_fingerprint(&[a, api_key_credential("b", "sk-b")])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_credential() {
+        assert!(validate_credential(&api_key_credential("a", "sk-a"))
+            .await
+            .is_ok());
+        assert!(validate_credential(&api_key_credential("a", "  "))
+            .await
+            .is_err());
+
+        let mut relay = api_key_credential("b", "sk-b");
+        relay
+            .credential
+            .set_base_url("ftp://relay.example.com".to_string());
+        assert!(validate_credential(&relay).await.is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let token_path = dir.path().join("kiro.json");
+        let oauth = ProviderCredential::new(
+            PoolProviderType::Kiro,
+            CredentialData::KiroOAuth {
+                creds_file_path: token_path.to_string_lossy().to_string(),
+            },
+        );
+        assert!(validate_credential(&oauth).await.is_err());
+        std::fs::write(&token_path, r#"{"accessToken":"x"}"#).unwrap();
+        assert!(validate_credential(&oauth).await.is_ok());
+    }
+}
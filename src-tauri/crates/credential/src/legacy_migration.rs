@@ -0,0 +1,394 @@
+//! 旧版 config.yaml 凭证迁移
+//!
+//! 早期版本的凭证只保存在 config.yaml 的 `credential_pool` 中。启动时将尚未迁移的条目
+//! 转换为凭证池（`provider_pool_credentials`）记录，并把条目 ID 记入
+//! `credential_pool.migrated_ids`，之后的启动不再重复处理。
+//!
+//! 映射规则：
+//! - `kiro` / `gemini` / `codex`：OAuth 凭证，token 文件路径相对于 `auth_dir`
+//! - `openai` / `claude`：API Key 凭证
+//! - `gemini_api_keys` / `vertex_api_keys`：保留排除模型与模型别名
+//! - `qwen`：Qwen OAuth 已不再支持，标记为跳过
+//!
+//! ASR 凭证仍由 config.yaml 管理，不参与迁移。
+
+use chrono::{DateTime, Utc};
+use lime_core::config::{expand_tilde, Config};
+use lime_core::database::dao::provider_pool::ProviderPoolDao;
+use lime_core::database::DbConnection;
+use lime_core::models::provider_pool_model::{
+    CredentialData, CredentialSource, PoolProviderType, ProviderCredential,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 迁移报告文件名（位于应用数据目录）
+pub const LEGACY_CREDENTIAL_MIGRATION_REPORT_FILE: &str = "legacy_credential_migration.json";
+
+/// 单个条目的迁移结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LegacyCredentialMigrationItem {
+    /// config.yaml 中的分组（kiro、openai 等）
+    pub section: String,
+    pub id: String,
+    /// 目标凭证池类型
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_type: Option<String>,
+    /// 跳过或失败原因
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// 迁移报告
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LegacyCredentialMigrationReport {
+    /// 新写入凭证池的条目
+    pub migrated: Vec<LegacyCredentialMigrationItem>,
+    /// 凭证池已存在或类型不再支持的条目（同样标记为已迁移）
+    pub skipped: Vec<LegacyCredentialMigrationItem>,
+    /// 迁移失败的条目（不标记，下次启动重试）
+    pub failed: Vec<LegacyCredentialMigrationItem>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl LegacyCredentialMigrationReport {
+    /// 是否有条目被处理（需要回写 config.yaml）
+    pub fn has_changes(&self) -> bool {
+        !self.migrated.is_empty() || !self.skipped.is_empty()
+    }
+
+    /// 读取上次保存的报告
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| format!("序列化迁移报告失败: {e}"))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {e}"))?;
+        }
+        std::fs::write(path, content).map_err(|e| format!("写入迁移报告失败: {e}"))
+    }
+}
+
+/// config.yaml 中的一条旧版凭证
+struct LegacyEntry {
+    section: &'static str,
+    id: String,
+    credential: Result<ProviderCredential, String>,
+}
+
+/// 将 config.yaml 中尚未迁移的凭证写入凭证池，并把处理过的条目记入
+/// `config.credential_pool.migrated_ids`
+///
+/// 调用方在 [`LegacyCredentialMigrationReport::has_changes`] 为真时负责保存配置。
+pub fn migrate_legacy_credentials(
+    db: &DbConnection,
+    config: &mut Config,
+) -> Result<LegacyCredentialMigrationReport, String> {
+    let entries = collect_legacy_entries(config);
+    let mut report = LegacyCredentialMigrationReport::default();
+    if entries.is_empty() {
+        return Ok(report);
+    }
+
+    let conn = lime_core::database::lock_db(db)?;
+    for entry in entries {
+        let credential = match entry.credential {
+            Ok(credential) => credential,
+            Err(reason) => {
+                report.skipped.push(LegacyCredentialMigrationItem {
+                    section: entry.section.to_string(),
+                    id: entry.id.clone(),
+                    provider_type: None,
+                    reason: Some(reason),
+                });
+                config.credential_pool.migrated_ids.push(entry.id);
+                continue;
+            }
+        };
+        let mut item = LegacyCredentialMigrationItem {
+            section: entry.section.to_string(),
+            id: entry.id.clone(),
+            provider_type: Some(credential.provider_type.to_string()),
+            reason: None,
+        };
+
+        if let Err(reason) = check_token_file(&credential) {
+            item.reason = Some(reason);
+            report.failed.push(item);
+            continue;
+        }
+
+        match ProviderPoolDao::get_by_uuid(&conn, &credential.uuid) {
+            Ok(Some(_)) => {
+                item.reason = Some("凭证池中已存在".to_string());
+                report.skipped.push(item);
+            }
+            Ok(None) => match ProviderPoolDao::insert(&conn, &credential) {
+                Ok(()) => report.migrated.push(item),
+                Err(e) => {
+                    item.reason = Some(format!("写入凭证池失败: {e}"));
+                    report.failed.push(item);
+                    continue;
+                }
+            },
+            Err(e) => {
+                item.reason = Some(format!("查询凭证池失败: {e}"));
+                report.failed.push(item);
+                continue;
+            }
+        }
+        config.credential_pool.migrated_ids.push(entry.id);
+    }
+
+    report.finished_at = Some(Utc::now());
+    Ok(report)
+}
+
+/// OAuth 凭证的 token 文件需存在，否则迁移后无法使用
+fn check_token_file(credential: &ProviderCredential) -> Result<(), String> {
+    let path = match &credential.credential {
+        CredentialData::KiroOAuth { creds_file_path }
+        | CredentialData::GeminiOAuth {
+            creds_file_path, ..
+        }
+        | CredentialData::CodexOAuth {
+            creds_file_path, ..
+        } => creds_file_path,
+        _ => return Ok(()),
+    };
+    if Path::new(path).is_file() {
+        Ok(())
+    } else {
+        Err(format!("token 文件不存在: {path}"))
+    }
+}
+
+/// 按映射规则转换尚未迁移的条目
+fn collect_legacy_entries(config: &Config) -> Vec<LegacyEntry> {
+    let pool = &config.credential_pool;
+    let auth_dir = expand_tilde(&config.auth_dir);
+    let token_path = |token_file: &str| auth_dir.join(token_file).to_string_lossy().to_string();
+    let mut entries = Vec::new();
+    let mut push = |section: &'static str,
+                    id: &str,
+                    disabled: bool,
+                    proxy_url: Option<&String>,
+                    mapped: Result<(PoolProviderType, CredentialData), String>| {
+        let credential = mapped.map(|(provider_type, data)| {
+            let mut credential = ProviderCredential::new_with_source(
+                provider_type,
+                data,
+                CredentialSource::Imported,
+            );
+            credential.uuid = id.to_string();
+            credential.name = Some(id.to_string());
+            credential.is_disabled = disabled;
+            credential.proxy_url = proxy_url.cloned();
+            credential
+        });
+        entries.push(LegacyEntry {
+            section,
+            id: id.to_string(),
+            credential,
+        });
+    };
+
+    for entry in &pool.kiro {
+        push(
+            "kiro",
+            &entry.id,
+            entry.disabled,
+            entry.proxy_url.as_ref(),
+            Ok((
+                PoolProviderType::Kiro,
+                CredentialData::KiroOAuth {
+                    creds_file_path: token_path(&entry.token_file),
+                },
+            )),
+        );
+    }
+    for entry in &pool.gemini {
+        push(
+            "gemini",
+            &entry.id,
+            entry.disabled,
+            entry.proxy_url.as_ref(),
+            Ok((
+                PoolProviderType::Gemini,
+                CredentialData::GeminiOAuth {
+                    creds_file_path: token_path(&entry.token_file),
+                    project_id: None,
+                },
+            )),
+        );
+    }
+    for entry in &pool.codex {
+        push(
+            "codex",
+            &entry.id,
+            entry.disabled,
+            entry.proxy_url.as_ref(),
+            Ok((
+                PoolProviderType::Codex,
+                CredentialData::CodexOAuth {
+                    creds_file_path: token_path(&entry.token_file),
+                    api_base_url: None,
+                },
+            )),
+        );
+    }
+    for entry in &pool.qwen {
+        push(
+            "qwen",
+            &entry.id,
+            entry.disabled,
+            entry.proxy_url.as_ref(),
+            Err("Qwen OAuth 凭证已不再支持，请改用 API Key Provider".to_string()),
+        );
+    }
+    for entry in &pool.openai {
+        push(
+            "openai",
+            &entry.id,
+            entry.disabled,
+            entry.proxy_url.as_ref(),
+            Ok((
+                PoolProviderType::OpenAI,
+                CredentialData::OpenAIKey {
+                    api_key: entry.api_key.clone(),
+                    base_url: entry.base_url.clone(),
+                },
+            )),
+        );
+    }
+    for entry in &pool.claude {
+        push(
+            "claude",
+            &entry.id,
+            entry.disabled,
+            entry.proxy_url.as_ref(),
+            Ok((
+                PoolProviderType::Claude,
+                CredentialData::ClaudeKey {
+                    api_key: entry.api_key.clone(),
+                    base_url: entry.base_url.clone(),
+                },
+            )),
+        );
+    }
+    for entry in &pool.gemini_api_keys {
+        push(
+            "gemini_api_keys",
+            &entry.id,
+            entry.disabled,
+            entry.proxy_url.as_ref(),
+            Ok((
+                PoolProviderType::GeminiApiKey,
+                CredentialData::GeminiApiKey {
+                    api_key: entry.api_key.clone(),
+                    base_url: entry.base_url.clone(),
+                    excluded_models: entry.excluded_models.clone(),
+                },
+            )),
+        );
+    }
+    for entry in &pool.vertex_api_keys {
+        push(
+            "vertex_api_keys",
+            &entry.id,
+            entry.disabled,
+            entry.proxy_url.as_ref(),
+            Ok((
+                PoolProviderType::Vertex,
+                CredentialData::VertexKey {
+                    api_key: entry.api_key.clone(),
+                    base_url: entry.base_url.clone(),
+                    model_aliases: entry
+                        .models
+                        .iter()
+                        .map(|m| (m.alias.clone(), m.name.clone()))
+                        .collect(),
+                },
+            )),
+        );
+    }
+
+    entries.retain(|entry| !pool.migrated_ids.contains(&entry.id));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::config::{ApiKeyEntry, CredentialEntry};
+    use std::sync::{Arc, Mutex};
+
+    fn test_db() -> DbConnection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        lime_core::database::schema::create_tables(&conn).unwrap();
+        Arc::new(Mutex::new(conn))
+    }
+
+    #[test]
+    fn test_migrate_legacy_credentials() {
+        let auth_dir = tempfile::tempdir().unwrap();
+        std::fs::write(auth_dir.path().join("kiro-1.json"), "{}").unwrap();
+
+        let mut config = Config::default();
+        config.auth_dir = auth_dir.path().to_string_lossy().to_string();
+        config.credential_pool.kiro = vec![
+            CredentialEntry {
+                id: "kiro-1".to_string(),
+                token_file: "kiro-1.json".to_string(),
+                disabled: false,
+                proxy_url: None,
+            },
+            CredentialEntry {
+                id: "kiro-missing".to_string(),
+                token_file: "missing.json".to_string(),
+                disabled: false,
+                proxy_url: None,
+            },
+        ];
+        config.credential_pool.qwen = vec![CredentialEntry {
+            id: "qwen-1".to_string(),
+            token_file: "qwen-1.json".to_string(),
+            disabled: false,
+            proxy_url: None,
+        }];
+        config.credential_pool.openai = vec![ApiKeyEntry {
+            id: "openai-1".to_string(),
+            api_key: "sk-test".to_string(),
+            base_url: Some("https://relay.example.com/v1".to_string()),
+            disabled: true,
+            proxy_url: None,
+        }];
+
+        let db = test_db();
+        let report = migrate_legacy_credentials(&db, &mut config).unwrap();
+        let ids = |items: &[LegacyCredentialMigrationItem]| {
+            items.iter().map(|i| i.id.clone()).collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&report.migrated), vec!["kiro-1", "openai-1"]);
+        assert_eq!(ids(&report.skipped), vec!["qwen-1"]);
+        assert_eq!(ids(&report.failed), vec!["kiro-missing"]);
+        assert!(report.has_changes());
+
+        let conn = db.lock().unwrap();
+        let openai = ProviderPoolDao::get_by_uuid(&conn, "openai-1")
+            .unwrap()
+            .unwrap();
+        assert!(openai.is_disabled);
+        assert_eq!(openai.source, CredentialSource::Imported);
+        drop(conn);
+
+        // 已迁移条目不再处理，失败条目下次重试
+        let report = migrate_legacy_credentials(&db, &mut config).unwrap();
+        assert!(report.migrated.is_empty() && report.skipped.is_empty());
+        assert_eq!(ids(&report.failed), vec!["kiro-missing"]);
+    }
+}
@@ -5,18 +5,46 @@
 //! ## 模块结构
 //!
 //! - `balancer` - 负载均衡策略（轮询、最少使用、随机）
-//! - `quota` - 配额超限检测、自动切换和冷却恢复
+//! - `import` - 从配置并发导入凭证（限流校验、进度上报、断点恢复）
+//! - `legacy_migration` - 启动时将旧版 config.yaml 凭证迁移到凭证池
+//! - `master_key` - 系统钥匙串中的版本化主密钥（轮换、按上下文派生子密钥）
+//! - `oauth_flow` - 按 Provider 描述驱动的通用 OAuth 登录流程（授权码 + PKCE、设备码）
+//! - `quota` - 配额超限检测、自动切换、冷却恢复、用量预测和剩余配额估算
+//! - `rate_limit_headers` - 解析上游响应头中的限流信息
 //! - `sync` - 凭证与 YAML 配置文件的同步
 
 mod balancer;
 pub mod encryption;
+mod import;
+mod legacy_migration;
+pub mod master_key;
+mod oauth_flow;
 mod quota;
+mod rate_limit_headers;
 mod sync;
 
 // 重新导出
-pub use balancer::{BalanceStrategy, CooldownInfo, CredentialSelection, LoadBalancer};
+pub use balancer::{
+    AffinityBinding, BalanceStrategy, CooldownInfo, CredentialSelection, LoadBalancer,
+    DEFAULT_AFFINITY_TTL_SECS,
+};
+pub use import::{
+    credentials_fingerprint, validate_credential, CredentialImportProgress, CredentialImportReport,
+    CredentialImportState, CREDENTIAL_IMPORT_PROGRESS_EVENT, DEFAULT_IMPORT_CONCURRENCY,
+};
+pub use legacy_migration::{
+    migrate_legacy_credentials, LegacyCredentialMigrationItem, LegacyCredentialMigrationReport,
+    LEGACY_CREDENTIAL_MIGRATION_REPORT_FILE,
+};
+pub use master_key::{MasterKeyError, MasterKeyring, ProviderCredentialCipher};
+pub use oauth_flow::{
+    OAuthFlowKind, OAuthFlowManager, OAuthFlowPoll, OAuthFlowStart, OAuthFlowStatus,
+    OAuthProviderDescriptor, OAuthTokenSet, DEFAULT_OAUTH_FLOW_TIMEOUT_SECS,
+};
 pub use quota::{
     create_shared_quota_manager, start_quota_cleanup_task, AllCredentialsExhaustedError,
-    QuotaAutoSwitchResult, QuotaExceededRecord, QuotaManager,
+    QuotaAutoSwitchResult, QuotaExceededRecord, QuotaForecast, QuotaManager, QuotaRemaining,
+    RemainingMeter, RemainingSource, DEFAULT_USAGE_WINDOW_SECS,
 };
+pub use rate_limit_headers::{parse_rate_limit_headers, ProviderLimit, ProviderRateLimits};
 pub use sync::{CredentialSyncService, SyncError};
@@ -0,0 +1,698 @@
+//! 版本化主密钥与密文格式
+//!
+//! 每个安装生成随机的 256-bit 主密钥，保存在系统钥匙串（macOS Keychain、
+//! Windows 凭据管理器、Linux Secret Service）中：
+//! - 主密钥带版本号，轮换时生成新版本，旧版本保留用于解密历史数据
+//! - 按调用方上下文（如 `plugin:<id>`）以 HKDF-SHA256 派生子密钥，不同上下文的密文互不可解
+//! - 格式：enc3:v<版本>:base64(nonce || ciphertext || tag)
+//! - 钥匙串从未可用的环境（如无 Secret Service 的 Linux）可回退到本地密钥文件
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hkdf::Hkdf;
+use lime_core::database::credential_cipher::{CipherError, CredentialCipher};
+use rand::{rngs::OsRng, RngCore};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::encryption::{EncryptionError, Encryptor};
+
+/// 版本化密文前缀
+const VERSIONED_PREFIX: &str = "enc3:";
+
+/// 钥匙串服务名
+pub const KEYCHAIN_SERVICE: &str = "lime";
+
+/// HKDF 子密钥派生的盐（固定值，区分用途）
+const SUBKEY_SALT: &[u8] = b"lime-master-key-subkey-v1";
+
+/// 钥匙串不可用后的重试间隔，期间直接返回上次的错误
+const KEYCHAIN_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 主密钥持久化
+pub trait MasterKeyStore: Send + Sync {
+    /// 读取序列化的密钥集，不存在时返回 None
+    fn load(&self) -> Result<Option<String>, MasterKeyError>;
+    /// 保存序列化的密钥集
+    fn save(&self, data: &str) -> Result<(), MasterKeyError>;
+}
+
+/// 系统钥匙串存储
+pub struct KeychainStore {
+    service: String,
+    account: String,
+}
+
+impl KeychainStore {
+    pub fn new(account: impl Into<String>) -> Self {
+        Self {
+            service: KEYCHAIN_SERVICE.to_string(),
+            account: account.into(),
+        }
+    }
+
+    fn entry(&self) -> Result<keyring::Entry, MasterKeyError> {
+        keyring::Entry::new(&self.service, &self.account)
+            .map_err(|e| MasterKeyError::Keychain(e.to_string()))
+    }
+}
+
+impl MasterKeyStore for KeychainStore {
+    fn load(&self) -> Result<Option<String>, MasterKeyError> {
+        match self.entry()?.get_password() {
+            Ok(data) => Ok(Some(data)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(MasterKeyError::Keychain(e.to_string())),
+        }
+    }
+
+    fn save(&self, data: &str) -> Result<(), MasterKeyError> {
+        self.entry()?
+            .set_password(data)
+            .map_err(|e| MasterKeyError::Keychain(e.to_string()))
+    }
+}
+
+/// 本地密钥文件存储（`<dir>/<account>.json`，Unix 上权限为 0600）
+pub struct FileKeyStore {
+    path: PathBuf,
+}
+
+impl FileKeyStore {
+    pub fn new(dir: impl Into<PathBuf>, account: &str) -> Self {
+        Self {
+            path: dir.into().join(format!("{account}.json")),
+        }
+    }
+}
+
+impl MasterKeyStore for FileKeyStore {
+    fn load(&self) -> Result<Option<String>, MasterKeyError> {
+        match fs::read_to_string(&self.path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(MasterKeyError::Keychain(format!(
+                "读取密钥文件 {} 失败: {e}",
+                self.path.display()
+            ))),
+        }
+    }
+
+    fn save(&self, data: &str) -> Result<(), MasterKeyError> {
+        let write = || -> std::io::Result<()> {
+            if let Some(parent) = self.path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let tmp = self.path.with_extension("json.tmp");
+            let mut options = fs::OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            std::io::Write::write_all(&mut options.open(&tmp)?, data.as_bytes())?;
+            fs::rename(&tmp, &self.path)
+        };
+        write().map_err(|e| {
+            MasterKeyError::Keychain(format!("写入密钥文件 {} 失败: {e}", self.path.display()))
+        })
+    }
+}
+
+/// 钥匙串优先、密钥文件兜底的存储
+///
+/// 只有钥匙串从未成功保存过密钥（没有 `<account>.keychain` 标记）时才回退到文件，
+/// 避免钥匙串临时锁定时生成新密钥、导致已有密文无法解密。一旦回退，后续固定使用文件。
+pub struct FallbackKeyStore {
+    keychain: Box<dyn MasterKeyStore>,
+    file: FileKeyStore,
+    /// 钥匙串曾经可用的标记文件
+    marker: PathBuf,
+    use_file: AtomicBool,
+}
+
+impl FallbackKeyStore {
+    pub fn new(keychain: Box<dyn MasterKeyStore>, dir: impl Into<PathBuf>, account: &str) -> Self {
+        let dir = dir.into();
+        Self {
+            keychain,
+            file: FileKeyStore::new(dir.clone(), account),
+            marker: dir.join(format!("{account}.keychain")),
+            use_file: AtomicBool::new(false),
+        }
+    }
+
+    fn mark_keychain_used(&self) {
+        if self.marker.exists() {
+            return;
+        }
+        let result = self
+            .marker
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&self.marker, b""));
+        if let Err(e) = result {
+            tracing::warn!("[主密钥] 写入钥匙串标记失败: {}", e);
+        }
+    }
+
+    /// 钥匙串失败时是否允许回退到文件
+    fn can_fall_back(&self, error: &MasterKeyError) -> bool {
+        if self.marker.exists() {
+            return false;
+        }
+        tracing::warn!("[主密钥] 钥匙串不可用，改用本地密钥文件: {}", error);
+        self.use_file.store(true, Ordering::SeqCst);
+        true
+    }
+}
+
+impl MasterKeyStore for FallbackKeyStore {
+    fn load(&self) -> Result<Option<String>, MasterKeyError> {
+        if let Some(data) = self.file.load()? {
+            self.use_file.store(true, Ordering::SeqCst);
+            return Ok(Some(data));
+        }
+        match self.keychain.load() {
+            Ok(data) => {
+                if data.is_some() {
+                    self.mark_keychain_used();
+                }
+                Ok(data)
+            }
+            Err(e) if self.can_fall_back(&e) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&self, data: &str) -> Result<(), MasterKeyError> {
+        if self.use_file.load(Ordering::SeqCst) {
+            return self.file.save(data);
+        }
+        match self.keychain.save(data) {
+            Ok(()) => {
+                self.mark_keychain_used();
+                Ok(())
+            }
+            Err(e) if self.can_fall_back(&e) => self.file.save(data),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// 内存存储（测试与无钥匙串环境）
+#[derive(Default)]
+pub struct MemoryKeyStore {
+    data: RwLock<Option<String>>,
+}
+
+impl MasterKeyStore for MemoryKeyStore {
+    fn load(&self) -> Result<Option<String>, MasterKeyError> {
+        Ok(self.data.read().map(|d| d.clone()).unwrap_or_default())
+    }
+
+    fn save(&self, data: &str) -> Result<(), MasterKeyError> {
+        if let Ok(mut guard) = self.data.write() {
+            *guard = Some(data.to_string());
+        }
+        Ok(())
+    }
+}
+
+/// 密钥集（序列化后存入钥匙串）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KeySet {
+    current: u32,
+    /// 版本 -> base64 编码的 32 字节主密钥
+    keys: BTreeMap<u32, String>,
+}
+
+impl KeySet {
+    fn generate() -> Self {
+        let mut keys = BTreeMap::new();
+        keys.insert(1, random_key());
+        Self { current: 1, keys }
+    }
+
+    fn key(&self, version: u32) -> Result<[u8; 32], MasterKeyError> {
+        let encoded = self
+            .keys
+            .get(&version)
+            .ok_or(MasterKeyError::UnknownKeyVersion(version))?;
+        let bytes = BASE64
+            .decode(encoded)
+            .map_err(|_| MasterKeyError::Corrupted(format!("密钥 v{version} 编码无效")))?;
+        bytes
+            .try_into()
+            .map_err(|_| MasterKeyError::Corrupted(format!("密钥 v{version} 长度无效")))
+    }
+}
+
+fn random_key() -> String {
+    let mut key = [0u8; 32];
+    OsRng.fill_bytes(&mut key);
+    BASE64.encode(key)
+}
+
+/// 版本化主密钥环
+///
+/// 首次加解密时才读取钥匙串，避免启动阶段触发系统授权弹窗。
+/// 钥匙串读写失败后在 [`KEYCHAIN_RETRY_INTERVAL`] 内不再访问，避免每次读取凭证都触发钥匙串调用。
+pub struct MasterKeyring {
+    store: Box<dyn MasterKeyStore>,
+    keys: RwLock<Option<KeySet>>,
+    /// 最近一次钥匙串失败（重试时间，错误）
+    unavailable: RwLock<Option<(Instant, MasterKeyError)>>,
+}
+
+impl MasterKeyring {
+    pub fn new(store: Box<dyn MasterKeyStore>) -> Self {
+        Self {
+            store,
+            keys: RwLock::new(None),
+            unavailable: RwLock::new(None),
+        }
+    }
+
+    /// 使用系统钥匙串中指定账户的主密钥
+    pub fn keychain(account: &str) -> Self {
+        Self::new(Box::new(KeychainStore::new(account)))
+    }
+
+    /// 使用系统钥匙串，钥匙串从未可用时回退到 `dir` 下的密钥文件
+    pub fn keychain_with_file_fallback(account: &str, dir: impl Into<PathBuf>) -> Self {
+        Self::new(Box::new(FallbackKeyStore::new(
+            Box::new(KeychainStore::new(account)),
+            dir,
+            account,
+        )))
+    }
+
+    /// 读取密钥集，不存在时生成并保存
+    fn with_keys<T>(
+        &self,
+        f: impl FnOnce(&KeySet) -> Result<T, MasterKeyError>,
+    ) -> Result<T, MasterKeyError> {
+        if let Some(keys) = self.keys.read().map_err(lock_error)?.as_ref() {
+            return f(keys);
+        }
+
+        if let Some((retry_at, error)) = self.unavailable.read().map_err(lock_error)?.as_ref() {
+            if Instant::now() < *retry_at {
+                return Err(error.clone());
+            }
+        }
+
+        let mut guard = self.keys.write().map_err(lock_error)?;
+        if guard.is_none() {
+            let keys = self.load_keys().map_err(|e| {
+                if matches!(e, MasterKeyError::Keychain(_)) {
+                    tracing::warn!(
+                        "[主密钥] 钥匙串不可用，{:?} 内不再重试: {}",
+                        KEYCHAIN_RETRY_INTERVAL,
+                        e
+                    );
+                    if let Ok(mut unavailable) = self.unavailable.write() {
+                        *unavailable = Some((Instant::now() + KEYCHAIN_RETRY_INTERVAL, e.clone()));
+                    }
+                }
+                e
+            })?;
+            *guard = Some(keys);
+            if let Ok(mut unavailable) = self.unavailable.write() {
+                *unavailable = None;
+            }
+        }
+        f(guard.as_ref().expect("密钥集已加载"))
+    }
+
+    /// 从存储读取密钥集，不存在时生成并保存
+    fn load_keys(&self) -> Result<KeySet, MasterKeyError> {
+        match self.store.load()? {
+            Some(data) => {
+                serde_json::from_str(&data).map_err(|e| MasterKeyError::Corrupted(e.to_string()))
+            }
+            None => {
+                let keys = KeySet::generate();
+                self.store.save(&serialize(&keys)?)?;
+                tracing::info!("[主密钥] 已生成主密钥 v1");
+                Ok(keys)
+            }
+        }
+    }
+
+    /// 当前密钥版本
+    pub fn current_version(&self) -> Result<u32, MasterKeyError> {
+        self.with_keys(|keys| Ok(keys.current))
+    }
+
+    /// 轮换主密钥，返回新版本号
+    ///
+    /// 旧版本密钥保留用于解密，已有密文可通过 `rewrap` 迁移到新版本。
+    pub fn rotate(&self) -> Result<u32, MasterKeyError> {
+        // 确保已加载
+        self.current_version()?;
+        let mut guard = self.keys.write().map_err(lock_error)?;
+        let keys = guard.as_mut().expect("密钥集已加载");
+
+        let mut rotated = keys.clone();
+        rotated.current = keys.keys.keys().max().copied().unwrap_or(0) + 1;
+        rotated.keys.insert(rotated.current, random_key());
+        self.store.save(&serialize(&rotated)?)?;
+
+        tracing::info!("[主密钥] 已轮换到 v{}", rotated.current);
+        *keys = rotated;
+        Ok(keys.current)
+    }
+
+    /// 使用当前版本密钥加密
+    pub fn encrypt(&self, context: &str, plaintext: &str) -> Result<String, MasterKeyError> {
+        self.with_keys(|keys| {
+            let sealed = encryptor(&keys.key(keys.current)?, context).seal(plaintext)?;
+            Ok(format!("{VERSIONED_PREFIX}v{}:{sealed}", keys.current))
+        })
+    }
+
+    /// 按密文头中的版本选择密钥解密
+    pub fn decrypt(&self, context: &str, ciphertext: &str) -> Result<String, MasterKeyError> {
+        let (version, sealed) = parse_header(ciphertext)?;
+        self.with_keys(|keys| Ok(encryptor(&keys.key(version)?, context).open(sealed)?))
+    }
+
+    /// 将旧版本密文重新加密为当前版本，已是当前版本时返回 None
+    pub fn rewrap(
+        &self,
+        context: &str,
+        ciphertext: &str,
+    ) -> Result<Option<String>, MasterKeyError> {
+        let (version, _) = parse_header(ciphertext)?;
+        if version == self.current_version()? {
+            return Ok(None);
+        }
+        let plaintext = self.decrypt(context, ciphertext)?;
+        self.encrypt(context, &plaintext).map(Some)
+    }
+
+    /// 检查文本是否为版本化密文
+    pub fn is_encrypted(text: &str) -> bool {
+        parse_header(text).is_ok()
+    }
+
+    /// 版本化密文前缀
+    pub fn prefix() -> &'static str {
+        VERSIONED_PREFIX
+    }
+}
+
+/// 凭证池 credential_data 的加密上下文
+pub const PROVIDER_CREDENTIAL_CONTEXT: &str = "provider_pool_credentials";
+
+/// 基于主密钥环的凭证池加密实现，注册到 `lime_core` 的凭证 DAO
+pub struct ProviderCredentialCipher {
+    keyring: MasterKeyring,
+}
+
+impl ProviderCredentialCipher {
+    /// 钥匙串中的主密钥账户
+    const KEYCHAIN_ACCOUNT: &'static str = "credential-master-key";
+
+    pub fn new(keyring: MasterKeyring) -> Self {
+        Self { keyring }
+    }
+
+    /// 使用系统钥匙串中的凭证主密钥
+    pub fn keychain() -> Self {
+        Self::new(MasterKeyring::keychain(Self::KEYCHAIN_ACCOUNT))
+    }
+
+    /// 检查系统钥匙串是否可读，返回凭证主密钥是否已存在（不会生成新密钥）
+    pub fn probe_keychain() -> Result<bool, MasterKeyError> {
+        Ok(KeychainStore::new(Self::KEYCHAIN_ACCOUNT).load()?.is_some())
+    }
+}
+
+impl CredentialCipher for ProviderCredentialCipher {
+    fn encrypt(&self, plaintext: &str) -> Result<String, CipherError> {
+        self.keyring
+            .encrypt(PROVIDER_CREDENTIAL_CONTEXT, plaintext)
+            .map_err(CipherError::from)
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> Result<String, CipherError> {
+        self.keyring
+            .decrypt(PROVIDER_CREDENTIAL_CONTEXT, ciphertext)
+            .map_err(CipherError::from)
+    }
+
+    fn is_encrypted(&self, text: &str) -> bool {
+        MasterKeyring::is_encrypted(text)
+    }
+}
+
+/// 解析密文头，返回密钥版本与密文主体
+fn parse_header(ciphertext: &str) -> Result<(u32, &str), MasterKeyError> {
+    let rest = ciphertext
+        .strip_prefix(VERSIONED_PREFIX)
+        .and_then(|rest| rest.strip_prefix('v'))
+        .ok_or(MasterKeyError::InvalidFormat)?;
+    let (version, sealed) = rest.split_once(':').ok_or(MasterKeyError::InvalidFormat)?;
+    let version = version.parse().map_err(|_| MasterKeyError::InvalidFormat)?;
+    Ok((version, sealed))
+}
+
+/// 按上下文以 HKDF-SHA256 派生子密钥
+fn encryptor(master: &[u8; 32], context: &str) -> Encryptor {
+    let mut key = [0u8; 32];
+    Hkdf::<Sha256>::new(Some(SUBKEY_SALT), master)
+        .expand(context.as_bytes(), &mut key)
+        .expect("32 字节输出长度对 HKDF-SHA256 有效");
+    Encryptor::from_raw_key(&key)
+}
+
+fn serialize(keys: &KeySet) -> Result<String, MasterKeyError> {
+    serde_json::to_string(keys).map_err(|e| MasterKeyError::Corrupted(e.to_string()))
+}
+
+fn lock_error<T>(_: T) -> MasterKeyError {
+    MasterKeyError::Keychain("密钥集锁已损坏".to_string())
+}
+
+/// 主密钥错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MasterKeyError {
+    /// 钥匙串不可用或读写失败
+    Keychain(String),
+    /// 钥匙串中的密钥集无法解析
+    Corrupted(String),
+    /// 密文使用的密钥版本不存在
+    UnknownKeyVersion(u32),
+    /// 无效的密文格式（缺少 enc3:v<版本>: 头）
+    InvalidFormat,
+    /// 加解密失败
+    Encryption(EncryptionError),
+}
+
+impl From<EncryptionError> for MasterKeyError {
+    fn from(e: EncryptionError) -> Self {
+        Self::Encryption(e)
+    }
+}
+
+impl std::fmt::Display for MasterKeyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Keychain(e) => write!(f, "系统钥匙串访问失败: {e}"),
+            Self::Corrupted(e) => write!(f, "主密钥数据损坏: {e}"),
+            Self::UnknownKeyVersion(v) => write!(f, "未知的密钥版本: v{v}"),
+            Self::InvalidFormat => write!(f, "无效的密文格式"),
+            Self::Encryption(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for MasterKeyError {}
+
+impl From<MasterKeyError> for CipherError {
+    /// 只有密钥确认不匹配（认证解密失败、未知密钥版本）才视为无法解密；
+    /// 钥匙串访问失败、密钥集损坏等可能在恢复后解决
+    fn from(e: MasterKeyError) -> Self {
+        match e {
+            MasterKeyError::UnknownKeyVersion(_)
+            | MasterKeyError::Encryption(EncryptionError::DecryptionFailed) => {
+                CipherError::Undecryptable(e.to_string())
+            }
+            _ => CipherError::Unavailable(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keyring() -> MasterKeyring {
+        MasterKeyring::new(Box::new(MemoryKeyStore::default()))
+    }
+
+    #[test]
+    fn test_encrypt_decrypt_with_context() {
+        let keyring = keyring();
+        let ciphertext = keyring.encrypt("plugin:a", "secret").unwrap();
+        assert!(ciphertext.starts_with("enc3:v1:"));
+        assert!(MasterKeyring::is_encrypted(&ciphertext));
+        assert_eq!(keyring.decrypt("plugin:a", &ciphertext).unwrap(), "secret");
+        assert_eq!(
+            keyring.decrypt("plugin:b", &ciphertext),
+            Err(MasterKeyError::Encryption(
+                EncryptionError::DecryptionFailed
+            ))
+        );
+        assert_eq!(
+            keyring.decrypt("plugin:a", "c2VjcmV0"),
+            Err(MasterKeyError::InvalidFormat)
+        );
+    }
+
+    #[test]
+    fn test_rotation_keeps_old_versions() {
+        let keyring = keyring();
+        let old = keyring.encrypt("ctx", "secret").unwrap();
+
+        assert_eq!(keyring.rotate().unwrap(), 2);
+        assert_eq!(keyring.decrypt("ctx", &old).unwrap(), "secret");
+
+        let rewrapped = keyring.rewrap("ctx", &old).unwrap().unwrap();
+        assert!(rewrapped.starts_with("enc3:v2:"));
+        assert_eq!(keyring.decrypt("ctx", &rewrapped).unwrap(), "secret");
+        assert_eq!(keyring.rewrap("ctx", &rewrapped).unwrap(), None);
+
+        assert_eq!(
+            keyring.decrypt("ctx", "enc3:v9:AAAA"),
+            Err(MasterKeyError::UnknownKeyVersion(9))
+        );
+    }
+
+    #[test]
+    fn test_keychain_failure_is_cached() {
+        struct Failing(std::sync::atomic::AtomicUsize);
+        impl MasterKeyStore for Failing {
+            fn load(&self) -> Result<Option<String>, MasterKeyError> {
+                self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Err(MasterKeyError::Keychain("locked".to_string()))
+            }
+            fn save(&self, _: &str) -> Result<(), MasterKeyError> {
+                Err(MasterKeyError::Keychain("locked".to_string()))
+            }
+        }
+
+        let store = std::sync::Arc::new(Failing(Default::default()));
+        struct Shared(std::sync::Arc<Failing>);
+        impl MasterKeyStore for Shared {
+            fn load(&self) -> Result<Option<String>, MasterKeyError> {
+                self.0.load()
+            }
+            fn save(&self, data: &str) -> Result<(), MasterKeyError> {
+                self.0.save(data)
+            }
+        }
+        let keyring = MasterKeyring::new(Box::new(Shared(store.clone())));
+        let cipher = ProviderCredentialCipher::new(keyring);
+
+        for _ in 0..5 {
+            assert!(matches!(
+                cipher.decrypt("enc3:v1:AAAA"),
+                Err(CipherError::Unavailable(_))
+            ));
+        }
+        assert_eq!(store.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_cipher_error_classification() {
+        let cipher = ProviderCredentialCipher::new(keyring());
+        let sealed = cipher.encrypt("{}").unwrap();
+        assert_eq!(cipher.decrypt(&sealed).unwrap(), "{}");
+        assert!(matches!(
+            cipher.decrypt("enc3:v9:AAAA"),
+            Err(CipherError::Undecryptable(_))
+        ));
+        let other = ProviderCredentialCipher::new(keyring());
+        other.encrypt("{}").unwrap();
+        assert!(matches!(
+            other.decrypt(&sealed),
+            Err(CipherError::Undecryptable(_))
+        ));
+    }
+
+    #[test]
+    fn test_fallback_store_uses_file_only_when_keychain_never_worked() {
+        struct Failing;
+        impl MasterKeyStore for Failing {
+            fn load(&self) -> Result<Option<String>, MasterKeyError> {
+                Err(MasterKeyError::Keychain("no secret service".to_string()))
+            }
+            fn save(&self, _: &str) -> Result<(), MasterKeyError> {
+                Err(MasterKeyError::Keychain("no secret service".to_string()))
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let keyring = MasterKeyring::new(Box::new(FallbackKeyStore::new(
+            Box::new(Failing),
+            dir.path(),
+            "plugin-master-key",
+        )));
+        let ciphertext = keyring.encrypt("ctx", "secret").unwrap();
+        assert!(dir.path().join("plugin-master-key.json").exists());
+
+        let reopened = MasterKeyring::new(Box::new(FallbackKeyStore::new(
+            Box::new(Failing),
+            dir.path(),
+            "plugin-master-key",
+        )));
+        assert_eq!(reopened.decrypt("ctx", &ciphertext).unwrap(), "secret");
+
+        // 钥匙串曾经可用时不回退，避免生成新密钥
+        let other = tempfile::tempdir().unwrap();
+        fs::write(other.path().join("plugin-master-key.keychain"), b"").unwrap();
+        let locked = MasterKeyring::new(Box::new(FallbackKeyStore::new(
+            Box::new(Failing),
+            other.path(),
+            "plugin-master-key",
+        )));
+        assert!(matches!(
+            locked.encrypt("ctx", "secret"),
+            Err(MasterKeyError::Keychain(_))
+        ));
+        assert!(!other.path().join("plugin-master-key.json").exists());
+    }
+
+    #[test]
+    fn test_keys_persist_in_store() {
+        struct Shared(std::sync::Arc<MemoryKeyStore>);
+        impl MasterKeyStore for Shared {
+            fn load(&self) -> Result<Option<String>, MasterKeyError> {
+                self.0.load()
+            }
+            fn save(&self, data: &str) -> Result<(), MasterKeyError> {
+                self.0.save(data)
+            }
+        }
+
+        let store = std::sync::Arc::new(MemoryKeyStore::default());
+        let first = MasterKeyring::new(Box::new(Shared(store.clone())));
+        let ciphertext = first.encrypt("ctx", "secret").unwrap();
+        first.rotate().unwrap();
+
+        let reopened = MasterKeyring::new(Box::new(Shared(store)));
+        assert_eq!(reopened.current_version().unwrap(), 2);
+        assert_eq!(reopened.decrypt("ctx", &ciphertext).unwrap(), "secret");
+    }
+}
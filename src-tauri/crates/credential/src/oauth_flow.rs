@@ -0,0 +1,768 @@
+//! 通用 OAuth 登录流程
+//!
+//! 按 Provider 描述（端点、scope、client id）驱动两种登录方式：
+//! - 授权码 + PKCE：在本地回调端口接收授权码后交换 Token
+//! - 设备码（RFC 8628）：返回用户码与验证地址，由调用方按间隔轮询
+//!
+//! 新增标准 OAuth Provider 只需提供一份 [`OAuthProviderDescriptor`]；
+//! Token 写入凭证文件与加入凭证池由调用方完成。
+
+use axum::{extract::Query, response::Html, routing::get, Router};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+/// 授权码流程等待回调的最长时间
+pub const DEFAULT_OAUTH_FLOW_TIMEOUT_SECS: u64 = 600;
+
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+const CALLBACK_SUCCESS_HTML: &str = "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>授权成功</title></head><body><h2>授权成功</h2><p>可以关闭此页面并返回 Lime。</p></body></html>";
+const CALLBACK_ERROR_HTML: &str = "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>授权失败</title></head><body><h2>授权失败</h2><p>请返回 Lime 重新登录。</p></body></html>";
+
+/// 登录方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthFlowKind {
+    /// 授权码 + PKCE（本地回调）
+    AuthorizationCode,
+    /// 设备码
+    DeviceCode,
+}
+
+/// OAuth Provider 描述
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OAuthProviderDescriptor {
+    /// 流程标识（`oauth_start_flow` 的 `provider` 参数）
+    pub id: String,
+    pub display_name: String,
+    /// 登录成功后加入的凭证池类型
+    pub pool_provider_type: String,
+    pub kind: OAuthFlowKind,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    /// 授权端点（授权码流程）
+    #[serde(default)]
+    pub authorize_url: Option<String>,
+    /// 设备授权端点（设备码流程）
+    #[serde(default)]
+    pub device_authorization_url: Option<String>,
+    pub token_url: String,
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// 本地回调端口，0 表示随机端口
+    #[serde(default)]
+    pub callback_port: u16,
+    #[serde(default = "default_callback_path")]
+    pub callback_path: String,
+    /// 授权 URL 的附加参数
+    #[serde(default)]
+    pub extra_authorize_params: Vec<(String, String)>,
+}
+
+fn default_callback_path() -> String {
+    "/oauth/callback".to_string()
+}
+
+impl OAuthProviderDescriptor {
+    /// OpenAI Codex（授权码 + PKCE，回调地址固定为 Codex CLI 注册的 1455 端口）
+    ///
+    /// client id 由调用方传入，与 `lime_providers` 中 Codex Provider 使用的保持一致。
+    pub fn codex(client_id: impl Into<String>) -> Self {
+        Self {
+            id: "codex".to_string(),
+            display_name: "OpenAI Codex".to_string(),
+            pool_provider_type: "codex".to_string(),
+            kind: OAuthFlowKind::AuthorizationCode,
+            client_id: client_id.into(),
+            client_secret: None,
+            authorize_url: Some("https://auth.openai.com/oauth/authorize".to_string()),
+            device_authorization_url: None,
+            token_url: "https://auth.openai.com/oauth/token".to_string(),
+            scopes: ["openid", "email", "profile", "offline_access"]
+                .map(str::to_string)
+                .to_vec(),
+            callback_port: 1455,
+            callback_path: "/auth/callback".to_string(),
+            extra_authorize_params: [
+                ("prompt", "login"),
+                ("id_token_add_organizations", "true"),
+                ("codex_cli_simplified_flow", "true"),
+            ]
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .to_vec(),
+        }
+    }
+
+    /// Gemini CLI（授权码 + PKCE，随机回调端口）
+    ///
+    /// client id / secret 由调用方传入，与 `lime_providers` 中 Gemini Provider 使用的保持一致。
+    pub fn gemini(client_id: impl Into<String>, client_secret: impl Into<String>) -> Self {
+        Self {
+            id: "gemini".to_string(),
+            display_name: "Gemini CLI".to_string(),
+            pool_provider_type: "gemini".to_string(),
+            kind: OAuthFlowKind::AuthorizationCode,
+            client_id: client_id.into(),
+            client_secret: Some(client_secret.into()),
+            authorize_url: Some("https://accounts.google.com/o/oauth2/v2/auth".to_string()),
+            device_authorization_url: None,
+            token_url: "https://oauth2.googleapis.com/token".to_string(),
+            scopes: [
+                "https://www.googleapis.com/auth/cloud-platform",
+                "https://www.googleapis.com/auth/userinfo.email",
+                "https://www.googleapis.com/auth/userinfo.profile",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+            callback_port: 0,
+            callback_path: "/oauth2callback".to_string(),
+            extra_authorize_params: [("access_type", "offline"), ("prompt", "consent")]
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .to_vec(),
+        }
+    }
+}
+
+/// Token 交换结果
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OAuthTokenSet {
+    pub access_token: String,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub id_token: Option<String>,
+    #[serde(default)]
+    pub token_type: Option<String>,
+    #[serde(default)]
+    pub scope: Option<String>,
+    #[serde(default)]
+    pub expires_in: Option<i64>,
+}
+
+impl OAuthTokenSet {
+    /// 生成凭证文件内容
+    ///
+    /// 同时写出毫秒时间戳（`expiry_date`）与 RFC3339（`expire` / `expires_at`）两种过期时间，
+    /// 兼容 Codex 与 Google 系凭证文件的读取方式。
+    pub fn to_token_file(&self, credential_type: &str) -> serde_json::Value {
+        let now = chrono::Utc::now();
+        let expires_at = self
+            .expires_in
+            .map(|secs| now + chrono::Duration::seconds(secs));
+        serde_json::json!({
+            "access_token": self.access_token,
+            "refresh_token": self.refresh_token,
+            "id_token": self.id_token,
+            "token_type": self.token_type,
+            "scope": self.scope,
+            "expires_in": self.expires_in,
+            "expiry_date": expires_at.map(|t| t.timestamp_millis()),
+            "expire": expires_at.map(|t| t.to_rfc3339()),
+            "expires_at": expires_at.map(|t| t.to_rfc3339()),
+            "timestamp": now.timestamp_millis(),
+            "last_refresh": now.to_rfc3339(),
+            "type": credential_type,
+        })
+    }
+}
+
+/// 流程启动信息
+#[derive(Debug, Clone, Serialize)]
+pub struct OAuthFlowStart {
+    pub flow_id: String,
+    pub provider: String,
+    pub kind: OAuthFlowKind,
+    /// 授权码流程：需在浏览器打开的授权 URL
+    pub auth_url: Option<String>,
+    /// 设备码流程：展示给用户的用户码
+    pub user_code: Option<String>,
+    /// 设备码流程：验证地址（优先使用带用户码的完整地址）
+    pub verification_uri: Option<String>,
+    pub expires_in: u64,
+    /// 建议的轮询间隔（秒）
+    pub interval: u64,
+}
+
+/// 流程状态
+#[derive(Debug, Clone, PartialEq)]
+pub enum OAuthFlowStatus {
+    Pending,
+    /// 设备码流程要求降低轮询频率
+    SlowDown,
+    Completed(OAuthTokenSet),
+    Failed(String),
+    Expired,
+}
+
+impl OAuthFlowStatus {
+    /// 是否为终止状态（完成后流程即被移除）
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, Self::Pending | Self::SlowDown)
+    }
+}
+
+/// 流程查询结果
+#[derive(Debug, Clone)]
+pub struct OAuthFlowPoll {
+    /// 流程所属的 Provider
+    pub provider: OAuthProviderDescriptor,
+    pub status: OAuthFlowStatus,
+}
+
+struct DeviceCodeState {
+    device_code: String,
+    interval: Duration,
+    last_poll: Option<Instant>,
+}
+
+struct OAuthFlow {
+    descriptor: OAuthProviderDescriptor,
+    expires_at: Instant,
+    status: OAuthFlowStatus,
+    device: Option<DeviceCodeState>,
+    /// 授权码流程回调服务器的关闭信号
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+/// 进行中的 OAuth 流程
+pub struct OAuthFlowManager {
+    client: reqwest::Client,
+    providers: Mutex<HashMap<String, OAuthProviderDescriptor>>,
+    flows: Arc<Mutex<HashMap<String, OAuthFlow>>>,
+    timeout: Duration,
+}
+
+impl OAuthFlowManager {
+    pub fn new(providers: Vec<OAuthProviderDescriptor>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            providers: Mutex::new(providers.into_iter().map(|p| (p.id.clone(), p)).collect()),
+            flows: Arc::new(Mutex::new(HashMap::new())),
+            timeout: Duration::from_secs(DEFAULT_OAUTH_FLOW_TIMEOUT_SECS),
+        }
+    }
+
+    /// 注册或替换 Provider 描述
+    pub fn register(&self, descriptor: OAuthProviderDescriptor) {
+        self.providers
+            .lock()
+            .unwrap()
+            .insert(descriptor.id.clone(), descriptor);
+    }
+
+    /// 已注册的 Provider（按 id 排序）
+    pub fn providers(&self) -> Vec<OAuthProviderDescriptor> {
+        let mut providers: Vec<_> = self.providers.lock().unwrap().values().cloned().collect();
+        providers.sort_by(|a, b| a.id.cmp(&b.id));
+        providers
+    }
+
+    /// 获取 Provider 描述
+    pub fn provider(&self, id: &str) -> Option<OAuthProviderDescriptor> {
+        self.providers.lock().unwrap().get(id).cloned()
+    }
+
+    /// 启动登录流程
+    pub async fn start(&self, provider: &str) -> Result<OAuthFlowStart, String> {
+        let descriptor = self
+            .provider(provider)
+            .ok_or_else(|| format!("未知的 OAuth Provider: {provider}"))?;
+        self.prune_expired();
+        match descriptor.kind {
+            OAuthFlowKind::AuthorizationCode => self.start_authorization_code(descriptor).await,
+            OAuthFlowKind::DeviceCode => self.start_device_code(descriptor).await,
+        }
+    }
+
+    /// 查询流程状态；终止状态返回后流程即被移除
+    pub async fn poll(&self, flow_id: &str) -> Result<OAuthFlowPoll, String> {
+        let device_request = {
+            let mut flows = self.flows.lock().unwrap();
+            let flow = flows
+                .get_mut(flow_id)
+                .ok_or_else(|| "登录流程不存在或已结束".to_string())?;
+            if Instant::now() >= flow.expires_at && !flow.status.is_terminal() {
+                flow.status = OAuthFlowStatus::Expired;
+            }
+            match &mut flow.device {
+                Some(device) if !flow.status.is_terminal() => {
+                    // 未到轮询间隔时不请求上游
+                    if device
+                        .last_poll
+                        .is_some_and(|last| last.elapsed() < device.interval)
+                    {
+                        return Ok(OAuthFlowPoll {
+                            provider: flow.descriptor.clone(),
+                            status: flow.status.clone(),
+                        });
+                    }
+                    device.last_poll = Some(Instant::now());
+                    Some((flow.descriptor.clone(), device.device_code.clone()))
+                }
+                _ => None,
+            }
+        };
+
+        if let Some((descriptor, device_code)) = device_request {
+            let status = self.poll_device_token(&descriptor, &device_code).await;
+            let mut flows = self.flows.lock().unwrap();
+            if let Some(flow) = flows.get_mut(flow_id) {
+                if status == OAuthFlowStatus::SlowDown {
+                    if let Some(device) = &mut flow.device {
+                        device.interval += Duration::from_secs(5);
+                    }
+                }
+                flow.status = status;
+            }
+        }
+
+        let mut flows = self.flows.lock().unwrap();
+        let poll = flows
+            .get(flow_id)
+            .map(|flow| OAuthFlowPoll {
+                provider: flow.descriptor.clone(),
+                status: flow.status.clone(),
+            })
+            .ok_or_else(|| "登录流程不存在或已结束".to_string())?;
+        if poll.status.is_terminal() {
+            if let Some(mut flow) = flows.remove(flow_id) {
+                if let Some(shutdown) = flow.shutdown.take() {
+                    let _ = shutdown.send(());
+                }
+            }
+        }
+        Ok(poll)
+    }
+
+    /// 取消流程，返回是否存在
+    pub fn cancel(&self, flow_id: &str) -> bool {
+        let removed = self.flows.lock().unwrap().remove(flow_id);
+        match removed {
+            Some(mut flow) => {
+                if let Some(shutdown) = flow.shutdown.take() {
+                    let _ = shutdown.send(());
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    async fn start_authorization_code(
+        &self,
+        descriptor: OAuthProviderDescriptor,
+    ) -> Result<OAuthFlowStart, String> {
+        let authorize_url = descriptor
+            .authorize_url
+            .clone()
+            .ok_or_else(|| format!("{} 未配置授权端点", descriptor.id))?;
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", descriptor.callback_port))
+            .await
+            .map_err(|e| format!("无法监听回调端口 {}: {e}", descriptor.callback_port))?;
+        let port = listener
+            .local_addr()
+            .map_err(|e| format!("获取回调端口失败: {e}"))?
+            .port();
+        let redirect_uri = format!("http://localhost:{port}{}", descriptor.callback_path);
+
+        let (code_verifier, code_challenge) = generate_pkce();
+        let state = random_token(32);
+        let auth_url = build_authorize_url(
+            &authorize_url,
+            &descriptor,
+            &redirect_uri,
+            &state,
+            &code_challenge,
+        )?;
+
+        let flow_id = random_token(16);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        let (callback_tx, callback_rx) = oneshot::channel::<HashMap<String, String>>();
+        let callback_tx = Arc::new(Mutex::new(Some(callback_tx)));
+        let expected_state = state.clone();
+        let app = Router::new().route(
+            &descriptor.callback_path,
+            get(move |Query(params): Query<HashMap<String, String>>| {
+                let callback_tx = callback_tx.clone();
+                let expected_state = expected_state.clone();
+                async move {
+                    // state 不匹配的请求直接忽略，继续等待正确的回调
+                    if params.get("state") != Some(&expected_state) {
+                        return Html(CALLBACK_ERROR_HTML);
+                    }
+                    let failed = params.contains_key("error") || !params.contains_key("code");
+                    if let Some(tx) = callback_tx.lock().unwrap().take() {
+                        let _ = tx.send(params);
+                    }
+                    Html(if failed {
+                        CALLBACK_ERROR_HTML
+                    } else {
+                        CALLBACK_SUCCESS_HTML
+                    })
+                }
+            }),
+        );
+        let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+            let _ = shutdown_rx.await;
+        });
+        tokio::spawn(async move {
+            if let Err(e) = server.await {
+                tracing::warn!("[OAuth] 回调服务器错误: {}", e);
+            }
+        });
+
+        self.flows.lock().unwrap().insert(
+            flow_id.clone(),
+            OAuthFlow {
+                descriptor: descriptor.clone(),
+                expires_at: Instant::now() + self.timeout,
+                status: OAuthFlowStatus::Pending,
+                device: None,
+                shutdown: Some(shutdown_tx),
+            },
+        );
+
+        // 收到回调后在后台交换 Token，结果由 poll 读取
+        let flows = self.flows.clone();
+        let client = self.client.clone();
+        let timeout = self.timeout;
+        let task_flow_id = flow_id.clone();
+        let task_descriptor = descriptor.clone();
+        tokio::spawn(async move {
+            let status = match tokio::time::timeout(timeout, callback_rx).await {
+                Ok(Ok(params)) => match (params.get("code"), params.get("error")) {
+                    (_, Some(error)) => OAuthFlowStatus::Failed(format!("授权被拒绝: {error}")),
+                    (Some(code), None) => {
+                        let form = vec![
+                            ("grant_type", "authorization_code".to_string()),
+                            ("code", code.clone()),
+                            ("redirect_uri", redirect_uri),
+                            ("code_verifier", code_verifier),
+                        ];
+                        match request_token(&client, &task_descriptor, form).await {
+                            Ok(tokens) => OAuthFlowStatus::Completed(tokens),
+                            Err(TokenError::Failed(e)) => OAuthFlowStatus::Failed(e),
+                            Err(TokenError::Pending(e)) => OAuthFlowStatus::Failed(e),
+                        }
+                    }
+                    (None, None) => OAuthFlowStatus::Failed("回调缺少授权码".to_string()),
+                },
+                Ok(Err(_)) => return,
+                Err(_) => OAuthFlowStatus::Expired,
+            };
+            let mut flows = flows.lock().unwrap();
+            if let Some(flow) = flows.get_mut(&task_flow_id) {
+                tracing::info!(
+                    "[OAuth] {} 授权码流程结束: {}",
+                    flow.descriptor.id,
+                    if matches!(status, OAuthFlowStatus::Completed(_)) {
+                        "成功"
+                    } else {
+                        "失败"
+                    }
+                );
+                flow.status = status;
+                if let Some(shutdown) = flow.shutdown.take() {
+                    let _ = shutdown.send(());
+                }
+            }
+        });
+
+        tracing::info!(
+            "[OAuth] {} 授权码流程已启动，回调地址: http://localhost:{}{}",
+            descriptor.id,
+            port,
+            descriptor.callback_path
+        );
+        Ok(OAuthFlowStart {
+            flow_id,
+            provider: descriptor.id,
+            kind: OAuthFlowKind::AuthorizationCode,
+            auth_url: Some(auth_url),
+            user_code: None,
+            verification_uri: None,
+            expires_in: self.timeout.as_secs(),
+            interval: 2,
+        })
+    }
+
+    async fn start_device_code(
+        &self,
+        descriptor: OAuthProviderDescriptor,
+    ) -> Result<OAuthFlowStart, String> {
+        let device_url = descriptor
+            .device_authorization_url
+            .clone()
+            .ok_or_else(|| format!("{} 未配置设备授权端点", descriptor.id))?;
+        let mut form = vec![("client_id", descriptor.client_id.clone())];
+        if !descriptor.scopes.is_empty() {
+            form.push(("scope", descriptor.scopes.join(" ")));
+        }
+        let response = self
+            .client
+            .post(&device_url)
+            .header("Accept", "application/json")
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| format!("设备授权请求失败: {e}"))?;
+        let status = response.status();
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| format!("解析设备授权响应失败: {e}"))?;
+        if !status.is_success() {
+            return Err(format!("设备授权失败: {status} {body}"));
+        }
+
+        let device_code = body["device_code"]
+            .as_str()
+            .ok_or("响应中缺少 device_code")?
+            .to_string();
+        let user_code = body["user_code"]
+            .as_str()
+            .ok_or("响应中缺少 user_code")?
+            .to_string();
+        let verification_uri = body["verification_uri_complete"]
+            .as_str()
+            .or_else(|| body["verification_uri"].as_str())
+            .or_else(|| body["verification_url"].as_str())
+            .ok_or("响应中缺少 verification_uri")?
+            .to_string();
+        let expires_in = body["expires_in"].as_u64().unwrap_or(600);
+        let interval = body["interval"].as_u64().unwrap_or(5).max(1);
+
+        let flow_id = random_token(16);
+        self.flows.lock().unwrap().insert(
+            flow_id.clone(),
+            OAuthFlow {
+                descriptor: descriptor.clone(),
+                expires_at: Instant::now() + Duration::from_secs(expires_in),
+                status: OAuthFlowStatus::Pending,
+                device: Some(DeviceCodeState {
+                    device_code,
+                    interval: Duration::from_secs(interval),
+                    last_poll: None,
+                }),
+                shutdown: None,
+            },
+        );
+
+        tracing::info!(
+            "[OAuth] {} 设备码流程已启动，user_code: {}",
+            descriptor.id,
+            user_code
+        );
+        Ok(OAuthFlowStart {
+            flow_id,
+            provider: descriptor.id,
+            kind: OAuthFlowKind::DeviceCode,
+            auth_url: None,
+            user_code: Some(user_code),
+            verification_uri: Some(verification_uri),
+            expires_in,
+            interval,
+        })
+    }
+
+    async fn poll_device_token(
+        &self,
+        descriptor: &OAuthProviderDescriptor,
+        device_code: &str,
+    ) -> OAuthFlowStatus {
+        let form = vec![
+            ("grant_type", DEVICE_CODE_GRANT_TYPE.to_string()),
+            ("device_code", device_code.to_string()),
+        ];
+        match request_token(&self.client, descriptor, form).await {
+            Ok(tokens) => OAuthFlowStatus::Completed(tokens),
+            Err(TokenError::Pending(error)) => device_error_status(&error),
+            Err(TokenError::Failed(error)) => OAuthFlowStatus::Failed(error),
+        }
+    }
+
+    /// 清理超时且无人读取的流程
+    fn prune_expired(&self) {
+        let now = Instant::now();
+        self.flows.lock().unwrap().retain(|_, flow| {
+            let keep = now < flow.expires_at + Duration::from_secs(60);
+            if !keep {
+                if let Some(shutdown) = flow.shutdown.take() {
+                    let _ = shutdown.send(());
+                }
+            }
+            keep
+        });
+    }
+}
+
+enum TokenError {
+    /// 上游返回的 OAuth 错误码（设备码流程据此判断是否继续轮询）
+    Pending(String),
+    Failed(String),
+}
+
+/// 向 Token 端点发起请求，自动附带 client_id / client_secret
+async fn request_token(
+    client: &reqwest::Client,
+    descriptor: &OAuthProviderDescriptor,
+    mut form: Vec<(&'static str, String)>,
+) -> Result<OAuthTokenSet, TokenError> {
+    form.push(("client_id", descriptor.client_id.clone()));
+    if let Some(secret) = &descriptor.client_secret {
+        form.push(("client_secret", secret.clone()));
+    }
+    let response = client
+        .post(&descriptor.token_url)
+        .header("Accept", "application/json")
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| TokenError::Failed(format!("Token 请求失败: {e}")))?;
+    let status = response.status();
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| TokenError::Failed(format!("解析 Token 响应失败: {e}")))?;
+
+    // GitHub 等端点以 200 返回 error 字段
+    if let Some(error) = body["error"].as_str() {
+        return Err(TokenError::Pending(error.to_string()));
+    }
+    if !status.is_success() {
+        return Err(TokenError::Failed(format!(
+            "Token 交换失败: {status} {body}"
+        )));
+    }
+    serde_json::from_value(body).map_err(|e| TokenError::Failed(format!("Token 响应无效: {e}")))
+}
+
+/// 设备码轮询的 OAuth 错误码对应的状态
+fn device_error_status(error: &str) -> OAuthFlowStatus {
+    match error {
+        "authorization_pending" => OAuthFlowStatus::Pending,
+        "slow_down" => OAuthFlowStatus::SlowDown,
+        "expired_token" => OAuthFlowStatus::Expired,
+        "access_denied" => OAuthFlowStatus::Failed("用户拒绝授权".to_string()),
+        other => OAuthFlowStatus::Failed(format!("授权错误: {other}")),
+    }
+}
+
+fn build_authorize_url(
+    authorize_url: &str,
+    descriptor: &OAuthProviderDescriptor,
+    redirect_uri: &str,
+    state: &str,
+    code_challenge: &str,
+) -> Result<String, String> {
+    let scope = descriptor.scopes.join(" ");
+    let mut params = vec![
+        ("client_id", descriptor.client_id.as_str()),
+        ("response_type", "code"),
+        ("redirect_uri", redirect_uri),
+        ("state", state),
+        ("code_challenge", code_challenge),
+        ("code_challenge_method", "S256"),
+    ];
+    if !scope.is_empty() {
+        params.push(("scope", scope.as_str()));
+    }
+    params.extend(
+        descriptor
+            .extra_authorize_params
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str())),
+    );
+    reqwest::Url::parse_with_params(authorize_url, &params)
+        .map(String::from)
+        .map_err(|e| format!("授权端点无效: {e}"))
+}
+
+/// 生成 PKCE 的 (code_verifier, code_challenge)
+fn generate_pkce() -> (String, String) {
+    let code_verifier = random_token(64);
+    let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+    (code_verifier, code_challenge)
+}
+
+fn random_token(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_authorize_url_includes_pkce_and_extra_params() {
+        let codex = OAuthProviderDescriptor::codex("client-id");
+        let (verifier, challenge) = generate_pkce();
+        assert_eq!(
+            challenge,
+            URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()))
+        );
+
+        let url = build_authorize_url(
+            codex.authorize_url.as_deref().unwrap(),
+            &codex,
+            "http://localhost:1455/auth/callback",
+            "state123",
+            &challenge,
+        )
+        .unwrap();
+        let parsed = reqwest::Url::parse(&url).unwrap();
+        let query: HashMap<_, _> = parsed.query_pairs().into_owned().collect();
+        assert_eq!(query["code_challenge"], challenge);
+        assert_eq!(query["code_challenge_method"], "S256");
+        assert_eq!(query["state"], "state123");
+        assert_eq!(query["scope"], "openid email profile offline_access");
+        assert_eq!(query["codex_cli_simplified_flow"], "true");
+        assert_eq!(query["client_id"], "client-id");
+    }
+
+    #[test]
+    fn test_device_error_status() {
+        assert_eq!(
+            device_error_status("authorization_pending"),
+            OAuthFlowStatus::Pending
+        );
+        assert_eq!(device_error_status("slow_down"), OAuthFlowStatus::SlowDown);
+        assert_eq!(
+            device_error_status("expired_token"),
+            OAuthFlowStatus::Expired
+        );
+        assert!(matches!(
+            device_error_status("access_denied"),
+            OAuthFlowStatus::Failed(_)
+        ));
+        assert!(!OAuthFlowStatus::SlowDown.is_terminal());
+        assert!(OAuthFlowStatus::Expired.is_terminal());
+    }
+
+    #[test]
+    fn test_token_file_has_both_expiry_formats() {
+        let tokens = OAuthTokenSet {
+            access_token: "at".to_string(),
+            refresh_token: Some("rt".to_string()),
+            id_token: None,
+            token_type: Some("Bearer".to_string()),
+            scope: None,
+            expires_in: Some(3600),
+        };
+        let file = tokens.to_token_file("gemini");
+        assert_eq!(file["type"], "gemini");
+        assert_eq!(file["refresh_token"], "rt");
+        assert!(file["expiry_date"].as_i64().unwrap() > chrono::Utc::now().timestamp_millis());
+        assert!(file["expire"].as_str().is_some());
+        assert_eq!(file["expire"], file["expires_at"]);
+    }
+}
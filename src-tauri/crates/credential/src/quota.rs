@@ -1,13 +1,27 @@
 //! 配额管理器实现
 //!
 //! 提供配额超限检测、自动切换和冷却恢复功能
+//!
+//! 用量预测：按凭证记录滚动窗口内的请求数与 Token 数，结合配置的已知配额
+//! （`QuotaExceededConfig::credential_limits`）计算用量比例与预计耗尽时间，
+//! 用量达到软上限（`soft_limit_ratio`）的凭证会在选择时被主动避开。
+//!
+//! 剩余配额估算：以上游响应头报告的剩余额度为基准，扣除之后本地记录的用量；
+//! 上游未报告的项回退到已知配额减去窗口内用量。每次更新通过事件推送给前端。
 
 use chrono::{DateTime, Duration, Utc};
 use dashmap::DashMap;
-use lime_core::config::QuotaExceededConfig;
+use lime_core::config::{CredentialQuotaLimit, QuotaExceededConfig};
 use lime_infra::resilience::{QUOTA_EXCEEDED_KEYWORDS, QUOTA_EXCEEDED_STATUS_CODES};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, RwLock};
+use tokio::sync::broadcast;
+
+use crate::rate_limit_headers::{ProviderLimit, ProviderRateLimits};
+
+/// 未配置配额的凭证默认统计窗口（秒）
+pub const DEFAULT_USAGE_WINDOW_SECS: u64 = 3600;
 
 /// 配额超限记录
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +36,115 @@ pub struct QuotaExceededRecord {
     pub reason: String,
 }
 
+/// 凭证用量预测
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaForecast {
+    /// 凭证 ID
+    pub credential_id: String,
+    /// 统计窗口（秒）
+    pub window_secs: u64,
+    /// 窗口内请求数
+    pub requests: u64,
+    /// 窗口内 Token 数
+    pub tokens: u64,
+    /// 已知请求数配额
+    pub max_requests: Option<u64>,
+    /// 已知 Token 配额
+    pub max_tokens: Option<u64>,
+    /// 用量占配额的比例（请求数与 Token 取较大者，未配置配额时为 None）
+    pub usage_ratio: Option<f64>,
+    /// 是否达到软上限
+    pub near_limit: bool,
+    /// 按当前速率预计耗尽配额的时间
+    pub projected_exhaustion_at: Option<DateTime<Utc>>,
+}
+
+/// 剩余额度的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RemainingSource {
+    /// 上游响应头报告（已扣除之后的本地用量）
+    Provider,
+    /// 配置的已知配额减去窗口内用量
+    Configured,
+}
+
+/// 单项剩余额度
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RemainingMeter {
+    /// 总额度（上游未返回时为 None）
+    pub limit: Option<u64>,
+    pub remaining: u64,
+    /// 剩余比例（总额度未知时为 None）
+    pub remaining_ratio: Option<f64>,
+    /// 额度重置时间
+    pub reset_at: Option<DateTime<Utc>>,
+    pub source: RemainingSource,
+}
+
+impl RemainingMeter {
+    fn new(
+        limit: Option<u64>,
+        remaining: u64,
+        reset_at: Option<DateTime<Utc>>,
+        source: RemainingSource,
+    ) -> Self {
+        let remaining = limit.map_or(remaining, |limit| remaining.min(limit));
+        Self {
+            limit,
+            remaining,
+            remaining_ratio: limit.map(|limit| remaining as f64 / limit.max(1) as f64),
+            reset_at,
+            source,
+        }
+    }
+}
+
+/// 凭证剩余配额估算
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuotaRemaining {
+    pub credential_id: String,
+    pub requests: Option<RemainingMeter>,
+    pub tokens: Option<RemainingMeter>,
+    /// 最近一次上游限流快照的时间
+    pub provider_observed_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl QuotaRemaining {
+    /// 请求数与 Token 中较小的剩余比例
+    pub fn min_remaining_ratio(&self) -> Option<f64> {
+        [self.requests, self.tokens]
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m.remaining_ratio)
+            .reduce(f64::min)
+    }
+}
+
+/// 单次请求的用量样本
+#[derive(Debug, Clone, Copy)]
+struct UsageSample {
+    at: DateTime<Utc>,
+    tokens: u64,
+}
+
+/// 软上限配置（可热更新）
+#[derive(Debug, Default)]
+struct SoftLimits {
+    ratio: f64,
+    limits: HashMap<String, CredentialQuotaLimit>,
+}
+
+impl SoftLimits {
+    fn from_config(config: &QuotaExceededConfig) -> Self {
+        Self {
+            ratio: config.soft_limit_ratio,
+            limits: config.credential_limits.clone(),
+        }
+    }
+}
+
 /// 配额管理器
 #[derive(Debug)]
 pub struct QuotaManager {
@@ -29,14 +152,28 @@ pub struct QuotaManager {
     config: QuotaExceededConfig,
     /// 超限凭证记录（credential_id -> record）
     exceeded_credentials: DashMap<String, QuotaExceededRecord>,
+    /// 各凭证滚动窗口内的用量样本
+    usage: DashMap<String, VecDeque<UsageSample>>,
+    /// 软上限配置
+    soft_limits: RwLock<SoftLimits>,
+    /// 各凭证最近一次上游限流快照
+    provider_limits: DashMap<String, ProviderRateLimits>,
+    /// 剩余配额变化事件
+    remaining_events: broadcast::Sender<QuotaRemaining>,
 }
 
 impl QuotaManager {
     /// 创建新的配额管理器
     pub fn new(config: QuotaExceededConfig) -> Self {
+        let soft_limits = RwLock::new(SoftLimits::from_config(&config));
+        let (remaining_events, _) = broadcast::channel(64);
         Self {
             config,
             exceeded_credentials: DashMap::new(),
+            usage: DashMap::new(),
+            soft_limits,
+            provider_limits: DashMap::new(),
+            remaining_events,
         }
     }
 
@@ -52,9 +189,16 @@ impl QuotaManager {
 
     /// 更新配置
     pub fn set_config(&mut self, config: QuotaExceededConfig) {
+        self.reload_soft_limits(&config);
         self.config = config;
     }
 
+    /// 热更新软上限配置（已知配额与软上限比例）
+    pub fn reload_soft_limits(&self, config: &QuotaExceededConfig) {
+        *self.soft_limits.write().unwrap_or_else(|e| e.into_inner()) =
+            SoftLimits::from_config(config);
+    }
+
     /// 获取冷却时长
     pub fn cooldown_duration(&self) -> Duration {
         Duration::seconds(self.config.cooldown_seconds as i64)
@@ -235,6 +379,254 @@ impl QuotaManager {
     }
 }
 
+impl QuotaManager {
+    /// 记录一次请求的用量
+    pub fn record_usage(&self, credential_id: &str, tokens: u64) {
+        let now = Utc::now();
+        let window = self.window_for(credential_id);
+        {
+            let mut samples = self.usage.entry(credential_id.to_string()).or_default();
+            samples.push_back(UsageSample { at: now, tokens });
+            prune_samples(&mut samples, now - window);
+        }
+        self.publish_remaining(credential_id);
+    }
+
+    /// 记录上游响应头报告的限流快照
+    pub fn record_provider_limits(&self, credential_id: &str, limits: ProviderRateLimits) {
+        self.provider_limits
+            .insert(credential_id.to_string(), limits);
+        self.publish_remaining(credential_id);
+    }
+
+    /// 订阅剩余配额变化事件
+    pub fn subscribe_remaining(&self) -> broadcast::Receiver<QuotaRemaining> {
+        self.remaining_events.subscribe()
+    }
+
+    /// 估算凭证的剩余配额
+    pub fn remaining(&self, credential_id: &str) -> QuotaRemaining {
+        let now = Utc::now();
+        let snapshot = self.provider_limits.get(credential_id).map(|s| *s);
+        let forecast = self.forecast(credential_id);
+        let window_reset_at = self.window_reset_at(credential_id, forecast.window_secs);
+
+        let provider_meter = |limit: Option<ProviderLimit>, count: fn(&UsageSample) -> u64| {
+            let snapshot = snapshot?;
+            let limit = limit?;
+            match limit.reset_at {
+                // 上游窗口已重置：总额度已知时按重置后的本地用量估算
+                Some(reset_at) if reset_at <= now => {
+                    let total = limit.limit?;
+                    let used = self.usage_since(credential_id, reset_at, false, count);
+                    Some(RemainingMeter::new(
+                        Some(total),
+                        total.saturating_sub(used),
+                        None,
+                        RemainingSource::Provider,
+                    ))
+                }
+                // 快照对应的请求已计入上游余量，其后的第一条本地样本不再扣减
+                reset_at => {
+                    let used = self.usage_since(credential_id, snapshot.observed_at, true, count);
+                    Some(RemainingMeter::new(
+                        limit.limit,
+                        limit.remaining.saturating_sub(used),
+                        reset_at,
+                        RemainingSource::Provider,
+                    ))
+                }
+            }
+        };
+        let configured_meter = |used: u64, max: Option<u64>| {
+            max.map(|max| {
+                RemainingMeter::new(
+                    Some(max),
+                    max.saturating_sub(used),
+                    window_reset_at,
+                    RemainingSource::Configured,
+                )
+            })
+        };
+
+        QuotaRemaining {
+            credential_id: credential_id.to_string(),
+            requests: provider_meter(snapshot.and_then(|s| s.requests), |_| 1)
+                .or_else(|| configured_meter(forecast.requests, forecast.max_requests)),
+            tokens: provider_meter(snapshot.and_then(|s| s.tokens), |s| s.tokens)
+                .or_else(|| configured_meter(forecast.tokens, forecast.max_tokens)),
+            provider_observed_at: snapshot.map(|s| s.observed_at),
+            updated_at: now,
+        }
+    }
+
+    /// 获取所有可估算剩余配额的凭证（按剩余比例升序，未知比例排在最后）
+    pub fn remaining_estimates(&self) -> Vec<QuotaRemaining> {
+        let mut ids: Vec<String> = self.usage.iter().map(|e| e.key().clone()).collect();
+        ids.extend(self.provider_limits.iter().map(|e| e.key().clone()));
+        {
+            let soft_limits = self.soft_limits.read().unwrap_or_else(|e| e.into_inner());
+            ids.extend(soft_limits.limits.keys().cloned());
+        }
+        ids.sort();
+        ids.dedup();
+
+        let mut estimates: Vec<QuotaRemaining> = ids
+            .iter()
+            .map(|id| self.remaining(id))
+            .filter(|r| r.requests.is_some() || r.tokens.is_some())
+            .collect();
+        estimates.sort_by(|a, b| {
+            a.min_remaining_ratio()
+                .unwrap_or(f64::INFINITY)
+                .total_cmp(&b.min_remaining_ratio().unwrap_or(f64::INFINITY))
+        });
+        estimates
+    }
+
+    /// 凭证用量是否已达到软上限
+    pub fn is_near_soft_limit(&self, credential_id: &str) -> bool {
+        self.forecast(credential_id).near_limit
+    }
+
+    /// 计算凭证的用量预测
+    pub fn forecast(&self, credential_id: &str) -> QuotaForecast {
+        let now = Utc::now();
+        let (ratio, limit) = {
+            let soft_limits = self.soft_limits.read().unwrap_or_else(|e| e.into_inner());
+            (
+                soft_limits.ratio,
+                soft_limits.limits.get(credential_id).cloned(),
+            )
+        };
+        let window_secs = limit
+            .as_ref()
+            .map_or(DEFAULT_USAGE_WINDOW_SECS, |l| l.window_secs);
+        let window_start = now - Duration::seconds(window_secs as i64);
+
+        let (requests, tokens, oldest) = match self.usage.get_mut(credential_id) {
+            Some(mut samples) => {
+                prune_samples(&mut samples, window_start);
+                (
+                    samples.len() as u64,
+                    samples.iter().map(|s| s.tokens).sum(),
+                    samples.front().map(|s| s.at),
+                )
+            }
+            None => (0, 0, None),
+        };
+
+        let max_requests = limit.as_ref().and_then(|l| l.max_requests);
+        let max_tokens = limit.as_ref().and_then(|l| l.max_tokens);
+        let usage_ratio = [(requests, max_requests), (tokens, max_tokens)]
+            .into_iter()
+            .filter_map(|(used, max)| max.map(|max| used as f64 / max.max(1) as f64))
+            .reduce(f64::max);
+
+        // 按窗口内的平均速率外推，取请求数与 Token 中先耗尽者
+        let elapsed_secs = oldest.map_or(0, |at| (now - at).num_seconds().max(1)) as f64;
+        let projected_exhaustion_at = [(requests, max_requests), (tokens, max_tokens)]
+            .into_iter()
+            .filter_map(|(used, max)| {
+                let max = max?;
+                if used >= max {
+                    return Some(now);
+                }
+                if used == 0 || elapsed_secs == 0.0 {
+                    return None;
+                }
+                let rate = used as f64 / elapsed_secs;
+                let secs = ((max - used) as f64 / rate).ceil() as i64;
+                Some(now + Duration::seconds(secs))
+            })
+            .min();
+
+        QuotaForecast {
+            credential_id: credential_id.to_string(),
+            window_secs,
+            requests,
+            tokens,
+            max_requests,
+            max_tokens,
+            usage_ratio,
+            near_limit: usage_ratio.is_some_and(|r| r >= ratio),
+            projected_exhaustion_at,
+        }
+    }
+
+    /// 获取所有有用量记录或配置了配额的凭证预测（按用量比例降序）
+    pub fn forecasts(&self) -> Vec<QuotaForecast> {
+        let mut ids: Vec<String> = self.usage.iter().map(|e| e.key().clone()).collect();
+        {
+            let soft_limits = self.soft_limits.read().unwrap_or_else(|e| e.into_inner());
+            ids.extend(soft_limits.limits.keys().cloned());
+        }
+        ids.sort();
+        ids.dedup();
+
+        let mut forecasts: Vec<QuotaForecast> = ids.iter().map(|id| self.forecast(id)).collect();
+        forecasts.sort_by(|a, b| {
+            b.usage_ratio
+                .unwrap_or(-1.0)
+                .total_cmp(&a.usage_ratio.unwrap_or(-1.0))
+        });
+        forecasts
+    }
+
+    /// 清除凭证的用量记录
+    pub fn clear_usage(&self, credential_id: &str) -> bool {
+        self.provider_limits.remove(credential_id);
+        self.usage.remove(credential_id).is_some()
+    }
+
+    fn publish_remaining(&self, credential_id: &str) {
+        if self.remaining_events.receiver_count() > 0 {
+            let _ = self.remaining_events.send(self.remaining(credential_id));
+        }
+    }
+
+    /// 统计 `since` 之后的本地用量
+    fn usage_since(
+        &self,
+        credential_id: &str,
+        since: DateTime<Utc>,
+        skip_first: bool,
+        count: fn(&UsageSample) -> u64,
+    ) -> u64 {
+        self.usage.get(credential_id).map_or(0, |samples| {
+            samples
+                .iter()
+                .filter(|s| s.at > since)
+                .skip(usize::from(skip_first))
+                .map(count)
+                .sum()
+        })
+    }
+
+    /// 滚动窗口中最早的样本移出窗口的时间
+    fn window_reset_at(&self, credential_id: &str, window_secs: u64) -> Option<DateTime<Utc>> {
+        self.usage
+            .get(credential_id)
+            .and_then(|samples| samples.front().map(|s| s.at))
+            .map(|at| at + Duration::seconds(window_secs as i64))
+    }
+
+    fn window_for(&self, credential_id: &str) -> Duration {
+        let soft_limits = self.soft_limits.read().unwrap_or_else(|e| e.into_inner());
+        let secs = soft_limits
+            .limits
+            .get(credential_id)
+            .map_or(DEFAULT_USAGE_WINDOW_SECS, |l| l.window_secs);
+        Duration::seconds(secs as i64)
+    }
+}
+
+fn prune_samples(samples: &mut VecDeque<UsageSample>, window_start: DateTime<Utc>) {
+    while samples.front().is_some_and(|s| s.at <= window_start) {
+        samples.pop_front();
+    }
+}
+
 impl Default for QuotaManager {
     fn default() -> Self {
         Self::with_defaults()
@@ -421,15 +813,18 @@ impl QuotaManager {
         self.mark_quota_exceeded(failed_credential_id, error_message);
 
         if self.config.switch_project {
-            for cred_id in available_credential_ids {
-                if cred_id != failed_credential_id && self.is_available(cred_id) {
-                    tracing::info!(
-                        from_credential = %failed_credential_id,
-                        to_credential = %cred_id,
-                        "配额超限，自动切换凭证"
-                    );
-                    return QuotaAutoSwitchResult::switched(cred_id.clone());
-                }
+            let candidates: Vec<String> = available_credential_ids
+                .iter()
+                .filter(|id| id.as_str() != failed_credential_id)
+                .cloned()
+                .collect();
+            if let Some(cred_id) = self.select_available_credential(&candidates) {
+                tracing::info!(
+                    from_credential = %failed_credential_id,
+                    to_credential = %cred_id,
+                    "配额超限，自动切换凭证"
+                );
+                return QuotaAutoSwitchResult::switched(cred_id);
             }
         }
 
@@ -454,16 +849,42 @@ impl QuotaManager {
     }
 
     /// 选择下一个可用凭证
+    ///
+    /// 优先选择用量未达到软上限的凭证，全部接近配额时仍返回第一个可用凭证。
     pub fn select_available_credential(
         &self,
         available_credential_ids: &[String],
     ) -> Option<String> {
-        for cred_id in available_credential_ids {
-            if self.is_available(cred_id) {
-                return Some(cred_id.clone());
-            }
+        let available = self.filter_available_credentials(available_credential_ids);
+        available
+            .iter()
+            .find(|id| !self.is_near_soft_limit(id))
+            .or(available.first())
+            .cloned()
+    }
+
+    /// 当前凭证达到软上限时选择替代凭证（主动切换，不标记超限）
+    ///
+    /// 当前凭证未接近配额，或没有未接近配额的可用替代时返回 None。
+    pub fn proactive_switch(
+        &self,
+        current_credential_id: &str,
+        available_credential_ids: &[String],
+    ) -> Option<String> {
+        if !self.is_near_soft_limit(current_credential_id) {
+            return None;
         }
-        None
+        let target = available_credential_ids.iter().find(|id| {
+            id.as_str() != current_credential_id
+                && self.is_available(id)
+                && !self.is_near_soft_limit(id)
+        })?;
+        tracing::info!(
+            from_credential = %current_credential_id,
+            to_credential = %target,
+            "凭证用量接近配额，主动切换"
+        );
+        Some(target.clone())
     }
 
     /// 过滤出可用的凭证 ID 列表
@@ -543,6 +964,7 @@ mod unit_tests {
             switch_project: true,
             switch_preview_model: false,
             cooldown_seconds: 300,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
         let available = vec![
@@ -567,6 +989,7 @@ mod unit_tests {
             switch_project: false,
             switch_preview_model: true,
             cooldown_seconds: 300,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
         let available = vec!["cred-1".to_string()];
@@ -590,6 +1013,7 @@ mod unit_tests {
             switch_project: true,
             switch_preview_model: false,
             cooldown_seconds: 300,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
         manager.mark_quota_exceeded("cred-1", "test");
@@ -690,6 +1114,7 @@ mod unit_tests {
             switch_project: true,
             switch_preview_model: true,
             cooldown_seconds: 300,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
         assert_eq!(manager.config().cooldown_seconds, 300);
@@ -714,6 +1139,7 @@ mod unit_tests {
             switch_project: true,
             switch_preview_model: true,
             cooldown_seconds: 1,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
         assert!(manager.is_available("cred-1"));
@@ -729,6 +1155,7 @@ mod unit_tests {
             switch_project: true,
             switch_preview_model: true,
             cooldown_seconds: 0,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
         manager.mark_quota_exceeded("cred-1", "test");
@@ -802,6 +1229,7 @@ mod unit_tests {
             switch_project: true,
             switch_preview_model: false,
             cooldown_seconds: 300,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
         assert_eq!(manager.get_preview_model("gemini-2.5-pro"), None);
@@ -843,6 +1271,7 @@ mod unit_tests {
             switch_project: true,
             switch_preview_model: true,
             cooldown_seconds: 300,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
         assert!(manager.earliest_recovery().is_none());
@@ -857,6 +1286,7 @@ mod unit_tests {
             switch_project: true,
             switch_preview_model: true,
             cooldown_seconds: 300,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
         assert!(manager.remaining_cooldown_seconds("cred-1").is_none());
@@ -887,4 +1317,131 @@ mod unit_tests {
         assert!(retry_value > 0);
         assert!(retry_value <= 300);
     }
+
+    fn config_with_limit(max_requests: u64) -> QuotaExceededConfig {
+        let mut config = QuotaExceededConfig::default();
+        config.credential_limits.insert(
+            "cred-1".to_string(),
+            CredentialQuotaLimit {
+                max_requests: Some(max_requests),
+                max_tokens: None,
+                window_secs: 3600,
+            },
+        );
+        config
+    }
+
+    #[test]
+    fn test_usage_forecast_and_soft_limit() {
+        let manager = QuotaManager::new(config_with_limit(10));
+
+        for _ in 0..8 {
+            manager.record_usage("cred-1", 100);
+        }
+        let forecast = manager.forecast("cred-1");
+        assert_eq!(forecast.requests, 8);
+        assert_eq!(forecast.tokens, 800);
+        assert_eq!(forecast.usage_ratio, Some(0.8));
+        assert!(!forecast.near_limit);
+        assert!(forecast.projected_exhaustion_at.is_some());
+
+        manager.record_usage("cred-1", 100);
+        assert!(manager.is_near_soft_limit("cred-1"));
+
+        // 未配置配额的凭证只统计用量
+        manager.record_usage("cred-2", 50);
+        let forecast = manager.forecast("cred-2");
+        assert_eq!(forecast.window_secs, DEFAULT_USAGE_WINDOW_SECS);
+        assert!(forecast.usage_ratio.is_none());
+        assert!(!forecast.near_limit);
+
+        let forecasts = manager.forecasts();
+        assert_eq!(forecasts.len(), 2);
+        assert_eq!(forecasts[0].credential_id, "cred-1");
+    }
+
+    #[test]
+    fn test_proactive_switch() {
+        let manager = QuotaManager::new(config_with_limit(2));
+        let ids = vec!["cred-1".to_string(), "cred-2".to_string()];
+
+        manager.record_usage("cred-1", 0);
+        assert!(manager.proactive_switch("cred-1", &ids).is_none());
+
+        manager.record_usage("cred-1", 0);
+        assert_eq!(
+            manager.proactive_switch("cred-1", &ids),
+            Some("cred-2".to_string())
+        );
+        assert_eq!(
+            manager.select_available_credential(&ids),
+            Some("cred-2".to_string())
+        );
+
+        // 软上限比例调高后不再主动切换
+        let mut config = config_with_limit(2);
+        config.soft_limit_ratio = 1.5;
+        manager.reload_soft_limits(&config);
+        assert!(manager.proactive_switch("cred-1", &ids).is_none());
+    }
+
+    #[test]
+    fn test_remaining_combines_provider_limits_and_local_usage() {
+        let manager = QuotaManager::new(config_with_limit(10));
+        let mut receiver = manager.subscribe_remaining();
+
+        // 仅有配置的配额时按窗口内用量估算
+        manager.record_usage("cred-1", 100);
+        let remaining = manager.remaining("cred-1");
+        let requests = remaining.requests.unwrap();
+        assert_eq!(requests.source, RemainingSource::Configured);
+        assert_eq!(requests.remaining, 9);
+        assert!(requests.reset_at.is_some());
+        assert_eq!(receiver.try_recv().unwrap().credential_id, "cred-1");
+
+        // 上游快照优先，快照对应的请求不重复扣减
+        let now = Utc::now();
+        manager.record_provider_limits(
+            "cred-1",
+            ProviderRateLimits {
+                requests: Some(ProviderLimit {
+                    limit: Some(50),
+                    remaining: 40,
+                    reset_at: Some(now + Duration::seconds(60)),
+                }),
+                tokens: None,
+                observed_at: now,
+            },
+        );
+        manager.record_usage("cred-1", 100);
+        manager.record_usage("cred-1", 100);
+        let remaining = manager.remaining("cred-1");
+        let requests = remaining.requests.unwrap();
+        assert_eq!(requests.source, RemainingSource::Provider);
+        assert_eq!(requests.remaining, 39);
+        assert_eq!(requests.remaining_ratio, Some(39.0 / 50.0));
+        // Token 未由上游报告，回退到配置（未配置 Token 配额）
+        assert!(remaining.tokens.is_none());
+        assert_eq!(receiver.try_recv().unwrap().provider_observed_at, Some(now));
+
+        // 上游窗口已重置时按总额度减去重置后的用量估算
+        manager.record_provider_limits(
+            "cred-2",
+            ProviderRateLimits {
+                requests: Some(ProviderLimit {
+                    limit: Some(5),
+                    remaining: 0,
+                    reset_at: Some(now - Duration::seconds(1)),
+                }),
+                tokens: None,
+                observed_at: now - Duration::seconds(30),
+            },
+        );
+        manager.record_usage("cred-2", 0);
+        assert_eq!(manager.remaining("cred-2").requests.unwrap().remaining, 4);
+
+        let estimates = manager.remaining_estimates();
+        assert_eq!(estimates.len(), 2);
+        assert_eq!(estimates[0].credential_id, "cred-1");
+    }
 }
@@ -0,0 +1,184 @@
+//! 上游限流响应头解析
+//!
+//! 支持两种格式：
+//! - Anthropic：`anthropic-ratelimit-{requests,tokens}-{limit,remaining,reset}`，
+//!   reset 为 RFC 3339 时间；未返回 `tokens-*` 时使用 `input-tokens-*`
+//! - OpenAI 兼容：`x-ratelimit-{limit,remaining,reset}-{requests,tokens}`，
+//!   reset 为 `1s` / `6m0s` / `20ms` 形式的相对时长
+
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+
+/// 上游报告的单项限额
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProviderLimit {
+    /// 窗口内总额度（部分上游只返回剩余量）
+    pub limit: Option<u64>,
+    /// 响应时的剩余额度
+    pub remaining: u64,
+    /// 额度重置时间
+    pub reset_at: Option<DateTime<Utc>>,
+}
+
+/// 一次响应携带的限流快照
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProviderRateLimits {
+    pub requests: Option<ProviderLimit>,
+    pub tokens: Option<ProviderLimit>,
+    /// 收到响应的时间
+    pub observed_at: DateTime<Utc>,
+}
+
+/// 从上游响应头解析限流快照，未携带任何限流头时返回 None
+pub fn parse_rate_limit_headers(
+    headers: &HeaderMap,
+    now: DateTime<Utc>,
+) -> Option<ProviderRateLimits> {
+    let requests = anthropic_limit(headers, "requests", now)
+        .or_else(|| openai_limit(headers, "requests", now));
+    let tokens = anthropic_limit(headers, "tokens", now)
+        .or_else(|| anthropic_limit(headers, "input-tokens", now))
+        .or_else(|| openai_limit(headers, "tokens", now));
+
+    if requests.is_none() && tokens.is_none() {
+        return None;
+    }
+    Some(ProviderRateLimits {
+        requests,
+        tokens,
+        observed_at: now,
+    })
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::trim)
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    header_str(headers, name).and_then(|v| v.parse().ok())
+}
+
+fn anthropic_limit(headers: &HeaderMap, kind: &str, now: DateTime<Utc>) -> Option<ProviderLimit> {
+    let prefix = format!("anthropic-ratelimit-{kind}");
+    Some(ProviderLimit {
+        remaining: header_u64(headers, &format!("{prefix}-remaining"))?,
+        limit: header_u64(headers, &format!("{prefix}-limit")),
+        reset_at: header_str(headers, &format!("{prefix}-reset")).and_then(|v| parse_reset(v, now)),
+    })
+}
+
+fn openai_limit(headers: &HeaderMap, kind: &str, now: DateTime<Utc>) -> Option<ProviderLimit> {
+    Some(ProviderLimit {
+        remaining: header_u64(headers, &format!("x-ratelimit-remaining-{kind}"))?,
+        limit: header_u64(headers, &format!("x-ratelimit-limit-{kind}")),
+        reset_at: header_str(headers, &format!("x-ratelimit-reset-{kind}"))
+            .and_then(|v| parse_reset(v, now)),
+    })
+}
+
+/// 解析重置时间：RFC 3339 时间、秒数或 `1h2m3.5s` 形式的相对时长
+fn parse_reset(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&Utc));
+    }
+    if let Ok(secs) = value.parse::<f64>() {
+        return Some(now + Duration::milliseconds((secs * 1000.0) as i64));
+    }
+    parse_go_duration_ms(value).map(|ms| now + Duration::milliseconds(ms))
+}
+
+fn parse_go_duration_ms(value: &str) -> Option<i64> {
+    if value.is_empty() {
+        return None;
+    }
+    let mut total_ms = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_len].parse().ok()?;
+        rest = &rest[number_len..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let unit_ms = match &rest[..unit_len] {
+            "h" => 3_600_000.0,
+            "m" => 60_000.0,
+            "s" => 1_000.0,
+            "ms" => 1.0,
+            _ => return None,
+        };
+        rest = &rest[unit_len..];
+        total_ms += number * unit_ms;
+    }
+    Some(total_ms as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_parse_anthropic_headers() {
+        let now = Utc::now();
+        let map = headers(&[
+            ("anthropic-ratelimit-requests-limit", "50"),
+            ("anthropic-ratelimit-requests-remaining", "49"),
+            ("anthropic-ratelimit-requests-reset", "2026-10-17T12:00:00Z"),
+            ("anthropic-ratelimit-input-tokens-limit", "40000"),
+            ("anthropic-ratelimit-input-tokens-remaining", "39000"),
+        ]);
+        let limits = parse_rate_limit_headers(&map, now).unwrap();
+        let requests = limits.requests.unwrap();
+        assert_eq!(requests.limit, Some(50));
+        assert_eq!(requests.remaining, 49);
+        assert_eq!(
+            requests.reset_at.unwrap().to_rfc3339(),
+            "2026-10-17T12:00:00+00:00"
+        );
+        let tokens = limits.tokens.unwrap();
+        assert_eq!(tokens.remaining, 39000);
+        assert!(tokens.reset_at.is_none());
+    }
+
+    #[test]
+    fn test_parse_openai_headers() {
+        let now = Utc::now();
+        let map = headers(&[
+            ("x-ratelimit-limit-requests", "500"),
+            ("x-ratelimit-remaining-requests", "499"),
+            ("x-ratelimit-reset-requests", "1m30s"),
+            ("x-ratelimit-remaining-tokens", "29000"),
+            ("x-ratelimit-reset-tokens", "20ms"),
+        ]);
+        let limits = parse_rate_limit_headers(&map, now).unwrap();
+        let requests = limits.requests.unwrap();
+        assert_eq!(requests.remaining, 499);
+        assert_eq!(requests.reset_at, Some(now + Duration::seconds(90)));
+        let tokens = limits.tokens.unwrap();
+        assert_eq!(tokens.limit, None);
+        assert_eq!(tokens.reset_at, Some(now + Duration::milliseconds(20)));
+    }
+
+    #[test]
+    fn test_no_rate_limit_headers() {
+        let map = headers(&[("content-type", "application/json")]);
+        assert!(parse_rate_limit_headers(&map, Utc::now()).is_none());
+        assert_eq!(parse_go_duration_ms("6m0s"), Some(360_000));
+        assert_eq!(parse_go_duration_ms("1x"), None);
+    }
+}
@@ -71,6 +71,15 @@ impl CredentialSyncService {
         Ok(manager.config().clone())
     }
 
+    /// 获取配置文件路径
+    pub(crate) fn config_path(&self) -> Result<PathBuf, SyncError> {
+        let manager = self
+            .config_manager
+            .read()
+            .map_err(|e| SyncError::ConfigError(format!("获取配置锁失败: {e}")))?;
+        Ok(manager.config_path().to_path_buf())
+    }
+
     /// 更新配置并保存
     fn update_config(&self, config: Config) -> Result<(), SyncError> {
         let mut manager = self
@@ -200,6 +209,11 @@ impl CredentialSyncService {
                     "Claude OAuth 凭证暂不支持同步到配置".to_string(),
                 ));
             }
+            CredentialData::Mock { .. } => {
+                return Err(SyncError::InvalidCredentialType(
+                    "Mock 凭证不支持同步到配置".to_string(),
+                ));
+            }
             CredentialData::AnthropicKey { api_key, base_url } => {
                 let entry = ApiKeyEntry {
                     id: credential.uuid.clone(),
@@ -353,6 +367,11 @@ impl CredentialSyncService {
                     "API Key Provider 凭证不支持同步到配置".to_string(),
                 ));
             }
+            PoolProviderType::Mock => {
+                return Err(SyncError::InvalidCredentialType(
+                    "Mock 凭证不支持同步到配置".to_string(),
+                ));
+            }
         }
 
         if !found {
@@ -482,6 +501,11 @@ impl CredentialSyncService {
                     "Claude OAuth 凭证暂不支持同步到配置".to_string(),
                 ));
             }
+            CredentialData::Mock { .. } => {
+                return Err(SyncError::InvalidCredentialType(
+                    "Mock 凭证不支持同步到配置".to_string(),
+                ));
+            }
             CredentialData::AnthropicKey { api_key, base_url } => {
                 if let Some(entry) = config
                     .credential_pool
@@ -0,0 +1,325 @@
+//! Provider 端点切换
+//!
+//! 按 `routing.endpoint_fallback` 为凭证的 base_url 生成等价端点候选列表，并维护各端点的健康状态：
+//! - 端点出现传输层失败（见 [`is_transport_failure`]）后进入冷却，冷却期内排到最后
+//! - 端点成功响应（任意非传输层失败的响应）后清除冷却
+//! - 只替换 base_url 的 `scheme://host[:port]` 部分，路径保持不变
+
+use lime_core::config::EndpointFallbackSettings;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 服务请求的端点响应头（如 `https://api.openai.com`）
+pub const UPSTREAM_ENDPOINT_HEADER: &str = "x-lime-upstream-endpoint";
+
+/// 传输层失败（DNS 解析、连接、TLS 握手）的错误消息关键词
+pub const TRANSPORT_FAILURE_KEYWORDS: &[&str] = &[
+    "dns error",
+    "failed to lookup address",
+    "name or service not known",
+    "no such host",
+    "nodename nor servname",
+    "temporary failure in name resolution",
+    "connection refused",
+    "connection reset",
+    "tls handshake",
+    "handshake failure",
+    "certificate",
+    "error trying to connect",
+    "无法连接到服务器",
+];
+
+/// 错误消息是否表示传输层失败（换一个端点可能成功）
+pub fn is_transport_failure(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    TRANSPORT_FAILURE_KEYWORDS
+        .iter()
+        .any(|keyword| lower.contains(keyword))
+}
+
+/// 拆分 URL 为规范化的源（`scheme://host[:port]`，小写、省略默认端口）与其余部分
+pub fn split_origin(url: &str) -> Option<(String, &str)> {
+    let url = url.trim();
+    let scheme_end = url.find("://")?;
+    let authority_start = scheme_end + 3;
+    let rest_start = url[authority_start..]
+        .find(['/', '?', '#'])
+        .map(|i| authority_start + i)
+        .unwrap_or(url.len());
+    if rest_start == authority_start {
+        return None;
+    }
+
+    let origin = url[..rest_start].to_ascii_lowercase();
+    let origin = origin
+        .strip_suffix(":443")
+        .filter(|o| o.starts_with("https://"))
+        .or_else(|| {
+            origin
+                .strip_suffix(":80")
+                .filter(|o| o.starts_with("http://"))
+        })
+        .map(str::to_string)
+        .unwrap_or(origin);
+    Some((origin, &url[rest_start..]))
+}
+
+/// 候选端点
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointCandidate {
+    /// 端点源（`scheme://host[:port]`）
+    pub origin: String,
+    /// 替换源之后的 base_url
+    pub base_url: String,
+    /// 是否处于冷却期（已排到最后）
+    pub cooling_down: bool,
+}
+
+/// 单个请求的端点计划
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointPlan {
+    pub provider: String,
+    /// 按尝试顺序排列的候选端点，第一个为凭证当前端点（未冷却时）
+    pub candidates: Vec<EndpointCandidate>,
+}
+
+/// 端点健康状态快照
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EndpointHealthSnapshot {
+    pub origin: String,
+    pub healthy: bool,
+    /// 连续失败次数
+    pub consecutive_failures: u32,
+    /// 累计服务请求数
+    pub served: u64,
+    /// 累计传输层失败数
+    pub failures: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    cooldown_until: Option<Instant>,
+    consecutive_failures: u32,
+    served: u64,
+    failures: u64,
+    last_error: Option<String>,
+}
+
+/// 端点切换管理器
+#[derive(Debug, Default)]
+pub struct EndpointFallbackManager {
+    settings: RwLock<EndpointFallbackSettings>,
+    /// 端点源 -> 健康状态
+    health: Mutex<HashMap<String, EndpointHealth>>,
+}
+
+impl EndpointFallbackManager {
+    pub fn new(settings: EndpointFallbackSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            health: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 热更新配置（清空健康状态）
+    pub fn reload(&self, settings: &EndpointFallbackSettings) {
+        *self.settings.write() = settings.clone();
+        self.health.lock().clear();
+    }
+
+    /// 为凭证的 base_url 生成端点计划
+    ///
+    /// 未启用、base_url 不属于该 Provider 的任何端点组或组内只有一个端点时返回 None。
+    pub fn plan(&self, provider: &str, base_url: &str) -> Option<EndpointPlan> {
+        self.plan_at(provider, base_url, Instant::now())
+    }
+
+    fn plan_at(&self, provider: &str, base_url: &str, now: Instant) -> Option<EndpointPlan> {
+        let settings = self.settings.read();
+        if !settings.enabled {
+            return None;
+        }
+        let (current, rest) = split_origin(base_url)?;
+        let provider = provider.to_lowercase();
+        let group = settings.groups.iter().find(|group| {
+            group.provider.to_lowercase() == provider
+                && group
+                    .endpoints
+                    .iter()
+                    .any(|e| split_origin(e).is_some_and(|(origin, _)| origin == current))
+        })?;
+
+        let mut origins = vec![current.clone()];
+        for (origin, _) in group.endpoints.iter().filter_map(|e| split_origin(e)) {
+            if !origins.contains(&origin) {
+                origins.push(origin);
+            }
+        }
+        if origins.len() < 2 {
+            return None;
+        }
+
+        let health = self.health.lock();
+        let (ready, cooling): (Vec<_>, Vec<_>) = origins
+            .into_iter()
+            .map(|origin| {
+                let cooling_down = health
+                    .get(&origin)
+                    .and_then(|h| h.cooldown_until)
+                    .is_some_and(|until| until > now);
+                EndpointCandidate {
+                    base_url: format!("{origin}{rest}"),
+                    origin,
+                    cooling_down,
+                }
+            })
+            .partition(|candidate| !candidate.cooling_down);
+
+        Some(EndpointPlan {
+            provider,
+            candidates: ready.into_iter().chain(cooling).collect(),
+        })
+    }
+
+    /// 记录端点传输层失败，按配置的冷却时间进入冷却
+    pub fn mark_failure(&self, origin: &str, error: &str) {
+        self.mark_failure_at(origin, error, Instant::now());
+    }
+
+    fn mark_failure_at(&self, origin: &str, error: &str, now: Instant) {
+        let cooldown = Duration::from_secs(self.settings.read().cooldown_secs);
+        let mut health = self.health.lock();
+        let entry = health.entry(origin.to_string()).or_default();
+        entry.consecutive_failures += 1;
+        entry.failures += 1;
+        entry.last_error = Some(error.chars().take(200).collect());
+        entry.cooldown_until = (!cooldown.is_zero()).then(|| now + cooldown);
+    }
+
+    /// 记录端点服务了请求，清除冷却
+    pub fn mark_success(&self, origin: &str) {
+        let mut health = self.health.lock();
+        let entry = health.entry(origin.to_string()).or_default();
+        entry.consecutive_failures = 0;
+        entry.served += 1;
+        entry.cooldown_until = None;
+    }
+
+    /// 已记录端点的健康状态
+    pub fn snapshot(&self) -> Vec<EndpointHealthSnapshot> {
+        let now = Instant::now();
+        let mut snapshot: Vec<_> = self
+            .health
+            .lock()
+            .iter()
+            .map(|(origin, h)| EndpointHealthSnapshot {
+                origin: origin.clone(),
+                healthy: !h.cooldown_until.is_some_and(|until| until > now),
+                consecutive_failures: h.consecutive_failures,
+                served: h.served,
+                failures: h.failures,
+                last_error: h.last_error.clone(),
+            })
+            .collect();
+        snapshot.sort_by(|a, b| a.origin.cmp(&b.origin));
+        snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::config::EndpointGroup;
+
+    fn manager() -> EndpointFallbackManager {
+        EndpointFallbackManager::new(EndpointFallbackSettings {
+            enabled: true,
+            cooldown_secs: 60,
+            groups: vec![EndpointGroup {
+                provider: "openai".to_string(),
+                endpoints: vec![
+                    "https://api.openai.com".to_string(),
+                    "https://openai-mirror.example.com/".to_string(),
+                ],
+            }],
+        })
+    }
+
+    #[test]
+    fn test_split_origin() {
+        assert_eq!(
+            split_origin("HTTPS://API.openai.com:443/v1"),
+            Some(("https://api.openai.com".to_string(), "/v1"))
+        );
+        assert_eq!(
+            split_origin("http://localhost:8080"),
+            Some(("http://localhost:8080".to_string(), ""))
+        );
+        assert_eq!(split_origin("api.openai.com/v1"), None);
+    }
+
+    #[test]
+    fn test_plan_keeps_path_and_skips_unknown_hosts() {
+        let manager = manager();
+        let plan = manager.plan("OpenAI", "https://api.openai.com/v1").unwrap();
+        let urls: Vec<_> = plan
+            .candidates
+            .iter()
+            .map(|c| c.base_url.as_str())
+            .collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://api.openai.com/v1",
+                "https://openai-mirror.example.com/v1"
+            ]
+        );
+
+        assert!(manager
+            .plan("openai", "https://my-proxy.local/v1")
+            .is_none());
+        assert!(manager.plan("claude", "https://api.openai.com").is_none());
+    }
+
+    #[test]
+    fn test_failed_endpoint_cools_down_until_success() {
+        let manager = manager();
+        let now = Instant::now();
+        manager.mark_failure_at("https://api.openai.com", "dns error", now);
+
+        let plan = manager
+            .plan_at("openai", "https://api.openai.com", now)
+            .unwrap();
+        assert_eq!(
+            plan.candidates[0].origin,
+            "https://openai-mirror.example.com"
+        );
+        assert!(plan.candidates[1].cooling_down);
+
+        let later = now + Duration::from_secs(61);
+        let plan = manager
+            .plan_at("openai", "https://api.openai.com", later)
+            .unwrap();
+        assert_eq!(plan.candidates[0].origin, "https://api.openai.com");
+
+        manager.mark_success("https://api.openai.com");
+        let health = manager.snapshot();
+        assert!(health[0].healthy);
+        assert_eq!(health[0].consecutive_failures, 0);
+        assert_eq!(health[0].failures, 1);
+    }
+
+    #[test]
+    fn test_is_transport_failure() {
+        assert!(is_transport_failure(
+            "error sending request: dns error: failed to lookup address information"
+        ));
+        assert!(is_transport_failure(
+            "网络连接失败，请检查网络设置后重试。详情：无法连接到服务器"
+        ));
+        assert!(!is_transport_failure("HTTP 429 - rate limited"));
+    }
+}
@@ -1,11 +1,16 @@
 //! 容错机制模块
 //!
-//! 提供重试、故障转移和超时控制功能
+//! 提供重试、故障转移、端点切换和超时控制功能
 
+mod endpoint_fallback;
 mod failover;
 mod retry;
 mod timeout;
 
+pub use endpoint_fallback::{
+    is_transport_failure, split_origin, EndpointCandidate, EndpointFallbackManager,
+    EndpointHealthSnapshot, EndpointPlan, TRANSPORT_FAILURE_KEYWORDS, UPSTREAM_ENDPOINT_HEADER,
+};
 pub use failover::{
     Failover, FailoverConfig, FailoverManager, FailoverResult, FailureType, SwitchEvent,
     QUOTA_EXCEEDED_KEYWORDS, QUOTA_EXCEEDED_STATUS_CODES,
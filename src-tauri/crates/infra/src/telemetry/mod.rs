@@ -1,19 +1,31 @@
 //! 监控与日志模块
 //!
-//! 提供请求日志记录、统计聚合和 Token 追踪功能
+//! 提供请求日志记录、统计聚合、Token 追踪、请求追踪尾部采样、单请求脱敏分享和匿名用量统计功能
 
 mod logger;
+mod share;
 mod stats;
 mod tokens;
+mod trace_sampling;
 mod types;
+mod usage_analytics;
 
 pub use logger::{LogRotationConfig, LoggerError, RequestLogger};
+pub use share::{
+    RequestShareBundle, ShareResponseSummary, REQUEST_BODY_TRACE_STAGE, SHARE_BUNDLE_VERSION,
+};
 pub use stats::StatsAggregator;
 pub use tokens::{
     ModelTokenStats, PeriodTokenStats, ProviderTokenStats, TokenSource, TokenStatsSummary,
     TokenTracker, TokenUsageRecord,
 };
+pub use trace_sampling::{
+    SampledTrace, TraceQuery, TraceSampleReason, TraceSampler, TRACE_FILE_NAME,
+};
 pub use types::{ModelStats, ProviderStats, RequestLog, RequestStatus, StatsSummary, TimeRange};
+pub use usage_analytics::{
+    UsageAnalytics, UsageAnalyticsSummary, UsageCounters, USAGE_ANALYTICS_FILE_NAME,
+};
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,278 @@
+//! 单个请求的脱敏分享包
+//!
+//! 将请求日志与采样追踪合并为一个可直接粘贴到问题反馈中的 JSON：
+//! - 请求体（需开启 `logging.include_request_body`，追踪中记录为 `request_body` 事件）
+//! - Provider、请求入口、转换路径（客户端类型 -> Provider）、响应状态与耗时
+//!
+//! 导出前统一脱敏：密钥类字段替换为占位符，URL 去除用户信息，
+//! 其余字符串再经 `CredentialSanitizer` 过滤常见密钥格式。凭证 ID 不导出。
+
+use chrono::{DateTime, Utc};
+use lime_core::config::redact_secret_fields;
+use lime_core::processor::TraceEvent;
+use lime_core::sanitizer::CredentialSanitizer;
+use lime_core::ProviderType;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::trace_sampling::SampledTrace;
+use super::types::{RequestLog, RequestStatus};
+
+/// 分享包格式版本
+pub const SHARE_BUNDLE_VERSION: u32 = 1;
+
+/// 记录请求体的追踪阶段
+pub const REQUEST_BODY_TRACE_STAGE: &str = "request_body";
+
+/// 响应摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareResponseSummary {
+    pub status: RequestStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_status: Option<u16>,
+    pub duration_ms: u64,
+    pub retry_count: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_tokens: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_tokens: Option<u32>,
+}
+
+/// 请求分享包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestShareBundle {
+    pub format_version: u32,
+    pub generated_at: DateTime<Utc>,
+    pub app_version: String,
+    pub request_id: String,
+    pub timestamp: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ProviderType>,
+    pub model: String,
+    pub is_stream: bool,
+    /// 请求入口（如 `POST /v1/chat/completions`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+    /// 转换路径（客户端类型 -> 路由 Provider -> 实际 Provider）
+    pub converter_path: Vec<String>,
+    pub response: ShareResponseSummary,
+    /// 已脱敏的请求体；未开启请求体记录或请求未被采样时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<Value>,
+    /// 已脱敏的追踪事件（不含请求体）
+    pub trace: Vec<TraceEvent>,
+}
+
+impl RequestShareBundle {
+    /// 由请求日志与采样追踪构建分享包，两者都不存在时返回 None
+    pub fn build(
+        log: Option<&RequestLog>,
+        trace: Option<&SampledTrace>,
+        sanitizer: &CredentialSanitizer,
+    ) -> Option<Self> {
+        let (request_id, timestamp, provider, model, is_stream, response) = match (log, trace) {
+            (Some(log), _) => (
+                log.id.clone(),
+                log.timestamp,
+                Some(log.provider),
+                log.model.clone(),
+                log.is_streaming,
+                ShareResponseSummary {
+                    status: log.status,
+                    http_status: log.http_status,
+                    duration_ms: log.duration_ms,
+                    retry_count: log.retry_count,
+                    error_message: log.error_message.as_deref().map(|m| sanitizer.sanitize(m)),
+                    input_tokens: log.input_tokens,
+                    output_tokens: log.output_tokens,
+                },
+            ),
+            (None, Some(trace)) => (
+                trace.request_id.clone(),
+                trace.timestamp,
+                trace.provider,
+                trace.model.clone(),
+                trace.is_stream,
+                ShareResponseSummary {
+                    status: trace.status,
+                    http_status: None,
+                    duration_ms: trace.duration_ms,
+                    retry_count: trace.retry_count,
+                    error_message: trace
+                        .error_message
+                        .as_deref()
+                        .map(|m| sanitizer.sanitize(m)),
+                    input_tokens: None,
+                    output_tokens: None,
+                },
+            ),
+            (None, None) => return None,
+        };
+
+        let events = trace.map(|t| t.events.as_slice()).unwrap_or_default();
+        let endpoint = events
+            .iter()
+            .find(|e| e.stage == "received")
+            .map(|e| e.message.clone());
+        let converter_path = converter_path(events);
+        let request_body = events
+            .iter()
+            .find(|e| e.stage == REQUEST_BODY_TRACE_STAGE)
+            .and_then(|e| e.data.clone())
+            .map(|body| redact_value(body, sanitizer));
+        let trace = events
+            .iter()
+            .filter(|e| e.stage != REQUEST_BODY_TRACE_STAGE)
+            .map(|e| TraceEvent {
+                offset_ms: e.offset_ms,
+                stage: e.stage.clone(),
+                message: sanitizer.sanitize(&e.message),
+                data: e.data.clone().map(|data| redact_value(data, sanitizer)),
+            })
+            .collect();
+
+        Some(Self {
+            format_version: SHARE_BUNDLE_VERSION,
+            generated_at: Utc::now(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            request_id,
+            timestamp,
+            provider,
+            model,
+            is_stream,
+            endpoint,
+            converter_path,
+            response,
+            request_body,
+            trace,
+        })
+    }
+}
+
+/// 从 `route` 事件提取转换路径：客户端类型、路由 Provider 与实际 Provider
+fn converter_path(events: &[TraceEvent]) -> Vec<String> {
+    let Some(route) = events.iter().find(|e| e.stage == "route") else {
+        return Vec::new();
+    };
+    let mut path = Vec::new();
+    if let Some(client) = route
+        .data
+        .as_ref()
+        .and_then(|data| data.get("client_type"))
+        .and_then(Value::as_str)
+    {
+        path.push(client.to_string());
+    }
+    for hop in route.message.split(" -> ").map(str::trim) {
+        if !hop.is_empty() && path.last().map(String::as_str) != Some(hop) {
+            path.push(hop.to_string());
+        }
+    }
+    path
+}
+
+/// 脱敏 JSON：密钥类字段替换占位符，其余字符串过滤常见密钥格式
+fn redact_value(mut value: Value, sanitizer: &CredentialSanitizer) -> Value {
+    redact_secret_fields(&mut value);
+    sanitize_strings(&mut value, sanitizer);
+    value
+}
+
+fn sanitize_strings(value: &mut Value, sanitizer: &CredentialSanitizer) {
+    match value {
+        Value::String(text) => *text = sanitizer.sanitize(text),
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| sanitize_strings(item, sanitizer)),
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|item| sanitize_strings(item, sanitizer)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::TraceSampleReason;
+
+    fn event(stage: &str, message: &str, data: Option<Value>) -> TraceEvent {
+        TraceEvent {
+            offset_ms: 0,
+            stage: stage.to_string(),
+            message: message.to_string(),
+            data,
+        }
+    }
+
+    #[test]
+    fn test_build_share_bundle_redacts_secrets() {
+        let mut log = RequestLog::new(
+            "req-1".to_string(),
+            ProviderType::Claude,
+            "claude-sonnet-4-5".to_string(),
+            false,
+        );
+        log.mark_failed(
+            120,
+            Some(401),
+            "invalid key sk-abcdefghijklmnopqrstuvwx".to_string(),
+        );
+        log.set_credential_id("cred-secret-id".to_string());
+
+        let trace = SampledTrace {
+            request_id: "req-1".to_string(),
+            timestamp: log.timestamp,
+            provider: Some(ProviderType::Claude),
+            model: log.model.clone(),
+            credential_id: Some("cred-secret-id".to_string()),
+            is_stream: false,
+            status: RequestStatus::Failed,
+            duration_ms: 120,
+            retry_count: 0,
+            reason: TraceSampleReason::Error,
+            error_message: None,
+            events: vec![
+                event("received", "POST /v1/chat/completions", None),
+                event(
+                    REQUEST_BODY_TRACE_STAGE,
+                    "request body",
+                    Some(serde_json::json!({
+                        "model": "claude-sonnet-4-5",
+                        "api_key": "plain-secret",
+                        "messages": [{ "role": "user", "content": "key sk-abcdefghijklmnopqrstuvwx" }]
+                    })),
+                ),
+                event(
+                    "route",
+                    "openai -> claude",
+                    Some(serde_json::json!({ "client_type": "cursor" })),
+                ),
+            ],
+            dropped_events: 0,
+        };
+
+        let sanitizer = CredentialSanitizer::with_defaults();
+        let bundle = RequestShareBundle::build(Some(&log), Some(&trace), &sanitizer).unwrap();
+        assert_eq!(
+            bundle.endpoint.as_deref(),
+            Some("POST /v1/chat/completions")
+        );
+        assert_eq!(bundle.converter_path, vec!["cursor", "openai", "claude"]);
+        assert_eq!(bundle.response.http_status, Some(401));
+        assert_eq!(bundle.trace.len(), 2);
+
+        let json = serde_json::to_string(&bundle).unwrap();
+        assert!(!json.contains("plain-secret"));
+        assert!(!json.contains("sk-abcdefghijklmnopqrstuvwx"));
+        assert!(!json.contains("cred-secret-id"));
+        assert_eq!(
+            bundle.request_body.unwrap()["model"],
+            serde_json::json!("claude-sonnet-4-5")
+        );
+
+        assert!(RequestShareBundle::build(None, None, &sanitizer).is_none());
+    }
+}
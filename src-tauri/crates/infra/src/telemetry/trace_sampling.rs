@@ -0,0 +1,492 @@
+//! 请求追踪尾部采样
+//!
+//! 请求处理过程中的详细追踪事件缓冲在 `RequestContext::trace` 中，
+//! 请求结束后由 [`TraceSampler`] 按结果决定是否保留：
+//! - 出错（失败/超时）的请求按 `error_sample_rate` 采样
+//! - 耗时超过 `latency_threshold_ms` 的慢请求按 `slow_sample_rate` 采样
+//! - 其余请求按 `success_sample_rate` 采样（默认不保留）
+//!
+//! 采样由请求 ID 的哈希决定，同一请求的判定结果稳定。保留的追踪写入内存环形缓冲，
+//! 并由后台线程追加到请求日志目录下的 `traces.jsonl`（不阻塞请求路径），启动时从文件恢复。
+
+use super::types::RequestStatus;
+use chrono::{DateTime, Utc};
+use lime_core::config::TraceSamplingSettings;
+use lime_core::processor::{RequestContext, TraceEvent};
+use lime_core::ProviderType;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+/// 追踪文件名
+pub const TRACE_FILE_NAME: &str = "traces.jsonl";
+
+/// 追踪被保留的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceSampleReason {
+    /// 请求出错
+    Error,
+    /// 请求耗时超过阈值
+    Slow,
+    /// 正常请求的基线采样
+    Baseline,
+}
+
+/// 已保留的请求追踪
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SampledTrace {
+    pub request_id: String,
+    pub timestamp: DateTime<Utc>,
+    pub provider: Option<ProviderType>,
+    pub model: String,
+    pub credential_id: Option<String>,
+    pub is_stream: bool,
+    pub status: RequestStatus,
+    pub duration_ms: u64,
+    pub retry_count: u32,
+    pub reason: TraceSampleReason,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    pub events: Vec<TraceEvent>,
+    #[serde(default)]
+    pub dropped_events: u32,
+}
+
+/// 追踪查询条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TraceQuery {
+    /// 仅返回指定原因的追踪
+    #[serde(default)]
+    pub reason: Option<TraceSampleReason>,
+    /// 仅返回指定模型的追踪
+    #[serde(default)]
+    pub model: Option<String>,
+    /// 最多返回条数（默认全部）
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// 尾部采样器
+pub struct TraceSampler {
+    settings: RwLock<TraceSamplingSettings>,
+    traces: RwLock<VecDeque<SampledTrace>>,
+    /// 追踪文件写入线程的任务通道（未配置文件时为空）
+    writer: Option<mpsc::Sender<TraceWrite>>,
+    writer_handle: Option<JoinHandle<()>>,
+}
+
+impl TraceSampler {
+    /// 创建采样器，追踪持久化到 `trace_file`（为空时仅保留在内存）
+    pub fn new(settings: &TraceSamplingSettings, trace_file: Option<PathBuf>) -> Self {
+        let max = settings.max_traces;
+        let loaded = trace_file
+            .as_deref()
+            .map(load_persisted)
+            .unwrap_or_default();
+        let file_lines = loaded.len();
+        let traces: VecDeque<SampledTrace> = loaded
+            .into_iter()
+            .skip(file_lines.saturating_sub(max))
+            .collect();
+
+        let (writer, writer_handle) = match trace_file {
+            Some(path) => {
+                let (tx, rx) = mpsc::channel();
+                let writer = TraceWriter {
+                    path,
+                    traces: traces.clone(),
+                    file_lines,
+                };
+                match std::thread::Builder::new()
+                    .name("trace-writer".to_string())
+                    .spawn(move || writer.run(rx))
+                {
+                    Ok(handle) => (Some(tx), Some(handle)),
+                    Err(e) => {
+                        tracing::warn!("[TRACE] 无法启动追踪写入线程，仅保留在内存: {}", e);
+                        (None, None)
+                    }
+                }
+            }
+            None => (None, None),
+        };
+
+        Self {
+            settings: RwLock::new(settings.clone()),
+            traces: RwLock::new(traces),
+            writer,
+            writer_handle,
+        }
+    }
+
+    /// 使用请求日志目录下的默认追踪文件创建采样器
+    pub fn with_default_file(settings: &TraceSamplingSettings) -> Self {
+        let trace_file = lime_core::app_paths::resolve_request_logs_dir()
+            .map(|dir| dir.join(TRACE_FILE_NAME))
+            .map_err(|e| tracing::warn!("[TRACE] 无法定位追踪文件目录，仅保留在内存: {}", e))
+            .ok();
+        Self::new(settings, trace_file)
+    }
+
+    /// 热更新配置
+    pub fn reload(&self, settings: &TraceSamplingSettings) {
+        *self.settings.write() = settings.clone();
+        let max = settings.max_traces;
+        let mut traces = self.traces.write();
+        while traces.len() > max {
+            traces.pop_front();
+        }
+    }
+
+    /// 当前配置
+    pub fn settings(&self) -> TraceSamplingSettings {
+        self.settings.read().clone()
+    }
+
+    /// 判定请求结束时是否保留追踪
+    pub fn decide(
+        &self,
+        request_id: &str,
+        status: RequestStatus,
+        duration_ms: u64,
+    ) -> Option<TraceSampleReason> {
+        let settings = self.settings.read();
+        if !settings.enabled || settings.max_traces == 0 {
+            return None;
+        }
+        let (reason, rate) = if matches!(status, RequestStatus::Failed | RequestStatus::Timeout) {
+            (TraceSampleReason::Error, settings.error_sample_rate)
+        } else if duration_ms >= settings.latency_threshold_ms {
+            (TraceSampleReason::Slow, settings.slow_sample_rate)
+        } else {
+            (TraceSampleReason::Baseline, settings.success_sample_rate)
+        };
+        (sample_point(request_id) < rate.clamp(0.0, 1.0)).then_some(reason)
+    }
+
+    /// 请求结束：按采样判定保留追踪，返回是否保留
+    ///
+    /// 重试中的中间状态不做判定，缓冲的事件会随上下文继续累积。
+    pub fn finish(
+        &self,
+        ctx: &RequestContext,
+        status: RequestStatus,
+        error_message: Option<String>,
+    ) -> bool {
+        if status == RequestStatus::Retrying {
+            return false;
+        }
+        let duration_ms = ctx.elapsed_ms();
+        let Some(reason) = self.decide(&ctx.request_id, status, duration_ms) else {
+            return false;
+        };
+
+        let trace = SampledTrace {
+            request_id: ctx.request_id.clone(),
+            timestamp: ctx.timestamp,
+            provider: ctx.provider,
+            model: ctx.resolved_model.clone(),
+            credential_id: ctx.credential_id.clone(),
+            is_stream: ctx.is_stream,
+            status,
+            duration_ms,
+            retry_count: ctx.retry_count,
+            reason,
+            error_message,
+            events: ctx.trace.clone(),
+            dropped_events: ctx.trace_dropped,
+        };
+        self.push(trace.clone());
+        self.persist(trace);
+        true
+    }
+
+    /// 查询保留的追踪（最新在前）
+    pub fn list(&self, query: &TraceQuery) -> Vec<SampledTrace> {
+        let traces = self.traces.read();
+        let iter = traces
+            .iter()
+            .rev()
+            .filter(|t| query.reason.is_none_or(|reason| t.reason == reason))
+            .filter(|t| query.model.as_deref().is_none_or(|model| t.model == model))
+            .cloned();
+        match query.limit {
+            Some(limit) => iter.take(limit).collect(),
+            None => iter.collect(),
+        }
+    }
+
+    /// 按请求 ID 获取追踪
+    pub fn get(&self, request_id: &str) -> Option<SampledTrace> {
+        self.traces
+            .read()
+            .iter()
+            .rev()
+            .find(|t| t.request_id == request_id)
+            .cloned()
+    }
+
+    /// 清空内存与文件中的追踪
+    pub fn clear(&self) -> Result<(), String> {
+        self.traces.write().clear();
+        let Some(writer) = &self.writer else {
+            return Ok(());
+        };
+        let (reply_tx, reply_rx) = mpsc::sync_channel(1);
+        writer
+            .send(TraceWrite::Clear(reply_tx))
+            .map_err(|_| "追踪写入线程已退出".to_string())?;
+        reply_rx
+            .recv()
+            .map_err(|_| "追踪写入线程已退出".to_string())?
+    }
+
+    fn push(&self, trace: SampledTrace) {
+        let max = self.settings.read().max_traces;
+        let mut traces = self.traces.write();
+        traces.push_back(trace);
+        while traces.len() > max {
+            traces.pop_front();
+        }
+    }
+
+    /// 交给写入线程持久化，请求路径上不做文件 IO
+    fn persist(&self, trace: SampledTrace) {
+        let Some(writer) = &self.writer else {
+            return;
+        };
+        let max_traces = self.settings.read().max_traces;
+        let job = TraceWrite::Append {
+            trace: Box::new(trace),
+            max_traces,
+        };
+        if writer.send(job).is_err() {
+            tracing::warn!("[TRACE] 追踪写入线程已退出，追踪仅保留在内存");
+        }
+    }
+}
+
+impl Drop for TraceSampler {
+    /// 关闭通道并等待写入线程处理完剩余任务
+    fn drop(&mut self) {
+        self.writer.take();
+        if let Some(handle) = self.writer_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// 追踪文件写入任务
+enum TraceWrite {
+    Append {
+        trace: Box<SampledTrace>,
+        max_traces: usize,
+    },
+    Clear(mpsc::SyncSender<Result<(), String>>),
+}
+
+/// 追踪文件写入线程状态
+///
+/// 自行保留最近写入的追踪，用于压缩重写，避免与内存缓冲中尚未落盘的追踪重复。
+struct TraceWriter {
+    path: PathBuf,
+    traces: VecDeque<SampledTrace>,
+    /// 追踪文件当前行数（用于判断何时重写压缩）
+    file_lines: usize,
+}
+
+impl TraceWriter {
+    fn run(mut self, rx: mpsc::Receiver<TraceWrite>) {
+        for job in rx {
+            match job {
+                TraceWrite::Append { trace, max_traces } => self.append(*trace, max_traces),
+                TraceWrite::Clear(reply) => {
+                    let _ = reply.send(self.clear());
+                }
+            }
+        }
+    }
+
+    /// 追加写入追踪文件；文件行数超过保留上限的两倍时按保留内容重写
+    fn append(&mut self, trace: SampledTrace, max_traces: usize) {
+        self.traces.push_back(trace);
+        while self.traces.len() > max_traces {
+            self.traces.pop_front();
+        }
+        let result = if self.file_lines >= max_traces.saturating_mul(2) {
+            self.rewrite_file()
+        } else {
+            self.append_line()
+        };
+        if let Err(e) = result {
+            tracing::warn!("[TRACE] 写入追踪文件失败 {}: {}", self.path.display(), e);
+        }
+    }
+
+    fn append_line(&mut self) -> std::io::Result<()> {
+        let Some(trace) = self.traces.back() else {
+            return Ok(());
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(trace)?)?;
+        self.file_lines += 1;
+        Ok(())
+    }
+
+    fn rewrite_file(&mut self) -> std::io::Result<()> {
+        let mut content = String::new();
+        for trace in self.traces.iter() {
+            content.push_str(&serde_json::to_string(trace)?);
+            content.push('\n');
+        }
+        fs::write(&self.path, content)?;
+        self.file_lines = self.traces.len();
+        Ok(())
+    }
+
+    fn clear(&mut self) -> Result<(), String> {
+        self.traces.clear();
+        self.file_lines = 0;
+        if self.path.exists() {
+            fs::remove_file(&self.path)
+                .map_err(|e| format!("删除追踪文件失败 {}: {e}", self.path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+fn load_persisted(path: &Path) -> Vec<SampledTrace> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str(&line).ok())
+        .collect()
+}
+
+/// 将请求 ID 映射到 [0, 1) 上的采样点
+fn sample_point(request_id: &str) -> f64 {
+    let mut hasher = DefaultHasher::new();
+    request_id.hash(&mut hasher);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> TraceSamplingSettings {
+        TraceSamplingSettings {
+            latency_threshold_ms: 1_000,
+            max_traces: 2,
+            ..TraceSamplingSettings::default()
+        }
+    }
+
+    fn temp_trace_file() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("lime-trace-test-{}", uuid::Uuid::new_v4()))
+            .join(TRACE_FILE_NAME)
+    }
+
+    #[test]
+    fn test_decide_by_outcome() {
+        let sampler = TraceSampler::new(&settings(), None);
+        assert_eq!(
+            sampler.decide("a", RequestStatus::Failed, 10),
+            Some(TraceSampleReason::Error)
+        );
+        assert_eq!(
+            sampler.decide("b", RequestStatus::Success, 5_000),
+            Some(TraceSampleReason::Slow)
+        );
+        assert_eq!(sampler.decide("c", RequestStatus::Success, 10), None);
+
+        sampler.reload(&TraceSamplingSettings {
+            success_sample_rate: 1.0,
+            ..settings()
+        });
+        assert_eq!(
+            sampler.decide("c", RequestStatus::Success, 10),
+            Some(TraceSampleReason::Baseline)
+        );
+
+        sampler.reload(&TraceSamplingSettings {
+            enabled: false,
+            ..settings()
+        });
+        assert_eq!(sampler.decide("a", RequestStatus::Failed, 10), None);
+    }
+
+    #[test]
+    fn test_finish_keeps_buffered_events_and_bounds_retention() {
+        let sampler = TraceSampler::new(&settings(), None);
+        let mut ctx = RequestContext::new("model".to_string());
+        ctx.trace("received", "request received");
+
+        assert!(!sampler.finish(&ctx, RequestStatus::Retrying, None));
+        assert!(!sampler.finish(&ctx, RequestStatus::Success, None));
+        assert!(sampler.finish(&ctx, RequestStatus::Failed, Some("boom".to_string())));
+
+        let trace = sampler.get(&ctx.request_id).unwrap();
+        assert_eq!(trace.events.len(), 1);
+        assert_eq!(trace.error_message.as_deref(), Some("boom"));
+
+        for _ in 0..3 {
+            let ctx = RequestContext::new("other".to_string());
+            sampler.finish(&ctx, RequestStatus::Timeout, None);
+        }
+        assert_eq!(sampler.list(&TraceQuery::default()).len(), 2);
+        assert!(sampler
+            .list(&TraceQuery {
+                model: Some("model".to_string()),
+                ..TraceQuery::default()
+            })
+            .is_empty());
+    }
+
+    #[test]
+    fn test_traces_survive_restart() {
+        let path = temp_trace_file();
+        let sampler = TraceSampler::new(&settings(), Some(path.clone()));
+        for _ in 0..6 {
+            let ctx = RequestContext::new("model".to_string());
+            sampler.finish(&ctx, RequestStatus::Failed, None);
+        }
+        let newest = sampler.list(&TraceQuery::default())[0].request_id.clone();
+        // 释放采样器时等待写入线程落盘
+        drop(sampler);
+
+        let restored = TraceSampler::new(&settings(), Some(path.clone()));
+        let traces = restored.list(&TraceQuery::default());
+        assert_eq!(traces.len(), 2);
+        assert_eq!(traces[0].request_id, newest);
+
+        restored.clear().unwrap();
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_sample_point_is_stable() {
+        let point = sample_point("req-1");
+        assert_eq!(point, sample_point("req-1"));
+        assert!((0.0..1.0).contains(&point));
+    }
+}
@@ -0,0 +1,264 @@
+//! 匿名用量统计（需用户主动开启）
+//!
+//! 开启后仅在本地累计粗粒度计数，供用户自行决定是否导出分享给维护者：
+//! - 各 Provider 类型的请求数（仅记录内置 Provider 类型，不含自定义 Provider ID）
+//! - 功能使用次数（功能名仅允许小写字母、数字、`_`、`.`、`-`）
+//!
+//! 不记录请求内容、模型名、凭证、IP 或任何用户/设备标识。计数保存在日志目录下的
+//! `usage_analytics.json`；导出摘要时为每个计数加入尺度为 `1/epsilon` 的拉普拉斯噪声。
+
+use chrono::{DateTime, Utc};
+use lime_core::config::UsageAnalyticsSettings;
+use lime_core::ProviderType;
+use parking_lot::RwLock;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// 用量统计文件名
+pub const USAGE_ANALYTICS_FILE_NAME: &str = "usage_analytics.json";
+
+/// 累计多少次更新后写入文件
+const FLUSH_EVERY: u32 = 50;
+
+/// 功能名最大长度
+const MAX_FEATURE_NAME_LEN: usize = 64;
+
+/// 本地累计的用量计数
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageCounters {
+    /// 开始累计的时间
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    /// 各 Provider 类型的请求数
+    #[serde(default)]
+    pub requests_by_provider: BTreeMap<String, u64>,
+    /// 功能使用次数
+    #[serde(default)]
+    pub features: BTreeMap<String, u64>,
+}
+
+/// 导出的用量摘要（计数已加噪）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageAnalyticsSummary {
+    pub since: Option<DateTime<Utc>>,
+    pub generated_at: DateTime<Utc>,
+    /// 加噪使用的隐私预算
+    pub epsilon: f64,
+    pub requests_by_provider: BTreeMap<String, u64>,
+    pub features: BTreeMap<String, u64>,
+}
+
+/// 匿名用量统计
+pub struct UsageAnalytics {
+    settings: RwLock<UsageAnalyticsSettings>,
+    counters: RwLock<UsageCounters>,
+    file: Option<PathBuf>,
+    /// 尚未写入文件的更新次数
+    pending: AtomicU32,
+}
+
+impl UsageAnalytics {
+    /// 创建统计器，计数持久化到 `file`（为空时仅保留在内存）
+    pub fn new(settings: &UsageAnalyticsSettings, file: Option<PathBuf>) -> Self {
+        let counters = file
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            settings: RwLock::new(settings.clone()),
+            counters: RwLock::new(counters),
+            file,
+            pending: AtomicU32::new(0),
+        }
+    }
+
+    /// 使用日志目录下的默认文件创建统计器
+    pub fn with_default_file(settings: &UsageAnalyticsSettings) -> Self {
+        let file = lime_core::app_paths::resolve_logs_dir()
+            .map(|dir| dir.join(USAGE_ANALYTICS_FILE_NAME))
+            .map_err(|e| tracing::warn!("[USAGE] 无法定位用量统计目录，仅保留在内存: {}", e))
+            .ok();
+        Self::new(settings, file)
+    }
+
+    /// 热更新配置；关闭时写出未保存的计数
+    pub fn reload(&self, settings: &UsageAnalyticsSettings) {
+        *self.settings.write() = settings.clone();
+        if !settings.enabled {
+            self.flush();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.read().enabled
+    }
+
+    /// 记录一次请求
+    pub fn record_request(&self, provider: Option<ProviderType>) {
+        let key = provider.map_or_else(|| "unknown".to_string(), |p| p.to_string());
+        self.increment(|counters| &mut counters.requests_by_provider, key);
+    }
+
+    /// 记录一次功能使用，功能名不合法时忽略并返回 false
+    pub fn record_feature(&self, feature: &str) -> bool {
+        if !is_valid_feature_name(feature) {
+            return false;
+        }
+        self.increment(|counters| &mut counters.features, feature.to_string());
+        true
+    }
+
+    /// 本地原始计数（仅供本机查看）
+    pub fn counters(&self) -> UsageCounters {
+        self.counters.read().clone()
+    }
+
+    /// 导出加噪后的摘要
+    pub fn export_summary(&self) -> UsageAnalyticsSummary {
+        self.flush();
+        let epsilon = self.settings.read().epsilon;
+        let counters = self.counters.read();
+        let mut rng = rand::thread_rng();
+        let mut noisy = |counts: &BTreeMap<String, u64>| -> BTreeMap<String, u64> {
+            counts
+                .iter()
+                .map(|(key, &count)| (key.clone(), add_laplace_noise(count, epsilon, &mut rng)))
+                .filter(|(_, count)| *count > 0)
+                .collect()
+        };
+        UsageAnalyticsSummary {
+            since: counters.since,
+            generated_at: Utc::now(),
+            epsilon,
+            requests_by_provider: noisy(&counters.requests_by_provider),
+            features: noisy(&counters.features),
+        }
+    }
+
+    /// 清空内存与文件中的计数
+    pub fn clear(&self) -> Result<(), String> {
+        *self.counters.write() = UsageCounters::default();
+        self.pending.store(0, Ordering::Relaxed);
+        if let Some(path) = &self.file {
+            if path.exists() {
+                fs::remove_file(path)
+                    .map_err(|e| format!("删除用量统计文件失败 {}: {e}", path.display()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// 写出未保存的计数
+    pub fn flush(&self) {
+        if self.pending.swap(0, Ordering::Relaxed) == 0 {
+            return;
+        }
+        let Some(path) = &self.file else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(&*self.counters.read())
+            .map_err(std::io::Error::from)
+            .and_then(|content| {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, content)
+            });
+        if let Err(e) = result {
+            tracing::warn!("[USAGE] 写入用量统计失败 {}: {}", path.display(), e);
+        }
+    }
+
+    fn increment(
+        &self,
+        select: impl FnOnce(&mut UsageCounters) -> &mut BTreeMap<String, u64>,
+        key: String,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        {
+            let mut counters = self.counters.write();
+            counters.since.get_or_insert_with(Utc::now);
+            *select(&mut counters).entry(key).or_insert(0) += 1;
+        }
+        if self.pending.fetch_add(1, Ordering::Relaxed) + 1 >= FLUSH_EVERY {
+            self.flush();
+        }
+    }
+}
+
+fn is_valid_feature_name(feature: &str) -> bool {
+    !feature.is_empty()
+        && feature.len() <= MAX_FEATURE_NAME_LEN
+        && feature
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '.' | '-'))
+}
+
+/// 为计数加入拉普拉斯噪声（敏感度 1），结果取整并截断为非负
+fn add_laplace_noise(count: u64, epsilon: f64, rng: &mut impl Rng) -> u64 {
+    let scale = 1.0 / epsilon.max(f64::MIN_POSITIVE);
+    let u: f64 = rng.gen_range(-0.5..0.5);
+    let noise = -scale * u.signum() * (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE).ln();
+    (count as f64 + noise).round().max(0.0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled(epsilon: f64) -> UsageAnalyticsSettings {
+        UsageAnalyticsSettings {
+            enabled: true,
+            epsilon,
+        }
+    }
+
+    #[test]
+    fn test_records_only_when_enabled() {
+        let analytics = UsageAnalytics::new(&UsageAnalyticsSettings::default(), None);
+        analytics.record_request(Some(ProviderType::Claude));
+        assert_eq!(analytics.counters(), UsageCounters::default());
+
+        analytics.reload(&enabled(1.0));
+        analytics.record_request(Some(ProviderType::Claude));
+        analytics.record_request(None);
+        assert!(analytics.record_feature("stream"));
+        assert!(!analytics.record_feature("/home/alice"));
+
+        let counters = analytics.counters();
+        assert!(counters.since.is_some());
+        assert_eq!(counters.requests_by_provider.get("claude"), Some(&1));
+        assert_eq!(counters.requests_by_provider.get("unknown"), Some(&1));
+        assert_eq!(counters.features.len(), 1);
+    }
+
+    #[test]
+    fn test_summary_noise_and_persistence() {
+        let file = std::env::temp_dir()
+            .join(format!("lime-usage-test-{}", uuid::Uuid::new_v4()))
+            .join(USAGE_ANALYTICS_FILE_NAME);
+        let analytics = UsageAnalytics::new(&enabled(1e9), Some(file.clone()));
+        for _ in 0..3 {
+            analytics.record_feature("virtual_model");
+        }
+
+        // 隐私预算极大时噪声可忽略
+        let summary = analytics.export_summary();
+        assert_eq!(summary.features.get("virtual_model"), Some(&3));
+
+        let restored = UsageAnalytics::new(&enabled(1.0), Some(file.clone()));
+        assert_eq!(restored.counters().features.get("virtual_model"), Some(&3));
+
+        restored.clear().unwrap();
+        assert!(!file.exists());
+        assert_eq!(restored.counters(), UsageCounters::default());
+        let _ = fs::remove_dir_all(file.parent().unwrap());
+    }
+}
@@ -5,17 +5,25 @@
 
 #![allow(dead_code)]
 
+use crate::elicitation::{
+    ElicitationBroker, McpElicitationAction, McpElicitationRequestPayload,
+    McpElicitationResolvedPayload, ELICITATION_REQUEST_EVENT, ELICITATION_RESOLVED_EVENT,
+};
 use lime_core::DynEmitter;
 use rmcp::{
     model::{
-        ClientCapabilities, ClientInfo, Implementation, LoggingMessageNotification,
-        LoggingMessageNotificationMethod, LoggingMessageNotificationParam, ProgressNotification,
-        ProgressNotificationMethod, ProgressNotificationParam, ProtocolVersion, ServerNotification,
+        ClientCapabilities, ClientInfo, CreateElicitationRequestParam, CreateElicitationResult,
+        ElicitationAction, Implementation, LoggingMessageNotification,
+        LoggingMessageNotificationMethod, LoggingMessageNotificationParam, NumberOrString,
+        ProgressNotification, ProgressNotificationMethod, ProgressNotificationParam, ProgressToken,
+        ProtocolVersion, ServerNotification,
     },
-    service::NotificationContext,
+    service::{NotificationContext, RequestContext},
     ClientHandler, RoleClient,
 };
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, Mutex};
 use tracing::{debug, info, warn};
 
@@ -38,11 +46,54 @@ pub struct McpLogMessagePayload {
     pub data: serde_json::Value,
 }
 
+/// 工具增量输出事件 Payload
+///
+/// 长耗时工具执行期间，服务器通过进度通知的 message 或携带 progressToken 的
+/// 日志消息推送部分结果，这里按调用维度转换为有序的增量片段。
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct McpToolOutputPayload {
+    pub server_name: String,
+    pub tool_name: String,
+    pub progress_token: String,
+    pub seq: u64,
+    pub delta: String,
+    pub progress: Option<f64>,
+    pub total: Option<f64>,
+    pub done: bool,
+}
+
+/// 进行中的工具调用输出流状态
+#[derive(Debug)]
+struct ToolOutputStream {
+    tool_name: String,
+    seq: u64,
+    last_message: Option<String>,
+}
+
+/// 登记前到达的部分结果的保留时间
+const EARLY_TOOL_OUTPUT_TTL: Duration = Duration::from_secs(10);
+/// 每个 progressToken 最多缓存的部分结果条数
+const MAX_EARLY_TOOL_OUTPUTS: usize = 64;
+
+/// 登记前到达的部分结果
+///
+/// progressToken 由 rmcp 在发送请求时生成，请求发出后才能登记输出流，
+/// 服务器在此之前推送的部分结果先缓存，登记时按顺序补发。
+#[derive(Debug)]
+struct EarlyToolOutput {
+    received_at: Instant,
+    chunks: Vec<(String, Option<f64>, Option<f64>)>,
+}
+
 /// Lime MCP 客户端处理器
 pub struct LimeMcpClient {
     emitter: Option<DynEmitter>,
     server_name: String,
     notification_handlers: Arc<Mutex<Vec<mpsc::Sender<ServerNotification>>>>,
+    tool_streams: Arc<std::sync::Mutex<HashMap<String, ToolOutputStream>>>,
+    early_tool_outputs: Arc<std::sync::Mutex<HashMap<String, EarlyToolOutput>>>,
+    /// elicitation 请求中转，未设置时不声明 elicitation 能力
+    elicitation: Option<Arc<ElicitationBroker>>,
 }
 
 impl LimeMcpClient {
@@ -51,9 +102,18 @@ impl LimeMcpClient {
             emitter,
             server_name,
             notification_handlers: Arc::new(Mutex::new(Vec::new())),
+            tool_streams: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            early_tool_outputs: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            elicitation: None,
         }
     }
 
+    /// 启用 elicitation，由 broker 转发用户输入
+    pub fn with_elicitation(mut self, broker: Arc<ElicitationBroker>) -> Self {
+        self.elicitation = Some(broker);
+        self
+    }
+
     pub fn notification_handlers(&self) -> Arc<Mutex<Vec<mpsc::Sender<ServerNotification>>>> {
         self.notification_handlers.clone()
     }
@@ -64,6 +124,123 @@ impl LimeMcpClient {
         rx
     }
 
+    /// 登记一次工具调用的 progressToken，后续关联通知转换为增量输出
+    ///
+    /// 登记前已到达的部分结果会按顺序补发。
+    pub fn begin_tool_stream(&self, progress_token: &ProgressToken, tool_name: &str) {
+        let key = progress_token_key(progress_token);
+        if let Ok(mut streams) = self.tool_streams.lock() {
+            streams.insert(
+                key.clone(),
+                ToolOutputStream {
+                    tool_name: tool_name.to_string(),
+                    seq: 0,
+                    last_message: None,
+                },
+            );
+        }
+
+        let early = match self.early_tool_outputs.lock() {
+            Ok(mut early) => early.remove(&key),
+            Err(_) => None,
+        };
+        for (delta, progress, total) in early.map(|e| e.chunks).unwrap_or_default() {
+            self.push_tool_output(&key, &delta, progress, total);
+        }
+    }
+
+    /// 结束工具调用的输出流；若期间产生过增量输出，则发送 done 事件
+    pub fn finish_tool_stream(&self, progress_token: &ProgressToken) {
+        let key = progress_token_key(progress_token);
+        let stream = match self.tool_streams.lock() {
+            Ok(mut streams) => streams.remove(&key),
+            Err(_) => None,
+        };
+        let Some(stream) = stream else {
+            return;
+        };
+        if stream.seq == 0 {
+            return;
+        }
+
+        let payload = McpToolOutputPayload {
+            server_name: self.server_name.clone(),
+            tool_name: stream.tool_name,
+            progress_token: key,
+            seq: stream.seq,
+            delta: String::new(),
+            progress: None,
+            total: None,
+            done: true,
+        };
+        self.emit_event("mcp:tool_output", &payload);
+    }
+
+    /// 将部分结果转换为增量输出事件，未登记的 token 返回 None
+    fn push_tool_output(
+        &self,
+        key: &str,
+        delta: &str,
+        progress: Option<f64>,
+        total: Option<f64>,
+    ) -> Option<McpToolOutputPayload> {
+        if delta.is_empty() {
+            return None;
+        }
+
+        let mut streams = self.tool_streams.lock().ok()?;
+        let Some(stream) = streams.get_mut(key) else {
+            drop(streams);
+            self.buffer_early_output(key, delta, progress, total);
+            return None;
+        };
+        // 进度 message 常被服务器重复发送，相同内容不重复输出
+        if stream.last_message.as_deref() == Some(delta) {
+            return None;
+        }
+        stream.last_message = Some(delta.to_string());
+        stream.seq += 1;
+
+        let payload = McpToolOutputPayload {
+            server_name: self.server_name.clone(),
+            tool_name: stream.tool_name.clone(),
+            progress_token: key.to_string(),
+            seq: stream.seq,
+            delta: delta.to_string(),
+            progress,
+            total,
+            done: false,
+        };
+        drop(streams);
+
+        self.emit_event("mcp:tool_output", &payload);
+        Some(payload)
+    }
+
+    /// 缓存尚未登记的 progressToken 的部分结果，顺带清理过期的缓存
+    fn buffer_early_output(
+        &self,
+        key: &str,
+        delta: &str,
+        progress: Option<f64>,
+        total: Option<f64>,
+    ) {
+        let Ok(mut early) = self.early_tool_outputs.lock() else {
+            return;
+        };
+        let now = Instant::now();
+        early.retain(|_, output| now.duration_since(output.received_at) < EARLY_TOOL_OUTPUT_TTL);
+        let output = early
+            .entry(key.to_string())
+            .or_insert_with(|| EarlyToolOutput {
+                received_at: now,
+                chunks: Vec::new(),
+            });
+        if output.chunks.len() < MAX_EARLY_TOOL_OUTPUTS {
+            output.chunks.push((delta.to_string(), progress, total));
+        }
+    }
+
     /// 发送事件（通过 DynEmitter）
     fn emit_event<T: serde::Serialize>(&self, event: &str, payload: &T) {
         if let Some(ref emitter) = self.emitter {
@@ -83,9 +260,17 @@ impl LimeMcpClient {
 
 impl ClientHandler for LimeMcpClient {
     fn get_info(&self) -> ClientInfo {
+        let capabilities = if self.elicitation.is_some() {
+            ClientCapabilities::builder()
+                .enable_sampling()
+                .enable_elicitation()
+                .build()
+        } else {
+            ClientCapabilities::builder().enable_sampling().build()
+        };
         ClientInfo {
             protocol_version: ProtocolVersion::V_2025_03_26,
-            capabilities: ClientCapabilities::builder().enable_sampling().build(),
+            capabilities,
             client_info: Implementation {
                 name: "lime".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
@@ -96,6 +281,63 @@ impl ClientHandler for LimeMcpClient {
         }
     }
 
+    async fn create_elicitation(
+        &self,
+        request: CreateElicitationRequestParam,
+        context: RequestContext<RoleClient>,
+    ) -> Result<CreateElicitationResult, rmcp::ErrorData> {
+        let Some(broker) = self.elicitation.clone() else {
+            return Ok(CreateElicitationResult {
+                action: ElicitationAction::Decline,
+                content: None,
+            });
+        };
+
+        let schema = serde_json::to_value(&request.requested_schema).unwrap_or_default();
+        let (request_id, rx) = broker.register(schema.clone());
+        info!(
+            server_name = %self.server_name,
+            request_id = %request_id,
+            "MCP 服务器请求用户输入"
+        );
+        self.emit_event(
+            ELICITATION_REQUEST_EVENT,
+            &McpElicitationRequestPayload {
+                request_id: request_id.clone(),
+                server_name: self.server_name.clone(),
+                message: request.message,
+                requested_schema: schema,
+                timeout_secs: broker.timeout().as_secs(),
+            },
+        );
+
+        let (response, resolution) = broker.wait(&request_id, rx, context.ct.cancelled()).await;
+        self.emit_event(
+            ELICITATION_RESOLVED_EVENT,
+            &McpElicitationResolvedPayload {
+                request_id,
+                server_name: self.server_name.clone(),
+                resolution,
+            },
+        );
+
+        let result = match response.action {
+            McpElicitationAction::Accept => CreateElicitationResult {
+                action: ElicitationAction::Accept,
+                content: response.content,
+            },
+            McpElicitationAction::Decline => CreateElicitationResult {
+                action: ElicitationAction::Decline,
+                content: None,
+            },
+            McpElicitationAction::Cancel => CreateElicitationResult {
+                action: ElicitationAction::Cancel,
+                content: None,
+            },
+        };
+        Ok(result)
+    }
+
     async fn on_progress(
         &self,
         params: ProgressNotificationParam,
@@ -114,10 +356,19 @@ impl ClientHandler for LimeMcpClient {
             progress_token: format!("{:?}", params.progress_token),
             progress: params.progress,
             total: params.total,
-            message: None,
+            message: params.message.clone(),
         };
         self.emit_event("mcp:progress", &payload);
 
+        if let Some(message) = params.message.as_deref() {
+            self.push_tool_output(
+                &progress_token_key(&params.progress_token),
+                message,
+                Some(params.progress),
+                params.total,
+            );
+        }
+
         let notification = ServerNotification::ProgressNotification(ProgressNotification {
             params: params.clone(),
             method: ProgressNotificationMethod,
@@ -162,6 +413,10 @@ impl ClientHandler for LimeMcpClient {
         };
         self.emit_event("mcp:log_message", &payload);
 
+        if let Some((key, delta)) = extract_partial_output(&params.data) {
+            self.push_tool_output(&key, &delta, None, None);
+        }
+
         let notification =
             ServerNotification::LoggingMessageNotification(LoggingMessageNotification {
                 params: params.clone(),
@@ -176,6 +431,45 @@ impl ClientHandler for LimeMcpClient {
     }
 }
 
+/// progressToken 的规范化字符串形式，用于关联通知与工具调用
+fn progress_token_key(token: &ProgressToken) -> String {
+    match &token.0 {
+        NumberOrString::Number(n) => n.to_string(),
+        NumberOrString::String(s) => s.to_string(),
+    }
+}
+
+/// 从日志消息中提取部分结果
+///
+/// 约定格式：`{"progressToken": <token>, "content": [{"type": "text", "text": "..."}]}`，
+/// 也接受直接携带 `text` 字段的简化形式。
+fn extract_partial_output(data: &serde_json::Value) -> Option<(String, String)> {
+    let obj = data.as_object()?;
+    let key = match obj.get("progressToken")? {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+
+    let text = if let Some(text) = obj.get("text").and_then(|v| v.as_str()) {
+        text.to_string()
+    } else {
+        obj.get("content")?
+            .as_array()?
+            .iter()
+            .filter(|item| item.get("type").and_then(|v| v.as_str()) == Some("text"))
+            .filter_map(|item| item.get("text").and_then(|v| v.as_str()))
+            .collect::<Vec<_>>()
+            .join("")
+    };
+
+    if text.is_empty() {
+        None
+    } else {
+        Some((key, text))
+    }
+}
+
 /// MCP 客户端包装器
 pub struct McpClientWrapper {
     pub server_name: String,
@@ -205,6 +499,11 @@ impl McpClientWrapper {
         }
     }
 
+    /// 是否为远程服务器（无本地子进程）
+    pub fn is_remote(&self) -> bool {
+        self.config.is_remote()
+    }
+
     pub fn handler(&self) -> Arc<LimeMcpClient> {
         self.client_handler.clone()
     }
@@ -258,16 +557,26 @@ mod tests {
         assert_eq!(info.client_info.name, "lime");
         assert_eq!(info.client_info.title, Some("Lime MCP Client".to_string()));
         assert_eq!(info.protocol_version, ProtocolVersion::V_2025_03_26);
+        assert!(info.capabilities.elicitation.is_none());
+
+        let client = LimeMcpClient::new("test-server".to_string(), None)
+            .with_elicitation(Arc::new(ElicitationBroker::default()));
+        assert!(client.get_info().capabilities.elicitation.is_some());
     }
 
     #[test]
     fn test_client_wrapper_creation() {
         let config = super::super::types::McpServerConfig {
+            transport: super::super::types::McpTransportKind::Stdio,
             command: "test-command".to_string(),
             args: vec!["--arg1".to_string()],
             env: std::collections::HashMap::new(),
             cwd: None,
             timeout: 30,
+            interpreter: None,
+            url: None,
+            headers: std::collections::HashMap::new(),
+            reconnect: Default::default(),
         };
 
         let wrapper = McpClientWrapper::new("test-server".to_string(), config, None);
@@ -290,4 +599,78 @@ mod tests {
 
         assert!(rx.try_recv().is_err());
     }
+
+    #[test]
+    fn test_tool_output_stream_sequencing() {
+        let client = LimeMcpClient::new("test-server".to_string(), None);
+        let token = ProgressToken(NumberOrString::Number(7));
+        let key = progress_token_key(&token);
+
+        // 未登记的 token 不产生输出
+        let other = progress_token_key(&ProgressToken(NumberOrString::Number(8)));
+        assert!(client
+            .push_tool_output(&other, "ignored", None, None)
+            .is_none());
+
+        client.begin_tool_stream(&token, "search");
+        let first = client
+            .push_tool_output(&key, "line 1", Some(1.0), Some(3.0))
+            .unwrap();
+        assert_eq!(first.seq, 1);
+        assert_eq!(first.tool_name, "search");
+        assert!(!first.done);
+
+        // 重复的进度消息不会产生新的增量
+        assert!(client
+            .push_tool_output(&key, "line 1", None, None)
+            .is_none());
+        let second = client.push_tool_output(&key, "line 2", None, None).unwrap();
+        assert_eq!(second.seq, 2);
+
+        client.finish_tool_stream(&token);
+        assert!(client
+            .push_tool_output(&key, "line 3", None, None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_tool_output_before_registration_is_replayed() {
+        let client = LimeMcpClient::new("test-server".to_string(), None);
+        let token = ProgressToken(NumberOrString::Number(9));
+        let key = progress_token_key(&token);
+
+        // 请求发出后、登记前服务器已推送部分结果
+        assert!(client.push_tool_output(&key, "early", None, None).is_none());
+
+        client.begin_tool_stream(&token, "search");
+        let next = client.push_tool_output(&key, "late", None, None).unwrap();
+        assert_eq!(next.seq, 2, "登记前的输出应先补发为第 1 条");
+        assert!(client.early_tool_outputs.lock().unwrap().is_empty());
+        client.finish_tool_stream(&token);
+    }
+
+    #[test]
+    fn test_extract_partial_output() {
+        let data = serde_json::json!({
+            "progressToken": 3,
+            "content": [
+                {"type": "text", "text": "hello "},
+                {"type": "image", "data": "..."},
+                {"type": "text", "text": "world"}
+            ]
+        });
+        assert_eq!(
+            extract_partial_output(&data),
+            Some(("3".to_string(), "hello world".to_string()))
+        );
+
+        let data = serde_json::json!({"progressToken": "abc", "text": "chunk"});
+        assert_eq!(
+            extract_partial_output(&data),
+            Some(("abc".to_string(), "chunk".to_string()))
+        );
+
+        assert!(extract_partial_output(&serde_json::json!("plain log")).is_none());
+        assert!(extract_partial_output(&serde_json::json!({"text": "no token"})).is_none());
+    }
 }
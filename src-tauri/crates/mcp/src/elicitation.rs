@@ -0,0 +1,305 @@
+//! MCP Elicitation（服务器请求用户输入）
+//!
+//! 服务器在工具调用过程中发起 `elicitation/create` 请求时：
+//! 1. 客户端登记一个待处理请求，发送 `mcp:elicitation_request` 事件（含 JSON Schema）
+//! 2. 前端收集用户输入后通过命令调用 `ElicitationBroker::respond`
+//! 3. 客户端将结果返回服务器；超时或服务器取消请求时按 cancel 处理，
+//!    并发送 `mcp:elicitation_resolved` 事件通知前端关闭表单
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::oneshot;
+
+use crate::types::McpError;
+
+/// elicitation 请求事件
+pub const ELICITATION_REQUEST_EVENT: &str = "mcp:elicitation_request";
+
+/// elicitation 结束事件（已回复、超时或被取消）
+pub const ELICITATION_RESOLVED_EVENT: &str = "mcp:elicitation_resolved";
+
+/// 默认等待用户输入的时间
+pub const DEFAULT_ELICITATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// elicitation 中转的共享状态（独立于 `McpManagerState`，避免与工具调用争用锁）
+pub type McpElicitationState = Arc<ElicitationBroker>;
+
+/// 用户操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum McpElicitationAction {
+    /// 提交输入
+    Accept,
+    /// 明确拒绝
+    Decline,
+    /// 关闭而未作选择
+    Cancel,
+}
+
+/// 用户回复
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpElicitationResponse {
+    pub action: McpElicitationAction,
+    /// accept 时为符合 schema 的对象
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<Value>,
+}
+
+impl McpElicitationResponse {
+    pub fn cancel() -> Self {
+        Self {
+            action: McpElicitationAction::Cancel,
+            content: None,
+        }
+    }
+}
+
+/// elicitation 请求事件 Payload
+#[derive(Debug, Clone, Serialize)]
+pub struct McpElicitationRequestPayload {
+    pub request_id: String,
+    pub server_name: String,
+    pub message: String,
+    pub requested_schema: Value,
+    pub timeout_secs: u64,
+}
+
+/// elicitation 结束原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpElicitationResolution {
+    Answered,
+    TimedOut,
+    Cancelled,
+}
+
+/// elicitation 结束事件 Payload
+#[derive(Debug, Clone, Serialize)]
+pub struct McpElicitationResolvedPayload {
+    pub request_id: String,
+    pub server_name: String,
+    pub resolution: McpElicitationResolution,
+}
+
+/// 待处理请求
+struct PendingElicitation {
+    schema: Value,
+    tx: oneshot::Sender<McpElicitationResponse>,
+}
+
+/// elicitation 请求中转
+///
+/// 由 `McpClientManager` 持有并共享给所有客户端处理器，命令层通过请求 ID 回复。
+pub struct ElicitationBroker {
+    pending: Mutex<HashMap<String, PendingElicitation>>,
+    next_id: AtomicU64,
+    timeout: Duration,
+}
+
+impl Default for ElicitationBroker {
+    fn default() -> Self {
+        Self::new(DEFAULT_ELICITATION_TIMEOUT)
+    }
+}
+
+impl ElicitationBroker {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            next_id: AtomicU64::new(1),
+            timeout,
+        }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// 登记请求，返回请求 ID 与回复接收端
+    pub fn register(&self, schema: Value) -> (String, oneshot::Receiver<McpElicitationResponse>) {
+        let id = format!("elicit-{}", self.next_id.fetch_add(1, Ordering::Relaxed));
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(id.clone(), PendingElicitation { schema, tx });
+        }
+        (id, rx)
+    }
+
+    /// 当前待处理的请求 ID
+    pub fn pending_ids(&self) -> Vec<String> {
+        self.pending
+            .lock()
+            .map(|pending| pending.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// 回复请求
+    ///
+    /// accept 的内容不符合 schema 时返回错误且请求保持待处理，前端可修正后重新提交。
+    pub fn respond(
+        &self,
+        request_id: &str,
+        response: McpElicitationResponse,
+    ) -> Result<(), McpError> {
+        let mut pending = self
+            .pending
+            .lock()
+            .map_err(|e| McpError::ProtocolError(e.to_string()))?;
+        let entry = pending.get(request_id).ok_or_else(|| {
+            McpError::ProtocolError(format!("elicitation 请求不存在或已结束: {request_id}"))
+        })?;
+        if response.action == McpElicitationAction::Accept {
+            validate_content(&entry.schema, response.content.as_ref())
+                .map_err(McpError::ProtocolError)?;
+        }
+
+        if let Some(entry) = pending.remove(request_id) {
+            // 接收端已丢弃说明请求刚好超时或被取消，忽略即可
+            let _ = entry.tx.send(response);
+        }
+        Ok(())
+    }
+
+    /// 等待用户回复，超时或 `cancelled` 完成时按 cancel 处理
+    pub async fn wait(
+        &self,
+        request_id: &str,
+        rx: oneshot::Receiver<McpElicitationResponse>,
+        cancelled: impl Future<Output = ()>,
+    ) -> (McpElicitationResponse, McpElicitationResolution) {
+        let outcome = tokio::select! {
+            result = tokio::time::timeout(self.timeout, rx) => match result {
+                Ok(Ok(response)) => (response, McpElicitationResolution::Answered),
+                Ok(Err(_)) => (McpElicitationResponse::cancel(), McpElicitationResolution::Cancelled),
+                Err(_) => (McpElicitationResponse::cancel(), McpElicitationResolution::TimedOut),
+            },
+            _ = cancelled => (McpElicitationResponse::cancel(), McpElicitationResolution::Cancelled),
+        };
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(request_id);
+        }
+        outcome
+    }
+}
+
+/// 按 elicitation schema 校验提交内容
+///
+/// MCP 规定 requestedSchema 为只含基本类型属性的扁平对象，这里校验必填字段、
+/// 基本类型与枚举取值。
+pub fn validate_content(schema: &Value, content: Option<&Value>) -> Result<(), String> {
+    let content = content
+        .and_then(Value::as_object)
+        .ok_or_else(|| "accept 时 content 必须为对象".to_string())?;
+
+    let required = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+    for field in required {
+        if content.get(field).is_none_or(Value::is_null) {
+            return Err(format!("缺少必填字段: {field}"));
+        }
+    }
+
+    let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+        return Ok(());
+    };
+    for (field, value) in content {
+        let Some(property) = properties.get(field) else {
+            continue;
+        };
+        let type_ok = match property.get("type").and_then(Value::as_str) {
+            Some("string") => value.is_string(),
+            Some("number") => value.is_number(),
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("boolean") => value.is_boolean(),
+            _ => true,
+        };
+        if !type_ok {
+            return Err(format!("字段 {field} 类型不匹配"));
+        }
+        if let Some(options) = property.get("enum").and_then(Value::as_array) {
+            if !options.contains(value) {
+                return Err(format!("字段 {field} 的取值不在可选范围内"));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "age": { "type": "integer" },
+                "plan": { "type": "string", "enum": ["free", "pro"] }
+            },
+            "required": ["name"]
+        })
+    }
+
+    #[test]
+    fn test_validate_content() {
+        let ok = serde_json::json!({ "name": "lime", "age": 3, "plan": "pro" });
+        assert!(validate_content(&schema(), Some(&ok)).is_ok());
+
+        let missing = serde_json::json!({ "age": 3 });
+        assert!(validate_content(&schema(), Some(&missing)).is_err());
+
+        let wrong_type = serde_json::json!({ "name": "lime", "age": "3" });
+        assert!(validate_content(&schema(), Some(&wrong_type)).is_err());
+
+        let bad_enum = serde_json::json!({ "name": "lime", "plan": "team" });
+        assert!(validate_content(&schema(), Some(&bad_enum)).is_err());
+
+        assert!(validate_content(&schema(), None).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_broker_respond_and_timeout() {
+        let broker = ElicitationBroker::new(Duration::from_millis(50));
+
+        let (id, rx) = broker.register(schema());
+        assert!(broker
+            .respond(
+                &id,
+                McpElicitationResponse {
+                    action: McpElicitationAction::Accept,
+                    content: Some(serde_json::json!({})),
+                },
+            )
+            .is_err());
+        let answer = McpElicitationResponse {
+            action: McpElicitationAction::Accept,
+            content: Some(serde_json::json!({ "name": "lime" })),
+        };
+        broker.respond(&id, answer.clone()).unwrap();
+        let (response, resolution) = broker.wait(&id, rx, std::future::pending()).await;
+        assert_eq!(response, answer);
+        assert_eq!(resolution, McpElicitationResolution::Answered);
+        assert!(broker.respond(&id, answer).is_err());
+
+        let (id, rx) = broker.register(schema());
+        let (response, resolution) = broker.wait(&id, rx, std::future::pending()).await;
+        assert_eq!(response.action, McpElicitationAction::Cancel);
+        assert_eq!(resolution, McpElicitationResolution::TimedOut);
+        assert!(broker.pending_ids().is_empty());
+
+        let (id, rx) = broker.register(schema());
+        let (_, resolution) = broker.wait(&id, rx, async {}).await;
+        assert_eq!(resolution, McpElicitationResolution::Cancelled);
+    }
+}
@@ -4,17 +4,34 @@
 //! 使用 DynEmitter 替代 Tauri AppHandle 进行事件发射，实现与 Tauri 的解耦。
 
 pub mod client;
+pub mod elicitation;
+pub mod log_capture;
 pub mod manager;
+pub mod process_registry;
+pub mod remote_transport;
+pub mod runtime_env;
 pub mod tool_converter;
+pub mod tool_diff;
 pub mod types;
 
-pub use client::{LimeMcpClient, McpClientWrapper};
+pub use client::{LimeMcpClient, McpClientWrapper, McpToolOutputPayload};
+pub use elicitation::{
+    ElicitationBroker, McpElicitationAction, McpElicitationRequestPayload,
+    McpElicitationResolution, McpElicitationResolvedPayload, McpElicitationResponse,
+    McpElicitationState,
+};
+pub use log_capture::{McpLogStore, McpLogWriter};
 pub use manager::McpClientManager;
+pub use process_registry::{McpProcessEntry, McpProcessRegistry, MCP_SESSION_ENV};
+pub use runtime_env::{
+    ExecutionEnvironment, McpEnvironmentDiagnostic, McpEnvironmentStatus, RuntimeKind,
+};
 pub use tool_converter::ToolConverter;
+pub use tool_diff::McpToolsDiff;
 pub use types::{
     McpContent, McpError, McpManagerState, McpPromptArgument, McpPromptDefinition,
-    McpPromptMessage, McpPromptResult, McpResourceContent, McpResourceDefinition,
-    McpServerCapabilities, McpServerConfig, McpServerErrorPayload, McpServerInfo,
-    McpServerStartedPayload, McpServerStoppedPayload, McpToolCall, McpToolDefinition,
-    McpToolResult, McpToolsUpdatedPayload,
+    McpPromptMessage, McpPromptResult, McpReconnectConfig, McpResourceContent,
+    McpResourceDefinition, McpServerCapabilities, McpServerConfig, McpServerErrorPayload,
+    McpServerInfo, McpServerStartedPayload, McpServerStoppedPayload, McpToolCall,
+    McpToolDefinition, McpToolResult, McpToolsUpdatedPayload, McpTransportKind,
 };
@@ -0,0 +1,335 @@
+//! MCP 服务器日志捕获
+//!
+//! 将每个 MCP 子进程的 stderr 按行写入独立的日志文件（`<logs>/mcp/<server>.log`），
+//! 超过大小上限时按 `.log.1`、`.log.2` … 轮转，同时在内存中保留最近若干行，
+//! 用于 `mcp:server_error` 事件和 UI 日志查看器。
+//!
+//! 文件写入通过 [`McpLogStore::spawn_writer`] 交给阻塞线程池批量完成，
+//! 读取 stderr 的异步任务不会被磁盘 IO 阻塞。
+//!
+//! stdout 是 MCP stdio 传输通道，不做捕获。
+
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+
+/// 单个日志文件默认上限（1 MB）
+const DEFAULT_MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// 默认保留的轮转文件数（不含当前文件）
+const DEFAULT_MAX_ROTATED_FILES: usize = 3;
+/// 内存中保留的最近行数
+const RECENT_LINES_CAPACITY: usize = 200;
+/// 错误事件中附带的最近行数
+pub const ERROR_EVENT_RECENT_LINES: usize = 20;
+
+/// MCP 服务器日志存储
+#[derive(Debug)]
+pub struct McpLogStore {
+    dir: PathBuf,
+    max_file_bytes: u64,
+    max_rotated_files: usize,
+    recent: Mutex<HashMap<String, VecDeque<String>>>,
+    write_lock: Mutex<()>,
+}
+
+impl McpLogStore {
+    /// 创建日志存储，`dir` 不存在时会在首次写入时创建
+    pub fn new(dir: PathBuf) -> Self {
+        Self::with_limits(dir, DEFAULT_MAX_FILE_BYTES, DEFAULT_MAX_ROTATED_FILES)
+    }
+
+    /// 自定义轮转参数
+    pub fn with_limits(dir: PathBuf, max_file_bytes: u64, max_rotated_files: usize) -> Self {
+        Self {
+            dir,
+            max_file_bytes: max_file_bytes.max(1),
+            max_rotated_files,
+            recent: Mutex::new(HashMap::new()),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// 日志目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 指定服务器的当前日志文件路径
+    pub fn log_path(&self, server_name: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.log", sanitize_file_name(server_name)))
+    }
+
+    /// 写入一行日志（同步写盘，异步上下文中请使用 [`Self::spawn_writer`]）
+    pub fn append_line(&self, server_name: &str, stream: &str, line: &str) {
+        let entry = self.record_recent(server_name, stream, line);
+        self.write_batch(server_name, &[entry]);
+    }
+
+    /// 为指定服务器启动后台写入任务
+    ///
+    /// 最近行立即记录到内存；文件写入在 `spawn_blocking` 中批量进行，
+    /// 通道暂时为空时才刷新缓冲区。所有 [`McpLogWriter`] 释放后任务结束。
+    pub fn spawn_writer(self: &Arc<Self>, server_name: &str) -> McpLogWriter {
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+        let store = Arc::clone(self);
+        let name = server_name.to_string();
+        tokio::task::spawn_blocking(move || {
+            while let Some(entry) = rx.blocking_recv() {
+                let mut batch = vec![entry];
+                while let Ok(entry) = rx.try_recv() {
+                    batch.push(entry);
+                }
+                store.write_batch(&name, &batch);
+            }
+        });
+        McpLogWriter {
+            store: Arc::clone(self),
+            server_name: server_name.to_string(),
+            tx,
+        }
+    }
+
+    /// 格式化日志行并记录到内存中的最近行
+    fn record_recent(&self, server_name: &str, stream: &str, line: &str) -> String {
+        let line = line.trim_end_matches(['\r', '\n']);
+        let entry = format!(
+            "{} [{stream}] {line}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f")
+        );
+
+        if let Ok(mut recent) = self.recent.lock() {
+            let lines = recent.entry(server_name.to_string()).or_default();
+            if lines.len() >= RECENT_LINES_CAPACITY {
+                lines.pop_front();
+            }
+            lines.push_back(entry.clone());
+        }
+        entry
+    }
+
+    fn write_batch(&self, server_name: &str, entries: &[String]) {
+        let _guard = self.write_lock.lock();
+        if let Err(e) = self.write_entries(server_name, entries) {
+            tracing::warn!(server_name = %server_name, error = %e, "写入 MCP 日志失败");
+        }
+    }
+
+    /// 获取内存中最近的日志行
+    pub fn recent_lines(&self, server_name: &str, limit: usize) -> Vec<String> {
+        let Ok(recent) = self.recent.lock() else {
+            return Vec::new();
+        };
+        recent
+            .get(server_name)
+            .map(|lines| {
+                let skip = lines.len().saturating_sub(limit);
+                lines.iter().skip(skip).cloned().collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 从日志文件读取最后 `limit` 行（必要时跨越轮转文件）
+    pub fn tail(&self, server_name: &str, limit: usize) -> std::io::Result<Vec<String>> {
+        let current = self.log_path(server_name);
+        let mut collected: VecDeque<String> = VecDeque::new();
+
+        for index in 0..=self.max_rotated_files {
+            if collected.len() >= limit {
+                break;
+            }
+            let path = rotated_path(&current, index);
+            if !path.exists() {
+                continue;
+            }
+            let lines: Vec<String> = BufReader::new(File::open(&path)?)
+                .lines()
+                .collect::<Result<_, _>>()?;
+            for line in lines.into_iter().rev() {
+                if collected.len() >= limit {
+                    break;
+                }
+                collected.push_front(line);
+            }
+        }
+
+        Ok(collected.into())
+    }
+
+    fn write_entries(&self, server_name: &str, entries: &[String]) -> std::io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.log_path(server_name);
+        let mut size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let mut writer: Option<BufWriter<File>> = None;
+
+        for entry in entries {
+            let entry_len = entry.len() as u64 + 1;
+            if size > 0 && size + entry_len > self.max_file_bytes {
+                if let Some(mut current) = writer.take() {
+                    current.flush()?;
+                }
+                self.rotate(&path)?;
+                size = 0;
+            }
+            if writer.is_none() {
+                let file = OpenOptions::new().create(true).append(true).open(&path)?;
+                writer = Some(BufWriter::new(file));
+            }
+            if let Some(current) = writer.as_mut() {
+                writeln!(current, "{entry}")?;
+            }
+            size += entry_len;
+        }
+
+        match writer {
+            Some(mut current) => current.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn rotate(&self, current: &Path) -> std::io::Result<()> {
+        if self.max_rotated_files == 0 {
+            return fs::remove_file(current);
+        }
+        let oldest = rotated_path(current, self.max_rotated_files);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for index in (1..self.max_rotated_files).rev() {
+            let from = rotated_path(current, index);
+            if from.exists() {
+                fs::rename(&from, rotated_path(current, index + 1))?;
+            }
+        }
+        fs::rename(current, rotated_path(current, 1))
+    }
+}
+
+/// 单个 MCP 服务器的日志写入端
+#[derive(Debug, Clone)]
+pub struct McpLogWriter {
+    store: Arc<McpLogStore>,
+    server_name: String,
+    tx: mpsc::UnboundedSender<String>,
+}
+
+impl McpLogWriter {
+    /// 记录一行日志，文件写入由后台任务完成
+    pub fn append_line(&self, stream: &str, line: &str) {
+        let entry = self.store.record_recent(&self.server_name, stream, line);
+        let _ = self.tx.send(entry);
+    }
+}
+
+/// 第 `index` 个轮转文件路径（0 为当前文件）
+fn rotated_path(current: &Path, index: usize) -> PathBuf {
+    if index == 0 {
+        return current.to_path_buf();
+    }
+    let mut name = current.as_os_str().to_os_string();
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if sanitized.trim_matches('.').is_empty() {
+        "server".to_string()
+    } else {
+        sanitized
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(tag: &str) -> PathBuf {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        std::env::temp_dir().join(format!("lime-mcp-log-{tag}-{}-{nanos}", std::process::id()))
+    }
+
+    #[test]
+    fn test_append_and_tail() {
+        let dir = temp_dir("tail");
+        let store = McpLogStore::new(dir.clone());
+        for i in 0..5 {
+            store.append_line("my/server", "stderr", &format!("line {i}\n"));
+        }
+
+        assert!(store.log_path("my/server").ends_with("my_server.log"));
+        let tail = store.tail("my/server", 2).unwrap();
+        assert_eq!(tail.len(), 2);
+        assert!(tail[0].ends_with("[stderr] line 3"));
+        assert!(tail[1].ends_with("[stderr] line 4"));
+
+        let recent = store.recent_lines("my/server", 3);
+        assert_eq!(recent.len(), 3);
+        assert!(recent[2].ends_with("line 4"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_spawn_writer_batches_to_file() {
+        let dir = temp_dir("writer");
+        let store = Arc::new(McpLogStore::new(dir.clone()));
+        let writer = store.spawn_writer("srv");
+        for i in 0..10 {
+            writer.append_line("stderr", &format!("line {i}"));
+        }
+        // 最近行同步可见
+        assert!(store.recent_lines("srv", 1)[0].ends_with("line 9"));
+        drop(writer);
+
+        let mut tail = Vec::new();
+        for _ in 0..50 {
+            tail = store.tail("srv", 10).unwrap();
+            if tail.len() == 10 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(tail.len(), 10);
+        assert!(tail[9].ends_with("[stderr] line 9"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_rotation_keeps_limited_files() {
+        let dir = temp_dir("rotate");
+        let store = McpLogStore::with_limits(dir.clone(), 64, 2);
+        for i in 0..20 {
+            store.append_line("srv", "stderr", &format!("message number {i}"));
+        }
+
+        let current = store.log_path("srv");
+        assert!(current.exists());
+        assert!(rotated_path(&current, 1).exists());
+        assert!(rotated_path(&current, 2).exists());
+        assert!(!rotated_path(&current, 3).exists());
+
+        // tail 跨越轮转文件，仍保持时间顺序
+        let tail = store.tail("srv", 3).unwrap();
+        assert!(tail[2].ends_with("message number 19"));
+        assert!(tail[1].ends_with("message number 18"));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -29,17 +29,25 @@
 use lime_core::{tool_calling::ToolSurfaceMetadata, DynEmitter};
 use std::collections::{HashMap, HashSet};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
+use rmcp::service::RunningService;
 use rmcp::transport::TokioChildProcess;
-use rmcp::ServiceExt;
+use rmcp::{RoleClient, ServiceExt};
 
 use crate::client::McpClientWrapper;
+use crate::elicitation::ElicitationBroker;
+use crate::log_capture::{McpLogStore, ERROR_EVENT_RECENT_LINES};
+use crate::process_registry::{McpProcessRegistry, MCP_SESSION_ENV};
+use crate::remote_transport;
+use crate::runtime_env::ExecutionEnvironment;
+use crate::tool_diff::{fingerprint_tools, McpToolsDiff, ToolFingerprints};
 use crate::types::*;
 
 const AUTO_DEFER_TOOL_COUNT_THRESHOLD: usize = 6;
@@ -91,6 +99,14 @@ pub struct McpClientManager {
     /// - Some(tools): 缓存有效
     tool_cache: Arc<RwLock<Option<Vec<McpToolDefinition>>>>,
 
+    /// 最近一次推送给前端的工具指纹快照
+    ///
+    /// 用于计算增量 mcp:tools_updated 事件。
+    emitted_tools: Arc<RwLock<ToolFingerprints>>,
+
+    /// mcp:tools_updated 事件版本号
+    tools_revision: Arc<AtomicU64>,
+
     /// 事件发射器
     ///
     /// 用于向前端发送 MCP 相关事件，如：
@@ -99,6 +115,19 @@ pub struct McpClientManager {
     /// - mcp:server_error
     /// - mcp:tools_updated
     emitter: Option<DynEmitter>,
+
+    /// 子进程 stderr 日志存储
+    ///
+    /// 未设置时 stderr 仅用于启动失败诊断，不落盘。
+    log_store: Option<Arc<McpLogStore>>,
+
+    /// 子进程登记表
+    ///
+    /// 未设置时子进程不带会话标记，应用崩溃后无法回收。
+    process_registry: Option<Arc<McpProcessRegistry>>,
+
+    /// elicitation 请求中转（所有服务器共享）
+    elicitation: Arc<ElicitationBroker>,
 }
 
 impl McpClientManager {
@@ -117,7 +146,12 @@ impl McpClientManager {
         Self {
             clients: Arc::new(RwLock::new(HashMap::new())),
             tool_cache: Arc::new(RwLock::new(None)),
+            emitted_tools: Arc::new(RwLock::new(HashMap::new())),
+            tools_revision: Arc::new(AtomicU64::new(0)),
             emitter,
+            log_store: None,
+            process_registry: None,
+            elicitation: Arc::new(ElicitationBroker::default()),
         }
     }
 
@@ -126,6 +160,39 @@ impl McpClientManager {
         self.emitter = Some(emitter);
     }
 
+    /// 设置 stderr 日志存储
+    pub fn set_log_store(&mut self, log_store: Arc<McpLogStore>) {
+        self.log_store = Some(log_store);
+    }
+
+    /// 设置子进程登记表
+    pub fn set_process_registry(&mut self, registry: Arc<McpProcessRegistry>) {
+        self.process_registry = Some(registry);
+    }
+
+    /// 移除子进程登记（进程已终止或启动失败）
+    fn unregister_process(&self, name: &str) {
+        if let Some(registry) = &self.process_registry {
+            registry.unregister(name);
+        }
+    }
+
+    /// elicitation 请求中转
+    ///
+    /// 工具调用期间管理器锁一直被持有，回复 elicitation 必须绕过管理器锁，
+    /// 因此由调用方单独持有该句柄。
+    pub fn elicitation_broker(&self) -> Arc<ElicitationBroker> {
+        self.elicitation.clone()
+    }
+
+    /// 读取指定服务器日志文件的最后 `limit` 行
+    pub fn tail_server_log(&self, name: &str, limit: usize) -> std::io::Result<Vec<String>> {
+        match &self.log_store {
+            Some(store) => store.tail(name, limit),
+            None => Ok(Vec::new()),
+        }
+    }
+
     // ========================================================================
     // 连接池管理方法
     // ========================================================================
@@ -344,14 +411,67 @@ impl McpClientManager {
             McpServerErrorPayload {
                 server_name: server_name.to_string(),
                 error: error.to_string(),
+                recent_logs: self
+                    .log_store
+                    .as_ref()
+                    .map(|store| store.recent_lines(server_name, ERROR_EVENT_RECENT_LINES))
+                    .unwrap_or_default(),
             },
         );
     }
 
-    /// 发送工具列表更新事件
-    pub fn emit_tools_updated(&self, tools: Vec<McpToolDefinition>) {
-        debug!(tool_count = tools.len(), "工具列表已更新");
-        self.emit_event("mcp:tools_updated", McpToolsUpdatedPayload { tools });
+    /// 发送增量工具列表更新事件
+    ///
+    /// 与上次推送的快照比较，仅发送新增、移除和变更的工具；无变化时不发送。
+    pub async fn emit_tools_updated(&self, tools: &[McpToolDefinition]) -> McpToolsDiff {
+        let mut emitted = self.emitted_tools.write().await;
+        let diff = McpToolsDiff::between(&emitted, tools);
+        if diff.is_empty() {
+            debug!(tool_count = tools.len(), "工具列表无变化，跳过事件");
+            return diff;
+        }
+        *emitted = fingerprint_tools(tools);
+        drop(emitted);
+
+        debug!(
+            added = diff.added.len(),
+            removed = diff.removed.len(),
+            changed = diff.changed.len(),
+            "工具列表已更新"
+        );
+        self.emit_event(
+            "mcp:tools_updated",
+            McpToolsUpdatedPayload {
+                revision: self.next_tools_revision(),
+                full_resync: false,
+                tools: Vec::new(),
+                added: diff.added.clone(),
+                removed: diff.removed.clone(),
+                changed: diff.changed.clone(),
+            },
+        );
+        diff
+    }
+
+    /// 发送全量工具列表同步事件，并重置增量快照
+    pub async fn emit_tools_resync(&self, tools: &[McpToolDefinition]) {
+        *self.emitted_tools.write().await = fingerprint_tools(tools);
+        debug!(tool_count = tools.len(), "工具列表全量同步");
+        self.emit_event(
+            "mcp:tools_updated",
+            McpToolsUpdatedPayload {
+                revision: self.next_tools_revision(),
+                full_resync: true,
+                tools: tools.to_vec(),
+                added: Vec::new(),
+                removed: Vec::new(),
+                changed: Vec::new(),
+            },
+        );
+    }
+
+    fn next_tools_revision(&self) -> u64 {
+        self.tools_revision.fetch_add(1, Ordering::SeqCst) + 1
     }
 
     // ========================================================================
@@ -372,7 +492,7 @@ impl McpClientManager {
     /// # 实现步骤（Task 4.2）
     ///
     /// 1. 检查服务器是否已运行
-    /// 2. 启动子进程
+    /// 2. 解析执行环境并校验命令，启动子进程
     /// 3. 建立 stdio 连接
     /// 4. 初始化 MCP 客户端
     /// 5. 失效工具缓存
@@ -385,8 +505,27 @@ impl McpClientManager {
             return Err(McpError::ServerAlreadyRunning(name.to_string()));
         }
 
-        // 2. 构建命令
-        let mut command = Command::new(&config.command);
+        // 远程服务器（Streamable HTTP / SSE）不需要本地进程
+        if config.is_remote() {
+            return self.start_remote_server(name, config).await;
+        }
+
+        // 2. 解析执行环境（补全 PATH、应用解释器配置），启动前校验命令与运行时
+        let environment = ExecutionEnvironment::for_config(config);
+        let diagnostic = environment.diagnose(config).await;
+        if !diagnostic.is_ok() {
+            let error_msg = format!("无法启动服务器进程: {}", diagnostic.summary());
+            error!(
+                server_name = %name,
+                status = ?diagnostic.status,
+                "MCP 服务器执行环境检查失败"
+            );
+            self.emit_server_error(name, &error_msg);
+            return Err(McpError::ProcessSpawnFailed(error_msg));
+        }
+
+        // 构建命令
+        let mut command = Command::new(environment.program(config));
         command.args(&config.args);
 
         // 设置环境变量
@@ -394,55 +533,20 @@ impl McpClientManager {
             command.env(key, value);
         }
 
-        // macOS GUI 应用的 PATH 通常不完整，需要补充常见的命令路径
-        // 确保 npx/node/uvx 等命令可被找到
-        if !config.env.contains_key("PATH") {
-            let current_path = std::env::var("PATH").unwrap_or_default();
-            let home = std::env::var("HOME").unwrap_or_else(|_| "/Users/unknown".to_string());
-            let extra_paths = [
-                format!("{home}/.nvm/versions/node/*/bin"),
-                format!("{home}/.local/bin"),
-                format!("{home}/.cargo/bin"),
-                format!("{home}/Library/pnpm"),
-                format!("{home}/.bun/bin"),
-                "/usr/local/bin".to_string(),
-                "/opt/homebrew/bin".to_string(),
-                "/opt/homebrew/sbin".to_string(),
-            ];
-            // 用 glob 展开 nvm 路径，取最新版本
-            let mut resolved_paths: Vec<String> = Vec::new();
-            for p in &extra_paths {
-                if p.contains('*') {
-                    if let Ok(entries) = glob::glob(p) {
-                        let mut matched: Vec<String> = entries
-                            .filter_map(|e| e.ok())
-                            .map(|e| e.to_string_lossy().to_string())
-                            .collect();
-                        matched.sort();
-                        if let Some(last) = matched.last() {
-                            resolved_paths.push(last.clone());
-                        }
-                    }
-                } else if std::path::Path::new(p).exists() {
-                    resolved_paths.push(p.clone());
-                }
-            }
-            if !resolved_paths.is_empty() {
-                let merged = if current_path.is_empty() {
-                    resolved_paths.join(":")
-                } else {
-                    format!("{}:{}", resolved_paths.join(":"), current_path)
-                };
-                command.env("PATH", &merged);
-                debug!(server_name = %name, "补充 PATH: {}", merged);
-            }
-        }
+        // GUI 应用的 PATH 通常不完整，补充常见运行时目录，确保 npx/node/uvx 等命令可被找到
+        environment.apply_path(&mut command);
+        debug!(server_name = %name, "子进程搜索路径: {:?}", environment.search_paths());
 
         // 设置工作目录（清洗 `\0` 和无效空白）
         if let Some(cwd) = config.sanitized_cwd() {
             command.current_dir(cwd);
         }
 
+        // 写入会话标记，应用崩溃后下次启动据此回收遗留进程
+        if let Some(registry) = &self.process_registry {
+            command.env(MCP_SESSION_ENV, registry.session());
+        }
+
         // Unix 系统设置进程组（使子进程独立于父进程组）
         #[cfg(unix)]
         command.process_group(0);
@@ -462,18 +566,34 @@ impl McpClientManager {
             }
         };
 
-        // 启动 stderr 读取任务（用于错误诊断）
-        let stderr_task = stderr_opt.take().map(|mut stderr| {
+        if let (Some(registry), Some(pid)) = (&self.process_registry, transport.id()) {
+            registry.register(name, pid);
+        }
+
+        // 启动 stderr 读取任务（写入日志文件，并用于错误诊断）
+        let stderr_task = stderr_opt.take().map(|stderr| {
+            let log_writer = self
+                .log_store
+                .as_ref()
+                .map(|store| store.spawn_writer(name));
             tokio::spawn(async move {
-                let mut all_stderr = Vec::new();
-                let _ = stderr.read_to_end(&mut all_stderr).await;
-                String::from_utf8_lossy(&all_stderr).into_owned()
+                let mut all_stderr = String::new();
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    if let Some(writer) = &log_writer {
+                        writer.append_line("stderr", &line);
+                    }
+                    all_stderr.push_str(&line);
+                    all_stderr.push('\n');
+                }
+                all_stderr
             })
         });
 
         // 4. 初始化 MCP 客户端
         let client_handler =
-            crate::client::LimeMcpClient::new(name.to_string(), self.emitter.clone());
+            crate::client::LimeMcpClient::new(name.to_string(), self.emitter.clone())
+                .with_elicitation(self.elicitation.clone());
 
         // 连接超时：至少 60 秒，避免 npx 首次下载时超时
         let timeout_secs = std::cmp::max(config.timeout, 60);
@@ -502,17 +622,134 @@ impl McpClientManager {
                     stderr = %stderr_content,
                     "MCP 客户端初始化失败"
                 );
+                self.unregister_process(name);
                 self.emit_server_error(name, &error_msg);
                 return Err(McpError::ConnectionFailed(error_msg));
             }
             Err(_) => {
                 let error_msg = format!("MCP 连接超时（{timeout_secs}秒）");
                 error!(server_name = %name, timeout = timeout_secs, "MCP 连接超时");
+                self.unregister_process(name);
                 self.emit_server_error(name, &error_msg);
                 return Err(McpError::Timeout);
             }
         };
 
+        self.register_running_service(name, config, running_service)
+            .await
+    }
+
+    /// 连接远程 MCP 服务器（Streamable HTTP / SSE）
+    ///
+    /// 连接失败时按 `config.reconnect` 指数退避重试。
+    async fn start_remote_server(
+        &self,
+        name: &str,
+        config: &McpServerConfig,
+    ) -> Result<(), McpError> {
+        info!(
+            server_name = %name,
+            transport = ?config.transport,
+            url = config.remote_url().unwrap_or_default(),
+            "连接远程 MCP 服务器"
+        );
+
+        let running_service = match self.connect_remote(name, config).await {
+            Ok(service) => service,
+            Err(e) => {
+                self.emit_server_error(name, &e.to_string());
+                return Err(e);
+            }
+        };
+
+        self.register_running_service(name, config, running_service)
+            .await
+    }
+
+    /// 建立远程连接，失败时按重连策略重试
+    async fn connect_remote(
+        &self,
+        name: &str,
+        config: &McpServerConfig,
+    ) -> Result<RunningService<RoleClient, crate::client::LimeMcpClient>, McpError> {
+        let timeout = Duration::from_secs(config.timeout.max(1));
+        let mut attempt = 0;
+        loop {
+            let client_handler =
+                crate::client::LimeMcpClient::new(name.to_string(), self.emitter.clone())
+                    .with_elicitation(self.elicitation.clone());
+            let result = match tokio::time::timeout(
+                timeout,
+                remote_transport::connect(client_handler, config),
+            )
+            .await
+            {
+                Ok(result) => result,
+                Err(_) => Err(McpError::Timeout),
+            };
+
+            match result {
+                Ok(service) => return Ok(service),
+                Err(e) if attempt < config.reconnect.max_attempts => {
+                    attempt += 1;
+                    let delay = config.reconnect.delay_for_attempt(attempt);
+                    warn!(
+                        server_name = %name,
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        error = %e,
+                        "远程 MCP 服务器连接失败，稍后重试"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(e) => {
+                    error!(server_name = %name, error = %e, "远程 MCP 服务器连接失败");
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    /// 远程连接断开后重新建立连接
+    ///
+    /// 仅对远程服务器生效；重连成功后重新加入连接池并发送 mcp:server_started 事件，
+    /// 失败时移出连接池并发送 mcp:server_error / mcp:server_stopped 事件。
+    pub async fn reconnect_server(&self, name: &str) -> Result<(), McpError> {
+        let config = self
+            .get_client_config(name)
+            .await
+            .ok_or_else(|| McpError::ServerNotRunning(name.to_string()))?;
+        if !config.is_remote() {
+            return Err(McpError::TransportClosed(name.to_string()));
+        }
+
+        // 其他调用方已在重连
+        let Some(stale) = self.remove_client(name).await else {
+            return Ok(());
+        };
+        if let Some(service) = stale.running_service() {
+            service.cancellation_token().cancel();
+        }
+        self.invalidate_tool_cache().await;
+
+        warn!(server_name = %name, "远程 MCP 连接已断开，尝试重连");
+        match self.connect_remote(name, &config).await {
+            Ok(service) => self.register_running_service(name, &config, service).await,
+            Err(e) => {
+                self.emit_server_error(name, &e.to_string());
+                self.emit_server_stopped(name);
+                Err(e)
+            }
+        }
+    }
+
+    /// 将已完成初始化的 rmcp 服务加入连接池
+    async fn register_running_service(
+        &self,
+        name: &str,
+        config: &McpServerConfig,
+        running_service: RunningService<RoleClient, crate::client::LimeMcpClient>,
+    ) -> Result<(), McpError> {
         // 获取服务器信息
         let server_info = running_service
             .peer_info()
@@ -615,6 +852,7 @@ impl McpClientManager {
             );
             // 不返回错误，因为进程可能已经退出
         }
+        self.unregister_process(name);
 
         // 5. 失效工具缓存
         self.invalidate_tool_cache().await;
@@ -682,7 +920,7 @@ impl McpClientManager {
     /// 3. 从所有运行中的服务器获取工具
     /// 4. 解决名称冲突（添加服务器前缀）
     /// 5. 更新缓存
-    /// 6. 发送 mcp:tools_updated 事件
+    /// 6. 发送增量 mcp:tools_updated 事件（无变化时不发送）
     /// 7. 返回工具列表
     pub async fn list_tools(&self) -> Result<Vec<McpToolDefinition>, McpError> {
         // 1. 检查缓存是否有效
@@ -691,7 +929,33 @@ impl McpClientManager {
             return Ok(cached_tools);
         }
 
-        // 2. 从所有运行中的服务器获取工具
+        // 2. 从所有运行中的服务器获取工具，并解决名称冲突
+        let resolved_tools = self.collect_tools().await;
+
+        // 3. 更新缓存
+        self.update_tool_cache(resolved_tools.clone()).await;
+
+        // 4. 发送增量 mcp:tools_updated 事件
+        self.emit_tools_updated(&resolved_tools).await;
+
+        info!(tool_count = resolved_tools.len(), "工具列表已更新");
+        Ok(resolved_tools)
+    }
+
+    /// 全量重新同步工具列表
+    ///
+    /// 忽略缓存重新查询所有服务器，并发送 `full_resync` 事件。
+    /// 前端发现增量事件版本号不连续时调用。
+    pub async fn resync_tools(&self) -> Result<Vec<McpToolDefinition>, McpError> {
+        let tools = self.collect_tools().await;
+        self.update_tool_cache(tools.clone()).await;
+        self.emit_tools_resync(&tools).await;
+        info!(tool_count = tools.len(), "工具列表已全量同步");
+        Ok(tools)
+    }
+
+    /// 从所有运行中的服务器查询工具定义（不读写缓存、不发送事件）
+    async fn collect_tools(&self) -> Vec<McpToolDefinition> {
         let mut all_tools: Vec<McpToolDefinition> = Vec::new();
         let clients = self.clients.read().await;
 
@@ -755,18 +1019,8 @@ impl McpClientManager {
         }
         drop(clients);
 
-        // 3. 解决名称冲突（添加服务器前缀）
-        let resolved_tools =
-            Self::apply_default_loading_policy(Self::resolve_tool_name_conflicts(all_tools));
-
-        // 4. 更新缓存
-        self.update_tool_cache(resolved_tools.clone()).await;
-
-        // 5. 发送 mcp:tools_updated 事件
-        self.emit_tools_updated(resolved_tools.clone());
-
-        info!(tool_count = resolved_tools.len(), "工具列表已更新");
-        Ok(resolved_tools)
+        // 解决名称冲突（添加服务器前缀）
+        Self::apply_default_loading_policy(Self::resolve_tool_name_conflicts(all_tools))
     }
 
     /// 根据上下文过滤工具列表
@@ -997,10 +1251,33 @@ impl McpClientManager {
     /// 3. 执行工具调用
     /// 4. 转换结果为 McpToolResult
     /// 5. 返回结果
+    ///
+    /// 远程服务器连接断开时先重连，再重试一次调用。
     pub async fn call_tool(
         &self,
         tool_name: &str,
         arguments: serde_json::Value,
+    ) -> Result<McpToolResult, McpError> {
+        match self.call_tool_once(tool_name, arguments.clone()).await {
+            Err(McpError::TransportClosed(reason)) => {
+                let (server_name, _) = self.resolve_tool_target(tool_name).await?;
+                warn!(
+                    tool_name = %tool_name,
+                    server_name = %server_name,
+                    reason = %reason,
+                    "工具调用时连接已断开"
+                );
+                self.reconnect_server(&server_name).await?;
+                self.call_tool_once(tool_name, arguments).await
+            }
+            result => result,
+        }
+    }
+
+    async fn call_tool_once(
+        &self,
+        tool_name: &str,
+        arguments: serde_json::Value,
     ) -> Result<McpToolResult, McpError> {
         info!(tool_name = %tool_name, "调用 MCP 工具");
 
@@ -1040,16 +1317,46 @@ impl McpClientManager {
             arguments: args,
         };
 
-        // 4. 执行工具调用
-        let result = service.call_tool(call_param).await.map_err(|e| {
-            error!(
-                tool_name = %actual_tool_name,
-                server_name = %server_name,
-                error = %e,
-                "工具调用失败"
-            );
-            McpError::ToolCallFailed(format!("{e}"))
-        })?;
+        // 4. 执行工具调用（携带 progressToken，以便将部分结果转换为增量输出）
+        let handler = wrapper.handler();
+        let request =
+            rmcp::model::ClientRequest::CallToolRequest(rmcp::model::Request::new(call_param));
+        let handle = service
+            .send_request_with_option(request, rmcp::service::PeerRequestOptions::no_options())
+            .await
+            .map_err(|e| {
+                error!(
+                    tool_name = %actual_tool_name,
+                    server_name = %server_name,
+                    error = %e,
+                    "工具调用失败"
+                );
+                Self::map_call_error(e)
+            })?;
+        // progressToken 由 rmcp 在发送时生成；此前到达的部分结果由 handler 缓存，登记时补发
+        let progress_token = handle.progress_token.clone();
+        handler.begin_tool_stream(&progress_token, tool_name);
+
+        let response = handle.await_response().await;
+        handler.finish_tool_stream(&progress_token);
+
+        let result = match response {
+            Ok(rmcp::model::ServerResult::CallToolResult(result)) => result,
+            Ok(_) => {
+                return Err(McpError::ToolCallFailed(
+                    "服务器返回了非预期的响应类型".to_string(),
+                ));
+            }
+            Err(e) => {
+                error!(
+                    tool_name = %actual_tool_name,
+                    server_name = %server_name,
+                    error = %e,
+                    "工具调用失败"
+                );
+                return Err(Self::map_call_error(e));
+            }
+        };
 
         // 5. 转换结果为 McpToolResult
         let mcp_result = Self::convert_call_tool_result(result);
@@ -1064,6 +1371,17 @@ impl McpClientManager {
         Ok(mcp_result)
     }
 
+    /// 将 rmcp 调用错误转换为 McpError，传输层断开单独区分以便重连
+    fn map_call_error(e: rmcp::service::ServiceError) -> McpError {
+        match e {
+            rmcp::service::ServiceError::TransportClosed
+            | rmcp::service::ServiceError::TransportSend(_) => {
+                McpError::TransportClosed(e.to_string())
+            }
+            e => McpError::ToolCallFailed(format!("{e}")),
+        }
+    }
+
     /// 解析工具目标（服务器名称和实际工具名）
     ///
     /// # Arguments
@@ -1688,11 +2006,16 @@ mod tests {
     /// 创建测试用的服务器配置
     fn create_test_config() -> McpServerConfig {
         McpServerConfig {
+            transport: McpTransportKind::Stdio,
             command: "test-command".to_string(),
             args: vec!["--arg1".to_string(), "--arg2".to_string()],
             env: HashMap::new(),
             cwd: None,
             timeout: 30,
+            interpreter: None,
+            url: None,
+            headers: HashMap::new(),
+            reconnect: Default::default(),
         }
     }
 
@@ -1975,11 +2298,16 @@ mod tests {
 
         // 使用不存在的命令
         let config = McpServerConfig {
+            transport: McpTransportKind::Stdio,
             command: "/nonexistent/command/that/does/not/exist".to_string(),
             args: vec![],
             env: HashMap::new(),
             cwd: None,
             timeout: 5,
+            interpreter: None,
+            url: None,
+            headers: HashMap::new(),
+            reconnect: Default::default(),
         };
 
         let result = manager.start_server("test-server", &config).await;
@@ -2060,11 +2388,16 @@ mod tests {
 
         // 使用无效命令重启（会失败在启动阶段）
         let config = McpServerConfig {
+            transport: McpTransportKind::Stdio,
             command: "/nonexistent/command".to_string(),
             args: vec![],
             env: HashMap::new(),
             cwd: None,
             timeout: 5,
+            interpreter: None,
+            url: None,
+            headers: HashMap::new(),
+            reconnect: Default::default(),
         };
 
         // 重启应该先停止成功，然后启动失败
@@ -2316,6 +2649,34 @@ mod tests {
         assert_eq!(result[0].name, "cached_tool");
     }
 
+    #[tokio::test]
+    async fn test_emit_tools_updated_only_reports_changes() {
+        let manager = McpClientManager::new(None);
+        let tools = vec![
+            create_test_tool("tool_a", "A", "server"),
+            create_test_tool("tool_b", "B", "server"),
+        ];
+
+        let diff = manager.emit_tools_updated(&tools).await;
+        assert_eq!(diff.added.len(), 2);
+        assert_eq!(manager.tools_revision.load(Ordering::SeqCst), 1);
+
+        // 无变化时不递增版本号
+        assert!(manager.emit_tools_updated(&tools).await.is_empty());
+        assert_eq!(manager.tools_revision.load(Ordering::SeqCst), 1);
+
+        let updated = vec![create_test_tool("tool_a", "A v2", "server")];
+        let diff = manager.emit_tools_updated(&updated).await;
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.removed, vec!["tool_b".to_string()]);
+        assert_eq!(manager.tools_revision.load(Ordering::SeqCst), 2);
+
+        // 全量同步后以新列表为基准
+        manager.emit_tools_resync(&tools).await;
+        assert_eq!(manager.tools_revision.load(Ordering::SeqCst), 3);
+        assert!(manager.emit_tools_updated(&tools).await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_list_tools_for_context_filters_deferred_and_caller() {
         let manager = McpClientManager::new(None);
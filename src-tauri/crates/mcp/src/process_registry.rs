@@ -0,0 +1,309 @@
+//! MCP 子进程登记与孤儿进程回收
+//!
+//! 应用崩溃时 stdio MCP 子进程不会随之退出。为此：
+//! - 每次运行生成会话标识（`<应用 pid>-<应用启动时间>`），启动子进程前写入环境变量
+//!   [`MCP_SESSION_ENV`]，子进程派生的孙进程同样继承该标记
+//! - 子进程启动后将 `{服务器名, pid, 进程启动时间, 会话}` 登记到 `mcp_processes.json`
+//! - 下次启动时只处理登记表中所属会话的应用进程已不存在的条目，终止该进程及其子进程树
+//!
+//! 终止前校验进程启动时间与登记一致，没有启动时间的登记视为失效、只移除不终止，
+//! pid 被系统复用后也不会误杀无关进程；其他仍在运行的应用实例的子进程会被保留。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use sysinfo::{Pid, Signal, System};
+use tracing::{debug, info, warn};
+
+/// 子进程会话标记环境变量
+pub const MCP_SESSION_ENV: &str = "LIME_MCP_SESSION";
+
+/// 登记文件名
+pub const MCP_PROCESS_REGISTRY_FILE_NAME: &str = "mcp_processes.json";
+
+/// 已登记的 MCP 子进程
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpProcessEntry {
+    pub server_name: String,
+    pub pid: u32,
+    /// 进程启动时间（秒级 Unix 时间戳），用于识别 pid 复用
+    #[serde(default)]
+    pub process_start_time: Option<u64>,
+    /// 启动该进程的应用会话
+    pub session: String,
+    pub started_at: DateTime<Utc>,
+}
+
+/// MCP 子进程登记表
+#[derive(Debug)]
+pub struct McpProcessRegistry {
+    session: String,
+    file: Option<PathBuf>,
+    entries: Mutex<Vec<McpProcessEntry>>,
+}
+
+impl McpProcessRegistry {
+    /// 创建登记表，读取 `file` 中上次运行遗留的登记（为空时仅保留在内存）
+    pub fn new(file: Option<PathBuf>) -> Self {
+        let entries = file
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            session: current_session(),
+            file,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// 使用应用数据目录下的默认文件创建登记表
+    pub fn with_default_file() -> Self {
+        Self::new(Some(lime_core::app_paths::best_effort_app_data_file(
+            MCP_PROCESS_REGISTRY_FILE_NAME,
+        )))
+    }
+
+    /// 当前会话标识（写入子进程的 [`MCP_SESSION_ENV`]）
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    /// 登记当前会话启动的子进程（同名服务器的旧登记会被替换）
+    pub fn register(&self, server_name: &str, pid: u32) {
+        let mut entries = self.lock();
+        entries.retain(|e| !(e.session == self.session && e.server_name == server_name));
+        entries.push(McpProcessEntry {
+            server_name: server_name.to_string(),
+            pid,
+            process_start_time: process_start_time(pid),
+            session: self.session.clone(),
+            started_at: Utc::now(),
+        });
+        self.persist(&entries);
+    }
+
+    /// 移除当前会话中指定服务器的登记
+    pub fn unregister(&self, server_name: &str) {
+        let mut entries = self.lock();
+        let before = entries.len();
+        entries.retain(|e| !(e.session == self.session && e.server_name == server_name));
+        if entries.len() != before {
+            self.persist(&entries);
+        }
+    }
+
+    /// 当前登记的全部进程
+    pub fn entries(&self) -> Vec<McpProcessEntry> {
+        self.lock().clone()
+    }
+
+    /// 终止上次运行遗留的孤儿进程，返回终止的进程数
+    ///
+    /// 只处理登记表中所属会话已失效的条目，连同其子进程树一起终止。
+    /// 应在启动任何 MCP 服务器之前调用。
+    pub fn cleanup_orphans(&self) -> usize {
+        let mut system = System::new_all();
+        system.refresh_all();
+
+        let stale: Vec<McpProcessEntry> = self
+            .lock()
+            .iter()
+            .filter(|e| e.session != self.session && !is_session_alive(&system, &e.session))
+            .cloned()
+            .collect();
+
+        let own_pid = Pid::from_u32(std::process::id());
+        let mut killed = 0;
+        for entry in &stale {
+            let root = Pid::from_u32(entry.pid);
+            if root == own_pid || !is_registered_process(&system, entry) {
+                debug!(
+                    server = %entry.server_name,
+                    pid = entry.pid,
+                    "登记的 MCP 进程已退出或 pid 已被复用，跳过"
+                );
+                continue;
+            }
+            // 先终止子进程，再终止登记的进程
+            for pid in process_tree(&system, root).into_iter().rev() {
+                if let Some(process) = system.process(pid) {
+                    info!(
+                        server = %entry.server_name,
+                        pid = pid.as_u32(),
+                        "终止遗留的 MCP 子进程"
+                    );
+                    if !process.kill_with(Signal::Term).unwrap_or(false) {
+                        let _ = process.kill();
+                    }
+                    killed += 1;
+                }
+            }
+        }
+
+        // 清理已失效会话的登记，保留仍在运行的其他实例
+        if !stale.is_empty() {
+            let mut entries = self.lock();
+            entries.retain(|e| !stale.contains(e));
+            debug!(removed = stale.len(), "移除失效的 MCP 进程登记");
+            self.persist(&entries);
+        }
+        killed
+    }
+
+    fn persist(&self, entries: &[McpProcessEntry]) {
+        let Some(path) = &self.file else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(entries)
+            .map_err(std::io::Error::from)
+            .and_then(|content| {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, content)
+            });
+        if let Err(e) = result {
+            warn!("写入 MCP 进程登记失败 {}: {}", path.display(), e);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<McpProcessEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// 当前应用进程的会话标识
+fn current_session() -> String {
+    let pid = std::process::id();
+    let mut system = System::new();
+    system.refresh_processes(
+        sysinfo::ProcessesToUpdate::Some(&[Pid::from_u32(pid)]),
+        true,
+    );
+    let start_time = system
+        .process(Pid::from_u32(pid))
+        .map(|p| p.start_time())
+        .unwrap_or_else(|| Utc::now().timestamp().max(0) as u64);
+    format!("{pid}-{start_time}")
+}
+
+/// 进程启动时间，进程不存在时返回 `None`
+fn process_start_time(pid: u32) -> Option<u64> {
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]), true);
+    system.process(pid).map(|p| p.start_time())
+}
+
+/// 登记的 pid 当前是否仍是当初启动的那个进程
+///
+/// 按启动时间比对；登记时未能取得启动时间的条目无法排除 pid 复用，一律视为失效。
+fn is_registered_process(system: &System, entry: &McpProcessEntry) -> bool {
+    let Some(start_time) = entry.process_start_time else {
+        return false;
+    };
+    system
+        .process(Pid::from_u32(entry.pid))
+        .is_some_and(|process| process.start_time() == start_time)
+}
+
+/// 以 `root` 为根的进程树（广度优先，根在最前）
+fn process_tree(system: &System, root: Pid) -> Vec<Pid> {
+    let mut tree = vec![root];
+    let mut index = 0;
+    while index < tree.len() {
+        let parent = tree[index];
+        let children: Vec<Pid> = system
+            .processes()
+            .iter()
+            .filter(|(pid, process)| process.parent() == Some(parent) && !tree.contains(pid))
+            .map(|(pid, _)| *pid)
+            .collect();
+        tree.extend(children);
+        index += 1;
+    }
+    tree
+}
+
+/// 会话所属的应用进程是否仍在运行（pid 与启动时间均匹配）
+fn is_session_alive(system: &System, session: &str) -> bool {
+    let Some((pid, start_time)) = session
+        .split_once('-')
+        .and_then(|(pid, start)| Some((pid.parse::<u32>().ok()?, start.parse::<u64>().ok()?)))
+    else {
+        return false;
+    };
+    system
+        .process(Pid::from_u32(pid))
+        .is_some_and(|process| process.start_time() == start_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_register_persists_and_reloads() {
+        let file = std::env::temp_dir()
+            .join(format!(
+                "lime-mcp-registry-{}-{}",
+                std::process::id(),
+                Utc::now().timestamp_nanos_opt().unwrap_or_default()
+            ))
+            .join(MCP_PROCESS_REGISTRY_FILE_NAME);
+        let registry = McpProcessRegistry::new(Some(file.clone()));
+        registry.register("fs", 100);
+        registry.register("git", 101);
+        registry.register("fs", 102);
+        registry.unregister("git");
+
+        let restored = McpProcessRegistry::new(Some(file.clone()));
+        let entries = restored.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].pid, 102);
+        assert_eq!(entries[0].session, registry.session());
+        let _ = fs::remove_dir_all(file.parent().unwrap());
+    }
+
+    #[test]
+    fn test_session_liveness() {
+        let registry = McpProcessRegistry::new(None);
+        let mut system = System::new_all();
+        system.refresh_all();
+        assert!(is_session_alive(&system, registry.session()));
+        // pid 相同但启动时间不同视为已失效（pid 被复用）
+        let reused = format!("{}-1", std::process::id());
+        assert!(!is_session_alive(&system, &reused));
+        assert!(!is_session_alive(&system, "not-a-session"));
+    }
+
+    #[test]
+    fn test_registered_process_requires_matching_start_time() {
+        let mut system = System::new_all();
+        system.refresh_all();
+        let pid = std::process::id();
+        let mut entry = McpProcessEntry {
+            server_name: "fs".to_string(),
+            pid,
+            process_start_time: process_start_time(pid),
+            session: "1-1".to_string(),
+            started_at: Utc::now(),
+        };
+        assert!(is_registered_process(&system, &entry));
+
+        entry.process_start_time = entry.process_start_time.map(|t| t + 1);
+        assert!(!is_registered_process(&system, &entry));
+
+        // 没有启动时间的登记即使 pid 仍存在也视为失效
+        entry.process_start_time = None;
+        assert!(!is_registered_process(&system, &entry));
+
+        assert_eq!(
+            process_tree(&system, Pid::from_u32(pid))[0],
+            Pid::from_u32(pid)
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! 远程 MCP 传输
+//!
+//! 支持通过 HTTP 连接远程 MCP 服务器：
+//! - Streamable HTTP（MCP 2025-03-26）：使用 rmcp 的 `StreamableHttpClientTransport`
+//! - SSE（MCP 2024-11-05，旧版）：GET 建立事件流，`endpoint` 事件给出消息 POST 地址，
+//!   服务器消息通过 `message` 事件返回
+//!
+//! 两种传输共用同一个 reqwest 客户端，配置中的请求头（如 Authorization）作为默认头附加到所有请求。
+
+use std::time::Duration;
+
+use futures::StreamExt;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT};
+use rmcp::model::{ClientJsonRpcMessage, ServerJsonRpcMessage};
+use rmcp::service::RunningService;
+use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
+use rmcp::transport::StreamableHttpClientTransport;
+use rmcp::{RoleClient, ServiceExt};
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, warn};
+
+use crate::client::LimeMcpClient;
+use crate::types::{McpError, McpServerConfig, McpTransportKind};
+
+/// SSE 消息通道容量
+const SSE_CHANNEL_CAPACITY: usize = 64;
+
+/// SSE 消息发送错误
+#[derive(Debug, thiserror::Error)]
+pub enum SseTransportError {
+    #[error("HTTP 请求失败: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// 解析后的 SSE 事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SseEvent {
+    pub event: String,
+    pub data: String,
+}
+
+/// 连接远程 MCP 服务器并完成初始化握手
+pub async fn connect(
+    handler: LimeMcpClient,
+    config: &McpServerConfig,
+) -> Result<RunningService<RoleClient, LimeMcpClient>, McpError> {
+    let url = config
+        .remote_url()
+        .ok_or_else(|| McpError::ConnectionFailed("远程服务器未配置 url".to_string()))?;
+    let client = build_http_client(config)?;
+
+    let service = match config.transport {
+        McpTransportKind::StreamableHttp => {
+            let transport = StreamableHttpClientTransport::with_client(
+                client,
+                StreamableHttpClientTransportConfig::with_uri(url),
+            );
+            handler.serve(transport).await
+        }
+        McpTransportKind::Sse => {
+            let (sink, stream) = open_sse_transport(client, url).await?;
+            handler.serve((sink, stream)).await
+        }
+        McpTransportKind::Stdio => {
+            return Err(McpError::ConnectionFailed(
+                "stdio 服务器不能通过远程传输连接".to_string(),
+            ));
+        }
+    };
+
+    service.map_err(|e| McpError::ConnectionFailed(format!("{e}")))
+}
+
+/// 构建附加了配置请求头的 HTTP 客户端
+///
+/// 不设置整体请求超时：SSE 与 Streamable HTTP 的事件流都是长连接。
+fn build_http_client(config: &McpServerConfig) -> Result<reqwest::Client, McpError> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in config.resolved_headers() {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| McpError::ConnectionFailed(format!("无效的请求头名称: {name}")))?;
+        let mut header_value = HeaderValue::from_str(&value)
+            .map_err(|_| McpError::ConnectionFailed(format!("无效的请求头值: {name}")))?;
+        header_value.set_sensitive(true);
+        headers.insert(header_name, header_value);
+    }
+
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .connect_timeout(Duration::from_secs(config.timeout.max(1)))
+        .build()
+        .map_err(|e| McpError::ConnectionFailed(format!("创建 HTTP 客户端失败: {e}")))
+}
+
+/// 建立旧版 SSE 传输，返回 (消息发送端, 服务器消息流)
+async fn open_sse_transport(
+    client: reqwest::Client,
+    url: &str,
+) -> Result<
+    (
+        impl futures::Sink<ClientJsonRpcMessage, Error = SseTransportError> + Send + Unpin + 'static,
+        impl futures::Stream<Item = ServerJsonRpcMessage> + Send + Unpin + 'static,
+    ),
+    McpError,
+> {
+    let base_url = reqwest::Url::parse(url)
+        .map_err(|e| McpError::ConnectionFailed(format!("无效的服务器地址 {url}: {e}")))?;
+
+    let response = client
+        .get(base_url.clone())
+        .header(ACCEPT, "text/event-stream")
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| McpError::ConnectionFailed(format!("建立 SSE 连接失败: {e}")))?;
+
+    let (endpoint_tx, endpoint_rx) = oneshot::channel::<Result<reqwest::Url, String>>();
+    let (message_tx, message_rx) = mpsc::channel::<ServerJsonRpcMessage>(SSE_CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        let mut endpoint_tx = Some(endpoint_tx);
+        let mut body = response.bytes_stream();
+        let mut buffer: Vec<u8> = Vec::new();
+
+        while let Some(chunk) = body.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(e) => {
+                    warn!(error = %e, "SSE 事件流读取失败");
+                    break;
+                }
+            };
+            // 按字节缓冲，避免多字节字符被分块截断
+            buffer.extend(chunk.iter().filter(|b| **b != b'\r'));
+
+            while let Some(pos) = buffer.windows(2).position(|w| w == b"\n\n") {
+                let block: Vec<u8> = buffer.drain(..pos + 2).collect();
+                let Some(event) = parse_sse_event(&String::from_utf8_lossy(&block)) else {
+                    continue;
+                };
+                match event.event.as_str() {
+                    "endpoint" => {
+                        if let Some(tx) = endpoint_tx.take() {
+                            let _ = tx.send(resolve_endpoint(&base_url, &event.data));
+                        }
+                    }
+                    "message" => match serde_json::from_str::<ServerJsonRpcMessage>(&event.data) {
+                        Ok(message) => {
+                            if message_tx.send(message).await.is_err() {
+                                // 传输已关闭
+                                return;
+                            }
+                        }
+                        Err(e) => warn!(error = %e, "无法解析 SSE 消息"),
+                    },
+                    other => debug!(event = %other, "忽略 SSE 事件"),
+                }
+            }
+        }
+        debug!("SSE 事件流已结束");
+    });
+
+    let endpoint = endpoint_rx
+        .await
+        .map_err(|_| McpError::ConnectionFailed("SSE 连接未返回 endpoint 事件".to_string()))?
+        .map_err(McpError::ConnectionFailed)?;
+    debug!(endpoint = %endpoint, "SSE 消息端点");
+
+    let sink = futures::sink::unfold(
+        (client, endpoint),
+        |(client, endpoint), message: ClientJsonRpcMessage| async move {
+            client
+                .post(endpoint.clone())
+                .json(&message)
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok::<_, SseTransportError>((client, endpoint))
+        },
+    );
+    let stream = futures::stream::unfold(message_rx, |mut rx| async move {
+        rx.recv().await.map(|message| (message, rx))
+    });
+
+    Ok((Box::pin(sink), Box::pin(stream)))
+}
+
+/// 解析单个 SSE 事件块（以空行结尾），忽略注释行；无 data 的事件返回 None
+pub(crate) fn parse_sse_event(block: &str) -> Option<SseEvent> {
+    let mut event = String::from("message");
+    let mut data: Vec<&str> = Vec::new();
+
+    for line in block.lines() {
+        if line.is_empty() || line.starts_with(':') {
+            continue;
+        }
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => event = value.to_string(),
+            "data" => data.push(value),
+            _ => {}
+        }
+    }
+
+    if data.is_empty() {
+        return None;
+    }
+    Some(SseEvent {
+        event,
+        data: data.join("\n"),
+    })
+}
+
+/// 解析 `endpoint` 事件给出的消息地址，只允许与 SSE 地址同源，避免鉴权头被发往其他主机
+pub(crate) fn resolve_endpoint(
+    base: &reqwest::Url,
+    endpoint: &str,
+) -> Result<reqwest::Url, String> {
+    let resolved = base
+        .join(endpoint.trim())
+        .map_err(|e| format!("无效的 SSE 消息端点 {endpoint}: {e}"))?;
+    if resolved.origin() != base.origin() {
+        return Err(format!("SSE 消息端点与服务器不同源: {resolved}"));
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sse_event() {
+        let event = parse_sse_event("event: endpoint\ndata: /messages?session=1\n\n").unwrap();
+        assert_eq!(event.event, "endpoint");
+        assert_eq!(event.data, "/messages?session=1");
+
+        let event = parse_sse_event(": ping\ndata: {\"a\":\ndata: 1}\n\n").unwrap();
+        assert_eq!(event.event, "message");
+        assert_eq!(event.data, "{\"a\":\n1}");
+
+        assert!(parse_sse_event(": keep-alive\n\n").is_none());
+    }
+
+    #[test]
+    fn test_resolve_endpoint_requires_same_origin() {
+        let base = reqwest::Url::parse("https://mcp.example.com/sse").unwrap();
+        assert_eq!(
+            resolve_endpoint(&base, "/messages?sessionId=abc")
+                .unwrap()
+                .as_str(),
+            "https://mcp.example.com/messages?sessionId=abc"
+        );
+        assert!(resolve_endpoint(&base, "https://evil.example.net/messages").is_err());
+    }
+}
@@ -0,0 +1,525 @@
+//! MCP 服务器执行环境
+//!
+//! GUI 应用继承的 PATH 通常不包含 nvm、Homebrew、uv 等安装目录，
+//! 导致 `npx` / `uvx` / `python` 无法找到。本模块负责：
+//! - 探测常见运行时安装位置，补全子进程 PATH
+//! - 应用服务器级解释器配置（`interpreter`）
+//! - 启动前解析命令并检查运行时版本，返回可操作的诊断信息
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::process::Command;
+
+use crate::types::McpServerConfig;
+
+/// 运行时版本检查超时
+const VERSION_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// MCP 服务器依赖的运行时
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeKind {
+    Node,
+    Python,
+    Uv,
+    Bun,
+    Docker,
+}
+
+impl RuntimeKind {
+    /// 根据启动命令推断运行时（忽略路径与 `.exe` / `.cmd` 后缀）
+    pub fn from_command(command: &str) -> Option<Self> {
+        let stem = Path::new(command)
+            .file_stem()?
+            .to_string_lossy()
+            .to_ascii_lowercase();
+        match stem.as_str() {
+            "node" | "npx" | "npm" | "pnpm" | "pnpx" | "yarn" => Some(Self::Node),
+            "uv" | "uvx" => Some(Self::Uv),
+            "bun" | "bunx" => Some(Self::Bun),
+            "docker" => Some(Self::Docker),
+            s if s.starts_with("python") || s.starts_with("pip") => Some(Self::Python),
+            _ => None,
+        }
+    }
+
+    /// 用于检查版本的可执行文件（按优先级）
+    fn version_binaries(self) -> &'static [&'static str] {
+        match self {
+            Self::Node => &["node"],
+            Self::Python => &["python3", "python"],
+            Self::Uv => &["uv"],
+            Self::Bun => &["bun"],
+            Self::Docker => &["docker"],
+        }
+    }
+
+    /// 最低支持版本
+    pub fn min_version(self) -> Option<(u64, u64)> {
+        match self {
+            Self::Node => Some((18, 0)),
+            Self::Python => Some((3, 10)),
+            _ => None,
+        }
+    }
+
+    /// 缺失或版本过旧时的处理建议
+    pub fn install_hint(self) -> &'static str {
+        match self {
+            Self::Node => "安装 Node.js 18 或更高版本（https://nodejs.org），或在服务器配置中将 interpreter 指向 node 可执行文件",
+            Self::Python => "安装 Python 3.10 或更高版本，或在服务器配置中将 interpreter 指向 python 可执行文件",
+            Self::Uv => "安装 uv（https://docs.astral.sh/uv/），安装后重启应用",
+            Self::Bun => "安装 Bun（https://bun.sh），安装后重启应用",
+            Self::Docker => "安装并启动 Docker Desktop",
+        }
+    }
+}
+
+/// 环境检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpEnvironmentStatus {
+    Ok,
+    /// 启动命令无法解析
+    MissingCommand,
+    /// 命令依赖的运行时不存在
+    MissingRuntime,
+    /// 运行时版本低于要求
+    RuntimeTooOld,
+    /// 配置的解释器路径无效
+    InvalidInterpreter,
+}
+
+/// MCP 服务器执行环境诊断
+#[derive(Debug, Clone, Serialize)]
+pub struct McpEnvironmentDiagnostic {
+    pub status: McpEnvironmentStatus,
+    pub command: String,
+    /// 解析到的启动命令路径
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolved_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<RuntimeKind>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_version: Option<String>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
+    /// 实际使用的 PATH 搜索目录
+    pub search_paths: Vec<String>,
+}
+
+impl McpEnvironmentDiagnostic {
+    pub fn is_ok(&self) -> bool {
+        self.status == McpEnvironmentStatus::Ok
+    }
+
+    /// 错误摘要（消息 + 建议）
+    pub fn summary(&self) -> String {
+        match &self.suggestion {
+            Some(suggestion) => format!("{}。{}", self.message, suggestion),
+            None => self.message.clone(),
+        }
+    }
+}
+
+/// 子进程执行环境
+#[derive(Debug, Clone)]
+pub struct ExecutionEnvironment {
+    search_paths: Vec<PathBuf>,
+    /// 是否需要覆盖子进程 PATH（配置中已显式指定 PATH 时不覆盖）
+    override_path: bool,
+}
+
+impl ExecutionEnvironment {
+    /// 为服务器配置构建执行环境
+    ///
+    /// 搜索顺序：解释器所在目录 > 配置中的 PATH（或常见运行时目录 + 当前 PATH）。
+    pub fn for_config(config: &McpServerConfig) -> Self {
+        let mut search_paths = Vec::new();
+        if let Some(dir) = config
+            .interpreter_path()
+            .as_deref()
+            .and_then(Path::parent)
+            .filter(|dir| !dir.as_os_str().is_empty())
+        {
+            search_paths.push(dir.to_path_buf());
+        }
+
+        let override_path = !config.env.contains_key("PATH");
+        match config.env.get("PATH") {
+            Some(path) => search_paths.extend(std::env::split_paths(path)),
+            None => {
+                search_paths.extend(common_runtime_dirs());
+                if let Some(path) = std::env::var_os("PATH") {
+                    search_paths.extend(std::env::split_paths(&path));
+                }
+            }
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        search_paths.retain(|p| !p.as_os_str().is_empty() && seen.insert(p.clone()));
+        Self {
+            search_paths,
+            override_path: override_path || config.interpreter.is_some(),
+        }
+    }
+
+    pub fn search_paths(&self) -> &[PathBuf] {
+        &self.search_paths
+    }
+
+    /// 子进程使用的 PATH 值
+    pub fn path_value(&self) -> Option<OsString> {
+        std::env::join_paths(&self.search_paths).ok()
+    }
+
+    /// 将 PATH 应用到子进程命令
+    pub fn apply_path(&self, command: &mut tokio::process::Command) {
+        if self.override_path {
+            if let Some(path) = self.path_value() {
+                command.env("PATH", path);
+            }
+        }
+    }
+
+    /// 在搜索目录中解析可执行文件
+    pub fn resolve(&self, program: &str) -> Option<PathBuf> {
+        let path = Path::new(program);
+        if path.components().count() > 1 || path.is_absolute() {
+            return executable_candidates(path)
+                .into_iter()
+                .find(|p| p.is_file());
+        }
+        self.search_paths
+            .iter()
+            .flat_map(|dir| executable_candidates(&dir.join(program)))
+            .find(|p| p.is_file())
+    }
+
+    /// 实际启动的程序
+    ///
+    /// 命令本身就是解释器（如 `python`、`node`）且配置了同类 `interpreter` 时，替换为解释器路径。
+    pub fn program(&self, config: &McpServerConfig) -> String {
+        if let Some(interpreter) = config.interpreter_path() {
+            let command_kind = RuntimeKind::from_command(&config.command);
+            let is_interpreter_itself = Path::new(&config.command)
+                .file_stem()
+                .map(|stem| {
+                    let stem = stem.to_string_lossy().to_ascii_lowercase();
+                    stem == "node" || stem.starts_with("python")
+                })
+                .unwrap_or(false);
+            if is_interpreter_itself
+                && command_kind.is_some()
+                && command_kind == RuntimeKind::from_command(&interpreter.to_string_lossy())
+            {
+                return interpreter.to_string_lossy().to_string();
+            }
+        }
+        config.command.clone()
+    }
+
+    /// 启动前检查命令与运行时
+    pub async fn diagnose(&self, config: &McpServerConfig) -> McpEnvironmentDiagnostic {
+        let runtime = RuntimeKind::from_command(&config.command);
+        let mut diagnostic = McpEnvironmentDiagnostic {
+            status: McpEnvironmentStatus::Ok,
+            command: config.command.clone(),
+            resolved_path: None,
+            runtime,
+            runtime_path: None,
+            runtime_version: None,
+            message: "执行环境正常".to_string(),
+            suggestion: None,
+            search_paths: self
+                .search_paths
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+        };
+
+        // 远程服务器不依赖本地命令与运行时
+        if config.is_remote() {
+            diagnostic.message = "远程服务器，无需本地执行环境".to_string();
+            return diagnostic;
+        }
+
+        if let Some(interpreter) = config.interpreter_path() {
+            if !interpreter.is_file() {
+                diagnostic.status = McpEnvironmentStatus::InvalidInterpreter;
+                diagnostic.message = format!("解释器不存在: {}", interpreter.display());
+                diagnostic.suggestion = Some("检查服务器配置中的 interpreter 路径".to_string());
+                return diagnostic;
+            }
+        }
+
+        let program = self.program(config);
+        // 相对路径命令（如 `./server.js`）相对于工作目录解析
+        let lookup = match config.sanitized_cwd() {
+            Some(cwd) if Path::new(&program).is_relative() && program.contains(['/', '\\']) => {
+                cwd.join(&program).to_string_lossy().to_string()
+            }
+            _ => program.clone(),
+        };
+        let Some(resolved) = self.resolve(&lookup) else {
+            match runtime {
+                Some(kind) => {
+                    diagnostic.status = McpEnvironmentStatus::MissingRuntime;
+                    diagnostic.message = format!("未找到命令 {program}（需要 {kind:?} 运行时）");
+                    diagnostic.suggestion = Some(kind.install_hint().to_string());
+                }
+                None => {
+                    diagnostic.status = McpEnvironmentStatus::MissingCommand;
+                    diagnostic.message = format!("未找到命令: {program}");
+                    diagnostic.suggestion = Some(
+                        "确认命令已安装，或在配置中使用绝对路径 / 在 env 中设置 PATH".to_string(),
+                    );
+                }
+            }
+            return diagnostic;
+        };
+        diagnostic.resolved_path = Some(resolved.to_string_lossy().to_string());
+
+        let Some(kind) = runtime else {
+            return diagnostic;
+        };
+        let runtime_binary = match config.interpreter_path() {
+            Some(interpreter)
+                if RuntimeKind::from_command(&interpreter.to_string_lossy()) == Some(kind) =>
+            {
+                Some(interpreter)
+            }
+            _ => kind
+                .version_binaries()
+                .iter()
+                .find_map(|binary| self.resolve(binary)),
+        };
+        let Some(runtime_binary) = runtime_binary else {
+            diagnostic.status = McpEnvironmentStatus::MissingRuntime;
+            diagnostic.message = format!("找到 {program}，但未找到 {kind:?} 运行时");
+            diagnostic.suggestion = Some(kind.install_hint().to_string());
+            return diagnostic;
+        };
+        diagnostic.runtime_path = Some(runtime_binary.to_string_lossy().to_string());
+
+        let version = self.runtime_version(&runtime_binary).await;
+        diagnostic.runtime_version = version.clone();
+        if let (Some(min), Some(version)) = (
+            kind.min_version(),
+            version.as_deref().and_then(parse_version),
+        ) {
+            if (version.0, version.1) < min {
+                diagnostic.status = McpEnvironmentStatus::RuntimeTooOld;
+                diagnostic.message = format!(
+                    "{kind:?} 版本 {}.{}.{} 过旧，需要 {}.{} 或更高版本",
+                    version.0, version.1, version.2, min.0, min.1
+                );
+                diagnostic.suggestion = Some(kind.install_hint().to_string());
+            }
+        }
+        diagnostic
+    }
+
+    /// 执行 `<runtime> --version`，失败时返回 None
+    async fn runtime_version(&self, binary: &Path) -> Option<String> {
+        let mut command = Command::new(binary);
+        command.arg("--version").kill_on_drop(true);
+        self.apply_path(&mut command);
+        let output = tokio::time::timeout(VERSION_CHECK_TIMEOUT, command.output())
+            .await
+            .ok()?
+            .ok()?;
+        let text = if output.stdout.is_empty() {
+            String::from_utf8_lossy(&output.stderr).to_string()
+        } else {
+            String::from_utf8_lossy(&output.stdout).to_string()
+        };
+        let text = text.lines().next()?.trim().to_string();
+        (!text.is_empty()).then_some(text)
+    }
+}
+
+/// 解析版本号，如 `v20.11.0`、`Python 3.12.1`、`uv 0.4.0 (abc)`
+pub fn parse_version(text: &str) -> Option<(u64, u64, u64)> {
+    text.split_whitespace().find_map(|token| {
+        let token = token.trim_start_matches(['v', 'V']);
+        let mut parts = token.split('.');
+        let major = parts.next()?.parse().ok()?;
+        let minor = parts
+            .next()
+            .and_then(|p| p.parse().ok())
+            .unwrap_or_default();
+        let patch = parts
+            .next()
+            .and_then(|p| {
+                p.chars()
+                    .take_while(char::is_ascii_digit)
+                    .collect::<String>()
+                    .parse()
+                    .ok()
+            })
+            .unwrap_or_default();
+        Some((major, minor, patch))
+    })
+}
+
+/// 可执行文件候选（Windows 下补充 `.exe` / `.cmd` / `.bat` 后缀）
+fn executable_candidates(path: &Path) -> Vec<PathBuf> {
+    let mut candidates = vec![path.to_path_buf()];
+    if cfg!(windows) && path.extension().is_none() {
+        candidates.extend(["exe", "cmd", "bat"].map(|ext| path.with_extension(ext)));
+    }
+    candidates
+}
+
+/// 常见运行时安装目录（仅返回存在的目录）
+pub fn common_runtime_dirs() -> Vec<PathBuf> {
+    let home = dirs::home_dir().unwrap_or_default();
+    let mut dirs: Vec<PathBuf> = Vec::new();
+
+    if cfg!(windows) {
+        if let Some(app_data) = std::env::var_os("APPDATA") {
+            dirs.push(PathBuf::from(app_data).join("npm"));
+        }
+        if let Some(program_files) = std::env::var_os("ProgramFiles") {
+            dirs.push(PathBuf::from(program_files).join("nodejs"));
+        }
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            let pattern = PathBuf::from(local).join("Programs/Python/Python3*");
+            dirs.extend(latest_glob_match(&pattern.to_string_lossy()));
+        }
+    } else {
+        // nvm 取最新版本
+        let nvm = home.join(".nvm/versions/node/*/bin");
+        dirs.extend(latest_glob_match(&nvm.to_string_lossy()));
+        dirs.push(home.join(".volta/bin"));
+        dirs.push(home.join(".fnm/aliases/default/bin"));
+        dirs.push(home.join(".asdf/shims"));
+        dirs.push(home.join(".local/share/mise/shims"));
+        dirs.push(home.join(".pyenv/shims"));
+        dirs.push(home.join("Library/pnpm"));
+        dirs.push(PathBuf::from("/usr/local/bin"));
+        dirs.push(PathBuf::from("/opt/homebrew/bin"));
+        dirs.push(PathBuf::from("/opt/homebrew/sbin"));
+    }
+    dirs.push(home.join(".local/bin"));
+    dirs.push(home.join(".cargo/bin"));
+    dirs.push(home.join(".bun/bin"));
+
+    dirs.retain(|dir| dir.is_dir());
+    dirs
+}
+
+/// glob 匹配中版本号最高的路径
+fn latest_glob_match(pattern: &str) -> Option<PathBuf> {
+    glob::glob(pattern)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .max_by_key(|path| {
+            path.to_string_lossy()
+                .split(['/', '\\'])
+                .filter_map(parse_version)
+                .last()
+                .unwrap_or_default()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::McpTransportKind;
+    use std::collections::HashMap;
+
+    fn config(command: &str, interpreter: Option<&str>, path: Option<&str>) -> McpServerConfig {
+        let mut env = HashMap::new();
+        if let Some(path) = path {
+            env.insert("PATH".to_string(), path.to_string());
+        }
+        McpServerConfig {
+            transport: McpTransportKind::Stdio,
+            command: command.to_string(),
+            args: Vec::new(),
+            env,
+            cwd: None,
+            timeout: 30,
+            interpreter: interpreter.map(str::to_string),
+            url: None,
+            headers: HashMap::new(),
+            reconnect: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_runtime_kind_from_command() {
+        assert_eq!(RuntimeKind::from_command("npx"), Some(RuntimeKind::Node));
+        assert_eq!(
+            RuntimeKind::from_command("C:\\nodejs\\npx.cmd"),
+            Some(RuntimeKind::Node)
+        );
+        assert_eq!(
+            RuntimeKind::from_command("/usr/bin/python3.12"),
+            Some(RuntimeKind::Python)
+        );
+        assert_eq!(RuntimeKind::from_command("uvx"), Some(RuntimeKind::Uv));
+        assert_eq!(RuntimeKind::from_command("my-server"), None);
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("v20.11.0"), Some((20, 11, 0)));
+        assert_eq!(parse_version("Python 3.12.1"), Some((3, 12, 1)));
+        assert_eq!(
+            parse_version("uv 0.4.18 (abc 2024-10-01)"),
+            Some((0, 4, 18))
+        );
+        assert_eq!(parse_version("3.13.0rc1"), Some((3, 13, 0)));
+        assert_eq!(parse_version("no version"), None);
+    }
+
+    #[test]
+    fn test_interpreter_dir_takes_precedence() {
+        let env = ExecutionEnvironment::for_config(&config(
+            "python3",
+            Some("/opt/py312/bin/python3"),
+            Some("/usr/bin"),
+        ));
+        assert_eq!(
+            env.search_paths(),
+            &[PathBuf::from("/opt/py312/bin"), PathBuf::from("/usr/bin")]
+        );
+        assert_eq!(
+            env.program(&config("python3", Some("/opt/py312/bin/python3"), None)),
+            "/opt/py312/bin/python3"
+        );
+        // 解释器与命令不是同类运行时时不替换
+        assert_eq!(
+            env.program(&config("uvx", Some("/opt/py312/bin/python3"), None)),
+            "uvx"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_diagnose_reports_missing_runtime_and_interpreter() {
+        let empty = std::env::temp_dir().join("lime-mcp-runtime-env-empty");
+        let path = empty.to_string_lossy().to_string();
+
+        let cfg = config("npx", None, Some(&path));
+        let diagnostic = ExecutionEnvironment::for_config(&cfg).diagnose(&cfg).await;
+        assert_eq!(diagnostic.status, McpEnvironmentStatus::MissingRuntime);
+        assert!(diagnostic.suggestion.unwrap().contains("Node.js"));
+
+        let cfg = config("my-server", None, Some(&path));
+        let diagnostic = ExecutionEnvironment::for_config(&cfg).diagnose(&cfg).await;
+        assert_eq!(diagnostic.status, McpEnvironmentStatus::MissingCommand);
+
+        let cfg = config("python3", Some("/nonexistent/python3"), Some(&path));
+        let diagnostic = ExecutionEnvironment::for_config(&cfg).diagnose(&cfg).await;
+        assert_eq!(diagnostic.status, McpEnvironmentStatus::InvalidInterpreter);
+    }
+}
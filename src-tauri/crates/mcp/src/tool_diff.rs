@@ -0,0 +1,150 @@
+//! MCP 工具列表增量比较
+//!
+//! 以工具名为键、工具定义（描述、输入 schema、元数据）的哈希为指纹，
+//! 计算两次工具列表之间的新增、移除与变更，用于增量 `mcp:tools_updated` 事件。
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use serde::Serialize;
+
+use crate::types::McpToolDefinition;
+
+/// 工具指纹快照（工具名 -> 定义哈希）
+pub type ToolFingerprints = HashMap<String, u64>;
+
+/// 计算工具定义的指纹
+///
+/// 对完整定义的 JSON 序列化结果求哈希；`serde_json` 对象键有序，
+/// 同一定义在进程内得到稳定结果。
+pub fn tool_fingerprint(tool: &McpToolDefinition) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    serde_json::to_string(tool)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// 生成工具列表的指纹快照
+pub fn fingerprint_tools(tools: &[McpToolDefinition]) -> ToolFingerprints {
+    tools
+        .iter()
+        .map(|tool| (tool.name.clone(), tool_fingerprint(tool)))
+        .collect()
+}
+
+/// 两次工具列表之间的差异
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct McpToolsDiff {
+    /// 新增的工具
+    pub added: Vec<McpToolDefinition>,
+    /// 移除的工具名
+    pub removed: Vec<String>,
+    /// 定义发生变化的工具（新定义）
+    pub changed: Vec<McpToolDefinition>,
+}
+
+impl McpToolsDiff {
+    /// 计算 `previous` 快照到 `current` 列表的差异
+    ///
+    /// 结果按工具名排序，便于前端与日志稳定展示。
+    pub fn between(previous: &ToolFingerprints, current: &[McpToolDefinition]) -> Self {
+        let mut diff = Self::default();
+        for tool in current {
+            match previous.get(&tool.name) {
+                None => diff.added.push(tool.clone()),
+                Some(hash) if *hash != tool_fingerprint(tool) => diff.changed.push(tool.clone()),
+                Some(_) => {}
+            }
+        }
+
+        let current_names: HashSet<&str> = current.iter().map(|tool| tool.name.as_str()).collect();
+        diff.removed = previous
+            .keys()
+            .filter(|name| !current_names.contains(name.as_str()))
+            .cloned()
+            .collect();
+
+        diff.added.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.changed.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.removed.sort();
+        diff
+    }
+
+    /// 是否没有任何变化
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, schema: serde_json::Value) -> McpToolDefinition {
+        McpToolDefinition {
+            name: name.to_string(),
+            description: format!("{name} tool"),
+            input_schema: schema,
+            server_name: "server".to_string(),
+            deferred_loading: None,
+            always_visible: None,
+            allowed_callers: None,
+            input_examples: None,
+            tags: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_detects_added_removed_and_changed() {
+        let before = vec![
+            tool("keep", serde_json::json!({"type": "object"})),
+            tool("edit", serde_json::json!({"type": "object"})),
+            tool("drop", serde_json::json!({})),
+        ];
+        let after = vec![
+            tool("keep", serde_json::json!({"type": "object"})),
+            tool(
+                "edit",
+                serde_json::json!({"type": "object", "properties": {"q": {"type": "string"}}}),
+            ),
+            tool("new", serde_json::json!({})),
+        ];
+
+        let diff = McpToolsDiff::between(&fingerprint_tools(&before), &after);
+        assert_eq!(
+            diff.added
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["new"]
+        );
+        assert_eq!(
+            diff.changed
+                .iter()
+                .map(|t| t.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["edit"]
+        );
+        assert_eq!(diff.removed, vec!["drop".to_string()]);
+    }
+
+    #[test]
+    fn test_diff_is_empty_for_identical_lists() {
+        let tools = vec![tool("a", serde_json::json!({"b": 1, "a": 2}))];
+        let diff = McpToolsDiff::between(&fingerprint_tools(&tools), &tools);
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_metadata_change_counts_as_changed() {
+        let before = vec![tool("a", serde_json::json!({}))];
+        let mut after = before.clone();
+        after[0].deferred_loading = Some(true);
+
+        let diff = McpToolsDiff::between(&fingerprint_tools(&before), &after);
+        assert_eq!(diff.changed.len(), 1);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+    }
+}
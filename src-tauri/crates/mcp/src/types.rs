@@ -12,6 +12,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+pub use lime_core::models::mcp_model::{McpReconnectConfig, McpTransportKind};
+
 // ============================================================================
 // 服务器配置和状态
 // ============================================================================
@@ -19,7 +21,11 @@ use std::path::PathBuf;
 /// MCP 服务器配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServerConfig {
-    /// 启动命令
+    /// 传输方式（默认本地 stdio 子进程）
+    #[serde(default)]
+    pub transport: McpTransportKind,
+    /// 启动命令（远程传输时为空）
+    #[serde(default)]
     pub command: String,
     /// 命令参数
     #[serde(default)]
@@ -32,6 +38,18 @@ pub struct McpServerConfig {
     /// 超时时间（秒）
     #[serde(default = "default_timeout")]
     pub timeout: u64,
+    /// 解释器路径（如指定 node / python 可执行文件），用于补全 PATH 与替换同类启动命令
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interpreter: Option<String>,
+    /// 远程服务器地址（Streamable HTTP / SSE）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// 远程请求附加的 HTTP 头（如 Authorization），值支持 `${VAR}` 引用环境变量
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// 远程服务器断线重连策略
+    #[serde(default)]
+    pub reconnect: McpReconnectConfig,
 }
 
 fn default_timeout() -> u64 {
@@ -59,6 +77,69 @@ impl McpServerConfig {
 
         Some(PathBuf::from(cleaned))
     }
+
+    /// 获取清洗后的解释器路径（展开 `~`）
+    pub fn interpreter_path(&self) -> Option<PathBuf> {
+        let interpreter = self.interpreter.as_deref()?.trim();
+        if interpreter.is_empty() {
+            return None;
+        }
+
+        if interpreter.starts_with("~/") || interpreter.starts_with("~\\") {
+            if let Some(home) = dirs::home_dir() {
+                return Some(home.join(&interpreter[2..]));
+            }
+        }
+
+        Some(PathBuf::from(interpreter))
+    }
+
+    /// 是否为远程服务器（Streamable HTTP / SSE）
+    pub fn is_remote(&self) -> bool {
+        self.transport.is_remote()
+    }
+
+    /// 获取清洗后的远程服务器地址
+    pub fn remote_url(&self) -> Option<&str> {
+        let url = self.url.as_deref()?.trim();
+        (!url.is_empty()).then_some(url)
+    }
+
+    /// 展开请求头中的 `${VAR}` 引用
+    ///
+    /// 优先使用配置中的 `env`，其次为进程环境变量；未定义的变量展开为空串。
+    pub fn resolved_headers(&self) -> Vec<(String, String)> {
+        let mut headers: Vec<(String, String)> = self
+            .headers
+            .iter()
+            .filter(|(name, _)| !name.trim().is_empty())
+            .map(|(name, value)| (name.trim().to_string(), self.expand_env_refs(value)))
+            .collect();
+        headers.sort();
+        headers
+    }
+
+    fn expand_env_refs(&self, value: &str) -> String {
+        let mut result = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find("${") {
+            let Some(end) = rest[start + 2..].find('}') else {
+                break;
+            };
+            result.push_str(&rest[..start]);
+            let name = &rest[start + 2..start + 2 + end];
+            let resolved = self
+                .env
+                .get(name)
+                .cloned()
+                .or_else(|| std::env::var(name).ok())
+                .unwrap_or_default();
+            result.push_str(&resolved);
+            rest = &rest[start + 3 + end..];
+        }
+        result.push_str(rest);
+        result
+    }
 }
 
 /// MCP 服务器信息（包含运行状态）
@@ -144,6 +225,67 @@ pub enum McpContent {
     },
 }
 
+impl McpContent {
+    /// 是否为图片（音频也以 Image 变体承载，按 MIME 区分）
+    pub fn is_image(&self) -> bool {
+        matches!(self, McpContent::Image { mime_type, .. } if mime_type.starts_with("image/"))
+    }
+
+    /// 文本表示，图片等二进制内容以占位文本代替
+    pub fn to_text(&self) -> String {
+        match self {
+            McpContent::Text { text } => text.clone(),
+            McpContent::Image { mime_type, .. } if self.is_image() => {
+                format!("[图片: {mime_type}]")
+            }
+            McpContent::Image { mime_type, .. } => format!("[媒体: {mime_type}]"),
+            McpContent::Resource { uri, text, .. } => match text {
+                Some(text) => format!("[资源: {uri}]\n{text}"),
+                None => format!("[资源: {uri}]"),
+            },
+        }
+    }
+
+    /// 转换为 Anthropic content 块，图片保留为 base64 图片块
+    pub fn to_anthropic_block(&self) -> serde_json::Value {
+        match self {
+            McpContent::Image { data, mime_type } if self.is_image() => serde_json::json!({
+                "type": "image",
+                "source": {
+                    "type": "base64",
+                    "media_type": mime_type,
+                    "data": data
+                }
+            }),
+            _ => serde_json::json!({ "type": "text", "text": self.to_text() }),
+        }
+    }
+}
+
+impl McpToolResult {
+    /// 结果中是否包含图片
+    pub fn has_images(&self) -> bool {
+        self.content.iter().any(McpContent::is_image)
+    }
+
+    /// 合并为纯文本，供不支持图片的调用方使用
+    pub fn text_output(&self) -> String {
+        self.content
+            .iter()
+            .map(McpContent::to_text)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// 转换为 Anthropic `tool_result` 的 content 数组
+    pub fn to_anthropic_content(&self) -> Vec<serde_json::Value> {
+        self.content
+            .iter()
+            .map(McpContent::to_anthropic_block)
+            .collect()
+    }
+}
+
 // ============================================================================
 // 提示词类型
 // ============================================================================
@@ -224,6 +366,9 @@ pub enum McpError {
     #[error("MCP 连接失败: {0}")]
     ConnectionFailed(String),
 
+    #[error("MCP 连接已断开: {0}")]
+    TransportClosed(String),
+
     #[error("工具不存在: {0}")]
     ToolNotFound(String),
 
@@ -262,12 +407,29 @@ pub struct McpServerStoppedPayload {
 pub struct McpServerErrorPayload {
     pub server_name: String,
     pub error: String,
+    /// 最近的 stderr 日志行（用于快速排查）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub recent_logs: Vec<String>,
 }
 
 /// 工具列表更新事件
+///
+/// 默认为增量更新：仅携带 `added` / `removed` / `changed`。
+/// `full_resync` 为 true 时 `tools` 为完整列表，前端应整体替换。
 #[derive(Debug, Clone, Serialize)]
 pub struct McpToolsUpdatedPayload {
+    /// 单调递增的版本号，前端据此发现漏收的事件
+    pub revision: u64,
+    /// 是否为全量同步
+    pub full_resync: bool,
+    /// 完整工具列表（仅全量同步时非空）
     pub tools: Vec<McpToolDefinition>,
+    /// 新增的工具
+    pub added: Vec<McpToolDefinition>,
+    /// 移除的工具名
+    pub removed: Vec<String>,
+    /// 定义发生变化的工具
+    pub changed: Vec<McpToolDefinition>,
 }
 
 // ============================================================================
@@ -284,20 +446,65 @@ pub type McpManagerState = Arc<Mutex<super::manager::McpClientManager>>;
 
 #[cfg(test)]
 mod tests {
-    use super::McpServerConfig;
+    use super::{McpContent, McpServerConfig, McpToolResult, McpTransportKind};
     use std::collections::HashMap;
     use std::path::PathBuf;
 
     fn sample_config(cwd: Option<String>) -> McpServerConfig {
         McpServerConfig {
+            transport: McpTransportKind::Stdio,
             command: "npx".to_string(),
             args: vec!["-y".to_string(), "some-server".to_string()],
             env: HashMap::new(),
             cwd,
             timeout: 30,
+            interpreter: None,
+            url: None,
+            headers: HashMap::new(),
+            reconnect: Default::default(),
         }
     }
 
+    #[test]
+    fn remote_config_should_deserialize_without_command() {
+        let config: McpServerConfig = serde_json::from_value(serde_json::json!({
+            "transport": "streamable_http",
+            "url": " https://mcp.example.com/mcp ",
+            "headers": { "Authorization": "Bearer ${MCP_TOKEN}" },
+            "env": { "MCP_TOKEN": "secret" },
+            "reconnect": { "max_attempts": 5 }
+        }))
+        .unwrap();
+
+        assert!(config.is_remote());
+        assert!(config.command.is_empty());
+        assert_eq!(config.remote_url(), Some("https://mcp.example.com/mcp"));
+        assert_eq!(
+            config.resolved_headers(),
+            vec![("Authorization".to_string(), "Bearer secret".to_string())]
+        );
+        assert_eq!(config.reconnect.max_attempts, 5);
+        assert_eq!(config.reconnect.initial_delay_ms, 500);
+
+        let stdio: McpServerConfig =
+            serde_json::from_value(serde_json::json!({ "command": "npx" })).unwrap();
+        assert_eq!(stdio.transport, McpTransportKind::Stdio);
+        assert!(!stdio.is_remote());
+    }
+
+    #[test]
+    fn reconnect_delay_should_back_off_exponentially() {
+        let reconnect = super::McpReconnectConfig {
+            max_attempts: 5,
+            initial_delay_ms: 500,
+            max_delay_ms: 3_000,
+        };
+        assert_eq!(reconnect.delay_for_attempt(1).as_millis(), 500);
+        assert_eq!(reconnect.delay_for_attempt(2).as_millis(), 1_000);
+        assert_eq!(reconnect.delay_for_attempt(3).as_millis(), 2_000);
+        assert_eq!(reconnect.delay_for_attempt(4).as_millis(), 3_000);
+    }
+
     #[test]
     fn sanitized_cwd_should_strip_nul_suffix() {
         let config = sample_config(Some(" /tmp/demo\0ignored ".to_string()));
@@ -309,4 +516,34 @@ mod tests {
         let config = sample_config(Some(" \0 ".to_string()));
         assert!(config.sanitized_cwd().is_none());
     }
+
+    #[test]
+    fn tool_result_should_keep_images_as_anthropic_blocks() {
+        let result = McpToolResult {
+            content: vec![
+                McpContent::Text {
+                    text: "screenshot".to_string(),
+                },
+                McpContent::Image {
+                    data: "AAAA".to_string(),
+                    mime_type: "image/png".to_string(),
+                },
+                McpContent::Image {
+                    data: "BBBB".to_string(),
+                    mime_type: "audio/wav".to_string(),
+                },
+            ],
+            is_error: false,
+        };
+
+        assert!(result.has_images());
+        let blocks = result.to_anthropic_content();
+        assert_eq!(blocks[1]["type"], "image");
+        assert_eq!(blocks[1]["source"]["media_type"], "image/png");
+        assert_eq!(blocks[2]["type"], "text");
+        assert_eq!(
+            result.text_output(),
+            "screenshot\n[图片: image/png]\n[媒体: audio/wav]"
+        );
+    }
 }
@@ -0,0 +1,475 @@
+//! 上下文窗口修剪
+//!
+//! 组装后的请求超过目标模型上下文窗口时，在分发给 Provider 之前按策略修剪消息：
+//!
+//! - `drop_oldest`：从最旧的非 system 消息开始丢弃
+//! - `drop_middle`：保留首条消息（通常是任务描述）与最近的消息，丢弃中间部分
+//! - `summarize_oldest`：丢弃最旧的消息，并生成一段本地摘录说明（不调用 LLM）。
+//!   说明明确标注为网关生成，由调用方放入 system 提示词，不会以对话消息的形式插入，
+//!   也不会伪造 assistant 回复
+//!
+//! system 消息与最后一条消息始终保留；修剪边界上失去对应调用的工具结果会一并移除。
+//! Token 计数通过 [`TokenCounter`] 抽象，默认使用启发式估算，可替换为真实 tokenizer。
+
+use crate::conversation_summarizer::estimate_tokens;
+pub use lime_core::config::ContextTrimStrategy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// 每条消息的结构开销（role、分隔符等）
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+/// drop_middle 保留的开头消息数
+const DROP_MIDDLE_HEAD_MESSAGES: usize = 1;
+/// 摘要中最多列出的消息条数
+const SUMMARY_MAX_POINTS: usize = 12;
+/// 摘要中每条消息保留的最大字符数
+const SUMMARY_POINT_MAX_CHARS: usize = 120;
+
+/// Token 计数器
+pub trait TokenCounter: Send + Sync {
+    /// 计算文本的 token 数
+    fn count_text(&self, text: &str) -> usize;
+
+    /// 计算单条消息的 token 数
+    fn count_message(&self, message: &Value) -> usize {
+        self.count_text(&message.to_string()) + MESSAGE_OVERHEAD_TOKENS
+    }
+}
+
+/// 启发式 Token 计数器（中文约 1.5 token/字，其余约 4 字节/token）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicTokenCounter;
+
+impl TokenCounter for HeuristicTokenCounter {
+    fn count_text(&self, text: &str) -> usize {
+        estimate_tokens(text)
+    }
+}
+
+/// 修剪报告（写入请求元数据）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextTrimReport {
+    /// 使用的策略
+    pub strategy: ContextTrimStrategy,
+    /// 消息可用的 token 预算
+    pub budget_tokens: usize,
+    /// 修剪前的消息 token 数
+    pub tokens_before: usize,
+    /// 修剪后的消息 token 数
+    pub tokens_after: usize,
+    /// 被移除的消息数（含被摘要的消息）
+    pub removed_count: usize,
+    /// 被压缩进摘要的消息数
+    pub summarized_count: usize,
+    /// 修剪后是否仍超出预算（可修剪的消息已耗尽）
+    pub still_over_budget: bool,
+}
+
+/// 修剪结果
+#[derive(Debug, Clone)]
+pub struct ContextTrimOutcome {
+    /// 修剪后的消息
+    pub messages: Vec<Value>,
+    /// 被移除消息的摘录说明（仅 `summarize_oldest`），调用方应将其放入 system 提示词
+    pub summary: Option<String>,
+    /// 修剪报告，未修剪时为 `None`
+    pub report: Option<ContextTrimReport>,
+}
+
+/// 上下文窗口修剪器
+#[derive(Clone)]
+pub struct ContextTrimmer {
+    counter: Arc<dyn TokenCounter>,
+}
+
+impl Default for ContextTrimmer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ContextTrimmer {
+    /// 使用启发式 Token 计数器创建修剪器
+    pub fn new() -> Self {
+        Self::with_counter(Arc::new(HeuristicTokenCounter))
+    }
+
+    /// 使用自定义 Token 计数器创建修剪器
+    pub fn with_counter(counter: Arc<dyn TokenCounter>) -> Self {
+        Self { counter }
+    }
+
+    /// 计算消息列表的 token 数
+    pub fn count_tokens(&self, messages: &[Value]) -> usize {
+        messages.iter().map(|m| self.counter.count_message(m)).sum()
+    }
+
+    /// 计算文本的 token 数
+    pub fn count_text(&self, text: &str) -> usize {
+        self.counter.count_text(text)
+    }
+
+    /// 将消息修剪到 `budget_tokens` 以内
+    ///
+    /// 兼容 Anthropic 和 OpenAI 消息格式（都使用 "role" 字段）
+    pub fn trim(
+        &self,
+        messages: Vec<Value>,
+        budget_tokens: usize,
+        strategy: ContextTrimStrategy,
+    ) -> ContextTrimOutcome {
+        let tokens_before = self.count_tokens(&messages);
+        if tokens_before <= budget_tokens {
+            return ContextTrimOutcome {
+                messages,
+                summary: None,
+                report: None,
+            };
+        }
+
+        let (mut result, mut conversation): (Vec<_>, Vec<_>) =
+            messages.into_iter().partition(is_system_message);
+        let head_len = match strategy {
+            ContextTrimStrategy::DropMiddle => DROP_MIDDLE_HEAD_MESSAGES.min(conversation.len()),
+            _ => 0,
+        };
+        // 只有 drop_middle 保留了开头的消息，其余策略修剪后不能以 assistant 消息开头
+        let allow_assistant_boundary = strategy == ContextTrimStrategy::DropMiddle;
+
+        let mut tokens = tokens_before;
+        let mut removed: Vec<Value> = Vec::new();
+        loop {
+            let summary_tokens = match strategy {
+                ContextTrimStrategy::SummarizeOldest => self.count_summary(&removed),
+                _ => 0,
+            };
+            // 始终保留最后一条消息（当前轮次）
+            if tokens + summary_tokens <= budget_tokens || conversation.len() <= head_len + 1 {
+                break;
+            }
+            let message = conversation.remove(head_len);
+            tokens -= self.counter.count_message(&message);
+            removed.push(message);
+        }
+
+        // 修剪边界上失去对应调用的工具结果一并移除
+        while conversation.len() > head_len + 1
+            && is_dangling_boundary(&conversation[head_len], allow_assistant_boundary)
+        {
+            let message = conversation.remove(head_len);
+            tokens -= self.counter.count_message(&message);
+            removed.push(message);
+        }
+
+        if removed.is_empty() {
+            result.extend(conversation);
+            return ContextTrimOutcome {
+                messages: result,
+                summary: None,
+                report: None,
+            };
+        }
+
+        let mut summarized_count = 0;
+        let mut summary = None;
+        if strategy == ContextTrimStrategy::SummarizeOldest {
+            tokens += self.count_summary(&removed);
+            summarized_count = removed.len();
+            summary = Some(build_summary(&removed));
+        }
+        result.extend(conversation);
+
+        ContextTrimOutcome {
+            messages: result,
+            summary,
+            report: Some(ContextTrimReport {
+                strategy,
+                budget_tokens,
+                tokens_before,
+                tokens_after: tokens,
+                removed_count: removed.len(),
+                summarized_count,
+                still_over_budget: tokens > budget_tokens,
+            }),
+        }
+    }
+
+    /// 摘录说明占用的 token 数（按一条 system 消息计）
+    fn count_summary(&self, removed: &[Value]) -> usize {
+        if removed.is_empty() {
+            return 0;
+        }
+        self.counter.count_message(&serde_json::json!({
+            "role": "system",
+            "content": build_summary(removed)
+        }))
+    }
+}
+
+/// 将摘录说明作为 system 消息插入 OpenAI 消息列表（位于已有 system 消息之后）
+pub fn insert_summary_system_message(messages: &mut Vec<Value>, summary: &str) {
+    let index = messages
+        .iter()
+        .take_while(|message| is_system_message(message))
+        .count();
+    messages.insert(
+        index,
+        serde_json::json!({ "role": "system", "content": summary }),
+    );
+}
+
+/// 将摘录说明追加到 Anthropic 请求的 `system` 字段（字符串或文本块数组）
+pub fn append_summary_to_system(system: &mut Option<Value>, summary: &str) {
+    match system {
+        Some(Value::String(text)) if !text.is_empty() => {
+            text.push_str("\n\n");
+            text.push_str(summary);
+        }
+        Some(Value::Array(blocks)) => {
+            blocks.push(serde_json::json!({ "type": "text", "text": summary }));
+        }
+        _ => *system = Some(Value::String(summary.to_string())),
+    }
+}
+
+fn message_role(message: &Value) -> Option<&str> {
+    message.get("role").and_then(|r| r.as_str())
+}
+
+fn is_system_message(message: &Value) -> bool {
+    message_role(message) == Some("system")
+}
+
+/// 修剪后不能作为对话开头的消息：
+/// OpenAI `tool` 消息、仅含 `tool_result` 的 Anthropic user 消息，以及（按需）assistant 消息
+fn is_dangling_boundary(message: &Value, allow_assistant: bool) -> bool {
+    match message_role(message) {
+        Some("tool") => true,
+        Some("assistant") => !allow_assistant,
+        Some("user") => message
+            .get("content")
+            .and_then(|c| c.as_array())
+            .is_some_and(|blocks| {
+                !blocks.is_empty()
+                    && blocks.iter().all(|block| {
+                        block.get("type").and_then(|t| t.as_str()) == Some("tool_result")
+                    })
+            }),
+        _ => false,
+    }
+}
+
+/// 生成被移除消息的本地摘录说明（每条消息取开头若干字符）
+fn build_summary(removed: &[Value]) -> String {
+    let mut lines: Vec<String> = removed
+        .iter()
+        .filter_map(|message| {
+            let text = message_text(message);
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if text.is_empty() {
+                return None;
+            }
+            let role = message_role(message).unwrap_or("unknown");
+            let mut point: String = text.chars().take(SUMMARY_POINT_MAX_CHARS).collect();
+            if point.len() < text.len() {
+                point.push('…');
+            }
+            Some(format!("- {role}: {point}"))
+        })
+        .collect();
+    // 保留最近的要点
+    if lines.len() > SUMMARY_MAX_POINTS {
+        let omitted = lines.len() - SUMMARY_MAX_POINTS;
+        lines.drain(..omitted);
+        lines.insert(0, format!("- （另有 {omitted} 条更早的消息已省略）"));
+    }
+
+    format!(
+        "[网关上下文说明 - 由代理本地生成，并非用户或助手的发言]\n         因上下文窗口限制，已省略 {} 条早期消息，以下仅为各条消息的开头摘录：\n\n{}",
+        removed.len(),
+        lines.join("\n")
+    )
+}
+
+/// 提取消息中的文本（含 Anthropic 文本块与工具结果）
+fn message_text(message: &Value) -> String {
+    match message.get("content") {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|block| match block.get("type").and_then(|t| t.as_str()) {
+                Some("text") => block.get("text").and_then(|t| t.as_str()).map(String::from),
+                Some("tool_use") => block
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .map(|name| format!("[调用工具 {name}]")),
+                Some("tool_result") => block
+                    .get("content")
+                    .and_then(|c| c.as_str())
+                    .map(String::from),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        _ => message
+            .get("tool_calls")
+            .and_then(|t| t.as_array())
+            .map(|calls| {
+                calls
+                    .iter()
+                    .filter_map(|call| call.pointer("/function/name").and_then(|n| n.as_str()))
+                    .map(|name| format!("[调用工具 {name}]"))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 每条消息固定 10 token，便于断言
+    struct FixedCounter;
+
+    impl TokenCounter for FixedCounter {
+        fn count_text(&self, _text: &str) -> usize {
+            10
+        }
+
+        fn count_message(&self, _message: &Value) -> usize {
+            10
+        }
+    }
+
+    fn conversation(turns: usize) -> Vec<Value> {
+        let mut messages = vec![json!({"role": "system", "content": "sys"})];
+        for i in 0..turns {
+            messages.push(json!({"role": "user", "content": format!("question {i}")}));
+            messages.push(json!({"role": "assistant", "content": format!("answer {i}")}));
+        }
+        messages.push(json!({"role": "user", "content": "latest"}));
+        messages
+    }
+
+    fn contents(messages: &[Value]) -> Vec<&str> {
+        messages
+            .iter()
+            .map(|m| m["content"].as_str().unwrap_or_default())
+            .collect()
+    }
+
+    #[test]
+    fn test_within_budget_is_untouched() {
+        let trimmer = ContextTrimmer::with_counter(Arc::new(FixedCounter));
+        let outcome = trimmer.trim(conversation(2), 100, ContextTrimStrategy::DropOldest);
+        assert!(outcome.report.is_none());
+        assert_eq!(outcome.messages.len(), 6);
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_system_and_latest() {
+        let trimmer = ContextTrimmer::with_counter(Arc::new(FixedCounter));
+        let outcome = trimmer.trim(conversation(3), 40, ContextTrimStrategy::DropOldest);
+        assert_eq!(
+            contents(&outcome.messages),
+            vec!["sys", "question 2", "answer 2", "latest"]
+        );
+        let report = outcome.report.unwrap();
+        assert_eq!(report.removed_count, 4);
+        assert_eq!(report.tokens_before, 80);
+        assert_eq!(report.tokens_after, 40);
+        assert!(!report.still_over_budget);
+    }
+
+    #[test]
+    fn test_drop_middle_keeps_first_message() {
+        let trimmer = ContextTrimmer::with_counter(Arc::new(FixedCounter));
+        let outcome = trimmer.trim(conversation(3), 50, ContextTrimStrategy::DropMiddle);
+        assert_eq!(
+            contents(&outcome.messages),
+            vec!["sys", "question 0", "question 2", "answer 2", "latest"]
+        );
+        assert_eq!(outcome.report.unwrap().removed_count, 3);
+    }
+
+    #[test]
+    fn test_summarize_oldest_returns_marked_summary() {
+        let trimmer = ContextTrimmer::with_counter(Arc::new(FixedCounter));
+        let outcome = trimmer.trim(conversation(3), 50, ContextTrimStrategy::SummarizeOldest);
+        // 摘录说明不作为对话消息插入
+        assert_eq!(
+            contents(&outcome.messages),
+            vec!["sys", "question 2", "answer 2", "latest"]
+        );
+        let summary = outcome.summary.unwrap();
+        assert!(summary.starts_with("[网关上下文说明"));
+        assert!(summary.contains("4 条早期消息"));
+        assert!(summary.contains("- user: question 0"));
+        assert!(summary.contains("- assistant: answer 1"));
+        let report = outcome.report.unwrap();
+        assert_eq!(report.summarized_count, 4);
+        assert_eq!(report.tokens_after, 50);
+    }
+
+    #[test]
+    fn test_summarize_oldest_never_starts_with_assistant() {
+        let trimmer = ContextTrimmer::with_counter(Arc::new(FixedCounter));
+        let outcome = trimmer.trim(conversation(3), 60, ContextTrimStrategy::SummarizeOldest);
+        let roles: Vec<&str> = outcome
+            .messages
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, vec!["system", "user", "assistant", "user"]);
+        assert_eq!(contents(&outcome.messages)[1], "question 2");
+        assert_eq!(outcome.report.unwrap().summarized_count, 4);
+    }
+
+    #[test]
+    fn test_summary_placement_helpers() {
+        let mut messages = conversation(0);
+        insert_summary_system_message(&mut messages, "note");
+        assert_eq!(contents(&messages), vec!["sys", "note", "latest"]);
+        assert_eq!(messages[1]["role"], "system");
+
+        let mut system = None;
+        append_summary_to_system(&mut system, "note");
+        assert_eq!(system, Some(json!("note")));
+        let mut system = Some(json!("client"));
+        append_summary_to_system(&mut system, "note");
+        assert_eq!(system, Some(json!("client\n\nnote")));
+        let mut system = Some(json!([{ "type": "text", "text": "client" }]));
+        append_summary_to_system(&mut system, "note");
+        assert_eq!(
+            system.unwrap()[1],
+            json!({ "type": "text", "text": "note" })
+        );
+    }
+
+    #[test]
+    fn test_drops_orphaned_tool_results() {
+        let trimmer = ContextTrimmer::with_counter(Arc::new(FixedCounter));
+        let messages = vec![
+            json!({"role": "user", "content": "run it"}),
+            json!({"role": "assistant", "content": null, "tool_calls": [{"id": "c1", "function": {"name": "bash"}}]}),
+            json!({"role": "tool", "tool_call_id": "c1", "content": "ok"}),
+            json!({"role": "user", "content": [{"type": "tool_result", "tool_use_id": "t1", "content": "done"}]}),
+            json!({"role": "user", "content": "next"}),
+        ];
+        let outcome = trimmer.trim(messages, 30, ContextTrimStrategy::DropOldest);
+        assert_eq!(contents(&outcome.messages), vec!["next"]);
+        assert_eq!(outcome.report.unwrap().removed_count, 4);
+    }
+
+    #[test]
+    fn test_reports_when_still_over_budget() {
+        let trimmer = ContextTrimmer::with_counter(Arc::new(FixedCounter));
+        let outcome = trimmer.trim(conversation(1), 5, ContextTrimStrategy::DropOldest);
+        assert_eq!(contents(&outcome.messages), vec!["sys", "latest"]);
+        assert!(outcome.report.unwrap().still_over_budget);
+    }
+}
@@ -0,0 +1,236 @@
+//! Provider 故障转移链
+//!
+//! 按 `routing.failover_chains` 为请求模型生成有序的跳列表，并维护各跳的冷却状态：
+//! - 跳失败（见 [`is_failover_status`]）后进入冷却，冷却期内排到链尾，仍作为最后手段
+//! - 跳成功后清除冷却
+//! - 每一跳的结果以 [`FailoverHopEvent`] 记录，描述最终由哪一跳服务请求
+
+use lime_core::config::{FailoverChainSettings, FailoverHop};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 请求上下文中记录故障转移事件的元数据键
+pub const FAILOVER_METADATA_KEY: &str = "failover_chain";
+
+/// 服务请求的跳响应头（如 `openrouter; hop=3/3`）
+pub const FAILOVER_HOP_HEADER: &str = "x-lime-failover-hop";
+
+/// 是否应切换到下一跳：限流、超时、服务端错误与认证失败
+///
+/// 其余 4xx 通常是请求本身的问题，换 Provider 也不会成功。
+pub fn is_failover_status(status: u16) -> bool {
+    matches!(status, 401 | 403 | 408 | 429) || status >= 500
+}
+
+/// 计划中的一跳
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedHop {
+    /// 在配置链中的位置（从 1 开始）
+    pub position: usize,
+    pub hop: FailoverHop,
+    /// 是否处于冷却期（已排到链尾）
+    pub cooling_down: bool,
+}
+
+/// 单个请求的故障转移计划
+#[derive(Debug, Clone, PartialEq)]
+pub struct FailoverPlan {
+    /// 匹配的模型系列
+    pub model_pattern: String,
+    /// 配置链长度
+    pub chain_len: usize,
+    /// 按尝试顺序排列的跳
+    pub hops: Vec<PlannedHop>,
+}
+
+/// 跳的结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailoverHopOutcome {
+    /// 由该跳服务请求
+    Served,
+    /// 请求失败，切换到下一跳
+    Failed,
+    /// 没有可用凭证，跳过
+    NoCredential,
+}
+
+/// 故障转移事件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailoverHopEvent {
+    pub position: usize,
+    pub provider: String,
+    pub outcome: FailoverHopOutcome,
+    /// 本跳尝试次数（含重试）
+    pub attempts: u32,
+    /// 本跳最后一次响应的状态码
+    pub status: Option<u16>,
+    pub cooling_down: bool,
+}
+
+/// 故障转移链管理器
+#[derive(Debug, Default)]
+pub struct FailoverChainManager {
+    settings: RwLock<FailoverChainSettings>,
+    /// (模型系列, Provider) -> 冷却截止时间
+    cooldowns: Mutex<HashMap<(String, String), Instant>>,
+}
+
+impl FailoverChainManager {
+    pub fn new(settings: FailoverChainSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            cooldowns: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 热更新配置（清空冷却状态）
+    pub fn reload(&self, settings: &FailoverChainSettings) {
+        *self.settings.write() = settings.clone();
+        self.cooldowns.lock().clear();
+    }
+
+    /// 为模型生成故障转移计划（未启用或没有匹配的链时返回 None）
+    pub fn plan(&self, model: &str) -> Option<FailoverPlan> {
+        self.plan_at(model, Instant::now())
+    }
+
+    fn plan_at(&self, model: &str, now: Instant) -> Option<FailoverPlan> {
+        let settings = self.settings.read();
+        let chain = settings.chain_for(model)?;
+        let mut cooldowns = self.cooldowns.lock();
+        cooldowns.retain(|_, until| *until > now);
+
+        let (ready, cooling): (Vec<_>, Vec<_>) = chain
+            .hops
+            .iter()
+            .enumerate()
+            .map(|(index, hop)| {
+                let key = (chain.model_pattern.clone(), hop.provider.to_lowercase());
+                PlannedHop {
+                    position: index + 1,
+                    hop: hop.clone(),
+                    cooling_down: cooldowns.contains_key(&key),
+                }
+            })
+            .partition(|planned| !planned.cooling_down);
+
+        Some(FailoverPlan {
+            model_pattern: chain.model_pattern.clone(),
+            chain_len: chain.hops.len(),
+            hops: ready.into_iter().chain(cooling).collect(),
+        })
+    }
+
+    /// 记录跳失败，按跳的冷却时间进入冷却
+    pub fn mark_failure(&self, plan: &FailoverPlan, hop: &PlannedHop) {
+        self.mark_failure_at(plan, hop, Instant::now());
+    }
+
+    fn mark_failure_at(&self, plan: &FailoverPlan, hop: &PlannedHop, now: Instant) {
+        if hop.hop.cooldown_secs == 0 {
+            return;
+        }
+        self.cooldowns.lock().insert(
+            Self::cooldown_key(plan, hop),
+            now + Duration::from_secs(hop.hop.cooldown_secs),
+        );
+    }
+
+    /// 记录跳成功，清除冷却
+    pub fn mark_success(&self, plan: &FailoverPlan, hop: &PlannedHop) {
+        self.cooldowns.lock().remove(&Self::cooldown_key(plan, hop));
+    }
+
+    fn cooldown_key(plan: &FailoverPlan, hop: &PlannedHop) -> (String, String) {
+        (plan.model_pattern.clone(), hop.hop.provider.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::config::FailoverChain;
+
+    fn hop(provider: &str, cooldown_secs: u64) -> FailoverHop {
+        FailoverHop {
+            provider: provider.to_string(),
+            max_retries: 0,
+            cooldown_secs,
+        }
+    }
+
+    fn manager() -> FailoverChainManager {
+        FailoverChainManager::new(FailoverChainSettings {
+            enabled: true,
+            chains: vec![FailoverChain {
+                model_pattern: "claude-*".to_string(),
+                hops: vec![
+                    hop("claude_oauth", 60),
+                    hop("anthropic", 0),
+                    hop("openrouter", 60),
+                ],
+            }],
+        })
+    }
+
+    fn providers(plan: &FailoverPlan) -> Vec<&str> {
+        plan.hops.iter().map(|h| h.hop.provider.as_str()).collect()
+    }
+
+    #[test]
+    fn test_plan_matches_model_family() {
+        let manager = manager();
+        let plan = manager.plan("Claude-Sonnet-4-5").unwrap();
+        assert_eq!(
+            providers(&plan),
+            vec!["claude_oauth", "anthropic", "openrouter"]
+        );
+        assert_eq!(plan.chain_len, 3);
+        assert!(manager.plan("gpt-4o").is_none());
+
+        manager.reload(&FailoverChainSettings::default());
+        assert!(manager.plan("claude-sonnet-4-5").is_none());
+    }
+
+    #[test]
+    fn test_failed_hop_cools_down_to_chain_tail() {
+        let manager = manager();
+        let now = Instant::now();
+        let plan = manager.plan_at("claude-opus-4", now).unwrap();
+        manager.mark_failure_at(&plan, &plan.hops[0], now);
+        // 冷却时间为 0 的跳不冷却
+        manager.mark_failure_at(&plan, &plan.hops[1], now);
+
+        let cooling = manager.plan_at("claude-opus-4", now).unwrap();
+        assert_eq!(
+            providers(&cooling),
+            vec!["anthropic", "openrouter", "claude_oauth"]
+        );
+        assert_eq!(cooling.hops[2].position, 1);
+        assert!(cooling.hops[2].cooling_down);
+
+        let expired = manager
+            .plan_at("claude-opus-4", now + Duration::from_secs(61))
+            .unwrap();
+        assert_eq!(providers(&expired)[0], "claude_oauth");
+
+        manager.mark_failure_at(&plan, &plan.hops[0], now);
+        manager.mark_success(&plan, &plan.hops[0]);
+        assert_eq!(
+            providers(&manager.plan_at("claude-opus-4", now).unwrap())[0],
+            "claude_oauth"
+        );
+    }
+
+    #[test]
+    fn test_is_failover_status() {
+        assert!(is_failover_status(429));
+        assert!(is_failover_status(503));
+        assert!(is_failover_status(401));
+        assert!(!is_failover_status(400));
+        assert!(!is_failover_status(200));
+    }
+}
@@ -9,9 +9,12 @@
 #![allow(clippy::too_many_arguments)]
 //!
 //! - `steps` - 管道步骤（认证、注入、路由、插件、Provider、遥测）
+//! - `failover_chain` - 按模型系列配置的 Provider 故障转移链
 
+pub mod context_trimmer;
 pub mod conversation_manager;
 pub mod conversation_summarizer;
+pub mod failover_chain;
 pub mod processor;
 pub mod steps;
 
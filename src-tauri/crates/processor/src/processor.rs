@@ -54,6 +54,12 @@ pub struct RequestProcessor {
     pub hint_router: Arc<RwLock<lime_core::router::HintRouter>>,
     /// 对话修剪器
     pub conversation_trimmer: Arc<crate::conversation_manager::ConversationTrimmer>,
+    /// 上下文窗口修剪器（超出模型上下文时按策略修剪）
+    pub context_trimmer: Arc<crate::context_trimmer::ContextTrimmer>,
+    /// 按模型系列配置的 Provider 故障转移链
+    pub failover_chains: Arc<crate::failover_chain::FailoverChainManager>,
+    /// Provider 等价端点切换（DNS / 连接 / TLS 失败时换端点）
+    pub endpoint_fallback: Arc<lime_infra::resilience::EndpointFallbackManager>,
 }
 
 impl RequestProcessor {
@@ -86,6 +92,9 @@ impl RequestProcessor {
             conversation_trimmer: Arc::new(crate::conversation_manager::ConversationTrimmer::new(
                 crate::conversation_manager::TrimConfig::default(),
             )),
+            context_trimmer: Arc::new(crate::context_trimmer::ContextTrimmer::new()),
+            failover_chains: Arc::new(crate::failover_chain::FailoverChainManager::default()),
+            endpoint_fallback: Arc::new(lime_infra::resilience::EndpointFallbackManager::default()),
         }
     }
 
@@ -107,6 +116,9 @@ impl RequestProcessor {
             conversation_trimmer: Arc::new(crate::conversation_manager::ConversationTrimmer::new(
                 crate::conversation_manager::TrimConfig::default(),
             )),
+            context_trimmer: Arc::new(crate::context_trimmer::ContextTrimmer::new()),
+            failover_chains: Arc::new(crate::failover_chain::FailoverChainManager::default()),
+            endpoint_fallback: Arc::new(lime_infra::resilience::EndpointFallbackManager::default()),
         }
     }
 
@@ -141,6 +153,9 @@ impl RequestProcessor {
             conversation_trimmer: Arc::new(crate::conversation_manager::ConversationTrimmer::new(
                 crate::conversation_manager::TrimConfig::default(),
             )),
+            context_trimmer: Arc::new(crate::context_trimmer::ContextTrimmer::new()),
+            failover_chains: Arc::new(crate::failover_chain::FailoverChainManager::default()),
+            endpoint_fallback: Arc::new(lime_infra::resilience::EndpointFallbackManager::default()),
         }
     }
 
@@ -3,6 +3,8 @@ use lime_core::models::anthropic::*;
 use lime_core::models::openai::*;
 use uuid::Uuid;
 
+use super::tool_result_image::ToolImage;
+
 /// 将 Anthropic MessagesRequest 转换为 OpenAI ChatCompletionRequest
 pub fn convert_anthropic_to_openai(request: &AnthropicMessagesRequest) -> ChatCompletionRequest {
     let mut openai_messages: Vec<ChatMessage> = Vec::new();
@@ -90,7 +92,7 @@ fn convert_anthropic_message(msg: &AnthropicMessage) -> Vec<ChatMessage> {
         serde_json::Value::Array(parts) => {
             let mut text_parts: Vec<String> = Vec::new();
             let mut tool_calls: Vec<ToolCall> = Vec::new();
-            let mut tool_results: Vec<(String, String)> = Vec::new(); // (tool_use_id, content)
+            let mut tool_results: Vec<(String, MessageContent)> = Vec::new(); // (tool_use_id, content)
 
             for part in parts {
                 let part_type = part.get("type").and_then(|t| t.as_str()).unwrap_or("");
@@ -158,7 +160,7 @@ fn convert_anthropic_message(msg: &AnthropicMessage) -> Vec<ChatMessage> {
                 for (tool_use_id, content) in tool_results {
                     result.push(ChatMessage {
                         role: "tool".to_string(),
-                        content: Some(MessageContent::Text(content)),
+                        content: Some(content),
                         tool_calls: None,
                         tool_call_id: Some(tool_use_id),
                         reasoning_content: None,
@@ -183,22 +185,38 @@ fn convert_anthropic_message(msg: &AnthropicMessage) -> Vec<ChatMessage> {
     result
 }
 
-fn extract_tool_result_content(content: Option<&serde_json::Value>) -> String {
+/// 提取 tool_result 内容
+///
+/// 仅含文本时合并为纯文本；包含图片块时保留为文本 + `image_url`（data URL）多段内容。
+fn extract_tool_result_content(content: Option<&serde_json::Value>) -> MessageContent {
     match content {
-        Some(serde_json::Value::String(s)) => s.clone(),
-        Some(serde_json::Value::Array(arr)) => arr
-            .iter()
-            .filter_map(|item| {
-                if item.get("type") == Some(&serde_json::Value::String("text".to_string())) {
-                    item.get("text")
-                        .and_then(|t| t.as_str())
-                        .map(|s| s.to_string())
-                } else {
-                    None
+        Some(serde_json::Value::String(s)) => MessageContent::Text(s.clone()),
+        Some(serde_json::Value::Array(arr)) => {
+            let mut texts: Vec<String> = Vec::new();
+            let mut parts: Vec<ContentPart> = Vec::new();
+            for item in arr {
+                if let Some(image) = ToolImage::from_anthropic_block(item) {
+                    parts.push(ContentPart::ImageUrl {
+                        image_url: ImageUrl {
+                            url: image.to_data_url(),
+                            detail: None,
+                        },
+                    });
+                } else if item.get("type").and_then(|t| t.as_str()) == Some("text") {
+                    if let Some(text) = item.get("text").and_then(|t| t.as_str()) {
+                        texts.push(text.to_string());
+                        parts.push(ContentPart::Text {
+                            text: text.to_string(),
+                        });
+                    }
                 }
-            })
-            .collect::<Vec<_>>()
-            .join("\n"),
-        _ => String::new(),
+            }
+            if parts.len() == texts.len() {
+                MessageContent::Text(texts.join("\n"))
+            } else {
+                MessageContent::Parts(parts)
+            }
+        }
+        _ => MessageContent::Text(String::new()),
     }
 }
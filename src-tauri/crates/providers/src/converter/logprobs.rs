@@ -0,0 +1,174 @@
+//! Chat Completions `logprobs` / `top_logprobs` / `echo` 透传
+//!
+//! `ChatCompletionRequest` 未建模这些字段，入口从原始请求体解析后在请求作用域内保存，
+//! 由 Provider 调用层决定处理方式：
+//! - OpenAI 兼容上游：写回请求体，响应与流式 chunk 中的 `logprobs` 原样返回
+//! - 其他 Provider：剔除并记录告警，响应头 `x-lime-warning` 说明已忽略
+
+use serde_json::Value;
+use std::future::Future;
+
+/// `top_logprobs` 上限（与 OpenAI API 一致）
+pub const MAX_TOP_LOGPROBS: u8 = 20;
+
+tokio::task_local! {
+    static REQUEST_LOGPROBS: Option<LogprobsOptions>;
+}
+
+/// 请求中的 logprobs 相关选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LogprobsOptions {
+    pub logprobs: bool,
+    pub top_logprobs: Option<u8>,
+    /// 旧版 completions 的 `echo`，部分 OpenAI 兼容服务在 chat 接口上也支持
+    pub echo: bool,
+}
+
+impl LogprobsOptions {
+    /// 从原始请求体解析，未请求任何相关能力时返回 `None`
+    ///
+    /// 仅声明 `top_logprobs` 时视为同时开启 `logprobs`（OpenAI 要求两者同时出现）。
+    pub fn from_request_value(request: &Value) -> Option<Self> {
+        let top_logprobs = request
+            .get("top_logprobs")
+            .and_then(Value::as_u64)
+            .map(|n| n.min(MAX_TOP_LOGPROBS as u64) as u8);
+        let logprobs = request
+            .get("logprobs")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+            || top_logprobs.is_some();
+        let echo = request
+            .get("echo")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+
+        let options = Self {
+            logprobs,
+            top_logprobs,
+            echo,
+        };
+        options.is_requested().then_some(options)
+    }
+
+    pub fn is_requested(&self) -> bool {
+        self.logprobs || self.echo
+    }
+
+    /// 写回上游请求体
+    pub fn apply_to_payload(&self, payload: &mut Value) {
+        let Some(obj) = payload.as_object_mut() else {
+            return;
+        };
+        if self.logprobs {
+            obj.insert("logprobs".to_string(), Value::Bool(true));
+        }
+        if let Some(top) = self.top_logprobs {
+            obj.insert("top_logprobs".to_string(), Value::from(top));
+        }
+        if self.echo {
+            obj.insert("echo".to_string(), Value::Bool(true));
+        }
+    }
+
+    /// 请求的字段名，用于告警
+    pub fn requested_fields(&self) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+        if self.logprobs {
+            fields.push("logprobs");
+        }
+        if self.top_logprobs.is_some() {
+            fields.push("top_logprobs");
+        }
+        if self.echo {
+            fields.push("echo");
+        }
+        fields
+    }
+
+    /// 不支持时返回给客户端的告警文本
+    pub fn unsupported_warning(&self, provider: &str) -> String {
+        format!(
+            "{} not supported by provider {}, ignored",
+            self.requested_fields().join(", "),
+            provider
+        )
+    }
+}
+
+/// 在请求作用域内携带 logprobs 选项执行 `future`
+pub async fn scope_request_logprobs<F: Future>(
+    options: Option<LogprobsOptions>,
+    future: F,
+) -> F::Output {
+    REQUEST_LOGPROBS.scope(options, future).await
+}
+
+/// 当前请求的 logprobs 选项（作用域外返回 `None`）
+pub fn current_request_logprobs() -> Option<LogprobsOptions> {
+    REQUEST_LOGPROBS.try_with(|options| *options).ok().flatten()
+}
+
+/// 在作用域内剔除 logprobs 选项执行 `future`（用于不支持的 Provider）
+pub async fn without_request_logprobs<F: Future>(future: F) -> F::Output {
+    REQUEST_LOGPROBS.scope(None, future).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_request_value() {
+        assert_eq!(
+            LogprobsOptions::from_request_value(&json!({"model": "gpt-4o"})),
+            None
+        );
+        assert_eq!(
+            LogprobsOptions::from_request_value(&json!({"logprobs": false})),
+            None
+        );
+
+        let options =
+            LogprobsOptions::from_request_value(&json!({"top_logprobs": 50, "echo": true}))
+                .unwrap();
+        assert!(options.logprobs);
+        assert_eq!(options.top_logprobs, Some(MAX_TOP_LOGPROBS));
+        assert!(options.echo);
+    }
+
+    #[test]
+    fn test_apply_to_payload() {
+        let options = LogprobsOptions {
+            logprobs: true,
+            top_logprobs: Some(5),
+            echo: false,
+        };
+        let mut payload = json!({"model": "gpt-4o"});
+        options.apply_to_payload(&mut payload);
+        assert_eq!(payload["logprobs"], json!(true));
+        assert_eq!(payload["top_logprobs"], json!(5));
+        assert!(payload.get("echo").is_none());
+        assert_eq!(
+            options.unsupported_warning("claude"),
+            "logprobs, top_logprobs not supported by provider claude, ignored"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_request_scope() {
+        assert_eq!(current_request_logprobs(), None);
+        let options = LogprobsOptions {
+            logprobs: true,
+            ..Default::default()
+        };
+        let inner = scope_request_logprobs(Some(options), async {
+            let scoped = current_request_logprobs();
+            let stripped = without_request_logprobs(async { current_request_logprobs() }).await;
+            (scoped, stripped)
+        })
+        .await;
+        assert_eq!(inner, (Some(options), None));
+    }
+}
@@ -0,0 +1,368 @@
+//! 请求转换损失检测
+//!
+//! 网关的请求模型只保留部分字段（未知字段在反序列化时丢弃），各 Provider 的转换器
+//! 又会丢弃或改写目标协议不支持的参数。本模块根据客户端原始请求体的顶层字段和
+//! 目标协议，列出被丢弃或改写的字段，供严格模式下通过响应头与审计日志告知用户。
+
+use lime_core::ProviderType;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// 客户端请求格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceFormat {
+    /// `/v1/chat/completions`
+    OpenAiChat,
+    /// `/v1/messages`
+    AnthropicMessages,
+}
+
+impl SourceFormat {
+    /// 网关请求模型保留的顶层字段
+    fn retained_fields(self) -> &'static [&'static str] {
+        match self {
+            SourceFormat::OpenAiChat => &[
+                "model",
+                "messages",
+                "stream",
+                "temperature",
+                "max_tokens",
+                "top_p",
+                "tools",
+                "tool_choice",
+                "reasoning_effort",
+                // 入口从原始请求体单独解析（见 `logprobs` 模块与 `StreamOptions`）
+                "stream_options",
+                "logprobs",
+                "top_logprobs",
+                "echo",
+            ],
+            SourceFormat::AnthropicMessages => &[
+                "model",
+                "messages",
+                "system",
+                "stream",
+                "temperature",
+                "max_tokens",
+                "tools",
+                "tool_choice",
+            ],
+        }
+    }
+}
+
+/// 上游目标协议
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TargetProtocol {
+    /// OpenAI Chat Completions 兼容接口
+    OpenAiCompatible,
+    /// Anthropic Messages
+    Anthropic,
+    /// Kiro / CodeWhisperer
+    CodeWhisperer,
+    /// Antigravity（Gemini 格式）
+    Gemini,
+}
+
+impl TargetProtocol {
+    /// 根据实际使用的 Provider 推断目标协议，未知 Provider 视为 OpenAI 兼容
+    pub fn for_provider(provider: &str) -> Self {
+        match provider.parse::<ProviderType>() {
+            Ok(ProviderType::Kiro) => TargetProtocol::CodeWhisperer,
+            Ok(ProviderType::Antigravity) => TargetProtocol::Gemini,
+            Ok(
+                ProviderType::Claude
+                | ProviderType::ClaudeOAuth
+                | ProviderType::Anthropic
+                | ProviderType::AnthropicCompatible,
+            ) => TargetProtocol::Anthropic,
+            _ => TargetProtocol::OpenAiCompatible,
+        }
+    }
+
+    /// 转换器对 OpenAI 格式字段的处理：(字段, 处理方式, 说明)
+    fn openai_field_losses(self) -> &'static [(&'static str, LossKind, &'static str)] {
+        match self {
+            TargetProtocol::OpenAiCompatible => &[],
+            TargetProtocol::Anthropic => &[
+                (
+                    "temperature",
+                    LossKind::Dropped,
+                    "转换为 Anthropic 请求时未携带",
+                ),
+                ("top_p", LossKind::Dropped, "转换为 Anthropic 请求时未携带"),
+                (
+                    "reasoning_effort",
+                    LossKind::Dropped,
+                    "Anthropic 无对应参数",
+                ),
+                (
+                    "tool_choice",
+                    LossKind::Transformed,
+                    "转换为 Anthropic tool_choice 格式",
+                ),
+            ],
+            TargetProtocol::CodeWhisperer => &[
+                (
+                    "temperature",
+                    LossKind::Dropped,
+                    "CodeWhisperer 不支持采样参数",
+                ),
+                ("top_p", LossKind::Dropped, "CodeWhisperer 不支持采样参数"),
+                (
+                    "max_tokens",
+                    LossKind::Dropped,
+                    "CodeWhisperer 不支持输出长度限制",
+                ),
+                (
+                    "reasoning_effort",
+                    LossKind::Dropped,
+                    "CodeWhisperer 无对应参数",
+                ),
+                ("tool_choice", LossKind::Transformed, "转换为工具使用提示"),
+            ],
+            TargetProtocol::Gemini => &[
+                (
+                    "tool_choice",
+                    LossKind::Dropped,
+                    "Gemini 请求未携带工具选择",
+                ),
+                (
+                    "reasoning_effort",
+                    LossKind::Transformed,
+                    "转换为 thinkingConfig",
+                ),
+            ],
+        }
+    }
+}
+
+/// 仅 OpenAI 兼容上游支持的字段，其余协议统一剔除
+const OPENAI_ONLY_FIELDS: &[&str] = &["logprobs", "top_logprobs", "echo"];
+
+/// 字段的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LossKind {
+    /// 字段被丢弃
+    Dropped,
+    /// 字段被改写为目标协议的近似形式
+    Transformed,
+}
+
+impl LossKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LossKind::Dropped => "dropped",
+            LossKind::Transformed => "transformed",
+        }
+    }
+}
+
+/// 单个字段的转换损失
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConversionLoss {
+    /// 客户端请求中的顶层字段名
+    pub field: String,
+    pub kind: LossKind,
+    pub reason: String,
+}
+
+impl fmt::Display for ConversionLoss {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.field, self.kind.as_str())
+    }
+}
+
+/// 检测原始请求体在转发到目标协议时被丢弃或改写的字段
+///
+/// `target` 为空时只报告网关请求模型不支持的字段。结果按字段名排序。
+pub fn detect_request_loss(
+    source: SourceFormat,
+    body: &serde_json::Value,
+    target: Option<TargetProtocol>,
+) -> Vec<ConversionLoss> {
+    let Some(object) = body.as_object() else {
+        return Vec::new();
+    };
+    let retained = source.retained_fields();
+    let mut losses = Vec::new();
+    for (field, value) in object {
+        if value.is_null() {
+            continue;
+        }
+        if !retained.contains(&field.as_str()) {
+            losses.push(ConversionLoss {
+                field: field.clone(),
+                kind: LossKind::Dropped,
+                reason: "网关请求模型不支持该字段".to_string(),
+            });
+            continue;
+        }
+        let Some(target) = target else {
+            continue;
+        };
+        if let Some(loss) = target_loss(source, target, field) {
+            losses.push(loss);
+        }
+    }
+    losses.sort_by(|a, b| a.field.cmp(&b.field));
+    losses
+}
+
+fn target_loss(
+    source: SourceFormat,
+    target: TargetProtocol,
+    field: &str,
+) -> Option<ConversionLoss> {
+    // Anthropic 请求发往 Anthropic 协议时原样透传
+    if source == SourceFormat::AnthropicMessages && target == TargetProtocol::Anthropic {
+        return None;
+    }
+    // Anthropic 请求先转换为 OpenAI 格式：tool_choice 按原格式透传
+    if source == SourceFormat::AnthropicMessages
+        && target == TargetProtocol::OpenAiCompatible
+        && field == "tool_choice"
+    {
+        return Some(ConversionLoss {
+            field: field.to_string(),
+            kind: LossKind::Transformed,
+            reason: "按 Anthropic 格式透传，目标协议可能无法识别".to_string(),
+        });
+    }
+    if target != TargetProtocol::OpenAiCompatible && OPENAI_ONLY_FIELDS.contains(&field) {
+        return Some(ConversionLoss {
+            field: field.to_string(),
+            kind: LossKind::Dropped,
+            reason: "仅 OpenAI 兼容上游支持".to_string(),
+        });
+    }
+    target
+        .openai_field_losses()
+        .iter()
+        .find(|(name, _, _)| *name == field)
+        .map(|(_, kind, reason)| ConversionLoss {
+            field: field.to_string(),
+            kind: *kind,
+            reason: reason.to_string(),
+        })
+}
+
+/// 组合为响应头的值，如 `seed=dropped, tool_choice=transformed`
+pub fn format_loss_header(losses: &[ConversionLoss]) -> String {
+    losses
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(losses: &[ConversionLoss]) -> Vec<(&str, LossKind)> {
+        losses
+            .iter()
+            .map(|loss| (loss.field.as_str(), loss.kind))
+            .collect()
+    }
+
+    #[test]
+    fn test_openai_request_losses_by_target() {
+        let body = json!({
+            "model": "gpt-4o",
+            "messages": [],
+            "temperature": 0.2,
+            "tool_choice": "auto",
+            "seed": 7,
+            "logprobs": true,
+            "frequency_penalty": 0.5,
+            "response_format": { "type": "json_object" },
+            "user": null,
+        });
+
+        assert_eq!(
+            fields(&detect_request_loss(SourceFormat::OpenAiChat, &body, None)),
+            vec![
+                ("frequency_penalty", LossKind::Dropped),
+                ("response_format", LossKind::Dropped),
+                ("seed", LossKind::Dropped),
+            ]
+        );
+
+        let kiro = detect_request_loss(
+            SourceFormat::OpenAiChat,
+            &body,
+            Some(TargetProtocol::for_provider("kiro")),
+        );
+        assert_eq!(
+            fields(&kiro),
+            vec![
+                ("frequency_penalty", LossKind::Dropped),
+                ("logprobs", LossKind::Dropped),
+                ("response_format", LossKind::Dropped),
+                ("seed", LossKind::Dropped),
+                ("temperature", LossKind::Dropped),
+                ("tool_choice", LossKind::Transformed),
+            ]
+        );
+        assert_eq!(
+            format_loss_header(&kiro[4..]),
+            "temperature=dropped, tool_choice=transformed"
+        );
+
+        let openai = detect_request_loss(
+            SourceFormat::OpenAiChat,
+            &body,
+            Some(TargetProtocol::for_provider("deepseek")),
+        );
+        assert_eq!(openai.len(), 3);
+    }
+
+    #[test]
+    fn test_anthropic_request_losses() {
+        let body = json!({
+            "model": "claude-sonnet-4-5",
+            "messages": [],
+            "max_tokens": 1024,
+            "top_k": 40,
+            "tool_choice": { "type": "auto" },
+        });
+
+        assert_eq!(
+            fields(&detect_request_loss(
+                SourceFormat::AnthropicMessages,
+                &body,
+                Some(TargetProtocol::for_provider("claude")),
+            )),
+            vec![("top_k", LossKind::Dropped)]
+        );
+        assert_eq!(
+            fields(&detect_request_loss(
+                SourceFormat::AnthropicMessages,
+                &body,
+                Some(TargetProtocol::OpenAiCompatible),
+            )),
+            vec![
+                ("tool_choice", LossKind::Transformed),
+                ("top_k", LossKind::Dropped),
+            ]
+        );
+        assert_eq!(
+            fields(&detect_request_loss(
+                SourceFormat::AnthropicMessages,
+                &body,
+                Some(TargetProtocol::CodeWhisperer),
+            )),
+            vec![
+                ("max_tokens", LossKind::Dropped),
+                ("tool_choice", LossKind::Transformed),
+                ("top_k", LossKind::Dropped),
+            ]
+        );
+    }
+}
@@ -1,15 +1,28 @@
 pub mod anthropic_to_openai;
 pub mod cw_to_openai;
+pub mod logprobs;
+pub mod lossiness;
+pub mod native_web_search;
+pub mod openai_responses;
 pub mod openai_to_antigravity;
 pub mod openai_to_cw;
 pub mod protocol_selector;
 pub mod reasoning_handler;
+pub mod tool_result_image;
 
 #[allow(unused_imports)]
 pub use anthropic_to_openai::*;
 #[allow(unused_imports)]
 pub use cw_to_openai::*;
 #[allow(unused_imports)]
+pub use logprobs::*;
+#[allow(unused_imports)]
+pub use lossiness::*;
+#[allow(unused_imports)]
+pub use native_web_search::*;
+#[allow(unused_imports)]
+pub use openai_responses::*;
+#[allow(unused_imports)]
 pub use openai_to_antigravity::*;
 #[allow(unused_imports)]
 pub use openai_to_cw::*;
@@ -17,3 +30,5 @@ pub use openai_to_cw::*;
 pub use protocol_selector::*;
 #[allow(unused_imports)]
 pub use reasoning_handler::*;
+#[allow(unused_imports)]
+pub use tool_result_image::*;
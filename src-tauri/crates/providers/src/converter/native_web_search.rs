@@ -0,0 +1,288 @@
+//! Provider 原生联网搜索工具
+//!
+//! Anthropic（`web_search_20250305`）与 Gemini（`googleSearch`）提供由服务端执行的联网搜索，
+//! 搜索会按次额外计费。客户端以 OpenAI `web_search` 或 Claude `web_search_20250305`
+//! 工具声明，本模块负责：
+//! - 识别 / 剔除请求中的联网搜索工具（凭证未开启时由调用方剔除）
+//! - 生成目标 Provider 的原生工具定义
+//! - 将响应中的搜索引用映射为 OpenAI `url_citation` 注解
+
+use lime_core::models::anthropic::AnthropicMessagesRequest;
+use lime_core::models::openai::{ChatCompletionRequest, Tool};
+use lime_core::models::provider_pool_model::{CredentialData, ProviderCredential};
+use std::collections::HashSet;
+
+/// Anthropic 原生联网搜索工具类型
+pub const ANTHROPIC_WEB_SEARCH_TOOL_TYPE: &str = "web_search_20250305";
+
+/// 联网搜索工具名
+pub const WEB_SEARCH_TOOL_NAME: &str = "web_search";
+
+/// 单次请求允许的最大搜索次数
+pub const DEFAULT_WEB_SEARCH_MAX_USES: u32 = 5;
+
+/// 是否为 OpenAI 请求中的联网搜索工具
+pub fn is_openai_web_search_tool(tool: &Tool) -> bool {
+    matches!(tool, Tool::WebSearch | Tool::WebSearch20250305)
+}
+
+/// OpenAI 请求是否声明了联网搜索工具
+pub fn openai_request_uses_web_search(request: &ChatCompletionRequest) -> bool {
+    request
+        .tools
+        .as_ref()
+        .is_some_and(|tools| tools.iter().any(is_openai_web_search_tool))
+}
+
+/// 剔除 OpenAI 请求中的联网搜索工具，返回是否有工具被剔除
+pub fn strip_openai_web_search_tools(request: &mut ChatCompletionRequest) -> bool {
+    let Some(tools) = request.tools.as_mut() else {
+        return false;
+    };
+    let before = tools.len();
+    tools.retain(|tool| !is_openai_web_search_tool(tool));
+    let stripped = tools.len() != before;
+    if tools.is_empty() {
+        request.tools = None;
+    }
+    stripped
+}
+
+/// 是否为 Anthropic 请求中的联网搜索工具名
+pub fn is_anthropic_web_search_tool_name(name: &str) -> bool {
+    name == WEB_SEARCH_TOOL_NAME || name == ANTHROPIC_WEB_SEARCH_TOOL_TYPE
+}
+
+/// Anthropic 请求是否声明了联网搜索工具
+pub fn anthropic_request_uses_web_search(request: &AnthropicMessagesRequest) -> bool {
+    request.tools.as_ref().is_some_and(|tools| {
+        tools
+            .iter()
+            .any(|tool| is_anthropic_web_search_tool_name(&tool.name))
+    })
+}
+
+/// 剔除 Anthropic 请求中的联网搜索工具，返回是否有工具被剔除
+pub fn strip_anthropic_web_search_tools(request: &mut AnthropicMessagesRequest) -> bool {
+    let Some(tools) = request.tools.as_mut() else {
+        return false;
+    };
+    let before = tools.len();
+    tools.retain(|tool| !is_anthropic_web_search_tool_name(&tool.name));
+    let stripped = tools.len() != before;
+    if tools.is_empty() {
+        request.tools = None;
+    }
+    stripped
+}
+
+/// Anthropic 原生联网搜索工具定义
+pub fn anthropic_web_search_tool() -> serde_json::Value {
+    serde_json::json!({
+        "type": ANTHROPIC_WEB_SEARCH_TOOL_TYPE,
+        "name": WEB_SEARCH_TOOL_NAME,
+        "max_uses": DEFAULT_WEB_SEARCH_MAX_USES
+    })
+}
+
+/// Gemini 原生联网搜索工具定义
+pub fn gemini_google_search_tool() -> serde_json::Value {
+    serde_json::json!({ "googleSearch": {} })
+}
+
+/// 列出当前可向客户端公开的原生工具
+///
+/// 仅当存在未禁用且开启了 `native_web_search` 的对应凭证时才列出。
+pub fn available_native_tools(credentials: &[ProviderCredential]) -> Vec<serde_json::Value> {
+    let enabled = |matches_provider: fn(&CredentialData) -> bool| {
+        credentials
+            .iter()
+            .any(|c| !c.is_disabled && c.native_web_search && matches_provider(&c.credential))
+    };
+
+    let mut tools = Vec::new();
+    if enabled(|c| {
+        matches!(
+            c,
+            CredentialData::ClaudeKey { .. } | CredentialData::AnthropicKey { .. }
+        )
+    }) {
+        tools.push(serde_json::json!({
+            "type": ANTHROPIC_WEB_SEARCH_TOOL_TYPE,
+            "name": WEB_SEARCH_TOOL_NAME,
+            "provider": "anthropic",
+            "openai_tool": { "type": "web_search" },
+            "billing": "per_search"
+        }));
+    }
+    if enabled(|c| matches!(c, CredentialData::AntigravityOAuth { .. })) {
+        tools.push(serde_json::json!({
+            "type": "google_search",
+            "name": WEB_SEARCH_TOOL_NAME,
+            "provider": "gemini",
+            "openai_tool": { "type": "web_search" },
+            "billing": "per_search"
+        }));
+    }
+    tools
+}
+
+/// 构建 OpenAI `url_citation` 注解
+pub fn url_citation_annotation(url: &str, title: Option<&str>) -> serde_json::Value {
+    let mut citation = serde_json::json!({ "url": url });
+    if let Some(title) = title.filter(|t| !t.is_empty()) {
+        citation["title"] = serde_json::Value::String(title.to_string());
+    }
+    serde_json::json!({
+        "type": "url_citation",
+        "url_citation": citation
+    })
+}
+
+/// 从 Anthropic 响应 content 中提取搜索引用
+///
+/// 优先使用文本块上的 `citations`，再补充 `web_search_tool_result` 中的结果，按 URL 去重。
+pub fn anthropic_content_to_annotations(content: &[serde_json::Value]) -> Vec<serde_json::Value> {
+    let mut seen = HashSet::new();
+    let mut annotations = Vec::new();
+    let mut push = |url: Option<&str>, title: Option<&str>| {
+        if let Some(url) = url.filter(|u| !u.is_empty()) {
+            if seen.insert(url.to_string()) {
+                annotations.push(url_citation_annotation(url, title));
+            }
+        }
+    };
+
+    for block in content {
+        if let Some(citations) = block.get("citations").and_then(|c| c.as_array()) {
+            for citation in citations {
+                push(
+                    citation.get("url").and_then(|u| u.as_str()),
+                    citation.get("title").and_then(|t| t.as_str()),
+                );
+            }
+        }
+    }
+    for block in content {
+        if block.get("type").and_then(|t| t.as_str()) != Some("web_search_tool_result") {
+            continue;
+        }
+        if let Some(results) = block.get("content").and_then(|c| c.as_array()) {
+            for result in results {
+                push(
+                    result.get("url").and_then(|u| u.as_str()),
+                    result.get("title").and_then(|t| t.as_str()),
+                );
+            }
+        }
+    }
+    annotations
+}
+
+/// 从 Gemini candidate 的 `groundingMetadata` 中提取搜索引用
+pub fn gemini_grounding_to_annotations(candidate: &serde_json::Value) -> Vec<serde_json::Value> {
+    let mut seen = HashSet::new();
+    candidate
+        .get("groundingMetadata")
+        .and_then(|m| m.get("groundingChunks"))
+        .and_then(|c| c.as_array())
+        .map(|chunks| {
+            chunks
+                .iter()
+                .filter_map(|chunk| chunk.get("web"))
+                .filter_map(|web| {
+                    let url = web.get("uri").and_then(|u| u.as_str())?;
+                    seen.insert(url.to_string()).then(|| {
+                        url_citation_annotation(url, web.get("title").and_then(|t| t.as_str()))
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anthropic_content_to_annotations_dedupes_urls() {
+        let content = vec![
+            serde_json::json!({
+                "type": "web_search_tool_result",
+                "tool_use_id": "srvtoolu_1",
+                "content": [
+                    {"type": "web_search_result", "url": "https://a.example", "title": "A"},
+                    {"type": "web_search_result", "url": "https://b.example", "title": "B"}
+                ]
+            }),
+            serde_json::json!({
+                "type": "text",
+                "text": "answer",
+                "citations": [{
+                    "type": "web_search_result_location",
+                    "url": "https://b.example",
+                    "title": "B",
+                    "cited_text": "..."
+                }]
+            }),
+        ];
+
+        let annotations = anthropic_content_to_annotations(&content);
+        let urls: Vec<&str> = annotations
+            .iter()
+            .filter_map(|a| a["url_citation"]["url"].as_str())
+            .collect();
+        assert_eq!(urls, vec!["https://b.example", "https://a.example"]);
+        assert_eq!(annotations[0]["type"], "url_citation");
+    }
+
+    #[test]
+    fn test_gemini_grounding_to_annotations() {
+        let candidate = serde_json::json!({
+            "groundingMetadata": {
+                "groundingChunks": [
+                    {"web": {"uri": "https://g.example", "title": "G"}},
+                    {"web": {"uri": "https://g.example", "title": "G"}},
+                    {"retrievedContext": {"uri": "ignored"}}
+                ]
+            }
+        });
+
+        let annotations = gemini_grounding_to_annotations(&candidate);
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0]["url_citation"]["title"], "G");
+        assert!(gemini_grounding_to_annotations(&serde_json::json!({})).is_empty());
+    }
+
+    #[test]
+    fn test_available_native_tools_requires_enabled_credential() {
+        let mut claude = ProviderCredential::new(
+            lime_core::models::provider_pool_model::PoolProviderType::Claude,
+            CredentialData::ClaudeKey {
+                api_key: "sk-test".to_string(),
+                base_url: None,
+            },
+        );
+        assert!(available_native_tools(std::slice::from_ref(&claude)).is_empty());
+
+        claude.native_web_search = true;
+        let tools = available_native_tools(std::slice::from_ref(&claude));
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0]["provider"], "anthropic");
+
+        claude.is_disabled = true;
+        assert!(available_native_tools(&[claude]).is_empty());
+    }
+
+    #[test]
+    fn test_native_tool_definitions() {
+        assert_eq!(
+            anthropic_web_search_tool()["type"],
+            ANTHROPIC_WEB_SEARCH_TOOL_TYPE
+        );
+        assert!(gemini_google_search_tool().get("googleSearch").is_some());
+        assert!(is_anthropic_web_search_tool_name("web_search"));
+        assert!(!is_anthropic_web_search_tool_name("search_docs"));
+    }
+}
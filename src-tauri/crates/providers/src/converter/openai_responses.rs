@@ -0,0 +1,422 @@
+//! OpenAI Responses API 与 chat completions 格式互转
+//!
+//! `/v1/responses` 请求先转换为 chat completions 请求体，经凭证池路由到各 Provider，
+//! 响应再转换回 Responses 对象（流式事件见 `streaming::responses_sse`）。
+//!
+//! 支持的输入条目：`message`（含省略 `type` 的简写形式）、`function_call`、
+//! `function_call_output`；`reasoning` 条目与非 function 类型的工具会被忽略。
+
+use lime_core::models::openai_responses::*;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+/// 将 Responses 请求转换为 chat completions 请求体
+///
+/// 流式请求会附带 `stream_options.include_usage`，以便在事件流末尾给出用量。
+pub fn convert_responses_to_openai(request: &ResponsesRequest) -> Value {
+    let mut messages: Vec<Value> = Vec::new();
+
+    if let Some(instructions) = request.instructions.as_deref().filter(|s| !s.is_empty()) {
+        messages.push(json!({ "role": "system", "content": instructions }));
+    }
+
+    match &request.input {
+        ResponsesInput::Text(text) => {
+            messages.push(json!({ "role": "user", "content": text }));
+        }
+        ResponsesInput::Items(items) => {
+            for item in items {
+                convert_input_item(item, &mut messages);
+            }
+        }
+    }
+
+    let mut body = Map::new();
+    body.insert("model".to_string(), json!(request.model));
+    body.insert("messages".to_string(), Value::Array(messages));
+    body.insert("stream".to_string(), json!(request.stream));
+    if request.stream {
+        body.insert(
+            "stream_options".to_string(),
+            json!({ "include_usage": true }),
+        );
+    }
+    if let Some(temperature) = request.temperature {
+        body.insert("temperature".to_string(), json!(temperature));
+    }
+    if let Some(top_p) = request.top_p {
+        body.insert("top_p".to_string(), json!(top_p));
+    }
+    if let Some(max_tokens) = request.max_output_tokens {
+        body.insert("max_tokens".to_string(), json!(max_tokens));
+    }
+    if let Some(effort) = request.reasoning.as_ref().and_then(|r| r.effort.as_ref()) {
+        body.insert("reasoning_effort".to_string(), json!(effort));
+    }
+
+    let tools: Vec<Value> = request
+        .tools
+        .iter()
+        .flatten()
+        .filter_map(convert_tool)
+        .collect();
+    if !tools.is_empty() {
+        body.insert("tools".to_string(), Value::Array(tools));
+        if let Some(tool_choice) = request.tool_choice.as_ref().and_then(convert_tool_choice) {
+            body.insert("tool_choice".to_string(), tool_choice);
+        }
+    }
+
+    Value::Object(body)
+}
+
+/// 将非流式 chat completions 响应转换为 Responses 对象
+pub fn convert_openai_response_to_responses(chat: &Value, model: &str) -> ResponsesResponse {
+    let choice = chat.pointer("/choices/0");
+    let message = choice.and_then(|c| c.get("message"));
+    let mut output = Vec::new();
+
+    if let Some(reasoning) = message
+        .and_then(|m| m.get("reasoning_content"))
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+    {
+        output.push(ResponseOutputItem::Reasoning {
+            id: new_item_id("rs"),
+            summary: vec![ReasoningSummaryPart::SummaryText {
+                text: reasoning.to_string(),
+            }],
+        });
+    }
+    if let Some(text) = message
+        .and_then(|m| m.get("content"))
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+    {
+        output.push(output_message(new_item_id("msg"), text.to_string()));
+    }
+    for tool_call in message
+        .and_then(|m| m.get("tool_calls"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let call_id = tool_call
+            .get("id")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| new_item_id("call"));
+        output.push(ResponseOutputItem::FunctionCall {
+            id: format!("fc_{call_id}"),
+            call_id,
+            name: str_at(tool_call, "/function/name").to_string(),
+            arguments: str_at(tool_call, "/function/arguments").to_string(),
+            status: "completed".to_string(),
+        });
+    }
+
+    let finish_reason = choice
+        .and_then(|c| c.get("finish_reason"))
+        .and_then(Value::as_str);
+    let (status, incomplete_details) = response_status(finish_reason);
+    ResponsesResponse {
+        id: response_id(chat.get("id").and_then(Value::as_str)),
+        object: "response".to_string(),
+        created_at: chat
+            .get("created")
+            .and_then(Value::as_i64)
+            .unwrap_or_else(|| chrono::Utc::now().timestamp()),
+        status: status.to_string(),
+        model: chat
+            .get("model")
+            .and_then(Value::as_str)
+            .filter(|m| !m.is_empty())
+            .unwrap_or(model)
+            .to_string(),
+        output,
+        usage: chat.get("usage").and_then(convert_usage),
+        incomplete_details,
+        error: None,
+    }
+}
+
+/// 由 chat completions 的 id 生成 Responses id
+pub(crate) fn response_id(chat_id: Option<&str>) -> String {
+    match chat_id.filter(|id| !id.is_empty()) {
+        Some(id) => format!("resp_{}", id.trim_start_matches("chatcmpl-")),
+        None => new_item_id("resp"),
+    }
+}
+
+/// 生成输出条目 id（`<prefix>_<uuid>`）
+pub(crate) fn new_item_id(prefix: &str) -> String {
+    format!("{prefix}_{}", Uuid::new_v4().simple())
+}
+
+/// 已完成的 assistant 文本消息条目
+pub(crate) fn output_message(id: String, text: String) -> ResponseOutputItem {
+    ResponseOutputItem::Message {
+        id,
+        role: "assistant".to_string(),
+        status: "completed".to_string(),
+        content: vec![ResponseOutputContent::OutputText {
+            text,
+            annotations: Vec::new(),
+        }],
+    }
+}
+
+/// 根据 `finish_reason` 得到响应状态与未完成原因
+pub(crate) fn response_status(
+    finish_reason: Option<&str>,
+) -> (&'static str, Option<ResponsesIncompleteDetails>) {
+    let reason = match finish_reason {
+        Some("length") => "max_output_tokens",
+        Some("content_filter") => "content_filter",
+        _ => return ("completed", None),
+    };
+    (
+        "incomplete",
+        Some(ResponsesIncompleteDetails {
+            reason: reason.to_string(),
+        }),
+    )
+}
+
+/// 转换 chat completions 的 usage
+pub(crate) fn convert_usage(usage: &Value) -> Option<ResponsesUsage> {
+    let input_tokens = usage.get("prompt_tokens").and_then(Value::as_u64)? as u32;
+    let output_tokens = usage
+        .get("completion_tokens")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32;
+    let total_tokens = usage
+        .get("total_tokens")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(input_tokens + output_tokens);
+    Some(ResponsesUsage {
+        input_tokens,
+        output_tokens,
+        total_tokens,
+    })
+}
+
+fn convert_input_item(item: &Value, messages: &mut Vec<Value>) {
+    let item_type = item.get("type").and_then(Value::as_str);
+    match item_type {
+        Some("message") | None if item.get("role").is_some() => {
+            let role = match str_at(item, "/role") {
+                "developer" => "system",
+                role => role,
+            };
+            let content = convert_message_content(item.get("content"), role == "assistant");
+            messages.push(json!({ "role": role, "content": content }));
+        }
+        Some("function_call") => {
+            let tool_call = json!({
+                "id": str_at(item, "/call_id"),
+                "type": "function",
+                "function": {
+                    "name": str_at(item, "/name"),
+                    "arguments": str_at(item, "/arguments"),
+                },
+            });
+            // 连续的 function_call 合并到同一条 assistant 消息
+            let last_assistant = messages
+                .last_mut()
+                .filter(|m| m.get("role").and_then(Value::as_str) == Some("assistant"));
+            match last_assistant {
+                Some(message) => {
+                    match message.get_mut("tool_calls").and_then(Value::as_array_mut) {
+                        Some(tool_calls) => tool_calls.push(tool_call),
+                        None => message["tool_calls"] = json!([tool_call]),
+                    }
+                }
+                None => messages.push(json!({
+                    "role": "assistant",
+                    "content": Value::Null,
+                    "tool_calls": [tool_call],
+                })),
+            }
+        }
+        Some("function_call_output") => {
+            let output = match item.get("output") {
+                Some(Value::String(text)) => text.clone(),
+                Some(Value::Array(parts)) => parts
+                    .iter()
+                    .filter_map(|p| p.get("text").and_then(Value::as_str))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                Some(other) => other.to_string(),
+                None => String::new(),
+            };
+            messages.push(json!({
+                "role": "tool",
+                "tool_call_id": str_at(item, "/call_id"),
+                "content": output,
+            }));
+        }
+        other => {
+            tracing::debug!("[RESPONSES] 忽略不支持的输入条目类型: {:?}", other);
+        }
+    }
+}
+
+/// 转换消息内容；纯文本（或 assistant 消息）合并为字符串，含图片时保留多段内容
+fn convert_message_content(content: Option<&Value>, text_only: bool) -> Value {
+    let parts = match content {
+        Some(Value::String(text)) => return json!(text),
+        Some(Value::Array(parts)) => parts,
+        _ => return json!(""),
+    };
+
+    let mut texts = Vec::new();
+    let mut converted = Vec::new();
+    for part in parts {
+        match part.get("type").and_then(Value::as_str) {
+            Some("input_text" | "output_text" | "text") => {
+                let text = str_at(part, "/text");
+                texts.push(text);
+                converted.push(json!({ "type": "text", "text": text }));
+            }
+            Some("input_image") if !text_only => {
+                let url = part
+                    .get("image_url")
+                    .and_then(|u| u.as_str().or_else(|| u.get("url").and_then(Value::as_str)));
+                if let Some(url) = url {
+                    let mut image_url = json!({ "url": url });
+                    if let Some(detail) = part.get("detail").and_then(Value::as_str) {
+                        image_url["detail"] = json!(detail);
+                    }
+                    converted.push(json!({ "type": "image_url", "image_url": image_url }));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if converted.len() == texts.len() {
+        json!(texts.join(""))
+    } else {
+        Value::Array(converted)
+    }
+}
+
+fn convert_tool(tool: &Value) -> Option<Value> {
+    if tool.get("type").and_then(Value::as_str) != Some("function") {
+        return None;
+    }
+    let mut function = json!({ "name": tool.get("name")?.as_str()? });
+    for key in ["description", "parameters", "strict"] {
+        if let Some(value) = tool.get(key).filter(|v| !v.is_null()) {
+            function[key] = value.clone();
+        }
+    }
+    Some(json!({ "type": "function", "function": function }))
+}
+
+fn convert_tool_choice(tool_choice: &Value) -> Option<Value> {
+    match tool_choice {
+        Value::String(_) => Some(tool_choice.clone()),
+        Value::Object(choice) if choice.get("type").and_then(Value::as_str) == Some("function") => {
+            Some(json!({
+                "type": "function",
+                "function": { "name": choice.get("name")?.as_str()? },
+            }))
+        }
+        _ => None,
+    }
+}
+
+fn str_at<'a>(value: &'a Value, pointer: &str) -> &'a str {
+    value.pointer(pointer).and_then(Value::as_str).unwrap_or("")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_request_with_tool_round_trip() {
+        let request: ResponsesRequest = serde_json::from_value(json!({
+            "model": "gpt-5-codex",
+            "instructions": "You are a coding agent",
+            "input": [
+                { "role": "user", "content": [{ "type": "input_text", "text": "list files" }] },
+                { "type": "reasoning", "summary": [] },
+                { "type": "function_call", "call_id": "call_1", "name": "shell", "arguments": "{\"cmd\":\"ls\"}" },
+                { "type": "function_call", "call_id": "call_2", "name": "shell", "arguments": "{}" },
+                { "type": "function_call_output", "call_id": "call_1", "output": "a.rs" },
+                { "type": "message", "role": "developer", "content": "be brief" }
+            ],
+            "tools": [
+                { "type": "function", "name": "shell", "parameters": { "type": "object" } },
+                { "type": "web_search" }
+            ],
+            "tool_choice": { "type": "function", "name": "shell" },
+            "max_output_tokens": 512,
+            "reasoning": { "effort": "high" },
+            "stream": true
+        }))
+        .unwrap();
+
+        let body = convert_responses_to_openai(&request);
+        let messages = body["messages"].as_array().unwrap();
+        let roles: Vec<&str> = messages
+            .iter()
+            .map(|m| m["role"].as_str().unwrap())
+            .collect();
+        assert_eq!(roles, vec!["system", "user", "assistant", "tool", "system"]);
+        assert_eq!(messages[1]["content"], "list files");
+        assert_eq!(messages[2]["tool_calls"].as_array().unwrap().len(), 2);
+        assert_eq!(messages[3]["tool_call_id"], "call_1");
+        assert_eq!(body["tools"].as_array().unwrap().len(), 1);
+        assert_eq!(body["tools"][0]["function"]["name"], "shell");
+        assert_eq!(body["tool_choice"]["function"]["name"], "shell");
+        assert_eq!(body["max_tokens"], 512);
+        assert_eq!(body["reasoning_effort"], "high");
+        assert_eq!(body["stream_options"]["include_usage"], true);
+    }
+
+    #[test]
+    fn test_convert_chat_response() {
+        let chat = json!({
+            "id": "chatcmpl-abc",
+            "created": 1700000000,
+            "model": "gpt-4o",
+            "choices": [{
+                "message": {
+                    "role": "assistant",
+                    "content": "done",
+                    "tool_calls": [{
+                        "id": "call_9",
+                        "type": "function",
+                        "function": { "name": "shell", "arguments": "{}" }
+                    }]
+                },
+                "finish_reason": "length"
+            }],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 5, "total_tokens": 15 }
+        });
+
+        let response = convert_openai_response_to_responses(&chat, "fallback");
+        assert_eq!(response.id, "resp_abc");
+        assert_eq!(response.model, "gpt-4o");
+        assert_eq!(response.status, "incomplete");
+        assert_eq!(
+            response.incomplete_details.unwrap().reason,
+            "max_output_tokens"
+        );
+        assert_eq!(response.output.len(), 2);
+        assert!(matches!(
+            &response.output[1],
+            ResponseOutputItem::FunctionCall { call_id, .. } if call_id == "call_9"
+        ));
+        assert_eq!(response.usage.unwrap().total_tokens, 15);
+
+        let json = serde_json::to_value(&response.output[0]).unwrap();
+        assert_eq!(json["type"], "message");
+        assert_eq!(json["content"][0]["type"], "output_text");
+    }
+}
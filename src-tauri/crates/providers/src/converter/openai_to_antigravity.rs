@@ -14,6 +14,7 @@
 //! ## 更新日志
 //! - 2025-12-28: 修复请求格式，对齐 CLIProxyAPI 实现
 
+use crate::converter::tool_result_image::ToolImage;
 use crate::session::{get_thought_signature, SessionManager};
 use lime_core::models::openai::*;
 use serde::{Deserialize, Serialize};
@@ -318,14 +319,20 @@ pub fn convert_openai_to_antigravity_with_context(
         }
     }
 
-    // 第二遍：收集 tool 响应
+    // 第二遍：收集 tool 响应（文本与图片分开收集）
     let mut tool_responses: std::collections::HashMap<String, String> =
         std::collections::HashMap::new();
+    let mut tool_images: std::collections::HashMap<String, Vec<GeminiPart>> =
+        std::collections::HashMap::new();
     for msg in &request.messages {
         if msg.role == "tool" {
             if let Some(tool_call_id) = &msg.tool_call_id {
                 let content = msg.get_content_text();
                 tool_responses.insert(tool_call_id.clone(), content);
+                let images = tool_message_image_parts(msg);
+                if !images.is_empty() {
+                    tool_images.insert(tool_call_id.clone(), images);
+                }
             }
         }
     }
@@ -444,8 +451,9 @@ pub fn convert_openai_to_antigravity_with_context(
                         });
                     }
 
-                    // 紧接着添加 tool 响应作为 user 消息
+                    // 紧接着添加 tool 响应作为 user 消息，工具结果中的图片附在所有响应之后
                     let mut tool_parts: Vec<GeminiPart> = Vec::new();
+                    let mut image_parts: Vec<GeminiPart> = Vec::new();
                     for fid in &function_ids {
                         if let Some(name) = tc_id_to_name.get(fid) {
                             let resp = tool_responses.get(fid).cloned().unwrap_or_default();
@@ -474,8 +482,12 @@ pub fn convert_openai_to_antigravity_with_context(
                                 }),
                                 thought_signature: None,
                             });
+                            if let Some(images) = tool_images.get(fid) {
+                                image_parts.extend(images.iter().cloned());
+                            }
                         }
                     }
+                    tool_parts.extend(image_parts);
 
                     if !tool_parts.is_empty() {
                         contents.push(GeminiContent {
@@ -540,14 +552,17 @@ pub fn convert_openai_to_antigravity_with_context(
                         })
                         .unwrap_or(false);
 
+                    let mut response_parts = vec![function_response];
+                    response_parts.extend(tool_message_image_parts(msg));
+
                     if should_merge {
                         if let Some(last) = contents.last_mut() {
-                            last.parts.push(function_response);
+                            last.parts.extend(response_parts);
                         }
                     } else {
                         contents.push(GeminiContent {
                             role: "user".to_string(),
-                            parts: vec![function_response],
+                            parts: response_parts,
                         });
                     }
                 }
@@ -614,9 +629,15 @@ pub fn convert_openai_to_antigravity_with_context(
         }
     }
 
+    // 原生联网搜索（googleSearch）仅 Gemini 模型支持；
+    // 凭证未开启原生联网搜索时，调用方会在转换前剔除 web_search 工具
+    let uses_google_search = !is_claude_model(actual_model)
+        && super::native_web_search::openai_request_uses_web_search(request);
+
     // 转换工具定义
     // 注意：Antigravity API 统一使用 functionDeclarations 格式
     // Claude 和 Gemini 模型都使用相同的结构，但字段名可能不同
+    let mut has_function_declarations = false;
     let tools: Option<serde_json::Value> = request.tools.as_ref().and_then(|tools| {
         let is_claude = is_claude_model(actual_model);
         let mut function_declarations: Vec<serde_json::Value> = Vec::new();
@@ -657,22 +678,26 @@ pub fn convert_openai_to_antigravity_with_context(
                     }
                 }
                 Tool::WebSearch | Tool::WebSearch20250305 => {
-                    // web_search 工具不转换
+                    // web_search 工具映射为 googleSearch，见下方
                 }
             }
         }
 
-        if function_declarations.is_empty() {
-            None
-        } else {
-            Some(serde_json::json!([{
+        let mut entries = Vec::new();
+        if !function_declarations.is_empty() {
+            has_function_declarations = true;
+            entries.push(serde_json::json!({
                 "functionDeclarations": function_declarations
-            }]))
+            }));
+        }
+        if uses_google_search {
+            entries.push(super::native_web_search::gemini_google_search_tool());
         }
+        (!entries.is_empty()).then(|| serde_json::Value::Array(entries))
     });
 
-    // 构建 toolConfig（如果有工具定义）
-    let tool_config: Option<serde_json::Value> = if tools.is_some() {
+    // 构建 toolConfig（如果有函数工具定义）
+    let tool_config: Option<serde_json::Value> = if has_function_declarations {
         Some(serde_json::json!({
             "functionCallingConfig": {
                 "mode": "AUTO"
@@ -702,6 +727,8 @@ pub fn convert_openai_to_antigravity_with_context(
     // - "image_gen": 图片生成请求
     let request_type = if is_image_generation_model(actual_model) {
         "image_gen"
+    } else if uses_google_search {
+        "web_search"
     } else {
         "agent"
     };
@@ -818,6 +845,30 @@ fn convert_user_content(msg: &ChatMessage) -> Vec<GeminiPart> {
 }
 
 /// 解析 data URL
+/// 提取 tool 消息中的图片，转换为 inlineData 部分
+fn tool_message_image_parts(msg: &ChatMessage) -> Vec<GeminiPart> {
+    let Some(MessageContent::Parts(parts)) = &msg.content else {
+        return Vec::new();
+    };
+    parts
+        .iter()
+        .filter_map(|part| match part {
+            ContentPart::ImageUrl { image_url } => ToolImage::from_data_url(&image_url.url),
+            ContentPart::Text { .. } => None,
+        })
+        .map(|image| GeminiPart {
+            text: None,
+            inline_data: Some(InlineData {
+                mime_type: image.media_type,
+                data: image.data,
+            }),
+            function_call: None,
+            function_response: None,
+            thought_signature: None,
+        })
+        .collect()
+}
+
 fn parse_data_url(url: &str) -> Option<(String, String)> {
     if url.starts_with("data:") {
         let parts: Vec<&str> = url.splitn(2, ',').collect();
@@ -983,6 +1034,12 @@ pub fn convert_antigravity_to_openai_response(
                 message["tool_calls"] = serde_json::json!(tool_calls);
             }
 
+            // googleSearch 的引用来源
+            let annotations = super::native_web_search::gemini_grounding_to_annotations(candidate);
+            if !annotations.is_empty() {
+                message["annotations"] = serde_json::Value::Array(annotations);
+            }
+
             choices.push(serde_json::json!({
                 "index": i,
                 "message": message,
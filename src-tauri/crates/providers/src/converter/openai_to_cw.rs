@@ -11,6 +11,8 @@
 use lime_core::models::codewhisperer::*;
 use lime_core::models::openai::*;
 use std::collections::HashMap;
+
+use super::tool_result_image::tool_message_text;
 use uuid::Uuid;
 
 /// 模型映射表
@@ -80,8 +82,8 @@ fn preprocess_messages(messages: &[&ChatMessage]) -> Vec<ProcessedMessage> {
     for msg in messages {
         match msg.role.as_str() {
             "tool" => {
-                // 收集 tool 结果
-                let content = msg.get_content_text();
+                // 收集 tool 结果（CodeWhisperer 只接受文本，图片以占位代替）
+                let content = tool_message_text(msg);
                 let tool_id = msg.tool_call_id.clone().unwrap_or_default();
                 pending_tool_results.push(CWToolResult {
                     content: vec![CWTextContent { text: content }],
@@ -69,6 +69,8 @@ impl ProtocolSelector {
             PoolProviderType::AzureOpenai => Protocol::OpenAI,
             PoolProviderType::AwsBedrock => Protocol::Anthropic,
             PoolProviderType::Ollama => Protocol::OpenAI,
+            // Mock Provider 以 OpenAI 格式返回模拟响应
+            PoolProviderType::Mock => Protocol::OpenAI,
         }
     }
 
@@ -0,0 +1,416 @@
+//! 工具结果中的图片
+//!
+//! 浏览器等工具返回的截图以图片块出现在工具结果中。本模块负责：
+//! - Anthropic `tool_result` 图片块与 OpenAI `image_url`（data URL）之间的转换
+//! - 发往支持图片的 Provider（Claude / Gemini）前自动缩放过大的 PNG 截图
+//! - 为不支持图片工具结果的 Provider 将图片替换为文本占位
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use image::{imageops::FilterType, ImageFormat};
+use lime_core::models::anthropic::AnthropicMessagesRequest;
+use lime_core::models::openai::{
+    ChatCompletionRequest, ChatMessage, ContentPart, ImageUrl, MessageContent,
+};
+use std::io::Cursor;
+
+/// 图片最长边上限（超出时缩放，与 Anthropic 建议尺寸一致）
+pub const MAX_TOOL_IMAGE_DIMENSION: u32 = 1568;
+
+/// 单张图片的最大字节数（缩放后仍超出时替换为占位）
+pub const MAX_TOOL_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// 工具结果中的一张 base64 图片
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolImage {
+    pub media_type: String,
+    pub data: String,
+}
+
+impl ToolImage {
+    /// 解析 `data:<mime>;base64,<data>` 格式的 URL
+    pub fn from_data_url(url: &str) -> Option<Self> {
+        let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+        let media_type = header.split(';').next().filter(|m| !m.is_empty())?;
+        Some(Self {
+            media_type: media_type.to_string(),
+            data: data.to_string(),
+        })
+    }
+
+    /// 解析 Anthropic `{"type": "image", "source": {"type": "base64", ...}}` 块
+    pub fn from_anthropic_block(block: &serde_json::Value) -> Option<Self> {
+        if block.get("type").and_then(|t| t.as_str()) != Some("image") {
+            return None;
+        }
+        let source = block.get("source")?;
+        if source.get("type").and_then(|t| t.as_str()) != Some("base64") {
+            return None;
+        }
+        Some(Self {
+            media_type: source
+                .get("media_type")
+                .and_then(|m| m.as_str())
+                .unwrap_or("image/png")
+                .to_string(),
+            data: source.get("data")?.as_str()?.to_string(),
+        })
+    }
+
+    pub fn to_data_url(&self) -> String {
+        format!("data:{};base64,{}", self.media_type, self.data)
+    }
+
+    pub fn to_anthropic_block(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "image",
+            "source": {
+                "type": "base64",
+                "media_type": self.media_type,
+                "data": self.data
+            }
+        })
+    }
+
+    /// 解码后的大致字节数
+    pub fn byte_len(&self) -> usize {
+        self.data.len() / 4 * 3
+    }
+
+    /// 不支持图片时使用的文本占位
+    pub fn placeholder(&self) -> String {
+        format!(
+            "[图片: {}, 约 {} KB，当前 Provider 不支持工具结果中的图片]",
+            self.media_type,
+            self.byte_len().div_ceil(1024)
+        )
+    }
+
+    /// 按尺寸上限缩放图片
+    ///
+    /// 仅处理 PNG（浏览器截图的常见格式），其他格式与无需缩放的图片原样返回。
+    pub fn downscaled(self) -> Self {
+        if self.media_type != "image/png" {
+            return self;
+        }
+        let Ok(bytes) = STANDARD.decode(&self.data) else {
+            return self;
+        };
+        let Ok(decoded) = image::load_from_memory_with_format(&bytes, ImageFormat::Png) else {
+            return self;
+        };
+        if decoded.width().max(decoded.height()) <= MAX_TOOL_IMAGE_DIMENSION {
+            return self;
+        }
+
+        let resized = decoded.resize(
+            MAX_TOOL_IMAGE_DIMENSION,
+            MAX_TOOL_IMAGE_DIMENSION,
+            FilterType::Triangle,
+        );
+        let mut out = Cursor::new(Vec::new());
+        if resized.write_to(&mut out, ImageFormat::Png).is_err() {
+            return self;
+        }
+        tracing::debug!(
+            "[TOOL_IMAGE] 已缩放工具结果图片: {}x{} -> {}x{}",
+            decoded.width(),
+            decoded.height(),
+            resized.width(),
+            resized.height()
+        );
+        Self {
+            media_type: self.media_type,
+            data: STANDARD.encode(out.into_inner()),
+        }
+    }
+
+    /// 为支持图片的 Provider 准备图片：先缩放，仍超出字节上限时返回占位文本
+    pub fn prepare(self) -> Result<Self, String> {
+        let image = self.downscaled();
+        if image.byte_len() > MAX_TOOL_IMAGE_BYTES {
+            Err(image.placeholder())
+        } else {
+            Ok(image)
+        }
+    }
+}
+
+/// 处理 OpenAI 请求中 `tool` 消息携带的图片
+///
+/// `supports_images` 为 true 时缩放过大的图片，否则将图片替换为文本占位。
+/// 返回被处理的图片数量。
+pub fn prepare_openai_tool_images(
+    request: &mut ChatCompletionRequest,
+    supports_images: bool,
+) -> usize {
+    let mut handled = 0;
+    for msg in request.messages.iter_mut().filter(|m| m.role == "tool") {
+        let Some(MessageContent::Parts(parts)) = &mut msg.content else {
+            continue;
+        };
+        let parts = std::mem::take(parts);
+
+        let mut new_parts = Vec::with_capacity(parts.len());
+        for part in parts {
+            let image = match &part {
+                ContentPart::ImageUrl { image_url } => ToolImage::from_data_url(&image_url.url),
+                ContentPart::Text { .. } => None,
+            };
+            let Some(image) = image else {
+                new_parts.push(part);
+                continue;
+            };
+            handled += 1;
+            let prepared = if supports_images {
+                image.prepare()
+            } else {
+                Err(image.placeholder())
+            };
+            new_parts.push(match prepared {
+                Ok(image) => ContentPart::ImageUrl {
+                    image_url: ImageUrl {
+                        url: image.to_data_url(),
+                        detail: None,
+                    },
+                },
+                Err(text) => ContentPart::Text { text },
+            });
+        }
+
+        let has_image = new_parts
+            .iter()
+            .any(|p| matches!(p, ContentPart::ImageUrl { .. }));
+        msg.content = Some(if has_image {
+            MessageContent::Parts(new_parts)
+        } else {
+            MessageContent::Text(
+                new_parts
+                    .into_iter()
+                    .filter_map(|p| match p {
+                        ContentPart::Text { text } => Some(text),
+                        ContentPart::ImageUrl { .. } => None,
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        });
+    }
+    handled
+}
+
+/// 处理 Anthropic 请求中 `tool_result` 块携带的图片
+///
+/// 语义同 [`prepare_openai_tool_images`]，返回被处理的图片数量。
+pub fn prepare_anthropic_tool_images(
+    request: &mut AnthropicMessagesRequest,
+    supports_images: bool,
+) -> usize {
+    let mut handled = 0;
+    for msg in request.messages.iter_mut() {
+        let Some(blocks) = msg.content.as_array_mut() else {
+            continue;
+        };
+        for block in blocks.iter_mut() {
+            if block.get("type").and_then(|t| t.as_str()) != Some("tool_result") {
+                continue;
+            }
+            let Some(items) = block.get_mut("content").and_then(|c| c.as_array_mut()) else {
+                continue;
+            };
+            for item in items.iter_mut() {
+                let Some(image) = ToolImage::from_anthropic_block(item) else {
+                    continue;
+                };
+                handled += 1;
+                let prepared = if supports_images {
+                    image.prepare()
+                } else {
+                    Err(image.placeholder())
+                };
+                *item = match prepared {
+                    Ok(image) => image.to_anthropic_block(),
+                    Err(text) => serde_json::json!({ "type": "text", "text": text }),
+                };
+            }
+        }
+    }
+    handled
+}
+
+/// 提取 `tool` 消息文本，图片以文本占位代替
+///
+/// 供只接受文本工具结果的协议（如 CodeWhisperer）使用。
+pub fn tool_message_text(msg: &ChatMessage) -> String {
+    let Some(MessageContent::Parts(parts)) = &msg.content else {
+        return msg.get_content_text();
+    };
+    parts
+        .iter()
+        .filter_map(|part| match part {
+            ContentPart::Text { text } => Some(text.clone()),
+            ContentPart::ImageUrl { image_url } => {
+                ToolImage::from_data_url(&image_url.url).map(|image| image.placeholder())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// OpenAI 请求的 `tool` 消息中是否包含图片
+pub fn openai_request_has_tool_images(request: &ChatCompletionRequest) -> bool {
+    request.messages.iter().any(|msg| {
+        msg.role == "tool"
+            && matches!(
+                &msg.content,
+                Some(MessageContent::Parts(parts))
+                    if parts.iter().any(|p| matches!(p, ContentPart::ImageUrl { .. }))
+            )
+    })
+}
+
+/// Anthropic 请求的 `tool_result` 块中是否包含图片
+pub fn anthropic_request_has_tool_images(request: &AnthropicMessagesRequest) -> bool {
+    request.messages.iter().any(|msg| {
+        msg.content.as_array().is_some_and(|blocks| {
+            blocks.iter().any(|block| {
+                block.get("type").and_then(|t| t.as_str()) == Some("tool_result")
+                    && block
+                        .get("content")
+                        .and_then(|c| c.as_array())
+                        .is_some_and(|items| {
+                            items
+                                .iter()
+                                .any(|item| ToolImage::from_anthropic_block(item).is_some())
+                        })
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png_data(width: u32, height: u32) -> String {
+        let image = image::RgbImage::new(width, height);
+        let mut out = Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(image)
+            .write_to(&mut out, ImageFormat::Png)
+            .unwrap();
+        STANDARD.encode(out.into_inner())
+    }
+
+    fn tool_message(parts: Vec<ContentPart>) -> ChatMessage {
+        ChatMessage {
+            role: "tool".to_string(),
+            content: Some(MessageContent::Parts(parts)),
+            tool_calls: None,
+            tool_call_id: Some("call_1".to_string()),
+            reasoning_content: None,
+        }
+    }
+
+    fn request_with(messages: Vec<ChatMessage>) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: "test".to_string(),
+            messages,
+            temperature: None,
+            max_tokens: None,
+            top_p: None,
+            stream: false,
+            tools: None,
+            tool_choice: None,
+            reasoning_effort: None,
+        }
+    }
+
+    #[test]
+    fn test_data_url_and_anthropic_block_round_trip() {
+        let image = ToolImage::from_data_url("data:image/png;base64,AAAA").unwrap();
+        assert_eq!(image.media_type, "image/png");
+        let block = image.to_anthropic_block();
+        assert_eq!(ToolImage::from_anthropic_block(&block), Some(image.clone()));
+        assert_eq!(image.to_data_url(), "data:image/png;base64,AAAA");
+        assert!(ToolImage::from_data_url("https://example.com/a.png").is_none());
+    }
+
+    #[test]
+    fn test_downscale_large_png() {
+        let image = ToolImage {
+            media_type: "image/png".to_string(),
+            data: png_data(3136, 100),
+        };
+        let scaled = image.downscaled();
+        let bytes = STANDARD.decode(&scaled.data).unwrap();
+        let decoded = image::load_from_memory(&bytes).unwrap();
+        assert_eq!(decoded.width(), MAX_TOOL_IMAGE_DIMENSION);
+        assert_eq!(decoded.height(), 50);
+
+        let small = ToolImage {
+            media_type: "image/png".to_string(),
+            data: png_data(10, 10),
+        };
+        assert_eq!(small.clone().downscaled(), small);
+    }
+
+    #[test]
+    fn test_prepare_openai_tool_images_placeholder_when_unsupported() {
+        let mut request = request_with(vec![tool_message(vec![
+            ContentPart::Text {
+                text: "screenshot taken".to_string(),
+            },
+            ContentPart::ImageUrl {
+                image_url: ImageUrl {
+                    url: "data:image/png;base64,AAAA".to_string(),
+                    detail: None,
+                },
+            },
+        ])]);
+        assert!(openai_request_has_tool_images(&request));
+
+        assert!(tool_message_text(&request.messages[0]).contains("[图片: image/png"));
+        assert_eq!(prepare_openai_tool_images(&mut request, false), 1);
+        match &request.messages[0].content {
+            Some(MessageContent::Text(text)) => {
+                assert!(text.starts_with("screenshot taken\n[图片: image/png"));
+            }
+            other => panic!("expected text content, got {other:?}"),
+        }
+        assert!(!openai_request_has_tool_images(&request));
+    }
+
+    #[test]
+    fn test_prepare_anthropic_tool_images_keeps_supported_images() {
+        let mut request = AnthropicMessagesRequest {
+            model: "claude-sonnet-4-5".to_string(),
+            messages: vec![lime_core::models::anthropic::AnthropicMessage {
+                role: "user".to_string(),
+                content: serde_json::json!([{
+                    "type": "tool_result",
+                    "tool_use_id": "toolu_1",
+                    "content": [
+                        { "type": "text", "text": "done" },
+                        {
+                            "type": "image",
+                            "source": { "type": "base64", "media_type": "image/png", "data": "AAAA" }
+                        }
+                    ]
+                }]),
+            }],
+            max_tokens: Some(1024),
+            system: None,
+            temperature: None,
+            stream: false,
+            tools: None,
+            tool_choice: None,
+        };
+        assert!(anthropic_request_has_tool_images(&request));
+
+        assert_eq!(prepare_anthropic_tool_images(&mut request, true), 1);
+        assert!(anthropic_request_has_tool_images(&request));
+
+        assert_eq!(prepare_anthropic_tool_images(&mut request, false), 1);
+        assert!(!anthropic_request_has_tool_images(&request));
+        assert_eq!(request.messages[0].content[0]["content"][1]["type"], "text");
+    }
+}
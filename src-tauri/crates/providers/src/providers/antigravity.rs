@@ -6,6 +6,7 @@
 
 use super::traits::{CredentialProvider, ProviderResult};
 use async_trait::async_trait;
+use lime_core::processor::RequestIdExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
@@ -885,6 +886,7 @@ impl AntigravityProvider {
             .header("Content-Type", "application/json")
             .header("User-Agent", "antigravity/1.11.9 windows/amd64")
             .json(body)
+            .with_request_id()
             .send()
             .await
             .map_err(|e| {
@@ -2199,6 +2201,7 @@ impl StreamingProvider for AntigravityProvider {
                 .header("Accept", "text/event-stream")
                 .header("User-Agent", "antigravity/1.11.9 windows/amd64")
                 .json(&payload)
+                .with_request_id()
                 .send()
                 .await;
 
@@ -1,6 +1,7 @@
 //! Claude Custom Provider (自定义 Claude API)
 use lime_core::models::anthropic::AnthropicMessagesRequest;
-use lime_core::models::openai::{ChatCompletionRequest, ContentPart, MessageContent};
+use lime_core::models::openai::{ChatCompletionRequest, ChatMessage, ContentPart, MessageContent};
+use lime_core::processor::RequestIdExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
@@ -88,6 +89,34 @@ impl ClaudeCustomProvider {
         }
     }
 
+    /// 转换 tool 消息内容为 tool_result content
+    ///
+    /// 纯文本保持字符串；包含图片时转换为文本 + 图片块数组。
+    fn convert_tool_result_content(msg: &ChatMessage) -> serde_json::Value {
+        match &msg.content {
+            Some(MessageContent::Parts(parts))
+                if parts
+                    .iter()
+                    .any(|p| matches!(p, ContentPart::ImageUrl { .. })) =>
+            {
+                let blocks: Vec<serde_json::Value> = parts
+                    .iter()
+                    .filter_map(|p| match p {
+                        ContentPart::Text { text } if !text.is_empty() => {
+                            Some(serde_json::json!({"type": "text", "text": text}))
+                        }
+                        ContentPart::Text { .. } => None,
+                        ContentPart::ImageUrl { image_url } => {
+                            Self::convert_image_url_to_claude(&image_url.url)
+                        }
+                    })
+                    .collect();
+                serde_json::Value::Array(blocks)
+            }
+            _ => serde_json::Value::String(msg.get_content_text()),
+        }
+    }
+
     /// 将 OpenAI 图片 URL 格式转换为 Claude 图片格式
     ///
     /// 支持两种格式：
@@ -176,7 +205,11 @@ impl ClaudeCustomProvider {
                 }
                 Some(anthropic_tool)
             }
-            _ => None,
+            // 原生联网搜索（是否允许由调用方按凭证设置决定）
+            lime_core::models::openai::Tool::WebSearch
+            | lime_core::models::openai::Tool::WebSearch20250305 => {
+                Some(crate::converter::native_web_search::anthropic_web_search_tool())
+            }
         }
     }
 
@@ -241,6 +274,7 @@ impl ClaudeCustomProvider {
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
             .json(request)
+            .with_request_id()
             .send()
             .await?;
 
@@ -363,6 +397,7 @@ impl ClaudeCustomProvider {
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
             .json(&anthropic_body)
+            .with_request_id()
             .send()
             .await?;
 
@@ -393,11 +428,27 @@ impl ClaudeCustomProvider {
         let anthropic_resp: serde_json::Value = resp.json().await?;
 
         // 转换回 OpenAI 格式
-        let content = anthropic_resp["content"]
+        // 使用原生联网搜索时 content 中还包含 server_tool_use / web_search_tool_result 块，
+        // 这里拼接全部文本块，并将搜索引用映射为 annotations
+        let content_blocks = anthropic_resp["content"]
             .as_array()
-            .and_then(|arr| arr.first())
-            .and_then(|block| block["text"].as_str())
-            .unwrap_or("");
+            .cloned()
+            .unwrap_or_default();
+        let content = content_blocks
+            .iter()
+            .filter(|block| block["type"].as_str() == Some("text"))
+            .filter_map(|block| block["text"].as_str())
+            .collect::<String>();
+        let annotations =
+            crate::converter::native_web_search::anthropic_content_to_annotations(&content_blocks);
+
+        let mut message = serde_json::json!({
+            "role": "assistant",
+            "content": content
+        });
+        if !annotations.is_empty() {
+            message["annotations"] = serde_json::Value::Array(annotations);
+        }
 
         Ok(serde_json::json!({
             "id": format!("chatcmpl-{}", uuid::Uuid::new_v4()),
@@ -409,10 +460,7 @@ impl ClaudeCustomProvider {
             "model": request.model,
             "choices": [{
                 "index": 0,
-                "message": {
-                    "role": "assistant",
-                    "content": content
-                },
+                "message": message,
                 "finish_reason": "stop"
             }],
             "usage": {
@@ -458,6 +506,7 @@ impl ClaudeCustomProvider {
             .header("anthropic-version", "2023-06-01")
             .header("Content-Type", "application/json")
             .json(request)
+            .with_request_id()
             .send()
             .await?;
 
@@ -544,11 +593,10 @@ impl StreamingProvider for ClaudeCustomProvider {
             if role == "tool" {
                 // 转换为 Anthropic tool_result content block
                 let tool_call_id = msg.tool_call_id.clone().unwrap_or_default();
-                let content = msg.get_content_text();
                 pending_tool_results.push(serde_json::json!({
                     "type": "tool_result",
                     "tool_use_id": tool_call_id,
-                    "content": content
+                    "content": Self::convert_tool_result_content(msg)
                 }));
                 continue;
             }
@@ -690,6 +738,7 @@ impl StreamingProvider for ClaudeCustomProvider {
             .header("Content-Type", "application/json")
             .header("Accept", "text/event-stream")
             .json(&anthropic_body)
+            .with_request_id()
             .send()
             .await
             .map_err(|e| ProviderError::from_reqwest_error(&e))?;
@@ -8,6 +8,7 @@
 use super::error::{
     create_auth_error, create_config_error, create_token_refresh_error, ProviderError,
 };
+use lime_core::processor::RequestIdExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
@@ -16,7 +17,8 @@ use std::path::PathBuf;
 // OAuth Constants
 const OPENAI_AUTH_URL: &str = "https://auth.openai.com/oauth/authorize";
 const OPENAI_TOKEN_URL: &str = "https://auth.openai.com/oauth/token";
-const OPENAI_CLIENT_ID: &str = "app_EMoamEEZ73f0CkXaXp7hrann";
+/// Codex CLI 注册的 OAuth client id（通用 OAuth 登录流程也使用该值）
+pub const OPENAI_CLIENT_ID: &str = "app_EMoamEEZ73f0CkXaXp7hrann";
 const DEFAULT_CALLBACK_PORT: u16 = 1455;
 const CODEX_API_BASE_URL: &str = "https://chatgpt.com/backend-api/codex";
 const DEFAULT_API_BASE_URL: &str = "https://api.openai.com";
@@ -1141,7 +1143,7 @@ impl CodexProvider {
             }
         }
 
-        let resp = req.send().await?;
+        let resp = req.with_request_id().send().await?;
 
         Ok(resp)
     }
@@ -1171,7 +1173,7 @@ impl CodexProvider {
 /// Extracts user information from the JWT ID token returned by OpenAI OAuth.
 /// The account_id is extracted from the `chatgpt_account_id` field in the
 /// `https://api.openai.com/auth` claim, which is required for Codex API calls.
-fn parse_jwt_claims(token: &str) -> (Option<String>, Option<String>) {
+pub fn parse_jwt_claims(token: &str) -> (Option<String>, Option<String>) {
     use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 
     let parts: Vec<&str> = token.split('.').collect();
@@ -1947,441 +1949,3 @@ mod tests {
         assert_eq!(result.unwrap(), "expired_access_token");
     }
 }
-
-// ============================================================================
-// OAuth 登录功能（参考 Antigravity 实现）
-// ============================================================================
-
-use once_cell::sync::Lazy;
-use std::sync::Arc;
-use tokio::sync::{oneshot, RwLock};
-use uuid::Uuid;
-
-/// 全局 Codex OAuth 服务器状态
-/// 用于在重新打开授权对话框时关闭之前的服务器
-static CODEX_OAUTH_SERVER_SHUTDOWN: Lazy<RwLock<Option<oneshot::Sender<()>>>> =
-    Lazy::new(|| RwLock::new(None));
-
-/// 停止之前运行的 Codex OAuth 服务器（如果有）
-pub async fn stop_codex_oauth_server() {
-    let mut guard = CODEX_OAUTH_SERVER_SHUTDOWN.write().await;
-    if let Some(shutdown_tx) = guard.take() {
-        tracing::info!("[Codex OAuth] 关闭之前的 OAuth 服务器");
-        let _ = shutdown_tx.send(());
-        // 给服务器一些时间来关闭
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
-    }
-}
-
-/// OAuth 登录成功后的凭证信息
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CodexOAuthResult {
-    pub credentials: CodexCredentials,
-    pub creds_file_path: String,
-}
-
-/// OpenAI OAuth 固定回调端口（必须与 client_id 注册的回调地址一致）
-const OPENAI_OAUTH_CALLBACK_PORT: u16 = 1455;
-
-/// OpenAI OAuth 固定回调路径（必须与 client_id 注册的回调地址一致）
-const OPENAI_OAUTH_CALLBACK_PATH: &str = "/auth/callback";
-
-/// 生成 OAuth 授权 URL（用于外部浏览器登录）
-///
-/// 注意：OpenAI OAuth 要求 redirect_uri 必须是预先注册的固定地址
-/// Codex CLI 的 client_id 只注册了 http://localhost:1455/auth/callback
-pub fn generate_codex_auth_url(state: &str, code_challenge: &str) -> String {
-    let redirect_uri =
-        format!("http://localhost:{OPENAI_OAUTH_CALLBACK_PORT}{OPENAI_OAUTH_CALLBACK_PATH}");
-
-    let params = [
-        ("client_id", OPENAI_CLIENT_ID),
-        ("response_type", "code"),
-        ("redirect_uri", redirect_uri.as_str()),
-        // 使用基础 scope，与 CLIProxyAPI 保持一致
-        ("scope", "openid email profile offline_access"),
-        ("state", state),
-        ("code_challenge", code_challenge),
-        ("code_challenge_method", "S256"),
-        ("prompt", "login"),
-        ("id_token_add_organizations", "true"),
-        ("codex_cli_simplified_flow", "true"),
-    ];
-
-    let query = params
-        .iter()
-        .map(|(k, v)| format!("{}={}", k, urlencoding::encode(v)))
-        .collect::<Vec<_>>()
-        .join("&");
-
-    format!("{OPENAI_AUTH_URL}?{query}")
-}
-
-/// 用授权码交换 Token
-pub async fn exchange_codex_code_for_token(
-    client: &Client,
-    code: &str,
-    code_verifier: &str,
-    redirect_uri: &str,
-) -> Result<serde_json::Value, Box<dyn Error + Send + Sync>> {
-    let params = [
-        ("grant_type", "authorization_code"),
-        ("client_id", OPENAI_CLIENT_ID),
-        ("code", code),
-        ("redirect_uri", redirect_uri),
-        ("code_verifier", code_verifier),
-    ];
-
-    let resp = client
-        .post(OPENAI_TOKEN_URL)
-        .header("Content-Type", "application/x-www-form-urlencoded")
-        .header("Accept", "application/json")
-        .form(&params)
-        .send()
-        .await?;
-
-    if !resp.status().is_success() {
-        let status = resp.status();
-        let body = resp.text().await.unwrap_or_default();
-        return Err(format!("Token 交换失败: {status} - {body}").into());
-    }
-
-    let data: serde_json::Value = resp.json().await?;
-    Ok(data)
-}
-
-/// OAuth 成功页面 HTML
-const CODEX_OAUTH_SUCCESS_HTML: &str = r#"<!DOCTYPE html>
-<html>
-<head>
-    <meta charset="utf-8">
-    <title>授权成功</title>
-    <style>
-        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; display: flex; justify-content: center; align-items: center; height: 100vh; margin: 0; background: linear-gradient(135deg, #10a37f 0%, #1a7f64 100%); }
-        .container { text-align: center; background: white; padding: 40px 60px; border-radius: 16px; box-shadow: 0 10px 40px rgba(0,0,0,0.2); }
-        h1 { color: #10a37f; margin-bottom: 16px; }
-        p { color: #666; margin-bottom: 8px; }
-        .email { color: #333; font-weight: 500; }
-    </style>
-</head>
-<body>
-    <div class="container">
-        <h1>✓ 授权成功</h1>
-        <p>Codex 账号已添加到 Lime</p>
-        <p class="email">EMAIL_PLACEHOLDER</p>
-        <p style="margin-top: 20px; color: #999;">可以关闭此页面</p>
-    </div>
-</body>
-</html>"#;
-
-/// OAuth 失败页面 HTML
-const CODEX_OAUTH_ERROR_HTML: &str = r#"<!DOCTYPE html>
-<html>
-<head>
-    <meta charset="utf-8">
-    <title>授权失败</title>
-    <style>
-        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; display: flex; justify-content: center; align-items: center; height: 100vh; margin: 0; background: linear-gradient(135deg, #ef4444 0%, #dc2626 100%); }
-        .container { text-align: center; background: white; padding: 40px 60px; border-radius: 16px; box-shadow: 0 10px 40px rgba(0,0,0,0.2); }
-        h1 { color: #ef4444; margin-bottom: 16px; }
-        p { color: #666; }
-        .error { color: #ef4444; font-size: 14px; margin-top: 16px; }
-    </style>
-</head>
-<body>
-    <div class="container">
-        <h1>✗ 授权失败</h1>
-        <p>ERROR_PLACEHOLDER</p>
-        <p style="margin-top: 20px; color: #999;">请关闭此页面后重试</p>
-    </div>
-</body>
-</html>"#;
-
-/// 启动 OAuth 服务器并返回授权 URL（不打开浏览器）
-/// 服务器会在后台等待回调，成功后返回凭证
-///
-/// 注意：OpenAI OAuth 要求使用固定的回调地址 http://localhost:1455/auth/callback
-pub async fn start_codex_oauth_server_and_get_url() -> Result<
-    (
-        String,
-        impl std::future::Future<Output = Result<CodexOAuthResult, Box<dyn Error + Send + Sync>>>,
-    ),
-    Box<dyn Error + Send + Sync>,
-> {
-    use axum::{extract::Query, response::Html, routing::get, Router};
-    use std::collections::HashMap;
-    use tokio::net::TcpListener;
-
-    // 首先停止之前可能运行的 OAuth 服务器
-    stop_codex_oauth_server().await;
-
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()?;
-
-    // 生成 PKCE codes
-    let pkce_codes = PKCECodes::generate()?;
-    let code_verifier = pkce_codes.code_verifier.clone();
-    let code_challenge = pkce_codes.code_challenge.clone();
-
-    // 生成随机 state
-    let state = Uuid::new_v4().to_string();
-    let state_clone = state.clone();
-
-    // 创建 channel 用于接收回调结果
-    let (tx, rx) = oneshot::channel::<Result<CodexOAuthResult, String>>();
-    let tx = Arc::new(tokio::sync::Mutex::new(Some(tx)));
-
-    // 创建 shutdown channel
-    let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
-
-    // 保存 shutdown sender 到全局状态
-    {
-        let mut guard = CODEX_OAUTH_SERVER_SHUTDOWN.write().await;
-        *guard = Some(shutdown_tx);
-    }
-
-    // 使用固定端口 1455（OpenAI OAuth 要求）
-    let port = OPENAI_OAUTH_CALLBACK_PORT;
-    let listener = TcpListener::bind(format!("127.0.0.1:{port}")).await.map_err(|e| {
-        if e.kind() == std::io::ErrorKind::AddrInUse {
-            format!(
-                "端口 {port} 已被占用。OpenAI OAuth 要求使用固定端口 1455，请关闭占用该端口的应用后重试。"
-            )
-        } else {
-            format!("绑定端口 {port} 失败: {e}")
-        }
-    })?;
-
-    let redirect_uri =
-        format!("http://localhost:{OPENAI_OAUTH_CALLBACK_PORT}{OPENAI_OAUTH_CALLBACK_PATH}");
-    let redirect_uri_clone = redirect_uri.clone();
-
-    // 生成授权 URL（不再传入 port 参数）
-    let auth_url = generate_codex_auth_url(&state, &code_challenge);
-
-    tracing::info!(
-        "[Codex OAuth] 服务器启动在端口 {}, 授权 URL: {}",
-        port,
-        auth_url
-    );
-
-    // 构建路由（使用固定的回调路径 /auth/callback）
-    let app = Router::new().route(
-        OPENAI_OAUTH_CALLBACK_PATH,
-        get(move |Query(params): Query<HashMap<String, String>>| {
-            let tx = tx.clone();
-            let client = client.clone();
-            let state_expected = state_clone.clone();
-            let redirect_uri = redirect_uri_clone.clone();
-            let code_verifier = code_verifier.clone();
-
-            async move {
-                let code = params.get("code");
-                let returned_state = params.get("state");
-                let error = params.get("error");
-
-                // 检查错误
-                if let Some(err) = error {
-                    let html = CODEX_OAUTH_ERROR_HTML.replace("ERROR_PLACEHOLDER", err);
-                    if let Some(sender) = tx.lock().await.take() {
-                        let _ = sender.send(Err(format!("OAuth 错误: {err}")));
-                    }
-                    return Html(html);
-                }
-
-                // 检查 state
-                if returned_state.map(|s| s.as_str()) != Some(&state_expected) {
-                    let html =
-                        CODEX_OAUTH_ERROR_HTML.replace("ERROR_PLACEHOLDER", "State 验证失败");
-                    if let Some(sender) = tx.lock().await.take() {
-                        let _ = sender.send(Err("State 验证失败".to_string()));
-                    }
-                    return Html(html);
-                }
-
-                // 检查 code
-                let code = match code {
-                    Some(c) => c,
-                    None => {
-                        let html =
-                            CODEX_OAUTH_ERROR_HTML.replace("ERROR_PLACEHOLDER", "未收到授权码");
-                        if let Some(sender) = tx.lock().await.take() {
-                            let _ = sender.send(Err("未收到授权码".to_string()));
-                        }
-                        return Html(html);
-                    }
-                };
-
-                // 交换 Token
-                let token_result =
-                    exchange_codex_code_for_token(&client, code, &code_verifier, &redirect_uri)
-                        .await;
-                let token_data = match token_result {
-                    Ok(data) => data,
-                    Err(e) => {
-                        let html =
-                            CODEX_OAUTH_ERROR_HTML.replace("ERROR_PLACEHOLDER", &e.to_string());
-                        if let Some(sender) = tx.lock().await.take() {
-                            let _ = sender.send(Err(e.to_string()));
-                        }
-                        return Html(html);
-                    }
-                };
-
-                let access_token = token_data["access_token"].as_str().unwrap_or_default();
-                let refresh_token = token_data["refresh_token"].as_str().map(|s| s.to_string());
-                let id_token = token_data["id_token"].as_str().map(|s| s.to_string());
-                let expires_in = token_data["expires_in"].as_i64();
-
-                // 解析 ID Token 获取用户信息
-                let (account_id, email) = if let Some(ref id_token) = id_token {
-                    parse_jwt_claims(id_token)
-                } else {
-                    (None, None)
-                };
-
-                // 构建凭证
-                let now = chrono::Utc::now();
-                let credentials = CodexCredentials {
-                    id_token,
-                    access_token: Some(access_token.to_string()),
-                    refresh_token,
-                    api_key: None,
-                    api_base_url: None,
-                    account_id,
-                    last_refresh: Some(now.to_rfc3339()),
-                    email: email.clone(),
-                    r#type: "codex".to_string(),
-                    expires_at: expires_in
-                        .map(|e| (now + chrono::Duration::seconds(e)).to_rfc3339()),
-                };
-
-                // 保存凭证到应用数据目录
-                let creds_dir = dirs::data_dir()
-                    .unwrap_or_else(|| PathBuf::from("."))
-                    .join("lime")
-                    .join("credentials")
-                    .join("codex");
-
-                if let Err(e) = std::fs::create_dir_all(&creds_dir) {
-                    let html = CODEX_OAUTH_ERROR_HTML
-                        .replace("ERROR_PLACEHOLDER", &format!("创建目录失败: {e}"));
-                    if let Some(sender) = tx.lock().await.take() {
-                        let _ = sender.send(Err(format!("创建目录失败: {e}")));
-                    }
-                    return Html(html);
-                }
-
-                // 生成唯一文件名
-                let uuid = Uuid::new_v4().to_string();
-                let timestamp = std::time::SystemTime::now()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap_or_default()
-                    .as_secs();
-                let filename = format!("codex_{}_{}.json", &uuid[..8], timestamp);
-                let creds_file_path = creds_dir.join(&filename);
-
-                // 保存凭证
-                let creds_json = match serde_json::to_string_pretty(&credentials) {
-                    Ok(json) => json,
-                    Err(e) => {
-                        let html = CODEX_OAUTH_ERROR_HTML
-                            .replace("ERROR_PLACEHOLDER", &format!("序列化凭证失败: {e}"));
-                        if let Some(sender) = tx.lock().await.take() {
-                            let _ = sender.send(Err(format!("序列化凭证失败: {e}")));
-                        }
-                        return Html(html);
-                    }
-                };
-
-                if let Err(e) = std::fs::write(&creds_file_path, &creds_json) {
-                    let html = CODEX_OAUTH_ERROR_HTML
-                        .replace("ERROR_PLACEHOLDER", &format!("保存凭证失败: {e}"));
-                    if let Some(sender) = tx.lock().await.take() {
-                        let _ = sender.send(Err(format!("保存凭证失败: {e}")));
-                    }
-                    return Html(html);
-                }
-
-                tracing::info!("[Codex OAuth] 凭证已保存到: {:?}", creds_file_path);
-
-                // 发送成功结果
-                let result = CodexOAuthResult {
-                    credentials,
-                    creds_file_path: creds_file_path.to_string_lossy().to_string(),
-                };
-
-                if let Some(sender) = tx.lock().await.take() {
-                    let _ = sender.send(Ok(result));
-                }
-
-                // 返回成功页面
-                let html = CODEX_OAUTH_SUCCESS_HTML.replace(
-                    "EMAIL_PLACEHOLDER",
-                    &email.unwrap_or_else(|| "未知邮箱".to_string()),
-                );
-                Html(html)
-            }
-        }),
-    );
-
-    // 启动服务器（支持优雅关闭）
-    let server = axum::serve(listener, app).with_graceful_shutdown(async move {
-        let _ = shutdown_rx.await;
-        tracing::info!("[Codex OAuth] 服务器收到关闭信号");
-    });
-
-    // 创建等待 future
-    let wait_future = async move {
-        // 设置超时（5 分钟）
-        let timeout = tokio::time::timeout(std::time::Duration::from_secs(300), async {
-            // 启动服务器（在后台运行）
-            tokio::spawn(async move {
-                if let Err(e) = server.await {
-                    tracing::error!("[Codex OAuth] 服务器错误: {}", e);
-                }
-            });
-
-            // 等待回调结果
-            match rx.await {
-                Ok(result) => result.map_err(|e| {
-                    Box::new(std::io::Error::other(e)) as Box<dyn Error + Send + Sync>
-                }),
-                Err(_) => Err("OAuth 回调通道关闭".into()),
-            }
-        });
-
-        match timeout.await {
-            Ok(result) => {
-                // 成功或失败后都清理全局状态
-                let mut guard = CODEX_OAUTH_SERVER_SHUTDOWN.write().await;
-                *guard = None;
-                result
-            }
-            Err(_) => {
-                // 超时后也清理全局状态
-                let mut guard = CODEX_OAUTH_SERVER_SHUTDOWN.write().await;
-                *guard = None;
-                Err("OAuth 登录超时（5分钟）".into())
-            }
-        }
-    };
-
-    Ok((auth_url, wait_future))
-}
-
-/// 启动 Codex OAuth 登录流程（自动打开浏览器）
-pub async fn start_codex_oauth_login() -> Result<CodexOAuthResult, Box<dyn Error + Send + Sync>> {
-    let (auth_url, wait_future) = start_codex_oauth_server_and_get_url().await?;
-
-    tracing::info!("[Codex OAuth] 打开浏览器进行授权: {}", auth_url);
-
-    // 打开浏览器
-    if let Err(e) = open::that(&auth_url) {
-        tracing::warn!("[Codex OAuth] 无法打开浏览器: {}. 请手动打开 URL.", e);
-    }
-
-    // 等待回调
-    wait_future.await
-}
@@ -10,6 +10,7 @@ use super::error::{
 };
 use super::traits::{CredentialProvider, ProviderResult};
 use async_trait::async_trait;
+use lime_core::processor::RequestIdExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
@@ -30,12 +31,12 @@ const DEFAULT_GEMINI_OAUTH_CLIENT_ID: &str =
 const DEFAULT_GEMINI_OAUTH_CLIENT_SECRET: &str = "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";
 
 // OAuth 凭证 - 优先从环境变量读取，否则使用硬编码的默认值
-fn get_oauth_client_id() -> String {
+pub fn get_oauth_client_id() -> String {
     std::env::var("GEMINI_OAUTH_CLIENT_ID")
         .unwrap_or_else(|_| DEFAULT_GEMINI_OAUTH_CLIENT_ID.to_string())
 }
 
-fn get_oauth_client_secret() -> String {
+pub fn get_oauth_client_secret() -> String {
     std::env::var("GEMINI_OAUTH_CLIENT_SECRET")
         .unwrap_or_else(|_| DEFAULT_GEMINI_OAUTH_CLIENT_SECRET.to_string())
 }
@@ -395,6 +396,7 @@ impl GeminiProvider {
             .header("Authorization", format!("Bearer {token}"))
             .header("Content-Type", "application/json")
             .json(body)
+            .with_request_id()
             .send()
             .await?;
 
@@ -608,6 +610,7 @@ impl GeminiApiKeyProvider {
             .header("x-goog-api-key", &credential.api_key)
             .header("Content-Type", "application/json")
             .json(body)
+            .with_request_id()
             .send()
             .await?;
 
@@ -639,6 +642,7 @@ impl GeminiApiKeyProvider {
             .header("x-goog-api-key", &credential.api_key)
             .header("Content-Type", "application/json")
             .json(body)
+            .with_request_id()
             .send()
             .await?;
 
@@ -760,8 +764,6 @@ mod gemini_api_key_tests {
 // Gemini OAuth 登录功能
 // ============================================================================
 
-use std::sync::Arc;
-use tokio::sync::oneshot;
 use uuid::Uuid;
 
 // Gemini CLI OAuth 配置 - 与 claude-relay-service 对齐
@@ -1023,53 +1025,6 @@ pub async fn fetch_gemini_project_id(
     }
 }
 
-/// OAuth 成功页面 HTML
-const GEMINI_OAUTH_SUCCESS_HTML: &str = r#"<!DOCTYPE html>
-<html>
-<head>
-    <meta charset="utf-8">
-    <title>授权成功</title>
-    <style>
-        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; display: flex; justify-content: center; align-items: center; height: 100vh; margin: 0; background: linear-gradient(135deg, #4285f4 0%, #34a853 100%); }
-        .container { text-align: center; background: white; padding: 40px 60px; border-radius: 16px; box-shadow: 0 10px 40px rgba(0,0,0,0.2); }
-        h1 { color: #22c55e; margin-bottom: 16px; }
-        p { color: #666; margin-bottom: 8px; }
-        .email { color: #333; font-weight: 500; }
-    </style>
-</head>
-<body>
-    <div class="container">
-        <h1>✓ 授权成功</h1>
-        <p>Gemini 账号已添加到 Lime</p>
-        <p class="email">EMAIL_PLACEHOLDER</p>
-        <p style="margin-top: 20px; color: #999;">可以关闭此页面</p>
-    </div>
-</body>
-</html>"#;
-
-/// OAuth 失败页面 HTML
-const GEMINI_OAUTH_ERROR_HTML: &str = r#"<!DOCTYPE html>
-<html>
-<head>
-    <meta charset="utf-8">
-    <title>授权失败</title>
-    <style>
-        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; display: flex; justify-content: center; align-items: center; height: 100vh; margin: 0; background: linear-gradient(135deg, #4285f4 0%, #34a853 100%); }
-        .container { text-align: center; background: white; padding: 40px 60px; border-radius: 16px; box-shadow: 0 10px 40px rgba(0,0,0,0.2); }
-        h1 { color: #ef4444; margin-bottom: 16px; }
-        p { color: #666; }
-        .error { color: #ef4444; font-size: 14px; margin-top: 16px; }
-    </style>
-</head>
-<body>
-    <div class="container">
-        <h1>✗ 授权失败</h1>
-        <p>ERROR_PLACEHOLDER</p>
-        <p style="margin-top: 20px; color: #999;">请关闭此页面后重试</p>
-    </div>
-</body>
-</html>"#;
-
 /// 保存 Gemini 凭证到文件
 async fn save_gemini_credentials_to_file(
     credentials: &GeminiCredentials,
@@ -1101,266 +1056,6 @@ async fn save_gemini_credentials_to_file(
     Ok(file_path.to_string_lossy().to_string())
 }
 
-/// 启动 OAuth 服务器并返回授权 URL（不打开浏览器）
-/// 服务器会在后台等待回调，成功后返回凭证
-pub async fn start_gemini_oauth_server_and_get_url() -> Result<
-    (
-        String,
-        impl std::future::Future<
-            Output = Result<GeminiOAuthResult, Box<dyn std::error::Error + Send + Sync>>,
-        >,
-    ),
-    Box<dyn std::error::Error + Send + Sync>,
-> {
-    use axum::{extract::Query, response::Html, routing::get, Router};
-    use std::collections::HashMap;
-    use tokio::net::TcpListener;
-
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(30))
-        .build()?;
-
-    // 生成 PKCE
-    let (code_verifier, code_challenge) = generate_pkce();
-
-    // 生成随机 state
-    let state = Uuid::new_v4().to_string();
-    let state_clone = state.clone();
-    let code_verifier_clone = code_verifier.clone();
-
-    // 创建 channel 用于接收回调结果
-    let (tx, rx) = oneshot::channel::<Result<GeminiOAuthResult, String>>();
-    let tx = Arc::new(tokio::sync::Mutex::new(Some(tx)));
-
-    // 尝试绑定到多个端口
-    let ports_to_try = [11451, 11452, 11453, 11454, 11455, 0];
-    let mut listener = None;
-    let mut bound_port = 0;
-
-    for port in ports_to_try {
-        match TcpListener::bind(format!("127.0.0.1:{port}")).await {
-            Ok(l) => {
-                bound_port = l.local_addr()?.port();
-                listener = Some(l);
-                tracing::info!("[Gemini OAuth] 成功绑定到端口 {}", bound_port);
-                break;
-            }
-            Err(e) => {
-                tracing::warn!("[Gemini OAuth] 端口 {} 绑定失败: {}", port, e);
-                continue;
-            }
-        }
-    }
-
-    let listener = listener.ok_or("无法绑定到任何可用端口")?;
-
-    // 生成授权 URL
-    let auth_url = generate_gemini_auth_url(&state, &code_challenge);
-
-    tracing::info!(
-        "[Gemini OAuth] 服务器启动在端口 {}, 授权 URL: {}",
-        bound_port,
-        auth_url
-    );
-
-    // 构建路由
-    let app = Router::new().route(
-        "/oauth-callback",
-        get(move |Query(params): Query<HashMap<String, String>>| {
-            let tx = tx.clone();
-            let client = client.clone();
-            let state_expected = state_clone.clone();
-            let code_verifier = code_verifier_clone.clone();
-
-            async move {
-                let code = params.get("code");
-                let returned_state = params.get("state");
-                let error = params.get("error");
-
-                // 检查错误
-                if let Some(err) = error {
-                    let error_desc = params
-                        .get("error_description")
-                        .map(|s| s.as_str())
-                        .unwrap_or("未知错误");
-                    let error_msg = format!("{err}: {error_desc}");
-                    tracing::error!("[Gemini OAuth] 授权失败: {}", error_msg);
-
-                    if let Some(tx) = tx.lock().await.take() {
-                        let _ = tx.send(Err(error_msg.clone()));
-                    }
-
-                    let html = GEMINI_OAUTH_ERROR_HTML.replace("ERROR_PLACEHOLDER", &error_msg);
-                    return Html(html);
-                }
-
-                // 验证 state
-                if returned_state.map(|s| s.as_str()) != Some(&state_expected) {
-                    let error_msg = "State 验证失败";
-                    tracing::error!("[Gemini OAuth] {}", error_msg);
-
-                    if let Some(tx) = tx.lock().await.take() {
-                        let _ = tx.send(Err(error_msg.to_string()));
-                    }
-
-                    let html = GEMINI_OAUTH_ERROR_HTML.replace("ERROR_PLACEHOLDER", error_msg);
-                    return Html(html);
-                }
-
-                // 获取授权码
-                let code = match code {
-                    Some(c) => c,
-                    None => {
-                        let error_msg = "未收到授权码";
-                        tracing::error!("[Gemini OAuth] {}", error_msg);
-
-                        if let Some(tx) = tx.lock().await.take() {
-                            let _ = tx.send(Err(error_msg.to_string()));
-                        }
-
-                        let html = GEMINI_OAUTH_ERROR_HTML.replace("ERROR_PLACEHOLDER", error_msg);
-                        return Html(html);
-                    }
-                };
-
-                tracing::info!("[Gemini OAuth] 收到授权码，正在交换 Token...");
-
-                // 交换 Token
-                let token_result =
-                    exchange_gemini_code_for_token(&client, code, &code_verifier).await;
-
-                match token_result {
-                    Ok(token_data) => {
-                        let access_token = token_data["access_token"]
-                            .as_str()
-                            .unwrap_or("")
-                            .to_string();
-                        let refresh_token =
-                            token_data["refresh_token"].as_str().map(|s| s.to_string());
-                        let expires_in = token_data["expires_in"].as_i64();
-
-                        // 获取用户邮箱
-                        let email = fetch_gemini_user_email(&client, &access_token)
-                            .await
-                            .ok()
-                            .flatten();
-
-                        // 获取项目 ID
-                        let project_id = fetch_gemini_project_id(&client, &access_token)
-                            .await
-                            .ok()
-                            .flatten();
-
-                        // 构建凭证
-                        let now = chrono::Utc::now();
-                        let expires_at =
-                            expires_in.map(|secs| now + chrono::Duration::seconds(secs));
-
-                        let credentials = GeminiCredentials {
-                            access_token: Some(access_token),
-                            refresh_token,
-                            token_type: Some("Bearer".to_string()),
-                            expiry_date: expires_at.map(|t| t.timestamp_millis()),
-                            expire: expires_at.map(|t| t.to_rfc3339()),
-                            scope: Some(GEMINI_OAUTH_SCOPES.join(" ")),
-                            email: email.clone(),
-                            last_refresh: Some(now.to_rfc3339()),
-                            cred_type: "gemini".to_string(),
-                            token: None,
-                        };
-
-                        // 保存凭证到文件
-                        match save_gemini_credentials_to_file(&credentials).await {
-                            Ok(file_path) => {
-                                tracing::info!("[Gemini OAuth] 凭证已保存到: {}", file_path);
-
-                                let result = GeminiOAuthResult {
-                                    credentials: credentials.clone(),
-                                    creds_file_path: file_path,
-                                };
-
-                                if let Some(tx) = tx.lock().await.take() {
-                                    let _ = tx.send(Ok(result));
-                                }
-
-                                let email_display = email.unwrap_or_else(|| "未知邮箱".to_string());
-                                let project_display = project_id
-                                    .map(|p| format!("<p>Project ID: {p}</p>"))
-                                    .unwrap_or_default();
-                                let html = GEMINI_OAUTH_SUCCESS_HTML
-                                    .replace("EMAIL_PLACEHOLDER", &email_display)
-                                    .replace(
-                                        "</div>\n</body>",
-                                        &format!("{project_display}</div>\n</body>"),
-                                    );
-                                Html(html)
-                            }
-                            Err(e) => {
-                                let error_msg = format!("保存凭证失败: {e}");
-                                tracing::error!("[Gemini OAuth] {}", error_msg);
-
-                                if let Some(tx) = tx.lock().await.take() {
-                                    let _ = tx.send(Err(error_msg.clone()));
-                                }
-
-                                let html = GEMINI_OAUTH_ERROR_HTML
-                                    .replace("ERROR_PLACEHOLDER", &error_msg);
-                                Html(html)
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        let error_msg = format!("Token 交换失败: {e}");
-                        tracing::error!("[Gemini OAuth] {}", error_msg);
-
-                        if let Some(tx) = tx.lock().await.take() {
-                            let _ = tx.send(Err(error_msg.clone()));
-                        }
-
-                        let html = GEMINI_OAUTH_ERROR_HTML.replace("ERROR_PLACEHOLDER", &error_msg);
-                        Html(html)
-                    }
-                }
-            }
-        }),
-    );
-
-    // 启动服务器
-    let server_future = async move {
-        axum::serve(listener, app)
-            .await
-            .map_err(|e| format!("服务器错误: {e}"))
-    };
-
-    // 启动服务器任务
-    tokio::spawn(server_future);
-
-    // 返回授权 URL 和等待结果的 Future
-    let wait_future = async move {
-        match rx.await {
-            Ok(result) => result.map_err(|e| e.into()),
-            Err(_) => Err("OAuth 回调通道关闭".into()),
-        }
-    };
-
-    Ok((auth_url, wait_future))
-}
-
-/// 启动 Gemini OAuth 登录流程（自动打开浏览器）
-pub async fn start_gemini_oauth_login(
-) -> Result<GeminiOAuthResult, Box<dyn std::error::Error + Send + Sync>> {
-    let (auth_url, wait_future) = start_gemini_oauth_server_and_get_url().await?;
-
-    // 打开浏览器
-    tracing::info!("[Gemini OAuth] 正在打开浏览器...");
-    if let Err(e) = open::that(&auth_url) {
-        tracing::warn!("[Gemini OAuth] 无法自动打开浏览器: {}", e);
-    }
-
-    // 等待回调
-    wait_future.await
-}
-
 // ============================================================================
 // CredentialProvider Trait 实现
 // ============================================================================
@@ -9,6 +9,7 @@ use crate::translator::kiro::openai::request::convert_openai_to_codewhisperer_wi
 use async_trait::async_trait;
 use lime_core::models::anthropic::AnthropicMessagesRequest;
 use lime_core::models::openai::*;
+use lime_core::processor::RequestIdExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::error::Error;
@@ -1093,6 +1094,7 @@ impl KiroProvider {
             // 添加 Connection: close 避免连接复用被检测
             .header("Connection", "close")
             .json(&cw_request)
+            .with_request_id()
             .send()
             .await?;
 
@@ -1158,6 +1160,7 @@ impl KiroProvider {
             )
             // 注意：不要设置 Connection: close，否则会导致流式响应无法工作
             .json(&cw_request)
+            .with_request_id()
             .send()
             .await
             .map_err(|e| {
@@ -1357,6 +1360,7 @@ impl KiroProvider {
                 ),
             )
             .json(&cw_request)
+            .with_request_id()
             .send()
             .await
             .map_err(|e| {
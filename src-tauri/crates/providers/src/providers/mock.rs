@@ -0,0 +1,216 @@
+//! Mock Provider（测试模式）
+//!
+//! 返回确定性的模拟响应，支持配置延迟与故障注入（429、500、非法 JSON），
+//! 作为普通凭证注册到凭证池，用于在不消耗真实额度的情况下测试路由、
+//! 风控与 UI 流程。
+
+use lime_core::models::openai::ChatCompletionRequest;
+use lime_core::models::provider_pool_model::{MockFault, MockProviderConfig};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// 每个 Mock 凭证的累计请求次数（用于按序注入故障）
+static CALL_COUNTERS: OnceLock<Mutex<HashMap<String, u64>>> = OnceLock::new();
+
+/// Mock 请求结果
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockOutcome {
+    /// 正常响应
+    Success(MockReply),
+    /// 注入的故障
+    Fault(MockFault),
+}
+
+/// Mock 正常响应内容
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockReply {
+    pub content: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+}
+
+pub struct MockProvider {
+    credential_id: String,
+    config: MockProviderConfig,
+}
+
+impl MockProvider {
+    pub fn new(credential_id: impl Into<String>, config: MockProviderConfig) -> Self {
+        Self {
+            credential_id: credential_id.into(),
+            config,
+        }
+    }
+
+    /// 处理一次请求：等待配置的延迟后返回响应或注入的故障
+    pub async fn respond(&self, request: &ChatCompletionRequest) -> MockOutcome {
+        if self.config.latency_ms > 0 {
+            tokio::time::sleep(Duration::from_millis(self.config.latency_ms)).await;
+        }
+        self.respond_now(request)
+    }
+
+    fn respond_now(&self, request: &ChatCompletionRequest) -> MockOutcome {
+        if let Some(fault) = self.config.fault_for_call(self.next_call_index()) {
+            return MockOutcome::Fault(fault);
+        }
+
+        let prompt = request
+            .messages
+            .iter()
+            .rev()
+            .find(|m| m.role == "user")
+            .map(|m| m.get_content_text())
+            .unwrap_or_default();
+        let content = match &self.config.response_text {
+            Some(text) => text.clone(),
+            None => format!("[mock] {}", prompt.trim()),
+        };
+        let input_tokens = request
+            .messages
+            .iter()
+            .map(|m| estimate_tokens(&m.get_content_text()))
+            .sum();
+
+        MockOutcome::Success(MockReply {
+            output_tokens: estimate_tokens(&content),
+            content,
+            input_tokens,
+        })
+    }
+
+    fn next_call_index(&self) -> u64 {
+        let counters = CALL_COUNTERS.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut counters = counters.lock().unwrap_or_else(|e| e.into_inner());
+        let count = counters.entry(self.credential_id.clone()).or_insert(0);
+        *count += 1;
+        *count
+    }
+}
+
+/// 构建 OpenAI Chat Completion 响应体
+pub fn build_openai_completion(model: &str, reply: &MockReply) -> serde_json::Value {
+    serde_json::json!({
+        "id": format!("chatcmpl-mock-{}", uuid::Uuid::new_v4()),
+        "object": "chat.completion",
+        "created": chrono::Utc::now().timestamp(),
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": { "role": "assistant", "content": reply.content },
+            "finish_reason": "stop"
+        }],
+        "usage": {
+            "prompt_tokens": reply.input_tokens,
+            "completion_tokens": reply.output_tokens,
+            "total_tokens": reply.input_tokens + reply.output_tokens
+        }
+    })
+}
+
+/// 构建 OpenAI SSE 流式响应体
+pub fn build_openai_sse(model: &str, reply: &MockReply) -> String {
+    let id = format!("chatcmpl-mock-{}", uuid::Uuid::new_v4());
+    let created = chrono::Utc::now().timestamp();
+    let chunk = |delta: serde_json::Value, finish_reason: Option<&str>| {
+        serde_json::json!({
+            "id": &id,
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": model,
+            "choices": [{ "index": 0, "delta": delta, "finish_reason": finish_reason }]
+        })
+    };
+
+    let mut sse = String::new();
+    sse.push_str(&format!(
+        "data: {}\n\n",
+        chunk(
+            serde_json::json!({ "role": "assistant", "content": reply.content }),
+            None
+        )
+    ));
+    sse.push_str(&format!(
+        "data: {}\n\n",
+        chunk(serde_json::json!({}), Some("stop"))
+    ));
+    sse.push_str("data: [DONE]\n\n");
+    sse
+}
+
+/// 故障对应的 HTTP 状态码与响应体
+pub fn fault_response(fault: MockFault) -> (u16, String) {
+    match fault {
+        MockFault::RateLimited => (
+            429,
+            serde_json::json!({
+                "error": { "type": "rate_limit_error", "message": "Mock provider: rate limited" }
+            })
+            .to_string(),
+        ),
+        MockFault::ServerError => (
+            500,
+            serde_json::json!({
+                "error": { "type": "server_error", "message": "Mock provider: internal error" }
+            })
+            .to_string(),
+        ),
+        MockFault::MalformedJson => (200, "{\"choices\": [{\"message\": ".to_string()),
+    }
+}
+
+/// 粗略估算 token 数（约 4 字符 1 token）
+fn estimate_tokens(text: &str) -> u32 {
+    (text.chars().count() as u32).div_ceil(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(prompt: &str) -> ChatCompletionRequest {
+        serde_json::from_value(serde_json::json!({
+            "model": "mock-model",
+            "messages": [{ "role": "user", "content": prompt }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mock_echoes_prompt_deterministically() {
+        let provider = MockProvider::new("mock-echo", MockProviderConfig::default());
+        let MockOutcome::Success(reply) = provider.respond_now(&request("hello")) else {
+            panic!("expected success");
+        };
+        assert_eq!(reply.content, "[mock] hello");
+        assert_eq!(reply.output_tokens, 4);
+
+        let body = build_openai_completion("mock-model", &reply);
+        assert_eq!(body["choices"][0]["message"]["content"], "[mock] hello");
+    }
+
+    #[test]
+    fn test_mock_injects_faults_on_schedule() {
+        let provider = MockProvider::new(
+            "mock-fault",
+            MockProviderConfig {
+                response_text: Some("ok".to_string()),
+                fault: Some(MockFault::ServerError),
+                fault_every: 2,
+                ..MockProviderConfig::default()
+            },
+        );
+        let outcomes: Vec<_> = (0..4)
+            .map(|_| provider.respond_now(&request("x")))
+            .collect();
+        assert!(matches!(outcomes[0], MockOutcome::Success(_)));
+        assert_eq!(outcomes[1], MockOutcome::Fault(MockFault::ServerError));
+        assert!(matches!(outcomes[2], MockOutcome::Success(_)));
+        assert_eq!(outcomes[3], MockOutcome::Fault(MockFault::ServerError));
+
+        let (status, body) = fault_response(MockFault::MalformedJson);
+        assert_eq!(status, 200);
+        assert!(serde_json::from_str::<serde_json::Value>(&body).is_err());
+    }
+}
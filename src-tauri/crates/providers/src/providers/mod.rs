@@ -5,8 +5,10 @@ pub mod codex;
 pub mod error;
 pub mod gemini;
 pub mod kiro;
+pub mod mock;
 pub mod novita;
 pub mod openai_custom;
+pub mod quick_fix;
 pub mod traits;
 pub mod vertex;
 
@@ -36,6 +38,8 @@ pub use gemini::{GeminiApiKeyCredential, GeminiApiKeyProvider, GeminiProvider};
 #[allow(unused_imports)]
 pub use kiro::KiroProvider;
 #[allow(unused_imports)]
+pub use mock::{MockOutcome, MockProvider, MockReply};
+#[allow(unused_imports)]
 pub use novita::{
     NovitaProvider, NOVITA_API_BASE_URL, NOVITA_DEFAULT_MODEL, NOVITA_EMBEDDING_MODEL,
     NOVITA_SUPPORTED_MODELS,
@@ -43,4 +47,6 @@ pub use novita::{
 #[allow(unused_imports)]
 pub use openai_custom::OpenAICustomProvider;
 #[allow(unused_imports)]
+pub use quick_fix::{QuickFix, QuickFixAction, QuickFixContext};
+#[allow(unused_imports)]
 pub use vertex::VertexProvider;
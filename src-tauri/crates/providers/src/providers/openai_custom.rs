@@ -1,6 +1,8 @@
 //! OpenAI Custom Provider (自定义 OpenAI 兼容 API)
+use crate::converter::logprobs::current_request_logprobs;
 use crate::converter::ReasoningHandler;
 use lime_core::models::openai::{ChatCompletionRequest, ChatMessage};
+use lime_core::processor::RequestIdExt;
 use reqwest::Client;
 use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
@@ -341,6 +343,9 @@ impl OpenAICustomProvider {
         let mut payload =
             serde_json::to_value(request).map_err(|e| format!("序列化 OpenAI 请求失败: {e}"))?;
         self.normalize_openai_request_payload(&mut payload);
+        if let Some(options) = current_request_logprobs() {
+            options.apply_to_payload(&mut payload);
+        }
 
         for url in &urls {
             eprintln!("[OPENAI_CUSTOM] call_api trying URL: {url}");
@@ -350,6 +355,7 @@ impl OpenAICustomProvider {
                 .header("Authorization", format!("Bearer {api_key}"))
                 .header("Content-Type", "application/json")
                 .json(&payload)
+                .with_request_id()
                 .send()
                 .await?;
 
@@ -391,6 +397,7 @@ impl OpenAICustomProvider {
             .header("Authorization", format!("Bearer {api_key}"))
             .header("Content-Type", "application/json")
             .json(&payload)
+            .with_request_id()
             .send()
             .await?;
 
@@ -405,6 +412,7 @@ impl OpenAICustomProvider {
                         .header("Authorization", format!("Bearer {api_key}"))
                         .header("Content-Type", "application/json")
                         .json(&payload)
+                        .with_request_id()
                         .send()
                         .await?;
                     Self::maybe_log_protocol_mismatch_hint(&fallback_url, resp2.status());
@@ -495,6 +503,9 @@ impl StreamingProvider for OpenAICustomProvider {
         let mut payload = serde_json::to_value(&stream_request)
             .map_err(|e| ProviderError::ConfigurationError(format!("序列化流式请求失败: {e}")))?;
         self.normalize_openai_request_payload(&mut payload);
+        if let Some(options) = current_request_logprobs() {
+            options.apply_to_payload(&mut payload);
+        }
 
         let url = self.build_url("chat/completions");
 
@@ -511,6 +522,7 @@ impl StreamingProvider for OpenAICustomProvider {
             .header("Content-Type", "application/json")
             .header("Accept", "text/event-stream")
             .json(&payload)
+            .with_request_id()
             .send()
             .await
             .map_err(|e| ProviderError::from_reqwest_error(&e))?;
@@ -524,6 +536,7 @@ impl StreamingProvider for OpenAICustomProvider {
                         .header("Content-Type", "application/json")
                         .header("Accept", "text/event-stream")
                         .json(&payload)
+                        .with_request_id()
                         .send()
                         .await
                         .map_err(|e| ProviderError::from_reqwest_error(&e))?
@@ -0,0 +1,292 @@
+//! Provider 错误的快速修复建议
+//!
+//! 将分类后的 [`ProviderError`] 映射为可一键执行的修复建议（刷新 Token、添加模型别名、
+//! 检查中转地址等），随错误响应返回并以事件推送给前端。
+
+use super::error::ProviderError;
+use serde::{Deserialize, Serialize};
+
+/// 使用 OAuth Token 的凭证池类型（认证失败时可刷新或重新登录）
+const OAUTH_PROVIDERS: &[&str] = &["kiro", "gemini", "antigravity", "codex", "claude_oauth"];
+
+/// 修复动作，前端按 `kind` 渲染对应按钮
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QuickFixAction {
+    /// 强制刷新凭证的 OAuth Token
+    RefreshToken { credential_id: String },
+    /// 重新登录获取新的 OAuth 凭证
+    Relogin {
+        credential_id: String,
+        provider: String,
+    },
+    /// 打开凭证编辑（API Key 无效、配置缺失等）
+    EditCredential { credential_id: String },
+    /// 为模型添加别名或映射
+    AddModelAlias { model: String },
+    /// 检查 base_url / 中转服务可达性
+    CheckEndpoint {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        credential_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base_url: Option<String>,
+    },
+    /// 添加更多凭证分担限流
+    AddCredential { provider: String },
+}
+
+/// 单条修复建议
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuickFix {
+    /// 按钮标题
+    pub title: String,
+    /// 说明
+    pub detail: String,
+    pub action: QuickFixAction,
+}
+
+impl QuickFix {
+    fn new(title: impl Into<String>, detail: impl Into<String>, action: QuickFixAction) -> Self {
+        Self {
+            title: title.into(),
+            detail: detail.into(),
+            action,
+        }
+    }
+}
+
+/// 生成建议所需的请求上下文
+#[derive(Debug, Clone, Default)]
+pub struct QuickFixContext {
+    /// 凭证池类型（如 `kiro`、`openai`）
+    pub provider: Option<String>,
+    pub credential_id: Option<String>,
+    pub model: Option<String>,
+    /// 凭证的自定义 base_url
+    pub base_url: Option<String>,
+}
+
+impl ProviderError {
+    /// 按状态码与错误消息分类
+    ///
+    /// 未提供状态码时尝试从消息中的 `HTTP 404` / `status 404` 提取，仍无法确定时按消息内容推断。
+    pub fn classify(status: Option<u16>, message: &str) -> Self {
+        match status.or_else(|| extract_status(message)) {
+            Some(status) => match ProviderError::from_http_status(status, message) {
+                // 网关返回的 502/504 通常是中转或上游不可达
+                ProviderError::ServerError(_) if is_unreachable(message) => {
+                    ProviderError::NetworkError(message.to_string())
+                }
+                error => error,
+            },
+            None if is_unreachable(message) => ProviderError::NetworkError(message.to_string()),
+            None => ProviderError::from(message.to_string()),
+        }
+    }
+
+    /// 错误对应的修复建议（无可执行建议时为空）
+    pub fn quick_fixes(&self, ctx: &QuickFixContext) -> Vec<QuickFix> {
+        let provider = ctx.provider.as_deref().unwrap_or_default();
+        let credential_id = ctx.credential_id.clone();
+        let is_oauth = OAUTH_PROVIDERS.contains(&provider);
+        let mut fixes = Vec::new();
+
+        match self {
+            ProviderError::TokenExpired(_) | ProviderError::AuthenticationError(_) if is_oauth => {
+                if let Some(id) = credential_id {
+                    let title = if provider == "kiro" {
+                        "刷新 Kiro Token".to_string()
+                    } else {
+                        "刷新 Token".to_string()
+                    };
+                    fixes.push(QuickFix::new(
+                        title,
+                        "access_token 已过期或失效，强制刷新后重试",
+                        QuickFixAction::RefreshToken {
+                            credential_id: id.clone(),
+                        },
+                    ));
+                    if matches!(self, ProviderError::AuthenticationError(_)) {
+                        fixes.push(QuickFix::new(
+                            "重新登录",
+                            "refresh_token 也已失效时需要重新授权",
+                            QuickFixAction::Relogin {
+                                credential_id: id,
+                                provider: provider.to_string(),
+                            },
+                        ));
+                    }
+                }
+            }
+            ProviderError::TokenExpired(_)
+            | ProviderError::AuthenticationError(_)
+            | ProviderError::ConfigurationError(_) => {
+                if let Some(id) = credential_id {
+                    fixes.push(QuickFix::new(
+                        "检查凭证",
+                        "API Key 无效、已撤销或凭证配置不完整",
+                        QuickFixAction::EditCredential { credential_id: id },
+                    ));
+                }
+            }
+            ProviderError::RequestError(msg) if is_model_not_found(msg) => {
+                if let Some(model) = ctx.model.clone() {
+                    fixes.push(QuickFix::new(
+                        "添加模型别名",
+                        format!("模型 {model} 不在上游支持列表中，可映射到可用模型"),
+                        QuickFixAction::AddModelAlias { model },
+                    ));
+                }
+            }
+            ProviderError::NetworkError(_) => {
+                fixes.push(QuickFix::new(
+                    "检查中转地址",
+                    "base_url 无法访问，请确认中转服务可用或切换端点",
+                    QuickFixAction::CheckEndpoint {
+                        credential_id,
+                        base_url: ctx.base_url.clone(),
+                    },
+                ));
+            }
+            ProviderError::ServerError(_) | ProviderError::ParseError(_)
+                if ctx.base_url.is_some() =>
+            {
+                fixes.push(QuickFix::new(
+                    "检查中转地址",
+                    "自定义 base_url 返回异常响应，中转服务可能故障",
+                    QuickFixAction::CheckEndpoint {
+                        credential_id,
+                        base_url: ctx.base_url.clone(),
+                    },
+                ));
+            }
+            ProviderError::RateLimitError(_) if !provider.is_empty() => {
+                fixes.push(QuickFix::new(
+                    "添加凭证",
+                    "当前凭证触发限流，添加更多凭证以分担请求",
+                    QuickFixAction::AddCredential {
+                        provider: provider.to_string(),
+                    },
+                ));
+            }
+            _ => {}
+        }
+        fixes
+    }
+}
+
+/// 从消息中提取 HTTP 状态码
+fn extract_status(message: &str) -> Option<u16> {
+    let lower = message.to_lowercase();
+    ["http ", "status ", "status: ", "status code "]
+        .iter()
+        .find_map(|prefix| {
+            let start = lower.find(prefix)? + prefix.len();
+            let digits: String = lower[start..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            digits
+                .parse::<u16>()
+                .ok()
+                .filter(|code| (400..=599).contains(code))
+        })
+}
+
+fn is_unreachable(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    [
+        "connection refused",
+        "error sending request",
+        "dns error",
+        "无法连接",
+        "bad gateway",
+        "all base urls failed",
+    ]
+    .iter()
+    .any(|pattern| lower.contains(pattern))
+}
+
+fn is_model_not_found(message: &str) -> bool {
+    let lower = message.to_lowercase();
+    lower.contains("model_not_found")
+        || lower.contains("unknown model")
+        || lower.contains("invalid model")
+        || lower.contains("模型不存在")
+        || (lower.contains("model")
+            && (lower.contains("not found")
+                || lower.contains("does not exist")
+                || lower.contains("not supported")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(provider: &str) -> QuickFixContext {
+        QuickFixContext {
+            provider: Some(provider.to_string()),
+            credential_id: Some("cred-1".to_string()),
+            model: Some("gpt-5".to_string()),
+            base_url: Some("https://relay.example.com".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_classify_extracts_status_and_unreachable() {
+        assert!(matches!(
+            ProviderError::classify(None, "Upstream error: HTTP 401 - invalid token"),
+            ProviderError::AuthenticationError(_)
+        ));
+        assert!(matches!(
+            ProviderError::classify(Some(502), "Bad Gateway"),
+            ProviderError::NetworkError(_)
+        ));
+        assert!(matches!(
+            ProviderError::classify(None, "error sending request for url"),
+            ProviderError::NetworkError(_)
+        ));
+    }
+
+    #[test]
+    fn test_quick_fixes_by_error_kind() {
+        let fixes = ProviderError::AuthenticationError("HTTP 403".into()).quick_fixes(&ctx("kiro"));
+        assert_eq!(fixes.len(), 2);
+        assert_eq!(fixes[0].title, "刷新 Kiro Token");
+        assert_eq!(
+            fixes[1].action,
+            QuickFixAction::Relogin {
+                credential_id: "cred-1".to_string(),
+                provider: "kiro".to_string(),
+            }
+        );
+
+        let fixes =
+            ProviderError::AuthenticationError("HTTP 401".into()).quick_fixes(&ctx("openai"));
+        assert!(matches!(
+            fixes[0].action,
+            QuickFixAction::EditCredential { .. }
+        ));
+
+        let fixes =
+            ProviderError::RequestError("HTTP 404 - The model `gpt-5` does not exist".into())
+                .quick_fixes(&ctx("openai"));
+        assert_eq!(
+            fixes[0].action,
+            QuickFixAction::AddModelAlias {
+                model: "gpt-5".to_string()
+            }
+        );
+
+        let fixes =
+            ProviderError::NetworkError("connection refused".into()).quick_fixes(&ctx("openai"));
+        assert!(matches!(
+            fixes[0].action,
+            QuickFixAction::CheckEndpoint { .. }
+        ));
+
+        assert!(ProviderError::Unknown("?".into())
+            .quick_fixes(&ctx("openai"))
+            .is_empty());
+    }
+}
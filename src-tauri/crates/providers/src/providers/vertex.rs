@@ -6,6 +6,7 @@
 #![allow(dead_code)]
 
 use lime_core::models::vertex_model::VertexApiKeyEntry;
+use lime_core::processor::RequestIdExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -192,6 +193,7 @@ impl VertexProvider {
             .header("x-goog-api-key", api_key)
             .header("Content-Type", "application/json")
             .json(&request)
+            .with_request_id()
             .send()
             .await?;
 
@@ -231,6 +233,7 @@ impl VertexProvider {
             .header("x-goog-api-key", api_key)
             .header("Content-Type", "application/json")
             .json(&request)
+            .with_request_id()
             .send()
             .await?;
 
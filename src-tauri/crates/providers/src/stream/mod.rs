@@ -23,14 +23,20 @@
 //! - `generators`: 前端流格式生成器
 //!   - `openai_sse`: OpenAI SSE 格式生成器
 //!   - `anthropic_sse`: Anthropic SSE 格式生成器
+//! - `response`: `lime_core::response_stream::ResponseChunk` 的解码器与 OpenAI SSE 编码器
 
 pub mod events;
 pub mod generators;
 pub mod parsers;
 pub mod pipeline;
+pub mod response;
 
 // 重新导出核心类型
 pub use events::{ContentBlockType, StopReason, StreamContext, StreamEvent};
 pub use generators::{AnthropicSseGenerator, OpenAiSseGenerator};
 pub use parsers::{AwsEventStreamParser, ParserState};
 pub use pipeline::{create_sse_stream, BackendType, FrontendType, PipelineConfig, StreamPipeline};
+pub use response::{
+    decode_sse_body, ChunkDecoder, CodexChunkDecoder, OpenAiChunkDecoder, OpenAiChunkEncoder,
+    SseDataDecoder,
+};
@@ -0,0 +1,497 @@
+//! `ResponseChunk` 编解码
+//!
+//! 将各 Provider 的原生流式协议解码为 `lime_core::response_stream::ResponseChunk`，
+//! 并提供面向 OpenAI Chat Completions SSE 的统一编码器。
+//!
+//! # 组件
+//!
+//! - `SseDataDecoder`: 字节流 → SSE `data` 负载
+//! - `ChunkDecoder`: SSE `data` 负载 → `ResponseChunk`
+//!   - `OpenAiChunkDecoder` / `CodexChunkDecoder`
+//! - `OpenAiChunkEncoder`: `ResponseChunk` → OpenAI SSE
+//! - `decode_sse_body`: 组合上述组件将已缓冲的响应体解码为 chunk 列表
+
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use lime_core::response_stream::{ResponseChunk, ResponseUsage};
+use serde_json::Value;
+
+// ============================================================================
+// SSE 分帧
+// ============================================================================
+
+/// SSE `data` 负载解码器
+///
+/// 按字节缓冲（避免多字节字符被分块截断），以空行分隔事件，
+/// 同一事件的多行 `data:` 以换行拼接；注释行与其他字段忽略。
+#[derive(Debug, Default)]
+pub struct SseDataDecoder {
+    buffer: Vec<u8>,
+}
+
+impl SseDataDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 追加字节，返回已完整的事件负载
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend(bytes.iter().filter(|b| **b != b'\r'));
+
+        let mut payloads = Vec::new();
+        while let Some(pos) = self.buffer.windows(2).position(|w| w == b"\n\n") {
+            let block: Vec<u8> = self.buffer.drain(..pos + 2).collect();
+            if let Some(data) = Self::parse_block(&String::from_utf8_lossy(&block)) {
+                payloads.push(data);
+            }
+        }
+        payloads
+    }
+
+    /// 流结束时处理缓冲区中未以空行结尾的事件
+    pub fn finish(&mut self) -> Vec<String> {
+        let block = std::mem::take(&mut self.buffer);
+        Self::parse_block(&String::from_utf8_lossy(&block))
+            .into_iter()
+            .collect()
+    }
+
+    fn parse_block(block: &str) -> Option<String> {
+        let data: Vec<&str> = block
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|value| value.strip_prefix(' ').unwrap_or(value))
+            .collect();
+        (!data.is_empty()).then(|| data.join("\n"))
+    }
+}
+
+// ============================================================================
+// 解码器
+// ============================================================================
+
+/// 将单个 SSE `data` 负载解码为 `ResponseChunk`
+pub trait ChunkDecoder: Send {
+    /// 解码一个负载，可能产出零个或多个 chunk
+    fn decode(&mut self, data: &str) -> Vec<ResponseChunk>;
+
+    /// 上游流结束时调用，用于补发结束 chunk
+    fn finish(&mut self) -> Vec<ResponseChunk> {
+        Vec::new()
+    }
+}
+
+/// OpenAI Chat Completions SSE 解码器
+///
+/// `finish_reason` 与 `[DONE]` 合并为一个 `Done`；上游未发送 `[DONE]` 时在流结束时补发。
+#[derive(Debug, Default)]
+pub struct OpenAiChunkDecoder {
+    finish_reason: Option<String>,
+    done: bool,
+}
+
+impl OpenAiChunkDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ChunkDecoder for OpenAiChunkDecoder {
+    fn decode(&mut self, data: &str) -> Vec<ResponseChunk> {
+        let data = data.trim();
+        if data == "[DONE]" {
+            return self.finish();
+        }
+        let Ok(value) = serde_json::from_str::<Value>(data) else {
+            return Vec::new();
+        };
+
+        let mut chunks = Vec::new();
+        if let Some(message) = error_message(&value) {
+            chunks.push(ResponseChunk::error(message));
+            return chunks;
+        }
+
+        if let Some(choice) = value["choices"].as_array().and_then(|c| c.first()) {
+            let delta = choice.get("delta").unwrap_or(&choice["message"]);
+            if let Some(text) = delta["content"].as_str().filter(|t| !t.is_empty()) {
+                chunks.push(ResponseChunk::text(text));
+            }
+            for (position, call) in delta["tool_calls"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .enumerate()
+            {
+                chunks.push(ResponseChunk::ToolDelta {
+                    index: call["index"].as_u64().unwrap_or(position as u64) as u32,
+                    id: call["id"].as_str().map(str::to_string),
+                    name: call["function"]["name"].as_str().map(str::to_string),
+                    arguments: call["function"]["arguments"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                });
+            }
+            if let Some(reason) = choice["finish_reason"].as_str() {
+                self.finish_reason = Some(reason.to_string());
+            }
+        }
+
+        if let Some(usage) = value.get("usage").filter(|u| u.is_object()) {
+            chunks.push(ResponseChunk::Usage(ResponseUsage {
+                input_tokens: token_count(&usage["prompt_tokens"]),
+                output_tokens: token_count(&usage["completion_tokens"]),
+                cache_read_input_tokens: usage["prompt_tokens_details"]["cached_tokens"]
+                    .as_u64()
+                    .map(|v| v as u32),
+                cache_creation_input_tokens: None,
+            }));
+        }
+        chunks
+    }
+
+    fn finish(&mut self) -> Vec<ResponseChunk> {
+        if std::mem::replace(&mut self.done, true) {
+            return Vec::new();
+        }
+        vec![ResponseChunk::Done {
+            finish_reason: self.finish_reason.take(),
+        }]
+    }
+}
+
+/// OpenAI Responses API（Codex）SSE 解码器
+#[derive(Debug, Default)]
+pub struct CodexChunkDecoder {
+    /// item_id -> 工具调用序号
+    tool_items: HashMap<String, u32>,
+    done: bool,
+}
+
+impl CodexChunkDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ChunkDecoder for CodexChunkDecoder {
+    fn decode(&mut self, data: &str) -> Vec<ResponseChunk> {
+        let data = data.trim();
+        if data == "[DONE]" {
+            return self.finish();
+        }
+        let Ok(value) = serde_json::from_str::<Value>(data) else {
+            return Vec::new();
+        };
+
+        match value["type"].as_str().unwrap_or_default() {
+            "response.output_item.added" => {
+                let item = &value["item"];
+                if item["type"].as_str() != Some("function_call") {
+                    return Vec::new();
+                }
+                let index = self.tool_items.len() as u32;
+                if let Some(item_id) = item["id"].as_str() {
+                    self.tool_items.insert(item_id.to_string(), index);
+                }
+                vec![ResponseChunk::ToolDelta {
+                    index,
+                    id: item["call_id"].as_str().map(str::to_string),
+                    name: item["name"].as_str().map(str::to_string),
+                    arguments: String::new(),
+                }]
+            }
+            "response.function_call_arguments.delta" => {
+                let Some(index) = value["item_id"]
+                    .as_str()
+                    .and_then(|id| self.tool_items.get(id).copied())
+                else {
+                    return Vec::new();
+                };
+                vec![ResponseChunk::ToolDelta {
+                    index,
+                    id: None,
+                    name: None,
+                    arguments: value["delta"].as_str().unwrap_or_default().to_string(),
+                }]
+            }
+            "response.completed" | "response.incomplete" => {
+                let response = &value["response"];
+                let mut chunks = Vec::new();
+                if let Some(usage) = response.get("usage").filter(|u| u.is_object()) {
+                    chunks.push(ResponseChunk::Usage(ResponseUsage {
+                        input_tokens: token_count(&usage["input_tokens"]),
+                        output_tokens: token_count(&usage["output_tokens"]),
+                        cache_read_input_tokens: usage["input_tokens_details"]["cached_tokens"]
+                            .as_u64()
+                            .map(|v| v as u32),
+                        cache_creation_input_tokens: None,
+                    }));
+                }
+                self.done = true;
+                let finish_reason = if !self.tool_items.is_empty() {
+                    "tool_calls"
+                } else if value["type"] == "response.incomplete" {
+                    "length"
+                } else {
+                    "stop"
+                };
+                chunks.push(ResponseChunk::done(Some(finish_reason)));
+                chunks
+            }
+            "response.failed" | "error" => {
+                let message = error_message(&value["response"])
+                    .or_else(|| error_message(&value))
+                    .unwrap_or_else(|| "上游响应失败".to_string());
+                vec![ResponseChunk::error(message)]
+            }
+            // 文本增量（response.output_text.delta 等）
+            event_type if event_type.ends_with("output_text.delta") || event_type.is_empty() => {
+                let mut chunks: Vec<ResponseChunk> = value["delta"]
+                    .as_str()
+                    .map(ResponseChunk::text)
+                    .into_iter()
+                    .collect();
+                // 非流式完整响应体
+                chunks.extend(output_texts(&value["output"]).map(ResponseChunk::text));
+                chunks
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn finish(&mut self) -> Vec<ResponseChunk> {
+        if std::mem::replace(&mut self.done, true) {
+            return Vec::new();
+        }
+        vec![ResponseChunk::done(None)]
+    }
+}
+
+/// 提取 Responses API `output` 中的 `output_text`
+fn output_texts(output: &Value) -> impl Iterator<Item = &str> {
+    output
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|item| item["type"].as_str() == Some("message"))
+        .flat_map(|item| item["content"].as_array().into_iter().flatten())
+        .filter(|content| content["type"].as_str() == Some("output_text"))
+        .filter_map(|content| content["text"].as_str())
+}
+
+fn error_message(value: &Value) -> Option<String> {
+    let error = value.get("error").filter(|e| !e.is_null())?;
+    Some(
+        error["message"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string()),
+    )
+}
+
+fn token_count(value: &Value) -> u32 {
+    value.as_u64().unwrap_or_default() as u32
+}
+
+// ============================================================================
+// 流组合
+// ============================================================================
+
+/// 解码完整的 SSE 响应体（已缓冲的非流式读取）
+pub fn decode_sse_body(body: &str, mut decoder: impl ChunkDecoder) -> Vec<ResponseChunk> {
+    let mut sse = SseDataDecoder::new();
+    let mut payloads = sse.push(body.as_bytes());
+    payloads.extend(sse.finish());
+
+    let mut chunks: Vec<ResponseChunk> = payloads
+        .iter()
+        .flat_map(|data| decoder.decode(data))
+        .collect();
+    chunks.extend(decoder.finish());
+    chunks
+}
+
+// ============================================================================
+// 编码器
+// ============================================================================
+
+/// `ResponseChunk` → OpenAI Chat Completions SSE 编码器
+///
+/// 同一响应的所有 chunk 共享 id / created / model。
+#[derive(Debug, Clone)]
+pub struct OpenAiChunkEncoder {
+    id: String,
+    created: u64,
+    model: String,
+}
+
+impl OpenAiChunkEncoder {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            id: format!("chatcmpl-{}", uuid::Uuid::new_v4()),
+            created: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            model: model.into(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// 编码单个 chunk 为 SSE 文本；`Done` 只输出结束 chunk，`data: [DONE]` 由 `terminator` 提供
+    pub fn encode(&self, chunk: &ResponseChunk) -> String {
+        match chunk {
+            ResponseChunk::TextDelta { text } => {
+                self.frame(serde_json::json!({ "content": text }), None)
+            }
+            ResponseChunk::ToolDelta {
+                index,
+                id,
+                name,
+                arguments,
+            } => {
+                let mut call = serde_json::json!({
+                    "index": index,
+                    "function": { "arguments": arguments }
+                });
+                if let Some(id) = id {
+                    call["id"] = Value::String(id.clone());
+                    call["type"] = Value::String("function".to_string());
+                }
+                if let Some(name) = name {
+                    call["function"]["name"] = Value::String(name.clone());
+                }
+                self.frame(serde_json::json!({ "tool_calls": [call] }), None)
+            }
+            ResponseChunk::Usage(usage) => {
+                let payload = serde_json::json!({
+                    "id": self.id,
+                    "object": "chat.completion.chunk",
+                    "created": self.created,
+                    "model": self.model,
+                    "choices": [],
+                    "usage": {
+                        "prompt_tokens": usage.input_tokens,
+                        "completion_tokens": usage.output_tokens,
+                        "total_tokens": usage.input_tokens + usage.output_tokens
+                    }
+                });
+                format!("data: {payload}\n\n")
+            }
+            ResponseChunk::Done { finish_reason } => self.frame(
+                serde_json::json!({}),
+                Some(finish_reason.as_deref().unwrap_or("stop")),
+            ),
+            ResponseChunk::Error { message } => {
+                let payload = serde_json::json!({
+                    "error": { "message": message, "type": "stream_error" }
+                });
+                format!("data: {payload}\n\n")
+            }
+        }
+    }
+
+    /// 流结束标记，每个响应只发送一次
+    pub fn terminator(&self) -> &'static str {
+        "data: [DONE]\n\n"
+    }
+
+    /// 编码一组 chunk 为完整响应，末尾追加一次 `data: [DONE]`
+    pub fn encode_all<'a>(&self, chunks: impl IntoIterator<Item = &'a ResponseChunk>) -> String {
+        let mut output: String = chunks.into_iter().map(|chunk| self.encode(chunk)).collect();
+        output.push_str(self.terminator());
+        output
+    }
+
+    fn frame(&self, delta: Value, finish_reason: Option<&str>) -> String {
+        let payload = serde_json::json!({
+            "id": self.id,
+            "object": "chat.completion.chunk",
+            "created": self.created,
+            "model": self.model,
+            "choices": [{
+                "index": 0,
+                "delta": delta,
+                "finish_reason": finish_reason
+            }]
+        });
+        format!("data: {payload}\n\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::response_stream::ResponseAccumulator;
+
+    #[test]
+    fn test_sse_decoder_handles_split_events() {
+        let mut sse = SseDataDecoder::new();
+        assert!(sse.push(b"data: {\"a\":").is_empty());
+        assert_eq!(sse.push(b"1}\r\n\r\n: ping\n\ndata: x"), vec!["{\"a\":1}"]);
+        assert_eq!(sse.finish(), vec!["x"]);
+    }
+
+    #[test]
+    fn test_openai_decoder() {
+        let body = concat!(
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"Hi\"},\"finish_reason\":null}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"function\":{\"name\":\"search\",\"arguments\":\"{\\\"q\\\":\"}}]}}]}\n\n",
+            "data: {\"choices\":[{\"index\":0,\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\"1}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":4,\"completion_tokens\":6}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let chunks = decode_sse_body(body, OpenAiChunkDecoder::new());
+        let result: ResponseAccumulator = chunks.iter().collect();
+
+        assert_eq!(result.text, "Hi");
+        assert_eq!(result.tool_calls[0].name, "search");
+        assert_eq!(result.tool_calls[0].arguments, "{\"q\":1}");
+        assert_eq!(result.usage.unwrap().input_tokens, 4);
+        assert_eq!(result.finish_reason.as_deref(), Some("tool_calls"));
+        assert_eq!(
+            chunks.iter().filter(|c| c.is_terminal()).count(),
+            1,
+            "[DONE] 与流结束只产生一个 Done"
+        );
+    }
+
+    #[test]
+    fn test_codex_decoder() {
+        let body = concat!(
+            "data: {\"type\":\"response.output_text.delta\",\"delta\":\"hi\"}\n\n",
+            "data: {\"type\":\"response.output_text.delta\",\"delta\":\"!\"}\n\n",
+            "data: {\"type\":\"response.completed\",\"response\":{\"usage\":{\"input_tokens\":2,\"output_tokens\":1}}}\n\n",
+        );
+        let result: ResponseAccumulator = decode_sse_body(body, CodexChunkDecoder::new())
+            .iter()
+            .collect();
+        assert_eq!(result.text, "hi!");
+        assert_eq!(result.usage.unwrap().output_tokens, 1);
+        assert_eq!(result.finish_reason.as_deref(), Some("stop"));
+    }
+
+    #[test]
+    fn test_openai_encoder_round_trip() {
+        let encoder = OpenAiChunkEncoder::new("gpt-4o");
+        let sse = encoder.encode_all(&[
+            ResponseChunk::text("Hello"),
+            ResponseChunk::done(Some("stop")),
+        ]);
+        assert!(sse.ends_with("data: [DONE]\n\n"));
+        assert_eq!(sse.matches("[DONE]").count(), 1);
+        assert_eq!(sse.matches(encoder.id()).count(), 2);
+
+        let result: ResponseAccumulator = decode_sse_body(&sse, OpenAiChunkDecoder::new())
+            .iter()
+            .collect();
+        assert_eq!(result.text, "Hello");
+        assert_eq!(result.finish_reason.as_deref(), Some("stop"));
+    }
+}
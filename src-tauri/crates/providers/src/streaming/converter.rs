@@ -512,6 +512,16 @@ impl StreamConverter {
                                                 false,
                                             ));
                                         }
+                                    } else if let Some(url) = delta
+                                        .get("citation")
+                                        .and_then(|c| c.get("url"))
+                                        .and_then(|u| u.as_str())
+                                    {
+                                        // 原生联网搜索引用
+                                        let title =
+                                            delta["citation"].get("title").and_then(|t| t.as_str());
+                                        sse_events
+                                            .push(self.create_openai_annotation_chunk(url, title));
                                     }
                                 }
                             }
@@ -734,6 +744,25 @@ impl StreamConverter {
         format!("data: {chunk}\n\n")
     }
 
+    fn create_openai_annotation_chunk(&self, url: &str, title: Option<&str>) -> String {
+        let chunk = serde_json::json!({
+            "id": self.response_id,
+            "object": "chat.completion.chunk",
+            "created": self.get_created_timestamp(),
+            "model": self.model,
+            "choices": [{
+                "index": 0,
+                "delta": {
+                    "annotations": [
+                        crate::converter::native_web_search::url_citation_annotation(url, title)
+                    ]
+                },
+                "finish_reason": null
+            }]
+        });
+        format!("data: {chunk}\n\n")
+    }
+
     fn create_openai_tool_call_chunk(
         &self,
         index: u32,
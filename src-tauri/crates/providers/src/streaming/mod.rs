@@ -12,6 +12,8 @@
 //! - `converter`: 流式格式转换器
 //! - `traits`: StreamingProvider trait 定义
 //! - `manager`: 流式管理器
+//! - `usage`: `stream_options.include_usage` 的 usage chunk 补发
+//! - `responses_sse`: chat completions SSE 转换为 Responses API 事件流
 
 pub mod anthropic_sse;
 pub mod aws_parser;
@@ -19,11 +21,15 @@ pub mod converter;
 pub mod error;
 pub mod manager;
 pub mod metrics;
+pub mod responses_sse;
 pub mod traits;
+pub mod usage;
 
 // 重新导出核心类型
 pub use converter::StreamFormat;
 pub use error::StreamError;
 pub use manager::{with_timeout, StreamConfig, StreamContext, StreamManager};
 pub use metrics::StreamMetrics;
+pub use responses_sse::ResponsesStreamConverter;
 pub use traits::{reqwest_stream_to_stream_response, StreamResponse};
+pub use usage::{StreamOptions, StreamUsage, StreamUsageTracker};
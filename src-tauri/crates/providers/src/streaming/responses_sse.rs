@@ -0,0 +1,621 @@
+//! Responses API 流式事件生成
+//!
+//! 将 OpenAI chat completions SSE（`chat.completion.chunk`）逐块转换为 Responses API
+//! 事件流：
+//!
+//! - `response.created` / `response.in_progress`：首个 chunk 到达时发送
+//! - 推理内容：`response.output_item.added` → `response.reasoning_summary_text.delta` …
+//! - 文本：`response.output_item.added` → `response.content_part.added` →
+//!   `response.output_text.delta` … → `*.done`
+//! - 工具调用：`response.output_item.added` → `response.function_call_arguments.delta` …
+//! - 结束：关闭所有未完成条目后发送 `response.completed`（或 `response.incomplete` /
+//!   `response.failed`），附带完整响应对象与 usage
+
+use crate::converter::openai_responses::{
+    convert_usage, new_item_id, response_id, response_status,
+};
+use lime_core::models::openai_responses::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+/// 输出条目的累计状态
+#[derive(Debug)]
+enum OutputSlot {
+    Reasoning {
+        id: String,
+        text: String,
+    },
+    Message {
+        id: String,
+        text: String,
+    },
+    FunctionCall {
+        id: String,
+        call_id: String,
+        name: String,
+        arguments: String,
+    },
+}
+
+impl OutputSlot {
+    fn to_item(&self, status: &str) -> ResponseOutputItem {
+        match self {
+            Self::Reasoning { id, text } => ResponseOutputItem::Reasoning {
+                id: id.clone(),
+                summary: vec![ReasoningSummaryPart::SummaryText { text: text.clone() }],
+            },
+            Self::Message { id, text } => ResponseOutputItem::Message {
+                id: id.clone(),
+                role: "assistant".to_string(),
+                status: status.to_string(),
+                content: vec![ResponseOutputContent::OutputText {
+                    text: text.clone(),
+                    annotations: Vec::new(),
+                }],
+            },
+            Self::FunctionCall {
+                id,
+                call_id,
+                name,
+                arguments,
+            } => ResponseOutputItem::FunctionCall {
+                id: id.clone(),
+                call_id: call_id.clone(),
+                name: name.clone(),
+                arguments: arguments.clone(),
+                status: status.to_string(),
+            },
+        }
+    }
+}
+
+/// chat completions SSE → Responses 事件流转换器
+#[derive(Debug)]
+pub struct ResponsesStreamConverter {
+    model: String,
+    response_id: Option<String>,
+    created_at: Option<i64>,
+    sequence: u64,
+    started: bool,
+    finished: bool,
+    line_buffer: Vec<u8>,
+    slots: Vec<OutputSlot>,
+    /// 当前未关闭的推理 / 文本条目（slots 下标）
+    open_reasoning: Option<usize>,
+    open_message: Option<usize>,
+    /// chat 工具调用 index → slots 下标
+    tool_calls: HashMap<u64, usize>,
+    /// 已发送 done 事件的条目
+    closed: Vec<bool>,
+    finish_reason: Option<String>,
+    usage: Option<ResponsesUsage>,
+}
+
+impl ResponsesStreamConverter {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            model: model.into(),
+            response_id: None,
+            created_at: None,
+            sequence: 0,
+            started: false,
+            finished: false,
+            line_buffer: Vec::new(),
+            slots: Vec::new(),
+            open_reasoning: None,
+            open_message: None,
+            tool_calls: HashMap::new(),
+            closed: Vec::new(),
+            finish_reason: None,
+            usage: None,
+        }
+    }
+
+    /// 处理一段 chat completions SSE 字节，返回 Responses 事件文本
+    ///
+    /// 按行缓冲，跨 chunk 切分的 `data:` 行也能被正确解析。
+    pub fn process(&mut self, bytes: &[u8]) -> String {
+        self.line_buffer.extend_from_slice(bytes);
+        let mut output = String::new();
+
+        while let Some(pos) = self.line_buffer.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = self.line_buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let Some(data) = line
+                .trim_end_matches(['\r', '\n'])
+                .strip_prefix("data:")
+                .map(str::trim)
+            else {
+                continue;
+            };
+            if data == "[DONE]" {
+                output.push_str(&self.complete(None));
+            } else if let Ok(chunk) = serde_json::from_str::<Value>(data) {
+                output.push_str(&self.observe_chunk(&chunk));
+            }
+        }
+
+        output
+    }
+
+    /// 流结束时调用：若尚未发送结束事件则补发
+    pub fn finish(&mut self) -> String {
+        let rest = std::mem::take(&mut self.line_buffer);
+        let mut output = String::new();
+        if !rest.is_empty() {
+            let mut tail = rest;
+            tail.push(b'\n');
+            output.push_str(&self.process(&tail));
+        }
+        output.push_str(&self.complete(None));
+        output
+    }
+
+    fn observe_chunk(&mut self, chunk: &Value) -> String {
+        if self.finished {
+            return String::new();
+        }
+        let mut output = String::new();
+
+        if self.response_id.is_none() {
+            self.response_id = chunk.get("id").and_then(Value::as_str).map(String::from);
+        }
+        if self.created_at.is_none() {
+            self.created_at = chunk.get("created").and_then(Value::as_i64);
+        }
+        if let Some(model) = chunk
+            .get("model")
+            .and_then(Value::as_str)
+            .filter(|m| !m.is_empty())
+        {
+            self.model = model.to_string();
+        }
+        output.push_str(&self.start());
+
+        if let Some(error) = chunk.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string());
+            let code = error
+                .get("code")
+                .or_else(|| error.get("type"))
+                .and_then(Value::as_str)
+                .unwrap_or("server_error")
+                .to_string();
+            output.push_str(&self.complete(Some(ResponsesError { code, message })));
+            return output;
+        }
+
+        if let Some(usage) = chunk.get("usage").and_then(convert_usage) {
+            self.usage = Some(usage);
+        }
+
+        let choices = chunk.get("choices").and_then(Value::as_array);
+        for choice in choices.into_iter().flatten() {
+            if let Some(delta) = choice.get("delta") {
+                if let Some(text) = delta
+                    .get("reasoning_content")
+                    .and_then(Value::as_str)
+                    .filter(|s| !s.is_empty())
+                {
+                    output.push_str(&self.reasoning_delta(text));
+                }
+                if let Some(text) = delta
+                    .get("content")
+                    .and_then(Value::as_str)
+                    .filter(|s| !s.is_empty())
+                {
+                    output.push_str(&self.text_delta(text));
+                }
+                for tool_call in delta
+                    .get("tool_calls")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                {
+                    output.push_str(&self.tool_call_delta(tool_call));
+                }
+            }
+            if let Some(reason) = choice.get("finish_reason").and_then(Value::as_str) {
+                self.finish_reason = Some(reason.to_string());
+            }
+        }
+
+        output
+    }
+
+    fn start(&mut self) -> String {
+        if self.started {
+            return String::new();
+        }
+        self.started = true;
+        let snapshot = self.snapshot("in_progress", None);
+        let mut output = self.event("response.created", json!({ "response": snapshot }));
+        output.push_str(&self.event("response.in_progress", json!({ "response": snapshot })));
+        output
+    }
+
+    fn reasoning_delta(&mut self, text: &str) -> String {
+        let mut output = self.close_message();
+        let index = match self.open_reasoning {
+            Some(index) => index,
+            None => {
+                let index = self.push_slot(OutputSlot::Reasoning {
+                    id: new_item_id("rs"),
+                    text: String::new(),
+                });
+                self.open_reasoning = Some(index);
+                output.push_str(&self.item_added(index));
+                output.push_str(&self.event(
+                    "response.reasoning_summary_part.added",
+                    json!({
+                        "item_id": self.slot_id(index),
+                        "output_index": index,
+                        "summary_index": 0,
+                        "part": { "type": "summary_text", "text": "" },
+                    }),
+                ));
+                index
+            }
+        };
+        if let OutputSlot::Reasoning { text: buffer, .. } = &mut self.slots[index] {
+            buffer.push_str(text);
+        }
+        output.push_str(&self.event(
+            "response.reasoning_summary_text.delta",
+            json!({
+                "item_id": self.slot_id(index),
+                "output_index": index,
+                "summary_index": 0,
+                "delta": text,
+            }),
+        ));
+        output
+    }
+
+    fn text_delta(&mut self, text: &str) -> String {
+        let mut output = self.close_reasoning();
+        let index = match self.open_message {
+            Some(index) => index,
+            None => {
+                let index = self.push_slot(OutputSlot::Message {
+                    id: new_item_id("msg"),
+                    text: String::new(),
+                });
+                self.open_message = Some(index);
+                output.push_str(&self.item_added(index));
+                output.push_str(&self.event(
+                    "response.content_part.added",
+                    json!({
+                        "item_id": self.slot_id(index),
+                        "output_index": index,
+                        "content_index": 0,
+                        "part": { "type": "output_text", "text": "", "annotations": [] },
+                    }),
+                ));
+                index
+            }
+        };
+        if let OutputSlot::Message { text: buffer, .. } = &mut self.slots[index] {
+            buffer.push_str(text);
+        }
+        output.push_str(&self.event(
+            "response.output_text.delta",
+            json!({
+                "item_id": self.slot_id(index),
+                "output_index": index,
+                "content_index": 0,
+                "delta": text,
+            }),
+        ));
+        output
+    }
+
+    fn tool_call_delta(&mut self, tool_call: &Value) -> String {
+        let mut output = self.close_reasoning();
+        output.push_str(&self.close_message());
+
+        let call_index = tool_call.get("index").and_then(Value::as_u64).unwrap_or(0);
+        let index = match self.tool_calls.get(&call_index) {
+            Some(index) => *index,
+            None => {
+                let call_id = tool_call
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| new_item_id("call"));
+                let index = self.push_slot(OutputSlot::FunctionCall {
+                    id: format!("fc_{call_id}"),
+                    call_id,
+                    name: tool_call
+                        .pointer("/function/name")
+                        .and_then(Value::as_str)
+                        .unwrap_or("")
+                        .to_string(),
+                    arguments: String::new(),
+                });
+                self.tool_calls.insert(call_index, index);
+                output.push_str(&self.item_added(index));
+                index
+            }
+        };
+
+        let delta = tool_call
+            .pointer("/function/arguments")
+            .and_then(Value::as_str)
+            .unwrap_or("");
+        if !delta.is_empty() {
+            if let OutputSlot::FunctionCall { arguments, .. } = &mut self.slots[index] {
+                arguments.push_str(delta);
+            }
+            output.push_str(&self.event(
+                "response.function_call_arguments.delta",
+                json!({
+                    "item_id": self.slot_id(index),
+                    "output_index": index,
+                    "delta": delta,
+                }),
+            ));
+        }
+        output
+    }
+
+    fn close_reasoning(&mut self) -> String {
+        match self.open_reasoning.take() {
+            Some(index) => self.close_slot(index),
+            None => String::new(),
+        }
+    }
+
+    fn close_message(&mut self) -> String {
+        match self.open_message.take() {
+            Some(index) => self.close_slot(index),
+            None => String::new(),
+        }
+    }
+
+    fn close_slot(&mut self, index: usize) -> String {
+        if self.closed[index] {
+            return String::new();
+        }
+        self.closed[index] = true;
+        let item_id = self.slot_id(index);
+        let events = match &self.slots[index] {
+            OutputSlot::Reasoning { text, .. } => vec![
+                (
+                    "response.reasoning_summary_text.done",
+                    json!({
+                        "item_id": item_id,
+                        "output_index": index,
+                        "summary_index": 0,
+                        "text": text,
+                    }),
+                ),
+                (
+                    "response.reasoning_summary_part.done",
+                    json!({
+                        "item_id": item_id,
+                        "output_index": index,
+                        "summary_index": 0,
+                        "part": { "type": "summary_text", "text": text },
+                    }),
+                ),
+            ],
+            OutputSlot::Message { text, .. } => vec![
+                (
+                    "response.output_text.done",
+                    json!({
+                        "item_id": item_id,
+                        "output_index": index,
+                        "content_index": 0,
+                        "text": text,
+                    }),
+                ),
+                (
+                    "response.content_part.done",
+                    json!({
+                        "item_id": item_id,
+                        "output_index": index,
+                        "content_index": 0,
+                        "part": { "type": "output_text", "text": text, "annotations": [] },
+                    }),
+                ),
+            ],
+            OutputSlot::FunctionCall { arguments, .. } => vec![(
+                "response.function_call_arguments.done",
+                json!({
+                    "item_id": item_id,
+                    "output_index": index,
+                    "arguments": arguments,
+                }),
+            )],
+        };
+        let mut output = String::new();
+        for (event_type, payload) in events {
+            output.push_str(&self.event(event_type, payload));
+        }
+        let item = self.slots[index].to_item("completed");
+        output.push_str(&self.event(
+            "response.output_item.done",
+            json!({ "output_index": index, "item": item }),
+        ));
+        output
+    }
+
+    /// 关闭所有条目并发送结束事件（只发送一次）
+    fn complete(&mut self, error: Option<ResponsesError>) -> String {
+        if self.finished {
+            return String::new();
+        }
+        let mut output = self.start();
+        self.finished = true;
+        for index in 0..self.slots.len() {
+            output.push_str(&self.close_slot(index));
+        }
+        self.open_reasoning = None;
+        self.open_message = None;
+
+        let (event_type, status, incomplete_details) = if error.is_some() {
+            ("response.failed", "failed", None)
+        } else {
+            match response_status(self.finish_reason.as_deref()) {
+                ("incomplete", details) => ("response.incomplete", "incomplete", details),
+                (_, details) => ("response.completed", "completed", details),
+            }
+        };
+        let mut snapshot = self.snapshot(status, error);
+        snapshot.incomplete_details = incomplete_details;
+        output.push_str(&self.event(event_type, json!({ "response": snapshot })));
+        output
+    }
+
+    fn item_added(&mut self, index: usize) -> String {
+        let item = self.slots[index].to_item("in_progress");
+        self.event(
+            "response.output_item.added",
+            json!({ "output_index": index, "item": item }),
+        )
+    }
+
+    fn push_slot(&mut self, slot: OutputSlot) -> usize {
+        self.slots.push(slot);
+        self.closed.push(false);
+        self.slots.len() - 1
+    }
+
+    fn slot_id(&self, index: usize) -> String {
+        match &self.slots[index] {
+            OutputSlot::Reasoning { id, .. }
+            | OutputSlot::Message { id, .. }
+            | OutputSlot::FunctionCall { id, .. } => id.clone(),
+        }
+    }
+
+    fn snapshot(&mut self, status: &str, error: Option<ResponsesError>) -> ResponsesResponse {
+        // 上游未返回 id 时生成一次并固定，保证各事件中的响应 id 一致
+        let chat_id = self
+            .response_id
+            .get_or_insert_with(|| uuid::Uuid::new_v4().simple().to_string());
+        let id = response_id(Some(chat_id));
+        let created_at = *self
+            .created_at
+            .get_or_insert_with(|| chrono::Utc::now().timestamp());
+        let item_status = if status == "in_progress" {
+            "in_progress"
+        } else {
+            "completed"
+        };
+        ResponsesResponse {
+            id,
+            object: "response".to_string(),
+            created_at,
+            status: status.to_string(),
+            model: self.model.clone(),
+            output: self.slots.iter().map(|s| s.to_item(item_status)).collect(),
+            usage: self.usage,
+            incomplete_details: None,
+            error,
+        }
+    }
+
+    fn event(&mut self, event_type: &str, payload: Value) -> String {
+        self.sequence += 1;
+        let mut data = payload;
+        data["type"] = json!(event_type);
+        data["sequence_number"] = json!(self.sequence);
+        format!("event: {event_type}\ndata: {data}\n\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(output: &str) -> Vec<Value> {
+        output
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .filter_map(|data| serde_json::from_str::<Value>(data).ok())
+            .collect()
+    }
+
+    fn types(events: &[Value]) -> Vec<&str> {
+        events.iter().map(|e| e["type"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_text_stream_events() {
+        let mut converter = ResponsesStreamConverter::new("gpt-4o");
+        let mut output = String::new();
+        // 故意在 JSON 中间切分
+        output.push_str(&converter.process(
+            b"data: {\"id\":\"chatcmpl-1\",\"created\":1,\"choices\":[{\"delta\":{\"content\":\"Hel",
+        ));
+        output.push_str(&converter.process(
+            b"lo\"}}]}\n\ndata: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}]}\n\n\
+              data: {\"choices\":[],\"usage\":{\"prompt_tokens\":3,\"completion_tokens\":1,\"total_tokens\":4}}\n\n\
+              data: [DONE]\n\n",
+        ));
+        output.push_str(&converter.finish());
+
+        let events = events(&output);
+        assert_eq!(
+            types(&events),
+            vec![
+                "response.created",
+                "response.in_progress",
+                "response.output_item.added",
+                "response.content_part.added",
+                "response.output_text.delta",
+                "response.output_text.done",
+                "response.content_part.done",
+                "response.output_item.done",
+                "response.completed",
+            ]
+        );
+        let sequences: Vec<u64> = events
+            .iter()
+            .map(|e| e["sequence_number"].as_u64().unwrap())
+            .collect();
+        assert_eq!(sequences, (1..=9).collect::<Vec<_>>());
+
+        let completed = &events[8]["response"];
+        assert_eq!(completed["id"], "resp_1");
+        assert_eq!(completed["status"], "completed");
+        assert_eq!(completed["output"][0]["content"][0]["text"], "Hello");
+        assert_eq!(completed["usage"]["total_tokens"], 4);
+        assert!(output.starts_with("event: response.created\n"));
+    }
+
+    #[test]
+    fn test_tool_call_stream_events() {
+        let mut converter = ResponsesStreamConverter::new("gpt-4o");
+        let mut output = converter.process(
+            b"data: {\"choices\":[{\"delta\":{\"reasoning_content\":\"think\"}}]}\n\n\
+              data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"id\":\"call_1\",\"function\":{\"name\":\"shell\",\"arguments\":\"{\\\"a\\\"\"}}]}}]}\n\n\
+              data: {\"choices\":[{\"delta\":{\"tool_calls\":[{\"index\":0,\"function\":{\"arguments\":\":1}\"}}]},\"finish_reason\":\"tool_calls\"}]}\n\n",
+        );
+        // 上游未发送 [DONE] 时由 finish 补发结束事件
+        output.push_str(&converter.finish());
+        assert!(converter.finish().is_empty());
+
+        let events = events(&output);
+        let types = types(&events);
+        assert_eq!(
+            types
+                .iter()
+                .filter(|t| **t == "response.output_item.done")
+                .count(),
+            2
+        );
+        assert_eq!(types.last(), Some(&"response.completed"));
+
+        let completed = &events.last().unwrap()["response"];
+        assert_eq!(completed["output"][0]["type"], "reasoning");
+        assert_eq!(completed["output"][1]["type"], "function_call");
+        assert_eq!(completed["output"][1]["call_id"], "call_1");
+        assert_eq!(completed["output"][1]["arguments"], "{\"a\":1}");
+    }
+}
@@ -0,0 +1,308 @@
+//! 流式响应 Usage 补发
+//!
+//! OpenAI 客户端通过 `stream_options: {"include_usage": true}` 要求在流结束前
+//! 收到一个 `choices` 为空、携带 `usage` 的 chunk。各 Provider / 转换器输出的
+//! OpenAI SSE 未必包含该 chunk，本模块在 `data: [DONE]` 之前统一补发：
+//!
+//! - 上游 chunk 中带有 `usage` 时直接采用其数值
+//! - 否则按请求体与已输出内容估算（约 4 字节 / token）
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// OpenAI `stream_options` 请求参数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamOptions {
+    #[serde(default)]
+    pub include_usage: bool,
+}
+
+impl StreamOptions {
+    /// 从原始请求体中解析 `stream_options`
+    pub fn from_request_value(request: &Value) -> Option<Self> {
+        request
+            .get("stream_options")
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+}
+
+/// 流式 Usage 统计
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamUsage {
+    pub prompt_tokens: u32,
+    pub completion_tokens: u32,
+    pub total_tokens: u32,
+}
+
+/// OpenAI SSE 流的 Usage 追踪器
+///
+/// 逐 chunk 透传 SSE 文本，同时累计输出内容与上游 usage，
+/// 在 `[DONE]` 前（或流异常结束时）补发 usage chunk。
+#[derive(Debug)]
+pub struct StreamUsageTracker {
+    model: String,
+    estimated_prompt_tokens: u32,
+    completion_bytes: usize,
+    upstream_usage: Option<StreamUsage>,
+    response_id: Option<String>,
+    created: Option<u64>,
+    line_buffer: Vec<u8>,
+    usage_emitted: bool,
+}
+
+impl StreamUsageTracker {
+    /// 创建追踪器，`estimated_prompt_tokens` 用于上游未返回 usage 时兜底
+    pub fn new(model: impl Into<String>, estimated_prompt_tokens: u32) -> Self {
+        Self {
+            model: model.into(),
+            estimated_prompt_tokens,
+            completion_bytes: 0,
+            upstream_usage: None,
+            response_id: None,
+            created: None,
+            line_buffer: Vec::new(),
+            usage_emitted: false,
+        }
+    }
+
+    /// 处理一段 SSE 字节，返回需要写给客户端的内容
+    ///
+    /// 按行缓冲，保证跨 chunk 切分的 `data:` 行（包括被截断的多字节字符）
+    /// 也能被正确解析。
+    pub fn process(&mut self, bytes: &[u8]) -> String {
+        self.line_buffer.extend_from_slice(bytes);
+        let mut output = String::new();
+
+        while let Some(pos) = self.line_buffer.iter().position(|b| *b == b'\n') {
+            let raw: Vec<u8> = self.line_buffer.drain(..=pos).collect();
+            let line = String::from_utf8_lossy(&raw);
+            let data = line
+                .trim_end_matches(['\r', '\n'])
+                .strip_prefix("data:")
+                .map(str::trim);
+
+            match data {
+                Some("[DONE]") => {
+                    output.push_str(&self.usage_chunk());
+                    output.push_str(&line);
+                }
+                Some(payload) => {
+                    if self.observe_chunk(payload) {
+                        output.push_str(&line);
+                    }
+                }
+                None => output.push_str(&line),
+            }
+        }
+
+        output
+    }
+
+    /// 流结束时调用：冲刷残留数据，若尚未补发 usage 则补发
+    pub fn finish(&mut self) -> String {
+        let rest = std::mem::take(&mut self.line_buffer);
+        let mut output = String::from_utf8_lossy(&rest).into_owned();
+        output.push_str(&self.usage_chunk());
+        output
+    }
+
+    /// 当前 usage（上游优先，否则为估算值）
+    pub fn usage(&self) -> StreamUsage {
+        self.upstream_usage.unwrap_or_else(|| {
+            let completion_tokens = (self.completion_bytes / 4) as u32;
+            StreamUsage {
+                prompt_tokens: self.estimated_prompt_tokens,
+                completion_tokens,
+                total_tokens: self.estimated_prompt_tokens + completion_tokens,
+            }
+        })
+    }
+
+    /// 解析单个 chunk，返回是否需要原样透传
+    ///
+    /// 上游自带的 usage-only chunk（`choices` 为空）会被吞掉，
+    /// 由 [`Self::usage_chunk`] 在 `[DONE]` 前统一输出，避免重复。
+    fn observe_chunk(&mut self, payload: &str) -> bool {
+        let Ok(chunk) = serde_json::from_str::<Value>(payload) else {
+            return true;
+        };
+
+        if self.response_id.is_none() {
+            self.response_id = chunk.get("id").and_then(Value::as_str).map(String::from);
+        }
+        if self.created.is_none() {
+            self.created = chunk.get("created").and_then(Value::as_u64);
+        }
+        if let Some(model) = chunk.get("model").and_then(Value::as_str) {
+            if !model.is_empty() {
+                self.model = model.to_string();
+            }
+        }
+
+        if let Some(usage) = chunk.get("usage").and_then(parse_usage) {
+            self.upstream_usage = Some(usage);
+        }
+
+        let choices = chunk.get("choices").and_then(Value::as_array);
+        for choice in choices.into_iter().flatten() {
+            let Some(delta) = choice.get("delta") else {
+                continue;
+            };
+            for key in ["content", "reasoning_content"] {
+                if let Some(text) = delta.get(key).and_then(Value::as_str) {
+                    self.completion_bytes += text.len();
+                }
+            }
+            for tool_call in delta
+                .get("tool_calls")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(arguments) = tool_call
+                    .pointer("/function/arguments")
+                    .and_then(Value::as_str)
+                {
+                    self.completion_bytes += arguments.len();
+                }
+            }
+        }
+
+        let usage_only = chunk.get("usage").is_some() && choices.is_none_or(|c| c.is_empty());
+        !usage_only
+    }
+
+    fn usage_chunk(&mut self) -> String {
+        if self.usage_emitted {
+            return String::new();
+        }
+        self.usage_emitted = true;
+
+        let usage = self.usage();
+        let id = self
+            .response_id
+            .clone()
+            .unwrap_or_else(|| format!("chatcmpl-{}", uuid::Uuid::new_v4()));
+        let created = self.created.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        });
+        let chunk = serde_json::json!({
+            "id": id,
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": self.model,
+            "choices": [],
+            "usage": usage,
+        });
+        format!("data: {chunk}\n\n")
+    }
+}
+
+/// 解析 OpenAI（prompt/completion）或 Anthropic（input/output）风格的 usage
+fn parse_usage(value: &Value) -> Option<StreamUsage> {
+    let field = |keys: [&str; 2]| {
+        keys.iter()
+            .find_map(|key| value.get(*key).and_then(Value::as_u64))
+            .map(|v| v as u32)
+    };
+    let prompt_tokens = field(["prompt_tokens", "input_tokens"]);
+    let completion_tokens = field(["completion_tokens", "output_tokens"]);
+    if prompt_tokens.is_none() && completion_tokens.is_none() {
+        return None;
+    }
+    let prompt_tokens = prompt_tokens.unwrap_or(0);
+    let completion_tokens = completion_tokens.unwrap_or(0);
+    let total_tokens = value
+        .get("total_tokens")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(prompt_tokens + completion_tokens);
+    Some(StreamUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage_chunks(output: &str) -> Vec<Value> {
+        output
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .filter_map(|data| serde_json::from_str::<Value>(data).ok())
+            .filter(|chunk| chunk.get("usage").is_some())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_stream_options() {
+        let request = serde_json::json!({
+            "model": "gpt-4o",
+            "stream": true,
+            "stream_options": {"include_usage": true}
+        });
+        assert_eq!(
+            StreamOptions::from_request_value(&request),
+            Some(StreamOptions {
+                include_usage: true
+            })
+        );
+        assert_eq!(
+            StreamOptions::from_request_value(&serde_json::json!({})),
+            None
+        );
+    }
+
+    #[test]
+    fn test_synthesizes_usage_before_done() {
+        let mut tracker = StreamUsageTracker::new("gpt-4o", 10);
+        let mut output = String::new();
+        // 故意在 JSON 中间切分
+        output.push_str(&tracker.process(
+            b"data: {\"id\":\"chatcmpl-1\",\"created\":1,\"choices\":[{\"delta\":{\"content\":\"12345",
+        ));
+        output.push_str(&tracker.process(b"678\"}}]}\n\ndata: [DONE]\n\n"));
+        output.push_str(&tracker.finish());
+
+        let chunks = usage_chunks(&output);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0]["id"], "chatcmpl-1");
+        assert_eq!(chunks[0]["choices"], serde_json::json!([]));
+        assert_eq!(chunks[0]["usage"]["prompt_tokens"], 10);
+        assert_eq!(chunks[0]["usage"]["completion_tokens"], 2);
+        assert!(output.find("\"usage\"").unwrap() < output.find("[DONE]").unwrap());
+    }
+
+    #[test]
+    fn test_prefers_upstream_usage_without_duplicating() {
+        let mut tracker = StreamUsageTracker::new("gpt-4o", 999);
+        let mut output = tracker.process(
+            b"data: {\"choices\":[{\"delta\":{\"content\":\"hi\"}}]}\n\n\
+             data: {\"choices\":[],\"usage\":{\"prompt_tokens\":7,\"completion_tokens\":3,\"total_tokens\":10}}\n\n\
+             data: [DONE]\n\n",
+        );
+        output.push_str(&tracker.finish());
+
+        let chunks = usage_chunks(&output);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0]["usage"]["prompt_tokens"], 7);
+        assert_eq!(chunks[0]["usage"]["total_tokens"], 10);
+    }
+
+    #[test]
+    fn test_emits_usage_when_stream_ends_without_done() {
+        let mut tracker = StreamUsageTracker::new("gpt-4o", 4);
+        let mut output =
+            tracker.process(b"data: {\"choices\":[{\"delta\":{\"content\":\"abcd\"}}]}\n\n");
+        output.push_str(&tracker.finish());
+        assert_eq!(usage_chunks(&output).len(), 1);
+        assert!(tracker.finish().is_empty());
+    }
+}
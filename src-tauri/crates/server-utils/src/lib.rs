@@ -638,23 +638,28 @@ pub async fn health() -> impl IntoResponse {
     }))
 }
 
+/// 内置模型列表
+pub fn builtin_models() -> Vec<serde_json::Value> {
+    vec![
+        serde_json::json!({"id": "claude-sonnet-4-5", "object": "model", "owned_by": "anthropic"}),
+        serde_json::json!({"id": "claude-sonnet-4-5-20250929", "object": "model", "owned_by": "anthropic"}),
+        serde_json::json!({"id": "gemini-3-pro-preview", "object": "model", "owned_by": "google"}),
+        serde_json::json!({"id": "gemini-3-pro-image-preview", "object": "model", "owned_by": "google"}),
+        serde_json::json!({"id": "gemini-3-flash-preview", "object": "model", "owned_by": "google"}),
+        serde_json::json!({"id": "gemini-2.5-computer-use-preview-10-2025", "object": "model", "owned_by": "google"}),
+        serde_json::json!({"id": "gemini-claude-sonnet-4-5", "object": "model", "owned_by": "google"}),
+        serde_json::json!({"id": "gemini-claude-sonnet-4-5-thinking", "object": "model", "owned_by": "google"}),
+        serde_json::json!({"id": "gemini-claude-opus-4-5-thinking", "object": "model", "owned_by": "google"}),
+        serde_json::json!({"id": "qwen3-coder-plus", "object": "model", "owned_by": "alibaba"}),
+        serde_json::json!({"id": "qwen3-coder-flash", "object": "model", "owned_by": "alibaba"}),
+    ]
+}
+
 /// 模型列表端点响应
 pub async fn models() -> impl IntoResponse {
     Json(serde_json::json!({
         "object": "list",
-        "data": [
-            {"id": "claude-sonnet-4-5", "object": "model", "owned_by": "anthropic"},
-            {"id": "claude-sonnet-4-5-20250929", "object": "model", "owned_by": "anthropic"},
-            {"id": "gemini-3-pro-preview", "object": "model", "owned_by": "google"},
-            {"id": "gemini-3-pro-image-preview", "object": "model", "owned_by": "google"},
-            {"id": "gemini-3-flash-preview", "object": "model", "owned_by": "google"},
-            {"id": "gemini-2.5-computer-use-preview-10-2025", "object": "model", "owned_by": "google"},
-            {"id": "gemini-claude-sonnet-4-5", "object": "model", "owned_by": "google"},
-            {"id": "gemini-claude-sonnet-4-5-thinking", "object": "model", "owned_by": "google"},
-            {"id": "gemini-claude-opus-4-5-thinking", "object": "model", "owned_by": "google"},
-            {"id": "qwen3-coder-plus", "object": "model", "owned_by": "alibaba"},
-            {"id": "qwen3-coder-flash", "object": "model", "owned_by": "alibaba"}
-        ]
+        "data": builtin_models()
     }))
 }
 
@@ -0,0 +1,834 @@
+//! HTTP / SOCKS5 正向代理
+//!
+//! 供无法修改 API Base URL、但支持 `HTTP_PROXY` 或 SOCKS5 代理的工具使用：
+//! - 明文 HTTP 请求（absolute-form）发往已知 Provider 主机时，改写为本地 API 服务器请求，
+//!   替换客户端认证为服务器 API Key，并通过 `X-Provider-Id` 指定 Provider，由凭证池选择凭证
+//! - 其余明文 HTTP 请求原样转发到目标主机
+//! - `CONNECT` 请求建立 TCP 隧道透传；发往已知 Provider 主机的 `CONNECT` 直接拒绝（403），
+//!   隧道内为端到端 TLS 无法改写，拒绝可避免 Provider 流量悄悄绕过凭证池
+//! - SOCKS5 仅支持无认证的 `CONNECT` 命令：目标为已知 Provider 主机时按明文 HTTP 读取隧道内的
+//!   请求并改写（443 端口拒绝），其余目标按 TCP 隧道透传
+//!
+//! 只有以明文 HTTP 访问 Provider 的客户端会经凭证池路由；官方 SDK 默认使用 HTTPS，
+//! 需将 Base URL 改为 `http://` 才会被改写（未实现本地 CA 的 HTTPS 拦截）。
+//!
+//! 安全限制：
+//! - 仅监听回环地址，且只接受来自回环地址的连接
+//! - 隧道与原样转发拒绝解析到回环、内网、链路本地地址的目标（`allowed_private_hosts` 除外），
+//!   并连接到已校验的解析结果，避免被用作访问内网的跳板
+//!
+//! 每个连接只处理一个请求，转发时统一使用 `Connection: close`。
+
+use lime_core::config::ForwardProxySettings;
+use std::collections::{HashMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+/// 内置的已知 Provider 主机（主机名 -> Provider ID）
+///
+/// 仅包含请求路径与本地 API 服务器路由一致的 Provider。
+pub const BUILTIN_PROVIDER_HOSTS: &[(&str, &str)] = &[
+    ("api.openai.com", "openai"),
+    ("api.anthropic.com", "claude"),
+];
+
+/// 请求头最大字节数
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+/// SOCKS5 协议版本
+const SOCKS_VERSION: u8 = 0x05;
+/// SOCKS5 无认证方式
+const SOCKS_NO_AUTH: u8 = 0x00;
+/// SOCKS5 无可用认证方式
+const SOCKS_NO_ACCEPTABLE_AUTH: u8 = 0xFF;
+/// SOCKS5 `CONNECT` 命令
+const SOCKS_CMD_CONNECT: u8 = 0x01;
+
+/// SOCKS5 应答码
+const SOCKS_REPLY_SUCCEEDED: u8 = 0x00;
+const SOCKS_REPLY_GENERAL_FAILURE: u8 = 0x01;
+const SOCKS_REPLY_NOT_ALLOWED: u8 = 0x02;
+const SOCKS_REPLY_HOST_UNREACHABLE: u8 = 0x04;
+const SOCKS_REPLY_COMMAND_NOT_SUPPORTED: u8 = 0x07;
+const SOCKS_REPLY_ADDRESS_NOT_SUPPORTED: u8 = 0x08;
+
+/// 转发时移除的逐跳请求头
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authorization",
+    "proxy-connection",
+];
+
+/// 拦截请求时替换的请求头
+const INTERCEPT_REPLACED_HEADERS: &[&str] =
+    &["host", "authorization", "x-api-key", "x-provider-id"];
+
+/// HTTP 请求头
+#[derive(Debug, Clone, PartialEq)]
+struct RequestHead {
+    method: String,
+    target: String,
+    version: String,
+    headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    fn parse(raw: &[u8]) -> Option<Self> {
+        let text = std::str::from_utf8(raw).ok()?;
+        let mut lines = text.split("\r\n").filter(|l| !l.is_empty());
+        let mut request_line = lines.next()?.split_whitespace();
+        let method = request_line.next()?.to_string();
+        let target = request_line.next()?.to_string();
+        let version = request_line.next()?.to_string();
+        let headers = lines
+            .map(|line| {
+                let (name, value) = line.split_once(':')?;
+                Some((name.trim().to_string(), value.trim().to_string()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Self {
+            method,
+            target,
+            version,
+            headers,
+        })
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = format!("{} {} {}\r\n", self.method, self.target, self.version);
+        for (name, value) in &self.headers {
+            out.push_str(&format!("{name}: {value}\r\n"));
+        }
+        out.push_str("\r\n");
+        out.into_bytes()
+    }
+
+    fn remove_headers(&mut self, names: &[&str]) {
+        self.headers
+            .retain(|(name, _)| !names.iter().any(|n| name.eq_ignore_ascii_case(n)));
+    }
+}
+
+/// 代理动作
+#[derive(Debug, Clone, PartialEq)]
+enum ProxyAction {
+    /// CONNECT 隧道透传
+    Tunnel { authority: String },
+    /// 拒绝发往已知 Provider 主机的 CONNECT 隧道
+    RefuseTunnel { provider: String },
+    /// 改写到本地 API 服务器
+    Intercept { provider: String, path: String },
+    /// 原样转发明文 HTTP 请求
+    Forward { authority: String, path: String },
+}
+
+/// 已知 Provider 主机表
+#[derive(Debug, Clone)]
+struct ProviderHosts(HashMap<String, String>);
+
+impl ProviderHosts {
+    fn new(extra: &HashMap<String, String>) -> Self {
+        let mut hosts: HashMap<String, String> = BUILTIN_PROVIDER_HOSTS
+            .iter()
+            .map(|(host, provider)| (host.to_string(), provider.to_string()))
+            .collect();
+        for (host, provider) in extra {
+            let host = host.trim().to_lowercase();
+            let provider = provider.trim();
+            if !host.is_empty() && !provider.is_empty() {
+                hosts.insert(host, provider.to_string());
+            }
+        }
+        Self(hosts)
+    }
+
+    fn provider_for(&self, host: &str) -> Option<&str> {
+        self.0.get(&host.to_lowercase()).map(String::as_str)
+    }
+}
+
+/// 拆分 `host[:port]`，支持 IPv6 方括号形式
+fn split_authority(authority: &str) -> (&str, Option<u16>) {
+    if let Some(rest) = authority.strip_prefix('[') {
+        if let Some((host, tail)) = rest.split_once(']') {
+            let port = tail.strip_prefix(':').and_then(|p| p.parse().ok());
+            return (host, port);
+        }
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => (host, port.parse().ok()),
+        _ => (authority, None),
+    }
+}
+
+fn with_default_port(authority: &str, default_port: u16) -> String {
+    match split_authority(authority) {
+        (_, Some(_)) => authority.to_string(),
+        _ => format!("{authority}:{default_port}"),
+    }
+}
+
+fn plan(head: &RequestHead, hosts: &ProviderHosts) -> Result<ProxyAction, &'static str> {
+    if head.method.eq_ignore_ascii_case("CONNECT") {
+        let (host, _) = split_authority(&head.target);
+        return Ok(match hosts.provider_for(host) {
+            Some(provider) => ProxyAction::RefuseTunnel {
+                provider: provider.to_string(),
+            },
+            None => ProxyAction::Tunnel {
+                authority: with_default_port(&head.target, 443),
+            },
+        });
+    }
+    let rest = head
+        .target
+        .get(..7)
+        .filter(|scheme| scheme.eq_ignore_ascii_case("http://"))
+        .map(|_| &head.target[7..])
+        .ok_or("仅支持 absolute-form 的 http:// 请求")?;
+    let (authority, path) = match rest.find(['/', '?']) {
+        Some(idx) if rest[idx..].starts_with('/') => (&rest[..idx], rest[idx..].to_string()),
+        Some(idx) => (&rest[..idx], format!("/{}", &rest[idx..])),
+        None => (rest, "/".to_string()),
+    };
+    if authority.is_empty() {
+        return Err("请求缺少目标主机");
+    }
+    let (host, _) = split_authority(authority);
+    Ok(match hosts.provider_for(host) {
+        Some(provider) => ProxyAction::Intercept {
+            provider: provider.to_string(),
+            path,
+        },
+        None => ProxyAction::Forward {
+            authority: with_default_port(authority, 80),
+            path,
+        },
+    })
+}
+
+/// 改写发往本地 API 服务器的请求头
+fn rewrite_intercepted(
+    head: &mut RequestHead,
+    path: String,
+    provider: &str,
+    local_authority: &str,
+    api_key: &str,
+) {
+    head.target = path;
+    head.remove_headers(HOP_BY_HOP_HEADERS);
+    head.remove_headers(INTERCEPT_REPLACED_HEADERS);
+    head.headers.extend([
+        ("Host".to_string(), local_authority.to_string()),
+        ("Authorization".to_string(), format!("Bearer {api_key}")),
+        ("x-api-key".to_string(), api_key.to_string()),
+        ("X-Provider-Id".to_string(), provider.to_string()),
+        ("Connection".to_string(), "close".to_string()),
+    ]);
+}
+
+/// 改写原样转发的请求头（仅改为 origin-form 并移除逐跳头）
+fn rewrite_forwarded(head: &mut RequestHead, path: String) {
+    head.target = path;
+    head.remove_headers(HOP_BY_HOP_HEADERS);
+    head.headers
+        .push(("Connection".to_string(), "close".to_string()));
+}
+
+/// 是否为不允许转发的回环、内网或链路本地地址
+fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_ipv4(ip),
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_private_ipv4(mapped);
+            }
+            let first = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                // fc00::/7 唯一本地地址
+                || (first & 0xfe00) == 0xfc00
+                // fe80::/10 链路本地地址
+                || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+fn is_private_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        // 100.64.0.0/10 运营商级 NAT
+        || (a == 100 && (64..128).contains(&b))
+}
+
+/// 允许转发的内网主机
+#[derive(Debug, Clone, Default)]
+struct PrivateAllowlist(HashSet<String>);
+
+impl PrivateAllowlist {
+    fn new(hosts: &[String]) -> Self {
+        Self(
+            hosts
+                .iter()
+                .map(|host| host.trim().trim_matches(['[', ']']).to_lowercase())
+                .filter(|host| !host.is_empty())
+                .collect(),
+        )
+    }
+
+    fn contains(&self, host: &str) -> bool {
+        self.0.contains(&host.to_lowercase())
+    }
+}
+
+/// 校验目标地址（`host:port`），返回可连接的解析结果
+///
+/// 目标解析到回环、内网地址且不在白名单中时拒绝。
+fn check_targets(
+    host: &str,
+    addrs: Vec<SocketAddr>,
+    allowlist: &PrivateAllowlist,
+) -> Result<Vec<SocketAddr>, &'static str> {
+    if addrs.is_empty() {
+        return Err("目标主机无法解析");
+    }
+    if !allowlist.contains(host) && addrs.iter().any(|addr| is_private_ip(addr.ip())) {
+        return Err("拒绝转发到回环或内网地址");
+    }
+    Ok(addrs)
+}
+
+/// 解析并校验上游目标
+async fn resolve_upstream(
+    authority: &str,
+    allowlist: &PrivateAllowlist,
+) -> Result<Vec<SocketAddr>, &'static str> {
+    let (host, _) = split_authority(authority);
+    let addrs = match tokio::net::lookup_host(authority).await {
+        Ok(addrs) => addrs.collect(),
+        Err(_) => Vec::new(),
+    };
+    check_targets(host, addrs, allowlist)
+}
+
+/// HTTP 正向代理
+#[derive(Debug, Clone)]
+pub struct ForwardProxy {
+    hosts: ProviderHosts,
+    allowlist: PrivateAllowlist,
+    /// 本地 API 服务器地址（`host:port`）
+    local_authority: String,
+    api_key: String,
+}
+
+impl ForwardProxy {
+    /// 创建正向代理
+    ///
+    /// `server_host` 为 API 服务器监听地址，通配地址会改用回环地址连接。
+    pub fn new(
+        settings: &ForwardProxySettings,
+        server_host: &str,
+        server_port: u16,
+        api_key: &str,
+    ) -> Self {
+        let host = match server_host {
+            "0.0.0.0" | "" => "127.0.0.1".to_string(),
+            "::" => "[::1]".to_string(),
+            other if other.contains(':') && !other.starts_with('[') => format!("[{other}]"),
+            other => other.to_string(),
+        };
+        Self {
+            hosts: ProviderHosts::new(&settings.provider_hosts),
+            allowlist: PrivateAllowlist::new(&settings.allowed_private_hosts),
+            local_authority: format!("{host}:{server_port}"),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// 接受连接直到收到关闭信号
+    pub async fn serve(self, listener: TcpListener, mut shutdown: oneshot::Receiver<()>) {
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, peer)) if !peer.ip().is_loopback() => {
+                        tracing::warn!("[FORWARD_PROXY] 拒绝非本机连接: {}", peer);
+                        drop(stream);
+                    }
+                    Ok((stream, _)) => {
+                        let proxy = self.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = proxy.handle(stream).await {
+                                tracing::debug!("[FORWARD_PROXY] 连接处理失败: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => tracing::warn!("[FORWARD_PROXY] 接受连接失败: {}", e),
+                },
+            }
+        }
+        tracing::info!("[FORWARD_PROXY] 已停止");
+    }
+
+    async fn handle(&self, mut client: TcpStream) -> std::io::Result<()> {
+        let mut first = [0u8; 1];
+        if client.peek(&mut first).await? == 1 {
+            match first[0] {
+                SOCKS_VERSION => return self.handle_socks5(client).await,
+                0x04 => {
+                    tracing::warn!("[FORWARD_PROXY] 不支持 SOCKS4，请改用 SOCKS5 或 HTTP 代理");
+                    // SOCKS4 “请求被拒绝”应答
+                    return client.write_all(&[0x00, 0x5B, 0, 0, 0, 0, 0, 0]).await;
+                }
+                _ => {}
+            }
+        }
+        let Some((raw_head, body_prefix)) = read_head(&mut client).await? else {
+            return respond_error(&mut client, "400 Bad Request").await;
+        };
+        let Some(mut head) = RequestHead::parse(&raw_head) else {
+            return respond_error(&mut client, "400 Bad Request").await;
+        };
+        let action = match plan(&head, &self.hosts) {
+            Ok(action) => action,
+            Err(reason) => {
+                tracing::debug!("[FORWARD_PROXY] 拒绝请求 {}: {}", head.target, reason);
+                return respond_error(&mut client, "400 Bad Request").await;
+            }
+        };
+
+        let upstream_addrs: Vec<SocketAddr> = match action {
+            ProxyAction::RefuseTunnel { provider } => {
+                tracing::warn!(
+                    "[FORWARD_PROXY] 拒绝到 {} ({}) 的 HTTPS 隧道：无法经凭证池改写，请将 Base URL 改为 http://",
+                    head.target,
+                    provider
+                );
+                return respond_error(&mut client, "403 Forbidden").await;
+            }
+            ProxyAction::Tunnel { authority } => {
+                let addrs = match resolve_upstream(&authority, &self.allowlist).await {
+                    Ok(addrs) => addrs,
+                    Err(reason) => {
+                        tracing::warn!("[FORWARD_PROXY] 拒绝隧道 {}: {}", authority, reason);
+                        return respond_error(&mut client, "403 Forbidden").await;
+                    }
+                };
+                let Ok(mut upstream) = TcpStream::connect(&addrs[..]).await else {
+                    return respond_error(&mut client, "502 Bad Gateway").await;
+                };
+                client
+                    .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+                    .await?;
+                if !body_prefix.is_empty() {
+                    upstream.write_all(&body_prefix).await?;
+                }
+                tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+                return Ok(());
+            }
+            ProxyAction::Intercept { provider, path } => {
+                match self.intercept(&mut head, path, &provider).await {
+                    Some(addrs) => addrs,
+                    None => return respond_error(&mut client, "502 Bad Gateway").await,
+                }
+            }
+            ProxyAction::Forward { authority, path } => {
+                let addrs = match resolve_upstream(&authority, &self.allowlist).await {
+                    Ok(addrs) => addrs,
+                    Err(reason) => {
+                        tracing::warn!("[FORWARD_PROXY] 拒绝转发 {}: {}", authority, reason);
+                        return respond_error(&mut client, "403 Forbidden").await;
+                    }
+                };
+                rewrite_forwarded(&mut head, path);
+                addrs
+            }
+        };
+
+        let Ok(mut upstream) = TcpStream::connect(&upstream_addrs[..]).await else {
+            return respond_error(&mut client, "502 Bad Gateway").await;
+        };
+        upstream.write_all(&head.encode()).await?;
+        upstream.write_all(&body_prefix).await?;
+        tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+        Ok(())
+    }
+
+    /// 改写拦截的请求，返回本地 API 服务器地址
+    async fn intercept(
+        &self,
+        head: &mut RequestHead,
+        path: String,
+        provider: &str,
+    ) -> Option<Vec<SocketAddr>> {
+        tracing::info!(
+            "[FORWARD_PROXY] 拦截 {} {} -> Provider {}",
+            head.method,
+            path,
+            provider
+        );
+        rewrite_intercepted(head, path, provider, &self.local_authority, &self.api_key);
+        tokio::net::lookup_host(&self.local_authority)
+            .await
+            .ok()
+            .map(|addrs| addrs.collect())
+    }
+
+    /// 处理 SOCKS5 连接
+    ///
+    /// 已知 Provider 主机按明文 HTTP 拦截（443 端口拒绝），其余目标按隧道透传。
+    async fn handle_socks5(&self, mut client: TcpStream) -> std::io::Result<()> {
+        let authority = match socks5_handshake(&mut client).await? {
+            SocksHandshake::Connect(authority) => authority,
+            SocksHandshake::Reject(code) => return socks5_reply(&mut client, code).await,
+            SocksHandshake::NoAcceptableAuth => return Ok(()),
+        };
+        let (host, port) = split_authority(&authority);
+
+        if let Some(provider) = self.hosts.provider_for(host) {
+            if port == Some(443) {
+                tracing::warn!(
+                    "[FORWARD_PROXY] 拒绝到 {} ({}) 的 SOCKS5 HTTPS 连接：无法经凭证池改写，请将 Base URL 改为 http://",
+                    authority,
+                    provider
+                );
+                return socks5_reply(&mut client, SOCKS_REPLY_NOT_ALLOWED).await;
+            }
+            let provider = provider.to_string();
+            socks5_reply(&mut client, SOCKS_REPLY_SUCCEEDED).await?;
+            let Some((raw_head, body_prefix)) = read_head(&mut client).await? else {
+                return respond_error(&mut client, "400 Bad Request").await;
+            };
+            let Some(mut head) =
+                RequestHead::parse(&raw_head).filter(|h| h.target.starts_with('/'))
+            else {
+                return respond_error(&mut client, "400 Bad Request").await;
+            };
+            let path = head.target.clone();
+            let Some(addrs) = self.intercept(&mut head, path, &provider).await else {
+                return respond_error(&mut client, "502 Bad Gateway").await;
+            };
+            let Ok(mut upstream) = TcpStream::connect(&addrs[..]).await else {
+                return respond_error(&mut client, "502 Bad Gateway").await;
+            };
+            upstream.write_all(&head.encode()).await?;
+            upstream.write_all(&body_prefix).await?;
+            tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+            return Ok(());
+        }
+
+        let addrs = match resolve_upstream(&authority, &self.allowlist).await {
+            Ok(addrs) => addrs,
+            Err(reason) => {
+                tracing::warn!("[FORWARD_PROXY] 拒绝 SOCKS5 连接 {}: {}", authority, reason);
+                return socks5_reply(&mut client, SOCKS_REPLY_NOT_ALLOWED).await;
+            }
+        };
+        let Ok(mut upstream) = TcpStream::connect(&addrs[..]).await else {
+            return socks5_reply(&mut client, SOCKS_REPLY_HOST_UNREACHABLE).await;
+        };
+        socks5_reply(&mut client, SOCKS_REPLY_SUCCEEDED).await?;
+        tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+        Ok(())
+    }
+}
+
+/// SOCKS5 握手结果
+#[derive(Debug, PartialEq)]
+enum SocksHandshake {
+    /// 请求连接的目标（`host:port`）
+    Connect(String),
+    /// 以该应答码拒绝请求
+    Reject(u8),
+    /// 客户端不支持无认证方式，已回复并应断开
+    NoAcceptableAuth,
+}
+
+/// 完成 SOCKS5 认证协商并读取连接请求
+async fn socks5_handshake<S>(stream: &mut S) -> std::io::Result<SocksHandshake>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut greeting = [0u8; 2];
+    stream.read_exact(&mut greeting).await?;
+    let mut methods = vec![0u8; greeting[1] as usize];
+    stream.read_exact(&mut methods).await?;
+    if greeting[0] != SOCKS_VERSION || !methods.contains(&SOCKS_NO_AUTH) {
+        stream
+            .write_all(&[SOCKS_VERSION, SOCKS_NO_ACCEPTABLE_AUTH])
+            .await?;
+        return Ok(SocksHandshake::NoAcceptableAuth);
+    }
+    stream.write_all(&[SOCKS_VERSION, SOCKS_NO_AUTH]).await?;
+
+    let mut request = [0u8; 4];
+    stream.read_exact(&mut request).await?;
+    let [version, command, _, address_type] = request;
+    if version != SOCKS_VERSION {
+        return Ok(SocksHandshake::Reject(SOCKS_REPLY_GENERAL_FAILURE));
+    }
+    let host = match address_type {
+        0x01 => {
+            let mut ip = [0u8; 4];
+            stream.read_exact(&mut ip).await?;
+            Ipv4Addr::from(ip).to_string()
+        }
+        0x03 => {
+            let mut len = [0u8; 1];
+            stream.read_exact(&mut len).await?;
+            let mut name = vec![0u8; len[0] as usize];
+            stream.read_exact(&mut name).await?;
+            match String::from_utf8(name) {
+                Ok(name) if !name.is_empty() && !name.contains(':') => name,
+                _ => return Ok(SocksHandshake::Reject(SOCKS_REPLY_GENERAL_FAILURE)),
+            }
+        }
+        0x04 => {
+            let mut ip = [0u8; 16];
+            stream.read_exact(&mut ip).await?;
+            format!("[{}]", Ipv6Addr::from(ip))
+        }
+        _ => return Ok(SocksHandshake::Reject(SOCKS_REPLY_ADDRESS_NOT_SUPPORTED)),
+    };
+    let mut port = [0u8; 2];
+    stream.read_exact(&mut port).await?;
+    if command != SOCKS_CMD_CONNECT {
+        return Ok(SocksHandshake::Reject(SOCKS_REPLY_COMMAND_NOT_SUPPORTED));
+    }
+    Ok(SocksHandshake::Connect(format!(
+        "{host}:{}",
+        u16::from_be_bytes(port)
+    )))
+}
+
+/// 发送 SOCKS5 应答（绑定地址填 0.0.0.0:0）
+async fn socks5_reply<S>(stream: &mut S, code: u8) -> std::io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    stream
+        .write_all(&[SOCKS_VERSION, code, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+        .await
+}
+
+/// 读取请求头，返回 (请求头, 已读取的请求体前缀)
+async fn read_head(stream: &mut TcpStream) -> std::io::Result<Option<(Vec<u8>, Vec<u8>)>> {
+    let mut buf = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+    loop {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let body = buf.split_off(end + 4);
+            return Ok(Some((buf, body)));
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Ok(None);
+        }
+    }
+}
+
+async fn respond_error(stream: &mut TcpStream, status: &str) -> std::io::Result<()> {
+    let response = format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+    stream.write_all(response.as_bytes()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(raw: &str) -> RequestHead {
+        RequestHead::parse(raw.as_bytes()).unwrap()
+    }
+
+    fn header<'a>(head: &'a RequestHead, name: &str) -> Option<&'a str> {
+        head.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[test]
+    fn test_plan_routes_by_host() {
+        let extra = HashMap::from([("LLM.Example.com".to_string(), "my-custom".to_string())]);
+        let hosts = ProviderHosts::new(&extra);
+
+        // 发往 Provider 主机的 HTTPS 隧道无法改写，直接拒绝而不是绕过凭证池
+        let connect = head("CONNECT api.openai.com:443 HTTP/1.1\r\nHost: api.openai.com\r\n\r\n");
+        assert_eq!(
+            plan(&connect, &hosts),
+            Ok(ProxyAction::RefuseTunnel {
+                provider: "openai".to_string()
+            })
+        );
+
+        let tunnel = head("CONNECT example.org HTTP/1.1\r\n\r\n");
+        assert_eq!(
+            plan(&tunnel, &hosts),
+            Ok(ProxyAction::Tunnel {
+                authority: "example.org:443".to_string()
+            })
+        );
+
+        let openai = head("POST http://API.openai.com/v1/chat/completions HTTP/1.1\r\n\r\n");
+        assert_eq!(
+            plan(&openai, &hosts),
+            Ok(ProxyAction::Intercept {
+                provider: "openai".to_string(),
+                path: "/v1/chat/completions".to_string()
+            })
+        );
+
+        let custom = head("POST http://llm.example.com:8080/v1/messages HTTP/1.1\r\n\r\n");
+        assert!(matches!(
+            plan(&custom, &hosts),
+            Ok(ProxyAction::Intercept { ref provider, .. }) if provider == "my-custom"
+        ));
+
+        let other = head("GET http://example.org?q=1 HTTP/1.1\r\n\r\n");
+        assert_eq!(
+            plan(&other, &hosts),
+            Ok(ProxyAction::Forward {
+                authority: "example.org:80".to_string(),
+                path: "/?q=1".to_string()
+            })
+        );
+
+        let origin_form = head("GET /v1/models HTTP/1.1\r\n\r\n");
+        assert!(plan(&origin_form, &hosts).is_err());
+    }
+
+    #[test]
+    fn test_rewrite_intercepted_replaces_auth() {
+        let mut request = head(
+            "POST http://api.anthropic.com/v1/messages HTTP/1.1\r\n\
+             Host: api.anthropic.com\r\n\
+             x-api-key: sk-ant-client\r\n\
+             Proxy-Connection: keep-alive\r\n\
+             Content-Length: 2\r\n\r\n",
+        );
+        rewrite_intercepted(
+            &mut request,
+            "/v1/messages".to_string(),
+            "claude",
+            "127.0.0.1:8999",
+            "server-key",
+        );
+
+        assert_eq!(request.target, "/v1/messages");
+        assert_eq!(header(&request, "host"), Some("127.0.0.1:8999"));
+        assert_eq!(header(&request, "x-api-key"), Some("server-key"));
+        assert_eq!(header(&request, "authorization"), Some("Bearer server-key"));
+        assert_eq!(header(&request, "x-provider-id"), Some("claude"));
+        assert_eq!(header(&request, "content-length"), Some("2"));
+        assert!(header(&request, "proxy-connection").is_none());
+
+        let encoded = String::from_utf8(request.encode()).unwrap();
+        assert!(encoded.starts_with("POST /v1/messages HTTP/1.1\r\n"));
+        assert!(encoded.ends_with("Connection: close\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_private_targets_are_refused() {
+        let addr = |s: &str| -> SocketAddr { s.parse().unwrap() };
+        let none = PrivateAllowlist::default();
+
+        for blocked in [
+            "127.0.0.1:80",
+            "10.0.0.5:443",
+            "192.168.1.1:80",
+            "169.254.169.254:80",
+            "100.64.0.1:80",
+            "0.0.0.0:80",
+            "[::1]:443",
+            "[fd00::1]:443",
+            "[fe80::1]:443",
+            "[::ffff:127.0.0.1]:80",
+        ] {
+            assert!(
+                check_targets("h", vec![addr(blocked)], &none).is_err(),
+                "{blocked}"
+            );
+        }
+        assert!(check_targets("h", vec![addr("1.1.1.1:443")], &none).is_ok());
+        // 任一解析结果为内网地址即拒绝（防止混合解析绕过）
+        assert!(
+            check_targets("h", vec![addr("1.1.1.1:443"), addr("127.0.0.1:443")], &none).is_err()
+        );
+        assert!(check_targets("h", Vec::new(), &none).is_err());
+
+        let allowlist = PrivateAllowlist::new(&["Intranet.local".to_string(), "[::1]".to_string()]);
+        assert!(check_targets("intranet.local", vec![addr("10.0.0.5:80")], &allowlist).is_ok());
+        assert!(check_targets("::1", vec![addr("[::1]:80")], &allowlist).is_ok());
+    }
+
+    #[test]
+    fn test_split_authority() {
+        assert_eq!(
+            split_authority("example.org:8080"),
+            ("example.org", Some(8080))
+        );
+        assert_eq!(split_authority("example.org"), ("example.org", None));
+        assert_eq!(split_authority("[::1]:443"), ("::1", Some(443)));
+        assert_eq!(with_default_port("[::1]", 80), "[::1]:80");
+    }
+
+    async fn socks5_exchange(client_bytes: &[u8]) -> (SocksHandshake, Vec<u8>) {
+        let (mut client, mut server) = tokio::io::duplex(1024);
+        client.write_all(client_bytes).await.unwrap();
+        let result = socks5_handshake(&mut server).await.unwrap();
+        drop(server);
+        let mut replies = Vec::new();
+        client.read_to_end(&mut replies).await.unwrap();
+        (result, replies)
+    }
+
+    #[tokio::test]
+    async fn test_socks5_handshake() {
+        // 域名目标
+        let mut bytes = vec![0x05, 0x01, 0x00, 0x05, 0x01, 0x00, 0x03, 14];
+        bytes.extend_from_slice(b"api.openai.com");
+        bytes.extend_from_slice(&80u16.to_be_bytes());
+        let (result, replies) = socks5_exchange(&bytes).await;
+        assert_eq!(
+            result,
+            SocksHandshake::Connect("api.openai.com:80".to_string())
+        );
+        assert_eq!(replies, vec![0x05, 0x00]);
+
+        // IPv4 / IPv6 目标
+        let (result, _) = socks5_exchange(&[
+            0x05, 0x01, 0x00, 0x05, 0x01, 0x00, 0x01, 1, 1, 1, 1, 0x01, 0xBB,
+        ])
+        .await;
+        assert_eq!(result, SocksHandshake::Connect("1.1.1.1:443".to_string()));
+        let mut bytes = vec![0x05, 0x01, 0x00, 0x05, 0x01, 0x00, 0x04];
+        bytes.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        bytes.extend_from_slice(&8080u16.to_be_bytes());
+        let (result, _) = socks5_exchange(&bytes).await;
+        assert_eq!(result, SocksHandshake::Connect("[::1]:8080".to_string()));
+
+        // 仅支持用户名密码认证的客户端被拒绝
+        let (result, replies) = socks5_exchange(&[0x05, 0x01, 0x02]).await;
+        assert_eq!(result, SocksHandshake::NoAcceptableAuth);
+        assert_eq!(replies, vec![0x05, 0xFF]);
+
+        // BIND 命令不支持
+        let (result, _) =
+            socks5_exchange(&[0x05, 0x01, 0x00, 0x05, 0x02, 0x00, 0x01, 1, 1, 1, 1, 0, 80]).await;
+        assert_eq!(
+            result,
+            SocksHandshake::Reject(SOCKS_REPLY_COMMAND_NOT_SUPPORTED)
+        );
+    }
+}
@@ -0,0 +1,419 @@
+//! 管理 API 端点（供命令行工具使用）
+//!
+//! 为 `lime-cli` 等脚本工具提供与 GUI 等价的只读查询和少量操作：
+//! - 列出凭证池
+//! - 查询并跟踪应用日志
+//! - 列出、导出会话
+//! - 列出并运行 Skill（仅 prompt 模式）
+//!
+//! 所有端点只接受服务器主 API Key，不接受租户 API Key。
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::AppState;
+use aster::session::SessionStore;
+use lime_core::database::DbConnection;
+use lime_core::logger::LogEntry;
+use lime_services::aster_session_store::LimeSessionStore;
+
+use super::verify_api_key;
+
+/// 日志查询默认条数
+const DEFAULT_LOG_LIMIT: usize = 100;
+
+/// 日志查询最大条数
+const MAX_LOG_LIMIT: usize = 1000;
+
+/// 管理 API 错误响应
+#[derive(Debug, Serialize)]
+pub struct AdminApiError {
+    pub error: String,
+    pub message: String,
+    pub status_code: u16,
+}
+
+impl AdminApiError {
+    fn new(status: StatusCode, error: &str, message: impl Into<String>) -> Self {
+        Self {
+            error: error.to_string(),
+            message: message.into(),
+            status_code: status.as_u16(),
+        }
+    }
+}
+
+impl IntoResponse for AdminApiError {
+    fn into_response(self) -> Response {
+        let status =
+            StatusCode::from_u16(self.status_code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (status, Json(self)).into_response()
+    }
+}
+
+/// 校验主 API Key
+async fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<(), Response> {
+    verify_api_key(headers, &state.api_key)
+        .await
+        .map_err(IntoResponse::into_response)
+}
+
+fn require_db(state: &AppState) -> Result<&DbConnection, AdminApiError> {
+    state.db.as_ref().ok_or_else(|| {
+        AdminApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "database_unavailable",
+            "数据库连接不可用",
+        )
+    })
+}
+
+/// GET /v1/admin/credentials - 列出凭证池（已脱敏）
+pub async fn admin_list_credentials(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Err(response) = require_admin(&state, &headers).await {
+        return response;
+    }
+    let result = require_db(&state).and_then(|db| {
+        state
+            .pool_service
+            .get_overview(db)
+            .map_err(|e| AdminApiError::new(StatusCode::INTERNAL_SERVER_ERROR, "query_failed", e))
+    });
+    match result {
+        Ok(mut overview) => {
+            // 完整 api_key 仅供 GUI 编辑使用，不通过 HTTP 返回
+            for cred in overview.iter_mut().flat_map(|o| o.credentials.iter_mut()) {
+                cred.api_key = None;
+            }
+            Json(overview).into_response()
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 日志查询参数
+#[derive(Debug, Default, Deserialize)]
+pub struct AdminLogsQuery {
+    /// 只返回不早于该时间戳（RFC 3339）的日志，用于增量跟踪
+    ///
+    /// 包含边界，避免与上一批最后一条同时间戳的日志丢失；调用方需自行去重。
+    #[serde(default)]
+    pub since: Option<String>,
+    /// 最多返回条数（取最新的部分）
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// 按级别过滤（info / warn / error / debug）
+    #[serde(default)]
+    pub level: Option<String>,
+}
+
+/// 日志查询响应
+#[derive(Debug, Serialize)]
+pub struct AdminLogsResponse {
+    pub entries: Vec<LogEntry>,
+    /// 最后一条日志的时间戳，作为下一次查询的 `since`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// 按查询条件筛选日志
+///
+/// 日志时间戳均为 UTC RFC 3339 格式，可直接按字符串比较先后。
+pub(crate) fn filter_logs(logs: Vec<LogEntry>, query: &AdminLogsQuery) -> AdminLogsResponse {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_LOG_LIMIT)
+        .clamp(1, MAX_LOG_LIMIT);
+    let mut entries: Vec<LogEntry> = logs
+        .into_iter()
+        .filter(|entry| {
+            query
+                .since
+                .as_deref()
+                .is_none_or(|since| entry.timestamp.as_str() >= since)
+        })
+        .filter(|entry| {
+            query
+                .level
+                .as_deref()
+                .is_none_or(|level| entry.level.eq_ignore_ascii_case(level))
+        })
+        .collect();
+    if entries.len() > limit {
+        entries.drain(..entries.len() - limit);
+    }
+
+    let cursor = entries
+        .last()
+        .map(|entry| entry.timestamp.clone())
+        .or_else(|| query.since.clone());
+    AdminLogsResponse { entries, cursor }
+}
+
+/// GET /v1/admin/logs - 查询应用日志
+pub async fn admin_logs(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<AdminLogsQuery>,
+) -> Response {
+    if let Err(response) = require_admin(&state, &headers).await {
+        return response;
+    }
+    let logs = state.logs.read().await.get_logs();
+    Json(filter_logs(logs, &query)).into_response()
+}
+
+/// GET /v1/admin/sessions - 列出会话（不含消息）
+pub async fn admin_list_sessions(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Err(response) = require_admin(&state, &headers).await {
+        return response;
+    }
+    let db = match require_db(&state) {
+        Ok(db) => db.clone(),
+        Err(e) => return e.into_response(),
+    };
+    match LimeSessionStore::new(db).list_sessions().await {
+        Ok(sessions) => Json(sessions).into_response(),
+        Err(e) => AdminApiError::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "query_failed",
+            e.to_string(),
+        )
+        .into_response(),
+    }
+}
+
+/// GET /v1/admin/sessions/:id/export - 导出会话（含消息）为 JSON
+///
+/// 导出内容可通过应用的会话导入功能恢复。
+pub async fn admin_export_session(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<String>,
+) -> Response {
+    if let Err(response) = require_admin(&state, &headers).await {
+        return response;
+    }
+    let db = match require_db(&state) {
+        Ok(db) => db.clone(),
+        Err(e) => return e.into_response(),
+    };
+    match LimeSessionStore::new(db).export_session(&id).await {
+        Ok(json) => ([(header::CONTENT_TYPE, "application/json")], json).into_response(),
+        Err(e) => AdminApiError::new(
+            StatusCode::NOT_FOUND,
+            "session_not_found",
+            format!("导出会话 {id} 失败: {e}"),
+        )
+        .into_response(),
+    }
+}
+
+/// Skill 摘要
+#[derive(Debug, Serialize)]
+pub struct AdminSkillSummary {
+    pub name: String,
+    pub display_name: String,
+    pub description: String,
+    pub execution_mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+}
+
+/// GET /v1/admin/skills - 列出本地已安装的 Skill
+pub async fn admin_list_skills(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Err(response) = require_admin(&state, &headers).await {
+        return response;
+    }
+    let mut skills: Vec<AdminSkillSummary> = lime_skills::get_skill_roots()
+        .iter()
+        .flat_map(|dir| lime_skills::load_skills_from_directory(dir))
+        .map(|skill| AdminSkillSummary {
+            name: skill.skill_name,
+            display_name: skill.display_name,
+            description: skill.description,
+            execution_mode: skill.execution_mode,
+            model: skill.model,
+        })
+        .collect();
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
+    skills.dedup_by(|a, b| a.name == b.name);
+    Json(skills).into_response()
+}
+
+/// 运行 Skill 请求
+#[derive(Debug, Deserialize)]
+pub struct AdminRunSkillRequest {
+    /// 用户输入
+    pub input: String,
+    /// 模型（未指定时使用 Skill 声明的模型）
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// POST /v1/admin/skills/:name/run - 运行 Skill
+///
+/// 以 Skill 内容作为系统提示词，通过本服务的 `/v1/chat/completions` 流程发起一次
+/// 非流式请求，返回 OpenAI 格式的响应。workflow 模式的 Skill 依赖桌面端运行时，
+/// 需在应用内执行。
+pub async fn admin_run_skill(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(request): Json<AdminRunSkillRequest>,
+) -> Response {
+    if let Err(response) = require_admin(&state, &headers).await {
+        return response;
+    }
+    if !is_valid_skill_name(&name) {
+        return AdminApiError::new(
+            StatusCode::BAD_REQUEST,
+            "invalid_skill_name",
+            format!("Skill 名称无效: {name}"),
+        )
+        .into_response();
+    }
+
+    let skill = match lime_skills::find_skill_by_name(&name) {
+        Ok(skill) => skill,
+        Err(e) => {
+            return AdminApiError::new(StatusCode::NOT_FOUND, "skill_not_found", e).into_response()
+        }
+    };
+    if skill.execution_mode == "workflow" {
+        return AdminApiError::new(
+            StatusCode::BAD_REQUEST,
+            "unsupported_execution_mode",
+            format!("Skill {name} 为 workflow 模式，请在应用内执行"),
+        )
+        .into_response();
+    }
+    let Some(model) = request.model.or(skill.model) else {
+        return AdminApiError::new(
+            StatusCode::BAD_REQUEST,
+            "model_required",
+            format!("Skill {name} 未声明模型，请通过 model 参数指定"),
+        )
+        .into_response();
+    };
+
+    tracing::info!("[ADMIN_API] 运行 Skill: name={}, model={}", name, model);
+    let body = serde_json::json!({
+        "model": model,
+        "stream": false,
+        "messages": [
+            { "role": "system", "content": skill.markdown_content },
+            { "role": "user", "content": request.input },
+        ],
+    });
+    crate::chat_completions_route(State(state), headers, Json(body)).await
+}
+
+/// Skill 名称只能是单个普通路径段，防止通过 `..` 或分隔符读取 Skill 目录之外的文件
+fn is_valid_skill_name(name: &str) -> bool {
+    let mut components = std::path::Path::new(name).components();
+    matches!(components.next(), Some(std::path::Component::Normal(_)))
+        && components.next().is_none()
+        && !name.contains(['/', '\\'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(timestamp: &str, level: &str) -> LogEntry {
+        LogEntry {
+            timestamp: timestamp.to_string(),
+            level: level.to_string(),
+            message: format!("{level} at {timestamp}"),
+        }
+    }
+
+    #[test]
+    fn test_filter_logs_since_level_and_limit() {
+        let logs = vec![
+            entry("2026-01-01T00:00:01+00:00", "info"),
+            entry("2026-01-01T00:00:02+00:00", "error"),
+            entry("2026-01-01T00:00:03+00:00", "info"),
+            entry("2026-01-01T00:00:04+00:00", "info"),
+        ];
+
+        let response = filter_logs(
+            logs.clone(),
+            &AdminLogsQuery {
+                since: Some("2026-01-01T00:00:01+00:00".to_string()),
+                limit: Some(2),
+                level: None,
+            },
+        );
+        assert_eq!(response.entries.len(), 2);
+        assert_eq!(response.entries[0].timestamp, "2026-01-01T00:00:03+00:00");
+        assert_eq!(
+            response.cursor.as_deref(),
+            Some("2026-01-01T00:00:04+00:00")
+        );
+
+        let response = filter_logs(
+            logs,
+            &AdminLogsQuery {
+                level: Some("ERROR".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(response.entries.len(), 1);
+        assert_eq!(response.entries[0].level, "error");
+    }
+
+    #[test]
+    fn test_filter_logs_since_is_inclusive() {
+        let response = filter_logs(
+            vec![
+                entry("2026-01-01T00:00:01+00:00", "info"),
+                entry("2026-01-01T00:00:02+00:00", "info"),
+                entry("2026-01-01T00:00:02+00:00", "warn"),
+            ],
+            &AdminLogsQuery {
+                since: Some("2026-01-01T00:00:02+00:00".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(response.entries.len(), 2);
+        assert!(response
+            .entries
+            .iter()
+            .all(|e| e.timestamp == "2026-01-01T00:00:02+00:00"));
+    }
+
+    #[test]
+    fn test_is_valid_skill_name() {
+        assert!(is_valid_skill_name("code-review"));
+        assert!(!is_valid_skill_name(""));
+        assert!(!is_valid_skill_name(".."));
+        assert!(!is_valid_skill_name("."));
+        assert!(!is_valid_skill_name("../secrets"));
+        assert!(!is_valid_skill_name("a/b"));
+        assert!(!is_valid_skill_name("a\\b"));
+        assert!(!is_valid_skill_name("/etc"));
+    }
+
+    #[test]
+    fn test_filter_logs_keeps_cursor_when_empty() {
+        let response = filter_logs(
+            vec![entry("2026-01-01T00:00:01+00:00", "info")],
+            &AdminLogsQuery {
+                since: Some("2026-01-01T00:00:05+00:00".to_string()),
+                ..Default::default()
+            },
+        );
+        assert!(response.entries.is_empty());
+        assert_eq!(
+            response.cursor.as_deref(),
+            Some("2026-01-01T00:00:05+00:00")
+        );
+    }
+}
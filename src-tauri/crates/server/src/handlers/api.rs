@@ -26,22 +26,41 @@ use std::{
     collections::{HashMap, HashSet},
     future::Future,
     sync::Arc,
+    time::Duration,
 };
 
 use crate::client_detector::ClientType;
+use crate::middleware::cost_cap::COST_PROVIDER_METADATA_KEY;
+use crate::middleware::cost_ledger::{COST_SESSION_METADATA_KEY, SESSION_ID_HEADER};
+use crate::middleware::degenerate_response::DegenerateResponseFormat;
 use crate::middleware::request_dedup::{
     build_request_fingerprint, RequestDedupCheck, RequestDedupStore,
 };
 use crate::middleware::response_cache::{CachedHttpResponse, ResponseCacheStore};
+use crate::middleware::session_affinity::current_affinity_session;
+use crate::middleware::tenant::{TenantRuntime, TENANT_METADATA_KEY};
 use crate::{record_request_telemetry, record_token_usage, AppState};
 use aster::context::MODEL_CONTEXT_WINDOWS;
+use lime_core::config::{ContextTrimSettings, ContextTrimStrategy, ContextUpgradeSettings};
+use lime_core::cpu_pool::{run_cpu_task, CpuTaskKind, CpuTaskPriority};
+use lime_core::credential::{RateLimitEvent, RiskController};
 use lime_core::errors::GatewayErrorCode;
 use lime_core::models::anthropic::AnthropicMessagesRequest;
 use lime_core::models::openai::{ChatCompletionRequest, ContentPart, MessageContent};
+use lime_core::models::VirtualModel;
+use lime_core::processor::{current_deadline, RequestDeadline, REQUEST_ID_HEADER};
 use lime_core::ProviderType;
+use lime_infra::resilience::UPSTREAM_ENDPOINT_HEADER;
+use lime_processor::context_trimmer;
+use lime_processor::failover_chain::{
+    is_failover_status, FailoverHopEvent, FailoverHopOutcome, FailoverPlan, PlannedHop,
+    FAILOVER_HOP_HEADER, FAILOVER_METADATA_KEY,
+};
 use lime_processor::RequestContext;
 use lime_providers::converter::anthropic_to_openai::convert_anthropic_to_openai;
+use lime_providers::converter::logprobs::{self, LogprobsOptions};
 use lime_providers::streaming::StreamFormat as StreamingFormat;
+use lime_providers::streaming::{StreamOptions, StreamUsageTracker};
 use lime_server_utils::{
     build_anthropic_response, build_anthropic_stream_response, build_error_response_with_meta,
     build_gateway_error_json, message_content_len, parse_cw_response, safe_truncate,
@@ -49,13 +68,97 @@ use lime_server_utils::{
 
 use super::{call_provider_anthropic, call_provider_openai};
 
-async fn select_credential_for_request(
+pub(crate) async fn select_credential_for_request(
+    state: &AppState,
+    request_id: Option<&str>,
+    selected_provider: &str,
+    model: &str,
+    client_type: &ClientType,
+    explicit_provider_id: Option<&str>,
+    tenant: Option<&TenantRuntime>,
+    log_prefix: &str,
+    include_error_code: bool,
+) -> Result<Option<lime_core::models::provider_pool_model::ProviderCredential>, Response> {
+    let cred = select_pool_credential(
+        state,
+        request_id,
+        selected_provider,
+        model,
+        client_type,
+        explicit_provider_id,
+        tenant,
+        log_prefix,
+        include_error_code,
+    )
+    .await?;
+    Ok(rotate_near_quota_credential(
+        state,
+        cred,
+        model,
+        client_type,
+        tenant,
+    ))
+}
+
+/// 选中的凭证用量达到软上限时，主动换用同类型中未接近配额的凭证
+///
+/// 没有合适的替代凭证时继续使用原凭证，由配额超限处理兜底。
+fn rotate_near_quota_credential(
+    state: &AppState,
+    cred: Option<lime_core::models::provider_pool_model::ProviderCredential>,
+    model: &str,
+    client_type: &ClientType,
+    tenant: Option<&TenantRuntime>,
+) -> Option<lime_core::models::provider_pool_model::ProviderCredential> {
+    let cred = cred?;
+    let quota_manager = &state.quota_manager;
+    if !quota_manager.is_near_soft_limit(&cred.uuid) {
+        return Some(cred);
+    }
+    let Some(db) = &state.db else {
+        return Some(cred);
+    };
+
+    let provider_type = cred.provider_type.to_string();
+    let session_id = current_affinity_session();
+    let alternative = state
+        .pool_service
+        .select_credential_with_filter(
+            db,
+            &provider_type,
+            Some(model),
+            Some(client_type),
+            session_id.as_deref(),
+            |c| {
+                c.uuid != cred.uuid
+                    && !quota_manager.is_near_soft_limit(&c.uuid)
+                    && tenant.is_none_or(|t| !t.restricts_credentials() || t.allows_credential(c))
+            },
+        )
+        .ok()
+        .flatten();
+    match alternative {
+        Some(alternative) => {
+            tracing::info!(
+                from_credential = %cred.uuid,
+                to_credential = %alternative.uuid,
+                provider = %provider_type,
+                "[QUOTA] 凭证用量接近配额，主动切换"
+            );
+            Some(alternative)
+        }
+        None => Some(cred),
+    }
+}
+
+async fn select_pool_credential(
     state: &AppState,
     request_id: Option<&str>,
     selected_provider: &str,
     model: &str,
     client_type: &ClientType,
     explicit_provider_id: Option<&str>,
+    tenant: Option<&TenantRuntime>,
     log_prefix: &str,
     _include_error_code: bool,
 ) -> Result<Option<lime_core::models::provider_pool_model::ProviderCredential>, Response> {
@@ -66,6 +169,35 @@ async fn select_credential_for_request(
             return Ok(None);
         }
     };
+    // 同一会话优先复用已绑定的凭证
+    let session_id = current_affinity_session();
+
+    // 租户限定了凭证子集时，只在该子集内选择，不走智能降级
+    if let Some(tenant) = tenant.filter(|t| t.restricts_credentials()) {
+        let provider = explicit_provider_id.unwrap_or(selected_provider);
+        return match state.pool_service.select_credential_with_filter(
+            db,
+            provider,
+            Some(model),
+            Some(client_type),
+            session_id.as_deref(),
+            |c| tenant.allows_credential(c),
+        ) {
+            Ok(cred) => {
+                if cred.is_none() {
+                    eprintln!(
+                        "[{log_prefix}] 租户 {} 在 provider={provider} 下没有可用凭证",
+                        tenant.id()
+                    );
+                }
+                Ok(cred)
+            }
+            Err(e) => {
+                eprintln!("[{log_prefix}] 租户 {} 选择凭证失败: {e}", tenant.id());
+                Ok(None)
+            }
+        };
+    }
 
     if let Some(explicit_provider_id) = explicit_provider_id {
         eprintln!("[{log_prefix}] 使用 X-Provider-Id 指定的 provider: {explicit_provider_id}");
@@ -76,6 +208,7 @@ async fn select_credential_for_request(
                 explicit_provider_id,
                 Some(model),
                 Some(client_type),
+                session_id.as_deref(),
             )
             .ok()
             .flatten();
@@ -115,6 +248,7 @@ async fn select_credential_for_request(
             selected_provider,
             Some(model),
             Some(client_type),
+            session_id.as_deref(),
         ) {
             Ok(cred) => {
                 if cred.is_some() {
@@ -143,6 +277,7 @@ async fn select_credential_for_request(
             Some(model),
             Some(provider_id_hint.as_str()),
             Some(client_type),
+            session_id.as_deref(),
         )
         .await
     {
@@ -165,31 +300,90 @@ async fn call_with_single_provider_resilience<F, Fut>(
     state: &AppState,
     request_id: &str,
     provider_label: &str,
+    credential_id: &str,
     is_stream: bool,
-    mut operation: F,
+    operation: F,
 ) -> Response
 where
     F: FnMut() -> Fut,
     Fut: Future<Output = Response>,
 {
-    let retrier = state.processor.retrier.clone();
-    let timeout_controller = state.processor.timeout.clone();
     let max_retries = if is_stream {
         0
     } else {
-        retrier.config().max_retries
+        state.processor.retrier.config().max_retries
     };
+    call_with_provider_retries(
+        state,
+        request_id,
+        provider_label,
+        credential_id,
+        max_retries,
+        operation,
+    )
+    .await
+    .0
+}
+
+/// 单个 Provider 的超时与重试，返回最终响应与尝试次数
+async fn call_with_provider_retries<F, Fut>(
+    state: &AppState,
+    request_id: &str,
+    provider_label: &str,
+    credential_id: &str,
+    max_retries: u32,
+    mut operation: F,
+) -> (Response, u32)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Response>,
+{
+    let retrier = state.processor.retrier.clone();
+    let timeout_controller = state.processor.timeout.clone();
+    let risk = state.pool_service.risk_controller().clone();
+    let deadline = current_deadline();
     let total_attempts = max_retries + 1;
     let mut attempt = 0u32;
 
     loop {
         attempt += 1;
 
-        let response = match timeout_controller.execute_with_timeout(operation()).await {
+        if deadline.is_some_and(|d| d.is_exceeded()) {
+            return (
+                deadline_exceeded_response(request_id, Some(provider_label)),
+                attempt - 1,
+            );
+        }
+        // 自适应冷却模式下按学习到的限流节奏主动节流（等待超出剩余时间时不等待）
+        if let Some(pacing) = risk
+            .pacing_delay_ms(credential_id)
+            .map(Duration::from_millis)
+        {
+            if retry_fits_deadline(deadline, pacing) {
+                tokio::time::sleep(pacing).await;
+            }
+        }
+        risk.record_request(credential_id);
+        // 单次调用的超时不超过请求剩余时间
+        let call = timeout_controller.execute_with_timeout(operation());
+        let result = match deadline {
+            Some(deadline) => match tokio::time::timeout(deadline.remaining(), call).await {
+                Ok(result) => result,
+                Err(_) => {
+                    return (
+                        deadline_exceeded_response(request_id, Some(provider_label)),
+                        attempt,
+                    )
+                }
+            },
+            None => call.await,
+        };
+
+        let response = match result {
             Ok(resp) => resp,
             Err(timeout_err) => {
-                if attempt <= max_retries {
-                    let delay = retrier.backoff_delay(attempt - 1);
+                let delay = retrier.backoff_delay(attempt.saturating_sub(1));
+                if attempt <= max_retries && retry_fits_deadline(deadline, delay) {
                     state.logs.write().await.add(
                         "warn",
                         &format!(
@@ -214,22 +408,35 @@ where
                     ),
                 );
 
-                return build_error_response_with_meta(
-                    StatusCode::GATEWAY_TIMEOUT.as_u16(),
-                    &format!("Provider request timeout: {}", timeout_err),
-                    Some(request_id),
-                    Some(provider_label),
-                    Some(GatewayErrorCode::UpstreamTimeout),
+                return (
+                    build_error_response_with_meta(
+                        StatusCode::GATEWAY_TIMEOUT.as_u16(),
+                        &format!("Provider request timeout: {}", timeout_err),
+                        Some(request_id),
+                        Some(provider_label),
+                        Some(GatewayErrorCode::UpstreamTimeout),
+                    ),
+                    attempt,
                 );
             }
         };
 
         let status_code = response.status().as_u16();
+        record_rate_limit_outcome(&risk, credential_id, &response);
+        let delay = retrier.backoff_delay(attempt - 1);
         let should_retry = attempt <= max_retries && retrier.config().is_retryable(status_code);
+        if should_retry && !retry_fits_deadline(deadline, delay) {
+            state.logs.write().await.add(
+                "warn",
+                &format!(
+                    "[DEADLINE] request_id={} provider={} attempt={}/{} status={} 剩余时间不足，停止重试",
+                    request_id, provider_label, attempt, total_attempts, status_code
+                ),
+            );
+            return (response, attempt);
+        }
 
         if should_retry {
-            let delay = retrier.backoff_delay(attempt - 1);
-
             if status_code == StatusCode::TOO_MANY_REQUESTS.as_u16() {
                 state.logs.write().await.add(
                     "warn",
@@ -266,8 +473,405 @@ where
             );
         }
 
+        return (response, attempt);
+    }
+}
+
+/// 将上游响应计入凭证的限流风控：429 进入冷却期并学习限流节奏，成功响应重置连续限流计数
+fn record_rate_limit_outcome(risk: &RiskController, credential_id: &str, response: &Response) {
+    let status = response.status();
+    if status.is_success() {
+        risk.record_success(credential_id);
+        return;
+    }
+    if status != StatusCode::TOO_MANY_REQUESTS {
+        return;
+    }
+    let mut event =
+        RateLimitEvent::new(credential_id.to_string()).with_status_code(status.as_u16());
+    if let Some(retry_after) = response
+        .headers()
+        .get(header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(RiskController::parse_retry_after)
+    {
+        event = event.with_retry_after(retry_after);
+    }
+    let cooldown_secs = risk.record_rate_limit(event);
+    tracing::info!(
+        credential_id = %credential_id,
+        cooldown_secs,
+        "[QUOTA] 凭证触发限流，进入冷却期"
+    );
+}
+
+/// 剩余时间是否足够等待退避后再发起一次调用
+fn retry_fits_deadline(deadline: Option<RequestDeadline>, delay: Duration) -> bool {
+    match deadline {
+        Some(deadline) => deadline.allows(delay, Duration::ZERO),
+        None => true,
+    }
+}
+
+/// 请求已超过截止时间时的响应
+fn deadline_exceeded_response(request_id: &str, provider: Option<&str>) -> Response {
+    build_error_response_with_meta(
+        StatusCode::GATEWAY_TIMEOUT.as_u16(),
+        "Request deadline exceeded",
+        Some(request_id),
+        provider,
+        Some(GatewayErrorCode::DeadlineExceeded),
+    )
+}
+
+/// 按故障转移链选择第一个有可用凭证的跳
+///
+/// 返回跳在计划中的下标、跳的 Provider 与凭证。
+async fn resolve_failover_chain_credential(
+    state: &AppState,
+    request_id: &str,
+    plan: &FailoverPlan,
+    model: &str,
+    client_type: &ClientType,
+    tenant: Option<&TenantRuntime>,
+    log_prefix: &str,
+) -> Option<(
+    usize,
+    String,
+    lime_core::models::provider_pool_model::ProviderCredential,
+)> {
+    for (index, planned) in plan.hops.iter().enumerate() {
+        let provider = planned.hop.provider.to_lowercase();
+        if let Ok(Some(cred)) = select_credential_for_request(
+            state,
+            Some(request_id),
+            &provider,
+            model,
+            client_type,
+            None,
+            tenant,
+            log_prefix,
+            true,
+        )
+        .await
+        {
+            return Some((index, provider, cred));
+        }
+    }
+    None
+}
+
+/// 服务请求的上游端点（配置了端点切换时由 Provider 调用写入响应头）
+fn upstream_endpoint(response: &Response) -> Option<&str> {
+    response
+        .headers()
+        .get(UPSTREAM_ENDPOINT_HEADER)
+        .and_then(|v| v.to_str().ok())
+}
+
+/// 按故障转移链依次调用各跳
+///
+/// 从计划中下标为 `start` 的跳（凭证已选好）开始，响应状态满足 [`is_failover_status`]
+/// 时让该跳进入冷却，并为下一跳重新选择凭证。各跳结果写入请求元数据与追踪，
+/// 服务请求的跳通过 `x-lime-failover-hop` 响应头返回。
+///
+/// 返回最终响应与服务请求的 Provider（所有跳均失败时为最后一跳）。
+async fn call_with_failover_chain<F, Fut>(
+    state: &AppState,
+    ctx: &mut RequestContext,
+    plan: &FailoverPlan,
+    start: usize,
+    credential: lime_core::models::provider_pool_model::ProviderCredential,
+    model: &str,
+    client_type: &ClientType,
+    tenant: Option<&TenantRuntime>,
+    is_stream: bool,
+    log_prefix: &str,
+    mut operation: F,
+) -> (Response, String)
+where
+    F: FnMut(lime_core::models::provider_pool_model::ProviderCredential) -> Fut,
+    Fut: Future<Output = Response>,
+{
+    let request_id = ctx.request_id.clone();
+    let event = |planned: &PlannedHop, outcome, attempts, status| FailoverHopEvent {
+        position: planned.position,
+        provider: planned.hop.provider.clone(),
+        outcome,
+        attempts,
+        status,
+        cooling_down: planned.cooling_down,
+    };
+    // 起始跳之前的跳在选择凭证时已确认没有可用凭证
+    let mut events: Vec<FailoverHopEvent> = plan.hops[..start]
+        .iter()
+        .map(|planned| event(planned, FailoverHopOutcome::NoCredential, 0, None))
+        .collect();
+    let mut next_credential = Some(credential);
+    let mut outcome: Option<(Response, String, &PlannedHop)> = None;
+
+    for planned in &plan.hops[start..] {
+        let provider = planned.hop.provider.to_lowercase();
+        // 截止时间已到时不再尝试后续跳，返回上一跳的结果
+        if outcome.is_some() && current_deadline().is_some_and(|d| d.is_exceeded()) {
+            state.logs.write().await.add(
+                "warn",
+                &format!(
+                    "[DEADLINE] request_id={} 截止时间已到，跳过故障转移链第 {}/{} 跳及之后",
+                    request_id, planned.position, plan.chain_len
+                ),
+            );
+            break;
+        }
+        let cred = match next_credential.take() {
+            Some(cred) => cred,
+            None => match select_credential_for_request(
+                state,
+                Some(&request_id),
+                &provider,
+                model,
+                client_type,
+                None,
+                tenant,
+                log_prefix,
+                true,
+            )
+            .await
+            {
+                Ok(Some(cred)) => cred,
+                _ => {
+                    events.push(event(planned, FailoverHopOutcome::NoCredential, 0, None));
+                    continue;
+                }
+            },
+        };
+
+        let provider_label = cred.provider_type.to_string();
+        let max_retries = if is_stream {
+            0
+        } else {
+            planned.hop.max_retries
+        };
+        let (response, attempts) = call_with_provider_retries(
+            state,
+            &request_id,
+            &provider_label,
+            &cred.uuid,
+            max_retries,
+            || operation(cred.clone()),
+        )
+        .await;
+        let status = response.status().as_u16();
+        ctx.set_credential_id(cred.uuid.clone());
+
+        if !is_failover_status(status) {
+            if response.status().is_success() {
+                state.processor.failover_chains.mark_success(plan, planned);
+            }
+            events.push(event(
+                planned,
+                FailoverHopOutcome::Served,
+                attempts,
+                Some(status),
+            ));
+            outcome = Some((response, provider, planned));
+            break;
+        }
+
+        state.processor.failover_chains.mark_failure(plan, planned);
+        events.push(event(
+            planned,
+            FailoverHopOutcome::Failed,
+            attempts,
+            Some(status),
+        ));
+        state.logs.write().await.add(
+            "warn",
+            &format!(
+                "[FAILOVER] request_id={} hop={}/{} provider={} status={} attempts={}",
+                request_id, planned.position, plan.chain_len, provider, status, attempts
+            ),
+        );
+        outcome = Some((response, provider, planned));
+    }
+
+    let served = events
+        .iter()
+        .find(|e| e.outcome == FailoverHopOutcome::Served);
+    let summary = match served {
+        Some(served) => format!(
+            "{} served by hop {}/{}",
+            served.provider, served.position, plan.chain_len
+        ),
+        None => format!("all {} hops failed", plan.chain_len),
+    };
+    if events.len() > 1 || served.is_none() {
+        state.logs.write().await.add(
+            "info",
+            &format!("[FAILOVER] request_id={request_id} {summary}"),
+        );
+    }
+    let record = serde_json::json!({
+        "model_pattern": plan.model_pattern,
+        "hops": events,
+    });
+    ctx.trace_with_data(FAILOVER_METADATA_KEY, summary, record.clone());
+    ctx.set_metadata(FAILOVER_METADATA_KEY, record);
+
+    match outcome {
+        Some((mut response, provider, planned)) => {
+            let value = format!("{}; hop={}/{}", provider, planned.position, plan.chain_len);
+            if let Ok(value) = header::HeaderValue::from_str(&value) {
+                response
+                    .headers_mut()
+                    .insert(header::HeaderName::from_static(FAILOVER_HOP_HEADER), value);
+            }
+            (response, provider)
+        }
+        // 起始跳总会被调用，不会出现；保守起见按无可用凭证处理
+        None => (
+            build_error_response_with_meta(
+                StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+                "No available credentials in failover chain",
+                Some(&request_id),
+                None,
+                Some(GatewayErrorCode::NoCredentials),
+            ),
+            plan.hops[start].hop.provider.to_lowercase(),
+        ),
+    }
+}
+
+/// 检测非流式成功响应是否为空 / 退化响应，是则换用同类型的另一个凭证重试一次
+///
+/// 原响应以 `degenerate` 状态单独写入审计日志；没有可用的替代凭证或重试失败时返回原响应。
+#[allow(clippy::too_many_arguments)]
+async fn retry_degenerate_response<F, Fut>(
+    state: &AppState,
+    ctx: &mut RequestContext,
+    response: Response,
+    format: DegenerateResponseFormat,
+    max_tokens: Option<u32>,
+    model: &str,
+    client_type: &ClientType,
+    tenant: Option<&TenantRuntime>,
+    log_prefix: &str,
+    operation: F,
+) -> Response
+where
+    F: FnOnce(lime_core::models::provider_pool_model::ProviderCredential) -> Fut,
+    Fut: Future<Output = Response>,
+{
+    let detector = &state.degenerate_detector;
+    if !response.status().is_success() || !detector.should_check(max_tokens) {
         return response;
     }
+    let (Some(db), Some(used_credential)) = (state.db.as_ref(), ctx.credential_id.clone()) else {
+        return response;
+    };
+    let Some(used) = state
+        .pool_service
+        .get_by_uuid(db, &used_credential)
+        .ok()
+        .flatten()
+    else {
+        return response;
+    };
+
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, REPLAY_CAPTURE_MAX_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            tracing::warn!(
+                "[DEGENERATE] request_id={} failed to read response body: {}",
+                ctx.request_id,
+                err
+            );
+            return build_error_response_with_meta(
+                StatusCode::BAD_GATEWAY.as_u16(),
+                "Failed to read upstream response",
+                Some(&ctx.request_id),
+                None,
+                Some(GatewayErrorCode::UpstreamError),
+            );
+        }
+    };
+    let original = |parts: axum::http::response::Parts, bytes: axum::body::Bytes| {
+        Response::from_parts(parts, Body::from(bytes))
+    };
+
+    let provider = used.provider_type.to_string();
+    let verdict = serde_json::from_slice::<serde_json::Value>(&bytes)
+        .ok()
+        .and_then(|value| detector.inspect(format, &value));
+    detector.record_checked(&provider, verdict.is_some());
+    let Some(verdict) = verdict else {
+        return original(parts, bytes);
+    };
+
+    let reason = verdict.describe();
+    state
+        .audit_log
+        .record_degenerate(state.db.as_ref(), &state.sanitizer, ctx, &reason);
+    ctx.trace(
+        "degenerate_response",
+        format!("{reason} credential={}", used.uuid),
+    );
+
+    let alternative = state
+        .pool_service
+        .select_credential_with_filter(
+            db,
+            &provider,
+            Some(model),
+            Some(client_type),
+            current_affinity_session().as_deref(),
+            |c| {
+                c.uuid != used.uuid
+                    && tenant.is_none_or(|t| !t.restricts_credentials() || t.allows_credential(c))
+            },
+        )
+        .ok()
+        .flatten();
+    let Some(alternative) = alternative else {
+        state.logs.write().await.add(
+            "warn",
+            &format!(
+                "[{log_prefix}] [DEGENERATE] request_id={} provider={} {}，没有可用于重试的其他凭证",
+                ctx.request_id, provider, reason
+            ),
+        );
+        return original(parts, bytes);
+    };
+
+    state.logs.write().await.add(
+        "warn",
+        &format!(
+            "[{log_prefix}] [DEGENERATE] request_id={} provider={} {}，换用凭证 {} 重试",
+            ctx.request_id, provider, reason, alternative.uuid
+        ),
+    );
+    ctx.set_credential_id(alternative.uuid.clone());
+    ctx.increment_retry();
+    let retried = operation(alternative).await;
+    if !retried.status().is_success() {
+        detector.record_retry(&provider, false);
+        ctx.set_credential_id(used.uuid);
+        return original(parts, bytes);
+    }
+
+    let (retry_parts, retry_body) = retried.into_parts();
+    let Ok(retry_bytes) = to_bytes(retry_body, REPLAY_CAPTURE_MAX_BYTES).await else {
+        detector.record_retry(&provider, false);
+        ctx.set_credential_id(used.uuid);
+        return original(parts, bytes);
+    };
+    let recovered = serde_json::from_slice::<serde_json::Value>(&retry_bytes)
+        .ok()
+        .is_some_and(|value| detector.inspect(format, &value).is_none());
+    detector.record_retry(&provider, recovered);
+    // 重试仍是退化响应时也返回重试结果：两次都退化说明问题在请求本身
+    Response::from_parts(retry_parts, Body::from(retry_bytes))
 }
 
 const REPLAY_CAPTURE_MAX_BYTES: usize = 2 * 1024 * 1024;
@@ -466,7 +1070,7 @@ fn set_request_id_header(response: &mut Response, request_id: &str) {
     if let Ok(value) = header::HeaderValue::from_str(request_id) {
         response
             .headers_mut()
-            .insert(header::HeaderName::from_static("x-lime-request-id"), value);
+            .insert(header::HeaderName::from_static(REQUEST_ID_HEADER), value);
     }
 }
 
@@ -615,7 +1219,11 @@ async fn begin_response_cache(
     has_idempotency_key: bool,
     store: Arc<ResponseCacheStore>,
 ) -> Result<ResponseCacheGuard, Response> {
-    if is_stream || has_idempotency_key || !store.is_enabled() || has_no_cache_header(headers) {
+    if is_stream
+        || has_idempotency_key
+        || !store.is_route_enabled(endpoint)
+        || has_no_cache_header(headers)
+    {
         return Ok(ResponseCacheGuard::disabled(store));
     }
 
@@ -623,11 +1231,7 @@ async fn begin_response_cache(
         return Ok(ResponseCacheGuard::disabled(store));
     }
 
-    let fingerprint = build_request_fingerprint(&serde_json::json!({
-        "endpoint": endpoint,
-        "payload": request_payload
-    }));
-    let key = format!("{endpoint}:{fingerprint}");
+    let key = store.cache_key(endpoint, request_payload);
 
     if let Some(cached) = store.get(&key) {
         let mut response = build_cached_response(cached);
@@ -720,6 +1324,80 @@ async fn finalize_replayable_response(
     }
 }
 
+/// 解析后的 chat completions 请求体：请求、`stream_options`、logprobs 选项
+pub type ChatCompletionBody = (
+    ChatCompletionRequest,
+    Option<StreamOptions>,
+    Option<LogprobsOptions>,
+);
+
+/// 解析 chat completions 请求体，同时提取 `ChatCompletionRequest` 未建模的
+/// `stream_options` 与 logprobs 选项
+pub fn parse_chat_completion_body(raw: serde_json::Value) -> Result<ChatCompletionBody, Response> {
+    let stream_options = StreamOptions::from_request_value(&raw);
+    let logprobs = LogprobsOptions::from_request_value(&raw);
+    match serde_json::from_value::<ChatCompletionRequest>(raw) {
+        Ok(request) => Ok((request, stream_options, logprobs)),
+        Err(e) => Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("Failed to deserialize the JSON body into the target type: {e}"),
+        )
+            .into_response()),
+    }
+}
+
+/// 请求声明 `stream_options.include_usage` 时创建 usage 追踪器
+pub fn stream_usage_tracker(
+    request: &ChatCompletionRequest,
+    stream_options: Option<&StreamOptions>,
+) -> Option<StreamUsageTracker> {
+    (request.stream && stream_options.is_some_and(|o| o.include_usage)).then(|| {
+        StreamUsageTracker::new(
+            request.model.clone(),
+            estimate_token_count_from_json(request),
+        )
+    })
+}
+
+/// 在 SSE 响应末尾补发 usage chunk
+///
+/// 作用于最终返回给客户端的 OpenAI SSE，与具体 Provider / 转换路径无关；
+/// 上游未返回 usage 时按请求体与输出内容估算。
+pub fn attach_stream_usage(response: Response, tracker: Option<StreamUsageTracker>) -> Response {
+    use futures::StreamExt;
+
+    let is_sse = response.status().is_success()
+        && response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"));
+    let Some(mut tracker) = tracker.filter(|_| is_sse) else {
+        return response;
+    };
+
+    let (parts, body) = response.into_parts();
+    let mut source = body.into_data_stream();
+    let stream = async_stream::stream! {
+        while let Some(chunk) = source.next().await {
+            match chunk {
+                Ok(bytes) => {
+                    let output = tracker.process(&bytes);
+                    if !output.is_empty() {
+                        yield Ok(axum::body::Bytes::from(output));
+                    }
+                }
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            }
+        }
+        yield Ok(axum::body::Bytes::from(tracker.finish()));
+    };
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -797,6 +1475,46 @@ mod tests {
         assert!(matches!(store.check(&key), IdempotencyCheck::New));
     }
 
+    #[test]
+    fn context_trim_strategy_should_prefer_header_then_route() {
+        let mut settings = ContextTrimSettings {
+            enabled: true,
+            ..Default::default()
+        };
+        settings.routes.insert(
+            "/v1/messages".to_string(),
+            ContextTrimStrategy::SummarizeOldest,
+        );
+
+        let empty = HeaderMap::new();
+        assert_eq!(
+            resolve_context_trim_strategy(&settings, &empty, "/v1/chat/completions"),
+            Some(ContextTrimStrategy::DropOldest)
+        );
+        assert_eq!(
+            resolve_context_trim_strategy(&settings, &empty, "/v1/messages"),
+            Some(ContextTrimStrategy::SummarizeOldest)
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTEXT_TRIM_HEADER, "drop-middle".parse().unwrap());
+        assert_eq!(
+            resolve_context_trim_strategy(&settings, &headers, "/v1/messages"),
+            Some(ContextTrimStrategy::DropMiddle)
+        );
+        headers.insert(CONTEXT_TRIM_HEADER, "off".parse().unwrap());
+        assert_eq!(
+            resolve_context_trim_strategy(&settings, &headers, "/v1/messages"),
+            None
+        );
+
+        settings.enabled = false;
+        assert_eq!(
+            resolve_context_trim_strategy(&settings, &empty, "/v1/messages"),
+            None
+        );
+    }
+
     #[test]
     fn idempotency_guard_drop_should_remove_inflight() {
         let store = create_store();
@@ -972,6 +1690,7 @@ mod tests {
             max_entries: 200,
             max_body_bytes: 1_048_576,
             cacheable_status_codes: vec![200, 201],
+            ..Default::default()
         }));
         let cache_key = "cache-hit-201-custom".to_string();
 
@@ -1087,6 +1806,48 @@ mod tests {
             .iter()
             .any(|reason| matches!(reason, CapabilityMismatchReason::ContextTooSmall { .. })));
     }
+
+    #[test]
+    fn select_context_upgrade_should_pick_first_sibling_with_enough_context() {
+        let mut settings = ContextUpgradeSettings {
+            enabled: true,
+            siblings: HashMap::from([(
+                "gpt-4o-mini".to_string(),
+                vec!["gpt-4o-small".to_string(), "gpt-4o".to_string()],
+            )]),
+        };
+        let context_of = |model: &str| match model {
+            "gpt-4o-mini" => Some(16_000),
+            "gpt-4o-small" => Some(32_000),
+            "gpt-4o" => Some(128_000),
+            _ => None,
+        };
+
+        let upgrade = select_context_upgrade(&settings, "GPT-4o-mini", 40_000, context_of);
+        assert_eq!(
+            upgrade,
+            Some(ContextUpgrade {
+                model: "gpt-4o".to_string(),
+                from_context: 16_000,
+                to_context: 128_000,
+                required: 44_000,
+            })
+        );
+        assert_eq!(
+            select_context_upgrade(&settings, "gpt-4o-mini", 10_000, context_of),
+            None
+        );
+        assert_eq!(
+            select_context_upgrade(&settings, "gpt-4o-mini", 200_000, context_of),
+            None
+        );
+
+        settings.enabled = false;
+        assert_eq!(
+            select_context_upgrade(&settings, "gpt-4o-mini", 40_000, context_of),
+            None
+        );
+    }
 }
 
 // ============================================================================
@@ -1094,7 +1855,7 @@ mod tests {
 // ============================================================================
 
 /// 根据客户端类型和端点配置选择 Provider
-async fn select_provider_for_client(headers: &HeaderMap, state: &AppState) -> (String, ClientType) {
+pub(crate) async fn select_provider_for_client(headers: &HeaderMap, state: &AppState) -> (String, ClientType) {
     let user_agent = headers
         .get("user-agent")
         .and_then(|v| v.to_str().ok())
@@ -1301,6 +2062,276 @@ fn resolve_model_capability_snapshot(state: &AppState, model: &str) -> ModelCapa
     snapshot
 }
 
+/// 会话级上下文修剪策略请求头（策略名，或 `off` 关闭）
+const CONTEXT_TRIM_HEADER: &str = "x-lime-context-trim";
+
+/// 上下文修剪报告在请求元数据中的键
+pub const CONTEXT_TRIM_METADATA_KEY: &str = "context_trim";
+
+/// 解析本次请求使用的上下文修剪策略
+///
+/// 优先级：请求头 > 路由配置 > 默认策略；请求头可在全局未启用时单独开启修剪。
+fn resolve_context_trim_strategy(
+    settings: &ContextTrimSettings,
+    headers: &HeaderMap,
+    route: &str,
+) -> Option<ContextTrimStrategy> {
+    if let Some(value) = headers
+        .get(CONTEXT_TRIM_HEADER)
+        .and_then(|v| v.to_str().ok())
+    {
+        if matches!(value.trim().to_ascii_lowercase().as_str(), "off" | "none") {
+            return None;
+        }
+        match value.parse::<ContextTrimStrategy>() {
+            Ok(strategy) => return Some(strategy),
+            Err(e) => tracing::warn!("[CONTEXT_TRIM] 忽略无效的 {}: {}", CONTEXT_TRIM_HEADER, e),
+        }
+    }
+
+    if !settings.enabled {
+        return None;
+    }
+    Some(
+        settings
+            .routes
+            .get(route)
+            .copied()
+            .unwrap_or(settings.strategy),
+    )
+}
+
+/// 请求超出目标模型上下文窗口时按策略修剪消息
+///
+/// `fixed_tokens` 为消息以外不可修剪部分（system、tools）的估算 token 数。
+/// 发生修剪时返回修剪后的消息与被移除消息的摘录说明（需由调用方放入 system 提示词），
+/// 并将修剪报告写入请求元数据。
+async fn apply_context_trim(
+    state: &AppState,
+    ctx: &mut RequestContext,
+    headers: &HeaderMap,
+    route: &str,
+    messages: Vec<serde_json::Value>,
+    fixed_tokens: usize,
+    max_output_tokens: Option<u32>,
+) -> Option<(Vec<serde_json::Value>, Option<String>)> {
+    let strategy = resolve_context_trim_strategy(&state.context_trim, headers, route)?;
+    let model = ctx.resolved_model.clone();
+    let context_length = resolve_model_capability_snapshot(state, &model).context_length?;
+    let reserve = max_output_tokens.unwrap_or(state.context_trim.reserve_output_tokens);
+    let budget = (context_length.saturating_sub(reserve) as usize).saturating_sub(fixed_tokens);
+
+    // Token 计数与摘要对长对话是 CPU 密集型操作，放到 CPU 线程池执行
+    let trimmer = state.processor.context_trimmer.clone();
+    let outcome = match run_cpu_task(
+        CpuTaskPriority::Normal,
+        CpuTaskKind::TokenCount,
+        move || trimmer.trim(messages, budget, strategy),
+    )
+    .await
+    {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            tracing::warn!("[CONTEXT_TRIM] 跳过上下文修剪: {}", e);
+            return None;
+        }
+    };
+    let report = outcome.report?;
+
+    state.logs.write().await.add(
+        if report.still_over_budget {
+            "warn"
+        } else {
+            "info"
+        },
+        &format!(
+            "[CONTEXT_TRIM] request_id={} model={} strategy={} removed={} summarized={} tokens={}->{} budget={}",
+            ctx.request_id,
+            model,
+            report.strategy.as_str(),
+            report.removed_count,
+            report.summarized_count,
+            report.tokens_before,
+            report.tokens_after,
+            report.budget_tokens
+        ),
+    );
+    ctx.set_metadata(
+        CONTEXT_TRIM_METADATA_KEY,
+        serde_json::to_value(&report).unwrap_or_default(),
+    );
+    Some((outcome.messages, outcome.summary))
+}
+
+/// 命中的虚拟模型记录在请求元数据中的键
+pub const VIRTUAL_MODEL_METADATA_KEY: &str = "virtual_model";
+
+/// 按名称查询已启用的虚拟模型
+pub(crate) fn load_virtual_model(state: &AppState, name: &str) -> Option<VirtualModel> {
+    let db = state.db.as_ref()?;
+    let conn = lime_core::database::lock_db(db).ok()?;
+    lime_core::database::dao::virtual_model::VirtualModelDao::get_enabled(&conn, name).ok()?
+}
+
+/// 解析虚拟模型
+///
+/// 请求模型命中虚拟模型时改写为目标模型、补齐预设参数，并返回虚拟模型指定的 Provider。
+async fn apply_virtual_model<T>(
+    state: &AppState,
+    ctx: &mut RequestContext,
+    request: &mut T,
+    model: &str,
+    apply_defaults: fn(&VirtualModel, &mut serde_json::Value),
+) -> Option<String>
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    let virtual_model = load_virtual_model(state, model)?;
+    let mut payload = serde_json::to_value(&*request).ok()?;
+    apply_defaults(&virtual_model, &mut payload);
+    payload["model"] = serde_json::Value::String(virtual_model.model.clone());
+    match serde_json::from_value(payload) {
+        Ok(updated) => *request = updated,
+        Err(e) => {
+            tracing::warn!(
+                "[VIRTUAL_MODEL] 应用虚拟模型 {} 失败: {}",
+                virtual_model.name,
+                e
+            );
+            return None;
+        }
+    }
+
+    state.logs.write().await.add(
+        "info",
+        &format!(
+            "[VIRTUAL_MODEL] request_id={} {} -> model={} provider={}",
+            ctx.request_id,
+            virtual_model.name,
+            virtual_model.model,
+            virtual_model.provider.as_deref().unwrap_or("-")
+        ),
+    );
+    ctx.set_metadata(
+        VIRTUAL_MODEL_METADATA_KEY,
+        serde_json::json!(virtual_model.name),
+    );
+    ctx.set_resolved_model(virtual_model.model.clone());
+    virtual_model.provider.map(|p| p.to_lowercase())
+}
+
+/// 模型自动升级记录在请求元数据中的键
+pub const MODEL_UPGRADE_METADATA_KEY: &str = "model_upgrade";
+
+/// 模型自动升级响应头（`原模型 -> 新模型`）
+const MODEL_UPGRADE_HEADER: &str = "x-lime-model-upgrade";
+
+/// 选定的升级目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ContextUpgrade {
+    model: String,
+    from_context: u32,
+    to_context: u32,
+    required: u32,
+}
+
+/// 请求超出当前模型上下文窗口时，选择第一个窗口足够的同系列模型
+///
+/// 当前模型窗口未知或已足够时不升级；余量与能力过滤保持一致（估算值的 1.1 倍）。
+fn select_context_upgrade(
+    settings: &ContextUpgradeSettings,
+    model: &str,
+    estimated_total_tokens: u32,
+    context_of: impl Fn(&str) -> Option<u32>,
+) -> Option<ContextUpgrade> {
+    if !settings.enabled {
+        return None;
+    }
+    let required = ((estimated_total_tokens as f64) * 1.1).ceil() as u32;
+    let from_context = context_of(model)?;
+    if from_context >= required {
+        return None;
+    }
+    settings.candidates(model).iter().find_map(|candidate| {
+        let to_context = context_of(candidate)?;
+        (to_context >= required).then(|| ContextUpgrade {
+            model: candidate.clone(),
+            from_context,
+            to_context,
+            required,
+        })
+    })
+}
+
+/// 按上下文窗口自动升级模型
+///
+/// 发生升级时改写 `model`，并将替换记录写入请求元数据与追踪。
+async fn apply_context_upgrade(
+    state: &AppState,
+    ctx: &mut RequestContext,
+    model: &mut String,
+    estimated_total_tokens: Option<u32>,
+) {
+    let Some(estimated_total_tokens) = estimated_total_tokens else {
+        return;
+    };
+    let Some(upgrade) =
+        select_context_upgrade(&state.context_upgrade, model, estimated_total_tokens, |m| {
+            resolve_model_capability_snapshot(state, m).context_length
+        })
+    else {
+        return;
+    };
+
+    let record = serde_json::json!({
+        "from": model.as_str(),
+        "to": upgrade.model,
+        "estimated_tokens": estimated_total_tokens,
+        "required_tokens": upgrade.required,
+        "from_context": upgrade.from_context,
+        "to_context": upgrade.to_context,
+    });
+    state.logs.write().await.add(
+        "info",
+        &format!(
+            "[MODEL_UPGRADE] request_id={} {} -> {} required={} context={}->{}",
+            ctx.request_id,
+            model,
+            upgrade.model,
+            upgrade.required,
+            upgrade.from_context,
+            upgrade.to_context
+        ),
+    );
+    ctx.trace_with_data(
+        MODEL_UPGRADE_METADATA_KEY,
+        format!("{} -> {}", model, upgrade.model),
+        record.clone(),
+    );
+    ctx.set_metadata(MODEL_UPGRADE_METADATA_KEY, record);
+    ctx.set_resolved_model(upgrade.model.clone());
+    *model = upgrade.model;
+}
+
+/// 发生模型自动升级时附加 `x-lime-model-upgrade` 响应头
+fn attach_model_upgrade_header(ctx: &RequestContext, mut response: Response) -> Response {
+    let Some(record) = ctx.get_metadata(MODEL_UPGRADE_METADATA_KEY) else {
+        return response;
+    };
+    let (Some(from), Some(to)) = (
+        record.get("from").and_then(|v| v.as_str()),
+        record.get("to").and_then(|v| v.as_str()),
+    ) else {
+        return response;
+    };
+    if let Ok(value) = header::HeaderValue::from_str(&format!("{from} -> {to}")) {
+        response
+            .headers_mut()
+            .insert(header::HeaderName::from_static(MODEL_UPGRADE_HEADER), value);
+    }
+    response
+}
+
 fn model_meets_capability_requirements(
     snapshot: &ModelCapabilitySnapshot,
     requirements: &CapabilityRequirements,
@@ -1468,6 +2499,7 @@ async fn resolve_openai_credential_with_capability_fallback(
     selected_provider: &str,
     client_type: &ClientType,
     explicit_provider_id: Option<&str>,
+    tenant: Option<&TenantRuntime>,
     request: &mut ChatCompletionRequest,
 ) -> Result<
     (
@@ -1484,6 +2516,7 @@ async fn resolve_openai_credential_with_capability_fallback(
             &request.model,
             client_type,
             explicit_provider_id,
+            tenant,
             "CHAT_COMPLETIONS",
             true,
         )
@@ -1505,6 +2538,7 @@ async fn resolve_openai_credential_with_capability_fallback(
             &candidate_model,
             client_type,
             None,
+            tenant,
             "CHAT_COMPLETIONS",
             true,
         )
@@ -1612,6 +2646,7 @@ async fn resolve_anthropic_credential_with_capability_fallback(
     selected_provider: &str,
     client_type: &ClientType,
     explicit_provider_id: Option<&str>,
+    tenant: Option<&TenantRuntime>,
     request: &mut AnthropicMessagesRequest,
 ) -> Result<
     (
@@ -1628,6 +2663,7 @@ async fn resolve_anthropic_credential_with_capability_fallback(
             &request.model,
             client_type,
             explicit_provider_id,
+            tenant,
             "ANTHROPIC_MESSAGES",
             false,
         )
@@ -1654,6 +2690,7 @@ async fn resolve_anthropic_credential_with_capability_fallback(
             &candidate_model,
             client_type,
             None,
+            tenant,
             "ANTHROPIC_MESSAGES",
             false,
         )
@@ -1755,20 +2792,153 @@ pub async fn verify_api_key_anthropic(
         }
     };
 
-    if key != expected_key {
-        let body = build_gateway_error_json(
-            StatusCode::UNAUTHORIZED.as_u16(),
-            "Invalid API key",
-            None,
-            None,
-            Some(GatewayErrorCode::AuthenticationFailed),
+    if key != expected_key {
+        let body = build_gateway_error_json(
+            StatusCode::UNAUTHORIZED.as_u16(),
+            "Invalid API key",
+            None,
+            None,
+            Some(GatewayErrorCode::AuthenticationFailed),
+        );
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(serde_json::json!({ "type": "error", "error": body["error"].clone() })),
+        ));
+    }
+
+    Ok(())
+}
+
+/// 从请求头提取客户端提交的 API Key
+///
+/// `prefer_x_api_key` 为 true 时优先读取 x-api-key（Anthropic 风格）。
+fn extract_request_api_key(headers: &HeaderMap, prefer_x_api_key: bool) -> Option<&str> {
+    let (first, second) = if prefer_x_api_key {
+        ("x-api-key", "authorization")
+    } else {
+        ("authorization", "x-api-key")
+    };
+    let raw = headers
+        .get(first)
+        .or_else(|| headers.get(second))
+        .and_then(|v| v.to_str().ok())?;
+    Some(raw.strip_prefix("Bearer ").unwrap_or(raw))
+}
+
+/// 认证请求并解析所属租户
+///
+/// - 服务器主 API Key：认证通过，不归属任何租户
+/// - 租户 API Key（已启用租户隔离）：认证通过，返回租户
+/// - 其他情况：返回原始认证错误
+pub(crate) async fn authenticate_request(
+    state: &AppState,
+    headers: &HeaderMap,
+    anthropic_format: bool,
+) -> Result<Option<Arc<TenantRuntime>>, (StatusCode, Json<serde_json::Value>)> {
+    let result = if anthropic_format {
+        verify_api_key_anthropic(headers, &state.api_key).await
+    } else {
+        verify_api_key(headers, &state.api_key).await
+    };
+
+    match result {
+        Ok(()) => Ok(None),
+        Err(err) => extract_request_api_key(headers, anthropic_format)
+            .and_then(|key| state.tenant_registry.resolve_api_key(key))
+            .map(Some)
+            .ok_or(err),
+    }
+}
+
+/// 构建 429 速率限制响应（附带 Retry-After）
+fn build_rate_limited_response(retry_after: std::time::Duration) -> Response {
+    let response = build_error_response_with_meta(
+        StatusCode::TOO_MANY_REQUESTS.as_u16(),
+        &format!(
+            "Rate limited. Retry after {} seconds",
+            retry_after.as_secs()
+        ),
+        None,
+        None,
+        Some(GatewayErrorCode::RateLimited),
+    );
+    let (mut parts, body) = response.into_parts();
+    parts.headers.insert(
+        header::RETRY_AFTER,
+        header::HeaderValue::from_str(&retry_after.as_secs().to_string())
+            .unwrap_or_else(|_| header::HeaderValue::from_static("60")),
+    );
+    Response::from_parts(parts, body)
+}
+
+/// 检查全局与租户级速率限制（Realtime 连接升级时同样使用）
+pub(crate) fn check_request_rate_limit(
+    state: &AppState,
+    headers: &HeaderMap,
+    tenant: Option<&TenantRuntime>,
+) -> Result<(), Response> {
+    if let Some(ref limiter) = state.rate_limiter {
+        let client_key = headers
+            .get("x-api-key")
+            .or_else(|| headers.get("authorization"))
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("anonymous");
+        if let crate::middleware::rate_limit::RateLimitResult::Limited { retry_after } =
+            limiter.check_rate_limit(client_key)
+        {
+            return Err(build_rate_limited_response(retry_after));
+        }
+    }
+
+    if let Some(tenant) = tenant {
+        if let crate::middleware::rate_limit::RateLimitResult::Limited { retry_after } =
+            tenant.check_rate_limit()
+        {
+            return Err(build_rate_limited_response(retry_after));
+        }
+    }
+
+    Ok(())
+}
+
+/// 租户限定了凭证子集却没有选到凭证时的错误响应
+///
+/// 必须在进入旧版 Kiro 兜底路径之前返回，避免租户越过自己的凭证子集。
+fn build_tenant_no_credentials_response(tenant: &TenantRuntime, request_id: &str) -> Response {
+    build_error_response_with_meta(
+        StatusCode::SERVICE_UNAVAILABLE.as_u16(),
+        &format!("No available credentials for tenant '{}'", tenant.id()),
+        Some(request_id),
+        None,
+        Some(GatewayErrorCode::NoCredentials),
+    )
+}
+
+/// 月度费用上限检查，通过时在上下文中记录计费 Provider
+fn check_cost_cap(
+    state: &AppState,
+    ctx: &mut RequestContext,
+    provider: &str,
+) -> Result<(), Response> {
+    if let Err(violation) = state.cost_cap_guard.check(provider) {
+        record_request_telemetry(
+            state,
+            ctx,
+            lime_infra::telemetry::RequestStatus::Failed,
+            Some(violation.message()),
         );
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(serde_json::json!({ "type": "error", "error": body["error"].clone() })),
+        return Err(build_error_response_with_meta(
+            StatusCode::PAYMENT_REQUIRED.as_u16(),
+            &violation.message(),
+            Some(&ctx.request_id),
+            Some(provider),
+            Some(GatewayErrorCode::CostCapExceeded),
         ));
     }
-
+    ctx.set_metadata(
+        COST_PROVIDER_METADATA_KEY,
+        serde_json::Value::String(provider.to_string()),
+    );
     Ok(())
 }
 
@@ -1784,50 +2954,56 @@ pub async fn chat_completions(
     eprintln!("[CHAT_COMPLETIONS] 流式: {}", request.stream);
     eprintln!("[CHAT_COMPLETIONS] 消息数量: {}", request.messages.len());
 
-    if let Err(e) = verify_api_key(&headers, &state.api_key).await {
-        eprintln!("[CHAT_COMPLETIONS] 认证失败!");
-        state
-            .logs
-            .write()
-            .await
-            .add("warn", "Unauthorized request to /v1/chat/completions");
-        return e.into_response();
-    }
+    let tenant = match authenticate_request(&state, &headers, false).await {
+        Ok(tenant) => tenant,
+        Err(e) => {
+            eprintln!("[CHAT_COMPLETIONS] 认证失败!");
+            state
+                .logs
+                .write()
+                .await
+                .add("warn", "Unauthorized request to /v1/chat/completions");
+            return e.into_response();
+        }
+    };
     eprintln!("[CHAT_COMPLETIONS] 认证成功");
 
-    // 速率限制检查
-    if let Some(ref limiter) = state.rate_limiter {
-        let client_key = headers
-            .get("x-api-key")
-            .or_else(|| headers.get("authorization"))
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("anonymous");
-        if let crate::middleware::rate_limit::RateLimitResult::Limited { retry_after } =
-            limiter.check_rate_limit(client_key)
-        {
-            let response = build_error_response_with_meta(
-                StatusCode::TOO_MANY_REQUESTS.as_u16(),
-                &format!(
-                    "Rate limited. Retry after {} seconds",
-                    retry_after.as_secs()
-                ),
-                None,
-                None,
-                Some(GatewayErrorCode::RateLimited),
-            );
-            let (mut parts, body) = response.into_parts();
-            parts.headers.insert(
-                header::RETRY_AFTER,
-                header::HeaderValue::from_str(&retry_after.as_secs().to_string())
-                    .unwrap_or_else(|_| header::HeaderValue::from_static("60")),
-            );
-            return Response::from_parts(parts, body);
-        }
+    // 速率限制检查（全局 + 租户级）
+    if let Err(response) = check_request_rate_limit(&state, &headers, tenant.as_deref()) {
+        return response;
     }
 
     // 创建请求上下文
     let mut ctx = RequestContext::new(request.model.clone()).with_stream(request.stream);
     eprintln!("[CHAT_COMPLETIONS] 请求ID: {}", ctx.request_id);
+    ctx.trace_with_data(
+        "received",
+        "POST /v1/chat/completions",
+        serde_json::json!({
+            "model": request.model,
+            "stream": request.stream,
+            "messages": request.messages.len(),
+            "tools": request.tools.as_ref().map_or(0, |tools| tools.len()),
+        }),
+    );
+    if state.include_request_body {
+        ctx.trace_with_data(
+            lime_infra::telemetry::REQUEST_BODY_TRACE_STAGE,
+            "request body",
+            serde_json::to_value(&request).unwrap_or_default(),
+        );
+    }
+    state.audit_log.capture_request_body(&mut ctx, &request);
+    if let Some(tenant) = &tenant {
+        ctx.set_metadata(TENANT_METADATA_KEY, serde_json::json!(tenant.id()));
+    }
+    if let Some(session_id) = headers
+        .get(SESSION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|s| !s.is_empty())
+    {
+        ctx.set_metadata(COST_SESSION_METADATA_KEY, serde_json::json!(session_id));
+    }
 
     // 幂等性检查（仅非流式）
     let idempotency_key = headers
@@ -1877,6 +3053,17 @@ pub async fn chat_completions(
         ),
     );
 
+    // 虚拟模型：改写为目标模型并补齐预设参数
+    let requested_model = request.model.clone();
+    let virtual_provider = apply_virtual_model(
+        &state,
+        &mut ctx,
+        &mut request,
+        &requested_model,
+        VirtualModel::apply_openai_defaults,
+    )
+    .await;
+
     // 使用 RequestProcessor 解析模型别名
     eprintln!("[CHAT_COMPLETIONS] 开始模型别名解析...");
     let resolved_model = state.processor.resolve_model(&request.model).await;
@@ -1966,6 +3153,43 @@ pub async fn chat_completions(
         }
     }
 
+    // 上下文窗口不足时自动升级到同系列大窗口模型
+    {
+        let estimated_total_tokens =
+            build_openai_capability_requirements(&request).estimated_total_tokens;
+        apply_context_upgrade(&state, &mut ctx, &mut request.model, estimated_total_tokens).await;
+    }
+
+    // 上下文窗口修剪
+    {
+        let messages_json: Vec<serde_json::Value> = serde_json::to_value(&request.messages)
+            .ok()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        let fixed_tokens = state
+            .processor
+            .context_trimmer
+            .count_text(&serde_json::to_string(&request.tools).unwrap_or_default());
+        if let Some((mut trimmed, summary)) = apply_context_trim(
+            &state,
+            &mut ctx,
+            &headers,
+            "/v1/chat/completions",
+            messages_json,
+            fixed_tokens,
+            request.max_tokens,
+        )
+        .await
+        {
+            if let Some(summary) = summary {
+                context_trimmer::insert_summary_system_message(&mut trimmed, &summary);
+            }
+            if let Ok(trimmed_msgs) = serde_json::from_value(serde_json::Value::Array(trimmed)) {
+                request.messages = trimmed_msgs;
+            }
+        }
+    }
+
     // 根据客户端类型选择 Provider
     // **Validates: Requirements 3.1, 3.3, 3.4**
     let (selected_provider, client_type) = select_provider_for_client(&headers, &state).await;
@@ -1984,28 +3208,77 @@ pub async fn chat_completions(
     let provider_id_header = headers
         .get("x-provider-id")
         .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_lowercase());
+        .map(|s| s.to_lowercase())
+        .or(virtual_provider);
 
     // 尝试选择凭证（含能力感知 + 跨 Provider 回退）：
     // 1) X-Provider-Id 指定时仅走精确匹配（不降级）
-    // 2) 否则先按 provider 链路做能力过滤，再选择可用凭证
+    // 2) 模型匹配配置的故障转移链时，按链的顺序选择第一个有可用凭证的跳
+    // 3) 否则先按 provider 链路做能力过滤，再选择可用凭证
     eprintln!("[CHAT_COMPLETIONS] 开始选择凭证...");
-    let (effective_provider, credential) = match resolve_openai_credential_with_capability_fallback(
-        &state,
-        &ctx.request_id,
-        &selected_provider,
-        &client_type,
-        provider_id_header.as_deref(),
-        &mut request,
-    )
-    .await
-    {
-        Ok(result) => result,
-        Err(resp) => return resp,
+    let failover_plan = provider_id_header
+        .is_none()
+        .then(|| state.processor.failover_chains.plan(&request.model))
+        .flatten();
+    let mut failover_start = 0;
+    let (effective_provider, credential) = if let Some(plan) = &failover_plan {
+        match resolve_failover_chain_credential(
+            &state,
+            &ctx.request_id,
+            plan,
+            &request.model,
+            &client_type,
+            tenant.as_deref(),
+            "CHAT_COMPLETIONS",
+        )
+        .await
+        {
+            Some((index, provider, cred)) => {
+                failover_start = index;
+                (provider, Some(cred))
+            }
+            None => (selected_provider.clone(), None),
+        }
+    } else {
+        match resolve_openai_credential_with_capability_fallback(
+            &state,
+            &ctx.request_id,
+            &selected_provider,
+            &client_type,
+            provider_id_header.as_deref(),
+            tenant.as_deref(),
+            &mut request,
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(resp) => return resp,
+        }
     };
     if ctx.resolved_model != request.model {
         ctx.set_resolved_model(request.model.clone());
     }
+    if let Some(cred) = &credential {
+        ctx.set_credential_id(cred.uuid.clone());
+    }
+    if let Some(tenant) = tenant.as_deref() {
+        if credential.is_none() && tenant.restricts_credentials() {
+            record_request_telemetry(
+                &state,
+                &ctx,
+                lime_infra::telemetry::RequestStatus::Failed,
+                Some("tenant has no available credentials".to_string()),
+            );
+            return build_tenant_no_credentials_response(tenant, &ctx.request_id);
+        }
+    }
+    let cost_provider = credential
+        .as_ref()
+        .map(|cred| cred.provider_type.to_string())
+        .unwrap_or_else(|| effective_provider.to_string());
+    if let Err(resp) = check_cost_cap(&state, &mut ctx, &cost_provider) {
+        return resp;
+    }
 
     // 记录路由结果（使用最终 provider/model）
     state.logs.write().await.add(
@@ -2015,9 +3288,23 @@ pub async fn chat_completions(
             ctx.request_id, ctx.resolved_model, effective_provider, selected_provider
         ),
     );
+    ctx.trace_with_data(
+        "route",
+        format!("{selected_provider} -> {effective_provider}"),
+        serde_json::json!({
+            "client_type": client_type.to_string(),
+            "original_model": ctx.original_model,
+            "model": ctx.resolved_model,
+            "credential": credential.as_ref().map(|cred| cred.uuid.clone()),
+        }),
+    );
 
     if !request.stream {
-        let request_payload = serde_json::to_value(&request).unwrap_or_default();
+        let mut request_payload = serde_json::to_value(&request).unwrap_or_default();
+        // logprobs 不在 ChatCompletionRequest 中，需计入缓存键
+        if let Some(options) = logprobs::current_request_logprobs() {
+            options.apply_to_payload(&mut request_payload);
+        }
         match begin_response_cache(
             &ctx.request_id,
             "chat_completions",
@@ -2031,11 +3318,14 @@ pub async fn chat_completions(
         {
             Ok(guard) => cache_guard = guard,
             Err(resp) => {
-                return attach_route_debug_headers(
-                    resp,
-                    &selected_provider,
-                    &effective_provider,
-                    &ctx.resolved_model,
+                return attach_model_upgrade_header(
+                    &ctx,
+                    attach_route_debug_headers(
+                        resp,
+                        &selected_provider,
+                        &effective_provider,
+                        &ctx.resolved_model,
+                    ),
                 );
             }
         }
@@ -2051,11 +3341,14 @@ pub async fn chat_completions(
         {
             Ok(guard) => dedup_guard = guard,
             Err(resp) => {
-                return attach_route_debug_headers(
-                    resp,
-                    &selected_provider,
-                    &effective_provider,
-                    &ctx.resolved_model,
+                return attach_model_upgrade_header(
+                    &ctx,
+                    attach_route_debug_headers(
+                        resp,
+                        &selected_provider,
+                        &effective_provider,
+                        &ctx.resolved_model,
+                    ),
                 );
             }
         }
@@ -2103,43 +3396,99 @@ pub async fn chat_completions(
 
         eprintln!("[CHAT_COMPLETIONS] 调用 Provider: {}", cred.provider_type);
         let provider_label = cred.provider_type.to_string();
-        let response = call_with_single_provider_resilience(
-            &state,
-            &ctx.request_id,
-            &provider_label,
-            request.stream,
-            || async { call_provider_openai(&state, &cred, &request, None).await },
-        )
-        .await;
+        let (response, effective_provider) = match &failover_plan {
+            Some(plan) => {
+                let (state_ref, request_ref) = (&state, &request);
+                call_with_failover_chain(
+                    &state,
+                    &mut ctx,
+                    plan,
+                    failover_start,
+                    cred,
+                    &request.model,
+                    &client_type,
+                    tenant.as_deref(),
+                    request.stream,
+                    "CHAT_COMPLETIONS",
+                    |cred| async move {
+                        call_provider_openai(state_ref, &cred, request_ref, None).await
+                    },
+                )
+                .await
+            }
+            None => (
+                call_with_single_provider_resilience(
+                    &state,
+                    &ctx.request_id,
+                    &provider_label,
+                    &cred.uuid,
+                    request.stream,
+                    || async { call_provider_openai(&state, &cred, &request, None).await },
+                )
+                .await,
+                effective_provider,
+            ),
+        };
         eprintln!(
             "[CHAT_COMPLETIONS] Provider 响应状态: {}",
             response.status()
         );
+        let response = if request.stream {
+            response
+        } else {
+            let (state_ref, request_ref) = (&state, &request);
+            retry_degenerate_response(
+                &state,
+                &mut ctx,
+                response,
+                DegenerateResponseFormat::OpenAi,
+                request.max_tokens,
+                &request.model,
+                &client_type,
+                tenant.as_deref(),
+                "CHAT_COMPLETIONS",
+                |cred| async move {
+                    call_provider_openai(state_ref, &cred, request_ref, None).await
+                },
+            )
+            .await
+        };
 
         // 记录请求统计
         let is_success = response.status().is_success();
-        let _status_code = response.status().as_u16();
+        let status_code = response.status().as_u16();
         let status = if is_success {
             lime_infra::telemetry::RequestStatus::Success
         } else {
             lime_infra::telemetry::RequestStatus::Failed
         };
+        ctx.trace_with_data(
+            "provider_response",
+            format!("HTTP {status_code}"),
+            serde_json::json!({
+                "provider": provider_label,
+                "endpoint": upstream_endpoint(&response),
+            }),
+        );
         record_request_telemetry(&state, &ctx, status, None);
 
         // 如果成功且需要 Flow 捕获，提取响应体内容和响应头
         // 注意：非流式响应需要读取 body，所以必须在这里处理
-        return attach_route_debug_headers(
-            finalize_replayable_response(
-                response,
-                &mut idempotency_guard,
-                &mut dedup_guard,
-                &mut cache_guard,
-                &ctx.request_id,
-            )
-            .await,
-            &selected_provider,
-            &effective_provider,
-            &ctx.resolved_model,
+        return attach_model_upgrade_header(
+            &ctx,
+            attach_route_debug_headers(
+                finalize_replayable_response(
+                    response,
+                    &mut idempotency_guard,
+                    &mut dedup_guard,
+                    &mut cache_guard,
+                    &ctx.request_id,
+                )
+                .await,
+                &selected_provider,
+                &effective_provider,
+                &ctx.resolved_model,
+            ),
         );
     }
 
@@ -2310,18 +3659,21 @@ pub async fn chat_completions(
                         // 完成 Flow 捕获并检查响应拦截
                         // **Validates: Requirements 2.1, 2.5**
                         let response = Json(response).into_response();
-                        return attach_route_debug_headers(
-                            finalize_replayable_response(
-                                response,
-                                &mut idempotency_guard,
-                                &mut dedup_guard,
-                                &mut cache_guard,
-                                &ctx.request_id,
-                            )
-                            .await,
-                            &selected_provider,
-                            &effective_provider,
-                            &ctx.resolved_model,
+                        return attach_model_upgrade_header(
+                            &ctx,
+                            attach_route_debug_headers(
+                                finalize_replayable_response(
+                                    response,
+                                    &mut idempotency_guard,
+                                    &mut dedup_guard,
+                                    &mut cache_guard,
+                                    &ctx.request_id,
+                                )
+                                .await,
+                                &selected_provider,
+                                &effective_provider,
+                                &ctx.resolved_model,
+                            ),
                         );
                     }
                     Err(e) => {
@@ -2423,18 +3775,21 @@ pub async fn chat_completions(
                                             // 完成 Flow 捕获并检查响应拦截（重试成功）
                                             // **Validates: Requirements 2.1, 2.5**
                                             let response = Json(response).into_response();
-                                            return attach_route_debug_headers(
-                                                finalize_replayable_response(
-                                                    response,
-                                                    &mut idempotency_guard,
-                                                    &mut dedup_guard,
-                                                    &mut cache_guard,
-                                                    &ctx.request_id,
-                                                )
-                                                .await,
-                                                &selected_provider,
-                                                &effective_provider,
-                                                &ctx.resolved_model,
+                                            return attach_model_upgrade_header(
+                                                &ctx,
+                                                attach_route_debug_headers(
+                                                    finalize_replayable_response(
+                                                        response,
+                                                        &mut idempotency_guard,
+                                                        &mut dedup_guard,
+                                                        &mut cache_guard,
+                                                        &ctx.request_id,
+                                                    )
+                                                    .await,
+                                                    &selected_provider,
+                                                    &effective_provider,
+                                                    &ctx.resolved_model,
+                                                ),
                                             );
                                         }
                                         Err(e) => {
@@ -2512,47 +3867,53 @@ pub async fn anthropic_messages(
     Json(mut request): Json<AnthropicMessagesRequest>,
 ) -> Response {
     // 使用 Anthropic 格式的认证验证（优先检查 x-api-key）
-    if let Err(e) = verify_api_key_anthropic(&headers, &state.api_key).await {
-        state
-            .logs
-            .write()
-            .await
-            .add("warn", "Unauthorized request to /v1/messages");
-        return e.into_response();
-    }
-
-    // 速率限制检查
-    if let Some(ref limiter) = state.rate_limiter {
-        let client_key = headers
-            .get("x-api-key")
-            .or_else(|| headers.get("authorization"))
-            .and_then(|v| v.to_str().ok())
-            .unwrap_or("anonymous");
-        if let crate::middleware::rate_limit::RateLimitResult::Limited { retry_after } =
-            limiter.check_rate_limit(client_key)
-        {
-            let response = build_error_response_with_meta(
-                StatusCode::TOO_MANY_REQUESTS.as_u16(),
-                &format!(
-                    "Rate limited. Retry after {} seconds",
-                    retry_after.as_secs()
-                ),
-                None,
-                None,
-                Some(GatewayErrorCode::RateLimited),
-            );
-            let (mut parts, body) = response.into_parts();
-            parts.headers.insert(
-                header::RETRY_AFTER,
-                header::HeaderValue::from_str(&retry_after.as_secs().to_string())
-                    .unwrap_or_else(|_| header::HeaderValue::from_static("60")),
-            );
-            return Response::from_parts(parts, body);
+    let tenant = match authenticate_request(&state, &headers, true).await {
+        Ok(tenant) => tenant,
+        Err(e) => {
+            state
+                .logs
+                .write()
+                .await
+                .add("warn", "Unauthorized request to /v1/messages");
+            return e.into_response();
         }
+    };
+
+    // 速率限制检查（全局 + 租户级）
+    if let Err(response) = check_request_rate_limit(&state, &headers, tenant.as_deref()) {
+        return response;
     }
 
     // 创建请求上下文
     let mut ctx = RequestContext::new(request.model.clone()).with_stream(request.stream);
+    ctx.trace_with_data(
+        "received",
+        "POST /v1/messages",
+        serde_json::json!({
+            "model": request.model,
+            "stream": request.stream,
+            "messages": request.messages.len(),
+            "tools": request.tools.as_ref().map_or(0, |tools| tools.len()),
+        }),
+    );
+    if state.include_request_body {
+        ctx.trace_with_data(
+            lime_infra::telemetry::REQUEST_BODY_TRACE_STAGE,
+            "request body",
+            serde_json::to_value(&request).unwrap_or_default(),
+        );
+    }
+    state.audit_log.capture_request_body(&mut ctx, &request);
+    if let Some(tenant) = &tenant {
+        ctx.set_metadata(TENANT_METADATA_KEY, serde_json::json!(tenant.id()));
+    }
+    if let Some(session_id) = headers
+        .get(SESSION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|s| !s.is_empty())
+    {
+        ctx.set_metadata(COST_SESSION_METADATA_KEY, serde_json::json!(session_id));
+    }
 
     // 幂等性检查（仅非流式）
     let idempotency_key = headers
@@ -2606,6 +3967,17 @@ pub async fn anthropic_messages(
         ),
     );
 
+    // 虚拟模型：改写为目标模型并补齐预设参数
+    let requested_model = request.model.clone();
+    let virtual_provider = apply_virtual_model(
+        &state,
+        &mut ctx,
+        &mut request,
+        &requested_model,
+        VirtualModel::apply_anthropic_defaults,
+    )
+    .await;
+
     // 使用 RequestProcessor 解析模型别名
     let resolved_model = state.processor.resolve_model(&request.model).await;
     ctx.set_resolved_model(resolved_model.clone());
@@ -2726,6 +4098,43 @@ pub async fn anthropic_messages(
         }
     }
 
+    // 上下文窗口不足时自动升级到同系列大窗口模型
+    {
+        let estimated_total_tokens =
+            build_anthropic_capability_requirements(&request).estimated_total_tokens;
+        apply_context_upgrade(&state, &mut ctx, &mut request.model, estimated_total_tokens).await;
+    }
+
+    // 上下文窗口修剪
+    {
+        let messages_json: Vec<serde_json::Value> = serde_json::to_value(&request.messages)
+            .ok()
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+        let trimmer = &state.processor.context_trimmer;
+        let fixed_tokens = trimmer
+            .count_text(&serde_json::to_string(&request.system).unwrap_or_default())
+            + trimmer.count_text(&serde_json::to_string(&request.tools).unwrap_or_default());
+        if let Some((trimmed, summary)) = apply_context_trim(
+            &state,
+            &mut ctx,
+            &headers,
+            "/v1/messages",
+            messages_json,
+            fixed_tokens,
+            request.max_tokens,
+        )
+        .await
+        {
+            if let Ok(trimmed_msgs) = serde_json::from_value(serde_json::Value::Array(trimmed)) {
+                request.messages = trimmed_msgs;
+                if let Some(summary) = summary {
+                    context_trimmer::append_summary_to_system(&mut request.system, &summary);
+                }
+            }
+        }
+    }
+
     // 根据客户端类型选择 Provider
     // **Validates: Requirements 3.1, 3.3, 3.4**
     let (selected_provider, client_type) = select_provider_for_client(&headers, &state).await;
@@ -2743,26 +4152,73 @@ pub async fn anthropic_messages(
     let provider_id_header = headers
         .get("x-provider-id")
         .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_lowercase());
-
-    // 尝试选择凭证（含能力感知 + 跨 Provider 回退）
-    let (effective_provider, credential) =
+        .map(|s| s.to_lowercase())
+        .or(virtual_provider);
+
+    // 尝试选择凭证（故障转移链优先，其次能力感知 + 跨 Provider 回退）
+    let failover_plan = provider_id_header
+        .is_none()
+        .then(|| state.processor.failover_chains.plan(&request.model))
+        .flatten();
+    let mut failover_start = 0;
+    let (effective_provider, credential) = if let Some(plan) = &failover_plan {
+        match resolve_failover_chain_credential(
+            &state,
+            &ctx.request_id,
+            plan,
+            &request.model,
+            &client_type,
+            tenant.as_deref(),
+            "ANTHROPIC_MESSAGES",
+        )
+        .await
+        {
+            Some((index, provider, cred)) => {
+                failover_start = index;
+                (provider, Some(cred))
+            }
+            None => (selected_provider.clone(), None),
+        }
+    } else {
         match resolve_anthropic_credential_with_capability_fallback(
             &state,
             &ctx.request_id,
             &selected_provider,
             &client_type,
             provider_id_header.as_deref(),
+            tenant.as_deref(),
             &mut request,
         )
         .await
         {
             Ok(result) => result,
             Err(resp) => return resp,
-        };
+        }
+    };
     if ctx.resolved_model != request.model {
         ctx.set_resolved_model(request.model.clone());
     }
+    if let Some(cred) = &credential {
+        ctx.set_credential_id(cred.uuid.clone());
+    }
+    if let Some(tenant) = tenant.as_deref() {
+        if credential.is_none() && tenant.restricts_credentials() {
+            record_request_telemetry(
+                &state,
+                &ctx,
+                lime_infra::telemetry::RequestStatus::Failed,
+                Some("tenant has no available credentials".to_string()),
+            );
+            return build_tenant_no_credentials_response(tenant, &ctx.request_id);
+        }
+    }
+    let cost_provider = credential
+        .as_ref()
+        .map(|cred| cred.provider_type.to_string())
+        .unwrap_or_else(|| effective_provider.to_string());
+    if let Err(resp) = check_cost_cap(&state, &mut ctx, &cost_provider) {
+        return resp;
+    }
 
     // 记录路由结果（使用最终 provider/model）
     state.logs.write().await.add(
@@ -2772,6 +4228,16 @@ pub async fn anthropic_messages(
             ctx.request_id, ctx.resolved_model, effective_provider, selected_provider
         ),
     );
+    ctx.trace_with_data(
+        "route",
+        format!("{selected_provider} -> {effective_provider}"),
+        serde_json::json!({
+            "client_type": client_type.to_string(),
+            "original_model": ctx.original_model,
+            "model": ctx.resolved_model,
+            "credential": credential.as_ref().map(|cred| cred.uuid.clone()),
+        }),
+    );
 
     if !request.stream {
         let request_payload = serde_json::to_value(&request).unwrap_or_default();
@@ -2788,11 +4254,14 @@ pub async fn anthropic_messages(
         {
             Ok(guard) => cache_guard = guard,
             Err(resp) => {
-                return attach_route_debug_headers(
-                    resp,
-                    &selected_provider,
-                    &effective_provider,
-                    &ctx.resolved_model,
+                return attach_model_upgrade_header(
+                    &ctx,
+                    attach_route_debug_headers(
+                        resp,
+                        &selected_provider,
+                        &effective_provider,
+                        &ctx.resolved_model,
+                    ),
                 );
             }
         }
@@ -2808,11 +4277,14 @@ pub async fn anthropic_messages(
         {
             Ok(guard) => dedup_guard = guard,
             Err(resp) => {
-                return attach_route_debug_headers(
-                    resp,
-                    &selected_provider,
-                    &effective_provider,
-                    &ctx.resolved_model,
+                return attach_model_upgrade_header(
+                    &ctx,
+                    attach_route_debug_headers(
+                        resp,
+                        &selected_provider,
+                        &effective_provider,
+                        &ctx.resolved_model,
+                    ),
                 );
             }
         }
@@ -2851,14 +4323,59 @@ pub async fn anthropic_messages(
         // **Validates: Requirements 2.1, 2.3, 2.5**
 
         let provider_label = cred.provider_type.to_string();
-        let response = call_with_single_provider_resilience(
-            &state,
-            &ctx.request_id,
-            &provider_label,
-            request.stream,
-            || async { call_provider_anthropic(&state, &cred, &request, None).await },
-        )
-        .await;
+        let (response, effective_provider) = match &failover_plan {
+            Some(plan) => {
+                let (state_ref, request_ref) = (&state, &request);
+                call_with_failover_chain(
+                    &state,
+                    &mut ctx,
+                    plan,
+                    failover_start,
+                    cred,
+                    &request.model,
+                    &client_type,
+                    tenant.as_deref(),
+                    request.stream,
+                    "ANTHROPIC_MESSAGES",
+                    |cred| async move {
+                        call_provider_anthropic(state_ref, &cred, request_ref, None).await
+                    },
+                )
+                .await
+            }
+            None => (
+                call_with_single_provider_resilience(
+                    &state,
+                    &ctx.request_id,
+                    &provider_label,
+                    &cred.uuid,
+                    request.stream,
+                    || async { call_provider_anthropic(&state, &cred, &request, None).await },
+                )
+                .await,
+                effective_provider,
+            ),
+        };
+        let response = if request.stream {
+            response
+        } else {
+            let (state_ref, request_ref) = (&state, &request);
+            retry_degenerate_response(
+                &state,
+                &mut ctx,
+                response,
+                DegenerateResponseFormat::Anthropic,
+                request.max_tokens,
+                &request.model,
+                &client_type,
+                tenant.as_deref(),
+                "ANTHROPIC_MESSAGES",
+                |cred| async move {
+                    call_provider_anthropic(state_ref, &cred, request_ref, None).await
+                },
+            )
+            .await
+        };
 
         // 记录请求统计
         let is_success = response.status().is_success();
@@ -2867,6 +4384,14 @@ pub async fn anthropic_messages(
         } else {
             lime_infra::telemetry::RequestStatus::Failed
         };
+        ctx.trace_with_data(
+            "provider_response",
+            format!("HTTP {}", response.status().as_u16()),
+            serde_json::json!({
+                "provider": provider_label,
+                "endpoint": upstream_endpoint(&response),
+            }),
+        );
         record_request_telemetry(&state, &ctx, status, None);
 
         // 估算 Token 使用量
@@ -2900,18 +4425,21 @@ pub async fn anthropic_messages(
         // 完成 Flow 捕获并检查响应拦截
         // **Validates: Requirements 2.1, 2.5**
 
-        return attach_route_debug_headers(
-            finalize_replayable_response(
-                response,
-                &mut idempotency_guard,
-                &mut dedup_guard,
-                &mut cache_guard,
-                &ctx.request_id,
-            )
-            .await,
-            &selected_provider,
-            &effective_provider,
-            &ctx.resolved_model,
+        return attach_model_upgrade_header(
+            &ctx,
+            attach_route_debug_headers(
+                finalize_replayable_response(
+                    response,
+                    &mut idempotency_guard,
+                    &mut dedup_guard,
+                    &mut cache_guard,
+                    &ctx.request_id,
+                )
+                .await,
+                &selected_provider,
+                &effective_provider,
+                &ctx.resolved_model,
+            ),
         );
     }
 
@@ -3092,18 +4620,21 @@ pub async fn anthropic_messages(
 
                         // 非流式响应
                         let response = build_anthropic_response(&request.model, &parsed);
-                        return attach_route_debug_headers(
-                            finalize_replayable_response(
-                                response,
-                                &mut idempotency_guard,
-                                &mut dedup_guard,
-                                &mut cache_guard,
-                                &ctx.request_id,
-                            )
-                            .await,
-                            &selected_provider,
-                            &effective_provider,
-                            &ctx.resolved_model,
+                        return attach_model_upgrade_header(
+                            &ctx,
+                            attach_route_debug_headers(
+                                finalize_replayable_response(
+                                    response,
+                                    &mut idempotency_guard,
+                                    &mut dedup_guard,
+                                    &mut cache_guard,
+                                    &ctx.request_id,
+                                )
+                                .await,
+                                &selected_provider,
+                                &effective_provider,
+                                &ctx.resolved_model,
+                            ),
                         );
                     }
                     Err(e) => {
@@ -3178,18 +4709,21 @@ pub async fn anthropic_messages(
                                             }
                                             let response =
                                                 build_anthropic_response(&request.model, &parsed);
-                                            return attach_route_debug_headers(
-                                                finalize_replayable_response(
-                                                    response,
-                                                    &mut idempotency_guard,
-                                                    &mut dedup_guard,
-                                                    &mut cache_guard,
-                                                    &ctx.request_id,
-                                                )
-                                                .await,
-                                                &selected_provider,
-                                                &effective_provider,
-                                                &ctx.resolved_model,
+                                            return attach_model_upgrade_header(
+                                                &ctx,
+                                                attach_route_debug_headers(
+                                                    finalize_replayable_response(
+                                                        response,
+                                                        &mut idempotency_guard,
+                                                        &mut dedup_guard,
+                                                        &mut cache_guard,
+                                                        &ctx.request_id,
+                                                    )
+                                                    .await,
+                                                    &selected_provider,
+                                                    &effective_provider,
+                                                    &ctx.resolved_model,
+                                                ),
                                             );
                                         }
                                         Err(e) => {
@@ -0,0 +1,260 @@
+//! 请求费用预估端点
+//!
+//! `POST /v1/estimate`：发送大任务前预估输入 Token、各候选凭证的预计费用，
+//! 以及按当前路由规则会选中哪个凭证。预估不会调用上游，也不计入用量与费用。
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::AppState;
+use lime_core::database::dao::provider_pool::ProviderPoolDao;
+use lime_core::database::lock_db;
+use lime_core::errors::GatewayErrorCode;
+use lime_core::models::provider_pool_model::{PoolProviderType, ProviderCredential};
+use lime_processor::context_trimmer::ContextTrimmer;
+use lime_server_utils::build_error_response_with_meta;
+
+use super::api::{authenticate_request, select_credential_for_request, select_provider_for_client};
+
+/// 费用预估请求
+#[derive(Debug, Deserialize)]
+pub struct CostEstimateRequest {
+    /// 目标模型，为空时取请求体中的 `model`
+    #[serde(default)]
+    pub model: Option<String>,
+    /// 待发送的请求体（OpenAI Chat / Anthropic Messages / Responses 格式）
+    #[serde(default)]
+    pub request: Value,
+    /// 预计输出 Token 数，为空时取请求体的 `max_tokens` 等字段
+    #[serde(default)]
+    pub max_output_tokens: Option<u32>,
+}
+
+/// 单个候选凭证的预估
+#[derive(Debug, Clone, Serialize)]
+pub struct CandidateCostEstimate {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub provider_type: String,
+    /// 是否可参与选择（健康、未禁用且支持该模型）
+    pub available: bool,
+    /// 是否为免费/本地 Provider（不计费）
+    pub free: bool,
+    /// 预计费用（USD）
+    pub cost_usd: f64,
+    /// 用量是否已接近配额软上限
+    pub near_quota: bool,
+    /// 是否会被月度费用上限拒绝
+    pub blocked_by_cost_cap: bool,
+    /// 是否为路由会选中的凭证
+    pub selected: bool,
+}
+
+/// 费用预估结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CostEstimateResponse {
+    pub model: String,
+    /// 路由选定的 Provider
+    pub provider: String,
+    /// 预估输入 Token 数
+    pub input_tokens: u32,
+    /// 预计输出 Token 数
+    pub output_tokens: u32,
+    /// 模型注册表中是否有该模型的定价（无定价时费用按 0 计）
+    pub pricing_available: bool,
+    pub currency: String,
+    pub candidates: Vec<CandidateCostEstimate>,
+    /// 路由会选中的凭证 UUID
+    pub selected_credential: Option<String>,
+}
+
+/// 预估请求费用
+pub async fn estimate_request_cost(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CostEstimateRequest>,
+) -> Response {
+    let tenant = match authenticate_request(&state, &headers, false).await {
+        Ok(tenant) => tenant,
+        Err(e) => return e.into_response(),
+    };
+    let Some(model) = request
+        .model
+        .clone()
+        .or_else(|| request.request["model"].as_str().map(str::to_string))
+        .filter(|model| !model.is_empty())
+    else {
+        return build_error_response_with_meta(
+            StatusCode::BAD_REQUEST.as_u16(),
+            "model is required",
+            None,
+            None,
+            Some(GatewayErrorCode::InvalidRequest),
+        );
+    };
+
+    let (input_tokens, output_tokens) = estimate_token_counts(
+        &state.processor.context_trimmer,
+        &request.request,
+        request.max_output_tokens,
+        state.context_trim.reserve_output_tokens,
+    );
+
+    // 与实际请求相同的凭证选择路径（X-Provider-Id、租户凭证子集、配额软上限切换）
+    let (selected_provider, client_type) = select_provider_for_client(&headers, &state).await;
+    let provider_id_header = headers
+        .get("x-provider-id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_lowercase());
+    let selected = select_credential_for_request(
+        &state,
+        None,
+        &selected_provider,
+        &model,
+        &client_type,
+        provider_id_header.as_deref(),
+        tenant.as_deref(),
+        "ESTIMATE",
+        false,
+    )
+    .await
+    .ok()
+    .flatten();
+
+    let provider = selected
+        .as_ref()
+        .map(|cred| cred.provider_type.to_string())
+        .or(provider_id_header)
+        .unwrap_or(selected_provider);
+    let mut credentials = candidate_credentials(&state, &provider);
+    if let Some(tenant) = tenant.as_deref().filter(|t| t.restricts_credentials()) {
+        credentials.retain(|c| tenant.allows_credential(c));
+    }
+    if let Some(selected) = &selected {
+        if !credentials.iter().any(|c| c.uuid == selected.uuid) {
+            credentials.insert(0, selected.clone());
+        }
+    }
+
+    let pricing = state
+        .cost_cap_guard
+        .lookup_pricing(state.db.as_ref(), &model);
+    // 与费用上限相同的计算路径，CNY 定价按配置汇率折算为 USD
+    let paid_cost =
+        state
+            .cost_cap_guard
+            .cost_for_pricing(pricing.as_ref(), input_tokens, output_tokens);
+    let selected_uuid = selected.as_ref().map(|cred| cred.uuid.clone());
+    let candidates = credentials
+        .into_iter()
+        .map(|cred| {
+            let provider_type = cred.provider_type.to_string();
+            let free = state.cost_cap_guard.is_free_provider(&provider_type);
+            CandidateCostEstimate {
+                available: cred.is_available() && cred.supports_model(&model),
+                free,
+                cost_usd: if free { 0.0 } else { paid_cost },
+                near_quota: state.quota_manager.is_near_soft_limit(&cred.uuid),
+                blocked_by_cost_cap: state.cost_cap_guard.check(&provider_type).is_err(),
+                selected: selected_uuid.as_deref() == Some(cred.uuid.as_str()),
+                uuid: cred.uuid,
+                name: cred.name,
+                provider_type,
+            }
+        })
+        .collect();
+
+    Json(CostEstimateResponse {
+        model,
+        provider,
+        input_tokens,
+        output_tokens,
+        pricing_available: pricing.is_some(),
+        currency: "USD".to_string(),
+        candidates,
+        selected_credential: selected_uuid,
+    })
+    .into_response()
+}
+
+/// 路由 Provider 下凭证池中的全部凭证（无法识别为凭证池类型时为空）
+fn candidate_credentials(state: &AppState, provider: &str) -> Vec<ProviderCredential> {
+    let (Some(db), Ok(provider_type)) = (&state.db, provider.parse::<PoolProviderType>()) else {
+        return Vec::new();
+    };
+    lock_db(db)
+        .ok()
+        .and_then(|conn| ProviderPoolDao::get_by_type(&conn, &provider_type).ok())
+        .unwrap_or_default()
+}
+
+/// 估算输入与输出 Token 数
+///
+/// 输入按消息、系统提示词、工具定义与 Responses `input` 计数；
+/// 输出优先使用显式指定值，其次是请求体的最大输出字段，最后使用上下文修剪的预留值。
+fn estimate_token_counts(
+    trimmer: &ContextTrimmer,
+    request: &Value,
+    max_output_tokens: Option<u32>,
+    default_output_tokens: u32,
+) -> (u32, u32) {
+    let messages = request["messages"].as_array().map(Vec::as_slice);
+    let mut input = trimmer.count_tokens(messages.unwrap_or_default());
+    for field in ["system", "tools", "input", "instructions"] {
+        match &request[field] {
+            Value::Null => {}
+            Value::String(text) => input += trimmer.count_text(text),
+            value => input += trimmer.count_text(&value.to_string()),
+        }
+    }
+
+    let output = max_output_tokens
+        .or_else(|| {
+            ["max_tokens", "max_completion_tokens", "max_output_tokens"]
+                .iter()
+                .find_map(|field| request[*field].as_u64())
+                .map(|tokens| tokens.min(u32::MAX as u64) as u32)
+        })
+        .unwrap_or(default_output_tokens);
+    (input.min(u32::MAX as usize) as u32, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_estimate_token_counts() {
+        let trimmer = ContextTrimmer::new();
+        let request = json!({
+            "model": "claude-sonnet-4-5",
+            "system": "You are a helpful assistant.",
+            "messages": [{"role": "user", "content": "Summarize this document please."}],
+            "max_tokens": 2048,
+        });
+
+        let (input, output) = estimate_token_counts(&trimmer, &request, None, 4096);
+        let messages_only = trimmer.count_tokens(request["messages"].as_array().unwrap());
+        assert!(input > messages_only);
+        assert_eq!(output, 2048);
+
+        let (_, output) = estimate_token_counts(&trimmer, &request, Some(100), 4096);
+        assert_eq!(output, 100);
+    }
+
+    #[test]
+    fn test_estimate_token_counts_defaults_output() {
+        let trimmer = ContextTrimmer::new();
+        let request = json!({"model": "gpt-4o", "input": "hello"});
+        let (input, output) = estimate_token_counts(&trimmer, &request, None, 4096);
+        assert!(input > 0);
+        assert_eq!(output, 4096);
+    }
+}
@@ -2,17 +2,23 @@
 //!
 //! 将 server 中的各类处理器拆分到独立文件
 
+pub mod admin_api;
 pub mod api;
 pub mod api_key_provider_utils;
 pub mod chrome_bridge_ws;
+pub mod cost_estimate;
 pub mod credentials_api;
 pub mod image_handler;
 pub mod kiro_credential;
 pub mod provider_calls;
+pub mod realtime_ws;
+pub mod responses;
 pub mod websocket;
 
+pub use admin_api::*;
 pub use api::*;
 pub use chrome_bridge_ws::*;
+pub use cost_estimate::*;
 pub use credentials_api::*;
 pub use image_handler::*;
 // 避免 SelectCredentialRequest 歧义 glob re-export（credentials_api 和 kiro_credential 都定义了同名类型）
@@ -22,4 +28,6 @@ pub use kiro_credential::{
     SelectCredentialResponse,
 };
 pub use provider_calls::*;
+pub use realtime_ws::*;
+pub use responses::*;
 pub use websocket::*;
@@ -48,21 +48,31 @@ use axum::{
     Json,
 };
 use futures::StreamExt;
+use std::borrow::Cow;
+use std::future::Future;
 
 use crate::AppState;
+use lime_core::cpu_pool::{run_cpu_task, CpuTaskKind, CpuTaskPriority};
 use lime_core::models::anthropic::AnthropicMessagesRequest;
 use lime_core::models::openai::ChatCompletionRequest;
-use lime_core::models::provider_pool_model::{CredentialData, ProviderCredential};
+use lime_core::models::provider_pool_model::{
+    CredentialData, MockProviderConfig, ProviderCredential,
+};
+use lime_core::response_stream::ResponseChunk;
+use lime_infra::resilience::{is_transport_failure, UPSTREAM_ENDPOINT_HEADER};
 use lime_providers::converter::anthropic_to_openai::convert_anthropic_to_openai;
+use lime_providers::converter::logprobs;
+use lime_providers::converter::native_web_search;
 use lime_providers::converter::openai_to_antigravity::{
     convert_antigravity_to_openai_response, convert_openai_to_antigravity_with_context,
 };
+use lime_providers::converter::tool_result_image;
 use lime_providers::providers::{
-    AntigravityProvider, ClaudeCustomProvider, CodexProvider, KiroProvider, OpenAICustomProvider,
-    VertexProvider,
+    mock, AntigravityProvider, ClaudeCustomProvider, CodexProvider, KiroProvider, MockProvider,
+    OpenAICustomProvider, VertexProvider,
 };
 use lime_providers::session::store_thought_signature;
-use lime_providers::stream::{PipelineConfig, StreamPipeline};
+use lime_providers::stream::{OpenAiChunkEncoder, PipelineConfig, StreamPipeline};
 use lime_providers::streaming::traits::StreamingProvider;
 use lime_providers::streaming::{
     StreamConfig, StreamContext, StreamError, StreamFormat as StreamingFormat, StreamManager,
@@ -73,8 +83,242 @@ use lime_server_utils::{
     build_error_response_with_status, parse_cw_response, safe_truncate, CWParsedResponse,
 };
 
+/// 记录上游响应头中的限流信息（用于剩余配额估算）
+fn record_rate_limits(state: &AppState, credential_id: &str, headers: &reqwest::header::HeaderMap) {
+    if let Some(limits) = lime_credential::parse_rate_limit_headers(headers, chrono::Utc::now()) {
+        state
+            .quota_manager
+            .record_provider_limits(credential_id, limits);
+    }
+}
+
+/// 按凭证设置过滤原生联网搜索工具（OpenAI 格式）
+///
+/// 支持原生联网搜索的凭证（Anthropic / Gemini）会按次额外计费，
+/// 未开启 `native_web_search` 时剔除请求中的 web_search 工具；其他凭证保持原样。
+fn gate_native_web_search_openai<'a>(
+    credential: &ProviderCredential,
+    request: &'a ChatCompletionRequest,
+) -> Cow<'a, ChatCompletionRequest> {
+    if credential.native_web_search
+        || !credential.credential.supports_native_web_search()
+        || !native_web_search::openai_request_uses_web_search(request)
+    {
+        return Cow::Borrowed(request);
+    }
+    let mut gated = request.clone();
+    native_web_search::strip_openai_web_search_tools(&mut gated);
+    tracing::info!(
+        "[NATIVE_WEB_SEARCH] 凭证 {} 未开启原生联网搜索，已移除 web_search 工具",
+        &credential.uuid[..8]
+    );
+    Cow::Owned(gated)
+}
+
+/// 按凭证设置过滤原生联网搜索工具（Anthropic 格式）
+fn gate_native_web_search_anthropic<'a>(
+    credential: &ProviderCredential,
+    request: &'a AnthropicMessagesRequest,
+) -> Cow<'a, AnthropicMessagesRequest> {
+    if credential.native_web_search
+        || !credential.credential.supports_native_web_search()
+        || !native_web_search::anthropic_request_uses_web_search(request)
+    {
+        return Cow::Borrowed(request);
+    }
+    let mut gated = request.clone();
+    native_web_search::strip_anthropic_web_search_tools(&mut gated);
+    tracing::info!(
+        "[NATIVE_WEB_SEARCH] 凭证 {} 未开启原生联网搜索，已移除 web_search 工具",
+        &credential.uuid[..8]
+    );
+    Cow::Owned(gated)
+}
+
+/// 按凭证能力处理工具结果中的图片（OpenAI 格式）
+///
+/// 支持图片的凭证缩放过大的截图，其他凭证将图片替换为文本占位。
+fn gate_tool_result_images_openai<'a>(
+    credential: &ProviderCredential,
+    request: &'a ChatCompletionRequest,
+) -> Cow<'a, ChatCompletionRequest> {
+    if !tool_result_image::openai_request_has_tool_images(request) {
+        return Cow::Borrowed(request);
+    }
+    let supports_images = credential.credential.supports_tool_result_images();
+    let mut gated = request.clone();
+    let handled = tool_result_image::prepare_openai_tool_images(&mut gated, supports_images);
+    tracing::debug!(
+        "[TOOL_IMAGE] 凭证 {} 处理工具结果图片 {} 张 (透传: {})",
+        &credential.uuid[..8],
+        handled,
+        supports_images
+    );
+    Cow::Owned(gated)
+}
+
+/// 按凭证能力处理工具结果中的图片（Anthropic 格式）
+fn gate_tool_result_images_anthropic<'a>(
+    credential: &ProviderCredential,
+    request: &'a AnthropicMessagesRequest,
+) -> Cow<'a, AnthropicMessagesRequest> {
+    if !tool_result_image::anthropic_request_has_tool_images(request) {
+        return Cow::Borrowed(request);
+    }
+    let supports_images = credential.credential.supports_tool_result_images();
+    let mut gated = request.clone();
+    let handled = tool_result_image::prepare_anthropic_tool_images(&mut gated, supports_images);
+    tracing::debug!(
+        "[TOOL_IMAGE] 凭证 {} 处理工具结果图片 {} 张 (透传: {})",
+        &credential.uuid[..8],
+        handled,
+        supports_images
+    );
+    Cow::Owned(gated)
+}
+
+/// 消息内容超过该字符数时，Anthropic -> OpenAI 转换放到 CPU 线程池执行
+const OFFLOAD_CONVERSION_THRESHOLD_CHARS: usize = 256 * 1024;
+
+/// 估算 JSON 值中字符串内容的总长度（不序列化）
+fn json_text_len(value: &serde_json::Value) -> usize {
+    match value {
+        serde_json::Value::String(s) => s.len(),
+        serde_json::Value::Array(items) => items.iter().map(json_text_len).sum(),
+        serde_json::Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| key.len() + json_text_len(value))
+            .sum(),
+        _ => 8,
+    }
+}
+
+/// Anthropic -> OpenAI 请求转换
+///
+/// 大请求（长对话、内联图片）的转换会占用异步运行时数毫秒到数百毫秒，
+/// 导致同一运行时上的流式响应卡顿，因此放到 CPU 线程池执行；线程池异常时回退为就地转换。
+async fn convert_anthropic_request(request: &AnthropicMessagesRequest) -> ChatCompletionRequest {
+    let size: usize = request
+        .messages
+        .iter()
+        .map(|msg| json_text_len(&msg.content))
+        .sum();
+    if size < OFFLOAD_CONVERSION_THRESHOLD_CHARS {
+        return convert_anthropic_to_openai(request);
+    }
+    let owned = request.clone();
+    match run_cpu_task(
+        CpuTaskPriority::Normal,
+        CpuTaskKind::JsonTransform,
+        move || convert_anthropic_to_openai(&owned),
+    )
+    .await
+    {
+        Ok(converted) => converted,
+        Err(e) => {
+            tracing::warn!("[CPU_POOL] 请求转换回退为就地执行: {}", e);
+            convert_anthropic_to_openai(request)
+        }
+    }
+}
+
+/// 错误响应体读取上限（判断是否为传输层失败）
+const TRANSPORT_ERROR_BODY_LIMIT: usize = 64 * 1024;
+
+/// 按 `routing.endpoint_fallback` 依次尝试凭证的等价端点
+///
+/// 响应为 DNS 解析、连接或 TLS 握手失败（见 [`is_transport_failure`]）时让该端点进入冷却，
+/// 换组内下一个端点重试；服务请求的端点通过 `x-lime-upstream-endpoint` 响应头返回。
+/// 凭证的 base_url 不属于任何端点组时直接调用。
+async fn call_with_endpoint_fallback<F, Fut>(
+    state: &AppState,
+    credential: &ProviderCredential,
+    mut operation: F,
+) -> Response
+where
+    F: FnMut(ProviderCredential) -> Fut,
+    Fut: Future<Output = Response>,
+{
+    let endpoint_fallback = &state.processor.endpoint_fallback;
+    let current_base_url = credential.credential.effective_base_url();
+    let plan = current_base_url.and_then(|base_url| {
+        endpoint_fallback.plan(&credential.provider_type.to_string(), base_url)
+    });
+    let Some(plan) = plan else {
+        return operation(credential.clone()).await;
+    };
+
+    let mut index = 0;
+    loop {
+        let candidate = &plan.candidates[index];
+        let mut cred = credential.clone();
+        if current_base_url != Some(candidate.base_url.as_str()) {
+            cred.credential.set_base_url(candidate.base_url.clone());
+        }
+        let (response, failure) = detect_transport_failure(operation(cred).await).await;
+
+        let Some(error) = failure else {
+            endpoint_fallback.mark_success(&candidate.origin);
+            if index > 0 {
+                state.logs.write().await.add(
+                    "info",
+                    &format!(
+                        "[ENDPOINT_FALLBACK] provider={} 由备用端点 {} 服务",
+                        plan.provider, candidate.origin
+                    ),
+                );
+            }
+            return with_upstream_endpoint(response, &candidate.origin);
+        };
+
+        endpoint_fallback.mark_failure(&candidate.origin, &error);
+        let next = plan.candidates.get(index + 1);
+        state.logs.write().await.add(
+            "warn",
+            &format!(
+                "[ENDPOINT_FALLBACK] provider={} endpoint={} 不可用（{}）: {}",
+                plan.provider,
+                candidate.origin,
+                next.map(|n| format!("切换到 {}", n.origin))
+                    .unwrap_or_else(|| "所有端点均不可用".to_string()),
+                safe_truncate(&error, 200)
+            ),
+        );
+        if next.is_none() {
+            return with_upstream_endpoint(response, &candidate.origin);
+        }
+        index += 1;
+    }
+}
+
+/// 读取 5xx 响应体判断是否为传输层失败，响应体读取后重新装回
+async fn detect_transport_failure(response: Response) -> (Response, Option<String>) {
+    if !response.status().is_server_error() {
+        return (response, None);
+    }
+    let (parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, TRANSPORT_ERROR_BODY_LIMIT)
+        .await
+        .unwrap_or_default();
+    let text = String::from_utf8_lossy(&bytes);
+    let failure = is_transport_failure(&text).then(|| text.into_owned());
+    (Response::from_parts(parts, Body::from(bytes)), failure)
+}
+
+fn with_upstream_endpoint(mut response: Response, origin: &str) -> Response {
+    if let Ok(value) = header::HeaderValue::from_str(origin) {
+        response.headers_mut().insert(
+            header::HeaderName::from_static(UPSTREAM_ENDPOINT_HEADER),
+            value,
+        );
+    }
+    response
+}
+
 /// 根据凭证调用 Provider (Anthropic 格式)
 ///
+/// 配置了等价端点时，传输层失败会自动切换端点。
+///
 /// # 参数
 /// - `state`: 应用状态
 /// - `credential`: 凭证信息
@@ -86,6 +330,22 @@ pub async fn call_provider_anthropic(
     request: &AnthropicMessagesRequest,
     flow_id: Option<&str>,
 ) -> Response {
+    call_with_endpoint_fallback(state, credential, |cred| async move {
+        call_provider_anthropic_at_endpoint(state, &cred, request, flow_id).await
+    })
+    .await
+}
+
+async fn call_provider_anthropic_at_endpoint(
+    state: &AppState,
+    credential: &ProviderCredential,
+    request: &AnthropicMessagesRequest,
+    flow_id: Option<&str>,
+) -> Response {
+    let gated_request = gate_native_web_search_anthropic(credential, request);
+    let image_gated_request = gate_tool_result_images_anthropic(credential, gated_request.as_ref());
+    let request = image_gated_request.as_ref();
+
     match &credential.credential {
         CredentialData::KiroOAuth { creds_file_path } => {
             // 如果是流式请求，使用真正的流式处理（需求 1.1, 6.1）
@@ -152,7 +412,7 @@ pub async fn call_provider_anthropic(
             let _ = kiro.load_credentials_from_path(creds_file_path).await;
             // 使用缓存的 token 覆盖文件中的 token（缓存的 token 更新）
             kiro.credentials.access_token = Some(token);
-            let openai_request = convert_anthropic_to_openai(request);
+            let openai_request = convert_anthropic_request(request).await;
             let resp = match kiro.call_api(&openai_request).await {
                 Ok(r) => r,
                 Err(e) => {
@@ -389,7 +649,7 @@ pub async fn call_provider_anthropic(
             // 获取 project_id 用于请求
             let proj_id = antigravity.project_id.clone().unwrap_or_default();
             // 先转换为 OpenAI 格式，再转换为 Antigravity 格式
-            let openai_request = convert_anthropic_to_openai(request);
+            let openai_request = convert_anthropic_request(request).await;
             let antigravity_request = convert_openai_to_antigravity_with_context(&openai_request, &proj_id);
             match antigravity
                 .generate_content(&request.model, &antigravity_request)
@@ -438,9 +698,10 @@ pub async fn call_provider_anthropic(
         }
         CredentialData::OpenAIKey { api_key, base_url } => {
             let openai = OpenAICustomProvider::with_config(api_key.clone(), base_url.clone());
-            let openai_request = convert_anthropic_to_openai(request);
+            let openai_request = convert_anthropic_request(request).await;
             match openai.call_api(&openai_request).await {
                 Ok(resp) => {
+                    record_rate_limits(state, &credential.uuid, resp.headers());
                     let status = resp.status();
                     if status.is_success() {
                         match resp.text().await {
@@ -571,6 +832,7 @@ pub async fn call_provider_anthropic(
             );
             match claude.call_api(request).await {
                 Ok(resp) => {
+                    record_rate_limits(state, &credential.uuid, resp.headers());
                     let status = resp.status();
                     // 打印响应状态
                     state.logs.write().await.add(
@@ -711,7 +973,7 @@ pub async fn call_provider_anthropic(
         }
         CredentialData::VertexKey { api_key, base_url, .. } => {
             // Vertex AI uses Gemini-compatible API, convert Anthropic to OpenAI format first
-            let openai_request = convert_anthropic_to_openai(request);
+            let openai_request = convert_anthropic_request(request).await;
             let vertex = VertexProvider::with_config(api_key.clone(), base_url.clone());
             match vertex.chat_completions(&serde_json::to_value(&openai_request).unwrap_or_default()).await {
                 Ok(resp) => {
@@ -761,6 +1023,25 @@ pub async fn call_provider_anthropic(
             )
                 .into_response()
         }
+        CredentialData::Mock { config } => {
+            let openai_request = convert_anthropic_request(request).await;
+            match call_mock_provider(state, credential, config, &openai_request).await {
+                Ok(reply) => {
+                    let parsed = CWParsedResponse {
+                        content: reply.content,
+                        tool_calls: Vec::new(),
+                        usage_credits: 0.0,
+                        context_usage_percentage: 0.0,
+                    };
+                    if request.stream {
+                        build_anthropic_stream_response(&request.model, &parsed)
+                    } else {
+                        build_anthropic_response(&request.model, &parsed)
+                    }
+                }
+                Err(response) => response,
+            }
+        }
         // 新增的凭证类型暂不支持 Anthropic 格式
         CredentialData::CodexOAuth { .. }
         | CredentialData::ClaudeOAuth { .. } => {
@@ -786,6 +1067,7 @@ pub async fn call_provider_anthropic(
             );
             match claude.call_api(request).await {
                 Ok(resp) => {
+                    record_rate_limits(state, &credential.uuid, resp.headers());
                     let status = resp.status();
                     state.logs.write().await.add(
                         "info",
@@ -904,18 +1186,60 @@ pub async fn call_provider_anthropic(
 
 /// 根据凭证调用 Provider (OpenAI 格式)
 ///
+/// 配置了等价端点时，传输层失败会自动切换端点。
+///
 /// # 参数
 /// - `state`: 应用状态
 /// - `credential`: 凭证信息
 /// - `request`: OpenAI 格式请求
 /// - `flow_id`: Flow ID（可选，用于流式响应处理）
 pub async fn call_provider_openai(
+    state: &AppState,
+    credential: &ProviderCredential,
+    request: &ChatCompletionRequest,
+    flow_id: Option<&str>,
+) -> Response {
+    call_with_endpoint_fallback(state, credential, |cred| async move {
+        call_provider_openai_at_endpoint(state, &cred, request, flow_id).await
+    })
+    .await
+}
+
+async fn call_provider_openai_at_endpoint(
+    state: &AppState,
+    credential: &ProviderCredential,
+    request: &ChatCompletionRequest,
+    flow_id: Option<&str>,
+) -> Response {
+    // logprobs 仅透传给支持的上游，其他凭证剔除并在响应头中告警
+    let Some(options) = logprobs::current_request_logprobs() else {
+        return call_provider_openai_inner(state, credential, request, flow_id).await;
+    };
+    if credential.credential.supports_logprobs() {
+        return call_provider_openai_inner(state, credential, request, flow_id).await;
+    }
+    let warning = options.unsupported_warning(&credential.provider_type.to_string());
+    tracing::warn!("[LOGPROBS] 凭证 {}: {}", &credential.uuid[..8], warning);
+    let mut response = logprobs::without_request_logprobs(call_provider_openai_inner(
+        state, credential, request, flow_id,
+    ))
+    .await;
+    if let Ok(value) = header::HeaderValue::from_str(&warning) {
+        response.headers_mut().insert("x-lime-warning", value);
+    }
+    response
+}
+
+async fn call_provider_openai_inner(
     state: &AppState,
     credential: &ProviderCredential,
     request: &ChatCompletionRequest,
     _flow_id: Option<&str>,
 ) -> Response {
     let _start_time = std::time::Instant::now();
+    let gated_request = gate_native_web_search_openai(credential, request);
+    let image_gated_request = gate_tool_result_images_openai(credential, gated_request.as_ref());
+    let request = image_gated_request.as_ref();
 
     // 调试：打印凭证类型
     let cred_type = match &credential.credential {
@@ -1623,6 +1947,7 @@ pub async fn call_provider_openai(
             // 非流式请求处理
             match openai.call_api(request).await {
                 Ok(resp) => {
+                    record_rate_limits(state, &credential.uuid, resp.headers());
                     if resp.status().is_success() {
                         match resp.text().await {
                             Ok(body) => {
@@ -1879,6 +2204,7 @@ pub async fn call_provider_openai(
 
                 match openai.call_api(request).await {
                     Ok(resp) => {
+                        record_rate_limits(state, &credential.uuid, resp.headers());
                         let status = resp.status();
                         state.logs.write().await.add(
                             "info",
@@ -1997,6 +2323,7 @@ pub async fn call_provider_openai(
             // 调用 Codex API
             match codex.call_api(&request_json).await {
                 Ok(response) => {
+                    record_rate_limits(state, &credential.uuid, response.headers());
                     let status = response.status();
                     let headers = response.headers().clone();
 
@@ -2148,6 +2475,26 @@ pub async fn call_provider_openai(
                 }
             }
         }
+        CredentialData::Mock { config } => {
+            match call_mock_provider(state, credential, config, request).await {
+                Ok(reply) if request.stream => Response::builder()
+                    .status(StatusCode::OK)
+                    .header(header::CONTENT_TYPE, "text/event-stream")
+                    .header(header::CACHE_CONTROL, "no-cache")
+                    .body(Body::from(mock::build_openai_sse(&request.model, &reply)))
+                    .unwrap_or_else(|_| {
+                        (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(serde_json::json!({"error": {"message": "Failed to build streaming response"}})),
+                        )
+                            .into_response()
+                    }),
+                Ok(reply) => {
+                    Json(mock::build_openai_completion(&request.model, &reply)).into_response()
+                }
+                Err(response) => response,
+            }
+        }
         // 新增的凭证类型暂不支持 OpenAI 格式
         CredentialData::ClaudeOAuth { .. } => {
             (
@@ -2159,6 +2506,51 @@ pub async fn call_provider_openai(
     }
 }
 
+/// 调用 Mock Provider
+///
+/// 成功时返回模拟响应；注入故障时直接返回对应的错误响应，
+/// 并像真实 Provider 一样更新凭证健康状态，便于测试风控与故障转移。
+async fn call_mock_provider(
+    state: &AppState,
+    credential: &ProviderCredential,
+    config: &MockProviderConfig,
+    request: &ChatCompletionRequest,
+) -> Result<mock::MockReply, Response> {
+    let provider = MockProvider::new(credential.uuid.clone(), config.clone());
+    match provider.respond(request).await {
+        mock::MockOutcome::Success(reply) => {
+            if let Some(db) = &state.db {
+                let _ = state
+                    .pool_service
+                    .mark_healthy(db, &credential.uuid, Some(&request.model));
+                let _ = state.pool_service.record_usage(db, &credential.uuid);
+            }
+            Ok(reply)
+        }
+        mock::MockOutcome::Fault(fault) => {
+            let (status_code, body) = mock::fault_response(fault);
+            tracing::info!(
+                "[MOCK_PROVIDER] 注入故障: {:?} (status={}, uuid={})",
+                fault,
+                status_code,
+                &credential.uuid[..8.min(credential.uuid.len())]
+            );
+            if status_code >= 500 {
+                if let Some(db) = &state.db {
+                    let _ = state
+                        .pool_service
+                        .mark_unhealthy(db, &credential.uuid, Some(&body));
+                }
+            }
+            Err(Response::builder()
+                .status(StatusCode::from_u16(status_code).unwrap_or(StatusCode::OK))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body))
+                .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response()))
+        }
+    }
+}
+
 // ============================================================================
 // 流式传输支持
 // ============================================================================
@@ -3129,145 +3521,13 @@ fn build_sse_response(
 
     eprintln!("[ANTIGRAVITY_PARSE] 构建 SSE，内容长度: {}", content.len());
 
-    let chunk_id = format!("chatcmpl-{}", uuid::Uuid::new_v4());
-    let created = chrono::Utc::now().timestamp();
-
-    let mut sse_output = String::new();
-
+    let mut chunks = Vec::new();
     if !content.is_empty() {
-        let content_chunk = serde_json::json!({
-            "id": &chunk_id,
-            "object": "chat.completion.chunk",
-            "created": created,
-            "model": model,
-            "choices": [{
-                "index": 0,
-                "delta": { "content": content },
-                "finish_reason": serde_json::Value::Null
-            }]
-        });
-        sse_output.push_str(&format!("data: {content_chunk}\n\n"));
+        chunks.push(ResponseChunk::text(content));
     }
+    chunks.push(ResponseChunk::done(Some("stop")));
 
-    let done_chunk = serde_json::json!({
-        "id": &chunk_id,
-        "object": "chat.completion.chunk",
-        "created": created,
-        "model": model,
-        "choices": [{
-            "index": 0,
-            "delta": {},
-            "finish_reason": "stop"
-        }]
-    });
-    sse_output.push_str(&format!("data: {done_chunk}\n\n"));
-    sse_output.push_str("data: [DONE]\n\n");
-
-    Ok(sse_output)
-}
-
-/// 将 Gemini 流式响应 chunk 转换为 OpenAI SSE 格式
-///
-/// Gemini 流式响应格式:
-/// ```json
-/// {"candidates":[{"content":{"parts":[{"text":"Hello"}],"role":"model"},"finishReason":"STOP"}]}
-/// ```
-///
-/// OpenAI SSE 格式:
-/// ```text
-/// data: {"id":"chatcmpl-xxx","object":"chat.completion.chunk","choices":[{"index":0,"delta":{"content":"Hello"},"finish_reason":null}]}
-/// ```
-fn convert_gemini_chunk_to_openai_sse(json: &serde_json::Value, model: &str) -> Option<String> {
-    // 检查是否有 candidates
-    let candidates = json.get("candidates")?.as_array()?;
-    if candidates.is_empty() {
-        return None;
-    }
-
-    let candidate = &candidates[0];
-
-    // 提取文本内容
-    let mut content_delta: Option<String> = None;
-    let mut has_image = false;
-    let mut image_data: Option<String> = None;
-
-    if let Some(content) = candidate.get("content") {
-        if let Some(parts) = content.get("parts").and_then(|p| p.as_array()) {
-            for part in parts {
-                // 处理文本
-                if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
-                    content_delta = Some(text.to_string());
-                }
-
-                // 处理图片（inlineData）
-                if let Some(inline_data) =
-                    part.get("inlineData").or_else(|| part.get("inline_data"))
-                {
-                    if let Some(data) = inline_data.get("data").and_then(|d| d.as_str()) {
-                        let mime_type = inline_data
-                            .get("mimeType")
-                            .or_else(|| inline_data.get("mime_type"))
-                            .and_then(|m| m.as_str())
-                            .unwrap_or("image/png");
-
-                        // 将图片作为 markdown 格式的 data URL
-                        let image_url = format!("data:{mime_type};base64,{data}");
-                        image_data = Some(format!("\n\n![Generated Image]({image_url})"));
-                        has_image = true;
-                    }
-                }
-            }
-        }
-    }
-
-    // 检查 finish_reason
-    let finish_reason = candidate
-        .get("finishReason")
-        .and_then(|f| f.as_str())
-        .map(|r| match r {
-            "STOP" => "stop",
-            "MAX_TOKENS" => "length",
-            "SAFETY" => "content_filter",
-            "RECITATION" => "content_filter",
-            _ => "stop",
-        });
-
-    // 如果没有内容变化且没有 finish_reason，跳过
-    if content_delta.is_none() && !has_image && finish_reason.is_none() {
-        return None;
-    }
-
-    // 合并文本和图片内容
-    let final_content = match (content_delta, image_data) {
-        (Some(text), Some(img)) => Some(format!("{text}{img}")),
-        (Some(text), None) => Some(text),
-        (None, Some(img)) => Some(img),
-        (None, None) => None,
-    };
-
-    // 构建 OpenAI 格式的 delta
-    let mut delta = serde_json::json!({});
-    if let Some(content) = final_content {
-        delta["content"] = serde_json::Value::String(content);
-    }
-
-    // 构建完整的 SSE 事件
-    let chunk_id = format!("chatcmpl-{}", uuid::Uuid::new_v4());
-    let created = chrono::Utc::now().timestamp();
-
-    let response = serde_json::json!({
-        "id": chunk_id,
-        "object": "chat.completion.chunk",
-        "created": created,
-        "model": model,
-        "choices": [{
-            "index": 0,
-            "delta": delta,
-            "finish_reason": finish_reason
-        }]
-    });
-
-    Some(format!("data: {response}\n\n"))
+    Ok(OpenAiChunkEncoder::new(model).encode_all(&chunks))
 }
 
 /// 将 OpenAI ChatCompletionResponse 转换为 Anthropic MessagesResponse 格式
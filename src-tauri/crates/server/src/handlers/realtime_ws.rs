@@ -0,0 +1,739 @@
+//! Realtime WebSocket 代理
+//!
+//! 接入 `/v1/realtime` 连接（OpenAI Realtime / Gemini Live），从凭证池挑选上游凭证，
+//! 双向透传音频与文本帧。上游异常断开时按退避策略重连，并重放客户端最近一次的会话配置帧。
+//!
+//! 使用租户 API Key 连接时与 `chat_completions` 一致：建立连接计入租户速率限制，
+//! 只在租户的凭证子集内选择凭证，并把连接结果与上游报告的 Token 用量计入租户统计。
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    extract::{
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::HeaderMap,
+    response::IntoResponse,
+};
+use futures::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio_tungstenite::tungstenite::{
+    self, client::IntoClientRequest, http::HeaderValue, protocol::CloseFrame as UpstreamCloseFrame,
+};
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+use crate::handlers::api::check_request_rate_limit;
+use crate::middleware::tenant::TenantRuntime;
+use crate::AppState;
+use lime_core::models::provider_pool_model::CredentialData;
+
+type UpstreamSocket = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// 上游连续重连的最大次数
+const MAX_RECONNECT_ATTEMPTS: u32 = 3;
+/// 重连退避基准（毫秒），按 2 的幂递增
+const RECONNECT_BASE_DELAY_MS: u64 = 500;
+/// 建立上游连接的超时
+const UPSTREAM_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+
+const OPENAI_DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const OPENAI_DEFAULT_REALTIME_MODEL: &str = "gpt-4o-realtime-preview";
+const GEMINI_DEFAULT_LIVE_MODEL: &str = "gemini-2.0-flash-live-001";
+const GEMINI_DEFAULT_BASE_URL: &str = "https://generativelanguage.googleapis.com";
+const GEMINI_LIVE_PATH: &str =
+    "/ws/google.ai.generativelanguage.v1beta.GenerativeService.BidiGenerateContent";
+/// 浏览器无法设置请求头，OpenAI SDK 通过该前缀的子协议携带密钥
+const API_KEY_SUBPROTOCOL_PREFIX: &str = "openai-insecure-api-key.";
+
+/// Realtime 连接查询参数
+#[derive(Debug, Deserialize, Default)]
+pub struct RealtimeQueryParams {
+    /// 模型名称（OpenAI 拼接到上游 URL，Gemini 由 setup 帧指定）
+    pub model: Option<String>,
+    /// 上游提供商：openai / gemini，缺省时按模型名推断
+    pub provider: Option<String>,
+    /// API 密钥（通过 URL 参数传递）
+    pub api_key: Option<String>,
+    /// Token（通过 URL 参数传递，与 api_key 等效）
+    pub token: Option<String>,
+}
+
+/// Realtime 上游提供商
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RealtimeProvider {
+    OpenAI,
+    Gemini,
+}
+
+impl RealtimeProvider {
+    /// 根据显式指定的提供商或模型名解析上游
+    fn resolve(provider: Option<&str>, model: Option<&str>) -> Option<Self> {
+        match provider.map(|p| p.trim().to_ascii_lowercase()).as_deref() {
+            Some("openai") => Some(Self::OpenAI),
+            Some("gemini") | Some("gemini_api_key") => Some(Self::Gemini),
+            Some(_) => None,
+            None => match model {
+                Some(m) if m.starts_with("gemini") => Some(Self::Gemini),
+                _ => Some(Self::OpenAI),
+            },
+        }
+    }
+
+    /// 凭证池中的提供商类型
+    fn pool_type(self) -> &'static str {
+        match self {
+            Self::OpenAI => "openai",
+            Self::Gemini => "gemini_api_key",
+        }
+    }
+
+    fn default_model(self) -> &'static str {
+        match self {
+            Self::OpenAI => OPENAI_DEFAULT_REALTIME_MODEL,
+            Self::Gemini => GEMINI_DEFAULT_LIVE_MODEL,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::OpenAI => "OpenAI Realtime",
+            Self::Gemini => "Gemini Live",
+        }
+    }
+
+    /// 是否为会话配置帧（OpenAI `session.update` / Gemini `setup`）
+    fn is_setup_frame(self, text: &str) -> bool {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(text) else {
+            return false;
+        };
+        match self {
+            Self::OpenAI => value.get("type").and_then(|t| t.as_str()) == Some("session.update"),
+            Self::Gemini => value.get("setup").is_some(),
+        }
+    }
+
+    /// 从上游事件中提取 Token 用量（OpenAI `response.done` / Gemini `usageMetadata`）
+    fn extract_usage(self, text: &str) -> Option<(u32, u32)> {
+        if !text.contains("usage") {
+            return None;
+        }
+        let value = serde_json::from_str::<serde_json::Value>(text).ok()?;
+        let count = |usage: &serde_json::Value, keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| usage.get(*key).and_then(|v| v.as_u64()))
+                .unwrap_or(0) as u32
+        };
+        match self {
+            Self::OpenAI => {
+                if value.get("type").and_then(|t| t.as_str()) != Some("response.done") {
+                    return None;
+                }
+                let usage = value.get("response")?.get("usage")?;
+                Some((
+                    count(usage, &["input_tokens"]),
+                    count(usage, &["output_tokens"]),
+                ))
+            }
+            Self::Gemini => {
+                let usage = value.get("usageMetadata")?;
+                Some((
+                    count(usage, &["promptTokenCount"]),
+                    count(usage, &["responseTokenCount", "candidatesTokenCount"]),
+                ))
+            }
+        }
+    }
+}
+
+/// 上游连接目标
+#[derive(Debug)]
+struct UpstreamTarget {
+    url: String,
+    headers: Vec<(&'static str, String)>,
+}
+
+/// 单个客户端连接的代理会话状态
+struct RealtimeSession {
+    provider: RealtimeProvider,
+    model: String,
+    /// 使用租户 API Key 连接时的租户
+    tenant: Option<Arc<TenantRuntime>>,
+    /// 最近一次会话配置帧，重连后重放
+    setup_frame: Option<String>,
+    /// 累计重连成功次数
+    reconnects: u32,
+}
+
+impl RealtimeSession {
+    fn capture_setup(&mut self, text: &str) {
+        if self.provider.is_setup_frame(text) {
+            self.setup_frame = Some(text.to_string());
+        }
+    }
+
+    /// 该帧是否为重连时已重放的会话配置帧
+    fn is_setup_replay(&self, frame: &tungstenite::Message) -> bool {
+        matches!(frame, tungstenite::Message::Text(text) if self.setup_frame.as_ref() == Some(text))
+    }
+}
+
+/// Realtime WebSocket 升级处理器
+pub async fn realtime_ws_upgrade(
+    ws: WebSocketUpgrade,
+    State(state): State<AppState>,
+    Query(params): Query<RealtimeQueryParams>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let subprotocol_key = extract_subprotocol_api_key(&headers);
+    let header_key = headers
+        .get("authorization")
+        .or_else(|| headers.get("x-api-key"))
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.strip_prefix("Bearer ").unwrap_or(s).to_string());
+    let key = header_key
+        .or_else(|| params.api_key.clone())
+        .or_else(|| params.token.clone())
+        .or_else(|| subprotocol_key.clone());
+
+    // Realtime 会消耗上游凭证，不允许匿名连接
+    let tenant = match key.as_deref() {
+        Some(k) if k == state.api_key => Ok(None),
+        Some(k) => state.tenant_registry.resolve_api_key(k).map(Some).ok_or(()),
+        None => Err(()),
+    };
+    let Ok(tenant) = tenant else {
+        return axum::http::Response::builder()
+            .status(401)
+            .body(Body::from("Invalid or missing API key"))
+            .unwrap()
+            .into_response();
+    };
+    if let Err(response) = check_request_rate_limit(&state, &headers, tenant.as_deref()) {
+        return response;
+    }
+
+    let Some(provider) =
+        RealtimeProvider::resolve(params.provider.as_deref(), params.model.as_deref())
+    else {
+        return axum::http::Response::builder()
+            .status(400)
+            .body(Body::from("Unsupported realtime provider"))
+            .unwrap()
+            .into_response();
+    };
+    let model = params
+        .model
+        .clone()
+        .unwrap_or_else(|| provider.default_model().to_string());
+
+    let client_info = headers
+        .get("user-agent")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+
+    // 通过子协议携带密钥时，必须回应 `realtime` 子协议才能完成浏览器握手
+    let ws = if subprotocol_key.is_some() {
+        ws.protocols(["realtime"])
+    } else {
+        ws
+    };
+
+    ws.on_upgrade(move |socket| {
+        handle_realtime_socket(socket, state, provider, model, tenant, client_info)
+    })
+    .into_response()
+}
+
+/// 处理 Realtime 客户端连接
+async fn handle_realtime_socket(
+    mut client: WebSocket,
+    state: AppState,
+    provider: RealtimeProvider,
+    model: String,
+    tenant: Option<Arc<TenantRuntime>>,
+    client_info: Option<String>,
+) {
+    let conn_id = uuid::Uuid::new_v4().to_string();
+    if let Err(e) = state
+        .ws_manager
+        .register(conn_id.clone(), client_info.clone())
+    {
+        state.logs.write().await.add(
+            "error",
+            &format!("[REALTIME] Failed to register connection: {}", e.message),
+        );
+        return;
+    }
+
+    state.logs.write().await.add(
+        "info",
+        &format!(
+            "[REALTIME] New connection: {} ({}, model: {}, client: {:?})",
+            &conn_id[..8],
+            provider.label(),
+            model,
+            client_info
+        ),
+    );
+
+    let mut session = RealtimeSession {
+        provider,
+        model,
+        tenant,
+        setup_frame: None,
+        reconnects: 0,
+    };
+
+    let connected = connect_upstream(&state, &session).await;
+    if let Some(tenant) = &session.tenant {
+        tenant.record_result(connected.is_ok());
+    }
+    match connected {
+        Ok(upstream) => relay(&state, &conn_id, &mut client, upstream, &mut session).await,
+        Err(e) => {
+            state.ws_manager.on_error();
+            state.logs.write().await.add(
+                "error",
+                &format!(
+                    "[REALTIME] Connection {} upstream connect failed: {}",
+                    &conn_id[..8],
+                    e
+                ),
+            );
+            let error = serde_json::json!({
+                "type": "error",
+                "error": { "type": "proxy_error", "message": e },
+            });
+            let _ = client.send(Message::Text(error.to_string())).await;
+            let _ = client
+                .send(Message::Close(Some(CloseFrame {
+                    code: 1011,
+                    reason: "Upstream realtime connection failed".into(),
+                })))
+                .await;
+        }
+    }
+
+    state.ws_manager.unregister(&conn_id);
+    state.logs.write().await.add(
+        "info",
+        &format!(
+            "[REALTIME] Connection {} closed (reconnects: {})",
+            &conn_id[..8],
+            session.reconnects
+        ),
+    );
+}
+
+/// 双向透传帧，上游异常断开时重连
+async fn relay(
+    state: &AppState,
+    conn_id: &str,
+    client: &mut WebSocket,
+    mut upstream: UpstreamSocket,
+    session: &mut RealtimeSession,
+) {
+    loop {
+        tokio::select! {
+            incoming = client.recv() => {
+                let msg = match incoming {
+                    Some(Ok(msg)) => msg,
+                    Some(Err(_)) | None => {
+                        let _ = upstream.close(None).await;
+                        break;
+                    }
+                };
+                state.ws_manager.on_message();
+                state.ws_manager.increment_request_count(conn_id);
+
+                if let Message::Text(text) = &msg {
+                    session.capture_setup(text);
+                }
+                let closing = matches!(msg, Message::Close(_));
+                let frame = client_to_upstream(msg);
+                if upstream.send(frame.clone()).await.is_err() && !closing {
+                    match reconnect_upstream(state, conn_id, client, session).await {
+                        Some(mut next) => {
+                            // 重放过 setup 帧时不再重复发送同一帧
+                            let replayed = session.is_setup_replay(&frame);
+                            if !replayed && next.send(frame).await.is_err() {
+                                break;
+                            }
+                            upstream = next;
+                        }
+                        None => break,
+                    }
+                }
+                if closing {
+                    break;
+                }
+            }
+            outgoing = upstream.next() => {
+                match outgoing {
+                    Some(Ok(tungstenite::Message::Close(frame)))
+                        if !should_reconnect(frame.as_ref()) =>
+                    {
+                        let _ = client
+                            .send(Message::Close(frame.map(|f| CloseFrame {
+                                code: f.code.into(),
+                                reason: f.reason,
+                            })))
+                            .await;
+                        break;
+                    }
+                    Some(Ok(tungstenite::Message::Close(_))) | Some(Err(_)) | None => {
+                        match reconnect_upstream(state, conn_id, client, session).await {
+                            Some(next) => upstream = next,
+                            None => break,
+                        }
+                    }
+                    Some(Ok(msg)) => {
+                        if let (Some(tenant), tungstenite::Message::Text(text)) =
+                            (&session.tenant, &msg)
+                        {
+                            if let Some((input, output)) = session.provider.extract_usage(text) {
+                                tenant.record_tokens(input, output);
+                            }
+                        }
+                        let Some(frame) = upstream_to_client(msg) else {
+                            continue;
+                        };
+                        if client.send(frame).await.is_err() {
+                            let _ = upstream.close(None).await;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 按退避策略重连上游，成功后通知客户端；全部失败时关闭客户端连接
+async fn reconnect_upstream(
+    state: &AppState,
+    conn_id: &str,
+    client: &mut WebSocket,
+    session: &mut RealtimeSession,
+) -> Option<UpstreamSocket> {
+    for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+        let delay = RECONNECT_BASE_DELAY_MS * 2u64.pow(attempt - 1);
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+
+        match connect_upstream(state, session).await {
+            Ok(upstream) => {
+                session.reconnects += 1;
+                state.logs.write().await.add(
+                    "info",
+                    &format!(
+                        "[REALTIME] Connection {} reconnected to upstream (attempt {})",
+                        &conn_id[..8],
+                        attempt
+                    ),
+                );
+                let event = serde_json::json!({
+                    "type": "proxy.reconnected",
+                    "attempt": attempt,
+                    "reconnects": session.reconnects,
+                    "session_restored": session.setup_frame.is_some(),
+                });
+                if client.send(Message::Text(event.to_string())).await.is_err() {
+                    return None;
+                }
+                return Some(upstream);
+            }
+            Err(e) => {
+                state.ws_manager.on_error();
+                state.logs.write().await.add(
+                    "warn",
+                    &format!(
+                        "[REALTIME] Connection {} reconnect attempt {}/{} failed: {}",
+                        &conn_id[..8],
+                        attempt,
+                        MAX_RECONNECT_ATTEMPTS,
+                        e
+                    ),
+                );
+            }
+        }
+    }
+
+    let _ = client
+        .send(Message::Close(Some(CloseFrame {
+            code: 1011,
+            reason: "Upstream realtime connection lost".into(),
+        })))
+        .await;
+    None
+}
+
+/// 从凭证池选择凭证并建立上游连接，已捕获的会话配置帧会立即重放
+async fn connect_upstream(
+    state: &AppState,
+    session: &RealtimeSession,
+) -> Result<UpstreamSocket, String> {
+    let db = state
+        .db
+        .as_ref()
+        .ok_or_else(|| "Database not initialized".to_string())?;
+    let pool_type = session.provider.pool_type();
+    let credential = match session
+        .tenant
+        .as_deref()
+        .filter(|t| t.restricts_credentials())
+    {
+        // 租户限定了凭证子集时，只在该子集内选择
+        Some(tenant) => state
+            .pool_service
+            .select_credential_with_filter(db, pool_type, Some(&session.model), None, None, |c| {
+                tenant.allows_credential(c)
+            })?
+            .ok_or_else(|| {
+                format!(
+                    "No available {pool_type} credential for tenant '{}'",
+                    tenant.id()
+                )
+            })?,
+        None => state
+            .pool_service
+            .select_credential(db, pool_type, Some(&session.model))?
+            .ok_or_else(|| format!("No available {pool_type} credential"))?,
+    };
+    let target = build_upstream_target(session.provider, &credential.credential, &session.model)?;
+
+    let mut request = target
+        .url
+        .as_str()
+        .into_client_request()
+        .map_err(|e| format!("Invalid upstream url: {e}"))?;
+    for (name, value) in target.headers {
+        let value = HeaderValue::from_str(&value).map_err(|e| e.to_string())?;
+        request.headers_mut().insert(name, value);
+    }
+
+    let connected = tokio::time::timeout(UPSTREAM_CONNECT_TIMEOUT, connect_async(request))
+        .await
+        .map_err(|_| "Upstream connect timed out".to_string())
+        .and_then(|result| result.map_err(|e| e.to_string()));
+    let mut upstream = match connected {
+        Ok((upstream, _)) => upstream,
+        Err(e) => {
+            let _ = state
+                .pool_service
+                .mark_unhealthy(db, &credential.uuid, Some(&e));
+            return Err(format!("{} connect failed: {e}", session.provider.label()));
+        }
+    };
+
+    let _ = state.pool_service.record_usage(db, &credential.uuid);
+    if let Some(setup) = &session.setup_frame {
+        upstream
+            .send(tungstenite::Message::Text(setup.clone()))
+            .await
+            .map_err(|e| format!("Failed to replay session setup: {e}"))?;
+    }
+    Ok(upstream)
+}
+
+/// 根据凭证构建上游 URL 与请求头
+fn build_upstream_target(
+    provider: RealtimeProvider,
+    credential: &CredentialData,
+    model: &str,
+) -> Result<UpstreamTarget, String> {
+    match (provider, credential) {
+        (RealtimeProvider::OpenAI, CredentialData::OpenAIKey { api_key, base_url }) => {
+            let base = to_ws_scheme(
+                base_url
+                    .as_deref()
+                    .unwrap_or(OPENAI_DEFAULT_BASE_URL)
+                    .trim_end_matches('/'),
+            );
+            Ok(UpstreamTarget {
+                url: format!("{base}/realtime?model={}", urlencoding::encode(model)),
+                headers: vec![
+                    ("authorization", format!("Bearer {api_key}")),
+                    ("openai-beta", "realtime=v1".to_string()),
+                ],
+            })
+        }
+        (
+            RealtimeProvider::Gemini,
+            CredentialData::GeminiApiKey {
+                api_key, base_url, ..
+            },
+        ) => {
+            let base = base_url
+                .as_deref()
+                .unwrap_or(GEMINI_DEFAULT_BASE_URL)
+                .trim_end_matches('/');
+            let base = base
+                .strip_suffix("/v1beta")
+                .or_else(|| base.strip_suffix("/v1"))
+                .unwrap_or(base);
+            Ok(UpstreamTarget {
+                url: format!(
+                    "{}{GEMINI_LIVE_PATH}?key={}",
+                    to_ws_scheme(base),
+                    urlencoding::encode(api_key)
+                ),
+                headers: Vec::new(),
+            })
+        }
+        _ => Err(format!(
+            "Credential type does not match {}",
+            provider.label()
+        )),
+    }
+}
+
+fn to_ws_scheme(base: &str) -> String {
+    if let Some(rest) = base.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = base.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        base.to_string()
+    }
+}
+
+/// 从 `Sec-WebSocket-Protocol` 中提取浏览器携带的 API Key
+fn extract_subprotocol_api_key(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("sec-websocket-protocol")
+        .and_then(|v| v.to_str().ok())?
+        .split(',')
+        .find_map(|p| p.trim().strip_prefix(API_KEY_SUBPROTOCOL_PREFIX))
+        .filter(|k| !k.is_empty())
+        .map(|k| k.to_string())
+}
+
+/// 上游关闭是否属于可恢复的异常（异常断开、服务端错误、重启、稍后重试）
+fn should_reconnect(frame: Option<&UpstreamCloseFrame<'_>>) -> bool {
+    frame.is_some_and(|f| matches!(u16::from(f.code), 1006 | 1011 | 1012 | 1013))
+}
+
+fn client_to_upstream(msg: Message) -> tungstenite::Message {
+    match msg {
+        Message::Text(text) => tungstenite::Message::Text(text),
+        Message::Binary(data) => tungstenite::Message::Binary(data),
+        Message::Ping(data) => tungstenite::Message::Ping(data),
+        Message::Pong(data) => tungstenite::Message::Pong(data),
+        Message::Close(frame) => tungstenite::Message::Close(frame.map(|f| UpstreamCloseFrame {
+            code: f.code.into(),
+            reason: f.reason,
+        })),
+    }
+}
+
+fn upstream_to_client(msg: tungstenite::Message) -> Option<Message> {
+    Some(match msg {
+        tungstenite::Message::Text(text) => Message::Text(text),
+        tungstenite::Message::Binary(data) => Message::Binary(data),
+        tungstenite::Message::Ping(data) => Message::Ping(data),
+        tungstenite::Message::Pong(data) => Message::Pong(data),
+        tungstenite::Message::Close(frame) => Message::Close(frame.map(|f| CloseFrame {
+            code: f.code.into(),
+            reason: f.reason,
+        })),
+        tungstenite::Message::Frame(_) => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_provider() {
+        assert_eq!(
+            RealtimeProvider::resolve(None, Some("gemini-2.0-flash-live-001")),
+            Some(RealtimeProvider::Gemini)
+        );
+        assert_eq!(
+            RealtimeProvider::resolve(None, Some("gpt-4o-realtime-preview")),
+            Some(RealtimeProvider::OpenAI)
+        );
+        assert_eq!(
+            RealtimeProvider::resolve(Some("OpenAI"), Some("gemini-x")),
+            Some(RealtimeProvider::OpenAI)
+        );
+        assert_eq!(RealtimeProvider::resolve(Some("kiro"), None), None);
+    }
+
+    #[test]
+    fn test_build_upstream_target() {
+        let openai = CredentialData::OpenAIKey {
+            api_key: "sk-test".to_string(),
+            base_url: Some("http://localhost:9000/v1/".to_string()),
+        };
+        let target =
+            build_upstream_target(RealtimeProvider::OpenAI, &openai, "gpt-4o-realtime").unwrap();
+        assert_eq!(
+            target.url,
+            "ws://localhost:9000/v1/realtime?model=gpt-4o-realtime"
+        );
+        assert!(target
+            .headers
+            .contains(&("authorization", "Bearer sk-test".to_string())));
+
+        let gemini = CredentialData::GeminiApiKey {
+            api_key: "g-key".to_string(),
+            base_url: Some("https://generativelanguage.googleapis.com/v1beta".to_string()),
+            excluded_models: Vec::new(),
+        };
+        let target = build_upstream_target(RealtimeProvider::Gemini, &gemini, "gemini").unwrap();
+        assert_eq!(
+            target.url,
+            format!("wss://generativelanguage.googleapis.com{GEMINI_LIVE_PATH}?key=g-key")
+        );
+
+        assert!(build_upstream_target(RealtimeProvider::Gemini, &openai, "gemini").is_err());
+    }
+
+    #[test]
+    fn test_capture_setup_and_subprotocol_key() {
+        let mut session = RealtimeSession {
+            provider: RealtimeProvider::OpenAI,
+            model: OPENAI_DEFAULT_REALTIME_MODEL.to_string(),
+            tenant: None,
+            setup_frame: None,
+            reconnects: 0,
+        };
+        session.capture_setup(r#"{"type":"input_audio_buffer.append","audio":""}"#);
+        assert!(session.setup_frame.is_none());
+        let update = r#"{"type":"session.update","session":{"voice":"alloy"}}"#;
+        session.capture_setup(update);
+        assert_eq!(session.setup_frame.as_deref(), Some(update));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "sec-websocket-protocol",
+            "realtime, openai-insecure-api-key.pc-key, openai-beta.realtime-v1"
+                .parse()
+                .unwrap(),
+        );
+        assert_eq!(
+            extract_subprotocol_api_key(&headers).as_deref(),
+            Some("pc-key")
+        );
+    }
+
+    #[test]
+    fn test_extract_usage() {
+        let done = r#"{"type":"response.done","response":{"usage":{"input_tokens":12,"output_tokens":34}}}"#;
+        assert_eq!(RealtimeProvider::OpenAI.extract_usage(done), Some((12, 34)));
+        let delta = r#"{"type":"response.audio.delta","delta":"usage"}"#;
+        assert_eq!(RealtimeProvider::OpenAI.extract_usage(delta), None);
+
+        let gemini = r#"{"serverContent":{"turnComplete":true},"usageMetadata":{"promptTokenCount":5,"responseTokenCount":7}}"#;
+        assert_eq!(RealtimeProvider::Gemini.extract_usage(gemini), Some((5, 7)));
+        assert_eq!(
+            RealtimeProvider::Gemini.extract_usage(r#"{"serverContent":{}}"#),
+            None
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! OpenAI Responses API 处理器
+//!
+//! `/v1/responses` 请求转换为 chat completions 后复用 [`chat_completions`]
+//! （认证、凭证池选择、路由、重试、遥测均与 chat completions 一致），
+//! 成功响应再转换回 Responses 对象或事件流；错误响应原样返回。
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::State,
+    http::{header, HeaderMap},
+    response::Response,
+    Json,
+};
+use lime_core::errors::GatewayErrorCode;
+use lime_core::models::openai_responses::ResponsesRequest;
+use lime_providers::converter::openai_responses::{
+    convert_openai_response_to_responses, convert_responses_to_openai,
+};
+use lime_providers::streaming::ResponsesStreamConverter;
+use lime_server_utils::build_error_response_with_meta;
+
+use super::{
+    attach_stream_usage, chat_completions, parse_chat_completion_body, stream_usage_tracker,
+};
+use crate::AppState;
+
+/// 非流式响应体读取上限
+const MAX_RESPONSE_BODY_BYTES: usize = 64 * 1024 * 1024;
+
+/// OpenAI Responses API 入口
+pub async fn responses(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<ResponsesRequest>,
+) -> Response {
+    if request.previous_response_id.is_some() {
+        return build_error_response_with_meta(
+            400,
+            "previous_response_id is not supported; send the full conversation in input",
+            None,
+            None,
+            Some(GatewayErrorCode::InvalidRequest),
+        );
+    }
+
+    let (chat_request, stream_options, _) =
+        match parse_chat_completion_body(convert_responses_to_openai(&request)) {
+            Ok(parsed) => parsed,
+            Err(response) => return response,
+        };
+    let usage_tracker = stream_usage_tracker(&chat_request, stream_options.as_ref());
+    let response = chat_completions(State(state), headers, Json(chat_request)).await;
+    let response = attach_stream_usage(response, usage_tracker);
+
+    if !response.status().is_success() {
+        return response;
+    }
+    if is_event_stream(&response) {
+        convert_stream_response(response, &request.model)
+    } else {
+        convert_json_response(response, &request.model).await
+    }
+}
+
+fn is_event_stream(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/event-stream"))
+}
+
+/// 将 chat completions SSE 转换为 Responses 事件流
+fn convert_stream_response(response: Response, model: &str) -> Response {
+    use futures::StreamExt;
+
+    let mut converter = ResponsesStreamConverter::new(model);
+    let (parts, body) = response.into_parts();
+    let mut source = body.into_data_stream();
+    let stream = async_stream::stream! {
+        while let Some(chunk) = source.next().await {
+            match chunk {
+                Ok(bytes) => {
+                    let output = converter.process(&bytes);
+                    if !output.is_empty() {
+                        yield Ok(axum::body::Bytes::from(output));
+                    }
+                }
+                Err(e) => {
+                    yield Err(e);
+                    return;
+                }
+            }
+        }
+        yield Ok(axum::body::Bytes::from(converter.finish()));
+    };
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+/// 将非流式 chat completions 响应转换为 Responses 对象
+async fn convert_json_response(response: Response, model: &str) -> Response {
+    let (mut parts, body) = response.into_parts();
+    let chat = match to_bytes(body, MAX_RESPONSE_BODY_BYTES)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| serde_json::from_slice(&bytes).map_err(|e| e.to_string()))
+    {
+        Ok(chat) => chat,
+        Err(e) => {
+            tracing::warn!("[RESPONSES] 解析 chat completions 响应失败: {}", e);
+            return build_error_response_with_meta(
+                502,
+                &format!("Failed to convert upstream response: {e}"),
+                None,
+                None,
+                Some(GatewayErrorCode::UpstreamError),
+            );
+        }
+    };
+
+    let converted = convert_openai_response_to_responses(&chat, model);
+    let body = serde_json::to_vec(&converted).unwrap_or_default();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    parts.headers.insert(
+        header::CONTENT_TYPE,
+        header::HeaderValue::from_static("application/json"),
+    );
+    Response::from_parts(parts, Body::from(body))
+}
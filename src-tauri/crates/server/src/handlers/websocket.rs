@@ -322,6 +322,8 @@ fn gateway_code_name(code: GatewayErrorCode) -> &'static str {
         GatewayErrorCode::RequestConflict => "REQUEST_CONFLICT",
         GatewayErrorCode::RateLimited => "RATE_LIMITED",
         GatewayErrorCode::NoCredentials => "NO_CREDENTIALS",
+        GatewayErrorCode::CostCapExceeded => "COST_CAP_EXCEEDED",
+        GatewayErrorCode::DeadlineExceeded => "DEADLINE_EXCEEDED",
         GatewayErrorCode::UpstreamTimeout => "UPSTREAM_TIMEOUT",
         GatewayErrorCode::UpstreamUnavailable => "UPSTREAM_UNAVAILABLE",
         GatewayErrorCode::UpstreamError => "UPSTREAM_ERROR",
@@ -335,10 +337,13 @@ fn gateway_to_ws_error_code(code: GatewayErrorCode) -> WsErrorCode {
             WsErrorCode::InvalidRequest
         }
         GatewayErrorCode::AuthenticationFailed => WsErrorCode::Unauthorized,
-        GatewayErrorCode::UpstreamTimeout => WsErrorCode::Timeout,
+        GatewayErrorCode::UpstreamTimeout | GatewayErrorCode::DeadlineExceeded => {
+            WsErrorCode::Timeout
+        }
         GatewayErrorCode::InternalError => WsErrorCode::InternalError,
         GatewayErrorCode::RateLimited
         | GatewayErrorCode::NoCredentials
+        | GatewayErrorCode::CostCapExceeded
         | GatewayErrorCode::UpstreamUnavailable
         | GatewayErrorCode::UpstreamError => WsErrorCode::UpstreamError,
     }
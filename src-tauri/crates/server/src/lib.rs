@@ -5,8 +5,10 @@
 pub mod auth;
 pub mod chrome_bridge;
 pub mod client_detector;
+pub mod forward_proxy;
 pub mod middleware;
 
+use crate::middleware::session_affinity::{extract_affinity_session, scope_affinity_session};
 use axum::{
     extract::{DefaultBodyLimit, Path, Query, State},
     http::{header, HeaderMap, HeaderValue, Method, StatusCode},
@@ -19,6 +21,7 @@ use lime_core::config::{
     HotReloadManager, ReloadResult,
 };
 use lime_core::database::dao::provider_pool::ProviderPoolDao;
+use lime_core::database::dao::virtual_model::VirtualModelDao;
 use lime_core::database::DbConnection;
 use lime_core::logger::LogStore;
 use lime_core::models::anthropic::*;
@@ -29,6 +32,7 @@ use lime_credential::CredentialSyncService;
 use lime_infra::injection::Injector;
 use lime_processor::{RequestContext, RequestProcessor};
 use lime_providers::converter::anthropic_to_openai::convert_anthropic_to_openai;
+use lime_providers::converter::logprobs::scope_request_logprobs;
 use lime_providers::providers::antigravity::AntigravityProvider;
 use lime_providers::providers::claude_custom::ClaudeCustomProvider;
 use lime_providers::providers::gemini::GeminiProvider;
@@ -37,7 +41,7 @@ use lime_providers::providers::openai_custom::OpenAICustomProvider;
 use lime_server_utils::{
     build_anthropic_response, build_anthropic_stream_response, build_error_response,
     build_error_response_with_status, build_gemini_cli_request, build_gemini_native_request,
-    models, parse_cw_response,
+    builtin_models, parse_cw_response,
 };
 use lime_services::kiro_event_service::KiroEventService;
 use lime_services::provider_pool_service::ProviderPoolService;
@@ -100,11 +104,88 @@ pub fn record_request_telemetry(
         stats.record(log.clone());
     }
 
+    // 记录租户用量
+    if let Some(tenant) = resolve_context_tenant(state, ctx) {
+        match status {
+            lime_infra::telemetry::RequestStatus::Success => tenant.record_result(true),
+            lime_infra::telemetry::RequestStatus::Retrying => {}
+            _ => tenant.record_result(false),
+        }
+    }
+
+    // 自适应凭证选择学习请求结果（重试中与取消的请求不计入）
+    if let Some(cred_id) = &ctx.credential_id {
+        let success = match status {
+            lime_infra::telemetry::RequestStatus::Success => Some(true),
+            lime_infra::telemetry::RequestStatus::Failed
+            | lime_infra::telemetry::RequestStatus::Timeout => Some(false),
+            _ => None,
+        };
+        let adaptive = state.pool_service.adaptive_selection();
+        if let (Some(success), true) = (success, adaptive.is_enabled()) {
+            adaptive.record_outcome(
+                Some(ctx.resolved_model.as_str()),
+                cred_id,
+                success,
+                ctx.elapsed_ms(),
+            );
+            if let Some(db) = &state.db {
+                adaptive.persist_if_due(db);
+            }
+        }
+    }
+
+    // 失败请求生成快速修复建议
+    if matches!(
+        status,
+        lime_infra::telemetry::RequestStatus::Failed
+            | lime_infra::telemetry::RequestStatus::Timeout
+    ) {
+        if let Some(message) = &sanitized_error {
+            let base_url = match (&state.db, &ctx.credential_id) {
+                (Some(db), Some(cred_id)) => state
+                    .pool_service
+                    .get_by_uuid(db, cred_id)
+                    .ok()
+                    .flatten()
+                    .and_then(|cred| cred.credential.base_url().map(str::to_string)),
+                _ => None,
+            };
+            let context = lime_providers::providers::QuickFixContext {
+                provider: ctx.provider.map(|p| p.to_string()),
+                credential_id: ctx.credential_id.clone(),
+                model: Some(ctx.resolved_model.clone()).filter(|m| !m.is_empty()),
+                base_url,
+            };
+            state.quick_fixes.record(&ctx.request_id, context, message);
+        }
+    }
+
     // 记录到请求日志记录器（用于前端日志列表显示）
     if let Some(logger) = &state.request_logger {
         let _ = logger.record(log.clone());
     }
 
+    // 审计日志（未开启时不记录）
+    state.audit_log.record(
+        state.db.as_ref(),
+        &state.sanitizer,
+        ctx,
+        status,
+        sanitized_error.clone(),
+    );
+
+    // 尾部采样：出错或慢请求保留缓冲的详细追踪
+    state.trace_sampler.finish(ctx, status, sanitized_error);
+
+    // 匿名用量统计（未开启时不记录）
+    if status != lime_infra::telemetry::RequestStatus::Retrying {
+        state.usage_analytics.record_request(ctx.provider);
+        if ctx.is_stream {
+            state.usage_analytics.record_feature("stream");
+        }
+    }
+
     tracing::info!(
         "[TELEMETRY] request_id={} provider={:?} model={} status={:?} duration_ms={}",
         ctx.request_id,
@@ -124,11 +205,24 @@ pub fn record_token_usage(
 ) {
     use lime_infra::telemetry::{TokenSource, TokenUsageRecord};
 
+    // 记录凭证用量（用于配额预测）
+    if let Some(credential_id) = &ctx.credential_id {
+        let tokens = input_tokens.unwrap_or(0) as u64 + output_tokens.unwrap_or(0) as u64;
+        state.quota_manager.record_usage(credential_id, tokens);
+    }
+
     // 只有当至少有一个 Token 值时才记录
     if input_tokens.is_none() && output_tokens.is_none() {
         return;
     }
 
+    state.audit_log.record_tokens(
+        state.db.as_ref(),
+        &ctx.request_id,
+        input_tokens.unwrap_or(0) as u64,
+        output_tokens.unwrap_or(0) as u64,
+    );
+
     let provider = ctx.provider.unwrap_or(lime_core::ProviderType::Kiro);
     let record = TokenUsageRecord::new(
         uuid::Uuid::new_v4().to_string(),
@@ -146,6 +240,55 @@ pub fn record_token_usage(
         tokens.record(record);
     }
 
+    if let Some(tenant) = resolve_context_tenant(state, ctx) {
+        tenant.record_tokens(input_tokens.unwrap_or(0), output_tokens.unwrap_or(0));
+    }
+
+    if let Some(provider) = ctx
+        .get_metadata(middleware::cost_cap::COST_PROVIDER_METADATA_KEY)
+        .and_then(|value| value.as_str())
+    {
+        let cost = state.cost_cap_guard.estimate_cost(
+            state.db.as_ref(),
+            &ctx.resolved_model,
+            input_tokens.unwrap_or(0),
+            output_tokens.unwrap_or(0),
+        );
+        state
+            .cost_cap_guard
+            .record(state.db.as_ref(), provider, cost);
+
+        let now = chrono::Utc::now();
+        let session_id = ctx
+            .get_metadata(middleware::cost_ledger::COST_SESSION_METADATA_KEY)
+            .and_then(|value| value.as_str())
+            .map(str::to_string);
+        state.cost_ledger.record(
+            state.db.as_ref(),
+            lime_core::database::dao::request_cost::RequestCostRecord {
+                request_id: ctx.request_id.clone(),
+                created_at: now.timestamp(),
+                day: now.format("%Y-%m-%d").to_string(),
+                month: now.format("%Y-%m").to_string(),
+                provider: provider.to_string(),
+                credential_id: ctx.credential_id.clone(),
+                model: ctx.resolved_model.clone(),
+                session_id,
+                tenant_id: ctx
+                    .get_metadata(middleware::tenant::TENANT_METADATA_KEY)
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string),
+                input_tokens: input_tokens.unwrap_or(0) as u64,
+                output_tokens: output_tokens.unwrap_or(0) as u64,
+                cost_usd: if state.cost_cap_guard.is_free_provider(provider) {
+                    0.0
+                } else {
+                    cost
+                },
+            },
+        );
+    }
+
     tracing::debug!(
         "[TOKEN] request_id={} input={} output={}",
         ctx.request_id,
@@ -154,6 +297,17 @@ pub fn record_token_usage(
     );
 }
 
+/// 从请求上下文元数据中解析所属租户
+fn resolve_context_tenant(
+    state: &AppState,
+    ctx: &RequestContext,
+) -> Option<Arc<middleware::tenant::TenantRuntime>> {
+    let tenant_id = ctx
+        .get_metadata(middleware::tenant::TENANT_METADATA_KEY)?
+        .as_str()?;
+    state.tenant_registry.get(tenant_id)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerStatus {
     pub running: bool,
@@ -185,6 +339,8 @@ pub struct ResponseCacheDiagnostics {
     pub config: middleware::response_cache::ResponseCacheConfig,
     pub stats: middleware::response_cache::ResponseCacheStats,
     pub hit_rate_percent: f64,
+    /// 按路由的命中统计
+    pub routes: Vec<middleware::response_cache::ResponseCacheRouteStats>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -222,6 +378,7 @@ pub fn build_response_cache_diagnostics(
         config: store.config(),
         stats: store.stats(),
         hit_rate_percent: store.hit_rate_percent(),
+        routes: store.route_stats(),
     }
 }
 
@@ -281,6 +438,8 @@ pub struct ServerState {
     /// 路由器引用（用于动态更新默认 Provider）
     pub router_ref: Option<Arc<RwLock<lime_core::router::Router>>>,
     shutdown_tx: Option<oneshot::Sender<()>>,
+    /// HTTP 正向代理关闭信号（仅在启用正向代理时存在）
+    forward_proxy_shutdown_tx: Option<oneshot::Sender<()>>,
     /// 服务器运行时使用的 API key（启动时从配置复制）
     /// 用于 test_api 命令，确保测试使用的 API key 和服务器一致
     pub running_api_key: Option<String>,
@@ -295,6 +454,28 @@ pub struct ServerState {
     pub request_dedup_store: Arc<middleware::request_dedup::RequestDedupStore>,
     /// 幂等性存储（用于状态统计与运行时共享）
     pub idempotency_store: Arc<middleware::idempotency::IdempotencyStore>,
+    /// 租户注册表（API Key -> 租户命名空间）
+    pub tenant_registry: Arc<middleware::tenant::TenantRegistry>,
+    /// 月度费用上限守卫
+    pub cost_cap_guard: Arc<middleware::cost_cap::CostCapGuard>,
+    /// 请求费用账本（明细 + 预算提醒）
+    pub cost_ledger: Arc<middleware::cost_ledger::CostLedger>,
+    /// 凭证配额管理器（用量预测与主动切换）
+    pub quota_manager: Arc<lime_credential::QuotaManager>,
+    /// 请求追踪尾部采样器
+    pub trace_sampler: Arc<lime_infra::telemetry::TraceSampler>,
+    /// 匿名用量统计（需用户主动开启）
+    pub usage_analytics: Arc<lime_infra::telemetry::UsageAnalytics>,
+    /// 请求审计日志
+    pub audit_log: Arc<middleware::audit_log::AuditLog>,
+    /// 按 API Key 的令牌桶限流器
+    pub api_key_rate_limiter: Arc<middleware::api_key_rate_limit::ApiKeyRateLimiter>,
+    /// 系统提示词护栏注入
+    pub system_prompt_guard: Arc<middleware::system_prompt_guard::SystemPromptGuard>,
+    /// 退化响应检测（空内容等，换凭证重试一次）
+    pub degenerate_detector: Arc<middleware::degenerate_response::DegenerateResponseDetector>,
+    /// 错误快速修复建议
+    pub quick_fixes: Arc<middleware::quick_fix::QuickFixRegistry>,
 }
 
 impl ServerState {
@@ -311,14 +492,34 @@ impl ServerState {
             middleware::request_dedup::RequestDedupConfig::default(),
         ));
         let response_cache_store = Arc::new(middleware::response_cache::ResponseCacheStore::new(
-            middleware::response_cache::ResponseCacheConfig {
-                enabled: config.server.response_cache.enabled,
-                ttl_secs: config.server.response_cache.ttl_secs,
-                max_entries: config.server.response_cache.max_entries,
-                max_body_bytes: config.server.response_cache.max_body_bytes,
-                cacheable_status_codes: config.server.response_cache.cacheable_status_codes.clone(),
-            },
+            (&config.server.response_cache).into(),
+        ));
+        let tenant_registry = Arc::new(middleware::tenant::TenantRegistry::new(&config.tenants));
+        let cost_cap_guard = Arc::new(middleware::cost_cap::CostCapGuard::new(&config.cost_caps));
+        let cost_ledger = Arc::new(middleware::cost_ledger::CostLedger::new(
+            &config.cost_budgets,
+        ));
+        let quota_manager = Arc::new(lime_credential::QuotaManager::new(
+            config.quota_exceeded.clone(),
+        ));
+        let trace_sampler = Arc::new(lime_infra::telemetry::TraceSampler::with_default_file(
+            &config.trace_sampling,
+        ));
+        let usage_analytics = Arc::new(lime_infra::telemetry::UsageAnalytics::with_default_file(
+            &config.usage_analytics,
         ));
+        let audit_log = Arc::new(middleware::audit_log::AuditLog::new(&config.audit_log));
+        let api_key_rate_limiter = Arc::new(
+            middleware::api_key_rate_limit::ApiKeyRateLimiter::new(&config.api_key_rate_limit),
+        );
+        let system_prompt_guard = Arc::new(
+            middleware::system_prompt_guard::SystemPromptGuard::new(&config.system_prompt_guard),
+        );
+        let degenerate_detector = Arc::new(
+            middleware::degenerate_response::DegenerateResponseDetector::new(
+                &config.degenerate_retry,
+            ),
+        );
 
         Self {
             config,
@@ -332,6 +533,7 @@ impl ServerState {
             default_provider_ref,
             router_ref: None,
             shutdown_tx: None,
+            forward_proxy_shutdown_tx: None,
             running_api_key: None,
             running_host: None,
             capability_routing_metrics_store: Arc::new(
@@ -340,6 +542,17 @@ impl ServerState {
             response_cache_store,
             request_dedup_store,
             idempotency_store,
+            tenant_registry,
+            cost_cap_guard,
+            cost_ledger,
+            quota_manager,
+            trace_sampler,
+            usage_analytics,
+            audit_log,
+            api_key_rate_limiter,
+            system_prompt_guard,
+            degenerate_detector,
+            quick_fixes: Arc::new(middleware::quick_fix::QuickFixRegistry::new()),
         }
     }
 
@@ -533,15 +746,81 @@ impl ServerState {
             Arc::new(middleware::capability_routing_metrics::CapabilityRoutingMetricsStore::new());
         self.capability_routing_metrics_store = capability_routing_metrics_store.clone();
         let response_cache_store = Arc::new(middleware::response_cache::ResponseCacheStore::new(
-            middleware::response_cache::ResponseCacheConfig {
-                enabled: config.server.response_cache.enabled,
-                ttl_secs: config.server.response_cache.ttl_secs,
-                max_entries: config.server.response_cache.max_entries,
-                max_body_bytes: config.server.response_cache.max_body_bytes,
-                cacheable_status_codes: config.server.response_cache.cacheable_status_codes.clone(),
-            },
+            (&config.server.response_cache).into(),
         ));
         self.response_cache_store = response_cache_store.clone();
+        self.tenant_registry.reload(&config.tenants);
+        let tenant_registry = self.tenant_registry.clone();
+        self.cost_cap_guard.reload(&config.cost_caps);
+        if let Some(ref db) = db {
+            self.cost_cap_guard.load_usage(db);
+        }
+        let cost_cap_guard = self.cost_cap_guard.clone();
+        self.cost_ledger.reload(&config.cost_budgets);
+        if let Some(ref db) = db {
+            self.cost_ledger.load_usage(db);
+        }
+        let cost_ledger = self.cost_ledger.clone();
+        pool_service
+            .adaptive_selection()
+            .reload(&config.adaptive_selection);
+        if let Some(ref db) = db {
+            pool_service.adaptive_selection().load(db);
+        }
+        let risk = pool_service.risk_controller();
+        risk.reload((&config.rate_limit_cooldown).into());
+        if let Some(ref db) = db {
+            match lime_core::database::lock_db(db).map(|conn| risk.load_profiles(&conn)) {
+                Ok(Ok(loaded)) if loaded > 0 => {
+                    tracing::info!("[凭证池] 已恢复 {} 个凭证的限流节奏", loaded)
+                }
+                Ok(Err(e)) => tracing::warn!("[凭证池] 恢复限流节奏失败: {}", e),
+                _ => {}
+            }
+        }
+        self.quota_manager
+            .reload_soft_limits(&config.quota_exceeded);
+        let quota_manager = self.quota_manager.clone();
+        self.trace_sampler.reload(&config.trace_sampling);
+        let trace_sampler = self.trace_sampler.clone();
+        self.usage_analytics.reload(&config.usage_analytics);
+        let usage_analytics = self.usage_analytics.clone();
+        self.audit_log.reload(&config.audit_log);
+        let audit_log = self.audit_log.clone();
+        self.api_key_rate_limiter.reload(&config.api_key_rate_limit);
+        if let Some(ref db) = db {
+            self.api_key_rate_limiter.load_overrides(db);
+        }
+        let api_key_rate_limiter = self.api_key_rate_limiter.clone();
+        self.system_prompt_guard.reload(&config.system_prompt_guard);
+        let system_prompt_guard = self.system_prompt_guard.clone();
+        self.degenerate_detector.reload(&config.degenerate_retry);
+        let degenerate_detector = self.degenerate_detector.clone();
+        let quick_fixes = self.quick_fixes.clone();
+
+        if config.server.forward_proxy.enabled {
+            let proxy = forward_proxy::ForwardProxy::new(
+                &config.server.forward_proxy,
+                &host,
+                port,
+                &api_key,
+            );
+            // 正向代理会注入服务器 API Key，无论 API 服务器监听何处都只监听回环地址
+            let proxy_addr = format!("127.0.0.1:{}", config.server.forward_proxy.port);
+            let (proxy_tx, proxy_rx) = oneshot::channel();
+            self.forward_proxy_shutdown_tx = Some(proxy_tx);
+            tokio::spawn(async move {
+                match tokio::net::TcpListener::bind(&proxy_addr).await {
+                    Ok(listener) => {
+                        tracing::info!("[FORWARD_PROXY] 正向代理监听于 {}", proxy_addr);
+                        proxy.serve(listener, proxy_rx).await;
+                    }
+                    Err(e) => {
+                        tracing::error!("[FORWARD_PROXY] 绑定 {} 失败: {}", proxy_addr, e);
+                    }
+                }
+            });
+        }
 
         tokio::spawn(async move {
             if let Err(e) = run_server(
@@ -567,6 +846,17 @@ impl ServerState {
                 response_cache_store,
                 request_dedup_store,
                 idempotency_store,
+                tenant_registry,
+                cost_cap_guard,
+                cost_ledger,
+                quota_manager,
+                trace_sampler,
+                usage_analytics,
+                audit_log,
+                api_key_rate_limiter,
+                system_prompt_guard,
+                degenerate_detector,
+                quick_fixes,
                 None, // dev_bridge_callback: 由主 crate 在重新导出层注入
             )
             .await
@@ -588,6 +878,9 @@ impl ServerState {
         if let Some(tx) = self.shutdown_tx.take() {
             let _ = tx.send(());
         }
+        if let Some(tx) = self.forward_proxy_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
         self.running = false;
         self.start_time = None;
         self.running_api_key = None;
@@ -651,6 +944,42 @@ pub struct AppState {
         Arc<middleware::capability_routing_metrics::CapabilityRoutingMetricsStore>,
     /// 凭证清理器
     pub sanitizer: Arc<lime_core::sanitizer::CredentialSanitizer>,
+    /// 租户注册表
+    pub tenant_registry: Arc<middleware::tenant::TenantRegistry>,
+    /// 月度费用上限守卫
+    pub cost_cap_guard: Arc<middleware::cost_cap::CostCapGuard>,
+    /// 请求费用账本（明细 + 预算提醒）
+    pub cost_ledger: Arc<middleware::cost_ledger::CostLedger>,
+    /// 凭证配额管理器（用量预测与主动切换）
+    pub quota_manager: Arc<lime_credential::QuotaManager>,
+    /// 请求追踪尾部采样器
+    pub trace_sampler: Arc<lime_infra::telemetry::TraceSampler>,
+    /// 匿名用量统计（需用户主动开启）
+    pub usage_analytics: Arc<lime_infra::telemetry::UsageAnalytics>,
+    /// 请求审计日志
+    pub audit_log: Arc<middleware::audit_log::AuditLog>,
+    /// 按 API Key 的令牌桶限流器
+    pub api_key_rate_limiter: Arc<middleware::api_key_rate_limit::ApiKeyRateLimiter>,
+    /// 系统提示词护栏注入
+    pub system_prompt_guard: Arc<middleware::system_prompt_guard::SystemPromptGuard>,
+    /// 退化响应检测（空内容等，换凭证重试一次）
+    pub degenerate_detector: Arc<middleware::degenerate_response::DegenerateResponseDetector>,
+    /// 错误快速修复建议
+    pub quick_fixes: Arc<middleware::quick_fix::QuickFixRegistry>,
+    /// 上下文窗口修剪配置
+    pub context_trim: Arc<lime_core::config::ContextTrimSettings>,
+    /// 上下文窗口不足时的模型自动升级配置
+    pub context_upgrade: Arc<lime_core::config::ContextUpgradeSettings>,
+    /// 按路由的认证配置（配置热重载时更新）
+    pub route_auth: Arc<parking_lot::RwLock<lime_core::config::RouteAuthSettings>>,
+    /// SSE 转发流控配置
+    pub sse_flow_control: Arc<lime_core::config::SseFlowControlSettings>,
+    /// 请求截止时间配置
+    pub request_deadline: Arc<lime_core::config::RequestDeadlineSettings>,
+    /// 协议转换损失检测配置
+    pub conversion_loss: Arc<lime_core::config::ConversionLossSettings>,
+    /// 是否在请求追踪中记录请求体（`logging.include_request_body`，用于分享包）
+    pub include_request_body: bool,
 }
 
 /// 启动配置文件监控
@@ -671,6 +1000,7 @@ async fn start_config_watcher(
     logs: Arc<RwLock<LogStore>>,
     db: Option<DbConnection>,
     config_manager: Option<Arc<std::sync::RwLock<ConfigManager>>>,
+    route_auth: Arc<parking_lot::RwLock<lime_core::config::RouteAuthSettings>>,
 ) -> Option<FileWatcher> {
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<FileChangeEvent>();
 
@@ -726,6 +1056,9 @@ async fn start_config_watcher(
                         let new_config = manager.config();
                         update_processor_config(&processor_clone, &new_config).await;
 
+                        // 更新按路由的认证规则（与监听地址的兼容性已在热重载校验中检查）
+                        *route_auth.write() = new_config.server.route_auth.clone();
+
                         // 同步凭证池
                         if let (Some(ref db), Some(ref cfg_manager)) =
                             (&db_clone, &config_manager_clone)
@@ -851,6 +1184,21 @@ async fn update_processor_config(processor: &RequestProcessor, config: &Config)
         );
     }
 
+    // 更新故障转移链
+    processor
+        .failover_chains
+        .reload(&config.routing.failover_chains);
+    tracing::debug!(
+        "[HOT_RELOAD] 故障转移链已更新: enabled={}, {} 条链",
+        config.routing.failover_chains.enabled,
+        config.routing.failover_chains.chains.len()
+    );
+
+    // 更新端点切换配置
+    processor
+        .endpoint_fallback
+        .reload(&config.routing.endpoint_fallback);
+
     // 注意：重试配置目前不支持热更新，因为 Retrier 是不可变的
     // 如果需要更新重试配置，需要重启服务器
     tracing::debug!(
@@ -868,10 +1216,10 @@ async fn update_processor_config(processor: &RequestProcessor, config: &Config)
 ///
 /// # 同步策略
 ///
-/// - 从配置中加载所有凭证
-/// - 对于配置中存在但数据库中不存在的凭证，添加到数据库
-/// - 对于配置中存在且数据库中也存在的凭证，更新数据库中的记录
+/// - 从配置中加载所有凭证，并发校验（`DEFAULT_IMPORT_CONCURRENCY`）
+/// - 校验通过的凭证按 UUID 插入或更新到数据库，校验失败的跳过并记录
 /// - 对于数据库中存在但配置中不存在的凭证，保留（不删除，避免丢失运行时状态）
+/// - 中途中断时保留导入状态，下次同步同一份配置时只处理剩余凭证
 async fn sync_credential_pool_from_config(
     db: &DbConnection,
     config_manager: &Arc<std::sync::RwLock<ConfigManager>>,
@@ -880,38 +1228,39 @@ async fn sync_credential_pool_from_config(
     // 创建凭证同步服务
     let sync_service = CredentialSyncService::new(config_manager.clone());
 
-    // 从配置加载凭证
-    let credentials = sync_service.load_from_config().map_err(|e| e.to_string())?;
-
-    let conn = lime_core::database::lock_db(db)?;
-    let mut synced_count = 0;
-
-    for cred in &credentials {
-        // 检查凭证是否已存在
-        let existing =
-            ProviderPoolDao::get_by_uuid(&conn, &cred.uuid).map_err(|e| e.to_string())?;
+    let report = sync_service
+        .import_from_config(
+            lime_credential::DEFAULT_IMPORT_CONCURRENCY,
+            |cred| {
+                let conn = lime_core::database::lock_db(db)?;
+                ProviderPoolDao::upsert(&conn, cred).map_err(|e| e.to_string())?;
+                tracing::debug!(
+                    "[HOT_RELOAD] 同步凭证: {} ({})",
+                    cred.uuid,
+                    cred.provider_type
+                );
+                Ok(())
+            },
+            |progress| {
+                tracing::debug!(
+                    "[HOT_RELOAD] 凭证同步进度: {}/{}",
+                    progress.processed,
+                    progress.total
+                );
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())?;
 
-        if existing.is_some() {
-            // 更新现有凭证
-            ProviderPoolDao::update(&conn, cred).map_err(|e| e.to_string())?;
-            tracing::debug!(
-                "[HOT_RELOAD] 更新凭证: {} ({})",
-                cred.uuid,
-                cred.provider_type
-            );
-        } else {
-            // 添加新凭证
-            ProviderPoolDao::insert(&conn, cred).map_err(|e| e.to_string())?;
-            tracing::debug!(
-                "[HOT_RELOAD] 添加凭证: {} ({})",
-                cred.uuid,
-                cred.provider_type
-            );
-        }
-        synced_count += 1;
+    if !report.errors.is_empty() {
+        tracing::warn!(
+            "[HOT_RELOAD] {} 个凭证校验失败，已跳过: {:?}",
+            report.errors.len(),
+            report.errors
+        );
     }
 
-    Ok(synced_count)
+    Ok(report.progress.succeeded + report.progress.resumed)
 }
 
 /// 开发桥接启动回调类型
@@ -942,6 +1291,17 @@ async fn run_server(
     response_cache_store: Arc<middleware::response_cache::ResponseCacheStore>,
     request_dedup_store: Arc<middleware::request_dedup::RequestDedupStore>,
     idempotency_store: Arc<middleware::idempotency::IdempotencyStore>,
+    tenant_registry: Arc<middleware::tenant::TenantRegistry>,
+    cost_cap_guard: Arc<middleware::cost_cap::CostCapGuard>,
+    cost_ledger: Arc<middleware::cost_ledger::CostLedger>,
+    quota_manager: Arc<lime_credential::QuotaManager>,
+    trace_sampler: Arc<lime_infra::telemetry::TraceSampler>,
+    usage_analytics: Arc<lime_infra::telemetry::UsageAnalytics>,
+    audit_log: Arc<middleware::audit_log::AuditLog>,
+    api_key_rate_limiter: Arc<middleware::api_key_rate_limit::ApiKeyRateLimiter>,
+    system_prompt_guard: Arc<middleware::system_prompt_guard::SystemPromptGuard>,
+    degenerate_detector: Arc<middleware::degenerate_response::DegenerateResponseDetector>,
+    quick_fixes: Arc<middleware::quick_fix::QuickFixRegistry>,
     dev_bridge_callback: Option<DevBridgeCallback>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let base_url = format!("http://{host}:{port}");
@@ -967,6 +1327,16 @@ async fn run_server(
         }
     }
 
+    // 从配置初始化故障转移链与端点切换
+    if let Some(cfg) = &config {
+        processor
+            .failover_chains
+            .reload(&cfg.routing.failover_chains);
+        processor
+            .endpoint_fallback
+            .reload(&cfg.routing.endpoint_fallback);
+    }
+
     // 从配置初始化 Router 的默认 Provider
     if let Some(cfg) = &config {
         let default_provider_str = &cfg.routing.default_provider;
@@ -1038,6 +1408,42 @@ async fn run_server(
             .map(|c| c.models.providers.clone())
             .unwrap_or_default(),
     );
+    let context_trim = Arc::new(
+        config
+            .as_ref()
+            .map(|c| c.conversation.context_trim.clone())
+            .unwrap_or_default(),
+    );
+    let context_upgrade = Arc::new(
+        config
+            .as_ref()
+            .map(|c| c.routing.context_upgrade.clone())
+            .unwrap_or_default(),
+    );
+    let sse_flow_control = Arc::new(
+        config
+            .as_ref()
+            .map(|c| c.server.sse_flow_control.clone())
+            .unwrap_or_default(),
+    );
+    let request_deadline = Arc::new(
+        config
+            .as_ref()
+            .map(|c| c.server.request_deadline.clone())
+            .unwrap_or_default(),
+    );
+    let conversion_loss = Arc::new(
+        config
+            .as_ref()
+            .map(|c| c.server.conversion_loss.clone())
+            .unwrap_or_default(),
+    );
+    let route_auth = Arc::new(parking_lot::RwLock::new(
+        config
+            .as_ref()
+            .map(|c| c.server.route_auth.clone())
+            .unwrap_or_default(),
+    ));
 
     // 创建 Kiro 事件服务
     let kiro_event_service = Arc::new(KiroEventService::new());
@@ -1051,6 +1457,9 @@ async fn run_server(
         .as_ref()
         .map(|c| c.retry.auto_switch_provider)
         .unwrap_or(true);
+    let include_request_body = config
+        .as_ref()
+        .is_some_and(|c| c.logging.include_request_body);
     let state = AppState {
         api_key: api_key.to_string(),
         base_url,
@@ -1085,6 +1494,24 @@ async fn run_server(
         response_cache_store,
         capability_routing_metrics_store,
         sanitizer: Arc::new(lime_core::sanitizer::CredentialSanitizer::with_defaults()),
+        tenant_registry,
+        cost_cap_guard,
+        cost_ledger,
+        quota_manager,
+        trace_sampler,
+        usage_analytics,
+        audit_log,
+        api_key_rate_limiter,
+        system_prompt_guard,
+        degenerate_detector,
+        quick_fixes,
+        context_trim,
+        context_upgrade,
+        route_auth,
+        sse_flow_control,
+        request_deadline,
+        conversion_loss,
+        include_request_body,
     };
 
     // ========== 开发模式：通过回调启动桥接服务器 ==========
@@ -1101,6 +1528,7 @@ async fn run_server(
             logs_clone,
             db_clone,
             config_manager,
+            state.route_auth.clone(),
         )
         .await
     } else {
@@ -1137,6 +1565,24 @@ async fn run_server(
             get(handlers::credentials_get_token),
         );
 
+    // 管理 API 路由（用于命令行工具）
+    let admin_api_routes = Router::new()
+        .route(
+            "/v1/admin/credentials",
+            get(handlers::admin_list_credentials),
+        )
+        .route("/v1/admin/logs", get(handlers::admin_logs))
+        .route("/v1/admin/sessions", get(handlers::admin_list_sessions))
+        .route(
+            "/v1/admin/sessions/:id/export",
+            get(handlers::admin_export_session),
+        )
+        .route("/v1/admin/skills", get(handlers::admin_list_skills))
+        .route(
+            "/v1/admin/skills/:name/run",
+            post(handlers::admin_run_skill),
+        );
+
     let allowed_origins = vec![
         HeaderValue::from_static("http://localhost:1420"),
         HeaderValue::from_static("http://127.0.0.1:1420"),
@@ -1162,29 +1608,25 @@ async fn run_server(
             header::CONTENT_TYPE,
             header::ACCEPT,
             header::ORIGIN,
-        ]);
+            header::HeaderName::from_static(lime_core::processor::UPSTREAM_REQUEST_ID_HEADER),
+        ])
+        // 允许前端读取请求 ID，用于关联错误与审计日志
+        .expose_headers([header::HeaderName::from_static(
+            lime_core::processor::REQUEST_ID_HEADER,
+        )]);
 
     let app = Router::new()
         .route("/health", get(health))
         .route("/cache", get(cache_diagnostics))
         .route("/stats", get(stats_diagnostics))
-        .route("/v1/models", get(models))
+        .route("/v1/models", get(list_models))
+        .route("/v1/tools/native", get(native_tools))
         .route("/v1/routes", get(list_routes))
-        .route("/v1/chat/completions", post(
-            |State(state): State<AppState>,
-             headers: HeaderMap,
-             Json(request): Json<lime_core::models::openai::ChatCompletionRequest>| async {
-                handlers::chat_completions(State(state), headers, Json(request)).await
-            }
-        ))
-        .route("/v1/messages", post(
-            |State(state): State<AppState>,
-             headers: HeaderMap,
-             Json(request): Json<AnthropicMessagesRequest>| async {
-                handlers::anthropic_messages(State(state), headers, Json(request)).await
-            }
-        ))
+        .route("/v1/chat/completions", post(chat_completions_route))
+        .route("/v1/messages", post(anthropic_messages_route))
         .route("/v1/messages/count_tokens", post(count_tokens))
+        .route("/v1/estimate", post(handlers::estimate_request_cost))
+        .route("/v1/responses", post(handlers::responses))
         // 图像生成 API 路由
         .route(
             "/v1/images/generations",
@@ -1193,6 +1635,7 @@ async fn run_server(
         // WebSocket 路由
         .route("/v1/ws", get(handlers::ws_upgrade_handler))
         .route("/ws", get(handlers::ws_upgrade_handler))
+        .route("/v1/realtime", get(handlers::realtime_ws_upgrade))
         .route(
             "/lime-chrome-observer/:lime_key",
             get(handlers::chrome_observer_ws_upgrade),
@@ -1214,6 +1657,52 @@ async fn run_server(
         .merge(kiro_api_routes)
         // 凭证 API 路由（用于 aster Agent 集成）
         .merge(credentials_api_routes)
+        // 管理 API 路由（用于命令行工具）
+        .merge(admin_api_routes)
+        // 号池限流响应头合成（供下游 SDK 退避）
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::pool_rate_limit_headers::synthesize_pool_rate_limit_headers,
+        ))
+        // 系统提示词护栏注入（按 API Key / 租户 / 路由匹配策略）
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::system_prompt_guard::inject_system_prompt_guard,
+        ))
+        // 协议转换损失检测（仅严格模式）
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::conversion_loss::detect_conversion_loss,
+        ))
+        // 按 API Key 的令牌桶限流（位于认证之内，仅统计已认证的 Key）
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::api_key_rate_limit::enforce_api_key_rate_limit,
+        ))
+        // 按路由认证（none / api_key / os_user）
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::route_auth::enforce_route_auth,
+        ))
+        // 请求截止时间（作用域与流式响应收尾，位于流控之内）
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::request_deadline::apply_request_deadline,
+        ))
+        // SSE 转发流控（积压合并与超限终止）
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::sse_flow_control::apply_sse_flow_control,
+        ))
+        // 错误响应附带快速修复建议（位于请求 ID 作用域内）
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::quick_fix::attach_quick_fixes,
+        ))
+        // 请求 ID（作用域、tracing span 与 x-lime-request-id 响应头）
+        .layer(axum::middleware::from_fn(
+            middleware::request_id::assign_request_id,
+        ))
         .layer(cors_layer)
         .layer(DefaultBodyLimit::max(body_limit))
         .layer(TimeoutLayer::with_status_code(
@@ -1232,11 +1721,15 @@ async fn run_server(
 
     tracing::info!("Server listening on {}", addr);
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(async move {
-            let _ = shutdown.await;
-        })
-        .await?;
+    // 携带对端地址，供 os_user 认证校验
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move {
+        let _ = shutdown.await;
+    })
+    .await?;
 
     Ok(())
 }
@@ -1321,6 +1814,49 @@ async fn cache_diagnostics(State(state): State<AppState>) -> Response {
         .into_response()
 }
 
+/// 模型列表：内置模型 + 已启用的虚拟模型
+async fn list_models(State(state): State<AppState>) -> Response {
+    let virtual_models = state
+        .db
+        .as_ref()
+        .and_then(|db| lime_core::database::lock_db(db).ok())
+        .and_then(|conn| VirtualModelDao::list(&conn).ok())
+        .unwrap_or_default();
+    let mut data = builtin_models();
+    data.extend(
+        virtual_models
+            .into_iter()
+            .filter(|vm| vm.enabled)
+            .map(|vm| {
+                serde_json::json!({
+                    "id": vm.name,
+                    "object": "model",
+                    "owned_by": "lime",
+                    "root": vm.model,
+                })
+            }),
+    );
+    Json(serde_json::json!({ "object": "list", "data": data })).into_response()
+}
+
+/// 列出当前可用的 Provider 原生工具（如联网搜索）
+///
+/// 仅当存在已开启 `native_web_search` 的 Anthropic / Gemini 凭证时才会列出，
+/// 客户端可据此决定是否在请求中声明 `web_search` 工具。
+async fn native_tools(State(state): State<AppState>) -> Response {
+    let credentials = state
+        .db
+        .as_ref()
+        .and_then(|db| lime_core::database::lock_db(db).ok())
+        .and_then(|conn| ProviderPoolDao::get_all(&conn).ok())
+        .unwrap_or_default();
+    Json(serde_json::json!({
+        "object": "list",
+        "data": lime_providers::converter::native_web_search::available_native_tools(&credentials)
+    }))
+    .into_response()
+}
+
 async fn stats_diagnostics(
     State(state): State<AppState>,
     Query(query): Query<StatsQuery>,
@@ -1812,6 +2348,11 @@ async fn list_routes(State(state): State<AppState>) -> impl IntoResponse {
                 protocol: "openai".to_string(),
                 url: format!("{display_base_url}/v1/chat/completions"),
             },
+            lime_core::models::route_model::RouteEndpoint {
+                path: "/v1/responses".to_string(),
+                protocol: "openai".to_string(),
+                url: format!("{display_base_url}/v1/responses"),
+            },
         ],
         tags: vec!["默认".to_string()],
         enabled: true,
@@ -1914,13 +2455,68 @@ async fn anthropic_messages_with_selector(
     }
 }
 
+/// OpenAI chat completions 入口
+///
+/// 先解析原始请求体以取得 `stream_options` 与 logprobs 选项，再交给 `handlers::chat_completions`。
+async fn chat_completions_route(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(raw): Json<serde_json::Value>,
+) -> Response {
+    let session_id = extract_affinity_session(&headers, &raw);
+    let (request, stream_options, logprobs) = match handlers::parse_chat_completion_body(raw) {
+        Ok(parsed) => parsed,
+        Err(response) => return response,
+    };
+    let usage_tracker = handlers::stream_usage_tracker(&request, stream_options.as_ref());
+    let response = scope_affinity_session(
+        session_id,
+        scope_request_logprobs(
+            logprobs,
+            handlers::chat_completions(State(state), headers, Json(request)),
+        ),
+    )
+    .await;
+    handlers::attach_stream_usage(response, usage_tracker)
+}
+
+/// Anthropic messages 入口
+///
+/// 先从原始请求体取得会话 ID（`metadata.user_id` 未在请求模型中建模），再交给 `handlers::anthropic_messages`。
+async fn anthropic_messages_route(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(raw): Json<serde_json::Value>,
+) -> Response {
+    let session_id = extract_affinity_session(&headers, &raw);
+    let request = match serde_json::from_value::<AnthropicMessagesRequest>(raw) {
+        Ok(request) => request,
+        Err(e) => {
+            return (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("Failed to deserialize the JSON body into the target type: {e}"),
+            )
+                .into_response()
+        }
+    };
+    scope_affinity_session(
+        session_id,
+        handlers::anthropic_messages(State(state), headers, Json(request)),
+    )
+    .await
+}
+
 /// 带选择器的 OpenAI chat completions 处理
 async fn chat_completions_with_selector(
     State(state): State<AppState>,
     Path(selector): Path<String>,
     headers: HeaderMap,
-    Json(request): Json<ChatCompletionRequest>,
+    Json(raw): Json<serde_json::Value>,
 ) -> Response {
+    let (request, stream_options, logprobs) = match handlers::parse_chat_completion_body(raw) {
+        Ok(parsed) => parsed,
+        Err(response) => return response,
+    };
     if let Err(e) = handlers::verify_api_key(&headers, &state.api_key).await {
         state.logs.write().await.add(
             "warn",
@@ -1970,7 +2566,13 @@ async fn chat_completions_with_selector(
             );
 
             // 注意：这里没有 Flow 捕获，因为是通过 selector 路由的请求
-            handlers::call_provider_openai(&state, &cred, &request, None).await
+            let usage_tracker = handlers::stream_usage_tracker(&request, stream_options.as_ref());
+            let response = scope_request_logprobs(
+                logprobs,
+                handlers::call_provider_openai(&state, &cred, &request, None),
+            )
+            .await;
+            handlers::attach_stream_usage(response, usage_tracker)
         }
         None => {
             // 不再回退到默认 provider，直接返回错误
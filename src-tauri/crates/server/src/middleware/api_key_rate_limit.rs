@@ -0,0 +1,311 @@
+//! 按 API Key 的令牌桶限流中间件
+//!
+//! 与按客户端的滑动窗口限流（`rate_limit`）相互独立：每个 API Key 一个令牌桶，
+//! 容量为突发上限（burst），按每分钟速率匀速补充。单个 Key 的覆盖配置保存在
+//! 数据库 `api_key_rate_limits` 表中（以 Key 指纹为主键）。
+//!
+//! 受限路由的响应附带 `X-RateLimit-Limit` / `X-RateLimit-Remaining` /
+//! `X-RateLimit-Reset`（桶补满所需秒数），超限时返回 429 与 `Retry-After`。
+
+use crate::middleware::route_auth::{is_known_api_key, request_api_key};
+use crate::AppState;
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use lime_core::config::ApiKeyRateLimitSettings;
+use lime_core::database::dao::api_key_rate_limit::{api_key_fingerprint, ApiKeyRateLimitDao};
+use lime_core::database::{lock_db, DbConnection};
+use lime_core::errors::GatewayErrorCode;
+use lime_server_utils::build_error_response_with_meta;
+use parking_lot::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// 令牌桶参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBucketLimit {
+    pub requests_per_minute: u32,
+    pub burst: u32,
+}
+
+impl TokenBucketLimit {
+    fn capacity(&self) -> f64 {
+        self.burst.max(1) as f64
+    }
+
+    fn tokens_per_sec(&self) -> f64 {
+        self.requests_per_minute.max(1) as f64 / 60.0
+    }
+
+    fn secs_to_refill(&self, tokens: f64) -> Duration {
+        Duration::from_secs_f64(tokens.max(0.0) / self.tokens_per_sec())
+    }
+}
+
+impl From<&ApiKeyRateLimitSettings> for TokenBucketLimit {
+    fn from(settings: &ApiKeyRateLimitSettings) -> Self {
+        Self {
+            requests_per_minute: settings.requests_per_minute,
+            burst: settings.burst,
+        }
+    }
+}
+
+/// 一次限流检查的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    /// 桶容量
+    pub limit: u32,
+    /// 本次请求后剩余的令牌数
+    pub remaining: u32,
+    /// 桶补满所需时间
+    pub reset_after: Duration,
+    /// 被限流时，获得下一个令牌所需时间
+    pub retry_after: Option<Duration>,
+}
+
+impl RateLimitDecision {
+    /// 写入 `X-RateLimit-*` 与 `Retry-After` 响应头
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        headers.insert("x-ratelimit-limit", HeaderValue::from(self.limit));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from(self.remaining));
+        headers.insert(
+            "x-ratelimit-reset",
+            HeaderValue::from(ceil_secs(self.reset_after)),
+        );
+        if let Some(retry_after) = self.retry_after {
+            headers.insert(
+                header::RETRY_AFTER,
+                HeaderValue::from(ceil_secs(retry_after).max(1)),
+            );
+        }
+    }
+}
+
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs_f64().ceil() as u64
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+/// 按 API Key 指纹分桶的令牌桶限流器
+pub struct ApiKeyRateLimiter {
+    settings: RwLock<ApiKeyRateLimitSettings>,
+    /// Key 指纹 -> 覆盖配置
+    overrides: RwLock<HashMap<String, TokenBucketLimit>>,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl ApiKeyRateLimiter {
+    pub fn new(settings: &ApiKeyRateLimitSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings.clone()),
+            overrides: RwLock::new(HashMap::new()),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn reload(&self, settings: &ApiKeyRateLimitSettings) {
+        *self.settings.write() = settings.clone();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.read().enabled
+    }
+
+    /// 从数据库加载全部覆盖配置（替换内存中的覆盖）
+    pub fn load_overrides(&self, db: &DbConnection) {
+        let rows = match lock_db(db)
+            .and_then(|conn| ApiKeyRateLimitDao::list(&conn).map_err(|e| e.to_string()))
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::warn!("[API_KEY_RATE_LIMIT] 加载限流覆盖配置失败: {}", e);
+                return;
+            }
+        };
+        *self.overrides.write() = rows
+            .into_iter()
+            .map(|row| {
+                (
+                    row.key_fingerprint,
+                    TokenBucketLimit {
+                        requests_per_minute: row.requests_per_minute,
+                        burst: row.burst,
+                    },
+                )
+            })
+            .collect();
+    }
+
+    pub fn set_override(&self, key_fingerprint: &str, limit: TokenBucketLimit) {
+        self.overrides
+            .write()
+            .insert(key_fingerprint.to_string(), limit);
+    }
+
+    pub fn remove_override(&self, key_fingerprint: &str) {
+        self.overrides.write().remove(key_fingerprint);
+    }
+
+    /// 消耗一个令牌，未启用时返回 None
+    pub fn check(&self, key_fingerprint: &str) -> Option<RateLimitDecision> {
+        self.check_at(key_fingerprint, Instant::now())
+    }
+
+    fn check_at(&self, key_fingerprint: &str, now: Instant) -> Option<RateLimitDecision> {
+        let limit = {
+            let settings = self.settings.read();
+            if !settings.enabled {
+                return None;
+            }
+            self.overrides
+                .read()
+                .get(key_fingerprint)
+                .copied()
+                .unwrap_or_else(|| TokenBucketLimit::from(&*settings))
+        };
+        let capacity = limit.capacity();
+
+        let mut buckets = self.buckets.lock();
+        let bucket = buckets
+            .entry(key_fingerprint.to_string())
+            .or_insert(Bucket {
+                tokens: capacity,
+                refilled_at: now,
+            });
+        let elapsed = now.saturating_duration_since(bucket.refilled_at);
+        bucket.tokens =
+            (bucket.tokens + elapsed.as_secs_f64() * limit.tokens_per_sec()).min(capacity);
+        bucket.refilled_at = now;
+
+        let allowed = bucket.tokens >= 1.0;
+        if allowed {
+            bucket.tokens -= 1.0;
+        }
+        Some(RateLimitDecision {
+            allowed,
+            limit: capacity as u32,
+            remaining: bucket.tokens.floor() as u32,
+            reset_after: limit.secs_to_refill(capacity - bucket.tokens),
+            retry_after: (!allowed).then(|| limit.secs_to_refill(1.0 - bucket.tokens)),
+        })
+    }
+}
+
+/// 按 API Key 限流中间件（仅作用于携带已知 API Key 的 `/v1/` 路由）
+pub async fn enforce_api_key_rate_limit(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !state.api_key_rate_limiter.is_enabled() || !request.uri().path().contains("/v1/") {
+        return next.run(request).await;
+    }
+    let Some(key) = request_api_key(request.headers(), request.uri().query())
+        .filter(|key| is_known_api_key(&state, key))
+    else {
+        return next.run(request).await;
+    };
+    let Some(decision) = state.api_key_rate_limiter.check(&api_key_fingerprint(&key)) else {
+        return next.run(request).await;
+    };
+
+    let mut response = if decision.allowed {
+        next.run(request).await
+    } else {
+        let retry_after = decision.retry_after.map(ceil_secs).unwrap_or(1).max(1);
+        tracing::debug!(
+            "[API_KEY_RATE_LIMIT] 限流 {}，{} 秒后重试",
+            request.uri().path(),
+            retry_after
+        );
+        build_error_response_with_meta(
+            StatusCode::TOO_MANY_REQUESTS.as_u16(),
+            &format!("API key rate limit exceeded. Retry after {retry_after} seconds"),
+            None,
+            None,
+            Some(GatewayErrorCode::RateLimited),
+        )
+    };
+    decision.apply_headers(response.headers_mut());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests_per_minute: u32, burst: u32) -> ApiKeyRateLimiter {
+        ApiKeyRateLimiter::new(&ApiKeyRateLimitSettings {
+            enabled: true,
+            requests_per_minute,
+            burst,
+        })
+    }
+
+    #[test]
+    fn test_disabled_returns_none() {
+        let limiter = ApiKeyRateLimiter::new(&ApiKeyRateLimitSettings::default());
+        assert!(limiter.check("key").is_none());
+    }
+
+    #[test]
+    fn test_burst_then_refill() {
+        let limiter = limiter(60, 3);
+        let start = Instant::now();
+
+        for expected_remaining in [2, 1, 0] {
+            let decision = limiter.check_at("key", start).unwrap();
+            assert!(decision.allowed);
+            assert_eq!(decision.limit, 3);
+            assert_eq!(decision.remaining, expected_remaining);
+        }
+
+        let limited = limiter.check_at("key", start).unwrap();
+        assert!(!limited.allowed);
+        assert_eq!(limited.retry_after, Some(Duration::from_secs(1)));
+        assert_eq!(limited.reset_after, Duration::from_secs(3));
+
+        // 每秒补充 1 个令牌
+        let refilled = limiter
+            .check_at("key", start + Duration::from_millis(1500))
+            .unwrap();
+        assert!(refilled.allowed);
+        assert_eq!(refilled.remaining, 0);
+
+        // 其他 Key 使用独立的桶
+        assert!(limiter.check_at("other", start).unwrap().allowed);
+    }
+
+    #[test]
+    fn test_override_and_headers() {
+        let limiter = limiter(60, 10);
+        limiter.set_override(
+            "vip",
+            TokenBucketLimit {
+                requests_per_minute: 60,
+                burst: 1,
+            },
+        );
+        let start = Instant::now();
+        assert!(limiter.check_at("vip", start).unwrap().allowed);
+        let limited = limiter.check_at("vip", start).unwrap();
+        assert!(!limited.allowed);
+
+        let mut headers = HeaderMap::new();
+        limited.apply_headers(&mut headers);
+        assert_eq!(headers["x-ratelimit-limit"], "1");
+        assert_eq!(headers["x-ratelimit-remaining"], "0");
+        assert_eq!(headers["x-ratelimit-reset"], "1");
+        assert_eq!(headers[header::RETRY_AFTER], "1");
+
+        limiter.remove_override("vip");
+        assert_eq!(limiter.check_at("vip", start).unwrap().limit, 10);
+    }
+}
@@ -0,0 +1,406 @@
+//! 请求审计日志
+//!
+//! 开启后每个请求结束时将脱敏后的元数据写入 `request_audit_log` 表（FTS5 全文索引），
+//! 用于排查跨 Provider / 凭证的路由问题：
+//! - 请求体由处理器在解析后通过 [`AuditLog::capture_request_body`] 写入上下文元数据，
+//!   先按字段名替换密钥值，写库前再经 `CredentialSanitizer` 过滤，并截断到 `max_body_chars`
+//! - Token 数通常在请求结束后才统计，通过 [`AuditLog::record_tokens`] 补写；
+//!   先于审计记录到达时暂存，写入记录时合并
+//! - 严格模式下的协议转换损失通过 [`AuditLog::record_conversion_warnings`] 补写，
+//!   流式请求的审计记录晚于响应头写入，同样先暂存再合并
+//! - 退化响应（空内容等）换凭证重试前，原响应通过 [`AuditLog::record_degenerate`]
+//!   以 `degenerate` 状态单独记录
+
+use lime_core::config::{redact_secret_fields, AuditLogSettings};
+use lime_core::database::dao::request_audit::{RequestAuditDao, RequestAuditRecord};
+use lime_core::database::{lock_db, DbConnection};
+use lime_core::processor::RequestContext;
+use lime_core::sanitizer::CredentialSanitizer;
+use lime_infra::telemetry::RequestStatus;
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// 请求上下文中记录请求体的元数据键
+pub const AUDIT_REQUEST_BODY_METADATA_KEY: &str = "audit_request_body";
+
+/// 被判定为退化并已换凭证重试的原响应的审计状态
+pub const DEGENERATE_AUDIT_STATUS: &str =
+    lime_core::database::dao::request_audit::DEGENERATE_STATUS;
+
+/// 暂存 Token 数 / 转换损失的最大请求数（超出时清空）
+const MAX_PENDING_TOKENS: usize = 1024;
+
+/// 过期记录清理间隔（秒）
+const PRUNE_INTERVAL_SECS: i64 = 3600;
+
+/// 请求审计日志
+pub struct AuditLog {
+    settings: RwLock<AuditLogSettings>,
+    /// 先于审计记录到达的 Token 数（request_id -> (输入, 输出)）
+    pending_tokens: Mutex<HashMap<String, (u64, u64)>>,
+    /// 先于审计记录到达的协议转换损失（request_id -> 损失描述）
+    pending_warnings: Mutex<HashMap<String, String>>,
+    /// 上次清理过期记录的时间（Unix 秒）
+    last_prune: AtomicI64,
+}
+
+impl AuditLog {
+    pub fn new(settings: &AuditLogSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings.clone()),
+            pending_tokens: Mutex::new(HashMap::new()),
+            pending_warnings: Mutex::new(HashMap::new()),
+            last_prune: AtomicI64::new(0),
+        }
+    }
+
+    /// 热更新配置
+    pub fn reload(&self, settings: &AuditLogSettings) {
+        *self.settings.write() = settings.clone();
+        if !settings.enabled {
+            self.pending_tokens.lock().clear();
+            self.pending_warnings.lock().clear();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.read().enabled
+    }
+
+    /// 记录请求体（脱敏并截断后写入上下文元数据）
+    pub fn capture_request_body(&self, ctx: &mut RequestContext, body: &impl Serialize) {
+        let max_chars = {
+            let settings = self.settings.read();
+            if !settings.enabled || !settings.include_request_body {
+                return;
+            }
+            settings.max_body_chars
+        };
+        let Ok(mut value) = serde_json::to_value(body) else {
+            return;
+        };
+        redact_secret_fields(&mut value);
+        let text = truncate_chars(&value.to_string(), max_chars);
+        ctx.set_metadata(
+            AUDIT_REQUEST_BODY_METADATA_KEY,
+            serde_json::Value::String(text),
+        );
+    }
+
+    /// 请求结束时写入审计记录（`retrying` 状态不记录）
+    ///
+    /// `error_message` 应已脱敏。
+    pub fn record(
+        &self,
+        db: Option<&DbConnection>,
+        sanitizer: &CredentialSanitizer,
+        ctx: &RequestContext,
+        status: RequestStatus,
+        error_message: Option<String>,
+    ) {
+        if !self.is_enabled() || status == RequestStatus::Retrying {
+            return;
+        }
+        let Some(db) = db else {
+            return;
+        };
+
+        let tokens = self.pending_tokens.lock().remove(&ctx.request_id);
+        let conversion_warnings = self.pending_warnings.lock().remove(&ctx.request_id);
+        let record = RequestAuditRecord {
+            id: 0,
+            request_id: ctx.request_id.clone(),
+            created_at: ctx.timestamp.timestamp(),
+            provider: ctx
+                .provider
+                .map_or_else(|| "unknown".to_string(), |p| p.to_string()),
+            model: ctx.resolved_model.clone(),
+            credential_id: ctx.credential_id.clone(),
+            is_stream: ctx.is_stream,
+            status: status.to_string(),
+            latency_ms: ctx.elapsed_ms(),
+            retry_count: ctx.retry_count,
+            input_tokens: tokens.map(|(input, _)| input),
+            output_tokens: tokens.map(|(_, output)| output),
+            error_message,
+            request_body: ctx
+                .get_metadata(AUDIT_REQUEST_BODY_METADATA_KEY)
+                .and_then(|value| value.as_str())
+                .map(|body| sanitizer.sanitize(body)),
+            conversion_warnings,
+        };
+
+        let result = lock_db(db).and_then(|conn| {
+            RequestAuditDao::insert(&conn, &record).map_err(|e| e.to_string())?;
+            self.prune_expired(&conn, record.created_at);
+            Ok(())
+        });
+        if let Err(e) = result {
+            tracing::warn!("[AUDIT] 写入审计记录失败: {}", e);
+        }
+    }
+
+    /// 记录被判定为退化的原响应
+    ///
+    /// 以 `degenerate` 状态单独写一条记录（凭证为原凭证），重试的结果在请求结束时
+    /// 照常写入；补写 Token 数与转换损失时不会覆盖这条记录。
+    pub fn record_degenerate(
+        &self,
+        db: Option<&DbConnection>,
+        sanitizer: &CredentialSanitizer,
+        ctx: &RequestContext,
+        reason: &str,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let Some(db) = db else {
+            return;
+        };
+
+        let record = RequestAuditRecord {
+            id: 0,
+            request_id: ctx.request_id.clone(),
+            created_at: ctx.timestamp.timestamp(),
+            provider: ctx
+                .provider
+                .map_or_else(|| "unknown".to_string(), |p| p.to_string()),
+            model: ctx.resolved_model.clone(),
+            credential_id: ctx.credential_id.clone(),
+            is_stream: ctx.is_stream,
+            status: DEGENERATE_AUDIT_STATUS.to_string(),
+            latency_ms: ctx.elapsed_ms(),
+            retry_count: ctx.retry_count,
+            input_tokens: None,
+            output_tokens: None,
+            error_message: Some(reason.to_string()),
+            request_body: ctx
+                .get_metadata(AUDIT_REQUEST_BODY_METADATA_KEY)
+                .and_then(|value| value.as_str())
+                .map(|body| sanitizer.sanitize(body)),
+            conversion_warnings: None,
+        };
+
+        let result = lock_db(db).and_then(|conn| {
+            RequestAuditDao::insert(&conn, &record)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        });
+        if let Err(e) = result {
+            tracing::warn!("[AUDIT] 写入退化响应记录失败: {}", e);
+        }
+    }
+
+    /// 补写请求的 Token 数
+    pub fn record_tokens(
+        &self,
+        db: Option<&DbConnection>,
+        request_id: &str,
+        input_tokens: u64,
+        output_tokens: u64,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let Some(db) = db else {
+            return;
+        };
+        let updated = lock_db(db).and_then(|conn| {
+            RequestAuditDao::update_tokens(&conn, request_id, input_tokens, output_tokens)
+                .map_err(|e| e.to_string())
+        });
+        match updated {
+            Ok(0) => {
+                let mut pending = self.pending_tokens.lock();
+                if pending.len() >= MAX_PENDING_TOKENS {
+                    pending.clear();
+                }
+                pending.insert(request_id.to_string(), (input_tokens, output_tokens));
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("[AUDIT] 更新 Token 数失败: {}", e),
+        }
+    }
+
+    /// 补写请求的协议转换损失
+    pub fn record_conversion_warnings(
+        &self,
+        db: Option<&DbConnection>,
+        request_id: &str,
+        warnings: &str,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let Some(db) = db else {
+            return;
+        };
+        let updated = lock_db(db).and_then(|conn| {
+            RequestAuditDao::update_conversion_warnings(&conn, request_id, warnings)
+                .map_err(|e| e.to_string())
+        });
+        match updated {
+            Ok(0) => {
+                let mut pending = self.pending_warnings.lock();
+                if pending.len() >= MAX_PENDING_TOKENS {
+                    pending.clear();
+                }
+                pending.insert(request_id.to_string(), warnings.to_string());
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("[AUDIT] 更新转换损失失败: {}", e),
+        }
+    }
+
+    /// 按保留天数清理过期记录（每小时最多一次）
+    fn prune_expired(&self, conn: &rusqlite::Connection, now: i64) {
+        let retention_days = self.settings.read().retention_days;
+        if retention_days == 0 {
+            return;
+        }
+        let last = self.last_prune.load(Ordering::Relaxed);
+        if now - last < PRUNE_INTERVAL_SECS
+            || self
+                .last_prune
+                .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_err()
+        {
+            return;
+        }
+        let cutoff = now - i64::from(retention_days) * 86_400;
+        match RequestAuditDao::prune_before(conn, cutoff) {
+            Ok(0) => {}
+            Ok(removed) => tracing::info!("[AUDIT] 清理过期审计记录 {} 条", removed),
+            Err(e) => tracing::warn!("[AUDIT] 清理过期审计记录失败: {}", e),
+        }
+    }
+}
+
+/// 按字符数截断，超出时追加截断标记
+fn truncate_chars(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}…[truncated]", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::database::dao::request_audit::AuditQuery;
+    use lime_core::database::schema::create_tables;
+    use std::sync::Arc;
+
+    fn enabled(max_body_chars: usize) -> AuditLogSettings {
+        AuditLogSettings {
+            enabled: true,
+            max_body_chars,
+            ..AuditLogSettings::default()
+        }
+    }
+
+    fn memory_db() -> DbConnection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        Arc::new(std::sync::Mutex::new(conn))
+    }
+
+    #[test]
+    fn test_capture_request_body_redacts_and_truncates() {
+        let audit = AuditLog::new(&enabled(40));
+        let mut ctx = RequestContext::new("gpt-4o".to_string());
+        audit.capture_request_body(
+            &mut ctx,
+            &serde_json::json!({ "api_key": "sk-secret-value", "prompt": "x".repeat(100) }),
+        );
+
+        let body = ctx
+            .get_metadata(AUDIT_REQUEST_BODY_METADATA_KEY)
+            .and_then(|v| v.as_str())
+            .unwrap();
+        assert!(!body.contains("sk-secret-value"));
+        assert!(body.ends_with("…[truncated]"));
+
+        let disabled = AuditLog::new(&AuditLogSettings::default());
+        let mut ctx = RequestContext::new("gpt-4o".to_string());
+        disabled.capture_request_body(&mut ctx, &serde_json::json!({ "prompt": "hi" }));
+        assert!(ctx.get_metadata(AUDIT_REQUEST_BODY_METADATA_KEY).is_none());
+    }
+
+    #[test]
+    fn test_tokens_merge_regardless_of_order() {
+        let db = memory_db();
+        let audit = AuditLog::new(&enabled(4096));
+        let sanitizer = CredentialSanitizer::with_defaults();
+
+        // Token 数先到达
+        let early = RequestContext::new("gpt-4o".to_string());
+        audit.record_tokens(Some(&db), &early.request_id, 5, 7);
+        audit.record_conversion_warnings(Some(&db), &early.request_id, "seed=dropped");
+        audit.record(Some(&db), &sanitizer, &early, RequestStatus::Success, None);
+
+        // 审计记录先写入
+        let late = RequestContext::new("gpt-4o".to_string());
+        audit.record(Some(&db), &sanitizer, &late, RequestStatus::Failed, None);
+        audit.record_tokens(Some(&db), &late.request_id, 1, 0);
+
+        let conn = db.lock().unwrap();
+        let records = RequestAuditDao::search(&conn, &AuditQuery::default()).unwrap();
+        assert_eq!(records.len(), 2);
+        for record in records {
+            let expected = if record.request_id == early.request_id {
+                (5, 7)
+            } else {
+                (1, 0)
+            };
+            assert_eq!(
+                (record.input_tokens, record.output_tokens),
+                (Some(expected.0), Some(expected.1))
+            );
+        }
+        let early_record = RequestAuditDao::search(&conn, &AuditQuery::default())
+            .unwrap()
+            .into_iter()
+            .find(|record| record.request_id == early.request_id)
+            .unwrap();
+        assert_eq!(
+            early_record.conversion_warnings.as_deref(),
+            Some("seed=dropped")
+        );
+    }
+
+    #[test]
+    fn test_degenerate_record_keeps_its_own_row() {
+        let db = memory_db();
+        let audit = AuditLog::new(&enabled(4096));
+        let sanitizer = CredentialSanitizer::with_defaults();
+
+        let mut ctx = RequestContext::new("gpt-4o".to_string());
+        ctx.set_credential_id("cred-original".to_string());
+        audit.record_degenerate(Some(&db), &sanitizer, &ctx, "degenerate response");
+
+        ctx.set_credential_id("cred-retry".to_string());
+        ctx.increment_retry();
+        audit.record(Some(&db), &sanitizer, &ctx, RequestStatus::Success, None);
+        audit.record_tokens(Some(&db), &ctx.request_id, 3, 9);
+
+        let conn = db.lock().unwrap();
+        let records = RequestAuditDao::search(&conn, &AuditQuery::default()).unwrap();
+        assert_eq!(records.len(), 2);
+        let degenerate = records
+            .iter()
+            .find(|record| record.status == DEGENERATE_AUDIT_STATUS)
+            .unwrap();
+        assert_eq!(degenerate.credential_id.as_deref(), Some("cred-original"));
+        assert_eq!(degenerate.output_tokens, None);
+        let retried = records
+            .iter()
+            .find(|record| record.status != DEGENERATE_AUDIT_STATUS)
+            .unwrap();
+        assert_eq!(retried.credential_id.as_deref(), Some("cred-retry"));
+        assert_eq!(retried.retry_count, 1);
+        assert_eq!(retried.output_tokens, Some(9));
+    }
+}
@@ -0,0 +1,129 @@
+//! 协议转换损失检测中间件
+//!
+//! 严格模式下缓存 `/v1/chat/completions` 与 `/v1/messages` 的原始请求体，
+//! 在处理器返回后按 `x-lime-effective-provider` 推断目标协议，检测被丢弃或改写的字段：
+//! - 响应附带 `x-lime-conversion-warnings`（如 `seed=dropped, tool_choice=transformed`）
+//! - 写入审计日志的 `conversion_warnings` 列（审计日志开启时）
+//!
+//! 宽松模式（默认）不读取请求体，行为与未启用时一致。
+
+use crate::AppState;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use lime_core::config::ConversionLossMode;
+use lime_core::errors::GatewayErrorCode;
+use lime_core::processor::REQUEST_ID_HEADER;
+use lime_providers::converter::lossiness::{
+    detect_request_loss, format_loss_header, SourceFormat, TargetProtocol,
+};
+use lime_server_utils::build_error_response_with_meta;
+
+/// 转换损失响应头
+pub const CONVERSION_WARNINGS_HEADER: &str = "x-lime-conversion-warnings";
+
+/// 缓存请求体的上限（与服务器请求体上限一致）
+const MAX_INSPECT_BODY_BYTES: usize = 100 * 1024 * 1024;
+
+/// 根据路由判断请求格式，非聊天路由返回 None
+fn source_format(method: &Method, path: &str) -> Option<SourceFormat> {
+    if method != Method::POST {
+        return None;
+    }
+    if path.ends_with("/v1/chat/completions") {
+        Some(SourceFormat::OpenAiChat)
+    } else if path.ends_with("/v1/messages") {
+        Some(SourceFormat::AnthropicMessages)
+    } else {
+        None
+    }
+}
+
+/// 协议转换损失检测中间件
+pub async fn detect_conversion_loss(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if state.conversion_loss.mode != ConversionLossMode::Strict {
+        return next.run(request).await;
+    }
+    let Some(source) = source_format(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_INSPECT_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return build_error_response_with_meta(
+                StatusCode::BAD_REQUEST.as_u16(),
+                &format!("Failed to read request body: {e}"),
+                None,
+                None,
+                Some(GatewayErrorCode::InvalidRequest),
+            );
+        }
+    };
+    let body_json = serde_json::from_slice::<serde_json::Value>(&bytes).ok();
+    let mut response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+
+    // 请求体无法解析时由处理器返回错误，这里不再检测
+    let Some(body_json) = body_json else {
+        return response;
+    };
+    let headers = response.headers();
+    let target = headers
+        .get("x-lime-effective-provider")
+        .and_then(|v| v.to_str().ok())
+        .map(TargetProtocol::for_provider);
+    let request_id = headers
+        .get(REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let losses = detect_request_loss(source, &body_json, target);
+    if losses.is_empty() {
+        return response;
+    }
+    let warnings = format_loss_header(&losses);
+    tracing::warn!(
+        "[CONVERSION_LOSS] 请求 {} 转换时字段被丢弃或改写: {}",
+        request_id.as_deref().unwrap_or("-"),
+        warnings
+    );
+    if let Ok(value) = HeaderValue::from_str(&warnings) {
+        response
+            .headers_mut()
+            .insert(CONVERSION_WARNINGS_HEADER, value);
+    }
+    if let Some(request_id) = request_id {
+        state
+            .audit_log
+            .record_conversion_warnings(state.db.as_ref(), &request_id, &warnings);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_format_by_route() {
+        assert_eq!(
+            source_format(&Method::POST, "/v1/chat/completions"),
+            Some(SourceFormat::OpenAiChat)
+        );
+        assert_eq!(
+            source_format(&Method::POST, "/kiro/v1/messages"),
+            Some(SourceFormat::AnthropicMessages)
+        );
+        assert_eq!(source_format(&Method::GET, "/v1/messages"), None);
+        assert_eq!(source_format(&Method::POST, "/v1/embeddings"), None);
+    }
+}
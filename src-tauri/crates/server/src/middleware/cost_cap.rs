@@ -0,0 +1,491 @@
+//! 月度费用硬上限
+//!
+//! 按模型注册表定价估算每次请求的费用，按自然月累计（全局 + 按 Provider），
+//! 超出上限后拒绝付费 Provider 的请求：
+//! - 免费/本地 Provider（`free_providers`）不计费、不受限
+//! - 支持临时放行（override），到期自动恢复
+//! - 预警、超限、放行状态变化通过广播事件通知
+
+use lime_core::config::CostCapSettings;
+use lime_core::database::dao::cost_usage::CostUsageDao;
+use lime_core::database::{lock_db, DbConnection};
+use lime_core::models::model_registry::ModelPricing;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tokio::sync::broadcast;
+
+/// 请求上下文中记录计费 Provider 的元数据键
+pub const COST_PROVIDER_METADATA_KEY: &str = "cost_provider";
+
+/// 全局上限的作用域名称
+pub const GLOBAL_SCOPE: &str = "global";
+
+/// 费用上限事件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CostCapEvent {
+    /// 费用达到预警比例
+    Warning {
+        scope: String,
+        spent_usd: f64,
+        limit_usd: f64,
+    },
+    /// 费用超出上限，后续付费请求将被拒绝
+    Exceeded {
+        scope: String,
+        spent_usd: f64,
+        limit_usd: f64,
+    },
+    /// 临时放行状态变化（None 表示取消放行）
+    OverrideChanged { until: Option<i64> },
+}
+
+/// 超限信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostCapViolation {
+    pub scope: String,
+    pub spent_usd: f64,
+    pub limit_usd: f64,
+}
+
+impl CostCapViolation {
+    pub fn message(&self) -> String {
+        let scope = if self.scope == GLOBAL_SCOPE {
+            "Monthly cost cap".to_string()
+        } else {
+            format!("Monthly cost cap for provider '{}'", self.scope)
+        };
+        format!(
+            "{} exceeded: spent ${:.2} of ${:.2}. Paid requests are blocked until next month or an override is set.",
+            scope, self.spent_usd, self.limit_usd
+        )
+    }
+}
+
+/// 费用上限状态
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CostCapStatus {
+    pub enabled: bool,
+    pub month: String,
+    pub total_spent_usd: f64,
+    pub monthly_limit_usd: Option<f64>,
+    pub provider_spent_usd: HashMap<String, f64>,
+    pub provider_limits_usd: HashMap<String, f64>,
+    pub override_until: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+struct MonthlySpend {
+    month: String,
+    total: f64,
+    by_provider: HashMap<String, f64>,
+    /// 本月已发送过的通知（`warning:<scope>` / `exceeded:<scope>`）
+    notified: HashSet<String>,
+}
+
+impl MonthlySpend {
+    fn roll_to(&mut self, month: &str) {
+        if self.month != month {
+            *self = MonthlySpend {
+                month: month.to_string(),
+                ..MonthlySpend::default()
+            };
+        }
+    }
+}
+
+/// 费用上限守卫
+pub struct CostCapGuard {
+    settings: RwLock<CostCapSettings>,
+    spend: Mutex<MonthlySpend>,
+    override_until: RwLock<Option<i64>>,
+    pricing_cache: RwLock<HashMap<String, Option<ModelPricing>>>,
+    events: broadcast::Sender<CostCapEvent>,
+}
+
+fn current_month() -> String {
+    chrono::Utc::now().format("%Y-%m").to_string()
+}
+
+fn normalize_provider(provider: &str) -> String {
+    provider.trim().to_lowercase()
+}
+
+impl CostCapGuard {
+    pub fn new(settings: &CostCapSettings) -> Self {
+        let (events, _) = broadcast::channel(32);
+        Self {
+            settings: RwLock::new(settings.clone()),
+            spend: Mutex::new(MonthlySpend {
+                month: current_month(),
+                ..MonthlySpend::default()
+            }),
+            override_until: RwLock::new(None),
+            pricing_cache: RwLock::new(HashMap::new()),
+            events,
+        }
+    }
+
+    /// 热更新配置（已累计的费用保留）
+    pub fn reload(&self, settings: &CostCapSettings) {
+        *self.settings.write() = settings.clone();
+        self.spend.lock().notified.clear();
+    }
+
+    /// 从数据库恢复本月累计费用，并清空定价缓存
+    pub fn load_usage(&self, db: &DbConnection) {
+        let month = current_month();
+        let rows = match lock_db(db)
+            .and_then(|conn| CostUsageDao::list_month(&conn, &month).map_err(|e| e.to_string()))
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::warn!("[COST_CAP] 加载本月费用失败: {}", e);
+                return;
+            }
+        };
+
+        let mut spend = self.spend.lock();
+        *spend = MonthlySpend {
+            month,
+            ..MonthlySpend::default()
+        };
+        for (provider, cost) in rows {
+            spend.total += cost;
+            spend.by_provider.insert(provider, cost);
+        }
+        self.pricing_cache.write().clear();
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<CostCapEvent> {
+        self.events.subscribe()
+    }
+
+    pub fn is_free_provider(&self, provider: &str) -> bool {
+        is_free_provider(&self.settings.read(), provider)
+    }
+
+    /// 设置临时放行截止时间（Unix 秒），None 表示取消放行
+    pub fn set_override(&self, until: Option<i64>) {
+        *self.override_until.write() = until;
+        let _ = self.events.send(CostCapEvent::OverrideChanged { until });
+    }
+
+    fn override_active(&self) -> bool {
+        self.override_until
+            .read()
+            .is_some_and(|until| until > chrono::Utc::now().timestamp())
+    }
+
+    /// 请求前检查：付费 Provider 超出全局或 Provider 上限时返回超限信息
+    pub fn check(&self, provider: &str) -> Result<(), CostCapViolation> {
+        let settings = self.settings.read();
+        if !settings.enabled || is_free_provider(&settings, provider) || self.override_active() {
+            return Ok(());
+        }
+
+        let provider = normalize_provider(provider);
+        let mut spend = self.spend.lock();
+        spend.roll_to(&current_month());
+
+        if let Some(limit) = provider_limit(&settings, &provider) {
+            let spent = spend.by_provider.get(&provider).copied().unwrap_or(0.0);
+            if spent >= limit {
+                return Err(CostCapViolation {
+                    scope: provider,
+                    spent_usd: spent,
+                    limit_usd: limit,
+                });
+            }
+        }
+
+        if let Some(limit) = settings.monthly_limit_usd {
+            if spend.total >= limit {
+                return Err(CostCapViolation {
+                    scope: GLOBAL_SCOPE.to_string(),
+                    spent_usd: spend.total,
+                    limit_usd: limit,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 按模型定价估算费用（USD，未找到定价时视为 0）
+    pub fn estimate_cost(
+        &self,
+        db: Option<&DbConnection>,
+        model: &str,
+        input_tokens: u32,
+        output_tokens: u32,
+    ) -> f64 {
+        let pricing = self.lookup_pricing(db, model);
+        self.cost_for_pricing(pricing.as_ref(), input_tokens, output_tokens)
+    }
+
+    /// 按给定定价计算费用并按配置汇率折算为 USD（无定价时为 0）
+    pub fn cost_for_pricing(
+        &self,
+        pricing: Option<&ModelPricing>,
+        input_tokens: u32,
+        output_tokens: u32,
+    ) -> f64 {
+        let cny_to_usd_rate = self.settings.read().cny_to_usd_rate;
+        compute_cost(pricing, input_tokens, output_tokens, cny_to_usd_rate)
+    }
+
+    /// 查询模型定价（按模型缓存）
+    pub fn lookup_pricing(&self, db: Option<&DbConnection>, model: &str) -> Option<ModelPricing> {
+        if let Some(cached) = self.pricing_cache.read().get(model) {
+            return cached.clone();
+        }
+        let db = db?;
+        let pricing = lock_db(db)
+            .ok()
+            .and_then(|conn| CostUsageDao::get_model_pricing(&conn, model).ok())
+            .flatten();
+        self.pricing_cache
+            .write()
+            .insert(model.to_string(), pricing.clone());
+        pricing
+    }
+
+    /// 记录一次请求的费用，并在跨越预警/上限阈值时发送事件
+    pub fn record(&self, db: Option<&DbConnection>, provider: &str, cost_usd: f64) {
+        if cost_usd <= 0.0 || self.is_free_provider(provider) {
+            return;
+        }
+        let provider = normalize_provider(provider);
+        let month = current_month();
+
+        let events = {
+            let settings = self.settings.read();
+            let mut spend = self.spend.lock();
+            spend.roll_to(&month);
+            spend.total += cost_usd;
+            *spend.by_provider.entry(provider.clone()).or_insert(0.0) += cost_usd;
+
+            let mut events = Vec::new();
+            if settings.enabled {
+                let provider_spent = spend.by_provider[&provider];
+                let checks = [
+                    (
+                        provider.clone(),
+                        provider_spent,
+                        provider_limit(&settings, &provider),
+                    ),
+                    (
+                        GLOBAL_SCOPE.to_string(),
+                        spend.total,
+                        settings.monthly_limit_usd,
+                    ),
+                ];
+                for (scope, spent, limit) in checks {
+                    let Some(limit) = limit else {
+                        continue;
+                    };
+                    if let Some(event) = threshold_event(
+                        &mut spend.notified,
+                        scope,
+                        spent,
+                        limit,
+                        settings.warning_ratio,
+                    ) {
+                        events.push(event);
+                    }
+                }
+            }
+            events
+        };
+
+        if let Some(db) = db {
+            if let Err(e) = lock_db(db).and_then(|conn| {
+                CostUsageDao::add_cost(&conn, &month, &provider, cost_usd)
+                    .map_err(|e| e.to_string())
+            }) {
+                tracing::warn!("[COST_CAP] 持久化费用失败: {}", e);
+            }
+        }
+
+        for event in events {
+            if let CostCapEvent::Exceeded {
+                scope,
+                spent_usd,
+                limit_usd,
+            } = &event
+            {
+                tracing::warn!(
+                    "[COST_CAP] 费用超出上限: scope={} spent={:.4} limit={:.2}",
+                    scope,
+                    spent_usd,
+                    limit_usd
+                );
+            }
+            let _ = self.events.send(event);
+        }
+    }
+
+    pub fn status(&self) -> CostCapStatus {
+        let settings = self.settings.read();
+        let mut spend = self.spend.lock();
+        spend.roll_to(&current_month());
+        CostCapStatus {
+            enabled: settings.enabled,
+            month: spend.month.clone(),
+            total_spent_usd: spend.total,
+            monthly_limit_usd: settings.monthly_limit_usd,
+            provider_spent_usd: spend.by_provider.clone(),
+            provider_limits_usd: settings.provider_limits_usd.clone(),
+            override_until: *self.override_until.read(),
+        }
+    }
+}
+
+fn is_free_provider(settings: &CostCapSettings, provider: &str) -> bool {
+    let provider = normalize_provider(provider);
+    settings
+        .free_providers
+        .iter()
+        .any(|free| normalize_provider(free) == provider)
+}
+
+fn provider_limit(settings: &CostCapSettings, provider: &str) -> Option<f64> {
+    settings
+        .provider_limits_usd
+        .iter()
+        .find(|(name, _)| normalize_provider(name) == provider)
+        .map(|(_, limit)| *limit)
+}
+
+/// 按定价计算费用并折算为 USD（未知货币按 USD 处理）
+fn compute_cost(
+    pricing: Option<&ModelPricing>,
+    input_tokens: u32,
+    output_tokens: u32,
+    cny_to_usd_rate: f64,
+) -> f64 {
+    let Some(pricing) = pricing else {
+        return 0.0;
+    };
+    let cost = pricing.estimate_cost(input_tokens, output_tokens);
+    if pricing.currency.trim().eq_ignore_ascii_case("CNY") {
+        cost * cny_to_usd_rate
+    } else {
+        cost
+    }
+}
+
+/// 跨越阈值时生成事件，同一作用域每月每类事件只发送一次
+fn threshold_event(
+    notified: &mut HashSet<String>,
+    scope: String,
+    spent: f64,
+    limit: f64,
+    warning_ratio: f64,
+) -> Option<CostCapEvent> {
+    if spent >= limit {
+        if notified.insert(format!("exceeded:{scope}")) {
+            return Some(CostCapEvent::Exceeded {
+                scope,
+                spent_usd: spent,
+                limit_usd: limit,
+            });
+        }
+    } else if spent >= limit * warning_ratio.clamp(0.0, 1.0)
+        && notified.insert(format!("warning:{scope}"))
+    {
+        return Some(CostCapEvent::Warning {
+            scope,
+            spent_usd: spent,
+            limit_usd: limit,
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> CostCapSettings {
+        CostCapSettings {
+            enabled: true,
+            monthly_limit_usd: Some(10.0),
+            provider_limits_usd: HashMap::from([("OpenAI".to_string(), 2.0)]),
+            ..CostCapSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_provider_cap_blocks_only_that_provider() {
+        let guard = CostCapGuard::new(&settings());
+        assert!(guard.check("openai").is_ok());
+
+        guard.record(None, "openai", 2.5);
+        let violation = guard.check("openai").unwrap_err();
+        assert_eq!(violation.scope, "openai");
+        assert!(guard.check("claude").is_ok());
+    }
+
+    #[test]
+    fn test_global_cap_allows_free_providers() {
+        let guard = CostCapGuard::new(&settings());
+        guard.record(None, "claude", 10.0);
+
+        assert_eq!(guard.check("claude").unwrap_err().scope, GLOBAL_SCOPE);
+        assert!(guard.check("ollama").is_ok());
+        assert!(guard.check("mock").is_ok());
+
+        guard.set_override(Some(chrono::Utc::now().timestamp() + 60));
+        assert!(guard.check("claude").is_ok());
+        guard.set_override(None);
+        assert!(guard.check("claude").is_err());
+    }
+
+    #[test]
+    fn test_threshold_events_are_sent_once() {
+        let guard = CostCapGuard::new(&settings());
+        let mut rx = guard.subscribe();
+
+        guard.record(None, "claude", 8.5);
+        guard.record(None, "claude", 0.1);
+        guard.record(None, "claude", 2.0);
+
+        let first = rx.try_recv().unwrap();
+        assert!(matches!(first, CostCapEvent::Warning { ref scope, .. } if scope == GLOBAL_SCOPE));
+        let second = rx.try_recv().unwrap();
+        assert!(
+            matches!(second, CostCapEvent::Exceeded { ref scope, .. } if scope == GLOBAL_SCOPE)
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_compute_cost() {
+        let pricing = ModelPricing {
+            input_per_million: Some(3.0),
+            output_per_million: Some(15.0),
+            ..ModelPricing::default()
+        };
+        let cost = compute_cost(Some(&pricing), 1_000_000, 200_000, 0.14);
+        assert!((cost - 6.0).abs() < 1e-9);
+        assert_eq!(compute_cost(None, 1000, 1000, 0.14), 0.0);
+
+        let pricing = ModelPricing {
+            currency: "CNY".to_string(),
+            ..pricing
+        };
+        let cost = compute_cost(Some(&pricing), 1_000_000, 200_000, 0.14);
+        assert!((cost - 0.84).abs() < 1e-9);
+
+        // 预估接口使用的 guard 方法按配置汇率折算
+        let guard = CostCapGuard::new(&CostCapSettings {
+            cny_to_usd_rate: 0.14,
+            ..settings()
+        });
+        let cost = guard.cost_for_pricing(Some(&pricing), 1_000_000, 200_000);
+        assert!((cost - 0.84).abs() < 1e-9);
+    }
+}
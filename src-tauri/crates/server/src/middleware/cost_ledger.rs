@@ -0,0 +1,339 @@
+//! 请求费用明细与预算提醒
+//!
+//! 每个请求的 Provider、凭证、模型、会话与 Token 数写入 `request_costs` 明细表，
+//! 费用按模型注册表定价估算（`CostCapGuard::estimate_cost`）。花费按日/月累计
+//! （全局 + 按 Provider），达到预算提醒比例或超出预算时广播事件。
+//! 预算只提醒、不拦截请求，拦截由 `cost_cap` 负责。
+
+use super::cost_cap::GLOBAL_SCOPE;
+use lime_core::config::{BudgetPeriod, CostBudgetSettings};
+use lime_core::database::dao::request_cost::{RequestCostDao, RequestCostRecord, SpendGroup};
+use lime_core::database::{lock_db, DbConnection};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use tokio::sync::broadcast;
+
+/// 请求上下文中记录会话 ID 的元数据键
+pub const COST_SESSION_METADATA_KEY: &str = "cost_session";
+
+/// 客户端传递会话 ID 的请求头
+pub const SESSION_ID_HEADER: &str = "x-session-id";
+
+/// 预算事件
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CostBudgetEvent {
+    /// 花费达到预算的提醒比例
+    Alert {
+        period: BudgetPeriod,
+        scope: String,
+        spent_usd: f64,
+        budget_usd: f64,
+    },
+    /// 花费超出预算
+    Exceeded {
+        period: BudgetPeriod,
+        scope: String,
+        spent_usd: f64,
+        budget_usd: f64,
+    },
+}
+
+#[derive(Debug, Default)]
+struct PeriodSpend {
+    day: String,
+    month: String,
+    /// 当日花费（作用域 -> USD）
+    daily: HashMap<String, f64>,
+    /// 当月花费（作用域 -> USD）
+    monthly: HashMap<String, f64>,
+    /// 当前周期已发送过的通知（`<周期>:<类型>:<作用域>`）
+    notified: HashSet<String>,
+}
+
+impl PeriodSpend {
+    fn roll_to(&mut self, day: &str, month: &str) {
+        if self.day != day {
+            self.day = day.to_string();
+            self.daily.clear();
+            self.notified.retain(|key| !key.starts_with("daily:"));
+        }
+        if self.month != month {
+            self.month = month.to_string();
+            self.monthly.clear();
+            self.notified.retain(|key| !key.starts_with("monthly:"));
+        }
+    }
+
+    fn add(&mut self, provider: &str, cost_usd: f64) {
+        for scope in [GLOBAL_SCOPE, provider] {
+            *self.daily.entry(scope.to_string()).or_insert(0.0) += cost_usd;
+            *self.monthly.entry(scope.to_string()).or_insert(0.0) += cost_usd;
+        }
+    }
+
+    fn spent(&self, period: BudgetPeriod, scope: &str) -> f64 {
+        let totals = match period {
+            BudgetPeriod::Daily => &self.daily,
+            BudgetPeriod::Monthly => &self.monthly,
+        };
+        totals.get(scope).copied().unwrap_or(0.0)
+    }
+}
+
+/// 请求费用账本
+pub struct CostLedger {
+    settings: RwLock<CostBudgetSettings>,
+    spend: Mutex<PeriodSpend>,
+    events: broadcast::Sender<CostBudgetEvent>,
+}
+
+fn normalize_provider(provider: &str) -> String {
+    provider.trim().to_lowercase()
+}
+
+fn period_key(period: BudgetPeriod) -> &'static str {
+    match period {
+        BudgetPeriod::Daily => "daily",
+        BudgetPeriod::Monthly => "monthly",
+    }
+}
+
+impl CostLedger {
+    pub fn new(settings: &CostBudgetSettings) -> Self {
+        let (events, _) = broadcast::channel(32);
+        Self {
+            settings: RwLock::new(settings.clone()),
+            spend: Mutex::new(PeriodSpend::default()),
+            events,
+        }
+    }
+
+    /// 热更新预算配置（已累计的花费保留）
+    pub fn reload(&self, settings: &CostBudgetSettings) {
+        *self.settings.write() = settings.clone();
+        self.spend.lock().notified.clear();
+    }
+
+    /// 从明细表恢复当日与当月的累计花费
+    pub fn load_usage(&self, db: &DbConnection) {
+        let now = chrono::Utc::now();
+        let day = now.format("%Y-%m-%d").to_string();
+        let month = now.format("%Y-%m").to_string();
+        let month_start = format!("{month}-01");
+
+        let loaded = lock_db(db).and_then(|conn| {
+            let daily = RequestCostDao::summarize(&conn, SpendGroup::Provider, &day, &day);
+            let monthly =
+                RequestCostDao::summarize(&conn, SpendGroup::Provider, &month_start, &day);
+            daily
+                .and_then(|d| monthly.map(|m| (d, m)))
+                .map_err(|e| e.to_string())
+        });
+        let (daily, monthly) = match loaded {
+            Ok(rows) => rows,
+            Err(e) => {
+                tracing::warn!("[COST_LEDGER] 加载累计花费失败: {}", e);
+                return;
+            }
+        };
+
+        let mut guard = self.spend.lock();
+        *guard = PeriodSpend {
+            day,
+            month,
+            ..PeriodSpend::default()
+        };
+        let spend = &mut *guard;
+        for (totals, rows) in [(&mut spend.daily, daily), (&mut spend.monthly, monthly)] {
+            for bucket in rows {
+                *totals.entry(GLOBAL_SCOPE.to_string()).or_insert(0.0) += bucket.cost_usd;
+                *totals.entry(bucket.key).or_insert(0.0) += bucket.cost_usd;
+            }
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<CostBudgetEvent> {
+        self.events.subscribe()
+    }
+
+    /// 记录一次请求的费用，并在跨越预算阈值时发送事件
+    pub fn record(&self, db: Option<&DbConnection>, mut record: RequestCostRecord) {
+        record.provider = normalize_provider(&record.provider);
+
+        if let Some(db) = db {
+            if let Err(e) = lock_db(db)
+                .and_then(|conn| RequestCostDao::insert(&conn, &record).map_err(|e| e.to_string()))
+            {
+                tracing::warn!("[COST_LEDGER] 写入费用明细失败: {}", e);
+            }
+        }
+
+        if record.cost_usd <= 0.0 {
+            return;
+        }
+
+        let events = {
+            let settings = self.settings.read();
+            let mut spend = self.spend.lock();
+            spend.roll_to(&record.day, &record.month);
+            spend.add(&record.provider, record.cost_usd);
+            if !settings.enabled {
+                return;
+            }
+
+            let mut events = Vec::new();
+            for budget in &settings.budgets {
+                let scope = match &budget.provider {
+                    Some(provider) => normalize_provider(provider),
+                    None => GLOBAL_SCOPE.to_string(),
+                };
+                // 只检查与本次请求相关的预算
+                if scope != GLOBAL_SCOPE && scope != record.provider {
+                    continue;
+                }
+                let spent = spend.spent(budget.period, &scope);
+                if let Some(event) = budget_event(
+                    &mut spend.notified,
+                    budget.period,
+                    scope,
+                    spent,
+                    budget.amount_usd,
+                    settings.alert_ratio,
+                ) {
+                    events.push(event);
+                }
+            }
+            events
+        };
+
+        for event in events {
+            tracing::info!("[COST_LEDGER] 预算事件: {:?}", event);
+            let _ = self.events.send(event);
+        }
+    }
+}
+
+/// 跨越阈值时生成事件，同一周期内同一作用域每类事件只发送一次
+fn budget_event(
+    notified: &mut HashSet<String>,
+    period: BudgetPeriod,
+    scope: String,
+    spent: f64,
+    budget: f64,
+    alert_ratio: f64,
+) -> Option<CostBudgetEvent> {
+    if budget <= 0.0 {
+        return None;
+    }
+    let period_key = period_key(period);
+    if spent >= budget {
+        if notified.insert(format!("{period_key}:exceeded:{scope}")) {
+            return Some(CostBudgetEvent::Exceeded {
+                period,
+                scope,
+                spent_usd: spent,
+                budget_usd: budget,
+            });
+        }
+    } else if spent >= budget * alert_ratio.clamp(0.0, 1.0)
+        && notified.insert(format!("{period_key}:alert:{scope}"))
+    {
+        return Some(CostBudgetEvent::Alert {
+            period,
+            scope,
+            spent_usd: spent,
+            budget_usd: budget,
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::config::CostBudget;
+
+    fn record(day: &str, provider: &str, cost_usd: f64) -> RequestCostRecord {
+        RequestCostRecord {
+            request_id: "req".to_string(),
+            created_at: 0,
+            day: day.to_string(),
+            month: day[..7].to_string(),
+            provider: provider.to_string(),
+            credential_id: None,
+            model: "gpt-4o".to_string(),
+            session_id: None,
+            tenant_id: None,
+            input_tokens: 0,
+            output_tokens: 0,
+            cost_usd,
+        }
+    }
+
+    fn settings() -> CostBudgetSettings {
+        CostBudgetSettings {
+            enabled: true,
+            budgets: vec![
+                CostBudget {
+                    period: BudgetPeriod::Daily,
+                    provider: None,
+                    amount_usd: 10.0,
+                },
+                CostBudget {
+                    period: BudgetPeriod::Monthly,
+                    provider: Some("OpenAI".to_string()),
+                    amount_usd: 5.0,
+                },
+            ],
+            ..CostBudgetSettings::default()
+        }
+    }
+
+    #[test]
+    fn test_budget_events_per_period_and_scope() {
+        let ledger = CostLedger::new(&settings());
+        let mut rx = ledger.subscribe();
+
+        ledger.record(None, record("2026-10-01", "openai", 4.5));
+        let event = rx.try_recv().unwrap();
+        assert!(matches!(
+            event,
+            CostBudgetEvent::Alert { period: BudgetPeriod::Monthly, ref scope, .. } if scope == "openai"
+        ));
+
+        // claude 不影响 openai 的月预算，但计入全局日预算
+        ledger.record(None, record("2026-10-01", "claude", 4.0));
+        let event = rx.try_recv().unwrap();
+        assert!(matches!(
+            event,
+            CostBudgetEvent::Alert { period: BudgetPeriod::Daily, ref scope, .. } if scope == GLOBAL_SCOPE
+        ));
+        assert!(rx.try_recv().is_err());
+
+        // 次日重新计算日预算，月预算继续累计
+        ledger.record(None, record("2026-10-02", "openai", 1.0));
+        let event = rx.try_recv().unwrap();
+        assert!(matches!(
+            event,
+            CostBudgetEvent::Exceeded { period: BudgetPeriod::Monthly, spent_usd, .. } if spent_usd == 5.5
+        ));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_disabled_budgets_only_accumulate() {
+        let ledger = CostLedger::new(&CostBudgetSettings {
+            enabled: false,
+            ..settings()
+        });
+        let mut rx = ledger.subscribe();
+        ledger.record(None, record("2026-10-01", "openai", 50.0));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(
+            ledger.spend.lock().spent(BudgetPeriod::Monthly, "openai"),
+            50.0
+        );
+    }
+}
@@ -0,0 +1,359 @@
+//! 空响应 / 退化响应检测
+//!
+//! 按 [`DegenerateRetrySettings`] 检查非流式响应的文本内容与结束原因，
+//! 判定为退化时由处理器换用另一个凭证重试一次。
+//! 按 Provider 统计检测次数、退化次数与重试结果，用于观察各 Provider 的退化率。
+
+use lime_core::config::DegenerateRetrySettings;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 响应体格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegenerateResponseFormat {
+    /// OpenAI chat completions（`choices[].message`）
+    OpenAi,
+    /// Anthropic messages（`content[]` 内容块）
+    Anthropic,
+}
+
+/// 退化判定结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DegenerateVerdict {
+    /// 去除首尾空白后的文本字符数
+    pub content_chars: usize,
+    /// 响应的结束原因
+    pub finish_reason: Option<String>,
+}
+
+impl DegenerateVerdict {
+    /// 写入日志与审计记录的说明
+    pub fn describe(&self) -> String {
+        format!(
+            "degenerate response: content_chars={} finish_reason={}",
+            self.content_chars,
+            self.finish_reason.as_deref().unwrap_or("none")
+        )
+    }
+}
+
+/// 单个 Provider 的退化统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DegenerateProviderStats {
+    pub provider: String,
+    /// 检测的响应数
+    pub checked: u64,
+    /// 判定为退化的响应数
+    pub degenerate: u64,
+    /// 换用其他凭证重试的次数
+    pub retried: u64,
+    /// 重试后得到正常响应的次数
+    pub recovered: u64,
+    /// 退化率（degenerate / checked）
+    pub degenerate_rate: f64,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Counters {
+    checked: u64,
+    degenerate: u64,
+    retried: u64,
+    recovered: u64,
+}
+
+/// 退化响应检测器
+pub struct DegenerateResponseDetector {
+    settings: RwLock<DegenerateRetrySettings>,
+    stats: Mutex<HashMap<String, Counters>>,
+}
+
+impl DegenerateResponseDetector {
+    pub fn new(settings: &DegenerateRetrySettings) -> Self {
+        Self {
+            settings: RwLock::new(settings.clone()),
+            stats: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 热更新配置（统计保留）
+    pub fn reload(&self, settings: &DegenerateRetrySettings) {
+        *self.settings.write() = settings.clone();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.read().enabled
+    }
+
+    /// 判断请求是否需要检测（`max_tokens` 很小的请求本就预期短输出）
+    pub fn should_check(&self, max_tokens: Option<u32>) -> bool {
+        let settings = self.settings.read();
+        settings.enabled && max_tokens.is_none_or(|max| max > settings.exempt_max_tokens)
+    }
+
+    /// 检查响应体，退化时返回判定结果
+    ///
+    /// 无法识别的响应结构不判定，避免误伤。
+    pub fn inspect(
+        &self,
+        format: DegenerateResponseFormat,
+        body: &serde_json::Value,
+    ) -> Option<DegenerateVerdict> {
+        let summary = match format {
+            DegenerateResponseFormat::OpenAi => summarize_openai(body)?,
+            DegenerateResponseFormat::Anthropic => summarize_anthropic(body)?,
+        };
+
+        let settings = self.settings.read();
+        let reason_matched = summary.finish_reason.as_deref().is_some_and(|reason| {
+            settings
+                .finish_reasons
+                .iter()
+                .any(|candidate| candidate.trim().eq_ignore_ascii_case(reason))
+        });
+        let too_short =
+            !summary.has_tool_calls && summary.content_chars < settings.min_content_chars;
+
+        (reason_matched || too_short).then_some(DegenerateVerdict {
+            content_chars: summary.content_chars,
+            finish_reason: summary.finish_reason,
+        })
+    }
+
+    /// 记录一次检测结果
+    pub fn record_checked(&self, provider: &str, degenerate: bool) {
+        let mut stats = self.stats.lock();
+        let counters = stats.entry(provider.to_string()).or_default();
+        counters.checked += 1;
+        if degenerate {
+            counters.degenerate += 1;
+        }
+    }
+
+    /// 记录一次重试结果
+    pub fn record_retry(&self, provider: &str, recovered: bool) {
+        let mut stats = self.stats.lock();
+        let counters = stats.entry(provider.to_string()).or_default();
+        counters.retried += 1;
+        if recovered {
+            counters.recovered += 1;
+        }
+    }
+
+    /// 按 Provider 汇总的统计（按退化率降序）
+    pub fn stats(&self) -> Vec<DegenerateProviderStats> {
+        let mut stats: Vec<DegenerateProviderStats> = self
+            .stats
+            .lock()
+            .iter()
+            .map(|(provider, counters)| DegenerateProviderStats {
+                provider: provider.clone(),
+                checked: counters.checked,
+                degenerate: counters.degenerate,
+                retried: counters.retried,
+                recovered: counters.recovered,
+                degenerate_rate: if counters.checked == 0 {
+                    0.0
+                } else {
+                    counters.degenerate as f64 / counters.checked as f64
+                },
+            })
+            .collect();
+        stats.sort_by(|a, b| {
+            b.degenerate_rate
+                .total_cmp(&a.degenerate_rate)
+                .then_with(|| a.provider.cmp(&b.provider))
+        });
+        stats
+    }
+
+    pub fn reset_stats(&self) {
+        self.stats.lock().clear();
+    }
+}
+
+/// 响应内容摘要
+struct ResponseSummary {
+    content_chars: usize,
+    has_tool_calls: bool,
+    finish_reason: Option<String>,
+}
+
+fn summarize_openai(body: &serde_json::Value) -> Option<ResponseSummary> {
+    let choices = body.get("choices")?.as_array()?;
+    let mut content_chars = 0;
+    let mut has_tool_calls = false;
+    for choice in choices {
+        let Some(message) = choice.get("message") else {
+            continue;
+        };
+        content_chars += match message.get("content") {
+            Some(serde_json::Value::String(text)) => text.trim().chars().count(),
+            Some(serde_json::Value::Array(parts)) => parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+                .map(|text| text.trim().chars().count())
+                .sum(),
+            _ => 0,
+        };
+        has_tool_calls |= message
+            .get("tool_calls")
+            .and_then(|calls| calls.as_array())
+            .is_some_and(|calls| !calls.is_empty())
+            || message
+                .get("function_call")
+                .is_some_and(|call| !call.is_null());
+    }
+    let finish_reason = choices
+        .first()
+        .and_then(|choice| choice.get("finish_reason"))
+        .and_then(|reason| reason.as_str())
+        .map(str::to_string);
+    Some(ResponseSummary {
+        content_chars,
+        has_tool_calls,
+        finish_reason,
+    })
+}
+
+fn summarize_anthropic(body: &serde_json::Value) -> Option<ResponseSummary> {
+    let blocks = body.get("content")?.as_array()?;
+    let mut content_chars = 0;
+    let mut has_tool_calls = false;
+    for block in blocks {
+        match block.get("type").and_then(|t| t.as_str()) {
+            Some("text") => {
+                content_chars += block
+                    .get("text")
+                    .and_then(|t| t.as_str())
+                    .map_or(0, |text| text.trim().chars().count());
+            }
+            Some("tool_use") | Some("server_tool_use") => has_tool_calls = true,
+            _ => {}
+        }
+    }
+    let finish_reason = body
+        .get("stop_reason")
+        .and_then(|reason| reason.as_str())
+        .map(str::to_string);
+    Some(ResponseSummary {
+        content_chars,
+        has_tool_calls,
+        finish_reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn detector() -> DegenerateResponseDetector {
+        DegenerateResponseDetector::new(&DegenerateRetrySettings::default())
+    }
+
+    fn openai(content: serde_json::Value, finish_reason: &str) -> serde_json::Value {
+        json!({
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": content },
+                "finish_reason": finish_reason,
+            }]
+        })
+    }
+
+    #[test]
+    fn test_openai_empty_and_single_char_are_degenerate() {
+        let detector = detector();
+        let format = DegenerateResponseFormat::OpenAi;
+
+        let verdict = detector
+            .inspect(format, &openai(json!(""), "stop"))
+            .unwrap();
+        assert_eq!(verdict.content_chars, 0);
+        assert_eq!(verdict.finish_reason.as_deref(), Some("stop"));
+        assert!(detector
+            .inspect(format, &openai(json!(" .\n"), "stop"))
+            .is_some());
+        assert!(detector
+            .inspect(format, &openai(json!(null), "stop"))
+            .is_some());
+        assert!(detector
+            .inspect(format, &openai(json!("好的"), "stop"))
+            .is_none());
+
+        // 只有工具调用的响应是正常的
+        let tool_call = json!({
+            "choices": [{
+                "message": {
+                    "content": null,
+                    "tool_calls": [{ "id": "call_1", "type": "function" }],
+                },
+                "finish_reason": "tool_calls",
+            }]
+        });
+        assert!(detector.inspect(format, &tool_call).is_none());
+
+        // 无法识别的结构不判定
+        assert!(detector.inspect(format, &json!({ "error": "x" })).is_none());
+    }
+
+    #[test]
+    fn test_finish_reason_and_exempt_max_tokens() {
+        let detector = DegenerateResponseDetector::new(&DegenerateRetrySettings {
+            finish_reasons: vec!["Error".to_string()],
+            ..DegenerateRetrySettings::default()
+        });
+
+        let anthropic = json!({
+            "content": [{ "type": "text", "text": "A long enough answer" }],
+            "stop_reason": "error",
+        });
+        assert!(detector
+            .inspect(DegenerateResponseFormat::Anthropic, &anthropic)
+            .is_some());
+        let tool_use = json!({
+            "content": [{ "type": "tool_use", "id": "toolu_1", "name": "search", "input": {} }],
+            "stop_reason": "tool_use",
+        });
+        assert!(detector
+            .inspect(DegenerateResponseFormat::Anthropic, &tool_use)
+            .is_none());
+
+        assert!(detector.should_check(None));
+        assert!(detector.should_check(Some(1024)));
+        assert!(!detector.should_check(Some(1)));
+        detector.reload(&DegenerateRetrySettings {
+            enabled: false,
+            ..DegenerateRetrySettings::default()
+        });
+        assert!(!detector.should_check(None));
+    }
+
+    #[test]
+    fn test_stats_per_provider() {
+        let detector = detector();
+        detector.record_checked("openai", true);
+        detector.record_checked("openai", false);
+        detector.record_retry("openai", true);
+        detector.record_checked("claude", false);
+
+        let stats = detector.stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].provider, "openai");
+        assert_eq!(
+            (
+                stats[0].checked,
+                stats[0].degenerate,
+                stats[0].retried,
+                stats[0].recovered
+            ),
+            (2, 1, 1, 1)
+        );
+        assert!((stats[0].degenerate_rate - 0.5).abs() < f64::EPSILON);
+        assert_eq!(stats[1].degenerate_rate, 0.0);
+
+        detector.reset_stats();
+        assert!(detector.stats().is_empty());
+    }
+}
@@ -1,7 +1,22 @@
 //! 服务器中间件模块
 
+pub mod api_key_rate_limit;
+pub mod audit_log;
 pub mod capability_routing_metrics;
+pub mod conversion_loss;
+pub mod cost_cap;
+pub mod cost_ledger;
+pub mod degenerate_response;
 pub mod idempotency;
+pub mod pool_rate_limit_headers;
+pub mod quick_fix;
 pub mod rate_limit;
+pub mod request_deadline;
 pub mod request_dedup;
+pub mod request_id;
 pub mod response_cache;
+pub mod route_auth;
+pub mod session_affinity;
+pub mod sse_flow_control;
+pub mod system_prompt_guard;
+pub mod tenant;
@@ -0,0 +1,300 @@
+//! 号池限流响应头合成中间件
+//!
+//! 下游客户端看不到号池状态。对经号池转发的请求（响应带 `x-lime-effective-provider`），
+//! 按该 Provider 类型下所有启用凭证的配额估算与冷却状态合成标准限流响应头：
+//! - `x-ratelimit-{limit,remaining,reset}-{requests,tokens}`：可用凭证的额度之和，
+//!   任一可用凭证无法估算时不输出对应项（号池总额度未知）
+//! - `retry-after`：429 / 503 响应且处理器未设置时，取号池最早恢复时间（配额冷却结束或额度重置）
+//!
+//! 上游单个凭证返回的同名响应头会被号池汇总值覆盖。
+
+use crate::AppState;
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use chrono::{DateTime, Utc};
+use lime_core::database::dao::provider_pool::ProviderPoolDao;
+use lime_core::database::lock_db;
+use lime_core::models::provider_pool_model::PoolProviderType;
+use lime_credential::{QuotaRemaining, RemainingMeter};
+
+/// 处理器写入的实际服务 Provider 响应头
+const EFFECTIVE_PROVIDER_HEADER: &str = "x-lime-effective-provider";
+
+/// 单个凭证的号池状态
+#[derive(Debug, Clone)]
+pub struct CredentialPoolState {
+    /// 健康、未禁用且未处于配额冷却
+    pub available: bool,
+    /// 配额冷却结束时间
+    pub cooldown_until: Option<DateTime<Utc>>,
+    pub remaining: QuotaRemaining,
+}
+
+/// 号池汇总后的单项额度
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PoolLimit {
+    /// 总额度（任一可用凭证总额度未知时为 None）
+    pub limit: Option<u64>,
+    pub remaining: u64,
+    /// 最早的额度重置时间
+    pub reset_at: Option<DateTime<Utc>>,
+}
+
+/// 号池限流汇总
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PoolRateLimits {
+    pub requests: Option<PoolLimit>,
+    pub tokens: Option<PoolLimit>,
+    /// 最早恢复额度的时间（冷却结束或耗尽额度重置）
+    pub recovery_at: Option<DateTime<Utc>>,
+}
+
+impl PoolRateLimits {
+    /// 汇总号池中各凭证的状态
+    pub fn aggregate(credentials: &[CredentialPoolState], now: DateTime<Utc>) -> Self {
+        let available: Vec<&CredentialPoolState> =
+            credentials.iter().filter(|c| c.available).collect();
+        let sum = |meter: fn(&QuotaRemaining) -> Option<RemainingMeter>| {
+            let meters = available
+                .iter()
+                .map(|c| meter(&c.remaining))
+                .collect::<Option<Vec<_>>>()?;
+            Some(PoolLimit {
+                limit: meters
+                    .iter()
+                    .map(|m| m.limit)
+                    .sum::<Option<u64>>()
+                    .filter(|_| !meters.is_empty()),
+                remaining: meters.iter().map(|m| m.remaining).sum(),
+                reset_at: meters.iter().filter_map(|m| m.reset_at).min(),
+            })
+        };
+
+        let cooldowns = credentials.iter().filter_map(|c| c.cooldown_until);
+        let exhausted_resets = available
+            .iter()
+            .flat_map(|c| [c.remaining.requests, c.remaining.tokens])
+            .flatten()
+            .filter(|m| m.remaining == 0)
+            .filter_map(|m| m.reset_at);
+
+        Self {
+            requests: sum(|r| r.requests),
+            tokens: sum(|r| r.tokens),
+            recovery_at: cooldowns
+                .chain(exhausted_resets)
+                .filter(|at| *at > now)
+                .min(),
+        }
+    }
+
+    /// 写入限流响应头
+    pub fn apply_headers(&self, status: StatusCode, headers: &mut HeaderMap, now: DateTime<Utc>) {
+        for (kind, limit) in [("requests", self.requests), ("tokens", self.tokens)] {
+            let Some(limit) = limit else {
+                continue;
+            };
+            insert(
+                headers,
+                &format!("x-ratelimit-remaining-{kind}"),
+                limit.remaining.to_string(),
+            );
+            if let Some(total) = limit.limit {
+                insert(
+                    headers,
+                    &format!("x-ratelimit-limit-{kind}"),
+                    total.to_string(),
+                );
+            }
+            if let Some(reset_at) = limit.reset_at {
+                insert(
+                    headers,
+                    &format!("x-ratelimit-reset-{kind}"),
+                    format!("{}s", secs_until(reset_at, now)),
+                );
+            }
+        }
+
+        let throttled = matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+        );
+        if throttled && !headers.contains_key(header::RETRY_AFTER) {
+            if let Some(recovery_at) = self.recovery_at {
+                headers.insert(
+                    header::RETRY_AFTER,
+                    HeaderValue::from(secs_until(recovery_at, now).max(1)),
+                );
+            }
+        }
+    }
+}
+
+fn insert(headers: &mut HeaderMap, name: &str, value: String) {
+    if let (Ok(name), Ok(value)) = (
+        header::HeaderName::from_bytes(name.as_bytes()),
+        HeaderValue::from_str(&value),
+    ) {
+        headers.insert(name, value);
+    }
+}
+
+/// 距离指定时间的秒数（向上取整，已过去时为 0）
+fn secs_until(at: DateTime<Utc>, now: DateTime<Utc>) -> i64 {
+    let millis = (at - now).num_milliseconds().max(0);
+    (millis + 999) / 1000
+}
+
+/// 读取 Provider 类型下所有启用凭证的号池状态
+fn pool_states(
+    state: &AppState,
+    provider_type: &PoolProviderType,
+) -> Option<Vec<CredentialPoolState>> {
+    let db = state.db.as_ref()?;
+    let credentials = {
+        let conn = lock_db(db).ok()?;
+        ProviderPoolDao::get_by_type(&conn, provider_type).ok()?
+    };
+    let quota = &state.quota_manager;
+    Some(
+        credentials
+            .iter()
+            .filter(|c| !c.is_disabled)
+            .map(|c| CredentialPoolState {
+                available: c.is_available() && quota.is_available(&c.uuid),
+                cooldown_until: quota.get_cooldown_until(&c.uuid),
+                remaining: quota.remaining(&c.uuid),
+            })
+            .collect(),
+    )
+}
+
+/// 号池限流响应头合成中间件
+pub async fn synthesize_pool_rate_limit_headers(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let mut response = next.run(request).await;
+
+    let Some(provider_type) = response
+        .headers()
+        .get(EFFECTIVE_PROVIDER_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<PoolProviderType>().ok())
+    else {
+        return response;
+    };
+    let Some(states) = pool_states(&state, &provider_type).filter(|s| !s.is_empty()) else {
+        return response;
+    };
+
+    let now = Utc::now();
+    let status = response.status();
+    PoolRateLimits::aggregate(&states, now).apply_headers(status, response.headers_mut(), now);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use lime_credential::RemainingSource;
+
+    fn meter(
+        limit: Option<u64>,
+        remaining: u64,
+        reset_at: Option<DateTime<Utc>>,
+    ) -> RemainingMeter {
+        RemainingMeter {
+            limit,
+            remaining,
+            remaining_ratio: None,
+            reset_at,
+            source: RemainingSource::Provider,
+        }
+    }
+
+    fn credential(
+        available: bool,
+        requests: Option<RemainingMeter>,
+        tokens: Option<RemainingMeter>,
+        now: DateTime<Utc>,
+    ) -> CredentialPoolState {
+        CredentialPoolState {
+            available,
+            cooldown_until: None,
+            remaining: QuotaRemaining {
+                credential_id: "cred".to_string(),
+                requests,
+                tokens,
+                provider_observed_at: None,
+                updated_at: now,
+            },
+        }
+    }
+
+    #[test]
+    fn test_aggregate_sums_available_credentials() {
+        let now = Utc::now();
+        let reset = now + Duration::seconds(30);
+        let mut cooling = credential(false, Some(meter(Some(100), 0, None)), None, now);
+        cooling.cooldown_until = Some(now + Duration::seconds(90));
+        let credentials = vec![
+            credential(
+                true,
+                Some(meter(Some(50), 10, Some(reset))),
+                Some(meter(None, 1000, None)),
+                now,
+            ),
+            credential(true, Some(meter(Some(50), 0, Some(reset))), None, now),
+            cooling,
+        ];
+
+        let limits = PoolRateLimits::aggregate(&credentials, now);
+        assert_eq!(
+            limits.requests,
+            Some(PoolLimit {
+                limit: Some(100),
+                remaining: 10,
+                reset_at: Some(reset),
+            })
+        );
+        // 第二个凭证无法估算 Token 余量，号池 Token 额度未知
+        assert_eq!(limits.tokens, None);
+        assert_eq!(limits.recovery_at, Some(reset));
+
+        let mut headers = HeaderMap::new();
+        limits.apply_headers(StatusCode::OK, &mut headers, now);
+        assert_eq!(headers["x-ratelimit-remaining-requests"], "10");
+        assert_eq!(headers["x-ratelimit-limit-requests"], "100");
+        assert_eq!(headers["x-ratelimit-reset-requests"], "30s");
+        assert!(!headers.contains_key("x-ratelimit-remaining-tokens"));
+        assert!(!headers.contains_key(header::RETRY_AFTER));
+    }
+
+    #[test]
+    fn test_retry_after_when_pool_exhausted() {
+        let now = Utc::now();
+        let mut cooling = credential(false, None, None, now);
+        cooling.cooldown_until = Some(now + Duration::milliseconds(45_500));
+
+        let limits = PoolRateLimits::aggregate(&[cooling], now);
+        let requests = limits.requests.unwrap();
+        assert_eq!(requests.remaining, 0);
+        assert_eq!(requests.limit, None);
+
+        let mut headers = HeaderMap::new();
+        limits.apply_headers(StatusCode::TOO_MANY_REQUESTS, &mut headers, now);
+        assert_eq!(headers["x-ratelimit-remaining-requests"], "0");
+        assert_eq!(headers[header::RETRY_AFTER], "46");
+
+        // 处理器已设置的 retry-after 保持不变
+        let mut headers = HeaderMap::new();
+        headers.insert(header::RETRY_AFTER, HeaderValue::from_static("5"));
+        limits.apply_headers(StatusCode::SERVICE_UNAVAILABLE, &mut headers, now);
+        assert_eq!(headers[header::RETRY_AFTER], "5");
+    }
+}
@@ -0,0 +1,224 @@
+//! 错误快速修复建议
+//!
+//! 请求失败时（见 [`crate::record_request_telemetry`]）将错误消息分类为 [`ProviderError`]，
+//! 结合凭证、模型与 base_url 生成修复建议：
+//! - 按请求 ID 暂存，由 [`attach_quick_fixes`] 写入 JSON 错误响应的 `error.quickFixes`
+//! - 广播 [`ProviderQuickFixEvent`]，桌面端转发为前端事件驱动一键修复按钮
+//! - 保留最近的建议供前端查询
+//!
+//! 流式响应中途出错时无法改写响应体，仅推送事件。
+
+use crate::AppState;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use axum::middleware::Next;
+use axum::response::Response;
+use chrono::{DateTime, Utc};
+use lime_core::processor::current_request_id;
+use lime_providers::providers::{ProviderError, QuickFix, QuickFixContext};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use tokio::sync::broadcast;
+
+/// 保留的最近建议数
+const RECENT_QUICK_FIX_LIMIT: usize = 100;
+
+/// 改写错误响应体的大小上限
+const MAX_ERROR_BODY_BYTES: usize = 1024 * 1024;
+
+/// 一次失败请求的修复建议
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderQuickFixEvent {
+    pub request_id: String,
+    pub timestamp: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 分类结果（`ProviderError::error_type`）
+    pub error_type: String,
+    /// 脱敏后的错误消息
+    pub message: String,
+    pub suggestions: Vec<QuickFix>,
+}
+
+/// 修复建议暂存与广播
+pub struct QuickFixRegistry {
+    recent: Mutex<VecDeque<ProviderQuickFixEvent>>,
+    sender: broadcast::Sender<ProviderQuickFixEvent>,
+}
+
+impl Default for QuickFixRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl QuickFixRegistry {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(RECENT_QUICK_FIX_LIMIT);
+        Self {
+            recent: Mutex::new(VecDeque::new()),
+            sender,
+        }
+    }
+
+    /// 分类错误并生成建议，无可执行建议时返回 None
+    pub fn record(
+        &self,
+        request_id: &str,
+        context: QuickFixContext,
+        message: &str,
+    ) -> Option<ProviderQuickFixEvent> {
+        let error = ProviderError::classify(None, message);
+        let suggestions = error.quick_fixes(&context);
+        if suggestions.is_empty() {
+            return None;
+        }
+
+        let event = ProviderQuickFixEvent {
+            request_id: request_id.to_string(),
+            timestamp: Utc::now(),
+            provider: context.provider,
+            credential_id: context.credential_id,
+            model: context.model,
+            error_type: error.error_type().to_string(),
+            message: message.to_string(),
+            suggestions,
+        };
+        {
+            let mut recent = self.recent.lock();
+            recent.retain(|e| e.request_id != event.request_id);
+            if recent.len() >= RECENT_QUICK_FIX_LIMIT {
+                recent.pop_front();
+            }
+            recent.push_back(event.clone());
+        }
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.sender.send(event.clone());
+        Some(event)
+    }
+
+    /// 获取请求的修复建议
+    pub fn get(&self, request_id: &str) -> Option<Vec<QuickFix>> {
+        self.recent
+            .lock()
+            .iter()
+            .rev()
+            .find(|e| e.request_id == request_id)
+            .map(|e| e.suggestions.clone())
+    }
+
+    /// 最近的修复建议（最新在前）
+    pub fn recent(&self, limit: usize) -> Vec<ProviderQuickFixEvent> {
+        self.recent
+            .lock()
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    /// 订阅后续的修复建议
+    pub fn subscribe(&self) -> broadcast::Receiver<ProviderQuickFixEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// 将修复建议写入 JSON 错误响应的 `error.quickFixes`
+pub async fn attach_quick_fixes(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    if !(response.status().is_client_error() || response.status().is_server_error()) {
+        return response;
+    }
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    let suggestions = current_request_id().and_then(|id| state.quick_fixes.get(&id));
+    let (true, Some(suggestions)) = (is_json, suggestions) else {
+        return response;
+    };
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_ERROR_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("[QUICK_FIX] 读取错误响应体失败: {}", e);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+    let body = match inject_quick_fixes(&bytes, &suggestions) {
+        Some(body) => {
+            parts.headers.remove(CONTENT_LENGTH);
+            Body::from(body)
+        }
+        None => Body::from(bytes),
+    };
+    Response::from_parts(parts, body)
+}
+
+/// 在 `{"error": {...}}` 中加入 `quickFixes`，响应体不是该结构时返回 None
+fn inject_quick_fixes(body: &[u8], suggestions: &[QuickFix]) -> Option<Vec<u8>> {
+    let mut json: serde_json::Value = serde_json::from_slice(body).ok()?;
+    let error = json.get_mut("error")?.as_object_mut()?;
+    error.insert(
+        "quickFixes".to_string(),
+        serde_json::to_value(suggestions).ok()?,
+    );
+    serde_json::to_vec(&json).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_providers::providers::QuickFixAction;
+
+    #[test]
+    fn test_record_and_inject() {
+        let registry = QuickFixRegistry::new();
+        let context = QuickFixContext {
+            provider: Some("kiro".to_string()),
+            credential_id: Some("cred-1".to_string()),
+            ..Default::default()
+        };
+        let mut receiver = registry.subscribe();
+
+        assert!(registry
+            .record("req-0", context.clone(), "something odd")
+            .is_none());
+        let event = registry
+            .record("req-1", context, "HTTP 403 - token invalid")
+            .unwrap();
+        assert_eq!(event.error_type, "AuthenticationError");
+        assert_eq!(receiver.try_recv().unwrap().request_id, "req-1");
+        assert!(registry.get("req-0").is_none());
+
+        let suggestions = registry.get("req-1").unwrap();
+        assert_eq!(
+            suggestions[0].action,
+            QuickFixAction::RefreshToken {
+                credential_id: "cred-1".to_string()
+            }
+        );
+
+        let body = br#"{"error":{"code":"AUTHENTICATION_FAILED","message":"x"}}"#;
+        let json: serde_json::Value =
+            serde_json::from_slice(&inject_quick_fixes(body, &suggestions).unwrap()).unwrap();
+        assert_eq!(
+            json["error"]["quickFixes"][0]["action"]["kind"],
+            "refresh_token"
+        );
+        assert!(inject_quick_fixes(b"[]", &suggestions).is_none());
+    }
+}
@@ -0,0 +1,296 @@
+//! 请求截止时间中间件
+//!
+//! 按 `x-lime-deadline-ms` 请求头或 `server.request_deadline.default_ms` 为请求设置整体截止时间：
+//! - 在截止时间作用域内执行后续处理，重试、故障转移与工具调用循环据此裁剪剩余预算
+//! - 截止前仍未返回响应时直接返回 504 `DEADLINE_EXCEEDED`
+//! - SSE 响应在距截止 `finalize_reserve_ms` 时停止转发上游，按协议补发以
+//!   `deadline_exceeded` 为结束原因的收尾事件后正常结束流
+
+use crate::middleware::sse_flow_control::is_sse_response;
+use crate::AppState;
+use axum::body::{Body, Bytes};
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use futures::StreamExt;
+use lime_core::config::RequestDeadlineSettings;
+use lime_core::errors::GatewayErrorCode;
+use lime_core::processor::{
+    current_request_id, parse_deadline_header, scope_deadline, RequestDeadline,
+    DEADLINE_EXCEEDED_FINISH_REASON, REQUEST_DEADLINE_HEADER,
+};
+use lime_server_utils::build_error_response_with_meta;
+use std::time::Duration;
+
+/// 流式响应的协议（决定收尾事件的格式）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamProtocol {
+    OpenAiChat,
+    OpenAiResponses,
+    Anthropic,
+}
+
+impl StreamProtocol {
+    fn from_path(path: &str) -> Self {
+        let path = path.trim_end_matches('/');
+        if path.ends_with("/messages") {
+            Self::Anthropic
+        } else if path.ends_with("/responses") {
+            Self::OpenAiResponses
+        } else {
+            Self::OpenAiChat
+        }
+    }
+}
+
+/// 请求截止时间中间件
+pub async fn apply_request_deadline(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let settings = state.request_deadline.clone();
+    let Some(budget) = request_budget(request.headers(), &settings) else {
+        return next.run(request).await;
+    };
+
+    let deadline = RequestDeadline::after(budget);
+    let protocol = StreamProtocol::from_path(request.uri().path());
+    let run = scope_deadline(deadline, next.run(request));
+    match tokio::time::timeout_at(deadline.instant().into(), run).await {
+        Ok(response) => finalize_sse_at_deadline(
+            response,
+            deadline,
+            Duration::from_millis(settings.finalize_reserve_ms),
+            protocol,
+        ),
+        Err(_) => {
+            let request_id = current_request_id();
+            tracing::warn!(
+                "[DEADLINE] request_id={} 预算 {}ms 内未返回响应",
+                request_id.as_deref().unwrap_or("-"),
+                budget.as_millis()
+            );
+            build_error_response_with_meta(
+                StatusCode::GATEWAY_TIMEOUT.as_u16(),
+                &format!("Request deadline exceeded after {}ms", budget.as_millis()),
+                request_id.as_deref(),
+                None,
+                Some(GatewayErrorCode::DeadlineExceeded),
+            )
+        }
+    }
+}
+
+/// 本次请求的预算：合法的客户端请求头优先，否则使用配置的默认值
+fn request_budget(headers: &HeaderMap, settings: &RequestDeadlineSettings) -> Option<Duration> {
+    let max = Duration::from_millis(settings.max_ms.max(1));
+    let from_header = settings
+        .allow_client_header
+        .then(|| headers.get(REQUEST_DEADLINE_HEADER)?.to_str().ok())
+        .flatten()
+        .and_then(|value| parse_deadline_header(value, max));
+    from_header.or_else(|| {
+        (settings.default_ms > 0).then(|| Duration::from_millis(settings.default_ms).min(max))
+    })
+}
+
+/// 为 SSE 响应加上截止收尾，非 SSE 响应原样返回
+fn finalize_sse_at_deadline(
+    response: Response,
+    deadline: RequestDeadline,
+    reserve: Duration,
+    protocol: StreamProtocol,
+) -> Response {
+    if !is_sse_response(&response) {
+        return response;
+    }
+
+    let cutoff = deadline
+        .instant()
+        .checked_sub(reserve)
+        .unwrap_or_else(|| deadline.instant());
+    let request_id = current_request_id().unwrap_or_default();
+    let (parts, body) = response.into_parts();
+    let mut source = body.into_data_stream();
+    let stream = async_stream::stream! {
+        // 上一个 chunk 是否以完整事件结尾，决定收尾事件前是否需要补齐分隔
+        let mut at_boundary = true;
+        loop {
+            match tokio::time::timeout_at(cutoff.into(), source.next()).await {
+                Ok(Some(Ok(bytes))) => {
+                    if !bytes.is_empty() {
+                        at_boundary = bytes.ends_with(b"\n\n");
+                    }
+                    yield Ok(bytes);
+                }
+                Ok(Some(Err(e))) => {
+                    yield Err(e);
+                    return;
+                }
+                Ok(None) => return,
+                Err(_) => {
+                    tracing::warn!(
+                        "[DEADLINE] request_id={} 流式响应临近截止，以 {} 结束",
+                        request_id,
+                        DEADLINE_EXCEEDED_FINISH_REASON
+                    );
+                    yield Ok(deadline_events(protocol, &request_id, at_boundary));
+                    return;
+                }
+            }
+        }
+    };
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+/// 按协议构造以 `deadline_exceeded` 结束的收尾事件
+fn deadline_events(protocol: StreamProtocol, request_id: &str, at_boundary: bool) -> Bytes {
+    let mut out = String::new();
+    if !at_boundary {
+        out.push_str("\n\n");
+    }
+    match protocol {
+        StreamProtocol::OpenAiChat => {
+            let chunk = serde_json::json!({
+                "id": format!("chatcmpl-{request_id}"),
+                "object": "chat.completion.chunk",
+                "created": chrono::Utc::now().timestamp(),
+                "choices": [{
+                    "index": 0,
+                    "delta": {},
+                    "finish_reason": DEADLINE_EXCEEDED_FINISH_REASON,
+                }],
+            });
+            out.push_str(&format!("data: {chunk}\n\ndata: [DONE]\n\n"));
+        }
+        StreamProtocol::OpenAiResponses => {
+            let event = serde_json::json!({
+                "type": "response.incomplete",
+                "response": {
+                    "status": "incomplete",
+                    "incomplete_details": { "reason": DEADLINE_EXCEEDED_FINISH_REASON },
+                },
+            });
+            out.push_str(&format!("event: response.incomplete\ndata: {event}\n\n"));
+        }
+        StreamProtocol::Anthropic => {
+            let delta = serde_json::json!({
+                "type": "message_delta",
+                "delta": { "stop_reason": DEADLINE_EXCEEDED_FINISH_REASON, "stop_sequence": null },
+                "usage": { "output_tokens": 0 },
+            });
+            out.push_str(&format!(
+                "event: message_delta\ndata: {delta}\n\nevent: message_stop\ndata: {{\"type\":\"message_stop\"}}\n\n"
+            ));
+        }
+    }
+    Bytes::from(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{header, HeaderValue};
+
+    fn slow_sse_response() -> Response {
+        let stream = async_stream::stream! {
+            yield Ok::<_, std::io::Error>(Bytes::from("data: {\"n\":1}\n\n"));
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            yield Ok(Bytes::from("data: {\"n\":2}\n\n"));
+        };
+        Response::builder()
+            .header(header::CONTENT_TYPE, "text/event-stream")
+            .body(Body::from_stream(stream))
+            .unwrap()
+    }
+
+    async fn collect_body(response: Response) -> String {
+        let frames: Vec<_> = response.into_body().into_data_stream().collect().await;
+        frames
+            .into_iter()
+            .map(|frame| String::from_utf8(frame.unwrap().to_vec()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_request_budget_prefers_valid_header() {
+        let settings = RequestDeadlineSettings {
+            default_ms: 30_000,
+            max_ms: 60_000,
+            ..Default::default()
+        };
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            request_budget(&headers, &settings),
+            Some(Duration::from_secs(30))
+        );
+
+        headers.insert(REQUEST_DEADLINE_HEADER, HeaderValue::from_static("5000"));
+        assert_eq!(
+            request_budget(&headers, &settings),
+            Some(Duration::from_secs(5))
+        );
+        headers.insert(REQUEST_DEADLINE_HEADER, HeaderValue::from_static("900000"));
+        assert_eq!(
+            request_budget(&headers, &settings),
+            Some(Duration::from_secs(60))
+        );
+
+        let ignore_header = RequestDeadlineSettings {
+            allow_client_header: false,
+            default_ms: 0,
+            ..settings
+        };
+        assert_eq!(request_budget(&headers, &ignore_header), None);
+    }
+
+    #[test]
+    fn test_stream_protocol_from_path() {
+        assert_eq!(
+            StreamProtocol::from_path("/v1/messages"),
+            StreamProtocol::Anthropic
+        );
+        assert_eq!(
+            StreamProtocol::from_path("/v1/responses"),
+            StreamProtocol::OpenAiResponses
+        );
+        assert_eq!(
+            StreamProtocol::from_path("/kiro/v1/chat/completions"),
+            StreamProtocol::OpenAiChat
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stream_is_finalized_before_deadline() {
+        let deadline = RequestDeadline::after(Duration::from_millis(100));
+        let response = finalize_sse_at_deadline(
+            slow_sse_response(),
+            deadline,
+            Duration::from_millis(20),
+            StreamProtocol::OpenAiChat,
+        );
+
+        let body = collect_body(response).await;
+        assert!(body.starts_with("data: {\"n\":1}\n\n"));
+        assert!(!body.contains("\"n\":2"));
+        assert!(body.contains("\"finish_reason\":\"deadline_exceeded\""));
+        assert!(body.ends_with("data: [DONE]\n\n"));
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_stream_ends_with_message_stop() {
+        let deadline = RequestDeadline::after(Duration::from_millis(50));
+        let response = finalize_sse_at_deadline(
+            slow_sse_response(),
+            deadline,
+            Duration::ZERO,
+            StreamProtocol::Anthropic,
+        );
+
+        let body = collect_body(response).await;
+        assert!(body.contains("\"stop_reason\":\"deadline_exceeded\""));
+        assert!(body.ends_with("event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n"));
+    }
+}
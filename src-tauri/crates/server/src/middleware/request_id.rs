@@ -0,0 +1,40 @@
+//! 请求 ID 中间件
+//!
+//! 为每个请求分配 request_id（客户端传入合法的 `x-request-id` 时沿用），并：
+//! - 在请求 ID 作用域与 `request` tracing span 内执行后续处理，日志携带 `request_id` 字段
+//! - 响应始终附带 `x-lime-request-id`（处理器已设置时保持不变）
+
+use axum::extract::Request;
+use axum::http::HeaderValue;
+use axum::middleware::Next;
+use axum::response::Response;
+use lime_core::processor::{
+    normalize_client_request_id, scope_request_id, REQUEST_ID_HEADER, UPSTREAM_REQUEST_ID_HEADER,
+};
+use tracing::Instrument;
+
+/// 请求 ID 中间件
+pub async fn assign_request_id(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(UPSTREAM_REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(normalize_client_request_id)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+    );
+
+    let mut response = scope_request_id(request_id.clone(), next.run(request))
+        .instrument(span)
+        .await;
+    if !response.headers().contains_key(REQUEST_ID_HEADER) {
+        if let Ok(value) = HeaderValue::from_str(&request_id) {
+            response.headers_mut().insert(REQUEST_ID_HEADER, value);
+        }
+    }
+    response
+}
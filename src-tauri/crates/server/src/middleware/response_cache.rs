@@ -4,15 +4,35 @@
 //! 典型使用方式：
 //! - 请求进入时：按规范化请求生成 key，先查缓存
 //! - 响应返回时：对可缓存状态码（默认仅 200）且体积可接受的响应写入缓存
+//!
+//! 缓存键有两种匹配方式：
+//! - `exact`：字段顺序规范化后完全相同才命中（与请求去重使用相同的指纹）
+//! - `semantic`：额外折叠文本空白、将单段文本内容展开为字符串，并忽略
+//!   `metadata` 等不影响输出的字段，适合反复发送相同提示词的调试场景
+//!
+//! 可按路由（`chat_completions` / `anthropic_messages`）单独关闭，命中统计同时按路由记录。
 
 use indexmap::IndexMap;
+use lime_core::config::{ResponseCacheMode, ResponseCacheSettings};
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::{
     collections::HashMap,
     time::{Duration, Instant},
 };
 
+use super::request_dedup::build_request_fingerprint;
+
+/// 语义模式下忽略的字段（不影响输出内容）
+const SEMANTIC_IGNORED_KEYS: &[&str] = &[
+    "metadata",
+    "stream_options",
+    "store",
+    "service_tier",
+    "cache_control",
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseCacheConfig {
     #[serde(default = "default_enabled")]
@@ -25,6 +45,11 @@ pub struct ResponseCacheConfig {
     pub max_body_bytes: usize,
     #[serde(default = "default_cacheable_status_codes")]
     pub cacheable_status_codes: Vec<u16>,
+    #[serde(default)]
+    pub mode: ResponseCacheMode,
+    /// 按路由启用，未列出的路由默认启用
+    #[serde(default)]
+    pub routes: HashMap<String, bool>,
 }
 
 fn default_enabled() -> bool {
@@ -51,6 +76,22 @@ impl Default for ResponseCacheConfig {
             max_entries: default_max_entries(),
             max_body_bytes: default_max_body_bytes(),
             cacheable_status_codes: default_cacheable_status_codes(),
+            mode: ResponseCacheMode::default(),
+            routes: HashMap::new(),
+        }
+    }
+}
+
+impl From<&ResponseCacheSettings> for ResponseCacheConfig {
+    fn from(settings: &ResponseCacheSettings) -> Self {
+        Self {
+            enabled: settings.enabled,
+            ttl_secs: settings.ttl_secs,
+            max_entries: settings.max_entries,
+            max_body_bytes: settings.max_body_bytes,
+            cacheable_status_codes: settings.cacheable_status_codes.clone(),
+            mode: settings.mode,
+            routes: settings.routes.clone(),
         }
     }
 }
@@ -70,6 +111,16 @@ pub struct ResponseCacheStats {
     pub evictions: u64,
 }
 
+/// 单个路由的缓存统计
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResponseCacheRouteStats {
+    pub route: String,
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub hit_rate_percent: f64,
+}
+
 #[derive(Debug, Clone)]
 struct CacheEntry {
     response: CachedHttpResponse,
@@ -87,6 +138,7 @@ pub struct ResponseCacheStore {
     config: ResponseCacheConfig,
     entries: Mutex<IndexMap<String, CacheEntry>>,
     counters: Mutex<CacheCounters>,
+    route_counters: Mutex<HashMap<String, CacheCounters>>,
 }
 
 impl ResponseCacheStore {
@@ -95,6 +147,7 @@ impl ResponseCacheStore {
             config,
             entries: Mutex::new(IndexMap::new()),
             counters: Mutex::new(CacheCounters::default()),
+            route_counters: Mutex::new(HashMap::new()),
         }
     }
 
@@ -102,6 +155,24 @@ impl ResponseCacheStore {
         self.config.enabled
     }
 
+    /// 缓存是否对该路由启用
+    pub fn is_route_enabled(&self, route: &str) -> bool {
+        self.config.enabled && self.config.routes.get(route) != Some(&false)
+    }
+
+    /// 按配置的匹配方式生成缓存键（`<route>:<fingerprint>`）
+    pub fn cache_key(&self, route: &str, payload: &Value) -> String {
+        let payload = match self.config.mode {
+            ResponseCacheMode::Exact => payload.clone(),
+            ResponseCacheMode::Semantic => normalize_semantic(payload),
+        };
+        let fingerprint = build_request_fingerprint(&serde_json::json!({
+            "endpoint": route,
+            "payload": payload
+        }));
+        format!("{route}:{fingerprint}")
+    }
+
     pub fn config(&self) -> ResponseCacheConfig {
         self.config.clone()
     }
@@ -121,18 +192,18 @@ impl ResponseCacheStore {
         let entry = entries.shift_remove(key);
         match entry {
             None => {
-                self.counters.lock().misses += 1;
+                self.count(key, |c| c.misses += 1);
                 None
             }
             Some(entry) => {
                 let ttl = Duration::from_secs(self.config.ttl_secs);
                 if entry.cached_at.elapsed() > ttl {
-                    self.counters.lock().misses += 1;
+                    self.count(key, |c| c.misses += 1);
                     None
                 } else {
                     let response = entry.response.clone();
                     entries.insert(key.to_string(), entry);
-                    self.counters.lock().hits += 1;
+                    self.count(key, |c| c.hits += 1);
                     Some(response)
                 }
             }
@@ -165,8 +236,8 @@ impl ResponseCacheStore {
         );
 
         while entries.len() > self.config.max_entries {
-            if entries.shift_remove_index(0).is_some() {
-                self.counters.lock().evictions += 1;
+            if let Some((evicted, _)) = entries.shift_remove_index(0) {
+                self.count(&evicted, |c| c.evictions += 1);
             }
         }
 
@@ -197,12 +268,76 @@ impl ResponseCacheStore {
 
     pub fn hit_rate_percent(&self) -> f64 {
         let stats = self.stats();
-        let total = stats.hits + stats.misses;
-        if total == 0 {
-            0.0
-        } else {
-            (stats.hits as f64 / total as f64) * 100.0
+        hit_rate_percent(stats.hits, stats.misses)
+    }
+
+    /// 按路由的命中统计（按路由名排序）
+    pub fn route_stats(&self) -> Vec<ResponseCacheRouteStats> {
+        let mut stats: Vec<ResponseCacheRouteStats> = self
+            .route_counters
+            .lock()
+            .iter()
+            .map(|(route, c)| ResponseCacheRouteStats {
+                route: route.clone(),
+                hits: c.hits,
+                misses: c.misses,
+                evictions: c.evictions,
+                hit_rate_percent: hit_rate_percent(c.hits, c.misses),
+            })
+            .collect();
+        stats.sort_by(|a, b| a.route.cmp(&b.route));
+        stats
+    }
+
+    fn count(&self, key: &str, update: impl Fn(&mut CacheCounters)) {
+        update(&mut self.counters.lock());
+        let route = key.split_once(':').map_or(key, |(route, _)| route);
+        update(
+            self.route_counters
+                .lock()
+                .entry(route.to_string())
+                .or_default(),
+        );
+    }
+}
+
+fn hit_rate_percent(hits: u64, misses: u64) -> f64 {
+    let total = hits + misses;
+    if total == 0 {
+        0.0
+    } else {
+        (hits as f64 / total as f64) * 100.0
+    }
+}
+
+/// 语义模式的请求规范化
+fn normalize_semantic(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut result = Map::new();
+            for (key, val) in map {
+                if val.is_null() || SEMANTIC_IGNORED_KEYS.contains(&key.as_str()) {
+                    continue;
+                }
+                let val = match key.as_str() {
+                    "content" | "system" => single_text_part(val).unwrap_or(val),
+                    _ => val,
+                };
+                result.insert(key.clone(), normalize_semantic(val));
+            }
+            Value::Object(result)
         }
+        Value::Array(items) => Value::Array(items.iter().map(normalize_semantic).collect()),
+        Value::String(text) => Value::String(text.split_whitespace().collect::<Vec<_>>().join(" ")),
+        _ => value.clone(),
+    }
+}
+
+/// 只有一段文本的内容数组等价于纯文本
+fn single_text_part(value: &Value) -> Option<&Value> {
+    match value.as_array()?.as_slice() {
+        [part] if part.get("type").and_then(Value::as_str) == Some("text") => part.get("text"),
+        _ => None,
     }
 }
 
@@ -265,6 +400,7 @@ mod tests {
             max_entries: 10,
             max_body_bytes: 1024,
             cacheable_status_codes: vec![200, 201, 204],
+            ..Default::default()
         });
         let inserted = store.set(
             "k201",
@@ -286,6 +422,7 @@ mod tests {
             max_entries: 2,
             max_body_bytes: 1024,
             cacheable_status_codes: vec![200],
+            ..Default::default()
         });
         assert!(store.set("k1", make_response("1")));
         assert!(store.set("k2", make_response("2")));
@@ -305,9 +442,69 @@ mod tests {
             max_entries: 10,
             max_body_bytes: 1024,
             cacheable_status_codes: vec![200],
+            ..Default::default()
         });
         assert!(store.set("k4", make_response("x")));
         std::thread::sleep(Duration::from_millis(1100));
         assert!(store.get("k4").is_none());
     }
+
+    #[test]
+    fn should_match_semantically_equivalent_requests() {
+        let a = serde_json::json!({
+            "model": "m",
+            "messages": [{"role": "user", "content": "hello   world\n"}],
+            "metadata": {"trace": "1"},
+        });
+        let b = serde_json::json!({
+            "model": "m",
+            "messages": [{"role": "user", "content": [{"type": "text", "text": "hello world"}]}],
+            "temperature": null,
+        });
+
+        let exact = ResponseCacheStore::new(ResponseCacheConfig::default());
+        assert_ne!(
+            exact.cache_key("chat_completions", &a),
+            exact.cache_key("chat_completions", &b)
+        );
+
+        let semantic = ResponseCacheStore::new(ResponseCacheConfig {
+            mode: ResponseCacheMode::Semantic,
+            ..Default::default()
+        });
+        assert_eq!(
+            semantic.cache_key("chat_completions", &a),
+            semantic.cache_key("chat_completions", &b)
+        );
+        let c = serde_json::json!({
+            "model": "m",
+            "messages": [{"role": "user", "content": "hello there"}],
+        });
+        assert_ne!(
+            semantic.cache_key("chat_completions", &a),
+            semantic.cache_key("chat_completions", &c)
+        );
+    }
+
+    #[test]
+    fn should_respect_route_flags_and_track_route_stats() {
+        let store = ResponseCacheStore::new(ResponseCacheConfig {
+            routes: HashMap::from([("anthropic_messages".to_string(), false)]),
+            ..Default::default()
+        });
+        assert!(store.is_route_enabled("chat_completions"));
+        assert!(!store.is_route_enabled("anthropic_messages"));
+
+        let key = store.cache_key("chat_completions", &serde_json::json!({"model": "m"}));
+        assert!(store.get(&key).is_none());
+        assert!(store.set(&key, make_response("1")));
+        assert!(store.get(&key).is_some());
+
+        let routes = store.route_stats();
+        assert_eq!(routes.len(), 1);
+        assert_eq!(routes[0].route, "chat_completions");
+        assert_eq!(routes[0].hits, 1);
+        assert_eq!(routes[0].misses, 1);
+        assert_eq!(routes[0].hit_rate_percent, 50.0);
+    }
 }
@@ -0,0 +1,322 @@
+//! 按路由的认证中间件
+//!
+//! 根据 `server.route_auth` 配置为每个路由选择认证方式：
+//! - `none`：无需认证
+//! - `api_key`：需要服务器主 API Key 或租户 API Key
+//! - `os_user`：仅允许本机当前操作系统用户。Linux 上通过 `/proc/net/tcp*`
+//!   查出对端 socket 的属主 UID 与本进程比对；无法取得对端凭据时（其他平台、
+//!   查不到 socket）不放行，退化为要求回环连接且携带有效 API Key
+//!
+//! `none` / `os_user` 放行的请求若未携带有效 Key，会补上服务器主 API Key，
+//! 使下游处理器自身的 Key 校验同样通过。
+//!
+//! 租户 API Key 只能访问 [`TENANT_SCOPED_ROUTES`] 中的路由：只有这些处理器会按租户限定凭证子集、
+//! 速率与用量，其余路由无论认证方式如何都以 403 拒绝租户 Key，避免越过租户范围。
+
+use crate::AppState;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use lime_core::config::RouteAuthMode;
+use lime_core::errors::GatewayErrorCode;
+use lime_server_utils::build_error_response_with_meta;
+use std::net::SocketAddr;
+
+/// 在处理器内执行租户范围（凭证子集、速率限制、用量统计）的路由
+pub const TENANT_SCOPED_ROUTES: &[&str] = &[
+    "/v1/chat/completions",
+    "/v1/messages",
+    "/v1/realtime",
+    "/v1/estimate",
+];
+
+/// 对端身份校验结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerIdentity {
+    /// 与本进程属于同一操作系统用户
+    SameUser,
+    /// 属于其他用户
+    OtherUser(u32),
+    /// 当前平台无法取得对端凭据
+    Unknown,
+}
+
+/// 路由认证中间件
+pub async fn enforce_route_auth(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    let mode = state.route_auth.read().resolve(&path);
+
+    if !is_tenant_scoped_route(&path)
+        && request_api_key(request.headers(), request.uri().query())
+            .is_some_and(|key| is_tenant_api_key(&state, &key))
+    {
+        tracing::debug!("[ROUTE_AUTH] 拒绝租户 API Key 访问非租户路由: {}", path);
+        return build_error_response_with_meta(
+            StatusCode::FORBIDDEN.as_u16(),
+            "Tenant API keys are not allowed on this route",
+            None,
+            None,
+            Some(GatewayErrorCode::AuthenticationFailed),
+        );
+    }
+
+    match mode {
+        RouteAuthMode::None => {}
+        RouteAuthMode::ApiKey => {
+            if !has_known_api_key(&state, &request) {
+                tracing::debug!("[ROUTE_AUTH] 拒绝未携带有效 API Key 的请求: {}", path);
+                return build_error_response_with_meta(
+                    StatusCode::UNAUTHORIZED.as_u16(),
+                    "Valid API key required for this route",
+                    None,
+                    None,
+                    Some(GatewayErrorCode::AuthenticationFailed),
+                );
+            }
+        }
+        RouteAuthMode::OsUser => {
+            let peer = request
+                .extensions()
+                .get::<ConnectInfo<SocketAddr>>()
+                .map(|info| info.0);
+            match verify_os_user(peer) {
+                Ok(PeerIdentity::Unknown) if !has_known_api_key(&state, &request) => {
+                    tracing::warn!(
+                        "[ROUTE_AUTH] 无法确认对端用户且未携带有效 API Key，拒绝请求: {}",
+                        path
+                    );
+                    return build_error_response_with_meta(
+                        StatusCode::UNAUTHORIZED.as_u16(),
+                        "Peer identity unavailable; a valid API key is required for this route",
+                        None,
+                        None,
+                        Some(GatewayErrorCode::AuthenticationFailed),
+                    );
+                }
+                Ok(_) => {}
+                Err(reason) => {
+                    tracing::warn!("[ROUTE_AUTH] 拒绝非本机用户请求 {}: {}", path, reason);
+                    return build_error_response_with_meta(
+                        StatusCode::FORBIDDEN.as_u16(),
+                        &format!(
+                            "This route only accepts requests from the local OS user: {reason}"
+                        ),
+                        None,
+                        None,
+                        Some(GatewayErrorCode::AuthenticationFailed),
+                    );
+                }
+            }
+        }
+    }
+
+    if mode != RouteAuthMode::ApiKey {
+        stamp_server_api_key(&state, request.headers_mut());
+    }
+
+    next.run(request).await
+}
+
+/// 从请求头或 `api_key` / `token` 查询参数中读取 API Key
+pub(crate) fn request_api_key(headers: &HeaderMap, query: Option<&str>) -> Option<String> {
+    let from_header = headers
+        .get(header::AUTHORIZATION)
+        .or_else(|| headers.get("x-api-key"))
+        .and_then(|v| v.to_str().ok())
+        .map(|raw| raw.strip_prefix("Bearer ").unwrap_or(raw).to_string());
+    if from_header.is_some() {
+        return from_header;
+    }
+
+    query?.split('&').find_map(|pair| {
+        let (name, value) = pair.split_once('=')?;
+        if name == "api_key" || name == "token" {
+            urlencoding::decode(value).ok().map(|v| v.into_owned())
+        } else {
+            None
+        }
+    })
+}
+
+fn has_known_api_key(state: &AppState, request: &Request) -> bool {
+    request_api_key(request.headers(), request.uri().query())
+        .is_some_and(|key| is_known_api_key(state, &key))
+}
+
+pub(crate) fn is_known_api_key(state: &AppState, key: &str) -> bool {
+    key == state.api_key || state.tenant_registry.resolve_api_key(key).is_some()
+}
+
+fn is_tenant_api_key(state: &AppState, key: &str) -> bool {
+    key != state.api_key && state.tenant_registry.resolve_api_key(key).is_some()
+}
+
+fn is_tenant_scoped_route(path: &str) -> bool {
+    TENANT_SCOPED_ROUTES.contains(&path)
+}
+
+/// 请求未携带有效 Key 时补上服务器主 API Key
+fn stamp_server_api_key(state: &AppState, headers: &mut HeaderMap) {
+    if request_api_key(headers, None).is_some_and(|key| is_known_api_key(state, &key)) {
+        return;
+    }
+    if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", state.api_key)) {
+        headers.remove("x-api-key");
+        headers.insert(header::AUTHORIZATION, value);
+        // Anthropic 风格处理器优先读取 x-api-key
+        if let Ok(raw) = HeaderValue::from_str(&state.api_key) {
+            headers.insert("x-api-key", raw);
+        }
+    }
+}
+
+/// 校验请求是否来自本机当前操作系统用户
+///
+/// 回环连接返回对端身份：`SameUser` 直接放行，`Unknown` 需由调用方改为校验 API Key。
+fn verify_os_user(peer: Option<SocketAddr>) -> Result<PeerIdentity, String> {
+    let peer = peer.ok_or_else(|| "无法获取对端地址".to_string())?;
+    if !peer.ip().is_loopback() && !is_mapped_loopback(&peer) {
+        return Err(format!("非回环连接 {}", peer.ip()));
+    }
+    match peer_identity(peer) {
+        PeerIdentity::OtherUser(uid) => Err(format!("对端进程属于其他用户 (uid={uid})")),
+        identity => Ok(identity),
+    }
+}
+
+fn is_mapped_loopback(peer: &SocketAddr) -> bool {
+    match peer {
+        SocketAddr::V6(addr) => addr
+            .ip()
+            .to_ipv4_mapped()
+            .is_some_and(|ip| ip.is_loopback()),
+        SocketAddr::V4(_) => false,
+    }
+}
+
+/// 查询对端 socket 属主
+#[cfg(target_os = "linux")]
+pub fn peer_identity(peer: SocketAddr) -> PeerIdentity {
+    use std::os::unix::fs::MetadataExt;
+
+    let Ok(own_uid) = std::fs::metadata("/proc/self").map(|m| m.uid()) else {
+        return PeerIdentity::Unknown;
+    };
+    let table = match peer {
+        SocketAddr::V4(_) => "/proc/net/tcp",
+        SocketAddr::V6(_) => "/proc/net/tcp6",
+    };
+    let Ok(content) = std::fs::read_to_string(table) else {
+        return PeerIdentity::Unknown;
+    };
+    match find_socket_uid(&content, &proc_net_address(peer)) {
+        Some(uid) if uid == own_uid => PeerIdentity::SameUser,
+        Some(uid) => PeerIdentity::OtherUser(uid),
+        None => PeerIdentity::Unknown,
+    }
+}
+
+/// 查询对端 socket 属主（当前平台不支持对端凭据）
+#[cfg(not(target_os = "linux"))]
+pub fn peer_identity(_peer: SocketAddr) -> PeerIdentity {
+    PeerIdentity::Unknown
+}
+
+/// 按 `/proc/net/tcp*` 的格式编码地址（按本机字节序的 32 位分组十六进制）
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn proc_net_address(addr: SocketAddr) -> String {
+    let ip_hex = match addr {
+        SocketAddr::V4(v4) => format!("{:08X}", u32::from_ne_bytes(v4.ip().octets())),
+        SocketAddr::V6(v6) => v6
+            .ip()
+            .octets()
+            .chunks(4)
+            .map(|chunk| {
+                format!(
+                    "{:08X}",
+                    u32::from_ne_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])
+                )
+            })
+            .collect(),
+    };
+    format!("{ip_hex}:{:04X}", addr.port())
+}
+
+/// 在 `/proc/net/tcp*` 内容中查找本地地址为 `local_address` 的 socket 属主 UID
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn find_socket_uid(content: &str, local_address: &str) -> Option<u32> {
+    content.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.get(1) == Some(&local_address) {
+            fields.get(7)?.parse().ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_api_key_sources() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            request_api_key(&headers, Some("token=abc%2B1")),
+            Some("abc+1".to_string())
+        );
+        assert_eq!(request_api_key(&headers, Some("foo=bar")), None);
+
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_static("Bearer sk-1"),
+        );
+        assert_eq!(
+            request_api_key(&headers, Some("api_key=other")),
+            Some("sk-1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_tenant_scoped_routes() {
+        assert!(is_tenant_scoped_route("/v1/chat/completions"));
+        assert!(is_tenant_scoped_route("/v1/messages"));
+        assert!(!is_tenant_scoped_route("/v1/messages/count_tokens"));
+        assert!(!is_tenant_scoped_route("/v1/responses"));
+        assert!(!is_tenant_scoped_route("/v1/admin/credentials"));
+        assert!(!is_tenant_scoped_route("/openai/v1/chat/completions"));
+    }
+
+    #[test]
+    fn test_find_socket_uid_matches_local_address() {
+        let peer: SocketAddr = "127.0.0.1:51234".parse().unwrap();
+        let address = proc_net_address(peer);
+        let content = format!(
+            "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+             0: 0100007F:0BD6 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 1 1\n   \
+             1: {address} 0100007F:0BD6 01 00000000:00000000 00:00000000 00000000  1001        0 2 1\n"
+        );
+        assert_eq!(find_socket_uid(&content, &address), Some(1001));
+        assert_eq!(find_socket_uid(&content, "0100007F:FFFF"), None);
+    }
+
+    #[test]
+    fn test_os_user_rejects_remote_peers() {
+        let remote: SocketAddr = "192.168.1.10:50000".parse().unwrap();
+        assert!(verify_os_user(Some(remote)).is_err());
+        assert!(verify_os_user(None).is_err());
+
+        // 查不到对端 socket 时不直接放行，交由调用方要求 API Key
+        let unlisted: SocketAddr = "127.0.0.1:1".parse().unwrap();
+        assert_eq!(verify_os_user(Some(unlisted)), Ok(PeerIdentity::Unknown));
+
+        let mapped: SocketAddr = "[::ffff:127.0.0.1]:50000".parse().unwrap();
+        assert!(is_mapped_loopback(&mapped));
+    }
+}
@@ -0,0 +1,81 @@
+//! 会话亲和作用域
+//!
+//! 入口从请求中提取会话 ID（优先 `x-session-id` 请求头，其次 Anthropic 请求体的
+//! `metadata.user_id` 或 OpenAI 请求体的 `user`），在请求作用域内保存；
+//! 凭证选择时传给 `ProviderPoolService`，使同一会话固定使用同一凭证以命中上游 prompt 缓存。
+
+use std::future::Future;
+
+use axum::http::HeaderMap;
+
+use super::cost_ledger::SESSION_ID_HEADER;
+
+/// 会话 ID 的最大长度，超出时不做亲和
+const MAX_SESSION_ID_LEN: usize = 256;
+
+tokio::task_local! {
+    static AFFINITY_SESSION: Option<String>;
+}
+
+/// 在会话亲和作用域内执行
+pub async fn scope_affinity_session<F: Future>(session_id: Option<String>, future: F) -> F::Output {
+    AFFINITY_SESSION.scope(session_id, future).await
+}
+
+/// 当前请求作用域内的会话 ID（作用域外返回 `None`）
+pub fn current_affinity_session() -> Option<String> {
+    AFFINITY_SESSION.try_with(Clone::clone).ok().flatten()
+}
+
+/// 从请求头与原始请求体中提取会话 ID
+pub fn extract_affinity_session(headers: &HeaderMap, body: &serde_json::Value) -> Option<String> {
+    let from_header = headers
+        .get(SESSION_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|v| !v.trim().is_empty());
+    let from_body = || {
+        body.pointer("/metadata/user_id")
+            .or_else(|| body.get("user"))
+            .and_then(|v| v.as_str())
+    };
+    from_header
+        .or_else(from_body)
+        .map(str::trim)
+        .filter(|id| !id.is_empty() && id.len() <= MAX_SESSION_ID_LEN)
+        .map(ToString::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_extract_affinity_session() {
+        let body = serde_json::json!({ "metadata": { "user_id": "user_abc_session_1" } });
+        assert_eq!(
+            extract_affinity_session(&HeaderMap::new(), &body).as_deref(),
+            Some("user_abc_session_1")
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(SESSION_ID_HEADER, HeaderValue::from_static("conv-a"));
+        assert_eq!(
+            extract_affinity_session(&headers, &body).as_deref(),
+            Some("conv-a")
+        );
+
+        let body = serde_json::json!({ "user": "  " });
+        assert_eq!(extract_affinity_session(&HeaderMap::new(), &body), None);
+    }
+
+    #[tokio::test]
+    async fn test_scope_affinity_session() {
+        assert_eq!(current_affinity_session(), None);
+        let inside = scope_affinity_session(Some("conv-a".to_string()), async {
+            current_affinity_session()
+        })
+        .await;
+        assert_eq!(inside.as_deref(), Some("conv-a"));
+    }
+}
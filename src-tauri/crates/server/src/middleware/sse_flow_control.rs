@@ -0,0 +1,218 @@
+//! SSE 转发流控中间件
+//!
+//! 上游 SSE 由后台任务持续读取并放入队列，客户端按自身消费速度拉取：
+//! - 队列积压时将已排队的多个 chunk 合并为一次写出，达到 `max_coalesce_bytes` 后停止合并
+//! - 积压字节数超过 `max_buffer_bytes` 时停止读取上游，发送 `buffer_overflow` 错误事件并结束流
+//! - 客户端断开后队列接收端被丢弃，后台任务随即停止读取上游
+
+use crate::AppState;
+use axum::body::{Body, Bytes};
+use axum::extract::{Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::Response;
+use futures::StreamExt;
+use lime_core::config::SseFlowControlSettings;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// 转发队列中的条目
+enum RelayItem {
+    Chunk(Bytes),
+    Error(axum::Error),
+    /// 积压超限，附带当时的积压字节数
+    Overflow(usize),
+}
+
+/// SSE 流控中间件
+pub async fn apply_sse_flow_control(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    relay_sse(response, &state.sse_flow_control)
+}
+
+/// 是否为成功的 SSE 响应
+pub(crate) fn is_sse_response(response: &Response) -> bool {
+    response.status().is_success()
+        && response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("text/event-stream"))
+}
+
+/// 为 SSE 响应加上流控，非 SSE 响应原样返回
+pub fn relay_sse(response: Response, settings: &SseFlowControlSettings) -> Response {
+    if !settings.enabled || !is_sse_response(&response) {
+        return response;
+    }
+
+    let max_buffer_bytes = settings.max_buffer_bytes.max(1);
+    let max_coalesce_bytes = settings.max_coalesce_bytes.max(1);
+    let buffered = Arc::new(AtomicUsize::new(0));
+    let (tx, mut rx) = mpsc::unbounded_channel();
+
+    let (parts, body) = response.into_parts();
+    let mut source = body.into_data_stream();
+    let producer_buffered = buffered.clone();
+    tokio::spawn(async move {
+        while let Some(chunk) = source.next().await {
+            let item = match chunk {
+                Ok(bytes) => {
+                    let len = bytes.len();
+                    let before = producer_buffered.fetch_add(len, Ordering::AcqRel);
+                    // 队列为空时总是放行，避免单个大 chunk 被误判为积压
+                    if before > 0 && before + len > max_buffer_bytes {
+                        let _ = tx.send(RelayItem::Overflow(before));
+                        return;
+                    }
+                    RelayItem::Chunk(bytes)
+                }
+                Err(e) => {
+                    let _ = tx.send(RelayItem::Error(e));
+                    return;
+                }
+            };
+            if tx.send(item).is_err() {
+                tracing::debug!("[SSE_FLOW] 客户端已断开，停止读取上游");
+                return;
+            }
+        }
+    });
+
+    let stream = async_stream::stream! {
+        while let Some(item) = rx.recv().await {
+            let pending = match item {
+                RelayItem::Chunk(first) => {
+                    let (bytes, consumed, pending) = coalesce(first, &mut rx, max_coalesce_bytes);
+                    buffered.fetch_sub(consumed, Ordering::AcqRel);
+                    yield Ok(bytes);
+                    pending
+                }
+                other => Some(other),
+            };
+            match pending {
+                Some(RelayItem::Error(e)) => {
+                    yield Err(e);
+                    return;
+                }
+                Some(RelayItem::Overflow(backlog)) => {
+                    tracing::warn!(
+                        "[SSE_FLOW] 客户端消费过慢，积压 {} 字节超过上限 {}，终止转发",
+                        backlog,
+                        max_buffer_bytes
+                    );
+                    yield Ok(overflow_event(backlog, max_buffer_bytes));
+                    return;
+                }
+                _ => {}
+            }
+        }
+    };
+    Response::from_parts(parts, Body::from_stream(stream))
+}
+
+/// 合并队列中已就绪的 chunk，返回合并后的字节、消耗的积压量及遇到的非数据条目
+fn coalesce(
+    first: Bytes,
+    rx: &mut mpsc::UnboundedReceiver<RelayItem>,
+    max_coalesce_bytes: usize,
+) -> (Bytes, usize, Option<RelayItem>) {
+    let mut size = first.len();
+    let mut chunks = vec![first];
+    let mut pending = None;
+    while size < max_coalesce_bytes {
+        match rx.try_recv() {
+            Ok(RelayItem::Chunk(next)) => {
+                size += next.len();
+                chunks.push(next);
+            }
+            Ok(other) => {
+                pending = Some(other);
+                break;
+            }
+            Err(_) => break,
+        }
+    }
+
+    let bytes = if chunks.len() == 1 {
+        chunks.pop().unwrap_or_default()
+    } else {
+        Bytes::from(chunks.concat())
+    };
+    (bytes, size, pending)
+}
+
+/// 积压超限时发送给客户端的错误事件（同时兼容 OpenAI 与 Anthropic 的错误结构）
+fn overflow_event(backlog: usize, max_buffer_bytes: usize) -> Bytes {
+    let payload = serde_json::json!({
+        "type": "error",
+        "error": {
+            "type": "buffer_overflow",
+            "message": format!(
+                "Client is not consuming the stream fast enough: {backlog} bytes buffered, limit is {max_buffer_bytes} bytes"
+            ),
+        }
+    });
+    Bytes::from(format!("event: error\ndata: {payload}\n\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn sse_response(chunks: Vec<&'static str>) -> Response {
+        let stream = futures::stream::iter(chunks.into_iter().map(Ok::<_, std::io::Error>));
+        Response::builder()
+            .header(header::CONTENT_TYPE, "text/event-stream")
+            .body(Body::from_stream(stream))
+            .unwrap()
+    }
+
+    async fn collect_frames(response: Response) -> Vec<String> {
+        // 让后台任务先读完上游，模拟客户端消费滞后
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        response
+            .into_body()
+            .into_data_stream()
+            .map(|frame| String::from_utf8(frame.unwrap().to_vec()).unwrap())
+            .collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_backlog_is_coalesced() {
+        let chunks = vec!["data: 1\n\n", "data: 2\n\n", "data: 3\n\n", "data: 4\n\n"];
+        let settings = SseFlowControlSettings {
+            max_coalesce_bytes: 18,
+            ..Default::default()
+        };
+
+        let frames = collect_frames(relay_sse(sse_response(chunks), &settings)).await;
+        assert_eq!(
+            frames,
+            vec!["data: 1\n\ndata: 2\n\n", "data: 3\n\ndata: 4\n\n"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overflow_aborts_with_error_event() {
+        let chunks = vec!["data: 1\n\n", "data: 2\n\n", "data: 3\n\n", "data: 4\n\n"];
+        let settings = SseFlowControlSettings {
+            enabled: true,
+            max_buffer_bytes: 25,
+            max_coalesce_bytes: 1024,
+        };
+
+        let frames = collect_frames(relay_sse(sse_response(chunks), &settings)).await;
+        assert_eq!(frames[0], "data: 1\n\ndata: 2\n\n");
+        assert!(frames[1].starts_with("event: error\n"));
+        assert!(frames[1].contains("buffer_overflow"));
+        assert_eq!(frames.len(), 2);
+    }
+}
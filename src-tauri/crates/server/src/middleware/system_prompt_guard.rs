@@ -0,0 +1,521 @@
+//! 系统提示词护栏注入中间件
+//!
+//! 按 `system_prompt_guard` 配置，为命中策略的请求在转发前注入组织要求的护栏文本：
+//! - `/v1/messages`：写入顶层 `system`（字符串或内容块数组）
+//! - `/v1/chat/completions`：合并到首条 `system` / `developer` 消息，不存在时新建
+//! - `/v1/responses`：写入 `instructions`
+//!
+//! 策略按 API Key / 租户 / 路由匹配，豁免路由优先。每次注入的策略与渲染后的文本
+//! 记入内存审计（条数受 `audit_capacity` 限制），API Key 只保存指纹。
+
+use crate::middleware::route_auth::request_api_key;
+use crate::AppState;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::Response;
+use lime_core::config::{
+    SystemPromptGuardPolicy, SystemPromptGuardPosition, SystemPromptGuardSettings,
+};
+use lime_core::database::dao::api_key_rate_limit::api_key_fingerprint;
+use lime_core::errors::GatewayErrorCode;
+use lime_core::processor::current_request_id;
+use lime_server_utils::build_error_response_with_meta;
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+
+/// 注入的策略 ID 响应头（逗号分隔）
+pub const SYSTEM_PROMPT_GUARD_HEADER: &str = "x-lime-system-guard";
+
+/// 缓存请求体的上限（与服务器请求体上限一致）
+const MAX_INSPECT_BODY_BYTES: usize = 100 * 1024 * 1024;
+
+/// 护栏文本注入的目标字段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GuardTarget {
+    /// Anthropic Messages 的 `system`
+    AnthropicSystem,
+    /// OpenAI Chat 的 system 消息
+    OpenAiSystemMessage,
+    /// Responses API 的 `instructions`
+    ResponsesInstructions,
+}
+
+/// 根据路由判断注入目标，不支持的路由返回 None
+fn guard_target(method: &Method, path: &str) -> Option<GuardTarget> {
+    if method != Method::POST {
+        return None;
+    }
+    if path.ends_with("/v1/messages") {
+        Some(GuardTarget::AnthropicSystem)
+    } else if path.ends_with("/v1/chat/completions") {
+        Some(GuardTarget::OpenAiSystemMessage)
+    } else if path.ends_with("/v1/responses") {
+        Some(GuardTarget::ResponsesInstructions)
+    } else {
+        None
+    }
+}
+
+/// 渲染后待注入的护栏文本
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardInjection {
+    pub policy_id: String,
+    pub position: SystemPromptGuardPosition,
+    pub text: String,
+}
+
+/// 模板变量
+#[derive(Debug, Clone)]
+pub struct GuardTemplateContext<'a> {
+    pub tenant: Option<&'a str>,
+    pub model: Option<&'a str>,
+    pub route: &'a str,
+}
+
+/// 渲染护栏模板
+pub fn render_template(
+    policy: &SystemPromptGuardPolicy,
+    context: &GuardTemplateContext<'_>,
+) -> String {
+    let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+    [
+        ("{{tenant}}", context.tenant.unwrap_or("")),
+        ("{{model}}", context.model.unwrap_or("")),
+        ("{{route}}", context.route),
+        ("{{date}}", date.as_str()),
+        ("{{policy}}", policy.id.trim()),
+    ]
+    .into_iter()
+    .fold(policy.template.clone(), |text, (name, value)| {
+        text.replace(name, value)
+    })
+    .trim()
+    .to_string()
+}
+
+/// 单次注入的审计记录
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuardInjectionRecord {
+    pub request_id: Option<String>,
+    /// 注入时间（Unix 毫秒）
+    pub timestamp_ms: i64,
+    pub route: String,
+    pub model: Option<String>,
+    pub tenant_id: Option<String>,
+    /// API Key 指纹（不保存明文）
+    pub api_key_fingerprint: Option<String>,
+    pub policy_ids: Vec<String>,
+    /// 实际注入的文本（按注入顺序）
+    pub injected: Vec<String>,
+}
+
+/// 系统提示词护栏
+pub struct SystemPromptGuard {
+    settings: RwLock<SystemPromptGuardSettings>,
+    audit: Mutex<VecDeque<GuardInjectionRecord>>,
+}
+
+impl SystemPromptGuard {
+    pub fn new(settings: &SystemPromptGuardSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings.clone()),
+            audit: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// 热更新配置（审计记录保留，超出新容量的旧记录被丢弃）
+    pub fn reload(&self, settings: &SystemPromptGuardSettings) {
+        *self.settings.write() = settings.clone();
+        let mut audit = self.audit.lock();
+        while audit.len() > settings.audit_capacity {
+            audit.pop_front();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        let settings = self.settings.read();
+        settings.enabled && settings.policies.iter().any(|p| p.enabled)
+    }
+
+    /// 计算请求命中的护栏文本（按策略声明顺序）
+    pub fn plan(
+        &self,
+        api_key: Option<&str>,
+        context: &GuardTemplateContext<'_>,
+    ) -> Vec<GuardInjection> {
+        let settings = self.settings.read();
+        if !settings.enabled {
+            return Vec::new();
+        }
+        settings
+            .policies
+            .iter()
+            .filter(|policy| policy.matches(api_key, context.tenant, context.route))
+            .map(|policy| GuardInjection {
+                policy_id: policy.id.trim().to_string(),
+                position: policy.position,
+                text: render_template(policy, context),
+            })
+            .filter(|injection| !injection.text.is_empty())
+            .collect()
+    }
+
+    /// 写入审计记录
+    pub fn record(&self, record: GuardInjectionRecord) {
+        let capacity = self.settings.read().audit_capacity;
+        if capacity == 0 {
+            return;
+        }
+        let mut audit = self.audit.lock();
+        while audit.len() >= capacity {
+            audit.pop_front();
+        }
+        audit.push_back(record);
+    }
+
+    /// 最近的注入记录（新的在前）
+    pub fn recent_injections(&self, limit: usize) -> Vec<GuardInjectionRecord> {
+        self.audit
+            .lock()
+            .iter()
+            .rev()
+            .take(limit)
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear_audit(&self) {
+        self.audit.lock().clear();
+    }
+}
+
+/// 将护栏文本合并到已有文本
+fn merge_text(existing: &str, text: &str, position: SystemPromptGuardPosition) -> String {
+    if existing.trim().is_empty() {
+        return text.to_string();
+    }
+    match position {
+        SystemPromptGuardPosition::Append => format!("{existing}\n\n{text}"),
+        SystemPromptGuardPosition::Prepend => format!("{text}\n\n{existing}"),
+    }
+}
+
+/// 将护栏文本注入字符串或内容块数组形式的字段
+///
+/// 内容块数组中新增 `{"type": <block_type>, "text": ...}`；其他形式视为缺失并覆盖。
+fn inject_into_content(
+    slot: &mut Value,
+    text: &str,
+    position: SystemPromptGuardPosition,
+    block_type: &str,
+) {
+    match slot {
+        Value::String(existing) => *existing = merge_text(existing, text, position),
+        Value::Array(blocks) => {
+            let block = serde_json::json!({ "type": block_type, "text": text });
+            match position {
+                SystemPromptGuardPosition::Append => blocks.push(block),
+                SystemPromptGuardPosition::Prepend => blocks.insert(0, block),
+            }
+        }
+        _ => *slot = Value::String(text.to_string()),
+    }
+}
+
+/// 将护栏文本注入请求体
+fn apply_injections(body: &mut Value, target: GuardTarget, injections: &[GuardInjection]) {
+    let Some(object) = body.as_object_mut() else {
+        return;
+    };
+    for injection in injections {
+        match target {
+            GuardTarget::AnthropicSystem => {
+                let slot = object.entry("system").or_insert(Value::Null);
+                inject_into_content(slot, &injection.text, injection.position, "text");
+            }
+            GuardTarget::ResponsesInstructions => {
+                let slot = object.entry("instructions").or_insert(Value::Null);
+                inject_into_content(slot, &injection.text, injection.position, "input_text");
+            }
+            GuardTarget::OpenAiSystemMessage => {
+                let messages = object
+                    .entry("messages")
+                    .or_insert_with(|| Value::Array(Vec::new()));
+                let Some(messages) = messages.as_array_mut() else {
+                    continue;
+                };
+                let index = messages.iter().position(|message| {
+                    matches!(
+                        message.get("role").and_then(Value::as_str),
+                        Some("system" | "developer")
+                    )
+                });
+                match index {
+                    Some(index) => {
+                        if let Some(message) = messages[index].as_object_mut() {
+                            let slot = message.entry("content").or_insert(Value::Null);
+                            inject_into_content(slot, &injection.text, injection.position, "text");
+                        }
+                    }
+                    None => messages.insert(
+                        0,
+                        serde_json::json!({ "role": "system", "content": injection.text }),
+                    ),
+                }
+            }
+        }
+    }
+}
+
+/// 系统提示词护栏注入中间件
+pub async fn inject_system_prompt_guard(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !state.system_prompt_guard.is_enabled() {
+        return next.run(request).await;
+    }
+    let Some(target) = guard_target(request.method(), request.uri().path()) else {
+        return next.run(request).await;
+    };
+
+    let route = request.uri().path().to_string();
+    let api_key = request_api_key(request.headers(), request.uri().query());
+    let tenant = api_key
+        .as_deref()
+        .and_then(|key| state.tenant_registry.resolve_api_key(key));
+    let tenant_id = tenant.as_ref().map(|tenant| tenant.id().to_string());
+
+    let (mut parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_INSPECT_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return build_error_response_with_meta(
+                StatusCode::BAD_REQUEST.as_u16(),
+                &format!("Failed to read request body: {e}"),
+                None,
+                None,
+                Some(GatewayErrorCode::InvalidRequest),
+            );
+        }
+    };
+    // 请求体无法解析时由处理器返回错误，这里原样转发
+    let Ok(mut body_json) = serde_json::from_slice::<Value>(&bytes) else {
+        return next
+            .run(Request::from_parts(parts, Body::from(bytes)))
+            .await;
+    };
+
+    let model = body_json
+        .get("model")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let injections = state.system_prompt_guard.plan(
+        api_key.as_deref(),
+        &GuardTemplateContext {
+            tenant: tenant_id.as_deref(),
+            model: model.as_deref(),
+            route: &route,
+        },
+    );
+    if injections.is_empty() {
+        return next
+            .run(Request::from_parts(parts, Body::from(bytes)))
+            .await;
+    }
+
+    apply_injections(&mut body_json, target, &injections);
+    let rewritten = match serde_json::to_vec(&body_json) {
+        Ok(rewritten) => rewritten,
+        Err(e) => {
+            tracing::warn!("[SYSTEM_GUARD] 序列化请求体失败，跳过注入: {}", e);
+            return next
+                .run(Request::from_parts(parts, Body::from(bytes)))
+                .await;
+        }
+    };
+    parts
+        .headers
+        .insert(header::CONTENT_LENGTH, rewritten.len().into());
+
+    let policy_ids: Vec<String> = injections.iter().map(|i| i.policy_id.clone()).collect();
+    let request_id = current_request_id();
+    tracing::info!(
+        "[SYSTEM_GUARD] 请求 {} 注入护栏策略: {}",
+        request_id.as_deref().unwrap_or("-"),
+        policy_ids.join(",")
+    );
+    state.system_prompt_guard.record(GuardInjectionRecord {
+        request_id,
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        route,
+        model,
+        tenant_id,
+        api_key_fingerprint: api_key.as_deref().map(api_key_fingerprint),
+        policy_ids: policy_ids.clone(),
+        injected: injections.into_iter().map(|i| i.text).collect(),
+    });
+
+    let mut response = next
+        .run(Request::from_parts(parts, Body::from(rewritten)))
+        .await;
+    if let Ok(value) = HeaderValue::from_str(&policy_ids.join(",")) {
+        response
+            .headers_mut()
+            .insert(SYSTEM_PROMPT_GUARD_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(id: &str, template: &str) -> SystemPromptGuardPolicy {
+        SystemPromptGuardPolicy {
+            id: id.to_string(),
+            name: String::new(),
+            enabled: true,
+            api_keys: Vec::new(),
+            tenants: Vec::new(),
+            routes: Vec::new(),
+            exempt_routes: Vec::new(),
+            template: template.to_string(),
+            position: SystemPromptGuardPosition::Append,
+        }
+    }
+
+    fn guard(policies: Vec<SystemPromptGuardPolicy>) -> SystemPromptGuard {
+        SystemPromptGuard::new(&SystemPromptGuardSettings {
+            enabled: true,
+            policies,
+            audit_capacity: 2,
+        })
+    }
+
+    fn context(route: &str) -> GuardTemplateContext<'_> {
+        GuardTemplateContext {
+            tenant: Some("team-a"),
+            model: Some("claude-sonnet-4"),
+            route,
+        }
+    }
+
+    fn injection(text: &str, position: SystemPromptGuardPosition) -> GuardInjection {
+        GuardInjection {
+            policy_id: "p".to_string(),
+            position,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_plan_matches_key_tenant_and_exempt_routes() {
+        let mut by_key = policy("by-key", "key {{route}}");
+        by_key.api_keys = vec!["sk-a".to_string()];
+        let mut by_tenant = policy("by-tenant", "tenant {{tenant}} / {{model}}");
+        by_tenant.tenants = vec!["team-a".to_string()];
+        by_tenant.exempt_routes = vec!["/kiro/*".to_string()];
+        let guard = guard(vec![by_key, by_tenant]);
+
+        let planned = guard.plan(Some("sk-a"), &context("/v1/messages"));
+        assert_eq!(planned.len(), 2);
+        assert_eq!(planned[0].text, "key /v1/messages");
+        assert_eq!(planned[1].text, "tenant team-a / claude-sonnet-4");
+
+        let planned = guard.plan(Some("sk-b"), &context("/kiro/v1/messages"));
+        assert!(planned.is_empty());
+    }
+
+    #[test]
+    fn test_route_restriction() {
+        let mut scoped = policy("chat-only", "chat");
+        scoped.routes = vec!["/v1/chat/completions".to_string()];
+        let guard = guard(vec![scoped]);
+
+        assert_eq!(guard.plan(None, &context("/v1/chat/completions")).len(), 1);
+        assert!(guard.plan(None, &context("/v1/messages")).is_empty());
+    }
+
+    #[test]
+    fn test_anthropic_system_injection() {
+        let mut body = serde_json::json!({ "model": "m", "messages": [] });
+        apply_injections(
+            &mut body,
+            GuardTarget::AnthropicSystem,
+            &[injection("guard", SystemPromptGuardPosition::Append)],
+        );
+        assert_eq!(body["system"], "guard");
+
+        let mut body = serde_json::json!({ "system": "client" });
+        apply_injections(
+            &mut body,
+            GuardTarget::AnthropicSystem,
+            &[injection("guard", SystemPromptGuardPosition::Prepend)],
+        );
+        assert_eq!(body["system"], "guard\n\nclient");
+
+        let mut body = serde_json::json!({ "system": [{ "type": "text", "text": "client" }] });
+        apply_injections(
+            &mut body,
+            GuardTarget::AnthropicSystem,
+            &[injection("guard", SystemPromptGuardPosition::Append)],
+        );
+        assert_eq!(body["system"][1]["text"], "guard");
+    }
+
+    #[test]
+    fn test_openai_system_message_injection() {
+        let mut body = serde_json::json!({
+            "messages": [{ "role": "user", "content": "hi" }]
+        });
+        apply_injections(
+            &mut body,
+            GuardTarget::OpenAiSystemMessage,
+            &[injection("guard", SystemPromptGuardPosition::Append)],
+        );
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][0]["content"], "guard");
+        assert_eq!(body["messages"][1]["role"], "user");
+
+        let mut body = serde_json::json!({
+            "messages": [
+                { "role": "user", "content": "hi" },
+                { "role": "developer", "content": "client" }
+            ]
+        });
+        apply_injections(
+            &mut body,
+            GuardTarget::OpenAiSystemMessage,
+            &[injection("guard", SystemPromptGuardPosition::Append)],
+        );
+        assert_eq!(body["messages"].as_array().unwrap().len(), 2);
+        assert_eq!(body["messages"][1]["content"], "client\n\nguard");
+    }
+
+    #[test]
+    fn test_audit_capacity() {
+        let guard = guard(Vec::new());
+        for i in 0..3 {
+            guard.record(GuardInjectionRecord {
+                request_id: Some(format!("req-{i}")),
+                timestamp_ms: i,
+                route: "/v1/messages".to_string(),
+                model: None,
+                tenant_id: None,
+                api_key_fingerprint: None,
+                policy_ids: vec!["p".to_string()],
+                injected: vec!["guard".to_string()],
+            });
+        }
+        let recent = guard.recent_injections(10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].request_id.as_deref(), Some("req-2"));
+        assert_eq!(recent[1].request_id.as_deref(), Some("req-1"));
+    }
+}
@@ -0,0 +1,395 @@
+//! 多租户命名空间中间件
+//!
+//! 将 API Key 映射到租户，为每个租户提供：
+//! - 独立的凭证子集（按凭证 UUID / Provider 类型过滤）
+//! - 独立的速率限制
+//! - 独立的请求与 Token 用量统计
+
+use crate::middleware::rate_limit::{RateLimitConfig, RateLimitResult, SlidingWindowRateLimiter};
+use lime_core::config::{TenantEntry, TenantSettings};
+use lime_core::models::provider_pool_model::ProviderCredential;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// 请求上下文中记录租户 ID 的元数据键
+pub const TENANT_METADATA_KEY: &str = "tenant_id";
+
+/// 租户用量快照
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TenantUsageSnapshot {
+    pub tenant_id: String,
+    pub tenant_name: String,
+    pub requests_total: u64,
+    pub success_total: u64,
+    pub failed_total: u64,
+    pub rate_limited_total: u64,
+    pub input_tokens_total: u64,
+    pub output_tokens_total: u64,
+}
+
+/// 租户运行时状态
+pub struct TenantRuntime {
+    entry: TenantEntry,
+    limiter: Option<SlidingWindowRateLimiter>,
+    requests_total: AtomicU64,
+    success_total: AtomicU64,
+    failed_total: AtomicU64,
+    rate_limited_total: AtomicU64,
+    input_tokens_total: AtomicU64,
+    output_tokens_total: AtomicU64,
+}
+
+impl TenantRuntime {
+    fn new(entry: TenantEntry) -> Self {
+        let limiter = entry.rate_limit.as_ref().map(|settings| {
+            SlidingWindowRateLimiter::new(RateLimitConfig {
+                enabled: settings.enabled,
+                requests_per_minute: settings.requests_per_minute,
+                window_secs: settings.window_secs,
+            })
+        });
+        Self {
+            entry,
+            limiter,
+            requests_total: AtomicU64::new(0),
+            success_total: AtomicU64::new(0),
+            failed_total: AtomicU64::new(0),
+            rate_limited_total: AtomicU64::new(0),
+            input_tokens_total: AtomicU64::new(0),
+            output_tokens_total: AtomicU64::new(0),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.entry.id
+    }
+
+    pub fn entry(&self) -> &TenantEntry {
+        &self.entry
+    }
+
+    /// 租户级速率限制检查（同时计入请求总数）
+    pub fn check_rate_limit(&self) -> RateLimitResult {
+        let result = match &self.limiter {
+            Some(limiter) => limiter.check_rate_limit(&self.entry.id),
+            None => RateLimitResult::Allowed,
+        };
+        match result {
+            RateLimitResult::Allowed => {
+                self.requests_total.fetch_add(1, Ordering::Relaxed);
+            }
+            RateLimitResult::Limited { .. } => {
+                self.rate_limited_total.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        result
+    }
+
+    /// 判断凭证是否属于该租户的凭证子集
+    pub fn allows_credential(&self, credential: &ProviderCredential) -> bool {
+        if !self.entry.allowed_credentials.is_empty()
+            && !self
+                .entry
+                .allowed_credentials
+                .iter()
+                .any(|uuid| uuid == &credential.uuid)
+        {
+            return false;
+        }
+
+        if !self.entry.allowed_providers.is_empty() {
+            let provider_type = credential.provider_type.to_string().to_lowercase();
+            return self
+                .entry
+                .allowed_providers
+                .iter()
+                .any(|provider| provider.trim().to_lowercase() == provider_type);
+        }
+
+        true
+    }
+
+    /// 是否对凭证做了限制（两个列表都为空时等价于使用全部凭证）
+    pub fn restricts_credentials(&self) -> bool {
+        !self.entry.allowed_credentials.is_empty() || !self.entry.allowed_providers.is_empty()
+    }
+
+    pub fn record_result(&self, success: bool) {
+        if success {
+            self.success_total.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failed_total.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn record_tokens(&self, input_tokens: u32, output_tokens: u32) {
+        self.input_tokens_total
+            .fetch_add(input_tokens as u64, Ordering::Relaxed);
+        self.output_tokens_total
+            .fetch_add(output_tokens as u64, Ordering::Relaxed);
+    }
+
+    pub fn usage(&self) -> TenantUsageSnapshot {
+        TenantUsageSnapshot {
+            tenant_id: self.entry.id.clone(),
+            tenant_name: self.entry.name.clone(),
+            requests_total: self.requests_total.load(Ordering::Relaxed),
+            success_total: self.success_total.load(Ordering::Relaxed),
+            failed_total: self.failed_total.load(Ordering::Relaxed),
+            rate_limited_total: self.rate_limited_total.load(Ordering::Relaxed),
+            input_tokens_total: self.input_tokens_total.load(Ordering::Relaxed),
+            output_tokens_total: self.output_tokens_total.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 继承旧运行时的用量计数（配置重载时保留统计）
+    fn inherit_usage(&self, previous: &TenantRuntime) {
+        let counters = [
+            (&self.requests_total, &previous.requests_total),
+            (&self.success_total, &previous.success_total),
+            (&self.failed_total, &previous.failed_total),
+            (&self.rate_limited_total, &previous.rate_limited_total),
+            (&self.input_tokens_total, &previous.input_tokens_total),
+            (&self.output_tokens_total, &previous.output_tokens_total),
+        ];
+        for (current, old) in counters {
+            current.store(old.load(Ordering::Relaxed), Ordering::Relaxed);
+        }
+    }
+}
+
+#[derive(Default)]
+struct TenantIndex {
+    enabled: bool,
+    by_id: HashMap<String, Arc<TenantRuntime>>,
+    by_api_key: HashMap<String, String>,
+}
+
+/// 租户注册表
+#[derive(Default)]
+pub struct TenantRegistry {
+    index: RwLock<TenantIndex>,
+}
+
+impl TenantRegistry {
+    pub fn new(settings: &TenantSettings) -> Self {
+        let registry = Self::default();
+        registry.reload(settings);
+        registry
+    }
+
+    /// 根据配置重建租户索引，已存在租户的用量统计会被保留
+    pub fn reload(&self, settings: &TenantSettings) {
+        let mut index = self.index.write();
+        let mut by_id = HashMap::new();
+        let mut by_api_key = HashMap::new();
+
+        for entry in &settings.tenants {
+            let id = entry.id.trim();
+            if id.is_empty() || !entry.enabled {
+                continue;
+            }
+            let runtime = TenantRuntime::new(TenantEntry {
+                id: id.to_string(),
+                ..entry.clone()
+            });
+            if let Some(previous) = index.by_id.get(id) {
+                runtime.inherit_usage(previous);
+            }
+            for key in &entry.api_keys {
+                let key = key.trim();
+                if key.is_empty() {
+                    continue;
+                }
+                if let Some(existing) = by_api_key.insert(key.to_string(), id.to_string()) {
+                    tracing::warn!(
+                        "[TENANT] API Key 同时配置在租户 {} 与 {} 中，以后者为准",
+                        existing,
+                        id
+                    );
+                }
+            }
+            by_id.insert(id.to_string(), Arc::new(runtime));
+        }
+
+        index.enabled = settings.enabled;
+        index.by_id = by_id;
+        index.by_api_key = by_api_key;
+        tracing::info!(
+            "[TENANT] 租户配置已加载: enabled={} tenants={}",
+            index.enabled,
+            index.by_id.len()
+        );
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.index.read().enabled
+    }
+
+    /// 根据 API Key 解析租户（未启用租户隔离时始终返回 None）
+    pub fn resolve_api_key(&self, api_key: &str) -> Option<Arc<TenantRuntime>> {
+        let index = self.index.read();
+        if !index.enabled {
+            return None;
+        }
+        let tenant_id = index.by_api_key.get(api_key)?;
+        index.by_id.get(tenant_id).cloned()
+    }
+
+    pub fn get(&self, tenant_id: &str) -> Option<Arc<TenantRuntime>> {
+        self.index.read().by_id.get(tenant_id).cloned()
+    }
+
+    /// 获取所有租户的用量快照（按租户 ID 排序）
+    pub fn usage_snapshots(&self) -> Vec<TenantUsageSnapshot> {
+        let mut snapshots: Vec<_> = self
+            .index
+            .read()
+            .by_id
+            .values()
+            .map(|tenant| tenant.usage())
+            .collect();
+        snapshots.sort_by(|a, b| a.tenant_id.cmp(&b.tenant_id));
+        snapshots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::config::RateLimitSettings;
+    use lime_core::models::provider_pool_model::{CredentialData, PoolProviderType};
+
+    fn tenant(id: &str, keys: &[&str]) -> TenantEntry {
+        TenantEntry {
+            id: id.to_string(),
+            name: id.to_uppercase(),
+            api_keys: keys.iter().map(|k| k.to_string()).collect(),
+            allowed_credentials: Vec::new(),
+            allowed_providers: Vec::new(),
+            rate_limit: None,
+            enabled: true,
+        }
+    }
+
+    fn openai_credential() -> ProviderCredential {
+        ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: "sk-test".to_string(),
+                base_url: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_resolve_requires_enabled() {
+        let mut settings = TenantSettings {
+            enabled: false,
+            tenants: vec![tenant("team-a", &["key-a"])],
+        };
+        let registry = TenantRegistry::new(&settings);
+        assert!(registry.resolve_api_key("key-a").is_none());
+
+        settings.enabled = true;
+        registry.reload(&settings);
+        let resolved = registry.resolve_api_key("key-a").expect("应解析到租户");
+        assert_eq!(resolved.id(), "team-a");
+        assert!(registry.resolve_api_key("unknown").is_none());
+    }
+
+    #[test]
+    fn test_disabled_tenant_skipped() {
+        let mut entry = tenant("team-a", &["key-a"]);
+        entry.enabled = false;
+        let registry = TenantRegistry::new(&TenantSettings {
+            enabled: true,
+            tenants: vec![entry],
+        });
+        assert!(registry.resolve_api_key("key-a").is_none());
+        assert!(registry.usage_snapshots().is_empty());
+    }
+
+    #[test]
+    fn test_credential_subset() {
+        let credential = openai_credential();
+
+        let mut entry = tenant("team-a", &["key-a"]);
+        let unrestricted = TenantRuntime::new(entry.clone());
+        assert!(!unrestricted.restricts_credentials());
+        assert!(unrestricted.allows_credential(&credential));
+
+        entry.allowed_providers = vec!["Claude".to_string()];
+        assert!(!TenantRuntime::new(entry.clone()).allows_credential(&credential));
+
+        entry.allowed_providers = vec!["OpenAI".to_string()];
+        assert!(TenantRuntime::new(entry.clone()).allows_credential(&credential));
+
+        entry.allowed_credentials = vec!["other-uuid".to_string()];
+        assert!(!TenantRuntime::new(entry.clone()).allows_credential(&credential));
+
+        entry.allowed_credentials = vec![credential.uuid.clone()];
+        assert!(TenantRuntime::new(entry).allows_credential(&credential));
+    }
+
+    #[test]
+    fn test_rate_limit_and_usage() {
+        let mut entry = tenant("team-a", &["key-a"]);
+        entry.rate_limit = Some(RateLimitSettings {
+            enabled: true,
+            requests_per_minute: 2,
+            window_secs: 60,
+        });
+        let runtime = TenantRuntime::new(entry);
+
+        assert!(matches!(
+            runtime.check_rate_limit(),
+            RateLimitResult::Allowed
+        ));
+        assert!(matches!(
+            runtime.check_rate_limit(),
+            RateLimitResult::Allowed
+        ));
+        assert!(matches!(
+            runtime.check_rate_limit(),
+            RateLimitResult::Limited { .. }
+        ));
+
+        runtime.record_result(true);
+        runtime.record_result(false);
+        runtime.record_tokens(10, 20);
+
+        let usage = runtime.usage();
+        assert_eq!(usage.requests_total, 2);
+        assert_eq!(usage.rate_limited_total, 1);
+        assert_eq!(usage.success_total, 1);
+        assert_eq!(usage.failed_total, 1);
+        assert_eq!(usage.input_tokens_total, 10);
+        assert_eq!(usage.output_tokens_total, 20);
+    }
+
+    #[test]
+    fn test_reload_keeps_usage() {
+        let settings = TenantSettings {
+            enabled: true,
+            tenants: vec![tenant("team-a", &["key-a"])],
+        };
+        let registry = TenantRegistry::new(&settings);
+        registry
+            .resolve_api_key("key-a")
+            .unwrap()
+            .record_tokens(5, 7);
+
+        let mut updated = settings.clone();
+        updated.tenants[0].api_keys = vec!["key-b".to_string()];
+        registry.reload(&updated);
+
+        assert!(registry.resolve_api_key("key-a").is_none());
+        let usage = registry.resolve_api_key("key-b").unwrap().usage();
+        assert_eq!(usage.input_tokens_total, 5);
+        assert_eq!(usage.output_tokens_total, 7);
+    }
+}
@@ -0,0 +1,385 @@
+//! 自适应凭证选择
+//!
+//! 按（模型, 凭证）学习请求结果，用 ε-greedy 策略把流量转向表现更好的凭证：
+//! - 得分：平滑成功率 `(成功 + 1) / (成功 + 失败 + 2)` 与延迟得分 `参考延迟 / (参考延迟 + 延迟)`
+//!   按 `latency_weight` 加权，尚无成功记录时延迟得分取 0.5
+//! - 选择：以 `exploration_rate` 的概率在候选中随机选择，其余时候选择得分最高的凭证
+//! - 学习：每次记录结果前历史计数乘以 `decay`，延迟按指数移动平均更新，
+//!   使凭证表现变化后能逐步重新分配流量
+//! - 持久化：统计写入 `adaptive_selection_stats`，重启后恢复
+
+use chrono::Utc;
+use lime_core::config::AdaptiveSelectionSettings;
+use lime_core::database::dao::adaptive_selection::{AdaptiveArmStats, AdaptiveSelectionDao};
+use lime_core::database::{lock_db, DbConnection};
+use lime_core::models::provider_pool_model::ProviderCredential;
+use parking_lot::{Mutex, RwLock};
+use rand::Rng;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+
+/// 请求未指定模型时使用的统计键
+pub const ANY_MODEL_KEY: &str = "*";
+
+/// 成功延迟的指数移动平均系数
+const LATENCY_EWMA_ALPHA: f64 = 0.2;
+
+/// 尚无统计的凭证得分（成功率与延迟得分均为 0.5）
+const PRIOR_SCORE: f64 = 0.5;
+
+/// 统计写库间隔（秒）
+const PERSIST_INTERVAL_SECS: i64 = 60;
+
+type ArmKey = (String, String);
+
+/// 带当前得分的统计
+#[derive(Debug, Clone, Serialize)]
+pub struct AdaptiveArmView {
+    #[serde(flatten)]
+    pub stats: AdaptiveArmStats,
+    /// 平滑成功率
+    pub success_rate: f64,
+    /// 综合得分（0-1）
+    pub score: f64,
+}
+
+/// 自适应凭证选择器
+pub struct AdaptiveSelector {
+    settings: RwLock<AdaptiveSelectionSettings>,
+    arms: RwLock<HashMap<ArmKey, AdaptiveArmStats>>,
+    /// 尚未写库的统计
+    dirty: Mutex<HashSet<ArmKey>>,
+    loaded: AtomicBool,
+    /// 上次写库时间（Unix 秒）
+    last_persist: AtomicI64,
+}
+
+impl Default for AdaptiveSelector {
+    fn default() -> Self {
+        Self::new(&AdaptiveSelectionSettings::default())
+    }
+}
+
+impl AdaptiveSelector {
+    pub fn new(settings: &AdaptiveSelectionSettings) -> Self {
+        Self {
+            settings: RwLock::new(settings.clone()),
+            arms: RwLock::new(HashMap::new()),
+            dirty: Mutex::new(HashSet::new()),
+            loaded: AtomicBool::new(false),
+            last_persist: AtomicI64::new(0),
+        }
+    }
+
+    /// 热更新配置（已学习的统计保留）
+    pub fn reload(&self, settings: &AdaptiveSelectionSettings) {
+        *self.settings.write() = settings.clone();
+    }
+
+    pub fn settings(&self) -> AdaptiveSelectionSettings {
+        self.settings.read().clone()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.read().enabled
+    }
+
+    /// 从数据库恢复统计（只执行一次，之后以内存为准）
+    pub fn load(&self, db: &DbConnection) {
+        if self.loaded.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let result = lock_db(db)
+            .and_then(|conn| AdaptiveSelectionDao::list(&conn).map_err(|e| e.to_string()));
+        match result {
+            Ok(rows) => {
+                let mut arms = self.arms.write();
+                for row in rows {
+                    arms.entry((row.model.clone(), row.credential_id.clone()))
+                        .or_insert(row);
+                }
+            }
+            Err(e) => {
+                self.loaded.store(false, Ordering::SeqCst);
+                tracing::warn!("[ADAPTIVE] 读取凭证选择统计失败: {}", e);
+            }
+        }
+    }
+
+    /// 在候选凭证中选择（候选不能为空）
+    pub fn select<'a>(
+        &self,
+        model: Option<&str>,
+        candidates: &'a [ProviderCredential],
+    ) -> &'a ProviderCredential {
+        let mut rng = rand::thread_rng();
+        let index = self.choose(
+            model,
+            candidates,
+            rng.gen::<f64>(),
+            rng.gen_range(0..candidates.len()),
+        );
+        &candidates[index]
+    }
+
+    /// 选择候选下标：`roll` 小于探索概率时返回 `random_index`，否则返回得分最高者
+    fn choose(
+        &self,
+        model: Option<&str>,
+        candidates: &[ProviderCredential],
+        roll: f64,
+        random_index: usize,
+    ) -> usize {
+        let settings = self.settings.read().clone();
+        if roll < settings.exploration_rate.clamp(0.0, 1.0) {
+            return random_index;
+        }
+        let model = model_key(model);
+        let arms = self.arms.read();
+        let mut best = 0;
+        let mut best_score = f64::MIN;
+        for (index, cred) in candidates.iter().enumerate() {
+            let score = arms
+                .get(&(model.to_string(), cred.uuid.clone()))
+                .map(|stats| arm_score(stats, &settings))
+                .unwrap_or(PRIOR_SCORE);
+            if score > best_score {
+                best_score = score;
+                best = index;
+            }
+        }
+        best
+    }
+
+    /// 记录一次请求结果（`latency_ms` 只在成功时计入延迟均值）
+    pub fn record_outcome(
+        &self,
+        model: Option<&str>,
+        credential_id: &str,
+        success: bool,
+        latency_ms: u64,
+    ) {
+        let decay = self.settings.read().decay.clamp(0.0, 1.0);
+        let key = (model_key(model).to_string(), credential_id.to_string());
+        {
+            let mut arms = self.arms.write();
+            let stats = arms.entry(key.clone()).or_insert_with(|| AdaptiveArmStats {
+                model: key.0.clone(),
+                credential_id: key.1.clone(),
+                successes: 0.0,
+                failures: 0.0,
+                latency_ms: 0.0,
+                samples: 0,
+                updated_at: 0,
+            });
+            stats.successes *= decay;
+            stats.failures *= decay;
+            if success {
+                stats.successes += 1.0;
+                stats.latency_ms = if stats.latency_ms <= 0.0 {
+                    latency_ms as f64
+                } else {
+                    stats.latency_ms * (1.0 - LATENCY_EWMA_ALPHA)
+                        + latency_ms as f64 * LATENCY_EWMA_ALPHA
+                };
+            } else {
+                stats.failures += 1.0;
+            }
+            stats.samples += 1;
+            stats.updated_at = Utc::now().timestamp();
+        }
+        self.dirty.lock().insert(key);
+    }
+
+    /// 距上次写库超过间隔时写入变更的统计
+    pub fn persist_if_due(&self, db: &DbConnection) {
+        let now = Utc::now().timestamp();
+        let last = self.last_persist.load(Ordering::Relaxed);
+        if now - last < PERSIST_INTERVAL_SECS
+            || self
+                .last_persist
+                .compare_exchange(last, now, Ordering::SeqCst, Ordering::Relaxed)
+                .is_err()
+        {
+            return;
+        }
+        if let Err(e) = self.persist(db) {
+            tracing::warn!("[ADAPTIVE] 写入凭证选择统计失败: {}", e);
+        }
+    }
+
+    /// 写入变更的统计，返回写入条数
+    pub fn persist(&self, db: &DbConnection) -> Result<usize, String> {
+        let keys: Vec<ArmKey> = self.dirty.lock().drain().collect();
+        if keys.is_empty() {
+            return Ok(0);
+        }
+        let rows: Vec<AdaptiveArmStats> = {
+            let arms = self.arms.read();
+            keys.iter()
+                .filter_map(|key| arms.get(key).cloned())
+                .collect()
+        };
+        let result = lock_db(db).and_then(|conn| {
+            for row in &rows {
+                AdaptiveSelectionDao::upsert(&conn, row).map_err(|e| e.to_string())?;
+            }
+            Ok(rows.len())
+        });
+        if result.is_err() {
+            self.dirty.lock().extend(keys);
+        }
+        result
+    }
+
+    /// 当前统计与得分（按模型、得分降序）
+    pub fn snapshot(&self, model: Option<&str>) -> Vec<AdaptiveArmView> {
+        let settings = self.settings.read().clone();
+        let mut views: Vec<AdaptiveArmView> = self
+            .arms
+            .read()
+            .values()
+            .filter(|stats| model.is_none() || model == Some(stats.model.as_str()))
+            .map(|stats| AdaptiveArmView {
+                success_rate: success_rate(stats),
+                score: arm_score(stats, &settings),
+                stats: stats.clone(),
+            })
+            .collect();
+        views.sort_by(|a, b| {
+            a.stats
+                .model
+                .cmp(&b.stats.model)
+                .then(b.score.total_cmp(&a.score))
+        });
+        views
+    }
+
+    /// 清除已学习的统计（指定模型时只清除该模型），返回清除的条数
+    pub fn reset(&self, db: Option<&DbConnection>, model: Option<&str>) -> Result<usize, String> {
+        let removed = {
+            let mut arms = self.arms.write();
+            let before = arms.len();
+            arms.retain(|(arm_model, _), _| model.is_some_and(|m| m != arm_model));
+            before - arms.len()
+        };
+        self.dirty
+            .lock()
+            .retain(|(arm_model, _)| model.is_some_and(|m| m != arm_model));
+        if let Some(db) = db {
+            let conn = lock_db(db)?;
+            AdaptiveSelectionDao::clear(&conn, model).map_err(|e| e.to_string())?;
+        }
+        tracing::info!(
+            "[ADAPTIVE] 已重置凭证选择统计 model={} count={}",
+            model.unwrap_or("*"),
+            removed
+        );
+        Ok(removed)
+    }
+
+    /// 删除凭证的全部统计
+    pub fn remove_credential(&self, db: &DbConnection, credential_id: &str) {
+        self.arms.write().retain(|(_, id), _| id != credential_id);
+        self.dirty.lock().retain(|(_, id)| id != credential_id);
+        if let Ok(conn) = lock_db(db) {
+            let _ = AdaptiveSelectionDao::delete_credential(&conn, credential_id);
+        }
+    }
+}
+
+fn model_key(model: Option<&str>) -> &str {
+    model.filter(|m| !m.is_empty()).unwrap_or(ANY_MODEL_KEY)
+}
+
+fn success_rate(stats: &AdaptiveArmStats) -> f64 {
+    (stats.successes + 1.0) / (stats.successes + stats.failures + 2.0)
+}
+
+fn latency_score(latency_ms: f64, settings: &AdaptiveSelectionSettings) -> f64 {
+    if latency_ms <= 0.0 {
+        return 0.5;
+    }
+    let reference = settings.reference_latency_ms.max(1) as f64;
+    reference / (reference + latency_ms)
+}
+
+fn arm_score(stats: &AdaptiveArmStats, settings: &AdaptiveSelectionSettings) -> f64 {
+    let weight = settings.latency_weight.clamp(0.0, 1.0);
+    (1.0 - weight) * success_rate(stats) + weight * latency_score(stats.latency_ms, settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::models::provider_pool_model::{CredentialData, PoolProviderType};
+
+    fn credential(uuid: &str) -> ProviderCredential {
+        let mut cred = ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: format!("sk-{uuid}"),
+                base_url: None,
+            },
+        );
+        cred.uuid = uuid.to_string();
+        cred
+    }
+
+    fn enabled() -> AdaptiveSelectionSettings {
+        AdaptiveSelectionSettings {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_exploits_better_credential_and_explores_by_roll() {
+        let selector = AdaptiveSelector::new(&enabled());
+        let candidates = vec![credential("slow"), credential("fast")];
+        for _ in 0..10 {
+            selector.record_outcome(Some("gpt-4o"), "slow", true, 20_000);
+            selector.record_outcome(Some("gpt-4o"), "slow", false, 0);
+            selector.record_outcome(Some("gpt-4o"), "fast", true, 800);
+        }
+
+        assert_eq!(selector.choose(Some("gpt-4o"), &candidates, 0.99, 0), 1);
+        // 命中探索概率时使用随机下标
+        assert_eq!(selector.choose(Some("gpt-4o"), &candidates, 0.01, 0), 0);
+        // 其他模型尚无统计，按先验得分取第一个
+        assert_eq!(selector.choose(Some("claude"), &candidates, 0.99, 1), 0);
+    }
+
+    #[test]
+    fn test_decay_lets_recent_failures_dominate() {
+        let settings = AdaptiveSelectionSettings {
+            decay: 0.5,
+            ..enabled()
+        };
+        let selector = AdaptiveSelector::new(&settings);
+        for _ in 0..20 {
+            selector.record_outcome(None, "a", true, 1000);
+        }
+        for _ in 0..5 {
+            selector.record_outcome(None, "a", false, 0);
+        }
+
+        let view = &selector.snapshot(Some(ANY_MODEL_KEY))[0];
+        assert_eq!(view.stats.samples, 25);
+        // 不衰减时成功率约为 0.78
+        assert!(view.success_rate < 0.3);
+    }
+
+    #[test]
+    fn test_reset_by_model() {
+        let selector = AdaptiveSelector::new(&enabled());
+        selector.record_outcome(Some("m1"), "a", true, 100);
+        selector.record_outcome(Some("m2"), "a", true, 100);
+
+        assert_eq!(selector.reset(None, Some("m1")).unwrap(), 1);
+        assert_eq!(selector.snapshot(None).len(), 1);
+        assert_eq!(selector.reset(None, None).unwrap(), 1);
+        assert!(selector.snapshot(None).is_empty());
+    }
+}
@@ -14,7 +14,12 @@ use lime_core::database::dao::api_key_provider::{
 };
 use lime_core::database::system_providers::{get_system_providers, to_api_key_provider};
 use lime_core::database::DbConnection;
-use lime_core::models::{CredentialData, CredentialSource, PoolProviderType, ProviderCredential};
+use lime_core::models::{
+    CredentialData, CredentialSource, PoolProviderType, ProviderCredential,
+    DEFAULT_CREDENTIAL_WEIGHT,
+};
+use lime_core::response_stream::ResponseAccumulator;
+use lime_providers::stream::{decode_sse_body, CodexChunkDecoder, OpenAiChunkDecoder};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
@@ -154,6 +159,15 @@ data: [DONE]\n";
         assert_eq!(content, "hi!");
     }
 
+    #[test]
+    fn test_parse_chat_completions_sse_content() {
+        let body = "data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"he\"}}]}\n\n\
+data: {\"choices\":[{\"index\":0,\"delta\":{\"content\":\"llo\"},\"finish_reason\":\"stop\"}]}\n\n\
+data: [DONE]\n";
+        let content = ApiKeyProviderService::parse_chat_completions_sse_content(body);
+        assert_eq!(content, "hello");
+    }
+
     #[test]
     fn test_uses_anthropic_protocol() {
         assert!(ApiKeyProviderService::uses_anthropic_protocol(
@@ -1033,28 +1047,10 @@ impl ApiKeyProviderService {
     }
 
     fn parse_chat_completions_sse_content(body: &str) -> String {
-        let mut out = String::new();
-
-        for line in body.lines() {
-            let line = line.trim();
-            if !line.starts_with("data:") {
-                continue;
-            }
-            let data = line.trim_start_matches("data:").trim();
-            if data.is_empty() || data == "[DONE]" {
-                continue;
-            }
-
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(data) {
-                if let Some(s) = v["choices"][0]["delta"]["content"].as_str() {
-                    out.push_str(s);
-                } else if let Some(s) = v["choices"][0]["message"]["content"].as_str() {
-                    out.push_str(s);
-                }
-            }
-        }
-
-        out
+        decode_sse_body(body, OpenAiChunkDecoder::new())
+            .iter()
+            .collect::<ResponseAccumulator>()
+            .text
     }
 
     fn build_openai_responses_request(
@@ -1229,43 +1225,10 @@ impl ApiKeyProviderService {
     }
 
     fn parse_codex_responses_sse_content(body: &str) -> String {
-        let mut out = String::new();
-
-        for line in body.lines() {
-            let line = line.trim();
-            if !line.starts_with("data:") {
-                continue;
-            }
-            let data = line.trim_start_matches("data:").trim();
-            if data.is_empty() || data == "[DONE]" {
-                continue;
-            }
-
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(data) {
-                // Codex responses 格式: {"type": "response.output_text.delta", "delta": "..."}
-                if let Some(s) = v["delta"].as_str() {
-                    out.push_str(s);
-                }
-                // 或者完整响应格式
-                if let Some(arr) = v["output"].as_array() {
-                    for item in arr {
-                        if item["type"].as_str() == Some("message") {
-                            if let Some(content_arr) = item["content"].as_array() {
-                                for c in content_arr {
-                                    if c["type"].as_str() == Some("output_text") {
-                                        if let Some(text) = c["text"].as_str() {
-                                            out.push_str(text);
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        out
+        decode_sse_body(body, CodexChunkDecoder::new())
+            .iter()
+            .collect::<ResponseAccumulator>()
+            .text
     }
 
     // ==================== Provider 操作 ====================
@@ -2273,6 +2236,9 @@ impl ApiKeyProviderService {
             cached_token: None,
             source: CredentialSource::Imported,
             proxy_url: None,
+            native_web_search: false,
+            fallback_base_urls: Vec::new(),
+            weight: DEFAULT_CREDENTIAL_WEIGHT,
         })
     }
 
@@ -2331,6 +2297,9 @@ impl ApiKeyProviderService {
             cached_token: None,
             source: CredentialSource::Imported, // 标记为导入来源
             proxy_url: None,
+            native_web_search: false,
+            fallback_base_urls: Vec::new(),
+            weight: DEFAULT_CREDENTIAL_WEIGHT,
         })
     }
 
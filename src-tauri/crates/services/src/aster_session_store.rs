@@ -19,7 +19,9 @@ use aster::session::{
 use async_trait::async_trait;
 use chrono::Utc;
 use lime_core::app_paths;
+use lime_core::database::dao::cost_usage::CostUsageDao;
 use lime_core::database::DbConnection;
+use lime_core::models::model_registry::MessageCost;
 use lime_core::workspace::WorkspaceManager;
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
@@ -30,12 +32,20 @@ use std::path::{Path, PathBuf};
 /// 将 aster 的会话数据存储到 Lime 的 SQLite 数据库
 pub struct LimeSessionStore {
     db: DbConnection,
+    /// 尚未归属到 assistant 消息的 token 用量（session_id → (input, output)）
+    ///
+    /// aster 在每次 Provider 调用结束后先更新 token 统计，再持久化对应的
+    /// assistant 消息，因此用量在下一条 assistant 消息写入时结算。
+    pending_usage: std::sync::Mutex<HashMap<String, (u32, u32)>>,
 }
 
 impl LimeSessionStore {
     /// 创建新的 SessionStore 实例
     pub fn new(db: DbConnection) -> Self {
-        Self { db }
+        Self {
+            db,
+            pending_usage: std::sync::Mutex::new(HashMap::new()),
+        }
     }
 
     pub fn load_extension_data_from_conn(
@@ -401,10 +411,18 @@ impl SessionStore for LimeSessionStore {
             }
         });
 
+        let cost_json = if role == "assistant" {
+            self.take_pending_cost(&conn, session_id)
+                .map(|cost| serde_json::to_string(&cost))
+                .transpose()?
+        } else {
+            None
+        };
+
         conn.execute(
-            "INSERT INTO agent_messages (session_id, role, content_json, timestamp, tool_calls_json, tool_call_id)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            rusqlite::params![session_id, role, content_json, timestamp, tool_calls_json, tool_call_id],
+            "INSERT INTO agent_messages (session_id, role, content_json, timestamp, tool_calls_json, tool_call_id, cost_json)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![session_id, role, content_json, timestamp, tool_calls_json, tool_call_id, cost_json],
         )
         .map_err(|e| anyhow!("添加消息失败: {e}"))?;
 
@@ -692,6 +710,8 @@ impl SessionStore for LimeSessionStore {
             self.replace_conversation(&new_session.id, conversation)
                 .await?;
         }
+        // 复制的历史统计不属于任何新消息
+        self.clear_pending_usage(&new_session.id);
 
         Ok(new_session)
     }
@@ -743,6 +763,8 @@ impl SessionStore for LimeSessionStore {
             self.replace_conversation(&new_session.id, conversation)
                 .await?;
         }
+        // 复制的历史统计不属于任何新消息
+        self.clear_pending_usage(&new_session.id);
 
         Ok(new_session)
     }
@@ -793,6 +815,7 @@ impl SessionStore for LimeSessionStore {
     }
 
     async fn update_token_stats(&self, session_id: &str, stats: TokenStatsUpdate) -> Result<()> {
+        self.record_pending_usage(session_id, stats.input_tokens, stats.output_tokens);
         let conn = self.db.lock().map_err(|e| anyhow!("数据库锁定失败: {e}"))?;
         let now = Utc::now().to_rfc3339();
         conn.execute(
@@ -992,6 +1015,62 @@ impl SessionStore for LimeSessionStore {
 // ============================================================================
 
 impl LimeSessionStore {
+    /// 累计尚未归属的 token 用量
+    fn record_pending_usage(
+        &self,
+        session_id: &str,
+        input_tokens: Option<i32>,
+        output_tokens: Option<i32>,
+    ) {
+        if input_tokens.is_none() && output_tokens.is_none() {
+            return;
+        }
+        let input = input_tokens.unwrap_or(0).max(0) as u32;
+        let output = output_tokens.unwrap_or(0).max(0) as u32;
+        if let Ok(mut pending) = self.pending_usage.lock() {
+            let entry = pending.entry(session_id.to_string()).or_insert((0, 0));
+            entry.0 = entry.0.saturating_add(input);
+            entry.1 = entry.1.saturating_add(output);
+        }
+    }
+
+    fn clear_pending_usage(&self, session_id: &str) {
+        if let Ok(mut pending) = self.pending_usage.lock() {
+            pending.remove(session_id);
+        }
+    }
+
+    /// 取出待结算用量并按会话模型的定价计算费用
+    fn take_pending_cost(
+        &self,
+        conn: &rusqlite::Connection,
+        session_id: &str,
+    ) -> Option<MessageCost> {
+        let (input, output) = self.pending_usage.lock().ok()?.remove(session_id)?;
+        let model: Option<String> = conn
+            .query_row(
+                "SELECT model FROM agent_sessions WHERE id = ?1",
+                rusqlite::params![session_id],
+                |row| row.get(0),
+            )
+            .ok()
+            .map(|model: String| Self::strip_mode_prefix(&model).to_string())
+            .filter(|model| !model.is_empty() && model != "default");
+        let pricing = model
+            .as_deref()
+            .and_then(|model| CostUsageDao::get_model_pricing(conn, model).ok().flatten());
+        Some(MessageCost::new(model, pricing.as_ref(), input, output))
+    }
+
+    /// 去掉 `agent:` 等对话模式前缀
+    fn strip_mode_prefix(model: &str) -> &str {
+        ["agent:", "general:", "creator:"]
+            .iter()
+            .find_map(|prefix| model.strip_prefix(prefix))
+            .unwrap_or(model)
+            .trim()
+    }
+
     /// 加载会话的对话历史
     fn load_conversation(
         &self,
@@ -1129,6 +1208,69 @@ mod tests {
         assert_eq!(persisted_model, "gpt-4.1");
     }
 
+    #[tokio::test]
+    async fn add_message_should_attach_pending_usage_cost_to_assistant_message() {
+        let store = setup_test_store();
+        let session = store
+            .create_session(
+                PathBuf::from("."),
+                "费用会话".to_string(),
+                SessionType::User,
+            )
+            .await
+            .expect("创建会话失败");
+        store
+            .update_provider_config(
+                &session.id,
+                None,
+                Some(ModelConfig::new("gpt-4.1").expect("model config")),
+            )
+            .await
+            .expect("更新 provider 配置失败");
+        {
+            let conn = store.db.lock().expect("锁数据库");
+            conn.execute(
+                "INSERT INTO model_registry (id, display_name, provider_id, provider_name, pricing, created_at, updated_at)
+                 VALUES ('gpt-4.1', 'GPT-4.1', 'openai', 'OpenAI', ?1, 0, 0)",
+                [r#"{"input_per_million":2.0,"output_per_million":8.0,"cache_read_per_million":null,"cache_write_per_million":null,"currency":"USD"}"#],
+            )
+            .expect("插入模型定价失败");
+        }
+
+        store
+            .add_message(&session.id, &Message::user().with_text("你好"))
+            .await
+            .expect("写入用户消息失败");
+        store
+            .update_token_stats(
+                &session.id,
+                TokenStatsUpdate {
+                    schedule_id: None,
+                    total_tokens: Some(1_500),
+                    input_tokens: Some(1_000),
+                    output_tokens: Some(500),
+                    accumulated_total: Some(1_500),
+                    accumulated_input: Some(1_000),
+                    accumulated_output: Some(500),
+                },
+            )
+            .await
+            .expect("更新 token 统计失败");
+        store
+            .add_message(&session.id, &Message::assistant().with_text("你好！"))
+            .await
+            .expect("写入助手消息失败");
+
+        let conn = store.db.lock().expect("锁数据库");
+        let messages = lime_core::database::dao::agent::AgentDao::get_messages(&conn, &session.id)
+            .expect("读取消息失败");
+        assert!(messages[0].cost.is_none());
+        let cost = messages[1].cost.as_ref().expect("助手消息应附带费用");
+        assert_eq!(cost.model.as_deref(), Some("gpt-4.1"));
+        assert_eq!(cost.total_tokens, 1_500);
+        assert!((cost.cost.unwrap() - 0.006).abs() < 1e-12);
+    }
+
     #[tokio::test]
     async fn get_session_should_prefer_default_workspace_root_when_missing_row() {
         let store = setup_test_store();
@@ -0,0 +1,749 @@
+//! 对话导出导入服务
+//!
+//! 将 ChatGPT / Claude 网页端导出的对话存档导入为通用对话会话：
+//! - ChatGPT：`conversations.json`，消息以 `mapping` 树存储，沿 `current_node` 回溯得到当前分支
+//! - Claude：`conversations.json`，每个对话包含线性的 `chat_messages`
+//!
+//! 支持直接传入 JSON 文件或导出的 zip 存档。会话 ID 由来源对话 ID 派生，重复导入时跳过已存在的会话。
+//! 附件尽力处理：带有提取文本的附件内联到消息中，其余附件以占位符标注并记入跳过列表。
+
+use chrono::{DateTime, TimeZone, Utc};
+use lime_core::agent::types::{AgentMessage, AgentSession, MessageContent};
+use lime_core::database::dao::agent::AgentDao;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::Read;
+use std::path::Path;
+
+/// 导入会话使用的模型标识（通用对话）
+const IMPORTED_SESSION_MODEL: &str = "general:default";
+
+/// 存档中对话文件的名称
+const CONVERSATIONS_FILE: &str = "conversations.json";
+
+/// 对话文件最大大小（512MB）
+const MAX_EXPORT_SIZE: u64 = 512 * 1024 * 1024;
+
+/// 导出存档格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChatExportFormat {
+    Chatgpt,
+    Claude,
+}
+
+impl ChatExportFormat {
+    fn session_prefix(self) -> &'static str {
+        match self {
+            Self::Chatgpt => "import-chatgpt-",
+            Self::Claude => "import-claude-",
+        }
+    }
+}
+
+/// 解析后的对话
+#[derive(Debug, Clone)]
+pub struct ImportedConversation {
+    /// 来源对话 ID
+    pub source_id: String,
+    pub title: String,
+    pub created_at: DateTime<Utc>,
+    pub messages: Vec<ImportedMessage>,
+}
+
+/// 解析后的消息
+#[derive(Debug, Clone)]
+pub struct ImportedMessage {
+    /// user / assistant
+    pub role: String,
+    pub text: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// 跳过的条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedChatItem {
+    /// 对话标题或 ID
+    pub conversation: String,
+    pub reason: String,
+}
+
+/// 导入报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatExportImportReport {
+    pub format: ChatExportFormat,
+    pub imported_sessions: usize,
+    pub imported_messages: usize,
+    pub skipped: Vec<SkippedChatItem>,
+}
+
+impl ChatExportImportReport {
+    fn new(format: ChatExportFormat) -> Self {
+        Self {
+            format,
+            imported_sessions: 0,
+            imported_messages: 0,
+            skipped: Vec::new(),
+        }
+    }
+}
+
+fn skip(skipped: &mut Vec<SkippedChatItem>, conversation: &str, reason: impl Into<String>) {
+    skipped.push(SkippedChatItem {
+        conversation: conversation.to_string(),
+        reason: reason.into(),
+    });
+}
+
+// ============ 读取 ============
+
+/// 读取导出文件（JSON 或 zip 存档）中的对话列表
+pub fn read_export(path: &Path) -> Result<Value, String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("读取导出文件失败: {e}"))?;
+    if metadata.len() > MAX_EXPORT_SIZE {
+        return Err(format!(
+            "导出文件过大（最大 {}MB）",
+            MAX_EXPORT_SIZE / 1024 / 1024
+        ));
+    }
+
+    let is_zip = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    let content = if is_zip {
+        read_conversations_from_zip(path)?
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("读取导出文件失败: {e}"))?
+    };
+    serde_json::from_str(&content).map_err(|e| format!("解析对话文件失败: {e}"))
+}
+
+fn read_conversations_from_zip(path: &Path) -> Result<String, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("打开存档失败: {e}"))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("读取存档失败: {e}"))?;
+    let index = (0..archive.len())
+        .filter_map(|i| {
+            let entry = archive.by_index(i).ok()?;
+            let name = entry.name().to_string();
+            (name == CONVERSATIONS_FILE || name.ends_with(&format!("/{CONVERSATIONS_FILE}")))
+                .then_some((i, name.matches('/').count()))
+        })
+        .min_by_key(|(_, depth)| *depth)
+        .map(|(i, _)| i)
+        .ok_or_else(|| format!("存档中未找到 {CONVERSATIONS_FILE}"))?;
+
+    let mut entry = archive
+        .by_index(index)
+        .map_err(|e| format!("读取存档失败: {e}"))?;
+    if entry.size() > MAX_EXPORT_SIZE {
+        return Err(format!(
+            "{CONVERSATIONS_FILE} 过大（最大 {}MB）",
+            MAX_EXPORT_SIZE / 1024 / 1024
+        ));
+    }
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| format!("读取 {CONVERSATIONS_FILE} 失败: {e}"))?;
+    Ok(content)
+}
+
+/// 根据对话结构识别导出格式
+pub fn detect_format(export: &Value) -> Option<ChatExportFormat> {
+    let first = export.as_array()?.first()?;
+    if first.get("mapping").is_some() {
+        Some(ChatExportFormat::Chatgpt)
+    } else if first.get("chat_messages").is_some() {
+        Some(ChatExportFormat::Claude)
+    } else {
+        None
+    }
+}
+
+// ============ 时间 ============
+
+/// ChatGPT 使用秒级浮点 Unix 时间戳
+fn from_unix_seconds(value: &Value) -> Option<DateTime<Utc>> {
+    let seconds = value.as_f64()?;
+    Utc.timestamp_millis_opt((seconds * 1000.0) as i64).single()
+}
+
+/// Claude 使用 RFC 3339 字符串
+fn from_rfc3339(value: &Value) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.as_str()?)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn non_empty_str(value: Option<&Value>) -> Option<&str> {
+    value
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+// ============ ChatGPT ============
+
+/// 解析 ChatGPT 导出
+pub fn parse_chatgpt(
+    export: &Value,
+    skipped: &mut Vec<SkippedChatItem>,
+) -> Vec<ImportedConversation> {
+    let Some(conversations) = export.as_array() else {
+        skip(skipped, CONVERSATIONS_FILE, "对话文件不是数组");
+        return Vec::new();
+    };
+    conversations
+        .iter()
+        .filter_map(|conversation| parse_chatgpt_conversation(conversation, skipped))
+        .collect()
+}
+
+fn parse_chatgpt_conversation(
+    conversation: &Value,
+    skipped: &mut Vec<SkippedChatItem>,
+) -> Option<ImportedConversation> {
+    let source_id = non_empty_str(conversation.get("conversation_id"))
+        .or_else(|| non_empty_str(conversation.get("id")));
+    let title = non_empty_str(conversation.get("title"))
+        .unwrap_or("未命名对话")
+        .to_string();
+    let Some(source_id) = source_id else {
+        skip(skipped, &title, "缺少对话 ID");
+        return None;
+    };
+    let Some(mapping) = conversation.get("mapping").and_then(Value::as_object) else {
+        skip(skipped, &title, "缺少 mapping");
+        return None;
+    };
+    let created_at = conversation
+        .get("create_time")
+        .and_then(from_unix_seconds)
+        .unwrap_or_else(Utc::now);
+
+    // 沿 current_node 回溯得到当前分支；缺失时从根节点沿最新的子节点向下
+    let mut path = Vec::new();
+    if let Some(current) = non_empty_str(conversation.get("current_node")) {
+        let mut node_id = Some(current);
+        while let Some(id) = node_id {
+            let Some(node) = mapping.get(id) else { break };
+            if path.len() > mapping.len() {
+                break;
+            }
+            path.push(node);
+            node_id = non_empty_str(node.get("parent"));
+        }
+        path.reverse();
+    } else {
+        let mut node = mapping
+            .values()
+            .find(|node| node.get("parent").unwrap_or(&Value::Null).is_null());
+        while let Some(current) = node {
+            if path.len() > mapping.len() {
+                break;
+            }
+            path.push(current);
+            node = current
+                .get("children")
+                .and_then(Value::as_array)
+                .and_then(|children| children.last())
+                .and_then(Value::as_str)
+                .and_then(|id| mapping.get(id));
+        }
+    }
+
+    let mut timestamp = created_at;
+    let mut messages = Vec::new();
+    for node in path {
+        let Some(message) = node.get("message").filter(|m| !m.is_null()) else {
+            continue;
+        };
+        let role = message
+            .pointer("/author/role")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        if role != "user" && role != "assistant" {
+            continue;
+        }
+        if message
+            .pointer("/metadata/is_visually_hidden_from_conversation")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+        {
+            continue;
+        }
+        if let Some(created) = message.get("create_time").and_then(from_unix_seconds) {
+            timestamp = created;
+        }
+
+        let mut text = chatgpt_message_text(message, &title, skipped);
+        if let Some(attachments) = message
+            .pointer("/metadata/attachments")
+            .and_then(Value::as_array)
+        {
+            for attachment in attachments {
+                let name = non_empty_str(attachment.get("name")).unwrap_or("未命名附件");
+                append_block(&mut text, &format!("[附件: {name}]"));
+                skip(skipped, &title, format!("附件 {name} 的内容不在导出中"));
+            }
+        }
+        if text.trim().is_empty() {
+            continue;
+        }
+        messages.push(ImportedMessage {
+            role: role.to_string(),
+            text,
+            timestamp,
+        });
+    }
+
+    Some(ImportedConversation {
+        source_id: source_id.to_string(),
+        title,
+        created_at,
+        messages,
+    })
+}
+
+fn chatgpt_message_text(
+    message: &Value,
+    title: &str,
+    skipped: &mut Vec<SkippedChatItem>,
+) -> String {
+    let Some(content) = message.get("content") else {
+        return String::new();
+    };
+    let content_type = content
+        .get("content_type")
+        .and_then(Value::as_str)
+        .unwrap_or("text");
+    match content_type {
+        "text" | "multimodal_text" => {
+            let mut text = String::new();
+            for part in content
+                .get("parts")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                match part {
+                    Value::String(s) => append_block(&mut text, s),
+                    Value::Object(_) => {
+                        let kind = part
+                            .get("content_type")
+                            .and_then(Value::as_str)
+                            .unwrap_or("unknown");
+                        let label = if kind.contains("image") {
+                            "图片"
+                        } else if kind.contains("audio") {
+                            "音频"
+                        } else {
+                            kind
+                        };
+                        append_block(&mut text, &format!("[附件: {label}]"));
+                        skip(skipped, title, format!("{label}附件的内容不在导出中"));
+                    }
+                    _ => {}
+                }
+            }
+            text
+        }
+        "code" => content
+            .get("text")
+            .and_then(Value::as_str)
+            .map(|code| {
+                let language = content
+                    .get("language")
+                    .and_then(Value::as_str)
+                    .filter(|l| *l != "unknown")
+                    .unwrap_or("");
+                format!("```{language}\n{code}\n```")
+            })
+            .unwrap_or_default(),
+        // 自定义指令、浏览记录等非对话内容
+        _ => String::new(),
+    }
+}
+
+fn append_block(text: &mut String, block: &str) {
+    if block.trim().is_empty() {
+        return;
+    }
+    if !text.is_empty() {
+        text.push_str("\n\n");
+    }
+    text.push_str(block);
+}
+
+// ============ Claude ============
+
+/// 解析 Claude 导出
+pub fn parse_claude(
+    export: &Value,
+    skipped: &mut Vec<SkippedChatItem>,
+) -> Vec<ImportedConversation> {
+    let Some(conversations) = export.as_array() else {
+        skip(skipped, CONVERSATIONS_FILE, "对话文件不是数组");
+        return Vec::new();
+    };
+    conversations
+        .iter()
+        .filter_map(|conversation| parse_claude_conversation(conversation, skipped))
+        .collect()
+}
+
+fn parse_claude_conversation(
+    conversation: &Value,
+    skipped: &mut Vec<SkippedChatItem>,
+) -> Option<ImportedConversation> {
+    let title = non_empty_str(conversation.get("name"))
+        .unwrap_or("未命名对话")
+        .to_string();
+    let Some(source_id) = non_empty_str(conversation.get("uuid")) else {
+        skip(skipped, &title, "缺少对话 ID");
+        return None;
+    };
+    let Some(chat_messages) = conversation.get("chat_messages").and_then(Value::as_array) else {
+        skip(skipped, &title, "缺少 chat_messages");
+        return None;
+    };
+    let created_at = conversation
+        .get("created_at")
+        .and_then(from_rfc3339)
+        .unwrap_or_else(Utc::now);
+
+    let mut timestamp = created_at;
+    let mut messages = Vec::new();
+    for message in chat_messages {
+        let role = match message.get("sender").and_then(Value::as_str) {
+            Some("human") => "user",
+            Some("assistant") => "assistant",
+            _ => continue,
+        };
+        if let Some(created) = message.get("created_at").and_then(from_rfc3339) {
+            timestamp = created;
+        }
+
+        // 优先使用结构化内容中的文本块，旧版导出只有 text 字段
+        let mut text = String::new();
+        for block in message
+            .get("content")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            if block.get("type").and_then(Value::as_str) == Some("text") {
+                if let Some(block_text) = block.get("text").and_then(Value::as_str) {
+                    append_block(&mut text, block_text);
+                }
+            }
+        }
+        if text.is_empty() {
+            if let Some(plain) = message.get("text").and_then(Value::as_str) {
+                append_block(&mut text, plain);
+            }
+        }
+
+        for attachment in message
+            .get("attachments")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let name = non_empty_str(attachment.get("file_name")).unwrap_or("未命名附件");
+            match non_empty_str(attachment.get("extracted_content")) {
+                Some(extracted) => {
+                    append_block(&mut text, &format!("[附件: {name}]\n{extracted}"));
+                }
+                None => {
+                    append_block(&mut text, &format!("[附件: {name}]"));
+                    skip(skipped, &title, format!("附件 {name} 没有可导入的文本内容"));
+                }
+            }
+        }
+        for file in message
+            .get("files")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let name = non_empty_str(file.get("file_name")).unwrap_or("未命名文件");
+            append_block(&mut text, &format!("[附件: {name}]"));
+            skip(skipped, &title, format!("文件 {name} 的内容不在导出中"));
+        }
+
+        if text.trim().is_empty() {
+            continue;
+        }
+        messages.push(ImportedMessage {
+            role: role.to_string(),
+            text,
+            timestamp,
+        });
+    }
+
+    Some(ImportedConversation {
+        source_id: source_id.to_string(),
+        title,
+        created_at,
+        messages,
+    })
+}
+
+// ============ 写入 ============
+
+/// 解析并导入导出内容，`format` 为空时自动识别
+pub fn import_export(
+    conn: &Connection,
+    export: &Value,
+    format: Option<ChatExportFormat>,
+) -> Result<ChatExportImportReport, String> {
+    let format = format
+        .or_else(|| detect_format(export))
+        .ok_or_else(|| "无法识别的导出格式，仅支持 ChatGPT 与 Claude 导出".to_string())?;
+
+    let mut report = ChatExportImportReport::new(format);
+    let conversations = match format {
+        ChatExportFormat::Chatgpt => parse_chatgpt(export, &mut report.skipped),
+        ChatExportFormat::Claude => parse_claude(export, &mut report.skipped),
+    };
+    for conversation in conversations {
+        import_conversation(conn, format, conversation, &mut report)?;
+    }
+    Ok(report)
+}
+
+fn import_conversation(
+    conn: &Connection,
+    format: ChatExportFormat,
+    conversation: ImportedConversation,
+    report: &mut ChatExportImportReport,
+) -> Result<(), String> {
+    if conversation.messages.is_empty() {
+        skip(&mut report.skipped, &conversation.title, "没有可导入的消息");
+        return Ok(());
+    }
+    let session_id = format!("{}{}", format.session_prefix(), conversation.source_id);
+    if AgentDao::session_exists(conn, &session_id).map_err(|e| e.to_string())? {
+        skip(&mut report.skipped, &conversation.title, "已导入过");
+        return Ok(());
+    }
+
+    let created_at = conversation.created_at.to_rfc3339();
+    let session = AgentSession {
+        id: session_id.clone(),
+        model: IMPORTED_SESSION_MODEL.to_string(),
+        messages: Vec::new(),
+        system_prompt: None,
+        title: Some(conversation.title.clone()),
+        working_dir: None,
+        execution_strategy: None,
+        created_at: created_at.clone(),
+        updated_at: created_at,
+    };
+
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| format!("开启事务失败: {e}"))?;
+    AgentDao::create_session(&tx, &session).map_err(|e| format!("创建会话失败: {e}"))?;
+    for message in &conversation.messages {
+        let message = AgentMessage {
+            role: message.role.clone(),
+            content: MessageContent::Text(message.text.clone()),
+            timestamp: message.timestamp.to_rfc3339(),
+            tool_calls: None,
+            tool_call_id: None,
+            reasoning_content: None,
+            cost: None,
+        };
+        AgentDao::add_message(&tx, &session_id, &message)
+            .map_err(|e| format!("写入消息失败: {e}"))?;
+    }
+    tx.commit().map_err(|e| format!("提交事务失败: {e}"))?;
+
+    report.imported_sessions += 1;
+    report.imported_messages += conversation.messages.len();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::database::schema::create_tables;
+    use serde_json::json;
+
+    fn chatgpt_export() -> Value {
+        json!([{
+            "title": "Rust 生命周期",
+            "conversation_id": "c-1",
+            "create_time": 1_700_000_000.5,
+            "current_node": "a2",
+            "mapping": {
+                "root": { "message": null, "parent": null, "children": ["sys"] },
+                "sys": {
+                    "message": {
+                        "author": { "role": "system" },
+                        "content": { "content_type": "text", "parts": [""] }
+                    },
+                    "parent": "root",
+                    "children": ["u1"]
+                },
+                "u1": {
+                    "message": {
+                        "author": { "role": "user" },
+                        "create_time": 1_700_000_010.0,
+                        "content": {
+                            "content_type": "multimodal_text",
+                            "parts": [
+                                { "content_type": "image_asset_pointer", "asset_pointer": "file-service://x" },
+                                "这张图里的代码为什么报错？"
+                            ]
+                        }
+                    },
+                    "parent": "sys",
+                    "children": ["a1", "a2"]
+                },
+                "a1": {
+                    "message": {
+                        "author": { "role": "assistant" },
+                        "create_time": 1_700_000_020.0,
+                        "content": { "content_type": "text", "parts": ["旧的回答"] }
+                    },
+                    "parent": "u1",
+                    "children": []
+                },
+                "a2": {
+                    "message": {
+                        "author": { "role": "assistant" },
+                        "create_time": 1_700_000_030.0,
+                        "content": { "content_type": "text", "parts": ["重新生成的回答"] }
+                    },
+                    "parent": "u1",
+                    "children": []
+                }
+            }
+        }])
+    }
+
+    fn claude_export() -> Value {
+        json!([
+            {
+                "uuid": "k-1",
+                "name": "周报",
+                "created_at": "2024-05-01T08:00:00Z",
+                "chat_messages": [
+                    {
+                        "sender": "human",
+                        "text": "帮我整理周报",
+                        "content": [{ "type": "text", "text": "帮我整理周报" }],
+                        "created_at": "2024-05-01T08:00:01Z",
+                        "attachments": [{ "file_name": "notes.txt", "extracted_content": "周一：评审" }],
+                        "files": [{ "file_name": "chart.png" }]
+                    },
+                    {
+                        "sender": "assistant",
+                        "text": "好的，以下是周报",
+                        "content": [],
+                        "created_at": "2024-05-01T08:00:05Z"
+                    }
+                ]
+            },
+            { "uuid": "k-2", "name": "", "created_at": "2024-05-02T08:00:00Z", "chat_messages": [] }
+        ])
+    }
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_parse_chatgpt_follows_current_branch() {
+        let export = chatgpt_export();
+        assert_eq!(detect_format(&export), Some(ChatExportFormat::Chatgpt));
+
+        let mut skipped = Vec::new();
+        let conversations = parse_chatgpt(&export, &mut skipped);
+        assert_eq!(conversations.len(), 1);
+
+        let conversation = &conversations[0];
+        assert_eq!(conversation.source_id, "c-1");
+        assert_eq!(
+            conversation.created_at.timestamp_millis(),
+            1_700_000_000_500
+        );
+        let texts: Vec<_> = conversation
+            .messages
+            .iter()
+            .map(|m| m.text.as_str())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "[附件: 图片]\n\n这张图里的代码为什么报错？",
+                "重新生成的回答"
+            ]
+        );
+        assert_eq!(
+            conversation.messages[1].timestamp.timestamp(),
+            1_700_000_030
+        );
+        assert_eq!(skipped.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_claude_inlines_extracted_attachments() {
+        let export = claude_export();
+        assert_eq!(detect_format(&export), Some(ChatExportFormat::Claude));
+
+        let mut skipped = Vec::new();
+        let conversations = parse_claude(&export, &mut skipped);
+        assert_eq!(conversations.len(), 2);
+
+        let messages = &conversations[0].messages;
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].role, "user");
+        assert_eq!(
+            messages[0].text,
+            "帮我整理周报\n\n[附件: notes.txt]\n周一：评审\n\n[附件: chart.png]"
+        );
+        assert_eq!(messages[1].text, "好的，以下是周报");
+        assert_eq!(conversations[1].title, "未命名对话");
+        assert_eq!(skipped.len(), 1);
+    }
+
+    #[test]
+    fn test_import_preserves_timestamps_and_skips_duplicates() {
+        let conn = setup_db();
+        let export = claude_export();
+
+        let report = import_export(&conn, &export, None).unwrap();
+        assert_eq!(report.format, ChatExportFormat::Claude);
+        assert_eq!(report.imported_sessions, 1);
+        assert_eq!(report.imported_messages, 2);
+        // 附件内容缺失 + 空对话
+        assert_eq!(report.skipped.len(), 2);
+
+        let session = AgentDao::get_session(&conn, "import-claude-k-1")
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.model, IMPORTED_SESSION_MODEL);
+        assert_eq!(session.title.as_deref(), Some("周报"));
+        assert!(session.created_at.starts_with("2024-05-01T08:00:00"));
+        let messages = AgentDao::get_messages(&conn, "import-claude-k-1").unwrap();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[1].timestamp.starts_with("2024-05-01T08:00:05"));
+
+        let again = import_export(&conn, &export, None).unwrap();
+        assert_eq!(again.imported_sessions, 0);
+        assert!(again.skipped.iter().any(|item| item.reason == "已导入过"));
+    }
+
+    #[test]
+    fn test_unknown_format_is_rejected() {
+        let conn = setup_db();
+        assert!(import_export(&conn, &json!([{ "foo": 1 }]), None).is_err());
+    }
+}
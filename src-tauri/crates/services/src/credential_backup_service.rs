@@ -0,0 +1,513 @@
+//! 凭证备份服务
+//!
+//! 将凭证池凭证（含 OAuth token 文件）、插件密钥与 ASR 凭证打包为单个口令加密的备份文件，
+//! 用于在设备之间迁移：
+//! - 加密方式与设备同步相同：PBKDF2-SHA256 派生密钥，XChaCha20-Poly1305 加密，明文包头作为 AAD
+//! - 包头只包含格式、版本与各类条目数量，不含任何凭证内容
+//! - 导入时按冲突策略处理已存在的条目：跳过、覆盖或合并
+//!
+//! 凭证的采集与写入由调用方负责，本服务只处理打包、加密与冲突判定。
+
+use crate::device_sync_service::{derive_key, KDF_ALGORITHM, KDF_ITERATIONS, SALT_SIZE};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use lime_core::config::AsrCredentialEntry;
+use lime_core::models::provider_pool_model::ProviderCredential;
+use serde::{Deserialize, Serialize};
+
+/// 备份文件格式标识
+pub const BACKUP_FORMAT: &str = "lime-credential-backup";
+/// 备份文件格式版本
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+/// 备份文件扩展名
+pub const BACKUP_FILE_EXTENSION: &str = "limebackup";
+/// 导入时接受的最小 PBKDF2 迭代次数，低于此值视为被降级的备份
+pub const MIN_KDF_ITERATIONS: u32 = 100_000;
+/// 导入时接受的最大 PBKDF2 迭代次数，避免篡改包头导致长时间阻塞
+pub const MAX_KDF_ITERATIONS: u32 = 10_000_000;
+
+/// 导入时已存在条目的处理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictStrategy {
+    /// 保留本地条目
+    #[default]
+    Skip,
+    /// 用备份中的条目替换本地条目
+    Overwrite,
+    /// 合并：配置取较新的一方，本地的使用统计保留
+    Merge,
+}
+
+// ============ 备份内容 ============
+
+/// 凭证池凭证
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupPoolCredential {
+    pub credential: ProviderCredential,
+    /// OAuth 凭证文件内容（base64），导入时写入本机凭证目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_file: Option<String>,
+}
+
+/// 插件密钥（插件存储中经 `crypto.encrypt` 加密的值，备份中为明文，导入时用本机主密钥重新加密）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupPluginSecret {
+    pub plugin_id: String,
+    pub key: String,
+    pub plaintext: String,
+}
+
+/// 备份明文
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialBackup {
+    #[serde(default)]
+    pub pool: Vec<BackupPoolCredential>,
+    #[serde(default)]
+    pub plugin_secrets: Vec<BackupPluginSecret>,
+    #[serde(default)]
+    pub asr: Vec<AsrCredentialEntry>,
+}
+
+impl CredentialBackup {
+    pub fn counts(&self) -> BackupCounts {
+        BackupCounts {
+            pool: self.pool.len(),
+            plugin_secrets: self.plugin_secrets.len(),
+            asr: self.asr.len(),
+        }
+    }
+}
+
+/// 各类条目数量
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupCounts {
+    pub pool: usize,
+    pub plugin_secrets: usize,
+    pub asr: usize,
+}
+
+// ============ 加密 ============
+
+/// 密钥派生参数
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupKdfParams {
+    pub algorithm: String,
+    pub iterations: u32,
+    /// base64 编码的盐
+    pub salt: String,
+}
+
+/// 备份文件头（明文，作为 AAD 参与认证）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialBackupHeader {
+    pub format: String,
+    pub version: u32,
+    /// 导出时的应用版本
+    pub app_version: String,
+    /// 导出时间（毫秒时间戳）
+    pub created_at: i64,
+    pub counts: BackupCounts,
+    pub kdf: BackupKdfParams,
+    /// base64 编码的 24 字节 nonce
+    pub nonce: String,
+}
+
+/// 加密备份文件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialBackupEnvelope {
+    pub header: CredentialBackupHeader,
+    /// base64 编码的密文
+    pub ciphertext: String,
+}
+
+impl CredentialBackupEnvelope {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let envelope: CredentialBackupEnvelope =
+            serde_json::from_slice(bytes).map_err(|e| format!("备份文件格式无效: {e}"))?;
+        if envelope.header.format != BACKUP_FORMAT {
+            return Err(format!("未知的备份格式: {}", envelope.header.format));
+        }
+        if envelope.header.version > BACKUP_FORMAT_VERSION {
+            return Err(format!(
+                "备份版本 {} 高于当前支持的版本 {BACKUP_FORMAT_VERSION}，请先升级应用",
+                envelope.header.version
+            ));
+        }
+        Ok(envelope)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec_pretty(self).map_err(|e| format!("备份序列化失败: {e}"))
+    }
+}
+
+fn header_aad(header: &CredentialBackupHeader) -> Result<Vec<u8>, String> {
+    serde_json::to_vec(header).map_err(|e| format!("备份文件头序列化失败: {e}"))
+}
+
+fn seal_backup_with_iterations(
+    backup: &CredentialBackup,
+    app_version: &str,
+    passphrase: &str,
+    iterations: u32,
+) -> Result<CredentialBackupEnvelope, String> {
+    if passphrase.is_empty() {
+        return Err("备份口令不能为空".to_string());
+    }
+    let mut salt = [0u8; SALT_SIZE];
+    OsRng.fill_bytes(&mut salt);
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let header = CredentialBackupHeader {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_FORMAT_VERSION,
+        app_version: app_version.to_string(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+        counts: backup.counts(),
+        kdf: BackupKdfParams {
+            algorithm: KDF_ALGORITHM.to_string(),
+            iterations,
+            salt: BASE64.encode(salt),
+        },
+        nonce: BASE64.encode(nonce),
+    };
+
+    let key = derive_key(passphrase, &salt, iterations);
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+    let plaintext = serde_json::to_vec(backup).map_err(|e| format!("备份序列化失败: {e}"))?;
+    let aad = header_aad(&header)?;
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &plaintext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| "备份加密失败".to_string())?;
+
+    Ok(CredentialBackupEnvelope {
+        header,
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+/// 使用口令加密备份
+pub fn seal_backup(
+    backup: &CredentialBackup,
+    app_version: &str,
+    passphrase: &str,
+) -> Result<CredentialBackupEnvelope, String> {
+    seal_backup_with_iterations(backup, app_version, passphrase, KDF_ITERATIONS)
+}
+
+/// 解密备份，口令错误或文件被篡改时失败
+pub fn open_backup(
+    envelope: &CredentialBackupEnvelope,
+    passphrase: &str,
+) -> Result<CredentialBackup, String> {
+    open_backup_with_min_iterations(envelope, passphrase, MIN_KDF_ITERATIONS)
+}
+
+fn open_backup_with_min_iterations(
+    envelope: &CredentialBackupEnvelope,
+    passphrase: &str,
+    min_iterations: u32,
+) -> Result<CredentialBackup, String> {
+    let header = &envelope.header;
+    if header.kdf.algorithm != KDF_ALGORITHM {
+        return Err(format!("不支持的密钥派生算法: {}", header.kdf.algorithm));
+    }
+    let iterations = header.kdf.iterations;
+    if !(min_iterations..=MAX_KDF_ITERATIONS).contains(&iterations) {
+        return Err(format!(
+            "备份密钥派生迭代次数无效: {iterations}（应在 {min_iterations} 到 {MAX_KDF_ITERATIONS} 之间）"
+        ));
+    }
+    let salt = BASE64
+        .decode(&header.kdf.salt)
+        .map_err(|_| "备份盐值无效".to_string())?;
+    let nonce = BASE64
+        .decode(&header.nonce)
+        .ok()
+        .filter(|nonce| nonce.len() == 24)
+        .ok_or_else(|| "备份 nonce 无效".to_string())?;
+    let ciphertext = BASE64
+        .decode(&envelope.ciphertext)
+        .map_err(|_| "备份密文无效".to_string())?;
+
+    let key = derive_key(passphrase, &salt, iterations);
+    let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+    let aad = header_aad(header)?;
+    let plaintext = cipher
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: &aad,
+            },
+        )
+        .map_err(|_| "解密失败：备份口令错误或备份文件已被修改".to_string())?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("备份内容无效: {e}"))
+}
+
+// ============ 冲突处理 ============
+
+/// 单个条目的导入决定
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportDecision<T> {
+    /// 本地不存在，新建
+    Insert(T),
+    /// 替换本地条目
+    Overwrite(T),
+    /// 与本地条目合并后的结果
+    Merge(T),
+    /// 保留本地条目
+    Skip,
+}
+
+impl<T> ImportDecision<T> {
+    /// 需要写入的条目（跳过时为 None）
+    pub fn into_value(self) -> Option<T> {
+        match self {
+            ImportDecision::Insert(value)
+            | ImportDecision::Overwrite(value)
+            | ImportDecision::Merge(value) => Some(value),
+            ImportDecision::Skip => None,
+        }
+    }
+}
+
+/// 按冲突策略决定备份条目如何导入
+pub fn resolve<T>(
+    local: Option<&T>,
+    incoming: T,
+    strategy: ConflictStrategy,
+    merge: impl FnOnce(&T, T) -> T,
+) -> ImportDecision<T> {
+    match (local, strategy) {
+        (None, _) => ImportDecision::Insert(incoming),
+        (Some(_), ConflictStrategy::Skip) => ImportDecision::Skip,
+        (Some(_), ConflictStrategy::Overwrite) => ImportDecision::Overwrite(incoming),
+        (Some(local), ConflictStrategy::Merge) => ImportDecision::Merge(merge(local, incoming)),
+    }
+}
+
+/// 合并凭证池凭证
+///
+/// 凭证数据与设置取 `updated_at` 较新的一方，使用统计与健康状态保留本地值，
+/// 模型列表取并集。
+pub fn merge_pool_credential(
+    local: &ProviderCredential,
+    incoming: ProviderCredential,
+) -> ProviderCredential {
+    let supported_models = union_models(&local.supported_models, &incoming.supported_models);
+    let not_supported_models =
+        union_models(&local.not_supported_models, &incoming.not_supported_models);
+
+    let mut merged = if incoming.updated_at > local.updated_at {
+        let mut merged = incoming;
+        merged.is_healthy = local.is_healthy;
+        merged.usage_count = local.usage_count;
+        merged.error_count = local.error_count;
+        merged.last_used = local.last_used;
+        merged.last_error_time = local.last_error_time;
+        merged.last_error_message = local.last_error_message.clone();
+        merged.last_health_check_time = local.last_health_check_time;
+        merged.last_health_check_model = local.last_health_check_model.clone();
+        merged.created_at = local.created_at.min(merged.created_at);
+        merged
+    } else {
+        local.clone()
+    };
+    merged.supported_models = supported_models;
+    merged.not_supported_models = not_supported_models;
+    merged
+}
+
+/// 本地列表在前，追加备份中独有的模型
+fn union_models(local: &[String], incoming: &[String]) -> Vec<String> {
+    let mut models = local.to_vec();
+    for model in incoming {
+        if !models.contains(model) {
+            models.push(model.clone());
+        }
+    }
+    models
+}
+
+/// 合并 ASR 凭证：本地已有的设置保留，本地缺失的 Provider 配置从备份补全
+pub fn merge_asr_entry(
+    local: &AsrCredentialEntry,
+    incoming: AsrCredentialEntry,
+) -> AsrCredentialEntry {
+    let mut merged = local.clone();
+    merged.name = merged.name.or(incoming.name);
+    merged.whisper_config = merged.whisper_config.or(incoming.whisper_config);
+    merged.xunfei_config = merged.xunfei_config.or(incoming.xunfei_config);
+    merged.baidu_config = merged.baidu_config.or(incoming.baidu_config);
+    merged.openai_config = merged.openai_config.or(incoming.openai_config);
+    merged
+}
+
+/// 某类条目的导入统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImportCounts {
+    pub inserted: usize,
+    pub overwritten: usize,
+    pub merged: usize,
+    pub skipped: usize,
+}
+
+impl ImportCounts {
+    pub fn record<T>(&mut self, decision: &ImportDecision<T>) {
+        match decision {
+            ImportDecision::Insert(_) => self.inserted += 1,
+            ImportDecision::Overwrite(_) => self.overwritten += 1,
+            ImportDecision::Merge(_) => self.merged += 1,
+            ImportDecision::Skip => self.skipped += 1,
+        }
+    }
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CredentialBackupImportReport {
+    pub pool: ImportCounts,
+    pub plugin_secrets: ImportCounts,
+    pub asr: ImportCounts,
+    /// 写入失败而跳过的条目
+    pub warnings: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use lime_core::models::provider_pool_model::{CredentialData, PoolProviderType};
+
+    fn openai_credential(api_key: &str) -> ProviderCredential {
+        ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: api_key.to_string(),
+                base_url: None,
+            },
+        )
+    }
+
+    fn api_key_of(cred: &ProviderCredential) -> &str {
+        cred.credential.api_key().unwrap_or_default()
+    }
+
+    #[test]
+    fn test_seal_and_open_backup() {
+        let backup = CredentialBackup {
+            pool: vec![BackupPoolCredential {
+                credential: openai_credential("sk-backup"),
+                token_file: None,
+            }],
+            plugin_secrets: vec![BackupPluginSecret {
+                plugin_id: "plugin-a".to_string(),
+                key: "token".to_string(),
+                plaintext: "secret".to_string(),
+            }],
+            asr: Vec::new(),
+        };
+
+        let envelope = seal_backup_with_iterations(&backup, "1.0.0", "passphrase", 1000).unwrap();
+        assert_eq!(envelope.header.counts.pool, 1);
+        assert_eq!(envelope.header.counts.plugin_secrets, 1);
+        let bytes = envelope.to_bytes().unwrap();
+        assert!(!String::from_utf8_lossy(&bytes).contains("sk-backup"));
+
+        let parsed = CredentialBackupEnvelope::from_bytes(&bytes).unwrap();
+        let opened = open_backup_with_min_iterations(&parsed, "passphrase", 1000).unwrap();
+        assert_eq!(api_key_of(&opened.pool[0].credential), "sk-backup");
+        assert_eq!(opened.plugin_secrets, backup.plugin_secrets);
+
+        assert!(open_backup_with_min_iterations(&parsed, "wrong", 1000).is_err());
+        let mut tampered = parsed.clone();
+        tampered.header.counts.pool = 5;
+        assert!(open_backup_with_min_iterations(&tampered, "passphrase", 1000).is_err());
+        assert!(seal_backup_with_iterations(&backup, "1.0.0", "", 1000).is_err());
+    }
+
+    #[test]
+    fn test_open_backup_rejects_out_of_range_iterations() {
+        let backup = CredentialBackup {
+            pool: Vec::new(),
+            plugin_secrets: Vec::new(),
+            asr: Vec::new(),
+        };
+        let mut envelope =
+            seal_backup_with_iterations(&backup, "1.0.0", "passphrase", 1000).unwrap();
+
+        let err = open_backup(&envelope, "passphrase").unwrap_err();
+        assert!(err.contains("迭代次数无效"), "{err}");
+
+        envelope.header.kdf.iterations = u32::MAX;
+        let err = open_backup(&envelope, "passphrase").unwrap_err();
+        assert!(err.contains("迭代次数无效"), "{err}");
+    }
+
+    #[test]
+    fn test_resolve_follows_strategy() {
+        let local = openai_credential("sk-local");
+        let incoming = openai_credential("sk-incoming");
+
+        assert!(matches!(
+            resolve(
+                None,
+                incoming.clone(),
+                ConflictStrategy::Skip,
+                merge_pool_credential
+            ),
+            ImportDecision::Insert(_)
+        ));
+        assert!(matches!(
+            resolve(
+                Some(&local),
+                incoming.clone(),
+                ConflictStrategy::Skip,
+                merge_pool_credential
+            ),
+            ImportDecision::Skip
+        ));
+        match resolve(
+            Some(&local),
+            incoming,
+            ConflictStrategy::Overwrite,
+            merge_pool_credential,
+        ) {
+            ImportDecision::Overwrite(cred) => assert_eq!(api_key_of(&cred), "sk-incoming"),
+            other => panic!("unexpected decision: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_merge_pool_credential_keeps_local_stats() {
+        let mut local = openai_credential("sk-local");
+        local.usage_count = 42;
+        local.supported_models = vec!["gpt-4o".to_string()];
+
+        let mut newer = openai_credential("sk-newer");
+        newer.uuid = local.uuid.clone();
+        newer.updated_at = local.updated_at + Duration::minutes(5);
+        newer.supported_models = vec!["gpt-4.1".to_string()];
+
+        let merged = merge_pool_credential(&local, newer);
+        assert_eq!(api_key_of(&merged), "sk-newer");
+        assert_eq!(merged.usage_count, 42);
+        assert_eq!(merged.supported_models, vec!["gpt-4o", "gpt-4.1"]);
+
+        let mut older = openai_credential("sk-older");
+        older.updated_at = local.updated_at - Duration::minutes(5);
+        older.supported_models = vec!["gpt-4o".to_string(), "o3".to_string()];
+        let merged = merge_pool_credential(&local, older);
+        assert_eq!(api_key_of(&merged), "sk-local");
+        assert_eq!(merged.supported_models, vec!["gpt-4o", "o3"]);
+    }
+}
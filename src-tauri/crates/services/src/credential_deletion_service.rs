@@ -0,0 +1,299 @@
+//! 凭证两阶段删除服务
+//!
+//! 1. 登记：禁用凭证（不再参与选择）并登记宽限期，宽限期内可撤销
+//! 2. 粉碎：宽限期结束后覆盖并删除凭证行（`secure_delete`）并截断 WAL、粉碎应用凭证目录内的
+//!    Token 文件、清除审计日志中该凭证的错误信息，并保存粉碎回执（含密钥指纹，不含密钥本身）
+//!
+//! 只依赖不解密的元数据，主密钥丢失、无法解密的凭证同样可以登记和粉碎。
+
+use crate::provider_pool_service::ProviderPoolService;
+use chrono::Utc;
+use lime_core::database::dao::credential_deletion::{
+    CredentialDeletionDao, CredentialDeletionReceipt, PendingCredentialDeletion,
+};
+use lime_core::database::dao::provider_pool::ProviderPoolDao;
+use lime_core::database::dao::request_audit::RequestAuditDao;
+use lime_core::database::{lock_db, DbConnection};
+use lime_core::models::provider_pool_model::{
+    get_oauth_creds_path, CredentialData, ProviderCredential,
+};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+/// 默认宽限期（分钟）
+pub const DEFAULT_DELETION_GRACE_MINUTES: u64 = 24 * 60;
+
+/// 待粉碎凭证与最近的粉碎回执
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CredentialDeletionOverview {
+    pub pending: Vec<PendingCredentialDeletion>,
+    pub receipts: Vec<CredentialDeletionReceipt>,
+}
+
+/// 登记删除：禁用凭证并在 `grace_minutes` 后粉碎（已登记时返回原登记）
+pub fn schedule_deletion(
+    pool: &ProviderPoolService,
+    db: &DbConnection,
+    uuid: &str,
+    grace_minutes: u64,
+) -> Result<PendingCredentialDeletion, String> {
+    let conn = lock_db(db)?;
+    if let Some(pending) =
+        CredentialDeletionDao::get_pending(&conn, uuid).map_err(|e| e.to_string())?
+    {
+        return Ok(pending);
+    }
+    let meta = ProviderPoolDao::get_meta(&conn, uuid)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("凭证不存在: {uuid}"))?;
+
+    let now = Utc::now().timestamp();
+    let pending = PendingCredentialDeletion {
+        uuid: meta.uuid,
+        provider_type: meta.provider_type,
+        name: meta.name,
+        was_disabled: meta.is_disabled,
+        requested_at: now,
+        purge_after: now + (grace_minutes.min(i64::MAX as u64 / 60) as i64) * 60,
+    };
+    ProviderPoolDao::set_disabled(&conn, uuid, true).map_err(|e| e.to_string())?;
+    CredentialDeletionDao::insert_pending(&conn, &pending).map_err(|e| e.to_string())?;
+    let cred = readable_credential(&conn, uuid);
+    drop(conn);
+
+    if let Some(cred) = cred {
+        pool.notify_credential_updated(&cred);
+    }
+    tracing::info!(
+        "[凭证删除] 凭证 {} 已禁用，将于 {} 粉碎",
+        pending.uuid,
+        pending.purge_after
+    );
+    Ok(pending)
+}
+
+/// 撤销删除：恢复登记前的禁用状态
+pub fn cancel_deletion(
+    pool: &ProviderPoolService,
+    db: &DbConnection,
+    uuid: &str,
+) -> Result<ProviderCredential, String> {
+    let conn = lock_db(db)?;
+    let pending = CredentialDeletionDao::get_pending(&conn, uuid)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("凭证 {uuid} 没有待执行的删除"))?;
+    if !ProviderPoolDao::set_disabled(&conn, uuid, pending.was_disabled)
+        .map_err(|e| e.to_string())?
+    {
+        return Err(format!("凭证不存在: {uuid}"));
+    }
+    CredentialDeletionDao::cancel(&conn, uuid).map_err(|e| e.to_string())?;
+    let cred = ProviderPoolDao::get_by_uuid(&conn, uuid)
+        .map_err(|e| format!("已撤销删除，但凭证无法读取: {e}"))?
+        .ok_or_else(|| format!("凭证不存在: {uuid}"))?;
+    drop(conn);
+
+    pool.notify_credential_updated(&cred);
+    tracing::info!("[凭证删除] 已撤销凭证 {} 的删除", uuid);
+    Ok(cred)
+}
+
+/// 待粉碎凭证与最近 `receipt_limit` 条回执
+pub fn deletion_overview(
+    db: &DbConnection,
+    receipt_limit: usize,
+) -> Result<CredentialDeletionOverview, String> {
+    let conn = lock_db(db)?;
+    Ok(CredentialDeletionOverview {
+        pending: CredentialDeletionDao::list_pending(&conn).map_err(|e| e.to_string())?,
+        receipts: CredentialDeletionDao::list_receipts(&conn, receipt_limit)
+            .map_err(|e| e.to_string())?,
+    })
+}
+
+/// 立即粉碎凭证
+///
+/// `credentials_dir` 为应用凭证存储目录，只粉碎（规范化路径后）位于该目录内的 Token 文件，
+/// 用户自行指定的外部凭证文件不会被改动。凭证无法解密时无法得知 Token 文件路径，
+/// 回执中的密钥指纹为空。
+pub fn shred_credential(
+    pool: &ProviderPoolService,
+    db: &DbConnection,
+    uuid: &str,
+    credentials_dir: Option<&Path>,
+) -> Result<CredentialDeletionReceipt, String> {
+    let (meta, cred, pending) = {
+        let conn = lock_db(db)?;
+        let meta = ProviderPoolDao::get_meta(&conn, uuid)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("凭证不存在: {uuid}"))?;
+        let cred = readable_credential(&conn, uuid);
+        let pending = CredentialDeletionDao::get_pending(&conn, uuid).map_err(|e| e.to_string())?;
+        (meta, cred, pending)
+    };
+
+    // 删除凭证行（ProviderPoolDao::shred）并清理端点健康状态、发布 Removed 事件
+    pool.delete_credential(db, uuid)?;
+
+    let mut purged = vec!["credential_data".to_string(), "token_cache".to_string()];
+    let token_path = cred
+        .as_ref()
+        .and_then(|cred| get_oauth_creds_path(&cred.credential));
+    if let Some(path) = token_path {
+        let path = Path::new(&path);
+        if credentials_dir.is_some_and(|dir| is_within(path, dir)) {
+            match shred_file(path) {
+                Ok(()) => purged.push("token_file".to_string()),
+                Err(e) => {
+                    tracing::warn!("[凭证删除] 粉碎 Token 文件失败 {}: {}", path.display(), e)
+                }
+            }
+        }
+    }
+
+    let conn = lock_db(db)?;
+    ProviderPoolDao::checkpoint_wal(&conn);
+    let scrubbed =
+        RequestAuditDao::scrub_credential_errors(&conn, uuid).map_err(|e| e.to_string())?;
+    if scrubbed > 0 {
+        purged.push(format!("request_audit_log.error_message ({scrubbed})"));
+    }
+
+    let now = Utc::now().timestamp();
+    let receipt = CredentialDeletionReceipt {
+        uuid: meta.uuid,
+        provider_type: meta.provider_type,
+        name: meta.name,
+        secret_fingerprint: cred
+            .map(|cred| secret_fingerprint(&cred.credential))
+            .unwrap_or_default(),
+        requested_at: pending.map(|p| p.requested_at).unwrap_or(now),
+        shredded_at: now,
+        purged,
+    };
+    CredentialDeletionDao::record_receipt(&conn, &receipt).map_err(|e| e.to_string())?;
+    tracing::info!(
+        "[凭证删除] 凭证 {} 已粉碎，清理项: {}",
+        receipt.uuid,
+        receipt.purged.join(", ")
+    );
+    Ok(receipt)
+}
+
+/// 粉碎所有宽限期已结束的凭证，返回回执与失败信息
+pub fn shred_due_deletions(
+    pool: &ProviderPoolService,
+    db: &DbConnection,
+    credentials_dir: Option<&Path>,
+) -> Result<(Vec<CredentialDeletionReceipt>, Vec<String>), String> {
+    let due = {
+        let conn = lock_db(db)?;
+        CredentialDeletionDao::list_due(&conn, Utc::now().timestamp()).map_err(|e| e.to_string())?
+    };
+
+    let mut receipts = Vec::new();
+    let mut errors = Vec::new();
+    for pending in due {
+        match shred_credential(pool, db, &pending.uuid, credentials_dir) {
+            Ok(receipt) => receipts.push(receipt),
+            Err(e) => {
+                // 凭证已被直接删除时，只需结束登记
+                if let Ok(conn) = lock_db(db) {
+                    if matches!(ProviderPoolDao::get_meta(&conn, &pending.uuid), Ok(None)) {
+                        let _ = CredentialDeletionDao::cancel(&conn, &pending.uuid);
+                    }
+                }
+                errors.push(format!("{}: {e}", pending.uuid));
+            }
+        }
+    }
+    Ok((receipts, errors))
+}
+
+/// 读取凭证，无法解密时返回 None（只影响回执指纹与 Token 文件粉碎）
+fn readable_credential(conn: &rusqlite::Connection, uuid: &str) -> Option<ProviderCredential> {
+    ProviderPoolDao::get_by_uuid(conn, uuid)
+        .map_err(|e| {
+            tracing::warn!("[凭证删除] 凭证 {} 无法读取，只按元数据处理: {}", uuid, e);
+            e
+        })
+        .ok()
+        .flatten()
+}
+
+/// 规范化后判断文件是否位于目录内（`..`、符号链接不能绕过），文件不存在时返回 false
+fn is_within(path: &Path, dir: &Path) -> bool {
+    match (path.canonicalize(), dir.canonicalize()) {
+        (Ok(path), Ok(dir)) => path.starts_with(dir),
+        _ => false,
+    }
+}
+
+/// 以零覆盖文件内容并同步到磁盘后删除
+fn shred_file(path: &Path) -> std::io::Result<()> {
+    let len = fs::metadata(path)?.len() as usize;
+    let mut file = fs::OpenOptions::new().write(true).open(path)?;
+    file.write_all(&vec![0u8; len])?;
+    file.sync_all()?;
+    drop(file);
+    fs::remove_file(path)
+}
+
+/// 凭证数据的 SHA-256 前 16 位十六进制
+fn secret_fingerprint(credential: &CredentialData) -> String {
+    let json = serde_json::to_string(credential).unwrap_or_default();
+    Sha256::digest(json.as_bytes())
+        .iter()
+        .take(8)
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shred_file_removes_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kiro_token.json");
+        fs::write(&path, r#"{"refreshToken":"secret"}"#).unwrap();
+
+        shred_file(&path).unwrap();
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_is_within_resolves_parent_components() {
+        let root = tempfile::tempdir().unwrap();
+        let creds = root.path().join("credentials");
+        fs::create_dir(&creds).unwrap();
+        let inside = creds.join("kiro_token.json");
+        let outside = root.path().join("user_token.json");
+        fs::write(&inside, "{}").unwrap();
+        fs::write(&outside, "{}").unwrap();
+
+        assert!(is_within(&inside, &creds));
+        assert!(!is_within(&creds.join("../user_token.json"), &creds));
+        assert!(!is_within(&outside, &creds));
+        assert!(!is_within(&creds.join("missing.json"), &creds));
+    }
+
+    #[test]
+    fn test_secret_fingerprint_is_stable_and_short() {
+        let key = CredentialData::OpenAIKey {
+            api_key: "sk-test".to_string(),
+            base_url: None,
+        };
+        let other = CredentialData::OpenAIKey {
+            api_key: "sk-other".to_string(),
+            base_url: None,
+        };
+        assert_eq!(secret_fingerprint(&key), secret_fingerprint(&key));
+        assert_ne!(secret_fingerprint(&key), secret_fingerprint(&other));
+        assert_eq!(secret_fingerprint(&key).len(), 16);
+        assert!(!secret_fingerprint(&key).contains("sk-test"));
+    }
+}
@@ -0,0 +1,386 @@
+//! 凭证端点健康固定
+//!
+//! 第三方中转的 base_url 经常不可用。对配置了自定义 base_url 的凭证，独立于凭证有效性
+//! 检测各 base_url 的可达性：主 base_url 不可达时固定到第一个可达的备用 base_url，
+//! 主 base_url 恢复后自动切回。状态只保存在内存中，重启后重新检测。
+
+use chrono::Utc;
+use lime_core::models::provider_pool_model::{
+    CredentialData, CredentialEndpointHealth, EndpointStatus, ProviderCredential,
+};
+use reqwest::Client;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// 端点可达性检测超时
+pub const ENDPOINT_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 单次端点检测结果：Ok 为耗时（毫秒），Err 为失败原因
+pub type EndpointProbe = Result<u64, String>;
+
+/// 凭证的候选 base_url（主 base_url 在前，备用按配置顺序，已去重）
+///
+/// 未配置自定义 base_url 的凭证返回空列表，不参与端点固定。
+pub fn candidate_base_urls(cred: &ProviderCredential) -> Vec<String> {
+    let Some(primary) = cred.credential.base_url().filter(|url| !url.is_empty()) else {
+        return Vec::new();
+    };
+    let mut urls = vec![primary.to_string()];
+    for url in &cred.fallback_base_urls {
+        if !urls.contains(url) {
+            urls.push(url.clone());
+        }
+    }
+    urls
+}
+
+/// 规范化备用 base_url 列表：去除空白与重复项，并校验协议
+pub fn normalize_fallback_base_urls(
+    credential: &CredentialData,
+    urls: Vec<String>,
+) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for url in urls {
+        let url = url.trim().trim_end_matches('/').to_string();
+        if url.is_empty() || normalized.contains(&url) {
+            continue;
+        }
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!(
+                "备用 Base URL 必须以 http:// 或 https:// 开头: {url}"
+            ));
+        }
+        normalized.push(url);
+    }
+    if !normalized.is_empty() && credential.base_url().is_none() {
+        return Err("只有配置了自定义 Base URL 的 API Key 凭证支持备用 Base URL".to_string());
+    }
+    Ok(normalized)
+}
+
+/// HTTP 状态码是否表示端点可达
+///
+/// 任何 HTTP 响应都说明中转服务在线（401/404 等属于凭证或路径问题），
+/// 只有网关类错误视为端点不可用。
+pub fn is_reachable_status(status: u16) -> bool {
+    !matches!(status, 502..=504)
+}
+
+/// 单个凭证的端点状态
+#[derive(Debug, Clone)]
+struct EndpointPin {
+    active: String,
+    endpoints: Vec<EndpointStatus>,
+}
+
+impl EndpointPin {
+    /// 按优先级选择第一个可达端点；全部不可达时保持当前端点
+    fn repin(&mut self) {
+        if let Some(endpoint) = self.endpoints.iter().find(|e| e.reachable) {
+            self.active = endpoint.url.clone();
+        }
+    }
+
+    fn to_health(&self) -> CredentialEndpointHealth {
+        CredentialEndpointHealth {
+            active_base_url: self.active.clone(),
+            failed_over: self
+                .endpoints
+                .first()
+                .is_some_and(|primary| primary.url != self.active),
+            endpoints: self.endpoints.clone(),
+        }
+    }
+}
+
+/// 端点健康注册表（凭证 UUID -> 端点状态）
+#[derive(Debug, Default)]
+pub struct EndpointHealthRegistry {
+    pins: RwLock<HashMap<String, EndpointPin>>,
+}
+
+impl EndpointHealthRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一轮检测结果并重新选择固定端点
+    ///
+    /// `probes` 与 `candidates` 一一对应。候选列表变化（如用户修改了 base_url）时，
+    /// 已删除端点的状态随之丢弃，避免固定到不再配置的端点。
+    pub fn record(&self, uuid: &str, candidates: &[String], probes: Vec<EndpointProbe>) {
+        let Some(primary) = candidates.first() else {
+            self.remove(uuid);
+            return;
+        };
+        let now = Utc::now().to_rfc3339();
+        let mut pins = self.pins.write().unwrap();
+        let pin = pins.entry(uuid.to_string()).or_insert_with(|| EndpointPin {
+            active: primary.clone(),
+            endpoints: Vec::new(),
+        });
+        let previous = std::mem::take(&mut pin.endpoints);
+        if !candidates.contains(&pin.active) {
+            pin.active = primary.clone();
+        }
+
+        for (url, probe) in candidates.iter().zip(probes) {
+            let failures = previous
+                .iter()
+                .find(|e| &e.url == url)
+                .map(|e| e.consecutive_failures)
+                .unwrap_or(0);
+            let status = match probe {
+                Ok(latency_ms) => EndpointStatus {
+                    url: url.clone(),
+                    reachable: true,
+                    latency_ms: Some(latency_ms),
+                    last_checked: Some(now.clone()),
+                    last_error: None,
+                    consecutive_failures: 0,
+                },
+                Err(error) => EndpointStatus {
+                    url: url.clone(),
+                    reachable: false,
+                    latency_ms: None,
+                    last_checked: Some(now.clone()),
+                    last_error: Some(error),
+                    consecutive_failures: failures + 1,
+                },
+            };
+            pin.endpoints.push(status);
+        }
+
+        let before = pin.active.clone();
+        pin.repin();
+        if pin.active != before {
+            tracing::warn!(
+                "[端点健康] 凭证 {} 的 base_url 由 {} 切换到 {}",
+                uuid,
+                before,
+                pin.active
+            );
+        }
+    }
+
+    /// 当前固定的 base_url（未检测过时为 None）
+    pub fn pinned_base_url(&self, uuid: &str) -> Option<String> {
+        self.pins
+            .read()
+            .unwrap()
+            .get(uuid)
+            .map(|pin| pin.active.clone())
+    }
+
+    /// 将固定的 base_url 应用到凭证副本，供实际请求使用
+    pub fn apply(&self, cred: &mut ProviderCredential) {
+        let Some(pinned) = self.pinned_base_url(&cred.uuid) else {
+            return;
+        };
+        if !candidate_base_urls(cred).contains(&pinned) {
+            return;
+        }
+        if cred.credential.base_url() != Some(pinned.as_str()) {
+            cred.credential.set_base_url(pinned);
+        }
+    }
+
+    /// 获取凭证的端点健康状态
+    pub fn get(&self, uuid: &str) -> Option<CredentialEndpointHealth> {
+        self.pins
+            .read()
+            .unwrap()
+            .get(uuid)
+            .map(EndpointPin::to_health)
+    }
+
+    /// 清除凭证的端点状态（凭证删除或不再使用自定义 base_url 时）
+    pub fn remove(&self, uuid: &str) {
+        self.pins.write().unwrap().remove(uuid);
+    }
+
+    /// 检测凭证所有候选 base_url 的可达性并更新固定端点
+    pub async fn check(
+        &self,
+        client: &Client,
+        cred: &ProviderCredential,
+    ) -> Option<CredentialEndpointHealth> {
+        let candidates = candidate_base_urls(cred);
+        if candidates.is_empty() {
+            self.remove(&cred.uuid);
+            return None;
+        }
+
+        let client = match cred.proxy_url.as_deref().filter(|p| !p.is_empty()) {
+            Some(proxy_url) => match build_proxy_client(proxy_url) {
+                Ok(client) => client,
+                Err(e) => {
+                    let probes = candidates.iter().map(|_| Err(e.clone())).collect();
+                    self.record(&cred.uuid, &candidates, probes);
+                    return self.get(&cred.uuid);
+                }
+            },
+            None => client.clone(),
+        };
+
+        let probes = futures::future::join_all(
+            candidates
+                .iter()
+                .map(|url| probe_endpoint(&client, url.as_str())),
+        )
+        .await;
+        self.record(&cred.uuid, &candidates, probes);
+        self.get(&cred.uuid)
+    }
+}
+
+/// 检测单个 base_url 是否可达
+async fn probe_endpoint(client: &Client, url: &str) -> EndpointProbe {
+    let start = Instant::now();
+    match client.get(url).timeout(ENDPOINT_CHECK_TIMEOUT).send().await {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            if is_reachable_status(status) {
+                Ok(start.elapsed().as_millis() as u64)
+            } else {
+                Err(format!("HTTP {status}"))
+            }
+        }
+        Err(e) if e.is_timeout() => {
+            Err(format!("连接超时（{}s）", ENDPOINT_CHECK_TIMEOUT.as_secs()))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn build_proxy_client(proxy_url: &str) -> Result<Client, String> {
+    let proxy = reqwest::Proxy::all(proxy_url).map_err(|e| format!("无效的代理地址: {e}"))?;
+    Client::builder()
+        .proxy(proxy)
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::models::provider_pool_model::PoolProviderType;
+
+    fn relay_credential() -> ProviderCredential {
+        let mut cred = ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: "sk-test".to_string(),
+                base_url: Some("https://relay-a.example.com/v1".to_string()),
+            },
+        );
+        cred.fallback_base_urls = vec![
+            "https://relay-b.example.com/v1".to_string(),
+            "https://relay-c.example.com/v1".to_string(),
+        ];
+        cred
+    }
+
+    #[test]
+    fn test_failover_and_recovery() {
+        let registry = EndpointHealthRegistry::new();
+        let mut cred = relay_credential();
+        let candidates = candidate_base_urls(&cred);
+        assert_eq!(candidates.len(), 3);
+
+        // 主端点与第一个备用不可达 -> 固定到第二个备用
+        registry.record(
+            &cred.uuid,
+            &candidates,
+            vec![
+                Err("HTTP 502".to_string()),
+                Err("timeout".to_string()),
+                Ok(80),
+            ],
+        );
+        let health = registry.get(&cred.uuid).unwrap();
+        assert!(health.failed_over);
+        assert_eq!(health.active_base_url, "https://relay-c.example.com/v1");
+        assert_eq!(health.endpoints[0].consecutive_failures, 1);
+
+        let mut routed = cred.clone();
+        registry.apply(&mut routed);
+        assert_eq!(
+            routed.credential.base_url(),
+            Some("https://relay-c.example.com/v1")
+        );
+
+        // 全部不可达时保持当前端点，失败次数累加
+        registry.record(
+            &cred.uuid,
+            &candidates,
+            vec![
+                Err("x".to_string()),
+                Err("x".to_string()),
+                Err("x".to_string()),
+            ],
+        );
+        let health = registry.get(&cred.uuid).unwrap();
+        assert_eq!(health.active_base_url, "https://relay-c.example.com/v1");
+        assert_eq!(health.endpoints[0].consecutive_failures, 2);
+
+        // 备用列表被修改后旧的固定端点不再生效
+        let mut edited = cred.clone();
+        edited.fallback_base_urls.clear();
+        registry.apply(&mut edited);
+        assert_eq!(
+            edited.credential.base_url(),
+            Some("https://relay-a.example.com/v1")
+        );
+
+        // 主端点恢复 -> 切回
+        registry.record(
+            &cred.uuid,
+            &candidates,
+            vec![Ok(40), Err("x".to_string()), Ok(80)],
+        );
+        let health = registry.get(&cred.uuid).unwrap();
+        assert!(!health.failed_over);
+        assert_eq!(health.endpoints[0].consecutive_failures, 0);
+        registry.apply(&mut cred);
+        assert_eq!(
+            cred.credential.base_url(),
+            Some("https://relay-a.example.com/v1")
+        );
+    }
+
+    #[test]
+    fn test_normalize_fallback_base_urls() {
+        let cred = relay_credential();
+        let urls = normalize_fallback_base_urls(
+            &cred.credential,
+            vec![
+                " https://relay-b.example.com/v1/ ".to_string(),
+                "https://relay-b.example.com/v1".to_string(),
+                String::new(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(urls, vec!["https://relay-b.example.com/v1".to_string()]);
+        assert!(normalize_fallback_base_urls(
+            &cred.credential,
+            vec!["relay-b.example.com".to_string()]
+        )
+        .is_err());
+
+        let oauth = CredentialData::KiroOAuth {
+            creds_file_path: "/tmp/creds.json".to_string(),
+        };
+        assert!(normalize_fallback_base_urls(&oauth, vec![])
+            .unwrap()
+            .is_empty());
+        assert!(normalize_fallback_base_urls(
+            &oauth,
+            vec!["https://relay-b.example.com".to_string()]
+        )
+        .is_err());
+        assert!(is_reachable_status(404));
+        assert!(!is_reachable_status(503));
+    }
+}
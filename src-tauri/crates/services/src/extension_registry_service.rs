@@ -0,0 +1,556 @@
+//! 扩展注册表服务
+//!
+//! 从自托管的索引 JSON 发现插件与 Skill，替代硬编码的 GitHub 查询：
+//! - 索引与包地址必须为 HTTPS
+//! - 索引签名为同地址的 `.minisig` 文件，按注册表配置的公钥与信任策略校验
+//! - 包按索引中的 sha256 校验，签名链覆盖到最终下载内容
+
+use anyhow::{anyhow, Context, Result};
+use minisign_verify::{PublicKey, Signature};
+use parking_lot::RwLock;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use lime_core::config::{ExtensionRegistryConfig, ExtensionRegistrySettings, RegistryTrustPolicy};
+
+const REGISTRY_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+const REGISTRY_INDEX_CACHE_TTL: Duration = Duration::from_secs(300);
+
+/// 索引签名文件后缀
+pub const INDEX_SIGNATURE_SUFFIX: &str = ".minisig";
+
+/// 注册表索引（JSON 根对象）
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RegistryIndex {
+    #[serde(default = "default_index_version")]
+    pub version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub plugins: Vec<RegistryPluginEntry>,
+    #[serde(default)]
+    pub skills: Vec<RegistrySkillEntry>,
+}
+
+fn default_index_version() -> u32 {
+    1
+}
+
+/// 索引中的插件条目
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RegistryPluginEntry {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    /// 插件包地址（zip / tar.gz）
+    pub download_url: String,
+    /// 插件包 sha256（十六进制）
+    pub sha256: String,
+}
+
+/// 索引中的 Skill 条目
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RegistrySkillEntry {
+    /// 安装目录名（同时是包内 Skill 目录名）
+    pub directory: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Skill 包地址（zip，包含 `<directory>/SKILL.md`）
+    pub download_url: String,
+    /// Skill 包 sha256（十六进制）
+    pub sha256: String,
+}
+
+/// 带来源注册表的条目
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryListing<T> {
+    pub registry_id: String,
+    pub registry_name: String,
+    #[serde(flatten)]
+    pub entry: T,
+}
+
+/// 注册表连通性检查结果
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryStatus {
+    pub registry_id: String,
+    pub ok: bool,
+    /// 索引是否通过签名校验
+    pub verified: bool,
+    pub plugin_count: usize,
+    pub skill_count: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct CachedIndex {
+    index_url: String,
+    index: RegistryIndex,
+    verified: bool,
+    fetched_at: Instant,
+}
+
+pub struct ExtensionRegistryService {
+    client: Client,
+    settings: RwLock<ExtensionRegistrySettings>,
+    index_cache: RwLock<HashMap<String, CachedIndex>>,
+}
+
+impl ExtensionRegistryService {
+    pub fn new(settings: ExtensionRegistrySettings) -> Result<Self> {
+        let client = Client::builder()
+            .timeout(REGISTRY_REQUEST_TIMEOUT)
+            .https_only(true)
+            .build()
+            .context("Failed to create HTTP client")?;
+
+        Ok(Self {
+            client,
+            settings: RwLock::new(settings),
+            index_cache: RwLock::new(HashMap::new()),
+        })
+    }
+
+    pub fn settings(&self) -> ExtensionRegistrySettings {
+        self.settings.read().clone()
+    }
+
+    /// 更新配置并清空索引缓存
+    pub fn update_settings(&self, settings: ExtensionRegistrySettings) {
+        *self.settings.write() = settings;
+        self.refresh_cache();
+    }
+
+    pub fn refresh_cache(&self) {
+        self.index_cache.write().clear();
+    }
+
+    /// 是否仍查询内置的 GitHub Skill 仓库
+    pub fn github_sources_enabled(&self) -> bool {
+        !self.settings.read().disable_github_sources
+    }
+
+    fn enabled_registries(&self) -> Vec<ExtensionRegistryConfig> {
+        self.settings
+            .read()
+            .registries
+            .iter()
+            .filter(|registry| registry.enabled)
+            .cloned()
+            .collect()
+    }
+
+    fn find_registry(&self, registry_id: &str) -> Result<ExtensionRegistryConfig> {
+        self.enabled_registries()
+            .into_iter()
+            .find(|registry| registry.id == registry_id)
+            .ok_or_else(|| anyhow!("扩展注册表不存在或已停用: {registry_id}"))
+    }
+
+    /// 获取注册表索引（带缓存），返回索引与是否通过签名校验
+    async fn load_index(
+        &self,
+        registry: &ExtensionRegistryConfig,
+    ) -> Result<(RegistryIndex, bool)> {
+        if let Some(cached) = self.index_cache.read().get(&registry.id) {
+            if cached.index_url == registry.index_url
+                && cached.fetched_at.elapsed() < REGISTRY_INDEX_CACHE_TTL
+            {
+                return Ok((cached.index.clone(), cached.verified));
+            }
+        }
+
+        let (index, verified) = self.fetch_index(registry).await?;
+        self.index_cache.write().insert(
+            registry.id.clone(),
+            CachedIndex {
+                index_url: registry.index_url.clone(),
+                index: index.clone(),
+                verified,
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok((index, verified))
+    }
+
+    /// 下载并校验注册表索引
+    pub async fn fetch_index(
+        &self,
+        registry: &ExtensionRegistryConfig,
+    ) -> Result<(RegistryIndex, bool)> {
+        validate_registry_url(&registry.index_url)?;
+        let index_bytes = self.get_bytes(&registry.index_url).await?;
+
+        let verified = match registry.trust {
+            RegistryTrustPolicy::Signed => {
+                let public_key = registry
+                    .public_key
+                    .as_deref()
+                    .filter(|key| !key.trim().is_empty())
+                    .ok_or_else(|| anyhow!("注册表 {} 要求签名但未配置公钥", registry.id))?;
+                let signature_url = format!("{}{}", registry.index_url, INDEX_SIGNATURE_SUFFIX);
+                let signature = self.get_bytes(&signature_url).await?;
+                let signature = String::from_utf8(signature)
+                    .map_err(|_| anyhow!("注册表 {} 的签名文件不是文本", registry.id))?;
+                verify_index_signature(&index_bytes, &signature, public_key)
+                    .with_context(|| format!("注册表 {} 索引签名校验失败", registry.id))?;
+                true
+            }
+            RegistryTrustPolicy::Unsigned => {
+                tracing::warn!(
+                    "[ExtensionRegistry] 注册表 {} 配置为接受未签名索引",
+                    registry.id
+                );
+                false
+            }
+        };
+
+        let index = parse_registry_index(&index_bytes)
+            .with_context(|| format!("注册表 {} 索引无效", registry.id))?;
+        Ok((index, verified))
+    }
+
+    /// 列出所有启用注册表中的插件（不可用的注册表跳过）
+    pub async fn list_plugins(&self) -> Vec<RegistryListing<RegistryPluginEntry>> {
+        let mut listings: Vec<RegistryListing<RegistryPluginEntry>> = Vec::new();
+        for registry in self.enabled_registries() {
+            match self.load_index(&registry).await {
+                Ok((index, _)) => {
+                    for entry in index.plugins {
+                        if listings.iter().any(|listing| listing.entry.id == entry.id) {
+                            continue;
+                        }
+                        listings.push(RegistryListing {
+                            registry_id: registry.id.clone(),
+                            registry_name: registry_display_name(&registry),
+                            entry,
+                        });
+                    }
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        "[ExtensionRegistry] 注册表 {} 暂时不可用，已跳过: {:#}",
+                        registry.id,
+                        error
+                    );
+                }
+            }
+        }
+        listings
+    }
+
+    /// 列出所有启用注册表中的 Skill（不可用的注册表跳过）
+    pub async fn list_skills(&self) -> Vec<RegistryListing<RegistrySkillEntry>> {
+        let mut listings: Vec<RegistryListing<RegistrySkillEntry>> = Vec::new();
+        for registry in self.enabled_registries() {
+            match self.load_index(&registry).await {
+                Ok((index, _)) => {
+                    for entry in index.skills {
+                        if listings
+                            .iter()
+                            .any(|listing| listing.entry.directory == entry.directory)
+                        {
+                            continue;
+                        }
+                        listings.push(RegistryListing {
+                            registry_id: registry.id.clone(),
+                            registry_name: registry_display_name(&registry),
+                            entry,
+                        });
+                    }
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        "[ExtensionRegistry] 注册表 {} 暂时不可用，已跳过: {:#}",
+                        registry.id,
+                        error
+                    );
+                }
+            }
+        }
+        listings
+    }
+
+    pub async fn find_plugin(
+        &self,
+        registry_id: &str,
+        plugin_id: &str,
+    ) -> Result<RegistryPluginEntry> {
+        let registry = self.find_registry(registry_id)?;
+        let (index, _) = self.load_index(&registry).await?;
+        index
+            .plugins
+            .into_iter()
+            .find(|entry| entry.id == plugin_id)
+            .ok_or_else(|| anyhow!("注册表 {registry_id} 中不存在插件: {plugin_id}"))
+    }
+
+    pub async fn find_skill(
+        &self,
+        registry_id: &str,
+        directory: &str,
+    ) -> Result<RegistrySkillEntry> {
+        let registry = self.find_registry(registry_id)?;
+        let (index, _) = self.load_index(&registry).await?;
+        index
+            .skills
+            .into_iter()
+            .find(|entry| entry.directory == directory)
+            .ok_or_else(|| anyhow!("注册表 {registry_id} 中不存在 Skill: {directory}"))
+    }
+
+    /// 检查所有已配置注册表的连通性与签名状态（不使用缓存）
+    pub async fn check_registries(&self) -> Vec<RegistryStatus> {
+        let registries = self.settings.read().registries.clone();
+        let mut statuses = Vec::with_capacity(registries.len());
+        for registry in registries {
+            let status = match self.fetch_index(&registry).await {
+                Ok((index, verified)) => RegistryStatus {
+                    registry_id: registry.id.clone(),
+                    ok: true,
+                    verified,
+                    plugin_count: index.plugins.len(),
+                    skill_count: index.skills.len(),
+                    error: None,
+                },
+                Err(error) => RegistryStatus {
+                    registry_id: registry.id.clone(),
+                    ok: false,
+                    verified: false,
+                    plugin_count: 0,
+                    skill_count: 0,
+                    error: Some(format!("{error:#}")),
+                },
+            };
+            statuses.push(status);
+        }
+        statuses
+    }
+
+    /// 下载包并按 sha256 校验
+    pub async fn download_package(&self, url: &str, sha256: &str) -> Result<Vec<u8>> {
+        validate_registry_url(url)?;
+        let bytes = self.get_bytes(url).await?;
+        verify_sha256(&bytes, sha256)?;
+        Ok(bytes)
+    }
+
+    async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .with_context(|| format!("请求失败: {url}"))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("HTTP {}: {url}", response.status()));
+        }
+        Ok(response
+            .bytes()
+            .await
+            .with_context(|| format!("读取响应失败: {url}"))?
+            .to_vec())
+    }
+}
+
+fn registry_display_name(registry: &ExtensionRegistryConfig) -> String {
+    if registry.name.trim().is_empty() {
+        registry.id.clone()
+    } else {
+        registry.name.clone()
+    }
+}
+
+/// 注册表与包地址必须为 HTTPS
+pub fn validate_registry_url(url: &str) -> Result<()> {
+    let parsed = url::Url::parse(url).with_context(|| format!("无效的地址: {url}"))?;
+    if parsed.scheme() != "https" {
+        return Err(anyhow!("注册表地址必须使用 HTTPS: {url}"));
+    }
+    Ok(())
+}
+
+/// 校验注册表配置：ID 唯一、地址为 HTTPS、要求签名时公钥有效
+pub fn validate_registry_settings(settings: &ExtensionRegistrySettings) -> Result<()> {
+    let mut ids = std::collections::HashSet::new();
+    for registry in &settings.registries {
+        let id = registry.id.trim();
+        if id.is_empty() {
+            return Err(anyhow!("注册表 ID 不能为空"));
+        }
+        if !ids.insert(id) {
+            return Err(anyhow!("注册表 ID 重复: {id}"));
+        }
+        validate_registry_url(&registry.index_url)?;
+        match (registry.trust, registry.public_key.as_deref()) {
+            (_, Some(key)) if !key.trim().is_empty() => {
+                PublicKey::from_base64(key.trim())
+                    .map_err(|e| anyhow!("注册表 {id} 的公钥无效: {e}"))?;
+            }
+            (RegistryTrustPolicy::Signed, _) => {
+                return Err(anyhow!("注册表 {id} 要求签名但未配置公钥"));
+            }
+            (RegistryTrustPolicy::Unsigned, _) => {}
+        }
+    }
+    Ok(())
+}
+
+/// 使用 minisign 公钥校验索引签名
+pub fn verify_index_signature(index: &[u8], signature: &str, public_key: &str) -> Result<()> {
+    let public_key =
+        PublicKey::from_base64(public_key.trim()).map_err(|e| anyhow!("公钥无效: {e}"))?;
+    let signature = Signature::decode(signature).map_err(|e| anyhow!("签名无效: {e}"))?;
+    public_key
+        .verify(index, &signature, false)
+        .map_err(|e| anyhow!("签名不匹配: {e}"))
+}
+
+/// 校验内容的 sha256
+pub fn verify_sha256(bytes: &[u8], expected: &str) -> Result<()> {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if actual != expected.trim().to_ascii_lowercase() {
+        return Err(anyhow!("校验和不匹配: 期望 {expected}, 实际 {actual}"));
+    }
+    Ok(())
+}
+
+/// 解析索引并校验条目
+pub fn parse_registry_index(bytes: &[u8]) -> Result<RegistryIndex> {
+    let index: RegistryIndex = serde_json::from_slice(bytes).context("索引 JSON 解析失败")?;
+    for entry in &index.plugins {
+        validate_registry_url(&entry.download_url)
+            .with_context(|| format!("插件 {} 的下载地址无效", entry.id))?;
+    }
+    for entry in &index.skills {
+        let directory = entry.directory.as_str();
+        if directory.is_empty() || directory.starts_with('.') || directory.contains(['/', '\\']) {
+            return Err(anyhow!("Skill 目录名无效: {directory}"));
+        }
+        validate_registry_url(&entry.download_url)
+            .with_context(|| format!("Skill {directory} 的下载地址无效"))?;
+    }
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_json(download_url: &str) -> String {
+        serde_json::json!({
+            "version": 1,
+            "plugins": [{
+                "id": "machine-id",
+                "name": "Machine ID",
+                "version": "1.0.0",
+                "download_url": download_url,
+                "sha256": "00"
+            }],
+            "skills": [{
+                "directory": "code-review",
+                "name": "Code Review",
+                "download_url": "https://registry.example.com/skills/code-review.zip",
+                "sha256": "00"
+            }]
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_parse_registry_index_requires_https() {
+        let index =
+            parse_registry_index(index_json("https://registry.example.com/p.zip").as_bytes())
+                .unwrap();
+        assert_eq!(index.plugins[0].id, "machine-id");
+        assert_eq!(index.skills[0].directory, "code-review");
+
+        assert!(
+            parse_registry_index(index_json("http://registry.example.com/p.zip").as_bytes())
+                .is_err()
+        );
+        assert!(validate_registry_url("ftp://registry.example.com/index.json").is_err());
+    }
+
+    #[test]
+    fn test_validate_registry_settings() {
+        let registry = ExtensionRegistryConfig {
+            id: "internal".to_string(),
+            name: "Internal".to_string(),
+            index_url: "https://registry.example.com/index.json".to_string(),
+            enabled: true,
+            public_key: None,
+            trust: RegistryTrustPolicy::Unsigned,
+        };
+        let mut settings = ExtensionRegistrySettings {
+            registries: vec![registry.clone()],
+            disable_github_sources: true,
+        };
+        assert!(validate_registry_settings(&settings).is_ok());
+
+        // 要求签名但未配置公钥
+        settings.registries[0].trust = RegistryTrustPolicy::Signed;
+        assert!(validate_registry_settings(&settings).is_err());
+
+        // ID 重复
+        settings.registries = vec![registry.clone(), registry];
+        assert!(validate_registry_settings(&settings).is_err());
+    }
+
+    #[test]
+    fn test_verify_sha256() {
+        let digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert!(verify_sha256(b"hello", digest).is_ok());
+        assert!(verify_sha256(b"hello", &digest.to_uppercase()).is_ok());
+        assert!(verify_sha256(b"hello!", digest).is_err());
+    }
+
+    #[test]
+    fn test_verify_index_signature_accepts_valid_signature() {
+        let public_key = "RWQBAgMEBQYHCAOhB7/zzhC+HXDdGOdLwJln5NYwm6UNXx3chmQSVTG4";
+        let signature = "untrusted comment: signature from minisign secret key\n\
+            RUQBAgMEBQYHCEHEckUN9fqYksP/+r/1FAqSNg8mLJ3Wf34IngYsd8GUbGM1BSZV8bniL5L3R9Rm500CvR34bbtDKM6TCI3puQc=\n\
+            trusted comment: timestamp:1700000000\tfile:index.json\thashed\n\
+            mRyD96pEHzZqsOsGmtEA3NFSmGO6kqleW06ndH65a8QQK6stShP5o7wzVu9JAszEZ+CXpgewFeEJrCVS180sAA==\n";
+
+        assert!(verify_index_signature(br#"{"version":1}"#, signature, public_key).is_ok());
+        // 内容被篡改后签名不再匹配
+        assert!(verify_index_signature(br#"{"version":2}"#, signature, public_key).is_err());
+    }
+
+    #[test]
+    fn test_verify_index_signature_rejects_garbage() {
+        assert!(verify_index_signature(b"{}", "not a signature", "not a key").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_index_rejects_unknown_or_insecure_registry() {
+        let service = ExtensionRegistryService::new(ExtensionRegistrySettings::default()).unwrap();
+        let registry = ExtensionRegistryConfig {
+            id: "internal".to_string(),
+            name: String::new(),
+            index_url: "http://registry.example.com/index.json".to_string(),
+            enabled: true,
+            public_key: None,
+            trust: RegistryTrustPolicy::Signed,
+        };
+        // 非 HTTPS 地址在发起请求前即被拒绝
+        assert!(service.fetch_index(&registry).await.is_err());
+        assert!(service.find_plugin("internal", "machine-id").await.is_err());
+    }
+}
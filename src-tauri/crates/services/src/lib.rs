@@ -22,6 +22,7 @@
 //! - `voice_asr_service` - ASR 识别服务
 //! - `voice_command_service` - 语音命令业务服务
 //! - `voice_recording_service` - 录音状态与设备服务
+//! - `voice_tts_service` - 语音合成（朗读）服务
 //! - `screenshot_capture_service` - 跨平台截图服务
 //! - `screenshot_image_service` - 截图图片编码服务
 //! - `machine_id_service` - 机器 ID 服务
@@ -29,13 +30,18 @@
 //! - `mcp_sync` - MCP 同步
 //! - `prompt_sync` - Prompt 同步
 //! - `skill_service` - 技能服务
+//! - `extension_registry_service` - 扩展注册表服务（自托管插件 / Skill 索引）
 //! - `backup_service` - 备份服务
+//! - `device_sync_service` - 设备间加密同步（同步文件夹 / WebDAV / S3）
+//! - `credential_backup_service` - 凭证加密备份与导入（冲突处理：跳过 / 覆盖 / 合并）
+//! - `credential_deletion_service` - 凭证两阶段删除（宽限期撤销 + 密钥粉碎与回执）
 //! - `material_service` - 素材服务
 //! - `persona_service` - 人设服务
 //! - `template_service` - 模板服务
 //! - `model_registry_service` - 模型注册服务
 //! - `model_service` - 模型服务
 //! - `prompt_service` - Prompt 服务
+//! - `prompt_library_service` - Prompt 库服务（版本、变量与按 ID 引用）
 //! - `mcp_service` - MCP 服务
 //! - `switch` - Provider 切换
 //! - `aster_session_store` - Aster 会话存储
@@ -46,6 +52,8 @@
 //! - `kiro_event_service` - Kiro 事件服务
 //! - `api_key_provider_service` - API Key Provider 服务
 //! - `provider_pool_service` - Provider 池服务
+//! - `endpoint_health` - 凭证 base_url 端点健康固定
+//! - `adaptive_selection` - 按请求结果学习的自适应凭证选择（ε-greedy）
 //! - `token_cache_service` - Token 缓存服务
 
 // 无外部依赖的服务
@@ -62,8 +70,11 @@ pub mod voice_config_service;
 pub mod voice_output_service;
 pub mod voice_processor_service;
 pub mod voice_recording_service;
+pub mod voice_tts_service;
 
 // 依赖 models 的服务
+pub mod endpoint_health;
+pub mod extension_registry_service;
 pub mod live_sync;
 pub mod machine_id_service;
 pub mod mcp_sync;
@@ -71,13 +82,19 @@ pub mod prompt_sync;
 pub mod skill_service;
 
 // 依赖 database + models 的服务
+pub mod adaptive_selection;
 pub mod aster_session_store;
 pub mod backup_service;
+pub mod chat_export_import_service;
+pub mod credential_backup_service;
+pub mod credential_deletion_service;
+pub mod device_sync_service;
 pub mod material_service;
 pub mod mcp_service;
 pub mod model_registry_service;
 pub mod model_service;
 pub mod persona_service;
+pub mod prompt_library_service;
 pub mod prompt_service;
 pub mod switch;
 pub mod template_service;
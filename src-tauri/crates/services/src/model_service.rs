@@ -116,6 +116,9 @@ impl ModelService {
                 // Vertex AI 使用固定的模型列表
                 Ok(self.get_default_models_for_provider(&credential.provider_type))
             }
+            CredentialData::Mock { .. } => {
+                Ok(self.get_default_models_for_provider(&credential.provider_type))
+            }
         }
     }
 
@@ -388,6 +391,7 @@ impl ModelService {
             PoolProviderType::GeminiApiKey => {
                 vec!["gemini-2.5-flash".to_string(), "gemini-2.5-pro".to_string()]
             }
+            PoolProviderType::Mock => vec!["mock-model".to_string()],
             _ => vec![],
         }
     }
@@ -0,0 +1,460 @@
+//! Prompt 库服务
+//!
+//! 在 [`PromptLibraryDao`] 之上提供：
+//! - 变量：内容中的 `{{name}}` 占位符，渲染时按传入值或默认值替换
+//! - 版本：保存时内容变化自动生成新版本，支持任意两个版本的逐行 diff 与回滚
+//! - 引用：对话系统提示词、Agent 自定义指令与 Skill 内容中的 `{{prompt:<id>}}`
+//!   （或 `{{prompt:<id>@<version>}}` 固定版本）在使用前展开为库中 Prompt 的内容
+
+use lime_core::database::dao::prompt_library::{
+    PromptLibraryDao, PromptLibraryEntry, PromptLibraryQuery, PromptLibraryVersion, PromptVariable,
+};
+use lime_core::database::DbConnection;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// 变量占位符 `{{name}}`
+static VARIABLE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap());
+
+/// 库 Prompt 引用 `{{prompt:<id>}}` / `{{prompt:<id>@<version>}}`
+static REFERENCE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\{\{\s*prompt:([A-Za-z0-9_.\-]+)(?:@(\d+))?\s*\}\}").unwrap());
+
+/// 引用展开的最大嵌套层数
+const MAX_REFERENCE_DEPTH: usize = 3;
+
+/// diff 行类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptDiffKind {
+    Equal,
+    Added,
+    Removed,
+}
+
+/// 版本 diff 中的一行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptDiffLine {
+    pub kind: PromptDiffKind,
+    pub text: String,
+}
+
+/// 提取内容中的变量名（按首次出现顺序去重）
+pub fn extract_variables(content: &str) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    VARIABLE_RE
+        .captures_iter(content)
+        .map(|caps| caps[1].to_string())
+        .filter(|name| seen.insert(name.clone()))
+        .collect()
+}
+
+/// 渲染变量：优先使用传入值，其次使用默认值，两者都没有时报错
+pub fn render_prompt(
+    content: &str,
+    variables: &[PromptVariable],
+    values: &HashMap<String, String>,
+) -> Result<String, String> {
+    let mut missing = Vec::new();
+    let rendered = VARIABLE_RE.replace_all(content, |caps: &Captures| {
+        let name = &caps[1];
+        let value = values.get(name).cloned().or_else(|| {
+            variables
+                .iter()
+                .find(|v| v.name == name)
+                .and_then(|v| v.default_value.clone())
+        });
+        value.unwrap_or_else(|| {
+            if !missing.iter().any(|m| m == name) {
+                missing.push(name.to_string());
+            }
+            caps[0].to_string()
+        })
+    });
+    if missing.is_empty() {
+        Ok(rendered.into_owned())
+    } else {
+        Err(format!("缺少变量: {}", missing.join(", ")))
+    }
+}
+
+/// 逐行 diff（最长公共子序列）
+pub fn diff_lines(old: &str, new: &str) -> Vec<PromptDiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let (n, m) = (old.len(), new.len());
+
+    // lcs[i][j] = old[i..] 与 new[j..] 的最长公共子序列长度
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let line = |kind, text: &str| PromptDiffLine {
+        kind,
+        text: text.to_string(),
+    };
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::with_capacity(n.max(m));
+    while i < n && j < m {
+        if old[i] == new[j] {
+            diff.push(line(PromptDiffKind::Equal, old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(line(PromptDiffKind::Removed, old[i]));
+            i += 1;
+        } else {
+            diff.push(line(PromptDiffKind::Added, new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|t| line(PromptDiffKind::Removed, t)));
+    diff.extend(new[j..].iter().map(|t| line(PromptDiffKind::Added, t)));
+    diff
+}
+
+/// 展开文本中的库 Prompt 引用
+///
+/// 引用的 Prompt 按默认值渲染变量（无默认值的占位符保留原样），最多展开 3 层嵌套；
+/// 不存在的引用保留原样并记录警告。
+pub fn expand_prompt_references(conn: &Connection, text: &str) -> String {
+    expand_with_depth(conn, text, 0)
+}
+
+fn expand_with_depth(conn: &Connection, text: &str, depth: usize) -> String {
+    if depth >= MAX_REFERENCE_DEPTH || !REFERENCE_RE.is_match(text) {
+        return text.to_string();
+    }
+    REFERENCE_RE
+        .replace_all(text, |caps: &Captures| {
+            let id = &caps[1];
+            let version = caps.get(2).and_then(|v| v.as_str().parse::<u32>().ok());
+            match load_reference(conn, id, version) {
+                Some((content, variables)) => {
+                    let rendered = render_with_defaults(&content, &variables);
+                    expand_with_depth(conn, &rendered, depth + 1)
+                }
+                None => {
+                    tracing::warn!("[PROMPT_LIBRARY] 引用的 Prompt 不存在: {}", &caps[0]);
+                    caps[0].to_string()
+                }
+            }
+        })
+        .into_owned()
+}
+
+fn load_reference(
+    conn: &Connection,
+    id: &str,
+    version: Option<u32>,
+) -> Option<(String, Vec<PromptVariable>)> {
+    let loaded = match version {
+        Some(version) => PromptLibraryDao::get_version(conn, id, version)
+            .map(|v| v.map(|v| (v.content, v.variables))),
+        None => PromptLibraryDao::get(conn, id).map(|e| e.map(|e| (e.content, e.variables))),
+    };
+    loaded.unwrap_or_else(|e| {
+        tracing::warn!("[PROMPT_LIBRARY] 读取 Prompt {} 失败: {}", id, e);
+        None
+    })
+}
+
+fn render_with_defaults(content: &str, variables: &[PromptVariable]) -> String {
+    VARIABLE_RE
+        .replace_all(content, |caps: &Captures| {
+            variables
+                .iter()
+                .find(|v| v.name == caps[1])
+                .and_then(|v| v.default_value.clone())
+                .unwrap_or_else(|| caps[0].to_string())
+        })
+        .into_owned()
+}
+
+pub struct PromptLibraryService;
+
+impl PromptLibraryService {
+    pub fn list(
+        db: &DbConnection,
+        query: &PromptLibraryQuery,
+    ) -> Result<Vec<PromptLibraryEntry>, String> {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        PromptLibraryDao::list(&conn, query).map_err(|e| e.to_string())
+    }
+
+    pub fn get(db: &DbConnection, id: &str) -> Result<Option<PromptLibraryEntry>, String> {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        PromptLibraryDao::get(&conn, id).map_err(|e| e.to_string())
+    }
+
+    /// 保存 Prompt（ID 为空时新建）
+    ///
+    /// 内容中出现但未声明的变量会自动补充为无默认值的变量。
+    pub fn save(
+        db: &DbConnection,
+        mut entry: PromptLibraryEntry,
+        note: Option<&str>,
+    ) -> Result<PromptLibraryEntry, String> {
+        entry.name = entry.name.trim().to_string();
+        if entry.name.is_empty() {
+            return Err("Prompt 名称不能为空".to_string());
+        }
+        if entry.content.trim().is_empty() {
+            return Err("Prompt 内容不能为空".to_string());
+        }
+        if entry.id.trim().is_empty() {
+            entry.id = uuid::Uuid::new_v4().to_string();
+        }
+        entry.folder = entry
+            .folder
+            .map(|f| f.trim().trim_matches('/').to_string())
+            .filter(|f| !f.is_empty());
+        let mut tags = BTreeSet::new();
+        entry
+            .tags
+            .retain(|t| !t.trim().is_empty() && tags.insert(t.trim().to_lowercase()));
+        for name in extract_variables(&entry.content) {
+            if !entry.variables.iter().any(|v| v.name == name) {
+                entry.variables.push(PromptVariable {
+                    name,
+                    description: None,
+                    default_value: None,
+                });
+            }
+        }
+
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        PromptLibraryDao::save(&conn, &entry, note, chrono::Utc::now().timestamp_millis())
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn delete(db: &DbConnection, id: &str) -> Result<bool, String> {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        PromptLibraryDao::delete(&conn, id).map_err(|e| e.to_string())
+    }
+
+    pub fn list_versions(db: &DbConnection, id: &str) -> Result<Vec<PromptLibraryVersion>, String> {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        PromptLibraryDao::list_versions(&conn, id).map_err(|e| e.to_string())
+    }
+
+    /// 对比两个版本的内容
+    pub fn diff_versions(
+        db: &DbConnection,
+        id: &str,
+        from_version: u32,
+        to_version: u32,
+    ) -> Result<Vec<PromptDiffLine>, String> {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        let from = Self::require_version(&conn, id, from_version)?;
+        let to = Self::require_version(&conn, id, to_version)?;
+        Ok(diff_lines(&from.content, &to.content))
+    }
+
+    /// 回滚到指定版本（以该版本内容生成新版本，历史保留）
+    pub fn restore_version(
+        db: &DbConnection,
+        id: &str,
+        version: u32,
+    ) -> Result<PromptLibraryEntry, String> {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        let target = Self::require_version(&conn, id, version)?;
+        let current = PromptLibraryDao::get(&conn, id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Prompt 不存在: {id}"))?;
+        let restored = PromptLibraryEntry {
+            content: target.content,
+            variables: target.variables,
+            ..current
+        };
+        PromptLibraryDao::save(
+            &conn,
+            &restored,
+            Some(&format!("恢复自版本 {version}")),
+            chrono::Utc::now().timestamp_millis(),
+        )
+        .map_err(|e| e.to_string())
+    }
+
+    /// 按传入变量值渲染 Prompt，并展开其中的库引用
+    pub fn render(
+        db: &DbConnection,
+        id: &str,
+        values: &HashMap<String, String>,
+    ) -> Result<String, String> {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        let entry = PromptLibraryDao::get(&conn, id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Prompt 不存在: {id}"))?;
+        let rendered = render_prompt(&entry.content, &entry.variables, values)?;
+        Ok(expand_prompt_references(&conn, &rendered))
+    }
+
+    /// 列出已使用的文件夹
+    pub fn list_folders(db: &DbConnection) -> Result<Vec<String>, String> {
+        let entries = Self::list(db, &PromptLibraryQuery::default())?;
+        let folders: BTreeSet<String> = entries.into_iter().filter_map(|e| e.folder).collect();
+        Ok(folders.into_iter().collect())
+    }
+
+    /// 列出已使用的标签
+    pub fn list_tags(db: &DbConnection) -> Result<Vec<String>, String> {
+        let entries = Self::list(db, &PromptLibraryQuery::default())?;
+        let tags: BTreeSet<String> = entries.into_iter().flat_map(|e| e.tags).collect();
+        Ok(tags.into_iter().collect())
+    }
+
+    /// 展开文本中的库 Prompt 引用（读取失败时返回原文）
+    pub fn expand_references(db: &DbConnection, text: &str) -> String {
+        if !REFERENCE_RE.is_match(text) {
+            return text.to_string();
+        }
+        match db.lock() {
+            Ok(conn) => expand_prompt_references(&conn, text),
+            Err(e) => {
+                tracing::warn!("[PROMPT_LIBRARY] 展开引用时获取数据库锁失败: {}", e);
+                text.to_string()
+            }
+        }
+    }
+
+    fn require_version(
+        conn: &Connection,
+        id: &str,
+        version: u32,
+    ) -> Result<PromptLibraryVersion, String> {
+        PromptLibraryDao::get_version(conn, id, version)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Prompt {id} 不存在版本 {version}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_core::database::schema::create_tables;
+    use std::sync::{Arc, Mutex};
+
+    fn memory_db() -> DbConnection {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+        Arc::new(Mutex::new(conn))
+    }
+
+    fn new_prompt(id: &str, content: &str) -> PromptLibraryEntry {
+        PromptLibraryEntry {
+            id: id.to_string(),
+            name: id.to_string(),
+            folder: None,
+            tags: Vec::new(),
+            description: None,
+            content: content.to_string(),
+            variables: Vec::new(),
+            version: 0,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_render_prompt_uses_values_then_defaults() {
+        let variables = vec![PromptVariable {
+            name: "tone".to_string(),
+            description: None,
+            default_value: Some("friendly".to_string()),
+        }];
+        let content = "Write about {{ topic }} in a {{tone}} tone. {{prompt:base}}";
+        assert_eq!(extract_variables(content), vec!["topic", "tone"]);
+
+        let values = HashMap::from([("topic".to_string(), "Rust".to_string())]);
+        assert_eq!(
+            render_prompt(content, &variables, &values).unwrap(),
+            "Write about Rust in a friendly tone. {{prompt:base}}"
+        );
+        assert_eq!(
+            render_prompt(content, &variables, &HashMap::new()).unwrap_err(),
+            "缺少变量: topic"
+        );
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let diff = diff_lines("a\nb\nc", "a\nc\nd");
+        let kinds: Vec<_> = diff.iter().map(|l| (l.kind, l.text.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (PromptDiffKind::Equal, "a"),
+                (PromptDiffKind::Removed, "b"),
+                (PromptDiffKind::Equal, "c"),
+                (PromptDiffKind::Added, "d"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_references_and_restore_version() {
+        let db = memory_db();
+        let mut base = new_prompt("base", "Be concise.");
+        base.variables.push(PromptVariable {
+            name: "lang".to_string(),
+            description: None,
+            default_value: Some("English".to_string()),
+        });
+        base.content = "Be concise. Reply in {{lang}}.".to_string();
+        PromptLibraryService::save(&db, base, None).unwrap();
+        PromptLibraryService::save(&db, new_prompt("wrapper", "Rules: {{prompt:base}}"), None)
+            .unwrap();
+
+        assert_eq!(
+            PromptLibraryService::expand_references(
+                &db,
+                "System. {{prompt:wrapper}} {{prompt:missing}}"
+            ),
+            "System. Rules: Be concise. Reply in English. {{prompt:missing}}"
+        );
+
+        let mut wrapper = PromptLibraryService::get(&db, "wrapper").unwrap().unwrap();
+        wrapper.content = "Rules v2".to_string();
+        PromptLibraryService::save(&db, wrapper, None).unwrap();
+        assert_eq!(
+            PromptLibraryService::expand_references(&db, "{{prompt:wrapper@1}}"),
+            "Rules: Be concise. Reply in English."
+        );
+
+        let restored = PromptLibraryService::restore_version(&db, "wrapper", 1).unwrap();
+        assert_eq!(restored.version, 3);
+        assert_eq!(restored.content, "Rules: {{prompt:base}}");
+        let diff = PromptLibraryService::diff_versions(&db, "wrapper", 2, 3).unwrap();
+        assert_eq!(diff.len(), 2);
+    }
+
+    #[test]
+    fn test_save_normalizes_and_declares_variables() {
+        let db = memory_db();
+        let mut prompt = new_prompt("", "Hello {{name}}");
+        prompt.folder = Some("/writing/blog/".to_string());
+        prompt.tags = vec!["Blog".to_string(), "blog".to_string(), " ".to_string()];
+
+        let saved = PromptLibraryService::save(&db, prompt, None).unwrap();
+        assert!(!saved.id.is_empty());
+        assert_eq!(saved.folder.as_deref(), Some("writing/blog"));
+        assert_eq!(saved.tags, vec!["Blog".to_string()]);
+        assert_eq!(saved.variables[0].name, "name");
+        assert_eq!(
+            PromptLibraryService::list_folders(&db).unwrap(),
+            vec!["writing/blog".to_string()]
+        );
+    }
+}
@@ -4,25 +4,32 @@
 
 #![allow(dead_code)]
 
+use crate::adaptive_selection::AdaptiveSelector;
 use crate::api_key_provider_service::ApiKeyProviderService;
+use crate::endpoint_health::{normalize_fallback_base_urls, EndpointHealthRegistry};
 use crate::provider_type_mapping::{
     api_provider_type_to_pool_type, is_custom_provider_id, parse_pool_provider_type,
     resolve_pool_provider_type_or_default,
 };
 use chrono::Utc;
+use lime_core::credential::{
+    CredentialPoolChange, CredentialPoolEvents, HealthChecker, HealthProbeRegistry, ProbeVariables,
+    RiskController, SessionAffinity,
+};
 use lime_core::database::dao::provider_pool::ProviderPoolDao;
 use lime_core::database::DbConnection;
 use lime_core::models::client_type::ClientType;
 use lime_core::models::provider_pool_model::{
     get_default_check_model, get_oauth_creds_path, CredentialData, CredentialDisplay,
-    HealthCheckResult, OAuthStatus, PoolProviderType, PoolStats, ProviderCredential,
-    ProviderPoolOverview,
+    CredentialEndpointHealth, HealthCheckResult, OAuthStatus, PoolProviderType, PoolStats,
+    ProviderCredential, ProviderPoolOverview,
 };
 use lime_core::models::route_model::RouteInfo;
 use lime_providers::providers::antigravity::TokenRefreshError;
 use lime_providers::providers::kiro::KiroProvider;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// 扩展 ProviderCredential 的客户端兼容性检查
 /// （此方法依赖 server::client_detector，不适合放在 core crate）
@@ -40,6 +47,12 @@ impl ProviderCredentialClientCompat for ProviderCredential {
         true
     }
 }
+
+/// 按凭证权重折算的使用次数
+fn weighted_usage(cred: &ProviderCredential) -> f64 {
+    cred.usage_count as f64 / cred.weight.max(1) as f64
+}
+
 use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicUsize;
 use std::time::Duration;
@@ -88,6 +101,18 @@ pub struct ProviderPoolService {
     max_error_count: u32,
     /// 健康检查超时时间
     health_check_timeout: Duration,
+    /// 自定义 base_url 的端点健康状态
+    endpoint_health: EndpointHealthRegistry,
+    /// 凭证池变更事件
+    events: Arc<CredentialPoolEvents>,
+    /// Provider 插件声明的健康探测
+    health_probes: Arc<HealthProbeRegistry>,
+    /// 按请求结果学习的自适应选择
+    adaptive: Arc<AdaptiveSelector>,
+    /// 会话亲和绑定（按 provider_type 隔离）
+    affinity: Arc<SessionAffinity>,
+    /// 限流冷却与节流
+    risk: Arc<RiskController>,
 }
 
 impl Default for ProviderPoolService {
@@ -106,7 +131,63 @@ impl ProviderPoolService {
             round_robin_index: std::sync::RwLock::new(HashMap::new()),
             max_error_count: 3,
             health_check_timeout: Duration::from_secs(30),
+            endpoint_health: EndpointHealthRegistry::new(),
+            events: Arc::new(CredentialPoolEvents::default()),
+            health_probes: Arc::new(HealthProbeRegistry::new()),
+            adaptive: Arc::new(AdaptiveSelector::default()),
+            affinity: Arc::new(SessionAffinity::default()),
+            risk: Arc::new(RiskController::with_defaults()),
+        }
+    }
+
+    /// 会话亲和绑定（需由调用方定期调用 `purge_expired` 清理）
+    pub fn session_affinity(&self) -> &Arc<SessionAffinity> {
+        &self.affinity
+    }
+
+    /// 限流风控（429 冷却期与自适应节流，需由调用方定期调用 `persist_profiles` 写库）
+    pub fn risk_controller(&self) -> &Arc<RiskController> {
+        &self.risk
+    }
+
+    /// 自适应凭证选择（启用后替代按权重选择）
+    pub fn adaptive_selection(&self) -> &Arc<AdaptiveSelector> {
+        &self.adaptive
+    }
+
+    /// Provider 插件声明的健康探测注册表
+    pub fn health_probes(&self) -> &Arc<HealthProbeRegistry> {
+        &self.health_probes
+    }
+
+    /// 凭证池变更事件总线
+    pub fn events(&self) -> &Arc<CredentialPoolEvents> {
+        &self.events
+    }
+
+    /// 发布凭证更新事件（供绕过本服务直接写库的调用方使用）
+    pub fn notify_credential_updated(&self, cred: &ProviderCredential) {
+        self.events.emit(CredentialPoolChange::Updated {
+            credential: Box::new(self.to_display(cred)),
+        });
+    }
+
+    /// 健康状态发生变化时发布事件
+    fn notify_health_change(
+        &self,
+        before: &ProviderCredential,
+        is_healthy: bool,
+        error: Option<&str>,
+    ) {
+        if before.is_healthy == is_healthy {
+            return;
         }
+        self.events.emit(CredentialPoolChange::HealthChanged {
+            uuid: before.uuid.clone(),
+            provider_type: before.provider_type.to_string(),
+            is_healthy,
+            error: error.map(str::to_string),
+        });
     }
 
     /// 获取所有凭证概览
@@ -124,7 +205,8 @@ impl ProviderPoolService {
             }
 
             let stats = PoolStats::from_credentials(&credentials);
-            let displays: Vec<CredentialDisplay> = credentials.iter().map(|c| c.into()).collect();
+            let displays: Vec<CredentialDisplay> =
+                credentials.iter().map(|c| self.to_display(c)).collect();
 
             overview.push(ProviderPoolOverview {
                 provider_type: provider_type.to_string(),
@@ -156,7 +238,14 @@ impl ProviderPoolService {
                 .flatten();
         }
 
-        Ok(credentials.iter().map(|c| c.into()).collect())
+        Ok(credentials.iter().map(|c| self.to_display(c)).collect())
+    }
+
+    /// 构建前端展示数据（附带端点健康状态）
+    fn to_display(&self, cred: &ProviderCredential) -> CredentialDisplay {
+        let mut display = CredentialDisplay::from(cred);
+        display.endpoint_health = self.endpoint_health.get(&cred.uuid);
+        display
     }
 
     /// 添加凭证
@@ -178,7 +267,11 @@ impl ProviderPoolService {
 
         let conn = lime_core::database::lock_db(db)?;
         ProviderPoolDao::insert(&conn, &cred).map_err(|e| e.to_string())?;
+        drop(conn);
 
+        self.events.emit(CredentialPoolChange::Added {
+            credential: Box::new(self.to_display(&cred)),
+        });
         Ok(cred)
     }
 
@@ -193,6 +286,8 @@ impl ProviderPoolService {
         check_model_name: Option<String>,
         not_supported_models: Option<Vec<String>>,
         proxy_url: Option<String>,
+        native_web_search: Option<bool>,
+        fallback_base_urls: Option<Vec<String>>,
     ) -> Result<ProviderCredential, String> {
         let conn = lime_core::database::lock_db(db)?;
         let mut cred = ProviderPoolDao::get_by_uuid(&conn, uuid)
@@ -220,16 +315,41 @@ impl ProviderPoolService {
         if let Some(p) = proxy_url {
             cred.proxy_url = if p.is_empty() { None } else { Some(p) };
         }
+        if let Some(enabled) = native_web_search {
+            if enabled && !cred.credential.supports_native_web_search() {
+                return Err("该凭证类型不支持原生联网搜索".to_string());
+            }
+            cred.native_web_search = enabled;
+        }
+        if let Some(urls) = fallback_base_urls {
+            cred.fallback_base_urls = normalize_fallback_base_urls(&cred.credential, urls)?;
+        }
         cred.updated_at = Utc::now();
 
         ProviderPoolDao::update(&conn, &cred).map_err(|e| e.to_string())?;
+        drop(conn);
+
+        self.notify_credential_updated(&cred);
         Ok(cred)
     }
 
-    /// 删除凭证
+    /// 删除凭证（覆盖密钥列后删除，见 [`ProviderPoolDao::shred`]）
     pub fn delete_credential(&self, db: &DbConnection, uuid: &str) -> Result<bool, String> {
+        self.adaptive.remove_credential(db, uuid);
         let conn = lime_core::database::lock_db(db)?;
-        ProviderPoolDao::delete(&conn, uuid).map_err(|e| e.to_string())
+        self.endpoint_health.remove(uuid);
+        // 只读元数据，无法解密的凭证也能删除
+        let existing = ProviderPoolDao::get_meta(&conn, uuid).map_err(|e| e.to_string())?;
+        let deleted = ProviderPoolDao::shred(&conn, uuid).map_err(|e| e.to_string())?;
+        drop(conn);
+
+        if let (true, Some(meta)) = (deleted, existing) {
+            self.events.emit(CredentialPoolChange::Removed {
+                uuid: meta.uuid,
+                provider_type: meta.provider_type,
+            });
+        }
+        Ok(deleted)
     }
 
     /// 选择一个可用的凭证（智能轮换策略）
@@ -245,19 +365,45 @@ impl ProviderPoolService {
         provider_type: &str,
         model: Option<&str>,
     ) -> Result<Option<ProviderCredential>, String> {
-        self.select_credential_with_client_check(db, provider_type, model, None)
+        self.select_credential_with_client_check(db, provider_type, model, None, None)
     }
 
     /// 选择凭证并检查客户端兼容性
     ///
-    /// 内部方法，支持客户端类型检查
+    /// 传入 `session_id` 时按会话亲和选择：绑定的凭证仍可用则复用，否则重新选择并改绑。
     pub fn select_credential_with_client_check(
         &self,
         db: &DbConnection,
         provider_type: &str,
         model: Option<&str>,
         client_type: Option<&lime_core::models::client_type::ClientType>,
+        session_id: Option<&str>,
     ) -> Result<Option<ProviderCredential>, String> {
+        self.select_credential_with_filter(
+            db,
+            provider_type,
+            model,
+            client_type,
+            session_id,
+            |_| true,
+        )
+    }
+
+    /// 在调用方限定的凭证子集中选择凭证
+    ///
+    /// `filter` 返回 false 的凭证不参与选择（如租户命名空间的凭证白名单）。
+    pub fn select_credential_with_filter<F>(
+        &self,
+        db: &DbConnection,
+        provider_type: &str,
+        model: Option<&str>,
+        client_type: Option<&lime_core::models::client_type::ClientType>,
+        session_id: Option<&str>,
+        filter: F,
+    ) -> Result<Option<ProviderCredential>, String>
+    where
+        F: Fn(&ProviderCredential) -> bool,
+    {
         if is_custom_provider_id(provider_type) {
             eprintln!("[SELECT_CREDENTIAL] custom provider '{provider_type}' 使用智能降级路径");
             return Ok(None);
@@ -379,17 +525,45 @@ impl ProviderPoolService {
             available.len()
         );
 
+        // 过滤调用方限定范围外的凭证
+        available.retain(|c| filter(c));
+
         if available.is_empty() {
             return Ok(None);
         }
 
-        // 如果只有一个可用凭证，直接返回
-        if available.len() == 1 {
-            return Ok(Some(available.into_iter().next().unwrap()));
+        // 限流冷却中的凭证让位于其他可用凭证（全部在冷却中时仍从中选择）
+        if available.iter().any(|c| !self.risk.is_in_cooldown(&c.uuid)) {
+            available.retain(|c| !self.risk.is_in_cooldown(&c.uuid));
         }
 
-        // 智能选择：基于权重分数选择最优凭证
-        let selected = self.select_best_credential_by_weight(&available);
+        // 会话绑定的凭证仍在候选中时直接复用，保证同一对话命中上游的 prompt 缓存
+        let session_id = session_id.filter(|id| !id.is_empty());
+        let bound = session_id
+            .and_then(|id| self.affinity.get(provider_type, id))
+            .and_then(|binding| {
+                available
+                    .iter()
+                    .position(|c| c.uuid == binding.credential_id)
+            });
+
+        // 如果只有一个可用凭证，直接返回；否则按自适应统计或权重分数选择最优凭证
+        let mut selected = if let Some(index) = bound {
+            available.swap_remove(index)
+        } else if available.len() == 1 {
+            available.into_iter().next().unwrap()
+        } else if self.adaptive.is_enabled() {
+            self.adaptive.select(model, &available).clone()
+        } else {
+            self.select_best_credential_by_weight(&available)
+        };
+
+        if let Some(id) = session_id {
+            self.affinity.bind(provider_type, id, &selected.uuid);
+        }
+
+        // 自定义 base_url 切换到当前固定的可达端点
+        self.endpoint_health.apply(&mut selected);
 
         Ok(Some(selected))
     }
@@ -405,6 +579,7 @@ impl ProviderPoolService {
     /// - `model`: 可选的模型名称
     /// - `provider_id_hint`: 可选的 provider_id 提示，用于 60+ Provider 直接查找
     /// - `client_type`: 可选的客户端类型，用于凭证兼容性检查
+    /// - `session_id`: 可选的会话 ID，用于 Provider Pool 内的会话亲和
     ///
     /// # 返回
     /// - `Ok(Some(credential))`: 找到可用凭证（来自 Pool 或降级）
@@ -418,15 +593,20 @@ impl ProviderPoolService {
         model: Option<&str>,
         provider_id_hint: Option<&str>,
         client_type: Option<&lime_core::models::client_type::ClientType>,
+        session_id: Option<&str>,
     ) -> Result<Option<ProviderCredential>, String> {
         eprintln!(
             "[select_credential_with_fallback] 开始: provider_type={provider_type}, model={model:?}, provider_id_hint={provider_id_hint:?}"
         );
 
         // Step 1: 尝试从 Provider Pool 选择 (OAuth + API Key)
-        if let Some(cred) =
-            self.select_credential_with_client_check(db, provider_type, model, client_type)?
-        {
+        if let Some(cred) = self.select_credential_with_client_check(
+            db,
+            provider_type,
+            model,
+            client_type,
+            session_id,
+        )? {
             eprintln!(
                 "[select_credential_with_fallback] 从 Provider Pool 找到凭证: {:?}",
                 cred.name
@@ -531,14 +711,14 @@ impl ProviderPoolService {
             score -= 20.0; // 不健康的凭证严重扣分
         }
 
-        // 2. 使用频率权重 (30分) - 使用次数越少分数越高
+        // 2. 使用频率权重 (30分) - 按凭证权重折算后的使用次数越少分数越高，
+        //    长期看各凭证的使用量与权重成正比
         let max_usage = all_credentials
             .iter()
-            .map(|c| c.usage_count)
-            .max()
-            .unwrap_or(1);
-        if max_usage > 0 {
-            let usage_ratio = cred.usage_count as f64 / max_usage as f64;
+            .map(weighted_usage)
+            .fold(0.0, f64::max);
+        if max_usage > 0.0 {
+            let usage_ratio = weighted_usage(cred) / max_usage;
             score += 30.0 * (1.0 - usage_ratio); // 使用越少分数越高
         } else {
             score += 30.0; // 如果都没使用过，给满分
@@ -591,6 +771,7 @@ impl ProviderPoolService {
         check_model: Option<&str>,
     ) -> Result<(), String> {
         let conn = lime_core::database::lock_db(db)?;
+        let before = ProviderPoolDao::get_by_uuid(&conn, uuid).map_err(|e| e.to_string())?;
         ProviderPoolDao::update_health_status(
             &conn,
             uuid,
@@ -601,7 +782,13 @@ impl ProviderPoolService {
             Some(Utc::now()),
             check_model,
         )
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+        drop(conn);
+
+        if let Some(before) = before {
+            self.notify_health_change(&before, true, None);
+        }
+        Ok(())
     }
 
     /// 标记凭证为不健康
@@ -629,13 +816,44 @@ impl ProviderPoolService {
             None,
             None,
         )
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+        drop(conn);
+
+        self.notify_health_change(&cred, is_healthy, error_message);
+        Ok(())
+    }
+
+    /// 更新凭证负载均衡权重（最小为 1）
+    pub fn set_credential_weight(
+        &self,
+        db: &DbConnection,
+        uuid: &str,
+        weight: u32,
+    ) -> Result<ProviderCredential, String> {
+        let conn = lime_core::database::lock_db(db)?;
+        if !ProviderPoolDao::set_weight(&conn, uuid, weight.max(1)).map_err(|e| e.to_string())? {
+            return Err(format!("Credential not found: {uuid}"));
+        }
+        let cred = ProviderPoolDao::get_by_uuid(&conn, uuid)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Credential not found: {uuid}"))?;
+        drop(conn);
+
+        self.notify_credential_updated(&cred);
+        Ok(cred)
     }
 
     /// 重置凭证计数器
     pub fn reset_counters(&self, db: &DbConnection, uuid: &str) -> Result<(), String> {
         let conn = lime_core::database::lock_db(db)?;
-        ProviderPoolDao::reset_counters(&conn, uuid).map_err(|e| e.to_string())
+        let before = ProviderPoolDao::get_by_uuid(&conn, uuid).map_err(|e| e.to_string())?;
+        ProviderPoolDao::reset_counters(&conn, uuid).map_err(|e| e.to_string())?;
+        drop(conn);
+
+        if let Some(before) = before {
+            self.notify_health_change(&before, true, None);
+        }
+        Ok(())
     }
 
     /// 重置指定类型的所有凭证健康状态
@@ -646,7 +864,15 @@ impl ProviderPoolService {
     ) -> Result<usize, String> {
         let pt = parse_pool_provider_type(provider_type)?;
         let conn = lime_core::database::lock_db(db)?;
-        ProviderPoolDao::reset_health_by_type(&conn, &pt).map_err(|e| e.to_string())
+        let before = ProviderPoolDao::get_by_type(&conn, &pt).map_err(|e| e.to_string())?;
+        let affected =
+            ProviderPoolDao::reset_health_by_type(&conn, &pt).map_err(|e| e.to_string())?;
+        drop(conn);
+
+        for cred in &before {
+            self.notify_health_change(cred, true, None);
+        }
+        Ok(affected)
     }
 
     /// 获取凭证健康状态
@@ -743,7 +969,11 @@ impl ProviderPoolService {
             None,
             None,
         )
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+        drop(conn);
+
+        self.notify_health_change(&cred, is_healthy, Some(&error_msg));
+        Ok(())
     }
 
     /// 选择一个健康的凭证
@@ -844,7 +1074,8 @@ impl ProviderPoolService {
         };
 
         let selected_index = index % available.len();
-        let selected = available[selected_index].clone();
+        let mut selected = available[selected_index].clone();
+        self.endpoint_health.apply(&mut selected);
 
         // 更新轮询索引
         {
@@ -866,13 +1097,18 @@ impl ProviderPoolService {
         db: &DbConnection,
         uuid: &str,
     ) -> Result<HealthCheckResult, String> {
-        let cred = {
+        let mut cred = {
             let conn = lime_core::database::lock_db(db)?;
             ProviderPoolDao::get_by_uuid(&conn, uuid)
                 .map_err(|e| e.to_string())?
                 .ok_or_else(|| format!("Credential not found: {uuid}"))?
         };
 
+        // 先检测 base_url 可达性，凭证有效性检查走当前可达的端点，
+        // 避免中转宕机被误判为凭证失效
+        self.endpoint_health.check(&self.client, &cred).await;
+        self.endpoint_health.apply(&mut cred);
+
         let check_model = cred
             .check_model_name
             .clone()
@@ -880,7 +1116,7 @@ impl ProviderPoolService {
 
         let start = std::time::Instant::now();
         let result = self
-            .perform_health_check(&cred.credential, &check_model)
+            .perform_health_check(cred.provider_type, &cred.credential, &check_model)
             .await;
         let duration_ms = start.elapsed().as_millis() as u64;
 
@@ -906,17 +1142,22 @@ impl ProviderPoolService {
                             tracing::info!("[健康检查] Token 刷新成功，重新检查健康状态");
 
                             // 重新获取凭证（token 已更新）
-                            let updated_cred = {
+                            let mut updated_cred = {
                                 let conn = lime_core::database::lock_db(db)?;
                                 ProviderPoolDao::get_by_uuid(&conn, uuid)
                                     .map_err(|e| e.to_string())?
                                     .ok_or_else(|| format!("Credential not found: {uuid}"))?
                             };
+                            self.endpoint_health.apply(&mut updated_cred);
 
                             // 重新执行健康检查
                             let retry_start = std::time::Instant::now();
                             let retry_result = self
-                                .perform_health_check(&updated_cred.credential, &check_model)
+                                .perform_health_check(
+                                    updated_cred.provider_type,
+                                    &updated_cred.credential,
+                                    &check_model,
+                                )
                                 .await;
                             let retry_duration_ms = retry_start.elapsed().as_millis() as u64;
 
@@ -973,6 +1214,52 @@ impl ProviderPoolService {
         }
     }
 
+    /// 检测凭证自定义 base_url 的可达性（不检查凭证有效性）
+    ///
+    /// 未配置自定义 base_url 的凭证返回 None。
+    pub async fn check_credential_endpoints(
+        &self,
+        db: &DbConnection,
+        uuid: &str,
+    ) -> Result<Option<CredentialEndpointHealth>, String> {
+        let cred = {
+            let conn = lime_core::database::lock_db(db)?;
+            ProviderPoolDao::get_by_uuid(&conn, uuid)
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("Credential not found: {uuid}"))?
+        };
+        Ok(self.endpoint_health.check(&self.client, &cred).await)
+    }
+
+    /// 检测所有启用凭证的自定义 base_url 可达性
+    pub async fn check_all_endpoints(
+        &self,
+        db: &DbConnection,
+    ) -> Result<HashMap<String, CredentialEndpointHealth>, String> {
+        let credentials = {
+            let conn = lime_core::database::lock_db(db)?;
+            ProviderPoolDao::get_all(&conn).map_err(|e| e.to_string())?
+        };
+
+        let checks = credentials
+            .iter()
+            .filter(|c| !c.is_disabled)
+            .map(|c| async move {
+                let health = self.endpoint_health.check(&self.client, c).await;
+                health.map(|h| (c.uuid.clone(), h))
+            });
+        Ok(futures::future::join_all(checks)
+            .await
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// 获取凭证的端点健康状态（未检测过时为 None）
+    pub fn get_endpoint_health(&self, uuid: &str) -> Option<CredentialEndpointHealth> {
+        self.endpoint_health.get(uuid)
+    }
+
     /// 执行指定类型的所有凭证健康检查
     pub async fn check_type_health(
         &self,
@@ -998,12 +1285,68 @@ impl ProviderPoolService {
         Ok(results)
     }
 
+    /// 检查 Provider 类型由插件声明了健康探测的凭证
+    ///
+    /// 供定时任务调用；单个凭证检查失败只记录日志，不中断其余凭证。
+    pub async fn check_plugin_credentials_health(
+        &self,
+        db: &DbConnection,
+    ) -> Result<Vec<HealthCheckResult>, String> {
+        let credentials = {
+            let conn = lime_core::database::lock_db(db)?;
+            ProviderPoolDao::get_all(&conn).map_err(|e| e.to_string())?
+        };
+
+        let mut results = Vec::new();
+        for uuid in self.plugin_probed_credentials(&credentials) {
+            match self.check_credential_health(db, &uuid).await {
+                Ok(result) => results.push(result),
+                Err(e) => tracing::warn!("[健康检查] 插件凭证 {} 检查失败: {}", uuid, e),
+            }
+        }
+        Ok(results)
+    }
+
+    /// 启用健康检查、且会走插件声明探测的凭证
+    fn plugin_probed_credentials(&self, credentials: &[ProviderCredential]) -> Vec<String> {
+        credentials
+            .iter()
+            .filter(|cred| !cred.is_disabled && cred.check_health)
+            .filter(|cred| cred.credential.api_key().is_some())
+            .filter(|cred| {
+                self.health_probes
+                    .get(&cred.provider_type.to_string())
+                    .is_some()
+            })
+            .map(|cred| cred.uuid.clone())
+            .collect()
+    }
+
     /// 执行实际的健康检查请求
+    ///
+    /// API Key 类凭证优先使用 Provider 插件声明的探测，其余走内置检查。
     async fn perform_health_check(
         &self,
+        provider_type: PoolProviderType,
         credential: &CredentialData,
         model: &str,
     ) -> Result<(), String> {
+        if let (Some(probe), Some(api_key)) = (
+            self.health_probes.get(&provider_type.to_string()),
+            credential.api_key(),
+        ) {
+            tracing::debug!("[HEALTH_CHECK] 使用插件声明的探测: {}", provider_type);
+            let vars = ProbeVariables {
+                api_key: Some(api_key.to_string()),
+                base_url: credential.base_url().map(str::to_string),
+                model: model.to_string(),
+            };
+            return HealthChecker::with_defaults()
+                .probe(&self.client, &probe, &vars)
+                .await
+                .map(|_| ());
+        }
+
         // 根据凭证类型构建测试请求
         match credential {
             CredentialData::KiroOAuth { creds_file_path } => {
@@ -1058,6 +1401,8 @@ impl ProviderPoolService {
                 self.check_claude_health(api_key, base_url.as_deref(), model)
                     .await
             }
+            // Mock 不发起网络请求，故障注入只作用于实际代理请求
+            CredentialData::Mock { .. } => Ok(()),
         }
     }
 
@@ -1960,7 +2305,11 @@ impl ProviderPoolService {
 
         let conn = lime_core::database::lock_db(db)?;
         ProviderPoolDao::insert(&conn, &cred).map_err(|e| e.to_string())?;
+        drop(conn);
 
+        self.events.emit(CredentialPoolChange::Added {
+            credential: Box::new(self.to_display(&cred)),
+        });
         Ok(cred)
     }
 
@@ -2143,6 +2492,7 @@ pub struct MigrationResult {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use lime_core::credential::{HealthProbe, RateLimitEvent};
     use lime_core::database::dao::api_key_provider::ApiProviderType;
 
     // ==================== Property 3: 不健康凭证排除 ====================
@@ -2168,6 +2518,104 @@ mod tests {
         assert_eq!(info.failure_count, 3);
     }
 
+    #[test]
+    fn test_select_best_credential_respects_weight() {
+        let service = ProviderPoolService::new();
+        let mut paid = ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: "sk-paid".to_string(),
+                base_url: None,
+            },
+        );
+        paid.weight = 3;
+        paid.usage_count = 20;
+        let mut free = ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: "sk-free".to_string(),
+                base_url: None,
+            },
+        );
+        free.usage_count = 10;
+
+        // 原始使用次数更多，但按权重折算后仍是负载更低的一方
+        let selected = service.select_best_credential_by_weight(&[free.clone(), paid.clone()]);
+        assert_eq!(selected.uuid, paid.uuid);
+
+        paid.usage_count = 40;
+        let selected = service.select_best_credential_by_weight(&[free.clone(), paid]);
+        assert_eq!(selected.uuid, free.uuid);
+    }
+
+    #[test]
+    fn test_select_credential_keeps_session_affinity() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        lime_core::database::schema::create_tables(&conn).unwrap();
+        let db: DbConnection = Arc::new(std::sync::Mutex::new(conn));
+        let credential = |api_key: &str, usage_count: u64| {
+            let mut cred = ProviderCredential::new(
+                PoolProviderType::OpenAI,
+                CredentialData::OpenAIKey {
+                    api_key: api_key.to_string(),
+                    base_url: None,
+                },
+            );
+            cred.usage_count = usage_count;
+            cred
+        };
+        let busy = credential("sk-busy", 50);
+        let idle = credential("sk-idle", 0);
+        {
+            let conn = db.lock().unwrap();
+            ProviderPoolDao::insert(&conn, &busy).unwrap();
+            ProviderPoolDao::insert(&conn, &idle).unwrap();
+        }
+
+        let service = ProviderPoolService::new();
+        let select = |session_id: Option<&str>| {
+            service
+                .select_credential_with_client_check(&db, "openai", None, None, session_id)
+                .unwrap()
+                .unwrap()
+                .uuid
+        };
+        assert_eq!(select(None), idle.uuid);
+
+        // 已绑定的会话即使评分更低也继续使用同一凭证
+        service
+            .session_affinity()
+            .bind("openai", "conv-a", &busy.uuid);
+        assert_eq!(select(Some("conv-a")), busy.uuid);
+        assert_eq!(select(Some("conv-a")), busy.uuid);
+
+        // 新会话按评分选择并绑定
+        assert_eq!(select(Some("conv-b")), idle.uuid);
+        assert_eq!(
+            service
+                .session_affinity()
+                .get("openai", "conv-b")
+                .unwrap()
+                .credential_id,
+            idle.uuid
+        );
+
+        // 限流冷却中的凭证让位于其他可用凭证，会话随之改绑
+        service
+            .risk_controller()
+            .record_rate_limit(RateLimitEvent::new(busy.uuid.clone()).with_status_code(429));
+        assert_eq!(select(Some("conv-a")), idle.uuid);
+
+        // 全部在冷却中时仍可选择
+        service
+            .risk_controller()
+            .record_rate_limit(RateLimitEvent::new(idle.uuid.clone()).with_status_code(429));
+        assert!(service
+            .select_credential_with_client_check(&db, "openai", None, None, None)
+            .unwrap()
+            .is_some());
+    }
+
     #[test]
     fn test_selection_error_no_credentials() {
         let error = SelectionError::NoCredentials;
@@ -2306,6 +2754,41 @@ mod tests {
         assert!(urls.contains(&"http://127.0.0.1:3030/v1/chat/completions".to_string()));
     }
 
+    #[test]
+    fn test_plugin_probed_credentials_require_declared_probe() {
+        let service = ProviderPoolService::new();
+        let probe: HealthProbe = serde_json::from_value(serde_json::json!({
+            "provider_type": "openai",
+            "endpoint": "{{base_url}}/v1/models",
+            "default_base_url": "https://api.openai.com"
+        }))
+        .unwrap();
+        service
+            .health_probes()
+            .replace_all(vec![("acme-plugin".to_string(), vec![probe])]);
+
+        let api_key = |key: &str| CredentialData::OpenAIKey {
+            api_key: key.to_string(),
+            base_url: None,
+        };
+        let probed = ProviderCredential::new(PoolProviderType::OpenAI, api_key("sk-a"));
+        let mut disabled = ProviderCredential::new(PoolProviderType::OpenAI, api_key("sk-b"));
+        disabled.is_disabled = true;
+        let mut unchecked = ProviderCredential::new(PoolProviderType::OpenAI, api_key("sk-c"));
+        unchecked.check_health = false;
+        let undeclared = ProviderCredential::new(
+            PoolProviderType::Claude,
+            CredentialData::ClaudeKey {
+                api_key: "sk-d".to_string(),
+                base_url: None,
+            },
+        );
+
+        let uuids =
+            service.plugin_probed_credentials(&[probed.clone(), disabled, unchecked, undeclared]);
+        assert_eq!(uuids, vec![probed.uuid]);
+    }
+
     #[test]
     fn test_build_openai_health_check_urls_defaults_to_official_endpoint() {
         let urls = ProviderPoolService::build_openai_health_check_urls(None);
@@ -62,6 +62,9 @@ pub(crate) fn pool_provider_type_to_api_type(
         PoolProviderType::Codex => None,
         PoolProviderType::ClaudeOAuth => None,
         PoolProviderType::Antigravity => None,
+
+        // 测试用 Mock，无对应 API Key Provider
+        PoolProviderType::Mock => None,
     }
 }
 
@@ -600,6 +600,7 @@ mod tests {
                 tool_calls_json TEXT,
                 tool_call_id TEXT,
                 reasoning_content TEXT,
+                cost_json TEXT,
                 FOREIGN KEY (session_id) REFERENCES agent_sessions(id) ON DELETE CASCADE
             )",
             [],
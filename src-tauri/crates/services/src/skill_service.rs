@@ -8,6 +8,9 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::time::timeout;
 
+use crate::extension_registry_service::{
+    ExtensionRegistryService, RegistryListing, RegistrySkillEntry,
+};
 use lime_core::app_paths;
 use lime_core::models::{
     parse_skill_manifest_from_content as parse_manifest_content, resolve_skill_source_kind,
@@ -106,6 +109,7 @@ pub struct SkillService {
     client: Client,
     repo_cache: RwLock<HashMap<RepoCacheKey, RepoCacheEntry>>,
     inflight_fetches: Mutex<HashMap<RepoCacheKey, Arc<tokio::sync::Notify>>>,
+    extension_registry: Option<Arc<ExtensionRegistryService>>,
 }
 
 impl SkillService {
@@ -119,9 +123,16 @@ impl SkillService {
             client,
             repo_cache: RwLock::new(HashMap::new()),
             inflight_fetches: Mutex::new(HashMap::new()),
+            extension_registry: None,
         })
     }
 
+    /// 接入扩展注册表，Skill 列表与安装同时覆盖自托管索引
+    pub fn with_extension_registry(mut self, registry: Arc<ExtensionRegistryService>) -> Self {
+        self.extension_registry = Some(registry);
+        self
+    }
+
     fn get_skills_dir(app_type: &AppType) -> Result<PathBuf> {
         let skills_dir = match app_type {
             AppType::Lime => app_paths::resolve_skills_dir().map_err(|e| anyhow!(e))?,
@@ -189,7 +200,11 @@ impl SkillService {
         let roots = Self::get_catalog_roots(app_type)?;
         self.collect_local_skills(app_type, &roots, &mut all_skills)?;
 
-        for repo in repos.iter().filter(|repo| repo.enabled) {
+        let github_enabled = self
+            .extension_registry
+            .as_ref()
+            .is_none_or(|registry| registry.github_sources_enabled());
+        for repo in repos.iter().filter(|repo| repo.enabled && github_enabled) {
             match self.fetch_skills_from_repo_cached(repo).await {
                 Ok(remote_skills) => {
                     for mut skill in remote_skills {
@@ -222,6 +237,20 @@ impl SkillService {
             }
         }
 
+        if let Some(registry) = &self.extension_registry {
+            for listing in registry.list_skills().await {
+                if all_skills
+                    .values()
+                    .any(|existing| existing.directory == listing.entry.directory)
+                {
+                    continue;
+                }
+                let skill =
+                    Self::build_skill_from_registry_listing(app_type, listing, installed_states);
+                all_skills.insert(skill.key.clone(), skill);
+            }
+        }
+
         let mut skills: Vec<Skill> = all_skills.into_values().collect();
         skills.sort_by(|a, b| a.name.cmp(&b.name));
         Ok(skills)
@@ -667,6 +696,78 @@ impl SkillService {
         ))
     }
 
+    /// 从扩展注册表安装 Skill（包按索引中的 sha256 校验）
+    pub async fn install_registry_skill(
+        &self,
+        app_type: &AppType,
+        registry_id: &str,
+        directory: &str,
+    ) -> Result<()> {
+        let registry = self
+            .extension_registry
+            .as_ref()
+            .ok_or_else(|| anyhow!("未启用扩展注册表"))?;
+        let entry = registry.find_skill(registry_id, directory).await?;
+        let bytes = registry
+            .download_package(&entry.download_url, &entry.sha256)
+            .await?;
+
+        let skills_dir = Self::get_skills_dir(app_type)?;
+        fs::create_dir_all(&skills_dir).context("Failed to create skills directory")?;
+        let target_dir = skills_dir.join(directory);
+        if target_dir.exists() {
+            fs::remove_dir_all(&target_dir).context("Failed to remove existing skill")?;
+        }
+
+        let result = Self::extract_skill_archive(bytes, &target_dir, directory)
+            .and_then(|()| self.validate_installed_skill_dir(&target_dir));
+        if result.is_err() {
+            let _ = fs::remove_dir_all(&target_dir);
+        }
+        result
+    }
+
+    fn build_skill_from_registry_listing(
+        app_type: &AppType,
+        listing: RegistryListing<RegistrySkillEntry>,
+        installed_states: &HashMap<String, SkillState>,
+    ) -> Skill {
+        let entry = listing.entry;
+        let app_key = format!(
+            "{}:{}",
+            app_type.to_string().to_lowercase(),
+            entry.directory
+        );
+        let mut metadata = HashMap::new();
+        if let Some(version) = entry.version {
+            metadata.insert("version".to_string(), version);
+        }
+        metadata.insert("registry".to_string(), listing.registry_name);
+
+        Skill {
+            key: format!("registry:{}:{}", listing.registry_id, entry.directory),
+            name: entry.name,
+            description: entry.description,
+            directory: entry.directory,
+            readme_url: None,
+            installed: installed_states
+                .get(&app_key)
+                .map(|state| state.installed)
+                .unwrap_or(false),
+            source_kind: SkillSourceKind::Other,
+            catalog_source: SkillCatalogSource::Remote,
+            repo_owner: None,
+            repo_name: None,
+            repo_branch: None,
+            registry_id: Some(listing.registry_id),
+            license: None,
+            metadata,
+            allowed_tools: Vec::new(),
+            resource_summary: None,
+            standard_compliance: None,
+        }
+    }
+
     async fn download_and_extract(
         &self,
         zip_url: &str,
@@ -685,6 +786,13 @@ impl SkillService {
         }
 
         let bytes = response.bytes().await.context("Failed to read response")?;
+        Self::extract_skill_archive(bytes.to_vec(), target_dir, directory)
+    }
+
+    /// 从 zip 中解压 `<directory>/` 下的文件（允许位于任意顶层目录之下）
+    ///
+    /// 含绝对路径或 `..` 的条目会使整个包被拒绝，防止写出目标目录之外。
+    fn extract_skill_archive(bytes: Vec<u8>, target_dir: &Path, directory: &str) -> Result<()> {
         let cursor = std::io::Cursor::new(bytes);
         let mut archive = zip::ZipArchive::new(cursor).context("Failed to open ZIP")?;
 
@@ -693,7 +801,10 @@ impl SkillService {
 
         for index in 0..archive.len() {
             let mut file = archive.by_index(index)?;
-            let file_path = file.name().to_string();
+            let Some(entry_path) = file.enclosed_name().map(Path::to_path_buf) else {
+                return Err(anyhow!("Unsafe path in archive: {}", file.name()));
+            };
+            let file_path = format!("/{}", entry_path.to_string_lossy().replace('\\', "/"));
 
             if !file_path.contains(&skill_prefix) {
                 continue;
@@ -710,7 +821,17 @@ impl SkillService {
                 continue;
             }
 
-            let output_path = target_dir.join(&relative_path);
+            let relative_path = Path::new(&relative_path);
+            if !relative_path
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return Err(anyhow!("Unsafe path in archive: {}", file.name()));
+            }
+            let output_path = target_dir.join(relative_path);
+            if !output_path.starts_with(target_dir) {
+                return Err(anyhow!("Unsafe path in archive: {}", file.name()));
+            }
             if file.is_dir() {
                 fs::create_dir_all(&output_path)?;
                 continue;
@@ -1040,6 +1161,7 @@ impl SkillService {
             repo_owner,
             repo_name,
             repo_branch,
+            registry_id: None,
             license: inspection.license,
             metadata: inspection.metadata,
             allowed_tools: inspection.allowed_tools,
@@ -1050,6 +1172,9 @@ impl SkillService {
 
     pub fn refresh_cache(&self) {
         self.repo_cache.write().clear();
+        if let Some(registry) = &self.extension_registry {
+            registry.refresh_cache();
+        }
     }
 }
 
@@ -1280,4 +1405,44 @@ metadata:
             .iter()
             .any(|error| error.contains("引用文件不存在")));
     }
+
+    #[test]
+    fn extract_skill_archive_should_accept_top_level_skill_directory() {
+        use std::io::Write;
+
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default();
+        writer.start_file("code-review/SKILL.md", options).unwrap();
+        writer.write_all(b"---\nname: code-review\n---\n").unwrap();
+        writer.start_file("other/SKILL.md", options).unwrap();
+        writer.write_all(b"ignored").unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let temp_dir = TempDir::new().unwrap();
+        let target_dir = temp_dir.path().join("code-review");
+        SkillService::extract_skill_archive(bytes, &target_dir, "code-review").unwrap();
+
+        assert!(target_dir.join("SKILL.md").is_file());
+        assert!(!temp_dir.path().join("other").exists());
+    }
+
+    #[test]
+    fn extract_skill_archive_should_reject_path_traversal() {
+        use std::io::Write;
+
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default();
+        writer.start_file("code-review/SKILL.md", options).unwrap();
+        writer.write_all(b"---\nname: code-review\n---\n").unwrap();
+        writer
+            .start_file("code-review/../../escaped.txt", options)
+            .unwrap();
+        writer.write_all(b"owned").unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let temp_dir = TempDir::new().unwrap();
+        let target_dir = temp_dir.path().join("skills").join("code-review");
+        assert!(SkillService::extract_skill_archive(bytes, &target_dir, "code-review").is_err());
+        assert!(!temp_dir.path().join("escaped.txt").exists());
+    }
 }
@@ -11,14 +11,16 @@
 use crate::kiro_event_service::KiroEventService;
 use chrono::Utc;
 use dashmap::DashMap;
+use lime_core::config::TokenRefreshSettings;
 use lime_core::database::dao::provider_pool::ProviderPoolDao;
 use lime_core::database::DbConnection;
 use lime_core::models::provider_pool_model::{
-    CachedTokenInfo, CredentialData, PoolProviderType, ProviderCredential,
+    get_oauth_creds_path, CachedTokenInfo, CredentialData, PoolProviderType, ProviderCredential,
 };
 use lime_providers::providers::gemini::GeminiProvider;
 use lime_providers::providers::kiro::KiroProvider;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Mutex;
 
 /// Token 刷新错误类型
@@ -495,6 +497,14 @@ impl TokenCacheService {
                     last_refresh_error: None,
                 })
             }
+            CredentialData::Mock { .. } => Ok(CachedTokenInfo {
+                access_token: Some("mock-token".to_string()),
+                refresh_token: None,
+                expiry_time: None,
+                last_refresh: Some(Utc::now()),
+                refresh_error_count: 0,
+                last_refresh_error: None,
+            }),
         }
     }
 
@@ -849,6 +859,14 @@ impl TokenCacheService {
                 refresh_error_count: 0,
                 last_refresh_error: None,
             }),
+            CredentialData::Mock { .. } => Ok(CachedTokenInfo {
+                access_token: Some("mock-token".to_string()),
+                refresh_token: None,
+                expiry_time: None,
+                last_refresh: None,
+                refresh_error_count: 0,
+                last_refresh_error: None,
+            }),
         }
     }
 
@@ -1060,4 +1078,226 @@ impl TokenCacheService {
         // 需要刷新（无缓存、已过期或即将过期）
         self.refresh_and_cache(db, uuid, false).await
     }
+
+    /// 后台刷新一轮即将过期的 OAuth Token
+    ///
+    /// 扫描凭证池中缓存 Token 的过期时间，对刷新窗口内过期的凭证逐个强制刷新；
+    /// 网络 / 服务类错误按指数退避加随机抖动重试，每次失败都会记入
+    /// `refresh_error_count` / `last_refresh_error`。
+    pub async fn refresh_expiring_tokens(
+        &self,
+        db: &DbConnection,
+        settings: &TokenRefreshSettings,
+    ) -> Result<TokenRefreshSweepReport, String> {
+        let window_minutes = settings.refresh_window_minutes as i64;
+        let due: Vec<String> = {
+            let conn = db.lock().map_err(|e| e.to_string())?;
+            let credentials = ProviderPoolDao::get_all(&conn).map_err(|e| e.to_string())?;
+            credentials
+                .into_iter()
+                .filter(|credential| {
+                    let cache = ProviderPoolDao::get_token_cache(&conn, &credential.uuid)
+                        .ok()
+                        .flatten();
+                    is_due_for_background_refresh(credential, cache.as_ref(), window_minutes)
+                })
+                .map(|credential| credential.uuid)
+                .collect()
+        };
+
+        let mut report = TokenRefreshSweepReport {
+            due: due.len(),
+            ..Default::default()
+        };
+        for uuid in due {
+            match self.refresh_with_retries(db, &uuid, settings).await {
+                Ok(true) => report.refreshed.push(uuid),
+                Ok(false) => {}
+                Err(failure) => report.failed.push(failure),
+            }
+        }
+        Ok(report)
+    }
+
+    /// 刷新单个凭证，返回是否实际执行了刷新
+    async fn refresh_with_retries(
+        &self,
+        db: &DbConnection,
+        uuid: &str,
+        settings: &TokenRefreshSettings,
+    ) -> Result<bool, TokenRefreshFailure> {
+        let window_minutes = settings.refresh_window_minutes as i64;
+        let max_attempts = settings.max_attempts.max(1);
+        let mut attempt = 0;
+        loop {
+            // 等待重试期间请求路径可能已完成刷新
+            let still_due = self
+                .get_cache_status(db, uuid)
+                .ok()
+                .flatten()
+                .map(|cache| cache.is_expiring_within_minutes(window_minutes))
+                .unwrap_or(false);
+            if !still_due {
+                return Ok(attempt > 0);
+            }
+
+            attempt += 1;
+            let error = match self.refresh_and_cache(db, uuid, true).await {
+                Ok(_) => return Ok(true),
+                Err(e) => e,
+            };
+            let classification = self.classify_refresh_error(&error);
+            let retryable = matches!(
+                classification.error_type,
+                RefreshErrorType::Network | RefreshErrorType::ServiceUnavailable
+            );
+            if !retryable || attempt >= max_attempts {
+                return Err(TokenRefreshFailure {
+                    uuid: uuid.to_string(),
+                    attempts: attempt,
+                    error_type: classification.error_type,
+                    error,
+                });
+            }
+
+            let delay = retry_delay(settings.retry_base_secs, attempt, jitter_seed(uuid));
+            tracing::warn!(
+                "[TOKEN_CACHE] Background refresh attempt {} failed for {}, retrying in {}ms: {}",
+                attempt,
+                short_uuid(uuid),
+                delay.as_millis(),
+                error
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// 后台刷新失败的凭证
+#[derive(Debug, Clone)]
+pub struct TokenRefreshFailure {
+    pub uuid: String,
+    /// 已尝试次数
+    pub attempts: u32,
+    pub error_type: RefreshErrorType,
+    pub error: String,
+}
+
+/// 一轮后台刷新的结果
+#[derive(Debug, Clone, Default)]
+pub struct TokenRefreshSweepReport {
+    /// 进入刷新窗口的凭证数
+    pub due: usize,
+    /// 刷新成功的凭证
+    pub refreshed: Vec<String>,
+    /// 重试后仍失败的凭证
+    pub failed: Vec<TokenRefreshFailure>,
+}
+
+/// 凭证是否需要后台刷新
+///
+/// 仅处理启用中的 OAuth 凭证，且已有带过期时间的缓存 Token；
+/// 尚无缓存的凭证在首次使用时由请求路径加载。
+fn is_due_for_background_refresh(
+    credential: &ProviderCredential,
+    cache: Option<&CachedTokenInfo>,
+    window_minutes: i64,
+) -> bool {
+    if credential.is_disabled || get_oauth_creds_path(&credential.credential).is_none() {
+        return false;
+    }
+    match cache {
+        Some(cache) if cache.access_token.is_some() && cache.expiry_time.is_some() => {
+            cache.is_expiring_within_minutes(window_minutes)
+        }
+        _ => false,
+    }
+}
+
+/// 第 `attempt` 次失败后的重试间隔：`base * 2^(attempt-1)` 加上 `[0, base)` 的抖动
+fn retry_delay(base_secs: u64, attempt: u32, seed: u64) -> Duration {
+    let base_ms = base_secs.max(1) * 1000;
+    let backoff_ms = base_ms.saturating_mul(1u64 << attempt.saturating_sub(1).min(10));
+    Duration::from_millis(backoff_ms + seed % base_ms)
+}
+
+/// 抖动种子：凭证 UUID 与当前时间混合，使不同凭证、不同轮次的重试时刻分散
+fn jitter_seed(uuid: &str) -> u64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    uuid.hash(&mut hasher);
+    Utc::now().timestamp_nanos_opt().hash(&mut hasher);
+    hasher.finish()
+}
+
+fn short_uuid(uuid: &str) -> &str {
+    uuid.get(..8).unwrap_or(uuid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_expiring_in(minutes: i64) -> CachedTokenInfo {
+        CachedTokenInfo {
+            access_token: Some("token".to_string()),
+            refresh_token: Some("refresh".to_string()),
+            expiry_time: Some(Utc::now() + chrono::Duration::minutes(minutes)),
+            last_refresh: Some(Utc::now()),
+            refresh_error_count: 0,
+            last_refresh_error: None,
+        }
+    }
+
+    #[test]
+    fn test_background_refresh_selects_expiring_oauth_tokens() {
+        let oauth = ProviderCredential::new(
+            PoolProviderType::Kiro,
+            CredentialData::KiroOAuth {
+                creds_file_path: "/tmp/kiro.json".to_string(),
+            },
+        );
+        assert!(is_due_for_background_refresh(
+            &oauth,
+            Some(&cache_expiring_in(10)),
+            15
+        ));
+        assert!(!is_due_for_background_refresh(
+            &oauth,
+            Some(&cache_expiring_in(60)),
+            15
+        ));
+        assert!(!is_due_for_background_refresh(&oauth, None, 15));
+
+        let mut disabled = oauth.clone();
+        disabled.is_disabled = true;
+        assert!(!is_due_for_background_refresh(
+            &disabled,
+            Some(&cache_expiring_in(1)),
+            15
+        ));
+
+        let api_key = ProviderCredential::new(
+            PoolProviderType::OpenAI,
+            CredentialData::OpenAIKey {
+                api_key: "sk-test".to_string(),
+                base_url: None,
+            },
+        );
+        assert!(!is_due_for_background_refresh(
+            &api_key,
+            Some(&cache_expiring_in(1)),
+            15
+        ));
+    }
+
+    #[test]
+    fn test_retry_delay_backs_off_with_bounded_jitter() {
+        assert_eq!(retry_delay(5, 1, 0), Duration::from_secs(5));
+        assert_eq!(retry_delay(5, 3, 0), Duration::from_secs(20));
+        let jittered = retry_delay(5, 2, u64::MAX);
+        assert!(jittered >= Duration::from_secs(10) && jittered < Duration::from_secs(15));
+    }
 }
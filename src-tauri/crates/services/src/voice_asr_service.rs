@@ -17,22 +17,53 @@
 //! - `ggml-small.bin` (~466MB)
 //! - `ggml-medium.bin` (~1.5GB)
 //!
+//! 模型文件可通过 `voice_core::WhisperModelManager` 下载（支持续传与 SHA-1 校验）。
+//!
 //! ## 使用示例
 //! ```rust,ignore
 //! let credential = AsrService::get_default_credential()?.unwrap();
 //! let text = AsrService::transcribe(&credential, &audio_data, 16000).await?;
 //! ```
+//!
+//! ## 流式识别
+//! `AsrService::transcribe_stream` 在识别过程中发送中间结果：讯飞使用 WebSocket 动态修正结果，
+//! 本地 Whisper 按块识别并发送累计文本，其他服务只发送最终结果。
+//!
+//! ## 模型实例缓存
+//! 本地 Whisper 实例按（模型大小, 语言）缓存并按 LRU 淘汰，识别时不再重复加载模型；
+//! 切换默认凭证时可通过 `AsrService::warm_up` 提前加载。
 
-#[cfg(feature = "local-whisper")]
 use std::path::PathBuf;
+#[cfg(feature = "local-whisper")]
+use std::sync::{Arc, OnceLock};
 
+use lime_core::config::{AsrCredentialEntry, AsrProviderType, WhisperModelSize};
 #[cfg(feature = "local-whisper")]
-use lime_core::config::WhisperModelSize;
-use lime_core::config::{AsrCredentialEntry, AsrProviderType};
+use lime_core::cpu_pool::{run_cpu_task, CpuTaskKind, CpuTaskPriority};
 
 use super::voice_config_service;
-use voice_core::asr_client::{AsrClient, BaiduClient, OpenAIWhisperClient, XunfeiClient};
-use voice_core::types::AudioData;
+use voice_core::asr_client::{
+    AsrClient, BaiduClient, OpenAIWhisperClient, PartialSender, XunfeiClient,
+};
+use voice_core::types::{AudioData, PartialTranscript, WhisperModel};
+#[cfg(feature = "local-whisper")]
+use voice_core::{ModelCache, WhisperCacheKey, WhisperTranscriber};
+
+/// 本地 Whisper 流式识别的分块时长（秒）
+#[cfg(feature = "local-whisper")]
+const WHISPER_STREAM_CHUNK_SECS: f32 = 5.0;
+
+/// 同时保留的本地 Whisper 实例数（medium 模型约占 1.5GB 内存）
+#[cfg(feature = "local-whisper")]
+const WHISPER_CACHE_CAPACITY: usize = 2;
+
+#[cfg(feature = "local-whisper")]
+static WHISPER_CACHE: OnceLock<ModelCache<WhisperCacheKey, WhisperTranscriber>> = OnceLock::new();
+
+#[cfg(feature = "local-whisper")]
+fn whisper_cache() -> &'static ModelCache<WhisperCacheKey, WhisperTranscriber> {
+    WHISPER_CACHE.get_or_init(|| ModelCache::new(WHISPER_CACHE_CAPACITY))
+}
 
 /// ASR 服务
 pub struct AsrService;
@@ -55,10 +86,36 @@ impl AsrService {
         credential: &AsrCredentialEntry,
         audio_data: &[u8],
         sample_rate: u32,
+    ) -> Result<String, String> {
+        Self::recognize(credential, audio_data, sample_rate, None).await
+    }
+
+    /// 使用指定凭证进行流式语音识别
+    ///
+    /// 识别过程中通过 `partials` 发送中间结果，成功后再发送一次最终结果。
+    /// 回退逻辑与 [`AsrService::transcribe`] 相同。
+    pub async fn transcribe_stream(
+        credential: &AsrCredentialEntry,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partials: PartialSender,
+    ) -> Result<String, String> {
+        let text = Self::recognize(credential, audio_data, sample_rate, Some(&partials)).await?;
+        let _ = partials.send(PartialTranscript::final_result(&text));
+        Ok(text)
+    }
+
+    /// 识别音频，云端失败时回退到本地 Whisper
+    async fn recognize(
+        credential: &AsrCredentialEntry,
+        audio_data: &[u8],
+        sample_rate: u32,
+        partials: Option<&PartialSender>,
     ) -> Result<String, String> {
         // 如果是本地 Whisper，直接调用
         if matches!(credential.provider, AsrProviderType::WhisperLocal) {
-            return Self::transcribe_whisper_local(credential, audio_data, sample_rate).await;
+            return Self::transcribe_whisper_local(credential, audio_data, sample_rate, partials)
+                .await;
         }
 
         // 云端服务：先尝试云端，失败则回退到本地 Whisper
@@ -70,7 +127,7 @@ impl AsrService {
                 Self::transcribe_baidu(credential, audio_data, sample_rate).await
             }
             AsrProviderType::Xunfei => {
-                Self::transcribe_xunfei(credential, audio_data, sample_rate).await
+                Self::transcribe_xunfei(credential, audio_data, sample_rate, partials).await
             }
             AsrProviderType::WhisperLocal => unreachable!(), // 已在上面处理
         };
@@ -92,8 +149,13 @@ impl AsrService {
         match Self::get_whisper_local_credential() {
             Ok(Some(whisper_credential)) => {
                 tracing::info!("正在使用本地 Whisper 进行回退识别...");
-                match Self::transcribe_whisper_local(&whisper_credential, audio_data, sample_rate)
-                    .await
+                match Self::transcribe_whisper_local(
+                    &whisper_credential,
+                    audio_data,
+                    sample_rate,
+                    partials,
+                )
+                .await
                 {
                     Ok(text) => {
                         tracing::info!("本地 Whisper 回退识别成功");
@@ -134,6 +196,7 @@ impl AsrService {
         credential: &AsrCredentialEntry,
         audio_data: &[u8],
         sample_rate: u32,
+        partials: Option<&PartialSender>,
     ) -> Result<String, String> {
         // 获取 Whisper 配置
         let whisper_config = credential
@@ -155,17 +218,95 @@ impl AsrService {
         // 转换模型大小枚举
         let model = Self::convert_model_size(&whisper_config.model);
 
-        // 创建 Whisper 识别器
-        let transcriber =
-            voice_core::WhisperTranscriber::new(model_path, model, &credential.language)
-                .map_err(|e| format!("Whisper 模型加载失败: {e}"))?;
+        // 模型加载与识别都是 CPU 密集型操作，放到 CPU 线程池执行，避免阻塞异步运行时
+        let language = credential.language.clone();
+        let partials = partials.cloned();
+        run_cpu_task(
+            CpuTaskPriority::High,
+            CpuTaskKind::Transcription,
+            move || -> Result<String, String> {
+                let transcriber = Self::load_whisper_transcriber(model_path, model, &language)?;
+
+                // 流式识别时分块识别，每块完成后发送累计文本
+                let result = match partials {
+                    Some(partials) => transcriber.transcribe_chunked(
+                        &audio,
+                        WHISPER_STREAM_CHUNK_SECS,
+                        |partial| {
+                            let _ = partials.send(partial);
+                        },
+                    ),
+                    None => transcriber.transcribe(&audio),
+                }
+                .map_err(|e| format!("Whisper 识别失败: {e}"))?;
 
-        // 执行识别
-        let result = transcriber
-            .transcribe(&audio)
-            .map_err(|e| format!("Whisper 识别失败: {e}"))?;
+                Ok(result.text)
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())?
+    }
 
-        Ok(result.text)
+    /// 获取缓存的 Whisper 实例，未命中时加载模型（CPU 密集，需在 CPU 线程池中调用）
+    #[cfg(feature = "local-whisper")]
+    fn load_whisper_transcriber(
+        model_path: PathBuf,
+        model: WhisperModel,
+        language: &str,
+    ) -> Result<Arc<WhisperTranscriber>, String> {
+        whisper_cache().get_or_try_load(&WhisperCacheKey::new(model, language), || {
+            tracing::info!("加载 Whisper 模型: {:?} ({})", model, language);
+            WhisperTranscriber::new(model_path, model, language)
+                .map_err(|e| format!("Whisper 模型加载失败: {e}"))
+        })
+    }
+
+    /// 预加载凭证使用的本地 Whisper 模型
+    ///
+    /// 非本地 Whisper 凭证或模型已缓存时返回 `false`。
+    #[cfg(feature = "local-whisper")]
+    pub async fn warm_up(credential: &AsrCredentialEntry) -> Result<bool, String> {
+        if !matches!(credential.provider, AsrProviderType::WhisperLocal) {
+            return Ok(false);
+        }
+        let whisper_config = credential
+            .whisper_config
+            .as_ref()
+            .ok_or("Whisper 本地配置缺失")?;
+        let model = Self::convert_model_size(&whisper_config.model);
+        let key = WhisperCacheKey::new(model, &credential.language);
+        if whisper_cache().contains(&key) {
+            return Ok(false);
+        }
+
+        let model_path = Self::get_whisper_model_path(&whisper_config.model)?;
+        let language = credential.language.clone();
+        run_cpu_task(
+            CpuTaskPriority::Low,
+            CpuTaskKind::Transcription,
+            move || Self::load_whisper_transcriber(model_path, model, &language).map(|_| true),
+        )
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    /// 预加载本地 Whisper 模型（未启用 local-whisper feature 时的 stub）
+    #[cfg(not(feature = "local-whisper"))]
+    pub async fn warm_up(_credential: &AsrCredentialEntry) -> Result<bool, String> {
+        Ok(false)
+    }
+
+    /// 移除指定模型的缓存实例（模型文件被删除或替换后调用）
+    pub fn evict_whisper_model(model: WhisperModel) -> usize {
+        #[cfg(feature = "local-whisper")]
+        {
+            whisper_cache().invalidate_model(model)
+        }
+        #[cfg(not(feature = "local-whisper"))]
+        {
+            let _ = model;
+            0
+        }
     }
 
     /// 本地 Whisper 识别（未启用 local-whisper feature 时的 stub）
@@ -174,13 +315,13 @@ impl AsrService {
         _credential: &AsrCredentialEntry,
         _audio_data: &[u8],
         _sample_rate: u32,
+        _partials: Option<&PartialSender>,
     ) -> Result<String, String> {
         Err("本地 Whisper 功能未启用。请使用云端 ASR 服务（OpenAI、百度、讯飞）".to_string())
     }
 
-    /// 获取 Whisper 模型文件路径
-    #[cfg(feature = "local-whisper")]
-    fn get_whisper_model_path(model_size: &WhisperModelSize) -> Result<PathBuf, String> {
+    /// Whisper 模型文件的存放路径（不检查文件是否存在）
+    pub fn whisper_model_path(model_size: &WhisperModelSize) -> Result<PathBuf, String> {
         // 模型文件名
         let filename = match model_size {
             WhisperModelSize::Tiny => "ggml-tiny.bin",
@@ -189,20 +330,29 @@ impl AsrService {
             WhisperModelSize::Medium => "ggml-medium.bin",
         };
 
-        // 模型存储目录：~/Library/Application Support/lime/models/whisper/
-        let models_dir = dirs::data_dir()
+        Ok(Self::whisper_models_dir()?.join(filename))
+    }
+
+    /// Whisper 模型存储目录：~/Library/Application Support/lime/models/whisper/
+    pub fn whisper_models_dir() -> Result<PathBuf, String> {
+        Ok(dirs::data_dir()
             .ok_or("无法获取数据目录")?
             .join("lime")
             .join("models")
-            .join("whisper");
+            .join("whisper"))
+    }
 
-        let model_path = models_dir.join(filename);
+    /// 获取 Whisper 模型文件路径
+    #[cfg(feature = "local-whisper")]
+    fn get_whisper_model_path(model_size: &WhisperModelSize) -> Result<PathBuf, String> {
+        let model_path = Self::whisper_model_path(model_size)?;
 
         // 检查模型文件是否存在
         if !model_path.exists() {
+            let models_dir = model_path.parent().unwrap_or(&model_path);
             return Err(format!(
-                "Whisper 模型文件不存在: {}\n请下载模型文件到: {}",
-                filename,
+                "Whisper 模型文件不存在: {}\n请在语音设置中下载模型，或手动下载到: {}",
+                model_path.file_name().unwrap_or_default().to_string_lossy(),
                 models_dir.display()
             ));
         }
@@ -271,6 +421,7 @@ impl AsrService {
         credential: &AsrCredentialEntry,
         audio_data: &[u8],
         sample_rate: u32,
+        partials: Option<&PartialSender>,
     ) -> Result<String, String> {
         let config = credential.xunfei_config.as_ref().ok_or("讯飞配置缺失")?;
         let audio = Self::build_audio_data(audio_data, sample_rate)?;
@@ -290,10 +441,11 @@ impl AsrService {
         )
         .with_language(xunfei_language);
 
-        let result = client
-            .transcribe(&audio)
-            .await
-            .map_err(|e| format!("讯飞识别失败: {e}"))?;
+        let result = match partials {
+            Some(partials) => client.transcribe_stream(&audio, partials.clone()).await,
+            None => client.transcribe(&audio).await,
+        }
+        .map_err(|e| format!("讯飞识别失败: {e}"))?;
 
         Ok(result.text)
     }
@@ -3,6 +3,7 @@
 //! 封装语音转写、润色、输出等可复用业务流程。
 
 use serde::{Deserialize, Serialize};
+use voice_core::asr_client::PartialSender;
 
 use super::voice_asr_service::AsrService;
 use super::voice_config_service;
@@ -28,10 +29,13 @@ pub struct PolishResult {
 }
 
 /// 执行语音识别
+///
+/// 传入 `partials` 时使用流式识别，识别过程中发送中间结果。
 pub async fn transcribe_audio(
     audio_data: &[u8],
     sample_rate: u32,
     credential_id: Option<&str>,
+    partials: Option<PartialSender>,
 ) -> Result<TranscribeResult, String> {
     tracing::info!(
         "[语音识别] 开始识别，音频大小: {} 字节，采样率: {}",
@@ -99,7 +103,12 @@ pub async fn transcribe_audio(
     let provider_name = voice_config_service::asr_provider_name(credential.provider);
     tracing::info!("[语音识别] 使用服务: {}", provider_name);
 
-    let text = AsrService::transcribe(&credential, audio_data, sample_rate).await?;
+    let text = match partials {
+        Some(partials) => {
+            AsrService::transcribe_stream(&credential, audio_data, sample_rate, partials).await?
+        }
+        None => AsrService::transcribe(&credential, audio_data, sample_rate).await?,
+    };
     tracing::info!("[语音识别] 识别完成，文本长度: {} 字符", text.len());
 
     Ok(TranscribeResult {
@@ -3,16 +3,33 @@
 //! 录音核心逻辑已迁移到 `voice-core` 的 `threaded_recorder` 模块。
 //! 本模块保留 Tauri State 包装和向后兼容导出路径。
 
+use lime_core::config::{VoiceVadConfig, VoiceVadMode};
 use parking_lot::Mutex;
 use std::sync::Arc;
 
-pub use voice_core::{AudioDeviceInfo, RecordingCommand, RecordingResponse, RecordingService};
+pub use voice_core::{
+    AudioDeviceInfo, RecordingCommand, RecordingResponse, RecordingService, VadConfig, VadMode,
+};
 
 /// 获取所有可用的麦克风设备
 pub fn list_audio_devices() -> Result<Vec<AudioDeviceInfo>, String> {
     voice_core::list_audio_devices().map_err(|e| e.to_string())
 }
 
+/// 将语音输入配置中的 VAD 配置转换为录音服务使用的配置
+pub fn vad_config_from(config: &VoiceVadConfig) -> VadConfig {
+    VadConfig {
+        enabled: config.enabled,
+        silence_threshold: config.silence_threshold.clamp(0.0, 1.0),
+        silence_duration_ms: config.silence_duration_ms,
+        min_speech_ms: config.min_speech_ms,
+        mode: match config.mode {
+            VoiceVadMode::AutoStop => VadMode::AutoStop,
+            VoiceVadMode::Segment => VadMode::Segment,
+        },
+    }
+}
+
 /// 全局录音服务状态（Tauri State 包装）
 pub struct RecordingServiceState(pub Arc<Mutex<RecordingService>>);
 
@@ -0,0 +1,226 @@
+//! 语音合成（朗读）服务
+//!
+//! 按 `config.voice` 的 TTS 设置合成并播放语音，用于朗读 Agent 回复：
+//! - `openai`：OpenAI TTS，凭证取自 API Key Provider（优先使用全局默认语音 Provider）
+//! - `edge`：edge-tts 兼容服务，地址取自 `voice.tts_endpoint`
+
+use lime_core::config::{MediaGenerationPreferenceConfig, VoiceConfig};
+use lime_core::database::dao::api_key_provider::ApiProviderType;
+use lime_core::database::DbConnection;
+use voice_core::tts::{EdgeTtsClient, OpenAITtsClient, TtsClient};
+use voice_core::types::AudioData;
+use voice_core::OutputHandler;
+
+use super::api_key_provider_service::ApiKeyProviderService;
+
+/// 单次朗读的最大字符数（OpenAI TTS 单次输入上限为 4096）
+pub const MAX_SPEECH_CHARS: usize = 4000;
+
+/// OpenAI TTS 凭证
+#[derive(Debug, Clone)]
+pub struct TtsCredential {
+    pub api_key: String,
+    pub api_host: String,
+    /// 全局默认语音模型
+    pub model: Option<String>,
+}
+
+/// 解析 OpenAI TTS 凭证
+///
+/// 配置了全局默认语音 Provider 时使用该 Provider，否则轮询任一 OpenAI 类型 Provider。
+pub fn resolve_openai_credential(
+    db: &DbConnection,
+    service: &ApiKeyProviderService,
+    preference: &MediaGenerationPreferenceConfig,
+) -> Result<Option<TtsCredential>, String> {
+    if let Some(provider_id) = preference.preferred_provider_id.as_deref() {
+        if let Some((api_key, provider)) =
+            service.get_next_api_key_with_provider_info(db, provider_id)?
+        {
+            return Ok(Some(TtsCredential {
+                api_key,
+                api_host: provider.api_host,
+                model: preference.preferred_model_id.clone(),
+            }));
+        }
+        if !preference.allow_fallback {
+            return Ok(None);
+        }
+    }
+
+    Ok(service
+        .get_next_api_key_by_type(db, ApiProviderType::Openai)?
+        .map(|(_, api_key, provider)| TtsCredential {
+            api_key,
+            api_host: provider.api_host,
+            model: None,
+        }))
+}
+
+/// 按语音配置创建 TTS 客户端
+///
+/// `voice` 为空时使用配置中的音色。
+pub fn build_tts_client(
+    config: &VoiceConfig,
+    voice: Option<&str>,
+    credential: Option<TtsCredential>,
+) -> Result<Box<dyn TtsClient>, String> {
+    let voice = voice
+        .or(config.tts_voice.as_deref())
+        .filter(|v| !v.trim().is_empty())
+        .map(str::to_string);
+    let speed = config.tts_rate.filter(|rate| *rate > 0.0);
+
+    match config.tts_service.as_deref().unwrap_or("openai") {
+        "openai" => {
+            let credential =
+                credential.ok_or("未找到可用的 OpenAI API Key，请先在 Provider 设置中添加")?;
+            let mut client = OpenAITtsClient::new(credential.api_key)
+                .with_host(openai_host(&credential.api_host));
+            if let Some(model) = credential.model {
+                client = client.with_model(model);
+            }
+            if let Some(voice) = voice {
+                client = client.with_voice(voice);
+            }
+            if let Some(speed) = speed {
+                client = client.with_speed(speed);
+            }
+            Ok(Box::new(client))
+        }
+        "edge" => {
+            let endpoint = config
+                .tts_endpoint
+                .as_deref()
+                .map(str::trim)
+                .filter(|e| !e.is_empty())
+                .ok_or("未配置 edge-tts 服务地址")?;
+            let mut client = EdgeTtsClient::new(endpoint.to_string());
+            if let Some(voice) = voice {
+                client = client.with_voice(voice);
+            }
+            if let Some(speed) = speed {
+                client = client.with_speed(speed);
+            }
+            Ok(Box::new(client))
+        }
+        other => Err(format!("暂不支持的 TTS 服务: {other}")),
+    }
+}
+
+/// OpenAI 客户端会自行拼接 `/v1`，去掉 Provider 地址末尾的 `/v1`
+fn openai_host(api_host: &str) -> String {
+    let host = api_host.trim().trim_end_matches('/');
+    host.strip_suffix("/v1").unwrap_or(host).to_string()
+}
+
+/// 将 Markdown 回复转换为适合朗读的纯文本
+///
+/// 去掉代码块、行内代码标记、链接地址、标题/列表/引用等标记，并截断到 [`MAX_SPEECH_CHARS`]。
+pub fn speakable_text(markdown: &str) -> String {
+    let mut lines = Vec::new();
+    let mut in_code_block = false;
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block || trimmed.is_empty() {
+            continue;
+        }
+        let content = trimmed
+            .trim_start_matches('#')
+            .trim_start_matches('>')
+            .trim_start();
+        let content = content
+            .strip_prefix("- ")
+            .or_else(|| content.strip_prefix("* "))
+            .unwrap_or(content);
+        lines.push(strip_inline_markup(content));
+    }
+
+    lines.join("\n").chars().take(MAX_SPEECH_CHARS).collect()
+}
+
+/// 去掉行内强调/代码标记，`[文本](链接)` 只保留文本
+fn strip_inline_markup(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' | '_' | '`' | '~' => {}
+            ']' if chars.peek() == Some(&'(') => {
+                for c in chars.by_ref() {
+                    if c == ')' {
+                        break;
+                    }
+                }
+            }
+            '[' => {}
+            _ => result.push(c),
+        }
+    }
+    result
+}
+
+/// 合成语音
+pub async fn synthesize(client: &dyn TtsClient, text: &str) -> Result<AudioData, String> {
+    let text = speakable_text(text);
+    if text.trim().is_empty() {
+        return Err("没有可朗读的文本".to_string());
+    }
+    tracing::info!(
+        "[语音合成] 使用 {} 合成 {} 字符",
+        client.name(),
+        text.chars().count()
+    );
+    client.synthesize(&text).await.map_err(|e| e.to_string())
+}
+
+/// 在阻塞线程中播放音频，播放完成后返回
+pub async fn play_audio(audio: AudioData) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || OutputHandler::play_audio(&audio))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speakable_text_strips_markdown() {
+        let markdown = "## 结果\n\n这是**重点**，见 [文档](https://example.com)。\n\n```rust\nfn main() {}\n```\n- 使用 `cargo test` 验证";
+        assert_eq!(
+            speakable_text(markdown),
+            "结果\n这是重点，见 文档。\n使用 cargo test 验证"
+        );
+    }
+
+    #[test]
+    fn test_build_client_requires_edge_endpoint() {
+        let config = VoiceConfig {
+            tts_service: Some("edge".to_string()),
+            ..VoiceConfig::default()
+        };
+        assert!(build_tts_client(&config, None, None).is_err());
+
+        let config = VoiceConfig {
+            tts_endpoint: Some("http://localhost:5050".to_string()),
+            ..config
+        };
+        let client = build_tts_client(&config, None, None).unwrap();
+        assert_eq!(client.name(), "Edge TTS");
+    }
+
+    #[test]
+    fn test_openai_host_strips_version_suffix() {
+        assert_eq!(
+            openai_host("https://api.openai.com/v1/"),
+            "https://api.openai.com"
+        );
+        assert_eq!(openai_host("https://proxy.local"), "https://proxy.local");
+    }
+}
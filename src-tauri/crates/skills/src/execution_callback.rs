@@ -3,7 +3,7 @@
 //! 定义 Skill 执行过程中的回调接口和事件数据类型。
 //! Tauri 实现（TauriExecutionCallback）留在主 crate。
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// 步骤开始事件 Payload
 #[derive(Debug, Clone, Serialize)]
@@ -15,12 +15,59 @@ pub struct StepStartPayload {
     pub total_steps: usize,
 }
 
+/// 单个步骤的模型用量
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StepUsage {
+    /// 执行该步骤的模型（未知时为空）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// 步骤耗时（毫秒，包含模型候选切换后的重试）
+    pub latency_ms: u64,
+    /// 估算费用（USD），模型没有定价时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+/// 一次执行的用量合计
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub latency_ms: u64,
+    /// 有定价步骤的费用之和，所有步骤都没有定价时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl ExecutionUsage {
+    /// 累加一个步骤的用量
+    pub fn add(&mut self, step: &StepUsage) {
+        self.input_tokens += step.input_tokens;
+        self.output_tokens += step.output_tokens;
+        self.latency_ms += step.latency_ms;
+        if let Some(cost) = step.cost_usd {
+            self.cost_usd = Some(self.cost_usd.unwrap_or(0.0) + cost);
+        }
+    }
+
+    pub fn from_steps<'a>(steps: impl IntoIterator<Item = &'a StepUsage>) -> Self {
+        let mut total = Self::default();
+        for step in steps {
+            total.add(step);
+        }
+        total
+    }
+}
+
 /// 步骤完成事件 Payload
 #[derive(Debug, Clone, Serialize)]
 pub struct StepCompletePayload {
     pub execution_id: String,
     pub step_id: String,
     pub output: String,
+    pub usage: StepUsage,
 }
 
 /// 步骤错误事件 Payload
@@ -39,6 +86,8 @@ pub struct ExecutionCompletePayload {
     pub success: bool,
     pub output: Option<String>,
     pub error: Option<String>,
+    /// 所有步骤的用量合计
+    pub usage: ExecutionUsage,
 }
 
 /// Tauri 事件名称常量
@@ -62,9 +111,45 @@ pub trait ExecutionCallback: Send + Sync {
         total_steps: usize,
     );
 
-    fn on_step_complete(&self, step_id: &str, output: &str);
+    fn on_step_complete(&self, step_id: &str, output: &str, usage: &StepUsage);
 
     fn on_step_error(&self, step_id: &str, error: &str, will_retry: bool);
 
-    fn on_complete(&self, success: bool, final_output: Option<&str>, error: Option<&str>);
+    fn on_complete(
+        &self,
+        success: bool,
+        final_output: Option<&str>,
+        error: Option<&str>,
+        usage: &ExecutionUsage,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_execution_usage_sums_steps() {
+        let steps = [
+            StepUsage {
+                model: Some("gpt-4o".to_string()),
+                input_tokens: 100,
+                output_tokens: 20,
+                latency_ms: 800,
+                cost_usd: Some(0.5),
+            },
+            StepUsage {
+                input_tokens: 50,
+                output_tokens: 10,
+                latency_ms: 200,
+                ..StepUsage::default()
+            },
+        ];
+
+        let total = ExecutionUsage::from_steps(&steps);
+        assert_eq!((total.input_tokens, total.output_tokens), (150, 30));
+        assert_eq!(total.latency_ms, 1000);
+        assert_eq!(total.cost_usd, Some(0.5));
+        assert_eq!(ExecutionUsage::from_steps(&steps[1..]).cost_usd, None);
+    }
 }
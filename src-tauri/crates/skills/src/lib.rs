@@ -8,6 +8,7 @@
 mod execution_callback;
 mod lime_llm_provider;
 mod llm_provider;
+mod output_processor;
 mod skill_loader;
 mod skill_matcher;
 
@@ -15,15 +16,19 @@ mod skill_matcher;
 pub mod ecommerce_review_reply;
 
 pub use execution_callback::{
-    events, ExecutionCallback, ExecutionCompletePayload, StepCompletePayload, StepErrorPayload,
-    StepStartPayload,
+    events, ExecutionCallback, ExecutionCompletePayload, ExecutionUsage, StepCompletePayload,
+    StepErrorPayload, StepStartPayload, StepUsage,
 };
 pub use lime_llm_provider::LimeLlmProvider;
-pub use llm_provider::{LlmProvider, SkillError};
+pub use llm_provider::{filter_allowed_tools, LlmProvider, SkillError, MAX_TOOL_ROUNDS};
+pub use output_processor::{
+    apply_output_processors, parse_output_processors, OutputProcessor, OutputProcessorError,
+    OUTPUT_PROCESSOR_ERROR_CODE, OUTPUT_PROCESSOR_STEP_ID,
+};
 pub use skill_loader::{
     find_skill_by_name, get_lime_skills_dir, get_project_skills_dir, get_skill_roots,
     load_skill_from_file, load_skills_from_directory, parse_allowed_tools, parse_boolean,
-    parse_skill_frontmatter, parse_workflow_steps, LoadedSkillDefinition, SkillFrontmatter,
-    SkillTriggerConfig, WorkflowStep,
+    parse_model_candidates, parse_skill_frontmatter, parse_workflow_steps, LoadedSkillDefinition,
+    SkillFrontmatter, SkillModelCandidate, SkillTriggerConfig, WorkflowStep,
 };
 pub use skill_matcher::{SkillMatch, SkillMatcher};
@@ -12,13 +12,19 @@ use lime_core::models::anthropic::AnthropicMessagesRequest;
 #[cfg(test)]
 use lime_core::models::provider_pool_model::PoolProviderType;
 use lime_core::models::provider_pool_model::{CredentialData, ProviderCredential};
+use lime_core::processor::current_deadline;
+use lime_mcp::tool_converter::{AnthropicToolUse, OpenAIFunctionCall, OpenAIToolCall};
+use lime_mcp::{McpManagerState, McpToolCall, McpToolDefinition, ToolConverter};
 use lime_providers::providers::claude_custom::ClaudeCustomProvider;
 use lime_providers::providers::kiro::KiroProvider;
 use lime_providers::providers::openai_custom::OpenAICustomProvider;
 use lime_services::api_key_provider_service::ApiKeyProviderService;
 use lime_services::provider_pool_service::ProviderPoolService;
 
-use crate::{LlmProvider, SkillError};
+use crate::{LlmProvider, SkillError, MAX_TOOL_ROUNDS};
+
+/// 未指定模型时使用的默认模型
+const DEFAULT_MODEL: &str = "claude-sonnet-4-5-20250514";
 
 /// Lime LLM Provider
 ///
@@ -106,6 +112,72 @@ impl LimeLlmProvider {
         }
     }
 
+    /// 使用 ProviderPoolService 选择凭证，优先偏好的 Provider 类型
+    async fn select_credential(&self, model_name: &str) -> Result<ProviderCredential, SkillError> {
+        // 确定要使用的 provider 类型
+        let provider_type = self.preferred_provider.as_deref().unwrap_or("claude"); // 默认使用 Claude
+
+        tracing::info!(
+            "[LimeLlmProvider] chat 调用: provider_type={}, model={}",
+            provider_type,
+            model_name
+        );
+
+        let credential = self
+            .pool_service
+            .select_credential_with_fallback(
+                &self.db,
+                &self.api_key_service,
+                provider_type,
+                Some(model_name),
+                None, // provider_id_hint
+                None, // client_type
+                None, // session_id
+            )
+            .await
+            .map_err(|e| SkillError::ProviderError(format!("选择凭证失败: {}", e)))?
+            .ok_or_else(|| {
+                // Requirements 1.5: 没有可用凭证时返回 ProviderError
+                SkillError::ProviderError(format!(
+                    "没有可用的凭证: provider_type={}, model={}",
+                    provider_type, model_name
+                ))
+            })?;
+
+        tracing::info!(
+            "[LimeLlmProvider] 选中凭证: uuid={}, type={:?}",
+            &credential.uuid[..8],
+            credential.provider_type
+        );
+
+        Ok(credential)
+    }
+
+    /// 记录凭证使用情况与健康状态
+    fn record_result(
+        &self,
+        credential: &ProviderCredential,
+        model_name: &str,
+        result: &Result<String, SkillError>,
+    ) {
+        // 记录使用情况
+        match result {
+            Ok(_) => {
+                let _ = self.pool_service.record_usage(&self.db, &credential.uuid);
+                let _ =
+                    self.pool_service
+                        .mark_healthy(&self.db, &credential.uuid, Some(model_name));
+            }
+            Err(e) => {
+                let _ = self.pool_service.mark_unhealthy(
+                    &self.db,
+                    &credential.uuid,
+                    Some(&e.to_string()),
+                );
+            }
+        }
+    }
+
     /// 根据凭证调用 LLM API
     ///
     /// # Arguments
@@ -368,6 +440,187 @@ impl LimeLlmProvider {
 
         Ok(content.to_string())
     }
+
+    /// 带工具调用的对话，按凭证类型选择 Anthropic 或 OpenAI 协议
+    async fn call_llm_with_tools(
+        &self,
+        credential: &ProviderCredential,
+        system_prompt: &str,
+        user_message: &str,
+        model: &str,
+        tools: &[McpToolDefinition],
+        mcp: &McpManagerState,
+    ) -> Result<String, SkillError> {
+        match &credential.credential {
+            CredentialData::ClaudeKey { api_key, base_url }
+            | CredentialData::AnthropicKey { api_key, base_url } => {
+                let claude = ClaudeCustomProvider::with_config(api_key.clone(), base_url.clone());
+                self.claude_tool_loop(&claude, system_prompt, user_message, model, tools, mcp)
+                    .await
+            }
+            CredentialData::OpenAIKey { api_key, base_url } => {
+                let openai = OpenAICustomProvider::with_config(api_key.clone(), base_url.clone());
+                self.openai_tool_loop(&openai, system_prompt, user_message, model, tools, mcp)
+                    .await
+            }
+            _ => Err(SkillError::ProviderError(format!(
+                "凭证类型不支持工具调用: {:?}",
+                credential.provider_type
+            ))),
+        }
+    }
+
+    /// Anthropic Messages API 工具调用循环
+    async fn claude_tool_loop(
+        &self,
+        claude: &ClaudeCustomProvider,
+        system_prompt: &str,
+        user_message: &str,
+        model: &str,
+        tools: &[McpToolDefinition],
+        mcp: &McpManagerState,
+    ) -> Result<String, SkillError> {
+        // 未开启对应 beta 时 Messages API 不接受 input_examples / allowed_callers
+        let anthropic_tools: Vec<_> = ToolConverter::to_anthropic(tools)
+            .into_iter()
+            .map(|mut tool| {
+                tool.input_examples = None;
+                tool.allowed_callers = None;
+                tool
+            })
+            .collect();
+        let mut messages = vec![serde_json::json!({ "role": "user", "content": user_message })];
+
+        for _ in 0..MAX_TOOL_ROUNDS {
+            ensure_within_deadline()?;
+            let request = serde_json::json!({
+                "model": model,
+                "max_tokens": 4096,
+                "system": system_prompt,
+                "messages": messages,
+                "tools": anthropic_tools,
+            });
+            let resp = claude
+                .messages(&request)
+                .await
+                .map_err(|e| SkillError::ProviderError(format!("Claude API 调用失败: {}", e)))?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                return Err(SkillError::ProviderError(format!(
+                    "Claude API 返回错误: status={}, body={}",
+                    status, body
+                )));
+            }
+            let json: serde_json::Value = resp
+                .json()
+                .await
+                .map_err(|e| SkillError::ProviderError(format!("解析响应失败: {}", e)))?;
+
+            let content = json["content"].as_array().cloned().unwrap_or_default();
+            let tool_uses: Vec<AnthropicToolUse> = content
+                .iter()
+                .filter(|block| block["type"] == "tool_use")
+                .filter_map(|block| serde_json::from_value(block.clone()).ok())
+                .collect();
+            if tool_uses.is_empty() {
+                return Ok(content
+                    .iter()
+                    .filter_map(|block| block["text"].as_str())
+                    .collect::<Vec<_>>()
+                    .join(""));
+            }
+
+            let mut results = Vec::with_capacity(tool_uses.len());
+            for tool_use in &tool_uses {
+                let call = ToolConverter::from_anthropic_use(tool_use);
+                let (output, is_error) = call_mcp_tool(mcp, tools, call).await;
+                results.push(serde_json::json!({
+                    "type": "tool_result",
+                    "tool_use_id": tool_use.id,
+                    "content": output,
+                    "is_error": is_error,
+                }));
+            }
+            messages.push(serde_json::json!({ "role": "assistant", "content": content }));
+            messages.push(serde_json::json!({ "role": "user", "content": results }));
+        }
+
+        Err(SkillError::ExecutionError(format!(
+            "工具调用超过 {} 轮仍未结束",
+            MAX_TOOL_ROUNDS
+        )))
+    }
+
+    /// OpenAI Chat Completions 工具调用循环
+    async fn openai_tool_loop(
+        &self,
+        openai: &OpenAICustomProvider,
+        system_prompt: &str,
+        user_message: &str,
+        model: &str,
+        tools: &[McpToolDefinition],
+        mcp: &McpManagerState,
+    ) -> Result<String, SkillError> {
+        let openai_tools = ToolConverter::to_openai(tools);
+        let mut messages = vec![
+            serde_json::json!({ "role": "system", "content": system_prompt }),
+            serde_json::json!({ "role": "user", "content": user_message }),
+        ];
+
+        for _ in 0..MAX_TOOL_ROUNDS {
+            ensure_within_deadline()?;
+            let request = serde_json::json!({
+                "model": model,
+                "max_tokens": 4096,
+                "stream": false,
+                "messages": messages,
+                "tools": openai_tools,
+            });
+            let resp = openai
+                .chat_completions(&request)
+                .await
+                .map_err(|e| SkillError::ProviderError(format!("OpenAI API 调用失败: {}", e)))?;
+            if !resp.status().is_success() {
+                let status = resp.status();
+                let body = resp.text().await.unwrap_or_default();
+                return Err(SkillError::ProviderError(format!(
+                    "OpenAI API 返回错误: status={}, body={}",
+                    status, body
+                )));
+            }
+            let json: serde_json::Value = resp
+                .json()
+                .await
+                .map_err(|e| SkillError::ProviderError(format!("解析响应失败: {}", e)))?;
+
+            let message = json["choices"][0]["message"].clone();
+            let tool_calls = parse_openai_tool_calls(&message);
+            if tool_calls.is_empty() {
+                return Ok(message["content"].as_str().unwrap_or("").to_string());
+            }
+
+            messages.push(serde_json::json!({
+                "role": "assistant",
+                "content": message["content"],
+                "tool_calls": tool_calls,
+            }));
+            for tool_call in &tool_calls {
+                let call = ToolConverter::from_openai_call(tool_call);
+                let (output, _) = call_mcp_tool(mcp, tools, call).await;
+                messages.push(serde_json::json!({
+                    "role": "tool",
+                    "tool_call_id": tool_call.id,
+                    "content": output,
+                }));
+            }
+        }
+
+        Err(SkillError::ExecutionError(format!(
+            "工具调用超过 {} 轮仍未结束",
+            MAX_TOOL_ROUNDS
+        )))
+    }
 }
 
 #[async_trait]
@@ -391,71 +644,118 @@ impl LlmProvider for LimeLlmProvider {
         user_message: &str,
         model: Option<&str>,
     ) -> Result<String, SkillError> {
-        // 确定要使用的 provider 类型
-        let provider_type = self.preferred_provider.as_deref().unwrap_or("claude"); // 默认使用 Claude
-
         // 确定要使用的模型
-        let model_name = model.unwrap_or("claude-sonnet-4-5-20250514");
+        let model_name = model.unwrap_or(DEFAULT_MODEL);
 
-        tracing::info!(
-            "[LimeLlmProvider] chat 调用: provider_type={}, model={}",
-            provider_type,
-            model_name
-        );
-
-        // 使用 ProviderPoolService 选择凭证（Requirements 1.2, 1.3）
-        let credential = self
-            .pool_service
-            .select_credential_with_fallback(
-                &self.db,
-                &self.api_key_service,
-                provider_type,
-                Some(model_name),
-                None, // provider_id_hint
-                None, // client_type
-            )
-            .await
-            .map_err(|e| SkillError::ProviderError(format!("选择凭证失败: {}", e)))?
-            .ok_or_else(|| {
-                // Requirements 1.5: 没有可用凭证时返回 ProviderError
-                SkillError::ProviderError(format!(
-                    "没有可用的凭证: provider_type={}, model={}",
-                    provider_type, model_name
-                ))
-            })?;
-
-        tracing::info!(
-            "[LimeLlmProvider] 选中凭证: uuid={}, type={:?}",
-            &credential.uuid[..8],
-            credential.provider_type
-        );
+        // 使用 ProviderPoolService 选择凭证（Requirements 1.2, 1.3, 1.5）
+        let credential = self.select_credential(model_name).await?;
 
         // 调用 LLM API（Requirements 1.4: 传递 model 参数）
         let result = self
             .call_llm_with_credential(&credential, system_prompt, user_message, model_name)
             .await;
 
-        // 记录使用情况
-        match &result {
-            Ok(_) => {
-                let _ = self.pool_service.record_usage(&self.db, &credential.uuid);
-                let _ =
-                    self.pool_service
-                        .mark_healthy(&self.db, &credential.uuid, Some(model_name));
-            }
-            Err(e) => {
-                let _ = self.pool_service.mark_unhealthy(
-                    &self.db,
-                    &credential.uuid,
-                    Some(&e.to_string()),
-                );
-            }
+        self.record_result(&credential, model_name, &result);
+
+        result
+    }
+
+    /// 带工具调用的对话
+    ///
+    /// 支持 Claude / Anthropic / OpenAI API Key 凭证；工具调用经 `McpClientManager` 执行，
+    /// 模型请求未提供的工具时返回错误结果而不执行。
+    async fn chat_with_tools(
+        &self,
+        system_prompt: &str,
+        user_message: &str,
+        model: Option<&str>,
+        tools: &[McpToolDefinition],
+        mcp: &McpManagerState,
+    ) -> Result<String, SkillError> {
+        if tools.is_empty() {
+            return self.chat(system_prompt, user_message, model).await;
         }
 
+        let model_name = model.unwrap_or(DEFAULT_MODEL);
+        let credential = self.select_credential(model_name).await?;
+        let result = self
+            .call_llm_with_tools(
+                &credential,
+                system_prompt,
+                user_message,
+                model_name,
+                tools,
+                mcp,
+            )
+            .await;
+
+        self.record_result(&credential, model_name, &result);
+
         result
     }
 }
 
+/// 从 OpenAI 响应消息中提取工具调用（兼容缺少 `type` 字段的实现）
+fn parse_openai_tool_calls(message: &serde_json::Value) -> Vec<OpenAIToolCall> {
+    message["tool_calls"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|call| {
+            let name = call["function"]["name"].as_str()?;
+            Some(OpenAIToolCall {
+                id: call["id"].as_str().unwrap_or_default().to_string(),
+                call_type: "function".to_string(),
+                function: OpenAIFunctionCall {
+                    name: name.to_string(),
+                    arguments: call["function"]["arguments"]
+                        .as_str()
+                        .unwrap_or("{}")
+                        .to_string(),
+                },
+            })
+        })
+        .collect()
+}
+
+/// 在请求截止时间作用域内时，每轮工具调用前确认仍有剩余时间
+fn ensure_within_deadline() -> Result<(), SkillError> {
+    match current_deadline() {
+        Some(deadline) if deadline.is_exceeded() => Err(SkillError::ExecutionError(
+            "请求已超过截止时间，停止工具调用".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// 执行工具调用，返回 (结果文本, 是否出错)
+///
+/// 只允许调用本次提供给模型的工具，避免模型绕过 Skill 的 allowed-tools 限制。
+async fn call_mcp_tool(
+    mcp: &McpManagerState,
+    tools: &[McpToolDefinition],
+    call: McpToolCall,
+) -> (String, bool) {
+    if !tools.iter().any(|tool| tool.name == call.name) {
+        return (format!("工具不可用: {}", call.name), true);
+    }
+
+    tracing::info!("[LimeLlmProvider] 调用工具: {}", call.name);
+    let result = mcp.lock().await.call_tool(&call.name, call.arguments).await;
+    match result {
+        Ok(result) => (
+            result
+                .content
+                .iter()
+                .map(|content| content.to_text())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            result.is_error,
+        ),
+        Err(e) => (e.to_string(), true),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -525,6 +825,28 @@ mod tests {
         assert_eq!(LimeLlmProvider::map_skill_provider_to_pool_type(""), None);
     }
 
+    #[test]
+    fn test_parse_openai_tool_calls() {
+        let message = serde_json::json!({
+            "content": null,
+            "tool_calls": [
+                {
+                    "id": "call_1",
+                    "function": { "name": "search_docs", "arguments": "{\"query\":\"rust\"}" }
+                },
+                { "id": "call_2", "function": {} }
+            ]
+        });
+        let calls = parse_openai_tool_calls(&message);
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].id, "call_1");
+        assert_eq!(
+            ToolConverter::from_openai_call(&calls[0]).arguments,
+            serde_json::json!({ "query": "rust" })
+        );
+        assert!(parse_openai_tool_calls(&serde_json::json!({ "content": "done" })).is_empty());
+    }
+
     #[test]
     fn test_skill_error_display() {
         let provider_err = SkillError::ProviderError("没有可用凭证".to_string());
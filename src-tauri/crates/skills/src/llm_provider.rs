@@ -4,8 +4,12 @@
 //! 具体实现（LimeLlmProvider）留在主 crate。
 
 use async_trait::async_trait;
+use lime_mcp::{McpManagerState, McpToolDefinition};
 use serde::{Deserialize, Serialize};
 
+/// 工具调用循环的最大轮数，防止模型反复调用工具不收敛
+pub const MAX_TOOL_ROUNDS: usize = 10;
+
 /// Skill 执行错误类型
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SkillError {
@@ -38,4 +42,66 @@ pub trait LlmProvider: Send + Sync {
         user_message: &str,
         model: Option<&str>,
     ) -> Result<String, SkillError>;
+
+    /// 带工具调用的对话
+    ///
+    /// 将 MCP 工具定义提供给模型，模型返回工具调用时经 `McpClientManager` 执行并回传结果，
+    /// 直到模型给出最终回复（最多 `MAX_TOOL_ROUNDS` 轮）。
+    /// 默认实现不支持工具：工具列表为空时退化为 `chat`，否则返回 ConfigError。
+    async fn chat_with_tools(
+        &self,
+        system_prompt: &str,
+        user_message: &str,
+        model: Option<&str>,
+        tools: &[McpToolDefinition],
+        mcp: &McpManagerState,
+    ) -> Result<String, SkillError> {
+        let _ = mcp;
+        if tools.is_empty() {
+            return self.chat(system_prompt, user_message, model).await;
+        }
+        Err(SkillError::ConfigError(
+            "当前 LLM Provider 不支持工具调用".to_string(),
+        ))
+    }
+}
+
+/// 按 Skill 声明的 `allowed-tools` 过滤可用工具，未声明时不提供任何工具
+pub fn filter_allowed_tools(
+    tools: Vec<McpToolDefinition>,
+    allowed_tools: Option<&[String]>,
+) -> Vec<McpToolDefinition> {
+    let Some(allowed) = allowed_tools else {
+        return Vec::new();
+    };
+    tools
+        .into_iter()
+        .filter(|tool| allowed.iter().any(|name| name == &tool.name))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str) -> McpToolDefinition {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "description": "",
+            "input_schema": { "type": "object" },
+            "server_name": "test",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_filter_allowed_tools() {
+        let tools = || vec![tool("read_file"), tool("write_file")];
+        assert!(filter_allowed_tools(tools(), None).is_empty());
+
+        let allowed = vec!["read_file".to_string()];
+        let filtered = filter_allowed_tools(tools(), Some(&allowed));
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].name, "read_file");
+    }
 }
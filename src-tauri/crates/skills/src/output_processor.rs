@@ -0,0 +1,203 @@
+//! Skill 输出后处理器
+//!
+//! 在 Skill 最后一步完成后，按 `metadata.lime_output_processors` 声明的顺序
+//! 对最终输出做格式化或校验，例如提取代码块、校验 JSON、正则捕获、模板渲染。
+
+use serde::{Deserialize, Serialize};
+
+/// 后处理失败时使用的步骤 ID
+pub const OUTPUT_PROCESSOR_STEP_ID: &str = "postprocess";
+
+/// 后处理失败的错误码（与 `<code>|<message>` 错误格式约定一致）
+pub const OUTPUT_PROCESSOR_ERROR_CODE: &str = "skill_postprocess_failed";
+
+/// 输出后处理器定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutputProcessor {
+    /// 提取第一个代码块（可指定语言）
+    ExtractCodeBlock {
+        #[serde(default)]
+        language: Option<String>,
+    },
+    /// 校验输出为合法 JSON，并输出格式化后的 JSON
+    ValidateJson,
+    /// 正则捕获，输出指定分组（默认第 1 组，无分组时取整体匹配）
+    RegexCapture {
+        pattern: String,
+        #[serde(default)]
+        group: Option<usize>,
+    },
+    /// 模板渲染，`{{output}}` 替换为当前输出
+    Template { template: String },
+}
+
+impl OutputProcessor {
+    fn kind(&self) -> &'static str {
+        match self {
+            OutputProcessor::ExtractCodeBlock { .. } => "extract_code_block",
+            OutputProcessor::ValidateJson => "validate_json",
+            OutputProcessor::RegexCapture { .. } => "regex_capture",
+            OutputProcessor::Template { .. } => "template",
+        }
+    }
+
+    fn apply(&self, input: &str) -> Result<String, String> {
+        match self {
+            OutputProcessor::ExtractCodeBlock { language } => {
+                extract_code_block(input, language.as_deref()).ok_or_else(|| match language {
+                    Some(lang) => format!("输出中未找到 {lang} 代码块"),
+                    None => "输出中未找到代码块".to_string(),
+                })
+            }
+            OutputProcessor::ValidateJson => {
+                let value: serde_json::Value = serde_json::from_str(input.trim())
+                    .map_err(|e| format!("输出不是合法 JSON: {e}"))?;
+                serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+            }
+            OutputProcessor::RegexCapture { pattern, group } => {
+                let re = regex::Regex::new(pattern).map_err(|e| format!("正则表达式无效: {e}"))?;
+                let captures = re
+                    .captures(input)
+                    .ok_or_else(|| format!("输出未匹配正则: {pattern}"))?;
+                let index = group.unwrap_or(if captures.len() > 1 { 1 } else { 0 });
+                captures
+                    .get(index)
+                    .map(|m| m.as_str().to_string())
+                    .ok_or_else(|| format!("正则分组 {index} 不存在"))
+            }
+            OutputProcessor::Template { template } => Ok(template.replace("{{output}}", input)),
+        }
+    }
+}
+
+/// 后处理错误
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputProcessorError {
+    /// 失败的处理器序号（从 1 开始）
+    pub index: usize,
+    /// 处理器类型
+    pub kind: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for OutputProcessorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "输出后处理失败（#{} {}）: {}",
+            self.index, self.kind, self.message
+        )
+    }
+}
+
+impl std::error::Error for OutputProcessorError {}
+
+/// 解析 frontmatter 中的后处理器声明（JSON 数组）
+pub fn parse_output_processors(value: Option<&str>) -> Result<Vec<OutputProcessor>, String> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(json) => serde_json::from_str::<Vec<OutputProcessor>>(json)
+            .map_err(|e| format!("lime_output_processors 解析失败: {e}")),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// 依次执行后处理器
+pub fn apply_output_processors(
+    processors: &[OutputProcessor],
+    output: &str,
+) -> Result<String, OutputProcessorError> {
+    let mut current = output.to_string();
+    for (idx, processor) in processors.iter().enumerate() {
+        current = processor
+            .apply(&current)
+            .map_err(|message| OutputProcessorError {
+                index: idx + 1,
+                kind: processor.kind().to_string(),
+                message,
+            })?;
+    }
+    Ok(current)
+}
+
+fn extract_code_block(input: &str, language: Option<&str>) -> Option<String> {
+    let re = regex::Regex::new(r"(?s)```([^\n`]*)\n(.*?)```").unwrap();
+    re.captures_iter(input).find_map(|captures| {
+        let info = captures.get(1).map(|m| m.as_str().trim()).unwrap_or("");
+        let matches = match language {
+            Some(lang) => info.eq_ignore_ascii_case(lang.trim()),
+            None => true,
+        };
+        matches.then(|| {
+            captures
+                .get(2)
+                .map(|m| m.as_str().trim_end_matches('\n').to_string())
+                .unwrap_or_default()
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_output_processors() {
+        let processors = parse_output_processors(Some(
+            r#"[{"type":"extract_code_block","language":"json"},{"type":"validate_json"}]"#,
+        ))
+        .unwrap();
+        assert_eq!(
+            processors,
+            vec![
+                OutputProcessor::ExtractCodeBlock {
+                    language: Some("json".to_string())
+                },
+                OutputProcessor::ValidateJson,
+            ]
+        );
+        assert!(parse_output_processors(None).unwrap().is_empty());
+        assert!(parse_output_processors(Some("not json")).is_err());
+    }
+
+    #[test]
+    fn test_extract_code_block_then_validate_json() {
+        let output = "说明文字\n```python\nprint(1)\n```\n```json\n{\"a\": 1}\n```";
+        let processors = vec![
+            OutputProcessor::ExtractCodeBlock {
+                language: Some("json".to_string()),
+            },
+            OutputProcessor::ValidateJson,
+        ];
+        let result = apply_output_processors(&processors, output).unwrap();
+        assert_eq!(result, "{\n  \"a\": 1\n}");
+    }
+
+    #[test]
+    fn test_regex_capture_and_template() {
+        let processors = vec![
+            OutputProcessor::RegexCapture {
+                pattern: r"标题[:：]\s*(.+)".to_string(),
+                group: None,
+            },
+            OutputProcessor::Template {
+                template: "# {{output}}".to_string(),
+            },
+        ];
+        let result = apply_output_processors(&processors, "标题：春日随笔\n正文...").unwrap();
+        assert_eq!(result, "# 春日随笔");
+    }
+
+    #[test]
+    fn test_failure_reports_processor_index() {
+        let processors = vec![
+            OutputProcessor::Template {
+                template: "{{output}}".to_string(),
+            },
+            OutputProcessor::ValidateJson,
+        ];
+        let error = apply_output_processors(&processors, "plain text").unwrap_err();
+        assert_eq!(error.index, 2);
+        assert_eq!(error.kind, "validate_json");
+    }
+}
@@ -13,6 +13,8 @@ use lime_core::models::{
 use lime_services::skill_service::SkillService;
 use serde::{Deserialize, Serialize};
 
+use crate::output_processor::{parse_output_processors, OutputProcessor};
+
 /// Skill 自动触发条件配置
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SkillTriggerConfig {
@@ -46,6 +48,18 @@ fn default_step_execution_mode() -> String {
     "prompt".to_string()
 }
 
+/// Skill 的 Provider / 模型候选
+///
+/// frontmatter 的 `model` 可以是单个模型，也可以是按优先级排列的列表，
+/// 列表项为模型名或 `{ provider, model }` 对象。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillModelCandidate {
+    /// 未指定时由执行器根据 Skill 的 `provider` 或模型名推断
+    #[serde(default)]
+    pub provider: Option<String>,
+    pub model: String,
+}
+
 /// Skill 前置元数据
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SkillFrontmatter {
@@ -59,6 +73,9 @@ pub struct SkillFrontmatter {
     pub when_to_use: Option<String>,
     pub version: Option<String>,
     pub model: Option<String>,
+    /// 按优先级排列的模型候选（首项与 `model` 一致）
+    #[serde(default)]
+    pub model_candidates: Vec<SkillModelCandidate>,
     pub provider: Option<String>,
     pub disable_model_invocation: Option<String>,
     pub execution_mode: Option<String>,
@@ -85,12 +102,16 @@ pub struct LoadedSkillDefinition {
     /// 结构化的自动触发条件配置
     pub when_to_use_config: Option<SkillTriggerConfig>,
     pub model: Option<String>,
+    /// 按优先级排列的模型候选，执行器在 Provider 出错时依次切换
+    pub model_candidates: Vec<SkillModelCandidate>,
     pub provider: Option<String>,
     pub disable_model_invocation: bool,
     pub execution_mode: String,
     pub workflow_ref: Option<String>,
     /// Workflow 步骤定义（仅 execution_mode == "workflow" 时有效）
     pub workflow_steps: Vec<WorkflowStep>,
+    /// 最终输出后处理器（metadata.lime_output_processors）
+    pub output_processors: Vec<OutputProcessor>,
     pub standard_compliance: SkillStandardCompliance,
 }
 
@@ -136,10 +157,14 @@ fn build_skill_frontmatter_from_manifest(parsed: &ParsedSkillManifest) -> SkillF
         .cloned()
         .or_else(|| parsed.raw_string("when-to-use"))
         .or_else(|| parsed.raw_string("when_to_use"));
-    let model = metadata
+    let model_candidates = metadata
         .get("lime_model_preference")
-        .cloned()
-        .or_else(|| parsed.raw_string("model"));
+        .map(|value| parse_model_candidates_csv(value))
+        .or_else(|| parsed.raw_value("model").map(parse_model_candidates))
+        .unwrap_or_default();
+    let model = model_candidates
+        .first()
+        .map(|candidate| candidate.model.clone());
     let provider = metadata
         .get("lime_provider_preference")
         .cloned()
@@ -174,6 +199,7 @@ fn build_skill_frontmatter_from_manifest(parsed: &ParsedSkillManifest) -> SkillF
         when_to_use,
         version,
         model,
+        model_candidates,
         provider,
         disable_model_invocation,
         execution_mode,
@@ -184,6 +210,47 @@ fn build_skill_frontmatter_from_manifest(parsed: &ParsedSkillManifest) -> SkillF
     }
 }
 
+/// 解析 `model` 字段：字符串（可用逗号分隔多个）、字符串列表或 `{ provider, model }` 列表
+pub fn parse_model_candidates(value: &serde_yaml::Value) -> Vec<SkillModelCandidate> {
+    match value {
+        serde_yaml::Value::String(value) => parse_model_candidates_csv(value),
+        serde_yaml::Value::Sequence(items) => items
+            .iter()
+            .filter_map(|item| match item {
+                serde_yaml::Value::String(model) => model_candidate(None, model),
+                serde_yaml::Value::Mapping(mapping) => {
+                    let field = |key: &str| {
+                        mapping
+                            .get(serde_yaml::Value::String(key.to_string()))
+                            .and_then(|value| value.as_str())
+                    };
+                    model_candidate(field("provider"), field("model")?)
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn parse_model_candidates_csv(value: &str) -> Vec<SkillModelCandidate> {
+    value
+        .split(',')
+        .filter_map(|model| model_candidate(None, model))
+        .collect()
+}
+
+fn model_candidate(provider: Option<&str>, model: &str) -> Option<SkillModelCandidate> {
+    let model = model.trim();
+    (!model.is_empty()).then(|| SkillModelCandidate {
+        provider: provider
+            .map(str::trim)
+            .filter(|provider| !provider.is_empty())
+            .map(str::to_string),
+        model: model.to_string(),
+    })
+}
+
 pub fn parse_allowed_tools(value: Option<&str>) -> Option<Vec<String>> {
     value.and_then(|v| {
         if v.is_empty() {
@@ -329,6 +396,21 @@ pub fn load_skill_from_file(
         execution_mode = "workflow".to_string();
     }
 
+    let output_processors = parse_output_processors(
+        frontmatter
+            .metadata
+            .get("lime_output_processors")
+            .map(|value| value.as_str()),
+    )
+    .unwrap_or_else(|error| {
+        tracing::warn!(
+            "[load_skill_from_file] 忽略无效的输出后处理器: skill={}, error={}",
+            skill_name,
+            error
+        );
+        Vec::new()
+    });
+
     let when_to_use_config = frontmatter
         .when_to_use
         .as_deref()
@@ -346,11 +428,13 @@ pub fn load_skill_from_file(
         when_to_use: frontmatter.when_to_use,
         when_to_use_config,
         model: frontmatter.model,
+        model_candidates: frontmatter.model_candidates,
         provider: frontmatter.provider,
         disable_model_invocation,
         execution_mode,
         workflow_ref: frontmatter.workflow_ref,
         workflow_steps,
+        output_processors,
         standard_compliance: inspection.standard_compliance,
     })
 }
@@ -432,7 +516,10 @@ pub fn find_skill_by_name(skill_name: &str) -> Result<LoadedSkillDefinition, Str
 
 #[cfg(test)]
 mod tests {
-    use super::{load_skill_from_file, load_skills_from_directory};
+    use super::{
+        load_skill_from_file, load_skills_from_directory, parse_skill_frontmatter,
+        SkillModelCandidate,
+    };
     use tempfile::TempDir;
 
     #[test]
@@ -467,6 +554,54 @@ metadata:
         assert!(skill.workflow_steps.is_empty());
     }
 
+    #[test]
+    fn parse_skill_frontmatter_should_read_model_fallback_list() {
+        let (frontmatter, _) = parse_skill_frontmatter(
+            r#"---
+name: fallback-skill
+description: Fallback skill
+model:
+  - claude-sonnet
+  - provider: openai
+    model: gpt-4o
+  - qwen3-coder-plus
+---
+Body
+"#,
+        );
+
+        assert_eq!(frontmatter.model.as_deref(), Some("claude-sonnet"));
+        assert_eq!(
+            frontmatter.model_candidates,
+            vec![
+                SkillModelCandidate {
+                    provider: None,
+                    model: "claude-sonnet".to_string(),
+                },
+                SkillModelCandidate {
+                    provider: Some("openai".to_string()),
+                    model: "gpt-4o".to_string(),
+                },
+                SkillModelCandidate {
+                    provider: None,
+                    model: "qwen3-coder-plus".to_string(),
+                },
+            ]
+        );
+
+        let (frontmatter, _) = parse_skill_frontmatter(
+            r#"---
+name: single-model-skill
+description: Single model skill
+model: gpt-4o
+---
+Body
+"#,
+        );
+        assert_eq!(frontmatter.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(frontmatter.model_candidates.len(), 1);
+    }
+
     #[test]
     fn load_skills_from_directory_should_skip_invalid_skill_packages() {
         let temp_dir = TempDir::new().unwrap();
@@ -257,11 +257,13 @@ mod tests {
                 do_not_trigger: do_not_trigger.into_iter().map(String::from).collect(),
             }),
             model: None,
+            model_candidates: Vec::new(),
             provider: None,
             disable_model_invocation: false,
             execution_mode: "prompt".to_string(),
             workflow_ref: None,
             workflow_steps: Vec::new(),
+            output_processors: Vec::new(),
             standard_compliance: lime_core::models::SkillStandardCompliance {
                 is_standard: true,
                 validation_errors: Vec::new(),
@@ -7,9 +7,13 @@ pub mod openai;
 pub mod xunfei;
 
 use async_trait::async_trait;
+use tokio::sync::mpsc::UnboundedSender;
 
 use crate::error::Result;
-use crate::types::{AudioData, TranscribeResult};
+use crate::types::{AudioData, PartialTranscript, TranscribeResult};
+
+/// 流式识别中间结果的发送端
+pub type PartialSender = UnboundedSender<PartialTranscript>;
 
 /// ASR 客户端 trait
 #[async_trait]
@@ -17,6 +21,18 @@ pub trait AsrClient: Send + Sync {
     /// 识别音频
     async fn transcribe(&self, audio: &AudioData) -> Result<TranscribeResult>;
 
+    /// 流式识别音频
+    ///
+    /// 识别过程中通过 `partials` 发送中间结果，最终结果通过返回值给出。
+    /// 默认实现不产生中间结果，等价于 [`AsrClient::transcribe`]。
+    async fn transcribe_stream(
+        &self,
+        audio: &AudioData,
+        _partials: PartialSender,
+    ) -> Result<TranscribeResult> {
+        self.transcribe(audio).await
+    }
+
     /// 获取服务名称
     fn name(&self) -> &'static str;
 }
@@ -22,9 +22,9 @@ use serde::{Deserialize, Serialize};
 use sha2::Sha256;
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
-use super::AsrClient;
+use super::{AsrClient, PartialSender};
 use crate::error::{Result, VoiceError};
-use crate::types::{AudioData, Segment, TranscribeResult};
+use crate::types::{AudioData, PartialTranscript, Segment, TranscribeResult};
 
 /// 讯飞 WebSocket 帧大小（字节）
 /// 讯飞建议每帧发送 1280 字节（约 40ms 的 16kHz 16bit 单声道音频）
@@ -269,6 +269,8 @@ impl XunfeiClient {
                 start: 0.0,
                 end: 0.0, // 讯飞不返回时间戳
                 text: full_text.clone(),
+                channel: None,
+                speaker: None,
             });
         }
 
@@ -279,11 +281,15 @@ impl XunfeiClient {
             segments,
         }
     }
-}
 
-#[async_trait]
-impl AsrClient for XunfeiClient {
-    async fn transcribe(&self, audio: &AudioData) -> Result<TranscribeResult> {
+    /// 建立 WebSocket 连接并识别音频
+    ///
+    /// `partials` 存在时，每收到一个响应就把动态修正后的当前文本作为中间结果发送。
+    async fn recognize(
+        &self,
+        audio: &AudioData,
+        partials: Option<PartialSender>,
+    ) -> Result<TranscribeResult> {
         // 生成鉴权 URL
         let url = self.generate_auth_url()?;
         tracing::debug!("讯飞 WebSocket URL 长度: {}", url.len());
@@ -343,6 +349,7 @@ impl AsrClient for XunfeiClient {
         // 启动接收任务
         let receive_task = tokio::spawn(async move {
             let mut responses: Vec<XunfeiResponse> = Vec::new();
+            let mut last_partial = String::new();
 
             while let Some(msg) = read.next().await {
                 match msg {
@@ -364,6 +371,15 @@ impl AsrClient for XunfeiClient {
                                     tracing::info!("收到最终识别结果");
                                     break;
                                 }
+
+                                // 发送中间结果（文本未变化时跳过）
+                                if let Some(ref partials) = partials {
+                                    let text = XunfeiClient::parse_result(&responses).text;
+                                    if text != last_partial {
+                                        let _ = partials.send(PartialTranscript::interim(&text));
+                                        last_partial = text;
+                                    }
+                                }
                             }
                             Err(e) => {
                                 tracing::error!("解析响应失败: {}", e);
@@ -472,6 +488,21 @@ impl AsrClient for XunfeiClient {
 
         Ok(result)
     }
+}
+
+#[async_trait]
+impl AsrClient for XunfeiClient {
+    async fn transcribe(&self, audio: &AudioData) -> Result<TranscribeResult> {
+        self.recognize(audio, None).await
+    }
+
+    async fn transcribe_stream(
+        &self,
+        audio: &AudioData,
+        partials: PartialSender,
+    ) -> Result<TranscribeResult> {
+        self.recognize(audio, Some(partials)).await
+    }
 
     fn name(&self) -> &'static str {
         "讯飞语音"
@@ -0,0 +1,199 @@
+//! 按声道区分说话人
+//!
+//! 适用于每位参会者各自占用一个声道的会议录音（多个麦克风或立体声双麦）。
+//! 按帧计算各声道能量，能量最高且超过阈值的声道视为当前说话人，
+//! 将连续的同一说话人帧合并为发言片段，再逐段调用 ASR 识别。
+
+use serde::{Deserialize, Serialize};
+
+use crate::asr_client::AsrClient;
+use crate::error::Result;
+use crate::types::{AudioData, Segment, TranscribeResult};
+
+/// 说话人区分配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiarizationConfig {
+    /// 分析帧长（毫秒）
+    pub frame_ms: u32,
+    /// 静音阈值（RMS，0.0-1.0）
+    pub silence_threshold: f32,
+    /// 最短发言时长（毫秒），更短的片段会被丢弃
+    pub min_speech_ms: u32,
+    /// 同一说话人片段间允许合并的最大停顿（毫秒）
+    pub max_gap_ms: u32,
+    /// 各声道对应的说话人名称（缺省为“说话人 N”）
+    #[serde(default)]
+    pub speaker_labels: Vec<String>,
+}
+
+impl Default for DiarizationConfig {
+    fn default() -> Self {
+        Self {
+            frame_ms: 30,
+            silence_threshold: 0.015,
+            min_speech_ms: 300,
+            max_gap_ms: 800,
+            speaker_labels: Vec::new(),
+        }
+    }
+}
+
+/// 发言片段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpeakerTurn {
+    /// 开始时间（秒）
+    pub start: f32,
+    /// 结束时间（秒）
+    pub end: f32,
+    /// 说话人所在声道
+    pub channel: u16,
+}
+
+/// 按声道区分说话人
+#[derive(Debug, Clone, Default)]
+pub struct ChannelDiarizer {
+    config: DiarizationConfig,
+}
+
+impl ChannelDiarizer {
+    /// 创建说话人区分器
+    pub fn new(config: DiarizationConfig) -> Self {
+        Self { config }
+    }
+
+    /// 获取声道对应的说话人名称
+    pub fn speaker_label(&self, channel: u16) -> String {
+        self.config
+            .speaker_labels
+            .get(channel as usize)
+            .filter(|label| !label.trim().is_empty())
+            .cloned()
+            .unwrap_or_else(|| format!("说话人 {}", channel + 1))
+    }
+
+    /// 检测发言片段
+    pub fn detect_turns(&self, audio: &AudioData) -> Vec<SpeakerTurn> {
+        let channels = audio.channels.max(1) as usize;
+        let frame_len =
+            (audio.sample_rate as usize * self.config.frame_ms.max(1) as usize / 1000).max(1);
+        let frame_secs = frame_len as f32 / audio.sample_rate as f32;
+        let max_gap_frames = self.config.max_gap_ms as f32 / 1000.0 / frame_secs;
+
+        // (起始帧, 结束帧（不含）, 声道)
+        let mut turns: Vec<(usize, usize, u16)> = Vec::new();
+        for (index, frame) in audio.samples.chunks(frame_len * channels).enumerate() {
+            let Some(channel) = self.dominant_channel(frame, channels) else {
+                continue;
+            };
+
+            match turns.last_mut() {
+                Some(last) if last.2 == channel && (index - last.1) as f32 <= max_gap_frames => {
+                    last.1 = index + 1;
+                }
+                _ => turns.push((index, index + 1, channel)),
+            }
+        }
+
+        let min_speech_secs = self.config.min_speech_ms as f32 / 1000.0;
+        turns
+            .into_iter()
+            .map(|(start, end, channel)| SpeakerTurn {
+                start: start as f32 * frame_secs,
+                end: (end as f32 * frame_secs).min(audio.duration_secs),
+                channel,
+            })
+            .filter(|turn| turn.end - turn.start >= min_speech_secs)
+            .collect()
+    }
+
+    /// 找出一帧中能量最高且超过静音阈值的声道
+    fn dominant_channel(&self, frame: &[i16], channels: usize) -> Option<u16> {
+        let frames = frame.len() / channels;
+        if frames == 0 {
+            return None;
+        }
+
+        let mut energy = vec![0.0f32; channels];
+        for chunk in frame.chunks_exact(channels) {
+            for (channel, &sample) in chunk.iter().enumerate() {
+                let value = sample as f32 / i16::MAX as f32;
+                energy[channel] += value * value;
+            }
+        }
+
+        energy
+            .iter()
+            .map(|sum| (sum / frames as f32).sqrt())
+            .enumerate()
+            .filter(|(_, rms)| *rms >= self.config.silence_threshold)
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(channel, _)| channel as u16)
+    }
+
+    /// 逐段识别并标注说话人
+    ///
+    /// 返回结果的 `text` 为“说话人: 内容”形式的逐行记录，
+    /// `segments` 的时间为相对整段录音的偏移。
+    pub async fn transcribe(
+        &self,
+        client: &dyn AsrClient,
+        audio: &AudioData,
+    ) -> Result<TranscribeResult> {
+        let mut lines = Vec::new();
+        let mut segments = Vec::new();
+        let mut language = None;
+
+        for turn in self.detect_turns(audio) {
+            let clip = Self::clip(audio, &turn);
+            let result = client.transcribe(&clip).await?;
+            let text = result.text.trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            let speaker = self.speaker_label(turn.channel);
+            lines.push(format!("{speaker}: {text}"));
+            language = language.or(result.language);
+
+            if result.segments.is_empty() {
+                segments.push(Segment {
+                    start: turn.start,
+                    end: turn.end,
+                    text: text.to_string(),
+                    channel: Some(turn.channel),
+                    speaker: Some(speaker),
+                });
+            } else {
+                segments.extend(result.segments.into_iter().map(|segment| Segment {
+                    start: segment.start + turn.start,
+                    end: segment.end + turn.start,
+                    channel: Some(turn.channel),
+                    speaker: Some(speaker.clone()),
+                    ..segment
+                }));
+            }
+        }
+
+        tracing::info!(
+            "[说话人区分] 声道数: {}，识别片段数: {}",
+            audio.channels,
+            segments.len()
+        );
+
+        Ok(TranscribeResult {
+            text: lines.join("\n"),
+            language,
+            confidence: None,
+            segments,
+        })
+    }
+
+    /// 截取发言片段所在声道的音频
+    fn clip(audio: &AudioData, turn: &SpeakerTurn) -> AudioData {
+        let mono = audio.channel(turn.channel);
+        let start = ((turn.start * audio.sample_rate as f32) as usize).min(mono.samples.len());
+        let end = ((turn.end * audio.sample_rate as f32) as usize).clamp(start, mono.samples.len());
+
+        AudioData::new(mono.samples[start..end].to_vec(), audio.sample_rate, 1)
+    }
+}
@@ -27,6 +27,10 @@ pub enum VoiceError {
     #[error("Whisper 模型加载失败: {0}")]
     WhisperModelError(String),
 
+    /// Whisper 模型下载错误
+    #[error("模型下载失败: {0}")]
+    ModelDownloadError(String),
+
     /// ASR 服务错误
     #[error("ASR 服务错误: {0}")]
     AsrError(String),
@@ -35,6 +39,14 @@ pub enum VoiceError {
     #[error("ASR 认证失败: {0}")]
     AsrAuthError(String),
 
+    /// 语音合成错误
+    #[error("语音合成错误: {0}")]
+    TtsError(String),
+
+    /// 音频播放错误
+    #[error("音频播放失败: {0}")]
+    PlaybackError(String),
+
     /// 输出错误
     #[error("文字输出错误: {0}")]
     OutputError(String),
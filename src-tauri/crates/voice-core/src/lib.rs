@@ -1,24 +1,34 @@
 //! voice-core - 语音输入核心库
 //!
-//! 提供音频录制、语音识别、文字输出等功能。
+//! 提供音频录制（支持多设备/多声道）、语音识别、说话人区分、文字输出、语音合成等功能。
 //! 不依赖 Tauri，可被任何 Rust 项目使用。
 
 pub mod asr_client;
 pub mod device;
+pub mod diarization;
 pub mod error;
+pub mod model_cache;
+pub mod model_manager;
 pub mod output;
 pub mod recorder;
 pub mod text_polish;
 pub mod threaded_recorder;
 #[cfg(feature = "local-whisper")]
 pub mod transcriber;
+pub mod tts;
 pub mod types;
+pub mod vad;
 
 pub use device::{list_audio_devices, AudioDeviceInfo};
+pub use diarization::{ChannelDiarizer, DiarizationConfig, SpeakerTurn};
 pub use error::{Result, VoiceError};
+pub use model_cache::{ModelCache, WhisperCacheKey};
+pub use model_manager::{ModelDownloadProgress, WhisperModelManager, WhisperModelStatus};
 pub use output::OutputHandler;
-pub use recorder::AudioRecorder;
+pub use recorder::{AudioRecorder, InputSource};
 pub use threaded_recorder::{RecordingCommand, RecordingResponse, RecordingService};
 #[cfg(feature = "local-whisper")]
 pub use transcriber::WhisperTranscriber;
+pub use tts::TtsClient;
 pub use types::*;
+pub use vad::{VadConfig, VadEvent, VadMode, VoiceActivityDetector};
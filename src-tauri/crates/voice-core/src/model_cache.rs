@@ -0,0 +1,131 @@
+//! 识别模型实例缓存
+//!
+//! 本地模型（如 Whisper）加载一次需要数秒，按键缓存已加载的实例，
+//! 超出容量时淘汰最久未使用的实例。
+//! 加载在缓存锁之外进行，同一时间只加载一个模型，避免并发加载占用过多内存。
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::types::WhisperModel;
+
+/// Whisper 实例缓存键：模型大小 + 识别语言
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct WhisperCacheKey {
+    pub model: WhisperModel,
+    pub language: String,
+}
+
+impl WhisperCacheKey {
+    pub fn new(model: WhisperModel, language: &str) -> Self {
+        Self {
+            model,
+            language: language.to_string(),
+        }
+    }
+}
+
+/// LRU 模型实例缓存
+pub struct ModelCache<K, V> {
+    capacity: usize,
+    /// 按最近使用排序，最近使用的在末尾
+    entries: Mutex<Vec<(K, Arc<V>)>>,
+    /// 串行化加载
+    loading: Mutex<()>,
+}
+
+impl<K: Eq + Clone, V> ModelCache<K, V> {
+    /// 创建缓存，`capacity` 至少为 1
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: Mutex::new(Vec::new()),
+            loading: Mutex::new(()),
+        }
+    }
+
+    /// 获取缓存的实例并标记为最近使用
+    pub fn get(&self, key: &K) -> Option<Arc<V>> {
+        let mut entries = self.entries.lock();
+        let index = entries.iter().position(|(k, _)| k == key)?;
+        let entry = entries.remove(index);
+        let value = entry.1.clone();
+        entries.push(entry);
+        Some(value)
+    }
+
+    /// 获取缓存的实例，未命中时调用 `load` 加载并放入缓存
+    ///
+    /// 加载失败时不缓存，错误原样返回。
+    pub fn get_or_try_load<E>(
+        &self,
+        key: &K,
+        load: impl FnOnce() -> std::result::Result<V, E>,
+    ) -> std::result::Result<Arc<V>, E> {
+        if let Some(value) = self.get(key) {
+            return Ok(value);
+        }
+
+        let _loading = self.loading.lock();
+        // 等待期间可能已被其他线程加载
+        if let Some(value) = self.get(key) {
+            return Ok(value);
+        }
+        let value = Arc::new(load()?);
+        self.insert(key.clone(), value.clone());
+        Ok(value)
+    }
+
+    /// 放入实例，超出容量时淘汰最久未使用的实例
+    pub fn insert(&self, key: K, value: Arc<V>) {
+        let mut entries = self.entries.lock();
+        entries.retain(|(k, _)| k != &key);
+        entries.push((key, value));
+        while entries.len() > self.capacity {
+            entries.remove(0);
+        }
+    }
+
+    /// 移除满足条件的实例，返回移除数量
+    ///
+    /// 正在使用中的实例在使用方释放后才会真正销毁。
+    pub fn remove_where(&self, predicate: impl Fn(&K) -> bool) -> usize {
+        let mut entries = self.entries.lock();
+        let before = entries.len();
+        entries.retain(|(k, _)| !predicate(k));
+        before - entries.len()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.lock().iter().any(|(k, _)| k == key)
+    }
+
+    /// 已缓存的键（最近使用的在末尾）
+    pub fn keys(&self) -> Vec<K> {
+        self.entries.lock().iter().map(|(k, _)| k.clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+}
+
+impl<V> ModelCache<WhisperCacheKey, V> {
+    /// 移除指定模型的所有语言实例（如模型文件被删除或重新下载）
+    pub fn invalidate_model(&self, model: WhisperModel) -> usize {
+        self.remove_where(|key| key.model == model)
+    }
+}
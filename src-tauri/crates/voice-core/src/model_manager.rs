@@ -0,0 +1,301 @@
+//! Whisper 模型管理
+//!
+//! 管理本地 Whisper 识别所需的 ggml 模型文件：
+//! - 列出可下载的模型及本地下载状态
+//! - 下载到模型目录：先写入 `<文件名>.part`，中断后按 HTTP Range 续传
+//! - 下载完成后校验大小与 SHA-1，通过后才重命名为正式文件
+//! - 删除模型（含未完成的 `.part` 文件）
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+
+use crate::error::{Result, VoiceError};
+use crate::types::WhisperModel;
+
+/// 模型下载地址（whisper.cpp 官方 Hugging Face 仓库）
+pub const DEFAULT_MODEL_BASE_URL: &str =
+    "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// 进度回调的最小间隔字节数
+const PROGRESS_STEP_BYTES: u64 = 1024 * 1024;
+
+/// 可下载的模型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WhisperModelSpec {
+    pub model: WhisperModel,
+    /// 文件大小（字节）
+    pub size_bytes: u64,
+    /// 文件 SHA-1（小写十六进制）
+    pub sha1: &'static str,
+}
+
+/// 可下载的模型列表（与 whisper.cpp `models/README.md` 一致）
+pub const WHISPER_MODELS: &[WhisperModelSpec] = &[
+    WhisperModelSpec {
+        model: WhisperModel::Tiny,
+        size_bytes: 77_691_713,
+        sha1: "bd577a113a864445d4c299885e0cb97d4ba92b5f",
+    },
+    WhisperModelSpec {
+        model: WhisperModel::Base,
+        size_bytes: 147_951_465,
+        sha1: "465707469ff3a37a2b9b8d8f89f2f99de7299dac",
+    },
+    WhisperModelSpec {
+        model: WhisperModel::Small,
+        size_bytes: 487_601_967,
+        sha1: "55356645c2b361a969dfd0ef2c5a50d530afd8d5",
+    },
+    WhisperModelSpec {
+        model: WhisperModel::Medium,
+        size_bytes: 1_533_763_059,
+        sha1: "fd9727b6e1217c2f614f9b698455c4ffd82463b4",
+    },
+];
+
+/// 模型本地状态
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WhisperModelStatus {
+    pub model: WhisperModel,
+    pub filename: &'static str,
+    /// 文件大小（字节）
+    pub size_bytes: u64,
+    /// 是否已下载完成
+    pub downloaded: bool,
+    /// 未完成下载已写入的字节数（可续传）
+    pub partial_bytes: u64,
+    /// 模型文件路径
+    pub path: String,
+}
+
+/// 下载进度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ModelDownloadProgress {
+    pub model: WhisperModel,
+    pub downloaded_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Whisper 模型管理器
+#[derive(Debug, Clone)]
+pub struct WhisperModelManager {
+    models_dir: PathBuf,
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl WhisperModelManager {
+    /// 创建管理器
+    ///
+    /// - `models_dir`: 模型存放目录，不存在时下载前自动创建
+    pub fn new(models_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            models_dir: models_dir.into(),
+            base_url: DEFAULT_MODEL_BASE_URL.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// 使用自定义下载地址（镜像站）
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// 查找可下载的模型
+    pub fn spec(model: WhisperModel) -> Option<&'static WhisperModelSpec> {
+        WHISPER_MODELS.iter().find(|spec| spec.model == model)
+    }
+
+    /// 模型文件路径
+    pub fn model_path(&self, model: WhisperModel) -> PathBuf {
+        self.models_dir.join(model.filename())
+    }
+
+    fn partial_path(&self, model: WhisperModel) -> PathBuf {
+        self.models_dir.join(format!("{}.part", model.filename()))
+    }
+
+    /// 列出可下载的模型及本地状态
+    pub fn list(&self) -> Vec<WhisperModelStatus> {
+        WHISPER_MODELS
+            .iter()
+            .map(|spec| {
+                let path = self.model_path(spec.model);
+                WhisperModelStatus {
+                    model: spec.model,
+                    filename: spec.model.filename(),
+                    size_bytes: spec.size_bytes,
+                    downloaded: path.is_file(),
+                    partial_bytes: file_len(&self.partial_path(spec.model)),
+                    path: path.to_string_lossy().to_string(),
+                }
+            })
+            .collect()
+    }
+
+    /// 下载模型
+    ///
+    /// 已存在 `.part` 文件时续传；`cancel` 置位后在下一个数据块处停止，保留 `.part` 文件。
+    /// 校验失败时删除 `.part` 文件。返回模型文件路径。
+    pub async fn download(
+        &self,
+        model: WhisperModel,
+        cancel: &AtomicBool,
+        mut on_progress: impl FnMut(ModelDownloadProgress),
+    ) -> Result<PathBuf> {
+        let spec = Self::spec(model).ok_or_else(|| {
+            VoiceError::ModelDownloadError(format!("不支持下载的模型: {}", model.filename()))
+        })?;
+        let path = self.model_path(model);
+        if path.is_file() {
+            return Ok(path);
+        }
+        fs::create_dir_all(&self.models_dir)?;
+
+        let partial = self.partial_path(model);
+        let mut hasher = Sha1::new();
+        let mut downloaded = hash_file(&partial, &mut hasher)?;
+        if downloaded > spec.size_bytes {
+            tracing::warn!("[模型下载] 未完成文件大小异常，重新下载: {:?}", partial);
+            hasher = Sha1::new();
+            downloaded = 0;
+        }
+
+        if downloaded < spec.size_bytes {
+            let url = format!("{}/{}", self.base_url, model.filename());
+            let mut request = self.client.get(&url);
+            if downloaded > 0 {
+                tracing::info!("[模型下载] 从 {} 字节处续传 {}", downloaded, url);
+                request = request.header(RANGE, format!("bytes={downloaded}-"));
+            }
+            let mut response = request
+                .send()
+                .await
+                .map_err(|e| VoiceError::NetworkError(e.to_string()))?;
+
+            match response.status() {
+                StatusCode::PARTIAL_CONTENT => {}
+                status if status.is_success() => {
+                    // 服务端不支持 Range，从头下载
+                    hasher = Sha1::new();
+                    downloaded = 0;
+                }
+                StatusCode::RANGE_NOT_SATISFIABLE => {
+                    let _ = fs::remove_file(&partial);
+                    return Err(VoiceError::ModelDownloadError(
+                        "续传位置无效，已清除未完成文件，请重试".to_string(),
+                    ));
+                }
+                status => {
+                    return Err(VoiceError::ModelDownloadError(format!("HTTP {status}")));
+                }
+            }
+
+            let mut file = if downloaded == 0 {
+                File::create(&partial)?
+            } else {
+                OpenOptions::new().append(true).open(&partial)?
+            };
+            on_progress(ModelDownloadProgress {
+                model,
+                downloaded_bytes: downloaded,
+                total_bytes: spec.size_bytes,
+            });
+
+            let mut reported = downloaded;
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| VoiceError::NetworkError(e.to_string()))?
+            {
+                if cancel.load(Ordering::Relaxed) {
+                    file.flush()?;
+                    return Err(VoiceError::ModelDownloadError("下载已取消".to_string()));
+                }
+                file.write_all(&chunk)?;
+                hasher.update(&chunk);
+                downloaded += chunk.len() as u64;
+                if downloaded - reported >= PROGRESS_STEP_BYTES {
+                    reported = downloaded;
+                    on_progress(ModelDownloadProgress {
+                        model,
+                        downloaded_bytes: downloaded,
+                        total_bytes: spec.size_bytes,
+                    });
+                }
+            }
+            file.flush()?;
+        }
+
+        if downloaded != spec.size_bytes {
+            return Err(VoiceError::ModelDownloadError(format!(
+                "下载不完整（{downloaded}/{} 字节），可重试续传",
+                spec.size_bytes
+            )));
+        }
+        let digest = to_hex(&hasher.finalize());
+        if digest != spec.sha1 {
+            let _ = fs::remove_file(&partial);
+            return Err(VoiceError::ModelDownloadError(format!(
+                "文件校验失败（SHA-1 {digest}，应为 {}）",
+                spec.sha1
+            )));
+        }
+
+        fs::rename(&partial, &path)?;
+        on_progress(ModelDownloadProgress {
+            model,
+            downloaded_bytes: downloaded,
+            total_bytes: spec.size_bytes,
+        });
+        tracing::info!("[模型下载] 模型已下载: {:?}", path);
+        Ok(path)
+    }
+
+    /// 删除模型文件及未完成的下载，返回是否删除了文件
+    pub fn delete(&self, model: WhisperModel) -> Result<bool> {
+        let mut removed = false;
+        for path in [self.model_path(model), self.partial_path(model)] {
+            if path.is_file() {
+                fs::remove_file(&path)?;
+                removed = true;
+            }
+        }
+        Ok(removed)
+    }
+}
+
+fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// 将已有文件内容计入哈希，返回文件大小（文件不存在时为 0）
+fn hash_file(path: &Path, hasher: &mut Sha1) -> Result<u64> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(e.into()),
+    };
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut total = 0u64;
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            return Ok(total);
+        }
+        hasher.update(&buf[..n]);
+        total += n as u64;
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -1,12 +1,16 @@
-//! 文字输出模块
+//! 输出模块
 //!
-//! 支持模拟键盘输入和剪贴板两种输出方式。
+//! 支持模拟键盘输入和剪贴板两种文字输出方式，以及播放合成语音。
+
+use std::sync::mpsc;
+use std::time::Duration;
 
 use arboard::Clipboard;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use enigo::{Enigo, Keyboard, Settings};
 
 use crate::error::{Result, VoiceError};
-use crate::types::OutputMode;
+use crate::types::{AudioData, OutputMode};
 
 /// 文字输出处理器
 pub struct OutputHandler {
@@ -57,6 +61,97 @@ impl OutputHandler {
         tracing::info!("已复制到剪贴板: {} 字符", text.chars().count());
         Ok(())
     }
+
+    /// 使用默认输出设备播放音频，阻塞直到播放完成
+    ///
+    /// 音频混合为单声道后按设备采样率线性重采样，写入设备的所有声道。
+    pub fn play_audio(audio: &AudioData) -> Result<()> {
+        if audio.samples.is_empty() {
+            return Ok(());
+        }
+
+        let host = cpal::default_host();
+        let device = host
+            .default_output_device()
+            .ok_or_else(|| VoiceError::PlaybackError("没有找到可用的音频输出设备".to_string()))?;
+        let config: cpal::StreamConfig = device
+            .default_output_config()
+            .map_err(|e| VoiceError::PlaybackError(e.to_string()))?
+            .into();
+        let channels = config.channels.max(1) as usize;
+        let samples = resample_mono(audio, config.sample_rate.0);
+        let duration = Duration::from_secs_f32(samples.len() as f32 / config.sample_rate.0 as f32);
+
+        let (done_tx, done_rx) = mpsc::channel();
+        let mut done_tx = Some(done_tx);
+        let mut position = 0;
+        let stream = device
+            .build_output_stream(
+                &config,
+                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                    for frame in data.chunks_mut(channels) {
+                        frame.fill(samples.get(position).copied().unwrap_or(0.0));
+                        position += 1;
+                    }
+                    if position >= samples.len() {
+                        if let Some(tx) = done_tx.take() {
+                            let _ = tx.send(());
+                        }
+                    }
+                },
+                |err| {
+                    tracing::error!("播放流错误: {}", err);
+                },
+                None,
+            )
+            .map_err(|e| VoiceError::PlaybackError(e.to_string()))?;
+        stream
+            .play()
+            .map_err(|e| VoiceError::PlaybackError(e.to_string()))?;
+
+        // 留出设备缓冲区排空的时间
+        if done_rx
+            .recv_timeout(duration + Duration::from_secs(2))
+            .is_err()
+        {
+            tracing::warn!("音频播放超时，提前结束");
+        }
+        std::thread::sleep(Duration::from_millis(100));
+
+        tracing::info!("音频播放完成: {:.2} 秒", audio.duration_secs);
+        Ok(())
+    }
+}
+
+/// 混合为单声道 f32 并线性重采样到目标采样率
+fn resample_mono(audio: &AudioData, target_rate: u32) -> Vec<f32> {
+    let channels = audio.channels.max(1) as usize;
+    let mono: Vec<f32> = audio
+        .samples
+        .chunks(channels)
+        .map(|frame| {
+            frame
+                .iter()
+                .map(|&s| s as f32 / i16::MAX as f32)
+                .sum::<f32>()
+                / frame.len() as f32
+        })
+        .collect();
+    if audio.sample_rate == target_rate || mono.len() < 2 {
+        return mono;
+    }
+
+    let ratio = audio.sample_rate as f64 / target_rate as f64;
+    let len = (mono.len() as f64 / ratio) as usize;
+    (0..len)
+        .map(|i| {
+            let pos = i as f64 * ratio;
+            let index = pos as usize;
+            let next = mono[(index + 1).min(mono.len() - 1)];
+            let frac = (pos - index as f64) as f32;
+            mono[index] + (next - mono[index]) * frac
+        })
+        .collect()
 }
 
 impl Default for OutputHandler {
@@ -17,18 +17,70 @@ pub const DEFAULT_CHANNELS: u16 = 1;
 /// 最大录音时长（秒）
 pub const MAX_RECORDING_DURATION: f32 = 60.0;
 
+/// 录音输入源
+///
+/// 一个输入源对应一个输入设备；`channels` 为 2 时按立体声采集，
+/// 左右声道分别作为独立声道保留（例如双领夹麦克风）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputSource {
+    /// 设备名称（`None` 表示系统默认输入设备）
+    pub device: Option<String>,
+    /// 采集声道数
+    pub channels: u16,
+}
+
+impl InputSource {
+    /// 默认输入设备，单声道
+    pub fn default_mono() -> Self {
+        Self {
+            device: None,
+            channels: DEFAULT_CHANNELS,
+        }
+    }
+
+    /// 指定设备
+    pub fn device(name: impl Into<String>, channels: u16) -> Self {
+        Self {
+            device: Some(name.into()),
+            channels: channels.max(1),
+        }
+    }
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        Self::default_mono()
+    }
+}
+
+/// 单个输入源的采集状态
+struct SourceCapture {
+    /// 输入源配置
+    source: InputSource,
+    /// 实际使用的设备名称
+    device_name: String,
+    /// 录音数据缓冲区（按 `source.channels` 交织）
+    samples: Arc<Mutex<Vec<i16>>>,
+    /// 音频流（录音时持有）
+    stream: Option<cpal::Stream>,
+}
+
 /// 音频录制器
+///
+/// 默认从系统默认麦克风采集单声道音频；通过 [`AudioRecorder::with_sources`]
+/// 可同时从多个设备或立体声通道采集，停止时所有声道按输入源顺序交织为
+/// 一个多声道 [`AudioData`]，可配合 [`crate::diarization`] 按声道区分说话人。
 pub struct AudioRecorder {
-    /// 录音数据缓冲区
-    samples: Arc<Mutex<Vec<i16>>>,
+    /// 输入源
+    sources: Vec<InputSource>,
+    /// 各输入源的采集状态
+    captures: Vec<SourceCapture>,
     /// 当前音量级别（0-100）
     volume_level: Arc<AtomicU32>,
     /// 是否正在录音
     is_recording: Arc<AtomicBool>,
     /// 录音开始时间
     start_time: Option<Instant>,
-    /// 音频流（录音时持有）
-    stream: Option<cpal::Stream>,
     /// 采样率
     sample_rate: u32,
 }
@@ -36,46 +88,103 @@ pub struct AudioRecorder {
 impl AudioRecorder {
     /// 创建新的录音器
     pub fn new() -> Result<Self> {
+        Self::with_sources(vec![InputSource::default_mono()])
+    }
+
+    /// 创建从多个输入源采集的录音器
+    pub fn with_sources(sources: Vec<InputSource>) -> Result<Self> {
+        if sources.is_empty() {
+            return Err(VoiceError::RecorderError("至少需要一个输入源".to_string()));
+        }
+
         Ok(Self {
-            samples: Arc::new(Mutex::new(Vec::new())),
+            sources,
+            captures: Vec::new(),
             volume_level: Arc::new(AtomicU32::new(0)),
             is_recording: Arc::new(AtomicBool::new(false)),
             start_time: None,
-            stream: None,
             sample_rate: DEFAULT_SAMPLE_RATE,
         })
     }
 
+    /// 输入源列表
+    pub fn sources(&self) -> &[InputSource] {
+        &self.sources
+    }
+
+    /// 各声道的标签（设备名，多声道设备附加声道序号）
+    ///
+    /// 顺序与 [`AudioRecorder::stop`] 返回音频的声道顺序一致，仅在录音开始后可用。
+    pub fn channel_labels(&self) -> Vec<String> {
+        self.captures
+            .iter()
+            .flat_map(|capture| {
+                let channels = capture.source.channels;
+                (0..channels).map(move |i| {
+                    if channels > 1 {
+                        format!("{} #{}", capture.device_name, i + 1)
+                    } else {
+                        capture.device_name.clone()
+                    }
+                })
+            })
+            .collect()
+    }
+
     /// 开始录音
     pub fn start(&mut self) -> Result<()> {
         if self.is_recording.load(Ordering::SeqCst) {
             return Ok(());
         }
 
-        // 清空缓冲区
-        if let Ok(mut samples) = self.samples.lock() {
-            samples.clear();
+        self.sample_rate = DEFAULT_SAMPLE_RATE;
+        self.is_recording.store(true, Ordering::SeqCst);
+
+        let mut captures = Vec::with_capacity(self.sources.len());
+        for source in &self.sources {
+            match self.start_source(source) {
+                Ok(capture) => captures.push(capture),
+                Err(e) => {
+                    self.is_recording.store(false, Ordering::SeqCst);
+                    return Err(e);
+                }
+            }
         }
 
-        // 获取默认输入设备
+        self.captures = captures;
+        self.start_time = Some(Instant::now());
+
+        tracing::info!("开始录音，输入源数: {}", self.captures.len());
+        Ok(())
+    }
+
+    /// 打开单个输入源的音频流
+    fn start_source(&self, source: &InputSource) -> Result<SourceCapture> {
         let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .ok_or(VoiceError::NoMicrophoneFound)?;
+        let device = match &source.device {
+            Some(name) => host
+                .input_devices()
+                .map_err(|e| VoiceError::RecorderError(format!("无法枚举音频设备: {e}")))?
+                .find(|d| d.name().map(|n| &n == name).unwrap_or(false))
+                .ok_or_else(|| VoiceError::RecorderError(format!("找不到输入设备: {name}")))?,
+            None => host
+                .default_input_device()
+                .ok_or(VoiceError::NoMicrophoneFound)?,
+        };
+        let device_name = device.name().unwrap_or_else(|_| "未知设备".to_string());
 
-        tracing::info!("使用麦克风: {:?}", device.name());
+        tracing::info!("使用麦克风: {} ({} 声道)", device_name, source.channels);
 
         // 配置音频格式
         let config = cpal::StreamConfig {
-            channels: DEFAULT_CHANNELS,
-            sample_rate: cpal::SampleRate(DEFAULT_SAMPLE_RATE),
+            channels: source.channels,
+            sample_rate: cpal::SampleRate(self.sample_rate),
             buffer_size: cpal::BufferSize::Default,
         };
 
-        self.sample_rate = DEFAULT_SAMPLE_RATE;
-
         // 创建共享状态
-        let samples = Arc::clone(&self.samples);
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let buffer_samples = Arc::clone(&samples);
         let volume_level = Arc::clone(&self.volume_level);
         let is_recording = Arc::clone(&self.is_recording);
 
@@ -84,7 +193,7 @@ impl AudioRecorder {
             .build_input_stream(
                 &config,
                 move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                    if !is_recording.load(Ordering::SeqCst) {
+                    if !is_recording.load(Ordering::SeqCst) || data.is_empty() {
                         return;
                     }
 
@@ -98,7 +207,7 @@ impl AudioRecorder {
                     let i16_samples: Vec<i16> =
                         data.iter().map(|&s| (s * i16::MAX as f32) as i16).collect();
 
-                    if let Ok(mut buffer) = samples.lock() {
+                    if let Ok(mut buffer) = buffer_samples.lock() {
                         buffer.extend(i16_samples);
                     }
                 },
@@ -114,15 +223,17 @@ impl AudioRecorder {
             .play()
             .map_err(|e| VoiceError::RecorderError(e.to_string()))?;
 
-        self.stream = Some(stream);
-        self.is_recording.store(true, Ordering::SeqCst);
-        self.start_time = Some(Instant::now());
-
-        tracing::info!("开始录音");
-        Ok(())
+        Ok(SourceCapture {
+            source: source.clone(),
+            device_name,
+            samples,
+            stream: Some(stream),
+        })
     }
 
     /// 停止录音并返回音频数据
+    ///
+    /// 多个输入源时返回按输入源顺序交织的多声道音频。
     pub fn stop(&mut self) -> Result<AudioData> {
         if !self.is_recording.load(Ordering::SeqCst) {
             return Err(VoiceError::RecorderError("未在录音中".to_string()));
@@ -131,21 +242,34 @@ impl AudioRecorder {
         // 停止录音
         self.is_recording.store(false, Ordering::SeqCst);
 
-        // 停止流
-        if let Some(stream) = self.stream.take() {
-            drop(stream);
+        // 停止流并获取录音数据
+        let mut tracks = Vec::with_capacity(self.captures.len());
+        for capture in &mut self.captures {
+            if let Some(stream) = capture.stream.take() {
+                drop(stream);
+            }
+            let samples = capture
+                .samples
+                .lock()
+                .map_err(|e| VoiceError::RecorderError(e.to_string()))?
+                .clone();
+            tracks.push(AudioData::new(
+                samples,
+                self.sample_rate,
+                capture.source.channels,
+            ));
         }
 
-        // 获取录音数据
-        let samples = self
-            .samples
-            .lock()
-            .map_err(|e| VoiceError::RecorderError(e.to_string()))?
-            .clone();
-
-        let audio = AudioData::new(samples, self.sample_rate, DEFAULT_CHANNELS);
+        let audio = match tracks.len() {
+            1 => tracks.remove(0),
+            _ => AudioData::interleave(&tracks)?,
+        };
 
-        tracing::info!("停止录音，时长: {:.2}s", audio.duration_secs);
+        tracing::info!(
+            "停止录音，时长: {:.2}s，声道数: {}",
+            audio.duration_secs,
+            audio.channels
+        );
 
         // 检查录音时长
         if !audio.is_valid() {
@@ -175,12 +299,7 @@ impl AudioRecorder {
     /// 取消录音
     pub fn cancel(&mut self) {
         self.is_recording.store(false, Ordering::SeqCst);
-        if let Some(stream) = self.stream.take() {
-            drop(stream);
-        }
-        if let Ok(mut samples) = self.samples.lock() {
-            samples.clear();
-        }
+        self.captures.clear();
         tracing::info!("取消录音");
     }
 }
@@ -18,8 +18,18 @@
 //! - 录音线程拥有 `cpal::Stream`，在独立线程中运行
 //! - Tauri 命令通过 channel 发送控制指令
 //! - 录音线程通过 channel 返回结果
+//!
+//! ## 语音活动检测
+//!
+//! 启用 VAD（见 [`RecordingService::set_vad_config`]）后，录音回调逐帧检测静音，
+//! 说话后持续静音超过设定时长时通知录音线程：
+//! - `AutoStop`：自动停止录音，通过事件 channel 发送 [`RecordingResponse::AutoStopped`]，
+//!   音频数据仍通过 [`RecordingService::stop`] 获取
+//! - `Segment`：切出当前这段音频，通过事件 channel 发送 [`RecordingResponse::Segment`]，
+//!   继续录音；之后 `stop` 只返回最后一段之后的音频
 
 use crate::types::AudioData;
+use crate::vad::{VadConfig, VadEvent, VadMode, VoiceActivityDetector};
 use parking_lot::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::mpsc::{self, Receiver, Sender};
@@ -36,6 +46,8 @@ pub enum RecordingCommand {
     Stop,
     /// 取消录音
     Cancel,
+    /// VAD 检测到说话后的静音（由录音回调发送）
+    SilenceDetected,
     /// 关闭录音线程
     Shutdown,
 }
@@ -49,6 +61,10 @@ pub enum RecordingResponse {
     AudioData(AudioData),
     /// 操作失败
     Error(String),
+    /// VAD 自动停止了录音（事件，音频数据通过 `stop` 获取）
+    AutoStopped,
+    /// VAD 切分出的一段音频（事件）
+    Segment(AudioData),
 }
 
 /// 录音服务
@@ -59,6 +75,8 @@ pub struct RecordingService {
     command_tx: Option<Sender<RecordingCommand>>,
     /// 响应接收端
     response_rx: Option<Receiver<RecordingResponse>>,
+    /// VAD 事件接收端（只能取走一次）
+    event_rx: Option<Receiver<RecordingResponse>>,
     /// 录音线程句柄
     thread_handle: Option<JoinHandle<()>>,
     /// 是否正在录音（共享状态，用于快速查询）
@@ -67,6 +85,8 @@ pub struct RecordingService {
     volume_level: Arc<AtomicU32>,
     /// 录音开始时间（共享状态）
     start_time: Arc<Mutex<Option<Instant>>>,
+    /// VAD 配置（每次开始录音时读取）
+    vad_config: Arc<Mutex<VadConfig>>,
 }
 
 impl RecordingService {
@@ -75,10 +95,12 @@ impl RecordingService {
         Self {
             command_tx: None,
             response_rx: None,
+            event_rx: None,
             thread_handle: None,
             is_recording: Arc::new(AtomicBool::new(false)),
             volume_level: Arc::new(AtomicU32::new(0)),
             start_time: Arc::new(Mutex::new(None)),
+            vad_config: Arc::new(Mutex::new(VadConfig::default())),
         }
     }
 
@@ -90,17 +112,26 @@ impl RecordingService {
 
         let (cmd_tx, cmd_rx) = mpsc::channel::<RecordingCommand>();
         let (resp_tx, resp_rx) = mpsc::channel::<RecordingResponse>();
-
+        let (event_tx, event_rx) = mpsc::channel::<RecordingResponse>();
+
+        let channels = RecordingChannels {
+            cmd_rx,
+            cmd_tx: cmd_tx.clone(),
+            resp_tx,
+            event_tx,
+        };
         let is_recording = Arc::clone(&self.is_recording);
         let volume_level = Arc::clone(&self.volume_level);
         let start_time = Arc::clone(&self.start_time);
+        let vad_config = Arc::clone(&self.vad_config);
 
         let handle = thread::spawn(move || {
-            recording_thread_main(cmd_rx, resp_tx, is_recording, volume_level, start_time);
+            recording_thread_main(channels, is_recording, volume_level, start_time, vad_config);
         });
 
         self.command_tx = Some(cmd_tx);
         self.response_rx = Some(resp_rx);
+        self.event_rx = Some(event_rx);
         self.thread_handle = Some(handle);
 
         tracing::info!("[录音服务] 录音线程已启动");
@@ -171,6 +202,20 @@ impl RecordingService {
         *self.start_time.lock() = None;
     }
 
+    /// 设置 VAD 配置（下次开始录音时生效）
+    pub fn set_vad_config(&self, config: VadConfig) {
+        *self.vad_config.lock() = config;
+    }
+
+    /// 取走 VAD 事件接收端
+    ///
+    /// 事件为 [`RecordingResponse::AutoStopped`] 或 [`RecordingResponse::Segment`]，
+    /// 接收端只能取走一次，之后返回 None。
+    pub fn take_event_receiver(&mut self) -> Option<Receiver<RecordingResponse>> {
+        self.ensure_thread_started();
+        self.event_rx.take()
+    }
+
     /// 获取当前音量级别（0-100）
     pub fn get_volume(&self) -> u32 {
         self.volume_level.load(Ordering::SeqCst)
@@ -198,6 +243,7 @@ impl RecordingService {
             let _ = handle.join();
         }
         self.response_rx = None;
+        self.event_rx = None;
         tracing::info!("[录音服务] 已关闭");
     }
 }
@@ -214,20 +260,52 @@ impl Drop for RecordingService {
     }
 }
 
+/// 录音线程使用的 channel
+struct RecordingChannels {
+    /// 命令接收端
+    cmd_rx: Receiver<RecordingCommand>,
+    /// 命令发送端（供录音回调发送 VAD 通知）
+    cmd_tx: Sender<RecordingCommand>,
+    /// 命令响应发送端
+    resp_tx: Sender<RecordingResponse>,
+    /// VAD 事件发送端
+    event_tx: Sender<RecordingResponse>,
+}
+
+/// 根据录音数据生成停止录音的响应
+fn finish_recording(samples: Vec<i16>, sample_rate: u32) -> RecordingResponse {
+    let audio = AudioData::new(samples, sample_rate, 1);
+    if !audio.is_valid() {
+        return RecordingResponse::Error("录音时间过短（需要至少 0.5 秒）".to_string());
+    }
+    RecordingResponse::AudioData(audio)
+}
+
 /// 录音线程主函数
 ///
 /// 在独立线程中运行，拥有 cpal::Stream
 fn recording_thread_main(
-    cmd_rx: Receiver<RecordingCommand>,
-    resp_tx: Sender<RecordingResponse>,
+    channels: RecordingChannels,
     is_recording: Arc<AtomicBool>,
     volume_level: Arc<AtomicU32>,
     start_time: Arc<Mutex<Option<Instant>>>,
+    vad_config: Arc<Mutex<VadConfig>>,
 ) {
     use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 
+    let RecordingChannels {
+        cmd_rx,
+        cmd_tx,
+        resp_tx,
+        event_tx,
+    } = channels;
+
     // 录音数据缓冲区
     let samples: Arc<Mutex<Vec<i16>>> = Arc::new(Mutex::new(Vec::new()));
+    // VAD 自动停止后等待 stop 取走的结果
+    let mut pending_stop: Option<RecordingResponse> = None;
+    // 本次录音的 VAD 模式（未启用时为 None）
+    let mut vad_mode: Option<VadMode> = None;
     // 当前活跃的音频流
     let mut active_stream: Option<cpal::Stream> = None;
     // 实际使用的采样率和声道数
@@ -248,6 +326,7 @@ fn recording_thread_main(
 
                 // 清空缓冲区
                 samples.lock().clear();
+                pending_stop = None;
 
                 // 获取输入设备
                 let host = cpal::default_host();
@@ -309,6 +388,14 @@ fn recording_thread_main(
                 let is_rec_clone = Arc::clone(&is_recording);
                 let channels = actual_channels;
 
+                // VAD 检测器（每次录音重新创建）
+                let vad = vad_config.lock().clone();
+                vad_mode = vad.enabled.then_some(vad.mode);
+                let mut detector = vad
+                    .enabled
+                    .then(|| VoiceActivityDetector::new(vad, actual_sample_rate));
+                let vad_tx = cmd_tx.clone();
+
                 // 回调计数器（用于调试）
                 let callback_count = Arc::new(AtomicU32::new(0));
                 let callback_count_clone = Arc::clone(&callback_count);
@@ -353,6 +440,13 @@ fn recording_thread_main(
                             data.to_vec()
                         };
 
+                        // 语音活动检测
+                        if let Some(detector) = detector.as_mut() {
+                            if detector.process(&mono_data) == Some(VadEvent::SilenceDetected) {
+                                let _ = vad_tx.send(RecordingCommand::SilenceDetected);
+                            }
+                        }
+
                         // 转换为 i16 并存储
                         let i16_samples: Vec<i16> = mono_data
                             .iter()
@@ -389,15 +483,20 @@ fn recording_thread_main(
 
                 let _ = resp_tx.send(RecordingResponse::Ok);
                 tracing::info!(
-                    "[录音线程] 开始录音，采样率: {}, 声道: {}",
+                    "[录音线程] 开始录音，采样率: {}, 声道: {}, VAD: {:?}",
                     actual_sample_rate,
-                    actual_channels
+                    actual_channels,
+                    vad_mode
                 );
             }
 
             Ok(RecordingCommand::Stop) => {
                 if !is_recording.load(Ordering::SeqCst) {
-                    let _ = resp_tx.send(RecordingResponse::Error("未在录音中".to_string()));
+                    // VAD 已自动停止，返回当时的录音结果
+                    let response = pending_stop
+                        .take()
+                        .unwrap_or_else(|| RecordingResponse::Error("未在录音中".to_string()));
+                    let _ = resp_tx.send(response);
                     continue;
                 }
 
@@ -411,22 +510,49 @@ fn recording_thread_main(
 
                 // 获取录音数据（已转换为单声道）
                 let audio_samples = samples.lock().clone();
-                let audio = AudioData::new(audio_samples, actual_sample_rate, 1);
 
                 // 重置开始时间
                 *start_time.lock() = None;
                 volume_level.store(0, Ordering::SeqCst);
 
                 // 检查录音时长
-                if !audio.is_valid() {
-                    let _ = resp_tx.send(RecordingResponse::Error(
-                        "录音时间过短（需要至少 0.5 秒）".to_string(),
-                    ));
+                let _ = resp_tx.send(finish_recording(audio_samples, actual_sample_rate));
+                tracing::info!("[录音线程] 停止录音");
+            }
+
+            Ok(RecordingCommand::SilenceDetected) => {
+                if !is_recording.load(Ordering::SeqCst) {
                     continue;
                 }
 
-                let _ = resp_tx.send(RecordingResponse::AudioData(audio));
-                tracing::info!("[录音线程] 停止录音");
+                match vad_mode {
+                    Some(VadMode::AutoStop) => {
+                        is_recording.store(false, Ordering::SeqCst);
+                        if let Some(stream) = active_stream.take() {
+                            drop(stream);
+                        }
+
+                        let audio_samples = samples.lock().clone();
+                        pending_stop = Some(finish_recording(audio_samples, actual_sample_rate));
+                        *start_time.lock() = None;
+                        volume_level.store(0, Ordering::SeqCst);
+
+                        let _ = event_tx.send(RecordingResponse::AutoStopped);
+                        tracing::info!("[录音线程] 检测到静音，自动停止录音");
+                    }
+                    Some(VadMode::Segment) => {
+                        let segment = std::mem::take(&mut *samples.lock());
+                        let audio = AudioData::new(segment, actual_sample_rate, 1);
+                        if audio.is_valid() {
+                            tracing::info!(
+                                "[录音线程] 检测到静音，切分音频段，时长: {:.2}s",
+                                audio.duration_secs
+                            );
+                            let _ = event_tx.send(RecordingResponse::Segment(audio));
+                        }
+                    }
+                    None => {}
+                }
             }
 
             Ok(RecordingCommand::Cancel) => {
@@ -440,6 +566,7 @@ fn recording_thread_main(
 
                 // 清空缓冲区
                 samples.lock().clear();
+                pending_stop = None;
 
                 // 重置状态
                 *start_time.lock() = None;
@@ -6,7 +6,7 @@ use std::path::PathBuf;
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
 use crate::error::{Result, VoiceError};
-use crate::types::{AudioData, Segment, TranscribeResult, WhisperModel};
+use crate::types::{AudioData, PartialTranscript, Segment, TranscribeResult, WhisperModel};
 
 /// Whisper 识别器
 pub struct WhisperTranscriber {
@@ -90,6 +90,8 @@ impl WhisperTranscriber {
                     start,
                     end,
                     text: segment_text,
+                    channel: None,
+                    speaker: None,
                 });
             }
         }
@@ -112,6 +114,56 @@ impl WhisperTranscriber {
         })
     }
 
+    /// 分块识别音频
+    ///
+    /// 按 `chunk_secs` 秒切分音频逐块识别，每识别完一块（最后一块除外）
+    /// 就通过 `on_partial` 发送截至目前的累计文本，最终结果通过返回值给出。
+    pub fn transcribe_chunked(
+        &self,
+        audio: &AudioData,
+        chunk_secs: f32,
+        mut on_partial: impl FnMut(PartialTranscript),
+    ) -> Result<TranscribeResult> {
+        let channels = audio.channels.max(1) as usize;
+        let chunk_len = ((audio.sample_rate as f32 * chunk_secs) as usize * channels).max(1);
+        let chunks: Vec<&[i16]> = audio.samples.chunks(chunk_len).collect();
+        let total_chunks = chunks.len();
+
+        let mut text = String::new();
+        let mut segments = Vec::new();
+        let mut language = None;
+
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let offset = (i * chunk_len / channels) as f32 / audio.sample_rate as f32;
+            let chunk_audio = AudioData::new(chunk.to_vec(), audio.sample_rate, audio.channels);
+            let result = self.transcribe(&chunk_audio)?;
+
+            if !result.text.is_empty() {
+                if !text.is_empty() && !self.language.starts_with("zh") {
+                    text.push(' ');
+                }
+                text.push_str(&result.text);
+            }
+            segments.extend(result.segments.into_iter().map(|segment| Segment {
+                start: segment.start + offset,
+                end: segment.end + offset,
+                ..segment
+            }));
+            language = language.or(result.language);
+
+            if i + 1 < total_chunks {
+                on_partial(PartialTranscript::interim(&text));
+            }
+        }
+
+        Ok(TranscribeResult {
+            text,
+            language,
+            confidence: None,
+            segments,
+        })
+    }
+
     /// 获取模型大小
     pub fn model(&self) -> WhisperModel {
         self.model
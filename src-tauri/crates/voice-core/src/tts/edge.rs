@@ -0,0 +1,109 @@
+//! edge-tts 兼容服务客户端
+//!
+//! 对接自建的 edge-tts HTTP 服务（如 openai-edge-tts），接口与 OpenAI
+//! `/v1/audio/speech` 兼容，音色使用 Edge 神经网络音色名（如 `zh-CN-XiaoxiaoNeural`）。
+//! 请求 WAV 输出，由 hound 解码。
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use super::TtsClient;
+use crate::error::{Result, VoiceError};
+use crate::types::AudioData;
+
+/// 默认音色
+pub const DEFAULT_EDGE_VOICE: &str = "zh-CN-XiaoxiaoNeural";
+
+/// edge-tts 请求
+#[derive(Debug, Serialize)]
+struct SpeechRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+    voice: &'a str,
+    response_format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speed: Option<f32>,
+}
+
+/// edge-tts 兼容服务客户端
+pub struct EdgeTtsClient {
+    api_host: String,
+    api_key: Option<String>,
+    voice: String,
+    speed: Option<f32>,
+}
+
+impl EdgeTtsClient {
+    /// 创建新的客户端
+    ///
+    /// - `api_host`: 服务地址（如 `http://localhost:5050`）
+    pub fn new(api_host: String) -> Self {
+        Self {
+            api_host,
+            api_key: None,
+            voice: DEFAULT_EDGE_VOICE.to_string(),
+            speed: None,
+        }
+    }
+
+    /// 设置 API Key（服务开启鉴权时）
+    pub fn with_api_key(mut self, api_key: String) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    /// 设置音色
+    pub fn with_voice(mut self, voice: String) -> Self {
+        self.voice = voice;
+        self
+    }
+
+    /// 设置语速（1.0 为正常语速）
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = Some(speed.clamp(0.25, 4.0));
+        self
+    }
+}
+
+#[async_trait]
+impl TtsClient for EdgeTtsClient {
+    async fn synthesize(&self, text: &str) -> Result<AudioData> {
+        let url = format!("{}/v1/audio/speech", self.api_host.trim_end_matches('/'));
+        let request = SpeechRequest {
+            model: "tts-1",
+            input: text,
+            voice: &self.voice,
+            response_format: "wav",
+            speed: self.speed,
+        };
+
+        let client = reqwest::Client::new();
+        let mut builder = client.post(&url).json(&request);
+        if let Some(ref api_key) = self.api_key {
+            builder = builder.header("Authorization", format!("Bearer {api_key}"));
+        }
+        let response = builder
+            .send()
+            .await
+            .map_err(|e| VoiceError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(VoiceError::TtsError(format!(
+                "edge-tts 服务错误: {status} - {body}"
+            )));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| VoiceError::NetworkError(e.to_string()))?;
+
+        AudioData::from_wav_bytes(&bytes)
+    }
+
+    fn name(&self) -> &'static str {
+        "Edge TTS"
+    }
+}
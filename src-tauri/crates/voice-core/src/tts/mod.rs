@@ -0,0 +1,25 @@
+//! 语音合成（TTS）客户端模块
+//!
+//! 支持 OpenAI TTS 与 edge-tts 兼容服务，合成结果为 [`AudioData`]，
+//! 可通过 [`crate::OutputHandler::play_audio`] 播放。
+
+pub mod edge;
+pub mod openai;
+
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::types::AudioData;
+
+/// TTS 客户端 trait
+#[async_trait]
+pub trait TtsClient: Send + Sync {
+    /// 合成语音
+    async fn synthesize(&self, text: &str) -> Result<AudioData>;
+
+    /// 获取服务名称
+    fn name(&self) -> &'static str;
+}
+
+pub use edge::EdgeTtsClient;
+pub use openai::OpenAITtsClient;
@@ -0,0 +1,113 @@
+//! OpenAI TTS 客户端
+//!
+//! 使用 OpenAI 的 `/v1/audio/speech` 接口合成语音，
+//! 请求原始 PCM 输出（24kHz、16-bit、单声道），无需额外解码。
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use super::TtsClient;
+use crate::error::{Result, VoiceError};
+use crate::types::AudioData;
+
+/// OpenAI TTS PCM 输出的采样率
+const PCM_SAMPLE_RATE: u32 = 24000;
+
+/// OpenAI TTS 请求
+#[derive(Debug, Serialize)]
+struct SpeechRequest<'a> {
+    model: &'a str,
+    input: &'a str,
+    voice: &'a str,
+    response_format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    speed: Option<f32>,
+}
+
+/// OpenAI TTS 客户端
+pub struct OpenAITtsClient {
+    api_key: String,
+    api_host: String,
+    model: String,
+    voice: String,
+    speed: Option<f32>,
+}
+
+impl OpenAITtsClient {
+    /// 创建新的客户端
+    pub fn new(api_key: String) -> Self {
+        Self {
+            api_key,
+            api_host: "https://api.openai.com".to_string(),
+            model: "tts-1".to_string(),
+            voice: "alloy".to_string(),
+            speed: None,
+        }
+    }
+
+    /// 设置 API Host（用于代理）
+    pub fn with_host(mut self, host: String) -> Self {
+        self.api_host = host;
+        self
+    }
+
+    /// 设置模型（如 `tts-1`、`tts-1-hd`、`gpt-4o-mini-tts`）
+    pub fn with_model(mut self, model: String) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// 设置音色（如 `alloy`、`nova`）
+    pub fn with_voice(mut self, voice: String) -> Self {
+        self.voice = voice;
+        self
+    }
+
+    /// 设置语速（0.25 - 4.0）
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = Some(speed.clamp(0.25, 4.0));
+        self
+    }
+}
+
+#[async_trait]
+impl TtsClient for OpenAITtsClient {
+    async fn synthesize(&self, text: &str) -> Result<AudioData> {
+        let url = format!("{}/v1/audio/speech", self.api_host.trim_end_matches('/'));
+        let request = SpeechRequest {
+            model: &self.model,
+            input: text,
+            voice: &self.voice,
+            response_format: "pcm",
+            speed: self.speed,
+        };
+
+        let client = reqwest::Client::new();
+        let response = client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| VoiceError::NetworkError(e.to_string()))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(VoiceError::TtsError(format!(
+                "OpenAI API 错误: {status} - {body}"
+            )));
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| VoiceError::NetworkError(e.to_string()))?;
+
+        Ok(AudioData::from_pcm16le_bytes(&bytes, PCM_SAMPLE_RATE, 1))
+    }
+
+    fn name(&self) -> &'static str {
+        "OpenAI TTS"
+    }
+}
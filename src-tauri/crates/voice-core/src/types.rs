@@ -4,6 +4,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::{Result, VoiceError};
+
 /// 音频数据
 #[derive(Debug, Clone)]
 pub struct AudioData {
@@ -52,6 +54,33 @@ impl AudioData {
             .collect()
     }
 
+    /// 从 WAV 格式字节解码（整数与浮点采样统一转换为 16-bit）
+    pub fn from_wav_bytes(bytes: &[u8]) -> Result<Self> {
+        let reader = hound::WavReader::new(std::io::Cursor::new(bytes))
+            .map_err(|e| VoiceError::AudioFormatError(e.to_string()))?;
+        let spec = reader.spec();
+        let samples: std::result::Result<Vec<i16>, hound::Error> =
+            match (spec.sample_format, spec.bits_per_sample) {
+                (hound::SampleFormat::Int, 16) => reader.into_samples::<i16>().collect(),
+                (hound::SampleFormat::Int, bits) => reader
+                    .into_samples::<i32>()
+                    .map(|s| {
+                        s.map(|s| match bits {
+                            0..=15 => (s << (16 - bits)) as i16,
+                            _ => (s >> (bits - 16)) as i16,
+                        })
+                    })
+                    .collect(),
+                (hound::SampleFormat::Float, _) => reader
+                    .into_samples::<f32>()
+                    .map(|s| s.map(|s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16))
+                    .collect(),
+            };
+        let samples = samples.map_err(|e| VoiceError::AudioFormatError(e.to_string()))?;
+
+        Ok(Self::new(samples, spec.sample_rate, spec.channels))
+    }
+
     /// 转换为 WAV 格式字节
     pub fn to_wav_bytes(&self) -> Vec<u8> {
         let mut cursor = std::io::Cursor::new(Vec::new());
@@ -71,6 +100,79 @@ impl AudioData {
 
         cursor.into_inner()
     }
+
+    /// 每个声道的帧数
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+
+    /// 提取单个声道为单声道音频（越界时返回空音频）
+    pub fn channel(&self, index: u16) -> AudioData {
+        let channels = self.channels.max(1) as usize;
+        let samples = if (index as usize) < channels {
+            self.samples
+                .iter()
+                .skip(index as usize)
+                .step_by(channels)
+                .copied()
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        AudioData::new(samples, self.sample_rate, 1)
+    }
+
+    /// 按声道拆分为多个单声道音频
+    pub fn split_channels(&self) -> Vec<AudioData> {
+        (0..self.channels.max(1)).map(|i| self.channel(i)).collect()
+    }
+
+    /// 混音为单声道（各声道取平均）
+    pub fn to_mono(&self) -> AudioData {
+        if self.channels <= 1 {
+            return self.clone();
+        }
+
+        let samples = self
+            .samples
+            .chunks_exact(self.channels as usize)
+            .map(|frame| {
+                let sum: i32 = frame.iter().map(|&s| s as i32).sum();
+                (sum / frame.len() as i32) as i16
+            })
+            .collect();
+
+        AudioData::new(samples, self.sample_rate, 1)
+    }
+
+    /// 将多个音频按声道顺序交织为一个多声道音频
+    ///
+    /// 所有输入的采样率必须一致；较短的声道在末尾补零。
+    pub fn interleave(tracks: &[AudioData]) -> Result<AudioData> {
+        let first = tracks
+            .first()
+            .ok_or_else(|| VoiceError::AudioFormatError("没有可合并的音轨".to_string()))?;
+        if tracks.iter().any(|t| t.sample_rate != first.sample_rate) {
+            return Err(VoiceError::AudioFormatError(
+                "音轨采样率不一致，无法合并".to_string(),
+            ));
+        }
+
+        let channels: Vec<AudioData> = tracks.iter().flat_map(|t| t.split_channels()).collect();
+        let channel_count = u16::try_from(channels.len())
+            .map_err(|_| VoiceError::AudioFormatError("声道数过多".to_string()))?;
+        let frames = channels.iter().map(|c| c.samples.len()).max().unwrap_or(0);
+
+        let mut samples = Vec::with_capacity(frames * channels.len());
+        for frame in 0..frames {
+            for channel in &channels {
+                samples.push(channel.samples.get(frame).copied().unwrap_or(0));
+            }
+        }
+
+        Ok(AudioData::new(samples, first.sample_rate, channel_count))
+    }
 }
 
 /// 识别结果
@@ -95,6 +197,39 @@ pub struct Segment {
     pub end: f32,
     /// 文本内容
     pub text: String,
+    /// 来源声道（多声道录音时）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u16>,
+    /// 说话人标签
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+/// 流式识别的中间结果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartialTranscript {
+    /// 截至目前的完整识别文本（不是增量）
+    pub text: String,
+    /// 是否为最终结果
+    pub is_final: bool,
+}
+
+impl PartialTranscript {
+    /// 中间结果
+    pub fn interim(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            is_final: false,
+        }
+    }
+
+    /// 最终结果
+    pub fn final_result(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            is_final: true,
+        }
+    }
 }
 
 /// ASR 引擎类型
@@ -112,7 +247,7 @@ pub enum AsrEngine {
 }
 
 /// Whisper 模型大小
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum WhisperModel {
     /// tiny - 最小，最快
@@ -0,0 +1,129 @@
+//! 语音活动检测（VAD）
+//!
+//! 基于音量（RMS）的简单静音检测，用于免手动停止的语音输入：
+//! - 检测到足够长的语音后进入说话状态
+//! - 说话状态下持续静音超过设定时长，触发 [`VadEvent::SilenceDetected`]
+//!
+//! 录音服务据此自动停止录音或切分出一段音频，见 [`VadMode`]。
+
+use serde::{Deserialize, Serialize};
+
+/// 检测到静音后的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum VadMode {
+    /// 自动停止录音
+    #[default]
+    AutoStop,
+    /// 切分出当前这段音频，继续录音
+    Segment,
+}
+
+/// VAD 配置
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VadConfig {
+    /// 是否启用
+    pub enabled: bool,
+    /// 静音阈值（RMS，0.0 - 1.0），低于该值视为静音
+    pub silence_threshold: f32,
+    /// 说话后持续静音多久触发（毫秒）
+    pub silence_duration_ms: u32,
+    /// 至少检测到多长的语音才算开始说话（毫秒），避免短促噪音误触发
+    pub min_speech_ms: u32,
+    /// 检测到静音后的动作
+    pub mode: VadMode,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            silence_threshold: 0.015,
+            silence_duration_ms: 1500,
+            min_speech_ms: 300,
+            mode: VadMode::AutoStop,
+        }
+    }
+}
+
+/// VAD 事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VadEvent {
+    /// 开始说话
+    SpeechStarted,
+    /// 说话后持续静音超过设定时长
+    SilenceDetected,
+}
+
+/// 语音活动检测器
+///
+/// 按录音回调的数据帧（单声道 f32 采样）逐帧输入，状态随帧累积。
+#[derive(Debug, Clone)]
+pub struct VoiceActivityDetector {
+    config: VadConfig,
+    /// 累计语音采样数（遇到足够长的静音后清零）
+    speech_samples: u64,
+    /// 连续静音采样数
+    silence_samples: u64,
+    /// 是否处于说话状态
+    speaking: bool,
+    min_speech_samples: u64,
+    silence_limit_samples: u64,
+}
+
+impl VoiceActivityDetector {
+    /// 创建检测器
+    pub fn new(config: VadConfig, sample_rate: u32) -> Self {
+        let samples_of = |ms: u32| sample_rate as u64 * ms as u64 / 1000;
+        Self {
+            min_speech_samples: samples_of(config.min_speech_ms),
+            silence_limit_samples: samples_of(config.silence_duration_ms).max(1),
+            config,
+            speech_samples: 0,
+            silence_samples: 0,
+            speaking: false,
+        }
+    }
+
+    /// 输入一帧单声道采样
+    pub fn process(&mut self, frame: &[f32]) -> Option<VadEvent> {
+        if frame.is_empty() {
+            return None;
+        }
+
+        let sum_sq: f32 = frame.iter().map(|s| s * s).sum();
+        let rms = (sum_sq / frame.len() as f32).sqrt();
+        let len = frame.len() as u64;
+
+        if rms >= self.config.silence_threshold {
+            self.silence_samples = 0;
+            self.speech_samples += len;
+            if !self.speaking && self.speech_samples >= self.min_speech_samples {
+                self.speaking = true;
+                return Some(VadEvent::SpeechStarted);
+            }
+            return None;
+        }
+
+        self.silence_samples += len;
+        if self.silence_samples < self.silence_limit_samples {
+            return None;
+        }
+
+        let was_speaking = self.speaking;
+        self.reset();
+        was_speaking.then_some(VadEvent::SilenceDetected)
+    }
+
+    /// 是否处于说话状态
+    pub fn is_speaking(&self) -> bool {
+        self.speaking
+    }
+
+    /// 重置状态
+    pub fn reset(&mut self) {
+        self.speech_samples = 0;
+        self.silence_samples = 0;
+        self.speaking = false;
+    }
+}
@@ -109,6 +109,32 @@ mod xunfei {
         }
     }
 
+    #[tokio::test]
+    async fn test_xunfei_transcribe_stream() {
+        let Some((app_id, api_key, api_secret)) = get_xunfei_credentials() else {
+            eprintln!("跳过测试: 未设置讯飞凭证环境变量");
+            return;
+        };
+
+        let client = XunfeiClient::new(app_id, api_key, api_secret);
+        let audio = generate_sine_wave_audio(2.0, 440.0);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let result = client
+            .transcribe_stream(&audio, tx)
+            .await
+            .unwrap_or_else(|e| panic!("❌ 讯飞流式识别失败: {e:?}"));
+
+        let mut partial_count = 0;
+        while let Ok(partial) = rx.try_recv() {
+            assert!(!partial.is_final);
+            partial_count += 1;
+        }
+        println!("✅ 讯飞流式识别成功");
+        println!("   中间结果数: {partial_count}");
+        println!("   识别结果: {:?}", result.text);
+    }
+
     #[tokio::test]
     async fn test_xunfei_invalid_credentials() {
         let client = XunfeiClient::new(
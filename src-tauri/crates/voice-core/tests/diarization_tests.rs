@@ -0,0 +1,112 @@
+//! 多声道音频与说话人区分测试
+
+use async_trait::async_trait;
+use voice_core::asr_client::AsrClient;
+use voice_core::diarization::{ChannelDiarizer, DiarizationConfig};
+use voice_core::types::{AudioData, TranscribeResult};
+use voice_core::Result;
+
+const SAMPLE_RATE: u32 = 16000;
+
+/// 生成单声道音频：`(时长秒, 振幅)` 依次拼接
+fn track(parts: &[(f32, i16)]) -> AudioData {
+    let samples = parts
+        .iter()
+        .flat_map(|&(secs, amplitude)| {
+            let len = (SAMPLE_RATE as f32 * secs) as usize;
+            (0..len).map(move |i| if i % 2 == 0 { amplitude } else { -amplitude })
+        })
+        .collect();
+    AudioData::new(samples, SAMPLE_RATE, 1)
+}
+
+/// 按片段时长返回固定文本的 ASR 客户端
+struct EchoClient;
+
+#[async_trait]
+impl AsrClient for EchoClient {
+    async fn transcribe(&self, audio: &AudioData) -> Result<TranscribeResult> {
+        assert_eq!(audio.channels, 1);
+        Ok(TranscribeResult {
+            text: format!("{:.1}s", audio.duration_secs),
+            language: Some("zh".to_string()),
+            confidence: None,
+            segments: Vec::new(),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+}
+
+#[test]
+fn test_interleave_and_split_channels() {
+    let left = AudioData::new(vec![1, 2, 3], SAMPLE_RATE, 1);
+    let right = AudioData::new(vec![-1, -2], SAMPLE_RATE, 1);
+
+    let stereo = AudioData::interleave(&[left, right]).unwrap();
+    assert_eq!(stereo.channels, 2);
+    assert_eq!(stereo.samples, vec![1, -1, 2, -2, 3, 0]);
+    assert_eq!(stereo.frames(), 3);
+
+    let channels = stereo.split_channels();
+    assert_eq!(channels[0].samples, vec![1, 2, 3]);
+    assert_eq!(channels[1].samples, vec![-1, -2, 0]);
+    assert_eq!(stereo.to_mono().samples, vec![0, 0, 1]);
+}
+
+#[test]
+fn test_interleave_rejects_mismatched_sample_rate() {
+    let a = AudioData::new(vec![0; 10], 16000, 1);
+    let b = AudioData::new(vec![0; 10], 44100, 1);
+    assert!(AudioData::interleave(&[a, b]).is_err());
+    assert!(AudioData::interleave(&[]).is_err());
+}
+
+#[test]
+fn test_detect_turns_by_dominant_channel() {
+    // 声道 0 先说 1 秒，随后声道 1 说 1 秒（声道 0 有轻微串音）
+    let left = track(&[(1.0, 8000), (1.0, 300)]);
+    let right = track(&[(1.0, 0), (1.0, 8000)]);
+    let audio = AudioData::interleave(&[left, right]).unwrap();
+
+    let turns = ChannelDiarizer::default().detect_turns(&audio);
+    assert_eq!(turns.len(), 2);
+    assert_eq!(turns[0].channel, 0);
+    assert_eq!(turns[1].channel, 1);
+    assert!((turns[0].end - 1.0).abs() < 0.05);
+    assert!((turns[1].start - turns[0].end).abs() < 0.05);
+}
+
+#[test]
+fn test_short_pause_merged_and_blips_dropped() {
+    let channel = track(&[(0.6, 8000), (0.3, 0), (0.6, 8000), (1.0, 0), (0.1, 8000)]);
+
+    let turns = ChannelDiarizer::default().detect_turns(&channel);
+    assert_eq!(turns.len(), 1);
+    assert!(turns[0].start.abs() < 0.05);
+    assert!((turns[0].end - 1.5).abs() < 0.05);
+}
+
+#[tokio::test]
+async fn test_transcribe_tags_segments_with_speaker() {
+    let left = track(&[(1.0, 8000), (1.0, 0)]);
+    let right = track(&[(1.0, 0), (1.0, 8000)]);
+    let audio = AudioData::interleave(&[left, right]).unwrap();
+
+    let diarizer = ChannelDiarizer::new(DiarizationConfig {
+        speaker_labels: vec!["主持人".to_string()],
+        ..DiarizationConfig::default()
+    });
+    let result = diarizer.transcribe(&EchoClient, &audio).await.unwrap();
+
+    assert_eq!(result.segments.len(), 2);
+    assert_eq!(result.segments[0].speaker.as_deref(), Some("主持人"));
+    assert_eq!(result.segments[0].channel, Some(0));
+    assert_eq!(result.segments[1].speaker.as_deref(), Some("说话人 2"));
+    assert_eq!(result.segments[1].channel, Some(1));
+    assert!(result.segments[1].start >= 0.95);
+    assert_eq!(result.text, "主持人: 1.0s\n说话人 2: 1.0s");
+    assert_eq!(result.language.as_deref(), Some("zh"));
+}
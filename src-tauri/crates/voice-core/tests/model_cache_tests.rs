@@ -0,0 +1,70 @@
+//! 模型实例缓存测试
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use voice_core::model_cache::{ModelCache, WhisperCacheKey};
+use voice_core::types::WhisperModel;
+
+#[test]
+fn test_get_or_try_load_caches_instance() {
+    let cache: ModelCache<WhisperCacheKey, String> = ModelCache::new(2);
+    let loads = AtomicUsize::new(0);
+    let key = WhisperCacheKey::new(WhisperModel::Base, "zh");
+
+    for _ in 0..3 {
+        let value = cache
+            .get_or_try_load(&key, || {
+                loads.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, String>("base-zh".to_string())
+            })
+            .unwrap();
+        assert_eq!(value.as_str(), "base-zh");
+    }
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    // 加载失败不缓存
+    let failed = WhisperCacheKey::new(WhisperModel::Small, "zh");
+    let result = cache.get_or_try_load(&failed, || Err::<String, _>("missing model"));
+    assert_eq!(result.unwrap_err(), "missing model");
+    assert!(!cache.contains(&failed));
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_lru_eviction() {
+    let cache: ModelCache<WhisperCacheKey, u32> = ModelCache::new(2);
+    let tiny = WhisperCacheKey::new(WhisperModel::Tiny, "auto");
+    let base = WhisperCacheKey::new(WhisperModel::Base, "auto");
+    let small = WhisperCacheKey::new(WhisperModel::Small, "auto");
+
+    cache.insert(tiny.clone(), Arc::new(1));
+    cache.insert(base.clone(), Arc::new(2));
+    // 访问 tiny 后 base 成为最久未使用
+    assert_eq!(cache.get(&tiny).as_deref(), Some(&1));
+    cache.insert(small.clone(), Arc::new(3));
+
+    assert_eq!(cache.keys(), vec![tiny.clone(), small.clone()]);
+    assert!(!cache.contains(&base));
+
+    // 淘汰后正在使用的实例仍然有效
+    let in_use = cache.get(&tiny).unwrap();
+    cache.clear();
+    assert_eq!(*in_use, 1);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_invalidate_model_removes_all_languages() {
+    let cache: ModelCache<WhisperCacheKey, u32> = ModelCache::new(4);
+    cache.insert(WhisperCacheKey::new(WhisperModel::Base, "zh"), Arc::new(1));
+    cache.insert(WhisperCacheKey::new(WhisperModel::Base, "en"), Arc::new(2));
+    cache.insert(WhisperCacheKey::new(WhisperModel::Tiny, "zh"), Arc::new(3));
+
+    assert_eq!(cache.invalidate_model(WhisperModel::Base), 2);
+    assert_eq!(
+        cache.keys(),
+        vec![WhisperCacheKey::new(WhisperModel::Tiny, "zh")]
+    );
+    assert_eq!(ModelCache::<WhisperCacheKey, u32>::new(0).capacity(), 1);
+}
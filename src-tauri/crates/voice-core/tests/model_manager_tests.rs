@@ -0,0 +1,78 @@
+//! Whisper 模型管理测试
+
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+
+use voice_core::model_manager::{WhisperModelManager, WHISPER_MODELS};
+use voice_core::types::WhisperModel;
+use voice_core::VoiceError;
+
+fn temp_models_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("voice-core-models-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_list_reports_downloaded_and_partial() {
+    let dir = temp_models_dir("list");
+    std::fs::write(dir.join("ggml-tiny.bin"), b"model").unwrap();
+    std::fs::write(dir.join("ggml-base.bin.part"), vec![0u8; 1024]).unwrap();
+
+    let manager = WhisperModelManager::new(&dir);
+    let models = manager.list();
+    assert_eq!(models.len(), WHISPER_MODELS.len());
+
+    let tiny = models
+        .iter()
+        .find(|m| m.model == WhisperModel::Tiny)
+        .unwrap();
+    assert!(tiny.downloaded);
+    let base = models
+        .iter()
+        .find(|m| m.model == WhisperModel::Base)
+        .unwrap();
+    assert!(!base.downloaded);
+    assert_eq!(base.partial_bytes, 1024);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_delete_removes_model_and_partial() {
+    let dir = temp_models_dir("delete");
+    std::fs::write(dir.join("ggml-small.bin"), b"model").unwrap();
+    std::fs::write(dir.join("ggml-small.bin.part"), b"partial").unwrap();
+
+    let manager = WhisperModelManager::new(&dir);
+    assert!(manager.delete(WhisperModel::Small).unwrap());
+    assert!(!dir.join("ggml-small.bin").exists());
+    assert!(!dir.join("ggml-small.bin.part").exists());
+    assert!(!manager.delete(WhisperModel::Small).unwrap());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_download_skips_existing_and_rejects_unknown_model() {
+    let dir = temp_models_dir("download");
+    std::fs::write(dir.join("ggml-medium.bin"), b"model").unwrap();
+
+    // 不可达的下载地址：已下载的模型不发起请求
+    let manager = WhisperModelManager::new(&dir).with_base_url("http://127.0.0.1:9");
+    let cancel = AtomicBool::new(false);
+    let path = manager
+        .download(WhisperModel::Medium, &cancel, |_| {})
+        .await
+        .unwrap();
+    assert_eq!(path, dir.join("ggml-medium.bin"));
+
+    let err = manager
+        .download(WhisperModel::Large, &cancel, |_| {})
+        .await
+        .unwrap_err();
+    assert!(matches!(err, VoiceError::ModelDownloadError(_)));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,38 @@
+//! 语音合成测试
+
+use voice_core::types::AudioData;
+
+#[test]
+fn test_wav_roundtrip() {
+    let audio = AudioData::new(vec![0, 1000, -1000, i16::MAX, i16::MIN], 24000, 1);
+    let decoded = AudioData::from_wav_bytes(&audio.to_wav_bytes()).unwrap();
+
+    assert_eq!(decoded.samples, audio.samples);
+    assert_eq!(decoded.sample_rate, 24000);
+    assert_eq!(decoded.channels, 1);
+}
+
+#[test]
+fn test_float_wav_converted_to_i16() {
+    let mut cursor = std::io::Cursor::new(Vec::new());
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: 16000,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+    for sample in [0.0f32, 1.0, -1.0, 2.0] {
+        writer.write_sample(sample).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let decoded = AudioData::from_wav_bytes(&cursor.into_inner()).unwrap();
+    assert_eq!(decoded.samples, vec![0, i16::MAX, -i16::MAX, i16::MAX]);
+    assert_eq!(decoded.channels, 2);
+}
+
+#[test]
+fn test_invalid_wav_rejected() {
+    assert!(AudioData::from_wav_bytes(b"not a wav file").is_err());
+}
@@ -0,0 +1,62 @@
+//! 语音活动检测测试
+
+use voice_core::vad::{VadConfig, VadEvent, VoiceActivityDetector};
+
+const SAMPLE_RATE: u32 = 16000;
+
+/// 生成 100ms 的数据帧
+fn frame(amplitude: f32) -> Vec<f32> {
+    vec![amplitude; (SAMPLE_RATE / 10) as usize]
+}
+
+fn detector() -> VoiceActivityDetector {
+    VoiceActivityDetector::new(
+        VadConfig {
+            enabled: true,
+            silence_threshold: 0.02,
+            silence_duration_ms: 500,
+            min_speech_ms: 200,
+            ..VadConfig::default()
+        },
+        SAMPLE_RATE,
+    )
+}
+
+#[test]
+fn test_silence_after_speech_triggers_once() {
+    let mut vad = detector();
+
+    assert_eq!(vad.process(&frame(0.1)), None);
+    assert_eq!(vad.process(&frame(0.1)), Some(VadEvent::SpeechStarted));
+    assert!(vad.is_speaking());
+
+    let events: Vec<_> = (0..10).filter_map(|_| vad.process(&frame(0.0))).collect();
+    assert_eq!(events, vec![VadEvent::SilenceDetected]);
+    assert!(!vad.is_speaking());
+}
+
+#[test]
+fn test_silence_without_speech_does_not_trigger() {
+    let mut vad = detector();
+
+    // 短促噪音不足 min_speech_ms，不算开始说话
+    assert_eq!(vad.process(&frame(0.1)), None);
+    for _ in 0..10 {
+        assert_eq!(vad.process(&frame(0.001)), None);
+    }
+    assert!(!vad.is_speaking());
+}
+
+#[test]
+fn test_brief_pause_keeps_speaking() {
+    let mut vad = detector();
+    vad.process(&frame(0.1));
+    vad.process(&frame(0.1));
+
+    // 停顿短于 silence_duration_ms 后继续说话
+    for _ in 0..4 {
+        assert_eq!(vad.process(&frame(0.0)), None);
+    }
+    assert_eq!(vad.process(&frame(0.1)), None);
+    assert!(vad.is_speaking());
+}
@@ -6,6 +6,8 @@ use super::super::{protocol::*, WsError};
 use chrono::{DateTime, Timelike, Utc};
 use lime_core::database::dao::agent_run::{AgentRun, AgentRunDao, AgentRunStatus};
 use lime_core::database::dao::chat::{ChatDao, ChatMessage, ChatMode, ChatSession};
+use lime_core::database::dao::cost_usage::CostUsageDao;
+use lime_core::models::model_registry::MessageCost;
 use lime_scheduler::{
     AgentExecutor, AgentScheduler, ScheduledTask, SchedulerDao, TaskExecutor, TaskFilter,
     DEFAULT_TASK_COOLDOWN_SECS, DEFAULT_TASK_FAILURE_THRESHOLD,
@@ -266,7 +268,13 @@ impl RpcHandler {
             match executor.execute(&task, &db_for_task).await {
                 Ok(result) => {
                     let content = extract_result_content(&result);
-                    let _ = append_assistant_message(&db_for_task, &session_id_for_task, &content);
+                    let cost = resolve_result_cost(&db_for_task, &task.model, &result);
+                    let _ = append_assistant_message(
+                        &db_for_task,
+                        &session_id_for_task,
+                        &content,
+                        cost.as_ref(),
+                    );
                     finalize_run(
                         &db_for_task,
                         &run_id_for_task,
@@ -286,6 +294,7 @@ impl RpcHandler {
                         &db_for_task,
                         &session_id_for_task,
                         &error_content,
+                        None,
                     );
                     finalize_run(
                         &db_for_task,
@@ -922,10 +931,11 @@ fn append_assistant_message(
     db: &lime_core::database::DbConnection,
     session_id: &str,
     content: &str,
+    cost: Option<&MessageCost>,
 ) -> Result<(), String> {
     let now = Utc::now().to_rfc3339();
     let conn = lime_core::database::lock_db(db)?;
-    let assistant_message = ChatMessage {
+    let mut assistant_message = ChatMessage {
         id: 0,
         session_id: session_id.to_string(),
         role: "assistant".to_string(),
@@ -935,11 +945,33 @@ fn append_assistant_message(
         metadata: None,
         created_at: now,
     };
+    if let Some(cost) = cost {
+        assistant_message.set_cost(cost);
+    }
     ChatDao::add_message(&conn, &assistant_message)
         .map_err(|e| format!("append assistant message failed: {e}"))?;
     Ok(())
 }
 
+/// 从执行结果的 usage 中计算消息费用
+fn resolve_result_cost(
+    db: &lime_core::database::DbConnection,
+    model: &str,
+    result: &serde_json::Value,
+) -> Option<MessageCost> {
+    let usage = result.get("usage")?;
+    let input_tokens = usage.get("input_tokens")?.as_u64()? as u32;
+    let output_tokens = usage.get("output_tokens")?.as_u64()? as u32;
+    let conn = lime_core::database::lock_db(db).ok()?;
+    let pricing = CostUsageDao::get_model_pricing(&conn, model).ok().flatten();
+    Some(MessageCost::new(
+        Some(model.to_string()),
+        pricing.as_ref(),
+        input_tokens,
+        output_tokens,
+    ))
+}
+
 fn text_content(text: &str) -> serde_json::Value {
     json!([{ "type": "text", "text": text }])
 }
@@ -5,6 +5,7 @@
 
 use crate::agent::aster_state::{AsterAgentState, SessionConfigBuilder};
 use crate::config::GlobalConfigManagerState;
+use crate::database::pagination::{Page, PageRequest};
 use crate::database::DbConnection;
 use crate::services::memory_profile_prompt_service::{
     merge_system_prompt_with_memory_context, MemoryPromptContext,
@@ -167,6 +168,14 @@ impl AsterAgentWrapper {
         lime_agent::list_sessions_sync(db)
     }
 
+    /// 分页列出会话
+    pub fn list_sessions_page_sync(
+        db: &DbConnection,
+        page: &PageRequest,
+    ) -> Result<Page<SessionInfo>, String> {
+        lime_agent::list_sessions_page_sync(db, page)
+    }
+
     /// 获取会话详情
     pub fn get_session_sync(db: &DbConnection, session_id: &str) -> Result<SessionDetail, String> {
         lime_agent::get_session_sync(db, session_id)
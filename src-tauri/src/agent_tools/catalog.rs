@@ -2,6 +2,8 @@ use crate::mcp::McpToolDefinition;
 use serde::{Deserialize, Serialize};
 
 pub const TOOL_SEARCH_TOOL_NAME: &str = "tool_search";
+pub const CODE_SEARCH_SYMBOLS_TOOL_NAME: &str = "code_search_symbols";
+pub const CODE_FIND_REFERENCES_TOOL_NAME: &str = "code_find_references";
 pub const SOCIAL_IMAGE_TOOL_NAME: &str = "social_generate_cover_image";
 pub const LIME_CREATE_VIDEO_TASK_TOOL_NAME: &str = "lime_create_video_generation_task";
 pub const LIME_CREATE_BROADCAST_TASK_TOOL_NAME: &str = "lime_create_broadcast_generation_task";
@@ -311,6 +313,24 @@ static NATIVE_TOOL_CATALOG: &[ToolCatalogEntry] = &[
         permission_plane: ToolPermissionPlane::SessionAllowlist,
         workspace_default_allow: true,
     },
+    ToolCatalogEntry {
+        name: CODE_SEARCH_SYMBOLS_TOOL_NAME,
+        profiles: CORE_PROFILES,
+        capabilities: WORKSPACE_IO_CAP,
+        lifecycle: ToolLifecycle::Current,
+        source: ToolSourceKind::LimeInjected,
+        permission_plane: ToolPermissionPlane::SessionAllowlist,
+        workspace_default_allow: true,
+    },
+    ToolCatalogEntry {
+        name: CODE_FIND_REFERENCES_TOOL_NAME,
+        profiles: CORE_PROFILES,
+        capabilities: WORKSPACE_IO_CAP,
+        lifecycle: ToolLifecycle::Current,
+        source: ToolSourceKind::LimeInjected,
+        permission_plane: ToolPermissionPlane::SessionAllowlist,
+        workspace_default_allow: true,
+    },
     ToolCatalogEntry {
         name: "spawn_agent",
         profiles: CORE_PROFILES,
@@ -627,6 +647,8 @@ mod tests {
         let names = workspace_default_allowed_tool_names(WorkspaceToolSurface::core());
         assert!(names.contains(&"spawn_agent"));
         assert!(names.contains(&"WebSearch"));
+        assert!(names.contains(&CODE_SEARCH_SYMBOLS_TOOL_NAME));
+        assert!(names.contains(&CODE_FIND_REFERENCES_TOOL_NAME));
         assert!(!names.contains(&"SubAgentTask"));
         assert!(!names.contains(&"read"));
         assert!(!names.contains(&"bash"));
@@ -643,12 +665,12 @@ mod tests {
     #[test]
     fn test_tool_catalog_entries_for_surface_counts_and_lifecycle_boundaries() {
         let core = tool_catalog_entries_for_surface(WorkspaceToolSurface::core());
-        assert_eq!(core.len(), 26);
+        assert_eq!(core.len(), 28);
         assert_eq!(
             core.iter()
                 .filter(|entry| entry.lifecycle == ToolLifecycle::Current)
                 .count(),
-            25
+            27
         );
         assert_eq!(
             core.iter()
@@ -664,7 +686,7 @@ mod tests {
             .all(|entry| !entry.profiles.contains(&ToolSurfaceProfile::BrowserAssist)));
 
         let creator = tool_catalog_entries_for_surface(WorkspaceToolSurface::creator());
-        assert_eq!(creator.len(), 34);
+        assert_eq!(creator.len(), 36);
         assert!(creator
             .iter()
             .any(|entry| entry.name == SOCIAL_IMAGE_TOOL_NAME));
@@ -673,14 +695,14 @@ mod tests {
             .any(|entry| entry.name == BROWSER_RUNTIME_TOOL_PREFIX));
 
         let browser = tool_catalog_entries_for_surface(WorkspaceToolSurface::browser_assist());
-        assert_eq!(browser.len(), 27);
+        assert_eq!(browser.len(), 29);
         assert!(browser
             .iter()
             .any(|entry| entry.name == BROWSER_RUNTIME_TOOL_PREFIX));
 
         let combined =
             tool_catalog_entries_for_surface(WorkspaceToolSurface::creator_with_browser_assist());
-        assert_eq!(combined.len(), 35);
+        assert_eq!(combined.len(), 37);
     }
 
     #[test]
@@ -699,7 +721,7 @@ mod tests {
         let names = workspace_default_allowed_tool_names(
             WorkspaceToolSurface::creator_with_browser_assist(),
         );
-        assert_eq!(names.len(), 22);
+        assert_eq!(names.len(), 24);
         assert!(names.contains(&SOCIAL_IMAGE_TOOL_NAME));
         assert!(names.contains(&"tool_search"));
         assert!(!names
@@ -774,7 +774,7 @@ mod tests {
             ],
         });
 
-        assert_eq!(inventory.counts.catalog_total, 26);
+        assert_eq!(inventory.counts.catalog_total, 28);
         assert_eq!(inventory.counts.registry_total, 3);
         assert_eq!(inventory.counts.registry_visible_total, 2);
         assert_eq!(inventory.counts.registry_catalog_unmapped_total, 1);
@@ -910,8 +910,8 @@ mod tests {
         .map(ToString::to_string)
         .collect::<Vec<_>>();
 
-        assert_eq!(inventory.counts.catalog_total, 35);
-        assert_eq!(inventory.counts.catalog_current_total, 34);
+        assert_eq!(inventory.counts.catalog_total, 37);
+        assert_eq!(inventory.counts.catalog_current_total, 36);
         assert_eq!(inventory.counts.catalog_compat_total, 1);
         assert_eq!(inventory.default_allowed_tools, expected_default_allowed);
         assert_eq!(
@@ -9,6 +9,7 @@ use crate::agent::{initialize_aster_runtime, AsterAgentState};
 use crate::commands::api_key_provider_cmd::ApiKeyProviderServiceState;
 use crate::commands::connect_cmd::ConnectStateWrapper;
 use crate::commands::context_memory::ContextMemoryServiceState;
+use crate::commands::extension_registry_cmd::ExtensionRegistryState;
 use crate::commands::machine_id_cmd::MachineIdState;
 use crate::commands::model_registry_cmd::ModelRegistryState;
 use crate::commands::orchestrator_cmd::OrchestratorState;
@@ -36,6 +37,7 @@ use lime_scheduler::AgentScheduler;
 use lime_server as server;
 use lime_services::api_key_provider_service::ApiKeyProviderService;
 use lime_services::context_memory_service::{ContextMemoryConfig, ContextMemoryService};
+use lime_services::extension_registry_service::ExtensionRegistryService;
 use lime_services::provider_pool_service::ProviderPoolService;
 use lime_services::skill_service::SkillService;
 use lime_services::token_cache_service::TokenCacheService;
@@ -51,6 +53,7 @@ pub struct AppStates {
     pub logs: LogState,
     pub db: DbConnection,
     pub skill_service: SkillServiceState,
+    pub extension_registry: ExtensionRegistryState,
     pub provider_pool_service: ProviderPoolServiceState,
     pub api_key_provider_service: ApiKeyProviderServiceState,
     pub credential_sync_service: CredentialSyncServiceState,
@@ -60,6 +63,7 @@ pub struct AppStates {
     pub plugin_manager: PluginManagerState,
     pub plugin_installer: PluginInstallerState,
     pub plugin_rpc_manager: crate::commands::plugin_rpc_cmd::PluginRpcManagerState,
+    pub plugin_crypto: crate::commands::plugin_cmd::PluginCryptoState,
     pub telemetry: crate::commands::telemetry_cmd::TelemetryState,
     pub aster_agent: AsterAgentState,
     pub orchestrator: OrchestratorState,
@@ -74,6 +78,7 @@ pub struct AppStates {
     pub context_memory_service: ContextMemoryServiceState,
     pub recording_service: RecordingServiceState,
     pub mcp_manager: McpManagerState,
+    pub mcp_elicitation: crate::mcp::McpElicitationState,
     pub automation_service: AutomationServiceState,
     pub workflow_service: Arc<RwLock<lime_services::content_creator::WorkflowService>>,
     pub progress_store: Arc<RwLock<lime_services::content_creator::ProgressStore>>,
@@ -105,6 +110,11 @@ pub fn init_states(config: &Config) -> Result<AppStates, String> {
         &config.logging,
     )));
 
+    // 凭证池数据加密（需在数据库初始化前注册，明文行在首次读取时加密）
+    database::credential_cipher::set_credential_cipher(Arc::new(
+        lime_credential::ProviderCredentialCipher::keychain(),
+    ));
+
     // 数据库
     let db = database::init_database().map_err(|e| format!("数据库初始化失败: {e}"))?;
 
@@ -129,8 +139,17 @@ pub fn init_states(config: &Config) -> Result<AppStates, String> {
 
     initialize_aster_runtime(db.clone()).map_err(|e| format!("Aster 运行时初始化失败: {e}"))?;
 
+    // 旧版 config.yaml 凭证迁移到凭证池（后续状态使用回写了迁移标记的配置）
+    let config = &migrate_legacy_config_credentials(&db, config);
+
     // 服务状态
-    let skill_service = SkillService::new().map_err(|e| format!("SkillService 初始化失败: {e}"))?;
+    let extension_registry = ExtensionRegistryService::new(config.extension_registries.clone())
+        .map_err(|e| format!("ExtensionRegistryService 初始化失败: {e}"))?;
+    let extension_registry_state = ExtensionRegistryState(Arc::new(extension_registry));
+
+    let skill_service = SkillService::new()
+        .map_err(|e| format!("SkillService 初始化失败: {e}"))?
+        .with_extension_registry(extension_registry_state.0.clone());
     let skill_service_state = SkillServiceState(Arc::new(skill_service));
 
     let provider_pool_service = ProviderPoolService::new();
@@ -165,8 +184,18 @@ pub fn init_states(config: &Config) -> Result<AppStates, String> {
 
     let resilience_config_state = ResilienceConfigState::default();
 
-    // 插件管理器
-    let plugin_manager = plugin::PluginManager::with_defaults();
+    // 插件管理器（安全模式下禁用插件加载）
+    let plugin_manager = if lime_core::safe_mode::is_safe_mode() {
+        plugin::PluginManager::new(
+            plugin::PluginLoader::default_plugins_dir(),
+            plugin::PluginManagerConfig {
+                enabled: false,
+                ..Default::default()
+            },
+        )
+    } else {
+        plugin::PluginManager::with_defaults()
+    };
     let plugin_manager_state = PluginManagerState(Arc::new(RwLock::new(plugin_manager)));
 
     // 插件安装器
@@ -175,6 +204,9 @@ pub fn init_states(config: &Config) -> Result<AppStates, String> {
     // 插件 RPC 管理器
     let plugin_rpc_manager_state = crate::commands::plugin_rpc_cmd::PluginRpcManagerState::new();
 
+    // 插件加密（主密钥在首次使用时从系统钥匙串加载）
+    let plugin_crypto_state = crate::commands::plugin_cmd::PluginCryptoState::new();
+
     // 遥测系统
     let (telemetry_state, shared_stats, shared_tokens, shared_logger) = init_telemetry(config)?;
 
@@ -248,7 +280,23 @@ pub fn init_states(config: &Config) -> Result<AppStates, String> {
     let recording_service_state = create_recording_service_state();
 
     // 初始化 MCP 客户端管理器（延迟设置 AppHandle，在 setup hook 中完成）
-    let mcp_manager = crate::mcp::McpClientManager::new(None);
+    let mut mcp_manager = crate::mcp::McpClientManager::new(None);
+    match lime_core::app_paths::resolve_logs_dir() {
+        Ok(logs_dir) => {
+            mcp_manager.set_log_store(Arc::new(crate::mcp::McpLogStore::new(logs_dir.join("mcp"))))
+        }
+        Err(e) => tracing::warn!("[启动] 无法解析日志目录，MCP 日志不落盘: {}", e),
+    }
+    // 回收上次异常退出遗留的 MCP 子进程（只处理其他会话的进程，不影响本次启动的服务器）
+    let mcp_process_registry = Arc::new(crate::mcp::McpProcessRegistry::with_default_file());
+    mcp_manager.set_process_registry(mcp_process_registry.clone());
+    std::thread::spawn(move || {
+        let killed = mcp_process_registry.cleanup_orphans();
+        if killed > 0 {
+            tracing::info!("[启动] 已终止 {} 个遗留的 MCP 子进程", killed);
+        }
+    });
+    let mcp_elicitation_state = mcp_manager.elicitation_broker();
     let mcp_manager_state: McpManagerState = Arc::new(tokio::sync::Mutex::new(mcp_manager));
 
     // 初始化自动化调度服务
@@ -272,6 +320,7 @@ pub fn init_states(config: &Config) -> Result<AppStates, String> {
         logs,
         db,
         skill_service: skill_service_state,
+        extension_registry: extension_registry_state,
         provider_pool_service: provider_pool_service_state,
         api_key_provider_service: api_key_provider_service_state,
         credential_sync_service: credential_sync_service_state,
@@ -281,6 +330,7 @@ pub fn init_states(config: &Config) -> Result<AppStates, String> {
         plugin_manager: plugin_manager_state,
         plugin_installer: plugin_installer_state,
         plugin_rpc_manager: plugin_rpc_manager_state,
+        plugin_crypto: plugin_crypto_state,
         telemetry: telemetry_state,
         aster_agent: aster_agent_state,
         orchestrator: orchestrator_state,
@@ -295,6 +345,7 @@ pub fn init_states(config: &Config) -> Result<AppStates, String> {
         context_memory_service: context_memory_service_state,
         recording_service: recording_service_state,
         mcp_manager: mcp_manager_state,
+        mcp_elicitation: mcp_elicitation_state,
         automation_service: automation_service_state,
         workflow_service: workflow_service_state,
         progress_store: progress_store_state,
@@ -304,6 +355,56 @@ pub fn init_states(config: &Config) -> Result<AppStates, String> {
     })
 }
 
+/// 将 config.yaml 中尚未迁移的凭证写入凭证池，保存迁移报告并回写配置
+///
+/// 迁移失败不影响启动，返回原配置。
+fn migrate_legacy_config_credentials(db: &DbConnection, config: &Config) -> Config {
+    let mut migrated = config.clone();
+    let report = match lime_credential::migrate_legacy_credentials(db, &mut migrated) {
+        Ok(report) => report,
+        Err(error) => {
+            tracing::warn!("[Bootstrap] 旧版凭证迁移失败: {}", error);
+            return config.clone();
+        }
+    };
+    if report.finished_at.is_none() {
+        return migrated;
+    }
+
+    tracing::info!(
+        "[Bootstrap] 旧版凭证迁移完成: migrated={}, skipped={}, failed={}",
+        report.migrated.len(),
+        report.skipped.len(),
+        report.failed.len()
+    );
+    for item in &report.failed {
+        tracing::warn!(
+            "[Bootstrap] 旧版凭证 {}:{} 迁移失败: {}",
+            item.section,
+            item.id,
+            item.reason.as_deref().unwrap_or_default()
+        );
+    }
+    let report_path = lime_core::app_paths::best_effort_app_data_file(
+        lime_credential::LEGACY_CREDENTIAL_MIGRATION_REPORT_FILE,
+    );
+    if let Err(error) = report.save(&report_path) {
+        tracing::warn!("[Bootstrap] 保存旧版凭证迁移报告失败: {}", error);
+    }
+    if report.has_changes() {
+        let config_path = ConfigManager::default_config_path();
+        if let Err(error) =
+            lime_core::config::YamlService::save_preserve_comments(&config_path, &migrated)
+        {
+            tracing::warn!(
+                "[Bootstrap] 回写迁移标记失败，下次启动将重新检查: {}",
+                error
+            );
+        }
+    }
+    migrated
+}
+
 fn build_context_memory_config(config: &Config) -> ContextMemoryConfig {
     let mut context_config = ContextMemoryConfig::default();
     let memory_config = &config.memory;
@@ -342,7 +443,9 @@ fn init_plugin_installer() -> Result<PluginInstallerState, String> {
     ) {
         Ok(installer) => {
             tracing::info!("[启动] 插件安装器初始化成功");
-            Ok(PluginInstallerState(Arc::new(RwLock::new(installer))))
+            Ok(PluginInstallerState(Arc::new(RwLock::new(
+                installer.with_app_version(env!("CARGO_PKG_VERSION")),
+            ))))
         }
         Err(e) => {
             tracing::error!("[启动] 插件安装器初始化失败: {}", e);
@@ -357,7 +460,9 @@ fn init_plugin_installer() -> Result<PluginInstallerState, String> {
                 &db_path,
             )
             .map_err(|e| format!("后备插件安装器初始化失败: {e}"))?;
-            Ok(PluginInstallerState(Arc::new(RwLock::new(installer))))
+            Ok(PluginInstallerState(Arc::new(RwLock::new(
+                installer.with_app_version(env!("CARGO_PKG_VERSION")),
+            ))))
         }
     }
 }
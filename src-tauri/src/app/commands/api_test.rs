@@ -98,6 +98,7 @@ pub async fn check_api_compatibility(
         | ProviderType::AzureOpenai
         | ProviderType::AwsBedrock
         | ProviderType::Ollama => vec![],
+        ProviderType::Mock => vec![("mock-model", "basic")],
     };
 
     for (model, test_type) in test_cases {
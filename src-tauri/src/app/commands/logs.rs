@@ -2,7 +2,7 @@
 //!
 //! 包含日志查询和清理命令。
 
-use crate::app::types::LogState;
+use crate::app::types::{AppState, LogState};
 use crate::logger;
 use chrono::Utc;
 use flate2::read::GzDecoder;
@@ -607,6 +607,9 @@ struct SupportBundleManifest {
     #[serde(skip_serializing_if = "Option::is_none")]
     windows_startup_diagnostics:
         Option<crate::commands::windows_startup_cmd::WindowsStartupDiagnostics>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pipeline_snapshot: Option<String>,
+    feature_flags: Vec<lime_core::config::FeatureFlagState>,
     included_sections: Vec<String>,
     omitted_sections: Vec<String>,
 }
@@ -622,12 +625,16 @@ struct SupportBundleContext {
     persisted_log_tail: Vec<logger::LogEntry>,
     windows_startup_diagnostics:
         Option<crate::commands::windows_startup_cmd::WindowsStartupDiagnostics>,
+    pipeline_snapshot: Option<lime_core::config::PipelineSnapshot>,
+    feature_flags: Vec<lime_core::config::FeatureFlagState>,
 }
 
 #[tauri::command]
 pub async fn export_support_bundle(
     logs: tauri::State<'_, LogState>,
+    state: tauri::State<'_, AppState>,
     app: AppHandle,
+    snapshot_name: Option<String>,
 ) -> Result<SupportBundleExportResult, String> {
     let (log_file_path, in_memory_log_count) = {
         let logs = logs.read().await;
@@ -647,6 +654,16 @@ pub async fn export_support_bundle(
         .ok()
         .or_else(|| legacy_lime_dir.as_ref().map(|dir| dir.join("lime.db")));
     let output_directory = default_support_bundle_output_dir();
+    let pipeline_snapshot = match snapshot_name.filter(|name| !name.trim().is_empty()) {
+        Some(name) => Some(
+            lime_core::config::PipelineSnapshotStore::new(
+                lime_core::config::PipelineSnapshotStore::default_dir(),
+            )
+            .load(name.trim())?,
+        ),
+        None => None,
+    };
+    let feature_flags = state.read().await.config.feature_flags.states();
 
     let result = export_support_bundle_to(
         &output_directory,
@@ -661,6 +678,8 @@ pub async fn export_support_bundle(
             windows_startup_diagnostics: Some(
                 crate::commands::windows_startup_cmd::collect_windows_startup_diagnostics(&app),
             ),
+            pipeline_snapshot,
+            feature_flags,
         },
     )?;
 
@@ -843,7 +862,7 @@ fn write_support_bundle_readme(path: &Path, omitted_sections: &[String]) -> Resu
         .join("\n");
 
     let content = format!(
-        "Lime 支持包\n\n已包含：\n- meta/manifest.json\n- meta/log-storage-diagnostics.json\n- meta/persisted-log-tail.json\n- meta/appdata-listing.json（如目录存在）\n- meta/legacy-listing.json（如目录存在）\n- logs/（如目录存在）\n- request_logs/（如目录存在）\n\n默认未包含：\n{omitted}\n"
+        "Lime 支持包\n\n已包含：\n- meta/manifest.json\n- meta/log-storage-diagnostics.json\n- meta/persisted-log-tail.json\n- meta/appdata-listing.json（如目录存在）\n- meta/legacy-listing.json（如目录存在）\n- meta/pipeline-snapshot.json（如附加了管线快照）\n- logs/（如目录存在）\n- request_logs/（如目录存在）\n\n默认未包含：\n{omitted}\n"
     );
 
     fs::write(path, content)
@@ -954,13 +973,16 @@ fn export_support_bundle_to(
         }
     }
 
-    let included_sections = vec![
+    let mut included_sections = vec![
         "meta/manifest.json".to_string(),
         "meta/log-storage-diagnostics.json".to_string(),
         "meta/persisted-log-tail.json".to_string(),
         "logs/".to_string(),
         "request_logs/".to_string(),
     ];
+    if context.pipeline_snapshot.is_some() {
+        included_sections.push("meta/pipeline-snapshot.json".to_string());
+    }
     let omitted_sections = vec![
         "config 内容".to_string(),
         "数据库内容".to_string(),
@@ -1001,6 +1023,11 @@ fn export_support_bundle_to(
         log_storage_diagnostics: context.log_storage_diagnostics.clone(),
         persisted_log_tail_lines: context.persisted_log_tail.len(),
         windows_startup_diagnostics: context.windows_startup_diagnostics.clone(),
+        pipeline_snapshot: context
+            .pipeline_snapshot
+            .as_ref()
+            .map(|snapshot| snapshot.name.clone()),
+        feature_flags: context.feature_flags.clone(),
         included_sections: included_sections.clone(),
         omitted_sections: omitted_sections.clone(),
     };
@@ -1015,6 +1042,10 @@ fn export_support_bundle_to(
         &context.persisted_log_tail,
     )?;
 
+    if let Some(snapshot) = context.pipeline_snapshot.as_ref() {
+        write_support_json(&meta_dir.join("pipeline-snapshot.json"), snapshot)?;
+    }
+
     if let Some(app_data_dir) = context.app_data_dir.as_deref() {
         let entries = collect_directory_tree_entries(app_data_dir);
         write_support_json(&meta_dir.join("appdata-listing.json"), &entries)?;
@@ -1304,6 +1335,8 @@ mod tests {
                 log_storage_diagnostics: diagnostics,
                 persisted_log_tail: tail,
                 windows_startup_diagnostics: None,
+                pipeline_snapshot: None,
+                feature_flags: lime_core::config::FeatureFlagSettings::default().states(),
             },
         )
         .expect("导出支持包失败");
@@ -1346,5 +1379,6 @@ mod tests {
             .expect("读取 manifest 内容失败");
         assert!(manifest_content.contains("request_logs/"));
         assert!(manifest_content.contains("credentials 目录正文"));
+        assert!(manifest_content.contains("wasm_plugins"));
     }
 }
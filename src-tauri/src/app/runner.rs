@@ -52,13 +52,39 @@ fn reveal_main_window(window: &tauri::WebviewWindow) {
 pub fn run() {
     let _profiling_guard = crate::profiling::init();
 
-    // 加载并验证配置
-    let config = match bootstrap::load_and_validate_config() {
-        Ok(cfg) => cfg,
+    // 启动哨兵：连续启动失败时进入安全模式
+    let startup_sentinel = match lime_core::safe_mode::StartupSentinel::for_app() {
+        Ok(sentinel) => Some(sentinel),
         Err(err) => {
-            tracing::error!("{}", err);
-            eprintln!("{err}");
-            return;
+            tracing::warn!("[SafeMode] 无法创建启动哨兵: {}", err);
+            None
+        }
+    };
+    let safe_mode_report = startup_sentinel
+        .as_ref()
+        .map(|sentinel| sentinel.begin())
+        .unwrap_or_default();
+    if safe_mode_report.active {
+        tracing::warn!(
+            "[SafeMode] 连续 {} 次启动失败，以安全模式启动（默认配置、禁用插件与 MCP 自动启动）: {:?}",
+            safe_mode_report.consecutive_failures,
+            safe_mode_report.suspected_culprit
+        );
+    }
+    let safe_mode = safe_mode_report.active;
+    lime_core::safe_mode::set_safe_mode_report(safe_mode_report);
+
+    // 加载并验证配置（安全模式下使用默认配置，且不写回配置文件）
+    let config = if safe_mode {
+        lime_core::config::Config::default()
+    } else {
+        match bootstrap::load_and_validate_config() {
+            Ok(cfg) => cfg,
+            Err(err) => {
+                tracing::error!("{}", err);
+                eprintln!("{err}");
+                return;
+            }
         }
     };
 
@@ -66,10 +92,18 @@ pub fn run() {
         crate::services::environment_service::apply_configured_environment(&config),
     );
 
+    // 后台模式或开机自启动拉起时，启动后不显示主窗口，仅保留托盘
+    let background_mode = config.background_mode;
+    let start_in_background =
+        background_mode || commands::background_mode_cmd::is_background_launch();
+
     // 初始化崩溃上报（保持 guard 生命周期直到应用退出）
     let _crash_reporting_guard = crate::crash_reporting::init_from_config(&config);
 
     // 初始化所有应用状态
+    if let Some(sentinel) = &startup_sentinel {
+        sentinel.mark_stage(lime_core::safe_mode::STAGE_STATES);
+    }
     let states = match bootstrap::init_states(&config) {
         Ok(s) => s,
         Err(err) => {
@@ -85,6 +119,7 @@ pub fn run() {
         logs,
         db,
         skill_service: skill_service_state,
+        extension_registry: extension_registry_state,
         provider_pool_service: provider_pool_service_state,
         api_key_provider_service: api_key_provider_service_state,
         credential_sync_service: credential_sync_service_state,
@@ -94,6 +129,7 @@ pub fn run() {
         plugin_manager: plugin_manager_state,
         plugin_installer: plugin_installer_state,
         plugin_rpc_manager: plugin_rpc_manager_state,
+        plugin_crypto: plugin_crypto_state,
         telemetry: telemetry_state,
         aster_agent: aster_agent_state,
         orchestrator: orchestrator_state,
@@ -108,6 +144,7 @@ pub fn run() {
         context_memory_service,
         recording_service,
         mcp_manager: mcp_manager_state,
+        mcp_elicitation: mcp_elicitation_state,
         automation_service: automation_service_state,
         workflow_service,
         progress_store,
@@ -139,13 +176,16 @@ pub fn run() {
     let global_config_manager_for_setup = global_config_manager_state.clone();
     let tunnel_logs_clone = logs.clone();
 
+    // 正常退出时清除启动哨兵，避免启动后很快退出被误判为崩溃
+    let exit_sentinel = startup_sentinel.clone();
+
     let mut builder = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
-            Some(vec!["--minimized"]),
+            Some(vec![commands::background_mode_cmd::BACKGROUND_LAUNCH_ARG]),
         ));
 
     // 在 macOS 上注册 Deep Link 插件
@@ -171,6 +211,7 @@ pub fn run() {
         .manage(logs)
         .manage(db)
         .manage(skill_service_state)
+        .manage(extension_registry_state)
         .manage(provider_pool_service_state)
         .manage(api_key_provider_service_state)
         .manage(credential_sync_service_state)
@@ -181,6 +222,7 @@ pub fn run() {
         .manage(plugin_manager_state)
         .manage(plugin_installer_state)
         .manage(plugin_rpc_manager_state)
+        .manage(plugin_crypto_state)
         .manage(aster_agent_state)
         .manage(orchestrator_state)
         .manage(connect_state)
@@ -194,6 +236,7 @@ pub fn run() {
         .manage(context_memory_service)
         .manage(recording_service)
         .manage(mcp_manager_state)
+        .manage(mcp_elicitation_state)
         .manage(automation_service_state)
         .manage(workflow_service)
         .manage(progress_store)
@@ -207,6 +250,7 @@ pub fn run() {
         .manage(gateway_tunnel_state)
         .manage(crate::services::openclaw_service::OpenClawServiceState::default())
         .manage(commands::telegram_remote_cmd::TelegramRemoteState::default())
+        .manage(commands::oauth_flow_cmd::OAuthFlowState::default())
         .on_window_event(move |window, event| {
             // 处理窗口关闭事件
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
@@ -217,7 +261,7 @@ pub fn run() {
                     // 使用 block_on 同步获取配置
                     let minimize_to_tray = tauri::async_runtime::block_on(async {
                         let state = app_state.read().await;
-                        state.config.minimize_to_tray
+                        state.config.minimize_to_tray || state.config.background_mode
                     });
 
                     if should_minimize_to_tray(&window_label, minimize_to_tray) {
@@ -234,6 +278,10 @@ pub fn run() {
             }
         })
         .setup(move |app| {
+            if let Some(sentinel) = &startup_sentinel {
+                sentinel.mark_stage(lime_core::safe_mode::STAGE_SETUP);
+            }
+
             #[cfg(desktop)]
             if let Some(public_key) = compiled_updater_public_key() {
                 app.handle().plugin(
@@ -245,9 +293,18 @@ pub fn run() {
                 tracing::info!("[启动] 未注入 updater 公钥，跳过注册 updater 插件");
             }
 
+            #[cfg(target_os = "macos")]
+            if background_mode {
+                app.set_activation_policy(tauri::ActivationPolicy::Accessory);
+            }
+
             // 启动时先最大化再显示，避免用户看到“先小窗后展开”的过程。
             if let Some(main_window) = app.get_webview_window("main") {
-                reveal_main_window(&main_window);
+                if start_in_background {
+                    tracing::info!("[启动] 后台启动，主窗口保持隐藏，可通过托盘打开");
+                } else {
+                    reveal_main_window(&main_window);
+                }
 
                 #[cfg(debug_assertions)]
                 if crate::profiling::should_open_webview_devtools() {
@@ -306,14 +363,23 @@ pub fn run() {
                 tracing::info!("[启动] MCP Manager 事件发射器已设置");
             }
 
+            // 插件 UI 事件权限范围（插件 UI 只接收清单中声明的事件）
+            let plugin_event_scope = std::sync::Arc::new(lime_core::plugin::PluginEventScope::new());
+            let plugin_ui_emitter = crate::plugin::PluginUIEmitter::new(
+                app.handle().clone(),
+                plugin_event_scope.clone(),
+            );
+            app.manage(crate::plugin::PluginUIEmitterState(Some(
+                plugin_ui_emitter.clone(),
+            )));
+
             // 设置 PluginManager 的任务事件发射器（用于发送 plugin-task-event）
             if let Some(plugin_manager) =
                 app.try_state::<crate::commands::plugin_cmd::PluginManagerState>()
             {
-                let app_handle = app.handle().clone();
-                let emitter = lime_core::DynEmitter::new(
-                    crate::app::TauriEventEmitter(app_handle),
-                );
+                let emitter = lime_core::DynEmitter::new(crate::plugin::PluginTaskEventEmitter::new(
+                    plugin_ui_emitter.clone(),
+                ));
                 tauri::async_runtime::block_on(async {
                     let manager = plugin_manager.0.read().await;
                     manager.set_task_emitter(emitter).await;
@@ -321,6 +387,79 @@ pub fn run() {
                 tracing::info!("[启动] PluginManager 任务事件发射器已设置");
             }
 
+            // 插件事件总线按主题投递到声明了该主题的插件 UI
+            if let Some(plugin_rpc) =
+                app.try_state::<crate::commands::plugin_rpc_cmd::PluginRpcManagerState>()
+            {
+                let emitter = lime_core::DynEmitter::new(crate::plugin::PluginBusEventEmitter::new(
+                    plugin_ui_emitter.clone(),
+                ));
+                plugin_rpc.event_bus().set_emitter(emitter);
+                tracing::info!("[启动] 插件事件总线发射器已设置");
+            }
+
+            // 插件目录热重载（安全模式下不加载插件，也不监控）
+            if !lime_core::safe_mode::is_safe_mode() {
+                crate::plugin::hot_reload::spawn_plugin_hot_reload(app.handle().clone());
+            }
+
+            // 转发费用上限事件（cost-cap-event）、预算事件（cost-budget-event）、
+            // 剩余配额事件（quota-remaining-event）与快速修复建议（provider-quick-fix）
+            if let Some(app_state) = app.try_state::<AppState>() {
+                let (
+                    cost_cap_receiver,
+                    cost_budget_receiver,
+                    quota_remaining_receiver,
+                    quick_fix_receiver,
+                ) = tauri::async_runtime::block_on(async {
+                    let s = app_state.read().await;
+                    (
+                        s.cost_cap_guard.subscribe(),
+                        s.cost_ledger.subscribe(),
+                        s.quota_manager.subscribe_remaining(),
+                        s.quick_fixes.subscribe(),
+                    )
+                });
+                crate::commands::cost_cap_cmd::spawn_cost_cap_event_forwarder(
+                    app.handle().clone(),
+                    cost_cap_receiver,
+                );
+                crate::commands::cost_ledger_cmd::spawn_cost_budget_event_forwarder(
+                    app.handle().clone(),
+                    cost_budget_receiver,
+                );
+                crate::commands::quota_cmd::spawn_quota_remaining_event_forwarder(
+                    app.handle().clone(),
+                    quota_remaining_receiver,
+                );
+                crate::commands::quick_fix_cmd::spawn_provider_quick_fix_forwarder(
+                    app.handle().clone(),
+                    quick_fix_receiver,
+                );
+            }
+
+            // 定时用量报告
+            crate::commands::usage_report_cmd::spawn_usage_report_scheduler(app.handle().clone());
+
+            // 插件凭证定时健康检查
+            crate::commands::provider_pool_cmd::spawn_plugin_health_check_scheduler(
+                app.handle().clone(),
+            );
+
+            // OAuth Token 后台刷新
+            crate::commands::provider_pool_cmd::spawn_token_refresh_scheduler(app.handle().clone());
+
+            // 宽限期结束的凭证定时粉碎
+            crate::commands::credential_deletion_cmd::spawn_credential_shred_scheduler(
+                app.handle().clone(),
+            );
+
+            // 转发凭证池变更事件（provider-pool-event）
+            crate::commands::provider_pool_cmd::spawn_provider_pool_event_forwarder(
+                app.handle().clone(),
+                pool_service_clone.events().subscribe(),
+            );
+
             let startup_runtime_resume = {
                 let aster_agent_state = app.try_state::<crate::agent::AsterAgentState>();
                 let db_state = app.try_state::<crate::database::DbConnection>();
@@ -829,6 +968,29 @@ pub fn run() {
             });
             tracing::info!("[启动] 后台更新检查任务已启动");
 
+            // 定期清理过期的凭证会话亲和绑定，并写入有变化的限流节奏
+            let session_affinity = pool_service_clone.session_affinity().clone();
+            let risk_controller = pool_service_clone.risk_controller().clone();
+            let db_for_risk = db_clone.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    ticker.tick().await;
+                    let purged = session_affinity.purge_expired();
+                    if purged > 0 {
+                        tracing::debug!("[凭证池] 已清理 {} 个过期会话亲和绑定", purged);
+                    }
+                    let persisted = lime_core::database::lock_db(&db_for_risk).and_then(|conn| {
+                        risk_controller
+                            .persist_profiles(&conn)
+                            .map_err(|e| e.to_string())
+                    });
+                    if let Err(e) = persisted {
+                        tracing::warn!("[凭证池] 写入限流节奏失败: {}", e);
+                    }
+                }
+            });
+
             // 启动会话文件清理任务（清理 30 天前的过期会话）
             tauri::async_runtime::spawn(async move {
                 // 延迟 10 秒执行，避免影响启动性能
@@ -1015,6 +1177,16 @@ pub fn run() {
                 });
             }
 
+            // setup 完成后稳定运行一段时间才清除启动哨兵
+            if let Some(sentinel) = startup_sentinel.clone() {
+                sentinel.mark_stage(lime_core::safe_mode::STAGE_RUNNING);
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+                    sentinel.mark_healthy();
+                    tracing::info!("[SafeMode] 应用已稳定运行，清除启动哨兵");
+                });
+            }
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -1040,6 +1212,10 @@ pub fn run() {
             commands::oauth_cmd::get_oauth_token_file_hash,
             commands::oauth_cmd::check_and_reload_oauth_credentials,
             commands::oauth_cmd::get_all_oauth_credentials,
+            // Generic OAuth flow commands
+            commands::oauth_flow_cmd::oauth_start_flow,
+            commands::oauth_flow_cmd::oauth_poll_flow,
+            commands::oauth_flow_cmd::oauth_cancel_flow,
             // Legacy Kiro commands (from app::commands, deprecated)
             app_commands::refresh_kiro_token,
             app_commands::reload_credentials,
@@ -1091,6 +1267,9 @@ pub fn run() {
             commands::config_cmd::get_tool_versions,
             commands::config_cmd::get_auto_launch_status,
             commands::config_cmd::set_auto_launch,
+            // Background mode commands
+            commands::background_mode_cmd::get_background_mode_status,
+            commands::background_mode_cmd::set_background_mode,
             // Config import/export commands
             commands::config_cmd::export_config,
             commands::config_cmd::validate_config_yaml,
@@ -1101,6 +1280,23 @@ pub fn run() {
             commands::config_cmd::export_config_yaml,
             commands::config_cmd::validate_import,
             commands::config_cmd::import_bundle,
+            // Device sync commands
+            commands::device_sync_cmd::get_device_sync_settings,
+            commands::device_sync_cmd::update_device_sync_settings,
+            commands::device_sync_cmd::get_device_sync_status,
+            commands::device_sync_cmd::device_sync_push,
+            commands::device_sync_cmd::device_sync_pull,
+            // Credential backup commands
+            commands::credential_backup_cmd::export_credential_backup,
+            commands::credential_backup_cmd::inspect_credential_backup,
+            commands::credential_backup_cmd::import_credential_backup,
+            // Credential deletion commands
+            commands::credential_deletion_cmd::schedule_provider_pool_credential_deletion,
+            commands::credential_deletion_cmd::cancel_provider_pool_credential_deletion,
+            commands::credential_deletion_cmd::shred_provider_pool_credential,
+            commands::credential_deletion_cmd::get_provider_pool_credential_deletions,
+            // Chat export import commands
+            commands::chat_export_import_cmd::import_chat_export,
             // Path utility commands
             commands::config_cmd::expand_path,
             commands::config_cmd::open_auth_dir,
@@ -1134,6 +1330,10 @@ pub fn run() {
             commands::openclaw_cmd::openclaw_get_channels,
             commands::openclaw_cmd::openclaw_sync_provider_config,
             commands::openclaw_cmd::openclaw_install_event,
+            // Safe mode commands
+            commands::safe_mode_cmd::get_safe_mode_report,
+            commands::database_recovery_cmd::get_database_recovery_report,
+            commands::self_check_cmd::run_self_check,
             // MCP commands
             commands::mcp_cmd::get_mcp_servers,
             commands::mcp_cmd::add_mcp_server,
@@ -1146,9 +1346,12 @@ pub fn run() {
             commands::mcp_cmd::mcp_list_servers_with_status,
             commands::mcp_cmd::mcp_start_server,
             commands::mcp_cmd::mcp_stop_server,
+            commands::mcp_cmd::mcp_tail_server_log,
+            commands::mcp_cmd::mcp_diagnose_server_environment,
             // MCP 工具管理命令
             commands::mcp_cmd::mcp_list_tools,
             commands::mcp_cmd::mcp_list_tools_for_context,
+            commands::mcp_cmd::mcp_resync_tools,
             commands::mcp_cmd::mcp_search_tools,
             commands::mcp_cmd::mcp_call_tool,
             commands::mcp_cmd::mcp_call_tool_with_caller,
@@ -1158,6 +1361,9 @@ pub fn run() {
             // MCP 资源管理命令
             commands::mcp_cmd::mcp_list_resources,
             commands::mcp_cmd::mcp_read_resource,
+            // MCP elicitation 命令
+            commands::mcp_cmd::mcp_respond_elicitation,
+            commands::mcp_cmd::mcp_list_pending_elicitations,
             // Channel commands
             commands::channels_cmd::get_ai_channels,
             commands::channels_cmd::get_ai_channel,
@@ -1181,6 +1387,17 @@ pub fn run() {
             commands::prompt_cmd::import_prompt_from_file,
             commands::prompt_cmd::get_current_prompt_file_content,
             commands::prompt_cmd::auto_import_prompt,
+            // Prompt library commands
+            commands::prompt_library_cmd::list_library_prompts,
+            commands::prompt_library_cmd::get_library_prompt,
+            commands::prompt_library_cmd::save_library_prompt,
+            commands::prompt_library_cmd::delete_library_prompt,
+            commands::prompt_library_cmd::list_library_prompt_versions,
+            commands::prompt_library_cmd::diff_library_prompt_versions,
+            commands::prompt_library_cmd::restore_library_prompt_version,
+            commands::prompt_library_cmd::render_library_prompt,
+            commands::prompt_library_cmd::list_library_prompt_folders,
+            commands::prompt_library_cmd::list_library_prompt_tags,
             // Skill commands
             commands::skill_cmd::get_skills,
             commands::skill_cmd::get_skills_for_app,
@@ -1211,15 +1428,20 @@ pub fn run() {
             commands::ecommerce_review_reply_cmd::execute_ecommerce_review_reply,
             // Provider Pool commands
             commands::provider_pool_cmd::get_provider_pool_overview,
+            commands::provider_pool_cmd::get_provider_pool_snapshot,
+            commands::provider_pool_cmd::get_provider_pool_events_since,
             commands::provider_pool_cmd::get_provider_pool_credentials,
             commands::provider_pool_cmd::add_provider_pool_credential,
             commands::provider_pool_cmd::update_provider_pool_credential,
             commands::provider_pool_cmd::delete_provider_pool_credential,
             commands::provider_pool_cmd::toggle_provider_pool_credential,
+            commands::provider_pool_cmd::set_provider_pool_credential_weight,
             commands::provider_pool_cmd::reset_provider_pool_credential,
             commands::provider_pool_cmd::reset_provider_pool_health,
             commands::provider_pool_cmd::check_provider_pool_credential_health,
             commands::provider_pool_cmd::check_provider_pool_type_health,
+            commands::provider_pool_cmd::check_provider_pool_credential_endpoints,
+            commands::provider_pool_cmd::check_provider_pool_endpoints,
             commands::provider_pool_cmd::add_kiro_oauth_credential,
             commands::provider_pool_cmd::add_kiro_from_json,
             commands::provider_pool_cmd::add_gemini_oauth_credential,
@@ -1229,25 +1451,29 @@ pub fn run() {
             commands::provider_pool_cmd::add_gemini_api_key_credential,
             commands::provider_pool_cmd::add_codex_oauth_credential,
             commands::provider_pool_cmd::add_claude_oauth_credential,
+            commands::provider_pool_cmd::add_mock_credential,
             commands::provider_pool_cmd::refresh_pool_credential_token,
             commands::provider_pool_cmd::get_pool_credential_oauth_status,
             commands::provider_pool_cmd::debug_kiro_credentials,
             commands::provider_pool_cmd::test_user_credentials,
             commands::provider_pool_cmd::migrate_private_config_to_pool,
+            commands::provider_pool_cmd::import_credentials_from_config,
+            commands::provider_pool_cmd::get_legacy_credential_migration_report,
             commands::provider_pool_cmd::start_antigravity_oauth_login,
             commands::provider_pool_cmd::get_antigravity_auth_url_and_wait,
-            commands::provider_pool_cmd::get_codex_auth_url_and_wait,
-            commands::provider_pool_cmd::start_codex_oauth_login,
             commands::provider_pool_cmd::get_claude_oauth_auth_url_and_wait,
             commands::provider_pool_cmd::start_claude_oauth_login,
             commands::provider_pool_cmd::exchange_claude_oauth_code,
             commands::provider_pool_cmd::claude_oauth_with_cookie,
             commands::provider_pool_cmd::get_gemini_auth_url_and_wait,
-            commands::provider_pool_cmd::start_gemini_oauth_login,
             commands::provider_pool_cmd::exchange_gemini_code,
             commands::provider_pool_cmd::get_kiro_credential_fingerprint,
             commands::provider_pool_cmd::get_credential_health,
             commands::provider_pool_cmd::get_all_credential_health,
+            commands::provider_pool_cmd::get_credential_encryption_status,
+            commands::provider_pool_cmd::migrate_credential_encryption,
+            commands::provider_pool_cmd::get_unreadable_credentials,
+            commands::provider_pool_cmd::purge_unreadable_credentials,
             // Kiro Builder ID 登录命令
             commands::provider_pool_cmd::start_kiro_builder_id_login,
             commands::provider_pool_cmd::poll_kiro_builder_id_auth,
@@ -1310,6 +1536,18 @@ pub fn run() {
             commands::telemetry_cmd::get_token_stats_by_provider,
             commands::telemetry_cmd::get_token_stats_by_model,
             commands::telemetry_cmd::get_token_stats_by_day,
+            commands::telemetry_cmd::get_request_traces,
+            commands::telemetry_cmd::get_request_trace,
+            commands::telemetry_cmd::export_request_share_bundle,
+            commands::telemetry_cmd::clear_request_traces,
+            commands::telemetry_cmd::get_trace_sampling_settings,
+            commands::telemetry_cmd::update_trace_sampling_settings,
+            commands::telemetry_cmd::get_usage_analytics_settings,
+            commands::telemetry_cmd::update_usage_analytics_settings,
+            commands::telemetry_cmd::record_usage_feature,
+            commands::telemetry_cmd::get_usage_analytics_counters,
+            commands::telemetry_cmd::export_usage_analytics_summary,
+            commands::telemetry_cmd::clear_usage_analytics,
             // Injection commands
             commands::injection_cmd::get_injection_config,
             commands::injection_cmd::set_injection_enabled,
@@ -1320,12 +1558,79 @@ pub fn run() {
             // Security & Performance commands
             commands::security_perf_cmd::get_rate_limit_config,
             commands::security_perf_cmd::update_rate_limit_config,
+            commands::security_perf_cmd::get_api_key_rate_limit_config,
+            commands::security_perf_cmd::update_api_key_rate_limit_config,
+            commands::security_perf_cmd::list_api_key_rate_limit_overrides,
+            commands::security_perf_cmd::set_api_key_rate_limit_override,
+            commands::security_perf_cmd::delete_api_key_rate_limit_override,
+            commands::security_perf_cmd::get_system_prompt_guard_config,
+            commands::security_perf_cmd::update_system_prompt_guard_config,
+            commands::security_perf_cmd::get_system_prompt_guard_audit,
+            commands::security_perf_cmd::clear_system_prompt_guard_audit,
+            commands::security_perf_cmd::get_degenerate_retry_config,
+            commands::security_perf_cmd::update_degenerate_retry_config,
+            commands::security_perf_cmd::get_degenerate_response_stats,
+            commands::security_perf_cmd::reset_degenerate_response_stats,
             commands::security_perf_cmd::get_conversation_config,
             commands::security_perf_cmd::update_conversation_config,
+            commands::security_perf_cmd::get_context_trim_config,
+            commands::security_perf_cmd::update_context_trim_config,
             commands::security_perf_cmd::get_hint_routes,
             commands::security_perf_cmd::update_hint_routes,
             commands::security_perf_cmd::get_pairing_config,
             commands::security_perf_cmd::update_pairing_config,
+            commands::security_perf_cmd::get_cpu_pool_stats,
+            // Tenant commands
+            commands::tenant_cmd::get_tenant_settings,
+            commands::tenant_cmd::set_tenants_enabled,
+            commands::tenant_cmd::upsert_tenant,
+            commands::tenant_cmd::delete_tenant,
+            commands::tenant_cmd::get_tenant_usage,
+            // Cost cap commands
+            commands::cost_cap_cmd::get_cost_cap_settings,
+            commands::cost_cap_cmd::update_cost_cap_settings,
+            commands::cost_cap_cmd::get_cost_cap_status,
+            commands::cost_cap_cmd::set_cost_cap_override,
+            // Cost ledger commands
+            commands::cost_ledger_cmd::get_cost_budget_settings,
+            commands::cost_ledger_cmd::update_cost_budget_settings,
+            commands::cost_ledger_cmd::get_cost_spend,
+            // Usage report commands
+            commands::usage_report_cmd::get_usage_report_settings,
+            commands::usage_report_cmd::update_usage_report_settings,
+            commands::usage_report_cmd::generate_usage_report_now,
+            // Audit log commands
+            commands::audit_log_cmd::get_audit_log_settings,
+            commands::audit_log_cmd::update_audit_log_settings,
+            commands::audit_log_cmd::search_audit_log,
+            commands::audit_log_cmd::export_audit_log,
+            commands::audit_log_cmd::clear_audit_log,
+            // Adaptive selection commands
+            commands::adaptive_selection_cmd::get_adaptive_selection_settings,
+            commands::adaptive_selection_cmd::update_adaptive_selection_settings,
+            commands::adaptive_selection_cmd::get_adaptive_selection_stats,
+            commands::adaptive_selection_cmd::reset_adaptive_selection,
+            // Quick fix commands
+            commands::quick_fix_cmd::get_recent_provider_quick_fixes,
+            // Quota forecast commands
+            commands::quota_cmd::get_quota_forecasts,
+            commands::quota_cmd::get_quota_remaining,
+            commands::quota_cmd::set_credential_quota_limit,
+            // Virtual model commands
+            commands::virtual_model_cmd::list_virtual_models,
+            commands::virtual_model_cmd::save_virtual_model,
+            commands::virtual_model_cmd::delete_virtual_model,
+            // Pipeline snapshot commands
+            commands::pipeline_snapshot_cmd::create_pipeline_snapshot,
+            commands::pipeline_snapshot_cmd::list_pipeline_snapshots,
+            commands::pipeline_snapshot_cmd::get_pipeline_snapshot,
+            commands::pipeline_snapshot_cmd::delete_pipeline_snapshot,
+            commands::pipeline_snapshot_cmd::diff_pipeline_snapshots,
+            // Feature flag commands
+            commands::feature_flag_cmd::list_feature_flags,
+            commands::feature_flag_cmd::set_feature_flag,
+            commands::quick_action_cmd::list_quick_actions,
+            commands::quick_action_cmd::run_quick_action,
             // Usage commands
             commands::usage_cmd::get_kiro_usage,
             // Tray commands
@@ -1345,15 +1650,32 @@ pub fn run() {
             commands::plugin_cmd::update_plugin_config,
             commands::plugin_cmd::get_plugin_config,
             commands::plugin_cmd::reload_plugins,
+            commands::plugin_cmd::reload_plugin,
             commands::plugin_cmd::unload_plugin,
             commands::plugin_cmd::get_plugins_dir,
             commands::plugin_cmd::list_plugin_tasks,
             commands::plugin_cmd::get_plugin_task,
             commands::plugin_cmd::cancel_plugin_task,
             commands::plugin_cmd::get_plugin_queue_stats,
+            commands::plugin_cmd::plugin_storage_get,
+            commands::plugin_cmd::plugin_storage_set,
+            commands::plugin_cmd::plugin_storage_delete,
+            commands::plugin_cmd::plugin_storage_keys,
+            commands::plugin_cmd::plugin_storage_usage,
+            commands::plugin_cmd::plugin_crypto_encrypt,
+            commands::plugin_cmd::plugin_crypto_decrypt,
+            commands::plugin_cmd::plugin_crypto_rotate_key,
             // Plugin Install commands
             commands::plugin_install_cmd::install_plugin_from_file,
             commands::plugin_install_cmd::install_plugin_from_url,
+            commands::plugin_install_cmd::install_plugin_from_registry,
+            commands::plugin_install_cmd::install_plugin_from_github,
+            commands::plugin_install_cmd::check_plugin_update,
+            commands::plugin_install_cmd::update_plugin,
+            commands::extension_registry_cmd::get_extension_registry_settings,
+            commands::extension_registry_cmd::update_extension_registry_settings,
+            commands::extension_registry_cmd::check_extension_registries,
+            commands::extension_registry_cmd::list_registry_plugins,
             commands::plugin_install_cmd::uninstall_plugin,
             commands::plugin_install_cmd::list_installed_plugins,
             commands::plugin_install_cmd::get_installed_plugin,
@@ -1361,6 +1683,7 @@ pub fn run() {
             // Plugin UI commands
             commands::plugin_cmd::get_plugins_with_ui,
             commands::plugin_cmd::get_plugin_ui,
+            commands::plugin_cmd::get_plugin_event_audit,
             commands::plugin_cmd::handle_plugin_action,
             commands::plugin_cmd::read_plugin_manifest_cmd,
             commands::plugin_cmd::launch_plugin_ui,
@@ -1414,8 +1737,12 @@ pub fn run() {
             commands::aster_agent_cmd::command_api::runtime_api::agent_runtime_interrupt_turn,
             commands::aster_agent_cmd::command_api::runtime_api::agent_runtime_promote_queued_turn,
             commands::aster_agent_cmd::command_api::runtime_api::agent_runtime_remove_queued_turn,
+            commands::aster_agent_cmd::command_api::runtime_api::agent_runtime_regenerate_turn,
+            commands::aster_agent_cmd::command_api::runtime_api::agent_runtime_list_turn_attempts,
+            commands::aster_agent_cmd::command_api::runtime_api::agent_runtime_switch_turn_attempt,
             commands::aster_agent_cmd::command_api::session_api::agent_runtime_create_session,
             commands::aster_agent_cmd::command_api::session_api::agent_runtime_list_sessions,
+            commands::aster_agent_cmd::command_api::session_api::agent_runtime_list_sessions_page,
             commands::aster_agent_cmd::command_api::runtime_api::agent_runtime_get_session,
             commands::aster_agent_cmd::command_api::runtime_api::agent_runtime_get_tool_inventory,
             commands::aster_agent_cmd::command_api::subagent_api::agent_runtime_spawn_subagent,
@@ -1777,6 +2104,7 @@ pub fn run() {
             // Voice Test commands
             commands::voice_test_cmd::test_tts,
             commands::voice_test_cmd::get_available_voices,
+            commands::voice_test_cmd::speak_text,
             // File Upload commands
             commands::file_upload_cmd::upload_avatar,
             commands::file_upload_cmd::delete_avatar,
@@ -1809,6 +2137,11 @@ pub fn run() {
             crate::voice::commands::cancel_recording,
             crate::voice::commands::get_recording_status,
             crate::voice::commands::list_audio_devices,
+            // Whisper 模型管理
+            crate::voice::commands::list_whisper_models,
+            crate::voice::commands::download_whisper_model,
+            crate::voice::commands::cancel_whisper_model_download,
+            crate::voice::commands::delete_whisper_model,
             // Automation commands
             commands::automation_cmd::get_automation_scheduler_config,
             commands::automation_cmd::update_automation_scheduler_config,
@@ -1849,8 +2182,16 @@ pub fn run() {
             commands::telegram_remote_cmd::stop_telegram_remote,
             commands::telegram_remote_cmd::get_telegram_remote_status,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(move |_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                if let Some(sentinel) = &exit_sentinel {
+                    sentinel.mark_healthy();
+                    tracing::info!("[SafeMode] 应用正常退出，清除启动哨兵");
+                }
+            }
+        });
 }
 
 #[cfg(test)]
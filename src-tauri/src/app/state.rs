@@ -175,7 +175,9 @@ fn init_plugin_installer() -> PluginInstallerState {
     match plugin::installer::PluginInstaller::from_paths(plugins_dir, temp_dir, &db_path) {
         Ok(installer) => {
             tracing::info!("[启动] 插件安装器初始化成功");
-            PluginInstallerState(Arc::new(RwLock::new(installer)))
+            PluginInstallerState(Arc::new(RwLock::new(
+                installer.with_app_version(env!("CARGO_PKG_VERSION")),
+            )))
         }
         Err(e) => {
             tracing::error!("[启动] 插件安装器初始化失败: {}", e);
@@ -190,7 +192,9 @@ fn init_plugin_installer() -> PluginInstallerState {
                 &db_path,
             )
             .expect("Failed to create fallback PluginInstaller");
-            PluginInstallerState(Arc::new(RwLock::new(installer)))
+            PluginInstallerState(Arc::new(RwLock::new(
+                installer.with_app_version(env!("CARGO_PKG_VERSION")),
+            )))
         }
     }
 }
@@ -0,0 +1,84 @@
+//! 自适应凭证选择命令
+//!
+//! - `get_adaptive_selection_settings` / `update_adaptive_selection_settings`: 读取与更新配置
+//! - `get_adaptive_selection_stats`: 各凭证按模型学习到的统计与得分
+//! - `reset_adaptive_selection`: 清除已学习的统计
+//!
+//! 选择策略见 [`lime_services::adaptive_selection`]。
+
+use crate::commands::provider_pool_cmd::ProviderPoolServiceState;
+use crate::config::save_config;
+use crate::database::DbConnection;
+use crate::AppState;
+use lime_core::config::AdaptiveSelectionSettings;
+use lime_services::adaptive_selection::AdaptiveArmView;
+use tauri::State;
+
+/// 获取自适应选择配置
+#[tauri::command]
+pub async fn get_adaptive_selection_settings(
+    state: State<'_, AppState>,
+) -> Result<AdaptiveSelectionSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.adaptive_selection.clone())
+}
+
+/// 更新自适应选择配置
+#[tauri::command]
+pub async fn update_adaptive_selection_settings(
+    state: State<'_, AppState>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+    settings: AdaptiveSelectionSettings,
+) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&settings.exploration_rate) {
+        return Err("探索概率必须在 0 到 1 之间".to_string());
+    }
+    if !(0.0..=1.0).contains(&settings.latency_weight) {
+        return Err("延迟权重必须在 0 到 1 之间".to_string());
+    }
+    if !(settings.decay > 0.0 && settings.decay <= 1.0) {
+        return Err("衰减系数必须在 0 到 1 之间（不含 0）".to_string());
+    }
+    if settings.reference_latency_ms == 0 {
+        return Err("参考延迟必须大于 0".to_string());
+    }
+
+    let mut s = state.write().await;
+    s.config.adaptive_selection = settings;
+    save_config(&s.config).map_err(|e| e.to_string())?;
+    pool_service
+        .0
+        .adaptive_selection()
+        .reload(&s.config.adaptive_selection);
+    Ok(())
+}
+
+/// 获取已学习的统计（按模型、得分降序）
+///
+/// # 参数
+/// - `model`: 只返回该模型的统计，为空时返回全部
+#[tauri::command]
+pub fn get_adaptive_selection_stats(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+    model: Option<String>,
+) -> Vec<AdaptiveArmView> {
+    let adaptive = pool_service.0.adaptive_selection();
+    adaptive.load(&db);
+    adaptive.snapshot(model.as_deref())
+}
+
+/// 清除已学习的统计，返回清除的条数
+///
+/// # 参数
+/// - `model`: 只清除该模型的统计，为空时全部清除
+#[tauri::command]
+pub fn reset_adaptive_selection(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+    model: Option<String>,
+) -> Result<usize, String> {
+    let adaptive = pool_service.0.adaptive_selection();
+    adaptive.load(&db);
+    adaptive.reset(Some(&db), model.as_deref())
+}
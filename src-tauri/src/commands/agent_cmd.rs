@@ -13,18 +13,20 @@ use tauri::State;
 ///
 /// # 参数
 /// - `s`: 要截断的字符串
-/// - `max_chars`: 最大字符数（按 Unicode 字符计算，非字节）
+/// - `max_chars`: 最大字符数（按 Unicode 字符计算，非字节），包含 "..." 后缀
 ///
 /// # 返回
-/// 截断后的字符串，如果被截断则添加 "..." 后缀
+/// 截断后的字符串，如果被截断则添加 "..." 后缀；`max_chars` 容不下后缀时直接截断
 fn truncate_string(s: &str, max_chars: usize) -> String {
-    let char_count = s.chars().count();
-    if char_count <= max_chars {
-        s.to_string()
-    } else {
-        let truncated: String = s.chars().take(max_chars).collect();
-        format!("{truncated}...")
+    if s.chars().count() <= max_chars {
+        return s.to_string();
+    }
+    let ellipsis_len = TITLE_ELLIPSIS.chars().count();
+    if max_chars <= ellipsis_len {
+        return s.chars().take(max_chars).collect();
     }
+    let truncated: String = s.chars().take(max_chars - ellipsis_len).collect();
+    format!("{}{TITLE_ELLIPSIS}", truncated.trim_end())
 }
 
 /// Agent 进程状态响应
@@ -114,31 +116,215 @@ pub async fn agent_get_process_status(
         })
     }
 }
+/// 标题语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TitleLanguage {
+    Chinese,
+    Japanese,
+    Korean,
+    English,
+}
+
+impl TitleLanguage {
+    fn default_title(self) -> &'static str {
+        match self {
+            TitleLanguage::Chinese => "新话题",
+            TitleLanguage::Japanese => "新しい会話",
+            TitleLanguage::Korean => "새 대화",
+            TitleLanguage::English => "New conversation",
+        }
+    }
+}
+
+/// 标题截断时追加的省略号
+const TITLE_ELLIPSIS: &str = "...";
+
+fn is_kana_char(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}')
+}
+
+fn is_hangul_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{AC00}'..='\u{D7AF}' | '\u{1100}'..='\u{11FF}' | '\u{3130}'..='\u{318F}'
+    )
+}
+
+fn is_han_char(c: char) -> bool {
+    matches!(c, '\u{4E00}'..='\u{9FFF}' | '\u{3400}'..='\u{4DBF}')
+}
+
+/// 根据消息内容推断标题语言，无法判断时回退到应用界面语言
+///
+/// 假名优先判为日语、谚文判为韩语，仅含汉字时判为中文。
+fn detect_title_language(text: &str, app_language: &str) -> TitleLanguage {
+    if text.chars().any(is_kana_char) {
+        return TitleLanguage::Japanese;
+    }
+    if text.chars().any(is_hangul_char) {
+        return TitleLanguage::Korean;
+    }
+    if text.chars().any(is_han_char) {
+        return TitleLanguage::Chinese;
+    }
+    if text.chars().any(|c| c.is_ascii_alphabetic()) {
+        return TitleLanguage::English;
+    }
+    let app_language = app_language.trim().to_ascii_lowercase();
+    match app_language.split(['-', '_']).next().unwrap_or("") {
+        "en" => TitleLanguage::English,
+        "ja" => TitleLanguage::Japanese,
+        "ko" => TitleLanguage::Korean,
+        _ => TitleLanguage::Chinese,
+    }
+}
+
+/// 按单词边界截断，省略号计入 `max_chars`；首个单词即超长时退回按字符截断
+fn truncate_title_words(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let ellipsis_len = TITLE_ELLIPSIS.chars().count();
+    if max_chars <= ellipsis_len {
+        return text.chars().take(max_chars).collect();
+    }
+    let budget = max_chars - ellipsis_len;
+    let mut title = String::new();
+    for word in text.split(' ') {
+        let next_len =
+            title.chars().count() + word.chars().count() + usize::from(!title.is_empty());
+        if next_len > budget {
+            break;
+        }
+        if !title.is_empty() {
+            title.push(' ');
+        }
+        title.push_str(word);
+    }
+    if title.is_empty() {
+        truncate_string(text, max_chars)
+    } else {
+        format!("{title}{TITLE_ELLIPSIS}")
+    }
+}
+
+/// 按语言规则从首条用户消息生成标题
+///
+/// 中文、日文按字符截断；英文、韩文按空格分词，避免切断单词。结果（含省略号）不超过 `max_chars`。
+fn build_fallback_title(content: &str, language: TitleLanguage, max_chars: usize) -> String {
+    let normalized = content.split_whitespace().collect::<Vec<_>>().join(" ");
+    if normalized.is_empty() {
+        return language.default_title().to_string();
+    }
+    let max_chars = max_chars.max(1);
+
+    match language {
+        TitleLanguage::Chinese | TitleLanguage::Japanese => truncate_string(&normalized, max_chars),
+        TitleLanguage::English | TitleLanguage::Korean => {
+            truncate_title_words(&normalized, max_chars)
+        }
+    }
+}
+
 /// 生成智能标题
 ///
-/// 根据对话内容生成一个简洁的标题
+/// 根据对话内容生成一个简洁的标题，标题语言跟随首条用户消息
 #[tauri::command]
 pub async fn agent_generate_title(
+    app_state: State<'_, AppState>,
     db: State<'_, DbConnection>,
     session_id: String,
 ) -> Result<String, String> {
+    let (app_language, max_chars) = {
+        let state = app_state.read().await;
+        (
+            state.config.language.clone(),
+            state.config.agent.title_max_chars,
+        )
+    };
+
     // 获取会话的前几条消息（用于生成标题）
     let messages = AsterAgentWrapper::list_title_preview_messages_sync(&db, &session_id, 4)?;
 
-    // 过滤出 user 和 assistant 消息
-    let chat_messages: Vec<_> = messages.iter().collect();
+    let first_user_msg = messages.iter().find(|msg| msg.role == "user");
+    let language = detect_title_language(
+        first_user_msg.map(|msg| msg.content.as_str()).unwrap_or(""),
+        &app_language,
+    );
 
-    if chat_messages.len() < 2 {
-        return Ok("新话题".to_string());
+    if messages.len() < 2 {
+        return Ok(language.default_title().to_string());
     }
 
-    // 这里简化处理：使用第一条用户消息的前 15 个字作为默认标题
-    if let Some(first_user_msg) = chat_messages.iter().find(|msg| msg.role == "user") {
-        let content = &first_user_msg.content;
-        // 使用字符边界安全截断
-        let title = truncate_string(content, 15);
-        Ok(title)
-    } else {
-        Ok("新话题".to_string())
+    match first_user_msg {
+        Some(msg) => Ok(build_fallback_title(&msg.content, language, max_chars)),
+        None => Ok(language.default_title().to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_title_language() {
+        assert_eq!(
+            detect_title_language("帮我写一段代码", "en"),
+            TitleLanguage::Chinese
+        );
+        assert_eq!(
+            detect_title_language("Write a sorting function", "zh"),
+            TitleLanguage::English
+        );
+        assert_eq!(
+            detect_title_language("123 ???", "en"),
+            TitleLanguage::English
+        );
+        assert_eq!(detect_title_language("", "zh"), TitleLanguage::Chinese);
+        assert_eq!(
+            detect_title_language("このコードを説明して", "zh"),
+            TitleLanguage::Japanese
+        );
+        assert_eq!(
+            detect_title_language("정렬 함수를 작성해 주세요", "en"),
+            TitleLanguage::Korean
+        );
+        assert_eq!(
+            detect_title_language("123", "ja-JP"),
+            TitleLanguage::Japanese
+        );
+    }
+
+    #[test]
+    fn test_build_fallback_title_respects_word_boundary() {
+        let title = build_fallback_title(
+            "How do I   implement\na binary search tree",
+            TitleLanguage::English,
+            20,
+        );
+        assert_eq!(title, "How do I...");
+        assert!(title.chars().count() <= 20);
+
+        let title = build_fallback_title("Supercalifragilistic", TitleLanguage::English, 8);
+        assert_eq!(title, "Super...");
+
+        let title = build_fallback_title("정렬 함수를 작성해 주세요", TitleLanguage::Korean, 10);
+        assert_eq!(title, "정렬 함수를...");
+    }
+
+    #[test]
+    fn test_build_fallback_title_chinese_and_empty() {
+        let title = build_fallback_title("请帮我分析这份销售数据报表", TitleLanguage::Chinese, 6);
+        assert_eq!(title, "请帮我...");
+        let title = build_fallback_title("请帮我分析", TitleLanguage::Chinese, 2);
+        assert_eq!(title, "请帮");
+        assert_eq!(
+            build_fallback_title("", TitleLanguage::Japanese, 10),
+            "新しい会話"
+        );
+        assert_eq!(
+            build_fallback_title("  ", TitleLanguage::English, 10),
+            "New conversation"
+        );
     }
 }
@@ -6,6 +6,7 @@ use crate::config::{
     load_config, save_config, AsrCredentialEntry, AsrProviderType, BaiduConfig, OpenAIAsrConfig,
     WhisperLocalConfig, XunfeiConfig,
 };
+use lime_services::voice_asr_service::AsrService;
 use serde::{Deserialize, Serialize};
 use tauri::command;
 use uuid::Uuid;
@@ -153,6 +154,15 @@ pub async fn set_default_asr_credential(id: String) -> Result<(), String> {
 
     save_config(&config).map_err(|e| e.to_string())?;
 
+    // 后台预加载本地 Whisper 模型，减少首次识别等待
+    if let Some(credential) = config.credential_pool.asr.into_iter().find(|c| c.id == id) {
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = AsrService::warm_up(&credential).await {
+                tracing::warn!("预加载 Whisper 模型失败: {}", e);
+            }
+        });
+    }
+
     Ok(())
 }
 
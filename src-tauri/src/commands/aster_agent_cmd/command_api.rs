@@ -2,7 +2,8 @@ use super::*;
 use crate::commands::aster_agent_cmd::runtime_turn::build_runtime_queue_executor;
 use crate::commands::aster_agent_cmd::session_runtime::{
     create_runtime_session_internal, list_runtime_sessions_internal,
-    rename_runtime_session_internal, update_runtime_session_execution_strategy_internal,
+    list_runtime_sessions_page_internal, rename_runtime_session_internal,
+    update_runtime_session_execution_strategy_internal,
 };
 use crate::commands::aster_agent_cmd::subagent_runtime::{
     agent_runtime_close_subagent_internal, agent_runtime_resume_subagent_internal,
@@ -48,7 +49,9 @@ pub(crate) use provider_api::{
 };
 pub(crate) use runtime_api::{
     agent_runtime_get_session, agent_runtime_get_tool_inventory, agent_runtime_interrupt_turn,
-    agent_runtime_promote_queued_turn, agent_runtime_remove_queued_turn, agent_runtime_submit_turn,
+    agent_runtime_list_turn_attempts, agent_runtime_promote_queued_turn,
+    agent_runtime_regenerate_turn, agent_runtime_remove_queued_turn, agent_runtime_submit_turn,
+    agent_runtime_switch_turn_attempt,
 };
 pub(crate) use session_api::{
     agent_runtime_create_session, agent_runtime_list_sessions, agent_runtime_update_session,
@@ -57,6 +57,7 @@ pub async fn aster_agent_configure_provider(
         credential_uuid: None,
         force_responses_api: false,
         credential_path: None,
+        temperature: request.temperature,
     };
 
     state
@@ -1,4 +1,5 @@
 use super::*;
+use lime_core::database::dao::turn_attempt::{TurnAttempt, TurnAttemptDao, TurnAttemptOverrides};
 
 #[tauri::command]
 pub async fn agent_runtime_submit_turn(
@@ -82,10 +83,18 @@ pub async fn agent_runtime_get_session(
     }
 
     let queued_turns = list_runtime_queue_snapshots_service(&session_id).await?;
-    Ok(AgentRuntimeSessionDetail::from_session_detail(
-        detail,
-        queued_turns,
-    ))
+    let mut detail = AgentRuntimeSessionDetail::from_session_detail(detail, queued_turns);
+    detail.model_lock = load_session_model_lock(&session_id)
+        .await
+        .unwrap_or_else(|error| {
+            tracing::warn!(
+                "[AsterAgent] 读取会话模型锁定失败: session_id={}, error={}",
+                session_id,
+                error
+            );
+            None
+        });
+    Ok(detail)
 }
 
 /// 统一运行时：获取工具库存快照。
@@ -252,3 +261,220 @@ pub async fn agent_runtime_promote_queued_turn(
 
     Ok(true)
 }
+
+/// 统一运行时：重新生成最后一轮回复。
+///
+/// 当前回复保留为该轮的一次尝试（可随时切回），新回复可覆盖模型、温度并追加补充要求。
+/// 返回新尝试的序号。
+#[tauri::command]
+pub async fn agent_runtime_regenerate_turn(
+    app: AppHandle,
+    state: State<'_, AsterAgentState>,
+    db: State<'_, DbConnection>,
+    api_key_provider_service: State<'_, ApiKeyProviderServiceState>,
+    logs: State<'_, LogState>,
+    config_manager: State<'_, GlobalConfigManagerState>,
+    mcp_manager: State<'_, McpManagerState>,
+    automation_state: State<'_, AutomationServiceState>,
+    request: AgentRuntimeRegenerateTurnRequest,
+) -> Result<u32, String> {
+    let session_id = request.session_id.trim().to_string();
+    ensure_session_idle(&session_id)?;
+
+    let overrides = request.overrides;
+    let mut turn_config = request.turn_config.unwrap_or_default();
+    turn_config.provider_config = resolve_regenerate_provider_config(
+        state.inner(),
+        turn_config.provider_config.take(),
+        &overrides,
+    )
+    .await?;
+    if overrides.model.is_some() {
+        // 重新生成时显式选择的模型视为已确认切换会话锁定模型
+        turn_config.metadata = Some(with_model_lock_override(turn_config.metadata.take()));
+    }
+
+    let regeneration = {
+        let conn = db.lock().map_err(|e| e.to_string())?;
+        TurnAttemptDao::begin_regeneration(
+            &conn,
+            &session_id,
+            &overrides,
+            chrono::Utc::now().timestamp_millis(),
+        )
+        .map_err(|e| format!("记录重新生成失败: {e}"))?
+    }
+    .ok_or_else(|| "会话中没有可重新生成的回复".to_string())?;
+
+    let (mut message, images) = split_turn_content(regeneration.base_content);
+    if let Some(instruction) = overrides
+        .instruction
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        message = format!("{message}\n\n【补充要求】{instruction}");
+    }
+    tracing::info!(
+        "[AsterAgent] 重新生成最后一轮: session={}, attempt={}, model={:?}, temperature={:?}",
+        session_id,
+        regeneration.attempt,
+        overrides.model,
+        overrides.temperature
+    );
+
+    let runtime_request: AsterChatRequest = AgentRuntimeSubmitTurnRequest {
+        message,
+        session_id,
+        event_name: request.event_name,
+        images,
+        workspace_id: request.workspace_id,
+        turn_config: Some(turn_config),
+        turn_id: None,
+        queue_if_busy: Some(false),
+        queued_turn_id: None,
+    }
+    .into();
+    let queued_task = build_queued_turn_task(runtime_request)?;
+    submit_runtime_turn_service(
+        app,
+        state.inner(),
+        db.inner(),
+        api_key_provider_service.inner(),
+        logs.inner(),
+        config_manager.inner(),
+        mcp_manager.inner(),
+        automation_state.inner(),
+        queued_task,
+        false,
+        build_runtime_queue_executor(),
+    )
+    .await?;
+
+    Ok(regeneration.attempt)
+}
+
+/// 统一运行时：列出最后一轮回复的全部尝试。
+#[tauri::command]
+pub async fn agent_runtime_list_turn_attempts(
+    db: State<'_, DbConnection>,
+    session_id: String,
+) -> Result<Vec<TurnAttempt>, String> {
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    TurnAttemptDao::list_last_turn(&conn, session_id.trim())
+        .map_err(|e| format!("读取回复尝试失败: {e}"))
+}
+
+/// 统一运行时：切换最后一轮显示的回复。
+#[tauri::command]
+pub async fn agent_runtime_switch_turn_attempt(
+    db: State<'_, DbConnection>,
+    request: AgentRuntimeSwitchTurnAttemptRequest,
+) -> Result<bool, String> {
+    let session_id = request.session_id.trim().to_string();
+    ensure_session_idle(&session_id)?;
+    let conn = db.lock().map_err(|e| e.to_string())?;
+    TurnAttemptDao::switch_attempt(
+        &conn,
+        &session_id,
+        request.attempt_id.trim(),
+        chrono::Utc::now().timestamp_millis(),
+    )
+    .map_err(|e| format!("切换回复失败: {e}"))
+}
+
+fn ensure_session_idle(session_id: &str) -> Result<(), String> {
+    if session_id.is_empty() {
+        return Err("session_id 不能为空".to_string());
+    }
+    let busy = aster::session::require_shared_session_runtime_queue_service()
+        .map_err(|error| format!("读取 runtime queue service 失败: {error}"))?
+        .has_active_turn(session_id);
+    if busy {
+        return Err("会话正在生成回复，请稍后再试".to_string());
+    }
+    Ok(())
+}
+
+/// 在本轮 Provider 配置上应用模型与温度覆盖
+///
+/// 没有覆盖时沿用请求中的配置；有覆盖但请求未指定 Provider 时，以当前 Provider 为基础。
+async fn resolve_regenerate_provider_config(
+    state: &AsterAgentState,
+    requested: Option<ConfigureProviderRequest>,
+    overrides: &TurnAttemptOverrides,
+) -> Result<Option<ConfigureProviderRequest>, String> {
+    if let Some(temperature) = overrides.temperature {
+        if !(0.0..=2.0).contains(&temperature) {
+            return Err("temperature 需在 0 到 2 之间".to_string());
+        }
+    }
+    let model = overrides
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    if model.is_none() && overrides.temperature.is_none() {
+        return Ok(requested);
+    }
+
+    let mut config = match requested {
+        Some(config) => config,
+        None => state
+            .get_provider_config()
+            .await
+            .map(|current| ConfigureProviderRequest {
+                provider_id: current.provider_selector,
+                provider_name: current.provider_name,
+                model_name: current.model_name,
+                // 凭证池选出的凭证不复用，由凭证池重新选择
+                api_key: current
+                    .api_key
+                    .filter(|_| current.credential_uuid.is_none()),
+                base_url: current.base_url,
+                temperature: None,
+            })
+            .ok_or_else(|| "Provider 未配置，无法覆盖模型或温度".to_string())?,
+    };
+    if let Some(model) = model {
+        config.model_name = model.to_string();
+    }
+    if overrides.temperature.is_some() {
+        config.temperature = overrides.temperature;
+    }
+    Ok(Some(config))
+}
+
+fn with_model_lock_override(metadata: Option<serde_json::Value>) -> serde_json::Value {
+    let mut metadata = match metadata {
+        Some(serde_json::Value::Object(object)) => object,
+        _ => serde_json::Map::new(),
+    };
+    if let Some(harness) = metadata
+        .get_mut("harness")
+        .and_then(serde_json::Value::as_object_mut)
+    {
+        harness.insert("model_lock_override".to_string(), true.into());
+    } else {
+        metadata.insert("model_lock_override".to_string(), true.into());
+    }
+    serde_json::Value::Object(metadata)
+}
+
+/// 拆分已保存的用户消息内容为文本与图片
+fn split_turn_content(content: serde_json::Value) -> (String, Option<Vec<ImageInput>>) {
+    let contents: Vec<MessageContent> = serde_json::from_value(content).unwrap_or_default();
+    let mut texts = Vec::new();
+    let mut images = Vec::new();
+    for item in contents {
+        match item {
+            MessageContent::Text(text) => texts.push(text.text.clone()),
+            MessageContent::Image(image) => images.push(ImageInput {
+                data: image.data.clone(),
+                media_type: image.mime_type.clone(),
+            }),
+            _ => {}
+        }
+    }
+    (texts.join("\n"), (!images.is_empty()).then_some(images))
+}
@@ -47,6 +47,15 @@ pub async fn agent_runtime_list_sessions(
     }
 }
 
+/// 分页列出会话（按更新时间降序），用于会话较多时按需加载
+#[tauri::command]
+pub async fn agent_runtime_list_sessions_page(
+    db: State<'_, DbConnection>,
+    page: Option<PageRequest>,
+) -> Result<Page<SessionInfo>, String> {
+    list_runtime_sessions_page_internal(db.inner(), &page.unwrap_or_default())
+}
+
 #[tauri::command]
 pub async fn agent_runtime_update_session(
     db: State<'_, DbConnection>,
@@ -72,5 +81,22 @@ pub async fn agent_runtime_update_session(
         )?;
     }
 
+    if let Some(model_lock) = request.model_lock {
+        let lock = if model_lock.locked {
+            Some(
+                SessionModelLock::new(model_lock.provider_selector, model_lock.model_name)
+                    .ok_or_else(|| "锁定模型需要 provider_selector 与 model_name".to_string())?,
+            )
+        } else {
+            None
+        };
+        tracing::info!(
+            "[AsterAgent] 更新会话模型锁定: session={}, lock={:?}",
+            trimmed_session_id,
+            lock
+        );
+        persist_session_model_lock(&trimmed_session_id, lock).await?;
+    }
+
     Ok(())
 }
@@ -1,4 +1,5 @@
 use super::*;
+use lime_core::database::dao::turn_attempt::TurnAttemptOverrides;
 
 /// Aster Agent 状态信息
 #[derive(Debug, Serialize)]
@@ -13,7 +14,7 @@ pub struct AsterAgentStatus {
 }
 
 /// Provider 配置请求
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ConfigureProviderRequest {
     #[serde(default)]
     pub provider_id: Option<String>,
@@ -23,6 +24,9 @@ pub struct ConfigureProviderRequest {
     pub api_key: Option<String>,
     #[serde(default)]
     pub base_url: Option<String>,
+    /// 采样温度（None 时使用 Provider 默认值）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
 }
 
 /// 从凭证池配置 Provider 的请求
@@ -96,7 +100,7 @@ pub struct AsterChatRequest {
     pub queued_turn_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AgentTurnConfigSnapshot {
     #[serde(default, alias = "providerConfig")]
     pub provider_config: Option<ConfigureProviderRequest>,
@@ -193,6 +197,30 @@ pub struct AgentRuntimePromoteQueuedTurnRequest {
     pub queued_turn_id: String,
 }
 
+/// 重新生成最后一轮回复的请求
+#[derive(Debug, Deserialize)]
+pub struct AgentRuntimeRegenerateTurnRequest {
+    #[serde(alias = "sessionId")]
+    pub session_id: String,
+    #[serde(alias = "eventName")]
+    pub event_name: String,
+    #[serde(alias = "workspaceId")]
+    pub workspace_id: String,
+    #[serde(default, alias = "turnConfig")]
+    pub turn_config: Option<AgentTurnConfigSnapshot>,
+    /// 本次生成的模型、温度与补充要求覆盖
+    #[serde(default)]
+    pub overrides: TurnAttemptOverrides,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AgentRuntimeSwitchTurnAttemptRequest {
+    #[serde(alias = "sessionId")]
+    pub session_id: String,
+    #[serde(alias = "attemptId")]
+    pub attempt_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentRuntimeSessionDetail {
     pub id: String,
@@ -212,6 +240,9 @@ pub struct AgentRuntimeSessionDetail {
     pub child_subagent_sessions: Vec<lime_agent::ChildSubagentSession>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub subagent_parent_context: Option<lime_agent::SubagentParentContext>,
+    /// 会话模型锁定
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_lock: Option<SessionModelLock>,
 }
 
 impl AgentRuntimeSessionDetail {
@@ -233,6 +264,7 @@ impl AgentRuntimeSessionDetail {
             queued_turns,
             child_subagent_sessions: detail.child_subagent_sessions,
             subagent_parent_context: detail.subagent_parent_context,
+            model_lock: None,
         }
     }
 }
@@ -380,6 +412,21 @@ pub struct AgentRuntimeUpdateSessionRequest {
     pub name: Option<String>,
     #[serde(default, alias = "executionStrategy")]
     pub execution_strategy: Option<AsterExecutionStrategy>,
+    /// 会话模型锁定（可选，不传则保持不变）
+    #[serde(default, alias = "modelLock")]
+    pub model_lock: Option<AgentRuntimeSessionModelLockRequest>,
+}
+
+/// 会话模型锁定请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRuntimeSessionModelLockRequest {
+    pub locked: bool,
+    /// 锁定的 Provider（provider_id 或 provider 类型），`locked=true` 时必填
+    #[serde(default, alias = "providerSelector")]
+    pub provider_selector: Option<String>,
+    /// 锁定的模型，`locked=true` 时必填
+    #[serde(default, alias = "modelName")]
+    pub model_name: Option<String>,
 }
 
 /// 自动续写参数
@@ -140,6 +140,11 @@ pub(crate) async fn ensure_lime_mcp_servers_running(
     db: &DbConnection,
     mcp_manager: &McpManagerState,
 ) -> (usize, usize) {
+    if lime_core::safe_mode::is_safe_mode() {
+        tracing::warn!("[AsterAgent] 安全模式启动，跳过 MCP server 自动启动");
+        return (0, 0);
+    }
+
     let servers = match McpService::get_all(db) {
         Ok(items) => items,
         Err(e) => {
@@ -170,11 +175,16 @@ pub(crate) async fn ensure_lime_mcp_servers_running(
 
         let parsed = server.parse_config();
         let config = McpServerConfig {
+            transport: parsed.transport,
             command: parsed.command,
             args: parsed.args,
             env: parsed.env,
             cwd: parsed.cwd,
             timeout: parsed.timeout,
+            interpreter: parsed.interpreter,
+            url: parsed.url,
+            headers: parsed.headers,
+            reconnect: parsed.reconnect,
         };
 
         match manager.start_server(&server.name, &config).await {
@@ -20,7 +20,8 @@ use crate::agent::{
 };
 use crate::agent_tools::catalog::{
     browser_runtime_tool_prefix, build_mcp_extension_surface, creator_tool_names,
-    WorkspaceToolSurface, LIME_CREATE_BROADCAST_TASK_TOOL_NAME, LIME_CREATE_COVER_TASK_TOOL_NAME,
+    WorkspaceToolSurface, CODE_FIND_REFERENCES_TOOL_NAME, CODE_SEARCH_SYMBOLS_TOOL_NAME,
+    LIME_CREATE_BROADCAST_TASK_TOOL_NAME, LIME_CREATE_COVER_TASK_TOOL_NAME,
     LIME_CREATE_IMAGE_TASK_TOOL_NAME, LIME_CREATE_RESOURCE_SEARCH_TASK_TOOL_NAME,
     LIME_CREATE_TYPESETTING_TASK_TOOL_NAME, LIME_CREATE_URL_PARSE_TASK_TOOL_NAME,
     LIME_CREATE_VIDEO_TASK_TOOL_NAME, SOCIAL_IMAGE_TOOL_NAME, TOOL_SEARCH_TOOL_NAME,
@@ -41,6 +42,7 @@ use crate::commands::webview_cmd::{
     BrowserBackendType,
 };
 use crate::config::{GlobalConfigManager, GlobalConfigManagerState};
+use crate::database::pagination::{Page, PageRequest};
 use crate::database::DbConnection;
 use crate::mcp::{McpManagerState, McpServerConfig};
 use crate::services::agent_timeline_service::AgentTimelineRecorder;
@@ -112,6 +114,7 @@ use lime_agent::{
 };
 use lime_services::api_key_provider_service::ApiKeyProviderService;
 use lime_services::mcp_service::McpService;
+use lime_services::prompt_library_service::PromptLibraryService;
 use lime_services::video_generation_service::{
     CreateVideoGenerationRequest, VideoGenerationService,
 };
@@ -299,20 +302,23 @@ pub(crate) use browser_assist::{
 pub(crate) use command_api::{
     agent_runtime_close_subagent, agent_runtime_create_session, agent_runtime_get_session,
     agent_runtime_get_tool_inventory, agent_runtime_interrupt_turn, agent_runtime_list_sessions,
-    agent_runtime_promote_queued_turn, agent_runtime_remove_queued_turn,
-    agent_runtime_resume_subagent, agent_runtime_send_subagent_input, agent_runtime_spawn_subagent,
-    agent_runtime_submit_turn, agent_runtime_update_session, agent_runtime_wait_subagents,
+    agent_runtime_list_turn_attempts, agent_runtime_promote_queued_turn,
+    agent_runtime_regenerate_turn, agent_runtime_remove_queued_turn, agent_runtime_resume_subagent,
+    agent_runtime_send_subagent_input, agent_runtime_spawn_subagent, agent_runtime_submit_turn,
+    agent_runtime_switch_turn_attempt, agent_runtime_update_session, agent_runtime_wait_subagents,
     aster_agent_configure_from_pool, aster_agent_configure_provider, aster_agent_init,
     aster_agent_reset, aster_agent_status,
 };
 pub(crate) use dto::{
     AgentRuntimeActionType, AgentRuntimeCloseSubagentRequest, AgentRuntimeCloseSubagentResponse,
     AgentRuntimeInterruptTurnRequest, AgentRuntimePromoteQueuedTurnRequest,
-    AgentRuntimeRemoveQueuedTurnRequest, AgentRuntimeRespondActionRequest,
-    AgentRuntimeResumeSubagentRequest, AgentRuntimeResumeSubagentResponse,
-    AgentRuntimeSendSubagentInputRequest, AgentRuntimeSendSubagentInputResponse,
-    AgentRuntimeSessionDetail, AgentRuntimeSpawnSubagentRequest, AgentRuntimeSpawnSubagentResponse,
-    AgentRuntimeSubmitTurnRequest, AgentRuntimeToolInventoryRequest,
+    AgentRuntimeRegenerateTurnRequest, AgentRuntimeRemoveQueuedTurnRequest,
+    AgentRuntimeRespondActionRequest, AgentRuntimeResumeSubagentRequest,
+    AgentRuntimeResumeSubagentResponse, AgentRuntimeSendSubagentInputRequest,
+    AgentRuntimeSendSubagentInputResponse, AgentRuntimeSessionDetail,
+    AgentRuntimeSessionModelLockRequest, AgentRuntimeSpawnSubagentRequest,
+    AgentRuntimeSpawnSubagentResponse, AgentRuntimeSubmitTurnRequest,
+    AgentRuntimeSwitchTurnAttemptRequest, AgentRuntimeToolInventoryRequest,
     AgentRuntimeUpdateSessionRequest, AgentRuntimeWaitSubagentsRequest,
     AgentRuntimeWaitSubagentsResponse, AsterAgentStatus, AsterChatRequest, AutoContinuePayload,
     ConfigureFromPoolRequest, ConfigureProviderRequest,
@@ -349,8 +355,9 @@ pub(crate) use runtime_turn::{
     RuntimePreparedTeamSessionCandidate,
 };
 pub(crate) use session_runtime::{
-    delete_runtime_session_internal, persist_session_provider_routing,
-    resolve_session_provider_selector,
+    delete_runtime_session_internal, is_model_lock_override_confirmed, load_session_model_lock,
+    persist_session_model_lock, persist_session_provider_routing, resolve_session_model_lock,
+    resolve_session_provider_selector, SessionModelLock, SessionModelLockDecision,
 };
 pub(crate) use subagent_runtime::{
     agent_runtime_close_subagent_internal, agent_runtime_resume_subagent_internal,
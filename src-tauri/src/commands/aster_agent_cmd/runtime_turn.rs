@@ -612,6 +612,9 @@ async fn execute_aster_chat_request(
     } else {
         (None, TurnSystemPromptSource::None)
     };
+    // 展开 Prompt 库引用（`{{prompt:<id>}}`），覆盖项目、会话与前端传入的系统提示词
+    let resolved_prompt =
+        resolved_prompt.map(|prompt| PromptLibraryService::expand_references(db, &prompt));
     turn_input_builder.set_base_system_prompt(system_prompt_source, resolved_prompt.clone());
 
     let prompt_with_runtime_agents =
@@ -701,8 +704,63 @@ async fn execute_aster_chat_request(
         effective_strategy
     );
 
-    // 如果提供了 Provider 配置，则配置 Provider
-    if let Some(provider_config) = &request.provider_config {
+    // 会话级模型锁定：锁定后忽略全局默认变更，未确认的切换请求继续使用锁定配置
+    let session_model_lock = load_session_model_lock(session_id)
+        .await
+        .unwrap_or_else(|error| {
+            tracing::warn!(
+                "[AsterAgent] 读取会话模型锁定失败，按未锁定处理: session={}, error={}",
+                session_id,
+                error
+            );
+            None
+        });
+    let model_lock_decision = resolve_session_model_lock(
+        session_model_lock.as_ref(),
+        request.provider_config.as_ref(),
+        is_model_lock_override_confirmed(request.metadata.as_ref()),
+    );
+    match &model_lock_decision {
+        SessionModelLockDecision::Relocked { lock, .. } => {
+            tracing::info!(
+                "[AsterAgent] 已确认切换锁定模型: session={}, provider={}, model={}",
+                session_id,
+                lock.provider_selector,
+                lock.model_name
+            );
+            persist_session_model_lock(session_id, Some(lock.clone())).await?;
+        }
+        SessionModelLockDecision::OverrideRequested { lock, requested } => {
+            let requested_provider = requested
+                .provider_id
+                .clone()
+                .unwrap_or_else(|| requested.provider_name.clone());
+            tracing::info!(
+                "[AsterAgent] 会话模型已锁定，忽略未确认的切换: session={}, locked={}/{}, requested={}/{}",
+                session_id,
+                lock.provider_selector,
+                lock.model_name,
+                requested_provider,
+                requested.model_name
+            );
+            let override_event = TauriAgentEvent::ModelLockOverrideRequested {
+                session_id: session_id.to_string(),
+                locked_provider: lock.provider_selector.clone(),
+                locked_model: lock.model_name.clone(),
+                requested_provider,
+                requested_model: requested.model_name.clone(),
+            };
+            if let Err(error) = app.emit(&request.event_name, &override_event) {
+                tracing::error!("[AsterAgent] 发送模型锁定确认事件失败: {}", error);
+            }
+        }
+        SessionModelLockDecision::Unlocked | SessionModelLockDecision::Locked(_) => {}
+    }
+    let requested_provider_config =
+        model_lock_decision.effective_provider_config(request.provider_config.as_ref());
+
+    // 如果提供了 Provider 配置（或会话已锁定），则配置 Provider
+    if let Some(provider_config) = &requested_provider_config {
         tracing::info!(
             "[AsterAgent] 收到 provider_config: provider_id={:?}, provider_name={}, model_name={}, has_api_key={}, base_url={:?}",
             provider_config.provider_id,
@@ -723,6 +781,7 @@ async fn execute_aster_chat_request(
             credential_uuid: None,
             force_responses_api: false,
             credential_path: None,
+            temperature: provider_config.temperature,
         };
         // 如果前端提供了 api_key，直接使用；否则从凭证池选择凭证
         if provider_config.api_key.is_some() {
@@ -739,11 +798,12 @@ async fn execute_aster_chat_request(
                 .as_deref()
                 .unwrap_or(&provider_config.provider_name);
             state
-                .configure_provider_from_pool(
+                .configure_provider_from_pool_with_temperature(
                     db,
                     provider_selector,
                     &provider_config.model_name,
                     session_id,
+                    provider_config.temperature,
                 )
                 .await?;
             persist_session_provider_routing(session_id, provider_selector).await?;
@@ -1326,7 +1386,17 @@ fn extract_subagent_parent_session_id(metadata: Option<&serde_json::Value>) -> O
 }
 
 async fn resolve_team_runtime_provider_group_for_request(request: &AsterChatRequest) -> String {
-    if let Some(provider_config) = request.provider_config.as_ref() {
+    let session_model_lock = load_session_model_lock(&request.session_id)
+        .await
+        .ok()
+        .flatten();
+    let provider_config = resolve_session_model_lock(
+        session_model_lock.as_ref(),
+        request.provider_config.as_ref(),
+        is_model_lock_override_confirmed(request.metadata.as_ref()),
+    )
+    .effective_provider_config(request.provider_config.as_ref());
+    if let Some(provider_config) = provider_config.as_ref() {
         if let Some(provider_selector) = provider_config
             .provider_id
             .as_deref()
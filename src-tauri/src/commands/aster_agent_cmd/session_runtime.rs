@@ -64,6 +64,184 @@ pub(crate) fn resolve_session_provider_selector(
     SessionProviderRoutingState::from_session(session).map(|state| state.provider_selector)
 }
 
+/// 会话级 Provider / 模型锁定
+///
+/// 锁定后会话忽略全局默认模型变更；切换到其他 Provider / 模型的请求不会直接生效，
+/// 而是发送 `model_lock_override_requested` 事件等待用户确认。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionModelLock {
+    pub provider_selector: String,
+    pub model_name: String,
+    /// 锁定时间（毫秒时间戳）
+    pub locked_at: i64,
+}
+
+impl SessionModelLock {
+    pub(crate) fn new(
+        provider_selector: Option<String>,
+        model_name: Option<String>,
+    ) -> Option<Self> {
+        Some(Self {
+            provider_selector: normalize_optional_text(provider_selector)?,
+            model_name: normalize_optional_text(model_name)?,
+            locked_at: chrono::Utc::now().timestamp_millis(),
+        })
+    }
+
+    fn from_provider_request(request: &ConfigureProviderRequest) -> Option<Self> {
+        Self::new(
+            Some(requested_provider_selector(request).to_string()),
+            Some(request.model_name.clone()),
+        )
+    }
+
+    /// 请求的 Provider / 模型是否与锁定一致
+    pub(crate) fn matches(&self, request: &ConfigureProviderRequest) -> bool {
+        requested_provider_selector(request)
+            .trim()
+            .eq_ignore_ascii_case(&self.provider_selector)
+            && request.model_name.trim() == self.model_name
+    }
+
+    /// 按锁定配置构造 Provider 请求（凭证从凭证池选择）
+    pub(crate) fn to_provider_request(&self) -> ConfigureProviderRequest {
+        ConfigureProviderRequest {
+            provider_id: Some(self.provider_selector.clone()),
+            provider_name: self.provider_selector.clone(),
+            model_name: self.model_name.clone(),
+            api_key: None,
+            base_url: None,
+            temperature: None,
+        }
+    }
+}
+
+fn requested_provider_selector(request: &ConfigureProviderRequest) -> &str {
+    request
+        .provider_id
+        .as_deref()
+        .filter(|value| !value.trim().is_empty())
+        .unwrap_or(&request.provider_name)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SessionModelLockState {
+    #[serde(default)]
+    lock: Option<SessionModelLock>,
+}
+
+impl ExtensionState for SessionModelLockState {
+    const EXTENSION_NAME: &'static str = "lime_model_lock";
+    const VERSION: &'static str = "v0";
+}
+
+/// 模型锁定对本回合 Provider 路由的决策
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SessionModelLockDecision {
+    /// 会话未锁定，按请求原样路由
+    Unlocked,
+    /// 使用锁定的 Provider / 模型
+    Locked(ConfigureProviderRequest),
+    /// 已确认切换：更新锁定并使用新的 Provider / 模型
+    Relocked {
+        lock: SessionModelLock,
+        provider_config: ConfigureProviderRequest,
+    },
+    /// 未确认的切换：继续使用锁定配置，并请求用户确认
+    OverrideRequested {
+        lock: SessionModelLock,
+        requested: ConfigureProviderRequest,
+    },
+}
+
+impl SessionModelLockDecision {
+    /// 本回合实际使用的 Provider 配置
+    pub(crate) fn effective_provider_config(
+        &self,
+        requested: Option<&ConfigureProviderRequest>,
+    ) -> Option<ConfigureProviderRequest> {
+        match self {
+            Self::Unlocked => requested.cloned(),
+            Self::Locked(provider_config)
+            | Self::Relocked {
+                provider_config, ..
+            } => Some(provider_config.clone()),
+            Self::OverrideRequested { lock, .. } => Some(lock.to_provider_request()),
+        }
+    }
+}
+
+/// 请求元数据中是否已确认切换锁定模型
+pub(crate) fn is_model_lock_override_confirmed(
+    request_metadata: Option<&serde_json::Value>,
+) -> bool {
+    extract_harness_bool(
+        request_metadata,
+        &["model_lock_override", "modelLockOverride"],
+    )
+    .unwrap_or(false)
+}
+
+pub(crate) fn resolve_session_model_lock(
+    lock: Option<&SessionModelLock>,
+    requested: Option<&ConfigureProviderRequest>,
+    override_confirmed: bool,
+) -> SessionModelLockDecision {
+    let Some(lock) = lock else {
+        return SessionModelLockDecision::Unlocked;
+    };
+    match requested {
+        None => SessionModelLockDecision::Locked(lock.to_provider_request()),
+        Some(requested) if lock.matches(requested) => {
+            SessionModelLockDecision::Locked(requested.clone())
+        }
+        Some(requested) => match SessionModelLock::from_provider_request(requested) {
+            Some(next_lock) if override_confirmed => SessionModelLockDecision::Relocked {
+                lock: next_lock,
+                provider_config: requested.clone(),
+            },
+            Some(_) => SessionModelLockDecision::OverrideRequested {
+                lock: lock.clone(),
+                requested: requested.clone(),
+            },
+            None => SessionModelLockDecision::Locked(lock.to_provider_request()),
+        },
+    }
+}
+
+pub(crate) async fn load_session_model_lock(
+    session_id: &str,
+) -> Result<Option<SessionModelLock>, String> {
+    let session = SessionManager::get_session(session_id, false)
+        .await
+        .map_err(|error| format!("读取会话模型锁定失败: {error}"))?;
+    Ok(
+        <SessionModelLockState as ExtensionState>::from_extension_data(&session.extension_data)
+            .and_then(|state| state.lock),
+    )
+}
+
+pub(crate) async fn persist_session_model_lock(
+    session_id: &str,
+    lock: Option<SessionModelLock>,
+) -> Result<(), String> {
+    let session = SessionManager::get_session(session_id, false)
+        .await
+        .map_err(|error| format!("读取会话模型锁定失败: {error}"))?;
+    let mut extension_data = session.extension_data.clone();
+    <SessionModelLockState as ExtensionState>::to_extension_data(
+        &SessionModelLockState { lock },
+        &mut extension_data,
+    )
+    .map_err(|error| error.to_string())?;
+    SessionManager::update_session(session_id)
+        .extension_data(extension_data)
+        .apply()
+        .await
+        .map_err(|error| format!("持久化会话模型锁定失败: {error}"))?;
+    Ok(())
+}
+
 pub(crate) async fn create_runtime_session_internal(
     db: &DbConnection,
     working_dir: Option<String>,
@@ -138,6 +316,13 @@ pub(crate) fn list_runtime_sessions_internal(
     AsterAgentWrapper::list_sessions_sync(db)
 }
 
+pub(crate) fn list_runtime_sessions_page_internal(
+    db: &DbConnection,
+    page: &PageRequest,
+) -> Result<Page<SessionInfo>, String> {
+    AsterAgentWrapper::list_sessions_page_sync(db, page)
+}
+
 pub(crate) fn rename_runtime_session_internal(
     db: &DbConnection,
     session_id: &str,
@@ -1,9 +1,9 @@
 #[cfg(test)]
 mod tests {
     use super::*;
-    use async_trait::async_trait;
     use crate::commands::aster_agent_cmd::action_runtime::build_runtime_action_scope;
     use crate::commands::aster_agent_cmd::dto::AgentRuntimeActionScope;
+    use async_trait::async_trait;
     use lime_agent::request_tool_policy::resolve_request_tool_policy;
     use regex::Regex;
     use std::ffi::OsString;
@@ -851,6 +851,7 @@ mod tests {
                         text: "hello".to_string(),
                     }],
                     timestamp: 0,
+                    cost: None,
                 },
             },
             "/tmp/workspace",
@@ -876,6 +877,7 @@ mod tests {
                         text: "hello".to_string(),
                     }],
                     timestamp: 0,
+                    cost: None,
                 },
             },
             "/tmp/workspace",
@@ -934,6 +936,70 @@ mod tests {
         assert!(provider_routing_matches_current(&previous, &current));
     }
 
+    #[test]
+    fn test_resolve_session_model_lock_keeps_locked_model_until_confirmed() {
+        let lock = SessionModelLock::new(
+            Some("anthropic".to_string()),
+            Some("claude-sonnet-4-5".to_string()),
+        )
+        .unwrap();
+        let requested = ConfigureProviderRequest {
+            provider_id: Some("openai".to_string()),
+            provider_name: "OpenAI".to_string(),
+            model_name: "gpt-4o".to_string(),
+            api_key: None,
+            base_url: None,
+            temperature: None,
+        };
+
+        assert_eq!(
+            resolve_session_model_lock(None, Some(&requested), false),
+            SessionModelLockDecision::Unlocked
+        );
+        // 未传 provider_config（全局默认）时沿用锁定配置
+        assert_eq!(
+            resolve_session_model_lock(Some(&lock), None, false),
+            SessionModelLockDecision::Locked(lock.to_provider_request())
+        );
+
+        let decision = resolve_session_model_lock(Some(&lock), Some(&requested), false);
+        assert!(matches!(
+            decision,
+            SessionModelLockDecision::OverrideRequested { .. }
+        ));
+        assert_eq!(
+            decision
+                .effective_provider_config(Some(&requested))
+                .map(|config| config.model_name),
+            Some("claude-sonnet-4-5".to_string())
+        );
+
+        match resolve_session_model_lock(Some(&lock), Some(&requested), true) {
+            SessionModelLockDecision::Relocked {
+                lock,
+                provider_config,
+            } => {
+                assert_eq!(lock.provider_selector, "openai");
+                assert_eq!(lock.model_name, "gpt-4o");
+                assert_eq!(provider_config, requested);
+            }
+            other => panic!("Expected Relocked decision, got {other:?}"),
+        }
+
+        let same = ConfigureProviderRequest {
+            provider_id: None,
+            provider_name: "Anthropic".to_string(),
+            model_name: "claude-sonnet-4-5".to_string(),
+            api_key: Some("sk-test".to_string()),
+            base_url: None,
+            temperature: None,
+        };
+        assert_eq!(
+            resolve_session_model_lock(Some(&lock), Some(&same), false),
+            SessionModelLockDecision::Locked(same.clone())
+        );
+    }
+
     #[test]
     fn test_aster_execution_strategy_default_is_auto() {
         assert_eq!(
@@ -1874,6 +1940,7 @@ mod tests {
                     text: "子代理最终结论".to_string(),
                 }],
                 timestamp: 0,
+                cost: None,
             }],
             execution_strategy: None,
             turns: vec![],
@@ -2,6 +2,8 @@ use super::*;
 
 #[path = "tool_runtime/browser_tools.rs"]
 mod browser_tools;
+#[path = "tool_runtime/code_index_tools.rs"]
+mod code_index_tools;
 #[path = "tool_runtime/creation_tools.rs"]
 mod creation_tools;
 #[path = "tool_runtime/search_bridge.rs"]
@@ -191,6 +193,7 @@ pub(crate) async fn apply_workspace_sandbox_permissions(
         should_auto_approve_tool_warnings("Task", auto_mode, execution_policy_input),
         sandboxed_bash_tool,
     );
+    code_index_tools::register_code_index_tools_to_registry(&mut registry, workspace_root);
 
     let subagent_runtime = SubagentControlRuntime::new(
         app_handle.clone(),
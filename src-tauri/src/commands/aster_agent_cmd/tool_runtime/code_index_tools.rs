@@ -0,0 +1,210 @@
+use super::*;
+use lime_agent::code_index::{workspace_code_index, CodeIndex, SymbolQuery};
+
+const DEFAULT_SYMBOL_LIMIT: usize = 30;
+const MAX_SYMBOL_LIMIT: usize = 200;
+const DEFAULT_REFERENCE_LIMIT: usize = 50;
+const MAX_REFERENCE_LIMIT: usize = 300;
+const DEFAULT_RECENT_CHANGE_LIMIT: usize = 20;
+
+fn parse_optional_str<'a>(params: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    params
+        .get(key)
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_limit(params: &serde_json::Value, default: usize, max: usize) -> usize {
+    params
+        .get("limit")
+        .and_then(|value| value.as_u64())
+        .map(|value| (value as usize).clamp(1, max))
+        .unwrap_or(default)
+}
+
+/// 在阻塞线程中刷新索引并执行查询
+async fn query_workspace_index<T, F>(workspace_root: &Path, query: F) -> Result<T, ToolError>
+where
+    T: Send + 'static,
+    F: FnOnce(&CodeIndex) -> Result<T, String> + Send + 'static,
+{
+    let index = workspace_code_index(workspace_root);
+    tokio::task::spawn_blocking(move || {
+        let mut index = index
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        index.refresh_if_stale();
+        query(&index)
+    })
+    .await
+    .map_err(|e| ToolError::execution_failed(format!("代码索引任务失败: {e}")))?
+    .map_err(ToolError::invalid_params)
+}
+
+fn to_pretty_json(value: serde_json::Value, tool_name: &str) -> Result<String, ToolError> {
+    serde_json::to_string_pretty(&value)
+        .map_err(|e| ToolError::execution_failed(format!("{tool_name} 序列化失败: {e}")))
+}
+
+pub(crate) struct CodeSearchSymbolsTool {
+    workspace_root: PathBuf,
+}
+
+impl CodeSearchSymbolsTool {
+    fn new(workspace_root: PathBuf) -> Self {
+        Self { workspace_root }
+    }
+}
+
+#[async_trait]
+impl Tool for CodeSearchSymbolsTool {
+    fn name(&self) -> &str {
+        CODE_SEARCH_SYMBOLS_TOOL_NAME
+    }
+
+    fn description(&self) -> &str {
+        "在当前工作区代码索引中按名称搜索符号定义（函数、方法、类型、类、常量等），返回文件路径、行号与定义行，并附带最近变更的文件列表。定位代码时优先使用本工具，再用 read 读取目标行附近内容，避免整文件读取。支持 Rust/TypeScript/JavaScript/Python/Go。"
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string", "description": "符号名称或片段，精确匹配优先；为空时按其他条件列出" },
+                "kind": {
+                    "type": "string",
+                    "enum": ["function", "method", "struct", "class", "interface", "enum", "trait", "type", "constant", "variable", "module", "macro"],
+                    "description": "按符号类型过滤"
+                },
+                "path_prefix": { "type": "string", "description": "只搜索该相对路径前缀下的文件，例如 src/components" },
+                "recently_changed_only": { "type": "boolean", "description": "只搜索最近变更的文件" },
+                "limit": { "type": "integer", "minimum": 1, "maximum": MAX_SYMBOL_LIMIT }
+            },
+            "required": []
+        })
+    }
+
+    fn options(&self) -> ToolOptions {
+        ToolOptions::new()
+            .with_max_retries(1)
+            .with_base_timeout(Duration::from_secs(60))
+            .with_dynamic_timeout(false)
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        _context: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let query = parse_optional_str(&params, "query")
+            .unwrap_or_default()
+            .to_string();
+        let kind = parse_optional_str(&params, "kind").map(ToString::to_string);
+        let path_prefix = parse_optional_str(&params, "path_prefix").map(ToString::to_string);
+        let recently_changed_only = params
+            .get("recently_changed_only")
+            .and_then(|value| value.as_bool())
+            .unwrap_or(false);
+        let limit = parse_limit(&params, DEFAULT_SYMBOL_LIMIT, MAX_SYMBOL_LIMIT);
+        if query.is_empty() && kind.is_none() && path_prefix.is_none() && !recently_changed_only {
+            return Err(ToolError::invalid_params(
+                "query、kind、path_prefix、recently_changed_only 至少提供一项",
+            ));
+        }
+
+        let result = query_workspace_index(&self.workspace_root, move |index| {
+            let symbols = index.search_symbols(&SymbolQuery {
+                query: &query,
+                kind: kind.as_deref(),
+                path_prefix: path_prefix.as_deref(),
+                recently_changed_only,
+                limit,
+            });
+            Ok(serde_json::json!({
+                "query": query,
+                "index": index.stats(),
+                "count": symbols.len(),
+                "symbols": symbols,
+                "recent_changes": index.recent_changes(DEFAULT_RECENT_CHANGE_LIMIT),
+            }))
+        })
+        .await?;
+
+        Ok(ToolResult::success(to_pretty_json(result, self.name())?))
+    }
+}
+
+pub(crate) struct CodeFindReferencesTool {
+    workspace_root: PathBuf,
+}
+
+impl CodeFindReferencesTool {
+    fn new(workspace_root: PathBuf) -> Self {
+        Self { workspace_root }
+    }
+}
+
+#[async_trait]
+impl Tool for CodeFindReferencesTool {
+    fn name(&self) -> &str {
+        CODE_FIND_REFERENCES_TOOL_NAME
+    }
+
+    fn description(&self) -> &str {
+        "在当前工作区代码索引中查找符号的引用位置（全词匹配，仅扫描实际使用该标识符的源码文件），返回文件路径、行列号与所在行，并标注哪一处是定义。适合在修改函数或类型前评估影响范围。"
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "symbol": { "type": "string", "description": "完整的符号名称" },
+                "path_prefix": { "type": "string", "description": "只在该相对路径前缀下查找" },
+                "limit": { "type": "integer", "minimum": 1, "maximum": MAX_REFERENCE_LIMIT }
+            },
+            "required": ["symbol"]
+        })
+    }
+
+    fn options(&self) -> ToolOptions {
+        ToolOptions::new()
+            .with_max_retries(1)
+            .with_base_timeout(Duration::from_secs(60))
+            .with_dynamic_timeout(false)
+    }
+
+    async fn execute(
+        &self,
+        params: serde_json::Value,
+        _context: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let symbol = parse_optional_str(&params, "symbol")
+            .ok_or_else(|| ToolError::invalid_params("Missing required parameter: symbol"))?
+            .to_string();
+        let path_prefix = parse_optional_str(&params, "path_prefix").map(ToString::to_string);
+        let limit = parse_limit(&params, DEFAULT_REFERENCE_LIMIT, MAX_REFERENCE_LIMIT);
+
+        let result = query_workspace_index(&self.workspace_root, move |index| {
+            let references = index.find_references(&symbol, path_prefix.as_deref(), limit)?;
+            Ok(serde_json::json!({
+                "symbol": symbol,
+                "count": references.len(),
+                "truncated": references.len() >= limit,
+                "references": references,
+            }))
+        })
+        .await?;
+
+        Ok(ToolResult::success(to_pretty_json(result, self.name())?))
+    }
+}
+
+pub(super) fn register_code_index_tools_to_registry(
+    registry: &mut aster::tools::ToolRegistry,
+    workspace_root: &str,
+) {
+    let workspace_root = PathBuf::from(workspace_root);
+    registry.register(Box::new(CodeSearchSymbolsTool::new(workspace_root.clone())));
+    registry.register(Box::new(CodeFindReferencesTool::new(workspace_root)));
+}
@@ -0,0 +1,81 @@
+//! 请求审计日志检索与导出命令
+
+use crate::config::save_config;
+use crate::database::{lock_db, DbConnection};
+use crate::AppState;
+use lime_core::config::AuditLogSettings;
+use lime_core::database::dao::request_audit::{AuditQuery, RequestAuditDao, RequestAuditRecord};
+use lime_core::database::pagination::Page;
+use tauri::State;
+
+/// 单次导出的最大记录数
+const MAX_EXPORT_RECORDS: usize = 50_000;
+
+/// 获取审计日志配置
+#[tauri::command]
+pub async fn get_audit_log_settings(
+    state: State<'_, AppState>,
+) -> Result<AuditLogSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.audit_log.clone())
+}
+
+/// 更新审计日志配置
+#[tauri::command]
+pub async fn update_audit_log_settings(
+    state: State<'_, AppState>,
+    settings: AuditLogSettings,
+) -> Result<(), String> {
+    if settings.max_body_chars == 0 {
+        return Err("请求体保留字符数必须大于 0".to_string());
+    }
+
+    let mut s = state.write().await;
+    s.config.audit_log = settings;
+    save_config(&s.config).map_err(|e| e.to_string())?;
+    s.audit_log.reload(&s.config.audit_log);
+    Ok(())
+}
+
+/// 分页检索审计日志（最新在前），以返回的 `next_cursor` 作为下一页的 `query.cursor`
+#[tauri::command]
+pub fn search_audit_log(
+    db: State<'_, DbConnection>,
+    query: Option<AuditQuery>,
+) -> Result<Page<RequestAuditRecord>, String> {
+    let conn = lock_db(&db)?;
+    RequestAuditDao::search_page(&conn, &query.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// 按检索条件导出审计日志为 JSON Lines（未指定 limit 时导出全部匹配记录）
+#[tauri::command]
+pub fn export_audit_log(
+    db: State<'_, DbConnection>,
+    query: Option<AuditQuery>,
+) -> Result<String, String> {
+    let mut query = query.unwrap_or_default();
+    query.limit = Some(
+        query
+            .limit
+            .unwrap_or(MAX_EXPORT_RECORDS)
+            .min(MAX_EXPORT_RECORDS),
+    );
+    let records = {
+        let conn = lock_db(&db)?;
+        RequestAuditDao::search(&conn, &query).map_err(|e| e.to_string())?
+    };
+
+    let mut output = String::new();
+    for record in &records {
+        output.push_str(&serde_json::to_string(record).map_err(|e| e.to_string())?);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// 清空审计日志
+#[tauri::command]
+pub fn clear_audit_log(db: State<'_, DbConnection>) -> Result<usize, String> {
+    let conn = lock_db(&db)?;
+    RequestAuditDao::clear(&conn).map_err(|e| e.to_string())
+}
@@ -0,0 +1,118 @@
+//! 后台模式命令
+//!
+//! 后台模式下应用启动时不显示主窗口，代理服务器照常自动启动，托盘图标作为唯一入口；
+//! 配合开机自启动（携带 `--minimized` 参数）即可在登录后作为常驻后台服务运行。
+
+use crate::config::save_config;
+use crate::AppState;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+
+/// 开机自启动时附带的启动参数
+pub const BACKGROUND_LAUNCH_ARG: &str = "--minimized";
+
+/// 本次是否由开机自启动以后台方式拉起
+pub fn is_background_launch() -> bool {
+    std::env::args().any(|arg| arg == BACKGROUND_LAUNCH_ARG)
+}
+
+/// 后台模式状态
+#[derive(Debug, Clone, Serialize)]
+pub struct BackgroundModeStatus {
+    /// 是否启用后台模式（仅托盘运行）
+    pub background_mode: bool,
+    /// 是否已注册开机自启动
+    pub autostart_enabled: bool,
+    /// 本次是否以后台方式启动
+    pub launched_in_background: bool,
+}
+
+/// 应用后台模式的窗口与 Dock 表现
+///
+/// 启用时隐藏主窗口；macOS 上同时隐藏 Dock 图标，只保留菜单栏托盘。
+pub fn apply_background_presence(app: &AppHandle, enabled: bool) {
+    if enabled {
+        if let Some(window) = app.get_webview_window("main") {
+            if let Err(e) = window.hide() {
+                tracing::warn!("[后台模式] 隐藏主窗口失败: {}", e);
+            }
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let policy = if enabled {
+            tauri::ActivationPolicy::Accessory
+        } else {
+            tauri::ActivationPolicy::Regular
+        };
+        if let Err(e) = app.set_activation_policy(policy) {
+            tracing::warn!("[后台模式] 设置 Dock 图标显示策略失败: {}", e);
+        }
+    }
+}
+
+async fn current_status(
+    app: &AppHandle,
+    state: &tauri::State<'_, AppState>,
+) -> Result<BackgroundModeStatus, String> {
+    let autostart_enabled = app
+        .autolaunch()
+        .is_enabled()
+        .map_err(|e| format!("Failed to get autostart status: {e}"))?;
+    let background_mode = state.read().await.config.background_mode;
+    Ok(BackgroundModeStatus {
+        background_mode,
+        autostart_enabled,
+        launched_in_background: is_background_launch(),
+    })
+}
+
+/// 获取后台模式状态
+#[tauri::command]
+pub async fn get_background_mode_status(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+) -> Result<BackgroundModeStatus, String> {
+    current_status(&app, &state).await
+}
+
+/// 切换后台模式
+///
+/// `autostart` 非空时同时注册或取消开机自启动。
+#[tauri::command]
+pub async fn set_background_mode(
+    app: AppHandle,
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+    autostart: Option<bool>,
+) -> Result<BackgroundModeStatus, String> {
+    if let Some(autostart) = autostart {
+        let manager = app.autolaunch();
+        if autostart {
+            manager
+                .enable()
+                .map_err(|e| format!("Failed to enable autostart: {e}"))?;
+        } else {
+            manager
+                .disable()
+                .map_err(|e| format!("Failed to disable autostart: {e}"))?;
+        }
+    }
+
+    {
+        let mut s = state.write().await;
+        if s.config.background_mode != enabled {
+            s.config.background_mode = enabled;
+            save_config(&s.config).map_err(|e| e.to_string())?;
+        }
+    }
+    apply_background_presence(&app, enabled);
+    tracing::info!(
+        "[后台模式] 已{}后台模式",
+        if enabled { "启用" } else { "关闭" }
+    );
+
+    current_status(&app, &state).await
+}
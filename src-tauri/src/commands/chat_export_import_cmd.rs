@@ -0,0 +1,42 @@
+//! 对话导出导入命令
+//!
+//! 将 ChatGPT / Claude 网页端导出的对话存档导入为通用对话会话，
+//! 解析与写入逻辑见 [`lime_services::chat_export_import_service`]。
+
+use crate::commands::provider_pool_cmd::expand_tilde;
+use crate::database::{lock_db, DbConnection};
+use lime_services::chat_export_import_service::{
+    import_export, read_export, ChatExportFormat, ChatExportImportReport,
+};
+use std::path::Path;
+use tauri::State;
+
+/// 导入对话导出存档
+///
+/// # 参数
+/// - `path`: `conversations.json` 或导出的 zip 存档路径
+/// - `format`: 导出格式，为空时根据内容自动识别
+#[tauri::command]
+pub async fn import_chat_export(
+    db: State<'_, DbConnection>,
+    path: String,
+    format: Option<ChatExportFormat>,
+) -> Result<ChatExportImportReport, String> {
+    let path = expand_tilde(&path);
+    let export = tokio::task::spawn_blocking(move || read_export(Path::new(&path)))
+        .await
+        .map_err(|e| format!("读取导出文件失败: {e}"))??;
+
+    let report = {
+        let conn = lock_db(&db)?;
+        import_export(&conn, &export, format)?
+    };
+    tracing::info!(
+        "[对话导入] {:?} 导出导入完成：{} 个会话、{} 条消息，跳过 {} 项",
+        report.format,
+        report.imported_sessions,
+        report.imported_messages,
+        report.skipped.len()
+    );
+    Ok(report)
+}
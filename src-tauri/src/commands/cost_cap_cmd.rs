@@ -0,0 +1,90 @@
+//! 月度费用上限管理命令
+
+use crate::config::save_config;
+use crate::AppState;
+use lime_core::config::CostCapSettings;
+use lime_server::middleware::cost_cap::{CostCapEvent, CostCapStatus};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+
+/// 费用上限事件名（预警 / 超限 / 放行变化）
+pub const COST_CAP_EVENT: &str = "cost-cap-event";
+
+/// 获取费用上限配置
+#[tauri::command]
+pub async fn get_cost_cap_settings(
+    state: tauri::State<'_, AppState>,
+) -> Result<CostCapSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.cost_caps.clone())
+}
+
+/// 更新费用上限配置
+#[tauri::command]
+pub async fn update_cost_cap_settings(
+    state: tauri::State<'_, AppState>,
+    settings: CostCapSettings,
+) -> Result<(), String> {
+    if settings.monthly_limit_usd.is_some_and(|limit| limit < 0.0)
+        || settings
+            .provider_limits_usd
+            .values()
+            .any(|limit| *limit < 0.0)
+    {
+        return Err("费用上限不能为负数".to_string());
+    }
+    if !(settings.warning_ratio > 0.0 && settings.warning_ratio <= 1.0) {
+        return Err("预警比例必须在 (0, 1] 范围内".to_string());
+    }
+
+    let mut s = state.write().await;
+    s.config.cost_caps = settings;
+    save_config(&s.config).map_err(|e| e.to_string())?;
+    s.cost_cap_guard.reload(&s.config.cost_caps);
+    Ok(())
+}
+
+/// 获取本月费用与上限状态
+#[tauri::command]
+pub async fn get_cost_cap_status(
+    state: tauri::State<'_, AppState>,
+) -> Result<CostCapStatus, String> {
+    let s = state.read().await;
+    Ok(s.cost_cap_guard.status())
+}
+
+/// 临时放行付费请求（分钟数为空时取消放行），返回放行截止时间（Unix 秒）
+#[tauri::command]
+pub async fn set_cost_cap_override(
+    state: tauri::State<'_, AppState>,
+    duration_minutes: Option<u64>,
+) -> Result<Option<i64>, String> {
+    let until = duration_minutes
+        .filter(|minutes| *minutes > 0)
+        .map(|minutes| chrono::Utc::now().timestamp() + (minutes as i64) * 60);
+    let s = state.read().await;
+    s.cost_cap_guard.set_override(until);
+    Ok(until)
+}
+
+/// 将费用上限事件转发到前端
+pub fn spawn_cost_cap_event_forwarder(
+    app_handle: AppHandle,
+    mut receiver: broadcast::Receiver<CostCapEvent>,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let Err(e) = app_handle.emit(COST_CAP_EVENT, &event) {
+                        tracing::warn!("[COST_CAP] 发送事件失败: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("[COST_CAP] 事件转发滞后，丢弃 {} 条", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
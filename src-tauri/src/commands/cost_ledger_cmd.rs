@@ -0,0 +1,85 @@
+//! 请求费用明细查询与预算提醒命令
+
+use crate::config::save_config;
+use crate::database::{lock_db, DbConnection};
+use crate::AppState;
+use lime_core::config::CostBudgetSettings;
+use lime_core::database::dao::request_cost::{RequestCostDao, SpendBucket, SpendGroup};
+use lime_server::middleware::cost_ledger::CostBudgetEvent;
+use tauri::{AppHandle, Emitter, State};
+use tokio::sync::broadcast;
+
+/// 预算事件名（提醒 / 超出）
+pub const COST_BUDGET_EVENT: &str = "cost-budget-event";
+
+/// 获取预算配置
+#[tauri::command]
+pub async fn get_cost_budget_settings(
+    state: State<'_, AppState>,
+) -> Result<CostBudgetSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.cost_budgets.clone())
+}
+
+/// 更新预算配置
+#[tauri::command]
+pub async fn update_cost_budget_settings(
+    state: State<'_, AppState>,
+    settings: CostBudgetSettings,
+) -> Result<(), String> {
+    if settings
+        .budgets
+        .iter()
+        .any(|budget| budget.amount_usd <= 0.0)
+    {
+        return Err("预算金额必须大于 0".to_string());
+    }
+    if !(settings.alert_ratio > 0.0 && settings.alert_ratio <= 1.0) {
+        return Err("提醒比例必须在 (0, 1] 范围内".to_string());
+    }
+
+    let mut s = state.write().await;
+    s.config.cost_budgets = settings;
+    save_config(&s.config).map_err(|e| e.to_string())?;
+    s.cost_ledger.reload(&s.config.cost_budgets);
+    Ok(())
+}
+
+/// 按维度汇总花费
+///
+/// 日期为 UTC `YYYY-MM-DD`（含两端），未指定时默认为本月初至今天。
+#[tauri::command]
+pub fn get_cost_spend(
+    db: State<'_, DbConnection>,
+    group: SpendGroup,
+    from_day: Option<String>,
+    to_day: Option<String>,
+) -> Result<Vec<SpendBucket>, String> {
+    let now = chrono::Utc::now();
+    let from_day = from_day.unwrap_or_else(|| now.format("%Y-%m-01").to_string());
+    let to_day = to_day.unwrap_or_else(|| now.format("%Y-%m-%d").to_string());
+    let conn = lock_db(&db)?;
+    RequestCostDao::summarize(&conn, group, &from_day, &to_day).map_err(|e| e.to_string())
+}
+
+/// 将预算事件转发到前端
+pub fn spawn_cost_budget_event_forwarder(
+    app_handle: AppHandle,
+    mut receiver: broadcast::Receiver<CostBudgetEvent>,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let Err(e) = app_handle.emit(COST_BUDGET_EVENT, &event) {
+                        tracing::warn!("[COST_LEDGER] 发送事件失败: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("[COST_LEDGER] 事件转发滞后，丢弃 {} 条", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
@@ -0,0 +1,331 @@
+//! 凭证备份命令
+//!
+//! - `export_credential_backup`: 将凭证池凭证、插件密钥与 ASR 凭证导出为口令加密的备份文件
+//! - `inspect_credential_backup`: 读取备份文件头（格式、导出时间与条目数量），无需口令
+//! - `import_credential_backup`: 解密备份并按冲突策略（跳过 / 覆盖 / 合并）导入
+//!
+//! 凭证的采集与写入在本模块完成，打包、加密与冲突判定见
+//! [`lime_services::credential_backup_service`]。
+
+use crate::commands::plugin_cmd::{plugin_crypto_context, PluginCryptoState};
+use crate::commands::provider_pool_cmd::{expand_tilde, get_credentials_dir};
+use crate::config::{load_config, save_config, AsrCredentialEntry};
+use crate::database::dao::plugin_storage::PluginStorageDao;
+use crate::database::dao::provider_pool::ProviderPoolDao;
+use crate::database::{lock_db, DbConnection};
+use crate::models::provider_pool_model::{
+    get_oauth_creds_path, set_oauth_creds_path, ProviderCredential,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use lime_credential::MasterKeyring;
+use lime_services::credential_backup_service::{
+    merge_asr_entry, merge_pool_credential, open_backup, resolve, seal_backup, BackupPluginSecret,
+    BackupPoolCredential, ConflictStrategy, CredentialBackup, CredentialBackupEnvelope,
+    CredentialBackupHeader, CredentialBackupImportReport, ImportDecision,
+};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+/// 导出结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialBackupExport {
+    #[serde(flatten)]
+    pub header: CredentialBackupHeader,
+    /// 未能导出的条目（token 文件缺失、插件密钥无法解密等）
+    pub warnings: Vec<String>,
+}
+
+fn read_envelope(path: &str) -> Result<CredentialBackupEnvelope, String> {
+    let bytes = fs::read(expand_tilde(path)).map_err(|e| format!("读取备份文件失败: {e}"))?;
+    CredentialBackupEnvelope::from_bytes(&bytes)
+}
+
+// ============ 导出 ============
+
+/// 采集凭证池凭证，OAuth 凭证附带 token 文件内容
+fn collect_pool(db: &DbConnection) -> Result<(Vec<BackupPoolCredential>, Vec<String>), String> {
+    let credentials = {
+        let conn = lock_db(db)?;
+        ProviderPoolDao::get_all(&conn).map_err(|e| format!("读取凭证池失败: {e}"))?
+    };
+
+    let mut warnings = Vec::new();
+    let pool = credentials
+        .into_iter()
+        .map(|credential| {
+            let token_file = get_oauth_creds_path(&credential.credential).and_then(|path| {
+                match fs::read(expand_tilde(&path)) {
+                    Ok(content) => Some(BASE64.encode(content)),
+                    Err(e) => {
+                        warnings.push(format!(
+                            "读取凭证 {} 的 token 文件失败: {e}",
+                            credential.uuid
+                        ));
+                        None
+                    }
+                }
+            });
+            BackupPoolCredential {
+                credential,
+                token_file,
+            }
+        })
+        .collect();
+    Ok((pool, warnings))
+}
+
+/// 采集插件存储中的加密值，解密为明文
+fn collect_plugin_secrets(
+    db: &DbConnection,
+    crypto: &MasterKeyring,
+) -> Result<(Vec<BackupPluginSecret>, Vec<String>), String> {
+    let entries = {
+        let conn = lock_db(db)?;
+        PluginStorageDao::list_string_values_with_prefix(&conn, MasterKeyring::prefix())
+            .map_err(|e| format!("读取插件存储失败: {e}"))?
+    };
+
+    let mut secrets = Vec::new();
+    let mut warnings = Vec::new();
+    for (plugin_id, key, raw) in entries {
+        let plaintext = serde_json::from_str::<String>(&raw)
+            .map_err(|e| e.to_string())
+            .and_then(|ciphertext| {
+                crypto
+                    .decrypt(&plugin_crypto_context(&plugin_id), &ciphertext)
+                    .map_err(|e| e.to_string())
+            });
+        match plaintext {
+            Ok(plaintext) => secrets.push(BackupPluginSecret {
+                plugin_id,
+                key,
+                plaintext,
+            }),
+            Err(e) => warnings.push(format!("解密插件密钥 {plugin_id}/{key} 失败: {e}")),
+        }
+    }
+    Ok((secrets, warnings))
+}
+
+/// 导出凭证备份
+#[tauri::command]
+pub async fn export_credential_backup(
+    db: State<'_, DbConnection>,
+    crypto: State<'_, PluginCryptoState>,
+    path: String,
+    passphrase: String,
+) -> Result<CredentialBackupExport, String> {
+    let (pool, mut warnings) = collect_pool(&db)?;
+    let (plugin_secrets, secret_warnings) = collect_plugin_secrets(&db, &crypto.0)?;
+    warnings.extend(secret_warnings);
+    let asr = load_config()
+        .map_err(|e| e.to_string())?
+        .credential_pool
+        .asr;
+    for warning in &warnings {
+        tracing::warn!("[凭证备份] {}", warning);
+    }
+
+    let backup = CredentialBackup {
+        pool,
+        plugin_secrets,
+        asr,
+    };
+    let envelope = seal_backup(&backup, env!("CARGO_PKG_VERSION"), &passphrase)?;
+
+    let path = expand_tilde(&path);
+    if let Some(parent) = Path::new(&path).parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建备份目录失败: {e}"))?;
+    }
+    fs::write(&path, envelope.to_bytes()?).map_err(|e| format!("写入备份文件失败: {e}"))?;
+    tracing::info!(
+        "[凭证备份] 已导出 {} 个凭证池凭证、{} 个插件密钥、{} 个 ASR 凭证",
+        envelope.header.counts.pool,
+        envelope.header.counts.plugin_secrets,
+        envelope.header.counts.asr
+    );
+    Ok(CredentialBackupExport {
+        header: envelope.header,
+        warnings,
+    })
+}
+
+/// 读取备份文件头
+#[tauri::command]
+pub async fn inspect_credential_backup(path: String) -> Result<CredentialBackupHeader, String> {
+    Ok(read_envelope(&path)?.header)
+}
+
+// ============ 导入 ============
+
+/// 将备份中的 token 文件写入本机凭证目录，返回新路径
+fn store_token_file(credential: &ProviderCredential, content: &str) -> Result<String, String> {
+    let bytes = BASE64
+        .decode(content)
+        .map_err(|_| "token 文件内容无效".to_string())?;
+    let provider_type = credential.provider_type.to_string();
+    let timestamp = chrono::Utc::now().timestamp();
+    let filename = format!(
+        "{provider_type}_{}_{timestamp}_{provider_type}.json",
+        credential.uuid.chars().take(8).collect::<String>()
+    );
+    let path = get_credentials_dir()?.join(filename);
+    fs::write(&path, bytes).map_err(|e| format!("写入 token 文件失败: {e}"))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+fn import_pool(
+    db: &DbConnection,
+    entries: Vec<BackupPoolCredential>,
+    strategy: ConflictStrategy,
+    report: &mut CredentialBackupImportReport,
+) -> Result<(), String> {
+    for entry in entries {
+        let uuid = entry.credential.uuid.clone();
+        let incoming_path = get_oauth_creds_path(&entry.credential.credential);
+        let local = {
+            let conn = lock_db(db)?;
+            ProviderPoolDao::get_by_uuid(&conn, &uuid).map_err(|e| e.to_string())?
+        };
+        let decision = resolve(
+            local.as_ref(),
+            entry.credential,
+            strategy,
+            merge_pool_credential,
+        );
+        report.pool.record(&decision);
+        let Some(mut credential) = decision.into_value() else {
+            continue;
+        };
+
+        // 写入的凭证数据来自备份时，token 文件同样使用备份中的内容
+        if let Some(content) = entry.token_file.as_deref() {
+            if incoming_path.is_some()
+                && get_oauth_creds_path(&credential.credential) == incoming_path
+            {
+                match store_token_file(&credential, content) {
+                    Ok(path) => {
+                        set_oauth_creds_path(&mut credential.credential, path);
+                    }
+                    Err(e) => {
+                        report.warnings.push(format!("凭证 {uuid}: {e}"));
+                        continue;
+                    }
+                }
+            }
+        }
+
+        let conn = lock_db(db)?;
+        if let Err(e) = ProviderPoolDao::upsert(&conn, &credential) {
+            report.warnings.push(format!("写入凭证 {uuid} 失败: {e}"));
+        }
+    }
+    Ok(())
+}
+
+/// 插件密钥为单值，合并策略按跳过处理
+fn import_plugin_secrets(
+    db: &DbConnection,
+    crypto: &MasterKeyring,
+    secrets: Vec<BackupPluginSecret>,
+    strategy: ConflictStrategy,
+    report: &mut CredentialBackupImportReport,
+) -> Result<(), String> {
+    let strategy = match strategy {
+        ConflictStrategy::Merge => ConflictStrategy::Skip,
+        other => other,
+    };
+    for secret in secrets {
+        let conn = lock_db(db)?;
+        let local = PluginStorageDao::get(&conn, &secret.plugin_id, &secret.key)
+            .map_err(|e| e.to_string())?;
+        let decision = resolve(local.as_ref(), secret, strategy, |_, incoming| incoming);
+        report.plugin_secrets.record(&decision);
+        let Some(secret) = decision.into_value() else {
+            continue;
+        };
+
+        let result = crypto
+            .encrypt(&plugin_crypto_context(&secret.plugin_id), &secret.plaintext)
+            .map_err(|e| e.to_string())
+            .and_then(|ciphertext| serde_json::to_string(&ciphertext).map_err(|e| e.to_string()))
+            .and_then(|raw| {
+                PluginStorageDao::set(&conn, &secret.plugin_id, &secret.key, &raw)
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            report.warnings.push(format!(
+                "写入插件密钥 {}/{} 失败: {e}",
+                secret.plugin_id, secret.key
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn import_asr(
+    entries: Vec<AsrCredentialEntry>,
+    strategy: ConflictStrategy,
+    report: &mut CredentialBackupImportReport,
+) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    let mut config = load_config().map_err(|e| e.to_string())?;
+    let asr = &mut config.credential_pool.asr;
+    let has_default = asr.iter().any(|entry| entry.is_default);
+    for mut entry in entries {
+        // 不改变本机的默认 ASR 凭证
+        if has_default {
+            entry.is_default = false;
+        }
+        let index = asr.iter().position(|local| local.id == entry.id);
+        let decision = resolve(
+            index.map(|i| &asr[i]),
+            entry,
+            strategy,
+            |local, incoming| merge_asr_entry(local, incoming),
+        );
+        report.asr.record(&decision);
+        match (index, decision) {
+            (Some(i), ImportDecision::Overwrite(mut entry)) => {
+                entry.is_default = asr[i].is_default;
+                asr[i] = entry;
+            }
+            (Some(i), ImportDecision::Merge(entry)) => asr[i] = entry,
+            (None, ImportDecision::Insert(entry)) => asr.push(entry),
+            _ => {}
+        }
+    }
+    save_config(&config).map_err(|e| e.to_string())
+}
+
+/// 导入凭证备份
+#[tauri::command]
+pub async fn import_credential_backup(
+    db: State<'_, DbConnection>,
+    crypto: State<'_, PluginCryptoState>,
+    path: String,
+    passphrase: String,
+    strategy: Option<ConflictStrategy>,
+) -> Result<CredentialBackupImportReport, String> {
+    let strategy = strategy.unwrap_or_default();
+    let backup = open_backup(&read_envelope(&path)?, &passphrase)?;
+
+    let mut report = CredentialBackupImportReport::default();
+    import_pool(&db, backup.pool, strategy, &mut report)?;
+    import_plugin_secrets(&db, &crypto.0, backup.plugin_secrets, strategy, &mut report)?;
+    import_asr(backup.asr, strategy, &mut report)?;
+
+    tracing::info!(
+        "[凭证备份] 导入完成（{:?}）：凭证池 {:?}，插件密钥 {:?}，ASR {:?}，警告 {} 条",
+        strategy,
+        report.pool,
+        report.plugin_secrets,
+        report.asr,
+        report.warnings.len()
+    );
+    Ok(report)
+}
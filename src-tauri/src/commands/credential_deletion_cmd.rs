@@ -0,0 +1,126 @@
+//! 凭证两阶段删除命令
+//!
+//! - `schedule_provider_pool_credential_deletion`: 禁用凭证并登记宽限期
+//! - `cancel_provider_pool_credential_deletion`: 宽限期内撤销删除
+//! - `shred_provider_pool_credential`: 跳过宽限期立即粉碎
+//! - `get_provider_pool_credential_deletions`: 待粉碎凭证与粉碎回执
+//!
+//! 宽限期结束后由 [`spawn_credential_shred_scheduler`] 自动粉碎，流程见
+//! [`lime_services::credential_deletion_service`]。
+
+use crate::commands::provider_pool_cmd::{
+    get_credentials_dir, CredentialSyncServiceState, ProviderPoolServiceState,
+};
+use crate::database::dao::credential_deletion::{
+    CredentialDeletionReceipt, PendingCredentialDeletion,
+};
+use crate::database::DbConnection;
+use crate::models::provider_pool_model::{PoolProviderType, ProviderCredential};
+use lime_services::credential_deletion_service::{
+    cancel_deletion, deletion_overview, schedule_deletion, shred_credential, shred_due_deletions,
+    CredentialDeletionOverview, DEFAULT_DELETION_GRACE_MINUTES,
+};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, State};
+
+/// 粉碎任务的调度间隔（秒）
+const CREDENTIAL_SHRED_TICK_SECS: u64 = 60;
+
+/// 概览中返回的回执数量
+const RECEIPT_LIMIT: usize = 50;
+
+/// 粉碎后同步移除 YAML 配置中的凭证
+fn remove_from_yaml(
+    sync_service: &CredentialSyncServiceState,
+    receipt: &CredentialDeletionReceipt,
+) {
+    let Some(sync) = &sync_service.0 else {
+        return;
+    };
+    if let Ok(pool_type) = receipt.provider_type.parse::<PoolProviderType>() {
+        if let Err(e) = sync.remove_credential(pool_type, &receipt.uuid) {
+            tracing::warn!("从 YAML 删除凭证失败: {}", e);
+        }
+    }
+}
+
+/// 登记删除凭证
+///
+/// # 参数
+/// - `grace_minutes`: 宽限期（分钟），为空时使用默认 24 小时
+#[tauri::command]
+pub fn schedule_provider_pool_credential_deletion(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+    uuid: String,
+    grace_minutes: Option<u64>,
+) -> Result<PendingCredentialDeletion, String> {
+    schedule_deletion(
+        &pool_service.0,
+        &db,
+        &uuid,
+        grace_minutes.unwrap_or(DEFAULT_DELETION_GRACE_MINUTES),
+    )
+}
+
+/// 撤销删除
+#[tauri::command]
+pub fn cancel_provider_pool_credential_deletion(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+    uuid: String,
+) -> Result<ProviderCredential, String> {
+    cancel_deletion(&pool_service.0, &db, &uuid)
+}
+
+/// 立即粉碎凭证
+#[tauri::command]
+pub fn shred_provider_pool_credential(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+    sync_service: State<'_, CredentialSyncServiceState>,
+    uuid: String,
+) -> Result<CredentialDeletionReceipt, String> {
+    let credentials_dir = get_credentials_dir().ok();
+    let receipt = shred_credential(&pool_service.0, &db, &uuid, credentials_dir.as_deref())?;
+    remove_from_yaml(&sync_service, &receipt);
+    Ok(receipt)
+}
+
+/// 获取待粉碎凭证与最近的粉碎回执
+#[tauri::command]
+pub fn get_provider_pool_credential_deletions(
+    db: State<'_, DbConnection>,
+) -> Result<CredentialDeletionOverview, String> {
+    deletion_overview(&db, RECEIPT_LIMIT)
+}
+
+/// 定时粉碎宽限期已结束的凭证
+pub fn spawn_credential_shred_scheduler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(CREDENTIAL_SHRED_TICK_SECS));
+        loop {
+            ticker.tick().await;
+            let (Some(db), Some(pool_service), Some(sync_service)) = (
+                app_handle.try_state::<DbConnection>(),
+                app_handle.try_state::<ProviderPoolServiceState>(),
+                app_handle.try_state::<CredentialSyncServiceState>(),
+            ) else {
+                continue;
+            };
+
+            let credentials_dir = get_credentials_dir().ok();
+            match shred_due_deletions(&pool_service.0, &db, credentials_dir.as_deref()) {
+                Ok((receipts, errors)) => {
+                    for receipt in &receipts {
+                        remove_from_yaml(&sync_service, receipt);
+                    }
+                    for error in errors {
+                        tracing::warn!("[凭证删除] 粉碎失败: {}", error);
+                    }
+                }
+                Err(e) => tracing::warn!("[凭证删除] 读取待粉碎凭证失败: {}", e),
+            }
+        }
+    });
+}
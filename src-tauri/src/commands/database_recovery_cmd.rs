@@ -0,0 +1,11 @@
+//! 数据库恢复命令
+
+use lime_core::database::integrity::DatabaseRecoveryReport;
+
+/// 获取本次启动的数据库完整性检查与恢复报告
+///
+/// 前端据此提示用户数据库曾损坏、采取了何种恢复措施以及可能丢失的数据。
+#[tauri::command]
+pub fn get_database_recovery_report() -> DatabaseRecoveryReport {
+    lime_core::database::integrity::recovery_report()
+}
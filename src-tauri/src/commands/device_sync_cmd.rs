@@ -0,0 +1,515 @@
+//! 设备同步命令
+//!
+//! - `get_device_sync_settings` / `update_device_sync_settings`: 同步存储与类别配置
+//! - `get_device_sync_status`: 本地与远端同步包的时钟关系及本地未推送的修改
+//! - `device_sync_push` / `device_sync_pull`: 推送或拉取加密同步包，`force` 用于冲突时覆盖
+//!
+//! 各类别内容的采集与应用在本模块完成，打包、加密与冲突判定见
+//! [`lime_services::device_sync_service`]。
+
+use crate::app::utils::is_valid_bind_host;
+use crate::commands::api_key_provider_cmd::ApiKeyProviderServiceState;
+use crate::config::{
+    save_config, Config, DeviceSyncSettings, ExportBundle, ExportOptions, ExportService,
+    GlobalConfigManagerState, ImportOptions, ImportService,
+};
+use crate::database::DbConnection;
+use crate::services::environment_service::apply_configured_environment;
+use crate::AppState;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use lime_core::database::dao::api_key_provider::{ApiKeyProvider, ApiKeyProviderDao};
+use lime_core::database::dao::prompt_library::{PromptLibraryEntry, PromptLibraryQuery};
+use lime_services::device_sync_service::{
+    DeviceSyncOutcome, DeviceSyncService, DeviceSyncStatus, SyncBundle, SyncCategory,
+};
+use lime_services::prompt_library_service::PromptLibraryService;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Component, Path};
+use tauri::State;
+
+/// 同步的 Skill 目录总大小上限
+const MAX_SKILLS_BYTES: u64 = 32 * 1024 * 1024;
+
+/// 推送 / 拉取结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceSyncResult {
+    #[serde(flatten)]
+    pub outcome: DeviceSyncOutcome,
+    /// 应用过程中跳过的条目
+    pub warnings: Vec<String>,
+}
+
+/// 凭证类别内容
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CredentialsPayload {
+    #[serde(default)]
+    providers: Vec<SyncedProvider>,
+    /// OAuth token 文件（相对 auth_dir 的路径 → base64 内容）
+    #[serde(default)]
+    token_files: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncedProvider {
+    provider: ApiKeyProvider,
+    #[serde(default)]
+    api_keys: Vec<SyncedApiKey>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncedApiKey {
+    api_key: String,
+    #[serde(default)]
+    alias: Option<String>,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// 采集与应用类别内容所需的上下文
+struct SyncContext<'a> {
+    state: &'a AppState,
+    config_manager: &'a GlobalConfigManagerState,
+    db: &'a DbConnection,
+    api_key_service: &'a ApiKeyProviderServiceState,
+}
+
+fn build_service(settings: &DeviceSyncSettings) -> Result<DeviceSyncService, String> {
+    if !settings.enabled {
+        return Err("设备同步未启用".to_string());
+    }
+    let state_path = lime_core::app_paths::preferred_data_dir()?.join("device_sync_state.json");
+    DeviceSyncService::from_settings(settings, state_path)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<serde_json::Value, String> {
+    serde_json::to_value(value).map_err(|e| format!("同步内容序列化失败: {e}"))
+}
+
+fn from_json<T: for<'de> Deserialize<'de>>(
+    category: SyncCategory,
+    value: serde_json::Value,
+) -> Result<T, String> {
+    serde_json::from_value(value).map_err(|e| format!("同步内容 {category:?} 无效: {e}"))
+}
+
+// ============ 采集 ============
+
+impl SyncContext<'_> {
+    async fn collect(&self, categories: &[SyncCategory]) -> Result<SyncBundle, String> {
+        let config = self.state.read().await.config.clone();
+        let mut bundle = SyncBundle::default();
+        for category in categories {
+            let value = match category {
+                SyncCategory::Settings => to_json(&syncable_config(&config))?,
+                SyncCategory::Credentials => to_json(&self.collect_credentials(&config)?)?,
+                SyncCategory::Skills => to_json(&collect_skills()?)?,
+                SyncCategory::Prompts => {
+                    let mut prompts =
+                        PromptLibraryService::list(self.db, &PromptLibraryQuery::default())?;
+                    prompts.sort_by(|a, b| a.id.cmp(&b.id));
+                    to_json(&prompts)?
+                }
+            };
+            bundle.insert(*category, value);
+        }
+        Ok(bundle)
+    }
+
+    fn collect_credentials(&self, config: &Config) -> Result<CredentialsPayload, String> {
+        let service = &self.api_key_service.0;
+        let mut providers = Vec::new();
+        for entry in service.get_all_providers(self.db)? {
+            let mut api_keys = Vec::new();
+            for key in &entry.api_keys {
+                api_keys.push(SyncedApiKey {
+                    api_key: service.decrypt_api_key(&key.api_key_encrypted)?,
+                    alias: key.alias.clone(),
+                    enabled: key.enabled,
+                });
+            }
+            // 未配置 Key 的系统 Provider 无需同步
+            if api_keys.is_empty() && entry.provider.is_system {
+                continue;
+            }
+            providers.push(SyncedProvider {
+                provider: entry.provider,
+                api_keys,
+            });
+        }
+
+        let token_files = ExportService::export(
+            config,
+            &ExportOptions::credentials_only(),
+            env!("CARGO_PKG_VERSION"),
+        )
+        .map_err(|e| e.to_string())?
+        .token_files
+        .into_iter()
+        .collect();
+
+        Ok(CredentialsPayload {
+            providers,
+            token_files,
+        })
+    }
+}
+
+/// 参与同步的配置（本设备的同步配置不参与同步）
+fn syncable_config(config: &Config) -> Config {
+    let mut config = config.clone();
+    config.device_sync = DeviceSyncSettings::default();
+    config
+}
+
+/// 采集本地 Skill 目录（相对路径 → base64 内容，跳过隐藏文件）
+fn collect_skills() -> Result<BTreeMap<String, String>, String> {
+    let root = lime_core::app_paths::resolve_skills_dir()?;
+    let mut files = BTreeMap::new();
+    let mut total = 0u64;
+    let mut stack = vec![root.clone()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            let content = std::fs::read(&path).map_err(|e| format!("读取 Skill 文件失败: {e}"))?;
+            total += content.len() as u64;
+            if total > MAX_SKILLS_BYTES {
+                return Err(format!(
+                    "Skill 目录超过 {} MB，无法同步",
+                    MAX_SKILLS_BYTES / 1024 / 1024
+                ));
+            }
+            let relative = path
+                .strip_prefix(&root)
+                .map_err(|e| e.to_string())?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.insert(relative, BASE64.encode(content));
+        }
+    }
+    Ok(files)
+}
+
+// ============ 应用 ============
+
+impl SyncContext<'_> {
+    async fn apply(&self, bundle: SyncBundle) -> Result<Vec<String>, String> {
+        let mut warnings = Vec::new();
+        for (category, value) in bundle.categories {
+            match category {
+                SyncCategory::Settings => self.apply_settings(from_json(category, value)?).await?,
+                SyncCategory::Credentials => {
+                    warnings.extend(self.apply_credentials(from_json(category, value)?).await?)
+                }
+                SyncCategory::Skills => warnings.extend(apply_skills(from_json(category, value)?)?),
+                SyncCategory::Prompts => {
+                    warnings.extend(self.apply_prompts(from_json(category, value)?)?)
+                }
+            }
+        }
+        Ok(warnings)
+    }
+
+    /// 替换本地配置（保留本设备的同步配置），与 `save_config` 命令相同的校验与生效流程
+    async fn apply_settings(&self, mut config: Config) -> Result<(), String> {
+        if !is_valid_bind_host(&config.server.host.to_lowercase()) {
+            return Err(format!("同步配置中的监听地址无效: {}", config.server.host));
+        }
+        if config.remote_management.allow_remote {
+            return Err("安全限制：同步配置开启了远程管理，已拒绝应用".to_string());
+        }
+
+        {
+            let mut s = self.state.write().await;
+            config.device_sync = s.config.device_sync.clone();
+            s.config = config.clone();
+        }
+        self.config_manager.0.save_config(&config).await?;
+        apply_configured_environment(&config).await;
+        tracing::info!("[DEVICE_SYNC] 已应用同步的配置");
+        Ok(())
+    }
+
+    /// 合并凭证：缺失的 Provider 新建，已存在的 Key 跳过，不删除本地凭证
+    async fn apply_credentials(&self, payload: CredentialsPayload) -> Result<Vec<String>, String> {
+        let service = &self.api_key_service.0;
+        let mut warnings = Vec::new();
+        for synced in payload.providers {
+            let provider_id = synced.provider.id.clone();
+            {
+                let conn = lime_core::database::lock_db(self.db)?;
+                let exists = ApiKeyProviderDao::get_provider_by_id(&conn, &provider_id)
+                    .map_err(|e| e.to_string())?
+                    .is_some();
+                if !exists {
+                    if let Err(e) = ApiKeyProviderDao::insert_provider(&conn, &synced.provider) {
+                        warnings.push(format!("创建 Provider {provider_id} 失败: {e}"));
+                        continue;
+                    }
+                }
+            }
+
+            let existing: HashSet<String> = service
+                .get_provider(self.db, &provider_id)?
+                .map(|p| p.api_keys)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|k| service.decrypt_api_key(&k.api_key_encrypted).ok())
+                .collect();
+            for key in synced.api_keys {
+                if existing.contains(&key.api_key) {
+                    continue;
+                }
+                match service.add_api_key(self.db, &provider_id, &key.api_key, key.alias) {
+                    Ok(entry) if !key.enabled => {
+                        service.toggle_api_key(self.db, &entry.id, false)?;
+                    }
+                    Ok(_) => {}
+                    Err(e) => warnings.push(format!("添加 {provider_id} 的 API Key 失败: {e}")),
+                }
+            }
+        }
+
+        if !payload.token_files.is_empty() {
+            let current = self.state.read().await.config.clone();
+            let mut bundle = ExportBundle::new(env!("CARGO_PKG_VERSION"));
+            bundle.token_files = payload.token_files.into_iter().collect();
+            let result = ImportService::import(
+                &bundle,
+                &current,
+                &ImportOptions { merge: true },
+                &current.auth_dir,
+            )
+            .map_err(|e| e.to_string())?;
+            warnings.extend(result.warnings);
+        }
+        Ok(warnings)
+    }
+
+    /// 按 ID 合并 Prompt：内容不同则保存为新版本，不删除本地独有的 Prompt
+    fn apply_prompts(&self, prompts: Vec<PromptLibraryEntry>) -> Result<Vec<String>, String> {
+        let mut warnings = Vec::new();
+        for prompt in prompts {
+            let unchanged = PromptLibraryService::get(self.db, &prompt.id)?.is_some_and(|local| {
+                local.name == prompt.name
+                    && local.folder == prompt.folder
+                    && local.tags == prompt.tags
+                    && local.description == prompt.description
+                    && local.content == prompt.content
+                    && local.variables == prompt.variables
+            });
+            if unchanged {
+                continue;
+            }
+            let name = prompt.name.clone();
+            if let Err(e) = PromptLibraryService::save(self.db, prompt, Some("设备同步")) {
+                warnings.push(format!("保存 Prompt {name} 失败: {e}"));
+            }
+        }
+        Ok(warnings)
+    }
+}
+
+/// 写入同步的 Skill 文件，不删除本地独有的文件
+fn apply_skills(files: BTreeMap<String, String>) -> Result<Vec<String>, String> {
+    let root = lime_core::app_paths::resolve_skills_dir()?;
+    let mut warnings = Vec::new();
+    for (relative, encoded) in files {
+        let relative_path = Path::new(&relative);
+        if !relative_path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+        {
+            warnings.push(format!("跳过非法的 Skill 路径: {relative}"));
+            continue;
+        }
+        let Ok(content) = BASE64.decode(&encoded) else {
+            warnings.push(format!("解码 Skill 文件 {relative} 失败"));
+            continue;
+        };
+        let path = root.join(relative_path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("创建 Skill 目录失败: {e}"))?;
+        }
+        if std::fs::read(&path).ok().as_deref() == Some(content.as_slice()) {
+            continue;
+        }
+        if let Err(e) = std::fs::write(&path, content) {
+            warnings.push(format!("写入 Skill 文件 {relative} 失败: {e}"));
+        }
+    }
+    Ok(warnings)
+}
+
+// ============ 命令 ============
+
+/// 获取设备同步配置
+#[tauri::command]
+pub async fn get_device_sync_settings(
+    state: State<'_, AppState>,
+) -> Result<DeviceSyncSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.device_sync.clone())
+}
+
+/// 更新设备同步配置
+#[tauri::command]
+pub async fn update_device_sync_settings(
+    state: State<'_, AppState>,
+    settings: DeviceSyncSettings,
+) -> Result<(), String> {
+    if settings.enabled {
+        lime_services::device_sync_service::storage_from_settings(&settings)?;
+    }
+    let mut s = state.write().await;
+    s.config.device_sync = settings;
+    save_config(&s.config).map_err(|e| e.to_string())
+}
+
+/// 获取同步状态（读取远端包头，无需口令）
+#[tauri::command]
+pub async fn get_device_sync_status(
+    state: State<'_, AppState>,
+    config_manager: State<'_, GlobalConfigManagerState>,
+    db: State<'_, DbConnection>,
+    api_key_service: State<'_, ApiKeyProviderServiceState>,
+) -> Result<DeviceSyncStatus, String> {
+    let settings = state.read().await.config.device_sync.clone();
+    let service = build_service(&settings)?;
+    let context = SyncContext {
+        state: &state,
+        config_manager: &config_manager,
+        db: &db,
+        api_key_service: &api_key_service,
+    };
+    let local = context
+        .collect(&SyncCategory::enabled_in(&settings))
+        .await?;
+    service.status(&local).await
+}
+
+/// 推送本地内容到同步存储
+#[tauri::command]
+pub async fn device_sync_push(
+    state: State<'_, AppState>,
+    config_manager: State<'_, GlobalConfigManagerState>,
+    db: State<'_, DbConnection>,
+    api_key_service: State<'_, ApiKeyProviderServiceState>,
+    passphrase: String,
+    force: Option<bool>,
+) -> Result<DeviceSyncResult, String> {
+    let settings = state.read().await.config.device_sync.clone();
+    let service = build_service(&settings)?;
+    let context = SyncContext {
+        state: &state,
+        config_manager: &config_manager,
+        db: &db,
+        api_key_service: &api_key_service,
+    };
+    let local = context
+        .collect(&SyncCategory::enabled_in(&settings))
+        .await?;
+    let outcome = service
+        .push(&local, &passphrase, force.unwrap_or(false))
+        .await?;
+    tracing::info!("[DEVICE_SYNC] 推送完成: {:?}", outcome.action);
+    Ok(DeviceSyncResult {
+        outcome,
+        warnings: Vec::new(),
+    })
+}
+
+/// 从同步存储拉取并应用内容
+#[tauri::command]
+pub async fn device_sync_pull(
+    state: State<'_, AppState>,
+    config_manager: State<'_, GlobalConfigManagerState>,
+    db: State<'_, DbConnection>,
+    api_key_service: State<'_, ApiKeyProviderServiceState>,
+    passphrase: String,
+    force: Option<bool>,
+) -> Result<DeviceSyncResult, String> {
+    let settings = state.read().await.config.device_sync.clone();
+    let service = build_service(&settings)?;
+    let context = SyncContext {
+        state: &state,
+        config_manager: &config_manager,
+        db: &db,
+        api_key_service: &api_key_service,
+    };
+    let categories = SyncCategory::enabled_in(&settings);
+    let local = context.collect(&categories).await?;
+    let pull = service
+        .pull(&local, &categories, &passphrase, force.unwrap_or(false))
+        .await?;
+
+    let mut warnings = Vec::new();
+    if let Some(bundle) = pull.bundle {
+        warnings = context.apply(bundle).await?;
+        let applied = context.collect(&categories).await?;
+        service.complete_pull(&pull.remote_clock, &applied)?;
+    }
+    tracing::info!(
+        "[DEVICE_SYNC] 拉取完成: {:?}, 警告 {} 条",
+        pull.outcome.action,
+        warnings.len()
+    );
+    Ok(DeviceSyncResult {
+        outcome: pull.outcome,
+        warnings,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syncable_config_excludes_device_sync_section() {
+        let mut config = Config::default();
+        config.device_sync.enabled = true;
+        config.device_sync.folder_path = Some("/tmp/sync".to_string());
+
+        let synced = syncable_config(&config);
+        assert_eq!(synced.device_sync, DeviceSyncSettings::default());
+        assert_eq!(synced.server, config.server);
+    }
+
+    #[test]
+    fn test_credentials_payload_defaults_key_enabled() {
+        let payload: CredentialsPayload = serde_json::from_value(serde_json::json!({
+            "providers": [],
+            "token_files": { "kiro/token.json": "e30=" }
+        }))
+        .unwrap();
+        assert!(payload.providers.is_empty());
+        assert_eq!(payload.token_files.len(), 1);
+
+        let key: SyncedApiKey =
+            serde_json::from_value(serde_json::json!({ "api_key": "sk-1" })).unwrap();
+        assert!(key.enabled);
+        assert!(key.alias.is_none());
+    }
+}
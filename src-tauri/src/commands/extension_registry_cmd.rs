@@ -0,0 +1,62 @@
+//! 扩展注册表命令
+//!
+//! - `get_extension_registry_settings` / `update_extension_registry_settings`: 注册表配置与信任策略
+//! - `check_extension_registries`: 检查各注册表的可达性与签名状态
+//! - `list_registry_plugins`: 列出注册表中的插件（安装见 `install_plugin_from_registry`）
+//!
+//! 注册表中的 Skill 直接合并到 `get_skills_for_app` 的结果中。
+
+use crate::config::{save_config, ExtensionRegistrySettings};
+use crate::AppState;
+use lime_services::extension_registry_service::{
+    validate_registry_settings, ExtensionRegistryService, RegistryListing, RegistryPluginEntry,
+    RegistryStatus,
+};
+use std::sync::Arc;
+use tauri::State;
+
+/// 扩展注册表服务状态
+pub struct ExtensionRegistryState(pub Arc<ExtensionRegistryService>);
+
+/// 获取扩展注册表配置
+#[tauri::command]
+pub async fn get_extension_registry_settings(
+    state: State<'_, AppState>,
+) -> Result<ExtensionRegistrySettings, String> {
+    let s = state.read().await;
+    Ok(s.config.extension_registries.clone())
+}
+
+/// 更新扩展注册表配置
+#[tauri::command]
+pub async fn update_extension_registry_settings(
+    state: State<'_, AppState>,
+    registry: State<'_, ExtensionRegistryState>,
+    settings: ExtensionRegistrySettings,
+) -> Result<(), String> {
+    validate_registry_settings(&settings).map_err(|e| e.to_string())?;
+
+    let mut s = state.write().await;
+    s.config.extension_registries = settings;
+    save_config(&s.config).map_err(|e| e.to_string())?;
+    registry
+        .0
+        .update_settings(s.config.extension_registries.clone());
+    Ok(())
+}
+
+/// 检查已配置注册表的可达性与签名状态
+#[tauri::command]
+pub async fn check_extension_registries(
+    registry: State<'_, ExtensionRegistryState>,
+) -> Result<Vec<RegistryStatus>, String> {
+    Ok(registry.0.check_registries().await)
+}
+
+/// 列出注册表中的插件
+#[tauri::command]
+pub async fn list_registry_plugins(
+    registry: State<'_, ExtensionRegistryState>,
+) -> Result<Vec<RegistryListing<RegistryPluginEntry>>, String> {
+    Ok(registry.0.list_plugins().await)
+}
@@ -0,0 +1,37 @@
+//! 功能开关命令
+//!
+//! 列出实验性功能开关的当前状态，并在运行时切换（写入配置文件）。
+
+use crate::config::save_config;
+use crate::AppState;
+use lime_core::config::{FeatureFlag, FeatureFlagState};
+
+/// 列出所有功能开关
+#[tauri::command]
+pub async fn list_feature_flags(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<FeatureFlagState>, String> {
+    Ok(state.read().await.config.feature_flags.states())
+}
+
+/// 切换功能开关，返回更新后的状态
+#[tauri::command]
+pub async fn set_feature_flag(
+    state: tauri::State<'_, AppState>,
+    key: String,
+    enabled: bool,
+) -> Result<FeatureFlagState, String> {
+    let flag = FeatureFlag::from_key(&key).ok_or_else(|| format!("未知的功能开关: {key}"))?;
+
+    let mut s = state.write().await;
+    s.config.feature_flags.set(flag, enabled);
+    save_config(&s.config).map_err(|e| e.to_string())?;
+    tracing::info!("[FEATURE_FLAG] {} = {}", key, enabled);
+
+    s.config
+        .feature_flags
+        .states()
+        .into_iter()
+        .find(|state| state.key == key)
+        .ok_or_else(|| format!("未知的功能开关: {key}"))
+}
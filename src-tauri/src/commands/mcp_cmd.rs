@@ -21,9 +21,12 @@
 //! - `mcp_list_servers_with_status`: 获取所有服务器及其运行状态
 //! - `mcp_start_server`: 启动指定的 MCP 服务器
 //! - `mcp_stop_server`: 停止指定的 MCP 服务器
+//! - `mcp_tail_server_log`: 读取服务器 stderr 日志的最后若干行
+//! - `mcp_diagnose_server_environment`: 检查服务器执行环境（命令、运行时及版本）
 //!
 //! ## 工具管理命令
 //! - `mcp_list_tools`: 获取所有可用工具
+//! - `mcp_resync_tools`: 全量重新同步工具列表
 //! - `mcp_list_tools_for_context`: 按调用方获取可见工具
 //! - `mcp_search_tools`: 搜索工具
 //! - `mcp_call_tool`: 调用指定工具
@@ -36,11 +39,17 @@
 //! ## 资源管理命令
 //! - `mcp_list_resources`: 获取所有可用资源
 //! - `mcp_read_resource`: 读取资源内容
+//!
+//! ## Elicitation 命令
+//! - `mcp_respond_elicitation`: 回复服务器发起的用户输入请求
+//! - `mcp_list_pending_elicitations`: 获取待回复的请求 ID
 
 use crate::database::DbConnection;
 use crate::mcp::{
-    McpManagerState, McpPromptDefinition, McpPromptResult, McpResourceContent,
-    McpResourceDefinition, McpServerConfig, McpServerInfo, McpToolDefinition, McpToolResult,
+    ExecutionEnvironment, McpElicitationAction, McpElicitationResponse, McpElicitationState,
+    McpEnvironmentDiagnostic, McpManagerState, McpPromptDefinition, McpPromptResult,
+    McpResourceContent, McpResourceDefinition, McpServerConfig, McpServerInfo, McpToolDefinition,
+    McpToolResult,
 };
 use crate::models::mcp_model::McpServer;
 use lime_services::mcp_service::McpService;
@@ -243,6 +252,45 @@ pub async fn mcp_stop_server(
     Ok(())
 }
 
+/// 读取 MCP 服务器 stderr 日志的最后若干行
+///
+/// 日志按服务器写入 `<logs>/mcp/<server>.log` 并自动轮转，
+/// 供 UI 日志查看器展示。`lines` 默认为 200。
+#[tauri::command]
+pub async fn mcp_tail_server_log(
+    mcp_manager: State<'_, McpManagerState>,
+    name: String,
+    lines: Option<usize>,
+) -> Result<Vec<String>, String> {
+    let manager = mcp_manager.lock().await;
+    manager
+        .tail_server_log(&name, lines.unwrap_or(200))
+        .map_err(|e| format!("读取 MCP 日志失败: {e}"))
+}
+
+/// 检查 MCP 服务器执行环境
+///
+/// 按启动时相同的规则解析命令与运行时（PATH 补全、`interpreter` 配置），
+/// 返回缺失运行时、版本过旧等诊断信息，不会启动服务器。
+#[tauri::command]
+pub async fn mcp_diagnose_server_environment(
+    db: State<'_, DbConnection>,
+    name: String,
+) -> Result<McpEnvironmentDiagnostic, String> {
+    let servers = McpService::get_all(&db)?;
+    let server = servers
+        .iter()
+        .find(|s| s.name == name)
+        .ok_or_else(|| format!("服务器配置不存在: {name}"))?;
+
+    let config = parse_server_config(&server.server_config);
+    let diagnostic = ExecutionEnvironment::for_config(&config)
+        .diagnose(&config)
+        .await;
+    debug!(server_name = %name, status = ?diagnostic.status, "MCP 执行环境检查完成");
+    Ok(diagnostic)
+}
+
 // ============================================================================
 // 辅助函数
 // ============================================================================
@@ -259,10 +307,14 @@ pub async fn mcp_stop_server(
 /// # Returns
 ///
 /// 返回解析后的 McpServerConfig，如果解析失败则返回默认值。
-fn parse_server_config(config_value: &serde_json::Value) -> McpServerConfig {
+pub(crate) fn parse_server_config(config_value: &serde_json::Value) -> McpServerConfig {
     serde_json::from_value(config_value.clone()).unwrap_or_else(|e| {
         debug!(error = %e, "解析服务器配置失败，使用默认值");
         McpServerConfig {
+            transport: config_value
+                .get("transport")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
             command: config_value
                 .get("command")
                 .and_then(|v| v.as_str())
@@ -294,6 +346,27 @@ fn parse_server_config(config_value: &serde_json::Value) -> McpServerConfig {
                 .get("timeout")
                 .and_then(|v| v.as_u64())
                 .unwrap_or(30),
+            interpreter: config_value
+                .get("interpreter")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            url: config_value
+                .get("url")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            headers: config_value
+                .get("headers")
+                .and_then(|v| v.as_object())
+                .map(|obj| {
+                    obj.iter()
+                        .filter_map(|(k, v)| v.as_str().map(|s| (k.clone(), s.to_string())))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            reconnect: config_value
+                .get("reconnect")
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+                .unwrap_or_default(),
         }
     })
 }
@@ -341,6 +414,22 @@ pub async fn mcp_list_tools(
     Ok(tools)
 }
 
+/// 全量重新同步工具列表
+///
+/// 忽略缓存重新查询所有服务器，并发送 `full_resync` 的 `mcp:tools_updated` 事件。
+/// 前端发现增量事件版本号不连续时调用。
+#[tauri::command]
+pub async fn mcp_resync_tools(
+    mcp_manager: State<'_, McpManagerState>,
+) -> Result<Vec<McpToolDefinition>, String> {
+    info!("全量重新同步 MCP 工具列表");
+    let manager = mcp_manager.lock().await;
+    manager.resync_tools().await.map_err(|e| {
+        error!(error = %e, "全量同步工具列表失败");
+        e.to_string()
+    })
+}
+
 /// 根据调用方获取可见工具（支持 deferred_loading 过滤）
 #[tauri::command]
 pub async fn mcp_list_tools_for_context(
@@ -592,3 +681,26 @@ pub async fn mcp_read_resource(
     info!(uri = %uri, "资源内容读取完成");
     Ok(result)
 }
+
+/// 回复 MCP 服务器的 elicitation 请求
+///
+/// 前端收到 `mcp:elicitation_request` 事件后展示表单，用户提交、拒绝或关闭时调用。
+/// accept 的内容不符合请求的 schema 时返回错误，请求保持待处理。
+#[tauri::command]
+pub fn mcp_respond_elicitation(
+    elicitation: State<'_, McpElicitationState>,
+    request_id: String,
+    action: McpElicitationAction,
+    content: Option<serde_json::Value>,
+) -> Result<(), String> {
+    info!(request_id = %request_id, action = ?action, "回复 MCP elicitation 请求");
+    elicitation
+        .respond(&request_id, McpElicitationResponse { action, content })
+        .map_err(|e| e.to_string())
+}
+
+/// 获取待回复的 elicitation 请求 ID（前端重新加载后用于清理残留表单）
+#[tauri::command]
+pub fn mcp_list_pending_elicitations(elicitation: State<'_, McpElicitationState>) -> Vec<String> {
+    elicitation.pending_ids()
+}
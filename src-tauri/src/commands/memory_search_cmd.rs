@@ -130,6 +130,7 @@ pub async fn unified_memory_semantic_search(
             None::<&str>,
             None::<&str>,
             None::<&lime_core::models::client_type::ClientType>,
+            None::<&str>,
         )
         .await
     {
@@ -201,6 +202,7 @@ pub async fn unified_memory_hybrid_search(
             None::<&str>,
             None::<&str>,
             None::<&lime_core::models::client_type::ClientType>,
+            None::<&str>,
         )
         .await
     {
@@ -1,14 +1,18 @@
 pub mod a2ui_form_cmd;
+pub mod adaptive_selection_cmd;
 pub mod agent_cmd;
 pub mod api_key_provider_cmd;
 pub mod asr_cmd;
 pub mod aster_agent_cmd;
+pub mod audit_log_cmd;
 pub mod auto_fix_cmd;
 pub mod automation_cmd;
+pub mod background_mode_cmd;
 pub mod browser_environment_cmd;
 pub mod browser_profile_cmd;
 pub mod browser_runtime_cmd;
 pub mod channels_cmd;
+pub mod chat_export_import_cmd;
 pub mod claw_solution_cmd;
 pub mod config_cmd;
 pub mod connect_cmd;
@@ -16,10 +20,18 @@ pub mod connection_cmd;
 pub mod content_cmd;
 pub mod content_workflow_cmd;
 pub mod context_memory;
+pub mod cost_cap_cmd;
+pub mod cost_ledger_cmd;
+pub mod credential_backup_cmd;
+pub mod credential_deletion_cmd;
+pub mod database_recovery_cmd;
+pub mod device_sync_cmd;
 pub mod document_import_cmd;
 pub mod ecommerce_review_reply_cmd;
 pub mod execution_run_cmd;
+pub mod extension_registry_cmd;
 pub mod external_tools_cmd;
+pub mod feature_flag_cmd;
 pub mod file_upload_cmd;
 pub mod gateway_channel_cmd;
 pub mod gateway_tunnel_cmd;
@@ -41,19 +53,27 @@ pub mod music_cmd;
 pub mod network_cmd;
 pub mod novel_cmd;
 pub mod oauth_cmd;
+pub mod oauth_flow_cmd;
 pub mod openclaw_cmd;
 pub mod orchestrator_cmd;
 pub mod persona_cmd;
+pub mod pipeline_snapshot_cmd;
 pub mod plugin_cmd;
 pub mod plugin_install_cmd;
 pub mod plugin_rpc_cmd;
 pub mod poster_material_cmd;
 pub mod prompt_cmd;
+pub mod prompt_library_cmd;
 pub mod provider_pool_cmd;
+pub mod quick_action_cmd;
+pub mod quick_fix_cmd;
+pub mod quota_cmd;
 pub mod resilience_cmd;
 pub mod route_cmd;
+pub mod safe_mode_cmd;
 pub mod screenshot_cmd;
 pub mod security_perf_cmd;
+pub mod self_check_cmd;
 pub mod session_files_cmd;
 pub mod skill_cmd;
 pub mod skill_error;
@@ -63,14 +83,17 @@ pub mod switch_cmd;
 pub mod telegram_remote_cmd;
 pub mod telemetry_cmd;
 pub mod template_cmd;
+pub mod tenant_cmd;
 pub mod terminal_cmd;
 pub mod theme_context_cmd;
 pub mod tray_cmd;
 pub mod unified_memory_cmd;
 pub mod update_cmd;
 pub mod usage_cmd;
+pub mod usage_report_cmd;
 pub mod usage_stats_cmd;
 pub mod video_generation_cmd;
+pub mod virtual_model_cmd;
 pub mod voice_test_cmd;
 pub mod websocket_cmd;
 pub mod webview_cmd;
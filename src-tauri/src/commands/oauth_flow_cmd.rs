@@ -0,0 +1,184 @@
+//! 通用 OAuth 登录命令
+//!
+//! - `oauth_start_flow`: 按 Provider 描述启动授权码（PKCE）或设备码流程，并打开浏览器
+//! - `oauth_poll_flow`: 查询流程状态，完成后写入凭证文件并加入凭证池
+//! - `oauth_cancel_flow`: 取消流程并关闭本地回调服务器
+//!
+//! 流程引擎见 [`lime_credential::OAuthFlowManager`]；内置 Provider 的 client id / secret
+//! 取自对应的 Provider 实现，与 Token 刷新使用的保持一致。
+
+use crate::commands::provider_pool_cmd::{get_credentials_dir, ProviderPoolServiceState};
+use crate::database::DbConnection;
+use crate::models::provider_pool_model::{CredentialData, ProviderCredential};
+use crate::providers::{codex, gemini};
+use lime_credential::{
+    OAuthFlowManager, OAuthFlowStart, OAuthFlowStatus, OAuthProviderDescriptor, OAuthTokenSet,
+};
+use serde::Serialize;
+use std::fs;
+use std::sync::Arc;
+use tauri::State;
+
+/// OAuth 流程管理器状态封装
+pub struct OAuthFlowState(pub Arc<OAuthFlowManager>);
+
+impl Default for OAuthFlowState {
+    fn default() -> Self {
+        Self(Arc::new(OAuthFlowManager::new(builtin_oauth_providers())))
+    }
+}
+
+/// 内置的 OAuth Provider
+fn builtin_oauth_providers() -> Vec<OAuthProviderDescriptor> {
+    vec![
+        OAuthProviderDescriptor::codex(codex::OPENAI_CLIENT_ID),
+        OAuthProviderDescriptor::gemini(
+            gemini::get_oauth_client_id(),
+            gemini::get_oauth_client_secret(),
+        ),
+    ]
+}
+
+/// 流程查询结果
+#[derive(Debug, Clone, Serialize)]
+pub struct OAuthFlowPollResponse {
+    /// pending / slow_down / completed / failed / expired
+    pub status: String,
+    /// 登录成功后加入凭证池的凭证
+    pub credential: Option<ProviderCredential>,
+    pub error: Option<String>,
+}
+
+impl OAuthFlowPollResponse {
+    fn status(status: &str, error: Option<String>) -> Self {
+        Self {
+            status: status.to_string(),
+            credential: None,
+            error,
+        }
+    }
+}
+
+/// 启动 OAuth 登录流程
+///
+/// # 参数
+/// - `provider`: Provider 描述的 id（如 `codex`、`gemini`）
+#[tauri::command]
+pub async fn oauth_start_flow(
+    flows: State<'_, OAuthFlowState>,
+    provider: String,
+) -> Result<OAuthFlowStart, String> {
+    let start = flows.0.start(&provider).await?;
+    if let Some(url) = start.auth_url.as_ref().or(start.verification_uri.as_ref()) {
+        if let Err(e) = open::that(url) {
+            tracing::warn!("[OAuth] 无法打开浏览器: {}. 请手动打开 URL.", e);
+        }
+    }
+    Ok(start)
+}
+
+/// 查询 OAuth 登录流程状态
+///
+/// 完成时写入凭证文件并加入凭证池，流程随之结束。
+#[tauri::command]
+pub async fn oauth_poll_flow(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+    flows: State<'_, OAuthFlowState>,
+    flow_id: String,
+    name: Option<String>,
+) -> Result<OAuthFlowPollResponse, String> {
+    let poll = flows.0.poll(&flow_id).await?;
+    match poll.status {
+        OAuthFlowStatus::Pending => Ok(OAuthFlowPollResponse::status("pending", None)),
+        OAuthFlowStatus::SlowDown => Ok(OAuthFlowPollResponse::status("slow_down", None)),
+        OAuthFlowStatus::Expired => Ok(OAuthFlowPollResponse::status(
+            "expired",
+            Some("授权已过期，请重新开始".to_string()),
+        )),
+        OAuthFlowStatus::Failed(error) => Ok(OAuthFlowPollResponse::status("failed", Some(error))),
+        OAuthFlowStatus::Completed(tokens) => {
+            let credential = save_credential(&db, &pool_service, &poll.provider, &tokens, name)?;
+            tracing::info!(
+                "[OAuth] {} 登录成功，凭证已添加到凭证池: {}",
+                poll.provider.id,
+                credential.uuid
+            );
+            Ok(OAuthFlowPollResponse {
+                status: "completed".to_string(),
+                credential: Some(credential),
+                error: None,
+            })
+        }
+    }
+}
+
+/// 取消 OAuth 登录流程
+#[tauri::command]
+pub fn oauth_cancel_flow(flows: State<'_, OAuthFlowState>, flow_id: String) -> bool {
+    flows.0.cancel(&flow_id)
+}
+
+/// 将 Token 写入凭证目录并加入凭证池
+///
+/// 凭证文件为 snake_case 字段，适用于 Gemini / Antigravity / Codex / Claude OAuth；
+/// Kiro 凭证文件格式不同，仍使用专用登录命令。
+fn save_credential(
+    db: &DbConnection,
+    pool_service: &ProviderPoolServiceState,
+    provider: &OAuthProviderDescriptor,
+    tokens: &OAuthTokenSet,
+    name: Option<String>,
+) -> Result<ProviderCredential, String> {
+    let provider_type = provider.pool_provider_type.as_str();
+    let uuid = uuid::Uuid::new_v4().to_string();
+    let filename = format!(
+        "{provider_type}_{}_{}_{provider_type}.json",
+        &uuid[..8],
+        chrono::Utc::now().timestamp()
+    );
+    let path = get_credentials_dir()?.join(filename);
+    let mut token_file = tokens.to_token_file(provider_type);
+    if provider_type == "codex" {
+        // Codex 请求需要 Chatgpt-Account-Id，与专用登录流程一样从 id_token 中解析
+        let (account_id, email) = tokens
+            .id_token
+            .as_deref()
+            .map(codex::parse_jwt_claims)
+            .unwrap_or_default();
+        token_file["account_id"] = serde_json::json!(account_id);
+        token_file["email"] = serde_json::json!(email);
+    }
+    let content =
+        serde_json::to_string_pretty(&token_file).map_err(|e| format!("序列化凭证失败: {e}"))?;
+    fs::write(&path, content).map_err(|e| format!("写入凭证文件失败: {e}"))?;
+    let creds_file_path = path.to_string_lossy().to_string();
+
+    let credential = match provider_type {
+        "gemini" => CredentialData::GeminiOAuth {
+            creds_file_path,
+            project_id: None,
+        },
+        "antigravity" => CredentialData::AntigravityOAuth {
+            creds_file_path,
+            project_id: None,
+        },
+        "codex" => CredentialData::CodexOAuth {
+            creds_file_path,
+            api_base_url: None,
+        },
+        "claude_oauth" => CredentialData::ClaudeOAuth { creds_file_path },
+        other => {
+            let _ = fs::remove_file(&path);
+            return Err(format!("凭证池类型 {other} 不支持 OAuth 凭证文件"));
+        }
+    };
+
+    pool_service
+        .0
+        .add_credential(db, provider_type, credential, name, Some(true), None)
+        .map_err(|e| {
+            let _ = fs::remove_file(&path);
+            e
+        })
+}
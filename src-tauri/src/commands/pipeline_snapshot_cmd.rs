@@ -0,0 +1,88 @@
+//! 管线快照命令
+//!
+//! 将当前生效配置、凭证池构成（不含密钥）与插件版本保存为命名快照，
+//! 支持列出、查看、删除与比较两个快照。
+
+use crate::commands::plugin_cmd::PluginManagerState;
+use crate::database::dao::provider_pool::ProviderPoolDao;
+use crate::database::DbConnection;
+use crate::AppState;
+use lime_core::config::{
+    PipelineSnapshot, PipelineSnapshotDiff, PipelineSnapshotStore, PipelineSnapshotSummary,
+};
+use tauri::State;
+
+fn snapshot_store() -> PipelineSnapshotStore {
+    PipelineSnapshotStore::new(PipelineSnapshotStore::default_dir())
+}
+
+/// 采集当前生效配置并保存为命名快照
+#[tauri::command]
+pub async fn create_pipeline_snapshot(
+    state: State<'_, AppState>,
+    db: State<'_, DbConnection>,
+    plugin_manager: State<'_, PluginManagerState>,
+    name: String,
+    note: Option<String>,
+    overwrite: Option<bool>,
+) -> Result<PipelineSnapshotSummary, String> {
+    let config = state.read().await.config.clone();
+    let credentials = {
+        let conn = db.lock().map_err(|e| format!("获取数据库连接失败: {e}"))?;
+        ProviderPoolDao::get_all(&conn).map_err(|e| format!("获取凭证列表失败: {e}"))?
+    };
+    let plugins = plugin_manager
+        .0
+        .read()
+        .await
+        .list()
+        .await
+        .into_iter()
+        .map(|plugin| (plugin.name, plugin.version));
+
+    let snapshot = PipelineSnapshot::capture(
+        name.trim(),
+        note,
+        env!("CARGO_PKG_VERSION"),
+        &config,
+        &credentials,
+        plugins,
+    )?;
+    let path = snapshot_store().save(&snapshot, overwrite.unwrap_or(false))?;
+    tracing::info!(
+        "[SNAPSHOT] 已保存管线快照 {}: {}",
+        snapshot.name,
+        path.display()
+    );
+    Ok(PipelineSnapshotSummary::from(&snapshot))
+}
+
+/// 列出所有管线快照
+#[tauri::command]
+pub fn list_pipeline_snapshots() -> Result<Vec<PipelineSnapshotSummary>, String> {
+    snapshot_store().list()
+}
+
+/// 获取快照完整内容
+#[tauri::command]
+pub fn get_pipeline_snapshot(name: String) -> Result<PipelineSnapshot, String> {
+    snapshot_store().load(&name)
+}
+
+/// 删除管线快照
+#[tauri::command]
+pub fn delete_pipeline_snapshot(name: String) -> Result<(), String> {
+    snapshot_store().delete(&name)
+}
+
+/// 比较两个快照，返回 `base` 到 `target` 的变更
+#[tauri::command]
+pub fn diff_pipeline_snapshots(
+    base: String,
+    target: String,
+) -> Result<PipelineSnapshotDiff, String> {
+    let store = snapshot_store();
+    let base = store.load(&base)?;
+    let target = store.load(&target)?;
+    Ok(PipelineSnapshotDiff::between(&base, &target))
+}
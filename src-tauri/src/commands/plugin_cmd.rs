@@ -3,18 +3,24 @@
 //! 提供插件管理和 UI 相关的 Tauri 命令：
 //! - get_plugin_status: 获取插件服务状态
 //! - get_plugins: 获取所有插件列表
+//! - reload_plugin: 热重载单个插件
 //! - get_plugins_with_ui: 获取带有 UI 配置的已安装插件列表
 //! - get_plugin_ui: 获取插件 UI 定义
 //! - handle_plugin_action: 处理插件 UI 操作
+//! - plugin_storage_*: 插件键值存储
+//! - plugin_crypto_*: 插件密钥加解密与主密钥轮换
 //!
 //! _需求: 3.1, 3.2, 3.3_
 
 #![allow(dead_code)]
 
+use crate::database::dao::plugin_storage::PluginStorageDao;
+use crate::database::{lock_db, DbConnection};
 use lime_core::plugin::{
-    PluginConfig, PluginInfo, PluginManager, PluginManifest, PluginQueueStats, PluginTaskRecord,
-    PluginTaskState, PluginType,
+    PluginConfig, PluginInfo, PluginManager, PluginManifest, PluginQueueStats, PluginStorage,
+    PluginStorageUsage, PluginTaskRecord, PluginTaskState, PluginType,
 };
+use lime_credential::MasterKeyring;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 use std::sync::Arc;
@@ -31,6 +37,67 @@ pub fn frontend_debug_log(message: String) {
 /// 插件管理器状态
 pub struct PluginManagerState(pub Arc<RwLock<PluginManager>>);
 
+/// 插件加密状态（主密钥保存在系统钥匙串，钥匙串从未可用时回退到应用数据目录下的密钥文件）
+pub struct PluginCryptoState(pub Arc<MasterKeyring>);
+
+impl PluginCryptoState {
+    /// 钥匙串中的主密钥账户
+    const KEYCHAIN_ACCOUNT: &'static str = "plugin-master-key";
+
+    pub fn new() -> Self {
+        let keyring = match lime_core::app_paths::preferred_data_dir() {
+            Ok(dir) => {
+                MasterKeyring::keychain_with_file_fallback(Self::KEYCHAIN_ACCOUNT, dir.join("keys"))
+            }
+            Err(e) => {
+                tracing::warn!("[插件加密] 无法定位密钥文件目录，仅使用系统钥匙串: {}", e);
+                MasterKeyring::keychain(Self::KEYCHAIN_ACCOUNT)
+            }
+        };
+        Self(Arc::new(keyring))
+    }
+}
+
+impl Default for PluginCryptoState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 插件的加密上下文，不同插件派生不同子密钥
+pub fn plugin_crypto_context(plugin_id: &str) -> String {
+    format!("plugin:{plugin_id}")
+}
+
+/// 确认插件已安装，防止以任意 `plugin_id` 访问其他插件或不存在的命名空间
+async fn ensure_plugin_installed(
+    installer_state: &PluginInstallerState,
+    plugin_id: &str,
+) -> Result<(), String> {
+    let installed = installer_state
+        .0
+        .read()
+        .await
+        .is_installed(plugin_id)
+        .map_err(|e| e.to_string())?;
+    if installed {
+        Ok(())
+    } else {
+        Err(format!("插件 {plugin_id} 未安装"))
+    }
+}
+
+/// 主密钥轮换结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginKeyRotationResult {
+    /// 新的密钥版本
+    pub version: u32,
+    /// 重新加密的存储项数
+    pub rewrapped: usize,
+    /// 重新加密失败的存储项（plugin_id/key）
+    pub failed: Vec<String>,
+}
+
 /// 插件状态响应
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginServiceStatus {
@@ -137,6 +204,19 @@ pub async fn reload_plugins(
     manager.load_all().await.map_err(|e| e.to_string())
 }
 
+/// 重新加载单个插件（关闭后端进程并重新加载，发送 `plugin:reloaded` 事件）
+#[tauri::command]
+pub async fn reload_plugin(
+    app_handle: tauri::AppHandle,
+    plugin_id: String,
+) -> Result<crate::plugin::hot_reload::PluginReloadedPayload, String> {
+    let payload = crate::plugin::hot_reload::reload_plugin_by_id(&app_handle, &plugin_id).await;
+    match &payload.error {
+        Some(error) => Err(error.clone()),
+        None => Ok(payload),
+    }
+}
+
 /// 卸载插件
 #[tauri::command]
 pub async fn unload_plugin(
@@ -211,6 +291,177 @@ pub async fn get_plugin_queue_stats(
     Ok(manager.get_queue_stats(plugin_id.as_deref()))
 }
 
+// ============================================================================
+// 插件键值存储
+// ============================================================================
+
+/// 读取插件存储值
+#[tauri::command]
+pub async fn plugin_storage_get(
+    db: tauri::State<'_, DbConnection>,
+    installer_state: tauri::State<'_, PluginInstallerState>,
+    plugin_id: String,
+    key: String,
+) -> Result<Option<serde_json::Value>, String> {
+    ensure_plugin_installed(&installer_state, &plugin_id).await?;
+    PluginStorage::new(db.inner().clone(), plugin_id)
+        .get(&key)
+        .map_err(|e| e.to_string())
+}
+
+/// 写入插件存储值
+#[tauri::command]
+pub async fn plugin_storage_set(
+    db: tauri::State<'_, DbConnection>,
+    installer_state: tauri::State<'_, PluginInstallerState>,
+    plugin_id: String,
+    key: String,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    ensure_plugin_installed(&installer_state, &plugin_id).await?;
+    PluginStorage::new(db.inner().clone(), plugin_id)
+        .set(&key, &value)
+        .map_err(|e| e.to_string())
+}
+
+/// 删除插件存储值
+#[tauri::command]
+pub async fn plugin_storage_delete(
+    db: tauri::State<'_, DbConnection>,
+    installer_state: tauri::State<'_, PluginInstallerState>,
+    plugin_id: String,
+    key: String,
+) -> Result<bool, String> {
+    ensure_plugin_installed(&installer_state, &plugin_id).await?;
+    PluginStorage::new(db.inner().clone(), plugin_id)
+        .delete(&key)
+        .map_err(|e| e.to_string())
+}
+
+/// 列出插件存储键
+#[tauri::command]
+pub async fn plugin_storage_keys(
+    db: tauri::State<'_, DbConnection>,
+    installer_state: tauri::State<'_, PluginInstallerState>,
+    plugin_id: String,
+) -> Result<Vec<String>, String> {
+    ensure_plugin_installed(&installer_state, &plugin_id).await?;
+    PluginStorage::new(db.inner().clone(), plugin_id)
+        .keys()
+        .map_err(|e| e.to_string())
+}
+
+/// 获取插件存储用量
+#[tauri::command]
+pub async fn plugin_storage_usage(
+    db: tauri::State<'_, DbConnection>,
+    installer_state: tauri::State<'_, PluginInstallerState>,
+    plugin_id: String,
+) -> Result<PluginStorageUsage, String> {
+    ensure_plugin_installed(&installer_state, &plugin_id).await?;
+    PluginStorage::new(db.inner().clone(), plugin_id)
+        .usage()
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// 插件加密
+// ============================================================================
+
+/// 使用插件子密钥加密
+#[tauri::command]
+pub async fn plugin_crypto_encrypt(
+    crypto: tauri::State<'_, PluginCryptoState>,
+    installer_state: tauri::State<'_, PluginInstallerState>,
+    plugin_id: String,
+    plaintext: String,
+) -> Result<String, String> {
+    ensure_plugin_installed(&installer_state, &plugin_id).await?;
+    crypto
+        .0
+        .encrypt(&plugin_crypto_context(&plugin_id), &plaintext)
+        .map_err(|e| e.to_string())
+}
+
+/// 使用插件子密钥解密
+#[tauri::command]
+pub async fn plugin_crypto_decrypt(
+    crypto: tauri::State<'_, PluginCryptoState>,
+    installer_state: tauri::State<'_, PluginInstallerState>,
+    plugin_id: String,
+    ciphertext: String,
+) -> Result<String, String> {
+    ensure_plugin_installed(&installer_state, &plugin_id).await?;
+    crypto
+        .0
+        .decrypt(&plugin_crypto_context(&plugin_id), &ciphertext)
+        .map_err(|e| e.to_string())
+}
+
+/// 轮换插件主密钥，并将插件存储中的密文重新加密为新版本
+#[tauri::command]
+pub async fn plugin_crypto_rotate_key(
+    db: tauri::State<'_, DbConnection>,
+    crypto: tauri::State<'_, PluginCryptoState>,
+) -> Result<PluginKeyRotationResult, String> {
+    let version = crypto.0.rotate().map_err(|e| e.to_string())?;
+    let (rewrapped, failed) = rewrap_plugin_storage(db.inner(), &crypto.0)?;
+    tracing::info!(
+        "[插件加密] 主密钥已轮换到 v{}，重新加密 {} 项，失败 {} 项",
+        version,
+        rewrapped,
+        failed.len()
+    );
+    Ok(PluginKeyRotationResult {
+        version,
+        rewrapped,
+        failed,
+    })
+}
+
+/// 重新加密插件存储中的旧版本密文
+///
+/// 只处理值本身为密文字符串的存储项；嵌套在对象中的密文由插件自行迁移。
+/// 失败项保留原值（旧密钥仍可解密）。
+fn rewrap_plugin_storage(
+    db: &DbConnection,
+    crypto: &MasterKeyring,
+) -> Result<(usize, Vec<String>), String> {
+    let conn = lock_db(db)?;
+    let entries = PluginStorageDao::list_string_values_with_prefix(&conn, MasterKeyring::prefix())
+        .map_err(|e| format!("读取插件存储失败: {e}"))?;
+
+    let mut rewrapped = 0;
+    let mut failed = Vec::new();
+    for (plugin_id, key, raw) in entries {
+        let result = serde_json::from_str::<String>(&raw)
+            .map_err(|e| e.to_string())
+            .and_then(|ciphertext| {
+                crypto
+                    .rewrap(&plugin_crypto_context(&plugin_id), &ciphertext)
+                    .map_err(|e| e.to_string())
+            })
+            .and_then(|rewrapped| match rewrapped {
+                Some(ciphertext) => {
+                    let raw = serde_json::to_string(&ciphertext).map_err(|e| e.to_string())?;
+                    PluginStorageDao::set(&conn, &plugin_id, &key, &raw)
+                        .map(|_| true)
+                        .map_err(|e| e.to_string())
+                }
+                None => Ok(false),
+            });
+        match result {
+            Ok(true) => rewrapped += 1,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!("[插件加密] 重新加密 {}/{} 失败: {}", plugin_id, key, e);
+                failed.push(format!("{plugin_id}/{key}"));
+            }
+        }
+    }
+    Ok((rewrapped, failed))
+}
+
 // ============================================================================
 // 插件 UI 注册系统
 // ============================================================================
@@ -416,17 +667,28 @@ pub async fn get_plugins_with_ui(
 // 插件 UI 相关命令
 // ============================================================================
 
-use lime_core::plugin::{UIMessage, UserAction};
+use crate::plugin::PluginUIEmitterState;
+use lime_core::plugin::{PluginEventAudit, UIMessage, UserAction};
 
 /// 获取插件 UI 定义
 /// 返回插件的初始 UI 消息列表
 #[tauri::command]
 pub async fn get_plugin_ui(
     state: tauri::State<'_, PluginManagerState>,
+    emitter_state: tauri::State<'_, PluginUIEmitterState>,
     plugin_id: String,
 ) -> Result<Vec<UIMessage>, String> {
     let manager = state.0.read().await;
 
+    // 按清单声明注册插件 UI 可接收的事件主题
+    if let Some(emitter) = emitter_state.get() {
+        let topics = read_plugin_manifest(&manager.plugins_dir().join(&plugin_id))
+            .and_then(|manifest| manifest.ui)
+            .map(|ui| ui.events)
+            .unwrap_or_default();
+        emitter.scope().register(&plugin_id, topics);
+    }
+
     // 获取插件的 Surface 定义
     let surfaces = manager
         .get_plugin_surfaces(&plugin_id)
@@ -439,6 +701,23 @@ pub async fn get_plugin_ui(
     Ok(messages)
 }
 
+/// 获取插件 UI 事件拒绝审计
+///
+/// 返回被权限范围拦截的事件投递记录，可按插件过滤
+#[tauri::command]
+pub async fn get_plugin_event_audit(
+    emitter_state: tauri::State<'_, PluginUIEmitterState>,
+    plugin_id: Option<String>,
+    limit: Option<usize>,
+) -> Result<PluginEventAudit, String> {
+    let emitter = emitter_state
+        .get()
+        .ok_or_else(|| "插件 UI 事件发射器未初始化".to_string())?;
+    Ok(emitter
+        .scope()
+        .audit(plugin_id.as_deref(), limit.unwrap_or(50)))
+}
+
 /// 处理插件 UI 操作
 /// 将用户操作转发给插件并返回响应消息
 #[tauri::command]
@@ -3,14 +3,20 @@
 //! 提供插件安装、卸载和管理的 Tauri 命令：
 //! - install_plugin_from_file: 从本地文件安装插件
 //! - install_plugin_from_url: 从 URL 安装插件
+//! - install_plugin_from_registry: 从扩展注册表安装插件
+//! - install_plugin_from_github: 从 GitHub 最新 release 安装插件
+//! - check_plugin_update / update_plugin: 检查并更新 GitHub 来源的插件
 //! - uninstall_plugin: 卸载插件
 //! - list_installed_plugins: 列出已安装插件
 //!
 //! _需求: 1.1, 2.1, 2.2, 2.4, 3.1, 3.2, 3.3, 4.2, 6.1_
 
+use crate::commands::extension_registry_cmd::ExtensionRegistryState;
+use crate::database::DbConnection;
 use lime_core::plugin::installer::{
-    InstallProgress, InstalledPlugin, PluginInstaller, ProgressCallback,
+    InstallProgress, InstalledPlugin, PluginInstaller, PluginUpdateInfo, ProgressCallback,
 };
+use lime_core::plugin::{PluginDatabase, PluginFileSandbox, PluginStorage};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -138,6 +144,151 @@ pub async fn install_plugin_from_url<R: Runtime>(
     }
 }
 
+/// 从扩展注册表安装插件
+///
+/// 插件包地址与 SHA256 取自注册表索引（已按信任策略校验签名）。
+#[tauri::command]
+pub async fn install_plugin_from_registry<R: Runtime>(
+    app_handle: AppHandle<R>,
+    state: tauri::State<'_, PluginInstallerState>,
+    registry: tauri::State<'_, ExtensionRegistryState>,
+    registry_id: String,
+    plugin_id: String,
+) -> Result<InstallResult, String> {
+    let entry = match registry.0.find_plugin(&registry_id, &plugin_id).await {
+        Ok(entry) => entry,
+        Err(e) => {
+            return Ok(InstallResult {
+                success: false,
+                plugin: None,
+                error: Some(format!("{e:#}")),
+            })
+        }
+    };
+
+    let installer = state.0.read().await;
+    let progress_callback = TauriProgressCallback::new(app_handle);
+
+    match installer
+        .install_from_url_verified(&entry.download_url, Some(&entry.sha256), &progress_callback)
+        .await
+    {
+        Ok(plugin) => Ok(InstallResult {
+            success: true,
+            plugin: Some(plugin),
+            error: None,
+        }),
+        Err(e) => {
+            progress_callback.on_progress(InstallProgress::failed(e.to_string()));
+            Ok(InstallResult {
+                success: false,
+                plugin: None,
+                error: Some(e.to_string()),
+            })
+        }
+    }
+}
+
+/// 从 GitHub 仓库的最新 release 安装插件
+///
+/// `repository` 支持 `owner/repo` 或 GitHub 仓库 / release 地址；
+/// 插件包必须能用 release 中的校验文件校验。
+#[tauri::command]
+pub async fn install_plugin_from_github<R: Runtime>(
+    app_handle: AppHandle<R>,
+    state: tauri::State<'_, PluginInstallerState>,
+    repository: String,
+    checksum_file: Option<String>,
+) -> Result<InstallResult, String> {
+    let installer = state.0.read().await;
+    let repository = repository.trim().trim_end_matches('/');
+    let repository = match repository.strip_prefix("https://github.com/") {
+        Some(path) if !path.contains("/releases/") => path,
+        _ => repository,
+    };
+    let release = match installer.downloader().parse_github_url(repository) {
+        Ok(release) => release,
+        Err(e) => {
+            return Ok(InstallResult {
+                success: false,
+                plugin: None,
+                error: Some(e.to_string()),
+            })
+        }
+    };
+
+    let progress_callback = TauriProgressCallback::new(app_handle);
+
+    match installer
+        .install_from_github(
+            &release.owner,
+            &release.repo,
+            checksum_file.as_deref(),
+            &progress_callback,
+        )
+        .await
+    {
+        Ok(plugin) => Ok(InstallResult {
+            success: true,
+            plugin: Some(plugin),
+            error: None,
+        }),
+        Err(e) => {
+            progress_callback.on_progress(InstallProgress::failed(e.to_string()));
+            Ok(InstallResult {
+                success: false,
+                plugin: None,
+                error: Some(e.to_string()),
+            })
+        }
+    }
+}
+
+/// 检查 GitHub 来源的插件是否有新版本
+#[tauri::command]
+pub async fn check_plugin_update(
+    state: tauri::State<'_, PluginInstallerState>,
+    plugin_id: String,
+) -> Result<PluginUpdateInfo, String> {
+    let installer = state.0.read().await;
+    installer
+        .check_update(&plugin_id)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 更新 GitHub 来源的插件到最新 release
+///
+/// 已是最新版本时 `success` 为 true 且 `plugin` 为空。
+#[tauri::command]
+pub async fn update_plugin<R: Runtime>(
+    app_handle: AppHandle<R>,
+    state: tauri::State<'_, PluginInstallerState>,
+    plugin_id: String,
+) -> Result<InstallResult, String> {
+    let installer = state.0.read().await;
+    let progress_callback = TauriProgressCallback::new(app_handle);
+
+    match installer
+        .update_from_github(&plugin_id, &progress_callback)
+        .await
+    {
+        Ok(plugin) => Ok(InstallResult {
+            success: true,
+            plugin,
+            error: None,
+        }),
+        Err(e) => {
+            progress_callback.on_progress(InstallProgress::failed(e.to_string()));
+            Ok(InstallResult {
+                success: false,
+                plugin: None,
+                error: Some(e.to_string()),
+            })
+        }
+    }
+}
+
 /// 卸载插件
 ///
 /// 流程: 删除文件 → 注销注册表
@@ -145,12 +296,30 @@ pub async fn install_plugin_from_url<R: Runtime>(
 #[tauri::command]
 pub async fn uninstall_plugin(
     state: tauri::State<'_, PluginInstallerState>,
+    db: tauri::State<'_, DbConnection>,
     plugin_id: String,
 ) -> Result<bool, String> {
     let installer = state.0.read().await;
 
     match installer.uninstall(&plugin_id).await {
-        Ok(()) => Ok(true),
+        Ok(()) => {
+            // 清理插件键值存储
+            if let Err(e) = PluginStorage::new(db.inner().clone(), plugin_id.as_str()).clear() {
+                tracing::warn!("清理插件 {} 存储失败: {}", plugin_id, e);
+            }
+            // 删除插件数据表
+            if let Err(e) = PluginDatabase::drop_tables(db.inner(), &plugin_id) {
+                tracing::warn!("清理插件 {} 数据表失败: {}", plugin_id, e);
+            }
+            // 清理插件数据目录
+            if let Ok(data_root) = lime_core::app_paths::resolve_plugin_data_dir() {
+                let sandbox = PluginFileSandbox::new(&data_root, plugin_id.as_str(), Vec::new());
+                if let Err(e) = sandbox.remove_all() {
+                    tracing::warn!("清理插件 {} 数据目录失败: {}", plugin_id, e);
+                }
+            }
+            Ok(true)
+        }
         Err(e) => Err(e.to_string()),
     }
 }
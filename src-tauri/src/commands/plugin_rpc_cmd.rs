@@ -2,17 +2,64 @@
 //!
 //! 提供插件与其 Binary 后端进程的 JSON-RPC 通信功能：
 //! - plugin_rpc_connect: 启动插件进程并建立连接
-//! - plugin_rpc_disconnect: 关闭插件进程
+//! - plugin_rpc_disconnect: 关闭插件进程（先发送 `shutdown` 通知，超时后强制终止）
 //! - plugin_rpc_call: 发送 RPC 请求并等待响应
 //!
 //! 支持异步通知：后端进程可以发送 JSON-RPC 通知，通过 Tauri 事件转发到前端。
 //!
+//! `plugin_type` 为 `wasm` 的插件（需启用 `wasm-plugins` 编译特性并开启 `wasm_plugins`
+//! 功能开关）不启动进程，而是在进程内加载 `entry` 指向的模块，调用与宿主请求走同一套方法，
+//! 见 `lime_core::plugin::wasm`。manifest 中的 `entry` 与平台二进制路径必须位于插件安装目录内。
+//!
+//! 后端进程也可以向宿主发起 JSON-RPC 请求，目前支持 `storage.*`（插件键值存储，
+//! 按插件 ID 隔离并受配额限制）、`crypto.*`（使用插件子密钥加解密）与 `fs.*`
+//! （插件数据目录内的文件读写，需要在 plugin.json 中声明 `file_system_read` /
+//! `file_system_write` 权限）：
+//! - storage.get `{key}` → 值或 null
+//! - storage.set `{key, value}` → null
+//! - storage.delete `{key}` → 是否存在
+//! - storage.keys → 键列表
+//! - storage.keys_page `{cursor?, limit?}` → `{items, next_cursor}` 分页键列表
+//! - storage.usage → 用量与配额
+//! - crypto.encrypt `{plaintext}` → `enc3:v<版本>:...` 密文
+//! - crypto.decrypt `{ciphertext}` → 明文
+//! - fs.read `{path, encoding?}` → 文件内容（`encoding` 为 `utf8`（默认）或 `base64`）
+//! - fs.write `{path, content, encoding?, append?}` → null
+//! - fs.list `{path?}` → `[{name, is_dir, size}]`
+//! - fs.usage → 数据目录用量与配额
+//! - event.emit `{topic, payload?}` → 投递到的订阅数
+//! - event.subscribe `{topic}` → `{subscription_id}`（需要 `event_subscribe` 权限）
+//! - event.unsubscribe `{subscription_id}` → 订阅是否存在
+//! - db.execute `{op: insert|update|delete, table, values?, where?}` → `{rows_affected, last_insert_id?}`
+//! - db.batch `{operations: [...]}` → 每个写操作的结果（同一事务，任一失败全部回滚）
+//! - db.query `{table, columns?, where?, order_by?, descending?, limit?, offset?}` → 行列表
+//!
+//! `db.*` 只能访问 plugin.json `database.tables` 中声明的表，连接时自动建表。
+//!
+//! 订阅的事件以 `event` 通知（`{subscription_id, source, topic, payload, timestamp}`）
+//! 写回进程 stdin；WASM 插件只能发布事件，不能订阅。
+//!
 //! _需求: 插件 RPC 通信_
 
+use crate::commands::plugin_cmd::{plugin_crypto_context, PluginCryptoState};
 use crate::commands::plugin_install_cmd::PluginInstallerState;
+use crate::database::DbConnection;
+use crate::AppState;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use lime_core::config::FeatureFlag;
+use lime_core::database::pagination::PageRequest;
+use lime_core::plugin::{
+    PluginBusEvent, PluginDatabase, PluginDatabaseSchema, PluginDbError, PluginDbQuery,
+    PluginDbWrite, PluginEventBus, PluginEventClient, PluginEventError, PluginEventSink,
+    PluginFileSandbox, PluginFsError, PluginPermission, PluginStorage, PluginStorageError,
+};
+#[cfg(feature = "wasm-plugins")]
+use lime_core::plugin::{WasmHostHandler, WasmLimits, WasmPlugin, WasmPluginError};
+use lime_credential::MasterKeyring;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
 use std::process::Stdio;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
@@ -52,12 +99,25 @@ struct JsonRpcNotification {
     params: Option<Value>,
 }
 
-/// JSON-RPC 消息（可能是响应或通知）
+/// 后端进程发往宿主的 JSON-RPC 请求
+#[derive(Debug, Deserialize, Clone)]
+struct JsonRpcHostRequest {
+    #[allow(dead_code)]
+    jsonrpc: String,
+    method: String,
+    params: Option<Value>,
+    id: Value,
+}
+
+/// JSON-RPC 消息（请求、通知或响应）
+///
+/// untagged 按顺序匹配：请求需要 method + id，通知需要 method，其余视为响应。
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 enum JsonRpcMessage {
-    Response(JsonRpcResponse),
+    Request(JsonRpcHostRequest),
     Notification(JsonRpcNotification),
+    Response(JsonRpcResponse),
 }
 
 /// JSON-RPC 错误
@@ -69,6 +129,15 @@ struct JsonRpcError {
     data: Option<Value>,
 }
 
+/// 方法不存在
+const RPC_METHOD_NOT_FOUND: i32 = -32601;
+/// 参数无效
+const RPC_INVALID_PARAMS: i32 = -32602;
+/// 宿主处理失败
+const RPC_HOST_ERROR: i32 = -32000;
+/// 插件未声明所需权限
+const RPC_PERMISSION_DENIED: i32 = -32001;
+
 /// RPC 通知事件 payload
 #[derive(Debug, Clone, Serialize)]
 struct RpcNotificationPayload {
@@ -84,7 +153,6 @@ struct PendingRequest {
 
 /// 插件进程信息
 struct PluginProcess {
-    #[allow(dead_code)]
     child: Child,
     stdin: Arc<Mutex<ChildStdin>>,
     pending_requests: Arc<Mutex<HashMap<u64, PendingRequest>>>,
@@ -95,13 +163,97 @@ struct PluginProcess {
 pub struct PluginRpcManagerState {
     /// 运行中的插件进程
     processes: RwLock<HashMap<String, Arc<Mutex<PluginProcess>>>>,
+    /// 已加载的 WASM 插件
+    #[cfg(feature = "wasm-plugins")]
+    wasm_plugins: RwLock<HashMap<String, Arc<std::sync::Mutex<WasmPlugin>>>>,
+    /// 插件事件总线
+    events: Arc<PluginEventBus>,
 }
 
+/// 优雅关闭时等待进程自行退出的时间
+const GRACEFUL_SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(3);
+
 impl PluginRpcManagerState {
     pub fn new() -> Self {
         Self {
             processes: RwLock::new(HashMap::new()),
+            #[cfg(feature = "wasm-plugins")]
+            wasm_plugins: RwLock::new(HashMap::new()),
+            events: Arc::new(PluginEventBus::new()),
+        }
+    }
+
+    /// 插件事件总线
+    pub fn event_bus(&self) -> &Arc<PluginEventBus> {
+        &self.events
+    }
+
+    /// 插件进程是否正在运行（或 WASM 插件已加载）
+    pub async fn is_connected(&self, plugin_id: &str) -> bool {
+        #[cfg(feature = "wasm-plugins")]
+        if self.wasm_plugins.read().await.contains_key(plugin_id) {
+            return true;
+        }
+        self.processes.read().await.contains_key(plugin_id)
+    }
+
+    /// 优雅关闭插件进程
+    ///
+    /// 先发送 `shutdown` 通知并等待进程自行退出，超时后强制终止；
+    /// 尚未返回的 RPC 请求会收到错误，不会一直等到超时。
+    /// 返回进程是否曾在运行。
+    pub async fn shutdown(&self, plugin_id: &str) -> bool {
+        self.events.unsubscribe_plugin(plugin_id);
+
+        #[cfg(feature = "wasm-plugins")]
+        if let Some(plugin) = self.wasm_plugins.write().await.remove(plugin_id) {
+            let result = tokio::task::spawn_blocking(move || match plugin.lock() {
+                Ok(mut plugin) => plugin.shutdown(),
+                Err(_) => Ok(()),
+            })
+            .await;
+            if let Ok(Err(e)) = result {
+                tracing::warn!("WASM 插件 {} 卸载回调失败: {}", plugin_id, e);
+            }
+            tracing::info!("WASM 插件 {} 已卸载", plugin_id);
+            return true;
+        }
+
+        let Some(process_arc) = self.processes.write().await.remove(plugin_id) else {
+            return false;
+        };
+        let mut process = process_arc.lock().await;
+
+        let notification = serde_json::json!({ "jsonrpc": "2.0", "method": "shutdown" });
+        if let Err(e) = write_message(&process.stdin, &notification).await {
+            tracing::debug!("插件 {} shutdown 通知发送失败: {}", plugin_id, e);
+        }
+
+        match tokio::time::timeout(GRACEFUL_SHUTDOWN_TIMEOUT, process.child.wait()).await {
+            Ok(Ok(status)) => {
+                tracing::info!("插件 {} 进程已退出: {}", plugin_id, status);
+            }
+            _ => {
+                if let Err(e) = process.child.kill().await {
+                    tracing::warn!("关闭插件 {} 进程失败: {}", plugin_id, e);
+                }
+            }
+        }
+
+        // 通知 stdout 读取任务退出
+        if let Some(tx) = process.shutdown_tx.take() {
+            let _ = tx.send(()).await;
+        }
+
+        let mut pending = process.pending_requests.lock().await;
+        for (_, request) in pending.drain() {
+            let _ = request
+                .response_tx
+                .send(Err(format!("插件 {plugin_id} 进程已关闭")));
         }
+
+        tracing::info!("插件 {} 进程已关闭", plugin_id);
+        true
     }
 }
 
@@ -111,6 +263,26 @@ impl Default for PluginRpcManagerState {
     }
 }
 
+/// 解析 manifest 中声明的插件文件路径
+///
+/// 只接受由普通路径段组成的相对路径（允许开头的 `./`），拒绝绝对路径与 `..`，
+/// 保证结果位于安装目录内。
+fn resolve_plugin_file(install_path: &Path, relative: &str) -> Result<PathBuf, String> {
+    let path = Path::new(relative);
+    let valid = path
+        .components()
+        .any(|component| matches!(component, Component::Normal(_)))
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !valid {
+        return Err(format!(
+            "manifest 中的文件路径无效（必须位于插件目录内）: {relative}"
+        ));
+    }
+    Ok(install_path.join(path))
+}
+
 /// 启动插件进程并建立 RPC 连接
 #[tauri::command]
 pub async fn plugin_rpc_connect(
@@ -118,13 +290,13 @@ pub async fn plugin_rpc_connect(
     app_handle: tauri::AppHandle,
     installer_state: tauri::State<'_, PluginInstallerState>,
     rpc_state: tauri::State<'_, PluginRpcManagerState>,
+    db: tauri::State<'_, DbConnection>,
+    crypto: tauri::State<'_, PluginCryptoState>,
+    app_state: tauri::State<'_, AppState>,
 ) -> Result<(), String> {
     // 检查是否已连接
-    {
-        let processes = rpc_state.processes.read().await;
-        if processes.contains_key(&plugin_id) {
-            return Ok(()); // 已连接
-        }
+    if rpc_state.is_connected(&plugin_id).await {
+        return Ok(());
     }
 
     // 获取插件信息
@@ -142,6 +314,75 @@ pub async fn plugin_rpc_connect(
     let manifest: Value =
         serde_json::from_str(&manifest_content).map_err(|e| format!("解析 manifest 失败: {e}"))?;
 
+    let storage = PluginStorage::new(db.inner().clone(), plugin_id.clone());
+    let schema: PluginDatabaseSchema = manifest
+        .get("database")
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("manifest 中 database 声明无效: {e}"))?
+        .unwrap_or_default();
+    let database = PluginDatabase::new(db.inner().clone(), plugin_id.clone(), schema);
+    database.migrate().map_err(|e| e.to_string())?;
+    let crypto = crypto.0.clone();
+    let permissions = manifest_permissions(&manifest);
+    let files = PluginFileSandbox::new(
+        &lime_core::app_paths::resolve_plugin_data_dir()?,
+        plugin_id.clone(),
+        permissions.clone(),
+    );
+
+    #[cfg(not(feature = "wasm-plugins"))]
+    if manifest["plugin_type"].as_str() == Some("wasm") {
+        return Err("当前构建未包含 WASM 插件运行时（需启用 wasm-plugins 编译特性）".to_string());
+    }
+
+    #[cfg(feature = "wasm-plugins")]
+    if manifest["plugin_type"].as_str() == Some("wasm") {
+        let wasm_enabled = app_state
+            .read()
+            .await
+            .config
+            .feature_flags
+            .is_enabled(FeatureFlag::WasmPlugins);
+        if !wasm_enabled {
+            return Err("WASM 插件运行时未开启，请在功能开关中启用 wasm_plugins".to_string());
+        }
+
+        let entry = manifest["entry"]
+            .as_str()
+            .filter(|entry| entry.ends_with(".wasm"))
+            .ok_or("manifest 中 entry 必须指向 .wasm 模块")?;
+        let module_path = resolve_plugin_file(&plugin.install_path, entry)?;
+        // WASM 插件没有可写回的通道，只能发布事件
+        let events = PluginEventClient::new(
+            rpc_state.events.clone(),
+            plugin_id.clone(),
+            permissions,
+            None,
+        );
+        let handler: WasmHostHandler = Arc::new(move |method: &str, params: Option<&Value>| {
+            dispatch_host_request(
+                &storage, &crypto, &files, &events, &database, method, params,
+            )
+        });
+        let id = plugin_id.clone();
+        let wasm = tokio::task::spawn_blocking(move || {
+            WasmPlugin::load(id, &module_path, handler, WasmLimits::default())
+        })
+        .await
+        .map_err(|e| format!("加载 WASM 插件失败: {e}"))?
+        .map_err(|e| e.to_string())?;
+
+        tracing::info!("WASM 插件 {} 已加载", plugin_id);
+        rpc_state
+            .wasm_plugins
+            .write()
+            .await
+            .insert(plugin_id, Arc::new(std::sync::Mutex::new(wasm)));
+        return Ok(());
+    }
+
     // 获取二进制文件路径
     let _binary_name = manifest["binary"]["binary_name"]
         .as_str()
@@ -161,7 +402,7 @@ pub async fn plugin_rpc_connect(
         .as_str()
         .ok_or_else(|| format!("manifest 中缺少 {platform_key} 平台的二进制文件"))?;
 
-    let binary_path = plugin.install_path.join(binary_filename);
+    let binary_path = resolve_plugin_file(&plugin.install_path, binary_filename)?;
     if !binary_path.exists() {
         return Err(format!("二进制文件不存在: {binary_path:?}"));
     }
@@ -184,6 +425,39 @@ pub async fn plugin_rpc_connect(
     let pending_requests: Arc<Mutex<HashMap<u64, PendingRequest>>> =
         Arc::new(Mutex::new(HashMap::new()));
 
+    // 订阅的事件经 channel 转发，由单独的任务写回 stdin
+    let (event_tx, mut event_rx) = mpsc::unbounded_channel::<(u64, PluginBusEvent)>();
+    let sink: PluginEventSink = Arc::new(move |subscription_id: u64, event: &PluginBusEvent| {
+        let _ = event_tx.send((subscription_id, event.clone()));
+    });
+    let events = PluginEventClient::new(
+        rpc_state.events.clone(),
+        plugin_id.clone(),
+        permissions,
+        Some(sink),
+    );
+    let event_stdin = stdin.clone();
+    let event_plugin_id = plugin_id.clone();
+    tokio::spawn(async move {
+        while let Some((subscription_id, event)) = event_rx.recv().await {
+            let notification = serde_json::json!({
+                "jsonrpc": "2.0",
+                "method": "event",
+                "params": {
+                    "subscription_id": subscription_id,
+                    "source": event.source,
+                    "topic": event.topic,
+                    "payload": event.payload,
+                    "timestamp": event.timestamp,
+                },
+            });
+            if let Err(e) = write_message(&event_stdin, &notification).await {
+                tracing::debug!("插件 {} 事件投递失败: {}", event_plugin_id, e);
+                break;
+            }
+        }
+    });
+
     // 创建 shutdown channel
     let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
 
@@ -191,6 +465,8 @@ pub async fn plugin_rpc_connect(
     let plugin_id_clone = plugin_id.clone();
     let pending_requests_clone = pending_requests.clone();
     let app_handle_clone = app_handle.clone();
+    let stdin_clone = stdin.clone();
+    let event_bus = rpc_state.events.clone();
 
     tokio::spawn(async move {
         let mut reader = BufReader::new(stdout);
@@ -221,6 +497,18 @@ pub async fn plugin_rpc_connect(
 
                             // 尝试解析为 JSON-RPC 消息
                             match serde_json::from_str::<JsonRpcMessage>(line_trimmed) {
+                                Ok(JsonRpcMessage::Request(request)) => {
+                                    // 这是后端进程发往宿主的请求，处理后写回 stdin
+                                    let response = handle_host_request(&storage, &crypto, &files, &events, &database, &request);
+                                    if let Err(e) = write_message(&stdin_clone, &response).await {
+                                        tracing::error!(
+                                            "插件 {} 宿主请求 {} 响应写入失败: {}",
+                                            plugin_id_clone,
+                                            request.method,
+                                            e
+                                        );
+                                    }
+                                }
                                 Ok(JsonRpcMessage::Response(response)) => {
                                     // 这是一个响应，找到对应的 pending request
                                     if let Some(id) = response.id {
@@ -269,6 +557,9 @@ pub async fn plugin_rpc_connect(
                 }
             }
         }
+
+        // 进程已不可达，取消其订阅（同时释放事件转发 channel）
+        event_bus.unsubscribe_plugin(&plugin_id_clone);
     });
 
     let process = PluginProcess {
@@ -291,23 +582,7 @@ pub async fn plugin_rpc_disconnect(
     plugin_id: String,
     rpc_state: tauri::State<'_, PluginRpcManagerState>,
 ) -> Result<(), String> {
-    let mut processes = rpc_state.processes.write().await;
-
-    if let Some(process_arc) = processes.remove(&plugin_id) {
-        let mut process = process_arc.lock().await;
-
-        // 发送 shutdown 信号
-        if let Some(tx) = process.shutdown_tx.take() {
-            let _ = tx.send(()).await;
-        }
-
-        // 终止进程
-        if let Err(e) = process.child.kill().await {
-            tracing::warn!("关闭插件 {} 进程失败: {}", plugin_id, e);
-        }
-        tracing::info!("插件 {} 进程已关闭", plugin_id);
-    }
-
+    rpc_state.shutdown(&plugin_id).await;
     Ok(())
 }
 
@@ -319,6 +594,25 @@ pub async fn plugin_rpc_call(
     params: Option<Value>,
     rpc_state: tauri::State<'_, PluginRpcManagerState>,
 ) -> Result<Value, String> {
+    #[cfg(feature = "wasm-plugins")]
+    let wasm_plugin = rpc_state.wasm_plugins.read().await.get(&plugin_id).cloned();
+    #[cfg(feature = "wasm-plugins")]
+    if let Some(plugin) = wasm_plugin {
+        return tokio::task::spawn_blocking(move || {
+            let mut plugin = plugin
+                .lock()
+                .map_err(|_| format!("WASM 插件 {plugin_id} 状态异常"))?;
+            plugin.call(&method, params.as_ref()).map_err(|e| match e {
+                WasmPluginError::Rpc { code, message } => {
+                    format!("RPC 错误 [{code}]: {message}")
+                }
+                other => other.to_string(),
+            })
+        })
+        .await
+        .map_err(|e| format!("WASM 插件调用失败: {e}"))?;
+    }
+
     let processes = rpc_state.processes.read().await;
     let process_arc = processes
         .get(&plugin_id)
@@ -338,7 +632,7 @@ pub async fn plugin_rpc_call(
     };
 
     let request_json =
-        serde_json::to_string(&request).map_err(|e| format!("序列化请求失败: {e}"))?;
+        serde_json::to_value(&request).map_err(|e| format!("序列化请求失败: {e}"))?;
 
     // 创建响应 channel
     let (response_tx, response_rx) = oneshot::channel();
@@ -350,21 +644,7 @@ pub async fn plugin_rpc_call(
     }
 
     // 发送请求
-    {
-        let mut stdin = process.stdin.lock().await;
-        stdin
-            .write_all(request_json.as_bytes())
-            .await
-            .map_err(|e| format!("发送请求失败: {e}"))?;
-        stdin
-            .write_all(b"\n")
-            .await
-            .map_err(|e| format!("发送换行失败: {e}"))?;
-        stdin
-            .flush()
-            .await
-            .map_err(|e| format!("刷新 stdin 失败: {e}"))?;
-    }
+    write_message(&process.stdin, &request_json).await?;
 
     // 释放 process lock，让 stdout 读取任务可以处理响应
     drop(process);
@@ -382,3 +662,591 @@ pub async fn plugin_rpc_call(
         }
     }
 }
+
+/// 向插件进程写入一行 JSON-RPC 消息
+async fn write_message(stdin: &Mutex<ChildStdin>, message: &Value) -> Result<(), String> {
+    let mut line = serde_json::to_string(message).map_err(|e| format!("序列化消息失败: {e}"))?;
+    line.push('\n');
+
+    let mut stdin = stdin.lock().await;
+    stdin
+        .write_all(line.as_bytes())
+        .await
+        .map_err(|e| format!("发送请求失败: {e}"))?;
+    stdin
+        .flush()
+        .await
+        .map_err(|e| format!("刷新 stdin 失败: {e}"))
+}
+
+/// 处理后端进程发往宿主的请求，返回完整的 JSON-RPC 响应
+fn handle_host_request(
+    storage: &PluginStorage,
+    crypto: &MasterKeyring,
+    files: &PluginFileSandbox,
+    events: &PluginEventClient,
+    database: &PluginDatabase,
+    request: &JsonRpcHostRequest,
+) -> Value {
+    let result = dispatch_host_request(
+        storage,
+        crypto,
+        files,
+        events,
+        database,
+        &request.method,
+        request.params.as_ref(),
+    );
+    match result {
+        Ok(result) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": request.id,
+            "result": result,
+        }),
+        Err((code, message)) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": request.id,
+            "error": { "code": code, "message": message },
+        }),
+    }
+}
+
+/// 读取 manifest 中声明的权限，无法识别的权限被忽略
+fn manifest_permissions(manifest: &Value) -> Vec<PluginPermission> {
+    manifest
+        .get("permissions")
+        .cloned()
+        .and_then(|permissions| serde_json::from_value(permissions).ok())
+        .unwrap_or_default()
+}
+
+fn fs_error(e: PluginFsError) -> (i32, String) {
+    let code = match e {
+        PluginFsError::PermissionDenied(_) => RPC_PERMISSION_DENIED,
+        PluginFsError::InvalidPath(_) => RPC_INVALID_PARAMS,
+        _ => RPC_HOST_ERROR,
+    };
+    (code, e.to_string())
+}
+
+fn event_error(e: PluginEventError) -> (i32, String) {
+    let code = match e {
+        PluginEventError::PermissionDenied(_) => RPC_PERMISSION_DENIED,
+        PluginEventError::InvalidTopic(_) => RPC_INVALID_PARAMS,
+        PluginEventError::Unsupported => RPC_HOST_ERROR,
+    };
+    (code, e.to_string())
+}
+
+/// 将整个 params 反序列化为结构化参数
+fn typed_params<T: serde::de::DeserializeOwned>(
+    params: Option<&Value>,
+) -> Result<T, (i32, String)> {
+    let params = params.ok_or_else(|| (RPC_INVALID_PARAMS, "缺少参数".to_string()))?;
+    serde_json::from_value(params.clone())
+        .map_err(|e| (RPC_INVALID_PARAMS, format!("参数无效: {e}")))
+}
+
+fn db_error(e: PluginDbError) -> (i32, String) {
+    let code = match e {
+        PluginDbError::Database(_) => RPC_HOST_ERROR,
+        _ => RPC_INVALID_PARAMS,
+    };
+    (code, e.to_string())
+}
+
+fn dispatch_host_request(
+    storage: &PluginStorage,
+    crypto: &MasterKeyring,
+    files: &PluginFileSandbox,
+    events: &PluginEventClient,
+    database: &PluginDatabase,
+    method: &str,
+    params: Option<&Value>,
+) -> Result<Value, (i32, String)> {
+    let str_param = |name: &str| {
+        params
+            .and_then(|p| p.get(name))
+            .and_then(Value::as_str)
+            .ok_or_else(|| (RPC_INVALID_PARAMS, format!("缺少参数 {name}")))
+    };
+    let key = || str_param("key");
+    let host_error = |e: PluginStorageError| (RPC_HOST_ERROR, e.to_string());
+    let context = plugin_crypto_context(storage.plugin_id());
+
+    match method {
+        "storage.get" => Ok(storage
+            .get(key()?)
+            .map_err(host_error)?
+            .unwrap_or(Value::Null)),
+        "storage.set" => {
+            let value = params
+                .and_then(|p| p.get("value"))
+                .ok_or_else(|| (RPC_INVALID_PARAMS, "缺少参数 value".to_string()))?;
+            storage.set(key()?, value).map_err(host_error)?;
+            Ok(Value::Null)
+        }
+        "storage.delete" => Ok(Value::Bool(storage.delete(key()?).map_err(host_error)?)),
+        "storage.keys" => Ok(serde_json::json!(storage.keys().map_err(host_error)?)),
+        "storage.keys_page" => {
+            let page: PageRequest = params
+                .map(|p| serde_json::from_value(p.clone()))
+                .transpose()
+                .map_err(|e| (RPC_INVALID_PARAMS, format!("分页参数无效: {e}")))?
+                .unwrap_or_default();
+            Ok(serde_json::json!(storage
+                .keys_page(&page)
+                .map_err(host_error)?))
+        }
+        "storage.usage" => Ok(serde_json::json!(storage.usage().map_err(host_error)?)),
+        "crypto.encrypt" => crypto
+            .encrypt(&context, str_param("plaintext")?)
+            .map(Value::String)
+            .map_err(|e| (RPC_HOST_ERROR, e.to_string())),
+        "crypto.decrypt" => crypto
+            .decrypt(&context, str_param("ciphertext")?)
+            .map(Value::String)
+            .map_err(|e| (RPC_HOST_ERROR, e.to_string())),
+        "fs.read" => {
+            let content = files.read(str_param("path")?).map_err(fs_error)?;
+            match str_param("encoding").unwrap_or("utf8") {
+                "base64" => Ok(Value::String(BASE64.encode(content))),
+                "utf8" => String::from_utf8(content)
+                    .map(Value::String)
+                    .map_err(|_| (RPC_HOST_ERROR, "文件不是有效的 UTF-8 文本".to_string())),
+                other => Err((RPC_INVALID_PARAMS, format!("不支持的编码: {other}"))),
+            }
+        }
+        "fs.write" => {
+            let content = str_param("content")?;
+            let content = match str_param("encoding").unwrap_or("utf8") {
+                "base64" => BASE64
+                    .decode(content)
+                    .map_err(|e| (RPC_INVALID_PARAMS, format!("base64 内容无效: {e}")))?,
+                "utf8" => content.as_bytes().to_vec(),
+                other => return Err((RPC_INVALID_PARAMS, format!("不支持的编码: {other}"))),
+            };
+            let append = params
+                .and_then(|p| p.get("append"))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            files
+                .write(str_param("path")?, &content, append)
+                .map_err(fs_error)?;
+            Ok(Value::Null)
+        }
+        "fs.list" => Ok(serde_json::json!(files
+            .list(str_param("path").unwrap_or(""))
+            .map_err(fs_error)?)),
+        "fs.usage" => Ok(serde_json::json!(files.usage().map_err(fs_error)?)),
+        "event.emit" => {
+            let payload = params
+                .and_then(|p| p.get("payload"))
+                .cloned()
+                .unwrap_or(Value::Null);
+            Ok(serde_json::json!(events
+                .emit(str_param("topic")?, payload)
+                .map_err(event_error)?))
+        }
+        "event.subscribe" => {
+            let id = events.subscribe(str_param("topic")?).map_err(event_error)?;
+            Ok(serde_json::json!({ "subscription_id": id }))
+        }
+        "event.unsubscribe" => {
+            let id = params
+                .and_then(|p| p.get("subscription_id"))
+                .and_then(Value::as_u64)
+                .ok_or_else(|| (RPC_INVALID_PARAMS, "缺少参数 subscription_id".to_string()))?;
+            Ok(Value::Bool(events.unsubscribe(id)))
+        }
+        "db.execute" => {
+            let op: PluginDbWrite = typed_params(params)?;
+            Ok(serde_json::json!(database
+                .execute(&op)
+                .map_err(db_error)?))
+        }
+        "db.batch" => {
+            let operations: Vec<PluginDbWrite> = params
+                .and_then(|p| p.get("operations"))
+                .cloned()
+                .map(serde_json::from_value)
+                .transpose()
+                .map_err(|e| (RPC_INVALID_PARAMS, format!("参数无效: {e}")))?
+                .ok_or_else(|| (RPC_INVALID_PARAMS, "缺少参数 operations".to_string()))?;
+            Ok(serde_json::json!(database
+                .batch(&operations)
+                .map_err(db_error)?))
+        }
+        "db.query" => {
+            let query: PluginDbQuery = typed_params(params)?;
+            Ok(serde_json::json!(database
+                .query(&query)
+                .map_err(db_error)?))
+        }
+        other => Err((RPC_METHOD_NOT_FOUND, format!("宿主不支持的方法: {other}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lime_credential::master_key::MemoryKeyStore;
+    use rusqlite::Connection;
+    use std::sync::Mutex as StdMutex;
+
+    fn test_storage(plugin_id: &str, db: &DbConnection) -> PluginStorage {
+        PluginStorage::new(db.clone(), plugin_id)
+    }
+
+    /// 未声明文件权限的沙箱，不会访问文件系统
+    fn no_files() -> PluginFileSandbox {
+        PluginFileSandbox::new(std::path::Path::new("unused"), "plugin-a", Vec::new())
+    }
+
+    /// 未声明数据表的句柄
+    fn no_database(db: &DbConnection) -> PluginDatabase {
+        PluginDatabase::new(db.clone(), "plugin-a", PluginDatabaseSchema::default())
+    }
+
+    /// 未声明订阅权限、也无法接收事件的总线句柄
+    fn no_events() -> PluginEventClient {
+        PluginEventClient::new(
+            Arc::new(PluginEventBus::new()),
+            "plugin-a",
+            Vec::new(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_resolve_plugin_file_stays_in_install_dir() {
+        let install = Path::new("/plugins/demo");
+        assert_eq!(
+            resolve_plugin_file(install, "bin/plugin.wasm").unwrap(),
+            install.join("bin/plugin.wasm")
+        );
+        assert!(resolve_plugin_file(install, "../other/plugin.wasm").is_err());
+        assert!(resolve_plugin_file(install, "bin/../../plugin.wasm").is_err());
+        assert!(resolve_plugin_file(install, "/etc/plugin.wasm").is_err());
+        assert!(resolve_plugin_file(install, "./plugin.wasm").is_ok());
+        assert!(resolve_plugin_file(install, ".").is_err());
+        assert!(resolve_plugin_file(install, "").is_err());
+    }
+
+    #[test]
+    fn test_message_variants() {
+        let request: JsonRpcMessage = serde_json::from_str(
+            r#"{"jsonrpc":"2.0","method":"storage.get","params":{"key":"k"},"id":"a1"}"#,
+        )
+        .unwrap();
+        assert!(matches!(request, JsonRpcMessage::Request(_)));
+
+        let notification: JsonRpcMessage =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","method":"progress","params":{"p":1}}"#)
+                .unwrap();
+        assert!(matches!(notification, JsonRpcMessage::Notification(_)));
+
+        let response: JsonRpcMessage =
+            serde_json::from_str(r#"{"jsonrpc":"2.0","result":1,"id":3}"#).unwrap();
+        assert!(matches!(response, JsonRpcMessage::Response(_)));
+    }
+
+    #[test]
+    fn test_storage_requests_are_namespaced() {
+        let conn = Connection::open_in_memory().unwrap();
+        lime_core::database::schema::create_tables(&conn).unwrap();
+        let db: DbConnection = Arc::new(StdMutex::new(conn));
+        let plugin_a = test_storage("plugin-a", &db);
+        let plugin_b = test_storage("plugin-b", &db);
+        let crypto = MasterKeyring::new(Box::new(MemoryKeyStore::default()));
+
+        let set = serde_json::json!({ "key": "count", "value": 3 });
+        assert_eq!(
+            dispatch_host_request(
+                &plugin_a,
+                &crypto,
+                &no_files(),
+                &no_events(),
+                &no_database(&db),
+                "storage.set",
+                Some(&set)
+            ),
+            Ok(Value::Null)
+        );
+        let get = serde_json::json!({ "key": "count" });
+        assert_eq!(
+            dispatch_host_request(
+                &plugin_a,
+                &crypto,
+                &no_files(),
+                &no_events(),
+                &no_database(&db),
+                "storage.get",
+                Some(&get)
+            ),
+            Ok(serde_json::json!(3))
+        );
+        assert_eq!(
+            dispatch_host_request(
+                &plugin_b,
+                &crypto,
+                &no_files(),
+                &no_events(),
+                &no_database(&db),
+                "storage.get",
+                Some(&get)
+            ),
+            Ok(Value::Null)
+        );
+
+        let response = handle_host_request(
+            &plugin_a,
+            &crypto,
+            &no_files(),
+            &no_events(),
+            &no_database(&db),
+            &JsonRpcHostRequest {
+                jsonrpc: "2.0".to_string(),
+                method: "storage.unknown".to_string(),
+                params: None,
+                id: serde_json::json!(7),
+            },
+        );
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], RPC_METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn test_crypto_requests_use_plugin_subkey() {
+        let conn = Connection::open_in_memory().unwrap();
+        lime_core::database::schema::create_tables(&conn).unwrap();
+        let db: DbConnection = Arc::new(StdMutex::new(conn));
+        let plugin_a = test_storage("plugin-a", &db);
+        let plugin_b = test_storage("plugin-b", &db);
+        let crypto = MasterKeyring::new(Box::new(MemoryKeyStore::default()));
+
+        let encrypt = serde_json::json!({ "plaintext": "token" });
+        let ciphertext = dispatch_host_request(
+            &plugin_a,
+            &crypto,
+            &no_files(),
+            &no_events(),
+            &no_database(&db),
+            "crypto.encrypt",
+            Some(&encrypt),
+        )
+        .unwrap();
+        assert!(ciphertext.as_str().unwrap().starts_with("enc3:v1:"));
+
+        let decrypt = serde_json::json!({ "ciphertext": ciphertext });
+        assert_eq!(
+            dispatch_host_request(
+                &plugin_a,
+                &crypto,
+                &no_files(),
+                &no_events(),
+                &no_database(&db),
+                "crypto.decrypt",
+                Some(&decrypt)
+            ),
+            Ok(Value::String("token".to_string()))
+        );
+        assert!(matches!(
+            dispatch_host_request(
+                &plugin_b,
+                &crypto,
+                &no_files(),
+                &no_events(),
+                &no_database(&db),
+                "crypto.decrypt",
+                Some(&decrypt)
+            ),
+            Err((RPC_HOST_ERROR, _))
+        ));
+        assert!(matches!(
+            dispatch_host_request(
+                &plugin_a,
+                &crypto,
+                &no_files(),
+                &no_events(),
+                &no_database(&db),
+                "crypto.encrypt",
+                None
+            ),
+            Err((RPC_INVALID_PARAMS, _))
+        ));
+    }
+
+    #[test]
+    fn test_fs_requests_require_permissions() {
+        let conn = Connection::open_in_memory().unwrap();
+        lime_core::database::schema::create_tables(&conn).unwrap();
+        let db: DbConnection = Arc::new(StdMutex::new(conn));
+        let storage = test_storage("plugin-a", &db);
+        let crypto = MasterKeyring::new(Box::new(MemoryKeyStore::default()));
+        let temp = tempfile::TempDir::new().unwrap();
+        let manifest = serde_json::json!({
+            "permissions": ["file_system_read", "file_system_write", "network_access"]
+        });
+        let files =
+            PluginFileSandbox::new(temp.path(), "plugin-a", manifest_permissions(&manifest));
+        let dispatch = |files: &PluginFileSandbox, method: &str, params: Value| {
+            dispatch_host_request(
+                &storage,
+                &crypto,
+                files,
+                &no_events(),
+                &no_database(&db),
+                method,
+                Some(&params),
+            )
+        };
+
+        let write =
+            serde_json::json!({ "path": "notes/a.txt", "content": "aGk=", "encoding": "base64" });
+        assert_eq!(dispatch(&files, "fs.write", write.clone()), Ok(Value::Null));
+        assert_eq!(
+            dispatch(
+                &files,
+                "fs.read",
+                serde_json::json!({ "path": "notes/a.txt" })
+            ),
+            Ok(Value::String("hi".to_string()))
+        );
+        assert_eq!(
+            dispatch(&files, "fs.list", serde_json::json!({ "path": "notes" })),
+            Ok(serde_json::json!([{ "name": "a.txt", "is_dir": false, "size": 2 }]))
+        );
+        assert!(matches!(
+            dispatch(
+                &files,
+                "fs.read",
+                serde_json::json!({ "path": "../plugin-b/a.txt" })
+            ),
+            Err((RPC_INVALID_PARAMS, _))
+        ));
+        assert!(matches!(
+            dispatch(&no_files(), "fs.write", write),
+            Err((RPC_PERMISSION_DENIED, _))
+        ));
+    }
+
+    #[test]
+    fn test_event_requests_deliver_to_subscribers() {
+        let conn = Connection::open_in_memory().unwrap();
+        lime_core::database::schema::create_tables(&conn).unwrap();
+        let db: DbConnection = Arc::new(StdMutex::new(conn));
+        let storage = test_storage("plugin-a", &db);
+        let crypto = MasterKeyring::new(Box::new(MemoryKeyStore::default()));
+        let bus = Arc::new(PluginEventBus::new());
+
+        let received = Arc::new(StdMutex::new(Vec::new()));
+        let sink_received = received.clone();
+        let sink: PluginEventSink = Arc::new(move |id: u64, event: &PluginBusEvent| {
+            sink_received.lock().unwrap().push((id, event.clone()));
+        });
+        let subscriber = PluginEventClient::new(
+            bus.clone(),
+            "plugin-b",
+            vec![PluginPermission::EventSubscribe],
+            Some(sink),
+        );
+        let publisher = PluginEventClient::new(bus, "plugin-a", Vec::new(), None);
+        let dispatch = |events: &PluginEventClient, method: &str, params: Value| {
+            dispatch_host_request(
+                &storage,
+                &crypto,
+                &no_files(),
+                events,
+                &no_database(&db),
+                method,
+                Some(&params),
+            )
+        };
+
+        let subscribed = dispatch(
+            &subscriber,
+            "event.subscribe",
+            serde_json::json!({ "topic": "sync.*" }),
+        )
+        .unwrap();
+        let emit = serde_json::json!({ "topic": "sync.done", "payload": { "n": 1 } });
+        assert_eq!(
+            dispatch(&publisher, "event.emit", emit),
+            Ok(serde_json::json!(1))
+        );
+        {
+            let received = received.lock().unwrap();
+            assert_eq!(received.len(), 1);
+            assert_eq!(received[0].0, subscribed["subscription_id"]);
+            assert_eq!(received[0].1.source, "plugin-a");
+            assert_eq!(received[0].1.payload["n"], 1);
+        }
+
+        assert!(matches!(
+            dispatch(
+                &publisher,
+                "event.subscribe",
+                serde_json::json!({ "topic": "sync.*" })
+            ),
+            Err((RPC_PERMISSION_DENIED, _))
+        ));
+        assert_eq!(
+            dispatch(&subscriber, "event.unsubscribe", subscribed),
+            Ok(Value::Bool(true))
+        );
+    }
+
+    #[test]
+    fn test_db_requests_use_declared_tables() {
+        let conn = Connection::open_in_memory().unwrap();
+        lime_core::database::schema::create_tables(&conn).unwrap();
+        let db: DbConnection = Arc::new(StdMutex::new(conn));
+        let storage = test_storage("plugin-a", &db);
+        let crypto = MasterKeyring::new(Box::new(MemoryKeyStore::default()));
+        let schema: PluginDatabaseSchema = serde_json::from_value(serde_json::json!({
+            "tables": [{
+                "name": "items",
+                "columns": [
+                    { "name": "id", "type": "integer", "primary_key": true },
+                    { "name": "label", "type": "text" }
+                ]
+            }]
+        }))
+        .unwrap();
+        let database = PluginDatabase::new(db.clone(), "plugin-a", schema);
+        database.migrate().unwrap();
+        let dispatch = |method: &str, params: Value| {
+            dispatch_host_request(
+                &storage,
+                &crypto,
+                &no_files(),
+                &no_events(),
+                &database,
+                method,
+                Some(&params),
+            )
+        };
+
+        let batch = serde_json::json!({ "operations": [
+            { "op": "insert", "table": "items", "values": { "label": "a" } },
+            { "op": "insert", "table": "items", "values": { "label": "b" } },
+            { "op": "delete", "table": "items", "where": { "label": "a" } }
+        ] });
+        assert_eq!(
+            dispatch("db.batch", batch).unwrap()[2],
+            serde_json::json!({ "rows_affected": 1 })
+        );
+        assert_eq!(
+            dispatch("db.query", serde_json::json!({ "table": "items" })),
+            Ok(serde_json::json!([{ "id": 2, "label": "b" }]))
+        );
+        assert!(matches!(
+            dispatch(
+                "db.execute",
+                serde_json::json!({ "op": "insert", "table": "plugin_storage", "values": {} })
+            ),
+            Err((RPC_INVALID_PARAMS, _))
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+//! Prompt 库命令
+//!
+//! 管理跨对话、Agent 与 Skill 共享的 Prompt 库（文件夹、标签、变量与版本历史）。
+
+use crate::database::DbConnection;
+use lime_core::database::dao::prompt_library::{
+    PromptLibraryEntry, PromptLibraryQuery, PromptLibraryVersion,
+};
+use lime_services::prompt_library_service::{PromptDiffLine, PromptLibraryService};
+use serde::Deserialize;
+use std::collections::HashMap;
+use tauri::State;
+
+/// 保存 Prompt 请求
+#[derive(Debug, Deserialize)]
+pub struct SaveLibraryPromptRequest {
+    pub prompt: PromptLibraryEntry,
+    /// 版本说明（内容变化生成新版本时记录）
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// 列出库中的 Prompt
+#[tauri::command]
+pub fn list_library_prompts(
+    db: State<'_, DbConnection>,
+    query: Option<PromptLibraryQuery>,
+) -> Result<Vec<PromptLibraryEntry>, String> {
+    PromptLibraryService::list(&db, &query.unwrap_or_default())
+}
+
+/// 获取单个 Prompt
+#[tauri::command]
+pub fn get_library_prompt(
+    db: State<'_, DbConnection>,
+    id: String,
+) -> Result<Option<PromptLibraryEntry>, String> {
+    PromptLibraryService::get(&db, &id)
+}
+
+/// 新建或更新 Prompt（ID 为空时新建）
+#[tauri::command]
+pub fn save_library_prompt(
+    db: State<'_, DbConnection>,
+    request: SaveLibraryPromptRequest,
+) -> Result<PromptLibraryEntry, String> {
+    PromptLibraryService::save(&db, request.prompt, request.note.as_deref())
+}
+
+/// 删除 Prompt 及其历史版本
+#[tauri::command]
+pub fn delete_library_prompt(db: State<'_, DbConnection>, id: String) -> Result<bool, String> {
+    PromptLibraryService::delete(&db, &id)
+}
+
+/// 列出 Prompt 的历史版本（最新在前）
+#[tauri::command]
+pub fn list_library_prompt_versions(
+    db: State<'_, DbConnection>,
+    id: String,
+) -> Result<Vec<PromptLibraryVersion>, String> {
+    PromptLibraryService::list_versions(&db, &id)
+}
+
+/// 对比两个版本
+#[tauri::command]
+pub fn diff_library_prompt_versions(
+    db: State<'_, DbConnection>,
+    id: String,
+    from_version: u32,
+    to_version: u32,
+) -> Result<Vec<PromptDiffLine>, String> {
+    PromptLibraryService::diff_versions(&db, &id, from_version, to_version)
+}
+
+/// 回滚到指定版本
+#[tauri::command]
+pub fn restore_library_prompt_version(
+    db: State<'_, DbConnection>,
+    id: String,
+    version: u32,
+) -> Result<PromptLibraryEntry, String> {
+    PromptLibraryService::restore_version(&db, &id, version)
+}
+
+/// 按变量值渲染 Prompt
+#[tauri::command]
+pub fn render_library_prompt(
+    db: State<'_, DbConnection>,
+    id: String,
+    values: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    PromptLibraryService::render(&db, &id, &values.unwrap_or_default())
+}
+
+/// 列出已使用的文件夹
+#[tauri::command]
+pub fn list_library_prompt_folders(db: State<'_, DbConnection>) -> Result<Vec<String>, String> {
+    PromptLibraryService::list_folders(&db)
+}
+
+/// 列出已使用的标签
+#[tauri::command]
+pub fn list_library_prompt_tags(db: State<'_, DbConnection>) -> Result<Vec<String>, String> {
+    PromptLibraryService::list_tags(&db)
+}
@@ -2,19 +2,34 @@
 
 #![allow(dead_code)]
 
+use crate::commands::plugin_install_cmd::PluginInstallerState;
+use crate::database::credential_cipher::{
+    self, list_unreadable_credentials, verify_credential_encryption, CredentialEncryptionReport,
+    UnreadableCredential,
+};
 use crate::database::dao::provider_pool::ProviderPoolDao;
 use crate::database::DbConnection;
 use crate::models::provider_pool_model::{
-    AddCredentialRequest, CredentialData, CredentialDisplay, HealthCheckResult, OAuthStatus,
-    PoolProviderType, ProviderCredential, ProviderPoolOverview, UpdateCredentialRequest,
+    AddCredentialRequest, CredentialData, CredentialDisplay, CredentialEndpointHealth,
+    HealthCheckResult, MockProviderConfig, OAuthStatus, PoolProviderType, ProviderCredential,
+    ProviderPoolOverview, UpdateCredentialRequest,
 };
+use crate::{AppState, TokenCacheServiceState};
 use chrono::Utc;
-use lime_credential::CredentialSyncService;
+use lime_core::credential::{CredentialPoolEvent, HealthProbe};
+use lime_credential::{
+    CredentialImportReport, CredentialSyncService, LegacyCredentialMigrationReport,
+    CREDENTIAL_IMPORT_PROGRESS_EVENT, DEFAULT_IMPORT_CONCURRENCY,
+    LEGACY_CREDENTIAL_MIGRATION_REPORT_FILE,
+};
+use lime_services::endpoint_health::normalize_fallback_base_urls;
 use lime_services::provider_pool_service::ProviderPoolService;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tauri::{Emitter, State};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
 pub struct ProviderPoolServiceState(pub Arc<ProviderPoolService>);
@@ -23,7 +38,7 @@ pub struct ProviderPoolServiceState(pub Arc<ProviderPoolService>);
 pub struct CredentialSyncServiceState(pub Option<Arc<CredentialSyncService>>);
 
 /// 展开路径中的 ~ 为用户主目录
-fn expand_tilde(path: &str) -> String {
+pub(crate) fn expand_tilde(path: &str) -> String {
     if let Some(stripped) = path.strip_prefix("~/") {
         if let Some(home) = dirs::home_dir() {
             return home.join(stripped).to_string_lossy().to_string();
@@ -33,7 +48,7 @@ fn expand_tilde(path: &str) -> String {
 }
 
 /// 获取应用凭证存储目录
-fn get_credentials_dir() -> Result<PathBuf, String> {
+pub(crate) fn get_credentials_dir() -> Result<PathBuf, String> {
     let app_data_dir = dirs::data_dir()
         .ok_or_else(|| "无法获取应用数据目录".to_string())?
         .join("lime")
@@ -254,6 +269,75 @@ pub fn get_provider_pool_overview(
     pool_service.0.get_overview(&db)
 }
 
+/// 凭证池变更事件名（前端监听）
+pub const PROVIDER_POOL_EVENT: &str = "provider-pool-event";
+
+/// 凭证池快照（附带生成时的事件序号）
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ProviderPoolSnapshot {
+    /// 快照对应的事件序号，序号不大于该值的事件已包含在快照中
+    pub seq: u64,
+    pub providers: Vec<ProviderPoolOverview>,
+    /// 因无法解密或解析而未列出的凭证数（见 `get_unreadable_credentials`）
+    #[serde(default)]
+    pub unreadable_count: usize,
+}
+
+/// 获取凭证池快照
+///
+/// 先取序号再读数据：读数据期间发生的变更会在快照和增量中各出现一次，重复应用是幂等的。
+#[tauri::command]
+pub fn get_provider_pool_snapshot(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+) -> Result<ProviderPoolSnapshot, String> {
+    let seq = pool_service.0.events().current_seq();
+    let providers = pool_service.0.get_overview(&db)?;
+    let (_, unreadable_count) = {
+        let conn = crate::database::lock_db(&db)?;
+        ProviderPoolDao::get_all_with_skipped(&conn).map_err(|e| e.to_string())?
+    };
+    Ok(ProviderPoolSnapshot {
+        seq,
+        providers,
+        unreadable_count,
+    })
+}
+
+/// 获取序号大于 `seq` 的凭证池事件
+///
+/// 返回 None 表示缺失的事件已超出保留窗口，前端需重新获取快照。
+#[tauri::command]
+pub fn get_provider_pool_events_since(
+    pool_service: State<'_, ProviderPoolServiceState>,
+    seq: u64,
+) -> Option<Vec<CredentialPoolEvent>> {
+    pool_service.0.events().since(seq)
+}
+
+/// 将凭证池变更事件转发到前端
+pub fn spawn_provider_pool_event_forwarder(
+    app_handle: AppHandle,
+    mut receiver: broadcast::Receiver<CredentialPoolEvent>,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let Err(e) = app_handle.emit(PROVIDER_POOL_EVENT, &event) {
+                        tracing::warn!("[PROVIDER_POOL] 发送事件失败: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    // 前端通过序号缺口发现丢失并调用 get_provider_pool_events_since 补齐
+                    tracing::warn!("[PROVIDER_POOL] 事件转发滞后，丢弃 {} 条", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
 /// 获取指定类型的凭证列表
 #[tauri::command]
 pub fn get_provider_pool_credentials(
@@ -406,6 +490,7 @@ pub fn update_provider_pool_credential(
 
         // 保存到数据库
         ProviderPoolDao::update(&conn, &updated_cred).map_err(|e| e.to_string())?;
+        pool_service.0.notify_credential_updated(&updated_cred);
 
         updated_cred
     } else if request.new_base_url.is_some() || request.new_api_key.is_some() {
@@ -472,11 +557,24 @@ pub fn update_provider_pool_credential(
         if let Some(not_supported_models) = request.not_supported_models {
             current_credential.not_supported_models = not_supported_models;
         }
+        if let Some(native_web_search) = request.native_web_search {
+            if native_web_search && !current_credential.credential.supports_native_web_search() {
+                return Err("该凭证类型不支持原生联网搜索".to_string());
+            }
+            current_credential.native_web_search = native_web_search;
+        }
+        if let Some(urls) = request.new_fallback_base_urls {
+            current_credential.fallback_base_urls =
+                normalize_fallback_base_urls(&current_credential.credential, urls)?;
+        }
 
         current_credential.updated_at = Utc::now();
 
         // 保存到数据库
         ProviderPoolDao::update(&conn, &current_credential).map_err(|e| e.to_string())?;
+        pool_service
+            .0
+            .notify_credential_updated(&current_credential);
 
         current_credential
     } else {
@@ -490,6 +588,8 @@ pub fn update_provider_pool_credential(
             request.check_model_name,
             request.not_supported_models,
             request.new_proxy_url,
+            request.native_web_search,
+            request.new_fallback_base_urls,
         )?
     };
 
@@ -543,9 +643,29 @@ pub fn toggle_provider_pool_credential(
     uuid: String,
     is_disabled: bool,
 ) -> Result<ProviderCredential, String> {
-    pool_service
-        .0
-        .update_credential(&db, &uuid, None, Some(is_disabled), None, None, None, None)
+    pool_service.0.update_credential(
+        &db,
+        &uuid,
+        None,
+        Some(is_disabled),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// 更新凭证负载均衡权重
+#[tauri::command]
+pub fn set_provider_pool_credential_weight(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+    uuid: String,
+    weight: u32,
+) -> Result<ProviderCredential, String> {
+    pool_service.0.set_credential_weight(&db, &uuid, weight)
 }
 
 /// 重置凭证计数器
@@ -568,13 +688,52 @@ pub fn reset_provider_pool_health(
     pool_service.0.reset_health_by_type(&db, &provider_type)
 }
 
+/// 按已安装插件的 plugin.json 刷新声明式健康探测
+///
+/// 每次健康检查前同步，插件安装、更新或卸载后无需额外通知。
+async fn sync_plugin_health_probes(
+    installer: &PluginInstallerState,
+    pool_service: &ProviderPoolService,
+) {
+    let plugins = match installer.0.read().await.list_installed() {
+        Ok(plugins) => plugins,
+        Err(e) => {
+            tracing::warn!("[健康检查] 读取已安装插件失败: {}", e);
+            return;
+        }
+    };
+
+    let declared = plugins
+        .into_iter()
+        .filter(|plugin| plugin.enabled)
+        .filter_map(|plugin| {
+            let content = fs::read_to_string(plugin.install_path.join("plugin.json")).ok()?;
+            let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+            let probes = manifest.get("health_probes")?.clone();
+            match serde_json::from_value::<Vec<HealthProbe>>(probes) {
+                Ok(probes) => Some((plugin.id, probes)),
+                Err(e) => {
+                    tracing::warn!("[健康检查] 插件 {} 的 health_probes 无效: {}", plugin.id, e);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    for error in pool_service.health_probes().replace_all(declared) {
+        tracing::warn!("[健康检查] 忽略插件健康探测: {}", error);
+    }
+}
+
 /// 执行单个凭证的健康检查
 #[tauri::command]
 pub async fn check_provider_pool_credential_health(
     db: State<'_, DbConnection>,
     pool_service: State<'_, ProviderPoolServiceState>,
+    installer: State<'_, PluginInstallerState>,
     uuid: String,
 ) -> Result<HealthCheckResult, String> {
+    sync_plugin_health_probes(&installer, &pool_service.0).await;
     tracing::info!("[DEBUG] 开始健康检查 for uuid: {}", uuid);
     let result = pool_service.0.check_credential_health(&db, &uuid).await;
     match &result {
@@ -593,11 +752,135 @@ pub async fn check_provider_pool_credential_health(
 pub async fn check_provider_pool_type_health(
     db: State<'_, DbConnection>,
     pool_service: State<'_, ProviderPoolServiceState>,
+    installer: State<'_, PluginInstallerState>,
     provider_type: String,
 ) -> Result<Vec<HealthCheckResult>, String> {
+    sync_plugin_health_probes(&installer, &pool_service.0).await;
     pool_service.0.check_type_health(&db, &provider_type).await
 }
 
+/// 插件凭证定时检查的调度间隔（秒）
+const PLUGIN_HEALTH_CHECK_TICK_SECS: u64 = 60;
+
+/// 插件凭证定时检查的最小间隔（分钟）
+const MIN_PLUGIN_HEALTH_CHECK_INTERVAL_MINUTES: u64 = 5;
+
+/// 定时检查 Provider 类型由插件声明了健康探测的凭证
+///
+/// 状态变化经凭证池事件总线以 `HealthChanged` 推送到前端（provider-pool-event）。
+pub fn spawn_plugin_health_check_scheduler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(PLUGIN_HEALTH_CHECK_TICK_SECS));
+        let mut last_run = Instant::now();
+        loop {
+            ticker.tick().await;
+            let (Some(app_state), Some(db), Some(pool_service), Some(installer)) = (
+                app_handle.try_state::<AppState>(),
+                app_handle.try_state::<DbConnection>(),
+                app_handle.try_state::<ProviderPoolServiceState>(),
+                app_handle.try_state::<PluginInstallerState>(),
+            ) else {
+                continue;
+            };
+            let settings = app_state.read().await.config.plugin_health_check.clone();
+            let interval_minutes = settings
+                .interval_minutes
+                .max(MIN_PLUGIN_HEALTH_CHECK_INTERVAL_MINUTES);
+            if !settings.enabled || last_run.elapsed() < Duration::from_secs(interval_minutes * 60)
+            {
+                continue;
+            }
+            last_run = Instant::now();
+
+            sync_plugin_health_probes(&installer, &pool_service.0).await;
+            match pool_service.0.check_plugin_credentials_health(&db).await {
+                Ok(results) if !results.is_empty() => tracing::info!(
+                    "[健康检查] 插件凭证定时检查完成：{} 个凭证，{} 个失败",
+                    results.len(),
+                    results.iter().filter(|r| !r.success).count()
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("[健康检查] 插件凭证定时检查失败: {}", e),
+            }
+        }
+    });
+}
+
+/// OAuth Token 后台刷新的调度间隔（秒）
+const TOKEN_REFRESH_TICK_SECS: u64 = 60;
+
+/// 定时刷新即将过期的 OAuth Token
+///
+/// 按 `token_refresh.scan_interval_minutes` 扫描凭证池缓存 Token 的过期时间，
+/// 刷新 `refresh_window_minutes` 内过期的凭证，请求路径无需再等待懒刷新。
+pub fn spawn_token_refresh_scheduler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(TOKEN_REFRESH_TICK_SECS));
+        let mut last_run: Option<Instant> = None;
+        loop {
+            ticker.tick().await;
+            let (Some(app_state), Some(db), Some(token_cache)) = (
+                app_handle.try_state::<AppState>(),
+                app_handle.try_state::<DbConnection>(),
+                app_handle.try_state::<TokenCacheServiceState>(),
+            ) else {
+                continue;
+            };
+            let settings = app_state.read().await.config.token_refresh.clone();
+            let interval = Duration::from_secs(settings.scan_interval_minutes.max(1) * 60);
+            let due = match last_run {
+                Some(last_run) => last_run.elapsed() >= interval,
+                None => true,
+            };
+            if !settings.enabled || !due {
+                continue;
+            }
+            last_run = Some(Instant::now());
+
+            match token_cache.0.refresh_expiring_tokens(&db, &settings).await {
+                Ok(report) if report.due > 0 => {
+                    tracing::info!(
+                        "[TOKEN_CACHE] 后台刷新完成：{} 个即将过期，{} 个已刷新，{} 个失败",
+                        report.due,
+                        report.refreshed.len(),
+                        report.failed.len()
+                    );
+                    for failure in &report.failed {
+                        tracing::warn!(
+                            "[TOKEN_CACHE] 后台刷新凭证 {} 失败（{:?}，尝试 {} 次）: {}",
+                            failure.uuid,
+                            failure.error_type,
+                            failure.attempts,
+                            failure.error
+                        );
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("[TOKEN_CACHE] 后台刷新扫描失败: {}", e),
+            }
+        }
+    });
+}
+
+/// 检测单个凭证自定义 base_url 的可达性（不检查凭证有效性）
+#[tauri::command]
+pub async fn check_provider_pool_credential_endpoints(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+    uuid: String,
+) -> Result<Option<CredentialEndpointHealth>, String> {
+    pool_service.0.check_credential_endpoints(&db, &uuid).await
+}
+
+/// 检测所有启用凭证的自定义 base_url 可达性，返回 uuid -> 端点状态
+#[tauri::command]
+pub async fn check_provider_pool_endpoints(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+) -> Result<HashMap<String, CredentialEndpointHealth>, String> {
+    pool_service.0.check_all_endpoints(&db).await
+}
+
 /// 添加 Kiro OAuth 凭证（通过文件路径）
 #[tauri::command]
 pub fn add_kiro_oauth_credential(
@@ -1001,6 +1284,24 @@ pub fn add_claude_oauth_credential(
     )
 }
 
+/// 添加测试用 Mock 凭证
+#[tauri::command]
+pub fn add_mock_credential(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+    config: MockProviderConfig,
+    name: Option<String>,
+) -> Result<ProviderCredential, String> {
+    pool_service.0.add_credential(
+        &db,
+        "mock",
+        CredentialData::Mock { config },
+        name,
+        Some(false),
+        None,
+    )
+}
+
 /// 刷新凭证的 OAuth Token
 #[tauri::command]
 pub async fn refresh_pool_credential_token(
@@ -1229,6 +1530,48 @@ pub fn migrate_private_config_to_pool(
     })
 }
 
+/// 从 YAML 配置批量导入凭证池
+///
+/// 并发校验凭证并逐个写入数据库，通过 `credential-import:progress` 事件上报进度；
+/// 中途中断后再次调用会从上次进度继续。
+#[tauri::command]
+pub async fn import_credentials_from_config(
+    app: tauri::AppHandle,
+    db: State<'_, DbConnection>,
+    global_config: State<'_, crate::config::GlobalConfigManagerState>,
+) -> Result<CredentialImportReport, String> {
+    let config_manager = crate::config::ConfigManager::with_config(
+        global_config.config(),
+        global_config.config_path().clone(),
+    );
+    let sync_service = CredentialSyncService::new(Arc::new(std::sync::RwLock::new(config_manager)));
+    let db = db.inner().clone();
+
+    sync_service
+        .import_from_config(
+            DEFAULT_IMPORT_CONCURRENCY,
+            |cred| {
+                let conn = db.lock().map_err(|e| e.to_string())?;
+                ProviderPoolDao::upsert(&conn, cred).map_err(|e| e.to_string())
+            },
+            |progress| {
+                let _ = app.emit(CREDENTIAL_IMPORT_PROGRESS_EVENT, progress);
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 获取启动时旧版 config.yaml 凭证迁移的报告
+///
+/// 返回 None 表示没有需要迁移的旧版凭证。
+#[tauri::command]
+pub fn get_legacy_credential_migration_report() -> Option<LegacyCredentialMigrationReport> {
+    LegacyCredentialMigrationReport::load(&lime_core::app_paths::best_effort_app_data_file(
+        LEGACY_CREDENTIAL_MIGRATION_REPORT_FILE,
+    ))
+}
+
 /// 迁移结果响应
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct MigrationResultResponse {
@@ -1357,109 +1700,6 @@ pub async fn start_antigravity_oauth_login(
     Ok(credential)
 }
 
-/// Codex OAuth 授权 URL 响应
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-pub struct CodexAuthUrlResponse {
-    pub auth_url: String,
-}
-
-/// 获取 Codex OAuth 授权 URL 并等待回调（不自动打开浏览器）
-///
-/// 启动服务器后通过事件发送授权 URL，然后等待回调
-/// 成功后返回凭证
-#[tauri::command]
-pub async fn get_codex_auth_url_and_wait(
-    app: tauri::AppHandle,
-    db: State<'_, DbConnection>,
-    pool_service: State<'_, ProviderPoolServiceState>,
-    name: Option<String>,
-) -> Result<ProviderCredential, String> {
-    use crate::providers::codex;
-
-    tracing::info!("[Codex OAuth] 启动服务器并获取授权 URL");
-
-    // 启动服务器并获取授权 URL
-    let (auth_url, wait_future) = codex::start_codex_oauth_server_and_get_url()
-        .await
-        .map_err(|e| format!("启动 OAuth 服务器失败: {e}"))?;
-
-    tracing::info!("[Codex OAuth] 授权 URL: {}", auth_url);
-
-    // 通过事件发送授权 URL 给前端
-    let _ = app.emit(
-        "codex-auth-url",
-        CodexAuthUrlResponse {
-            auth_url: auth_url.clone(),
-        },
-    );
-
-    // 等待回调
-    let result = wait_future.await.map_err(|e| e.to_string())?;
-
-    tracing::info!(
-        "[Codex OAuth] 登录成功，凭证保存到: {}",
-        result.creds_file_path
-    );
-
-    // 添加到凭证池
-    let credential = pool_service.0.add_credential(
-        &db,
-        "codex",
-        CredentialData::CodexOAuth {
-            creds_file_path: result.creds_file_path,
-            api_base_url: None,
-        },
-        name,
-        Some(true),
-        None,
-    )?;
-
-    tracing::info!("[Codex OAuth] 凭证已添加到凭证池: {}", credential.uuid);
-
-    Ok(credential)
-}
-
-/// 启动 Codex OAuth 登录流程
-///
-/// 打开浏览器让用户登录 OpenAI 账号，获取 Codex 凭证
-#[tauri::command]
-pub async fn start_codex_oauth_login(
-    db: State<'_, DbConnection>,
-    pool_service: State<'_, ProviderPoolServiceState>,
-    name: Option<String>,
-) -> Result<ProviderCredential, String> {
-    use crate::providers::codex;
-
-    tracing::info!("[Codex OAuth] 开始 OAuth 登录流程");
-
-    // 启动 OAuth 登录
-    let result = codex::start_codex_oauth_login()
-        .await
-        .map_err(|e| format!("Codex OAuth 登录失败: {e}"))?;
-
-    tracing::info!(
-        "[Codex OAuth] 登录成功，凭证保存到: {}",
-        result.creds_file_path
-    );
-
-    // 添加到凭证池
-    let credential = pool_service.0.add_credential(
-        &db,
-        "codex",
-        CredentialData::CodexOAuth {
-            creds_file_path: result.creds_file_path,
-            api_base_url: None,
-        },
-        name,
-        Some(true),
-        None,
-    )?;
-
-    tracing::info!("[Codex OAuth] 凭证已添加到凭证池: {}", credential.uuid);
-
-    Ok(credential)
-}
-
 /// Claude OAuth 授权 URL 响应
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ClaudeOAuthAuthUrlResponse {
@@ -1865,47 +2105,6 @@ pub async fn exchange_gemini_code(
     Ok(credential)
 }
 
-/// 启动 Gemini OAuth 登录流程
-///
-/// 打开浏览器让用户登录 Google 账号，获取 Gemini 凭证
-#[tauri::command]
-pub async fn start_gemini_oauth_login(
-    db: State<'_, DbConnection>,
-    pool_service: State<'_, ProviderPoolServiceState>,
-    name: Option<String>,
-) -> Result<ProviderCredential, String> {
-    use crate::providers::gemini;
-
-    tracing::info!("[Gemini OAuth] 开始 OAuth 登录流程");
-
-    // 启动 OAuth 登录
-    let result = gemini::start_gemini_oauth_login()
-        .await
-        .map_err(|e| format!("Gemini OAuth 登录失败: {e}"))?;
-
-    tracing::info!(
-        "[Gemini OAuth] 登录成功，凭证保存到: {}",
-        result.creds_file_path
-    );
-
-    // 添加到凭证池
-    let credential = pool_service.0.add_credential(
-        &db,
-        "gemini",
-        CredentialData::GeminiOAuth {
-            creds_file_path: result.creds_file_path,
-            project_id: None,
-        },
-        name,
-        Some(true),
-        None,
-    )?;
-
-    tracing::info!("[Gemini OAuth] 凭证已添加到凭证池: {}", credential.uuid);
-
-    Ok(credential)
-}
-
 // ============ Kiro Builder ID 登录相关命令 ============
 
 /// Kiro Builder ID 登录状态
@@ -3863,3 +4062,61 @@ pub async fn get_all_credential_health(
 ) -> Result<Vec<lime_services::provider_pool_service::CredentialHealthInfo>, String> {
     pool_service.0.get_all_credential_health(&db)
 }
+
+/// 获取凭证数据的加密迁移状态（逐行校验是否仍有明文）
+#[tauri::command]
+pub fn get_credential_encryption_status(
+    db: State<'_, DbConnection>,
+) -> Result<CredentialEncryptionReport, String> {
+    let conn = crate::database::lock_db(&db)?;
+    verify_credential_encryption(&conn)
+}
+
+/// 立即加密所有明文凭证并校验，不再等待首次读取时的惰性迁移
+#[tauri::command]
+pub fn migrate_credential_encryption(
+    db: State<'_, DbConnection>,
+) -> Result<CredentialEncryptionReport, String> {
+    let conn = crate::database::lock_db(&db)?;
+    let report = credential_cipher::migrate_credential_encryption(&conn)?;
+    if !report.is_complete() {
+        tracing::warn!(
+            "[凭证加密] 迁移后仍有 {} 条明文、{} 条无法解密",
+            report.plaintext.len(),
+            report.unreadable.len()
+        );
+    }
+    Ok(report)
+}
+
+/// 列出无法用当前密钥解密的凭证（主密钥丢失后这些凭证不会出现在凭证池中）
+#[tauri::command]
+pub fn get_unreadable_credentials(
+    db: State<'_, DbConnection>,
+) -> Result<Vec<UnreadableCredential>, String> {
+    let conn = crate::database::lock_db(&db)?;
+    list_unreadable_credentials(&conn)
+}
+
+/// 移除无法解密的凭证（按元数据粉碎，不解密），返回被移除凭证的元数据，供从备份重新导入
+#[tauri::command]
+pub fn purge_unreadable_credentials(
+    db: State<'_, DbConnection>,
+    pool_service: State<'_, ProviderPoolServiceState>,
+) -> Result<Vec<UnreadableCredential>, String> {
+    // 钥匙串暂不可用时拒绝，避免删除钥匙串恢复后仍可读的凭证
+    let unreadable = {
+        let conn = crate::database::lock_db(&db)?;
+        credential_cipher::list_purgeable_credentials(&conn)?
+    };
+    for cred in &unreadable {
+        pool_service.0.delete_credential(&db, &cred.uuid)?;
+    }
+    if !unreadable.is_empty() {
+        tracing::warn!(
+            "[凭证加密] 已移除 {} 条无法解密的凭证，需要重新导入",
+            unreadable.len()
+        );
+    }
+    Ok(unreadable)
+}
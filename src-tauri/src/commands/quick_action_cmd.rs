@@ -0,0 +1,263 @@
+//! 快捷操作（命令面板）命令
+//!
+//! - `list_quick_actions`: 从当前状态收集可执行的快捷操作，按查询模糊排序
+//! - `run_quick_action`: 按操作 ID 统一分发执行
+//!
+//! 新增操作只需在此收集候选并在分发中处理，前端面板无需改动。
+
+use crate::agent::AsterAgentState;
+use crate::commands::api_key_provider_cmd::ApiKeyProviderServiceState;
+use crate::commands::model_registry_cmd::ModelRegistryState;
+use crate::commands::provider_pool_cmd::ProviderPoolServiceState;
+use crate::config::GlobalConfigManagerState;
+use crate::database::dao::provider_pool::ProviderPoolDao;
+use crate::database::DbConnection;
+use crate::mcp::McpManagerState;
+use crate::services::quick_action_service::{
+    parse_action_id, rank_quick_actions, QuickAction, QuickActionKind,
+};
+use crate::skills::{execute_named_skill, list_executable_skill_catalog, SkillExecutionRequest};
+use crate::tray::{menu_events, TrayModelSelectedPayload};
+use crate::AppState;
+use lime_services::mcp_service::McpService;
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// 默认返回条数
+const DEFAULT_QUICK_ACTION_LIMIT: usize = 50;
+
+/// 快捷操作执行结果
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickActionOutcome {
+    pub id: String,
+    pub kind: QuickActionKind,
+    /// 面向用户的结果描述
+    pub message: String,
+    /// 操作返回的数据（如 Skill 执行结果）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+/// 列出快捷操作
+#[tauri::command]
+pub async fn list_quick_actions(
+    state: State<'_, AppState>,
+    db: State<'_, DbConnection>,
+    model_registry: State<'_, ModelRegistryState>,
+    mcp_manager: State<'_, McpManagerState>,
+    query: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<QuickAction>, String> {
+    let default_model = state.read().await.config.agent.default_model.clone();
+    let mut actions = Vec::new();
+
+    // 切换默认模型
+    if let Some(service) = model_registry.read().await.as_ref() {
+        let mut seen = HashSet::new();
+        for model in service.get_all_models().await {
+            if model.id == default_model || !seen.insert(model.id.clone()) {
+                continue;
+            }
+            actions.push(
+                QuickAction::new(
+                    QuickActionKind::SwitchDefaultModel,
+                    &model.id,
+                    format!("切换默认模型: {}", model.display_name),
+                )
+                .with_subtitle(format!("{} · {}", model.provider_name, model.id))
+                .with_keywords([model.id, model.provider_id]),
+            );
+        }
+    }
+
+    // 启用 / 禁用凭证
+    let credentials = {
+        let conn = db.lock().map_err(|e| format!("获取数据库连接失败: {e}"))?;
+        ProviderPoolDao::get_all(&conn).map_err(|e| format!("获取凭证列表失败: {e}"))?
+    };
+    for credential in credentials {
+        let provider = credential.provider_type.to_string();
+        let label = credential
+            .name
+            .clone()
+            .unwrap_or_else(|| credential.uuid.chars().take(8).collect());
+        let verb = if credential.is_disabled {
+            "启用"
+        } else {
+            "禁用"
+        };
+        actions.push(
+            QuickAction::new(
+                QuickActionKind::ToggleProvider,
+                &credential.uuid,
+                format!("{verb}凭证: {label}"),
+            )
+            .with_subtitle(provider.clone())
+            .with_keywords([provider, verb.to_string()]),
+        );
+    }
+
+    // 启动 MCP 服务器（已运行的不列出）
+    let running: HashSet<String> = mcp_manager
+        .lock()
+        .await
+        .get_running_servers()
+        .await
+        .into_iter()
+        .collect();
+    for server in McpService::get_all(&db)? {
+        if running.contains(&server.name) {
+            continue;
+        }
+        actions.push(
+            QuickAction::new(
+                QuickActionKind::StartMcpServer,
+                &server.name,
+                format!("启动 MCP 服务器: {}", server.name),
+            )
+            .with_subtitle(server.description.unwrap_or_default()),
+        );
+    }
+
+    // 执行 Skill（目录不可用时跳过）
+    for skill in list_executable_skill_catalog().unwrap_or_default() {
+        actions.push(
+            QuickAction::new(
+                QuickActionKind::RunSkill,
+                &skill.name,
+                format!("执行 Skill: {}", skill.display_name),
+            )
+            .with_subtitle(skill.description)
+            .with_keywords([skill.name])
+            .with_input("输入内容"),
+        );
+    }
+
+    Ok(rank_quick_actions(
+        actions,
+        query.as_deref().unwrap_or_default(),
+        limit.unwrap_or(DEFAULT_QUICK_ACTION_LIMIT),
+    ))
+}
+
+/// 执行快捷操作
+#[tauri::command]
+pub async fn run_quick_action(
+    app_handle: AppHandle,
+    id: String,
+    input: Option<String>,
+) -> Result<QuickActionOutcome, String> {
+    let (kind, target) = parse_action_id(&id)?;
+    let target = target.to_string();
+    tracing::info!("[QUICK_ACTION] 执行快捷操作: {}", id);
+
+    let (message, data) = match kind {
+        QuickActionKind::SwitchDefaultModel => {
+            // 只允许切换到模型注册表中的模型（同名模型取列表中首个，与候选收集一致）
+            let model = {
+                let model_registry = app_handle.state::<ModelRegistryState>();
+                let registry = model_registry.read().await;
+                let service = registry.as_ref().ok_or("模型注册表尚未初始化")?;
+                service
+                    .get_all_models()
+                    .await
+                    .into_iter()
+                    .find(|model| model.id == target)
+                    .ok_or_else(|| format!("模型不存在: {target}"))?
+            };
+
+            let state = app_handle.state::<AppState>();
+            let config_manager = app_handle.state::<GlobalConfigManagerState>();
+            let config = {
+                let mut s = state.write().await;
+                s.config.agent.default_model = model.id.clone();
+                s.config.clone()
+            };
+            config_manager.0.save_config(&config).await?;
+
+            // 聊天界面按前端保存的 Provider / 模型偏好选择模型，沿用托盘切换模型事件同步
+            let payload = TrayModelSelectedPayload {
+                provider_type: model.provider_id.clone(),
+                model: model.id.clone(),
+            };
+            if let Err(e) = app_handle.emit(menu_events::MODEL_SELECTED, payload) {
+                tracing::warn!("[QUICK_ACTION] 发送模型切换事件失败: {}", e);
+            }
+            (format!("默认模型已切换为 {}", model.display_name), None)
+        }
+        QuickActionKind::ToggleProvider => {
+            let db = app_handle.state::<DbConnection>();
+            let pool_service = app_handle.state::<ProviderPoolServiceState>();
+            let is_disabled = {
+                let conn = db.lock().map_err(|e| format!("获取数据库连接失败: {e}"))?;
+                ProviderPoolDao::get_by_uuid(&conn, &target)
+                    .map_err(|e| format!("获取凭证失败: {e}"))?
+                    .ok_or_else(|| format!("凭证不存在: {target}"))?
+                    .is_disabled
+            };
+            let credential = pool_service.0.update_credential(
+                &db,
+                &target,
+                None,
+                Some(!is_disabled),
+                None,
+                None,
+                None,
+                None,
+                None,
+                None,
+            )?;
+            let label = credential.name.unwrap_or(credential.uuid);
+            let message = if credential.is_disabled {
+                format!("已禁用凭证 {label}")
+            } else {
+                format!("已启用凭证 {label}")
+            };
+            (message, None)
+        }
+        QuickActionKind::StartMcpServer => {
+            crate::commands::mcp_cmd::mcp_start_server(
+                app_handle.state::<DbConnection>(),
+                app_handle.state::<McpManagerState>(),
+                target.clone(),
+            )
+            .await?;
+            (format!("MCP 服务器 {target} 已启动"), None)
+        }
+        QuickActionKind::RunSkill => {
+            let user_input = input
+                .filter(|value| !value.trim().is_empty())
+                .ok_or_else(|| "执行 Skill 需要输入内容".to_string())?;
+            let result = execute_named_skill(
+                &app_handle,
+                app_handle.state::<DbConnection>().inner(),
+                app_handle.state::<ApiKeyProviderServiceState>().inner(),
+                app_handle.state::<GlobalConfigManagerState>().inner(),
+                app_handle.state::<AsterAgentState>().inner(),
+                SkillExecutionRequest {
+                    skill_name: target.clone(),
+                    user_input,
+                    provider_override: None,
+                    model_override: None,
+                    execution_id: None,
+                    session_id: None,
+                },
+            )
+            .await?;
+            let message = if result.success {
+                format!("Skill {target} 执行完成")
+            } else {
+                format!("Skill {target} 执行失败")
+            };
+            (message, serde_json::to_value(&result).ok())
+        }
+    };
+
+    Ok(QuickActionOutcome {
+        id,
+        kind,
+        message,
+        data,
+    })
+}
@@ -0,0 +1,45 @@
+//! 错误快速修复建议命令
+
+use crate::AppState;
+use lime_server::middleware::quick_fix::ProviderQuickFixEvent;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+
+/// 快速修复建议事件名（请求失败且有可执行建议时推送）
+pub const PROVIDER_QUICK_FIX_EVENT: &str = "provider-quick-fix";
+
+/// 默认返回的建议数
+const DEFAULT_QUICK_FIX_LIMIT: usize = 20;
+
+/// 获取最近的快速修复建议（最新在前）
+#[tauri::command]
+pub async fn get_recent_provider_quick_fixes(
+    state: tauri::State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<Vec<ProviderQuickFixEvent>, String> {
+    let s = state.read().await;
+    Ok(s.quick_fixes
+        .recent(limit.unwrap_or(DEFAULT_QUICK_FIX_LIMIT)))
+}
+
+/// 将快速修复建议转发到前端
+pub fn spawn_provider_quick_fix_forwarder(
+    app_handle: AppHandle,
+    mut receiver: broadcast::Receiver<ProviderQuickFixEvent>,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    if let Err(e) = app_handle.emit(PROVIDER_QUICK_FIX_EVENT, &event) {
+                        tracing::warn!("[QUICK_FIX] 发送事件失败: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("[QUICK_FIX] 事件转发滞后，丢弃 {} 条", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
@@ -0,0 +1,87 @@
+//! 凭证配额预测与剩余配额命令
+
+use crate::config::save_config;
+use crate::AppState;
+use lime_core::config::CredentialQuotaLimit;
+use lime_credential::{QuotaForecast, QuotaRemaining};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+
+/// 剩余配额变化事件名
+pub const QUOTA_REMAINING_EVENT: &str = "quota-remaining-event";
+
+/// 获取各凭证的用量预测（按用量比例降序）
+#[tauri::command]
+pub async fn get_quota_forecasts(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<QuotaForecast>, String> {
+    let s = state.read().await;
+    Ok(s.quota_manager.forecasts())
+}
+
+/// 获取各凭证的剩余配额估算（按剩余比例升序）
+#[tauri::command]
+pub async fn get_quota_remaining(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<QuotaRemaining>, String> {
+    let s = state.read().await;
+    Ok(s.quota_manager.remaining_estimates())
+}
+
+/// 设置凭证的已知配额（为空时移除）
+#[tauri::command]
+pub async fn set_credential_quota_limit(
+    state: tauri::State<'_, AppState>,
+    credential_id: String,
+    limit: Option<CredentialQuotaLimit>,
+) -> Result<(), String> {
+    if let Some(limit) = &limit {
+        if limit.window_secs == 0 {
+            return Err("配额窗口必须大于 0 秒".to_string());
+        }
+        if limit.max_requests.is_none() && limit.max_tokens.is_none() {
+            return Err("请至少设置请求数或 Token 配额".to_string());
+        }
+    }
+
+    let mut s = state.write().await;
+    match limit {
+        Some(limit) => {
+            s.config
+                .quota_exceeded
+                .credential_limits
+                .insert(credential_id, limit);
+        }
+        None => {
+            s.config
+                .quota_exceeded
+                .credential_limits
+                .remove(&credential_id);
+        }
+    }
+    save_config(&s.config).map_err(|e| e.to_string())?;
+    s.quota_manager.reload_soft_limits(&s.config.quota_exceeded);
+    Ok(())
+}
+
+/// 将剩余配额变化事件转发到前端
+pub fn spawn_quota_remaining_event_forwarder(
+    app_handle: AppHandle,
+    mut receiver: broadcast::Receiver<QuotaRemaining>,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(remaining) => {
+                    if let Err(e) = app_handle.emit(QUOTA_REMAINING_EVENT, &remaining) {
+                        tracing::warn!("[QUOTA] 发送剩余配额事件失败: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!("[QUOTA] 剩余配额事件转发滞后，丢弃 {} 条", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
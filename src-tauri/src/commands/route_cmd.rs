@@ -46,6 +46,11 @@ pub async fn get_available_routes(
                 protocol: "openai".to_string(),
                 url: format!("{base_url}/v1/chat/completions"),
             },
+            crate::models::route_model::RouteEndpoint {
+                path: "/v1/responses".to_string(),
+                protocol: "openai".to_string(),
+                url: format!("{base_url}/v1/responses"),
+            },
         ],
         tags: vec!["默认".to_string()],
         enabled: true,
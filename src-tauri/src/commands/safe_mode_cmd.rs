@@ -0,0 +1,11 @@
+//! 安全模式命令
+
+use lime_core::safe_mode::SafeModeReport;
+
+/// 获取本次启动的安全模式报告
+///
+/// 前端据此提示用户当前处于安全模式，并展示可疑原因。
+#[tauri::command]
+pub fn get_safe_mode_report() -> SafeModeReport {
+    lime_core::safe_mode::safe_mode_report()
+}
@@ -1,7 +1,15 @@
 //! 安全与性能配置命令
 
 use crate::config::save_config;
+use crate::database::dao::api_key_rate_limit::{
+    api_key_fingerprint, ApiKeyRateLimitDao, ApiKeyRateLimitOverride,
+};
+use crate::database::{lock_db, DbConnection};
 use crate::AppState;
+use lime_core::cpu_pool::{cpu_pool_stats, CpuPoolStats};
+use lime_server::middleware::api_key_rate_limit::TokenBucketLimit;
+use lime_server::middleware::degenerate_response::DegenerateProviderStats;
+use lime_server::middleware::system_prompt_guard::GuardInjectionRecord;
 use serde::{Deserialize, Serialize};
 
 // ========== 速率限制 ==========
@@ -38,6 +46,174 @@ pub async fn update_rate_limit_config(
     save_config(&s.config).map_err(|e| e.to_string())
 }
 
+// ========== 按 API Key 限流 ==========
+
+#[tauri::command]
+pub async fn get_api_key_rate_limit_config(
+    state: tauri::State<'_, AppState>,
+) -> Result<lime_core::config::ApiKeyRateLimitSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.api_key_rate_limit.clone())
+}
+
+/// 更新按 API Key 限流的全局默认值（立即生效）
+#[tauri::command]
+pub async fn update_api_key_rate_limit_config(
+    state: tauri::State<'_, AppState>,
+    config: lime_core::config::ApiKeyRateLimitSettings,
+) -> Result<(), String> {
+    let mut s = state.write().await;
+    s.api_key_rate_limiter.reload(&config);
+    s.config.api_key_rate_limit = config;
+    save_config(&s.config).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn list_api_key_rate_limit_overrides(
+    db: tauri::State<'_, DbConnection>,
+) -> Result<Vec<ApiKeyRateLimitOverride>, String> {
+    let conn = lock_db(&db)?;
+    ApiKeyRateLimitDao::list(&conn).map_err(|e| e.to_string())
+}
+
+/// 为单个 API Key 设置令牌桶覆盖（数据库只保存 Key 指纹）
+#[tauri::command]
+pub async fn set_api_key_rate_limit_override(
+    state: tauri::State<'_, AppState>,
+    db: tauri::State<'_, DbConnection>,
+    api_key: String,
+    label: Option<String>,
+    requests_per_minute: u32,
+    burst: u32,
+) -> Result<ApiKeyRateLimitOverride, String> {
+    if api_key.trim().is_empty() {
+        return Err("API Key 不能为空".to_string());
+    }
+    if requests_per_minute == 0 || burst == 0 {
+        return Err("每分钟请求数与突发上限必须大于 0".to_string());
+    }
+    let record = ApiKeyRateLimitOverride {
+        key_fingerprint: api_key_fingerprint(api_key.trim()),
+        label: label.filter(|l| !l.trim().is_empty()),
+        requests_per_minute,
+        burst,
+        updated_at: chrono::Utc::now().timestamp(),
+    };
+    {
+        let conn = lock_db(&db)?;
+        ApiKeyRateLimitDao::upsert(&conn, &record).map_err(|e| e.to_string())?;
+    }
+    state.read().await.api_key_rate_limiter.set_override(
+        &record.key_fingerprint,
+        TokenBucketLimit {
+            requests_per_minute,
+            burst,
+        },
+    );
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn delete_api_key_rate_limit_override(
+    state: tauri::State<'_, AppState>,
+    db: tauri::State<'_, DbConnection>,
+    key_fingerprint: String,
+) -> Result<bool, String> {
+    let deleted = {
+        let conn = lock_db(&db)?;
+        ApiKeyRateLimitDao::delete(&conn, &key_fingerprint).map_err(|e| e.to_string())?
+    };
+    state
+        .read()
+        .await
+        .api_key_rate_limiter
+        .remove_override(&key_fingerprint);
+    Ok(deleted)
+}
+
+// ========== 系统提示词护栏 ==========
+
+/// 审计查询默认返回条数
+const DEFAULT_GUARD_AUDIT_LIMIT: usize = 100;
+
+#[tauri::command]
+pub async fn get_system_prompt_guard_config(
+    state: tauri::State<'_, AppState>,
+) -> Result<lime_core::config::SystemPromptGuardSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.system_prompt_guard.clone())
+}
+
+/// 更新系统提示词护栏策略（立即生效）
+#[tauri::command]
+pub async fn update_system_prompt_guard_config(
+    state: tauri::State<'_, AppState>,
+    config: lime_core::config::SystemPromptGuardSettings,
+) -> Result<(), String> {
+    config.validate()?;
+    let mut s = state.write().await;
+    s.system_prompt_guard.reload(&config);
+    s.config.system_prompt_guard = config;
+    save_config(&s.config).map_err(|e| e.to_string())
+}
+
+/// 获取最近的护栏注入记录（新的在前）
+#[tauri::command]
+pub async fn get_system_prompt_guard_audit(
+    state: tauri::State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<Vec<GuardInjectionRecord>, String> {
+    let s = state.read().await;
+    Ok(s.system_prompt_guard
+        .recent_injections(limit.unwrap_or(DEFAULT_GUARD_AUDIT_LIMIT)))
+}
+
+#[tauri::command]
+pub async fn clear_system_prompt_guard_audit(
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state.read().await.system_prompt_guard.clear_audit();
+    Ok(())
+}
+
+// ========== 退化响应重试 ==========
+
+#[tauri::command]
+pub async fn get_degenerate_retry_config(
+    state: tauri::State<'_, AppState>,
+) -> Result<lime_core::config::DegenerateRetrySettings, String> {
+    let s = state.read().await;
+    Ok(s.config.degenerate_retry.clone())
+}
+
+/// 更新退化响应检测配置（立即生效）
+#[tauri::command]
+pub async fn update_degenerate_retry_config(
+    state: tauri::State<'_, AppState>,
+    config: lime_core::config::DegenerateRetrySettings,
+) -> Result<(), String> {
+    let mut s = state.write().await;
+    s.degenerate_detector.reload(&config);
+    s.config.degenerate_retry = config;
+    save_config(&s.config).map_err(|e| e.to_string())
+}
+
+/// 获取各 Provider 的退化响应统计（按退化率降序）
+#[tauri::command]
+pub async fn get_degenerate_response_stats(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<DegenerateProviderStats>, String> {
+    Ok(state.read().await.degenerate_detector.stats())
+}
+
+#[tauri::command]
+pub async fn reset_degenerate_response_stats(
+    state: tauri::State<'_, AppState>,
+) -> Result<(), String> {
+    state.read().await.degenerate_detector.reset_stats();
+    Ok(())
+}
+
 // ========== 对话管理 ==========
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -72,6 +248,27 @@ pub async fn update_conversation_config(
     save_config(&s.config).map_err(|e| e.to_string())
 }
 
+// ========== 上下文窗口修剪 ==========
+
+#[tauri::command]
+pub async fn get_context_trim_config(
+    state: tauri::State<'_, AppState>,
+) -> Result<lime_core::config::ContextTrimSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.conversation.context_trim.clone())
+}
+
+/// 更新上下文修剪配置（服务器重启后生效）
+#[tauri::command]
+pub async fn update_context_trim_config(
+    state: tauri::State<'_, AppState>,
+    config: lime_core::config::ContextTrimSettings,
+) -> Result<(), String> {
+    let mut s = state.write().await;
+    s.config.conversation.context_trim = config;
+    save_config(&s.config).map_err(|e| e.to_string())
+}
+
 // ========== 提示路由 ==========
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -142,3 +339,11 @@ pub async fn update_pairing_config(
     s.config.pairing.enabled = config.enabled;
     save_config(&s.config).map_err(|e| e.to_string())
 }
+
+// ========== CPU 线程池 ==========
+
+/// 获取 CPU 线程池利用率与各类任务耗时统计
+#[tauri::command]
+pub async fn get_cpu_pool_stats() -> Result<CpuPoolStats, String> {
+    Ok(cpu_pool_stats())
+}
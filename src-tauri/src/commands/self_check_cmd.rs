@@ -0,0 +1,353 @@
+//! 首次运行健康自检命令
+//!
+//! 端到端检查运行环境，返回带修复提示的检查清单供引导页展示：
+//! - 端口：代理服务端口是否可用
+//! - 数据库：主库是否可写
+//! - 钥匙串：系统钥匙串是否可访问（凭证加密依赖）
+//! - 凭证：是否至少有一个可用凭证
+//! - MCP 运行时：已启用 MCP 服务器的命令与运行时是否存在
+//! - 模型文件：本地 Whisper 模型文件是否已下载
+
+use crate::commands::mcp_cmd::parse_server_config;
+use crate::database::dao::api_key_provider::ApiKeyProviderDao;
+use crate::database::dao::provider_pool::ProviderPoolDao;
+use crate::database::{lock_db, DbConnection};
+use crate::mcp::ExecutionEnvironment;
+use crate::AppState;
+use lime_core::config::{AsrCredentialEntry, AsrProviderType};
+use lime_credential::ProviderCredentialCipher;
+use lime_services::mcp_service::McpService;
+use lime_services::voice_asr_service::AsrService;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// 单项检查结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SelfCheckStatus {
+    Pass,
+    /// 不影响基本使用，但相关功能不可用
+    Warn,
+    Fail,
+    /// 未配置相关功能，无需检查
+    Skipped,
+}
+
+/// 检查项
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfCheckItem {
+    /// 检查项标识（port / database / keychain / credentials / mcp_runtime / model_files）
+    pub id: &'static str,
+    pub status: SelfCheckStatus,
+    pub message: String,
+    /// 修复提示
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl SelfCheckItem {
+    fn new(id: &'static str, status: SelfCheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            status,
+            message: message.into(),
+            hint: None,
+        }
+    }
+
+    fn with_hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+}
+
+/// 自检报告
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfCheckReport {
+    /// 没有失败项
+    pub passed: bool,
+    pub items: Vec<SelfCheckItem>,
+}
+
+impl SelfCheckReport {
+    fn new(items: Vec<SelfCheckItem>) -> Self {
+        Self {
+            passed: items.iter().all(|i| i.status != SelfCheckStatus::Fail),
+            items,
+        }
+    }
+}
+
+/// 执行首次运行自检
+#[tauri::command]
+pub async fn run_self_check(
+    state: tauri::State<'_, AppState>,
+    db: tauri::State<'_, DbConnection>,
+) -> Result<SelfCheckReport, String> {
+    let (running, host, port, asr) = {
+        let s = state.read().await;
+        (
+            s.running,
+            s.config.server.host.clone(),
+            s.config.server.port,
+            s.config.credential_pool.asr.clone(),
+        )
+    };
+
+    let mut items = vec![check_port(running, &host, port).await];
+    {
+        let conn = lock_db(&db)?;
+        items.push(check_database(&conn));
+    }
+    items.push(check_keychain().await);
+    {
+        let conn = lock_db(&db)?;
+        items.push(check_credentials(&conn));
+    }
+    items.push(check_mcp_runtime(&db).await);
+    items.push(check_model_files(&asr));
+
+    Ok(SelfCheckReport::new(items))
+}
+
+async fn check_port(running: bool, host: &str, port: u16) -> SelfCheckItem {
+    const ID: &str = "port";
+    if running {
+        return SelfCheckItem::new(
+            ID,
+            SelfCheckStatus::Pass,
+            format!("服务已在 {host}:{port} 运行"),
+        );
+    }
+    match tokio::net::TcpListener::bind((host, port)).await {
+        Ok(_) => SelfCheckItem::new(ID, SelfCheckStatus::Pass, format!("端口 {port} 可用")),
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => SelfCheckItem::new(
+            ID,
+            SelfCheckStatus::Fail,
+            format!("端口 {port} 已被其他程序占用"),
+        )
+        .with_hint("关闭占用该端口的程序，或在「设置 → 服务器」中更换端口"),
+        Err(e) => SelfCheckItem::new(
+            ID,
+            SelfCheckStatus::Fail,
+            format!("无法监听 {host}:{port}: {e}"),
+        )
+        .with_hint("检查监听地址是否为本机地址，或改用 127.0.0.1"),
+    }
+}
+
+/// 在事务中建表后回滚，验证主库可写
+fn check_database(conn: &Connection) -> SelfCheckItem {
+    const ID: &str = "database";
+    let result = conn.unchecked_transaction().and_then(|tx| {
+        tx.execute_batch("CREATE TABLE __self_check (id INTEGER)")?;
+        tx.rollback()
+    });
+    match result {
+        Ok(()) => SelfCheckItem::new(ID, SelfCheckStatus::Pass, "数据库可写"),
+        Err(e) => SelfCheckItem::new(ID, SelfCheckStatus::Fail, format!("数据库不可写: {e}"))
+            .with_hint("检查数据目录的写入权限与剩余磁盘空间"),
+    }
+}
+
+async fn check_keychain() -> SelfCheckItem {
+    const ID: &str = "keychain";
+    match tokio::task::spawn_blocking(ProviderCredentialCipher::probe_keychain).await {
+        Ok(Ok(true)) => SelfCheckItem::new(ID, SelfCheckStatus::Pass, "系统钥匙串可访问"),
+        Ok(Ok(false)) => SelfCheckItem::new(
+            ID,
+            SelfCheckStatus::Pass,
+            "系统钥匙串可访问，首次保存凭证时将生成主密钥",
+        ),
+        Ok(Err(e)) => SelfCheckItem::new(ID, SelfCheckStatus::Fail, e.to_string()).with_hint(
+            "允许应用访问系统钥匙串；Linux 需安装并解锁 Secret Service（如 gnome-keyring）",
+        ),
+        Err(e) => SelfCheckItem::new(ID, SelfCheckStatus::Fail, format!("钥匙串检查失败: {e}")),
+    }
+}
+
+/// 凭证池中健康且未禁用的凭证，或启用了 API Key 的 Provider
+fn check_credentials(conn: &Connection) -> SelfCheckItem {
+    const ID: &str = "credentials";
+    let pool = match ProviderPoolDao::get_all(conn) {
+        Ok(credentials) => credentials,
+        Err(e) => {
+            return SelfCheckItem::new(ID, SelfCheckStatus::Fail, format!("读取凭证池失败: {e}"))
+        }
+    };
+    let api_key_providers = match ApiKeyProviderDao::get_enabled_providers_with_keys(conn) {
+        Ok(providers) => providers.len(),
+        Err(e) => {
+            return SelfCheckItem::new(
+                ID,
+                SelfCheckStatus::Fail,
+                format!("读取 API Key Provider 失败: {e}"),
+            )
+        }
+    };
+
+    let enabled = pool.iter().filter(|c| !c.is_disabled).count();
+    let healthy = pool
+        .iter()
+        .filter(|c| !c.is_disabled && c.is_healthy)
+        .count();
+    if healthy + api_key_providers > 0 {
+        return SelfCheckItem::new(
+            ID,
+            SelfCheckStatus::Pass,
+            format!("{healthy} 个健康凭证，{api_key_providers} 个已配置 API Key 的 Provider"),
+        );
+    }
+    if enabled > 0 {
+        return SelfCheckItem::new(
+            ID,
+            SelfCheckStatus::Fail,
+            format!("{enabled} 个凭证均不健康"),
+        )
+        .with_hint("在「凭证池」中刷新 Token 或重新授权，然后执行健康检查");
+    }
+    SelfCheckItem::new(ID, SelfCheckStatus::Fail, "尚未配置任何凭证")
+        .with_hint("在「凭证池」中添加 OAuth 凭证，或在「API Key Provider」中填写 API Key")
+}
+
+/// MCP 为可选功能，运行时缺失只给出警告
+async fn check_mcp_runtime(db: &DbConnection) -> SelfCheckItem {
+    const ID: &str = "mcp_runtime";
+    let servers = match McpService::get_all(db) {
+        Ok(servers) => servers,
+        Err(e) => {
+            return SelfCheckItem::new(ID, SelfCheckStatus::Warn, format!("读取 MCP 配置失败: {e}"))
+        }
+    };
+
+    let mut checked = 0;
+    let mut failures = Vec::new();
+    for server in servers.iter().filter(|s| s.enabled_lime) {
+        let config = parse_server_config(&server.server_config);
+        let diagnostic = ExecutionEnvironment::for_config(&config)
+            .diagnose(&config)
+            .await;
+        checked += 1;
+        if !diagnostic.is_ok() {
+            failures.push((server.name.clone(), diagnostic));
+        }
+    }
+
+    if checked == 0 {
+        return SelfCheckItem::new(ID, SelfCheckStatus::Skipped, "未启用 MCP 服务器");
+    }
+    let Some((_, first)) = failures.first() else {
+        return SelfCheckItem::new(
+            ID,
+            SelfCheckStatus::Pass,
+            format!("{checked} 个 MCP 服务器执行环境正常"),
+        );
+    };
+    let names: Vec<&str> = failures.iter().map(|(name, _)| name.as_str()).collect();
+    let item = SelfCheckItem::new(
+        ID,
+        SelfCheckStatus::Warn,
+        format!(
+            "{} 个 MCP 服务器无法启动（{}）：{}",
+            failures.len(),
+            names.join("、"),
+            first.message
+        ),
+    );
+    match &first.suggestion {
+        Some(suggestion) => item.with_hint(suggestion.clone()),
+        None => item,
+    }
+}
+
+/// 已启用的本地 Whisper 凭证对应的模型文件
+fn check_model_files(asr: &[AsrCredentialEntry]) -> SelfCheckItem {
+    const ID: &str = "model_files";
+    let mut expected = Vec::new();
+    for entry in asr
+        .iter()
+        .filter(|e| !e.disabled && e.provider == AsrProviderType::WhisperLocal)
+    {
+        let config = entry.whisper_config.clone().unwrap_or_default();
+        let path = match config.model_path {
+            Some(path) => Ok(PathBuf::from(path)),
+            None => AsrService::whisper_model_path(&config.model),
+        };
+        match path {
+            Ok(path) => expected.push(path),
+            Err(e) => return SelfCheckItem::new(ID, SelfCheckStatus::Warn, e),
+        }
+    }
+
+    if expected.is_empty() {
+        return SelfCheckItem::new(ID, SelfCheckStatus::Skipped, "未启用本地语音识别模型");
+    }
+    let missing: Vec<&PathBuf> = expected.iter().filter(|p| !p.is_file()).collect();
+    if missing.is_empty() {
+        return SelfCheckItem::new(
+            ID,
+            SelfCheckStatus::Pass,
+            format!("{} 个 Whisper 模型文件已就绪", expected.len()),
+        );
+    }
+    let paths: Vec<String> = missing.iter().map(|p| p.display().to_string()).collect();
+    SelfCheckItem::new(
+        ID,
+        SelfCheckStatus::Warn,
+        format!("缺少 Whisper 模型文件：{}", paths.join("、")),
+    )
+    .with_hint("下载对应的 ggml 模型文件到上述路径，或改用云端语音识别服务")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::schema::create_tables;
+    use lime_core::config::{WhisperLocalConfig, WhisperModelSize};
+
+    #[test]
+    fn test_check_database_and_credentials() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        assert_eq!(check_database(&conn).status, SelfCheckStatus::Pass);
+        let exists: bool = conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE name = '__self_check')",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert!(!exists);
+
+        let credentials = check_credentials(&conn);
+        assert_eq!(credentials.status, SelfCheckStatus::Fail);
+        assert!(credentials.hint.is_some());
+        assert!(!SelfCheckReport::new(vec![credentials]).passed);
+    }
+
+    #[test]
+    fn test_check_model_files() {
+        assert_eq!(check_model_files(&[]).status, SelfCheckStatus::Skipped);
+
+        let entry = AsrCredentialEntry {
+            id: "whisper".to_string(),
+            provider: AsrProviderType::WhisperLocal,
+            name: None,
+            is_default: true,
+            disabled: false,
+            language: "zh".to_string(),
+            whisper_config: Some(WhisperLocalConfig {
+                model: WhisperModelSize::Base,
+                model_path: Some("/nonexistent/ggml-base.bin".to_string()),
+            }),
+            xunfei_config: None,
+            baidu_config: None,
+            openai_config: None,
+        };
+        let item = check_model_files(&[entry]);
+        assert_eq!(item.status, SelfCheckStatus::Warn);
+        assert!(item.message.contains("/nonexistent/ggml-base.bin"));
+    }
+}
@@ -625,28 +625,37 @@ pub async fn install_skill_for_app(
         .find(|s| s.directory == directory)
         .ok_or_else(|| format!("Skill not found: {directory}"))?;
 
-    let repo_owner = skill
-        .repo_owner
-        .as_ref()
-        .ok_or_else(|| "Missing repo owner".to_string())?
-        .clone();
-    let repo_name = skill
-        .repo_name
-        .as_ref()
-        .ok_or_else(|| "Missing repo name".to_string())?
-        .clone();
-    let repo_branch = skill
-        .repo_branch
-        .as_ref()
-        .ok_or_else(|| "Missing repo branch".to_string())?
-        .clone();
-
-    // 安装技能
-    skill_service
-        .0
-        .install_skill(&app_type, &repo_owner, &repo_name, &repo_branch, &directory)
-        .await
-        .map_err(|e| e.to_string())?;
+    if let Some(registry_id) = &skill.registry_id {
+        // 来自扩展注册表：按索引中的地址与校验和安装
+        skill_service
+            .0
+            .install_registry_skill(&app_type, registry_id, &directory)
+            .await
+            .map_err(|e| format!("{e:#}"))?;
+    } else {
+        let repo_owner = skill
+            .repo_owner
+            .as_ref()
+            .ok_or_else(|| "Missing repo owner".to_string())?
+            .clone();
+        let repo_name = skill
+            .repo_name
+            .as_ref()
+            .ok_or_else(|| "Missing repo name".to_string())?
+            .clone();
+        let repo_branch = skill
+            .repo_branch
+            .as_ref()
+            .ok_or_else(|| "Missing repo branch".to_string())?
+            .clone();
+
+        // 安装技能
+        skill_service
+            .0
+            .install_skill(&app_type, &repo_owner, &repo_name, &repo_branch, &directory)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
 
     // 更新数据库
     let key = get_skill_key(&app_type, &directory);
@@ -1,13 +1,17 @@
 //! 遥测命令模块
 //!
-//! 提供请求日志、统计数据和 Token 追踪的 Tauri 命令
+//! 提供请求日志、统计数据、Token 追踪、采样请求追踪、请求分享包和匿名用量统计的 Tauri 命令
 
+use crate::config::save_config;
 use crate::telemetry::{
     ModelStats, ModelTokenStats, ProviderStats, ProviderTokenStats, RequestLog, RequestLogger,
-    RequestStatus, StatsAggregator, StatsSummary, TimeRange, TokenStatsSummary, TokenTracker,
+    RequestShareBundle, RequestStatus, SampledTrace, StatsAggregator, StatsSummary, TimeRange,
+    TokenStatsSummary, TokenTracker, TraceQuery, UsageAnalyticsSummary, UsageCounters,
 };
+use crate::AppState;
 use crate::ProviderType;
 use chrono::{DateTime, Utc};
+use lime_core::config::{TraceSamplingSettings, UsageAnalyticsSettings};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -284,3 +288,146 @@ pub async fn get_token_stats_by_day(
     let tokens = state.tokens.read();
     Ok(tokens.by_day(days.unwrap_or(7)))
 }
+
+// ========== 请求追踪命令 ==========
+
+/// 获取尾部采样保留的请求追踪（最新在前）
+#[tauri::command]
+pub async fn get_request_traces(
+    state: tauri::State<'_, AppState>,
+    query: Option<TraceQuery>,
+) -> Result<Vec<SampledTrace>, String> {
+    let s = state.read().await;
+    Ok(s.trace_sampler.list(&query.unwrap_or_default()))
+}
+
+/// 按请求 ID 获取追踪详情
+#[tauri::command]
+pub async fn get_request_trace(
+    state: tauri::State<'_, AppState>,
+    request_id: String,
+) -> Result<Option<SampledTrace>, String> {
+    let s = state.read().await;
+    Ok(s.trace_sampler.get(&request_id))
+}
+
+/// 导出单个请求的脱敏分享包
+///
+/// 合并请求日志与采样追踪（含开启 `logging.include_request_body` 时记录的请求体），
+/// 脱敏后返回，可直接粘贴到问题反馈或分享给他人。
+#[tauri::command]
+pub async fn export_request_share_bundle(
+    telemetry: tauri::State<'_, TelemetryState>,
+    state: tauri::State<'_, AppState>,
+    request_id: String,
+) -> Result<RequestShareBundle, String> {
+    let log = telemetry.logger.get_by_id(&request_id);
+    let trace = state.read().await.trace_sampler.get(&request_id);
+    let sanitizer = lime_core::sanitizer::CredentialSanitizer::with_defaults();
+    RequestShareBundle::build(log.as_ref(), trace.as_ref(), &sanitizer)
+        .ok_or_else(|| format!("未找到请求 {request_id} 的日志或追踪"))
+}
+
+/// 清空保留的请求追踪
+#[tauri::command]
+pub async fn clear_request_traces(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let s = state.read().await;
+    s.trace_sampler.clear()
+}
+
+/// 获取尾部采样配置
+#[tauri::command]
+pub async fn get_trace_sampling_settings(
+    state: tauri::State<'_, AppState>,
+) -> Result<TraceSamplingSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.trace_sampling.clone())
+}
+
+/// 更新尾部采样配置
+#[tauri::command]
+pub async fn update_trace_sampling_settings(
+    state: tauri::State<'_, AppState>,
+    settings: TraceSamplingSettings,
+) -> Result<(), String> {
+    let rates = [
+        settings.error_sample_rate,
+        settings.slow_sample_rate,
+        settings.success_sample_rate,
+    ];
+    if rates.iter().any(|rate| !(0.0..=1.0).contains(rate)) {
+        return Err("采样率必须在 [0, 1] 范围内".to_string());
+    }
+
+    let mut s = state.write().await;
+    s.config.trace_sampling = settings;
+    save_config(&s.config).map_err(|e| e.to_string())?;
+    s.trace_sampler.reload(&s.config.trace_sampling);
+    Ok(())
+}
+
+// ========== 匿名用量统计命令 ==========
+
+/// 获取匿名用量统计配置
+#[tauri::command]
+pub async fn get_usage_analytics_settings(
+    state: tauri::State<'_, AppState>,
+) -> Result<UsageAnalyticsSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.usage_analytics.clone())
+}
+
+/// 更新匿名用量统计配置（开启/关闭、隐私预算）
+#[tauri::command]
+pub async fn update_usage_analytics_settings(
+    state: tauri::State<'_, AppState>,
+    settings: UsageAnalyticsSettings,
+) -> Result<(), String> {
+    if !(settings.epsilon.is_finite() && settings.epsilon > 0.0) {
+        return Err("epsilon 必须为正数".to_string());
+    }
+
+    let mut s = state.write().await;
+    s.config.usage_analytics = settings;
+    save_config(&s.config).map_err(|e| e.to_string())?;
+    s.usage_analytics.reload(&s.config.usage_analytics);
+    Ok(())
+}
+
+/// 记录前端功能使用（未开启统计时忽略）
+#[tauri::command]
+pub async fn record_usage_feature(
+    state: tauri::State<'_, AppState>,
+    feature: String,
+) -> Result<(), String> {
+    let s = state.read().await;
+    if !s.usage_analytics.record_feature(&feature) {
+        return Err(format!("无效的功能名: {feature}"));
+    }
+    Ok(())
+}
+
+/// 获取本地累计的原始用量计数
+#[tauri::command]
+pub async fn get_usage_analytics_counters(
+    state: tauri::State<'_, AppState>,
+) -> Result<UsageCounters, String> {
+    let s = state.read().await;
+    Ok(s.usage_analytics.counters())
+}
+
+/// 导出加噪后的用量摘要
+#[tauri::command]
+pub async fn export_usage_analytics_summary(
+    state: tauri::State<'_, AppState>,
+) -> Result<UsageAnalyticsSummary, String> {
+    let s = state.read().await;
+    Ok(s.usage_analytics.export_summary())
+}
+
+/// 清空本地用量计数
+#[tauri::command]
+pub async fn clear_usage_analytics(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    let s = state.read().await;
+    s.usage_analytics.clear()
+}
@@ -0,0 +1,102 @@
+//! 多租户命名空间管理命令
+
+use crate::config::save_config;
+use crate::AppState;
+use lime_core::config::{TenantEntry, TenantSettings};
+use lime_server::middleware::tenant::TenantUsageSnapshot;
+
+/// 获取租户配置
+#[tauri::command]
+pub async fn get_tenant_settings(
+    state: tauri::State<'_, AppState>,
+) -> Result<TenantSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.tenants.clone())
+}
+
+/// 启用/禁用租户隔离
+#[tauri::command]
+pub async fn set_tenants_enabled(
+    state: tauri::State<'_, AppState>,
+    enabled: bool,
+) -> Result<(), String> {
+    let mut s = state.write().await;
+    s.config.tenants.enabled = enabled;
+    apply_tenant_settings(&s)
+}
+
+/// 新增或更新租户（按 ID 匹配）
+#[tauri::command]
+pub async fn upsert_tenant(
+    state: tauri::State<'_, AppState>,
+    tenant: TenantEntry,
+) -> Result<(), String> {
+    let id = tenant.id.trim().to_string();
+    if id.is_empty() {
+        return Err("租户 ID 不能为空".to_string());
+    }
+
+    let mut s = state.write().await;
+    if !s.config.server.api_key.trim().is_empty()
+        && tenant
+            .api_keys
+            .iter()
+            .any(|key| key.trim() == s.config.server.api_key.trim())
+    {
+        return Err("租户 API Key 不能与服务器主 API Key 相同".to_string());
+    }
+    if let Some(conflict) = s.config.tenants.tenants.iter().find(|existing| {
+        existing.id != id
+            && existing
+                .api_keys
+                .iter()
+                .any(|key| tenant.api_keys.iter().any(|k| k.trim() == key.trim()))
+    }) {
+        return Err(format!("API Key 已被租户 {} 使用", conflict.id));
+    }
+
+    let tenant = TenantEntry { id, ..tenant };
+    match s
+        .config
+        .tenants
+        .tenants
+        .iter_mut()
+        .find(|existing| existing.id == tenant.id)
+    {
+        Some(existing) => *existing = tenant,
+        None => s.config.tenants.tenants.push(tenant),
+    }
+    apply_tenant_settings(&s)
+}
+
+/// 删除租户
+#[tauri::command]
+pub async fn delete_tenant(
+    state: tauri::State<'_, AppState>,
+    tenant_id: String,
+) -> Result<bool, String> {
+    let mut s = state.write().await;
+    let before = s.config.tenants.tenants.len();
+    s.config.tenants.tenants.retain(|t| t.id != tenant_id);
+    if s.config.tenants.tenants.len() == before {
+        return Ok(false);
+    }
+    apply_tenant_settings(&s)?;
+    Ok(true)
+}
+
+/// 获取各租户的用量统计（自服务启动以来）
+#[tauri::command]
+pub async fn get_tenant_usage(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<TenantUsageSnapshot>, String> {
+    let s = state.read().await;
+    Ok(s.tenant_registry.usage_snapshots())
+}
+
+/// 保存配置并热更新运行中的租户注册表
+fn apply_tenant_settings(s: &lime_server::ServerState) -> Result<(), String> {
+    save_config(&s.config).map_err(|e| e.to_string())?;
+    s.tenant_registry.reload(&s.config.tenants);
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! 定时用量报告命令
+
+use crate::config::save_config;
+use crate::database::{lock_db, DbConnection};
+use crate::services::usage_report_service::{self, ReportRange, UsageReportOutcome};
+use crate::AppState;
+use lime_core::config::{UsageReportPeriod, UsageReportSettings};
+use tauri::{AppHandle, Manager, State};
+
+/// 定时报告的检查间隔
+const SCHEDULE_CHECK_INTERVAL_SECS: u64 = 3600;
+
+/// 获取用量报告配置
+#[tauri::command]
+pub async fn get_usage_report_settings(
+    state: State<'_, AppState>,
+) -> Result<UsageReportSettings, String> {
+    let s = state.read().await;
+    Ok(s.config.usage_reports.clone())
+}
+
+/// 更新用量报告配置
+#[tauri::command]
+pub async fn update_usage_report_settings(
+    state: State<'_, AppState>,
+    settings: UsageReportSettings,
+) -> Result<(), String> {
+    if let Some(url) = settings
+        .webhook_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+    {
+        if !(url.starts_with("https://") || url.starts_with("http://")) {
+            return Err("Webhook 地址必须以 http:// 或 https:// 开头".to_string());
+        }
+    }
+
+    let mut s = state.write().await;
+    s.config.usage_reports = settings;
+    save_config(&s.config).map_err(|e| e.to_string())
+}
+
+/// 立即生成当前周期（截至今天）的报告
+///
+/// 未指定周期时使用配置中的周期；不影响定时报告的进度。
+#[tauri::command]
+pub async fn generate_usage_report_now(
+    state: State<'_, AppState>,
+    db: State<'_, DbConnection>,
+    period: Option<UsageReportPeriod>,
+) -> Result<UsageReportOutcome, String> {
+    let settings = state.read().await.config.usage_reports.clone();
+    let range = ReportRange::current(
+        period.unwrap_or(settings.period),
+        chrono::Utc::now().date_naive(),
+    );
+    usage_report_service::generate_and_deliver(&db, &settings, range).await
+}
+
+/// 启动定时报告任务：每小时检查上一周期的报告是否已生成
+pub fn spawn_usage_report_scheduler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut ticker =
+            tokio::time::interval(std::time::Duration::from_secs(SCHEDULE_CHECK_INTERVAL_SECS));
+        loop {
+            ticker.tick().await;
+            let (Some(app_state), Some(db)) = (
+                app_handle.try_state::<AppState>(),
+                app_handle.try_state::<DbConnection>(),
+            ) else {
+                continue;
+            };
+            let settings = app_state.read().await.config.usage_reports.clone();
+            if !settings.enabled {
+                continue;
+            }
+            if let Err(e) = run_scheduled_report(&db, &settings).await {
+                tracing::warn!("[USAGE_REPORT] 定时报告生成失败: {}", e);
+            }
+        }
+    });
+}
+
+async fn run_scheduled_report(
+    db: &DbConnection,
+    settings: &UsageReportSettings,
+) -> Result<(), String> {
+    let range = ReportRange::previous(settings.period, chrono::Utc::now().date_naive());
+    let report_key = range.key();
+    {
+        let conn = lock_db(db)?;
+        if usage_report_service::last_generated_key(&conn).as_deref() == Some(report_key.as_str()) {
+            return Ok(());
+        }
+    }
+
+    let outcome = usage_report_service::generate_and_deliver(db, settings, range).await?;
+    // Webhook 推送失败时不记录进度，下次检查时重试
+    if outcome.webhook_delivered == Some(false) {
+        return Err(outcome.webhook_error.unwrap_or_default());
+    }
+    let conn = lock_db(db)?;
+    usage_report_service::mark_generated(&conn, &report_key).map_err(|e| e.to_string())
+}
@@ -0,0 +1,46 @@
+//! 虚拟模型（用户自定义模型别名）管理命令
+
+use crate::database::{lock_db, DbConnection};
+use lime_core::database::dao::virtual_model::VirtualModelDao;
+use lime_core::models::VirtualModel;
+use tauri::State;
+
+/// 获取全部虚拟模型
+#[tauri::command]
+pub fn list_virtual_models(db: State<'_, DbConnection>) -> Result<Vec<VirtualModel>, String> {
+    let conn = lock_db(&db)?;
+    VirtualModelDao::list(&conn).map_err(|e| e.to_string())
+}
+
+/// 新增或更新虚拟模型（按名称）
+#[tauri::command]
+pub fn save_virtual_model(
+    db: State<'_, DbConnection>,
+    mut model: VirtualModel,
+) -> Result<(), String> {
+    model.name = model.name.trim().to_string();
+    model.model = model.model.trim().to_string();
+    model.provider = model
+        .provider
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty());
+    if model.name.is_empty() || model.model.is_empty() {
+        return Err("虚拟模型名称和目标模型不能为空".to_string());
+    }
+    if model.name == model.model {
+        return Err("虚拟模型名称不能与目标模型相同".to_string());
+    }
+    if model.temperature.is_some_and(|t| !(0.0..=2.0).contains(&t)) {
+        return Err("temperature 必须在 [0, 2] 范围内".to_string());
+    }
+
+    let conn = lock_db(&db)?;
+    VirtualModelDao::upsert(&conn, &model).map_err(|e| e.to_string())
+}
+
+/// 删除虚拟模型
+#[tauri::command]
+pub fn delete_virtual_model(db: State<'_, DbConnection>, name: String) -> Result<bool, String> {
+    let conn = lock_db(&db)?;
+    VirtualModelDao::delete(&conn, &name).map_err(|e| e.to_string())
+}
@@ -1,9 +1,15 @@
 //! 语音测试命令
 //!
-//! 提供 TTS 语音测试功能
+//! 提供 TTS 语音测试与朗读功能
 
+use lime_services::voice_tts_service;
 use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
+use voice_core::TtsClient;
+
+use crate::commands::api_key_provider_cmd::ApiKeyProviderServiceState;
+use crate::config::load_config;
+use crate::database::DbConnection;
 
 /// TTS 测试结果
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,62 +22,88 @@ pub struct TtsTestResult {
     pub audio_path: Option<String>,
 }
 
+/// TTS 测试文本
+const TTS_TEST_TEXT: &str = "你好，这是一段语音合成测试。";
+
+/// 按当前语音配置创建 TTS 客户端，`service` / `voice` 覆盖配置中的值
+fn build_tts_client(
+    db: &DbConnection,
+    provider_service: &ApiKeyProviderServiceState,
+    service: Option<&str>,
+    voice: Option<&str>,
+) -> Result<Box<dyn TtsClient>, String> {
+    let config = load_config().map_err(|e| e.to_string())?;
+    let mut voice_config = config.voice.clone();
+    if let Some(service) = service {
+        voice_config.tts_service = Some(service.to_string());
+    }
+
+    let credential = if voice_config.tts_service.as_deref().unwrap_or("openai") == "openai" {
+        voice_tts_service::resolve_openai_credential(
+            db,
+            &provider_service.0,
+            &config.content_creator.media_defaults.voice,
+        )?
+    } else {
+        None
+    };
+    voice_tts_service::build_tts_client(&voice_config, voice, credential)
+}
+
 /// 测试 TTS 语音合成
+///
+/// 合成一段测试文本并播放，音频同时保存到临时目录。
 #[tauri::command]
 pub async fn test_tts(
+    db: State<'_, DbConnection>,
+    provider_service: State<'_, ApiKeyProviderServiceState>,
     service: String,
     voice: String,
     _app: AppHandle,
 ) -> Result<TtsTestResult, String> {
     tracing::info!("[语音测试] 测试 TTS: service={}, voice={}", service, voice);
 
-    // TODO: 实现真实的 TTS 测试
-    // 1. 根据 service 选择相应的 TTS 引擎
-    // 2. 使用指定的 voice 合成测试文本
-    // 3. 保存音频文件并返回路径
+    let result = async {
+        let client = build_tts_client(&db, &provider_service, Some(&service), Some(&voice))?;
+        let audio = voice_tts_service::synthesize(client.as_ref(), TTS_TEST_TEXT).await?;
+        let path = std::env::temp_dir().join("lime_tts_test.wav");
+        std::fs::write(&path, audio.to_wav_bytes()).map_err(|e| e.to_string())?;
+        voice_tts_service::play_audio(audio).await?;
+        Ok::<_, String>(path)
+    }
+    .await;
 
-    // 模拟测试
-    match service.as_str() {
-        "openai" => {
-            tracing::info!("[语音测试] 使用 OpenAI TTS");
-            // TODO: 调用 OpenAI TTS API
-        }
-        "azure" => {
-            tracing::info!("[语音测试] 使用 Azure TTS");
-            // TODO: 调用 Azure TTS API
-        }
-        "google" => {
-            tracing::info!("[语音测试] 使用 Google TTS");
-            // TODO: 调用 Google TTS API
-        }
-        "edge" => {
-            tracing::info!("[语音测试] 使用 Edge TTS");
-            // TODO: 调用 Edge TTS API
-        }
-        "macos" => {
-            tracing::info!("[语音测试] 使用 macOS 系统 TTS");
-            // TODO: 调用 macOS 系统 say 命令
-        }
-        _ => {
-            return Ok(TtsTestResult {
+    Ok(match result {
+        Ok(path) => TtsTestResult {
+            success: true,
+            error: None,
+            audio_path: Some(path.to_string_lossy().to_string()),
+        },
+        Err(e) => {
+            tracing::warn!("[语音测试] TTS 测试失败: {}", e);
+            TtsTestResult {
                 success: false,
-                error: Some(format!("不支持的 TTS 服务: {service}")),
+                error: Some(e),
                 audio_path: None,
-            });
+            }
         }
-    }
-
-    // 模拟异步处理
-    tokio::time::sleep(tokio::time::Duration::from_millis(2000)).await;
-
-    // 模拟成功结果
-    Ok(TtsTestResult {
-        success: true,
-        error: None,
-        audio_path: Some("/tmp/test_tts_output.wav".to_string()),
     })
 }
 
+/// 朗读文本（如 Agent 回复）
+///
+/// 使用 `config.voice` 的 TTS 设置合成并播放，播放完成后返回。
+#[tauri::command]
+pub async fn speak_text(
+    db: State<'_, DbConnection>,
+    provider_service: State<'_, ApiKeyProviderServiceState>,
+    text: String,
+) -> Result<(), String> {
+    let client = build_tts_client(&db, &provider_service, None, None)?;
+    let audio = voice_tts_service::synthesize(client.as_ref(), &text).await?;
+    voice_tts_service::play_audio(audio).await
+}
+
 /// 语音选项
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VoiceOption {
@@ -91,7 +123,6 @@ pub async fn get_available_voices(
 ) -> Result<Vec<VoiceOption>, String> {
     tracing::info!("[语音测试] 获取可用语音: service={}", service);
 
-    // TODO: 根据服务返回可用的语音列表
     let voices = match service.as_str() {
         "openai" => vec![
             VoiceOption {
@@ -125,7 +156,7 @@ pub async fn get_available_voices(
                 language: "en".to_string(),
             },
         ],
-        "azure" => vec![
+        "azure" | "edge" => vec![
             VoiceOption {
                 id: "zh-CN-XiaoxiaoNeural".to_string(),
                 name: "晓晓 (女)".to_string(),
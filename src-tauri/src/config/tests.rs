@@ -39,6 +39,11 @@ fn arb_server_config() -> impl Strategy<Value = ServerConfig> {
         api_key,
         tls: lime_core::config::TlsConfig::default(),
         response_cache: lime_core::config::ResponseCacheSettings::default(),
+        route_auth: lime_core::config::RouteAuthSettings::default(),
+        sse_flow_control: lime_core::config::SseFlowControlSettings::default(),
+        request_deadline: lime_core::config::RequestDeadlineSettings::default(),
+        forward_proxy: lime_core::config::ForwardProxySettings::default(),
+        conversion_loss: lime_core::config::ConversionLossSettings::default(),
     })
 }
 
@@ -117,6 +122,9 @@ fn arb_routing_config() -> impl Strategy<Value = RoutingConfig> {
         .prop_map(|(default_provider, model_aliases)| RoutingConfig {
             default_provider,
             model_aliases,
+            context_upgrade: Default::default(),
+            failover_chains: Default::default(),
+            endpoint_fallback: Default::default(),
         })
 }
 
@@ -357,6 +365,11 @@ fn arb_valid_server_config() -> impl Strategy<Value = ServerConfig> {
         api_key,
         tls: lime_core::config::TlsConfig::default(),
         response_cache: lime_core::config::ResponseCacheSettings::default(),
+        route_auth: lime_core::config::RouteAuthSettings::default(),
+        sse_flow_control: lime_core::config::SseFlowControlSettings::default(),
+        request_deadline: lime_core::config::RequestDeadlineSettings::default(),
+        forward_proxy: lime_core::config::ForwardProxySettings::default(),
+        conversion_loss: lime_core::config::ConversionLossSettings::default(),
     })
 }
 
@@ -1194,6 +1207,7 @@ fn arb_credential_pool_config() -> impl Strategy<Value = CredentialPoolConfig> {
                 vertex_api_keys: vec![],
                 codex: vec![],
                 asr: vec![],
+                migrated_ids: vec![],
             },
         )
 }
@@ -2213,6 +2227,7 @@ fn arb_extended_credential_pool_config() -> impl Strategy<Value = CredentialPool
                     vertex_api_keys,
                     codex,
                     asr: vec![],
+                    migrated_ids: vec![],
                 }
             },
         )
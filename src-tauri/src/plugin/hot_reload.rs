@@ -0,0 +1,138 @@
+//! 插件热重载
+//!
+//! 监控插件目录，插件文件变化时无需重启应用：
+//! 1. 优雅关闭插件的 Binary 后端进程，前端收到事件后按需重新连接
+//! 2. 通过 `PluginManager` 加载、重新加载或卸载脚本插件
+//! 3. 发送 `plugin:reloaded` 事件通知前端刷新插件列表
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::commands::plugin_cmd::PluginManagerState;
+use crate::commands::plugin_rpc_cmd::PluginRpcManagerState;
+use lime_core::plugin::watcher::DEFAULT_PLUGIN_WATCH_DEBOUNCE;
+use lime_core::plugin::{
+    PluginDirChange, PluginDirChangeKind, PluginDirWatcher, PluginReloadAction,
+};
+
+/// 插件重新加载事件
+pub const PLUGIN_RELOADED_EVENT: &str = "plugin:reloaded";
+
+/// 插件重新加载事件 payload
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginReloadedPayload {
+    /// 插件 ID（插件目录名）
+    pub plugin_id: String,
+    /// 处理结果：loaded / reloaded / unloaded / updated / removed / failed
+    pub action: String,
+    /// 是否关闭了正在运行的后端进程
+    pub process_stopped: bool,
+    /// 重新加载失败时的错误信息
+    pub error: Option<String>,
+}
+
+/// 启动插件目录监控
+///
+/// 监控器随后台任务存活，应用退出时一同结束。
+pub fn spawn_plugin_hot_reload(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let Some(manager_state) = app_handle.try_state::<PluginManagerState>() else {
+            return;
+        };
+        let plugins_dir = manager_state.0.read().await.plugins_dir().to_path_buf();
+
+        let (_watcher, mut changes) =
+            match PluginDirWatcher::start(&plugins_dir, DEFAULT_PLUGIN_WATCH_DEBOUNCE) {
+                Ok(started) => started,
+                Err(e) => {
+                    tracing::warn!("[插件热重载] 启动插件目录监控失败: {}", e);
+                    return;
+                }
+            };
+
+        while let Some(change) = changes.recv().await {
+            apply_plugin_change(&app_handle, change).await;
+        }
+    });
+}
+
+/// 手动重新加载单个插件
+pub async fn reload_plugin_by_id(app_handle: &AppHandle, plugin_id: &str) -> PluginReloadedPayload {
+    let plugin_dir = match app_handle.try_state::<PluginManagerState>() {
+        Some(state) => state.0.read().await.plugins_dir().join(plugin_id),
+        None => PathBuf::from(plugin_id),
+    };
+    let kind = if plugin_dir.is_dir() {
+        PluginDirChangeKind::Changed
+    } else {
+        PluginDirChangeKind::Removed
+    };
+
+    apply_plugin_change(
+        app_handle,
+        PluginDirChange {
+            plugin_id: plugin_id.to_string(),
+            plugin_dir,
+            kind,
+        },
+    )
+    .await
+}
+
+/// 处理单个插件目录变化并发送 `plugin:reloaded` 事件
+async fn apply_plugin_change(
+    app_handle: &AppHandle,
+    change: PluginDirChange,
+) -> PluginReloadedPayload {
+    tracing::info!(
+        "[插件热重载] 插件 {} 目录变化: {:?}",
+        change.plugin_id,
+        change.kind
+    );
+
+    // 旧进程仍在使用旧的二进制与配置，先关闭再让前端重新连接
+    let process_stopped = match app_handle.try_state::<PluginRpcManagerState>() {
+        Some(rpc_state) => rpc_state.shutdown(&change.plugin_id).await,
+        None => false,
+    };
+
+    let result = match app_handle.try_state::<PluginManagerState>() {
+        Some(manager_state) => {
+            let manager = manager_state.0.read().await;
+            manager.sync_dir(&change.plugin_dir).await
+        }
+        None => Ok(PluginReloadAction::Skipped),
+    };
+
+    let (action, error) = match result {
+        Ok(PluginReloadAction::Loaded(_)) => ("loaded", None),
+        Ok(PluginReloadAction::Reloaded(_)) => ("reloaded", None),
+        Ok(PluginReloadAction::Unloaded(_)) => ("unloaded", None),
+        Ok(PluginReloadAction::Skipped) => match change.kind {
+            PluginDirChangeKind::Changed => ("updated", None),
+            PluginDirChangeKind::Removed => ("removed", None),
+        },
+        Err(e) => {
+            tracing::warn!("[插件热重载] 重新加载插件 {} 失败: {}", change.plugin_id, e);
+            ("failed", Some(e.to_string()))
+        }
+    };
+
+    let payload = PluginReloadedPayload {
+        plugin_id: change.plugin_id,
+        action: action.to_string(),
+        process_stopped,
+        error,
+    };
+    if let Err(e) = app_handle.emit(PLUGIN_RELOADED_EVENT, &payload) {
+        tracing::error!(
+            "[插件热重载] 发送 {} 事件失败: {}",
+            PLUGIN_RELOADED_EVENT,
+            e
+        );
+    }
+    payload
+}
@@ -6,6 +6,10 @@
 // 核心插件能力从 core crate 导出
 pub use lime_core::plugin::*;
 
-// Tauri 依赖的 UI 事件模块保留在主 crate
+// Tauri 依赖的 UI 事件与热重载模块保留在主 crate
+pub mod hot_reload;
 pub mod ui_events;
-pub use ui_events::{PluginUIEmitter, PluginUIEmitterState, PluginUIEventPayload};
+pub use ui_events::{
+    PluginBusEventEmitter, PluginScopedEvent, PluginTaskEventEmitter, PluginUIEmitter,
+    PluginUIEmitterState, PluginUIEventPayload,
+};
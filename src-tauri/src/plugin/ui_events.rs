@@ -1,8 +1,15 @@
 //! 插件 UI 事件系统
 //!
-//! 提供从 Rust 向前端推送 UI 更新的能力
+//! 提供从 Rust 向前端推送 UI 更新的能力。
+//!
+//! 所有发往插件 UI 的事件都经过 [`PluginEventScope`] 权限检查，
+//! 只投递到插件专属通道（`plugin-event:<plugin_id>`），
+//! 未在清单 `ui.events` 中声明的事件会被拒绝并记录审计。
 
+use lime_core::plugin::{plugin_event_channel, PluginEventScope};
+use lime_core::EventEmit;
 use serde::Serialize;
+use std::sync::Arc;
 use tauri::{AppHandle, Emitter};
 
 use super::ui_types::UIMessage;
@@ -17,15 +24,58 @@ pub struct PluginUIEventPayload {
     pub message: UIMessage,
 }
 
+/// 插件专属通道上的事件信封
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginScopedEvent {
+    /// 目标插件 ID
+    pub plugin_id: String,
+    /// 原始事件名称
+    pub event: String,
+    /// 事件数据
+    pub payload: serde_json::Value,
+}
+
 /// 插件 UI 事件发射器
+#[derive(Clone)]
 pub struct PluginUIEmitter {
     app_handle: AppHandle,
+    scope: Arc<PluginEventScope>,
 }
 
 impl PluginUIEmitter {
     /// 创建新的事件发射器
-    pub fn new(app_handle: AppHandle) -> Self {
-        Self { app_handle }
+    pub fn new(app_handle: AppHandle, scope: Arc<PluginEventScope>) -> Self {
+        Self { app_handle, scope }
+    }
+
+    /// 事件权限范围
+    pub fn scope(&self) -> &Arc<PluginEventScope> {
+        &self.scope
+    }
+
+    /// 向插件 UI 投递事件
+    ///
+    /// 返回是否实际投递；未授权的事件被拒绝并记录审计，不视为错误。
+    pub fn deliver(
+        &self,
+        plugin_id: &str,
+        event: &str,
+        payload: serde_json::Value,
+    ) -> Result<bool, String> {
+        deliver_scoped(&self.app_handle, &self.scope, plugin_id, event, payload)
+    }
+
+    /// 向所有已注册的插件 UI 投递事件，返回实际投递数
+    pub fn broadcast(&self, event: &str, payload: &serde_json::Value) -> usize {
+        self.scope
+            .registered_plugins()
+            .iter()
+            .filter(|plugin_id| {
+                self.deliver(plugin_id, event, payload.clone())
+                    .unwrap_or(false)
+            })
+            .count()
     }
 
     /// 发送单个 UI 消息
@@ -34,10 +84,10 @@ impl PluginUIEmitter {
             plugin_id: plugin_id.to_string(),
             message,
         };
+        let payload = serde_json::to_value(payload).map_err(|e| e.to_string())?;
 
-        self.app_handle
-            .emit("plugin-ui-message", payload)
-            .map_err(|e| e.to_string())
+        self.deliver(plugin_id, "plugin-ui-message", payload)
+            .map(|_| ())
     }
 
     /// 发送多个 UI 消息
@@ -77,6 +127,96 @@ impl PluginUIEmitter {
     }
 }
 
+/// 经过权限检查后投递到插件专属通道
+fn deliver_scoped(
+    app_handle: &AppHandle,
+    scope: &PluginEventScope,
+    plugin_id: &str,
+    event: &str,
+    payload: serde_json::Value,
+) -> Result<bool, String> {
+    if !scope.authorize(plugin_id, event) {
+        return Ok(false);
+    }
+    let envelope = PluginScopedEvent {
+        plugin_id: plugin_id.to_string(),
+        event: event.to_string(),
+        payload,
+    };
+    app_handle
+        .emit(&plugin_event_channel(plugin_id), envelope)
+        .map(|_| true)
+        .map_err(|e| e.to_string())
+}
+
+/// 插件管理页使用的任务刷新信号（不含任务详情与错误信息）
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginTaskNotice<'a> {
+    plugin_id: &'a str,
+    task_id: &'a str,
+    state: &'a serde_json::Value,
+}
+
+/// 插件任务事件发射器
+///
+/// 完整任务事件只经权限检查投递到所属插件 UI 的专属通道；
+/// 全局只发送不含详情的刷新信号，供插件管理页刷新任务列表。
+pub struct PluginTaskEventEmitter {
+    emitter: PluginUIEmitter,
+}
+
+impl PluginTaskEventEmitter {
+    pub fn new(emitter: PluginUIEmitter) -> Self {
+        Self { emitter }
+    }
+}
+
+impl EventEmit for PluginTaskEventEmitter {
+    fn emit_event(&self, event: &str, payload: &serde_json::Value) -> Result<(), String> {
+        let Some(plugin_id) = payload.get("pluginId").and_then(|v| v.as_str()) else {
+            return Err("任务事件缺少 pluginId".to_string());
+        };
+        self.emitter.deliver(plugin_id, event, payload.clone())?;
+
+        let notice = PluginTaskNotice {
+            plugin_id,
+            task_id: payload
+                .get("taskId")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default(),
+            state: payload.get("state").unwrap_or(&serde_json::Value::Null),
+        };
+        self.emitter
+            .app_handle
+            .emit(event, notice)
+            .map_err(|e| format!("Tauri emit 失败: {e}"))
+    }
+}
+
+/// 插件事件总线发射器
+///
+/// 按事件主题投递给在清单 `ui.events` 中声明了该主题的插件 UI，不再全局广播。
+pub struct PluginBusEventEmitter {
+    emitter: PluginUIEmitter,
+}
+
+impl PluginBusEventEmitter {
+    pub fn new(emitter: PluginUIEmitter) -> Self {
+        Self { emitter }
+    }
+}
+
+impl EventEmit for PluginBusEventEmitter {
+    fn emit_event(&self, _event: &str, payload: &serde_json::Value) -> Result<(), String> {
+        let Some(topic) = payload.get("topic").and_then(|v| v.as_str()) else {
+            return Err("事件总线事件缺少 topic".to_string());
+        };
+        self.emitter.broadcast(topic, payload);
+        Ok(())
+    }
+}
+
 /// 全局事件发射器状态
 pub struct PluginUIEmitterState(pub Option<PluginUIEmitter>);
 
@@ -87,8 +227,8 @@ impl PluginUIEmitterState {
     }
 
     /// 初始化发射器
-    pub fn init(&mut self, app_handle: AppHandle) {
-        self.0 = Some(PluginUIEmitter::new(app_handle));
+    pub fn init(&mut self, app_handle: AppHandle, scope: Arc<PluginEventScope>) {
+        self.0 = Some(PluginUIEmitter::new(app_handle, scope));
     }
 
     /// 获取发射器
@@ -22,10 +22,12 @@ pub mod memory_rules_loader_service;
 pub mod memory_source_resolver_service;
 pub mod novel_service;
 pub mod openclaw_service;
+pub mod quick_action_service;
 pub mod runtime_agents_template_service;
 pub mod sysinfo_service;
 pub mod update_check_service;
 pub mod update_window;
+pub mod usage_report_service;
 pub mod web_search_prompt_service;
 pub mod web_search_runtime_service;
 pub mod workspace_health_service;
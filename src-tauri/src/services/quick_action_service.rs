@@ -0,0 +1,250 @@
+//! 快捷操作（命令面板）服务
+//!
+//! 定义快捷操作的元数据、ID 编码与模糊匹配排序。
+//! 候选项由 `quick_action_cmd` 从当前状态收集，前端面板只负责展示与回传 ID。
+
+use serde::{Deserialize, Serialize};
+
+/// 快捷操作类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickActionKind {
+    /// 切换默认模型
+    SwitchDefaultModel,
+    /// 启用 / 禁用 Provider 凭证
+    ToggleProvider,
+    /// 启动 MCP 服务器
+    StartMcpServer,
+    /// 执行 Skill
+    RunSkill,
+}
+
+impl QuickActionKind {
+    pub const ALL: [QuickActionKind; 4] = [
+        QuickActionKind::SwitchDefaultModel,
+        QuickActionKind::ToggleProvider,
+        QuickActionKind::StartMcpServer,
+        QuickActionKind::RunSkill,
+    ];
+
+    /// 操作 ID 前缀
+    pub fn prefix(self) -> &'static str {
+        match self {
+            QuickActionKind::SwitchDefaultModel => "model",
+            QuickActionKind::ToggleProvider => "provider",
+            QuickActionKind::StartMcpServer => "mcp",
+            QuickActionKind::RunSkill => "skill",
+        }
+    }
+
+    /// 参与匹配的类型关键词
+    fn keywords(self) -> &'static [&'static str] {
+        match self {
+            QuickActionKind::SwitchDefaultModel => &["model", "switch", "default", "切换模型"],
+            QuickActionKind::ToggleProvider => &["provider", "toggle", "credential", "凭证"],
+            QuickActionKind::StartMcpServer => &["mcp", "start", "server", "启动"],
+            QuickActionKind::RunSkill => &["skill", "run", "执行"],
+        }
+    }
+}
+
+/// 快捷操作
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuickAction {
+    /// 操作 ID（`<prefix>:<target>`），分发时原样回传
+    pub id: String,
+    pub kind: QuickActionKind,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    /// 额外的匹配关键词
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// 执行时需要用户输入（如 Skill 的输入），值为输入框占位文本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_placeholder: Option<String>,
+}
+
+impl QuickAction {
+    pub fn new(kind: QuickActionKind, target: &str, title: impl Into<String>) -> Self {
+        Self {
+            id: format!("{}:{}", kind.prefix(), target),
+            kind,
+            title: title.into(),
+            subtitle: None,
+            keywords: kind.keywords().iter().map(|k| k.to_string()).collect(),
+            input_placeholder: None,
+        }
+    }
+
+    pub fn with_subtitle(mut self, subtitle: impl Into<String>) -> Self {
+        let subtitle = subtitle.into();
+        if !subtitle.trim().is_empty() {
+            self.subtitle = Some(subtitle);
+        }
+        self
+    }
+
+    pub fn with_keywords<I, S>(mut self, keywords: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.keywords.extend(keywords.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn with_input(mut self, placeholder: impl Into<String>) -> Self {
+        self.input_placeholder = Some(placeholder.into());
+        self
+    }
+}
+
+/// 解析操作 ID，返回操作类型与目标
+pub fn parse_action_id(id: &str) -> Result<(QuickActionKind, &str), String> {
+    let (prefix, target) = id
+        .split_once(':')
+        .ok_or_else(|| format!("无效的快捷操作 ID: {id}"))?;
+    let kind = QuickActionKind::ALL
+        .into_iter()
+        .find(|kind| kind.prefix() == prefix)
+        .ok_or_else(|| format!("未知的快捷操作类型: {prefix}"))?;
+    if target.is_empty() {
+        return Err(format!("快捷操作缺少目标: {id}"));
+    }
+    Ok((kind, target))
+}
+
+/// 模糊匹配得分
+///
+/// `query` 的字符需按顺序出现在 `text` 中（忽略大小写），否则返回 `None`。
+/// 连续命中、命中词首与前缀匹配加分，跳过的字符扣分。
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(0);
+    }
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+
+    let mut score = 0i64;
+    let mut qi = 0;
+    let mut last_match: Option<usize> = None;
+    for (ti, &c) in text.iter().enumerate() {
+        if qi == query.len() {
+            break;
+        }
+        if c != query[qi] {
+            continue;
+        }
+        score += 10;
+        let at_word_start = ti == 0 || !text[ti - 1].is_alphanumeric();
+        if at_word_start {
+            score += 8;
+        }
+        match last_match {
+            Some(last) if last + 1 == ti => score += 15,
+            Some(last) => score -= (ti - last - 1).min(10) as i64,
+            None => score -= ti.min(10) as i64,
+        }
+        last_match = Some(ti);
+        qi += 1;
+    }
+    if qi < query.len() {
+        return None;
+    }
+    if last_match == Some(query.len() - 1) {
+        score += 20;
+    }
+    Some(score)
+}
+
+/// 计算操作与查询的得分，标题优先于副标题与关键词
+fn action_score(action: &QuickAction, query: &str) -> Option<i64> {
+    let title = fuzzy_score(query, &action.title).map(|s| s * 2);
+    let others = action
+        .subtitle
+        .iter()
+        .chain(action.keywords.iter())
+        .filter_map(|text| fuzzy_score(query, text))
+        .max();
+    title.max(others)
+}
+
+/// 按查询过滤并排序快捷操作
+///
+/// 空查询保持原有顺序；同分时按标题排序，结果截断为 `limit` 条。
+pub fn rank_quick_actions(
+    actions: Vec<QuickAction>,
+    query: &str,
+    limit: usize,
+) -> Vec<QuickAction> {
+    if query.trim().is_empty() {
+        return actions.into_iter().take(limit).collect();
+    }
+
+    let mut scored: Vec<(i64, QuickAction)> = actions
+        .into_iter()
+        .filter_map(|action| action_score(&action, query).map(|score| (score, action)))
+        .collect();
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score.cmp(a_score).then_with(|| a.title.cmp(&b.title))
+    });
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, action)| action)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_prefers_contiguous_prefix() {
+        assert!(fuzzy_score("xyz", "claude-sonnet").is_none());
+        let prefix = fuzzy_score("son", "sonnet").unwrap();
+        let scattered = fuzzy_score("son", "s-o-n").unwrap();
+        assert!(prefix > scattered);
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert!(fuzzy_score("CS", "claude-sonnet").is_some());
+    }
+
+    #[test]
+    fn test_parse_action_id() {
+        assert_eq!(
+            parse_action_id("model:gpt-4o:latest").unwrap(),
+            (QuickActionKind::SwitchDefaultModel, "gpt-4o:latest")
+        );
+        assert!(parse_action_id("unknown:x").is_err());
+        assert!(parse_action_id("skill:").is_err());
+        assert!(parse_action_id("nocolon").is_err());
+    }
+
+    #[test]
+    fn test_rank_quick_actions_orders_by_score() {
+        let actions = vec![
+            QuickAction::new(QuickActionKind::SwitchDefaultModel, "gpt-4o", "gpt-4o"),
+            QuickAction::new(
+                QuickActionKind::SwitchDefaultModel,
+                "claude-sonnet-4-5",
+                "claude-sonnet-4-5",
+            ),
+            QuickAction::new(QuickActionKind::StartMcpServer, "filesystem", "filesystem"),
+        ];
+
+        let ranked = rank_quick_actions(actions.clone(), "sonnet", 10);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].id, "model:claude-sonnet-4-5");
+
+        // 类型关键词也参与匹配
+        let ranked = rank_quick_actions(actions.clone(), "mcp", 10);
+        assert_eq!(ranked[0].kind, QuickActionKind::StartMcpServer);
+
+        assert_eq!(rank_quick_actions(actions, "", 2).len(), 2);
+    }
+}
@@ -0,0 +1,570 @@
+//! 用量报告服务
+//!
+//! 按日/周汇总请求数、Token、费用（`request_costs`）与错误率（`request_audit_log`），
+//! 按 Provider、模型、凭证与租户分组，渲染为 Markdown / CSV 写入输出目录，
+//! 并可推送到 Webhook。定时调度见 `commands::usage_report_cmd`。
+
+use crate::config::{UsageReportFormat, UsageReportPeriod, UsageReportSettings};
+use crate::database::dao::request_audit::{AuditStatusBucket, RequestAuditDao};
+use crate::database::dao::request_cost::{RequestCostDao, SpendBucket, SpendGroup};
+use crate::database::{lock_db, DbConnection};
+use chrono::{Datelike, Duration, NaiveDate};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// settings 表中记录最近一次定时报告的键
+const LAST_REPORT_SETTING_KEY: &str = "usage_report.last_generated";
+
+/// 报告覆盖的日期范围（UTC，含首尾）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportRange {
+    pub period: UsageReportPeriod,
+    pub from_day: NaiveDate,
+    pub to_day: NaiveDate,
+}
+
+impl ReportRange {
+    /// 上一个完整周期（定时报告使用）
+    pub fn previous(period: UsageReportPeriod, today: NaiveDate) -> Self {
+        let last_day_of_previous = Self::current(period, today).from_day - Duration::days(1);
+        Self::current(period, last_day_of_previous)
+    }
+
+    /// 当前周期（截至今天，手动生成使用）
+    pub fn current(period: UsageReportPeriod, today: NaiveDate) -> Self {
+        let from_day = match period {
+            UsageReportPeriod::Daily => today,
+            UsageReportPeriod::Weekly => {
+                today - Duration::days(today.weekday().num_days_from_monday() as i64)
+            }
+        };
+        let to_day = match period {
+            UsageReportPeriod::Daily => today,
+            UsageReportPeriod::Weekly => from_day + Duration::days(6),
+        };
+        Self {
+            period,
+            from_day,
+            to_day,
+        }
+    }
+
+    /// 报告键，同时用作输出文件名
+    pub fn key(&self) -> String {
+        match self.period {
+            UsageReportPeriod::Daily => format!("daily-{}", self.from_day),
+            UsageReportPeriod::Weekly => format!("weekly-{}", self.from_day),
+        }
+    }
+
+    fn timestamp_bounds(&self) -> (i64, i64) {
+        let from = self.from_day.and_hms_opt(0, 0, 0).unwrap().and_utc();
+        let to = self.to_day.and_hms_opt(23, 59, 59).unwrap().and_utc();
+        (from.timestamp(), to.timestamp())
+    }
+}
+
+/// 报告中的一行（某个维度取值的汇总）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageReportRow {
+    /// 维度取值（凭证/租户为空的请求归入空字符串）
+    pub key: String,
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+    /// 错误率（0.0 - 1.0），审计日志没有该维度的数据时为空
+    pub error_rate: Option<f64>,
+}
+
+impl UsageReportRow {
+    fn from_bucket(bucket: SpendBucket) -> Self {
+        Self {
+            key: bucket.key,
+            requests: bucket.requests,
+            input_tokens: bucket.input_tokens,
+            output_tokens: bucket.output_tokens,
+            cost_usd: bucket.cost_usd,
+            error_rate: None,
+        }
+    }
+}
+
+/// 用量报告
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageReport {
+    pub report_key: String,
+    pub range: ReportRange,
+    /// 生成时间（Unix 秒）
+    pub generated_at: i64,
+    pub total: UsageReportRow,
+    pub by_provider: Vec<UsageReportRow>,
+    pub by_model: Vec<UsageReportRow>,
+    pub by_credential: Vec<UsageReportRow>,
+    pub by_tenant: Vec<UsageReportRow>,
+}
+
+impl UsageReport {
+    fn sections(&self) -> [(&'static str, &'static str, &[UsageReportRow]); 4] {
+        [
+            ("provider", "Provider", &self.by_provider),
+            ("model", "模型", &self.by_model),
+            ("credential", "凭证", &self.by_credential),
+            ("tenant", "租户", &self.by_tenant),
+        ]
+    }
+}
+
+/// 一次报告生成与投递的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageReportOutcome {
+    pub report: UsageReport,
+    /// 写入的文件路径
+    pub files: Vec<String>,
+    /// Webhook 投递结果（未配置时为空）
+    pub webhook_delivered: Option<bool>,
+    /// Webhook 投递失败原因
+    pub webhook_error: Option<String>,
+}
+
+/// 汇总指定范围内的用量
+pub fn build_report(
+    conn: &Connection,
+    range: &ReportRange,
+    generated_at: i64,
+) -> Result<UsageReport, rusqlite::Error> {
+    let from_day = range.from_day.to_string();
+    let to_day = range.to_day.to_string();
+    let summarize = |group: SpendGroup| -> Result<Vec<UsageReportRow>, rusqlite::Error> {
+        let mut rows: Vec<UsageReportRow> =
+            RequestCostDao::summarize(conn, group, &from_day, &to_day)?
+                .into_iter()
+                .map(UsageReportRow::from_bucket)
+                .collect();
+        rows.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd).then(a.key.cmp(&b.key)));
+        Ok(rows)
+    };
+
+    let mut by_provider = summarize(SpendGroup::Provider)?;
+    let (from_ts, to_ts) = range.timestamp_bounds();
+    let statuses = RequestAuditDao::summarize_status(conn, from_ts, to_ts)?;
+    apply_error_rates(&mut by_provider, &statuses);
+
+    let mut total = UsageReportRow {
+        key: String::new(),
+        requests: 0,
+        input_tokens: 0,
+        output_tokens: 0,
+        cost_usd: 0.0,
+        error_rate: error_rate(
+            statuses.iter().map(|s| s.total).sum(),
+            statuses.iter().map(|s| s.failed).sum(),
+        ),
+    };
+    for row in &by_provider {
+        total.requests += row.requests;
+        total.input_tokens += row.input_tokens;
+        total.output_tokens += row.output_tokens;
+        total.cost_usd += row.cost_usd;
+    }
+
+    Ok(UsageReport {
+        report_key: range.key(),
+        range: *range,
+        generated_at,
+        total,
+        by_provider,
+        by_model: summarize(SpendGroup::Model)?,
+        by_credential: summarize(SpendGroup::Credential)?,
+        by_tenant: summarize(SpendGroup::Tenant)?,
+    })
+}
+
+fn error_rate(total: u64, failed: u64) -> Option<f64> {
+    (total > 0).then(|| failed as f64 / total as f64)
+}
+
+/// 将审计日志的错误率合并到按 Provider 的行；只出现在审计日志中的 Provider 追加为新行
+fn apply_error_rates(rows: &mut Vec<UsageReportRow>, statuses: &[AuditStatusBucket]) {
+    let mut by_provider: HashMap<String, (u64, u64)> = HashMap::new();
+    for status in statuses {
+        let entry = by_provider
+            .entry(status.provider.trim().to_lowercase())
+            .or_default();
+        entry.0 += status.total;
+        entry.1 += status.failed;
+    }
+    for row in rows.iter_mut() {
+        if let Some((total, failed)) = by_provider.remove(&row.key) {
+            row.error_rate = error_rate(total, failed);
+        }
+    }
+    let mut remaining: Vec<_> = by_provider.into_iter().collect();
+    remaining.sort();
+    rows.extend(
+        remaining
+            .into_iter()
+            .map(|(provider, (total, failed))| UsageReportRow {
+                key: provider,
+                requests: 0,
+                input_tokens: 0,
+                output_tokens: 0,
+                cost_usd: 0.0,
+                error_rate: error_rate(total, failed),
+            }),
+    );
+}
+
+fn format_error_rate(rate: Option<f64>) -> String {
+    rate.map(|r| format!("{:.2}%", r * 100.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn display_key(key: &str) -> &str {
+    if key.is_empty() {
+        "(未指定)"
+    } else {
+        key
+    }
+}
+
+/// 渲染为 Markdown
+pub fn render_markdown(report: &UsageReport) -> String {
+    let period = match report.range.period {
+        UsageReportPeriod::Daily => "日报",
+        UsageReportPeriod::Weekly => "周报",
+    };
+    let mut out = format!(
+        "# 用量{period}（{} ~ {}，UTC）\n\n",
+        report.range.from_day, report.range.to_day
+    );
+    out.push_str(&format!(
+        "- 请求数：{}\n- 输入 Token：{}\n- 输出 Token：{}\n- 费用：${:.4}\n- 错误率：{}\n",
+        report.total.requests,
+        report.total.input_tokens,
+        report.total.output_tokens,
+        report.total.cost_usd,
+        format_error_rate(report.total.error_rate),
+    ));
+
+    for (_, title, rows) in report.sections() {
+        if rows.is_empty() {
+            continue;
+        }
+        out.push_str(&format!(
+            "\n## 按{title}\n\n| {title} | 请求数 | 输入 Token | 输出 Token | 费用 (USD) | 错误率 |\n|---|---:|---:|---:|---:|---:|\n"
+        ));
+        for row in rows {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {:.4} | {} |\n",
+                display_key(&row.key).replace('|', "\\|"),
+                row.requests,
+                row.input_tokens,
+                row.output_tokens,
+                row.cost_usd,
+                format_error_rate(row.error_rate),
+            ));
+        }
+    }
+    out
+}
+
+fn escape_csv_cell(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 渲染为 CSV（首行为合计，维度列为 `total`）
+pub fn render_csv(report: &UsageReport) -> String {
+    let mut out =
+        String::from("dimension,key,requests,input_tokens,output_tokens,cost_usd,error_rate\n");
+    let total = [("total", "", std::slice::from_ref(&report.total))];
+    for (dimension, _, rows) in total.into_iter().chain(report.sections()) {
+        for row in rows {
+            out.push_str(&format!(
+                "{},{},{},{},{},{:.6},{}\n",
+                dimension,
+                escape_csv_cell(&row.key),
+                row.requests,
+                row.input_tokens,
+                row.output_tokens,
+                row.cost_usd,
+                row.error_rate
+                    .map(|r| format!("{r:.4}"))
+                    .unwrap_or_default(),
+            ));
+        }
+    }
+    out
+}
+
+fn output_dir(settings: &UsageReportSettings) -> Result<PathBuf, String> {
+    match settings
+        .output_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+    {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => lime_core::app_paths::resolve_reports_dir(),
+    }
+}
+
+/// 生成报告，写入输出目录并推送 Webhook
+pub async fn generate_and_deliver(
+    db: &DbConnection,
+    settings: &UsageReportSettings,
+    range: ReportRange,
+) -> Result<UsageReportOutcome, String> {
+    let report = {
+        let conn = lock_db(db)?;
+        build_report(&conn, &range, chrono::Utc::now().timestamp())
+            .map_err(|e| format!("汇总用量失败: {e}"))?
+    };
+
+    let formats = if settings.formats.is_empty() {
+        vec![UsageReportFormat::Markdown]
+    } else {
+        settings.formats.clone()
+    };
+    let dir = output_dir(settings)?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("无法创建报告目录 {}: {e}", dir.display()))?;
+    let mut files = Vec::new();
+    for format in formats {
+        let (extension, content) = match format {
+            UsageReportFormat::Markdown => ("md", render_markdown(&report)),
+            UsageReportFormat::Csv => ("csv", render_csv(&report)),
+        };
+        let path = dir.join(format!("usage-report-{}.{extension}", report.report_key));
+        std::fs::write(&path, content)
+            .map_err(|e| format!("写入报告 {} 失败: {e}", path.display()))?;
+        files.push(path.to_string_lossy().to_string());
+    }
+
+    let (webhook_delivered, webhook_error) = match settings
+        .webhook_url
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+    {
+        Some(url) => match post_webhook(url, &report).await {
+            Ok(()) => (Some(true), None),
+            Err(e) => {
+                tracing::warn!("[USAGE_REPORT] Webhook 推送失败: {}", e);
+                (Some(false), Some(e))
+            }
+        },
+        None => (None, None),
+    };
+
+    tracing::info!(
+        "[USAGE_REPORT] 已生成报告 {}（{} 个文件）",
+        report.report_key,
+        files.len()
+    );
+    Ok(UsageReportOutcome {
+        report,
+        files,
+        webhook_delivered,
+        webhook_error,
+    })
+}
+
+async fn post_webhook(url: &str, report: &UsageReport) -> Result<(), String> {
+    #[derive(Serialize)]
+    struct WebhookPayload<'a> {
+        event: &'static str,
+        markdown: String,
+        report: &'a UsageReport,
+    }
+
+    let response = reqwest::Client::new()
+        .post(url)
+        .header("Idempotency-Key", report.report_key.as_str())
+        .json(&WebhookPayload {
+            event: "usage_report",
+            markdown: render_markdown(report),
+            report,
+        })
+        .timeout(std::time::Duration::from_secs(30))
+        .send()
+        .await
+        .map_err(|e| format!("Webhook 请求失败: {e}"))?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Webhook 返回错误: {}", response.status()))
+    }
+}
+
+/// 最近一次定时报告的键
+pub fn last_generated_key(conn: &Connection) -> Option<String> {
+    conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        [LAST_REPORT_SETTING_KEY],
+        |row| row.get(0),
+    )
+    .ok()
+}
+
+/// 记录定时报告已生成，避免重启后重复生成
+pub fn mark_generated(conn: &Connection, report_key: &str) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        params![LAST_REPORT_SETTING_KEY, report_key],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::dao::request_audit::RequestAuditRecord;
+    use crate::database::dao::request_cost::RequestCostRecord;
+    use crate::database::schema::create_tables;
+
+    fn day(value: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_report_ranges() {
+        // 2026-10-14 为周三
+        let today = day("2026-10-14");
+        let daily = ReportRange::previous(UsageReportPeriod::Daily, today);
+        assert_eq!(
+            (daily.from_day, daily.to_day),
+            (day("2026-10-13"), day("2026-10-13"))
+        );
+        assert_eq!(daily.key(), "daily-2026-10-13");
+
+        let weekly = ReportRange::previous(UsageReportPeriod::Weekly, today);
+        assert_eq!(
+            (weekly.from_day, weekly.to_day),
+            (day("2026-10-05"), day("2026-10-11"))
+        );
+        assert_eq!(weekly.key(), "weekly-2026-10-05");
+
+        let current = ReportRange::current(UsageReportPeriod::Weekly, today);
+        assert_eq!(
+            (current.from_day, current.to_day),
+            (day("2026-10-12"), day("2026-10-18"))
+        );
+    }
+
+    #[test]
+    fn test_build_and_render_report() {
+        let conn = Connection::open_in_memory().unwrap();
+        create_tables(&conn).unwrap();
+
+        for (provider, tenant, cost_usd) in [
+            ("openai", Some("team-a"), 1.5),
+            ("openai", None, 0.5),
+            ("claude", Some("team-a"), 3.0),
+        ] {
+            RequestCostDao::insert(
+                &conn,
+                &RequestCostRecord {
+                    request_id: uuid::Uuid::new_v4().to_string(),
+                    created_at: 0,
+                    day: "2026-10-13".to_string(),
+                    month: "2026-10".to_string(),
+                    provider: provider.to_string(),
+                    credential_id: None,
+                    model: format!("{provider}-model"),
+                    session_id: None,
+                    tenant_id: tenant.map(str::to_string),
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    cost_usd,
+                },
+            )
+            .unwrap();
+        }
+        let at = day("2026-10-13")
+            .and_hms_opt(8, 0, 0)
+            .unwrap()
+            .and_utc()
+            .timestamp();
+        for (request_id, provider, status) in [
+            ("r1", "openai", "success"),
+            ("r2", "openai", "failed"),
+            ("r3", "gemini", "failed"),
+        ] {
+            RequestAuditDao::insert(
+                &conn,
+                &RequestAuditRecord {
+                    id: 0,
+                    request_id: request_id.to_string(),
+                    created_at: at,
+                    provider: provider.to_string(),
+                    model: "m".to_string(),
+                    credential_id: None,
+                    is_stream: false,
+                    status: status.to_string(),
+                    latency_ms: 0,
+                    retry_count: 0,
+                    input_tokens: None,
+                    output_tokens: None,
+                    error_message: None,
+                    request_body: None,
+                    conversion_warnings: None,
+                },
+            )
+            .unwrap();
+        }
+
+        let range = ReportRange::previous(UsageReportPeriod::Daily, day("2026-10-14"));
+        let report = build_report(&conn, &range, 0).unwrap();
+        assert_eq!(report.total.requests, 3);
+        assert_eq!(report.total.cost_usd, 5.0);
+        assert_eq!(report.total.error_rate, Some(2.0 / 3.0));
+        assert_eq!(
+            report
+                .by_provider
+                .iter()
+                .map(|row| (row.key.as_str(), row.error_rate))
+                .collect::<Vec<_>>(),
+            vec![
+                ("claude", None),
+                ("openai", Some(0.5)),
+                ("gemini", Some(1.0))
+            ]
+        );
+        assert_eq!(
+            report
+                .by_tenant
+                .iter()
+                .map(|row| (row.key.as_str(), row.requests))
+                .collect::<Vec<_>>(),
+            vec![("team-a", 2), ("", 1)]
+        );
+
+        let markdown = render_markdown(&report);
+        assert!(markdown.starts_with("# 用量日报（2026-10-13 ~ 2026-10-13，UTC）"));
+        assert!(markdown.contains("| openai | 2 | 20 | 10 | 2.0000 | 50.00% |"));
+        assert!(markdown.contains("| (未指定) | 1 |"));
+
+        let csv = render_csv(&report);
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("dimension,key,requests,input_tokens,output_tokens,cost_usd,error_rate")
+        );
+        assert_eq!(lines.next(), Some("total,,3,30,15,5.000000,0.6667"));
+        assert!(csv.contains("tenant,team-a,2,20,10,4.500000,\n"));
+
+        assert_eq!(last_generated_key(&conn), None);
+        mark_generated(&conn, &report.report_key).unwrap();
+        assert_eq!(
+            last_generated_key(&conn).as_deref(),
+            Some("daily-2026-10-13")
+        );
+    }
+}
@@ -208,7 +208,11 @@ mod tests {
                 success: true,
                 output: Some("Done".to_string()),
                 error: None,
+                provider: None,
+                model: None,
+                usage: None,
             }],
+            usage: Default::default(),
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -6,14 +6,18 @@
 use lime_agent::{
     execute_skill_prompt as execute_agent_skill_prompt,
     execute_skill_workflow as execute_agent_skill_workflow, AsterAgentState, SkillEventEmitter,
-    SkillExecutionError, SkillWorkflowExecution, TauriAgentEvent,
+    SkillExecutionError, SkillModelChain, SkillWorkflowExecution, TauriAgentEvent,
+};
+use lime_services::prompt_library_service::PromptLibraryService;
+use lime_skills::{
+    ExecutionCallback, ExecutionUsage, LimeLlmProvider, LoadedSkillDefinition, StepUsage,
 };
-use lime_skills::{ExecutionCallback, LoadedSkillDefinition};
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use uuid::Uuid;
 
 use crate::commands::api_key_provider_cmd::ApiKeyProviderServiceState;
+use crate::commands::provider_pool_cmd::ProviderPoolServiceState;
 use crate::commands::skill_error::{
     format_skill_error, SKILL_ERR_EXECUTE_FAILED, SKILL_ERR_SESSION_INIT_FAILED,
 };
@@ -24,7 +28,8 @@ use crate::services::execution_tracker_service::{ExecutionTracker, RunSource};
 use super::execution_callback::TauriExecutionCallback;
 use super::load_executable_skill_definition;
 use super::runtime::{
-    build_skill_run_finish_decision, build_skill_run_start_metadata, prepare_skill_execution,
+    build_skill_memory_prompt, build_skill_run_finish_decision, build_skill_run_start_metadata,
+    execute_skill_with_mcp_tools, prepare_skill_execution, MCP_TOOL_EXECUTION_MODE,
 };
 use super::social_post::finalize_skill_output;
 
@@ -68,22 +73,28 @@ impl ExecutionCallback for TauriExecutionCallbackAdapter<'_> {
             .on_step_start(step_id, step_name, current_step, total_steps);
     }
 
-    fn on_step_complete(&self, step_id: &str, output: &str) {
-        self.inner.on_step_complete(step_id, output);
+    fn on_step_complete(&self, step_id: &str, output: &str, usage: &StepUsage) {
+        self.inner.on_step_complete(step_id, output, usage);
     }
 
     fn on_step_error(&self, step_id: &str, error: &str, will_retry: bool) {
         self.inner.on_step_error(step_id, error, will_retry);
     }
 
-    fn on_complete(&self, success: bool, final_output: Option<&str>, error: Option<&str>) {
+    fn on_complete(
+        &self,
+        success: bool,
+        final_output: Option<&str>,
+        error: Option<&str>,
+        usage: &ExecutionUsage,
+    ) {
         let mapped_error = if success {
             error.map(|value| value.to_string())
         } else {
             error.map(|value| ensure_skill_error_code(SKILL_ERR_EXECUTE_FAILED, value))
         };
         self.inner
-            .on_complete(success, final_output, mapped_error.as_deref());
+            .on_complete(success, final_output, mapped_error.as_deref(), usage);
     }
 }
 
@@ -121,6 +132,14 @@ fn map_execution_result(mut result: SkillExecutionResult) -> SkillExecutionResul
     result
 }
 
+/// 展开 Skill 正文与工作流步骤提示词中的 Prompt 库引用（`{{prompt:<id>}}`）
+fn expand_skill_prompt_references(db: &DbConnection, skill: &mut LoadedSkillDefinition) {
+    skill.markdown_content = PromptLibraryService::expand_references(db, &skill.markdown_content);
+    for step in &mut skill.workflow_steps {
+        step.prompt = PromptLibraryService::expand_references(db, &step.prompt);
+    }
+}
+
 pub async fn execute_named_skill(
     app_handle: &AppHandle,
     db: &DbConnection,
@@ -183,7 +202,23 @@ pub async fn execute_named_skill(
                     model_override_for_run
                 );
 
-                let skill = load_executable_skill_definition(&skill_name_for_run)?;
+                let mut skill = load_executable_skill_definition(&skill_name_for_run)?;
+                expand_skill_prompt_references(&db, &mut skill);
+                if skill.execution_mode == MCP_TOOL_EXECUTION_MODE {
+                    return execute_skill_mcp(
+                        &app_handle,
+                        &db,
+                        &api_key_provider_service,
+                        &config_manager,
+                        &skill,
+                        &user_input_for_run,
+                        &execution_id_for_run,
+                        &session_id_for_run,
+                        provider_override_for_run.as_deref(),
+                        model_override_for_run.as_deref(),
+                    )
+                    .await;
+                }
                 let prepared = prepare_skill_execution(
                     &app_handle,
                     &db,
@@ -215,6 +250,7 @@ pub async fn execute_named_skill(
                     &session_id_for_run,
                     &prepared.callback,
                     prepared.memory_prompt.as_deref(),
+                    Some(prepared.provider_selection.model_chain.as_ref()),
                 )
                 .await
             },
@@ -236,6 +272,104 @@ pub async fn execute_named_skill(
         .await
 }
 
+/// 以 MCP 工具模式执行 Skill（`execution_mode: mcp`），按单步骤上报进度
+#[allow(clippy::too_many_arguments)]
+async fn execute_skill_mcp(
+    app_handle: &AppHandle,
+    db: &DbConnection,
+    api_key_provider_service: &ApiKeyProviderServiceState,
+    config_manager: &GlobalConfigManagerState,
+    skill: &LoadedSkillDefinition,
+    user_input: &str,
+    execution_id: &str,
+    session_id: &str,
+    provider_override: Option<&str>,
+    model_override: Option<&str>,
+) -> Result<SkillExecutionResult, String> {
+    let pool_service = app_handle.state::<ProviderPoolServiceState>().0.clone();
+    let mcp = app_handle
+        .state::<crate::mcp::McpManagerState>()
+        .inner()
+        .clone();
+    let api_key_service = api_key_provider_service.0.clone();
+    let llm = match provider_override.or(skill.provider.as_deref()) {
+        Some(provider) => LimeLlmProvider::with_preferred_provider(
+            pool_service,
+            api_key_service,
+            db.clone(),
+            provider.to_string(),
+        ),
+        None => LimeLlmProvider::new(pool_service, api_key_service, db.clone()),
+    };
+    let memory_prompt = build_skill_memory_prompt(db, config_manager, session_id);
+
+    let callback = TauriExecutionCallback::new(app_handle.clone(), execution_id.to_string());
+    let callback_adapter = TauriExecutionCallbackAdapter::new(&callback);
+    callback_adapter.on_step_start("main", &skill.display_name, 1, 1);
+    let outcome = execute_skill_with_mcp_tools(
+        &llm,
+        &mcp,
+        skill,
+        user_input,
+        memory_prompt.as_deref(),
+        model_override,
+    )
+    .await;
+
+    let usage = ExecutionUsage::default();
+    let result = match outcome {
+        Ok(output) => {
+            let output = finalize_skill_output(
+                app_handle,
+                &skill.skill_name,
+                user_input,
+                execution_id,
+                &output,
+            );
+            callback_adapter.on_step_complete("main", &output, &StepUsage::default());
+            callback_adapter.on_complete(true, Some(&output), None, &usage);
+            SkillExecutionResult {
+                success: true,
+                output: Some(output.clone()),
+                error: None,
+                steps_completed: vec![StepResult {
+                    step_id: "main".to_string(),
+                    step_name: skill.display_name.clone(),
+                    success: true,
+                    output: Some(output),
+                    error: None,
+                    provider: None,
+                    model: None,
+                    usage: None,
+                }],
+                usage,
+            }
+        }
+        Err(error) => {
+            callback_adapter.on_step_error("main", &error, false);
+            callback_adapter.on_complete(false, None, Some(&error), &usage);
+            SkillExecutionResult {
+                success: false,
+                output: None,
+                error: Some(error.clone()),
+                steps_completed: vec![StepResult {
+                    step_id: "main".to_string(),
+                    step_name: skill.display_name.clone(),
+                    success: false,
+                    output: None,
+                    error: Some(error),
+                    provider: None,
+                    model: None,
+                    usage: None,
+                }],
+                usage,
+            }
+        }
+    };
+    emit_skill_final_done(app_handle, execution_id);
+    Ok(result)
+}
+
 pub async fn execute_skill_prompt(
     app_handle: &AppHandle,
     aster_state: &AsterAgentState,
@@ -245,6 +379,7 @@ pub async fn execute_skill_prompt(
     session_id: &str,
     callback: &TauriExecutionCallback,
     memory_prompt: Option<&str>,
+    model_chain: Option<&SkillModelChain>,
 ) -> Result<SkillExecutionResult, String> {
     let callback_adapter = TauriExecutionCallbackAdapter::new(callback);
     callback_adapter.on_step_start("main", &skill.display_name, 1, 1);
@@ -258,6 +393,7 @@ pub async fn execute_skill_prompt(
             session_id,
             memory_prompt,
             create_skill_event_emitter(app_handle),
+            model_chain,
         )
         .await
         .map_err(map_execution_error)?,
@@ -268,8 +404,15 @@ pub async fn execute_skill_prompt(
             .error
             .clone()
             .unwrap_or_else(|| format_skill_error(SKILL_ERR_EXECUTE_FAILED, "Unknown error"));
-        callback_adapter.on_step_error("main", &error_message, false);
-        callback_adapter.on_complete(false, None, Some(&error_message));
+        let failed_step_id = result
+            .steps_completed
+            .iter()
+            .rev()
+            .find(|step| !step.success)
+            .map(|step| step.step_id.as_str())
+            .unwrap_or("main");
+        callback_adapter.on_step_error(failed_step_id, &error_message, false);
+        callback_adapter.on_complete(false, None, Some(&error_message), &result.usage);
         emit_skill_final_done(app_handle, execution_id);
         return Ok(result);
     }
@@ -286,8 +429,13 @@ pub async fn execute_skill_prompt(
         step_result.output = Some(final_output.clone());
     }
 
-    callback_adapter.on_step_complete("main", &final_output);
-    callback_adapter.on_complete(true, Some(&final_output), None);
+    let main_usage = result
+        .steps_completed
+        .first()
+        .and_then(|step| step.usage.clone())
+        .unwrap_or_default();
+    callback_adapter.on_step_complete("main", &final_output, &main_usage);
+    callback_adapter.on_complete(true, Some(&final_output), None, &result.usage);
     emit_skill_final_done(app_handle, execution_id);
     Ok(result)
 }
@@ -301,6 +449,7 @@ pub async fn execute_skill_workflow(
     session_id: &str,
     callback: &TauriExecutionCallback,
     memory_prompt: Option<&str>,
+    model_chain: Option<&SkillModelChain>,
 ) -> Result<SkillExecutionResult, String> {
     let callback_adapter = TauriExecutionCallbackAdapter::new(callback);
     execute_agent_skill_workflow(SkillWorkflowExecution {
@@ -312,6 +461,7 @@ pub async fn execute_skill_workflow(
         callback: &callback_adapter,
         memory_prompt,
         emitter: create_skill_event_emitter(app_handle),
+        model_chain,
     })
     .await
     .map(map_execution_result)
@@ -327,6 +477,7 @@ pub async fn execute_skill_definition(
     session_id: &str,
     callback: &TauriExecutionCallback,
     memory_prompt: Option<&str>,
+    model_chain: Option<&SkillModelChain>,
 ) -> Result<SkillExecutionResult, String> {
     if skill.execution_mode == "workflow" && !skill.workflow_steps.is_empty() {
         execute_skill_workflow(
@@ -338,6 +489,7 @@ pub async fn execute_skill_definition(
             session_id,
             callback,
             memory_prompt,
+            model_chain,
         )
         .await
     } else {
@@ -350,6 +502,7 @@ pub async fn execute_skill_definition(
             session_id,
             callback,
             memory_prompt,
+            model_chain,
         )
         .await
     }
@@ -6,8 +6,8 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use tauri::{AppHandle, Emitter};
 
 use lime_skills::{
-    events, ExecutionCallback, ExecutionCompletePayload, StepCompletePayload, StepErrorPayload,
-    StepStartPayload,
+    events, ExecutionCallback, ExecutionCompletePayload, ExecutionUsage, StepCompletePayload,
+    StepErrorPayload, StepStartPayload, StepUsage,
 };
 
 /// Tauri 执行回调
@@ -73,18 +73,23 @@ impl ExecutionCallback for TauriExecutionCallback {
         }
     }
 
-    fn on_step_complete(&self, step_id: &str, output: &str) {
+    fn on_step_complete(&self, step_id: &str, output: &str, usage: &StepUsage) {
         let payload = StepCompletePayload {
             execution_id: self.execution_id.clone(),
             step_id: step_id.to_string(),
             output: output.to_string(),
+            usage: usage.clone(),
         };
 
         tracing::info!(
-            "[TauriExecutionCallback] 步骤完成: execution_id={}, step_id={}, output_len={}",
+            "[TauriExecutionCallback] 步骤完成: execution_id={}, step_id={}, output_len={}, model={:?}, tokens={}/{}, latency_ms={}",
             self.execution_id,
             step_id,
-            output.len()
+            output.len(),
+            usage.model,
+            usage.input_tokens,
+            usage.output_tokens,
+            usage.latency_ms
         );
 
         if let Err(e) = self.app_handle.emit(events::STEP_COMPLETE, &payload) {
@@ -118,12 +123,19 @@ impl ExecutionCallback for TauriExecutionCallback {
         }
     }
 
-    fn on_complete(&self, success: bool, final_output: Option<&str>, error: Option<&str>) {
+    fn on_complete(
+        &self,
+        success: bool,
+        final_output: Option<&str>,
+        error: Option<&str>,
+        usage: &ExecutionUsage,
+    ) {
         let payload = ExecutionCompletePayload {
             execution_id: self.execution_id.clone(),
             success,
             output: final_output.map(|s| s.to_string()),
             error: error.map(|s| s.to_string()),
+            usage: usage.clone(),
         };
 
         if success {
@@ -5,15 +5,24 @@ use crate::commands::aster_agent_cmd::{
     ensure_social_image_tool_registered,
 };
 use crate::commands::skill_error::{
-    format_skill_error, SKILL_ERR_PROVIDER_UNAVAILABLE, SKILL_ERR_SESSION_INIT_FAILED,
+    format_skill_error, SKILL_ERR_EXECUTE_FAILED, SKILL_ERR_PROVIDER_UNAVAILABLE,
+    SKILL_ERR_SESSION_INIT_FAILED,
 };
 use crate::config::GlobalConfigManagerState;
 use crate::database::dao::agent_run::AgentRunStatus;
 use crate::database::DbConnection;
+use crate::mcp::McpManagerState;
 use crate::services::execution_tracker_service::RunFinishDecision;
 use crate::services::memory_profile_prompt_service::{build_memory_prompt, MemoryPromptContext};
-use lime_skills::LoadedSkillDefinition;
+use crate::AppState;
+use lime_agent::{SkillCostEstimator, SkillModelChain};
+use lime_server::middleware::cost_cap::CostCapGuard;
+use lime_skills::{
+    apply_output_processors, filter_allowed_tools, LlmProvider, LoadedSkillDefinition,
+};
 use std::path::Path;
+use std::sync::Arc;
+use tauri::Manager;
 
 use super::execution::SkillExecutionResult;
 use super::execution_callback::TauriExecutionCallback;
@@ -27,6 +36,8 @@ pub struct SkillProviderSelection {
     pub requested_model: String,
     pub resolved_provider: String,
     pub resolved_model: String,
+    /// 模型候选链（记录执行期间的切换）
+    pub model_chain: Arc<SkillModelChain>,
 }
 
 pub struct PreparedSkillExecution {
@@ -43,8 +54,10 @@ const FALLBACK_TOOL_CAPABLE_PROVIDERS: &[(&str, &str)] = &[
     ("gemini", "gemini-2.0-flash"),
 ];
 const SOCIAL_POST_WITH_COVER_SKILL_NAME: &str = "social_post_with_cover";
+/// MCP 工具模式：不经过 Aster Agent，由 `LlmProvider::chat_with_tools` 直接调用凭证池
+pub const MCP_TOOL_EXECUTION_MODE: &str = "mcp";
 
-fn build_skill_memory_prompt(
+pub(super) fn build_skill_memory_prompt(
     db: &DbConnection,
     config_manager: &GlobalConfigManagerState,
     session_id: &str,
@@ -78,6 +91,65 @@ fn resolve_requested_provider(
     (requested_provider, requested_model)
 }
 
+/// 解析按优先级排列的 `(provider, model)` 候选
+///
+/// 显式覆盖时只使用覆盖值；首个候选沿用 Skill 的 `provider`，
+/// 其余未指定 Provider 的候选按模型名推断。
+fn resolve_model_candidates(
+    skill: &LoadedSkillDefinition,
+    provider_override: Option<&str>,
+    model_override: Option<&str>,
+) -> Vec<(String, String)> {
+    let mut candidates = skill.model_candidates.iter();
+    let primary = match candidates.next() {
+        Some(first) if provider_override.is_none() && model_override.is_none() => (
+            first
+                .provider
+                .clone()
+                .or_else(|| skill.provider.clone())
+                .unwrap_or_else(|| DEFAULT_SKILL_PROVIDER.to_string()),
+            first.model.clone(),
+        ),
+        _ => {
+            return vec![resolve_requested_provider(
+                skill,
+                provider_override,
+                model_override,
+            )]
+        }
+    };
+
+    std::iter::once(primary)
+        .chain(candidates.map(|candidate| {
+            let provider = candidate
+                .provider
+                .clone()
+                .unwrap_or_else(|| infer_provider_from_model(&candidate.model));
+            (provider, candidate.model.clone())
+        }))
+        .collect()
+}
+
+fn infer_provider_from_model(model: &str) -> String {
+    let normalized = model.trim().to_ascii_lowercase();
+    if let Some((prefix, _)) = normalized.split_once('/') {
+        if !prefix.is_empty() && prefix != "models" {
+            return prefix.to_string();
+        }
+    }
+    if normalized.contains("claude") {
+        "anthropic".to_string()
+    } else if normalized.contains("gemini") {
+        "gemini".to_string()
+    } else if normalized.contains("qwen") {
+        "qwen".to_string()
+    } else if normalized.contains("glm") {
+        "zhipuai".to_string()
+    } else {
+        "openai".to_string()
+    }
+}
+
 async fn ensure_skill_agent_ready(
     app_handle: &tauri::AppHandle,
     db: &DbConnection,
@@ -124,66 +196,61 @@ async fn ensure_skill_agent_ready(
     Ok(())
 }
 
+/// 按费用上限的计费路径为 Skill 步骤计费（CNY 定价按配置汇率折算为 USD）
+struct CostCapSkillEstimator {
+    guard: Arc<CostCapGuard>,
+    db: DbConnection,
+}
+
+impl SkillCostEstimator for CostCapSkillEstimator {
+    fn estimate_cost_usd(&self, model: &str, input_tokens: u32, output_tokens: u32) -> Option<f64> {
+        let pricing = self
+            .guard
+            .lookup_pricing(Some(&self.db), model)
+            .filter(|p| p.input_per_million.is_some() || p.output_per_million.is_some())?;
+        Some(
+            self.guard
+                .cost_for_pricing(Some(&pricing), input_tokens, output_tokens),
+        )
+    }
+}
+
 async fn configure_skill_provider_with_fallback(
     aster_state: &AsterAgentState,
     db: &DbConnection,
+    cost_cap_guard: Option<Arc<CostCapGuard>>,
     session_id: &str,
-    requested_provider: &str,
-    requested_model: &str,
+    candidates: Vec<(String, String)>,
 ) -> Result<SkillProviderSelection, String> {
-    let mut configure_result = aster_state
-        .configure_provider_from_pool(db, requested_provider, requested_model, session_id)
-        .await;
-
-    if configure_result.is_err() {
-        tracing::warn!(
-            "[execute_skill] 首选 Provider {} 配置失败: {:?}，尝试 fallback",
-            requested_provider,
-            configure_result.as_ref().err()
-        );
-
-        for (fallback_provider, fallback_model) in FALLBACK_TOOL_CAPABLE_PROVIDERS {
-            if *fallback_provider == requested_provider {
-                continue;
-            }
-            match aster_state
-                .configure_provider_from_pool(db, fallback_provider, fallback_model, session_id)
-                .await
-            {
-                Ok(config) => {
-                    tracing::info!(
-                        "[execute_skill] Fallback 到 {} / {} 成功",
-                        fallback_provider,
-                        fallback_model
-                    );
-                    configure_result = Ok(config);
-                    break;
-                }
-                Err(error) => {
-                    tracing::warn!(
-                        "[execute_skill] Fallback {} 也失败: {}",
-                        fallback_provider,
-                        error
-                    );
-                }
-            }
-        }
+    let (requested_provider, requested_model) = candidates[0].clone();
+    let mut model_chain = SkillModelChain::new(db.clone(), session_id, candidates)
+        .with_configure_fallbacks(FALLBACK_TOOL_CAPABLE_PROVIDERS);
+    if let Some(guard) = cost_cap_guard {
+        model_chain = model_chain.with_cost_estimator(Arc::new(CostCapSkillEstimator {
+            guard,
+            db: db.clone(),
+        }));
     }
 
-    let configured_provider = configure_result.map_err(|error| {
-        format_skill_error(
-            SKILL_ERR_PROVIDER_UNAVAILABLE,
-            format!(
-                "无法配置任何可用的 Provider（需要支持工具调用的 Provider，如 Anthropic、OpenAI 或 Google）: {error}"
-            ),
-        )
-    })?;
+    let (resolved_provider, resolved_model) =
+        model_chain
+            .activate_next(aster_state)
+            .await
+            .map_err(|error| {
+                format_skill_error(
+                    SKILL_ERR_PROVIDER_UNAVAILABLE,
+                    format!(
+                        "无法配置任何可用的 Provider（需要支持工具调用的 Provider，如 Anthropic、OpenAI 或 Google）: {error}"
+                    ),
+                )
+            })?;
 
     Ok(SkillProviderSelection {
-        requested_provider: requested_provider.to_string(),
-        requested_model: requested_model.to_string(),
-        resolved_provider: configured_provider.provider_name,
-        resolved_model: configured_provider.model_name,
+        requested_provider,
+        requested_model,
+        resolved_provider,
+        resolved_model,
+        model_chain: Arc::new(model_chain),
     })
 }
 
@@ -208,14 +275,16 @@ pub async fn prepare_skill_execution(
     )
     .await?;
 
-    let (requested_provider, requested_model) =
-        resolve_requested_provider(skill, provider_override, model_override);
+    let cost_cap_guard = match app_handle.try_state::<AppState>() {
+        Some(state) => Some(state.read().await.cost_cap_guard.clone()),
+        None => None,
+    };
     let provider_selection = configure_skill_provider_with_fallback(
         aster_state,
         db,
+        cost_cap_guard,
         session_id,
-        &requested_provider,
-        &requested_model,
+        resolve_model_candidates(skill, provider_override, model_override),
     )
     .await?;
 
@@ -234,6 +303,51 @@ pub async fn prepare_skill_execution(
     })
 }
 
+/// 以 MCP 工具模式执行 Skill，返回经输出后处理器处理后的最终输出
+///
+/// 只向模型提供 Skill `allowed-tools` 声明的 MCP 工具，未声明时不提供任何工具。
+pub async fn execute_skill_with_mcp_tools(
+    llm: &dyn LlmProvider,
+    mcp: &McpManagerState,
+    skill: &LoadedSkillDefinition,
+    user_input: &str,
+    memory_prompt: Option<&str>,
+    model_override: Option<&str>,
+) -> Result<String, String> {
+    let available = mcp.lock().await.list_tools().await.map_err(|error| {
+        format_skill_error(
+            SKILL_ERR_EXECUTE_FAILED,
+            format!("获取 MCP 工具失败: {error}"),
+        )
+    })?;
+    let tools = filter_allowed_tools(available, skill.allowed_tools.as_deref());
+    if let Some(allowed) = &skill.allowed_tools {
+        let missing: Vec<_> = allowed
+            .iter()
+            .filter(|name| !tools.iter().any(|tool| &tool.name == *name))
+            .collect();
+        if !missing.is_empty() {
+            tracing::warn!(
+                "[execute_skill] Skill {} 声明的工具未在已启动的 MCP 服务器中找到: {:?}",
+                skill.skill_name,
+                missing
+            );
+        }
+    }
+
+    let system_prompt = match memory_prompt {
+        Some(memory_prompt) => format!("{}\n\n{memory_prompt}", skill.markdown_content),
+        None => skill.markdown_content.clone(),
+    };
+    let model = model_override.or(skill.model.as_deref());
+    let output = llm
+        .chat_with_tools(&system_prompt, user_input, model, &tools, mcp)
+        .await
+        .map_err(|error| format_skill_error(SKILL_ERR_EXECUTE_FAILED, error.to_string()))?;
+    apply_output_processors(&skill.output_processors, &output)
+        .map_err(|error| format_skill_error(SKILL_ERR_EXECUTE_FAILED, error.to_string()))
+}
+
 pub fn build_skill_run_start_metadata(
     skill_name: &str,
     execution_id: &str,
@@ -250,6 +364,58 @@ pub fn build_skill_run_start_metadata(
     })
 }
 
+fn apply_provider_selection_metadata(
+    metadata: &mut serde_json::Value,
+    provider_override: Option<&str>,
+    model_override: Option<&str>,
+    provider_selection: Option<&SkillProviderSelection>,
+) {
+    let Some(selection) = provider_selection else {
+        metadata["requested_provider"] = serde_json::json!(provider_override);
+        metadata["requested_model"] = serde_json::json!(model_override);
+        return;
+    };
+
+    // 执行中发生模型切换时，以最终生效的候选为准
+    let (resolved_provider, resolved_model) =
+        selection.model_chain.current().unwrap_or_else(|| {
+            (
+                selection.resolved_provider.clone(),
+                selection.resolved_model.clone(),
+            )
+        });
+    metadata["requested_provider"] = serde_json::json!(selection.requested_provider);
+    metadata["requested_model"] = serde_json::json!(selection.requested_model);
+    metadata["resolved_provider"] = serde_json::json!(resolved_provider);
+    metadata["resolved_model"] = serde_json::json!(resolved_model);
+    metadata["model_fallback_chain"] = serde_json::json!(selection.model_chain.attempts());
+}
+
+/// 记录每个步骤实际使用的 Provider / 模型
+fn with_step_models(
+    mut metadata: serde_json::Value,
+    execution: &SkillExecutionResult,
+) -> serde_json::Value {
+    let step_models: Vec<serde_json::Value> = execution
+        .steps_completed
+        .iter()
+        .filter(|step| step.provider.is_some() || step.model.is_some() || step.usage.is_some())
+        .map(|step| {
+            serde_json::json!({
+                "step_id": step.step_id,
+                "provider": step.provider,
+                "model": step.model,
+                "usage": step.usage,
+            })
+        })
+        .collect();
+    if !step_models.is_empty() {
+        metadata["step_models"] = serde_json::json!(step_models);
+    }
+    metadata["usage"] = serde_json::json!(execution.usage);
+    metadata
+}
+
 fn build_success_metadata(
     skill_name: &str,
     execution_id: &str,
@@ -265,15 +431,12 @@ fn build_success_metadata(
         "model_override": model_override,
     });
 
-    if let Some(selection) = provider_selection {
-        metadata["requested_provider"] = serde_json::json!(selection.requested_provider);
-        metadata["requested_model"] = serde_json::json!(selection.requested_model);
-        metadata["resolved_provider"] = serde_json::json!(selection.resolved_provider);
-        metadata["resolved_model"] = serde_json::json!(selection.resolved_model);
-    } else {
-        metadata["requested_provider"] = serde_json::json!(provider_override);
-        metadata["requested_model"] = serde_json::json!(model_override);
-    }
+    apply_provider_selection_metadata(
+        &mut metadata,
+        provider_override,
+        model_override,
+        provider_selection,
+    );
 
     if skill_name == SOCIAL_POST_WITH_COVER_SKILL_NAME {
         metadata["workflow"] = serde_json::json!("social_content_pipeline_v1");
@@ -303,15 +466,12 @@ fn build_error_metadata(
     if let Some(value) = success {
         metadata["success"] = serde_json::json!(value);
     }
-    if let Some(selection) = provider_selection {
-        metadata["requested_provider"] = serde_json::json!(selection.requested_provider);
-        metadata["requested_model"] = serde_json::json!(selection.requested_model);
-        metadata["resolved_provider"] = serde_json::json!(selection.resolved_provider);
-        metadata["resolved_model"] = serde_json::json!(selection.resolved_model);
-    } else {
-        metadata["requested_provider"] = serde_json::json!(provider_override);
-        metadata["requested_model"] = serde_json::json!(model_override);
-    }
+    apply_provider_selection_metadata(
+        &mut metadata,
+        provider_override,
+        model_override,
+        provider_selection,
+    );
 
     metadata
 }
@@ -329,26 +489,32 @@ pub fn build_skill_run_finish_decision(
             status: AgentRunStatus::Success,
             error_code: None,
             error_message: None,
-            metadata: Some(build_success_metadata(
-                skill_name,
-                execution_id,
-                provider_override,
-                model_override,
-                provider_selection,
-                collect_social_artifact_paths_from_output(execution.output.as_deref()),
+            metadata: Some(with_step_models(
+                build_success_metadata(
+                    skill_name,
+                    execution_id,
+                    provider_override,
+                    model_override,
+                    provider_selection,
+                    collect_social_artifact_paths_from_output(execution.output.as_deref()),
+                ),
+                execution,
             )),
         },
         Ok(execution) => RunFinishDecision {
             status: AgentRunStatus::Error,
             error_code: Some("skill_execute_failed".to_string()),
             error_message: execution.error.clone(),
-            metadata: Some(build_error_metadata(
-                skill_name,
-                execution_id,
-                provider_override,
-                model_override,
-                provider_selection,
-                Some(false),
+            metadata: Some(with_step_models(
+                build_error_metadata(
+                    skill_name,
+                    execution_id,
+                    provider_override,
+                    model_override,
+                    provider_selection,
+                    Some(false),
+                ),
+                execution,
             )),
         },
         Err(error) => RunFinishDecision {
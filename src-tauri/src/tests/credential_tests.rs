@@ -1401,6 +1401,7 @@ fn arb_quota_config() -> impl Strategy<Value = QuotaExceededConfig> {
             switch_project,
             switch_preview_model,
             cooldown_seconds,
+            ..Default::default()
         },
     )
 }
@@ -1590,6 +1591,7 @@ proptest! {
             switch_project: true,
             switch_preview_model: false,
             cooldown_seconds: 300,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
 
@@ -1650,6 +1652,7 @@ proptest! {
             switch_project: false,
             switch_preview_model: false,
             cooldown_seconds: 300,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
 
@@ -1695,6 +1698,7 @@ proptest! {
             switch_project: true,
             switch_preview_model: false,
             cooldown_seconds: 300,
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
 
@@ -1747,6 +1751,7 @@ proptest! {
             switch_project: true,
             switch_preview_model: true,
             cooldown_seconds: 0, // 立即过期
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
 
@@ -1809,6 +1814,7 @@ proptest! {
             switch_project: true,
             switch_preview_model: true,
             cooldown_seconds: 3600, // 1 小时
+            ..Default::default()
         };
         let manager = QuotaManager::new(config);
 
@@ -1862,11 +1868,13 @@ proptest! {
             switch_project: true,
             switch_preview_model: true,
             cooldown_seconds: 0, // 立即过期
+            ..Default::default()
         };
         let active_config = QuotaExceededConfig {
             switch_project: true,
             switch_preview_model: true,
             cooldown_seconds: 3600, // 1 小时
+            ..Default::default()
         };
 
         // 使用一个管理器，但手动设置不同的过期时间
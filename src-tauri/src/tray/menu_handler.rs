@@ -35,11 +35,12 @@ pub mod menu_events {
     pub const MODEL_SELECTED: &str = "tray-model-selected";
 }
 
+/// 切换模型事件载荷（聊天界面据此更新 Provider / 模型偏好）
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct TrayModelSelectedPayload {
-    provider_type: String,
-    model: String,
+pub struct TrayModelSelectedPayload {
+    pub provider_type: String,
+    pub model: String,
 }
 
 /// 处理菜单事件
@@ -2,12 +2,22 @@
 //!
 //! 提供前端调用的语音输入相关命令。
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
 use lime_core::config::{VoiceInputConfig, VoiceInstruction};
+use lime_services::voice_asr_service::AsrService;
 use lime_services::voice_command_service;
-use tauri::{command, AppHandle};
+use parking_lot::Mutex;
+use tauri::{command, AppHandle, Emitter};
+use voice_core::types::WhisperModel;
+use voice_core::{WhisperModelManager, WhisperModelStatus};
 
 use super::config;
-use super::recording_service::{AudioDeviceInfo, RecordingServiceState};
+use super::recording_service::{
+    vad_config_from, AudioDeviceInfo, RecordingResponse, RecordingServiceState,
+};
 use tauri::State;
 
 fn normalize_shortcut(value: Option<String>) -> Option<String> {
@@ -130,15 +140,41 @@ pub async fn close_voice_window(app: AppHandle) -> Result<(), String> {
 
 pub use lime_services::voice_command_service::{PolishResult, TranscribeResult};
 
+/// 流式识别中间结果事件
+pub const ASR_PARTIAL_RESULT_EVENT: &str = "asr:partial_result";
+
 /// 执行语音识别
+///
+/// `stream` 为 true 时使用流式识别，识别过程中通过 `asr:partial_result` 事件推送中间结果。
 #[command]
 pub async fn transcribe_audio(
+    app: AppHandle,
     audio_data: Vec<u8>,
     sample_rate: u32,
     credential_id: Option<String>,
+    stream: Option<bool>,
 ) -> Result<TranscribeResult, String> {
-    voice_command_service::transcribe_audio(&audio_data, sample_rate, credential_id.as_deref())
-        .await
+    let partials = if stream.unwrap_or(false) {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(partial) = rx.recv().await {
+                if let Err(e) = app.emit(ASR_PARTIAL_RESULT_EVENT, &partial) {
+                    tracing::warn!("[语音识别] 推送中间结果失败: {}", e);
+                }
+            }
+        });
+        Some(tx)
+    } else {
+        None
+    };
+
+    voice_command_service::transcribe_audio(
+        &audio_data,
+        sample_rate,
+        credential_id.as_deref(),
+        partials,
+    )
+    .await
 }
 
 /// 润色文本
@@ -167,14 +203,56 @@ pub struct StopRecordingResult {
     pub duration: f32,
 }
 
+/// VAD 切分出音频段的事件
+pub const VOICE_RECORDING_SEGMENT_EVENT: &str = "voice-recording-segment";
+
+/// 转发录音服务的 VAD 事件到前端
+///
+/// - 自动停止：发送 `voice-stop-recording`，前端按正常流程调用 `stop_recording` 取回音频
+/// - 切分音频段：发送 `voice-recording-segment`，载荷与 `stop_recording` 的返回值相同
+fn spawn_vad_event_forwarder(app: AppHandle, events: std::sync::mpsc::Receiver<RecordingResponse>) {
+    std::thread::spawn(move || {
+        while let Ok(event) = events.recv() {
+            let result = match event {
+                RecordingResponse::AutoStopped => {
+                    tracing::info!("[录音命令] VAD 自动停止录音");
+                    app.emit("voice-stop-recording", ())
+                }
+                RecordingResponse::Segment(audio) => app.emit(
+                    VOICE_RECORDING_SEGMENT_EVENT,
+                    StopRecordingResult {
+                        audio_data: audio.to_pcm16le_bytes(),
+                        sample_rate: audio.sample_rate,
+                        duration: audio.duration_secs,
+                    },
+                ),
+                _ => continue,
+            };
+            if let Err(e) = result {
+                tracing::warn!("[录音命令] 推送 VAD 事件失败: {}", e);
+            }
+        }
+    });
+}
+
 /// 开始录音
+///
+/// 语音输入配置启用 VAD 时，说话结束后自动停止录音或切分音频段。
 #[command]
 pub async fn start_recording(
+    app: AppHandle,
     recording_service: State<'_, RecordingServiceState>,
     device_id: Option<String>,
 ) -> Result<(), String> {
     tracing::info!("[录音命令] 收到开始录音请求，设备ID: {:?}", device_id);
+    let vad = config::load_voice_config()
+        .map(|voice_config| voice_config.vad)
+        .unwrap_or_default();
     let mut service = recording_service.0.lock();
+    service.set_vad_config(vad_config_from(&vad));
+    if let Some(events) = service.take_event_receiver() {
+        spawn_vad_event_forwarder(app, events);
+    }
     let result = service.start(device_id);
     tracing::info!("[录音命令] 开始录音结果: {:?}", result.is_ok());
     result
@@ -265,3 +343,84 @@ pub async fn get_recording_status(
     );
     Ok(status)
 }
+
+/// Whisper 模型下载进度事件
+pub const WHISPER_MODEL_DOWNLOAD_PROGRESS_EVENT: &str = "whisper-model-download-progress";
+
+/// 进行中的模型下载及其取消标记
+static MODEL_DOWNLOADS: OnceLock<Mutex<HashMap<WhisperModel, Arc<AtomicBool>>>> = OnceLock::new();
+
+fn model_downloads() -> &'static Mutex<HashMap<WhisperModel, Arc<AtomicBool>>> {
+    MODEL_DOWNLOADS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn whisper_model_manager() -> Result<WhisperModelManager, String> {
+    Ok(WhisperModelManager::new(AsrService::whisper_models_dir()?))
+}
+
+/// 列出可下载的 Whisper 模型及本地状态
+#[command]
+pub async fn list_whisper_models() -> Result<Vec<WhisperModelStatus>, String> {
+    Ok(whisper_model_manager()?.list())
+}
+
+/// 下载 Whisper 模型
+///
+/// 已有未完成的下载时续传，下载过程中通过 `whisper-model-download-progress` 事件推送进度。
+/// 返回模型文件路径。
+#[command]
+pub async fn download_whisper_model(app: AppHandle, model: WhisperModel) -> Result<String, String> {
+    let manager = whisper_model_manager()?;
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut downloads = model_downloads().lock();
+        if downloads.contains_key(&model) {
+            return Err(format!("模型 {} 正在下载中", model.filename()));
+        }
+        downloads.insert(model, cancel.clone());
+    }
+
+    let result = manager
+        .download(model, &cancel, |progress| {
+            if let Err(e) = app.emit(WHISPER_MODEL_DOWNLOAD_PROGRESS_EVENT, progress) {
+                tracing::warn!("[模型下载] 推送下载进度失败: {}", e);
+            }
+        })
+        .await;
+    model_downloads().lock().remove(&model);
+    if result.is_ok() {
+        // 模型文件已替换，丢弃旧实例
+        AsrService::evict_whisper_model(model);
+    }
+
+    result
+        .map(|path| path.to_string_lossy().to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// 取消 Whisper 模型下载（保留已下载部分，可续传）
+#[command]
+pub async fn cancel_whisper_model_download(model: WhisperModel) -> Result<bool, String> {
+    Ok(match model_downloads().lock().get(&model) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    })
+}
+
+/// 删除 Whisper 模型（含未完成的下载）
+#[command]
+pub async fn delete_whisper_model(model: WhisperModel) -> Result<bool, String> {
+    if model_downloads().lock().contains_key(&model) {
+        return Err(format!(
+            "模型 {} 正在下载中，请先取消下载",
+            model.filename()
+        ));
+    }
+    AsrService::evict_whisper_model(model);
+    whisper_model_manager()?
+        .delete(model)
+        .map_err(|e| e.to_string())
+}
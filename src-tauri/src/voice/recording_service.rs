@@ -4,6 +4,6 @@
 //! 本模块保留兼容导出。
 
 pub use lime_services::voice_recording_service::{
-    create_recording_service_state, list_audio_devices, AudioDeviceInfo, RecordingCommand,
-    RecordingResponse, RecordingService, RecordingServiceState,
+    create_recording_service_state, list_audio_devices, vad_config_from, AudioDeviceInfo,
+    RecordingCommand, RecordingResponse, RecordingService, RecordingServiceState,
 };
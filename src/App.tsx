@@ -11,6 +11,7 @@
 import React, { Suspense, lazy, useState, useEffect, useCallback } from "react";
 import styled from "styled-components";
 import { getWindowsStartupDiagnostics } from "@/lib/api/serverRuntime";
+import { getDatabaseRecoveryReport } from "@/lib/api/databaseRecovery";
 import { withI18nPatch } from "./i18n/withI18nPatch";
 import { SplashScreen } from "./components/SplashScreen";
 import { AppSidebar } from "./components/AppSidebar";
@@ -29,6 +30,7 @@ import { useGlobalTrayModelSync } from "./hooks/useGlobalTrayModelSync";
 import { ComponentDebugProvider } from "./contexts/ComponentDebugContext";
 import { SoundProvider } from "./contexts/SoundProvider";
 import { ComponentDebugOverlay } from "./components/dev";
+import { McpElicitationDialog } from "./components/mcp/McpElicitationDialog";
 import {
   AgentPageParams,
   getThemeByWorkspacePage,
@@ -468,6 +470,55 @@ function AppContent() {
       });
   }, []);
 
+  useEffect(() => {
+    if (!isTauriDesktopEnvironment()) {
+      return;
+    }
+
+    void getDatabaseRecoveryReport()
+      .then((report) => {
+        if (!report.corrupted) {
+          return;
+        }
+
+        const details = [
+          report.message,
+          report.lost_tables.length > 0
+            ? `未能恢复的数据表：${report.lost_tables.join("、")}`
+            : null,
+          report.quarantined_path
+            ? `损坏文件已保存至：${report.quarantined_path}`
+            : null,
+        ]
+          .filter(Boolean)
+          .join("\n");
+
+        if (
+          report.action === "restored_backup" ||
+          report.action === "salvaged"
+        ) {
+          toast.warning("检测到数据库损坏，已自动恢复", {
+            description: details,
+            duration: 15000,
+          });
+          return;
+        }
+
+        toast.error(
+          report.action === "rebuilt"
+            ? "数据库已损坏且无法恢复，已重建空数据库"
+            : "数据库损坏，自动恢复失败",
+          {
+            description: details,
+            duration: Infinity,
+          },
+        );
+      })
+      .catch((error) => {
+        console.warn("[App] 获取数据库恢复报告失败:", error);
+      });
+  }, []);
+
   useEffect(() => {
     void ensureDefaultWorkspaceReady()
       .then((result) => {
@@ -874,6 +925,8 @@ function AppContent() {
             />
           </Suspense>
 
+          <McpElicitationDialog />
+
           <ComponentDebugOverlay />
         </AppContainer>
       </ComponentDebugProvider>
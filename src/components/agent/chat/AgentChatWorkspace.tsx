@@ -3891,6 +3891,10 @@ export function AgentChatWorkspace({
     clearMessages,
     deleteMessage,
     editMessage,
+    turnAttempts = [],
+    isRegenerating = false,
+    regenerateLastTurn,
+    switchTurnAttempt,
     handlePermissionResponse,
     pendingActions = [],
     triggerAIGuide,
@@ -11982,6 +11986,10 @@ export function AgentChatWorkspace({
                       currentTurnId={currentTurnId}
                       onDeleteMessage={deleteMessage}
                       onEditMessage={editMessage}
+                      onRegenerateLastTurn={regenerateLastTurn}
+                      turnAttempts={turnAttempts}
+                      onSwitchTurnAttempt={switchTurnAttempt}
+                      isRegenerating={isRegenerating}
                       onA2UISubmit={handleA2UISubmit}
                       onWriteFile={handleWriteFile}
                       onFileClick={handleFileClick}
@@ -12007,6 +12015,10 @@ export function AgentChatWorkspace({
                     currentTurnId={currentTurnId}
                     onDeleteMessage={deleteMessage}
                     onEditMessage={editMessage}
+                    onRegenerateLastTurn={regenerateLastTurn}
+                    turnAttempts={turnAttempts}
+                    onSwitchTurnAttempt={switchTurnAttempt}
+                    isRegenerating={isRegenerating}
                     onA2UISubmit={handleA2UISubmit}
                     onWriteFile={handleWriteFile}
                     onFileClick={handleFileClick}
@@ -509,4 +509,62 @@ describe("MessageList", () => {
     ).toBeTruthy();
     expect(timelineNodes[0]?.previousElementSibling).toBe(streamingNodes[1]);
   });
+
+  it("最后一条助手回复应显示版本切换与重新生成入口", () => {
+    const now = new Date();
+    const messages: Message[] = [
+      { id: "msg-user", role: "user", content: "写一首诗", timestamp: now },
+      {
+        id: "msg-assistant",
+        role: "assistant",
+        content: "春眠不觉晓",
+        timestamp: now,
+      },
+    ];
+    const attempt = (id: string, attempt: number, active: boolean) => ({
+      id,
+      session_id: "session-1",
+      attempt,
+      active,
+      overrides: {},
+      preview: "",
+      created_at: 0,
+    });
+    const onSwitchTurnAttempt = vi.fn();
+    const onRegenerateLastTurn = vi.fn();
+
+    const container = render(messages, {
+      turnAttempts: [attempt("a1", 1, false), attempt("a2", 2, true)],
+      onSwitchTurnAttempt,
+      onRegenerateLastTurn,
+    });
+
+    expect(container.textContent).toContain("2 / 2");
+    const previous = container.querySelector(
+      'button[aria-label="上一个版本"]',
+    ) as HTMLButtonElement;
+    act(() => {
+      previous.click();
+    });
+    expect(onSwitchTurnAttempt).toHaveBeenCalledWith("a1");
+
+    act(() => {
+      (
+        container.querySelector(
+          'button[aria-label="重新生成"]',
+        ) as HTMLButtonElement
+      ).click();
+    });
+    const submit = Array.from(container.querySelectorAll("button")).find(
+      (button) => button.textContent === "重新生成",
+    );
+    act(() => {
+      submit?.click();
+    });
+    expect(onRegenerateLastTurn).toHaveBeenCalledWith({
+      model: undefined,
+      temperature: undefined,
+      instruction: undefined,
+    });
+  });
 });
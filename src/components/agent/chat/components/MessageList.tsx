@@ -8,10 +8,16 @@ import {
   FileText,
   Loader2,
   ExternalLink,
+  Volume2,
 } from "lucide-react";
 import { Button } from "@/components/ui/button";
 import { toast } from "sonner";
+import { speakText } from "@/lib/api/voiceTools";
 import type { Artifact } from "@/lib/artifact/types";
+import type {
+  AgentRuntimeTurnAttempt,
+  TurnAttemptOverrides,
+} from "@/lib/api/agentRuntime";
 import {
   MessageListContainer,
   MessageWrapper,
@@ -28,6 +34,7 @@ import { MarkdownRenderer } from "./MarkdownRenderer";
 import { StreamingRenderer } from "./StreamingRenderer";
 import { TokenUsageDisplay } from "./TokenUsageDisplay";
 import { AgentThreadTimeline } from "./AgentThreadTimeline";
+import { TurnAttemptControls } from "./TurnAttemptControls";
 import {
   formatArtifactWritePhaseLabel,
   resolveArtifactPreviewText,
@@ -58,6 +65,14 @@ interface MessageListProps {
   assistantLabel?: string;
   onDeleteMessage?: (id: string) => void;
   onEditMessage?: (id: string, content: string) => void;
+  /** 重新生成最后一轮回复（可覆盖模型、温度并追加补充要求） */
+  onRegenerateLastTurn?: (overrides: TurnAttemptOverrides) => void;
+  /** 最后一轮回复的全部版本 */
+  turnAttempts?: AgentRuntimeTurnAttempt[];
+  /** 切换最后一轮回复的版本 */
+  onSwitchTurnAttempt?: (attemptId: string) => void;
+  /** 是否正在重新生成 */
+  isRegenerating?: boolean;
   /** A2UI 表单提交回调 */
   onA2UISubmit?: (formData: A2UIFormData, messageId: string) => void;
   /** 是否渲染消息内联 A2UI */
@@ -98,6 +113,10 @@ const MessageListInner: React.FC<MessageListProps> = ({
   assistantLabel = "Lime",
   onDeleteMessage,
   onEditMessage,
+  onRegenerateLastTurn,
+  turnAttempts = [],
+  onSwitchTurnAttempt,
+  isRegenerating = false,
   onA2UISubmit,
   renderA2UIInline = true,
   a2uiFormDataMap,
@@ -115,6 +134,7 @@ const MessageListInner: React.FC<MessageListProps> = ({
   const scrollRef = useRef<HTMLDivElement>(null);
   const containerRef = useRef<HTMLDivElement>(null);
   const [copiedId, setCopiedId] = useState<string | null>(null);
+  const [speakingId, setSpeakingId] = useState<string | null>(null);
   const [editingId, setEditingId] = useState<string | null>(null);
   const [editContent, setEditContent] = useState("");
   const [isUserScrolling, setIsUserScrolling] = useState(false);
@@ -229,6 +249,18 @@ const MessageListInner: React.FC<MessageListProps> = ({
     }
   };
 
+  const handleSpeak = async (content: string, id: string) => {
+    if (speakingId) return;
+    setSpeakingId(id);
+    try {
+      await speakText(content);
+    } catch (e) {
+      toast.error(e instanceof Error ? e.message : String(e));
+    } finally {
+      setSpeakingId(null);
+    }
+  };
+
   const handleEdit = (msg: Message) => {
     setEditingId(msg.id);
     setEditContent(msg.content);
@@ -465,6 +497,22 @@ const MessageListInner: React.FC<MessageListProps> = ({
                     <Copy size={12} />
                   )}
                 </Button>
+                {msg.role === "assistant" && (
+                  <Button
+                    variant="ghost"
+                    size="icon"
+                    className="h-6 w-6 text-muted-foreground hover:text-foreground"
+                    title="朗读"
+                    disabled={speakingId !== null}
+                    onClick={() => handleSpeak(msg.content, msg.id)}
+                  >
+                    {speakingId === msg.id ? (
+                      <Loader2 size={12} className="animate-spin" />
+                    ) : (
+                      <Volume2 size={12} />
+                    )}
+                  </Button>
+                )}
                 {msg.role === "user" && (
                   <Button
                     variant="ghost"
@@ -485,6 +533,17 @@ const MessageListInner: React.FC<MessageListProps> = ({
                 </Button>
               </MessageActions>
             )}
+
+            {onRegenerateLastTurn &&
+              msg.id === lastAssistantMessageId &&
+              !msg.isThinking && (
+                <TurnAttemptControls
+                  attempts={turnAttempts}
+                  isRegenerating={isRegenerating}
+                  onRegenerate={onRegenerateLastTurn}
+                  onSwitchAttempt={onSwitchTurnAttempt}
+                />
+              )}
           </MessageBubble>
         </ContentColumn>
       </MessageWrapper>
@@ -0,0 +1,179 @@
+/**
+ * 最后一轮回复的重新生成与版本切换
+ *
+ * - 重新生成：可选覆盖模型、温度并追加补充要求，原回复保留为一个版本
+ * - 版本切换：在同一轮的多次生成之间前后切换
+ */
+
+import React, { useMemo, useState } from "react";
+import { ChevronLeft, ChevronRight, Loader2, RefreshCw } from "lucide-react";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Textarea } from "@/components/ui/textarea";
+import type {
+  AgentRuntimeTurnAttempt,
+  TurnAttemptOverrides,
+} from "@/lib/api/agentRuntime";
+
+interface TurnAttemptControlsProps {
+  attempts: AgentRuntimeTurnAttempt[];
+  isRegenerating?: boolean;
+  onRegenerate: (overrides: TurnAttemptOverrides) => void;
+  onSwitchAttempt?: (attemptId: string) => void;
+}
+
+function describeOverrides(overrides: TurnAttemptOverrides): string {
+  return [
+    overrides.model,
+    overrides.temperature !== undefined && overrides.temperature !== null
+      ? `温度 ${overrides.temperature}`
+      : null,
+    overrides.instruction ? `补充：${overrides.instruction}` : null,
+  ]
+    .filter(Boolean)
+    .join(" · ");
+}
+
+export const TurnAttemptControls: React.FC<TurnAttemptControlsProps> = ({
+  attempts,
+  isRegenerating = false,
+  onRegenerate,
+  onSwitchAttempt,
+}) => {
+  const [formOpen, setFormOpen] = useState(false);
+  const [model, setModel] = useState("");
+  const [temperature, setTemperature] = useState("");
+  const [instruction, setInstruction] = useState("");
+
+  const activeIndex = useMemo(
+    () => attempts.findIndex((attempt) => attempt.active),
+    [attempts],
+  );
+  const activeAttempt = activeIndex >= 0 ? attempts[activeIndex] : null;
+  const parsedTemperature = temperature.trim()
+    ? Number(temperature.trim())
+    : undefined;
+  const temperatureInvalid =
+    parsedTemperature !== undefined &&
+    (Number.isNaN(parsedTemperature) ||
+      parsedTemperature < 0 ||
+      parsedTemperature > 2);
+
+  const switchTo = (index: number) => {
+    const target = attempts[index];
+    if (target && !target.active) {
+      onSwitchAttempt?.(target.id);
+    }
+  };
+
+  const submit = () => {
+    if (temperatureInvalid) {
+      return;
+    }
+    onRegenerate({
+      model: model.trim() || undefined,
+      temperature: parsedTemperature,
+      instruction: instruction.trim() || undefined,
+    });
+    setFormOpen(false);
+    setInstruction("");
+  };
+
+  return (
+    <div className="mt-2 flex flex-col gap-2 text-xs text-muted-foreground">
+      <div className="flex items-center gap-1">
+        {attempts.length > 1 && activeIndex >= 0 && (
+          <>
+            <Button
+              variant="ghost"
+              size="icon"
+              className="h-6 w-6"
+              aria-label="上一个版本"
+              disabled={activeIndex === 0 || isRegenerating}
+              onClick={() => switchTo(activeIndex - 1)}
+            >
+              <ChevronLeft size={12} />
+            </Button>
+            <span className="tabular-nums">
+              {activeIndex + 1} / {attempts.length}
+            </span>
+            <Button
+              variant="ghost"
+              size="icon"
+              className="h-6 w-6"
+              aria-label="下一个版本"
+              disabled={activeIndex === attempts.length - 1 || isRegenerating}
+              onClick={() => switchTo(activeIndex + 1)}
+            >
+              <ChevronRight size={12} />
+            </Button>
+          </>
+        )}
+        <Button
+          variant="ghost"
+          size="icon"
+          className="h-6 w-6 hover:text-foreground"
+          aria-label="重新生成"
+          disabled={isRegenerating}
+          onClick={() => setFormOpen((open) => !open)}
+        >
+          {isRegenerating ? (
+            <Loader2 size={12} className="animate-spin" />
+          ) : (
+            <RefreshCw size={12} />
+          )}
+        </Button>
+        {activeAttempt && describeOverrides(activeAttempt.overrides) && (
+          <span className="truncate">
+            {describeOverrides(activeAttempt.overrides)}
+          </span>
+        )}
+      </div>
+
+      {formOpen && (
+        <div className="flex flex-col gap-2 rounded-md border border-border/70 bg-muted/20 p-2">
+          <div className="flex gap-2">
+            <Input
+              className="h-7 text-xs"
+              placeholder="模型（留空沿用当前模型）"
+              value={model}
+              onChange={(event) => setModel(event.target.value)}
+            />
+            <Input
+              className="h-7 w-28 text-xs"
+              placeholder="温度 0-2"
+              inputMode="decimal"
+              value={temperature}
+              aria-invalid={temperatureInvalid}
+              onChange={(event) => setTemperature(event.target.value)}
+            />
+          </div>
+          <Textarea
+            className="min-h-[56px] text-xs"
+            placeholder="补充要求（可选），如：更简洁、换一种语气"
+            value={instruction}
+            onChange={(event) => setInstruction(event.target.value)}
+          />
+          <div className="flex justify-end gap-2">
+            <Button
+              variant="ghost"
+              size="sm"
+              className="h-7 text-xs"
+              onClick={() => setFormOpen(false)}
+            >
+              取消
+            </Button>
+            <Button
+              size="sm"
+              className="h-7 text-xs"
+              disabled={temperatureInvalid}
+              onClick={submit}
+            >
+              重新生成
+            </Button>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+};
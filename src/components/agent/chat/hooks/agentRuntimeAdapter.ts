@@ -6,12 +6,16 @@ import {
   getAgentRuntimeSession,
   initAsterAgent,
   interruptAgentRuntimeTurn,
+  listAgentRuntimeTurnAttempts,
   promoteAgentRuntimeQueuedTurn,
+  regenerateAgentRuntimeTurn,
   removeAgentRuntimeQueuedTurn,
   listAgentRuntimeSessions,
   respondAgentRuntimeAction,
   submitAgentRuntimeTurn,
+  switchAgentRuntimeTurnAttempt,
   updateAgentRuntimeSession,
+  type AgentRuntimeTurnAttempt,
   type AsterExecutionStrategy,
   type AsterProviderConfig,
   type AgentSearchMode,
@@ -19,6 +23,7 @@ import {
   type AsterSessionInfo,
   type AutoContinueRequestPayload,
   type ImageInput,
+  type TurnAttemptOverrides,
 } from "@/lib/api/agentRuntime";
 import type { StreamEvent } from "@/lib/api/agentStream";
 import type { ActionRequiredScope } from "../types";
@@ -41,6 +46,16 @@ export interface AgentRuntimeTurnRequest {
   queuedTurnId?: string;
 }
 
+export interface AgentRuntimeRegenerateRequest {
+  sessionId: string;
+  eventName: string;
+  workspaceId: string;
+  providerConfig?: AsterProviderConfig;
+  executionStrategy?: AsterExecutionStrategy;
+  systemPrompt?: string;
+  overrides: TurnAttemptOverrides;
+}
+
 export interface AgentRuntimeActionResponse {
   sessionId: string;
   requestId: string;
@@ -72,6 +87,9 @@ export interface AgentRuntimeAdapter {
   interruptTurn(sessionId: string): Promise<boolean>;
   promoteQueuedTurn(sessionId: string, queuedTurnId: string): Promise<boolean>;
   removeQueuedTurn(sessionId: string, queuedTurnId: string): Promise<boolean>;
+  regenerateTurn(request: AgentRuntimeRegenerateRequest): Promise<number>;
+  listTurnAttempts(sessionId: string): Promise<AgentRuntimeTurnAttempt[]>;
+  switchTurnAttempt(sessionId: string, attemptId: string): Promise<boolean>;
   respondToAction(request: AgentRuntimeActionResponse): Promise<void>;
   listenToTurnEvents(
     eventName: string,
@@ -149,6 +167,28 @@ export const defaultAgentRuntimeAdapter: AgentRuntimeAdapter = {
       queued_turn_id: queuedTurnId,
     });
   },
+  async regenerateTurn(request) {
+    return regenerateAgentRuntimeTurn({
+      session_id: request.sessionId,
+      event_name: request.eventName,
+      workspace_id: request.workspaceId,
+      turn_config: {
+        provider_config: request.providerConfig,
+        execution_strategy: request.executionStrategy,
+        system_prompt: request.systemPrompt,
+      },
+      overrides: request.overrides,
+    });
+  },
+  async listTurnAttempts(sessionId) {
+    return listAgentRuntimeTurnAttempts(sessionId);
+  },
+  async switchTurnAttempt(sessionId, attemptId) {
+    return switchAgentRuntimeTurnAttempt({
+      session_id: sessionId,
+      attempt_id: attemptId,
+    });
+  },
   async respondToAction(request) {
     await respondAgentRuntimeAction({
       session_id: request.sessionId,
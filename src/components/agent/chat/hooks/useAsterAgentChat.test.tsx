@@ -57,6 +57,7 @@ vi.mock("@/lib/api/agentRuntime", () => ({
   interruptAgentRuntimeTurn: mockInterruptAgentRuntimeTurn,
   removeAgentRuntimeQueuedTurn: mockRemoveAgentRuntimeQueuedTurn,
   respondAgentRuntimeAction: mockRespondAgentRuntimeAction,
+  listAgentRuntimeTurnAttempts: vi.fn(async () => []),
 }));
 
 vi.mock("@/lib/api/agentStream", () => ({
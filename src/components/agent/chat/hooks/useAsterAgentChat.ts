@@ -17,6 +17,7 @@ import { useAgentContext } from "./useAgentContext";
 import { useAgentSession } from "./useAgentSession";
 import { useAgentTools } from "./useAgentTools";
 import { useAgentStream } from "./useAgentStream";
+import { useTurnAttempts } from "./useTurnAttempts";
 import {
   buildLiveTaskSnapshot,
   type SendMessageFn,
@@ -122,6 +123,20 @@ export function useAsterAgentChat(options: UseAsterAgentChatRuntimeOptions) {
     setPendingActions: tools.setPendingActions,
   });
 
+  const turnAttempts = useTurnAttempts({
+    runtime,
+    sessionId: session.sessionId,
+    sessionIdRef,
+    providerTypeRef: context.providerTypeRef,
+    modelRef: context.modelRef,
+    executionStrategy: context.executionStrategy,
+    systemPrompt,
+    getRequiredWorkspaceId: context.getRequiredWorkspaceId,
+    refreshSessionDetail: session.refreshSessionDetail,
+    messagesCount: session.messages.length,
+    isSending: stream.isSending,
+  });
+
   sendMessageRef.current = stream.sendMessage;
   topicsUpdaterRef.current = session.updateTopicExecutionStrategy;
 
@@ -414,6 +429,10 @@ export function useAsterAgentChat(options: UseAsterAgentChatRuntimeOptions) {
     clearMessages: session.clearMessages,
     deleteMessage: session.deleteMessage,
     editMessage: session.editMessage,
+    turnAttempts: turnAttempts.turnAttempts,
+    isRegenerating: turnAttempts.isRegenerating,
+    regenerateLastTurn: turnAttempts.regenerateLastTurn,
+    switchTurnAttempt: turnAttempts.switchTurnAttempt,
     handlePermissionResponse: tools.handlePermissionResponse,
     triggerAIGuide: context.triggerAIGuide,
 
@@ -0,0 +1,201 @@
+/**
+ * 最后一轮回复的重新生成与多版本切换
+ *
+ * 重新生成时后端把当前回复保留为该轮的一次尝试，再以覆盖参数重新执行；
+ * 切换尝试时由后端交换消息，前端刷新会话详情即可。
+ */
+
+import {
+  useCallback,
+  useEffect,
+  useRef,
+  useState,
+  type MutableRefObject,
+} from "react";
+import { toast } from "sonner";
+import type {
+  AgentRuntimeTurnAttempt,
+  AsterExecutionStrategy,
+  TurnAttemptOverrides,
+} from "@/lib/api/agentRuntime";
+import { parseStreamEvent } from "@/lib/api/agentStream";
+import { mapProviderName } from "./agentChatCoreUtils";
+import type { AgentRuntimeAdapter } from "./agentRuntimeAdapter";
+
+/** 重新生成结束（成功或失败）的流事件 */
+const REGENERATE_TERMINAL_EVENTS = new Set([
+  "final_done",
+  "turn_completed",
+  "turn_failed",
+  "error",
+]);
+
+interface UseTurnAttemptsOptions {
+  runtime: AgentRuntimeAdapter;
+  sessionId: string | null;
+  sessionIdRef: MutableRefObject<string | null>;
+  providerTypeRef: MutableRefObject<string>;
+  modelRef: MutableRefObject<string>;
+  executionStrategy: AsterExecutionStrategy;
+  systemPrompt?: string;
+  getRequiredWorkspaceId: () => string;
+  refreshSessionDetail: (sessionId?: string) => Promise<boolean>;
+  /** 会话消息数量，变化时重新读取尝试列表 */
+  messagesCount: number;
+  isSending: boolean;
+}
+
+export function useTurnAttempts(options: UseTurnAttemptsOptions) {
+  const {
+    runtime,
+    sessionId,
+    sessionIdRef,
+    providerTypeRef,
+    modelRef,
+    executionStrategy,
+    systemPrompt,
+    getRequiredWorkspaceId,
+    refreshSessionDetail,
+    messagesCount,
+    isSending,
+  } = options;
+
+  const [turnAttempts, setTurnAttempts] = useState<AgentRuntimeTurnAttempt[]>(
+    [],
+  );
+  const [isRegenerating, setIsRegenerating] = useState(false);
+  const unlistenRef = useRef<(() => void) | null>(null);
+
+  const loadTurnAttempts = useCallback(
+    async (targetSessionId: string) => {
+      try {
+        const attempts = await runtime.listTurnAttempts(targetSessionId);
+        if (sessionIdRef.current === targetSessionId) {
+          setTurnAttempts(attempts);
+        }
+      } catch (error) {
+        console.warn("[AsterChat] 读取回复尝试失败:", error);
+        setTurnAttempts([]);
+      }
+    },
+    [runtime, sessionIdRef],
+  );
+
+  useEffect(() => {
+    if (!sessionId) {
+      setTurnAttempts([]);
+      return;
+    }
+    if (isSending || isRegenerating) {
+      return;
+    }
+    void loadTurnAttempts(sessionId);
+  }, [isRegenerating, isSending, loadTurnAttempts, messagesCount, sessionId]);
+
+  useEffect(() => {
+    return () => {
+      unlistenRef.current?.();
+      unlistenRef.current = null;
+    };
+  }, []);
+
+  const regenerateLastTurn = useCallback(
+    async (overrides: TurnAttemptOverrides = {}) => {
+      const activeSessionId = sessionIdRef.current;
+      if (!activeSessionId || isRegenerating) {
+        return false;
+      }
+
+      const eventName = `aster_regenerate_${crypto.randomUUID()}`;
+      const finish = () => {
+        unlistenRef.current?.();
+        unlistenRef.current = null;
+        setIsRegenerating(false);
+        void refreshSessionDetail(activeSessionId);
+      };
+
+      setIsRegenerating(true);
+      try {
+        unlistenRef.current?.();
+        unlistenRef.current = await runtime.listenToTurnEvents(
+          eventName,
+          (event) => {
+            const data = parseStreamEvent(event.payload);
+            if (!data) {
+              return;
+            }
+            if (data.type === "turn_started") {
+              void refreshSessionDetail(activeSessionId);
+            } else if (REGENERATE_TERMINAL_EVENTS.has(data.type)) {
+              finish();
+            }
+          },
+        );
+
+        const providerType = providerTypeRef.current;
+        await runtime.regenerateTurn({
+          sessionId: activeSessionId,
+          eventName,
+          workspaceId: getRequiredWorkspaceId(),
+          providerConfig: {
+            provider_id: providerType,
+            provider_name: mapProviderName(providerType),
+            model_name: modelRef.current,
+          },
+          executionStrategy,
+          systemPrompt,
+          overrides,
+        });
+        await refreshSessionDetail(activeSessionId);
+        return true;
+      } catch (error) {
+        console.error("[AsterChat] 重新生成失败:", error);
+        toast.error(`重新生成失败: ${error}`);
+        finish();
+        return false;
+      }
+    },
+    [
+      executionStrategy,
+      getRequiredWorkspaceId,
+      isRegenerating,
+      modelRef,
+      providerTypeRef,
+      refreshSessionDetail,
+      runtime,
+      sessionIdRef,
+      systemPrompt,
+    ],
+  );
+
+  const switchTurnAttempt = useCallback(
+    async (attemptId: string) => {
+      const activeSessionId = sessionIdRef.current;
+      if (!activeSessionId) {
+        return false;
+      }
+      try {
+        const switched = await runtime.switchTurnAttempt(
+          activeSessionId,
+          attemptId,
+        );
+        if (switched) {
+          await refreshSessionDetail(activeSessionId);
+          await loadTurnAttempts(activeSessionId);
+        }
+        return switched;
+      } catch (error) {
+        toast.error(`切换回复失败: ${error}`);
+        return false;
+      }
+    },
+    [loadTurnAttempts, refreshSessionDetail, runtime, sessionIdRef],
+  );
+
+  return {
+    turnAttempts,
+    isRegenerating,
+    regenerateLastTurn,
+    switchTurnAttempt,
+  };
+}
@@ -32,6 +32,15 @@ describe("resolveSkillFailure", () => {
 
     expect(result.code).toBe("skill_execute_failed");
   });
+
+  it("应解析输出后处理错误码", () => {
+    const result = resolveSkillFailure(
+      "skill_postprocess_failed|输出后处理失败（#1 validate_json）: 输出不是合法 JSON",
+    );
+
+    expect(result.code).toBe("skill_postprocess_failed");
+    expect(result.message).toContain("validate_json");
+  });
 });
 
 describe("formatSkillFailureMessage", () => {
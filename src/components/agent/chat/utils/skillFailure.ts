@@ -6,6 +6,7 @@ export type SkillFailureCode =
   | "skill_workspace_mismatch"
   | "skill_stream_failed"
   | "skill_cancelled"
+  | "skill_postprocess_failed"
   | "skill_execute_failed";
 
 export interface SkillFailureInfo {
@@ -104,6 +105,13 @@ function byCode(code: SkillFailureCode, message: string): SkillFailureInfo {
         message,
         recoveryHint: "已取消执行，可直接重新发送同一技能命令。",
       };
+    case "skill_postprocess_failed":
+      return {
+        code,
+        message,
+        recoveryHint:
+          "模型输出不符合技能声明的输出格式，请重试或检查技能的输出后处理配置。",
+      };
     case "skill_execute_failed":
     default:
       return {
@@ -175,6 +183,7 @@ function normalizeSkillFailureCode(
     "skill_workspace_mismatch",
     "skill_stream_failed",
     "skill_cancelled",
+    "skill_postprocess_failed",
     "skill_execute_failed",
   ];
 
@@ -358,6 +358,28 @@ const EXACT_TOOL_CONFIGS = new Map<string, ToolDisplayConfig>([
       actionKey: "search",
     },
   ],
+  [
+    "codesearchsymbols",
+    {
+      family: "search",
+      label: "符号搜索",
+      verb: "搜索",
+      icon: Code2,
+      groupTitle: "探索",
+      actionKey: "search",
+    },
+  ],
+  [
+    "codefindreferences",
+    {
+      family: "search",
+      label: "引用查找",
+      verb: "查找",
+      icon: Code2,
+      groupTitle: "探索",
+      actionKey: "search",
+    },
+  ],
   [
     "websearch",
     {
@@ -1368,6 +1390,7 @@ export const resolveToolPrimarySubject = (
     resolveToolArgumentPreview(args, [
       "pattern",
       "query",
+      "symbol",
       "q",
       "search_query",
       "libraryName",
@@ -1384,7 +1407,7 @@ export const extractSearchQueryLabel = (toolCall: ToolCallState): string => {
     string,
     unknown
   >;
-  for (const key of ["query", "q", "pattern", "search", "url"]) {
+  for (const key of ["query", "symbol", "q", "pattern", "search", "url"]) {
     const value = record[key];
     if (typeof value === "string" && value.trim()) {
       return value.trim();
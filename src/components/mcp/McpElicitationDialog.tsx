@@ -0,0 +1,310 @@
+/**
+ * MCP Elicitation 对话框
+ *
+ * 监听 `mcp:elicitation_request` 事件，按服务器给出的扁平 JSON Schema
+ * 生成表单，收集用户输入后通过 `mcp_respond_elicitation` 回复。
+ * 多个请求按到达顺序排队；收到 `mcp:elicitation_resolved`（超时、取消）时移除对应请求。
+ *
+ * @module components/mcp/McpElicitationDialog
+ */
+
+import { useEffect, useState } from "react";
+import type { UnlistenFn } from "@tauri-apps/api/event";
+import {
+  Dialog,
+  DialogContent,
+  DialogHeader,
+  DialogTitle,
+  DialogDescription,
+  DialogFooter,
+} from "@/components/ui/dialog";
+import { Button } from "@/components/ui/button";
+import { Input } from "@/components/ui/input";
+import { Label } from "@/components/ui/label";
+import { Checkbox } from "@/components/ui/checkbox";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
+import {
+  mcpApi,
+  McpElicitationAction,
+  McpElicitationRequest,
+  McpElicitationResolved,
+} from "@/lib/api/mcp";
+import { safeListen } from "@/lib/dev-bridge";
+
+interface ElicitationField {
+  name: string;
+  type: string;
+  title: string;
+  description: string;
+  required: boolean;
+  options: string[];
+  defaultValue: unknown;
+}
+
+/** 从扁平 JSON Schema 提取表单字段 */
+function extractFields(schema: Record<string, unknown>): ElicitationField[] {
+  const properties = (schema.properties || {}) as Record<
+    string,
+    Record<string, unknown>
+  >;
+  const required = (schema.required || []) as string[];
+  return Object.entries(properties).map(([name, prop]) => ({
+    name,
+    type: (prop.type as string) || "string",
+    title: (prop.title as string) || name,
+    description: (prop.description as string) || "",
+    required: required.includes(name),
+    options: Array.isArray(prop.enum)
+      ? (prop.enum as unknown[]).map(String)
+      : [],
+    defaultValue: prop.default,
+  }));
+}
+
+function initialValues(fields: ElicitationField[]): Record<string, unknown> {
+  const values: Record<string, unknown> = {};
+  for (const field of fields) {
+    if (field.defaultValue !== undefined) {
+      values[field.name] = field.defaultValue;
+    } else if (field.type === "boolean") {
+      values[field.name] = false;
+    }
+  }
+  return values;
+}
+
+/** 把表单值转换为符合 schema 的内容，返回错误信息或内容 */
+function buildContent(
+  fields: ElicitationField[],
+  values: Record<string, unknown>,
+): { content?: Record<string, unknown>; error?: string } {
+  const content: Record<string, unknown> = {};
+  for (const field of fields) {
+    const raw = values[field.name];
+    const empty = raw === undefined || raw === "";
+    if (empty) {
+      if (field.required) {
+        return { error: `请填写「${field.title}」` };
+      }
+      continue;
+    }
+    if (field.type === "number" || field.type === "integer") {
+      const num = Number(raw);
+      if (
+        Number.isNaN(num) ||
+        (field.type === "integer" && !Number.isInteger(num))
+      ) {
+        return { error: `「${field.title}」需要填写有效数字` };
+      }
+      content[field.name] = num;
+    } else {
+      content[field.name] = raw;
+    }
+  }
+  return { content };
+}
+
+export function McpElicitationDialog() {
+  const [queue, setQueue] = useState<McpElicitationRequest[]>([]);
+  const [values, setValues] = useState<Record<string, unknown>>({});
+  const [submitting, setSubmitting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const current = queue[0];
+  const fields = current ? extractFields(current.requested_schema) : [];
+
+  useEffect(() => {
+    let mounted = true;
+    const unlisteners: UnlistenFn[] = [];
+
+    const setupListeners = async () => {
+      try {
+        const unlistenRequest = await safeListen<McpElicitationRequest>(
+          "mcp:elicitation_request",
+          (event) => {
+            if (!mounted) {
+              return;
+            }
+            setQueue((prev) =>
+              prev.some((req) => req.request_id === event.payload.request_id)
+                ? prev
+                : [...prev, event.payload],
+            );
+          },
+        );
+        unlisteners.push(unlistenRequest);
+
+        const unlistenResolved = await safeListen<McpElicitationResolved>(
+          "mcp:elicitation_resolved",
+          (event) => {
+            if (!mounted) {
+              return;
+            }
+            setQueue((prev) =>
+              prev.filter((req) => req.request_id !== event.payload.request_id),
+            );
+          },
+        );
+        unlisteners.push(unlistenResolved);
+      } catch (e) {
+        console.error("[McpElicitationDialog] 设置事件监听失败:", e);
+      }
+    };
+
+    setupListeners();
+
+    return () => {
+      mounted = false;
+      unlisteners.forEach((unlisten) => unlisten());
+    };
+  }, []);
+
+  const currentId = current?.request_id;
+  useEffect(() => {
+    setValues(
+      current ? initialValues(extractFields(current.requested_schema)) : {},
+    );
+    setError(null);
+    // 仅在切换到新请求时重置表单
+    // eslint-disable-next-line react-hooks/exhaustive-deps
+  }, [currentId]);
+
+  const respond = async (
+    action: McpElicitationAction,
+    content?: Record<string, unknown>,
+  ) => {
+    if (!current) {
+      return;
+    }
+    setSubmitting(true);
+    try {
+      await mcpApi.respondElicitation(current.request_id, action, content);
+    } catch (e) {
+      // 请求可能已超时或被取消，仍从队列中移除
+      console.warn("[McpElicitationDialog] 回复 elicitation 失败:", e);
+    } finally {
+      setSubmitting(false);
+      setQueue((prev) =>
+        prev.filter((req) => req.request_id !== current.request_id),
+      );
+    }
+  };
+
+  const handleSubmit = () => {
+    const result = buildContent(fields, values);
+    if (result.error) {
+      setError(result.error);
+      return;
+    }
+    void respond("accept", result.content);
+  };
+
+  const setValue = (name: string, value: unknown) => {
+    setValues((prev) => ({ ...prev, [name]: value }));
+    setError(null);
+  };
+
+  return (
+    <Dialog
+      open={!!current}
+      onOpenChange={(open) => {
+        if (!open && !submitting) {
+          void respond("cancel");
+        }
+      }}
+    >
+      <DialogContent className="sm:max-w-[500px] max-h-[90vh] overflow-y-auto">
+        <DialogHeader>
+          <DialogTitle>{current?.server_name} 请求输入</DialogTitle>
+          <DialogDescription>{current?.message}</DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-4 py-2">
+          {fields.map((field) => (
+            <div key={field.name} className="space-y-1.5">
+              {field.type === "boolean" ? (
+                <div className="flex items-center gap-2">
+                  <Checkbox
+                    id={`elicitation-${field.name}`}
+                    checked={values[field.name] === true}
+                    onCheckedChange={(checked) => setValue(field.name, checked)}
+                  />
+                  <Label htmlFor={`elicitation-${field.name}`}>
+                    {field.title}
+                  </Label>
+                </div>
+              ) : (
+                <>
+                  <Label htmlFor={`elicitation-${field.name}`}>
+                    {field.title}
+                    {field.required && (
+                      <span className="text-destructive ml-0.5">*</span>
+                    )}
+                  </Label>
+                  {field.options.length > 0 ? (
+                    <Select
+                      value={(values[field.name] as string) ?? ""}
+                      onValueChange={(value) => setValue(field.name, value)}
+                    >
+                      <SelectTrigger id={`elicitation-${field.name}`}>
+                        <SelectValue placeholder="请选择" />
+                      </SelectTrigger>
+                      <SelectContent>
+                        {field.options.map((option) => (
+                          <SelectItem key={option} value={option}>
+                            {option}
+                          </SelectItem>
+                        ))}
+                      </SelectContent>
+                    </Select>
+                  ) : (
+                    <Input
+                      id={`elicitation-${field.name}`}
+                      type={
+                        field.type === "number" || field.type === "integer"
+                          ? "number"
+                          : "text"
+                      }
+                      value={String(values[field.name] ?? "")}
+                      onChange={(e) => setValue(field.name, e.target.value)}
+                    />
+                  )}
+                </>
+              )}
+              {field.description && (
+                <p className="text-xs text-muted-foreground">
+                  {field.description}
+                </p>
+              )}
+            </div>
+          ))}
+          {current && (
+            <p className="text-xs text-muted-foreground">
+              请在 {current.timeout_secs} 秒内回复，超时将自动取消
+            </p>
+          )}
+          {error && <p className="text-sm text-destructive">{error}</p>}
+        </div>
+
+        <DialogFooter className="gap-2">
+          <Button
+            variant="outline"
+            disabled={submitting}
+            onClick={() => void respond("decline")}
+          >
+            拒绝
+          </Button>
+          <Button disabled={submitting} onClick={handleSubmit}>
+            提交
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
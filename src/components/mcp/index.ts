@@ -5,3 +5,4 @@ export { McpToolsBrowser } from "./McpToolsBrowser";
 export { McpToolCaller } from "./McpToolCaller";
 export { McpPromptsBrowser } from "./McpPromptsBrowser";
 export { McpResourcesBrowser } from "./McpResourcesBrowser";
+export { McpElicitationDialog } from "./McpElicitationDialog";
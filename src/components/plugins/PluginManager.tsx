@@ -17,6 +17,7 @@ import {
   getPluginTask,
   listInstalledPlugins,
   listPluginTasks,
+  listenPluginReloaded,
   reloadPlugins,
   unloadPlugin,
 } from "@/lib/api/plugins";
@@ -781,6 +782,34 @@ export function PluginManager({ onNavigate }: PluginManagerProps = {}) {
     };
   }, [fetchRuntimeData]);
 
+  useEffect(() => {
+    let unlisten: (() => void) | null = null;
+    let disposed = false;
+
+    void listenPluginReloaded((event) => {
+      if (event.error) {
+        toast.error(`插件 ${event.pluginId} 重新加载失败: ${event.error}`);
+      }
+      void fetchData();
+      notifyPluginUIChanged();
+    })
+      .then((fn) => {
+        if (disposed) {
+          fn();
+        } else {
+          unlisten = fn;
+        }
+      })
+      .catch((err) => {
+        console.warn("[PluginManager] 监听 plugin:reloaded 失败:", err);
+      });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [fetchData]);
+
   useEffect(() => {
     try {
       const persisted: PersistedRuntimeFilters = {
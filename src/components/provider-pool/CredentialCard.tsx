@@ -25,6 +25,7 @@ import {
 } from "lucide-react";
 import type {
   CredentialDisplay,
+  CredentialEndpointHealth,
   CredentialSource,
 } from "@/lib/api/providerPool";
 import {
@@ -56,6 +57,19 @@ interface CredentialCardProps {
   onSwitchToLocal?: () => void;
 }
 
+/** 端点健康状态提示（▶ 标记当前使用的端点） */
+function formatEndpointTooltip(health: CredentialEndpointHealth): string {
+  return health.endpoints
+    .map((endpoint) => {
+      const marker = endpoint.url === health.active_base_url ? "▶ " : "";
+      const status = endpoint.reachable
+        ? `可达 ${endpoint.latency_ms ?? "-"}ms`
+        : `不可达 ${endpoint.last_error ?? ""}`;
+      return `${marker}${endpoint.url}: ${status}`;
+    })
+    .join("\n");
+}
+
 export function CredentialCard({
   credential,
   onToggle,
@@ -292,6 +306,11 @@ export function CredentialCard({
   const isHealthy = credential.is_healthy && !credential.is_disabled;
   const hasError = credential.error_count > 0;
   const isOAuth = credential.credential_type.includes("oauth");
+  const endpointHealth = credential.endpoint_health;
+  const anyEndpointReachable =
+    endpointHealth?.endpoints.some((e) => e.reachable) ?? true;
+  const showEndpointBadge =
+    !!endpointHealth && (endpointHealth.failed_over || !anyEndpointReachable);
 
   return (
     <div
@@ -350,6 +369,19 @@ export function CredentialCard({
                 代理
               </span>
             )}
+            {showEndpointBadge && endpointHealth && (
+              <span
+                className={`rounded-full px-2.5 py-1 text-xs font-medium inline-flex items-center gap-1.5 whitespace-nowrap ${
+                  anyEndpointReachable
+                    ? "bg-amber-100 text-amber-700 dark:bg-amber-900/30 dark:text-amber-400"
+                    : "bg-red-100 text-red-700 dark:bg-red-900/30 dark:text-red-400"
+                }`}
+                title={formatEndpointTooltip(endpointHealth)}
+              >
+                <AlertTriangle className="h-3 w-3 shrink-0" />
+                {anyEndpointReachable ? "备用端点" : "端点不可达"}
+              </span>
+            )}
           </div>
         </div>
 
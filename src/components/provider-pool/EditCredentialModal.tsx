@@ -66,6 +66,7 @@ const providerModels: Record<PoolProviderType, string[]> = {
     "gemini-2.5-flash-preview-09-2025",
     "gemini-3-pro-preview",
   ], // Gemini API Key
+  mock: ["mock-model"], // 测试用 Mock Provider
 };
 
 export function EditCredentialModal({
@@ -87,11 +88,15 @@ export function EditCredentialModal({
   const [newProjectId, setNewProjectId] = useState("");
   // API Key 相关状态
   const [newBaseUrl, setNewBaseUrl] = useState("");
+  // 备用 Base URL（每行一个）
+  const [fallbackBaseUrls, setFallbackBaseUrls] = useState("");
   const [newApiKey, setNewApiKey] = useState("");
   const [showApiKey, setShowApiKey] = useState(false);
 
   // 代理 URL 相关状态
   const [proxyUrl, setProxyUrl] = useState("");
+  // 原生联网搜索开关
+  const [nativeWebSearch, setNativeWebSearch] = useState(false);
   const [proxyError, setProxyError] = useState<string | null>(null);
 
   // 初始化表单数据
@@ -113,12 +118,14 @@ export function EditCredentialModal({
       setNewProjectId("");
       // 初始化 base_url 为已保存的值
       setNewBaseUrl(credential.base_url || "");
+      setFallbackBaseUrls((credential.fallback_base_urls || []).join("\n"));
       // 初始化 api_key 为已保存的值
       setNewApiKey(credential.api_key || "");
       setShowApiKey(false);
       // 初始化代理 URL 为已保存的值
       setProxyUrl(credential.proxy_url || "");
       setProxyError(null);
+      setNativeWebSearch(credential.native_web_search ?? false);
       setError(null);
     }
   }, [credential]);
@@ -214,10 +221,21 @@ export function EditCredentialModal({
         new_project_id: newProjectId.trim() || undefined,
         // API Key 的 base_url（始终传递当前值，空字符串表示使用默认 URL）
         new_base_url: isApiKey ? newBaseUrl.trim() : undefined,
+        // 备用 Base URL：始终传递当前值，空数组表示清除
+        new_fallback_base_urls: isApiKey
+          ? fallbackBaseUrls
+              .split("\n")
+              .map((url) => url.trim())
+              .filter(Boolean)
+          : undefined,
         // API Key 的 api_key（始终传递当前值）
         new_api_key: isApiKey ? newApiKey.trim() : undefined,
         // 代理 URL：始终传递当前值，空字符串表示清除代理
         new_proxy_url: proxyUrl.trim(),
+        // 原生联网搜索：仅支持的凭证类型传递
+        native_web_search: credential.supports_native_web_search
+          ? nativeWebSearch
+          : undefined,
       };
 
       console.log("[EditCredentialModal] 提交更新请求:", updateRequest);
@@ -405,6 +423,23 @@ export function EditCredentialModal({
                   留空使用默认 URL，或输入自定义代理地址（不要包含 /v1 后缀）
                 </p>
               </div>
+              {newBaseUrl.trim() && (
+                <div>
+                  <label className="mb-1 block text-sm font-medium">
+                    备用 Base URL（可选）
+                  </label>
+                  <textarea
+                    value={fallbackBaseUrls}
+                    onChange={(e) => setFallbackBaseUrls(e.target.value)}
+                    placeholder="每行一个，主 Base URL 不可达时按顺序切换"
+                    rows={3}
+                    className="w-full rounded-lg border bg-background px-3 py-2 text-sm font-mono"
+                  />
+                  <p className="mt-1 text-xs text-muted-foreground">
+                    健康检测会单独探测各地址的可达性，主地址恢复后自动切回
+                  </p>
+                </div>
+              )}
             </>
           )}
 
@@ -484,6 +519,26 @@ export function EditCredentialModal({
             </div>
           </div>
 
+          {/* 原生联网搜索（仅 Anthropic / Gemini 凭证） */}
+          {credential.supports_native_web_search && (
+            <div className="rounded-lg border p-4 space-y-2">
+              <label className="flex items-center gap-2 text-sm font-medium">
+                <input
+                  type="checkbox"
+                  checked={nativeWebSearch}
+                  onChange={(e) => setNativeWebSearch(e.target.checked)}
+                  className="rounded border-gray-300"
+                />
+                启用 Provider 原生联网搜索
+              </label>
+              <p className="text-xs text-muted-foreground">
+                客户端声明 web_search 工具时，使用 Provider
+                自带的联网搜索（Claude web_search / Gemini Google
+                Search）。搜索按次额外计费，关闭时会自动移除该工具。
+              </p>
+            </div>
+          )}
+
           {/* 使用统计（只读） */}
           <div className="rounded-lg bg-muted/50 p-4">
             <label className="mb-3 block text-sm font-medium">使用统计</label>
@@ -76,6 +76,7 @@ const providerLabels: Record<PoolProviderType, string> = {
   codex: "Codex (OpenAI)",
   claude_oauth: "Claude OAuth",
   gemini_api_key: "Gemini",
+  mock: "Mock",
 };
 
 // 判断是否为配置类型 tab
@@ -3,9 +3,13 @@
  * 支持 OpenAI OAuth 登录和文件导入两种模式
  */
 
-import { useState, useEffect } from "react";
-import { safeListen } from "@/lib/dev-bridge";
+import { useState, useEffect, useRef } from "react";
 import { providerPoolApi } from "@/lib/api/providerPool";
+import {
+  cancelOAuthFlow,
+  startOAuthFlow,
+  waitForOAuthFlow,
+} from "@/lib/api/oauthFlow";
 import { ModeSelector } from "./ModeSelector";
 import { FileImportForm } from "./FileImportForm";
 import { OAuthUrlDisplay } from "./OAuthUrlDisplay";
@@ -39,27 +43,18 @@ export function CodexForm({
   const [authUrl, setAuthUrl] = useState<string | null>(null);
   const [waitingForCallback, setWaitingForCallback] = useState(false);
 
-  // 监听后端发送的授权 URL 事件
-  useEffect(() => {
-    let unlisten: (() => void) | undefined;
-
-    const setupListener = async () => {
-      unlisten = await safeListen<{ auth_url: string }>(
-        "codex-auth-url",
-        (event) => {
-          setAuthUrl(event.payload.auth_url);
-        },
-      );
-    };
-
-    setupListener();
+  const flowIdRef = useRef<string | null>(null);
 
+  // 关闭表单时取消未完成的登录流程，释放本地回调端口
+  useEffect(() => {
     return () => {
-      if (unlisten) unlisten();
+      if (flowIdRef.current) {
+        void cancelOAuthFlow(flowIdRef.current);
+      }
     };
   }, []);
 
-  // 获取授权 URL 并启动服务器等待回调
+  // 启动通用 OAuth 流程，展示授权 URL 并轮询直到回调完成
   const handleGetAuthUrl = async () => {
     setLoading(true);
     setError(null);
@@ -67,8 +62,16 @@ export function CodexForm({
     setWaitingForCallback(true);
 
     try {
+      if (flowIdRef.current) {
+        await cancelOAuthFlow(flowIdRef.current);
+      }
+      const start = await startOAuthFlow("codex");
+      flowIdRef.current = start.flow_id;
+      setAuthUrl(start.auth_url ?? null);
+
       const trimmedName = name.trim() || undefined;
-      await providerPoolApi.getCodexAuthUrlAndWait(trimmedName);
+      await waitForOAuthFlow(start, trimmedName);
+      flowIdRef.current = null;
       onSuccess();
     } catch (e) {
       const errorMsg = e instanceof Error ? e.message : String(e);
@@ -124,8 +127,8 @@ export function CodexForm({
           <div className="space-y-4">
             <div className="rounded-lg border border-green-200 bg-green-50 p-4 dark:border-green-800 dark:bg-green-950/30">
               <p className="text-sm text-green-700 dark:text-green-300">
-                点击下方按钮获取授权 URL，然后复制到浏览器（支持指纹浏览器）完成
-                OpenAI 登录。
+                点击下方按钮将自动打开浏览器完成 OpenAI
+                登录，也可以复制授权 URL 到其他浏览器（支持指纹浏览器）。
               </p>
               <p className="mt-2 text-xs text-green-600 dark:text-green-400">
                 授权成功后，凭证将自动保存并添加到凭证池。
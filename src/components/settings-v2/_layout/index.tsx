@@ -42,6 +42,7 @@ import { ApiServerPage } from "@/components/api-server/ApiServerPage";
 import { McpPanel } from "@/components/mcp";
 import { ChannelsSettings } from "../system/channels";
 import { EnvironmentSettings } from "../system/environment";
+import { DeviceSyncSettingsPanel } from "../system/device-sync";
 import { WebSearchSettings } from "../system/web-search";
 import { ChromeRelaySettings } from "../system/chrome-relay";
 
@@ -238,6 +239,14 @@ function renderSettingsContent(
         </>
       );
 
+    case SettingsTabs.DeviceSync:
+      return (
+        <>
+          <SettingHeader title="设备同步" />
+          <DeviceSyncSettingsPanel />
+        </>
+      );
+
     case SettingsTabs.ChromeRelay:
       return (
         <>
@@ -18,6 +18,7 @@ import {
 import { cn } from "@/lib/utils";
 import { useApiKeyProvider } from "@/hooks/useApiKeyProvider";
 import { getConfig, saveConfig, type Config } from "@/lib/api/appConfig";
+import { testTts } from "@/lib/api/voiceTools";
 import {
   buildPersistedMediaGenerationPreference,
   getTtsModelsForProvider,
@@ -51,6 +52,8 @@ interface VoiceConfig {
   voice_input_enabled?: boolean;
   /** 启用语音输出 */
   voice_output_enabled?: boolean;
+  /** edge-tts 兼容服务地址 */
+  tts_endpoint?: string;
 }
 
 const DEFAULT_VOICE_CONFIG: VoiceConfig = {
@@ -214,13 +217,15 @@ export function VoiceSettings() {
   const handleTestTTS = async () => {
     setTestingTTS(true);
     try {
-      // TODO: 实现 TTS 测试 API
-      // await testTTS(voiceConfig.tts_service, voiceConfig.tts_voice);
-
-      // 模拟测试
-      await new Promise((resolve) => setTimeout(resolve, 2000));
-
-      showMessage("success", "语音测试成功");
+      const result = await testTts(
+        voiceConfig.tts_service || "openai",
+        voiceConfig.tts_voice || "alloy",
+      );
+      if (result.success) {
+        showMessage("success", "语音测试成功");
+      } else {
+        showMessage("error", result.error || "测试失败");
+      }
     } catch (e) {
       console.error("TTS 测试失败:", e);
       showMessage("error", "测试失败");
@@ -470,6 +475,28 @@ export function VoiceSettings() {
             </div>
           </div>
 
+          {/* edge-tts 服务地址 */}
+          {voiceConfig.tts_service === "edge" && (
+            <div>
+              <label className="text-xs text-muted-foreground mb-1.5 block">
+                edge-tts 服务地址
+              </label>
+              <input
+                type="text"
+                defaultValue={voiceConfig.tts_endpoint ?? ""}
+                placeholder="http://localhost:5050"
+                onBlur={(e) =>
+                  saveVoiceConfig(
+                    "tts_endpoint",
+                    e.target.value.trim() || undefined,
+                  )
+                }
+                disabled={loading}
+                className="w-full rounded-2xl border border-slate-200 bg-white px-3 py-2 text-sm text-slate-900 shadow-sm shadow-slate-950/5 outline-none focus:border-slate-300 focus:ring-2 focus:ring-slate-200"
+              />
+            </div>
+          )}
+
           {/* 语音选择 */}
           <div>
             <label className="text-xs text-muted-foreground mb-1.5 block">
@@ -22,6 +22,7 @@ import {
   Route,
   Search,
   Variable,
+  RefreshCcw,
   Monitor,
   ShieldCheck,
   Activity,
@@ -168,6 +169,11 @@ export function useSettingsCategory(): CategoryGroup[] {
           label: t("settings.tab.environment", "环境变量"),
           icon: Variable,
         },
+        {
+          key: SettingsTabs.DeviceSync,
+          label: t("settings.tab.deviceSync", "设备同步"),
+          icon: RefreshCcw,
+        },
         {
           key: SettingsTabs.ChromeRelay,
           label: t("settings.tab.chromeRelay", "Chrome Relay"),
@@ -0,0 +1,455 @@
+/**
+ * @file 设备同步设置
+ * @description 配置加密同步存储（同步文件夹 / WebDAV / S3）与同步类别，推送或拉取同步包
+ * @module components/settings-v2/system/device-sync
+ */
+
+import { useCallback, useEffect, useState } from "react";
+import { Download, RefreshCw, Upload } from "lucide-react";
+import {
+  getDeviceSyncSettings,
+  getDeviceSyncStatus,
+  pullDeviceSync,
+  pushDeviceSync,
+  updateDeviceSyncSettings,
+  type DeviceSyncAction,
+  type DeviceSyncCategory,
+  type DeviceSyncResult,
+  type DeviceSyncSettings,
+  type DeviceSyncStatus,
+  type DeviceSyncStorageKind,
+} from "@/lib/api/deviceSync";
+
+const INPUT_CLASS_NAME =
+  "w-full px-3 py-2 rounded-lg border bg-background text-sm focus:ring-2 focus:ring-primary/20 focus:border-primary outline-none";
+const SECONDARY_BUTTON_CLASS_NAME =
+  "inline-flex items-center gap-1.5 px-3 py-2 rounded-lg border text-sm hover:bg-muted disabled:opacity-50";
+
+const STORAGE_OPTIONS: { value: DeviceSyncStorageKind; label: string }[] = [
+  { value: "folder", label: "同步文件夹" },
+  { value: "webdav", label: "WebDAV" },
+  { value: "s3", label: "S3 兼容存储" },
+];
+
+const CATEGORY_OPTIONS: {
+  key: DeviceSyncCategory;
+  label: string;
+  description: string;
+}[] = [
+  {
+    key: "settings",
+    label: "应用配置",
+    description: "不含本页的同步配置",
+  },
+  {
+    key: "credentials",
+    label: "凭证",
+    description: "API Key Provider 与 OAuth 凭证文件",
+  },
+  { key: "skills", label: "Skill", description: "本地 Skill 目录" },
+  { key: "prompts", label: "Prompt 库", description: "按 ID 合并" },
+];
+
+const CATEGORY_LABELS: Record<DeviceSyncCategory, string> = {
+  settings: "应用配置",
+  credentials: "凭证",
+  skills: "Skill",
+  prompts: "Prompt 库",
+};
+
+const ACTION_LABELS: Record<DeviceSyncAction, string> = {
+  pushed: "已推送",
+  pulled: "已拉取并应用",
+  up_to_date: "已是最新",
+  remote_newer: "远端有其他设备的更新，请先拉取",
+  local_newer: "本地有未推送的修改",
+  conflict: "本地与远端都有修改，可选择强制推送或强制拉取",
+};
+
+const formatTime = (timestamp?: number | null) =>
+  timestamp ? new Date(timestamp).toLocaleString() : "从未同步";
+
+const formatCategories = (categories: DeviceSyncCategory[]) =>
+  categories.length > 0
+    ? categories.map((category) => CATEGORY_LABELS[category]).join("、")
+    : "无";
+
+export function DeviceSyncSettingsPanel() {
+  const [settings, setSettings] = useState<DeviceSyncSettings | null>(null);
+  const [status, setStatus] = useState<DeviceSyncStatus | null>(null);
+  const [passphrase, setPassphrase] = useState("");
+  const [busy, setBusy] = useState(false);
+  const [conflict, setConflict] = useState<"push" | "pull" | null>(null);
+  const [message, setMessage] = useState<{
+    type: "success" | "error";
+    text: string;
+  } | null>(null);
+
+  const refreshStatus = useCallback(async (current: DeviceSyncSettings) => {
+    if (!current.enabled) {
+      setStatus(null);
+      return;
+    }
+    try {
+      setStatus(await getDeviceSyncStatus());
+    } catch (e) {
+      setStatus(null);
+      setMessage({
+        type: "error",
+        text: `读取同步状态失败: ${e instanceof Error ? e.message : String(e)}`,
+      });
+    }
+  }, []);
+
+  useEffect(() => {
+    void getDeviceSyncSettings()
+      .then((loaded) => {
+        setSettings(loaded);
+        void refreshStatus(loaded);
+      })
+      .catch((e) => console.error(e));
+  }, [refreshStatus]);
+
+  const update = (patch: Partial<DeviceSyncSettings>) =>
+    setSettings((previous) => (previous ? { ...previous, ...patch } : null));
+
+  const handleSave = async () => {
+    if (!settings) return;
+    setBusy(true);
+    setMessage(null);
+    try {
+      await updateDeviceSyncSettings(settings);
+      setMessage({ type: "success", text: "设置已保存" });
+      await refreshStatus(settings);
+    } catch (e) {
+      setMessage({
+        type: "error",
+        text: `保存失败: ${e instanceof Error ? e.message : String(e)}`,
+      });
+    }
+    setBusy(false);
+  };
+
+  const handleSync = async (direction: "push" | "pull", force = false) => {
+    if (!settings || !passphrase) {
+      setMessage({ type: "error", text: "请输入同步口令" });
+      return;
+    }
+    setBusy(true);
+    setMessage(null);
+    setConflict(null);
+    try {
+      const result: DeviceSyncResult =
+        direction === "push"
+          ? await pushDeviceSync(passphrase, force)
+          : await pullDeviceSync(passphrase, force);
+      const done = result.action === "pushed" || result.action === "pulled";
+      if (result.action === "conflict") {
+        setConflict(direction);
+      }
+      const warnings =
+        result.warnings.length > 0 ? `（${result.warnings.join("；")}）` : "";
+      setMessage({
+        type: done || result.action === "up_to_date" ? "success" : "error",
+        text: done
+          ? `${ACTION_LABELS[result.action]}：${formatCategories(result.categories)}${warnings}`
+          : ACTION_LABELS[result.action],
+      });
+      await refreshStatus(settings);
+    } catch (e) {
+      setMessage({
+        type: "error",
+        text: e instanceof Error ? e.message : String(e),
+      });
+    }
+    setBusy(false);
+  };
+
+  if (!settings) {
+    return (
+      <div className="flex items-center justify-center h-64">
+        <RefreshCw className="h-6 w-6 animate-spin text-muted-foreground" />
+      </div>
+    );
+  }
+
+  return (
+    <div className="space-y-6 max-w-2xl">
+      {message && (
+        <div
+          className={`rounded-lg border p-3 text-sm ${
+            message.type === "error"
+              ? "border-destructive bg-destructive/10 text-destructive"
+              : "border-green-500 bg-green-50 text-green-700 dark:bg-green-900/20 dark:text-green-400"
+          }`}
+        >
+          {message.text}
+        </div>
+      )}
+
+      <div className="space-y-4">
+        <div>
+          <h3 className="text-sm font-medium">同步存储</h3>
+          <p className="text-xs text-muted-foreground">
+            同步包使用口令加密后存放在你自己的存储中，口令不会被保存
+          </p>
+        </div>
+
+        <div className="space-y-4 p-4 rounded-lg border">
+          <div className="flex items-center justify-between">
+            <label className="block text-sm font-medium">启用设备同步</label>
+            <input
+              type="checkbox"
+              checked={settings.enabled}
+              onChange={(e) => update({ enabled: e.target.checked })}
+              className="h-4 w-4 rounded border"
+            />
+          </div>
+
+          <div className="grid grid-cols-2 gap-4">
+            <div>
+              <label className="block text-sm font-medium mb-1.5">
+                存储类型
+              </label>
+              <select
+                value={settings.storage}
+                onChange={(e) =>
+                  update({ storage: e.target.value as DeviceSyncStorageKind })
+                }
+                className={INPUT_CLASS_NAME}
+              >
+                {STORAGE_OPTIONS.map((option) => (
+                  <option key={option.value} value={option.value}>
+                    {option.label}
+                  </option>
+                ))}
+              </select>
+            </div>
+            <div>
+              <label className="block text-sm font-medium mb-1.5">
+                设备名称
+              </label>
+              <input
+                type="text"
+                value={settings.device_name ?? ""}
+                placeholder="默认使用主机名"
+                onChange={(e) =>
+                  update({ device_name: e.target.value || undefined })
+                }
+                className={INPUT_CLASS_NAME}
+              />
+            </div>
+          </div>
+
+          {settings.storage === "folder" && (
+            <div>
+              <label className="block text-sm font-medium mb-1.5">
+                同步文件夹
+              </label>
+              <input
+                type="text"
+                value={settings.folder_path ?? ""}
+                placeholder="~/Dropbox/lime"
+                onChange={(e) =>
+                  update({ folder_path: e.target.value || undefined })
+                }
+                className={INPUT_CLASS_NAME}
+              />
+              <p className="text-xs text-muted-foreground mt-1">
+                由 iCloud Drive、Dropbox、Syncthing 等同步盘负责在设备间传输
+              </p>
+            </div>
+          )}
+
+          {settings.storage === "webdav" && (
+            <div className="space-y-3">
+              <input
+                type="text"
+                value={settings.webdav.url}
+                placeholder="https://dav.example.com/lime/"
+                onChange={(e) =>
+                  update({
+                    webdav: { ...settings.webdav, url: e.target.value },
+                  })
+                }
+                className={INPUT_CLASS_NAME}
+              />
+              <div className="grid grid-cols-2 gap-4">
+                <input
+                  type="text"
+                  value={settings.webdav.username ?? ""}
+                  placeholder="用户名"
+                  onChange={(e) =>
+                    update({
+                      webdav: { ...settings.webdav, username: e.target.value },
+                    })
+                  }
+                  className={INPUT_CLASS_NAME}
+                />
+                <input
+                  type="password"
+                  value={settings.webdav.password ?? ""}
+                  placeholder="密码"
+                  onChange={(e) =>
+                    update({
+                      webdav: { ...settings.webdav, password: e.target.value },
+                    })
+                  }
+                  className={INPUT_CLASS_NAME}
+                />
+              </div>
+            </div>
+          )}
+
+          {settings.storage === "s3" && (
+            <div className="grid grid-cols-2 gap-3">
+              {(
+                [
+                  ["endpoint", "端点，如 https://s3.us-east-1.amazonaws.com"],
+                  ["region", "区域"],
+                  ["bucket", "Bucket"],
+                  ["prefix", "对象前缀（可选）"],
+                  ["access_key_id", "Access Key ID"],
+                  ["secret_access_key", "Secret Access Key"],
+                ] as const
+              ).map(([field, placeholder]) => (
+                <input
+                  key={field}
+                  type={field === "secret_access_key" ? "password" : "text"}
+                  value={settings.s3[field] ?? ""}
+                  placeholder={placeholder}
+                  onChange={(e) =>
+                    update({ s3: { ...settings.s3, [field]: e.target.value } })
+                  }
+                  className={`${INPUT_CLASS_NAME} ${
+                    field === "endpoint" ? "col-span-2" : ""
+                  }`}
+                />
+              ))}
+            </div>
+          )}
+
+          <div className="space-y-2">
+            <label className="block text-sm font-medium">同步内容</label>
+            {CATEGORY_OPTIONS.map((option) => (
+              <label
+                key={option.key}
+                className="flex items-center justify-between text-sm"
+              >
+                <span>
+                  {option.label}
+                  <span className="ml-2 text-xs text-muted-foreground">
+                    {option.description}
+                  </span>
+                </span>
+                <input
+                  type="checkbox"
+                  checked={settings.categories[option.key]}
+                  onChange={(e) =>
+                    update({
+                      categories: {
+                        ...settings.categories,
+                        [option.key]: e.target.checked,
+                      },
+                    })
+                  }
+                  className="h-4 w-4 rounded border"
+                />
+              </label>
+            ))}
+          </div>
+
+          <button
+            onClick={handleSave}
+            disabled={busy}
+            className="w-full px-4 py-2 rounded-lg bg-primary text-primary-foreground text-sm font-medium hover:bg-primary/90 disabled:opacity-50"
+          >
+            保存设置
+          </button>
+        </div>
+      </div>
+
+      {settings.enabled && (
+        <div className="space-y-4">
+          <div>
+            <h3 className="text-sm font-medium">同步</h3>
+            <p className="text-xs text-muted-foreground">
+              {status
+                ? `${status.device_name} · ${status.storage}`
+                : "保存设置后可查看同步状态"}
+            </p>
+          </div>
+
+          <div className="space-y-4 p-4 rounded-lg border">
+            {status && (
+              <div className="space-y-1 text-sm text-muted-foreground">
+                <div>上次同步：{formatTime(status.last_synced_at)}</div>
+                <div>本地修改：{formatCategories(status.local_changes)}</div>
+                <div>
+                  远端：
+                  {status.remote
+                    ? `${status.remote.device_name} 于 ${formatTime(status.remote.updated_at)} 推送`
+                    : "尚无同步包"}
+                </div>
+                <div>状态：{ACTION_LABELS[status.suggested_action]}</div>
+              </div>
+            )}
+
+            <input
+              type="password"
+              value={passphrase}
+              placeholder="同步口令（所有设备需一致）"
+              onChange={(e) => setPassphrase(e.target.value)}
+              className={INPUT_CLASS_NAME}
+            />
+
+            <div className="flex flex-wrap gap-2">
+              <button
+                onClick={() => void handleSync("push")}
+                disabled={busy}
+                className={SECONDARY_BUTTON_CLASS_NAME}
+              >
+                <Upload className="h-4 w-4" />
+                推送
+              </button>
+              <button
+                onClick={() => void handleSync("pull")}
+                disabled={busy}
+                className={SECONDARY_BUTTON_CLASS_NAME}
+              >
+                <Download className="h-4 w-4" />
+                拉取
+              </button>
+              <button
+                onClick={() => void refreshStatus(settings)}
+                disabled={busy}
+                className={SECONDARY_BUTTON_CLASS_NAME}
+              >
+                <RefreshCw className="h-4 w-4" />
+                刷新状态
+              </button>
+              {conflict && (
+                <>
+                  <button
+                    onClick={() => void handleSync("push", true)}
+                    disabled={busy}
+                    className={`${SECONDARY_BUTTON_CLASS_NAME} text-destructive`}
+                  >
+                    强制推送（覆盖远端）
+                  </button>
+                  <button
+                    onClick={() => void handleSync("pull", true)}
+                    disabled={busy}
+                    className={`${SECONDARY_BUTTON_CLASS_NAME} text-destructive`}
+                  >
+                    强制拉取（覆盖本地）
+                  </button>
+                </>
+              )}
+            </div>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -1,6 +1,7 @@
 import { useState, useEffect } from "react";
 import { Eye, EyeOff, Copy, Check, RefreshCw } from "lucide-react";
 import { getConfig, saveConfig, type Config } from "@/lib/api/appConfig";
+import type { ResponseCacheMode } from "@/lib/api/appConfigTypes";
 
 const DEFAULT_RESPONSE_CACHE_SETTINGS = {
   enabled: true,
@@ -8,8 +9,15 @@ const DEFAULT_RESPONSE_CACHE_SETTINGS = {
   max_entries: 200,
   max_body_bytes: 1_048_576,
   cacheable_status_codes: [200],
+  mode: "exact" as ResponseCacheMode,
+  routes: {} as Record<string, boolean>,
 };
 
+const RESPONSE_CACHE_ROUTES = [
+  { route: "chat_completions", label: "OpenAI Chat Completions / Responses" },
+  { route: "anthropic_messages", label: "Anthropic Messages" },
+];
+
 function normalizeResponseCacheSettings(config: Config): Config {
   const rawSettings = config.server.response_cache;
   const mergedCodes =
@@ -435,6 +443,80 @@ export function ProxySettings() {
               使用英文逗号分隔；默认仅 200
             </p>
           </div>
+
+          <div>
+            <label className="block text-sm font-medium mb-1.5">匹配方式</label>
+            <select
+              value={config.server.response_cache?.mode ?? "exact"}
+              onChange={(event) =>
+                setConfig((previous) => {
+                  if (!previous) return previous;
+                  const normalizedConfig =
+                    normalizeResponseCacheSettings(previous);
+                  return {
+                    ...normalizedConfig,
+                    server: {
+                      ...normalizedConfig.server,
+                      response_cache: {
+                        ...normalizedConfig.server.response_cache,
+                        mode: event.target.value as ResponseCacheMode,
+                      },
+                    },
+                  };
+                })
+              }
+              className="w-full px-3 py-2 rounded-lg border bg-background text-sm focus:ring-2 focus:ring-primary/20 focus:border-primary outline-none"
+            >
+              <option value="exact">精确（请求完全相同）</option>
+              <option value="semantic">
+                语义（忽略空白差异与不影响输出的字段）
+              </option>
+            </select>
+          </div>
+
+          <div>
+            <label className="block text-sm font-medium mb-1.5">
+              启用的路由
+            </label>
+            <div className="space-y-2">
+              {RESPONSE_CACHE_ROUTES.map(({ route, label }) => (
+                <label
+                  key={route}
+                  className="flex items-center justify-between text-sm"
+                >
+                  <span>{label}</span>
+                  <input
+                    type="checkbox"
+                    checked={
+                      config.server.response_cache?.routes?.[route] ?? true
+                    }
+                    onChange={(event) =>
+                      setConfig((previous) => {
+                        if (!previous) return previous;
+                        const normalizedConfig =
+                          normalizeResponseCacheSettings(previous);
+                        return {
+                          ...normalizedConfig,
+                          server: {
+                            ...normalizedConfig.server,
+                            response_cache: {
+                              ...normalizedConfig.server.response_cache,
+                              routes: {
+                                ...normalizedConfig.server.response_cache
+                                  .routes,
+                                [route]: event.target.checked,
+                              },
+                            },
+                          },
+                        };
+                      })
+                    }
+                    className="h-4 w-4 rounded border"
+                  />
+                </label>
+              ))}
+            </div>
+          </div>
         </div>
       </div>
     </div>
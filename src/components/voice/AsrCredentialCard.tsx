@@ -17,6 +17,7 @@ import {
 } from "lucide-react";
 import type { AsrCredentialEntry, AsrProviderType } from "./types";
 import { ASR_PROVIDERS } from "./types";
+import { WhisperModelDownload } from "./WhisperModelDownload";
 
 interface AsrCredentialCardProps {
   credential: AsrCredentialEntry;
@@ -153,6 +154,13 @@ export function AsrCredentialCard({
         </div>
       </div>
 
+      {/* 本地 Whisper 模型下载 */}
+      {credential.provider === "whisper_local" && (
+        <WhisperModelDownload
+          model={credential.whisper_config?.model ?? "base"}
+        />
+      )}
+
       {/* 测试结果 */}
       {testResult && (
         <div
@@ -12,10 +12,11 @@ import {
   Sparkles,
   Volume2,
   Globe,
+  Timer,
 } from "lucide-react";
 import { cn } from "@/lib/utils";
 import { ShortcutSettings } from "@/components/smart-input/ShortcutSettings";
-import { VoiceInputConfig } from "@/lib/api/asrProvider";
+import { VoiceInputConfig, VoiceVadConfig } from "@/lib/api/asrProvider";
 import { MicrophoneTest } from "./MicrophoneTest";
 import { PolishModelSelector } from "./PolishModelSelector";
 import {
@@ -26,6 +27,15 @@ import {
   SelectValue,
 } from "@/components/ui/select";
 
+/** 与后端 VoiceVadConfig 默认值一致 */
+const DEFAULT_VAD_CONFIG: VoiceVadConfig = {
+  enabled: false,
+  silence_threshold: 0.015,
+  silence_duration_ms: 1500,
+  min_speech_ms: 300,
+  mode: "auto_stop",
+};
+
 interface VoiceSettingsProps {
   config: VoiceInputConfig;
   onConfigChange: (config: VoiceInputConfig) => Promise<void>;
@@ -41,6 +51,8 @@ export function VoiceSettings({
 }: VoiceSettingsProps) {
   const [saving, setSaving] = useState(false);
   const isMacOS = navigator.userAgent.includes("Mac");
+  const vadConfig = config.vad ?? DEFAULT_VAD_CONFIG;
+  const vadSilenceSecs = (vadConfig.silence_duration_ms / 1000).toFixed(1);
 
   // 切换功能开关
   const handleToggle = useCallback(async () => {
@@ -104,6 +116,20 @@ export function VoiceSettings({
     }
   }, [config, onConfigChange, disabled, saving]);
 
+  // 切换静音自动停止
+  const handleToggleVad = useCallback(async () => {
+    if (disabled || saving) return;
+    setSaving(true);
+    try {
+      await onConfigChange({
+        ...config,
+        vad: { ...vadConfig, enabled: !vadConfig.enabled },
+      });
+    } finally {
+      setSaving(false);
+    }
+  }, [config, vadConfig, onConfigChange, disabled, saving]);
+
   // 更新润色模型
   const handlePolishModelChange = useCallback(
     async (modelId: string) => {
@@ -319,6 +345,40 @@ export function VoiceSettings({
             </div>
           </div>
 
+          {/* 静音自动停止设置 */}
+          <div className="pt-3 border-t">
+            <div className="flex items-center justify-between">
+              <div className="flex items-center gap-2">
+                <Timer className="h-4 w-4 text-muted-foreground" />
+                <div>
+                  <span className="text-sm">说完自动停止</span>
+                  <p className="text-xs text-muted-foreground">
+                    说话后静音 {vadSilenceSecs} 秒自动停止录音
+                  </p>
+                </div>
+              </div>
+              <label className="relative inline-flex items-center cursor-pointer">
+                <input
+                  type="checkbox"
+                  checked={vadConfig.enabled}
+                  onChange={handleToggleVad}
+                  disabled={disabled || saving}
+                  className="sr-only peer"
+                />
+                <div
+                  className={cn(
+                    "w-9 h-5 rounded-full transition-colors",
+                    "bg-muted peer-checked:bg-primary",
+                    "after:content-[''] after:absolute after:top-0.5 after:left-0.5",
+                    "after:bg-white after:rounded-full after:h-4 after:w-4",
+                    "after:transition-transform peer-checked:after:translate-x-4",
+                    (disabled || saving) && "opacity-50 cursor-not-allowed",
+                  )}
+                />
+              </label>
+            </div>
+          </div>
+
           {/* 翻译模式快捷键设置 */}
           <div className="pt-3 border-t">
             <div className="flex items-center gap-2 mb-3">
@@ -0,0 +1,168 @@
+/**
+ * @file Whisper 模型下载组件
+ * @description 显示本地 Whisper 凭证所需模型的下载状态，支持下载、续传、取消与删除
+ * @module components/voice/WhisperModelDownload
+ */
+
+import { useCallback, useEffect, useRef, useState } from "react";
+import { Download, Square, Trash2 } from "lucide-react";
+import type { WhisperModelSize, WhisperModelStatus } from "./types";
+import {
+  cancelWhisperModelDownload,
+  deleteWhisperModel,
+  downloadWhisperModel,
+  listenWhisperModelDownloadProgress,
+  listWhisperModels,
+} from "./types";
+
+interface WhisperModelDownloadProps {
+  model: WhisperModelSize;
+}
+
+const formatSize = (bytes: number): string =>
+  bytes >= 1024 * 1024 * 1024
+    ? `${(bytes / 1024 / 1024 / 1024).toFixed(1)}GB`
+    : `${Math.round(bytes / 1024 / 1024)}MB`;
+
+export function WhisperModelDownload({ model }: WhisperModelDownloadProps) {
+  const [status, setStatus] = useState<WhisperModelStatus | null>(null);
+  const [downloading, setDownloading] = useState(false);
+  const [downloadedBytes, setDownloadedBytes] = useState(0);
+  const [error, setError] = useState<string | null>(null);
+  const cancelledRef = useRef(false);
+
+  const refresh = useCallback(async () => {
+    try {
+      const models = await listWhisperModels();
+      const current = models.find((m) => m.model === model) ?? null;
+      setStatus(current);
+      setDownloadedBytes(current?.partial_bytes ?? 0);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    }
+  }, [model]);
+
+  useEffect(() => {
+    void refresh();
+  }, [refresh]);
+
+  // 下载期间监听进度
+  useEffect(() => {
+    if (!downloading) return;
+    let disposed = false;
+    let unlisten: (() => void) | undefined;
+    void listenWhisperModelDownloadProgress((progress) => {
+      if (progress.model === model) {
+        setDownloadedBytes(progress.downloaded_bytes);
+      }
+    }).then((fn) => {
+      if (disposed) {
+        fn();
+      } else {
+        unlisten = fn;
+      }
+    });
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [downloading, model]);
+
+  const handleDownload = async () => {
+    cancelledRef.current = false;
+    setError(null);
+    setDownloading(true);
+    try {
+      await downloadWhisperModel(model);
+    } catch (e) {
+      if (!cancelledRef.current) {
+        setError(e instanceof Error ? e.message : String(e));
+      }
+    } finally {
+      setDownloading(false);
+      await refresh();
+    }
+  };
+
+  const handleCancel = async () => {
+    cancelledRef.current = true;
+    await cancelWhisperModelDownload(model);
+  };
+
+  const handleDelete = async () => {
+    setError(null);
+    try {
+      await deleteWhisperModel(model);
+    } catch (e) {
+      setError(e instanceof Error ? e.message : String(e));
+    }
+    await refresh();
+  };
+
+  if (!status) return null;
+
+  const percent =
+    status.size_bytes > 0
+      ? Math.min(100, Math.floor((downloadedBytes / status.size_bytes) * 100))
+      : 0;
+  const stateLabel = (() => {
+    if (status.downloaded) return "已下载";
+    if (downloading) return `下载中 ${percent}%`;
+    if (downloadedBytes > 0) return `已下载 ${percent}%，可继续下载`;
+    return "未下载";
+  })();
+
+  return (
+    <div className="mt-3 rounded-lg bg-muted/50 px-3 py-2 text-sm">
+      <div className="flex items-center justify-between gap-2">
+        <span className="text-muted-foreground">
+          模型 {status.filename}（{formatSize(status.size_bytes)}）：
+          {stateLabel}
+        </span>
+        <div className="flex items-center gap-1">
+          {downloading ? (
+            <button
+              onClick={handleCancel}
+              className="rounded-lg p-1.5 text-muted-foreground hover:bg-muted hover:text-foreground"
+              title="取消下载"
+            >
+              <Square className="h-4 w-4" />
+            </button>
+          ) : (
+            !status.downloaded && (
+              <button
+                onClick={handleDownload}
+                className="rounded-lg p-1.5 text-muted-foreground hover:bg-muted hover:text-foreground"
+                title={downloadedBytes > 0 ? "继续下载" : "下载模型"}
+              >
+                <Download className="h-4 w-4" />
+              </button>
+            )
+          )}
+          {!downloading && (status.downloaded || downloadedBytes > 0) && (
+            <button
+              onClick={handleDelete}
+              className="rounded-lg p-1.5 text-muted-foreground hover:bg-red-100 hover:text-red-600 dark:hover:bg-red-950"
+              title="删除模型"
+            >
+              <Trash2 className="h-4 w-4" />
+            </button>
+          )}
+        </div>
+      </div>
+
+      {downloading && (
+        <div className="mt-2 h-1.5 overflow-hidden rounded-full bg-muted">
+          <div
+            className="h-full bg-primary transition-all"
+            style={{ width: `${percent}%` }}
+          />
+        </div>
+      )}
+
+      {error && (
+        <div className="mt-1 text-red-600 dark:text-red-400">{error}</div>
+      )}
+    </div>
+  );
+}
@@ -12,3 +12,4 @@ export { VoiceSettings } from "./VoiceSettings";
 export { InstructionEditor } from "./InstructionEditor";
 export { MicrophoneTest } from "./MicrophoneTest";
 export { VolumeWaveform } from "./VolumeWaveform";
+export { WhisperModelDownload } from "./WhisperModelDownload";
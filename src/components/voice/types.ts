@@ -18,6 +18,8 @@ export type {
   VoiceOutputConfig,
   VoiceInstruction,
   VoiceInputConfig,
+  WhisperModelStatus,
+  WhisperModelDownloadProgress,
 } from "@/lib/api/asrProvider";
 
 // 导出 API 函数
@@ -33,6 +35,11 @@ export {
   getVoiceInstructions,
   saveVoiceInstruction,
   deleteVoiceInstruction,
+  listWhisperModels,
+  downloadWhisperModel,
+  cancelWhisperModelDownload,
+  deleteWhisperModel,
+  listenWhisperModelDownloadProgress,
 } from "@/lib/api/asrProvider";
 
 /** ASR Provider 显示信息 */
@@ -42,10 +42,40 @@ interface McpServerStoppedPayload {
 interface McpServerErrorPayload {
   server_name: string;
   error: string;
+  /** 最近的 stderr 日志行 */
+  recent_logs?: string[];
 }
 
 interface McpToolsUpdatedPayload {
+  /** 单调递增的版本号 */
+  revision: number;
+  /** 为 true 时 tools 为完整列表 */
+  full_resync: boolean;
   tools: McpToolDefinition[];
+  added: McpToolDefinition[];
+  removed: string[];
+  changed: McpToolDefinition[];
+}
+
+/** 将增量更新应用到当前工具列表 */
+export function applyToolsDiff(
+  current: McpToolDefinition[],
+  payload: Pick<McpToolsUpdatedPayload, "added" | "removed" | "changed">,
+): McpToolDefinition[] {
+  const removed = new Set(payload.removed);
+  const replacements = new Map(
+    [...payload.changed, ...payload.added].map((tool) => [tool.name, tool]),
+  );
+  const next = current
+    .filter((tool) => !removed.has(tool.name))
+    .map((tool) => replacements.get(tool.name) ?? tool);
+  const existing = new Set(next.map((tool) => tool.name));
+  for (const tool of replacements.values()) {
+    if (!existing.has(tool.name)) {
+      next.push(tool);
+    }
+  }
+  return next;
 }
 
 // ============================================================================
@@ -238,6 +268,7 @@ export function useMcp(): UseMcpReturn {
 
   useEffect(() => {
     let mounted = true;
+    let lastToolsRevision: number | null = null;
     const unlisteners: UnlistenFn[] = [];
 
     const init = async () => {
@@ -283,6 +314,7 @@ export function useMcp(): UseMcpReturn {
               "[useMcp] 服务器错误:",
               event.payload.server_name,
               event.payload.error,
+              event.payload.recent_logs ?? [],
             );
             if (mounted) {
               setError(`${event.payload.server_name}: ${event.payload.error}`);
@@ -294,10 +326,39 @@ export function useMcp(): UseMcpReturn {
         const unlistenTools = await safeListen<McpToolsUpdatedPayload>(
           "mcp:tools_updated",
           (event) => {
-            console.log("[useMcp] 工具列表已更新:", event.payload.tools.length);
-            if (mounted) {
-              setTools(event.payload.tools);
+            const payload = event.payload;
+            if (!mounted) {
+              return;
+            }
+            if (payload.full_resync) {
+              console.log("[useMcp] 工具列表全量同步:", payload.tools.length);
+              lastToolsRevision = payload.revision;
+              setTools(payload.tools);
+              return;
             }
+            // 版本号不连续说明漏收了增量事件，回退到全量同步
+            if (
+              lastToolsRevision !== null &&
+              payload.revision !== lastToolsRevision + 1
+            ) {
+              console.warn(
+                "[useMcp] 工具事件版本号不连续，执行全量同步:",
+                lastToolsRevision,
+                payload.revision,
+              );
+              mcpApi.resyncTools().catch((e) => {
+                console.error("[useMcp] 全量同步工具列表失败:", e);
+              });
+              return;
+            }
+            lastToolsRevision = payload.revision;
+            console.log(
+              "[useMcp] 工具列表增量更新:",
+              payload.added.length,
+              payload.removed.length,
+              payload.changed.length,
+            );
+            setTools((current) => applyToolsDiff(current, payload));
           },
         );
         unlisteners.push(unlistenTools);
@@ -23,6 +23,8 @@ import {
   type StepCompletePayload,
   type StepErrorPayload,
   type ExecutionCompletePayload,
+  type ExecutionUsage,
+  type StepUsage,
 } from "@/lib/api/skill-execution";
 
 // ============================================================================
@@ -36,11 +38,15 @@ export interface UseSkillExecutionOptions {
   /** 步骤开始回调 */
   onStepStart?: (stepId: string, stepName: string, total: number) => void;
   /** 步骤完成回调 */
-  onStepComplete?: (stepId: string, output: string) => void;
+  onStepComplete?: (stepId: string, output: string, usage?: StepUsage) => void;
   /** 步骤错误回调 */
   onStepError?: (stepId: string, error: string, willRetry: boolean) => void;
   /** 执行完成回调 */
-  onComplete?: (success: boolean, output?: string) => void;
+  onComplete?: (
+    success: boolean,
+    output?: string,
+    usage?: ExecutionUsage,
+  ) => void;
 }
 
 /**
@@ -211,6 +217,7 @@ export function useSkillExecution(
           callbacksRef.current.onStepComplete?.(
             payload.step_id,
             payload.output,
+            payload.usage,
           );
         },
       );
@@ -263,7 +270,11 @@ export function useSkillExecution(
             setError(payload.error);
           }
 
-          callbacksRef.current.onComplete?.(payload.success, payload.output);
+          callbacksRef.current.onComplete?.(
+            payload.success,
+            payload.output,
+            payload.usage,
+          );
         },
       );
       unlistenFns.push(unlistenComplete);
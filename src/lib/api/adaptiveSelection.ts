@@ -0,0 +1,55 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+export interface AdaptiveSelectionSettings {
+  enabled: boolean;
+  /** 随机探索的概率（0-1） */
+  exploration_rate: number;
+  /** 延迟在得分中的权重（0-1），其余为成功率 */
+  latency_weight: number;
+  /** 延迟得分为 0.5 时的参考延迟（毫秒） */
+  reference_latency_ms: number;
+  /** 每次记录前历史计数的衰减系数（0-1] */
+  decay: number;
+}
+
+/** 单个（模型, 凭证）学习到的统计 */
+export interface AdaptiveArmStats {
+  /** 模型名，未指定模型的请求为 `*` */
+  model: string;
+  credential_id: string;
+  /** 衰减后的成功次数 */
+  successes: number;
+  /** 衰减后的失败次数 */
+  failures: number;
+  /** 成功请求延迟的移动平均（毫秒），0 表示尚无成功记录 */
+  latency_ms: number;
+  samples: number;
+  /** Unix 秒 */
+  updated_at: number;
+  /** 平滑成功率 */
+  success_rate: number;
+  /** 综合得分（0-1） */
+  score: number;
+}
+
+export async function getAdaptiveSelectionSettings(): Promise<AdaptiveSelectionSettings> {
+  return safeInvoke("get_adaptive_selection_settings");
+}
+
+export async function updateAdaptiveSelectionSettings(
+  settings: AdaptiveSelectionSettings,
+): Promise<void> {
+  return safeInvoke("update_adaptive_selection_settings", { settings });
+}
+
+/** 获取学习到的统计，指定 model 时只返回该模型 */
+export async function getAdaptiveSelectionStats(
+  model?: string,
+): Promise<AdaptiveArmStats[]> {
+  return safeInvoke("get_adaptive_selection_stats", { model });
+}
+
+/** 清除学习到的统计，返回清除的条数 */
+export async function resetAdaptiveSelection(model?: string): Promise<number> {
+  return safeInvoke("reset_adaptive_selection", { model });
+}
@@ -11,6 +11,7 @@ import type {
   AgentThreadTurn,
   ToolResultImage,
 } from "./agentStream";
+import type { Page, PageRequest } from "./pagination";
 import {
   normalizeQueuedTurnSnapshots,
   type QueuedTurnSnapshot,
@@ -67,6 +68,8 @@ export interface AsterProviderConfig {
   model_name: string;
   api_key?: string;
   base_url?: string;
+  /** 采样温度（不传时使用 Provider 默认值） */
+  temperature?: number;
 }
 
 export interface AutoContinueRequestPayload {
@@ -182,6 +185,36 @@ export interface AsterSubagentParentContext {
 /**
  * TauriMessageContent（匹配后端 TauriMessageContent 枚举）
  */
+/** 单条消息的 token 用量与费用 */
+export interface MessageCost {
+  model?: string;
+  input_tokens: number;
+  output_tokens: number;
+  total_tokens: number;
+  /** 模型无定价时为空 */
+  cost?: number;
+  currency: string;
+}
+
+/** 汇总会话内各消息的费用（无需按定价重新计算） */
+export function sumSessionCost(
+  messages: Array<{ cost?: MessageCost }>,
+): { total_tokens: number; cost: number; currency: string } {
+  return messages.reduce(
+    (acc, message) => {
+      if (!message.cost) {
+        return acc;
+      }
+      return {
+        total_tokens: acc.total_tokens + message.cost.total_tokens,
+        cost: acc.cost + (message.cost.cost ?? 0),
+        currency: message.cost.currency || acc.currency,
+      };
+    },
+    { total_tokens: 0, cost: 0, currency: "USD" },
+  );
+}
+
 export interface TauriMessageContent {
   type: string;
   text?: string;
@@ -221,6 +254,8 @@ export interface AsterSessionDetail {
     role: string;
     content: TauriMessageContent[];
     timestamp: number;
+    /** assistant 消息持久化时计算的 token 用量与费用 */
+    cost?: MessageCost;
   }>;
   turns?: AgentThreadTurn[];
   items?: AgentThreadItem[];
@@ -228,6 +263,7 @@ export interface AsterSessionDetail {
   todo_items?: AsterTodoItem[];
   child_subagent_sessions?: AsterSubagentSessionInfo[];
   subagent_parent_context?: AsterSubagentParentContext;
+  model_lock?: SessionModelLock;
 }
 
 export interface AgentTurnConfigSnapshot {
@@ -267,6 +303,45 @@ export interface AgentRuntimePromoteQueuedTurnRequest {
   queued_turn_id: string;
 }
 
+/**
+ * 重新生成时的参数覆盖
+ */
+export interface TurnAttemptOverrides {
+  model?: string;
+  temperature?: number;
+  /** 追加到原消息后的补充要求 */
+  instruction?: string;
+}
+
+export interface AgentRuntimeRegenerateTurnRequest {
+  session_id: string;
+  event_name: string;
+  workspace_id: string;
+  turn_config?: AgentTurnConfigSnapshot;
+  overrides?: TurnAttemptOverrides;
+}
+
+/**
+ * 最后一轮回复的一次尝试
+ */
+export interface AgentRuntimeTurnAttempt {
+  id: string;
+  session_id: string;
+  /** 尝试序号（从 1 开始） */
+  attempt: number;
+  /** 是否为当前显示的回复 */
+  active: boolean;
+  overrides: TurnAttemptOverrides;
+  model?: string | null;
+  preview: string;
+  created_at: number;
+}
+
+export interface AgentRuntimeSwitchTurnAttemptRequest {
+  session_id: string;
+  attempt_id: string;
+}
+
 export interface AgentRuntimeRespondActionRequest {
   session_id: string;
   request_id: string;
@@ -283,10 +358,28 @@ export interface AgentRuntimeRespondActionRequest {
   };
 }
 
+/** 会话模型锁定 */
+export interface SessionModelLock {
+  provider_selector: string;
+  model_name: string;
+  /** 锁定时间（毫秒时间戳） */
+  locked_at: number;
+}
+
+export interface AgentRuntimeSessionModelLockRequest {
+  locked: boolean;
+  /** `locked=true` 时必填 */
+  provider_selector?: string;
+  /** `locked=true` 时必填 */
+  model_name?: string;
+}
+
 export interface AgentRuntimeUpdateSessionRequest {
   session_id: string;
   name?: string;
   execution_strategy?: AsterExecutionStrategy;
+  /** 不传则保持锁定状态不变 */
+  model_lock?: AgentRuntimeSessionModelLockRequest;
 }
 
 export interface AgentRuntimeSpawnSubagentRequest {
@@ -574,6 +667,27 @@ export async function promoteAgentRuntimeQueuedTurn(
   return await safeInvoke("agent_runtime_promote_queued_turn", { request });
 }
 
+/**
+ * 重新生成最后一轮回复，返回新尝试的序号
+ */
+export async function regenerateAgentRuntimeTurn(
+  request: AgentRuntimeRegenerateTurnRequest,
+): Promise<number> {
+  return await safeInvoke("agent_runtime_regenerate_turn", { request });
+}
+
+export async function listAgentRuntimeTurnAttempts(
+  sessionId: string,
+): Promise<AgentRuntimeTurnAttempt[]> {
+  return await safeInvoke("agent_runtime_list_turn_attempts", { sessionId });
+}
+
+export async function switchAgentRuntimeTurnAttempt(
+  request: AgentRuntimeSwitchTurnAttemptRequest,
+): Promise<boolean> {
+  return await safeInvoke("agent_runtime_switch_turn_attempt", { request });
+}
+
 export async function respondAgentRuntimeAction(
   request: AgentRuntimeRespondActionRequest,
 ): Promise<void> {
@@ -647,6 +761,13 @@ export async function listAgentRuntimeSessions(): Promise<AsterSessionInfo[]> {
   }
 }
 
+/** 分页列出会话（按更新时间降序） */
+export async function listAgentRuntimeSessionsPage(
+  page?: PageRequest,
+): Promise<Page<AsterSessionInfo>> {
+  return await safeInvoke("agent_runtime_list_sessions_page", { page });
+}
+
 export async function getAgentRuntimeSession(
   sessionId: string,
 ): Promise<AsterSessionDetail> {
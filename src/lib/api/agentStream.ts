@@ -241,6 +241,7 @@ export type StreamEvent =
   | StreamEventQueueStarted
   | StreamEventQueueCleared
   | StreamEventSubagentStatusChanged
+  | StreamEventModelLockOverrideRequested
   | StreamEventDone
   | StreamEventFinalDone
   | StreamEventWarning
@@ -483,6 +484,19 @@ export interface StreamEventSubagentStatusChanged {
     | "not_found";
 }
 
+/**
+ * 会话已锁定模型，切换请求需要确认
+ * 本回合仍使用锁定配置；确认后在请求元数据中携带 `model_lock_override: true` 重新提交
+ */
+export interface StreamEventModelLockOverrideRequested {
+  type: "model_lock_override_requested";
+  session_id: string;
+  locked_provider: string;
+  locked_model: string;
+  requested_provider: string;
+  requested_model: string;
+}
+
 /**
  * 完成事件（单次 API 响应完成，工具循环可能继续）
  * Requirements: 9.5 - THE Frontend SHALL display token usage statistics after each Agent response
@@ -848,6 +862,15 @@ export function parseStreamEvent(data: unknown): StreamEvent | null {
             | "closed"
             | "not_found") || "idle",
       };
+    case "model_lock_override_requested":
+      return {
+        type: "model_lock_override_requested",
+        session_id: (event.session_id as string) || "",
+        locked_provider: (event.locked_provider as string) || "",
+        locked_model: (event.locked_model as string) || "",
+        requested_provider: (event.requested_provider as string) || "",
+        requested_model: (event.requested_model as string) || "",
+      };
     case "final_done":
       return {
         type: "final_done",
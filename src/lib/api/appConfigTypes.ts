@@ -14,12 +14,41 @@ export interface TlsConfig {
   key_path: string | null;
 }
 
+/** 响应缓存匹配方式：exact 规范化字段顺序后完全相同；semantic 额外折叠空白并忽略不影响输出的字段 */
+export type ResponseCacheMode = "exact" | "semantic";
+
 export interface ResponseCacheConfig {
   enabled: boolean;
   ttl_secs: number;
   max_entries: number;
   max_body_bytes: number;
   cacheable_status_codes: number[];
+  mode: ResponseCacheMode;
+  /** 按路由启用（chat_completions / anthropic_messages），未列出的路由默认启用 */
+  routes: Record<string, boolean>;
+}
+
+/** 路由认证方式：无需认证 / API Key / 本机当前操作系统用户 */
+export type RouteAuthMode = "none" | "api_key" | "os_user";
+
+export interface RouteAuthRule {
+  /** 精确匹配；以 `*` 结尾时按前缀匹配 */
+  path: string;
+  mode: RouteAuthMode;
+}
+
+export interface RouteAuthConfig {
+  default_mode: RouteAuthMode;
+  rules: RouteAuthRule[];
+}
+
+export interface ForwardProxyConfig {
+  enabled: boolean;
+  port: number;
+  /** 额外拦截的主机名 -> Provider ID */
+  provider_hosts?: Record<string, string>;
+  /** 允许转发的回环/内网主机，默认全部拒绝 */
+  allowed_private_hosts?: string[];
 }
 
 export interface RemoteManagementConfig {
@@ -28,10 +57,20 @@ export interface RemoteManagementConfig {
   disable_control_panel: boolean;
 }
 
+export interface CredentialQuotaLimit {
+  max_requests?: number | null;
+  max_tokens?: number | null;
+  window_secs: number;
+}
+
 export interface QuotaExceededConfig {
   switch_project: boolean;
   switch_preview_model: boolean;
   cooldown_seconds: number;
+  /** 用量达到已知配额的该比例时主动切换凭证（0~1） */
+  soft_limit_ratio?: number;
+  /** 各凭证的已知配额（凭证 UUID -> 配额） */
+  credential_limits?: Record<string, CredentialQuotaLimit>;
 }
 
 export interface MultiSearchEngineEntryConfig {
@@ -91,6 +130,8 @@ export interface VoiceConfig {
   stt_auto_stop?: boolean;
   voice_input_enabled?: boolean;
   voice_output_enabled?: boolean;
+  /** edge-tts 兼容服务地址（tts_service 为 edge 时使用） */
+  tts_endpoint?: string;
 }
 
 export interface ImageGenConfig {
@@ -172,6 +213,8 @@ export interface Config {
     api_key: string;
     tls: TlsConfig;
     response_cache: ResponseCacheConfig;
+    route_auth?: RouteAuthConfig;
+    forward_proxy?: ForwardProxyConfig;
   };
   providers: {
     kiro: {
@@ -205,6 +248,8 @@ export interface Config {
   credential_pool: CredentialPoolConfig;
   proxy_url: string | null;
   minimize_to_tray: boolean;
+  /** 后台模式：启动时不显示主窗口，仅保留托盘 */
+  background_mode?: boolean;
   language: string;
   experimental?: ExperimentalFeatures;
   tool_calling?: ToolCallingConfig;
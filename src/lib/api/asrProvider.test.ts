@@ -1,16 +1,22 @@
 import { beforeEach, describe, expect, it, vi } from "vitest";
-import { safeInvoke } from "@/lib/dev-bridge";
+import { safeInvoke, safeListen } from "@/lib/dev-bridge";
 import {
   addAsrCredential,
   cancelRecording,
+  cancelWhisperModelDownload,
   closeVoiceWindow,
   deleteAsrCredential,
   deleteVoiceInstruction,
+  deleteWhisperModel,
+  downloadWhisperModel,
   getAsrCredentials,
   getRecordingStatus,
   getVoiceInputConfig,
   getVoiceInstructions,
   listAudioDevices,
+  listenAsrPartialResult,
+  listenWhisperModelDownloadProgress,
+  listWhisperModels,
   openInputWithText,
   openVoiceWindow,
   outputVoiceText,
@@ -27,6 +33,7 @@ import {
 
 vi.mock("@/lib/dev-bridge", () => ({
   safeInvoke: vi.fn(),
+  safeListen: vi.fn(),
 }));
 
 describe("asrProvider API", () => {
@@ -168,4 +175,80 @@ describe("asrProvider API", () => {
       text: "prefilled",
     });
   });
+
+  it("流式识别应开启 stream 并转发中间结果", async () => {
+    const unlisten = vi.fn();
+    let listener: ((event: { payload: unknown }) => void) | undefined;
+    vi.mocked(safeListen).mockImplementationOnce(async (_event, handler) => {
+      listener = handler as typeof listener;
+      return unlisten;
+    });
+    vi.mocked(safeInvoke).mockResolvedValueOnce({
+      text: "你好世界",
+      provider: "讯飞语音",
+    });
+
+    const partials: string[] = [];
+    await expect(
+      listenAsrPartialResult((result) => partials.push(result.text)),
+    ).resolves.toBe(unlisten);
+    listener?.({ payload: { text: "你好", is_final: false } });
+    await transcribeAudio(new Uint8Array([1]), 16000, undefined, true);
+
+    expect(safeListen).toHaveBeenCalledWith(
+      "asr:partial_result",
+      expect.any(Function),
+    );
+    expect(safeInvoke).toHaveBeenCalledWith("transcribe_audio", {
+      audioData: [1],
+      sampleRate: 16000,
+      credentialId: undefined,
+      stream: true,
+    });
+    expect(partials).toEqual(["你好"]);
+  });
+
+  it("应代理 Whisper 模型管理命令并转发下载进度", async () => {
+    const unlisten = vi.fn();
+    let listener: ((event: { payload: unknown }) => void) | undefined;
+    vi.mocked(safeListen).mockImplementationOnce(async (_event, handler) => {
+      listener = handler as typeof listener;
+      return unlisten;
+    });
+    vi.mocked(safeInvoke)
+      .mockResolvedValueOnce([
+        { model: "base", downloaded: false, partial_bytes: 1024 },
+      ])
+      .mockResolvedValueOnce("/models/ggml-base.bin")
+      .mockResolvedValueOnce(true)
+      .mockResolvedValueOnce(true);
+
+    const progress: number[] = [];
+    await expect(
+      listenWhisperModelDownloadProgress((p) =>
+        progress.push(p.downloaded_bytes),
+      ),
+    ).resolves.toBe(unlisten);
+    listener?.({
+      payload: { model: "base", downloaded_bytes: 2048, total_bytes: 4096 },
+    });
+
+    await expect(listWhisperModels()).resolves.toEqual([
+      expect.objectContaining({ partial_bytes: 1024 }),
+    ]);
+    await expect(downloadWhisperModel("base")).resolves.toBe(
+      "/models/ggml-base.bin",
+    );
+    await expect(cancelWhisperModelDownload("base")).resolves.toBe(true);
+    await expect(deleteWhisperModel("base")).resolves.toBe(true);
+
+    expect(safeListen).toHaveBeenCalledWith(
+      "whisper-model-download-progress",
+      expect.any(Function),
+    );
+    expect(safeInvoke).toHaveBeenNthCalledWith(2, "download_whisper_model", {
+      model: "base",
+    });
+    expect(progress).toEqual([2048]);
+  });
 });
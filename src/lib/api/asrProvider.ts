@@ -4,7 +4,7 @@
  * 定义语音识别服务相关的类型，与 Rust 后端保持一致。
  */
 
-import { safeInvoke } from "@/lib/dev-bridge";
+import { safeInvoke, safeListen } from "@/lib/dev-bridge";
 
 // ============ ASR Provider 类型 ============
 
@@ -73,6 +73,21 @@ export interface VoiceOutputConfig {
   type_delay_ms: number;
 }
 
+/** 语音活动检测到静音后的动作 */
+export type VoiceVadMode = "auto_stop" | "segment";
+
+/** 语音活动检测配置 */
+export interface VoiceVadConfig {
+  enabled: boolean;
+  /** 静音阈值（RMS，0-1） */
+  silence_threshold: number;
+  /** 说话后持续静音多久触发（毫秒） */
+  silence_duration_ms: number;
+  /** 至少检测到多长的语音才算开始说话（毫秒） */
+  min_speech_ms: number;
+  mode: VoiceVadMode;
+}
+
 /** 语音处理指令 */
 export interface VoiceInstruction {
   id: string;
@@ -99,6 +114,8 @@ export interface VoiceInputConfig {
   translate_shortcut?: string;
   /** 翻译模式使用的指令 ID */
   translate_instruction_id: string;
+  /** 语音活动检测（静音自动停止）配置 */
+  vad?: VoiceVadConfig;
 }
 
 // ============ 麦克风设备类型 ============
@@ -204,19 +221,45 @@ export interface PolishResult {
   instruction_name: string;
 }
 
-/** 执行语音识别 */
+/** 流式识别中间结果事件 */
+export const ASR_PARTIAL_RESULT_EVENT = "asr:partial_result";
+
+/** 流式识别中间结果 */
+export interface AsrPartialResult {
+  /** 截至目前的完整识别文本（不是增量） */
+  text: string;
+  is_final: boolean;
+}
+
+/**
+ * 执行语音识别
+ *
+ * `stream` 为 true 时后端通过 `asr:partial_result` 事件推送中间结果，
+ * 配合 `listenAsrPartialResult` 显示实时字幕。
+ */
 export async function transcribeAudio(
   audioData: Uint8Array,
   sampleRate: number,
   credentialId?: string,
+  stream?: boolean,
 ): Promise<TranscribeResult> {
   return safeInvoke<TranscribeResult>("transcribe_audio", {
     audioData: Array.from(audioData),
     sampleRate,
     credentialId,
+    stream,
   });
 }
 
+/** 监听流式识别中间结果 */
+export async function listenAsrPartialResult(
+  handler: (result: AsrPartialResult) => void,
+): Promise<() => void> {
+  return safeListen<AsrPartialResult>(ASR_PARTIAL_RESULT_EVENT, (event) =>
+    handler(event.payload),
+  );
+}
+
 /** 润色文本 */
 export async function polishVoiceText(
   text: string,
@@ -283,11 +326,92 @@ export async function cancelRecording(): Promise<void> {
   return safeInvoke<void>("cancel_recording");
 }
 
+/** VAD 切分出音频段的事件，载荷与 stopRecording 的返回值相同 */
+export const VOICE_RECORDING_SEGMENT_EVENT = "voice-recording-segment";
+
+/** 监听 VAD 切分出的音频段（仅 vad.mode 为 segment 时触发） */
+export async function listenRecordingSegment(
+  handler: (segment: StopRecordingResult) => void,
+): Promise<() => void> {
+  return safeListen<StopRecordingResult>(
+    VOICE_RECORDING_SEGMENT_EVENT,
+    (event) => handler(event.payload),
+  );
+}
+
 /** 获取录音状态 */
 export async function getRecordingStatus(): Promise<RecordingStatus> {
   return safeInvoke<RecordingStatus>("get_recording_status");
 }
 
+// ============ Whisper 模型管理 ============
+
+/** 本地 Whisper 模型状态 */
+export interface WhisperModelStatus {
+  model: WhisperModelSize;
+  filename: string;
+  /** 文件大小（字节） */
+  size_bytes: number;
+  /** 是否已下载完成 */
+  downloaded: boolean;
+  /** 未完成下载已写入的字节数（可续传） */
+  partial_bytes: number;
+  /** 模型文件路径 */
+  path: string;
+}
+
+/** Whisper 模型下载进度事件 */
+export const WHISPER_MODEL_DOWNLOAD_PROGRESS_EVENT =
+  "whisper-model-download-progress";
+
+/** Whisper 模型下载进度 */
+export interface WhisperModelDownloadProgress {
+  model: WhisperModelSize;
+  downloaded_bytes: number;
+  total_bytes: number;
+}
+
+/** 列出可下载的 Whisper 模型及本地状态 */
+export async function listWhisperModels(): Promise<WhisperModelStatus[]> {
+  return safeInvoke<WhisperModelStatus[]>("list_whisper_models");
+}
+
+/**
+ * 下载 Whisper 模型
+ *
+ * 有未完成的下载时续传，下载完成并通过校验后返回模型文件路径。
+ * 进度通过 `listenWhisperModelDownloadProgress` 监听。
+ */
+export async function downloadWhisperModel(
+  model: WhisperModelSize,
+): Promise<string> {
+  return safeInvoke<string>("download_whisper_model", { model });
+}
+
+/** 取消 Whisper 模型下载（保留已下载部分） */
+export async function cancelWhisperModelDownload(
+  model: WhisperModelSize,
+): Promise<boolean> {
+  return safeInvoke<boolean>("cancel_whisper_model_download", { model });
+}
+
+/** 删除 Whisper 模型（含未完成的下载） */
+export async function deleteWhisperModel(
+  model: WhisperModelSize,
+): Promise<boolean> {
+  return safeInvoke<boolean>("delete_whisper_model", { model });
+}
+
+/** 监听 Whisper 模型下载进度 */
+export async function listenWhisperModelDownloadProgress(
+  handler: (progress: WhisperModelDownloadProgress) => void,
+): Promise<() => void> {
+  return safeListen<WhisperModelDownloadProgress>(
+    WHISPER_MODEL_DOWNLOAD_PROGRESS_EVENT,
+    (event) => handler(event.payload),
+  );
+}
+
 /** 打开带预填文本的输入框 */
 export async function openInputWithText(text: string): Promise<void> {
   return safeInvoke<void>("open_input_with_text", { text });
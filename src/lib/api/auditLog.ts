@@ -0,0 +1,74 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+import type { Page } from "./pagination";
+
+export interface AuditLogSettings {
+  enabled: boolean;
+  /** 是否记录请求体（脱敏后截断） */
+  include_request_body: boolean;
+  max_body_chars: number;
+  /** 保留天数，0 表示不自动清理 */
+  retention_days: number;
+}
+
+/** 单次请求的审计记录 */
+export interface RequestAuditRecord {
+  id: number;
+  request_id: string;
+  /** Unix 秒 */
+  created_at: number;
+  provider: string;
+  model: string;
+  credential_id: string | null;
+  is_stream: boolean;
+  status: "success" | "failed" | "timeout" | "cancelled";
+  latency_ms: number;
+  retry_count: number;
+  input_tokens: number | null;
+  output_tokens: number | null;
+  error_message: string | null;
+  request_body: string | null;
+  /** 严格模式下的协议转换损失，如 `seed=dropped, tool_choice=transformed` */
+  conversion_warnings: string | null;
+}
+
+export interface AuditQuery {
+  /** 全文检索关键字（空格分隔，全部匹配） */
+  text?: string;
+  provider?: string;
+  model?: string;
+  status?: string;
+  /** Unix 秒（含） */
+  from?: number;
+  to?: number;
+  limit?: number;
+  /** 仅导出使用；分页检索请使用 cursor */
+  offset?: number;
+  /** 上一页返回的 next_cursor */
+  cursor?: string | null;
+}
+
+/** 分页检索（最新在前） */
+export async function searchAuditLog(
+  query?: AuditQuery,
+): Promise<Page<RequestAuditRecord>> {
+  return safeInvoke<Page<RequestAuditRecord>>("search_audit_log", { query });
+}
+
+/** 按检索条件导出为 JSON Lines */
+export async function exportAuditLog(query?: AuditQuery): Promise<string> {
+  return safeInvoke<string>("export_audit_log", { query });
+}
+
+export async function clearAuditLog(): Promise<number> {
+  return safeInvoke<number>("clear_audit_log");
+}
+
+export async function getAuditLogSettings(): Promise<AuditLogSettings> {
+  return safeInvoke<AuditLogSettings>("get_audit_log_settings");
+}
+
+export async function updateAuditLogSettings(
+  settings: AuditLogSettings,
+): Promise<void> {
+  return safeInvoke<void>("update_audit_log_settings", { settings });
+}
@@ -0,0 +1,27 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 后台模式状态（启动时不显示主窗口，仅保留托盘） */
+export interface BackgroundModeStatus {
+  background_mode: boolean;
+  autostart_enabled: boolean;
+  launched_in_background: boolean;
+}
+
+export async function getBackgroundModeStatus(): Promise<BackgroundModeStatus> {
+  return safeInvoke<BackgroundModeStatus>("get_background_mode_status");
+}
+
+/**
+ * 切换后台模式
+ *
+ * @param autostart 传入时同时注册或取消开机自启动
+ */
+export async function setBackgroundMode(
+  enabled: boolean,
+  autostart?: boolean,
+): Promise<BackgroundModeStatus> {
+  return safeInvoke<BackgroundModeStatus>("set_background_mode", {
+    enabled,
+    autostart,
+  });
+}
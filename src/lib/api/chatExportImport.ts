@@ -0,0 +1,25 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 支持导入的对话导出格式 */
+export type ChatExportFormat = "chatgpt" | "claude";
+
+/** 未导入的对话、附件及原因 */
+export interface SkippedChatItem {
+  conversation: string;
+  reason: string;
+}
+
+export interface ChatExportImportReport {
+  format: ChatExportFormat;
+  imported_sessions: number;
+  imported_messages: number;
+  skipped: SkippedChatItem[];
+}
+
+/** 将 ChatGPT / Claude 的对话导出（conversations.json 或 zip 存档）导入为通用对话 */
+export async function importChatExport(
+  path: string,
+  format?: ChatExportFormat,
+): Promise<ChatExportImportReport> {
+  return safeInvoke("import_chat_export", { path, format });
+}
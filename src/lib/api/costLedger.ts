@@ -0,0 +1,77 @@
+import { safeInvoke, safeListen } from "@/lib/dev-bridge";
+
+export const COST_BUDGET_EVENT = "cost-budget-event";
+
+export type BudgetPeriod = "daily" | "monthly";
+
+/** 费用预算（只提醒，不拦截请求） */
+export interface CostBudget {
+  period: BudgetPeriod;
+  /** 限定 Provider，为空表示全部 */
+  provider?: string | null;
+  amount_usd: number;
+}
+
+export interface CostBudgetSettings {
+  enabled: boolean;
+  budgets: CostBudget[];
+  /** 达到预算的该比例时提醒（0~1） */
+  alert_ratio: number;
+}
+
+export type SpendGroup =
+  | "day"
+  | "month"
+  | "provider"
+  | "credential"
+  | "model"
+  | "session"
+  | "tenant";
+
+export interface SpendBucket {
+  key: string;
+  requests: number;
+  input_tokens: number;
+  output_tokens: number;
+  cost_usd: number;
+}
+
+export interface CostBudgetEvent {
+  type: "alert" | "exceeded";
+  period: BudgetPeriod;
+  /** "global" 或 Provider 名称 */
+  scope: string;
+  spent_usd: number;
+  budget_usd: number;
+}
+
+/** 按维度汇总花费，日期为 UTC YYYY-MM-DD，默认本月初至今天 */
+export async function getCostSpend(
+  group: SpendGroup,
+  fromDay?: string,
+  toDay?: string,
+): Promise<SpendBucket[]> {
+  return safeInvoke<SpendBucket[]>("get_cost_spend", {
+    group,
+    fromDay,
+    toDay,
+  });
+}
+
+export async function getCostBudgetSettings(): Promise<CostBudgetSettings> {
+  return safeInvoke<CostBudgetSettings>("get_cost_budget_settings");
+}
+
+export async function updateCostBudgetSettings(
+  settings: CostBudgetSettings,
+): Promise<void> {
+  return safeInvoke<void>("update_cost_budget_settings", { settings });
+}
+
+export async function listenCostBudgetEvents(
+  handler: (event: CostBudgetEvent) => void,
+): Promise<() => void> {
+  return safeListen<CostBudgetEvent>(COST_BUDGET_EVENT, (event) =>
+    handler(event.payload),
+  );
+}
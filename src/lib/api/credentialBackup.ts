@@ -0,0 +1,61 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 导入时已存在条目的处理方式 */
+export type CredentialBackupConflictStrategy = "skip" | "overwrite" | "merge";
+
+export interface CredentialBackupCounts {
+  pool: number;
+  plugin_secrets: number;
+  asr: number;
+}
+
+/** 备份文件头（无需口令即可读取） */
+export interface CredentialBackupHeader {
+  format: string;
+  version: number;
+  app_version: string;
+  /** 导出时间（毫秒时间戳） */
+  created_at: number;
+  counts: CredentialBackupCounts;
+}
+
+export interface CredentialBackupExport extends CredentialBackupHeader {
+  /** 未能导出的条目 */
+  warnings: string[];
+}
+
+export interface CredentialBackupImportCounts {
+  inserted: number;
+  overwritten: number;
+  merged: number;
+  skipped: number;
+}
+
+export interface CredentialBackupImportReport {
+  pool: CredentialBackupImportCounts;
+  plugin_secrets: CredentialBackupImportCounts;
+  asr: CredentialBackupImportCounts;
+  warnings: string[];
+}
+
+/** 导出凭证池、插件密钥与 ASR 凭证到口令加密的备份文件 */
+export async function exportCredentialBackup(
+  path: string,
+  passphrase: string,
+): Promise<CredentialBackupExport> {
+  return safeInvoke("export_credential_backup", { path, passphrase });
+}
+
+export async function inspectCredentialBackup(
+  path: string,
+): Promise<CredentialBackupHeader> {
+  return safeInvoke("inspect_credential_backup", { path });
+}
+
+export async function importCredentialBackup(
+  path: string,
+  passphrase: string,
+  strategy: CredentialBackupConflictStrategy = "skip",
+): Promise<CredentialBackupImportReport> {
+  return safeInvoke("import_credential_backup", { path, passphrase, strategy });
+}
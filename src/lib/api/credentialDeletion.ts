@@ -0,0 +1,61 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+import type { ProviderCredential } from "./providerPool";
+
+/** 待粉碎的凭证 */
+export interface PendingCredentialDeletion {
+  uuid: string;
+  provider_type: string;
+  name?: string | null;
+  /** 登记前是否已被手动禁用 */
+  was_disabled: boolean;
+  /** 登记时间（Unix 秒） */
+  requested_at: number;
+  /** 宽限期结束时间（Unix 秒） */
+  purge_after: number;
+}
+
+/** 粉碎回执（只含密钥指纹，不含密钥本身） */
+export interface CredentialDeletionReceipt {
+  uuid: string;
+  provider_type: string;
+  name?: string | null;
+  secret_fingerprint: string;
+  requested_at: number;
+  shredded_at: number;
+  /** 已清理的项目 */
+  purged: string[];
+}
+
+export interface CredentialDeletionOverview {
+  pending: PendingCredentialDeletion[];
+  receipts: CredentialDeletionReceipt[];
+}
+
+/** 登记删除：立即禁用凭证，宽限期（默认 24 小时）结束后粉碎 */
+export async function scheduleCredentialDeletion(
+  uuid: string,
+  graceMinutes?: number,
+): Promise<PendingCredentialDeletion> {
+  return safeInvoke("schedule_provider_pool_credential_deletion", {
+    uuid,
+    graceMinutes,
+  });
+}
+
+/** 宽限期内撤销删除 */
+export async function cancelCredentialDeletion(
+  uuid: string,
+): Promise<ProviderCredential> {
+  return safeInvoke("cancel_provider_pool_credential_deletion", { uuid });
+}
+
+/** 跳过宽限期立即粉碎凭证 */
+export async function shredCredential(
+  uuid: string,
+): Promise<CredentialDeletionReceipt> {
+  return safeInvoke("shred_provider_pool_credential", { uuid });
+}
+
+export async function getCredentialDeletions(): Promise<CredentialDeletionOverview> {
+  return safeInvoke("get_provider_pool_credential_deletions");
+}
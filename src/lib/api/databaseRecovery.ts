@@ -0,0 +1,25 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 启动时对损坏数据库采取的恢复动作 */
+export type DatabaseRecoveryAction =
+  | "none"
+  | "restored_backup"
+  | "salvaged"
+  | "rebuilt"
+  | "failed";
+
+/** 数据库完整性检查与恢复报告 */
+export interface DatabaseRecoveryReport {
+  corrupted: boolean;
+  issues: string[];
+  action: DatabaseRecoveryAction;
+  quarantined_path?: string | null;
+  restored_from?: string | null;
+  salvaged_tables: string[];
+  lost_tables: string[];
+  message?: string | null;
+}
+
+export async function getDatabaseRecoveryReport(): Promise<DatabaseRecoveryReport> {
+  return safeInvoke<DatabaseRecoveryReport>("get_database_recovery_report");
+}
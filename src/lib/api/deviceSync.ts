@@ -0,0 +1,118 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 同步存储类型 */
+export type DeviceSyncStorageKind = "folder" | "webdav" | "s3";
+
+/** 同步类别 */
+export type DeviceSyncCategory =
+  | "settings"
+  | "credentials"
+  | "skills"
+  | "prompts";
+
+export interface DeviceSyncWebdavConfig {
+  /** 存放同步包的目录地址 */
+  url: string;
+  username?: string;
+  password?: string;
+}
+
+export interface DeviceSyncS3Config {
+  endpoint: string;
+  region: string;
+  bucket: string;
+  prefix?: string;
+  access_key_id: string;
+  secret_access_key: string;
+}
+
+export interface DeviceSyncCategories {
+  settings: boolean;
+  credentials: boolean;
+  skills: boolean;
+  prompts: boolean;
+}
+
+export interface DeviceSyncSettings {
+  enabled: boolean;
+  /** 本设备显示名称（为空时使用主机名） */
+  device_name?: string;
+  storage: DeviceSyncStorageKind;
+  /** 同步文件夹路径（storage = folder） */
+  folder_path?: string;
+  webdav: DeviceSyncWebdavConfig;
+  s3: DeviceSyncS3Config;
+  categories: DeviceSyncCategories;
+}
+
+/** 向量时钟：设备 ID → 推送次数 */
+export type VectorClock = Record<string, number>;
+
+export type ClockOrdering = "equal" | "before" | "after" | "concurrent";
+
+export type DeviceSyncAction =
+  | "pushed"
+  | "pulled"
+  | "up_to_date"
+  | "remote_newer"
+  | "local_newer"
+  | "conflict";
+
+/** 远端同步包概要 */
+export interface RemoteSyncInfo {
+  device_id: string;
+  device_name: string;
+  /** 推送时间（毫秒时间戳） */
+  updated_at: number;
+  clock: VectorClock;
+  categories: DeviceSyncCategory[];
+}
+
+export interface DeviceSyncStatus {
+  device_id: string;
+  device_name: string;
+  storage: string;
+  local_clock: VectorClock;
+  last_synced_at?: number | null;
+  /** 自上次同步后本地有修改的类别 */
+  local_changes: DeviceSyncCategory[];
+  remote?: RemoteSyncInfo | null;
+  relation?: ClockOrdering | null;
+  suggested_action: DeviceSyncAction;
+}
+
+export interface DeviceSyncResult {
+  action: DeviceSyncAction;
+  categories: DeviceSyncCategory[];
+  local_changes: DeviceSyncCategory[];
+  remote?: RemoteSyncInfo | null;
+  warnings: string[];
+}
+
+export async function getDeviceSyncSettings(): Promise<DeviceSyncSettings> {
+  return safeInvoke("get_device_sync_settings");
+}
+
+export async function updateDeviceSyncSettings(
+  settings: DeviceSyncSettings,
+): Promise<void> {
+  return safeInvoke("update_device_sync_settings", { settings });
+}
+
+export async function getDeviceSyncStatus(): Promise<DeviceSyncStatus> {
+  return safeInvoke("get_device_sync_status");
+}
+
+export async function pushDeviceSync(
+  passphrase: string,
+  force = false,
+): Promise<DeviceSyncResult> {
+  return safeInvoke("device_sync_push", { passphrase, force });
+}
+
+export async function pullDeviceSync(
+  passphrase: string,
+  force = false,
+): Promise<DeviceSyncResult> {
+  return safeInvoke("device_sync_pull", { passphrase, force });
+}
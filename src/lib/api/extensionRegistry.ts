@@ -0,0 +1,77 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 注册表信任策略 */
+export type RegistryTrustPolicy = "signed" | "unsigned";
+
+/** 单个扩展注册表（自托管索引） */
+export interface ExtensionRegistryConfig {
+  id: string;
+  name: string;
+  /** 索引 JSON 地址（HTTPS），签名位于 `<index_url>.minisig` */
+  index_url: string;
+  enabled: boolean;
+  /** minisign 公钥（base64） */
+  public_key?: string;
+  trust: RegistryTrustPolicy;
+}
+
+export interface ExtensionRegistrySettings {
+  registries: ExtensionRegistryConfig[];
+  /** 停用内置的 GitHub Skill 仓库查询 */
+  disable_github_sources: boolean;
+}
+
+export interface RegistryStatus {
+  registryId: string;
+  ok: boolean;
+  /** 索引是否通过签名校验 */
+  verified: boolean;
+  pluginCount: number;
+  skillCount: number;
+  error?: string;
+}
+
+/** 注册表中的插件 */
+export interface RegistryPlugin {
+  registryId: string;
+  registryName: string;
+  id: string;
+  name: string;
+  version: string;
+  description: string;
+  author?: string;
+  homepage?: string;
+  download_url: string;
+  sha256: string;
+}
+
+export interface RegistryPluginInstallResult {
+  success: boolean;
+  plugin: Record<string, unknown> | null;
+  error: string | null;
+}
+
+export async function getExtensionRegistrySettings(): Promise<ExtensionRegistrySettings> {
+  return safeInvoke("get_extension_registry_settings");
+}
+
+export async function updateExtensionRegistrySettings(
+  settings: ExtensionRegistrySettings,
+): Promise<void> {
+  return safeInvoke("update_extension_registry_settings", { settings });
+}
+
+export async function checkExtensionRegistries(): Promise<RegistryStatus[]> {
+  return safeInvoke("check_extension_registries");
+}
+
+export async function listRegistryPlugins(): Promise<RegistryPlugin[]> {
+  return safeInvoke("list_registry_plugins");
+}
+
+export async function installPluginFromRegistry(
+  registryId: string,
+  pluginId: string,
+): Promise<RegistryPluginInstallResult> {
+  return safeInvoke("install_plugin_from_registry", { registryId, pluginId });
+}
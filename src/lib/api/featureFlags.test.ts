@@ -0,0 +1,33 @@
+import { beforeEach, describe, expect, it, vi } from "vitest";
+import { safeInvoke } from "@/lib/dev-bridge";
+import { listFeatureFlags, setFeatureFlag } from "./featureFlags";
+
+vi.mock("@/lib/dev-bridge", () => ({
+  safeInvoke: vi.fn(),
+}));
+
+describe("featureFlags API", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("应代理功能开关列表与切换", async () => {
+    const flag = {
+      key: "wasm_plugins",
+      description: "WASM 插件",
+      enabled: true,
+      default_enabled: false,
+      overridden: true,
+    };
+    vi.mocked(safeInvoke)
+      .mockResolvedValueOnce([flag])
+      .mockResolvedValueOnce(flag);
+
+    await expect(listFeatureFlags()).resolves.toEqual([flag]);
+    await expect(setFeatureFlag("wasm_plugins", true)).resolves.toEqual(flag);
+    expect(safeInvoke).toHaveBeenLastCalledWith("set_feature_flag", {
+      key: "wasm_plugins",
+      enabled: true,
+    });
+  });
+});
@@ -0,0 +1,23 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 功能开关状态（默认值在后端代码中定义，配置只保存覆盖项） */
+export interface FeatureFlagState {
+  key: string;
+  description: string;
+  enabled: boolean;
+  default_enabled: boolean;
+  /** 是否被配置覆盖 */
+  overridden: boolean;
+}
+
+export async function listFeatureFlags(): Promise<FeatureFlagState[]> {
+  return safeInvoke<FeatureFlagState[]>("list_feature_flags");
+}
+
+/** 切换功能开关，返回更新后的状态 */
+export async function setFeatureFlag(
+  key: string,
+  enabled: boolean,
+): Promise<FeatureFlagState> {
+  return safeInvoke<FeatureFlagState>("set_feature_flag", { key, enabled });
+}
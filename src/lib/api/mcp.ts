@@ -4,15 +4,35 @@ import { safeInvoke } from "@/lib/dev-bridge";
 // 基础类型定义
 // ============================================================================
 
+/** MCP 传输方式 */
+export type McpTransportKind = "stdio" | "streamable_http" | "sse";
+
+/** 远程服务器断线重连策略 */
+export interface McpReconnectConfig {
+  max_attempts: number;
+  initial_delay_ms: number;
+  max_delay_ms: number;
+}
+
 export interface McpServer {
   id: string;
   name: string;
   server_config: {
+    /** 传输方式，默认 stdio */
+    transport?: McpTransportKind;
+    /** 启动命令（远程传输时为空） */
     command: string;
     args?: string[];
     env?: Record<string, string>;
     cwd?: string;
     timeout?: number;
+    /** 解释器路径（node / python 可执行文件） */
+    interpreter?: string;
+    /** 远程服务器地址（streamable_http / sse） */
+    url?: string;
+    /** 远程请求头，值支持 `${VAR}` 引用环境变量 */
+    headers?: Record<string, string>;
+    reconnect?: McpReconnectConfig;
   };
   description?: string;
   enabled_lime: boolean;
@@ -126,6 +146,47 @@ export interface McpResourceContent {
   blob?: string;
 }
 
+/** 执行环境检查结果 */
+export type McpEnvironmentStatus =
+  | "ok"
+  | "missing_command"
+  | "missing_runtime"
+  | "runtime_too_old"
+  | "invalid_interpreter";
+
+/** MCP 服务器执行环境诊断 */
+export interface McpEnvironmentDiagnostic {
+  status: McpEnvironmentStatus;
+  command: string;
+  resolved_path?: string;
+  runtime?: "node" | "python" | "uv" | "bun" | "docker";
+  runtime_path?: string;
+  runtime_version?: string;
+  message: string;
+  suggestion?: string;
+  search_paths: string[];
+}
+
+/** elicitation 用户操作 */
+export type McpElicitationAction = "accept" | "decline" | "cancel";
+
+/** `mcp:elicitation_request` 事件载荷 */
+export interface McpElicitationRequest {
+  request_id: string;
+  server_name: string;
+  message: string;
+  /** 扁平对象的 JSON Schema（仅基本类型属性） */
+  requested_schema: Record<string, unknown>;
+  timeout_secs: number;
+}
+
+/** `mcp:elicitation_resolved` 事件载荷 */
+export interface McpElicitationResolved {
+  request_id: string;
+  server_name: string;
+  resolution: "answered" | "timed_out" | "cancelled";
+}
+
 // ============================================================================
 // API 封装
 // ============================================================================
@@ -175,6 +236,16 @@ export const mcpApi = {
   stopServer: (name: string): Promise<void> =>
     safeInvoke("mcp_stop_server", { name }),
 
+  /** 读取服务器 stderr 日志的最后若干行 */
+  tailServerLog: (name: string, lines?: number): Promise<string[]> =>
+    safeInvoke("mcp_tail_server_log", { name, lines }),
+
+  /** 检查服务器执行环境（命令、运行时及版本），不会启动服务器 */
+  diagnoseServerEnvironment: (
+    name: string,
+  ): Promise<McpEnvironmentDiagnostic> =>
+    safeInvoke("mcp_diagnose_server_environment", { name }),
+
   // --------------------------------------------------------------------------
   // 工具管理 API
   // --------------------------------------------------------------------------
@@ -182,6 +253,10 @@ export const mcpApi = {
   /** 获取所有可用工具 */
   listTools: (): Promise<McpToolDefinition[]> => safeInvoke("mcp_list_tools"),
 
+  /** 全量重新同步工具列表（会发送 full_resync 事件） */
+  resyncTools: (): Promise<McpToolDefinition[]> =>
+    safeInvoke("mcp_resync_tools"),
+
   /** 按调用上下文获取可见工具（支持 deferred_loading） */
   listToolsForContext: (
     caller?: string,
@@ -238,4 +313,20 @@ export const mcpApi = {
   /** 读取资源内容 */
   readResource: (uri: string): Promise<McpResourceContent> =>
     safeInvoke("mcp_read_resource", { uri }),
+
+  // --------------------------------------------------------------------------
+  // Elicitation API
+  // --------------------------------------------------------------------------
+
+  /** 回复服务器的用户输入请求（accept 时 content 需符合 schema） */
+  respondElicitation: (
+    requestId: string,
+    action: McpElicitationAction,
+    content?: Record<string, unknown>,
+  ): Promise<void> =>
+    safeInvoke("mcp_respond_elicitation", { requestId, action, content }),
+
+  /** 获取待回复的请求 ID */
+  listPendingElicitations: (): Promise<string[]> =>
+    safeInvoke("mcp_list_pending_elicitations"),
 };
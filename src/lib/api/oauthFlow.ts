@@ -0,0 +1,85 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+import {
+  invalidateProviderPoolOverviewCache,
+  type ProviderCredential,
+} from "./providerPool";
+
+export type OAuthFlowKind = "authorization_code" | "device_code";
+
+export type OAuthFlowStatus =
+  | "pending"
+  | "slow_down"
+  | "completed"
+  | "failed"
+  | "expired";
+
+/** 流程启动信息 */
+export interface OAuthFlowStart {
+  flow_id: string;
+  provider: string;
+  kind: OAuthFlowKind;
+  /** 授权码流程：授权 URL（已尝试自动打开浏览器） */
+  auth_url?: string | null;
+  /** 设备码流程：展示给用户的用户码 */
+  user_code?: string | null;
+  /** 设备码流程：验证地址 */
+  verification_uri?: string | null;
+  expires_in: number;
+  /** 建议的轮询间隔（秒） */
+  interval: number;
+}
+
+export interface OAuthFlowPollResponse {
+  status: OAuthFlowStatus;
+  /** 登录成功后加入凭证池的凭证 */
+  credential?: ProviderCredential | null;
+  error?: string | null;
+}
+
+/** 启动 OAuth 登录流程（provider 如 codex、gemini） */
+export async function startOAuthFlow(provider: string): Promise<OAuthFlowStart> {
+  return safeInvoke("oauth_start_flow", { provider });
+}
+
+/** 查询流程状态，完成时凭证已加入凭证池 */
+export async function pollOAuthFlow(
+  flowId: string,
+  name?: string,
+): Promise<OAuthFlowPollResponse> {
+  return safeInvoke("oauth_poll_flow", { flowId, name });
+}
+
+export async function cancelOAuthFlow(flowId: string): Promise<boolean> {
+  return safeInvoke("oauth_cancel_flow", { flowId });
+}
+
+/**
+ * 按建议间隔轮询直到流程结束，返回加入凭证池的凭证
+ *
+ * 失败、过期或被取消时抛出错误；`slow_down` 时按 RFC 8628 增加 5 秒间隔。
+ */
+export async function waitForOAuthFlow(
+  start: OAuthFlowStart,
+  name?: string,
+): Promise<ProviderCredential> {
+  let intervalMs = Math.max(start.interval, 1) * 1000;
+  for (;;) {
+    await new Promise((resolve) => setTimeout(resolve, intervalMs));
+    const poll = await pollOAuthFlow(start.flow_id, name);
+    switch (poll.status) {
+      case "pending":
+        break;
+      case "slow_down":
+        intervalMs += 5000;
+        break;
+      case "completed":
+        if (!poll.credential) {
+          throw new Error("登录完成但未返回凭证");
+        }
+        invalidateProviderPoolOverviewCache();
+        return poll.credential;
+      default:
+        throw new Error(poll.error || "OAuth 登录失败");
+    }
+  }
+}
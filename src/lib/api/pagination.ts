@@ -0,0 +1,14 @@
+/** 游标分页请求（对应后端 `database::pagination::PageRequest`） */
+export interface PageRequest {
+  /** 上一页返回的 next_cursor，为空时从第一页开始 */
+  cursor?: string | null;
+  /** 单页条数（默认 50，最大 500） */
+  limit?: number;
+}
+
+/** 一页结果 */
+export interface Page<T> {
+  items: T[];
+  /** 下一页游标，没有更多数据时为 null */
+  next_cursor: string | null;
+}
@@ -0,0 +1,90 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 管线快照摘要 */
+export interface PipelineSnapshotSummary {
+  name: string;
+  created_at: string;
+  app_version: string;
+  note?: string | null;
+  credential_count: number;
+  plugin_count: number;
+}
+
+/** 凭证快照（不含密钥） */
+export interface CredentialSnapshot {
+  provider_type: string;
+  name?: string | null;
+  credential: Record<string, unknown>;
+  is_disabled: boolean;
+  check_health: boolean;
+  not_supported_models: string[];
+  proxy_url?: string | null;
+  native_web_search: boolean;
+  fallback_base_urls?: string[];
+  source: string;
+}
+
+/** 命名管线快照：脱敏后的生效配置、凭证池构成与插件版本 */
+export interface PipelineSnapshot {
+  name: string;
+  created_at: string;
+  app_version: string;
+  note?: string | null;
+  config: Record<string, unknown>;
+  credentials: Record<string, CredentialSnapshot>;
+  plugins: Record<string, string>;
+}
+
+export type SnapshotChangeKind = "added" | "removed" | "changed";
+
+export interface SnapshotChange {
+  path: string;
+  kind: SnapshotChangeKind;
+  before?: unknown;
+  after?: unknown;
+}
+
+/** 两个快照之间的差异 */
+export interface PipelineSnapshotDiff {
+  base: string;
+  target: string;
+  changes: SnapshotChange[];
+}
+
+export async function createPipelineSnapshot(
+  name: string,
+  note?: string,
+  overwrite = false,
+): Promise<PipelineSnapshotSummary> {
+  return safeInvoke<PipelineSnapshotSummary>("create_pipeline_snapshot", {
+    name,
+    note: note ?? null,
+    overwrite,
+  });
+}
+
+export async function listPipelineSnapshots(): Promise<
+  PipelineSnapshotSummary[]
+> {
+  return safeInvoke<PipelineSnapshotSummary[]>("list_pipeline_snapshots");
+}
+
+export async function getPipelineSnapshot(
+  name: string,
+): Promise<PipelineSnapshot> {
+  return safeInvoke<PipelineSnapshot>("get_pipeline_snapshot", { name });
+}
+
+export async function deletePipelineSnapshot(name: string): Promise<void> {
+  return safeInvoke<void>("delete_pipeline_snapshot", { name });
+}
+
+export async function diffPipelineSnapshots(
+  base: string,
+  target: string,
+): Promise<PipelineSnapshotDiff> {
+  return safeInvoke<PipelineSnapshotDiff>("diff_pipeline_snapshots", {
+    base,
+    target,
+  });
+}
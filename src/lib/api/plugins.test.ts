@@ -1,22 +1,28 @@
 import { beforeEach, describe, expect, it, vi } from "vitest";
-import { safeInvoke } from "@/lib/dev-bridge";
+import { safeInvoke, safeListen } from "@/lib/dev-bridge";
 import {
   cancelPluginTask,
+  checkPluginUpdate,
   disablePlugin,
   enablePlugin,
   getPluginQueueStats,
   getPluginStatus,
   getPlugins,
   getPluginTask,
+  installPluginFromGithub,
   listInstalledPlugins,
   listPluginTasks,
+  listenPluginReloaded,
+  reloadPlugin,
   reloadPlugins,
   uninstallPlugin,
   unloadPlugin,
+  updatePlugin,
 } from "./plugins";
 
 vi.mock("@/lib/dev-bridge", () => ({
   safeInvoke: vi.fn(),
+  safeListen: vi.fn(),
 }));
 
 describe("plugins API", () => {
@@ -69,4 +75,72 @@ describe("plugins API", () => {
     await expect(uninstallPlugin("plugin-1")).resolves.toBe(true);
     await expect(cancelPluginTask("task-1")).resolves.toBe(true);
   });
+
+  it("应重新加载单个插件并监听热重载事件", async () => {
+    const reloaded = {
+      pluginId: "demo",
+      action: "reloaded" as const,
+      processStopped: true,
+      error: null,
+    };
+    vi.mocked(safeInvoke).mockResolvedValueOnce(reloaded);
+    await expect(reloadPlugin("demo")).resolves.toEqual(reloaded);
+    expect(safeInvoke).toHaveBeenCalledWith("reload_plugin", {
+      pluginId: "demo",
+    });
+
+    const unlisten = vi.fn();
+    let listener: ((event: { payload: unknown }) => void) | undefined;
+    vi.mocked(safeListen).mockImplementationOnce(async (_event, handler) => {
+      listener = handler as typeof listener;
+      return unlisten;
+    });
+    const handler = vi.fn();
+
+    await expect(listenPluginReloaded(handler)).resolves.toBe(unlisten);
+    listener?.({ payload: reloaded });
+    expect(safeListen).toHaveBeenCalledWith(
+      "plugin:reloaded",
+      expect.any(Function),
+    );
+    expect(handler).toHaveBeenCalledWith(reloaded);
+  });
+
+  it("应代理 GitHub release 安装与更新", async () => {
+    const update = {
+      plugin_id: "demo",
+      installed_version: "1.0.0",
+      latest_version: "1.1.0",
+      tag: "v1.1.0",
+      repository: "owner/demo",
+      has_update: true,
+    };
+    vi.mocked(safeInvoke)
+      .mockResolvedValueOnce({
+        success: true,
+        plugin: { id: "demo" },
+        error: null,
+      })
+      .mockResolvedValueOnce(update)
+      .mockResolvedValueOnce({ success: true, plugin: null, error: null });
+
+    await expect(installPluginFromGithub("owner/demo")).resolves.toEqual({
+      success: true,
+      plugin: { id: "demo" },
+      error: null,
+    });
+    expect(safeInvoke).toHaveBeenCalledWith("install_plugin_from_github", {
+      repository: "owner/demo",
+      checksumFile: undefined,
+    });
+    await expect(checkPluginUpdate("demo")).resolves.toEqual(update);
+    await expect(updatePlugin("demo")).resolves.toEqual({
+      success: true,
+      plugin: null,
+      error: null,
+    });
+    expect(safeInvoke).toHaveBeenLastCalledWith("update_plugin", {
+      pluginId: "demo",
+    });
+  });
 });
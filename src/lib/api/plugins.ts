@@ -1,4 +1,4 @@
-import { safeInvoke } from "@/lib/dev-bridge";
+import { safeInvoke, safeListen } from "@/lib/dev-bridge";
 
 export interface ListPluginTasksParams {
   taskState?: string | null;
@@ -43,6 +43,38 @@ export async function reloadPlugins(): Promise<void> {
   await safeInvoke("reload_plugins");
 }
 
+export const PLUGIN_RELOADED_EVENT = "plugin:reloaded";
+
+/** 插件热重载结果（插件目录变化或手动重新加载后发送） */
+export interface PluginReloadedEvent {
+  pluginId: string;
+  action:
+    | "loaded"
+    | "reloaded"
+    | "unloaded"
+    | "updated"
+    | "removed"
+    | "failed";
+  /** 是否关闭了正在运行的后端进程，需要时重新连接 */
+  processStopped: boolean;
+  error: string | null;
+}
+
+export async function reloadPlugin(
+  pluginId: string,
+): Promise<PluginReloadedEvent> {
+  return safeInvoke<PluginReloadedEvent>("reload_plugin", { pluginId });
+}
+
+/** 监听插件热重载事件 */
+export async function listenPluginReloaded(
+  handler: (event: PluginReloadedEvent) => void,
+): Promise<() => void> {
+  return safeListen<PluginReloadedEvent>(PLUGIN_RELOADED_EVENT, (event) =>
+    handler(event.payload),
+  );
+}
+
 export async function unloadPlugin(name: string): Promise<void> {
   await safeInvoke("unload_plugin", { name });
 }
@@ -51,6 +83,115 @@ export async function uninstallPlugin(pluginId: string): Promise<boolean> {
   return safeInvoke<boolean>("uninstall_plugin", { pluginId });
 }
 
+export const PLUGIN_INSTALL_PROGRESS_EVENT = "plugin-install-progress";
+
+export interface PluginInstallResult<T = Record<string, unknown>> {
+  success: boolean;
+  /** 更新时已是最新版本则为 null */
+  plugin: T | null;
+  error: string | null;
+}
+
+/** GitHub 来源插件的更新检查结果 */
+export interface PluginUpdateInfo {
+  plugin_id: string;
+  installed_version: string;
+  latest_version: string;
+  tag: string;
+  /** owner/repo */
+  repository: string;
+  has_update: boolean;
+}
+
+/** 从 GitHub 最新 release 安装插件（`owner/repo` 或仓库地址），进度通过 plugin-install-progress 事件推送 */
+export async function installPluginFromGithub<T = Record<string, unknown>>(
+  repository: string,
+  checksumFile?: string,
+): Promise<PluginInstallResult<T>> {
+  return safeInvoke<PluginInstallResult<T>>("install_plugin_from_github", {
+    repository,
+    checksumFile,
+  });
+}
+
+export async function checkPluginUpdate(
+  pluginId: string,
+): Promise<PluginUpdateInfo> {
+  return safeInvoke<PluginUpdateInfo>("check_plugin_update", { pluginId });
+}
+
+export async function updatePlugin<T = Record<string, unknown>>(
+  pluginId: string,
+): Promise<PluginInstallResult<T>> {
+  return safeInvoke<PluginInstallResult<T>>("update_plugin", { pluginId });
+}
+
 export async function cancelPluginTask(taskId: string): Promise<boolean> {
   return safeInvoke<boolean>("cancel_plugin_task", { taskId });
 }
+
+export interface PluginStorageUsage {
+  keys: number;
+  used_bytes: number;
+  quota_bytes: number;
+}
+
+export async function pluginStorageGet<T = unknown>(
+  pluginId: string,
+  key: string,
+): Promise<T | null> {
+  return safeInvoke<T | null>("plugin_storage_get", { pluginId, key });
+}
+
+export async function pluginStorageSet(
+  pluginId: string,
+  key: string,
+  value: unknown,
+): Promise<void> {
+  await safeInvoke("plugin_storage_set", { pluginId, key, value });
+}
+
+export async function pluginStorageDelete(
+  pluginId: string,
+  key: string,
+): Promise<boolean> {
+  return safeInvoke<boolean>("plugin_storage_delete", { pluginId, key });
+}
+
+export async function pluginStorageKeys(pluginId: string): Promise<string[]> {
+  return safeInvoke<string[]>("plugin_storage_keys", { pluginId });
+}
+
+export async function pluginStorageUsage(
+  pluginId: string,
+): Promise<PluginStorageUsage> {
+  return safeInvoke<PluginStorageUsage>("plugin_storage_usage", { pluginId });
+}
+
+/** 主密钥轮换结果 */
+export interface PluginKeyRotationResult {
+  version: number;
+  rewrapped: number;
+  /** 重新加密失败的存储项（plugin_id/key） */
+  failed: string[];
+}
+
+/** 使用插件子密钥加密，返回 `enc3:v<版本>:...` 密文 */
+export async function pluginCryptoEncrypt(
+  pluginId: string,
+  plaintext: string,
+): Promise<string> {
+  return safeInvoke<string>("plugin_crypto_encrypt", { pluginId, plaintext });
+}
+
+export async function pluginCryptoDecrypt(
+  pluginId: string,
+  ciphertext: string,
+): Promise<string> {
+  return safeInvoke<string>("plugin_crypto_decrypt", { pluginId, ciphertext });
+}
+
+/** 轮换插件主密钥，并重新加密插件存储中的密文 */
+export async function pluginCryptoRotateKey(): Promise<PluginKeyRotationResult> {
+  return safeInvoke<PluginKeyRotationResult>("plugin_crypto_rotate_key");
+}
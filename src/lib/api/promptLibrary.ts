@@ -0,0 +1,127 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** Prompt 变量（内容中以 `{{name}}` 引用） */
+export interface PromptVariable {
+  name: string;
+  description?: string | null;
+  /** 未传值时使用的默认值，为空时必填 */
+  default_value?: string | null;
+}
+
+/**
+ * Prompt 库条目
+ *
+ * 系统提示词、Agent 自定义指令与 Skill 内容中可通过 `{{prompt:<id>}}`
+ * 或 `{{prompt:<id>@<version>}}` 引用。
+ */
+export interface LibraryPrompt {
+  /** 新建时留空，由后端生成 */
+  id: string;
+  name: string;
+  /** 文件夹路径，如 `writing/blog` */
+  folder?: string | null;
+  tags: string[];
+  description?: string | null;
+  content: string;
+  variables: PromptVariable[];
+  version: number;
+  /** Unix 毫秒 */
+  created_at: number;
+  updated_at: number;
+}
+
+export interface LibraryPromptVersion {
+  prompt_id: string;
+  version: number;
+  content: string;
+  variables: PromptVariable[];
+  note: string | null;
+  created_at: number;
+}
+
+export interface LibraryPromptQuery {
+  /** 文件夹（含子文件夹） */
+  folder?: string;
+  tag?: string;
+  /** 按名称、描述与内容模糊匹配 */
+  text?: string;
+}
+
+export interface PromptDiffLine {
+  kind: "equal" | "added" | "removed";
+  text: string;
+}
+
+export async function listLibraryPrompts(
+  query?: LibraryPromptQuery,
+): Promise<LibraryPrompt[]> {
+  return safeInvoke<LibraryPrompt[]>("list_library_prompts", { query });
+}
+
+export async function getLibraryPrompt(
+  id: string,
+): Promise<LibraryPrompt | null> {
+  return safeInvoke<LibraryPrompt | null>("get_library_prompt", { id });
+}
+
+/** 保存 Prompt；内容或变量变化时生成新版本 */
+export async function saveLibraryPrompt(
+  prompt: LibraryPrompt,
+  note?: string,
+): Promise<LibraryPrompt> {
+  return safeInvoke<LibraryPrompt>("save_library_prompt", {
+    request: { prompt, note },
+  });
+}
+
+export async function deleteLibraryPrompt(id: string): Promise<boolean> {
+  return safeInvoke<boolean>("delete_library_prompt", { id });
+}
+
+/** 历史版本（最新在前） */
+export async function listLibraryPromptVersions(
+  id: string,
+): Promise<LibraryPromptVersion[]> {
+  return safeInvoke<LibraryPromptVersion[]>("list_library_prompt_versions", {
+    id,
+  });
+}
+
+export async function diffLibraryPromptVersions(
+  id: string,
+  fromVersion: number,
+  toVersion: number,
+): Promise<PromptDiffLine[]> {
+  return safeInvoke<PromptDiffLine[]>("diff_library_prompt_versions", {
+    id,
+    fromVersion,
+    toVersion,
+  });
+}
+
+/** 以指定版本内容生成新版本 */
+export async function restoreLibraryPromptVersion(
+  id: string,
+  version: number,
+): Promise<LibraryPrompt> {
+  return safeInvoke<LibraryPrompt>("restore_library_prompt_version", {
+    id,
+    version,
+  });
+}
+
+/** 按变量值渲染，缺少必填变量时报错 */
+export async function renderLibraryPrompt(
+  id: string,
+  values?: Record<string, string>,
+): Promise<string> {
+  return safeInvoke<string>("render_library_prompt", { id, values });
+}
+
+export async function listLibraryPromptFolders(): Promise<string[]> {
+  return safeInvoke<string[]>("list_library_prompt_folders");
+}
+
+export async function listLibraryPromptTags(): Promise<string[]> {
+  return safeInvoke<string[]>("list_library_prompt_tags");
+}
@@ -1,4 +1,4 @@
-import { safeInvoke } from "@/lib/dev-bridge";
+import { safeInvoke, safeListen } from "@/lib/dev-bridge";
 
 interface ProviderPoolQueryOptions {
   forceRefresh?: boolean;
@@ -69,7 +69,8 @@ export type PoolProviderType =
   | "claude"
   | "codex"
   | "claude_oauth"
-  | "gemini_api_key";
+  | "gemini_api_key"
+  | "mock";
 
 // Credential data types
 export interface KiroOAuthCredential {
@@ -118,6 +119,19 @@ export interface ClaudeOAuthCredential {
   creds_file_path: string;
 }
 
+export type MockFault = "rate_limited" | "server_error" | "malformed_json";
+
+export interface MockProviderConfig {
+  response_text?: string;
+  latency_ms?: number;
+  fault?: MockFault;
+  fault_every?: number;
+}
+
+export interface MockCredential extends MockProviderConfig {
+  type: "mock";
+}
+
 export type CredentialData =
   | KiroOAuthCredential
   | GeminiOAuthCredential
@@ -126,7 +140,8 @@ export type CredentialData =
   | ClaudeKeyCredential
   | GeminiApiKeyCredential
   | CodexOAuthCredential
-  | ClaudeOAuthCredential;
+  | ClaudeOAuthCredential
+  | MockCredential;
 
 // Provider credential
 export interface ProviderCredential {
@@ -184,6 +199,35 @@ export interface CredentialDisplay {
   api_key?: string;
   // 凭证级代理 URL（可覆盖全局代理设置）
   proxy_url?: string;
+  // 是否支持 Provider 原生联网搜索（Anthropic / Gemini）
+  supports_native_web_search?: boolean;
+  // 是否已开启原生联网搜索（按搜索次数额外计费）
+  native_web_search?: boolean;
+  // 备用 base_url 列表（主 base_url 不可达时按顺序切换）
+  fallback_base_urls?: string[];
+  // 负载均衡权重（选择比例与权重成正比，默认 1）
+  weight?: number;
+  // 端点健康状态（未检测过时为空）
+  endpoint_health?: CredentialEndpointHealth | null;
+}
+
+// 单个 base_url 的可达性状态
+export interface EndpointStatus {
+  url: string;
+  reachable: boolean;
+  latency_ms?: number;
+  last_checked?: string;
+  last_error?: string;
+  consecutive_failures: number;
+}
+
+// 凭证的端点健康状态
+export interface CredentialEndpointHealth {
+  // 当前固定使用的 base_url
+  active_base_url: string;
+  // 是否已切换到备用 base_url
+  failed_over: boolean;
+  endpoints: EndpointStatus[];
 }
 
 // Pool statistics
@@ -203,6 +247,42 @@ export interface ProviderPoolOverview {
   credentials: CredentialDisplay[];
 }
 
+export const PROVIDER_POOL_EVENT = "provider-pool-event";
+
+// Provider pool change (tagged by `kind`)
+export type ProviderPoolChange =
+  | { kind: "added"; credential: CredentialDisplay }
+  | { kind: "updated"; credential: CredentialDisplay }
+  | { kind: "removed"; uuid: string; provider_type: string }
+  | {
+      kind: "health_changed";
+      uuid: string;
+      provider_type: string;
+      is_healthy: boolean;
+      error?: string;
+    }
+  | {
+      kind: "cooldown_started";
+      uuid: string;
+      provider_type: string;
+      until: string;
+    }
+  | { kind: "cooldown_ended"; uuid: string; provider_type: string };
+
+// Provider pool change event with sequence number
+export type ProviderPoolEvent = ProviderPoolChange & {
+  seq: number;
+  timestamp: string;
+};
+
+// Provider pool snapshot; events with seq <= snapshot seq are already applied
+export interface ProviderPoolSnapshot {
+  seq: number;
+  providers: ProviderPoolOverview[];
+  /** Credentials hidden because they cannot be decrypted or parsed */
+  unreadable_count?: number;
+}
+
 // Health check result
 export interface HealthCheckResult {
   uuid: string;
@@ -257,6 +337,10 @@ export interface UpdateCredentialRequest {
   new_api_key?: string;
   /// 新的代理 URL（可覆盖全局代理设置）
   new_proxy_url?: string;
+  /// 是否开启原生联网搜索（会产生额外计费）
+  native_web_search?: boolean;
+  /// 新的备用 base_url 列表（空数组表示清除）
+  new_fallback_base_urls?: string[];
 }
 
 export const providerPoolApi = {
@@ -267,6 +351,26 @@ export const providerPoolApi = {
     return loadOverview(options);
   },
 
+  // Get overview together with the event sequence it reflects
+  async getSnapshot(): Promise<ProviderPoolSnapshot> {
+    return safeInvoke("get_provider_pool_snapshot");
+  },
+
+  // Get events after `seq`; null means the gap is too large, refetch snapshot
+  async getEventsSince(seq: number): Promise<ProviderPoolEvent[] | null> {
+    return safeInvoke("get_provider_pool_events_since", { seq });
+  },
+
+  // Subscribe to provider pool change events
+  async listenEvents(
+    handler: (event: ProviderPoolEvent) => void,
+  ): Promise<() => void> {
+    return safeListen<ProviderPoolEvent>(PROVIDER_POOL_EVENT, (event) => {
+      invalidateProviderPoolOverviewCache();
+      handler(event.payload);
+    });
+  },
+
   // Get credentials for a specific provider type
   async getCredentials(
     providerType: PoolProviderType,
@@ -316,6 +420,16 @@ export const providerPoolApi = {
     );
   },
 
+  // 更新凭证负载均衡权重（运行时生效，持久化到凭证池）
+  async setCredentialWeight(
+    uuid: string,
+    weight: number,
+  ): Promise<ProviderCredential> {
+    return invalidateOverviewAfterMutation(
+      safeInvoke("set_provider_pool_credential_weight", { uuid, weight }),
+    );
+  },
+
   // Reset credential counters
   async resetCredential(uuid: string): Promise<void> {
     return invalidateOverviewAfterMutation(
@@ -346,6 +460,24 @@ export const providerPoolApi = {
     );
   },
 
+  // Check reachability of a credential's custom base_url endpoints
+  async checkCredentialEndpoints(
+    uuid: string,
+  ): Promise<CredentialEndpointHealth | null> {
+    return invalidateOverviewAfterMutation(
+      safeInvoke("check_provider_pool_credential_endpoints", { uuid }),
+    );
+  },
+
+  // Check reachability of all enabled credentials' custom base_url endpoints
+  async checkAllEndpoints(): Promise<
+    Record<string, CredentialEndpointHealth>
+  > {
+    return invalidateOverviewAfterMutation(
+      safeInvoke("check_provider_pool_endpoints"),
+    );
+  },
+
   // Provider-specific add methods
   async addKiroOAuth(
     credsFilePath: string,
@@ -453,6 +585,16 @@ export const providerPoolApi = {
     );
   },
 
+  // 添加测试用 Mock 凭证（确定性响应，可配置延迟与故障注入）
+  async addMock(
+    config: MockProviderConfig,
+    name?: string,
+  ): Promise<ProviderCredential> {
+    return invalidateOverviewAfterMutation(
+      safeInvoke("add_mock_credential", { config, name }),
+    );
+  },
+
   // Antigravity OAuth 登录（打开浏览器授权）
   async startAntigravityOAuthLogin(
     name?: string,
@@ -481,21 +623,6 @@ export const providerPoolApi = {
     );
   },
 
-  // Codex OAuth 登录（打开浏览器授权）
-  async startCodexOAuthLogin(name?: string): Promise<ProviderCredential> {
-    return invalidateOverviewAfterMutation(
-      safeInvoke("start_codex_oauth_login", { name }),
-    );
-  },
-
-  // 获取 Codex OAuth 授权 URL 并等待回调（不自动打开浏览器）
-  // 服务器会在后台等待回调，成功后返回凭证
-  async getCodexAuthUrlAndWait(name?: string): Promise<ProviderCredential> {
-    return invalidateOverviewAfterMutation(
-      safeInvoke("get_codex_auth_url_and_wait", { name }),
-    );
-  },
-
   // Claude OAuth 登录（打开浏览器授权）
   async startClaudeOAuthLogin(name?: string): Promise<ProviderCredential> {
     return invalidateOverviewAfterMutation(
@@ -529,13 +656,6 @@ export const providerPoolApi = {
     );
   },
 
-  // Gemini OAuth 登录（打开浏览器授权）
-  async startGeminiOAuthLogin(name?: string): Promise<ProviderCredential> {
-    return invalidateOverviewAfterMutation(
-      safeInvoke("start_gemini_oauth_login", { name }),
-    );
-  },
-
   // 获取 Gemini OAuth 授权 URL 并等待回调（不自动打开浏览器）
   // 服务器会在后台等待回调，成功后返回凭证
   async getGeminiAuthUrlAndWait(name?: string): Promise<ProviderCredential> {
@@ -626,6 +746,20 @@ export const providerPoolApi = {
     );
   },
 
+  // 从 YAML 配置批量导入凭证（进度通过 CREDENTIAL_IMPORT_PROGRESS_EVENT 事件上报）
+  async importCredentialsFromConfig(): Promise<CredentialImportReport> {
+    return invalidateOverviewAfterMutation(
+      safeInvoke("import_credentials_from_config"),
+    );
+  },
+
+  // 获取启动时旧版 config.yaml 凭证迁移报告（无旧版凭证时为 null）
+  async getLegacyCredentialMigrationReport(): Promise<
+    LegacyCredentialMigrationReport | null
+  > {
+    return safeInvoke("get_legacy_credential_migration_report");
+  },
+
   // 获取单个凭证的健康状态
   // Requirements: 4.4
   async getCredentialHealth(
@@ -640,6 +774,28 @@ export const providerPoolApi = {
     return safeInvoke("get_all_credential_health");
   },
 
+  // ============ 凭证加密 ============
+
+  // 获取凭证数据的加密迁移状态
+  async getCredentialEncryptionStatus(): Promise<CredentialEncryptionReport> {
+    return safeInvoke("get_credential_encryption_status");
+  },
+
+  // 立即加密所有明文凭证并校验
+  async migrateCredentialEncryption(): Promise<CredentialEncryptionReport> {
+    return safeInvoke("migrate_credential_encryption");
+  },
+
+  // 列出无法用当前密钥解密的凭证（主密钥丢失后）
+  async getUnreadableCredentials(): Promise<UnreadableCredential[]> {
+    return safeInvoke("get_unreadable_credentials");
+  },
+
+  // 移除无法解密的凭证，返回被移除凭证的元数据
+  async purgeUnreadableCredentials(): Promise<UnreadableCredential[]> {
+    return safeInvoke("purge_unreadable_credentials");
+  },
+
   // ============ 模型管理 ============
 
   // 获取凭证支持的模型列表（从数据库缓存）
@@ -682,6 +838,47 @@ export interface MigrationResult {
   errors: string[];
 }
 
+// 凭证批量导入进度事件
+export const CREDENTIAL_IMPORT_PROGRESS_EVENT = "credential-import:progress";
+
+export interface CredentialImportProgress {
+  total: number;
+  // 已处理数（含上次导入已完成的部分）
+  processed: number;
+  succeeded: number;
+  failed: number;
+  // 从上次中断处恢复时跳过的已完成数
+  resumed: number;
+  current?: string | null;
+  done: boolean;
+}
+
+export interface CredentialImportReport {
+  progress: CredentialImportProgress;
+  // 校验或写入失败的凭证（UUID -> 原因）
+  errors: Record<string, string>;
+}
+
+// 旧版 config.yaml 凭证迁移条目
+export interface LegacyCredentialMigrationItem {
+  // config.yaml 中的分组（kiro、openai 等）
+  section: string;
+  id: string;
+  provider_type?: string;
+  // 跳过或失败原因
+  reason?: string;
+}
+
+// 旧版 config.yaml 凭证迁移报告
+export interface LegacyCredentialMigrationReport {
+  migrated: LegacyCredentialMigrationItem[];
+  // 凭证池已存在或类型不再支持
+  skipped: LegacyCredentialMigrationItem[];
+  // 下次启动重试
+  failed: LegacyCredentialMigrationItem[];
+  finished_at: string | null;
+}
+
 // Kiro Builder ID 登录响应
 export interface KiroBuilderIdLoginResponse {
   success: boolean;
@@ -726,6 +923,30 @@ export interface KiroFingerprintInfo {
   auth_method: string;
 }
 
+// 凭证数据加密迁移状态
+export interface CredentialEncryptionReport {
+  /** 是否已注册加密实现 */
+  cipher_available: boolean;
+  total: number;
+  /** 已加密且可正常解密的行数 */
+  encrypted: number;
+  /** 本次迁移加密的行数 */
+  migrated: number;
+  /** 仍为明文的凭证 UUID */
+  plaintext: string[];
+  /** 标记为已加密但无法解密的凭证 UUID */
+  unreadable: string[];
+  /** 暂时无法校验（钥匙串锁定等）的凭证 UUID */
+  key_unavailable: string[];
+}
+
+// 无法解密的凭证（只含元数据）
+export interface UnreadableCredential {
+  uuid: string;
+  provider_type: string;
+  name: string | null;
+}
+
 // 凭证健康状态信息
 // Requirements: 4.4
 export interface CredentialHealthInfo {
@@ -0,0 +1,49 @@
+import { safeInvoke, safeListen } from "@/lib/dev-bridge";
+
+/** 快速修复建议事件名 */
+export const PROVIDER_QUICK_FIX_EVENT = "provider-quick-fix";
+
+/** 修复动作，按 `kind` 渲染对应按钮 */
+export type QuickFixAction =
+  | { kind: "refresh_token"; credential_id: string }
+  | { kind: "relogin"; credential_id: string; provider: string }
+  | { kind: "edit_credential"; credential_id: string }
+  | { kind: "add_model_alias"; model: string }
+  | { kind: "check_endpoint"; credential_id?: string; base_url?: string }
+  | { kind: "add_credential"; provider: string };
+
+export interface QuickFix {
+  title: string;
+  detail: string;
+  action: QuickFixAction;
+}
+
+/** 一次失败请求的修复建议 */
+export interface ProviderQuickFixEvent {
+  request_id: string;
+  timestamp: string;
+  provider?: string;
+  credential_id?: string;
+  model?: string;
+  /** 错误分类，如 `AuthenticationError`、`NetworkError` */
+  error_type: string;
+  /** 脱敏后的错误消息 */
+  message: string;
+  suggestions: QuickFix[];
+}
+
+/** 获取最近的快速修复建议（最新在前） */
+export async function getRecentProviderQuickFixes(
+  limit?: number,
+): Promise<ProviderQuickFixEvent[]> {
+  return safeInvoke("get_recent_provider_quick_fixes", { limit });
+}
+
+/** 监听请求失败时推送的快速修复建议 */
+export async function listenProviderQuickFix(
+  handler: (event: ProviderQuickFixEvent) => void,
+): Promise<() => void> {
+  return safeListen<ProviderQuickFixEvent>(PROVIDER_QUICK_FIX_EVENT, (event) =>
+    handler(event.payload),
+  );
+}
@@ -0,0 +1,42 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 快捷操作类型 */
+export type QuickActionKind =
+  | "switch_default_model"
+  | "toggle_provider"
+  | "start_mcp_server"
+  | "run_skill";
+
+/** 命令面板中的快捷操作 */
+export interface QuickAction {
+  /** 操作 ID（`<prefix>:<target>`），执行时原样回传 */
+  id: string;
+  kind: QuickActionKind;
+  title: string;
+  subtitle?: string;
+  keywords: string[];
+  /** 存在时表示执行前需要用户输入，值为占位文本 */
+  input_placeholder?: string;
+}
+
+/** 快捷操作执行结果 */
+export interface QuickActionOutcome {
+  id: string;
+  kind: QuickActionKind;
+  message: string;
+  data?: unknown;
+}
+
+export async function listQuickActions(
+  query?: string,
+  limit?: number,
+): Promise<QuickAction[]> {
+  return safeInvoke<QuickAction[]>("list_quick_actions", { query, limit });
+}
+
+export async function runQuickAction(
+  id: string,
+  input?: string,
+): Promise<QuickActionOutcome> {
+  return safeInvoke<QuickActionOutcome>("run_quick_action", { id, input });
+}
@@ -0,0 +1,72 @@
+import { safeInvoke, safeListen } from "@/lib/dev-bridge";
+import type { CredentialQuotaLimit } from "./appConfigTypes";
+
+export const QUOTA_REMAINING_EVENT = "quota-remaining-event";
+
+/** 凭证在滚动窗口内的用量预测 */
+export interface QuotaForecast {
+  credential_id: string;
+  window_secs: number;
+  requests: number;
+  tokens: number;
+  max_requests: number | null;
+  max_tokens: number | null;
+  /** 用量占配额的比例，未配置配额时为 null */
+  usage_ratio: number | null;
+  /** 是否达到软上限（选择凭证时会被主动避开） */
+  near_limit: boolean;
+  /** 按当前速率预计耗尽配额的时间（ISO 8601） */
+  projected_exhaustion_at: string | null;
+}
+
+/** 剩余额度来源：上游响应头（已扣除之后的本地用量）或配置的已知配额 */
+export type RemainingSource = "provider" | "configured";
+
+/** 单项剩余额度 */
+export interface RemainingMeter {
+  /** 总额度，上游未返回时为 null */
+  limit: number | null;
+  remaining: number;
+  /** 剩余比例，总额度未知时为 null */
+  remaining_ratio: number | null;
+  /** 额度重置时间（ISO 8601） */
+  reset_at: string | null;
+  source: RemainingSource;
+}
+
+/** 凭证剩余配额估算（用于燃尽进度条） */
+export interface QuotaRemaining {
+  credential_id: string;
+  requests: RemainingMeter | null;
+  tokens: RemainingMeter | null;
+  /** 最近一次上游限流快照的时间（ISO 8601） */
+  provider_observed_at: string | null;
+  updated_at: string;
+}
+
+export async function getQuotaForecasts(): Promise<QuotaForecast[]> {
+  return safeInvoke<QuotaForecast[]>("get_quota_forecasts");
+}
+
+export async function setCredentialQuotaLimit(
+  credentialId: string,
+  limit: CredentialQuotaLimit | null,
+): Promise<void> {
+  return safeInvoke<void>("set_credential_quota_limit", {
+    credentialId,
+    limit,
+  });
+}
+
+export async function getQuotaRemaining(): Promise<QuotaRemaining[]> {
+  return safeInvoke<QuotaRemaining[]>("get_quota_remaining");
+}
+
+/** 监听剩余配额变化（每次上游响应或用量记录后推送对应凭证的最新估算） */
+export async function listenQuotaRemainingEvents(
+  handler: (remaining: QuotaRemaining) => void,
+): Promise<() => void> {
+  return safeListen<QuotaRemaining>(QUOTA_REMAINING_EVENT, (event) =>
+    handler(event.payload),
+  );
+}
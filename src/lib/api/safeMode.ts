@@ -0,0 +1,13 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 安全模式报告（连续启动失败后以默认配置、禁用插件与 MCP 启动） */
+export interface SafeModeReport {
+  active: boolean;
+  consecutive_failures: number;
+  failed_stage?: string | null;
+  suspected_culprit?: string | null;
+}
+
+export async function getSafeModeReport(): Promise<SafeModeReport> {
+  return safeInvoke<SafeModeReport>("get_safe_mode_report");
+}
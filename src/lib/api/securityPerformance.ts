@@ -6,12 +6,97 @@ export interface RateLimitConfig {
   window_secs: number;
 }
 
+export interface ApiKeyRateLimitConfig {
+  enabled: boolean;
+  requests_per_minute: number;
+  burst: number;
+}
+
+export interface ApiKeyRateLimitOverride {
+  /** API Key 指纹（SHA-256 前 16 位），不含明文 Key */
+  key_fingerprint: string;
+  label?: string | null;
+  requests_per_minute: number;
+  burst: number;
+  updated_at: number;
+}
+
+export type SystemPromptGuardPosition = "append" | "prepend";
+
+export interface SystemPromptGuardPolicy {
+  id: string;
+  name?: string;
+  enabled: boolean;
+  /** 匹配的 API Key（与 tenants 均为空时匹配所有 Key） */
+  api_keys: string[];
+  /** 匹配的租户 ID */
+  tenants: string[];
+  /** 生效路由（末尾 `*` 为前缀匹配），为空时对所有支持的路由生效 */
+  routes: string[];
+  /** 豁免路由，优先于 routes */
+  exempt_routes: string[];
+  /** 护栏文本模板，支持 {{tenant}} {{model}} {{route}} {{date}} {{policy}} */
+  template: string;
+  position: SystemPromptGuardPosition;
+}
+
+export interface SystemPromptGuardConfig {
+  enabled: boolean;
+  policies: SystemPromptGuardPolicy[];
+  /** 内存中保留的注入审计条数 */
+  audit_capacity: number;
+}
+
+export interface SystemPromptGuardInjection {
+  request_id?: string | null;
+  /** 注入时间（毫秒时间戳） */
+  timestamp_ms: number;
+  route: string;
+  model?: string | null;
+  tenant_id?: string | null;
+  /** API Key 指纹，不含明文 Key */
+  api_key_fingerprint?: string | null;
+  policy_ids: string[];
+  /** 实际注入的文本 */
+  injected: string[];
+}
+
+export interface DegenerateRetryConfig {
+  enabled: boolean;
+  /** 去除空白后少于该字符数（且无工具调用）视为退化 */
+  min_content_chars: number;
+  /** 视为退化的结束原因（不区分大小写） */
+  finish_reasons: string[];
+  /** 请求的 max_tokens 不超过该值时不检测 */
+  exempt_max_tokens: number;
+}
+
+export interface DegenerateProviderStats {
+  provider: string;
+  checked: number;
+  degenerate: number;
+  retried: number;
+  recovered: number;
+  /** degenerate / checked */
+  degenerate_rate: number;
+}
+
 export interface ConversationConfig {
   trim_enabled: boolean;
   max_messages: number;
   summary_enabled: boolean;
 }
 
+export type ContextTrimStrategy = "drop_oldest" | "drop_middle" | "summarize_oldest";
+
+export interface ContextTrimConfig {
+  enabled: boolean;
+  strategy: ContextTrimStrategy;
+  reserve_output_tokens: number;
+  /** 按路由覆盖策略，键为请求路径（如 `/v1/messages`） */
+  routes?: Record<string, ContextTrimStrategy>;
+}
+
 export interface HintRouteEntry {
   hint: string;
   provider: string;
@@ -22,6 +107,28 @@ export interface PairingConfig {
   enabled: boolean;
 }
 
+export interface CpuTaskKindStats {
+  kind: "transcription" | "json_transform" | "token_count" | "other";
+  completed: number;
+  panicked: number;
+  avg_queue_ms: number;
+  avg_run_ms: number;
+  max_run_ms: number;
+}
+
+export interface CpuPoolStats {
+  workers: number;
+  busy_workers: number;
+  queued_high: number;
+  queued_normal: number;
+  queued_low: number;
+  completed: number;
+  panicked: number;
+  /** 启动以来的平均利用率（0-1） */
+  utilization: number;
+  kinds: CpuTaskKindStats[];
+}
+
 export async function getRateLimitConfig(): Promise<RateLimitConfig> {
   return await safeInvoke("get_rate_limit_config");
 }
@@ -30,6 +137,86 @@ export async function updateRateLimitConfig(config: RateLimitConfig): Promise<vo
   return await safeInvoke("update_rate_limit_config", { config });
 }
 
+export async function getApiKeyRateLimitConfig(): Promise<ApiKeyRateLimitConfig> {
+  return await safeInvoke("get_api_key_rate_limit_config");
+}
+
+export async function updateApiKeyRateLimitConfig(
+  config: ApiKeyRateLimitConfig,
+): Promise<void> {
+  return await safeInvoke("update_api_key_rate_limit_config", { config });
+}
+
+export async function listApiKeyRateLimitOverrides(): Promise<
+  ApiKeyRateLimitOverride[]
+> {
+  return await safeInvoke("list_api_key_rate_limit_overrides");
+}
+
+export async function setApiKeyRateLimitOverride(
+  apiKey: string,
+  requestsPerMinute: number,
+  burst: number,
+  label?: string,
+): Promise<ApiKeyRateLimitOverride> {
+  return await safeInvoke("set_api_key_rate_limit_override", {
+    apiKey,
+    label,
+    requestsPerMinute,
+    burst,
+  });
+}
+
+export async function deleteApiKeyRateLimitOverride(
+  keyFingerprint: string,
+): Promise<boolean> {
+  return await safeInvoke("delete_api_key_rate_limit_override", {
+    keyFingerprint,
+  });
+}
+
+export async function getSystemPromptGuardConfig(): Promise<
+  SystemPromptGuardConfig
+> {
+  return await safeInvoke("get_system_prompt_guard_config");
+}
+
+export async function updateSystemPromptGuardConfig(
+  config: SystemPromptGuardConfig,
+): Promise<void> {
+  return await safeInvoke("update_system_prompt_guard_config", { config });
+}
+
+export async function getSystemPromptGuardAudit(
+  limit?: number,
+): Promise<SystemPromptGuardInjection[]> {
+  return await safeInvoke("get_system_prompt_guard_audit", { limit });
+}
+
+export async function clearSystemPromptGuardAudit(): Promise<void> {
+  return await safeInvoke("clear_system_prompt_guard_audit");
+}
+
+export async function getDegenerateRetryConfig(): Promise<DegenerateRetryConfig> {
+  return await safeInvoke("get_degenerate_retry_config");
+}
+
+export async function updateDegenerateRetryConfig(
+  config: DegenerateRetryConfig,
+): Promise<void> {
+  return await safeInvoke("update_degenerate_retry_config", { config });
+}
+
+export async function getDegenerateResponseStats(): Promise<
+  DegenerateProviderStats[]
+> {
+  return await safeInvoke("get_degenerate_response_stats");
+}
+
+export async function resetDegenerateResponseStats(): Promise<void> {
+  return await safeInvoke("reset_degenerate_response_stats");
+}
+
 export async function getConversationConfig(): Promise<ConversationConfig> {
   return await safeInvoke("get_conversation_config");
 }
@@ -38,6 +225,14 @@ export async function updateConversationConfig(config: ConversationConfig): Prom
   return await safeInvoke("update_conversation_config", { config });
 }
 
+export async function getContextTrimConfig(): Promise<ContextTrimConfig> {
+  return await safeInvoke("get_context_trim_config");
+}
+
+export async function updateContextTrimConfig(config: ContextTrimConfig): Promise<void> {
+  return await safeInvoke("update_context_trim_config", { config });
+}
+
 export async function getHintRoutes(): Promise<HintRouteEntry[]> {
   return await safeInvoke("get_hint_routes");
 }
@@ -53,3 +248,7 @@ export async function getPairingConfig(): Promise<PairingConfig> {
 export async function updatePairingConfig(config: PairingConfig): Promise<void> {
   return await safeInvoke("update_pairing_config", { config });
 }
+
+export async function getCpuPoolStats(): Promise<CpuPoolStats> {
+  return await safeInvoke("get_cpu_pool_stats");
+}
@@ -0,0 +1,31 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+export type SelfCheckStatus = "pass" | "warn" | "fail" | "skipped";
+
+/** 检查项标识 */
+export type SelfCheckId =
+  | "port"
+  | "database"
+  | "keychain"
+  | "credentials"
+  | "mcp_runtime"
+  | "model_files";
+
+export interface SelfCheckItem {
+  id: SelfCheckId;
+  status: SelfCheckStatus;
+  message: string;
+  /** 修复提示 */
+  hint?: string;
+}
+
+/** 首次运行自检报告 */
+export interface SelfCheckReport {
+  /** 没有 fail 项 */
+  passed: boolean;
+  items: SelfCheckItem[];
+}
+
+export async function runSelfCheck(): Promise<SelfCheckReport> {
+  return safeInvoke<SelfCheckReport>("run_self_check");
+}
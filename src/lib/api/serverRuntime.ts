@@ -33,6 +33,8 @@ export interface ResponseCacheConfig {
   max_entries: number;
   max_body_bytes: number;
   cacheable_status_codes: number[];
+  mode: "exact" | "semantic";
+  routes: Record<string, boolean>;
 }
 
 export interface ResponseCacheStats {
@@ -80,10 +82,20 @@ export interface TelemetrySummary {
   total_tokens: number;
 }
 
+export interface ResponseCacheRouteStats {
+  route: string;
+  hits: number;
+  misses: number;
+  evictions: number;
+  hit_rate_percent: number;
+}
+
 export interface ResponseCacheDiagnostics {
   config: ResponseCacheConfig;
   stats: ResponseCacheStats;
   hit_rate_percent: number;
+  /** 按路由的命中统计 */
+  routes: ResponseCacheRouteStats[];
 }
 
 export interface RequestDedupConfig {
@@ -196,8 +208,12 @@ export async function getLogStorageDiagnostics(): Promise<LogStorageDiagnostics>
   return safeInvoke("get_log_storage_diagnostics");
 }
 
-export async function exportSupportBundle(): Promise<SupportBundleExportResult> {
-  return safeInvoke("export_support_bundle");
+export async function exportSupportBundle(
+  snapshotName?: string,
+): Promise<SupportBundleExportResult> {
+  return safeInvoke("export_support_bundle", {
+    snapshotName: snapshotName ?? null,
+  });
 }
 
 export async function getWindowsStartupDiagnostics(): Promise<WindowsStartupDiagnostics> {
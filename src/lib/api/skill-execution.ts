@@ -71,6 +71,33 @@ export interface SkillDetailInfo extends ExecutableSkillInfo {
   when_to_use?: string;
 }
 
+/**
+ * 步骤用量
+ *
+ * 单个步骤的模型、token、耗时与估算费用
+ */
+export interface StepUsage {
+  /** 执行该步骤的模型 */
+  model?: string;
+  input_tokens: number;
+  output_tokens: number;
+  /** 耗时（毫秒，包含模型候选切换后的重试） */
+  latency_ms: number;
+  /** 估算费用（USD），模型没有定价时缺省 */
+  cost_usd?: number;
+}
+
+/**
+ * 执行用量合计
+ */
+export interface ExecutionUsage {
+  input_tokens: number;
+  output_tokens: number;
+  latency_ms: number;
+  /** 有定价步骤的费用之和 */
+  cost_usd?: number;
+}
+
 /**
  * 步骤执行结果
  *
@@ -87,6 +114,12 @@ export interface StepResult {
   output?: string;
   /** 错误信息 */
   error?: string;
+  /** 执行该步骤的 Provider（模型候选链切换时可能与首选不同） */
+  provider?: string;
+  /** 执行该步骤的模型 */
+  model?: string;
+  /** 步骤用量（未调用模型的步骤缺省） */
+  usage?: StepUsage;
 }
 
 /**
@@ -103,6 +136,8 @@ export interface SkillExecutionResult {
   error?: string;
   /** 已完成的步骤结果 */
   steps_completed: StepResult[];
+  /** 所有步骤的用量合计 */
+  usage?: ExecutionUsage;
 }
 
 /**
@@ -161,6 +196,8 @@ export interface StepCompletePayload {
   step_id: string;
   /** 输出内容 */
   output: string;
+  /** 步骤用量 */
+  usage: StepUsage;
 }
 
 /**
@@ -195,6 +232,8 @@ export interface ExecutionCompletePayload {
   output?: string;
   /** 错误信息（失败时） */
   error?: string;
+  /** 所有步骤的用量合计 */
+  usage: ExecutionUsage;
 }
 
 // ============================================================================
@@ -53,6 +53,8 @@ export interface Skill {
   repoOwner?: string;
   repoName?: string;
   repoBranch?: string;
+  /** 来源扩展注册表 ID */
+  registryId?: string;
   license?: string;
   metadata?: Record<string, string>;
   allowedTools?: string[];
@@ -190,3 +190,151 @@ export async function getTokenStatsByDay(
 ): Promise<PeriodTokenStats[]> {
   return safeInvoke("get_token_stats_by_day", { days });
 }
+
+// ========== 请求追踪采样 API ==========
+
+export type TraceSampleReason = "error" | "slow" | "baseline";
+
+export interface TraceEvent {
+  offset_ms: number;
+  stage: string;
+  message: string;
+  data?: unknown;
+}
+
+export interface SampledTrace {
+  request_id: string;
+  timestamp: string;
+  provider?: string;
+  model: string;
+  credential_id?: string;
+  is_stream: boolean;
+  status: RequestStatus;
+  duration_ms: number;
+  retry_count: number;
+  reason: TraceSampleReason;
+  error_message?: string;
+  events: TraceEvent[];
+  dropped_events: number;
+}
+
+export interface TraceQuery {
+  reason?: TraceSampleReason;
+  model?: string;
+  limit?: number;
+}
+
+export interface TraceSamplingSettings {
+  enabled: boolean;
+  latency_threshold_ms: number;
+  error_sample_rate: number;
+  slow_sample_rate: number;
+  success_sample_rate: number;
+  max_traces: number;
+}
+
+export async function getRequestTraces(
+  query?: TraceQuery,
+): Promise<SampledTrace[]> {
+  return safeInvoke("get_request_traces", { query });
+}
+
+export async function getRequestTrace(
+  requestId: string,
+): Promise<SampledTrace | null> {
+  return safeInvoke("get_request_trace", { requestId });
+}
+
+/** 单请求脱敏分享包 */
+export interface RequestShareBundle {
+  format_version: number;
+  generated_at: string;
+  app_version: string;
+  request_id: string;
+  timestamp: string;
+  provider?: string;
+  model: string;
+  is_stream: boolean;
+  endpoint?: string;
+  /** 客户端类型 -> 路由 Provider -> 实际 Provider */
+  converter_path: string[];
+  response: {
+    status: RequestStatus;
+    http_status?: number;
+    duration_ms: number;
+    retry_count: number;
+    error_message?: string;
+    input_tokens?: number;
+    output_tokens?: number;
+  };
+  /** 需开启 logging.include_request_body 且请求被采样 */
+  request_body?: unknown;
+  trace: TraceEvent[];
+}
+
+export async function exportRequestShareBundle(
+  requestId: string,
+): Promise<RequestShareBundle> {
+  return safeInvoke("export_request_share_bundle", { requestId });
+}
+
+export async function clearRequestTraces(): Promise<void> {
+  return safeInvoke("clear_request_traces");
+}
+
+export async function getTraceSamplingSettings(): Promise<TraceSamplingSettings> {
+  return safeInvoke("get_trace_sampling_settings");
+}
+
+export async function updateTraceSamplingSettings(
+  settings: TraceSamplingSettings,
+): Promise<void> {
+  return safeInvoke("update_trace_sampling_settings", { settings });
+}
+
+/** 匿名用量统计配置（默认关闭） */
+export interface UsageAnalyticsSettings {
+  enabled: boolean;
+  /** 导出摘要的隐私预算（越小噪声越大） */
+  epsilon: number;
+}
+
+/** 本地累计的原始用量计数 */
+export interface UsageCounters {
+  since: string | null;
+  requests_by_provider: Record<string, number>;
+  features: Record<string, number>;
+}
+
+/** 导出的用量摘要（计数已加噪） */
+export interface UsageAnalyticsSummary extends UsageCounters {
+  generated_at: string;
+  epsilon: number;
+}
+
+export async function getUsageAnalyticsSettings(): Promise<UsageAnalyticsSettings> {
+  return safeInvoke("get_usage_analytics_settings");
+}
+
+export async function updateUsageAnalyticsSettings(
+  settings: UsageAnalyticsSettings,
+): Promise<void> {
+  return safeInvoke("update_usage_analytics_settings", { settings });
+}
+
+/** 记录功能使用（未开启统计时忽略） */
+export async function recordUsageFeature(feature: string): Promise<void> {
+  return safeInvoke("record_usage_feature", { feature });
+}
+
+export async function getUsageAnalyticsCounters(): Promise<UsageCounters> {
+  return safeInvoke("get_usage_analytics_counters");
+}
+
+export async function exportUsageAnalyticsSummary(): Promise<UsageAnalyticsSummary> {
+  return safeInvoke("export_usage_analytics_summary");
+}
+
+export async function clearUsageAnalytics(): Promise<void> {
+  return safeInvoke("clear_usage_analytics");
+}
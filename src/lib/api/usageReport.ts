@@ -0,0 +1,68 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+export type UsageReportPeriod = "daily" | "weekly";
+
+export type UsageReportFormat = "markdown" | "csv";
+
+export interface UsageReportSettings {
+  enabled: boolean;
+  period: UsageReportPeriod;
+  formats: UsageReportFormat[];
+  /** 输出目录，为空时写入应用数据目录下的 reports */
+  output_dir?: string | null;
+  webhook_url?: string | null;
+}
+
+export interface UsageReportRow {
+  /** 维度取值，凭证/租户为空时为空字符串 */
+  key: string;
+  requests: number;
+  input_tokens: number;
+  output_tokens: number;
+  cost_usd: number;
+  /** 错误率（0~1），来自请求审计日志，无数据时为空 */
+  error_rate?: number | null;
+}
+
+export interface UsageReport {
+  report_key: string;
+  range: {
+    period: UsageReportPeriod;
+    /** UTC YYYY-MM-DD（含） */
+    from_day: string;
+    to_day: string;
+  };
+  generated_at: number;
+  total: UsageReportRow;
+  by_provider: UsageReportRow[];
+  by_model: UsageReportRow[];
+  by_credential: UsageReportRow[];
+  by_tenant: UsageReportRow[];
+}
+
+export interface UsageReportOutcome {
+  report: UsageReport;
+  files: string[];
+  /** 未配置 Webhook 时为空 */
+  webhook_delivered?: boolean | null;
+  webhook_error?: string | null;
+}
+
+export async function getUsageReportSettings(): Promise<UsageReportSettings> {
+  return safeInvoke<UsageReportSettings>("get_usage_report_settings");
+}
+
+export async function updateUsageReportSettings(
+  settings: UsageReportSettings,
+): Promise<void> {
+  return safeInvoke<void>("update_usage_report_settings", { settings });
+}
+
+/** 立即生成当前周期（截至今天）的报告，未指定周期时使用配置中的周期 */
+export async function generateUsageReportNow(
+  period?: UsageReportPeriod,
+): Promise<UsageReportOutcome> {
+  return safeInvoke<UsageReportOutcome>("generate_usage_report_now", {
+    period,
+  });
+}
@@ -0,0 +1,26 @@
+import { safeInvoke } from "@/lib/dev-bridge";
+
+/** 虚拟模型：映射到 Provider + 模型 + 参数预设的自定义模型名 */
+export interface VirtualModel {
+  name: string;
+  provider?: string | null;
+  model: string;
+  temperature?: number | null;
+  systemPrefix?: string | null;
+  description?: string | null;
+  enabled: boolean;
+  createdAt?: number;
+  updatedAt?: number;
+}
+
+export async function listVirtualModels(): Promise<VirtualModel[]> {
+  return safeInvoke<VirtualModel[]>("list_virtual_models");
+}
+
+export async function saveVirtualModel(model: VirtualModel): Promise<void> {
+  return safeInvoke<void>("save_virtual_model", { model });
+}
+
+export async function deleteVirtualModel(name: string): Promise<boolean> {
+  return safeInvoke<boolean>("delete_virtual_model", { name });
+}
@@ -1,6 +1,6 @@
 import { beforeEach, describe, expect, it, vi } from "vitest";
 import { safeInvoke } from "@/lib/dev-bridge";
-import { getAvailableVoices, testTts } from "./voiceTools";
+import { getAvailableVoices, speakText, testTts } from "./voiceTools";
 
 vi.mock("@/lib/dev-bridge", () => ({
   safeInvoke: vi.fn(),
@@ -23,4 +23,11 @@ describe("voiceTools API", () => {
       expect.objectContaining({ id: "alloy" }),
     ]);
   });
+
+  it("应代理朗读命令", async () => {
+    vi.mocked(safeInvoke).mockResolvedValueOnce(undefined);
+
+    await expect(speakText("你好")).resolves.toBeUndefined();
+    expect(safeInvoke).toHaveBeenCalledWith("speak_text", { text: "你好" });
+  });
 });
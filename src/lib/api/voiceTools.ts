@@ -24,3 +24,12 @@ export async function getAvailableVoices(
 ): Promise<VoiceOption[]> {
   return safeInvoke("get_available_voices", { service });
 }
+
+/**
+ * 朗读文本（如 Agent 回复）
+ *
+ * 使用语音设置中的 TTS 服务合成并播放，播放完成后返回。
+ */
+export async function speakText(text: string): Promise<void> {
+  return safeInvoke("speak_text", { text });
+}
@@ -266,6 +266,8 @@ describe("buildCrashDiagnosticPayload", () => {
             max_entries: 100,
             max_body_bytes: 1024,
             cacheable_status_codes: [200],
+            mode: "exact",
+            routes: {},
           },
           stats: {
             size: 0,
@@ -274,6 +276,7 @@ describe("buildCrashDiagnosticPayload", () => {
             evictions: 0,
           },
           hit_rate_percent: 0,
+          routes: [],
         },
         request_dedup: {
           config: {
@@ -0,0 +1,30 @@
+/**
+ * @file 插件事件通道
+ * @description 插件 UI 专属事件通道名与事件审计查询
+ * @module lib/plugin-ui/eventChannel
+ */
+
+import { safeInvoke } from "@/lib/dev-bridge";
+import type { PluginEventAudit, PluginId } from "./types";
+
+/** 插件专属事件通道前缀，需与 Rust 端 PLUGIN_EVENT_CHANNEL_PREFIX 保持一致 */
+export const PLUGIN_EVENT_CHANNEL_PREFIX = "plugin-event:";
+
+/**
+ * 插件专属事件通道名
+ * Tauri 事件名只允许字母数字与 `-` `/` `:` `_`，其余字符替换为 `_`
+ */
+export function pluginEventChannel(pluginId: PluginId): string {
+  return `${PLUGIN_EVENT_CHANNEL_PREFIX}${pluginId.replace(/[^A-Za-z0-9\-/:_]/g, "_")}`;
+}
+
+/** 获取被拒绝的插件事件投递记录 */
+export async function getPluginEventAudit(
+  pluginId?: PluginId,
+  limit?: number,
+): Promise<PluginEventAudit> {
+  return safeInvoke<PluginEventAudit>("get_plugin_event_audit", {
+    pluginId,
+    limit,
+  });
+}
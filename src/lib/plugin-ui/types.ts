@@ -449,6 +449,28 @@ export interface PluginTaskEventPayload {
   error?: PluginTaskError;
 }
 
+/** 插件专属通道上的事件信封 */
+export interface PluginScopedEvent {
+  pluginId: PluginId;
+  /** 原始事件名称（如 plugin-ui-message、plugin-task-event 或清单声明的主题） */
+  event: string;
+  payload: unknown;
+}
+
+/** 被拒绝的插件事件投递记录 */
+export interface DeniedEventDelivery {
+  plugin_id: string;
+  event: string;
+  reason: string;
+  timestamp: string;
+}
+
+/** 插件事件审计快照 */
+export interface PluginEventAudit {
+  total_denied: number;
+  recent: DeniedEventDelivery[];
+}
+
 /** 插件任务记录 */
 export interface PluginTaskRecord {
   taskId: string;
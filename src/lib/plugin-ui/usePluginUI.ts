@@ -9,8 +9,10 @@ import { safeInvoke, safeListen } from "@/lib/dev-bridge";
 import type { UnlistenFn } from "@tauri-apps/api/event";
 import { surfaceManager, SurfaceManager } from "./SurfaceManager";
 import { initPluginUI } from "./index";
+import { pluginEventChannel } from "./eventChannel";
 import type {
   PluginId,
+  PluginScopedEvent,
   PluginTaskEventPayload,
   SurfaceId,
   SurfaceState,
@@ -77,55 +79,34 @@ export function usePluginUI(options: UsePluginUIOptions): UsePluginUIResult {
     return unsubscribe;
   }, [pluginId, manager]);
 
-  // 监听插件任务事件
+  // 监听插件专属事件通道（Rust 端仅投递清单中声明的事件）
   useEffect(() => {
     let unlisten: UnlistenFn | null = null;
 
-    const setupTaskListener = async () => {
+    const setupListener = async () => {
       try {
-        unlisten = await safeListen<PluginTaskEventPayload>(
-          "plugin-task-event",
+        unlisten = await safeListen<PluginScopedEvent>(
+          pluginEventChannel(pluginId),
           (event) => {
-            if (event.payload.pluginId !== pluginId) {
+            const envelope = event.payload;
+            if (envelope.pluginId !== pluginId) {
               return;
             }
-            setTaskEvents((prev) => {
-              const next = [...prev, event.payload];
-              if (next.length > 100) {
-                return next.slice(next.length - 100);
-              }
-              return next;
-            });
+            if (envelope.event === "plugin-ui-message") {
+              const payload = envelope.payload as { message: ServerMessage };
+              manager.processMessage(pluginId, payload.message);
+            } else if (envelope.event === "plugin-task-event") {
+              const payload = envelope.payload as PluginTaskEventPayload;
+              setTaskEvents((prev) => {
+                const next = [...prev, payload];
+                if (next.length > 100) {
+                  return next.slice(next.length - 100);
+                }
+                return next;
+              });
+            }
           },
         );
-      } catch (err) {
-        console.error("[usePluginUI] 监听任务事件失败:", err);
-      }
-    };
-
-    setupTaskListener();
-
-    return () => {
-      if (unlisten) {
-        unlisten();
-      }
-    };
-  }, [pluginId]);
-
-  // 监听来自 Rust 的 UI 消息
-  useEffect(() => {
-    let unlisten: UnlistenFn | null = null;
-
-    const setupListener = async () => {
-      try {
-        unlisten = await safeListen<{
-          pluginId: string;
-          message: ServerMessage;
-        }>("plugin-ui-message", (event) => {
-          if (event.payload.pluginId === pluginId) {
-            manager.processMessage(pluginId, event.payload.message);
-          }
-        });
       } catch (err) {
         console.error("[usePluginUI] 监听事件失败:", err);
       }
@@ -623,6 +623,8 @@ const defaultMocks: Record<string, any> = {
         max_entries: 200,
         max_body_bytes: 1048576,
         cacheable_status_codes: [200],
+        mode: "exact",
+        routes: {},
       },
       tls: {
         enable: false,
@@ -665,6 +667,8 @@ const defaultMocks: Record<string, any> = {
       switch_project: true,
       switch_preview_model: false,
       cooldown_seconds: 60,
+      soft_limit_ratio: 0.9,
+      credential_limits: {},
     },
     ampcode: {
       upstream_url: null,
@@ -982,6 +986,8 @@ const defaultMocks: Record<string, any> = {
         max_entries: 200,
         max_body_bytes: 1048576,
         cacheable_status_codes: [200],
+        mode: "exact",
+        routes: {},
       },
       stats: {
         size: 0,
@@ -990,6 +996,7 @@ const defaultMocks: Record<string, any> = {
         evictions: 0,
       },
       hit_rate_percent: 0,
+      routes: [],
     },
     request_dedup: {
       config: {
@@ -1650,8 +1657,12 @@ const defaultMocks: Record<string, any> = {
   }),
   agent_runtime_submit_turn: () => ({}),
   agent_runtime_interrupt_turn: () => true,
+  agent_runtime_regenerate_turn: () => 2,
+  agent_runtime_list_turn_attempts: () => [],
+  agent_runtime_switch_turn_attempt: () => true,
   agent_runtime_create_session: () => "mock-aster-session",
   agent_runtime_list_sessions: () => [],
+  agent_runtime_list_sessions_page: () => ({ items: [], next_cursor: null }),
   agent_runtime_get_session: () => ({ id: "mock", messages: [] }),
   agent_runtime_get_tool_inventory: () => ({
     request: {
@@ -1793,10 +1804,25 @@ const defaultMocks: Record<string, any> = {
   unload_plugin: () => ({ success: true }),
   uninstall_plugin: () => ({ success: true }),
   launch_plugin_ui: () => ({}),
+  get_plugin_event_audit: () => ({ total_denied: 0, recent: [] }),
   list_plugin_tasks: () => [],
   get_plugin_task: () => null,
   cancel_plugin_task: () => true,
   get_plugin_queue_stats: () => [],
+  plugin_storage_get: () => null,
+  plugin_storage_set: () => undefined,
+  plugin_storage_delete: () => false,
+  plugin_storage_keys: () => [],
+  plugin_storage_usage: () => ({
+    keys: 0,
+    used_bytes: 0,
+    quota_bytes: 10 * 1024 * 1024,
+  }),
+  plugin_crypto_encrypt: (args: any) =>
+    `enc3:v1:${btoa(args?.plaintext ?? "")}`,
+  plugin_crypto_decrypt: (args: any) =>
+    atob(String(args?.ciphertext ?? "").replace(/^enc3:v\d+:/, "")),
+  plugin_crypto_rotate_key: () => ({ version: 2, rewrapped: 0, failed: [] }),
 
   // 凭证池相关
   get_relay_providers: () => [],
@@ -1804,15 +1830,24 @@ const defaultMocks: Record<string, any> = {
   get_system_provider_catalog: () => [],
   get_pool_overview: () => [],
   get_provider_pool_overview: () => [],
+  get_provider_pool_snapshot: () => ({
+    seq: 0,
+    providers: [],
+    unreadable_count: 0,
+  }),
+  get_provider_pool_events_since: () => [],
   get_provider_pool_credentials: () => [],
   add_provider_pool_credential: () => ({ success: true }),
   update_provider_pool_credential: () => ({ success: true }),
   delete_provider_pool_credential: () => ({ success: true }),
   toggle_provider_pool_credential: () => ({ success: true }),
+  set_provider_pool_credential_weight: () => ({ success: true }),
   reset_provider_pool_credential: () => ({ success: true }),
   reset_provider_pool_health: () => ({ success: true }),
   check_provider_pool_credential_health: () => ({ healthy: false }),
   check_provider_pool_type_health: () => ({ healthy: false }),
+  check_provider_pool_credential_endpoints: () => null,
+  check_provider_pool_endpoints: () => ({}),
 
   // API Key Provider 相关
   get_api_key_providers: () => [],
@@ -2046,6 +2081,7 @@ const defaultMocks: Record<string, any> = {
   add_antigravity_oauth_credential: () => ({ success: true }),
   add_codex_oauth_credential: () => ({ success: true }),
   add_claude_oauth_credential: () => ({ success: true }),
+  add_mock_credential: () => ({ success: true }),
   add_iflow_oauth_credential: () => ({ success: true }),
   add_iflow_cookie_credential: () => ({ success: true }),
   start_kiro_builder_id_login: () => ({ success: true }),
@@ -2059,8 +2095,40 @@ const defaultMocks: Record<string, any> = {
   refresh_pool_credential_token: () => ({ success: true }),
   get_pool_credential_oauth_status: () => ({ status: "unknown" }),
   migrate_private_config_to_pool: () => ({ success: true }),
+  import_credentials_from_config: () => ({
+    progress: {
+      total: 0,
+      processed: 0,
+      succeeded: 0,
+      failed: 0,
+      resumed: 0,
+      done: true,
+    },
+    errors: {},
+  }),
+  get_legacy_credential_migration_report: () => null,
   get_credential_health: () => ({ healthy: false }),
   get_all_credential_health: () => [],
+  get_credential_encryption_status: () => ({
+    cipher_available: true,
+    total: 0,
+    encrypted: 0,
+    migrated: 0,
+    plaintext: [],
+    unreadable: [],
+    key_unavailable: [],
+  }),
+  migrate_credential_encryption: () => ({
+    cipher_available: true,
+    total: 0,
+    encrypted: 0,
+    migrated: 0,
+    plaintext: [],
+    unreadable: [],
+    key_unavailable: [],
+  }),
+  get_unreadable_credentials: () => [],
+  purge_unreadable_credentials: () => [],
   get_kiro_credential_fingerprint: () => ({ fingerprint: "" }),
   switch_kiro_to_local: () => ({ success: true }),
 
@@ -2077,6 +2145,18 @@ const defaultMocks: Record<string, any> = {
   save_oauth_credential: () => ({ success: true }),
   get_oauth_credentials: () => [],
   get_all_oauth_credentials: () => [],
+  oauth_start_flow: () => ({
+    flow_id: "mock-flow",
+    provider: "codex",
+    kind: "authorization_code",
+    auth_url: "https://example.com/oauth",
+    user_code: null,
+    verification_uri: null,
+    expires_in: 600,
+    interval: 2,
+  }),
+  oauth_poll_flow: () => ({ status: "pending", credential: null, error: null }),
+  oauth_cancel_flow: () => true,
   reload_oauth_credentials: () => ({ success: true }),
   refresh_oauth_token: () => ({ success: true }),
   get_oauth_env_variables: () => [],
@@ -2136,8 +2216,20 @@ const defaultMocks: Record<string, any> = {
   mcp_list_servers_with_status: () => [],
   mcp_start_server: () => ({ success: true }),
   mcp_stop_server: () => ({ success: true }),
+  mcp_tail_server_log: () => [],
+  mcp_diagnose_server_environment: (args: any) => ({
+    status: "ok",
+    command: "npx",
+    resolved_path: "/usr/local/bin/npx",
+    runtime: "node",
+    runtime_path: "/usr/local/bin/node",
+    runtime_version: "v20.11.0",
+    message: `${args?.name ?? "server"} 执行环境正常`,
+    search_paths: ["/usr/local/bin", "/usr/bin"],
+  }),
   mcp_list_tools: () => [],
   mcp_list_tools_for_context: () => [],
+  mcp_resync_tools: () => [],
   mcp_search_tools: () => [],
   mcp_call_tool: () => ({ content: [], is_error: false }),
   mcp_call_tool_with_caller: () => ({ content: [], is_error: false }),
@@ -2145,6 +2237,8 @@ const defaultMocks: Record<string, any> = {
   mcp_get_prompt: () => ({ description: "", messages: [] }),
   mcp_list_resources: () => [],
   mcp_read_resource: () => ({}),
+  mcp_respond_elicitation: () => null,
+  mcp_list_pending_elicitations: () => [],
 
   // Switch Provider 相关
   get_switch_providers: () => [],
@@ -2182,6 +2276,156 @@ const defaultMocks: Record<string, any> = {
   get_request_log_detail: () => ({ log: null }),
   clear_request_logs: () => ({ success: true }),
   report_frontend_crash: () => ({ success: true }),
+  get_safe_mode_report: () => ({
+    active: false,
+    consecutive_failures: 0,
+    failed_stage: null,
+    suspected_culprit: null,
+  }),
+  get_database_recovery_report: () => ({
+    corrupted: false,
+    issues: [],
+    action: "none",
+    quarantined_path: null,
+    restored_from: null,
+    salvaged_tables: [],
+    lost_tables: [],
+    message: null,
+  }),
+  run_self_check: () => ({
+    passed: true,
+    items: [
+      { id: "port", status: "pass", message: "端口 8999 可用" },
+      { id: "database", status: "pass", message: "数据库可写" },
+      { id: "keychain", status: "pass", message: "系统钥匙串可访问" },
+      {
+        id: "credentials",
+        status: "pass",
+        message: "1 个健康凭证，0 个已配置 API Key 的 Provider",
+      },
+      { id: "mcp_runtime", status: "skipped", message: "未启用 MCP 服务器" },
+      {
+        id: "model_files",
+        status: "skipped",
+        message: "未启用本地语音识别模型",
+      },
+    ],
+  }),
+  create_pipeline_snapshot: (args: any) => ({
+    name: args?.name ?? "snapshot",
+    created_at: new Date().toISOString(),
+    app_version: "mock",
+    note: args?.note ?? null,
+    credential_count: 0,
+    plugin_count: 0,
+  }),
+  list_pipeline_snapshots: () => [],
+  get_pipeline_snapshot: (args: any) => ({
+    name: args?.name ?? "snapshot",
+    created_at: new Date().toISOString(),
+    app_version: "mock",
+    config: {},
+    credentials: {},
+    plugins: {},
+  }),
+  delete_pipeline_snapshot: () => ({}),
+  diff_pipeline_snapshots: (args: any) => ({
+    base: args?.base ?? "",
+    target: args?.target ?? "",
+    changes: [],
+  }),
+  list_feature_flags: () => [
+    {
+      key: "request_hedging",
+      description: "慢请求超时后向备用凭证发送对冲请求，取先返回的结果",
+      enabled: false,
+      default_enabled: false,
+      overridden: false,
+    },
+    {
+      key: "wasm_plugins",
+      description: "允许加载 .wasm 插件（沙箱运行，无需原生二进制）",
+      enabled: false,
+      default_enabled: false,
+      overridden: false,
+    },
+    {
+      key: "ensemble_mode",
+      description: "同一请求分发到多个模型并合并结果",
+      enabled: false,
+      default_enabled: false,
+      overridden: false,
+    },
+  ],
+  set_feature_flag: (args: any) => ({
+    key: args?.key ?? "",
+    description: "",
+    enabled: Boolean(args?.enabled),
+    default_enabled: false,
+    overridden: Boolean(args?.enabled),
+  }),
+  list_virtual_models: () => [],
+  save_virtual_model: () => ({}),
+  delete_virtual_model: () => true,
+  get_cost_spend: () => [],
+  get_cost_budget_settings: () => ({
+    enabled: false,
+    budgets: [],
+    alert_ratio: 0.8,
+  }),
+  update_cost_budget_settings: () => ({}),
+  get_usage_report_settings: () => ({
+    enabled: false,
+    period: "daily",
+    formats: ["markdown"],
+  }),
+  update_usage_report_settings: () => ({}),
+  generate_usage_report_now: () => ({
+    report: null,
+    files: [],
+    webhook_delivered: null,
+    webhook_error: null,
+  }),
+  search_audit_log: () => ({ items: [], next_cursor: null }),
+  export_audit_log: () => "",
+  clear_audit_log: () => 0,
+  get_audit_log_settings: () => ({
+    enabled: false,
+    include_request_body: true,
+    max_body_chars: 4096,
+    retention_days: 30,
+  }),
+  update_audit_log_settings: () => ({}),
+  get_adaptive_selection_settings: () => ({
+    enabled: false,
+    exploration_rate: 0.1,
+    latency_weight: 0.3,
+    reference_latency_ms: 5000,
+    decay: 0.98,
+  }),
+  update_adaptive_selection_settings: () => ({}),
+  get_adaptive_selection_stats: () => [],
+  reset_adaptive_selection: () => 0,
+  get_recent_provider_quick_fixes: () => [],
+  get_quota_forecasts: () => [],
+  get_quota_remaining: () => [],
+  set_credential_quota_limit: () => ({}),
+  list_quick_actions: () => [],
+  run_quick_action: (args: any) => ({
+    id: args?.id ?? "",
+    kind: "switch_default_model",
+    message: "",
+  }),
+  get_background_mode_status: () => ({
+    background_mode: false,
+    autostart_enabled: false,
+    launched_in_background: false,
+  }),
+  set_background_mode: (args: any) => ({
+    background_mode: Boolean(args?.enabled),
+    autostart_enabled: Boolean(args?.autostart),
+    launched_in_background: false,
+  }),
   get_stats_summary: () => ({ summary: {} }),
   get_stats_by_provider: () => ({ stats: [] }),
   get_stats_by_model: () => ({ stats: [] }),
@@ -2189,6 +2433,115 @@ const defaultMocks: Record<string, any> = {
   get_token_stats_by_provider: () => ({ stats: [] }),
   get_token_stats_by_model: () => ({ stats: [] }),
   get_token_stats_by_day: () => ({ stats: [] }),
+  get_request_traces: () => [],
+  get_request_trace: () => null,
+  export_request_share_bundle: (args: any) => ({
+    format_version: 1,
+    generated_at: new Date().toISOString(),
+    app_version: "0.0.0",
+    request_id: args?.requestId ?? "",
+    timestamp: new Date().toISOString(),
+    model: "",
+    is_stream: false,
+    converter_path: [],
+    response: { status: "success", duration_ms: 0, retry_count: 0 },
+    trace: [],
+  }),
+  clear_request_traces: () => undefined,
+  get_trace_sampling_settings: () => ({
+    enabled: true,
+    latency_threshold_ms: 30000,
+    error_sample_rate: 1,
+    slow_sample_rate: 1,
+    success_sample_rate: 0,
+    max_traces: 200,
+  }),
+  update_trace_sampling_settings: () => undefined,
+  get_usage_analytics_settings: () => ({ enabled: false, epsilon: 1 }),
+  update_usage_analytics_settings: () => undefined,
+  record_usage_feature: () => undefined,
+  get_usage_analytics_counters: () => ({
+    since: null,
+    requests_by_provider: {},
+    features: {},
+  }),
+  export_usage_analytics_summary: () => ({
+    since: null,
+    generated_at: new Date().toISOString(),
+    epsilon: 1,
+    requests_by_provider: {},
+    features: {},
+  }),
+  clear_usage_analytics: () => undefined,
+
+  // 扩展注册表相关
+  get_extension_registry_settings: () => ({
+    registries: [],
+    disable_github_sources: false,
+  }),
+  update_extension_registry_settings: () => undefined,
+  check_extension_registries: () => [],
+  list_registry_plugins: () => [],
+  install_plugin_from_registry: () => ({
+    success: false,
+    plugin: null,
+    error: "mock",
+  }),
+  install_plugin_from_github: () => ({
+    success: false,
+    plugin: null,
+    error: "mock",
+  }),
+  check_plugin_update: (args: any) => ({
+    plugin_id: args?.pluginId ?? "",
+    installed_version: "0.0.0",
+    latest_version: "0.0.0",
+    tag: "v0.0.0",
+    repository: "",
+    has_update: false,
+  }),
+  update_plugin: () => ({ success: true, plugin: null, error: null }),
+
+  // 设备同步相关
+  get_device_sync_settings: () => ({
+    enabled: false,
+    storage: "folder",
+    webdav: { url: "" },
+    s3: {
+      endpoint: "",
+      region: "us-east-1",
+      bucket: "",
+      access_key_id: "",
+      secret_access_key: "",
+    },
+    categories: {
+      settings: true,
+      credentials: false,
+      skills: true,
+      prompts: true,
+    },
+  }),
+  update_device_sync_settings: () => undefined,
+
+  // 凭证备份相关
+  inspect_credential_backup: () => ({
+    format: "lime-credential-backup",
+    version: 1,
+    app_version: "0.0.0",
+    created_at: Date.now(),
+    counts: { pool: 0, plugin_secrets: 0, asr: 0 },
+  }),
+
+  // 凭证删除相关
+  get_provider_pool_credential_deletions: () => ({ pending: [], receipts: [] }),
+
+  // 对话导出导入相关
+  import_chat_export: () => ({
+    format: "chatgpt",
+    imported_sessions: 0,
+    imported_messages: 0,
+    skipped: [],
+  }),
 
   // Routes 相关
   get_available_routes: () => ({ routes: [] }),
@@ -2205,6 +2558,23 @@ const defaultMocks: Record<string, any> = {
   get_current_prompt_file_content: () => ({ content: "" }),
   auto_import_prompt: () => ({ success: true }),
 
+  // Prompt 库相关
+  list_library_prompts: () => [],
+  get_library_prompt: () => null,
+  save_library_prompt: (args: any) => ({
+    ...args?.request?.prompt,
+    version: 1,
+    created_at: Date.now(),
+    updated_at: Date.now(),
+  }),
+  delete_library_prompt: () => true,
+  list_library_prompt_versions: () => [],
+  diff_library_prompt_versions: () => [],
+  restore_library_prompt_version: () => null,
+  render_library_prompt: () => "",
+  list_library_prompt_folders: () => [],
+  list_library_prompt_tags: () => [],
+
   // Window 相关
   get_window_size: () => ({ width: 1280, height: 800 }),
   set_window_size: () => ({}),
@@ -2261,8 +2631,6 @@ const defaultMocks: Record<string, any> = {
   // OAuth 登录相关
   start_antigravity_oauth_login: () => ({ success: true }),
   get_antigravity_auth_url_and_wait: () => ({ url: "" }),
-  start_codex_oauth_login: () => ({ success: true }),
-  get_codex_auth_url_and_wait: () => ({ url: "" }),
   start_claude_oauth_login: () => ({ success: true }),
   get_claude_oauth_auth_url_and_wait: () => ({ url: "" }),
   claude_oauth_with_cookie: () => ({ success: true }),
@@ -2270,7 +2638,6 @@ const defaultMocks: Record<string, any> = {
   get_qwen_device_code_and_wait: () => ({ code: "" }),
   start_iflow_oauth_login: () => ({ success: true }),
   get_iflow_auth_url_and_wait: () => ({ url: "" }),
-  start_gemini_oauth_login: () => ({ success: true }),
   get_gemini_auth_url_and_wait: () => ({ url: "" }),
   exchange_gemini_code: () => ({ success: true }),
 
@@ -87,6 +87,8 @@ export function SmartInputPage() {
   const [inputValue, setInputValue] = useState("");
   const [isLoading, setIsLoading] = useState(false);
   const [voiceState, setVoiceState] = useState<VoiceState>("idle");
+  // 识别过程中的实时字幕（流式识别中间结果）
+  const [liveCaption, setLiveCaption] = useState("");
   const [voiceMode, setVoiceMode] = useState(false);
   const [soundEnabled, setSoundEnabled] = useState(true);
   const [translateMode, setTranslateMode] = useState(false);
@@ -115,6 +117,35 @@ export function SmartInputPage() {
     })();
   }, []);
 
+  // 识别中监听流式识别中间结果
+  useEffect(() => {
+    if (voiceState !== "transcribing") {
+      setLiveCaption("");
+      return;
+    }
+
+    let disposed = false;
+    let unlisten: (() => void) | null = null;
+    (async () => {
+      const { listenAsrPartialResult } = await import("@/lib/api/asrProvider");
+      const fn = await listenAsrPartialResult((result) => {
+        setLiveCaption(result.text);
+      });
+      if (disposed) {
+        fn();
+      } else {
+        unlisten = fn;
+      }
+    })().catch((err) => {
+      console.error("[语音识别] 监听中间结果失败:", err);
+    });
+
+    return () => {
+      disposed = true;
+      unlisten?.();
+    };
+  }, [voiceState]);
+
   // 显示错误提示
   const showError = useCallback((msg: string) => {
     setErrorMsg(msg);
@@ -336,6 +367,8 @@ export function SmartInputPage() {
         const transcribeResult = await transcribeAudio(
           audioData,
           result.sample_rate,
+          undefined,
+          true,
         );
         console.log("[语音识别] 结果:", transcribeResult.text);
 
@@ -439,6 +472,8 @@ export function SmartInputPage() {
             const transcribeResult = await transcribeAudio(
               audioData,
               result.sample_rate,
+              undefined,
+              true,
             );
             console.log("[语音识别] 结果:", transcribeResult.text);
 
@@ -610,7 +645,9 @@ export function SmartInputPage() {
           <div className="screenshot-attachment processing">
             <Loader2 size={12} className="animate-spin" />
             <span>
-              {voiceState === "transcribing" ? "识别中..." : "润色中..."}
+              {voiceState === "transcribing"
+                ? liveCaption || "识别中..."
+                : "润色中..."}
             </span>
           </div>
         )}
@@ -41,6 +41,7 @@ export enum SettingsTabs {
   Channels = "channels",
   WebSearch = "web-search",
   Environment = "environment",
+  DeviceSync = "device-sync",
   ChromeRelay = "chrome-relay",
   SecurityPerformance = "security-performance",
   Automation = "automation",
@@ -91,6 +92,7 @@ export const SETTINGS_GROUPS: Record<SettingsGroupKey, SettingsTabs[]> = {
     SettingsTabs.Channels,
     SettingsTabs.WebSearch,
     SettingsTabs.Environment,
+    SettingsTabs.DeviceSync,
     SettingsTabs.ChromeRelay,
     SettingsTabs.SecurityPerformance,
     SettingsTabs.Automation,